@@ -49,12 +49,14 @@ chrome.runtime.onMessage.addListener((message, sender, sendResponse) => {
     
     return true; // Indicates async response
   } else if (message.action === 'sendPageData') {
-    const { title, content, url, extractionMethod } = message.data;
+    const { title, content, url, links, extractionMethod } = message.data;
     
     // Log extraction method for debugging
     console.log(`Sending document via ${extractionMethod || 'unknown'} extraction: ${title}`);
     
-    fetch('http://localhost:3000/documents', {
+    // Async mode: the server answers 202 once the document is validated and
+    // queued, and chunking/embedding continue in the background
+    fetch('http://localhost:3000/api/v1/documents?async=true', {
       method: 'POST',
       headers: {
         'Content-Type': 'application/json'
@@ -63,6 +65,7 @@ chrome.runtime.onMessage.addListener((message, sender, sendResponse) => {
         title, 
         content, 
         url, 
+        links: links || [],
         extractionMethod: extractionMethod || 'dom' 
       })
     })
@@ -70,7 +73,10 @@ chrome.runtime.onMessage.addListener((message, sender, sendResponse) => {
       if (response.ok) {
         return response.json().then(data => {
           console.log('Success:', data);
-          sendResponse({ success: true });
+          sendResponse({ success: true, queued: response.status === 202 });
+          if (data.statusUrl) {
+            pollIngestionJob(`http://localhost:3000${data.statusUrl}`, title);
+          }
         });
       } else {
         return response.json().then(error => {
@@ -142,4 +148,32 @@ chrome.runtime.onMessage.addListener((message, sender, sendResponse) => {
     });
     return true; // Indicates that sendResponse will be called asynchronously
   }
-});
\ No newline at end of file
+});
+// Follow a queued ingestion job until it finishes, logging the outcome.
+// The popup has usually closed by then, so failures are reported here.
+function pollIngestionJob(statusUrl, title, attempt = 0) {
+  const MAX_ATTEMPTS = 120;
+  if (attempt >= MAX_ATTEMPTS) {
+    console.warn(`Gave up waiting for ingestion of "${title}"`);
+    return;
+  }
+
+  setTimeout(() => {
+    fetch(statusUrl)
+      .then(response => response.json())
+      .then(job => {
+        if (job.status === 'done' && job.saved && job.saved.status === 'already_exists') {
+          console.log(`"${title}" was already saved on ${job.saved.savedAt} (UTC)`);
+        } else if (job.status === 'done') {
+          console.log(`Ingested "${title}": ${job.message}`);
+        } else if (job.status === 'failed') {
+          console.error(`Ingestion of "${title}" failed: ${job.error}`);
+        } else {
+          pollIngestionJob(statusUrl, title, attempt + 1);
+        }
+      })
+      .catch(error => {
+        console.error('Error polling ingestion job:', error);
+      });
+  }, 1000);
+}
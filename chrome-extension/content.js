@@ -1,3 +1,10 @@
+// Hrefs of the page's anchors, sent so LocalMind can link saved documents
+function collectPageLinks() {
+  return Array.from(document.querySelectorAll('a[href]'), a => a.href)
+    .filter(href => href.startsWith('http'))
+    .slice(0, 500);
+}
+
 (async () => {
   try {
     // Initialize ConfigManager
@@ -26,6 +33,7 @@
           title: document.title,
           url: currentUrl,
           content: document.body.innerText,
+          links: collectPageLinks(),
           extractionMethod: 'dom',
           success: true
         };
@@ -48,6 +56,7 @@
         title: document.title,
         url: window.location.href,
         content: document.body.innerText,
+        links: collectPageLinks(),
         extractionMethod: 'dom',
         success: true,
         error: error.message
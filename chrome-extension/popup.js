@@ -154,7 +154,9 @@ chrome.runtime.onMessage.addListener((message, sender, sendResponse) => {
       data: message.data
     }, (response) => {
       if (response && response.success) {
-        statusMessage.textContent = 'Page saved successfully!';
+        statusMessage.textContent = response.queued
+          ? 'Page sent! LocalMind is indexing it.'
+          : 'Page saved successfully!';
         statusMessage.style.color = 'green';
         setTimeout(() => {
           window.close(); // Close the popup after a short delay
@@ -48,6 +48,20 @@ class EmbeddingResponse(TypedDict):
     dimension: int
 
 
+class BatchEmbeddingRequest(TypedDict):
+    """Request payload for embedding several texts at once."""
+
+    texts: list[str]
+
+
+class BatchEmbeddingResponse(TypedDict):
+    """Response payload containing one embedding per input text."""
+
+    embeddings: list[list[float]]
+    model: str
+    dimension: int
+
+
 class HealthResponse(TypedDict):
     """Health check response."""
 
@@ -86,6 +100,7 @@ state_error: str | None = None
 MODEL_NAME = "google/embeddinggemma-300M"
 EXPECTED_DIMENSION = 768
 MAX_TEXT_LENGTH = 2000
+MAX_BATCH_SIZE = 64
 
 
 def load_model() -> SentenceTransformer:
@@ -190,23 +205,10 @@ async def health_check() -> HealthResponse:
     )
 
 
-@app.post("/embed", response_model=dict)
-async def generate_embedding(request: EmbeddingRequest) -> EmbeddingResponse:
+def _require_model() -> SentenceTransformer:
     """
-    Generate embedding for input text.
-
-    Args:
-        request: EmbeddingRequest containing text to embed
-
-    Returns:
-        EmbeddingResponse with generated embedding vector
-
-    Raises:
-        HTTPException: If model not loaded (503), validation fails (400),
-                      or generation fails (500)
+    Return the loaded model, or raise 503 while it is loading or failed to load.
     """
-    global model
-
     # Check if model is still loading
     if server_state == ServerState.LOADING:
         raise HTTPException(
@@ -223,8 +225,14 @@ async def generate_embedding(request: EmbeddingRequest) -> EmbeddingResponse:
             detail=error_detail,
         )
 
-    # Validate request
-    text = request.get("text", "").strip()
+    return model
+
+
+def _validate_text(text: str) -> str:
+    """
+    Strip input text and reject empty or overlong text with 400.
+    """
+    text = text.strip()
 
     if not text:
         raise HTTPException(
@@ -238,6 +246,27 @@ async def generate_embedding(request: EmbeddingRequest) -> EmbeddingResponse:
             detail=f"Text too long (max {MAX_TEXT_LENGTH} characters)",
         )
 
+    return text
+
+
+@app.post("/embed", response_model=dict)
+async def generate_embedding(request: EmbeddingRequest) -> EmbeddingResponse:
+    """
+    Generate embedding for input text.
+
+    Args:
+        request: EmbeddingRequest containing text to embed
+
+    Returns:
+        EmbeddingResponse with generated embedding vector
+
+    Raises:
+        HTTPException: If model not loaded (503), validation fails (400),
+                      or generation fails (500)
+    """
+    model = _require_model()
+    text = _validate_text(request.get("text", ""))
+
     # Generate embedding
     try:
         logger.debug(f"Generating embedding for text: {text[:50]}...")
@@ -279,6 +308,70 @@ async def generate_embedding(request: EmbeddingRequest) -> EmbeddingResponse:
         )
 
 
+@app.post("/embed_batch", response_model=dict)
+async def generate_embeddings(request: BatchEmbeddingRequest) -> BatchEmbeddingResponse:
+    """
+    Generate embeddings for several texts in one model call.
+
+    Args:
+        request: BatchEmbeddingRequest containing up to MAX_BATCH_SIZE texts
+
+    Returns:
+        BatchEmbeddingResponse with one embedding per text, in input order
+
+    Raises:
+        HTTPException: If model not loaded (503), validation fails (400),
+                      or generation fails (500)
+    """
+    model = _require_model()
+
+    texts = request.get("texts", [])
+    if not texts:
+        raise HTTPException(
+            status_code=status.HTTP_400_BAD_REQUEST,
+            detail="No texts provided",
+        )
+    if len(texts) > MAX_BATCH_SIZE:
+        raise HTTPException(
+            status_code=status.HTTP_400_BAD_REQUEST,
+            detail=f"Too many texts (max {MAX_BATCH_SIZE} per request)",
+        )
+    texts = [_validate_text(text) for text in texts]
+
+    try:
+        logger.debug(f"Generating embeddings for {len(texts)} texts")
+
+        embeddings = [embedding.tolist() for embedding in model.encode(texts)]
+
+        if any(len(embedding) != EXPECTED_DIMENSION for embedding in embeddings):
+            logger.error(f"Dimension mismatch in batch: expected {EXPECTED_DIMENSION}")
+            raise HTTPException(
+                status_code=status.HTTP_500_INTERNAL_SERVER_ERROR,
+                detail="Embedding dimension validation failed",
+            )
+
+        return BatchEmbeddingResponse(
+            embeddings=embeddings,
+            model=MODEL_NAME,
+            dimension=EXPECTED_DIMENSION,
+        )
+
+    except HTTPException:
+        raise
+    except MemoryError as e:
+        logger.error(f"Out of memory during batch embedding generation: {e}")
+        raise HTTPException(
+            status_code=status.HTTP_507_INSUFFICIENT_STORAGE,
+            detail="Out of memory. Try a smaller batch or restart the server.",
+        )
+    except Exception as e:
+        logger.error(f"Batch embedding generation failed: {e}")
+        raise HTTPException(
+            status_code=status.HTTP_500_INTERNAL_SERVER_ERROR,
+            detail=f"Embedding generation failed: {str(e)}",
+        )
+
+
 @app.exception_handler(Exception)
 async def global_exception_handler(request: object, exc: Exception) -> JSONResponse:
     """
@@ -0,0 +1,76 @@
+//! Highlights and notes on documents
+//!
+//! Text selected in the document view can be saved as a highlight with an
+//! optional note. Highlights are stored in the `annotations` table by their
+//! byte range in the document's stored content, and drawn over the text
+//! when the document is viewed again. Each is also stored as a small
+//! document of its own (source `HIGHLIGHT_SOURCE` in `services`) so
+//! searches find it; opening that document opens the highlighted one at
+//! the highlight instead.
+
+/// A highlight on a document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Annotation {
+    pub id: i64,
+    pub document_id: i64,
+    /// Byte range in the document's stored content
+    pub start: usize,
+    pub end: usize,
+    /// The highlighted text
+    pub quote: String,
+    pub note: Option<String>,
+    /// The document searches find the highlight by
+    pub highlight_document_id: Option<i64>,
+    pub created_at: String,
+}
+
+/// The range of a selection in `content` without the whitespace at its
+/// ends; `None` when nothing but whitespace is selected or the range does
+/// not fall on character boundaries.
+pub fn trim_range(content: &str, start: usize, end: usize) -> Option<(usize, usize)> {
+    let selected = content.get(start..end)?;
+    let trimmed = selected.trim_start();
+    let start = start + selected.len() - trimmed.len();
+    let end = start + trimmed.trim_end().len();
+    (start < end).then_some((start, end))
+}
+
+/// Title of the document a highlight is found by.
+pub fn highlight_title(document_title: &str) -> String {
+    format!("Highlight: {}", document_title)
+}
+
+/// Text of the document a highlight is found by: the quote, then the note.
+pub fn highlight_content(quote: &str, note: Option<&str>) -> String {
+    match note {
+        Some(note) => format!("{}\n\nNote: {}", quote, note),
+        None => quote.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trims_selections() {
+        let content = "One  Ünïcode line.\n\nTwo";
+        let (start, end) = trim_range(content, 3, 20).unwrap();
+        assert_eq!(&content[start..end], "Ünïcode line.");
+        assert_eq!(trim_range(content, 20, 25), Some((22, 25)));
+        assert_eq!(trim_range(content, 3, 5), None);
+        // Inside "Ü"
+        assert_eq!(trim_range(content, 6, 10), None);
+        assert_eq!(trim_range(content, 0, 100), None);
+    }
+
+    #[test]
+    fn highlight_documents_carry_the_note() {
+        assert_eq!(highlight_title("Rust book"), "Highlight: Rust book");
+        assert_eq!(highlight_content("quote", None), "quote");
+        assert_eq!(
+            highlight_content("quote", Some("why")),
+            "quote\n\nNote: why"
+        );
+    }
+}
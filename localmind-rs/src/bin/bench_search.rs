@@ -0,0 +1,222 @@
+// Benchmarks search latency and quality against the existing database.
+//
+// Each query is run N times through the same stages RagPipeline uses:
+// query embedding, chunk vector search, and document hydration. p50/p95
+// latency is reported per stage, plus recall of the vector results against
+// the FTS (BM25) top-k for the same query as a cheap quality baseline.
+//
+// Usage: bench_search [--queries FILE] [--iterations N] [--k K]
+//   FILE contains one query per line (blank lines and # comments ignored).
+
+use localmind_rs::{
+    db::Database, local_embedding::LocalEmbeddingClient, rag::escape_fts_query,
+    vector::VectorStore, Result,
+};
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+const DEFAULT_QUERIES: &[&str] = &[
+    "rust async runtime",
+    "how to cook rice",
+    "machine learning embeddings",
+    "python virtual environment",
+    "database indexing performance",
+];
+
+struct Config {
+    queries: Vec<String>,
+    iterations: usize,
+    k: usize,
+}
+
+fn parse_args() -> Result<Config> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let mut queries_file = None;
+    let mut iterations = 5;
+    let mut k = 10;
+
+    let mut i = 0;
+    while i < args.len() {
+        let value = args.get(i + 1);
+        match args[i].as_str() {
+            "--queries" => queries_file = value.cloned(),
+            "--iterations" => {
+                iterations = value
+                    .and_then(|v| v.parse().ok())
+                    .ok_or("--iterations needs a number")?
+            }
+            "--k" => {
+                k = value
+                    .and_then(|v| v.parse().ok())
+                    .ok_or("--k needs a number")?
+            }
+            other => return Err(format!("Unknown argument: {}", other).into()),
+        }
+        i += 2;
+    }
+
+    let queries: Vec<String> = match queries_file {
+        Some(path) => std::fs::read_to_string(&path)
+            .map_err(|e| format!("Cannot read {}: {}", path, e))?
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with('#'))
+            .map(String::from)
+            .collect(),
+        None => DEFAULT_QUERIES.iter().map(|q| q.to_string()).collect(),
+    };
+
+    if queries.is_empty() {
+        return Err("No queries to run".into());
+    }
+
+    Ok(Config {
+        queries,
+        iterations: iterations.max(1),
+        k: k.max(1),
+    })
+}
+
+/// Return the value at percentile `p` (0.0-1.0) of an unsorted sample.
+fn percentile(samples: &[Duration], p: f64) -> Duration {
+    if samples.is_empty() {
+        return Duration::ZERO;
+    }
+    let mut sorted = samples.to_vec();
+    sorted.sort();
+    let idx = ((sorted.len() - 1) as f64 * p).round() as usize;
+    sorted[idx]
+}
+
+fn report(stage: &str, samples: &[Duration]) {
+    println!(
+        "  {:<16} p50 {:>8.2} ms   p95 {:>8.2} ms   (n={})",
+        stage,
+        percentile(samples, 0.50).as_secs_f64() * 1000.0,
+        percentile(samples, 0.95).as_secs_f64() * 1000.0,
+        samples.len()
+    );
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let config = parse_args()?;
+
+    println!("LocalMind Search Benchmark");
+    println!("==========================");
+    println!(
+        "{} queries x {} iterations, k = {}",
+        config.queries.len(),
+        config.iterations,
+        config.k
+    );
+    println!();
+
+    let db = Database::new().await?;
+    let embedding_client = LocalEmbeddingClient::new();
+    if !embedding_client.health_check().await.unwrap_or(false) {
+        return Err("Embedding server is not ready. Start it before benchmarking.".into());
+    }
+
+    let load_start = Instant::now();
+    let mut vector_store = VectorStore::new();
+    vector_store.load_chunk_vectors(db.get_all_chunk_embeddings().await?)?;
+    println!(
+        "Loaded {} chunk vectors in {:.2} s",
+        vector_store.chunk_vector_count(),
+        load_start.elapsed().as_secs_f64()
+    );
+    println!();
+
+    let mut embed_times = Vec::new();
+    let mut search_times = Vec::new();
+    let mut hydrate_times = Vec::new();
+    let mut total_times = Vec::new();
+    let mut recalls = Vec::new();
+
+    for query in &config.queries {
+        let mut vector_docs: Vec<i64> = Vec::new();
+
+        for _ in 0..config.iterations {
+            let total_start = Instant::now();
+
+            let start = Instant::now();
+            let embedding = embedding_client
+                .generate_embedding(query)
+                .await
+                .map_err(|e| format!("Embedding failed: {}", e))?;
+            embed_times.push(start.elapsed());
+
+            // Over-fetch chunks like RagPipeline does, then keep the best chunk per doc
+            let start = Instant::now();
+            let chunks = vector_store.search_chunks_with_cutoff(&embedding, config.k * 2, 0.0)?;
+            search_times.push(start.elapsed());
+
+            let start = Instant::now();
+            let mut seen = HashSet::new();
+            let mut docs = Vec::new();
+            for chunk in chunks {
+                if !seen.insert(chunk.doc_id) {
+                    continue;
+                }
+                if let Some(doc) = db.get_document(chunk.doc_id).await? {
+                    docs.push(doc.id);
+                    if docs.len() >= config.k {
+                        break;
+                    }
+                }
+            }
+            hydrate_times.push(start.elapsed());
+            total_times.push(total_start.elapsed());
+
+            vector_docs = docs;
+        }
+
+        // Recall of vector results against the FTS top-k for the same query
+        let fts_docs: Vec<i64> = db
+            .search_documents_scored(&escape_fts_query(query), config.k as i64, false)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(doc, _)| doc.id)
+            .collect();
+        if fts_docs.is_empty() {
+            println!("\"{}\": no FTS baseline results", query);
+        } else {
+            let vector_set: HashSet<i64> = vector_docs.iter().copied().collect();
+            let found = fts_docs.iter().filter(|id| vector_set.contains(id)).count();
+            let recall = found as f64 / fts_docs.len() as f64;
+            println!(
+                "\"{}\": recall@{} vs FTS = {:.2} ({}/{})",
+                query,
+                config.k,
+                recall,
+                found,
+                fts_docs.len()
+            );
+            recalls.push(recall);
+        }
+    }
+
+    println!();
+    println!("Latency");
+    report("embedding", &embed_times);
+    report("vector search", &search_times);
+    report("hydration", &hydrate_times);
+    report("total", &total_times);
+
+    println!();
+    if recalls.is_empty() {
+        println!("Recall vs FTS: no queries had FTS results");
+    } else {
+        let mean = recalls.iter().sum::<f64>() / recalls.len() as f64;
+        println!(
+            "Mean recall@{} vs FTS: {:.3} over {} queries",
+            config.k,
+            mean,
+            recalls.len()
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,74 @@
+// Evaluates retrieval quality against a labeled query set.
+//
+// The cases file is JSON: either an array of
+//   { "query": "...", "expected_doc_url": "https://..." }
+// or an object { "cases": [ ... ] } wrapping the same array.
+//
+// Usage: eval_retrieval <cases.json> [--mode fused|expanded|vector|fts] [--json]
+
+use localmind_rs::{
+    db::Database,
+    eval::{load_cases, run_eval, SearchMode},
+    rag::RagPipeline,
+    Result,
+};
+use std::path::PathBuf;
+
+const RECALL_KS: &[usize] = &[1, 3, 5, 10];
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let mut cases_path = None;
+    let mut mode = SearchMode::Fused;
+    let mut json = false;
+
+    let mut iter = args.into_iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--mode" => {
+                let value = iter.next().unwrap_or_default();
+                mode = SearchMode::parse(&value).ok_or_else(|| {
+                    format!(
+                        "Unknown mode '{}' (use fused, expanded, vector or fts)",
+                        value
+                    )
+                })?;
+            }
+            "--json" => json = true,
+            _ if cases_path.is_none() => cases_path = Some(PathBuf::from(arg)),
+            other => return Err(format!("Unexpected argument: {}", other).into()),
+        }
+    }
+
+    let cases_path = cases_path
+        .ok_or("Usage: eval_retrieval <cases.json> [--mode fused|expanded|vector|fts] [--json]")?;
+    let cases = load_cases(&cases_path)?;
+
+    let db = Database::new().await?;
+    let rag = RagPipeline::new(db).await?;
+
+    let report = run_eval(&rag, &cases, mode, RECALL_KS).await?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    println!();
+    println!("Retrieval evaluation ({:?}, {} cases)", mode, cases.len());
+    println!("==========================================");
+    for case in &report.cases {
+        let rank = case
+            .rank
+            .map_or_else(|| "miss".to_string(), |r| format!("#{}", r));
+        println!("  {:>5}  {}", rank, case.query);
+    }
+    println!();
+    println!("MRR:        {:.3}", report.mrr);
+    for (k, recall) in &report.recall_at {
+        println!("Recall@{:<3} {:.3}", k, recall);
+    }
+
+    Ok(())
+}
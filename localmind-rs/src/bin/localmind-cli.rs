@@ -0,0 +1,490 @@
+// Command-line access to the LocalMind index.
+//
+// When LocalMind is running (GUI or --headless) its HTTP server is detected
+// where its settings say it listens, then on ports 3000-3010 of this machine,
+// and search/add/delete/stats go through it, so the running app's in-memory
+// vector index stays in sync. Otherwise the database and RAG
+// pipeline are opened directly. Pass --local to skip server detection.
+
+use localmind_rs::{
+    bookmark::fetch_bookmark_content,
+    db::{Database, IndexStats},
+    folder_watcher::read_file_content,
+    http_settings::{HttpServerSettings, DEFAULT_PORTS},
+    rag::{DocumentSource, RagPipeline, SearchOptions},
+    Result,
+};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const USAGE: &str = "Usage: localmind-cli [--local] <command> [options]
+
+Commands:
+  search <query> [--json] [--include-dead]   Search the index
+  add <url|file> [--title <title>]           Fetch or read a document and index it
+  delete <id>                                Delete a document by ID
+  stats [--json]                             Show index statistics
+  export [--output <file>]                   Export all documents as JSON
+  reindex                                    Re-chunk and re-embed every document
+
+Options:
+  --local   Use the database directly even if LocalMind is running
+  --expand  Also match synonyms and paraphrases of a search query
+  --exact   Search the query as typed, without spelling correction";
+
+/// Where commands are executed.
+enum Backend {
+    /// A running LocalMind HTTP server at this base URL, and a client that
+    /// trusts its certificate when it is served over HTTPS
+    Server {
+        base_url: String,
+        client: reqwest::Client,
+    },
+    /// Direct access to the database (and RAG pipeline when needed)
+    Local,
+}
+
+/// Parsed command-line arguments: positional values plus flags.
+struct Args {
+    positional: Vec<String>,
+    flags: Vec<String>,
+    options: Vec<(String, String)>,
+}
+
+impl Args {
+    fn parse(raw: Vec<String>) -> Self {
+        // Options that take a value
+        const VALUE_OPTIONS: &[&str] = &["--title", "--output"];
+
+        let mut positional = Vec::new();
+        let mut flags = Vec::new();
+        let mut options = Vec::new();
+        let mut iter = raw.into_iter();
+        while let Some(arg) = iter.next() {
+            if VALUE_OPTIONS.contains(&arg.as_str()) {
+                if let Some(value) = iter.next() {
+                    options.push((arg, value));
+                }
+            } else if arg.starts_with("--") {
+                flags.push(arg);
+            } else {
+                positional.push(arg);
+            }
+        }
+        Self {
+            positional,
+            flags,
+            options,
+        }
+    }
+
+    fn has_flag(&self, flag: &str) -> bool {
+        self.flags.iter().any(|f| f == flag)
+    }
+
+    fn option(&self, name: &str) -> Option<&str> {
+        self.options
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let args = Args::parse(std::env::args().skip(1).collect());
+
+    let Some(command) = args.positional.first().cloned() else {
+        eprintln!("{}", USAGE);
+        std::process::exit(2);
+    };
+
+    let backend = if args.has_flag("--local") {
+        Backend::Local
+    } else {
+        match detect_server().await {
+            Some((base_url, client)) => Backend::Server { base_url, client },
+            None => Backend::Local,
+        }
+    };
+
+    let result = match command.as_str() {
+        "search" => cmd_search(&backend, &args).await,
+        "add" => cmd_add(&backend, &args).await,
+        "delete" => cmd_delete(&backend, &args).await,
+        "stats" => cmd_stats(&backend, &args).await,
+        "export" => cmd_export(&args).await,
+        "reindex" => cmd_reindex(&backend).await,
+        "help" | "--help" | "-h" => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        other => Err(format!("Unknown command '{}'\n\n{}", other, USAGE).into()),
+    };
+
+    if let Err(e) = result {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+/// Look for a running LocalMind HTTP server: first where its settings say it
+/// listens, then on the default ports of this machine.
+async fn detect_server() -> Option<(String, reqwest::Client)> {
+    let settings = match Database::new().await {
+        Ok(db) => db.get_http_server_settings().await.unwrap_or_default(),
+        Err(_) => HttpServerSettings::default(),
+    };
+
+    let mut builder = reqwest::Client::builder();
+    if settings.uses_tls() {
+        let cert = localmind_rs::tls::self_signed_cert_path(&localmind_rs::db::data_dir());
+        if let Some(cert) = std::fs::read(cert)
+            .ok()
+            .and_then(|pem| reqwest::Certificate::from_pem(&pem).ok())
+        {
+            builder = builder.add_root_certificate(cert);
+        }
+    }
+    let client = builder.build().ok()?;
+
+    let mut candidates = configured_urls(&settings);
+    for port in DEFAULT_PORTS {
+        let url = format!("http://127.0.0.1:{}/api/v1", port);
+        if !candidates.contains(&url) {
+            candidates.push(url);
+        }
+    }
+
+    for base_url in candidates {
+        let response = match client
+            .get(format!("{}/stats", base_url))
+            .timeout(Duration::from_millis(500))
+            .send()
+            .await
+        {
+            Ok(r) => r,
+            Err(_) => continue,
+        };
+        // A LocalMind server answers /stats with IndexStats, or 503 while starting up
+        if response.status() == reqwest::StatusCode::SERVICE_UNAVAILABLE {
+            return Some((base_url, client));
+        }
+        if response.status().is_success() && response.json::<IndexStats>().await.is_ok() {
+            return Some((base_url, client));
+        }
+    }
+    None
+}
+
+/// Base URLs of the API on the address and ports in its settings. A server
+/// bound to every interface is reached on localhost, which its self-signed
+/// certificate is issued for.
+fn configured_urls(settings: &HttpServerSettings) -> Vec<String> {
+    let ip = match settings.ip() {
+        IpAddr::V4(ip) if ip.is_unspecified() => IpAddr::V4(Ipv4Addr::LOCALHOST),
+        IpAddr::V6(ip) if ip.is_unspecified() => IpAddr::V6(Ipv6Addr::LOCALHOST),
+        ip => ip,
+    };
+    settings
+        .ports()
+        .map(|port| {
+            let authority = if settings.uses_tls() && ip.is_loopback() {
+                format!("localhost:{}", port)
+            } else {
+                SocketAddr::new(ip, port).to_string()
+            };
+            format!("{}://{}/api/v1", settings.scheme(), authority)
+        })
+        .collect()
+}
+
+/// Turn a non-success server response into an error carrying its message.
+async fn server_error(response: reqwest::Response) -> Box<dyn std::error::Error + Send + Sync> {
+    let status = response.status();
+    let message = response
+        .json::<serde_json::Value>()
+        .await
+        .ok()
+        .and_then(|v| v["message"].as_str().map(String::from))
+        .unwrap_or_else(|| status.to_string());
+    format!("Server returned {}: {}", status, message).into()
+}
+
+async fn open_rag() -> Result<RagPipeline> {
+    let db = Database::new().await?;
+    RagPipeline::new(db).await
+}
+
+async fn cmd_search(backend: &Backend, args: &Args) -> Result<()> {
+    let query = args.positional[1..].join(" ");
+    if query.trim().is_empty() {
+        return Err("search requires a query".into());
+    }
+    let options = SearchOptions {
+        include_dead: args.has_flag("--include-dead"),
+        expand_query: args.has_flag("--expand"),
+        collection_id: None,
+        boost_linked: false,
+        correct_spelling: !args.has_flag("--exact"),
+    };
+    let flag = |on: bool| if on { "true" } else { "false" };
+
+    let hits: Vec<DocumentSource> = match backend {
+        Backend::Server { base_url, client } => {
+            let response = client
+                .get(format!("{}/search", base_url))
+                .query(&[
+                    ("q", query.as_str()),
+                    ("include_dead", flag(options.include_dead)),
+                    ("expand", flag(options.expand_query)),
+                    ("exact", flag(!options.correct_spelling)),
+                ])
+                .send()
+                .await?;
+            if !response.status().is_success() {
+                return Err(server_error(response).await);
+            }
+            response.json().await?
+        }
+        Backend::Local => {
+            let rag = open_rag().await?;
+            rag.get_search_hits_fused(&query, options).await?
+        }
+    };
+
+    if args.has_flag("--json") {
+        println!("{}", serde_json::to_string_pretty(&hits)?);
+        return Ok(());
+    }
+
+    if hits.is_empty() {
+        println!("No results for \"{}\"", query);
+        return Ok(());
+    }
+
+    for (rank, hit) in hits.iter().enumerate() {
+        let mut markers = String::new();
+        if hit.is_dead {
+            markers.push_str(" [dead]");
+        }
+        if hit.needs_auth {
+            markers.push_str(" [auth]");
+        }
+        println!(
+            "{:>2}. {:>3.0}%  {}{}  (id {})",
+            rank + 1,
+            hit.similarity * 100.0,
+            hit.title,
+            markers,
+            hit.doc_id
+        );
+        let snippet: String = hit
+            .content_snippet
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .chars()
+            .take(160)
+            .collect();
+        println!("           {}", snippet);
+    }
+    Ok(())
+}
+
+/// Read a local file or fetch a URL, returning (title, content, url).
+async fn load_document(target: &str, title: Option<&str>) -> Result<(String, String, String)> {
+    if target.starts_with("http://") || target.starts_with("https://") {
+        let (content, _needs_auth) = fetch_bookmark_content(target).await?;
+        if content.trim().is_empty() {
+            return Err(format!("No content could be extracted from {}", target).into());
+        }
+        let title = title
+            .map(String::from)
+            .unwrap_or_else(|| target.to_string());
+        let content = format!("{}\n\n{}", title, content);
+        return Ok((title, content, target.to_string()));
+    }
+
+    let path = PathBuf::from(target);
+    let path = path
+        .canonicalize()
+        .map_err(|e| format!("Cannot read {}: {}", target, e))?;
+    let content = read_file_content(&path).map_err(|e| e.to_string())?;
+    let title = title.map(String::from).unwrap_or_else(|| {
+        path.file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("unknown")
+            .to_string()
+    });
+    let url = format!("file://{}", path.display());
+    Ok((title, content, url))
+}
+
+async fn cmd_add(backend: &Backend, args: &Args) -> Result<()> {
+    let target = args
+        .positional
+        .get(1)
+        .ok_or("add requires a URL or file path")?;
+    let (title, content, url) = load_document(target, args.option("--title")).await?;
+
+    match backend {
+        Backend::Server { base_url, client } => {
+            let response = client
+                .post(format!("{}/documents", base_url))
+                .json(&serde_json::json!({
+                    "title": title,
+                    "content": content,
+                    "url": url,
+                    "extractionMethod": "cli",
+                }))
+                .send()
+                .await?;
+            if !response.status().is_success() {
+                return Err(server_error(response).await);
+            }
+            println!("Added \"{}\"", title);
+        }
+        Backend::Local => {
+            let rag = open_rag().await?;
+            if let Some(existing) = rag.db.get_document_by_url(&url).await? {
+                rag.update_document(existing.id, &title, &content).await?;
+                println!("Updated \"{}\" (id {})", title, existing.id);
+            } else {
+                let id = rag
+                    .ingest_document(&title, &content, Some(&url), "cli", None)
+                    .await?;
+                println!("Added \"{}\" (id {})", title, id);
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn cmd_delete(backend: &Backend, args: &Args) -> Result<()> {
+    let id: i64 = args
+        .positional
+        .get(1)
+        .ok_or("delete requires a document ID")?
+        .parse()
+        .map_err(|_| "Document ID must be a number")?;
+
+    match backend {
+        Backend::Server { base_url, client } => {
+            let response = client
+                .delete(format!("{}/documents/{}", base_url, id))
+                .send()
+                .await?;
+            if !response.status().is_success() {
+                return Err(server_error(response).await);
+            }
+        }
+        Backend::Local => {
+            // Vectors are loaded from the DB at startup, so deleting the rows is enough
+            let db = Database::new().await?;
+            if db.get_document(id).await?.is_none() {
+                return Err(format!("Document {} not found", id).into());
+            }
+            db.delete_document(id).await?;
+        }
+    }
+    println!("Deleted document {}", id);
+    Ok(())
+}
+
+async fn cmd_stats(backend: &Backend, args: &Args) -> Result<()> {
+    let stats: IndexStats = match backend {
+        Backend::Server { base_url, client } => {
+            let response = client.get(format!("{}/stats", base_url)).send().await?;
+            if !response.status().is_success() {
+                return Err(server_error(response).await);
+            }
+            response.json().await?
+        }
+        Backend::Local => Database::new().await?.get_index_stats().await?,
+    };
+
+    if args.has_flag("--json") {
+        println!("{}", serde_json::to_string_pretty(&stats)?);
+        return Ok(());
+    }
+
+    println!("Documents:   {}", stats.documents);
+    println!("Chunks:      {}", stats.chunks);
+    println!("Dead links:  {}", stats.dead);
+    println!("Needs auth:  {}", stats.needs_auth);
+    if !stats.by_source.is_empty() {
+        println!();
+        println!("By source:");
+        for (source, count) in &stats.by_source {
+            println!("  {:>7}  {}", count, source);
+        }
+    }
+    Ok(())
+}
+
+/// Export every document as a JSON array, dead and trashed ones included;
+/// `deleted_at` is when a trashed one was moved to the trash. Reads the
+/// database directly since export never modifies it.
+async fn cmd_export(args: &Args) -> Result<()> {
+    let db = Database::new().await?;
+    let documents: Vec<serde_json::Value> = db
+        .get_documents_for_export()
+        .await?
+        .into_iter()
+        .map(|(doc, deleted_at)| {
+            serde_json::json!({
+                "id": doc.id,
+                "title": doc.title,
+                "url": doc.url,
+                "source": doc.source,
+                "created_at": doc.created_at,
+                "profile": doc.profile,
+                "is_dead": doc.is_dead.unwrap_or(false),
+                "needs_auth": doc.needs_auth.unwrap_or(false),
+                "deleted_at": deleted_at,
+                "content": doc.content,
+            })
+        })
+        .collect();
+    let json = serde_json::to_string_pretty(&documents)?;
+
+    match args.option("--output") {
+        Some(path) => {
+            std::fs::write(Path::new(path), json)?;
+            eprintln!("Exported {} documents to {}", documents.len(), path);
+        }
+        None => println!("{}", json),
+    }
+    Ok(())
+}
+
+async fn cmd_reindex(backend: &Backend) -> Result<()> {
+    if let Backend::Server { base_url, .. } = backend {
+        return Err(format!(
+            "LocalMind is running ({}). Close it before reindexing so its index is not left stale.",
+            base_url
+        )
+        .into());
+    }
+
+    let rag = open_rag().await?;
+    // Trashed documents stay out of the index until they are restored
+    let documents = rag.db.get_documents_not_in_trash().await?;
+    let total = documents.len();
+    let mut failed = 0;
+
+    for (i, doc) in documents.iter().enumerate() {
+        match rag.reindex_document(doc.id).await {
+            Ok(chunks) => println!("[{}/{}] {} ({} chunks)", i + 1, total, doc.title, chunks),
+            Err(e) => {
+                failed += 1;
+                eprintln!("[{}/{}] {} failed: {}", i + 1, total, doc.title, e);
+            }
+        }
+    }
+
+    println!("Reindexed {} documents ({} failed)", total - failed, failed);
+    Ok(())
+}
@@ -1,11 +1,17 @@
 use crate::{
-    bookmark_exclusion::ExclusionRules, fetcher::WebFetcher, youtube::YouTubeProcessor, Result,
+    bookmark_exclusion::ExclusionRules,
+    fetcher::WebFetcher,
+    html_text::PageStructure,
+    safari::{get_safari_profile, is_safari_bookmarks_file, parse_safari_bookmarks},
+    youtube::YouTubeProcessor,
+    Result,
 };
 use notify::{Event, EventKind, RecursiveMode, Watcher};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
 use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BookmarkItem {
@@ -55,17 +61,129 @@ pub struct ChromeProfile {
     pub bookmarks_path: PathBuf,
 }
 
+/// Quiet period after the last file event before bookmarks are re-read.
+const WATCH_DEBOUNCE: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// Read attempts for a bookmarks file that is locked or being replaced.
+const READ_ATTEMPTS: u32 = 5;
+
+/// Delay before the first read retry; doubled after each attempt.
+const READ_RETRY_DELAY: std::time::Duration = std::time::Duration::from_millis(50);
+
+/// Update sent by a running bookmarks file watcher.
+#[derive(Debug, Clone)]
+pub enum BookmarkWatchEvent {
+    /// The file was saved; carries the re-parsed bookmarks
+    Changed(Vec<BookmarkItem>),
+    /// Watching or re-reading the file failed
+    Error(String),
+}
+
+/// A running bookmarks file watcher, returned by
+/// `BookmarkMonitor::start_monitoring`.
+///
+/// The OS watcher delivers events from its own thread for as long as this
+/// struct is alive. `shutdown` (or dropping it) stops the watcher and the
+/// task that re-parses the file.
+pub struct BookmarkWatcher {
+    _watcher: notify::RecommendedWatcher,
+    shutdown: CancellationToken,
+}
+
+impl BookmarkWatcher {
+    /// Stop watching; the same as dropping the watcher.
+    pub fn shutdown(self) {}
+}
+
+impl Drop for BookmarkWatcher {
+    fn drop(&mut self) {
+        self.shutdown.cancel();
+    }
+}
+
+/// Where a `BookmarkMonitor` reads bookmarks from.
+#[derive(Debug, Clone)]
+pub enum BookmarkSource {
+    /// A Chrome `Bookmarks` file or a Safari `Bookmarks.plist`
+    File(PathBuf),
+    /// Chrome bookmarks JSON held in memory, e.g. a test fixture; it cannot
+    /// be watched
+    Json(String),
+}
+
+/// Bookmark URLs added and removed between two reads of a bookmarks file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BookmarkDiff {
+    /// URLs only in the newer read, in the order they appear there
+    pub added: Vec<String>,
+    /// URLs only in the older read, in the order they appeared there
+    pub removed: Vec<String>,
+}
+
+impl BookmarkDiff {
+    /// True when both reads hold the same URLs (edits, moves, reordering).
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Compare two reads of a bookmarks file by URL. A URL bookmarked twice
+/// counts once, so moving a bookmark between folders is not a change.
+pub fn diff_bookmarks(before: &[BookmarkItem], after: &[BookmarkItem]) -> BookmarkDiff {
+    fn urls(items: &[BookmarkItem]) -> Vec<&str> {
+        let mut seen = std::collections::HashSet::new();
+        items
+            .iter()
+            .filter_map(|item| item.url.as_deref())
+            .filter(|url| !url.is_empty() && seen.insert(*url))
+            .collect()
+    }
+
+    let before = urls(before);
+    let after = urls(after);
+    let only_in = |urls: &[&str], other: &[&str]| -> Vec<String> {
+        let other: std::collections::HashSet<&str> = other.iter().copied().collect();
+        urls.iter()
+            .filter(|url| !other.contains(*url))
+            .map(|url| url.to_string())
+            .collect()
+    };
+    BookmarkDiff {
+        added: only_in(&after, &before),
+        removed: only_in(&before, &after),
+    }
+}
+
 pub struct BookmarkMonitor {
-    bookmarks_path: PathBuf,
-    tx: mpsc::UnboundedSender<Vec<BookmarkItem>>,
+    source: BookmarkSource,
+    tx: mpsc::UnboundedSender<BookmarkWatchEvent>,
 }
 
 impl BookmarkMonitor {
-    pub fn new() -> Result<(Self, mpsc::UnboundedReceiver<Vec<BookmarkItem>>)> {
-        let bookmarks_path = Self::get_chrome_bookmarks_path()?;
-        let (tx, rx) = mpsc::unbounded_channel();
+    /// Monitor for the default Chrome profile's bookmarks file.
+    pub fn new() -> Result<(Self, mpsc::UnboundedReceiver<BookmarkWatchEvent>)> {
+        Ok(Self::from_path(Self::get_chrome_bookmarks_path()?))
+    }
+
+    /// Monitor for the bookmarks file at `path`; it need not exist yet.
+    pub fn from_path(
+        path: impl Into<PathBuf>,
+    ) -> (Self, mpsc::UnboundedReceiver<BookmarkWatchEvent>) {
+        Self::from_source(BookmarkSource::File(path.into()))
+    }
 
-        Ok((Self { bookmarks_path, tx }, rx))
+    /// Monitor for Chrome bookmarks JSON held in memory.
+    pub fn from_json(
+        json: impl Into<String>,
+    ) -> (Self, mpsc::UnboundedReceiver<BookmarkWatchEvent>) {
+        Self::from_source(BookmarkSource::Json(json.into()))
+    }
+
+    pub fn from_source(
+        source: BookmarkSource,
+    ) -> (Self, mpsc::UnboundedReceiver<BookmarkWatchEvent>) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        (Self { source, tx }, rx)
     }
 
     pub fn get_chrome_bookmarks_path() -> Result<PathBuf> {
@@ -105,27 +223,26 @@ impl BookmarkMonitor {
     }
 
     pub fn parse_bookmarks(&self) -> Result<Vec<BookmarkItem>> {
-        let content = fs::read_to_string(&self.bookmarks_path)?;
-        let chrome_bookmarks: ChromeBookmarks = serde_json::from_str(&content)?;
-
         let mut all_bookmarks = Vec::new();
 
-        // Extract bookmarks from bookmark bar
-        self.extract_bookmarks(&chrome_bookmarks.roots.bookmark_bar, &mut all_bookmarks);
-
-        // Extract bookmarks from other bookmarks
-        self.extract_bookmarks(&chrome_bookmarks.roots.other, &mut all_bookmarks);
-
-        // Extract synced bookmarks if present
-        if let Some(synced) = &chrome_bookmarks.roots.synced {
-            self.extract_bookmarks(synced, &mut all_bookmarks);
+        // Extract bookmarks from bookmark bar, other and synced bookmarks
+        for root in &self.get_bookmark_roots()? {
+            self.extract_bookmarks(root, &mut all_bookmarks);
         }
 
         Ok(all_bookmarks)
     }
 
+    /// Top-level bookmark folders: bookmark bar, other and synced bookmarks
+    /// for Chrome, or Favorites, Bookmarks Menu and Reading List for Safari.
     pub fn get_bookmark_roots(&self) -> Result<Vec<BookmarkItem>> {
-        let content = fs::read_to_string(&self.bookmarks_path)?;
+        let content = match &self.source {
+            BookmarkSource::File(path) if is_safari_bookmarks_file(path) => {
+                return parse_safari_bookmarks(path);
+            }
+            BookmarkSource::File(path) => std::borrow::Cow::Owned(read_bookmarks_file(path)?),
+            BookmarkSource::Json(json) => std::borrow::Cow::Borrowed(json.as_str()),
+        };
         let chrome_bookmarks: ChromeBookmarks = serde_json::from_str(&content)?;
 
         let mut roots = Vec::new();
@@ -200,64 +317,90 @@ impl BookmarkMonitor {
         }
     }
 
-    pub async fn start_monitoring(&self) -> Result<()> {
-        let (tx, mut rx) = mpsc::channel(100);
-        let bookmarks_path_watcher = self.bookmarks_path.clone();
-        let bookmarks_path_monitor = self.bookmarks_path.clone();
-        let notification_tx = self.tx.clone();
-
-        // Create watcher in a blocking thread
-        let _handle = tokio::task::spawn_blocking(move || {
-            let mut watcher =
-                notify::recommended_watcher(move |res: notify::Result<Event>| match res {
-                    Ok(event) => {
-                        if matches!(event.kind, EventKind::Modify(_)) {
-                            if let Err(e) = tx.blocking_send(()) {
-                                eprintln!("Failed to send file change notification: {}", e);
-                            }
-                        }
+    /// Watch the bookmarks file and send the re-parsed bookmarks on every
+    /// save, as `BookmarkWatchEvent`s on this monitor's channel.
+    ///
+    /// Browsers save atomically (write a temp file, then rename it over the
+    /// original), which replaces the watched inode and shows up as a create or
+    /// rename rather than a modify. The parent directory is watched instead,
+    /// and events are filtered down to the bookmarks file name.
+    ///
+    /// Must be called from within a tokio runtime. Watching stops when the
+    /// returned `BookmarkWatcher` is shut down or dropped.
+    pub fn start_monitoring(&self) -> Result<BookmarkWatcher> {
+        let BookmarkSource::File(bookmarks_path) = &self.source else {
+            return Err("Bookmarks held in memory cannot be watched".into());
+        };
+        let (tx, rx) = mpsc::channel(1);
+        let watch_dir = bookmarks_path
+            .parent()
+            .ok_or("Bookmarks file has no parent directory")?
+            .to_path_buf();
+        let file_name = bookmarks_path
+            .file_name()
+            .ok_or("Bookmarks path has no file name")?
+            .to_os_string();
+        let error_tx = self.tx.clone();
+
+        let mut watcher =
+            notify::recommended_watcher(move |res: notify::Result<Event>| match res {
+                Ok(event) => {
+                    if is_bookmarks_file_event(&event, &file_name) {
+                        // One pending notification is enough; the debounce
+                        // re-reads the latest file anyway
+                        let _ = tx.try_send(());
                     }
-                    Err(e) => eprintln!("Watch error: {:?}", e),
-                })
-                .unwrap();
-
-            watcher
-                .watch(&bookmarks_path_watcher, RecursiveMode::NonRecursive)
-                .unwrap();
-
-            // Keep the watcher alive
-            loop {
-                std::thread::sleep(std::time::Duration::from_millis(100));
+                }
+                Err(e) => {
+                    let _ = error_tx.send(BookmarkWatchEvent::Error(format!("Watch error: {}", e)));
+                }
+            })?;
+        watcher.watch(&watch_dir, RecursiveMode::NonRecursive)?;
+
+        let shutdown = CancellationToken::new();
+        let task_shutdown = shutdown.clone();
+        let monitor = BookmarkMonitor {
+            source: self.source.clone(),
+            tx: self.tx.clone(),
+        };
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = task_shutdown.cancelled() => {}
+                _ = monitor.forward_changes(rx) => {}
             }
         });
 
-        // Process file change notifications
-        tokio::spawn(async move {
-            while (rx.recv().await).is_some() {
-                // Debounce: wait a bit for file to stabilize
-                tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-
-                // Parse bookmarks and send update
-                let monitor = BookmarkMonitor {
-                    bookmarks_path: bookmarks_path_monitor.clone(),
-                    tx: notification_tx.clone(),
-                };
+        Ok(BookmarkWatcher {
+            _watcher: watcher,
+            shutdown,
+        })
+    }
 
-                match monitor.parse_bookmarks() {
-                    Ok(bookmarks) => {
-                        if let Err(e) = notification_tx.send(bookmarks) {
-                            eprintln!("Failed to send bookmark update: {}", e);
-                            break;
-                        }
-                    }
-                    Err(e) => {
-                        eprintln!("Failed to parse bookmarks: {}", e);
-                    }
-                }
+    /// Re-parse the bookmarks file after each burst of file events until the
+    /// watcher or the receiving side goes away.
+    async fn forward_changes(self, mut rx: mpsc::Receiver<()>) {
+        while (rx.recv().await).is_some() {
+            // Debounce: wait until the file has been quiet for a while, so
+            // a burst of events from one save triggers a single re-parse
+            while let Ok(Some(())) = tokio::time::timeout(WATCH_DEBOUNCE, rx.recv()).await {}
+
+            // Parse bookmarks (retrying briefly if the file is locked)
+            let monitor = BookmarkMonitor {
+                source: self.source.clone(),
+                tx: self.tx.clone(),
+            };
+            let parsed = tokio::task::spawn_blocking(move || monitor.parse_bookmarks())
+                .await
+                .unwrap_or_else(|e| Err(e.to_string().into()));
+
+            let event = match parsed {
+                Ok(bookmarks) => BookmarkWatchEvent::Changed(bookmarks),
+                Err(e) => BookmarkWatchEvent::Error(format!("Failed to parse bookmarks: {}", e)),
+            };
+            if self.tx.send(event).is_err() {
+                break;
             }
-        });
-
-        Ok(())
+        }
     }
 
     pub async fn get_bookmarks_for_ingestion(&self) -> Result<Vec<(String, String, String, bool)>> {
@@ -288,7 +431,9 @@ impl BookmarkMonitor {
                     let cleaned_title = YouTubeProcessor::cleanup_title(&title);
 
                     // Try to get transcript first
-                    match YouTubeProcessor::fetch_transcript(url).await {
+                    match YouTubeProcessor::fetch_transcript(url, Some(cleaned_title.as_str()))
+                        .await
+                    {
                         Ok(Some(transcript)) => {
                             println!("Using YouTube transcript for bookmark: {}", cleaned_title);
                             (
@@ -397,10 +542,14 @@ impl BookmarkMonitor {
             .await
     }
 
-    pub async fn get_bookmarks_metadata_with_exclusion(
+    /// Bookmarks left after applying exclusion rules, with their folders.
+    ///
+    /// This is the dry run behind `get_bookmarks_metadata_with_exclusion`;
+    /// the first-ingestion preview uses it directly to count by folder.
+    pub fn get_bookmarks_with_paths_with_exclusion(
         &self,
         exclusion_rules: &ExclusionRules,
-    ) -> Result<Vec<(String, String)>> {
+    ) -> Result<Vec<BookmarkItemWithPath>> {
         let roots = self.get_bookmark_roots()?;
         let mut bookmarks_with_paths = Vec::new();
 
@@ -414,6 +563,15 @@ impl BookmarkMonitor {
             );
         }
 
+        Ok(bookmarks_with_paths)
+    }
+
+    pub async fn get_bookmarks_metadata_with_exclusion(
+        &self,
+        exclusion_rules: &ExclusionRules,
+    ) -> Result<Vec<(String, String)>> {
+        let bookmarks_with_paths = self.get_bookmarks_with_paths_with_exclusion(exclusion_rules)?;
+
         let mut result = Vec::new();
         println!(
             "Found {} bookmarks for processing (after exclusions)",
@@ -450,27 +608,19 @@ impl BookmarkMonitor {
             }
             Err(e) => {
                 eprintln!("ERROR: Failed to parse bookmark folders: {}", e);
-                eprintln!("Bookmarks path: {:?}", self.bookmarks_path);
+                if let BookmarkSource::File(path) = &self.source {
+                    eprintln!("Bookmarks path: {:?}", path);
+                }
                 Vec::new()
             }
         }
     }
 
     fn parse_bookmarks_with_folders(&self) -> Result<Vec<BookmarkFolder>> {
-        let content = fs::read_to_string(&self.bookmarks_path)?;
-        let chrome_bookmarks: ChromeBookmarks = serde_json::from_str(&content)?;
-
         let mut folders = Vec::new();
 
-        // Extract folders from bookmark bar
-        self.extract_folders(&chrome_bookmarks.roots.bookmark_bar, &mut folders, &[]);
-
-        // Extract folders from other bookmarks
-        self.extract_folders(&chrome_bookmarks.roots.other, &mut folders, &[]);
-
-        // Extract synced bookmarks if present
-        if let Some(synced) = &chrome_bookmarks.roots.synced {
-            self.extract_folders(synced, &mut folders, &[]);
+        for root in &self.get_bookmark_roots()? {
+            self.extract_folders(root, &mut folders, &[]);
         }
 
         Ok(folders)
@@ -515,6 +665,19 @@ impl BookmarkMonitor {
         }
     }
 
+    /// Folders holding the bookmark for `url`, outermost (the root) first.
+    pub fn folder_path_of_url(&self, url: &str) -> Option<Vec<String>> {
+        let roots = self.get_bookmark_roots().ok()?;
+        find_bookmark_folder(&roots, url)
+    }
+
+    /// URLs of the bookmarks in the folder at `path` and its subfolders.
+    pub fn urls_in_folder(&self, path: &[String]) -> Vec<String> {
+        self.get_bookmark_roots()
+            .map(|roots| folder_urls(&roots, path))
+            .unwrap_or_default()
+    }
+
     #[allow(clippy::only_used_in_recursion)]
     fn count_bookmarks_in_folder(&self, item: &BookmarkItem) -> usize {
         let mut count = 0;
@@ -534,61 +697,91 @@ impl BookmarkMonitor {
 
     /// Fetch bookmark content. Returns (content, needs_auth).
     pub async fn fetch_bookmark_content(&self, url: &str) -> Result<(String, bool)> {
-        let fetcher = WebFetcher::new();
+        fetch_bookmark_content(url).await
+    }
+}
 
-        // Check if this is a YouTube URL and try to get transcript
-        if YouTubeProcessor::is_youtube_url(url) {
-            println!("Processing YouTube bookmark: {}", url);
-            match YouTubeProcessor::fetch_transcript(url).await {
-                Ok(Some(transcript)) => {
-                    println!("Using YouTube transcript for bookmark: {}", url);
-                    return Ok((
-                        format!("Bookmark: {}\nURL: {}\n\n{}", url, url, transcript),
-                        false,
-                    ));
-                }
-                Ok(None) => {
-                    println!("No YouTube transcript available, using original content");
-                }
-                Err(e) => {
-                    println!(
-                        "Failed to fetch YouTube transcript: {}, using original content",
-                        e
-                    );
-                }
-            }
-        }
+/// Fetched content of a bookmarked page.
+pub struct BookmarkContent {
+    pub content: String,
+    pub needs_auth: bool,
+    /// Headings and outgoing links of the page, for the document link
+    /// graph and structure-aware chunking
+    pub structure: PageStructure,
+    /// The page's preview image, for its thumbnail
+    pub image_url: Option<String>,
+    /// The page's title, when it has one
+    pub title: Option<String>,
+}
 
-        // Fallback to regular content fetching with auth detection
-        match fetcher.fetch_page_content_with_status(url).await {
-            Ok(result) => {
-                let content = if result.needs_auth {
-                    // Auth-blocked: no useful content to embed, title is prepended by caller
-                    String::new()
-                } else if result.content.is_empty() {
-                    format!("Bookmark: {}\nURL: {}\n\n[No content extracted]", url, url)
-                } else {
-                    format!("Bookmark: {}\nURL: {}\n\n{}", url, url, result.content)
-                };
-                Ok((content, result.needs_auth))
+/// Fetch content for a bookmark URL, preferring a YouTube transcript when one
+/// is available. Returns (content, needs_auth), or an error if the page could
+/// not be fetched so the caller can record the failure instead of indexing it.
+/// Linked images are not captioned.
+pub async fn fetch_bookmark_content(url: &str) -> Result<(String, bool)> {
+    let page = fetch_bookmark_page(url, &Default::default()).await?;
+    Ok((page.content, page.needs_auth))
+}
+
+/// Like `fetch_bookmark_content`, but also returns the page's structure,
+/// preview image and title, and captions linked images with `image_captions`
+/// when they are on.
+pub async fn fetch_bookmark_page(
+    url: &str,
+    image_captions: &crate::image_caption::ImageCaptionSettings,
+) -> Result<BookmarkContent> {
+    let fetcher = WebFetcher::new().with_image_captions(image_captions.clone());
+
+    // Check if this is a YouTube URL and try to get transcript
+    if YouTubeProcessor::is_youtube_url(url) {
+        println!("Processing YouTube bookmark: {}", url);
+        match YouTubeProcessor::fetch_transcript(url, None).await {
+            Ok(Some(transcript)) => {
+                println!("Using YouTube transcript for bookmark: {}", url);
+                return Ok(BookmarkContent {
+                    content: format!("Bookmark: {}\nURL: {}\n\n{}", url, url, transcript),
+                    needs_auth: false,
+                    structure: PageStructure::default(),
+                    image_url: YouTubeProcessor::extract_video_id(url)
+                        .map(|id| format!("https://img.youtube.com/vi/{}/hqdefault.jpg", id)),
+                    title: None,
+                });
+            }
+            Ok(None) => {
+                println!("No YouTube transcript available, using original content");
             }
             Err(e) => {
-                println!("Failed to fetch content from {}: {}", url, e);
-                Ok((
-                    format!(
-                        "Bookmark: {}\nURL: {}\n\n[Error fetching content: {}]",
-                        url, url, e
-                    ),
-                    false,
-                ))
+                println!(
+                    "Failed to fetch YouTube transcript: {}, using original content",
+                    e
+                );
             }
         }
     }
-}
 
-impl Default for BookmarkMonitor {
-    fn default() -> Self {
-        Self::new().unwrap().0
+    // Fallback to regular content fetching with auth detection
+    match fetcher.fetch_page_content_with_status(url).await {
+        Ok(result) => {
+            let content = if result.needs_auth {
+                // Auth-blocked: no useful content to embed, title is prepended by caller
+                String::new()
+            } else if result.content.is_empty() {
+                format!("Bookmark: {}\nURL: {}\n\n[No content extracted]", url, url)
+            } else {
+                format!("Bookmark: {}\nURL: {}\n\n{}", url, url, result.content)
+            };
+            Ok(BookmarkContent {
+                content,
+                needs_auth: result.needs_auth,
+                structure: result.structure,
+                image_url: result.image_url,
+                title: result.title,
+            })
+        }
+        Err(e) => {
+            println!("Failed to fetch content from {}: {}", url, e);
+            Err(e.to_string().into())
+        }
     }
 }
 
@@ -596,18 +789,137 @@ impl Default for BookmarkMonitor {
 impl BookmarkMonitor {
     pub fn for_profile(
         profile: &ChromeProfile,
-    ) -> Result<(Self, mpsc::UnboundedReceiver<Vec<BookmarkItem>>)> {
-        let (tx, rx) = mpsc::unbounded_channel();
-        Ok((
-            Self {
-                bookmarks_path: profile.bookmarks_path.clone(),
-                tx,
-            },
-            rx,
-        ))
+    ) -> Result<(Self, mpsc::UnboundedReceiver<BookmarkWatchEvent>)> {
+        Ok(Self::from_path(profile.bookmarks_path.clone()))
     }
 }
 
+impl BookmarkMonitor {
+    /// Monitors for the bookmark trees shown in the exclusion settings: the
+    /// default Chrome profile and, on macOS, Safari.
+    pub fn for_settings() -> Result<Vec<Self>> {
+        let mut monitors = Vec::new();
+        let chrome_error = match Self::new() {
+            Ok((monitor, _)) => {
+                monitors.push(monitor);
+                None
+            }
+            Err(e) => Some(e),
+        };
+
+        if let Some(profile) = get_safari_profile() {
+            monitors.push(Self::for_profile(&profile)?.0);
+        }
+
+        match chrome_error {
+            Some(e) if monitors.is_empty() => Err(e),
+            _ => Ok(monitors),
+        }
+    }
+}
+
+/// Folder names leading to the first bookmark for `url` under `roots`,
+/// outermost first and including the root itself, or `None` when no
+/// bookmark has that URL.
+pub fn find_bookmark_folder(roots: &[BookmarkItem], url: &str) -> Option<Vec<String>> {
+    fn search(item: &BookmarkItem, url: &str, path: &mut Vec<String>) -> bool {
+        let Some(children) = &item.children else {
+            return false;
+        };
+        path.push(item.name.clone());
+        for child in children {
+            if child.url.as_deref() == Some(url) || search(child, url, path) {
+                return true;
+            }
+        }
+        path.pop();
+        false
+    }
+
+    let mut path = Vec::new();
+    roots
+        .iter()
+        .any(|root| search(root, url, &mut path))
+        .then_some(path)
+}
+
+/// URLs of the bookmarks under the folder reached by following `path` (folder
+/// names, outermost first, as in `BookmarkFolder::path`) from `roots`,
+/// including those in its subfolders. Empty when there is no such folder.
+pub fn folder_urls(roots: &[BookmarkItem], path: &[String]) -> Vec<String> {
+    fn collect(item: &BookmarkItem, urls: &mut Vec<String>) {
+        if let Some(url) = &item.url {
+            urls.push(url.clone());
+        }
+        for child in item.children.iter().flatten() {
+            collect(child, urls);
+        }
+    }
+
+    let Some((first, rest)) = path.split_first() else {
+        return Vec::new();
+    };
+    let mut folder = roots.iter().find(|root| root.name == *first);
+    for name in rest {
+        folder = folder.and_then(|f| {
+            f.children
+                .iter()
+                .flatten()
+                .find(|child| child.url.is_none() && child.name == *name)
+        });
+    }
+
+    let mut urls = Vec::new();
+    if let Some(folder) = folder {
+        collect(folder, &mut urls);
+    }
+    urls
+}
+
+/// Whether a watcher event on the bookmarks directory concerns the bookmarks
+/// file itself: written in place, created, or renamed into place.
+fn is_bookmarks_file_event(event: &Event, file_name: &std::ffi::OsStr) -> bool {
+    matches!(event.kind, EventKind::Modify(_) | EventKind::Create(_))
+        && event
+            .paths
+            .iter()
+            .any(|path| path.file_name() == Some(file_name))
+}
+
+/// Read a bookmarks file, retrying with backoff while it is briefly locked by
+/// the browser (Windows sharing violations) or missing mid-replace.
+fn read_bookmarks_file(path: &Path) -> Result<String> {
+    let mut delay = READ_RETRY_DELAY;
+    let mut attempt = 1;
+    loop {
+        match fs::read_to_string(path) {
+            Ok(content) => return Ok(content),
+            Err(e) if attempt < READ_ATTEMPTS && is_transient_read_error(&e) => {
+                std::thread::sleep(delay);
+                delay *= 2;
+                attempt += 1;
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+/// Errors that usually clear up once the browser finishes saving.
+fn is_transient_read_error(error: &std::io::Error) -> bool {
+    // ERROR_SHARING_VIOLATION and ERROR_LOCK_VIOLATION on Windows
+    const SHARING_VIOLATION: i32 = 32;
+    const LOCK_VIOLATION: i32 = 33;
+
+    matches!(
+        error.kind(),
+        std::io::ErrorKind::NotFound | std::io::ErrorKind::PermissionDenied
+    ) || (cfg!(windows)
+        && matches!(
+            error.raw_os_error(),
+            Some(SHARING_VIOLATION) | Some(LOCK_VIOLATION)
+        ))
+}
+
 /// Return the Chrome "User Data" directory (parent of Default/, Profile 1/, etc.)
 pub fn get_chrome_data_dir() -> Result<PathBuf> {
     let home_dir = dirs::home_dir().ok_or("Could not find home directory")?;
@@ -651,7 +963,8 @@ pub fn get_all_chrome_profiles() -> Vec<ChromeProfile> {
         .filter_map(|entry| {
             let dir_name = entry.file_name().to_string_lossy().to_string();
 
-            // Only "Default" or "Profile N" directories
+            // Only "Default" or "Profile N" directories; this leaves out
+            // "Guest Profile" and "System Profile", which are not the user's
             let is_profile = dir_name == "Default"
                 || (dir_name.starts_with("Profile ") && dir_name[8..].parse::<u32>().is_ok());
 
@@ -665,6 +978,9 @@ pub fn get_all_chrome_profiles() -> Vec<ChromeProfile> {
             }
 
             let prefs_path = entry.path().join("Preferences");
+            if is_ephemeral_profile(&prefs_path) {
+                return None;
+            }
             let display_name = read_profile_display_name(&prefs_path, &dir_name);
 
             Some(ChromeProfile {
@@ -688,6 +1004,24 @@ pub fn get_all_chrome_profiles() -> Vec<ChromeProfile> {
     profiles
 }
 
+/// Discover every bookmark source: all Chrome profiles, then Safari when its
+/// bookmarks file exists.
+pub fn get_all_bookmark_profiles() -> Vec<ChromeProfile> {
+    let mut profiles = get_all_chrome_profiles();
+    profiles.extend(get_safari_profile());
+    profiles
+}
+
+/// Whether policy forces the profile to be ephemeral (managed guest-like
+/// sessions whose data is wiped on close), so its bookmarks are not indexed.
+fn is_ephemeral_profile(prefs_path: &Path) -> bool {
+    std::fs::read_to_string(prefs_path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .and_then(|json| json["profile"]["ephemeral_mode"].as_bool())
+        .unwrap_or(false)
+}
+
 /// Read the human-readable profile name from Chrome's Preferences JSON.
 /// Falls back to the directory name if parsing fails.
 fn read_profile_display_name(prefs_path: &Path, fallback: &str) -> String {
@@ -722,10 +1056,70 @@ fn read_profile_display_name(prefs_path: &Path, fallback: &str) -> String {
 mod tests {
     use super::*;
 
+    /// Chrome bookmarks with nested folders, an empty folder and all three roots
+    const FIXTURE: &str = include_str!("../tests/fixtures/chrome_bookmarks.json");
+
+    fn fixture_monitor() -> BookmarkMonitor {
+        BookmarkMonitor::from_json(FIXTURE).0
+    }
+
+    fn urls(bookmarks: &[BookmarkItemWithPath]) -> Vec<&str> {
+        bookmarks
+            .iter()
+            .filter_map(|b| b.item.url.as_deref())
+            .collect()
+    }
+
+    #[test]
+    fn test_watcher_accepts_atomic_saves_of_bookmarks_file_only() {
+        use notify::event::{CreateKind, ModifyKind, RemoveKind, RenameMode};
+
+        let file_name = std::ffi::OsStr::new("Bookmarks");
+        let dir = PathBuf::from("profile");
+        let event = |kind, name: &str| Event::new(kind).add_path(dir.join(name));
+
+        assert!(is_bookmarks_file_event(
+            &event(EventKind::Create(CreateKind::File), "Bookmarks"),
+            file_name
+        ));
+        assert!(is_bookmarks_file_event(
+            &event(
+                EventKind::Modify(ModifyKind::Name(RenameMode::To)),
+                "Bookmarks"
+            ),
+            file_name
+        ));
+        assert!(!is_bookmarks_file_event(
+            &event(EventKind::Create(CreateKind::File), "Bookmarks.tmp"),
+            file_name
+        ));
+        assert!(!is_bookmarks_file_event(
+            &event(EventKind::Remove(RemoveKind::File), "Bookmarks"),
+            file_name
+        ));
+    }
+
+    #[test]
+    fn test_read_bookmarks_file_retries_until_file_appears() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("Bookmarks");
+
+        let writer_path = path.clone();
+        let writer = std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(60));
+            fs::write(writer_path, "{}").unwrap();
+        });
+
+        assert_eq!(read_bookmarks_file(&path).unwrap(), "{}");
+        writer.join().unwrap();
+
+        assert!(read_bookmarks_file(&temp_dir.path().join("missing")).is_err());
+    }
+
     #[test]
     fn test_extract_bookmarks_with_exclusion_rules() {
         // This test will verify that exclusion rules filter out bookmarks
-        let monitor = BookmarkMonitor::new().unwrap().0;
+        let monitor = fixture_monitor();
         let exclusion_rules = ExclusionRules::new(
             vec!["excluded_folder_id".to_string()],
             vec!["*.internal.com".to_string()],
@@ -756,7 +1150,7 @@ mod tests {
 
     #[test]
     fn test_extract_bookmarks_excludes_by_folder() {
-        let monitor = BookmarkMonitor::new().unwrap().0;
+        let monitor = fixture_monitor();
         let exclusion_rules = ExclusionRules::new(vec!["excluded_folder".to_string()], vec![]);
 
         let mut bookmarks = Vec::new();
@@ -783,7 +1177,7 @@ mod tests {
 
     #[test]
     fn test_extract_bookmarks_excludes_by_domain() {
-        let monitor = BookmarkMonitor::new().unwrap().0;
+        let monitor = fixture_monitor();
         let exclusion_rules = ExclusionRules::new(vec![], vec!["*.internal.com".to_string()]);
 
         let mut bookmarks = Vec::new();
@@ -810,7 +1204,7 @@ mod tests {
 
     #[test]
     fn test_extract_bookmarks_tracks_folder_path() {
-        let monitor = BookmarkMonitor::new().unwrap().0;
+        let monitor = fixture_monitor();
         let exclusion_rules = ExclusionRules::empty();
 
         let mut bookmarks = Vec::new();
@@ -839,28 +1233,192 @@ mod tests {
 
     #[test]
     fn test_get_bookmark_folders_structure() {
-        // Test that we can extract folder structure from Chrome bookmarks
-        let monitor = BookmarkMonitor::new().unwrap().0;
-        let folders = monitor.get_bookmark_folders();
-
-        // Should return folders from actual Chrome bookmarks file (if it exists)
-        // This will be > 0 if Chrome bookmarks exist, 0 otherwise
-        println!("Found {} bookmark folders", folders.len());
-
-        // Verify structure if folders exist
-        if !folders.is_empty() {
-            for folder in folders.iter().take(3) {
-                assert!(!folder.id.is_empty(), "Folder ID should not be empty");
-                assert!(!folder.name.is_empty(), "Folder name should not be empty");
-                assert!(
-                    folder.bookmark_count >= 0,
-                    "Bookmark count should be non-negative"
-                );
-                println!(
-                    "  Folder: {} (ID: {}, Count: {})",
-                    folder.name, folder.id, folder.bookmark_count
-                );
-            }
+        let folders = fixture_monitor().get_bookmark_folders();
+
+        let summary: Vec<(&str, &str, usize)> = folders
+            .iter()
+            .map(|f| (f.id.as_str(), f.name.as_str(), f.bookmark_count))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("1", "Bookmarks bar", 5),
+                ("5", "Work", 3),
+                ("7", "Rust", 2),
+                ("10", "Archive", 0),
+                ("11", "Videos", 1),
+                ("2", "Other bookmarks", 1),
+                ("13", "Recipes", 1),
+                ("3", "Mobile bookmarks", 1),
+            ]
+        );
+        assert_eq!(folders[2].path, vec!["Bookmarks bar", "Work", "Rust"]);
+
+        // Every folder lists the bookmarks it counts
+        let monitor = fixture_monitor();
+        for folder in &folders {
+            assert_eq!(
+                monitor.urls_in_folder(&folder.path).len(),
+                folder.bookmark_count
+            );
         }
+        assert!(monitor
+            .urls_in_folder(&["Bookmarks bar".to_string(), "Missing".to_string()])
+            .is_empty());
+        assert!(monitor.urls_in_folder(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_parse_bookmarks_from_json_and_file_sources() {
+        let from_json = fixture_monitor().parse_bookmarks().unwrap();
+        assert_eq!(from_json.len(), 7);
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("Bookmarks");
+        fs::write(&path, FIXTURE).unwrap();
+        let from_file = BookmarkMonitor::from_path(&path)
+            .0
+            .parse_bookmarks()
+            .unwrap();
+        let ids = |items: &[BookmarkItem]| items.iter().map(|b| b.id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(&from_file), ids(&from_json));
+
+        assert!(BookmarkMonitor::from_json("{}")
+            .0
+            .parse_bookmarks()
+            .is_err());
+        assert!(BookmarkMonitor::from_path(temp_dir.path().join("missing"))
+            .0
+            .parse_bookmarks()
+            .is_err());
+    }
+
+    #[test]
+    fn test_in_memory_bookmarks_cannot_be_watched() {
+        assert!(fixture_monitor().start_monitoring().is_err());
+    }
+
+    #[test]
+    fn test_nested_folder_and_domain_exclusions() {
+        let monitor = fixture_monitor();
+        let all = monitor
+            .get_bookmarks_with_paths_with_exclusion(&ExclusionRules::empty())
+            .unwrap();
+        assert_eq!(all.len(), 7);
+        let tokio = all
+            .iter()
+            .find(|b| b.item.url.as_deref() == Some("https://tokio.rs/tokio/tutorial"))
+            .unwrap();
+        assert_eq!(tokio.folder_path, vec!["Bookmarks bar", "Work", "Rust"]);
+        assert_eq!(tokio.folder_id, "7");
+
+        // Excluding a folder drops its subfolders too
+        let work = ExclusionRules::new(vec!["5".to_string()], vec![]);
+        let kept = monitor
+            .get_bookmarks_with_paths_with_exclusion(&work)
+            .unwrap();
+        assert_eq!(
+            urls(&kept),
+            vec![
+                "https://blog.rust-lang.org/",
+                "https://www.youtube.com/watch?v=abc123",
+                "https://example.com/sourdough",
+                "https://news.example.org/",
+            ]
+        );
+
+        // Folder and domain rules combine
+        let rules = ExclusionRules::new(vec!["7".to_string()], vec!["*.internal.com".to_string()]);
+        let kept = monitor
+            .get_bookmarks_with_paths_with_exclusion(&rules)
+            .unwrap();
+        assert_eq!(urls(&kept).len(), 4);
+        assert!(!urls(&kept).contains(&"https://wiki.internal.com/home"));
+
+        // A whole root can be excluded
+        let synced = ExclusionRules::new(vec!["3".to_string()], vec![]);
+        let kept = monitor
+            .get_bookmarks_with_paths_with_exclusion(&synced)
+            .unwrap();
+        assert!(!urls(&kept).contains(&"https://news.example.org/"));
+        assert_eq!(kept.len(), 6);
+    }
+
+    #[tokio::test]
+    async fn test_metadata_applies_exclusions_and_cleans_youtube_titles() {
+        let rules = ExclusionRules::new(vec![], vec!["*.internal.com".to_string()]);
+        let metadata = fixture_monitor()
+            .get_bookmarks_metadata_with_exclusion(&rules)
+            .await
+            .unwrap();
+        assert_eq!(metadata.len(), 6);
+        assert!(metadata.contains(&(
+            "Rust talk".to_string(),
+            "https://www.youtube.com/watch?v=abc123".to_string()
+        )));
+    }
+
+    #[test]
+    fn test_diff_bookmarks_by_url() {
+        let before = fixture_monitor().parse_bookmarks().unwrap();
+        assert!(diff_bookmarks(&before, &before).is_empty());
+
+        let mut after: Vec<BookmarkItem> =
+            before.iter().filter(|b| b.id != "14").cloned().collect();
+        let mut added = before[0].clone();
+        added.id = "20".to_string();
+        added.url = Some("https://serde.rs/".to_string());
+        after.push(added);
+        // Bookmarking an existing URL again, e.g. in another folder, is no change
+        let mut duplicate = before[1].clone();
+        duplicate.id = "21".to_string();
+        after.push(duplicate);
+
+        assert_eq!(
+            diff_bookmarks(&before, &after),
+            BookmarkDiff {
+                added: vec!["https://serde.rs/".to_string()],
+                removed: vec!["https://example.com/sourdough".to_string()],
+            }
+        );
+        assert_eq!(diff_bookmarks(&[], &before).added.len(), 7);
+    }
+
+    #[test]
+    fn test_find_bookmark_folder() {
+        let item =
+            |name: &str, url: Option<&str>, children: Option<Vec<BookmarkItem>>| BookmarkItem {
+                date_added: "1234567890".to_string(),
+                date_modified: None,
+                id: name.to_string(),
+                name: name.to_string(),
+                url: url.map(str::to_string),
+                children,
+            };
+        let roots = vec![
+            item(
+                "Bookmarks bar",
+                None,
+                Some(vec![
+                    item("Top", Some("https://top.example.com"), None),
+                    item(
+                        "Work",
+                        None,
+                        Some(vec![item("Docs", Some("https://docs.rs"), None)]),
+                    ),
+                ]),
+            ),
+            item("Other bookmarks", None, Some(vec![])),
+        ];
+
+        assert_eq!(
+            find_bookmark_folder(&roots, "https://docs.rs"),
+            Some(vec!["Bookmarks bar".to_string(), "Work".to_string()])
+        );
+        assert_eq!(
+            find_bookmark_folder(&roots, "https://top.example.com"),
+            Some(vec!["Bookmarks bar".to_string()])
+        );
+        assert_eq!(find_bookmark_folder(&roots, "https://missing.dev"), None);
     }
 }
@@ -88,6 +88,18 @@ impl ExclusionRules {
         Ok(())
     }
 
+    /// Exact-match pattern that excludes a URL's host (leading "www." dropped,
+    /// since exact patterns also match the www. host).
+    pub fn pattern_for_url(url: &str) -> Option<String> {
+        let parsed = Url::parse(url).ok()?;
+        let host = parsed.host_str()?;
+        let host = host.strip_prefix("www.").unwrap_or(host);
+        Some(match parsed.port() {
+            Some(port) => format!("{}:{}", host, port),
+            None => host.to_string(),
+        })
+    }
+
     /// Matches a URL against a domain pattern
     fn matches_domain_pattern(url: &str, pattern: &str) -> bool {
         // Parse URL to extract host
@@ -168,6 +180,21 @@ impl ExclusionRules {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_pattern_for_url_excludes_its_host() {
+        let url = "https://www.example.com/some/page";
+        let pattern = ExclusionRules::pattern_for_url(url).unwrap();
+        assert_eq!(pattern, "example.com");
+        assert!(ExclusionRules::validate_pattern(&pattern).is_ok());
+        assert!(ExclusionRules::new(vec![], vec![pattern]).is_url_excluded(url));
+
+        assert_eq!(
+            ExclusionRules::pattern_for_url("http://localhost:8080/x").as_deref(),
+            Some("localhost:8080")
+        );
+        assert_eq!(ExclusionRules::pattern_for_url("not a url"), None);
+    }
+
     #[test]
     fn test_validate_pattern_empty() {
         let result = ExclusionRules::validate_pattern("");
@@ -0,0 +1,266 @@
+//! Bookmark file import
+//!
+//! Parses bookmark exports from other browsers and feed readers so they can
+//! go through the same fetch/ingest queue as Chrome bookmarks:
+//!
+//! - Netscape bookmark HTML (`<DL><DT><A HREF=...>`), exported by Firefox,
+//!   Safari, Edge, Chrome and most bookmark managers
+//! - OPML outlines, as exported by feed readers. A feed is imported as its
+//!   website (`htmlUrl`), falling back to the feed URL itself.
+
+use crate::bookmark_exclusion::ExclusionRules;
+use crate::Result;
+use regex::Regex;
+use std::path::Path;
+
+/// Queue profile name for imported bookmarks, so an interrupted import can be
+/// resumed separately from Chrome profiles.
+pub const IMPORT_PROFILE: &str = "Imported";
+
+/// Supported bookmark file formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    NetscapeHtml,
+    Opml,
+}
+
+impl ImportFormat {
+    /// Guess the format from the file contents.
+    pub fn detect(text: &str) -> Option<Self> {
+        let head: String = text.chars().take(2048).collect::<String>().to_lowercase();
+        if head.contains("<opml") {
+            Some(ImportFormat::Opml)
+        } else if head.contains("netscape-bookmark-file") || head.contains("<dl") {
+            Some(ImportFormat::NetscapeHtml)
+        } else {
+            None
+        }
+    }
+}
+
+/// A bookmark read from an import file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedBookmark {
+    pub title: String,
+    pub url: String,
+    /// Folder names from the top of the file down to the containing folder
+    pub folder_path: Vec<String>,
+}
+
+/// Read and parse a bookmark file, detecting its format.
+pub fn read_bookmark_file(path: &Path) -> Result<Vec<ImportedBookmark>> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+    parse_bookmark_file(&text)
+}
+
+/// Parse the contents of a bookmark file, detecting its format.
+pub fn parse_bookmark_file(text: &str) -> Result<Vec<ImportedBookmark>> {
+    let bookmarks = match ImportFormat::detect(text) {
+        Some(ImportFormat::NetscapeHtml) => parse_netscape_html(text),
+        Some(ImportFormat::Opml) => parse_opml(text),
+        None => return Err("Not a bookmark HTML export or OPML file".into()),
+    };
+    if bookmarks.is_empty() {
+        return Err("No bookmarks found in file".into());
+    }
+    Ok(bookmarks)
+}
+
+/// Parse a Netscape bookmark HTML export.
+///
+/// Folders are `<H3>` headings, each followed by a `<DL>` list holding its
+/// contents. Only http(s) links are kept.
+pub fn parse_netscape_html(text: &str) -> Vec<ImportedBookmark> {
+    let token = Regex::new(
+        r#"(?is)<h3[^>]*>(.*?)</h3>|<a\s[^>]*?href\s*=\s*"([^"]*)"[^>]*>(.*?)</a>|<dl[^>]*>|</dl>"#,
+    )
+    .expect("valid regex");
+
+    // One entry per open <DL>; None for lists that are not a named folder
+    let mut stack: Vec<Option<String>> = Vec::new();
+    let mut pending_folder: Option<String> = None;
+    let mut bookmarks = Vec::new();
+
+    for caps in token.captures_iter(text) {
+        if let Some(folder) = caps.get(1) {
+            pending_folder = Some(unescape(folder.as_str()));
+        } else if let Some(href) = caps.get(2) {
+            let url = unescape(href.as_str());
+            if is_web_url(&url) {
+                let title = unescape(caps.get(3).map_or("", |m| m.as_str()));
+                bookmarks.push(ImportedBookmark {
+                    title: if title.is_empty() { url.clone() } else { title },
+                    url,
+                    folder_path: stack.iter().flatten().cloned().collect(),
+                });
+            }
+        } else if caps[0].starts_with("</") {
+            stack.pop();
+        } else {
+            stack.push(pending_folder.take());
+        }
+    }
+
+    bookmarks
+}
+
+/// Parse an OPML outline. Outlines with children become folders.
+pub fn parse_opml(text: &str) -> Vec<ImportedBookmark> {
+    let token = Regex::new(r"(?is)<outline\b([^>]*?)(/?)>|</outline>").expect("valid regex");
+    let attribute = Regex::new(r#"(?s)([\w:]+)\s*=\s*"([^"]*)""#).expect("valid regex");
+
+    let mut stack: Vec<String> = Vec::new();
+    let mut bookmarks = Vec::new();
+
+    for caps in token.captures_iter(text) {
+        let Some(attrs) = caps.get(1) else {
+            stack.pop();
+            continue;
+        };
+
+        let mut title = None;
+        let mut html_url = None;
+        let mut feed_url = None;
+        for attr in attribute.captures_iter(attrs.as_str()) {
+            let value = unescape(&attr[2]);
+            match attr[1].to_lowercase().as_str() {
+                "title" => title = Some(value),
+                "text" => {
+                    title.get_or_insert(value);
+                }
+                "htmlurl" | "url" => html_url = Some(value),
+                "xmlurl" => feed_url = Some(value),
+                _ => {}
+            }
+        }
+
+        if let Some(url) = html_url.or(feed_url).filter(|u| is_web_url(u)) {
+            bookmarks.push(ImportedBookmark {
+                title: title.clone().unwrap_or_else(|| url.clone()),
+                url,
+                folder_path: stack.clone(),
+            });
+        }
+
+        let self_closing = caps.get(2).is_some_and(|m| !m.as_str().is_empty());
+        if !self_closing {
+            stack.push(title.unwrap_or_default());
+        }
+    }
+
+    bookmarks
+}
+
+/// Keep bookmarks not excluded by the domain rules, as `(title, url)` pairs
+/// for the ingest queue.
+///
+/// Folder rules refer to Chrome folder IDs, which imported files do not have,
+/// so only domain patterns apply.
+pub fn apply_exclusion_rules(
+    bookmarks: &[ImportedBookmark],
+    rules: &ExclusionRules,
+) -> Vec<(String, String)> {
+    bookmarks
+        .iter()
+        .filter(|b| !rules.is_url_excluded(&b.url))
+        .map(|b| (b.title.clone(), b.url.clone()))
+        .collect()
+}
+
+fn is_web_url(url: &str) -> bool {
+    let lower = url.to_lowercase();
+    lower.starts_with("http://") || lower.starts_with("https://")
+}
+
+/// Decode the handful of entities bookmark exporters emit, and drop any
+/// markup left inside titles.
+pub(crate) fn unescape(text: &str) -> String {
+    let without_tags = Regex::new(r"<[^>]*>")
+        .expect("valid regex")
+        .replace_all(text, "");
+    without_tags
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NETSCAPE: &str = r#"<!DOCTYPE NETSCAPE-Bookmark-file-1>
+<META HTTP-EQUIV="Content-Type" CONTENT="text/html; charset=UTF-8">
+<TITLE>Bookmarks</TITLE>
+<H1>Bookmarks</H1>
+<DL><p>
+    <DT><H3 ADD_DATE="1">Bookmarks Bar</H3>
+    <DL><p>
+        <DT><A HREF="https://www.rust-lang.org/" ADD_DATE="1">Rust</A>
+        <DT><H3>Work &amp; Stuff</H3>
+        <DL><p>
+            <DT><A HREF="https://example.com/?a=1&amp;b=2">Example</A>
+            <DT><A HREF="javascript:void(0)">Bookmarklet</A>
+        </DL><p>
+    </DL><p>
+    <DT><A HREF="https://top.example/">Top level</A>
+</DL><p>
+"#;
+
+    const OPML: &str = r#"<?xml version="1.0"?>
+<opml version="2.0">
+  <body>
+    <outline text="Tech">
+      <outline text="Blog" type="rss" xmlUrl="https://blog.example/feed" htmlUrl="https://blog.example/"/>
+      <outline text="Feed only" type="rss" xmlUrl="https://feed.example/rss.xml"/>
+    </outline>
+    <outline text="Loose" type="link" url="https://loose.example/"/>
+  </body>
+</opml>"#;
+
+    #[test]
+    fn parses_netscape_folders_and_links() {
+        assert_eq!(
+            ImportFormat::detect(NETSCAPE),
+            Some(ImportFormat::NetscapeHtml)
+        );
+        let bookmarks = parse_bookmark_file(NETSCAPE).unwrap();
+        assert_eq!(bookmarks.len(), 3);
+        assert_eq!(bookmarks[0].title, "Rust");
+        assert_eq!(bookmarks[0].folder_path, vec!["Bookmarks Bar"]);
+        assert_eq!(bookmarks[1].url, "https://example.com/?a=1&b=2");
+        assert_eq!(
+            bookmarks[1].folder_path,
+            vec!["Bookmarks Bar", "Work & Stuff"]
+        );
+        assert!(bookmarks[2].folder_path.is_empty());
+    }
+
+    #[test]
+    fn parses_opml_feeds_as_sites() {
+        assert_eq!(ImportFormat::detect(OPML), Some(ImportFormat::Opml));
+        let bookmarks = parse_bookmark_file(OPML).unwrap();
+        assert_eq!(bookmarks.len(), 3);
+        assert_eq!(bookmarks[0].url, "https://blog.example/");
+        assert_eq!(bookmarks[0].folder_path, vec!["Tech"]);
+        assert_eq!(bookmarks[1].url, "https://feed.example/rss.xml");
+        assert_eq!(bookmarks[2].title, "Loose");
+        assert!(bookmarks[2].folder_path.is_empty());
+    }
+
+    #[test]
+    fn rejects_other_files_and_applies_domain_rules() {
+        assert!(parse_bookmark_file("just some text").is_err());
+
+        let bookmarks = parse_bookmark_file(NETSCAPE).unwrap();
+        let rules = ExclusionRules::new(vec![], vec!["example.com".to_string()]);
+        let kept = apply_exclusion_rules(&bookmarks, &rules);
+        assert_eq!(kept.len(), 2);
+        assert!(kept.iter().all(|(_, url)| !url.contains("example.com")));
+    }
+}
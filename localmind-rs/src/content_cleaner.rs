@@ -0,0 +1,402 @@
+//! Content cleaning by extraction method
+//!
+//! Documents reach the HTTP API already extracted by the extension, but how
+//! they were extracted decides what junk they carry: the Google Docs mobile
+//! view leaks inline CSS and JavaScript, raw HTML needs converting to text,
+//! and DOM or clipboard text only needs its whitespace tidied. Each kind of
+//! cleanup is a `ContentCleaner`; a `CleanerPipeline` maps every
+//! `ExtractionMethod` to the cleaners run for it, and new sources register
+//! their own with `CleanerPipeline::register`.
+
+use regex::Regex;
+use std::collections::HashMap;
+
+/// How the extension (or another client) extracted a document's text.
+///
+/// Parsed from the `extractionMethod` field of `POST /documents`; unknown
+/// values are treated as DOM text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExtractionMethod {
+    /// `innerText` of the page
+    Dom,
+    /// Copied via the clipboard from canvas-rendered pages
+    Clipboard,
+    /// Google Docs mobile basic view (`google-docs-mobilebasic`,
+    /// `google-docs-export`)
+    GoogleDocs,
+    /// Raw HTML markup
+    Html,
+    /// Already clean text (CLI, scripts)
+    PlainText,
+}
+
+impl ExtractionMethod {
+    pub fn parse(value: &str) -> Self {
+        let value = value.trim().to_lowercase();
+        match value.as_str() {
+            "clipboard" => ExtractionMethod::Clipboard,
+            "html" => ExtractionMethod::Html,
+            "text" | "plain" | "plaintext" | "cli" => ExtractionMethod::PlainText,
+            v if v.starts_with("google-docs") => ExtractionMethod::GoogleDocs,
+            _ => ExtractionMethod::Dom,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ExtractionMethod::Dom => "dom",
+            ExtractionMethod::Clipboard => "clipboard",
+            ExtractionMethod::GoogleDocs => "google-docs",
+            ExtractionMethod::Html => "html",
+            ExtractionMethod::PlainText => "text",
+        }
+    }
+}
+
+/// One cleanup step applied to extracted content.
+pub trait ContentCleaner: Send + Sync {
+    /// Short name for logs.
+    fn name(&self) -> &'static str;
+
+    fn clean(&self, content: &str) -> String;
+}
+
+/// Cleaners to run for each extraction method, in order.
+pub struct CleanerPipeline {
+    stages: HashMap<ExtractionMethod, Vec<Box<dyn ContentCleaner>>>,
+}
+
+impl Default for CleanerPipeline {
+    fn default() -> Self {
+        let mut pipeline = Self::empty();
+        pipeline.register(ExtractionMethod::Dom, PlainTextCleaner);
+        pipeline.register(ExtractionMethod::Clipboard, PlainTextCleaner);
+        pipeline.register(ExtractionMethod::PlainText, PlainTextCleaner);
+        pipeline.register(ExtractionMethod::GoogleDocs, GoogleDocsCleaner);
+        pipeline.register(ExtractionMethod::GoogleDocs, PlainTextCleaner);
+        pipeline.register(ExtractionMethod::Html, GenericHtmlCleaner);
+        pipeline.register(ExtractionMethod::Html, PlainTextCleaner);
+        pipeline
+    }
+}
+
+impl CleanerPipeline {
+    /// A pipeline with no cleaners; content passes through unchanged.
+    pub fn empty() -> Self {
+        Self {
+            stages: HashMap::new(),
+        }
+    }
+
+    /// Append a cleaner to the stages run for `method`.
+    pub fn register(&mut self, method: ExtractionMethod, cleaner: impl ContentCleaner + 'static) {
+        self.stages
+            .entry(method)
+            .or_default()
+            .push(Box::new(cleaner));
+    }
+
+    /// Run every cleaner registered for `method` over `content`.
+    pub fn clean(&self, method: ExtractionMethod, content: &str) -> String {
+        let Some(stages) = self.stages.get(&method) else {
+            return content.to_string();
+        };
+
+        let mut cleaned = content.to_string();
+        for stage in stages {
+            let before = cleaned.len();
+            cleaned = stage.clean(&cleaned);
+            log::debug!(
+                "{} cleaner ({}): {} -> {} bytes",
+                stage.name(),
+                method.as_str(),
+                before,
+                cleaned.len()
+            );
+        }
+        cleaned
+    }
+}
+
+/// Normalizes whitespace: CRLF to LF, control characters dropped, trailing
+/// spaces trimmed and runs of blank lines collapsed to one.
+pub struct PlainTextCleaner;
+
+impl ContentCleaner for PlainTextCleaner {
+    fn name(&self) -> &'static str {
+        "plain-text"
+    }
+
+    fn clean(&self, content: &str) -> String {
+        let normalized: String = content
+            .replace("\r\n", "\n")
+            .replace('\r', "\n")
+            .chars()
+            .filter(|c| !c.is_control() || *c == '\n' || *c == '\t')
+            .collect();
+
+        let mut lines: Vec<&str> = Vec::new();
+        let mut previous_blank = true;
+        for line in normalized.lines().map(str::trim_end) {
+            let blank = line.trim().is_empty();
+            if blank && previous_blank {
+                continue;
+            }
+            lines.push(if blank { "" } else { line });
+            previous_blank = blank;
+        }
+        lines.join("\n").trim().to_string()
+    }
+}
+
+/// Converts HTML markup to text, dropping scripts and stylesheets. Lines
+/// are not wrapped (see `html_text`).
+pub struct GenericHtmlCleaner;
+
+impl ContentCleaner for GenericHtmlCleaner {
+    fn name(&self) -> &'static str {
+        "html"
+    }
+
+    fn clean(&self, content: &str) -> String {
+        crate::html_text::extract(content, None).text
+    }
+}
+
+/// Strips the inline JavaScript, CSS imports and list-style rules that the
+/// Google Docs mobile basic view leaks into extracted text.
+pub struct GoogleDocsCleaner;
+
+impl ContentCleaner for GoogleDocsCleaner {
+    fn name(&self) -> &'static str {
+        "google-docs"
+    }
+
+    fn clean(&self, content: &str) -> String {
+        let mut cleaned = content.to_string();
+
+        // Strategy: Conservatively remove only clearly identifiable CSS/JS artifacts
+        // Google Docs mobile basic view structure:
+        // 1. JavaScript error handling (if ((!this['DOCS_initDocsMobileWeb'])...)
+        // 2. DOCS_initDocsMobileWeb(...args...) call
+        // 3. CSS imports and styles
+        // 4. Actual document content
+
+        // Remove everything up to and including the DOCS_initDocsMobileWeb call
+        // This is safe because it's always JavaScript initialization code
+        if let Some(init_pos) = cleaned.find("DOCS_initDocsMobileWeb(") {
+            if let Some(close_paren) = cleaned[init_pos..].find(");") {
+                cleaned.replace_range(0..init_pos + close_paren + 2, "");
+            }
+        }
+
+        // Remove CSS imports (@import url(...);) - these are always at the top
+        let css_import_re = Regex::new(r"@import\s+url\([^)]+\);?").unwrap();
+        cleaned = css_import_re.replace_all(&cleaned, "").to_string();
+
+        // Remove ALL CSS blocks - be aggressive since we know Google Docs mobile view has lots of CSS
+        // Match any CSS selector followed by braces with CSS properties
+        // This catches: ul.lst-kix_list_b-8{list-style-type:none}, .class{property:value}, etc.
+        let css_block_re = Regex::new(r"[\.\#\w\-]+\s*\{[^}]*\}").unwrap();
+        cleaned = css_block_re.replace_all(&cleaned, "").to_string();
+
+        // Remove CSS selectors with child combinators (e.g., ".lst-kix_list_13-0 > li{")
+        let css_child_re = Regex::new(r"\.[\w\-]+\s*>\s*[\w\-]+\s*\{[^}]*\}").unwrap();
+        cleaned = css_child_re.replace_all(&cleaned, "").to_string();
+
+        // Remove list style counter rules with :before pseudo-elements
+        let css_before_re = Regex::new(r"\.[\w\-]+\s*>\s*[^{]*:before\s*\{[^}]*\}").unwrap();
+        cleaned = css_before_re.replace_all(&cleaned, "").to_string();
+
+        // Remove standalone CSS selectors that appear before text (e.g., ".lst-kix_list_c-0 >")
+        // Match the pattern and the capital letter, then replace with just the capital
+        let css_selector_re = Regex::new(r"\.lst-kix_[\w\-]+\s*>\s+([A-Z])").unwrap();
+        cleaned = css_selector_re.replace_all(&cleaned, "$1").to_string();
+
+        // Remove any remaining CSS-like patterns that start with ul., ol., .lst-kix, etc.
+        let css_list_re = Regex::new(r"(?:ul|ol)\.lst-kix_[\w\-]+").unwrap();
+        cleaned = css_list_re.replace_all(&cleaned, "").to_string();
+
+        // Remove any remaining .lst-kix patterns
+        let css_lst_kix_re = Regex::new(r"\.lst-kix_[\w\-]+").unwrap();
+        cleaned = css_lst_kix_re.replace_all(&cleaned, "").to_string();
+
+        // Remove setTimeout and other window. JavaScript calls
+        let js_call_re = Regex::new(r"window\.[a-zA-Z]+\([^)]*\);?").unwrap();
+        cleaned = js_call_re.replace_all(&cleaned, "").to_string();
+
+        // Remove counter-reset and counter-increment rules (these are CSS-only)
+        let counter_re = Regex::new(r"counter-(?:reset|increment):\s*[^;}]+[;}]").unwrap();
+        cleaned = counter_re.replace_all(&cleaned, "").to_string();
+
+        // Remove CSS properties that appear standalone (not in blocks)
+        // Only match if they look like CSS (property: value; format)
+        let css_prop_re = Regex::new(r"^\s*[a-z\-]+:\s*[^;]+;\s*$").unwrap();
+        cleaned = css_prop_re.replace_all(&cleaned, "").to_string();
+
+        // Line-based filter: remove lines that are clearly CSS/JS remnants.
+        // This catches patterns that the regex-based cleanup above may miss,
+        // e.g. concatenated CSS rules or :before{content:"..."} fragments.
+        cleaned = cleaned
+            .lines()
+            .filter(|line| {
+                let trimmed = line.trim();
+                if trimmed.is_empty() {
+                    return true;
+                }
+                // Drop lines containing CSS patterns that never appear in real content
+                let is_css_junk = trimmed.contains("lst-kix_")
+                    || trimmed.contains("list-style-type:")
+                    || trimmed.contains(":before{")
+                    || trimmed.contains("{content:");
+                !is_css_junk
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        // Find where actual document content starts by scanning for the first line
+        // that looks like real prose or a heading: mostly alphabetic, no CSS/JS structure chars,
+        // longer than 10 characters. This is more robust than hardcoded title strings.
+        let mut content_start = None;
+        let mut scan_pos = 0usize;
+        for line in cleaned.lines() {
+            if scan_pos >= 5000 {
+                break;
+            }
+            let alpha_count = line.chars().filter(|c| c.is_alphabetic()).count();
+            let has_css_chars = line.contains('{')
+                || line.contains('}')
+                || line.contains(';')
+                || line.contains('@');
+            if line.len() > 10 && !has_css_chars && alpha_count * 2 > line.len() {
+                content_start = Some(scan_pos);
+                break;
+            }
+            // lines() strips the newline character, add 1 to account for it
+            scan_pos += line.len() + 1;
+        }
+
+        // If we found a content marker, remove everything before it
+        // But first check that what we're removing is actually junk
+        if let Some(start_pos) = content_start {
+            if start_pos > 0 {
+                let leading_text = &cleaned[..start_pos];
+                // Check if leading text is mostly CSS/JS junk
+                let has_css_js = leading_text.contains("lst-kix")
+                    || leading_text.contains("list-style-type")
+                    || leading_text.contains("DOCS_")
+                    || leading_text.contains("@import")
+                    || leading_text.contains("window.")
+                    || (leading_text.matches('{').count() > 5
+                        && leading_text.matches('}').count() > 5);
+
+                // Also check if it's mostly non-alphabetic (CSS/JS is mostly punctuation)
+                let alpha_count = leading_text.chars().filter(|c| c.is_alphabetic()).count();
+                let is_mostly_junk =
+                    leading_text.len() > 50 && alpha_count < leading_text.len() / 3;
+
+                if has_css_js || is_mostly_junk {
+                    cleaned.replace_range(0..start_pos, "");
+                }
+            }
+        }
+
+        // Clean up excessive whitespace (3 or more spaces/newlines → 2 newlines)
+        let whitespace_re = Regex::new(r"\s{3,}").unwrap();
+        cleaned = whitespace_re.replace_all(&cleaned, "\n\n").to_string();
+
+        // Remove empty lines at the start and end, but preserve content
+        let empty_lines_start_re = Regex::new(r"^\s*\n+").unwrap();
+        cleaned = empty_lines_start_re.replace(&cleaned, "").to_string();
+        let empty_lines_end_re = Regex::new(r"\n+\s*$").unwrap();
+        cleaned = empty_lines_end_re.replace(&cleaned, "").to_string();
+
+        // Trim and return - but don't truncate!
+        cleaned.trim().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_extension_method_names() {
+        assert_eq!(
+            ExtractionMethod::parse("google-docs-export"),
+            ExtractionMethod::GoogleDocs
+        );
+        assert_eq!(
+            ExtractionMethod::parse("google-docs-mobilebasic"),
+            ExtractionMethod::GoogleDocs
+        );
+        assert_eq!(
+            ExtractionMethod::parse("Clipboard"),
+            ExtractionMethod::Clipboard
+        );
+        assert_eq!(ExtractionMethod::parse("cli"), ExtractionMethod::PlainText);
+        assert_eq!(ExtractionMethod::parse("whatever"), ExtractionMethod::Dom);
+    }
+
+    #[test]
+    fn plain_text_cleaner_collapses_blank_lines() {
+        let cleaned = PlainTextCleaner.clean("\r\n  Title  \r\n\r\n\r\n\nBody\u{0}text   \n\n");
+        assert_eq!(cleaned, "Title\n\nBodytext");
+    }
+
+    #[test]
+    fn html_cleaner_drops_scripts_and_tags() {
+        let html = "<html><head><style>p { color: red; }</style>\
+            <script>alert('x')</script></head>\
+            <body><h1>Heading</h1><p>Some <b>bold</b> text.</p></body></html>";
+        let cleaned = CleanerPipeline::default().clean(ExtractionMethod::Html, html);
+        assert!(cleaned.contains("Heading"));
+        assert!(cleaned.contains("bold"));
+        assert!(!cleaned.contains("color: red"));
+        assert!(!cleaned.contains("alert"));
+        assert!(!cleaned.contains("<p>"));
+    }
+
+    #[test]
+    fn html_cleaner_does_not_wrap_lines() {
+        let sentence = "A paragraph long enough to have been wrapped at eighty columns before, \
+            which split sentences across lines.";
+        let html = format!("<p>{}</p><p>Next</p>", sentence);
+        let cleaned = CleanerPipeline::default().clean(ExtractionMethod::Html, &html);
+        assert_eq!(cleaned, format!("{}\n\nNext", sentence));
+    }
+
+    #[test]
+    fn google_docs_cleaner_removes_css_and_js() {
+        let raw = "if ((!this['DOCS_initDocsMobileWeb'])) {} DOCS_initDocsMobileWeb(1, 2);\
+            @import url(https://themes.googleusercontent.com/fonts/css?kit=abc);\
+            ul.lst-kix_list_1-0{list-style-type:none}.c1{font-weight:700}\n\
+            Meeting notes for the quarterly planning session\n\n\n\n\
+            We agreed to ship the new importer first.";
+        let cleaned = CleanerPipeline::default().clean(ExtractionMethod::GoogleDocs, raw);
+        assert!(cleaned.starts_with("Meeting notes for the quarterly planning session"));
+        assert!(cleaned.contains("We agreed to ship the new importer first."));
+        assert!(!cleaned.contains("lst-kix"));
+        assert!(!cleaned.contains("DOCS_init"));
+        assert!(!cleaned.contains("@import"));
+    }
+
+    #[test]
+    fn registered_cleaners_run_in_order() {
+        struct Upper;
+        impl ContentCleaner for Upper {
+            fn name(&self) -> &'static str {
+                "upper"
+            }
+            fn clean(&self, content: &str) -> String {
+                content.to_uppercase()
+            }
+        }
+
+        let mut pipeline = CleanerPipeline::empty();
+        assert_eq!(pipeline.clean(ExtractionMethod::Dom, " a "), " a ");
+        pipeline.register(ExtractionMethod::Dom, Upper);
+        pipeline.register(ExtractionMethod::Dom, PlainTextCleaner);
+        assert_eq!(pipeline.clean(ExtractionMethod::Dom, " a "), "A");
+    }
+}
@@ -1,15 +1,32 @@
+use crate::document::ChunkProvenance;
+use crate::html_text::PageStructure;
+use crate::query_syntax::ParsedQuery;
 use crate::Result;
 use rusqlite::{params, Connection};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use tokio::sync::{Semaphore, SemaphorePermit};
 
+/// A chunk ready to store: (chunk_start, chunk_end, serialized embedding,
+/// provenance). The provenance's content hash lets later ingests reuse the
+/// embedding when the same chunk text comes up again.
+pub type ChunkRow = (usize, usize, Vec<u8>, ChunkProvenance);
+
+/// Days either side of one year ago that count as "saved one year ago".
+pub const ON_THIS_DAY_WINDOW_DAYS: i64 = 3;
+
+/// Minimum age in days before a document can be resurfaced as forgotten.
+pub const FORGOTTEN_MIN_AGE_DAYS: i64 = 30;
+
 /// Normalize a URL for deduplication.
-/// Strips fragments (#...) and Google Docs query params (tab=, etc.)
-/// so that the same document isn't stored multiple times.
-pub fn normalize_url(url: &str) -> String {
-    // Strip fragment
+/// Strips fragments (#...), the `tracking_params` (see `tracking_params`)
+/// and Google Docs query params (tab=, etc.) so that the same document isn't
+/// stored multiple times. `Database::normalize_url` uses the database's own
+/// list.
+pub fn normalize_url(url: &str, tracking_params: &[String]) -> String {
+    // Strip fragment and tracking parameters
     let without_fragment = url.split('#').next().unwrap_or(url);
+    let without_fragment = &crate::tracking_params::strip(without_fragment, tracking_params);
 
     // For Google Docs, strip query params entirely - the doc ID is the identity
     if without_fragment.contains("docs.google.com/document/") {
@@ -29,11 +46,211 @@ pub enum OperationPriority {
     BackgroundIngest, // Lower priority - can be interrupted
 }
 
+impl OperationPriority {
+    /// Label of the database timings metric
+    fn label(self) -> &'static str {
+        match self {
+            OperationPriority::UserSearch => "user_search",
+            OperationPriority::BackgroundIngest => "background_ingest",
+        }
+    }
+}
+
 pub struct Database {
     conn: Arc<Mutex<Connection>>,
     // Semaphore to control concurrent access with priority
     search_semaphore: Arc<Semaphore>,
     ingest_semaphore: Arc<Semaphore>,
+    /// Settings read on every save, search or fetch, loaded when the
+    /// database opens and kept up to date by their setters
+    cached_settings: std::sync::RwLock<CachedSettings>,
+}
+
+/// Settings each database keeps in memory rather than reading from the
+/// config table every time they are needed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CachedSettings {
+    /// Query parameters stripped from URLs (see `tracking_params`)
+    pub tracking_params: Vec<String>,
+    pub image_captions: crate::image_caption::ImageCaptionSettings,
+    pub domain_preferences: Vec<crate::domain_preferences::DomainPreference>,
+    pub domain_boost: crate::domain_preferences::DomainBoost,
+}
+
+impl Default for CachedSettings {
+    fn default() -> Self {
+        Self {
+            tracking_params: crate::tracking_params::defaults(),
+            image_captions: Default::default(),
+            domain_preferences: Vec::new(),
+            domain_boost: Default::default(),
+        }
+    }
+}
+
+/// Index-wide counts reported by `localmind-cli stats` and `GET /api/v1/stats`.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct IndexStats {
+    pub documents: i64,
+    pub chunks: i64,
+    pub dead: i64,
+    pub needs_auth: i64,
+    /// Document count per source (e.g. "chrome_bookmark"), largest first,
+    /// as `[source, count]` pairs
+    #[schema(value_type = Vec<Vec<Object>>)]
+    pub by_source: Vec<(String, i64)>,
+    /// Chunks embedded by a previous model, which semantic search skips
+    /// until they are re-embedded; only counted by `RagPipeline`
+    #[serde(default)]
+    pub chunks_needing_migration: i64,
+}
+
+/// A document in the trash, waiting to be restored or purged.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct TrashedDocument {
+    pub id: i64,
+    pub title: String,
+    pub url: Option<String>,
+    /// Unix timestamp (seconds) of when it was moved to the trash
+    pub deleted_at: i64,
+}
+
+/// A named, user-curated group of documents.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Collection {
+    pub id: i64,
+    pub name: String,
+    pub document_count: i64,
+    /// Filter expression of a smart collection (see `query_syntax`); its
+    /// documents are whichever currently match it
+    pub query: Option<String>,
+}
+
+impl Collection {
+    /// Whether the collection is defined by a filter rather than members.
+    pub fn is_smart(&self) -> bool {
+        self.query.is_some()
+    }
+}
+
+/// A document's position on the Explore map (see `projection`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectionPoint {
+    pub document_id: i64,
+    pub title: String,
+    pub source: String,
+    /// Coordinates in 0..=1
+    pub x: f32,
+    pub y: f32,
+}
+
+/// Locally recorded usage, shown in the stats view.
+#[derive(Debug, Clone, Default)]
+pub struct UsageInsights {
+    /// Searches recorded since the last purge
+    pub searches: i64,
+    /// Most opened documents as (id, title, open count), most opened first
+    pub most_revisited: Vec<(i64, String, i64)>,
+    /// Queries that found nothing as (query, times searched), most frequent first
+    pub zero_result_queries: Vec<(String, i64)>,
+    /// Days with the most documents saved as (YYYY-MM-DD, documents), busiest first
+    pub busiest_ingest_days: Vec<(String, i64)>,
+}
+
+/// Number of recent searches whose results are kept for session restore.
+pub const SEARCH_SESSION_LIMIT: usize = 20;
+
+/// One result of a saved search, in rank order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionHit {
+    pub doc_id: i64,
+    pub similarity: f32,
+    /// Byte offsets of the matching passage, when known
+    pub passage: Option<(usize, usize)>,
+}
+
+/// A recent search and the documents it found, kept so the results view can
+/// be restored on the next launch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchSession {
+    pub query: String,
+    /// Respelled query the results are for, if any
+    pub corrected_query: Option<String>,
+    /// Whether the hits are passages rather than whole documents
+    pub passages: bool,
+    pub hits: Vec<SessionHit>,
+}
+
+/// A recently saved document, as listed on the home dashboard.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecentSave {
+    pub id: i64,
+    pub title: String,
+    pub url: Option<String>,
+    pub created_at: String,
+}
+
+/// Counts and recent activity shown on the home dashboard.
+#[derive(Debug, Clone, Default)]
+pub struct HomeSummary {
+    pub documents: i64,
+    /// Documents saved in the last seven days
+    pub saved_this_week: i64,
+    pub unread: i64,
+    /// Searches run in the last seven days
+    pub searches_this_week: i64,
+    /// Latest saves of each source as (source, saves), the source saved to
+    /// most recently first
+    pub recent_by_source: Vec<(String, Vec<RecentSave>)>,
+    /// Distinct recent queries, newest first
+    pub recent_searches: Vec<String>,
+}
+
+/// A search box completion for what has been typed so far.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AutocompleteSuggestion {
+    /// A document whose title matches, opened directly
+    Document { id: i64, title: String },
+    /// A tag and the number of documents carrying it
+    Tag { tag: String, count: i64 },
+    /// A website domain and the number of documents saved from it
+    Domain { domain: String, count: i64 },
+}
+
+/// A saved document whose title or URL starts with typed text, for the
+/// browser extension's popup.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, utoipa::ToSchema)]
+pub struct DocumentSuggestion {
+    pub id: i64,
+    pub title: String,
+    pub url: Option<String>,
+}
+
+/// A document at the other end of a link, for the document view.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkedDocument {
+    pub id: i64,
+    pub title: String,
+    pub url: Option<String>,
+}
+
+/// Indexing and link-checking details of a document, for the document
+/// view's metadata panel.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DocumentMetadata {
+    /// Chunks with a stored embedding
+    pub embedded_chunks: i64,
+    /// Chunks the document was split into, when recorded
+    pub chunk_count: Option<i64>,
+    /// When the link checker last checked the URL
+    pub link_checked_at: Option<String>,
+    /// Times the document was opened
+    pub view_count: i64,
+    /// Summary stored when the document was ingested, if its source's
+    /// policy asks for one
+    pub summary: Option<String>,
+    /// Detected language (ISO 639-3), when known
+    pub language: Option<String>,
 }
 
 pub struct Document {
@@ -49,27 +266,75 @@ pub struct Document {
     pub profile: Option<String>,
 }
 
+/// Directory holding the database and other LocalMind data files.
+pub fn data_dir() -> std::path::PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| std::env::current_dir().unwrap())
+        .join("localmind")
+}
+
 impl Database {
+    /// Open the database of the active library.
     pub async fn new() -> Result<Self> {
-        let data_dir = dirs::data_dir()
-            .unwrap_or_else(|| std::env::current_dir().unwrap())
-            .join("localmind");
+        let registry = crate::library::LibraryRegistry::load();
+        Self::open(&crate::library::database_path(&registry.active)).await
+    }
 
-        std::fs::create_dir_all(&data_dir)?;
-        let db_path = data_dir.join("localmind.db");
+    /// Open (creating if needed) the database at `db_path`.
+    pub async fn open(db_path: &std::path::Path) -> Result<Self> {
+        if let Some(parent) = db_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
 
-        let conn = Connection::open(&db_path)?;
+        let conn = Connection::open(db_path)?;
+        // WAL keeps the database consistent if the app crashes mid-write
+        conn.execute_batch("PRAGMA journal_mode = WAL; PRAGMA synchronous = NORMAL;")?;
         let db = Self {
             conn: Arc::new(Mutex::new(conn)),
             // Allow 10 concurrent searches, but only 1 background ingest
             search_semaphore: Arc::new(Semaphore::new(10)),
             ingest_semaphore: Arc::new(Semaphore::new(1)),
+            cached_settings: std::sync::RwLock::new(CachedSettings::default()),
         };
 
         db.init_schema().await?;
+        let settings = CachedSettings {
+            tracking_params: db.get_tracking_params().await?,
+            image_captions: db.get_image_caption_settings().await?,
+            domain_preferences: db.get_domain_preferences().await?,
+            domain_boost: db.get_domain_boost().await?,
+        };
+        db.update_cached_settings(|cached| *cached = settings);
         Ok(db)
     }
 
+    /// The settings kept in memory (see `CachedSettings`).
+    pub fn cached_settings(&self) -> CachedSettings {
+        self.cached_settings
+            .read()
+            .map(|settings| settings.clone())
+            .unwrap_or_default()
+    }
+
+    fn update_cached_settings(&self, update: impl FnOnce(&mut CachedSettings)) {
+        if let Ok(mut settings) = self.cached_settings.write() {
+            update(&mut settings);
+        }
+    }
+
+    /// The tracking parameters in effect for this database.
+    pub fn tracking_params(&self) -> Vec<String> {
+        self.cached_settings
+            .read()
+            .map(|settings| settings.tracking_params.clone())
+            .unwrap_or_else(|_| crate::tracking_params::defaults())
+    }
+
+    /// `url` normalized with this database's tracking parameters.
+    pub fn normalize_url(&self, url: &str) -> String {
+        normalize_url(url, &self.tracking_params())
+    }
+
     async fn init_schema(&self) -> Result<()> {
         let _permit = self
             .get_priority_access(OperationPriority::UserSearch)
@@ -107,6 +372,71 @@ impl Database {
         // Add profile column if it doesn't exist (migration)
         let _ = conn.execute("ALTER TABLE documents ADD COLUMN profile TEXT", []);
 
+        // Add dead-link checker columns if they don't exist (migration)
+        let _ = conn.execute(
+            "ALTER TABLE documents ADD COLUMN link_check_failures INTEGER DEFAULT 0",
+            [],
+        );
+        let _ = conn.execute("ALTER TABLE documents ADD COLUMN link_checked_at TEXT", []);
+
+        // Number of chunks the document was split into, written in the same
+        // transaction as its embeddings so partial ingests can be detected
+        let _ = conn.execute("ALTER TABLE documents ADD COLUMN chunk_count INTEGER", []);
+
+        // Add favorite (pinned) columns if they don't exist (migration)
+        let _ = conn.execute(
+            "ALTER TABLE documents ADD COLUMN is_favorite BOOLEAN DEFAULT 0",
+            [],
+        );
+        let _ = conn.execute("ALTER TABLE documents ADD COLUMN favorited_at TEXT", []);
+
+        // Add read/unread columns if they don't exist (migration). Documents
+        // saved before the reading list existed start out read, so only new
+        // saves show up as unread.
+        if conn
+            .execute(
+                "ALTER TABLE documents ADD COLUMN is_read BOOLEAN DEFAULT 0",
+                [],
+            )
+            .is_ok()
+        {
+            conn.execute("UPDATE documents SET is_read = 1", [])?;
+        }
+        let _ = conn.execute("ALTER TABLE documents ADD COLUMN read_at TEXT", []);
+
+        // Soft-delete column (migration). Trashed documents keep their chunks
+        // and FTS rows until purged, but are left out of lists and search.
+        let _ = conn.execute("ALTER TABLE documents ADD COLUMN deleted_at TEXT", []);
+
+        // How often a document has been opened (migration), used to resurface
+        // saved items that were never looked at again
+        let _ = conn.execute(
+            "ALTER TABLE documents ADD COLUMN view_count INTEGER DEFAULT 0",
+            [],
+        );
+        let _ = conn.execute("ALTER TABLE documents ADD COLUMN last_viewed_at TEXT", []);
+        // Migration: when the chunk embeddings were last written
+        let _ = conn.execute("ALTER TABLE documents ADD COLUMN indexed_at TEXT", []);
+        // Migration: extractive summary stored by ingestion policies
+        let _ = conn.execute("ALTER TABLE documents ADD COLUMN summary TEXT", []);
+        // When the link was first found dead (unix seconds), for retention rules
+        let _ = conn.execute("ALTER TABLE documents ADD COLUMN dead_since TEXT", []);
+        // Last change to the document (unix seconds), for sync's
+        // last-write-wins merging; kept up to date by triggers
+        let _ = conn.execute("ALTER TABLE documents ADD COLUMN modified_at TEXT", []);
+        // Migration: detected language (ISO 639-3), '' when it could not be
+        // told, NULL until detected
+        let _ = conn.execute("ALTER TABLE documents ADD COLUMN language TEXT", []);
+        // Migration: why a fetched page was indexed by title and URL only
+        // (see `page_quality`), '' when the user kept it in full anyway
+        let _ = conn.execute("ALTER TABLE documents ADD COLUMN quality_issue TEXT", []);
+        // Migration: content is only a fetch placeholder; not embedded and
+        // left out of search until refetched
+        let _ = conn.execute(
+            "ALTER TABLE documents ADD COLUMN needs_refetch INTEGER NOT NULL DEFAULT 0",
+            [],
+        );
+
         // Create FTS table for text search (without content_tokenize for compatibility)
         conn.execute(
             "CREATE VIRTUAL TABLE IF NOT EXISTS documents_fts USING fts5(
@@ -115,6 +445,49 @@ impl Database {
             [],
         )?;
 
+        // Vocabulary of the full-text index (term, documents containing it),
+        // where spelling corrections for empty searches come from
+        conn.execute(
+            "CREATE VIRTUAL TABLE IF NOT EXISTS documents_fts_vocab
+             USING fts5vocab(documents_fts, row)",
+            [],
+        )?;
+
+        // Prefix index over titles alone, for search box autocomplete. It
+        // reads titles from `documents` (external content), so it is filled
+        // once when created and kept in sync by triggers.
+        let titles_index_exists: bool = conn
+            .prepare("SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'titles_fts'")?
+            .exists([])?;
+        conn.execute(
+            "CREATE VIRTUAL TABLE IF NOT EXISTS titles_fts USING fts5(
+                title, content = 'documents', content_rowid = 'id', prefix = '2 3'
+            )",
+            [],
+        )?;
+        if !titles_index_exists {
+            conn.execute("INSERT INTO titles_fts(titles_fts) VALUES ('rebuild')", [])?;
+        }
+        conn.execute(
+            "CREATE TRIGGER IF NOT EXISTS documents_titles_ai AFTER INSERT ON documents BEGIN
+                INSERT INTO titles_fts(rowid, title) VALUES (new.id, new.title);
+            END",
+            [],
+        )?;
+        conn.execute(
+            "CREATE TRIGGER IF NOT EXISTS documents_titles_ad AFTER DELETE ON documents BEGIN
+                INSERT INTO titles_fts(titles_fts, rowid, title) VALUES ('delete', old.id, old.title);
+            END",
+            [],
+        )?;
+        conn.execute(
+            "CREATE TRIGGER IF NOT EXISTS documents_titles_au AFTER UPDATE OF title ON documents BEGIN
+                INSERT INTO titles_fts(titles_fts, rowid, title) VALUES ('delete', old.id, old.title);
+                INSERT INTO titles_fts(rowid, title) VALUES (new.id, new.title);
+            END",
+            [],
+        )?;
+
         // Create trigger to keep FTS in sync
         conn.execute(
             "CREATE TRIGGER IF NOT EXISTS documents_ai AFTER INSERT ON documents BEGIN
@@ -144,6 +517,14 @@ impl Database {
             [],
         )?;
 
+        // Hash of each chunk's text and embedding model, so unchanged chunks
+        // are reused instead of re-embedded (migration)
+        let _ = conn.execute("ALTER TABLE embeddings ADD COLUMN content_hash TEXT", []);
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_embeddings_content_hash ON embeddings(content_hash)",
+            [],
+        )?;
+
         // Create config table for storing key-value settings
         conn.execute(
             "CREATE TABLE IF NOT EXISTS config (
@@ -179,120 +560,540 @@ impl Database {
             [],
         )?;
 
-        Ok(())
-    }
-
-    async fn get_priority_access(
-        &self,
-        priority: OperationPriority,
-    ) -> Result<SemaphorePermit<'_>> {
-        match priority {
-            OperationPriority::UserSearch => {
-                // User searches get immediate access
-                Ok(self.search_semaphore.acquire().await.unwrap())
-            }
-            OperationPriority::BackgroundIngest => {
-                // Background ingests wait and can be interrupted
-                // Try to acquire with timeout to avoid blocking searches
-                match tokio::time::timeout(
-                    Duration::from_millis(100),
-                    self.ingest_semaphore.acquire(),
-                )
-                .await
-                {
-                    Ok(permit) => Ok(permit.unwrap()),
-                    Err(_) => {
-                        // If we can't get access quickly, yield to searches
-                        tokio::task::yield_now().await;
-                        Ok(self.ingest_semaphore.acquire().await.unwrap())
-                    }
-                }
-            }
-        }
-    }
+        // Create ingest_queue table so bookmark ingestion can resume after restart
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS ingest_queue (
+                id          INTEGER PRIMARY KEY AUTOINCREMENT,
+                url         TEXT UNIQUE NOT NULL,
+                title       TEXT NOT NULL,
+                profile     TEXT,
+                status      TEXT NOT NULL DEFAULT 'pending',
+                attempts    INTEGER NOT NULL DEFAULT 0,
+                last_error  TEXT,
+                updated_at  TEXT NOT NULL
+            )",
+            [],
+        )?;
 
-    async fn execute_with_priority<T, F>(
-        &self,
-        priority: OperationPriority,
-        operation: F,
-    ) -> Result<T>
-    where
-        F: FnOnce(&Connection) -> Result<T>,
-    {
-        let _permit = self.get_priority_access(priority).await?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_ingest_queue_status ON ingest_queue(status)",
+            [],
+        )?;
 
-        // For background operations, check if we should yield frequently
-        if matches!(priority, OperationPriority::BackgroundIngest) {
-            // Yield to any waiting search operations
-            if self.search_semaphore.available_permits() < 10 {
-                tokio::task::yield_now().await;
-            }
-        }
+        // Create ingest_errors table: bookmarks whose fetch failed are reported
+        // here instead of being indexed as placeholder documents
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS ingest_errors (
+                id          INTEGER PRIMARY KEY AUTOINCREMENT,
+                url         TEXT UNIQUE NOT NULL,
+                title       TEXT NOT NULL,
+                profile     TEXT,
+                error       TEXT NOT NULL,
+                attempts    INTEGER NOT NULL DEFAULT 1,
+                ignored     BOOLEAN NOT NULL DEFAULT 0,
+                updated_at  TEXT NOT NULL
+            )",
+            [],
+        )?;
 
-        let start_time = Instant::now();
-        let result = {
-            let conn = self.conn.lock().unwrap();
-            operation(&conn)
-        };
+        // Create collections tables: named groups of documents that searches
+        // can be scoped to
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS collections (
+                id          INTEGER PRIMARY KEY AUTOINCREMENT,
+                name        TEXT UNIQUE NOT NULL COLLATE NOCASE,
+                created_at  TEXT NOT NULL
+            )",
+            [],
+        )?;
 
-        // Log slow operations for debugging
-        let elapsed = start_time.elapsed();
-        if elapsed > Duration::from_millis(100) {
-            println!(
-                "⚠️ Slow database operation took {:?} (priority: {:?})",
-                elapsed, priority
-            );
-        }
+        // Smart collections (migration): a stored filter expression, whose
+        // matching documents make up the collection instead of its members
+        let _ = conn.execute("ALTER TABLE collections ADD COLUMN query TEXT", []);
 
-        result
-    }
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS collection_documents (
+                collection_id INTEGER NOT NULL
+                                REFERENCES collections(id) ON DELETE CASCADE,
+                document_id   INTEGER NOT NULL
+                                REFERENCES documents(id) ON DELETE CASCADE,
+                added_at      TEXT NOT NULL,
+                PRIMARY KEY (collection_id, document_id)
+            )",
+            [],
+        )?;
 
-    #[allow(clippy::too_many_arguments)]
-    pub async fn insert_document(
-        &self,
-        title: &str,
-        content: &str,
-        url: Option<&str>,
-        source: &str,
-        embedding: Option<&[u8]>,
-        is_dead: Option<bool>,
-        priority: OperationPriority,
-        profile: Option<&str>,
-    ) -> Result<i64> {
-        let normalized_url = url.map(normalize_url);
-        let url_ref = normalized_url.as_deref();
-        self.execute_with_priority(priority, |conn| {
-            conn.execute(
-                "INSERT INTO documents (title, content, url, source, embedding, is_dead, profile) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-                params![title, content, url_ref, source, embedding, is_dead, profile],
-            )?;
-            Ok(conn.last_insert_rowid())
-        }).await
-    }
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_collection_documents_document_id
+             ON collection_documents(document_id)",
+            [],
+        )?;
 
-    pub async fn get_document(&self, id: i64) -> Result<Option<Document>> {
-        self.execute_with_priority(OperationPriority::UserSearch, |conn| {
-            let mut stmt = conn.prepare(
-                "SELECT id, title, content, url, source, created_at, embedding, is_dead, needs_auth, profile
-                 FROM documents WHERE id = ?1",
-            )?;
+        // Create document tags table: free-form labels, many per document
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS document_tags (
+                document_id INTEGER NOT NULL
+                                REFERENCES documents(id) ON DELETE CASCADE,
+                tag         TEXT NOT NULL COLLATE NOCASE,
+                created_at  TEXT NOT NULL,
+                PRIMARY KEY (document_id, tag)
+            )",
+            [],
+        )?;
 
-            let doc = stmt.query_row(params![id], |row| {
-                Ok(Document {
-                    id: row.get(0)?,
-                    title: row.get(1)?,
-                    content: row.get(2)?,
-                    url: row.get(3)?,
-                    source: row.get(4)?,
-                    created_at: row.get(5)?,
-                    embedding: row.get(6)?,
-                    is_dead: row.get(7)?,
-                    needs_auth: row.get(8)?,
-                    profile: row.get(9)?,
-                })
-            });
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_document_tags_tag ON document_tags(tag)",
+            [],
+        )?;
 
-            match doc {
+        // Record when a document last changed, including trashing and its tags
+        conn.execute(
+            "CREATE TRIGGER IF NOT EXISTS documents_modified_au
+             AFTER UPDATE OF title, content, url, deleted_at ON documents BEGIN
+                UPDATE documents SET modified_at = strftime('%s', 'now') WHERE id = new.id;
+            END",
+            [],
+        )?;
+        conn.execute(
+            "CREATE TRIGGER IF NOT EXISTS document_tags_modified_ai AFTER INSERT ON document_tags BEGIN
+                UPDATE documents SET modified_at = strftime('%s', 'now') WHERE id = new.document_id;
+            END",
+            [],
+        )?;
+        conn.execute(
+            "CREATE TRIGGER IF NOT EXISTS document_tags_modified_ad AFTER DELETE ON document_tags BEGIN
+                UPDATE documents SET modified_at = strftime('%s', 'now') WHERE id = old.document_id;
+            END",
+            [],
+        )?;
+
+        // Create document links table: outgoing links from a document to
+        // other pages by URL. The target may not be indexed (yet).
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS document_links (
+                source_id   INTEGER NOT NULL
+                                REFERENCES documents(id) ON DELETE CASCADE,
+                target_url  TEXT NOT NULL,
+                PRIMARY KEY (source_id, target_url)
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_document_links_target_url
+             ON document_links(target_url)",
+            [],
+        )?;
+
+        // Create document structure table: headings and anchors of pages
+        // ingested from HTML, as JSON (see html_text.rs)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS document_structure (
+                document_id INTEGER PRIMARY KEY REFERENCES documents(id) ON DELETE CASCADE,
+                headings    TEXT NOT NULL,
+                links       TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // Where each page of a PDF starts, as JSON (migration)
+        let _ = conn.execute(
+            "ALTER TABLE document_structure ADD COLUMN pages TEXT NOT NULL DEFAULT '[]'",
+            [],
+        );
+
+        // Where each chunk comes from: the section heading and anchor, PDF
+        // page and transcript time it starts at (migration). Chunks stored before
+        // get their section from the headings stored for their document.
+        if conn
+            .execute("ALTER TABLE embeddings ADD COLUMN section_heading TEXT", [])
+            .is_ok()
+        {
+            conn.execute(
+                "UPDATE embeddings SET section_heading = (
+                     SELECT json_extract(h.value, '$.text')
+                     FROM document_structure s, json_each(s.headings) h
+                     WHERE s.document_id = embeddings.document_id
+                       AND json_extract(h.value, '$.offset') <= embeddings.chunk_start
+                     ORDER BY json_extract(h.value, '$.offset') DESC
+                     LIMIT 1
+                 )
+                 WHERE document_id IN (SELECT document_id FROM document_structure)",
+                [],
+            )?;
+        }
+        let _ = conn.execute("ALTER TABLE embeddings ADD COLUMN section_anchor TEXT", []);
+        let _ = conn.execute("ALTER TABLE embeddings ADD COLUMN page_number INTEGER", []);
+        let _ = conn.execute(
+            "ALTER TABLE embeddings ADD COLUMN timestamp_seconds INTEGER",
+            [],
+        );
+
+        // Model of chunk rows written while switching embedding model; NULL
+        // for the model searched, see `embedding_migration` (migration)
+        let _ = conn.execute("ALTER TABLE embeddings ADD COLUMN model_id TEXT", []);
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_embeddings_model_id ON embeddings(model_id)",
+            [],
+        )?;
+
+        // Link targets are matched against document URLs
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_documents_url ON documents(url)",
+            [],
+        )?;
+
+        // Create search history table: one row per search with its result
+        // count, for the usage insights in the stats view. Never leaves the
+        // machine and can be purged from there.
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS search_history (
+                id            INTEGER PRIMARY KEY AUTOINCREMENT,
+                query         TEXT NOT NULL,
+                result_count  INTEGER NOT NULL,
+                searched_at   TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // Create search session tables: the last few searches with the
+        // documents they found, in rank order, so the results view can be
+        // shown again on the next launch without searching
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS search_sessions (
+                id              INTEGER PRIMARY KEY AUTOINCREMENT,
+                query           TEXT NOT NULL,
+                corrected_query TEXT,
+                passages        INTEGER NOT NULL DEFAULT 0,
+                searched_at     TEXT NOT NULL
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS search_session_hits (
+                session_id  INTEGER NOT NULL,
+                rank        INTEGER NOT NULL,
+                doc_id      INTEGER NOT NULL,
+                similarity  REAL NOT NULL,
+                chunk_start INTEGER,
+                chunk_end   INTEGER,
+                PRIMARY KEY (session_id, rank)
+            )",
+            [],
+        )?;
+
+        // Create query embedding cache table: embeddings of past searches per
+        // backend, so repeated queries skip the embedding round-trip
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS query_embeddings (
+                query        TEXT NOT NULL,
+                backend      TEXT NOT NULL,
+                embedding    BLOB NOT NULL,
+                hits         INTEGER NOT NULL DEFAULT 1,
+                last_used_at TEXT NOT NULL,
+                PRIMARY KEY (query, backend)
+            )",
+            [],
+        )?;
+
+        // Create favicon cache table: one small PNG per site, empty when the
+        // site has none (see favicon.rs)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS favicons (
+                host       TEXT PRIMARY KEY,
+                icon       BLOB NOT NULL,
+                fetched_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
+        // Create document image table: each page's preview image and, once
+        // fetched, its JPEG thumbnail (empty when the fetch failed; see
+        // thumbnail.rs)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS document_images (
+                document_id INTEGER PRIMARY KEY REFERENCES documents(id) ON DELETE CASCADE,
+                image_url   TEXT NOT NULL,
+                thumbnail   BLOB
+            )",
+            [],
+        )?;
+
+        // Create projection table: each document's cached position on the
+        // Explore map (see projection.rs)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS document_projection (
+                document_id INTEGER PRIMARY KEY REFERENCES documents(id) ON DELETE CASCADE,
+                x           REAL NOT NULL,
+                y           REAL NOT NULL
+            )",
+            [],
+        )?;
+
+        // Create domain preferences table: domains marked as high or low
+        // quality in Settings (see domain_preferences.rs)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS domain_preferences (
+                domain     TEXT PRIMARY KEY,
+                quality    TEXT NOT NULL CHECK (quality IN ('high', 'low')),
+                created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+            )",
+            [],
+        )?;
+
+        // Create document versions table: content a page had before it was
+        // fetched again (see document_versions.rs)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS document_versions (
+                id          INTEGER PRIMARY KEY AUTOINCREMENT,
+                document_id INTEGER NOT NULL REFERENCES documents(id) ON DELETE CASCADE,
+                title       TEXT NOT NULL,
+                content     TEXT NOT NULL,
+                saved_at    TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_document_versions_document
+             ON document_versions(document_id)",
+            [],
+        )?;
+
+        // Create annotations table: highlights and notes on ranges of a
+        // document's content, each with the document searches find it by
+        // (see annotations.rs)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS annotations (
+                id                    INTEGER PRIMARY KEY AUTOINCREMENT,
+                document_id           INTEGER NOT NULL REFERENCES documents(id) ON DELETE CASCADE,
+                start_offset          INTEGER NOT NULL,
+                end_offset            INTEGER NOT NULL,
+                quote                 TEXT NOT NULL,
+                note                  TEXT,
+                highlight_document_id INTEGER REFERENCES documents(id) ON DELETE SET NULL,
+                created_at            TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_annotations_document ON annotations(document_id)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_annotations_highlight_document
+             ON annotations(highlight_document_id)",
+            [],
+        )?;
+
+        // Create reminders table: documents snoozed until a date from their
+        // context menu, one reminder each (see reminders.rs)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS reminders (
+                document_id INTEGER PRIMARY KEY REFERENCES documents(id) ON DELETE CASCADE,
+                remind_at   TEXT NOT NULL,
+                notify      INTEGER NOT NULL DEFAULT 0,
+                fired_at    TEXT,
+                created_at  TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+            )",
+            [],
+        )?;
+
+        // Move legacy "[Error fetching content: ...]" placeholder documents out
+        // of the index and into ingest_errors (migration)
+        let placeholder_ids: Vec<i64> = {
+            let mut stmt = conn.prepare(
+                "SELECT id FROM documents
+                 WHERE source = 'chrome_bookmark' AND url IS NOT NULL
+                   AND content LIKE '%[Error fetching content: %'",
+            )?;
+            let ids = stmt
+                .query_map([], |row| row.get(0))?
+                .collect::<std::result::Result<Vec<i64>, _>>()?;
+            ids
+        };
+        if !placeholder_ids.is_empty() {
+            let now = chrono_utc_now();
+            for doc_id in &placeholder_ids {
+                conn.execute(
+                    "INSERT OR IGNORE INTO ingest_errors (url, title, profile, error, updated_at)
+                     SELECT url, title, profile,
+                            rtrim(substr(content, instr(content, '[Error fetching content: ') + 25), ']'),
+                            ?2
+                     FROM documents WHERE id = ?1",
+                    params![doc_id, now],
+                )?;
+                conn.execute(
+                    "DELETE FROM documents_fts WHERE rowid = ?1",
+                    params![doc_id],
+                )?;
+                conn.execute("DELETE FROM documents WHERE id = ?1", params![doc_id])?;
+            }
+            println!(
+                "Moved {} failed bookmark placeholders to ingest_errors",
+                placeholder_ids.len()
+            );
+        }
+
+        // Stop embedding the remaining placeholder-only documents: mark them
+        // for refetching and drop their chunks (migration)
+        let candidates: Vec<(i64, String, String, Option<String>)> = {
+            let mut stmt = conn.prepare(
+                "SELECT id, title, content, url FROM documents
+                 WHERE needs_refetch = 0
+                   AND (content LIKE '%[No content extracted%'
+                        OR content LIKE '%[Error fetching content%')",
+            )?;
+            let rows = stmt
+                .query_map([], |row| {
+                    Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            rows
+        };
+        let mut marked = 0;
+        for (doc_id, title, content, url) in candidates {
+            if !crate::page_quality::is_placeholder(&title, &content, url.as_deref()) {
+                continue;
+            }
+            conn.execute(
+                "UPDATE documents SET needs_refetch = 1, chunk_count = 0 WHERE id = ?1",
+                params![doc_id],
+            )?;
+            conn.execute(
+                "DELETE FROM embeddings WHERE document_id = ?1",
+                params![doc_id],
+            )?;
+            marked += 1;
+        }
+        if marked > 0 {
+            println!("Marked {} placeholder documents for refetching", marked);
+        }
+
+        Ok(())
+    }
+
+    async fn get_priority_access(
+        &self,
+        priority: OperationPriority,
+    ) -> Result<SemaphorePermit<'_>> {
+        match priority {
+            OperationPriority::UserSearch => {
+                // User searches get immediate access
+                Ok(self.search_semaphore.acquire().await.unwrap())
+            }
+            OperationPriority::BackgroundIngest => {
+                // Background ingests wait and can be interrupted
+                // Try to acquire with timeout to avoid blocking searches
+                match tokio::time::timeout(
+                    Duration::from_millis(100),
+                    self.ingest_semaphore.acquire(),
+                )
+                .await
+                {
+                    Ok(permit) => Ok(permit.unwrap()),
+                    Err(_) => {
+                        // If we can't get access quickly, yield to searches
+                        tokio::task::yield_now().await;
+                        Ok(self.ingest_semaphore.acquire().await.unwrap())
+                    }
+                }
+            }
+        }
+    }
+
+    async fn execute_with_priority<T, F>(
+        &self,
+        priority: OperationPriority,
+        operation: F,
+    ) -> Result<T>
+    where
+        F: FnOnce(&Connection) -> Result<T>,
+    {
+        let _permit = self.get_priority_access(priority).await?;
+
+        // For background operations, check if we should yield frequently
+        if matches!(priority, OperationPriority::BackgroundIngest) {
+            // Yield to any waiting search operations
+            if self.search_semaphore.available_permits() < 10 {
+                tokio::task::yield_now().await;
+            }
+        }
+
+        let start_time = Instant::now();
+        let result = {
+            let conn = self.conn.lock().unwrap();
+            operation(&conn)
+        };
+
+        // Log slow operations for debugging
+        let elapsed = start_time.elapsed();
+        crate::monitoring::record_db_operation(priority.label(), elapsed);
+        if elapsed > Duration::from_millis(100) {
+            println!(
+                "⚠️ Slow database operation took {:?} (priority: {:?})",
+                elapsed, priority
+            );
+        }
+
+        result
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn insert_document(
+        &self,
+        title: &str,
+        content: &str,
+        url: Option<&str>,
+        source: &str,
+        embedding: Option<&[u8]>,
+        is_dead: Option<bool>,
+        priority: OperationPriority,
+        profile: Option<&str>,
+    ) -> Result<i64> {
+        let normalized_url = url.map(|url| self.normalize_url(url));
+        let url_ref = normalized_url.as_deref();
+        self.execute_with_priority(priority, |conn| {
+            conn.execute(
+                "INSERT INTO documents (title, content, url, source, embedding, is_dead, profile) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![title, content, url_ref, source, embedding, is_dead, profile],
+            )?;
+            Ok(conn.last_insert_rowid())
+        }).await
+    }
+
+    pub async fn get_document(&self, id: i64) -> Result<Option<Document>> {
+        self.select_document(id, false).await
+    }
+
+    /// Like `get_document`, but `None` for a document in the trash. Used to
+    /// resolve search hits.
+    pub async fn get_live_document(&self, id: i64) -> Result<Option<Document>> {
+        self.select_document(id, true).await
+    }
+
+    async fn select_document(&self, id: i64, live_only: bool) -> Result<Option<Document>> {
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, title, content, url, source, created_at, embedding, is_dead, needs_auth, profile
+                 FROM documents WHERE id = ?1 AND (?2 = 0 OR deleted_at IS NULL)",
+            )?;
+
+            let doc = stmt.query_row(params![id, live_only], |row| {
+                Ok(Document {
+                    id: row.get(0)?,
+                    title: row.get(1)?,
+                    content: row.get(2)?,
+                    url: row.get(3)?,
+                    source: row.get(4)?,
+                    created_at: row.get(5)?,
+                    embedding: row.get(6)?,
+                    is_dead: row.get(7)?,
+                    needs_auth: row.get(8)?,
+                    profile: row.get(9)?,
+                })
+            });
+
+            match doc {
                 Ok(document) => Ok(Some(document)),
                 Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
                 Err(e) => Err(Box::new(e)),
@@ -301,1104 +1102,7133 @@ impl Database {
         .await
     }
 
-    /// Get most recently added documents for home screen display
-    ///
-    /// Returns up to `limit` documents ordered by creation date (newest first).
-    /// Excludes dead/broken bookmarks.
-    pub async fn get_recent_documents(&self, limit: usize) -> Result<Vec<Document>> {
-        self.get_recent_documents_filtered(limit, None).await
+    /// Get most recently added documents for home screen display
+    ///
+    /// Returns up to `limit` documents ordered by creation date (newest first).
+    /// Excludes dead/broken bookmarks.
+    pub async fn get_recent_documents(&self, limit: usize) -> Result<Vec<Document>> {
+        self.get_recent_documents_filtered(limit, None).await
+    }
+
+    /// Like `get_recent_documents` but optionally filtered to a specific Chrome profile.
+    pub async fn get_recent_documents_filtered(
+        &self,
+        limit: usize,
+        profile: Option<String>,
+    ) -> Result<Vec<Document>> {
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            let (sql, params_vec): (String, Vec<Box<dyn rusqlite::ToSql>>) =
+                if let Some(ref p) = profile {
+                    (
+                    "SELECT id, title, content, url, source, created_at, embedding, is_dead, needs_auth, profile
+                     FROM documents
+                     WHERE (is_dead = 0 OR is_dead IS NULL) AND deleted_at IS NULL AND profile = ?1
+                     ORDER BY created_at DESC
+                     LIMIT ?2".to_string(),
+                    vec![Box::new(p.clone()), Box::new(limit as i64)],
+                )
+                } else {
+                    (
+                    "SELECT id, title, content, url, source, created_at, embedding, is_dead, needs_auth, profile
+                     FROM documents
+                     WHERE (is_dead = 0 OR is_dead IS NULL) AND deleted_at IS NULL
+                     ORDER BY created_at DESC
+                     LIMIT ?1".to_string(),
+                    vec![Box::new(limit as i64)],
+                )
+                };
+
+            let mut stmt = conn.prepare(&sql)?;
+            let param_refs: Vec<&dyn rusqlite::ToSql> =
+                params_vec.iter().map(|p| p.as_ref()).collect();
+
+            let docs = stmt
+                .query_map(&param_refs[..], |row| {
+                    Ok(Document {
+                        id: row.get(0)?,
+                        title: row.get(1)?,
+                        content: row.get(2)?,
+                        url: row.get(3)?,
+                        source: row.get(4)?,
+                        created_at: row.get(5)?,
+                        embedding: row.get(6)?,
+                        is_dead: row.get(7)?,
+                        needs_auth: row.get(8)?,
+                        profile: row.get(9)?,
+                    })
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            Ok(docs)
+        })
+        .await
+    }
+
+    /// Pin or unpin a document.
+    pub async fn set_document_favorite(&self, doc_id: i64, favorite: bool) -> Result<()> {
+        let now = chrono_utc_now();
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            conn.execute(
+                "UPDATE documents
+                 SET is_favorite = ?2,
+                     favorited_at = CASE WHEN ?2 THEN ?3 ELSE NULL END
+                 WHERE id = ?1",
+                params![doc_id, favorite, now],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Pinned documents, most recently pinned first, optionally filtered to a
+    /// Chrome profile. Unlike recent documents, dead pins are still returned.
+    pub async fn get_favorite_documents(&self, profile: Option<String>) -> Result<Vec<Document>> {
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, title, content, url, source, created_at, embedding, is_dead, needs_auth, profile
+                 FROM documents
+                 WHERE is_favorite = 1 AND deleted_at IS NULL AND (?1 IS NULL OR profile = ?1)
+                 ORDER BY favorited_at DESC, id DESC",
+            )?;
+
+            let docs = stmt
+                .query_map(params![profile], |row| {
+                    Ok(Document {
+                        id: row.get(0)?,
+                        title: row.get(1)?,
+                        content: row.get(2)?,
+                        url: row.get(3)?,
+                        source: row.get(4)?,
+                        created_at: row.get(5)?,
+                        embedding: row.get(6)?,
+                        is_dead: row.get(7)?,
+                        needs_auth: row.get(8)?,
+                        profile: row.get(9)?,
+                    })
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            Ok(docs)
+        })
+        .await
+    }
+
+    /// Mark documents read or unread, returning how many rows changed.
+    pub async fn set_documents_read(&self, doc_ids: &[i64], read: bool) -> Result<usize> {
+        let doc_ids = doc_ids.to_vec();
+        let now = chrono_utc_now();
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            let tx = conn.unchecked_transaction()?;
+            let mut changed = 0;
+            {
+                let mut stmt = tx.prepare(
+                    "UPDATE documents
+                     SET is_read = ?2,
+                         read_at = CASE WHEN ?2 THEN ?3 ELSE NULL END
+                     WHERE id = ?1 AND COALESCE(is_read, 0) != ?2",
+                )?;
+                for doc_id in &doc_ids {
+                    changed += stmt.execute(params![doc_id, read, now])?;
+                }
+            }
+            tx.commit()?;
+            Ok(changed)
+        })
+        .await
+    }
+
+    /// IDs of every unread document.
+    pub async fn get_unread_document_ids(&self) -> Result<std::collections::HashSet<i64>> {
+        self.execute_with_priority(OperationPriority::UserSearch, |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id FROM documents WHERE COALESCE(is_read, 0) = 0 AND deleted_at IS NULL",
+            )?;
+            let ids = stmt
+                .query_map([], |row| row.get(0))?
+                .collect::<std::result::Result<_, _>>()?;
+            Ok(ids)
+        })
+        .await
+    }
+
+    /// Most recent unread documents (the reading list), optionally filtered to a
+    /// Chrome profile. Excludes dead bookmarks.
+    pub async fn get_unread_documents(
+        &self,
+        limit: usize,
+        profile: Option<String>,
+    ) -> Result<Vec<Document>> {
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, title, content, url, source, created_at, embedding, is_dead, needs_auth, profile
+                 FROM documents
+                 WHERE COALESCE(is_read, 0) = 0
+                   AND (is_dead = 0 OR is_dead IS NULL)
+                   AND deleted_at IS NULL
+                   AND (?1 IS NULL OR profile = ?1)
+                 ORDER BY created_at DESC
+                 LIMIT ?2",
+            )?;
+
+            let docs = stmt
+                .query_map(params![profile, limit as i64], |row| {
+                    Ok(Document {
+                        id: row.get(0)?,
+                        title: row.get(1)?,
+                        content: row.get(2)?,
+                        url: row.get(3)?,
+                        source: row.get(4)?,
+                        created_at: row.get(5)?,
+                        embedding: row.get(6)?,
+                        is_dead: row.get(7)?,
+                        needs_auth: row.get(8)?,
+                        profile: row.get(9)?,
+                    })
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            Ok(docs)
+        })
+        .await
+    }
+
+    /// Record that a document was opened.
+    pub async fn record_document_view(&self, doc_id: i64) -> Result<()> {
+        let now = chrono_utc_now();
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            conn.execute(
+                "UPDATE documents
+                 SET view_count = COALESCE(view_count, 0) + 1,
+                     last_viewed_at = ?2
+                 WHERE id = ?1",
+                params![doc_id, now],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Documents saved around this date one year ago (within
+    /// `ON_THIS_DAY_WINDOW_DAYS` either side), oldest first, optionally
+    /// filtered to a Chrome profile. Excludes dead bookmarks.
+    pub async fn get_documents_saved_year_ago(
+        &self,
+        limit: usize,
+        profile: Option<String>,
+    ) -> Result<Vec<Document>> {
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            let window = format!("{} days", ON_THIS_DAY_WINDOW_DAYS);
+            let mut stmt = conn.prepare(
+                "SELECT id, title, content, url, source, created_at, embedding, is_dead, needs_auth, profile
+                 FROM documents
+                 WHERE date(created_at) BETWEEN date('now', '-1 year', '-' || ?1)
+                                            AND date('now', '-1 year', '+' || ?1)
+                   AND (is_dead = 0 OR is_dead IS NULL)
+                   AND deleted_at IS NULL
+                   AND (?2 IS NULL OR profile = ?2)
+                 ORDER BY created_at ASC
+                 LIMIT ?3",
+            )?;
+
+            let docs = stmt
+                .query_map(params![window, profile, limit as i64], document_from_row)?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            Ok(docs)
+        })
+        .await
+    }
+
+    /// A random pick of older documents (saved more than
+    /// `FORGOTTEN_MIN_AGE_DAYS` ago) that are not pinned. Documents that were
+    /// opened less often are more likely to be picked, so never-opened ones
+    /// come up most.
+    pub async fn get_forgotten_documents(
+        &self,
+        limit: usize,
+        profile: Option<String>,
+    ) -> Result<Vec<Document>> {
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            let min_age = format!("-{} days", FORGOTTEN_MIN_AGE_DAYS);
+            let mut stmt = conn.prepare(
+                "SELECT id, title, content, url, source, created_at, embedding, is_dead, needs_auth, profile
+                 FROM documents
+                 WHERE created_at < datetime('now', ?1)
+                   AND COALESCE(is_favorite, 0) = 0
+                   AND (is_dead = 0 OR is_dead IS NULL)
+                   AND deleted_at IS NULL
+                   AND (?2 IS NULL OR profile = ?2)
+                 ORDER BY (ABS(RANDOM()) % 1000) * (1 + COALESCE(view_count, 0))
+                 LIMIT ?3",
+            )?;
+
+            let docs = stmt
+                .query_map(params![min_age, profile, limit as i64], document_from_row)?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            Ok(docs)
+        })
+        .await
+    }
+
+    pub async fn get_documents_batch(&self, ids: &[i64]) -> Result<Vec<Document>> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        self.execute_with_priority(OperationPriority::UserSearch, |conn| {
+            // Build the IN clause with placeholders
+            let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+            let query = format!(
+                "SELECT id, title, content, url, source, created_at, embedding, is_dead, needs_auth, profile
+                 FROM documents WHERE id IN ({})",
+                placeholders
+            );
+
+            let mut stmt = conn.prepare(&query)?;
+
+            // Convert ids to params
+            let params: Vec<_> = ids.iter().map(|id| id as &dyn rusqlite::ToSql).collect();
+
+            let docs = stmt
+                .query_map(&params[..], |row| {
+                    Ok(Document {
+                        id: row.get(0)?,
+                        title: row.get(1)?,
+                        content: row.get(2)?,
+                        url: row.get(3)?,
+                        source: row.get(4)?,
+                        created_at: row.get(5)?,
+                        embedding: row.get(6)?,
+                        is_dead: row.get(7)?,
+                        needs_auth: row.get(8)?,
+                        profile: row.get(9)?,
+                    })
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            Ok(docs)
+        })
+        .await
+    }
+
+    pub async fn search_documents(&self, query: &str, limit: i64) -> Result<Vec<Document>> {
+        self.execute_with_priority(OperationPriority::UserSearch, |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT d.id, d.title, d.content, d.url, d.source, d.created_at, d.embedding, d.is_dead, d.needs_auth, d.profile
+                 FROM documents d
+                 JOIN documents_fts fts ON d.id = fts.rowid
+                 WHERE documents_fts MATCH ?1 AND (d.is_dead IS NULL OR d.is_dead = 0)
+                   AND d.deleted_at IS NULL AND d.needs_refetch = 0
+                 ORDER BY rank
+                 LIMIT ?2"
+            )?;
+
+            let docs = stmt.query_map(params![query, limit], |row| {
+                Ok(Document {
+                    id: row.get(0)?,
+                    title: row.get(1)?,
+                    content: row.get(2)?,
+                    url: row.get(3)?,
+                    source: row.get(4)?,
+                    created_at: row.get(5)?,
+                    embedding: row.get(6)?,
+                    is_dead: row.get(7)?,
+                    needs_auth: row.get(8)?,
+                    profile: row.get(9)?,
+                })
+            })?;
+
+            let mut results = Vec::new();
+            for doc in docs {
+                results.push(doc?);
+            }
+            Ok(results)
+        }).await
+    }
+
+    /// FTS5 search returning each document alongside its positive BM25 score (-rank).
+    /// Higher score = better match. Results are ordered best-first.
+    /// Dead documents are excluded unless `include_dead` is set.
+    pub async fn search_documents_scored(
+        &self,
+        query: &str,
+        limit: i64,
+        include_dead: bool,
+    ) -> Result<Vec<(Document, f64)>> {
+        self.search_documents_scored_in(query, limit, include_dead, None)
+            .await
+    }
+
+    /// Like `search_documents_scored`, optionally restricted to one collection.
+    pub async fn search_documents_scored_in(
+        &self,
+        query: &str,
+        limit: i64,
+        include_dead: bool,
+        collection_id: Option<i64>,
+    ) -> Result<Vec<(Document, f64)>> {
+        self.execute_with_priority(OperationPriority::UserSearch, |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT d.id, d.title, d.content, d.url, d.source, d.created_at, d.embedding,
+                        d.is_dead, d.needs_auth, d.profile, -fts.rank AS bm25_score
+                 FROM documents d
+                 JOIN documents_fts fts ON d.id = fts.rowid
+                 WHERE documents_fts MATCH ?1 AND (?3 OR d.is_dead IS NULL OR d.is_dead = 0)
+                   AND d.deleted_at IS NULL AND d.needs_refetch = 0
+                   AND (?4 IS NULL OR d.id IN (
+                       SELECT document_id FROM collection_documents WHERE collection_id = ?4))
+                 ORDER BY rank
+                 LIMIT ?2",
+            )?;
+
+            let rows =
+                stmt.query_map(params![query, limit, include_dead, collection_id], |row| {
+                    let doc = Document {
+                        id: row.get(0)?,
+                        title: row.get(1)?,
+                        content: row.get(2)?,
+                        url: row.get(3)?,
+                        source: row.get(4)?,
+                        created_at: row.get(5)?,
+                        embedding: row.get(6)?,
+                        is_dead: row.get(7)?,
+                        needs_auth: row.get(8)?,
+                        profile: row.get(9)?,
+                    };
+                    let bm25_score: f64 = row.get(10)?;
+                    Ok((doc, bm25_score))
+                })?;
+
+            let mut results = Vec::new();
+            for row in rows {
+                results.push(row?);
+            }
+            Ok(results)
+        })
+        .await
+    }
+
+    /// Whether `term` (lowercase) occurs anywhere in the full-text index.
+    pub async fn is_indexed_term(&self, term: &str) -> Result<bool> {
+        let term = term.to_string();
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            let mut stmt = conn.prepare("SELECT 1 FROM documents_fts_vocab WHERE term = ?1")?;
+            Ok(stmt.exists(params![term])?)
+        })
+        .await
+    }
+
+    /// IDs of the documents matching an FTS5 query, without ranking.
+    pub async fn get_fts_document_ids(
+        &self,
+        fts_query: &str,
+    ) -> Result<std::collections::HashSet<i64>> {
+        let fts_query = fts_query.to_string();
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            let mut stmt =
+                conn.prepare("SELECT rowid FROM documents_fts WHERE documents_fts MATCH ?1")?;
+            let ids = stmt
+                .query_map(params![fts_query], |row| row.get(0))?
+                .collect::<std::result::Result<_, _>>()?;
+            Ok(ids)
+        })
+        .await
+    }
+
+    /// IDs of the documents passing the metadata operators of a search query
+    /// (`site:`, `source:`, `lang:`, `tag:`, `before:` and `after:`). A site matches the
+    /// URL host or any subdomain of it; all tags are required. Documents
+    /// needing a refetch never pass.
+    pub async fn get_filtered_document_ids(
+        &self,
+        filters: &ParsedQuery,
+    ) -> Result<std::collections::HashSet<i64>> {
+        let filters = filters.clone();
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            Ok(filtered_document_ids(conn, &filters)?)
+        })
+        .await
+    }
+
+    /// Completions for a partly typed query: documents whose title words
+    /// start with every typed word, then tags and domains starting with the
+    /// typed text (ignoring case), `limit` in all. Deleted documents are
+    /// skipped.
+    pub async fn get_autocomplete_suggestions(
+        &self,
+        prefix: &str,
+        limit: usize,
+    ) -> Result<Vec<AutocompleteSuggestion>> {
+        let prefix = prefix.trim().to_lowercase();
+        let title_query = prefix
+            .split_whitespace()
+            .map(|w| format!("\"{}\"*", w.replace('"', "")))
+            .collect::<Vec<_>>()
+            .join(" ");
+        if title_query.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            let mut suggestions = Vec::new();
+
+            let mut titles = conn.prepare(
+                "SELECT d.id, d.title FROM titles_fts
+                 JOIN documents d ON d.id = titles_fts.rowid
+                 WHERE titles_fts MATCH ?1 AND d.deleted_at IS NULL
+                 ORDER BY rank
+                 LIMIT ?2",
+            )?;
+            for row in titles.query_map(params![title_query, limit as i64], |row| {
+                Ok(AutocompleteSuggestion::Document {
+                    id: row.get(0)?,
+                    title: row.get(1)?,
+                })
+            })? {
+                suggestions.push(row?);
+            }
+
+            let mut tags = conn.prepare(
+                "SELECT t.tag, COUNT(*) FROM document_tags t
+                 JOIN documents d ON d.id = t.document_id
+                 WHERE lower(substr(t.tag, 1, length(?1))) = ?1 AND d.deleted_at IS NULL
+                 GROUP BY t.tag
+                 ORDER BY COUNT(*) DESC, t.tag
+                 LIMIT ?2",
+            )?;
+            for row in tags.query_map(params![prefix, limit as i64], |row| {
+                Ok(AutocompleteSuggestion::Tag {
+                    tag: row.get(0)?,
+                    count: row.get(1)?,
+                })
+            })? {
+                suggestions.push(row?);
+            }
+
+            // Domains come from document URLs; "www." is ignored when matching
+            if !prefix.contains(char::is_whitespace) {
+                let mut urls = conn.prepare(
+                    "SELECT url FROM documents
+                     WHERE deleted_at IS NULL
+                       AND (instr(url, '://' || ?1) > 0 OR instr(url, '://www.' || ?1) > 0)",
+                )?;
+                let mut domains: std::collections::HashMap<String, i64> =
+                    std::collections::HashMap::new();
+                for url in urls.query_map(params![prefix], |row| row.get::<_, String>(0))? {
+                    let host = url::Url::parse(&url?)
+                        .ok()
+                        .and_then(|u| u.host_str().map(str::to_lowercase));
+                    if let Some(host) = host {
+                        let domain = host.trim_start_matches("www.").to_string();
+                        if domain.starts_with(&prefix) {
+                            *domains.entry(domain).or_insert(0) += 1;
+                        }
+                    }
+                }
+                let mut domains: Vec<(String, i64)> = domains.into_iter().collect();
+                domains.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+                suggestions.extend(
+                    domains
+                        .into_iter()
+                        .take(limit)
+                        .map(|(domain, count)| AutocompleteSuggestion::Domain { domain, count }),
+                );
+            }
+
+            suggestions.truncate(limit);
+            Ok(suggestions)
+        })
+        .await
+    }
+
+    /// Documents whose title words start with every typed word, then ones
+    /// whose URL starts with the typed text (ignoring the scheme and
+    /// "www."), `limit` in all. Dead and deleted documents are skipped.
+    pub async fn get_document_suggestions(
+        &self,
+        prefix: &str,
+        limit: usize,
+    ) -> Result<Vec<DocumentSuggestion>> {
+        let prefix = prefix.trim().to_lowercase();
+        let title_query = prefix
+            .split_whitespace()
+            .map(|w| format!("\"{}\"*", w.replace('"', "")))
+            .collect::<Vec<_>>()
+            .join(" ");
+        if title_query.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            let row_to_suggestion = |row: &rusqlite::Row| -> rusqlite::Result<_> {
+                Ok(DocumentSuggestion {
+                    id: row.get(0)?,
+                    title: row.get(1)?,
+                    url: row.get(2)?,
+                })
+            };
+
+            let mut titles = conn.prepare(
+                "SELECT d.id, d.title, d.url FROM titles_fts
+                 JOIN documents d ON d.id = titles_fts.rowid
+                 WHERE titles_fts MATCH ?1
+                   AND d.deleted_at IS NULL AND COALESCE(d.is_dead, 0) = 0
+                 ORDER BY rank
+                 LIMIT ?2",
+            )?;
+            let mut suggestions = titles
+                .query_map(params![title_query, limit as i64], row_to_suggestion)?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            if suggestions.len() < limit && !prefix.contains(char::is_whitespace) {
+                let mut urls = conn.prepare(
+                    "SELECT id, title, url FROM documents
+                     WHERE deleted_at IS NULL AND COALESCE(is_dead, 0) = 0
+                       AND (instr(lower(url), '://' || ?1) > 0
+                            OR instr(lower(url), '://www.' || ?1) > 0)
+                     ORDER BY id DESC
+                     LIMIT ?2",
+                )?;
+                for suggestion in
+                    urls.query_map(params![prefix, limit as i64], row_to_suggestion)?
+                {
+                    let suggestion = suggestion?;
+                    if suggestions.len() >= limit {
+                        break;
+                    }
+                    if !suggestions.iter().any(|s| s.id == suggestion.id) {
+                        suggestions.push(suggestion);
+                    }
+                }
+            }
+            Ok(suggestions)
+        })
+        .await
+    }
+
+    /// Indexed terms that could be a respelling of `word`: same first letter
+    /// and within two characters of its length, with the number of documents
+    /// containing each. `word` is expected lowercase, as the index stores it.
+    pub async fn get_spelling_candidates(&self, word: &str) -> Result<Vec<(String, i64)>> {
+        let mut chars = word.chars();
+        let Some(first) = chars.next() else {
+            return Ok(Vec::new());
+        };
+        let start = first.to_string();
+        let end = char::from_u32(first as u32 + 1)
+            .map(|c| c.to_string())
+            .unwrap_or_else(|| format!("{}\u{10FFFF}", first));
+        let len = word.chars().count() as i64;
+
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT term, doc FROM documents_fts_vocab
+                 WHERE term >= ?1 AND term < ?2
+                   AND length(term) BETWEEN ?3 AND ?4",
+            )?;
+            let candidates = stmt
+                .query_map(params![start, end, len - 2, len + 2], |row| {
+                    Ok((row.get(0)?, row.get(1)?))
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok(candidates)
+        })
+        .await
+    }
+
+    pub async fn insert_chunk_embedding(
+        &self,
+        document_id: i64,
+        chunk_start: usize,
+        chunk_end: usize,
+        embedding: &[u8],
+        priority: OperationPriority,
+    ) -> Result<i64> {
+        self.execute_with_priority(priority, |conn| {
+            conn.execute(
+                "INSERT INTO embeddings (document_id, chunk_start, chunk_end, embedding)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![document_id, chunk_start as i64, chunk_end as i64, embedding],
+            )?;
+            Ok(conn.last_insert_rowid())
+        })
+        .await
+    }
+
+    /// Insert a document and all of its chunk embeddings in one transaction.
+    ///
+    /// `chunks` holds one `ChunkRow` per chunk. Returns the document ID and the embedding IDs in chunk order. A crash part-way
+    /// through leaves neither the document nor any of its chunks behind.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn insert_document_with_chunks(
+        &self,
+        title: &str,
+        content: &str,
+        url: Option<&str>,
+        source: &str,
+        profile: Option<&str>,
+        needs_auth: bool,
+        chunks: &[ChunkRow],
+    ) -> Result<(i64, Vec<i64>)> {
+        let normalized_url = url.map(|url| self.normalize_url(url));
+        self.execute_with_priority(OperationPriority::BackgroundIngest, |conn| {
+            let tx = conn.unchecked_transaction()?;
+            tx.execute(
+                "INSERT INTO documents (title, content, url, source, profile, needs_auth, chunk_count)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    title,
+                    content,
+                    normalized_url,
+                    source,
+                    profile,
+                    needs_auth,
+                    chunks.len() as i64
+                ],
+            )?;
+            let doc_id = tx.last_insert_rowid();
+            let embedding_ids = write_chunk_embeddings(&tx, doc_id, chunks)?;
+            tx.commit()?;
+            Ok((doc_id, embedding_ids))
+        })
+        .await
+    }
+
+    /// Insert a document with no chunk embeddings yet, for keyword-only mode.
+    ///
+    /// `chunk_count` is left NULL so `get_incomplete_documents` picks the
+    /// document up for embedding once the backend is available.
+    pub async fn insert_document_pending_embedding(
+        &self,
+        title: &str,
+        content: &str,
+        url: Option<&str>,
+        source: &str,
+        profile: Option<&str>,
+        needs_auth: bool,
+    ) -> Result<i64> {
+        let normalized_url = url.map(|url| self.normalize_url(url));
+        self.execute_with_priority(OperationPriority::BackgroundIngest, |conn| {
+            conn.execute(
+                "INSERT INTO documents (title, content, url, source, profile, needs_auth)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![title, content, normalized_url, source, profile, needs_auth],
+            )?;
+            Ok(conn.last_insert_rowid())
+        })
+        .await
+    }
+
+    /// When a document's chunk embeddings were last written (UTC,
+    /// "YYYY-MM-DD HH:MM:SS"); its save time if it predates the record or
+    /// has never been embedded. `None` for an unknown document.
+    pub async fn get_last_indexed_at(&self, doc_id: i64) -> Result<Option<String>> {
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            match conn.query_row(
+                "SELECT COALESCE(indexed_at, created_at) FROM documents WHERE id = ?1",
+                params![doc_id],
+                |row| row.get(0),
+            ) {
+                Ok(indexed_at) => Ok(Some(indexed_at)),
+                Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+                Err(e) => Err(Box::new(e)),
+            }
+        })
+        .await
+    }
+
+    /// Replace a document's content and chunk embeddings in one transaction,
+    /// clearing its dead/auth flags. Returns the new embedding IDs.
+    pub async fn update_document_with_chunks(
+        &self,
+        doc_id: i64,
+        title: &str,
+        content: &str,
+        chunks: &[ChunkRow],
+    ) -> Result<Vec<i64>> {
+        self.execute_with_priority(OperationPriority::BackgroundIngest, |conn| {
+            let tx = conn.unchecked_transaction()?;
+            // Keep the content being replaced, unless it was only a fetch
+            // placeholder (see document_versions.rs)
+            tx.execute(
+                "INSERT INTO document_versions (document_id, title, content)
+                 SELECT id, title, content FROM documents
+                 WHERE id = ?1 AND content != ?2 AND needs_refetch = 0",
+                params![doc_id, content],
+            )?;
+            tx.execute(
+                "DELETE FROM document_versions
+                 WHERE document_id = ?1 AND id NOT IN (
+                     SELECT id FROM document_versions WHERE document_id = ?1
+                     ORDER BY id DESC LIMIT ?2
+                 )",
+                params![doc_id, crate::document_versions::MAX_VERSIONS as i64],
+            )?;
+            tx.execute(
+                "UPDATE documents SET title = ?1, content = ?2, is_dead = 0, needs_auth = 0,
+                        dead_since = NULL
+                 WHERE id = ?3",
+                params![title, content, doc_id],
+            )?;
+            tx.execute(
+                "UPDATE documents_fts SET title = ?1, content = ?2 WHERE rowid = ?3",
+                params![title, content, doc_id],
+            )?;
+            let embedding_ids = write_chunk_embeddings(&tx, doc_id, chunks)?;
+            tx.commit()?;
+            Ok(embedding_ids)
+        })
+        .await
+    }
+
+    /// Replace a document's chunk embeddings in one transaction without
+    /// touching its content. Returns the new embedding IDs.
+    pub async fn replace_document_chunks(
+        &self,
+        doc_id: i64,
+        chunks: &[ChunkRow],
+    ) -> Result<Vec<i64>> {
+        self.execute_with_priority(OperationPriority::BackgroundIngest, |conn| {
+            let tx = conn.unchecked_transaction()?;
+            let embedding_ids = write_chunk_embeddings(&tx, doc_id, chunks)?;
+            tx.commit()?;
+            Ok(embedding_ids)
+        })
+        .await
+    }
+
+    /// Stored embeddings for any of the given chunk content hashes, keyed by
+    /// hash, so identical chunks can skip the embedding backend.
+    pub async fn get_embeddings_by_hash(
+        &self,
+        hashes: &[String],
+    ) -> Result<std::collections::HashMap<String, Vec<f32>>> {
+        self.execute_with_priority(OperationPriority::BackgroundIngest, |conn| {
+            let mut stmt =
+                conn.prepare("SELECT embedding FROM embeddings WHERE content_hash = ?1 LIMIT 1")?;
+            let mut found = std::collections::HashMap::new();
+            for hash in hashes {
+                if found.contains_key(hash) {
+                    continue;
+                }
+                match stmt.query_row(params![hash], |row| row.get::<_, Vec<u8>>(0)) {
+                    Ok(bytes) => {
+                        found.insert(hash.clone(), bincode::deserialize(&bytes)?);
+                    }
+                    Err(rusqlite::Error::QueryReturnedNoRows) => {}
+                    Err(e) => return Err(Box::new(e)),
+                }
+            }
+            Ok(found)
+        })
+        .await
+    }
+
+    /// Provenance of the stored chunks of `doc_ids`, keyed by document ID
+    /// and chunk start.
+    pub async fn get_chunk_provenance(
+        &self,
+        doc_ids: &[i64],
+    ) -> Result<std::collections::HashMap<(i64, usize), ChunkProvenance>> {
+        if doc_ids.is_empty() {
+            return Ok(Default::default());
+        }
+
+        self.execute_with_priority(OperationPriority::UserSearch, |conn| {
+            let placeholders = doc_ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+            let mut stmt = conn.prepare(&format!(
+                "SELECT document_id, chunk_start, section_heading, page_number,
+                        timestamp_seconds, content_hash, section_anchor
+                 FROM embeddings WHERE model_id IS NULL AND document_id IN ({})",
+                placeholders
+            ))?;
+            let params: Vec<_> = doc_ids
+                .iter()
+                .map(|id| id as &dyn rusqlite::ToSql)
+                .collect();
+            let rows = stmt.query_map(&params[..], |row| {
+                let start: i64 = row.get(1)?;
+                Ok((
+                    (row.get::<_, i64>(0)?, start as usize),
+                    ChunkProvenance {
+                        section_heading: row.get(2)?,
+                        page_number: row.get(3)?,
+                        timestamp_seconds: row.get(4)?,
+                        content_hash: row.get(5)?,
+                        section_anchor: row.get(6)?,
+                    },
+                ))
+            })?;
+            let mut provenance = std::collections::HashMap::new();
+            for row in rows {
+                let (key, chunk) = row?;
+                provenance.insert(key, chunk);
+            }
+            Ok(provenance)
+        })
+        .await
+    }
+
+    /// IDs of documents whose chunk embeddings are incomplete: fewer stored
+    /// chunks than recorded in `chunk_count`, or (for rows written before
+    /// `chunk_count` existed) no chunks at all.
+    pub async fn get_incomplete_documents(&self) -> Result<Vec<i64>> {
+        self.execute_with_priority(OperationPriority::BackgroundIngest, |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT d.id
+                 FROM documents d
+                 LEFT JOIN embeddings e ON e.document_id = d.id AND e.model_id IS NULL
+                 WHERE d.deleted_at IS NULL
+                 GROUP BY d.id
+                 HAVING (d.chunk_count IS NULL AND COUNT(e.id) = 0)
+                     OR COUNT(e.id) < d.chunk_count
+                 ORDER BY d.id",
+            )?;
+            let ids = stmt
+                .query_map([], |row| row.get(0))?
+                .collect::<std::result::Result<Vec<i64>, _>>()?;
+            Ok(ids)
+        })
+        .await
+    }
+
+    /// Chunk embeddings of every document not in the trash. Blobs that fail
+    /// to deserialize are skipped so one corrupt row does not stop startup;
+    /// the integrity audit reports them.
+    pub async fn get_all_chunk_embeddings(
+        &self,
+    ) -> Result<Vec<(i64, i64, usize, usize, Vec<f32>)>> {
+        self.execute_with_priority(OperationPriority::BackgroundIngest, |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT e.id, e.document_id, e.chunk_start, e.chunk_end, e.embedding
+                 FROM embeddings e
+                 JOIN documents d ON d.id = e.document_id
+                 WHERE d.deleted_at IS NULL AND e.model_id IS NULL
+                 ORDER BY e.document_id, e.chunk_start",
+            )?;
+
+            let rows = stmt.query_map([], |row| {
+                let id: i64 = row.get(0)?;
+                let document_id: i64 = row.get(1)?;
+                let chunk_start: i64 = row.get(2)?;
+                let chunk_end: i64 = row.get(3)?;
+                let embedding_bytes: Vec<u8> = row.get(4)?;
+                let embedding: Option<Vec<f32>> = bincode::deserialize(&embedding_bytes).ok();
+                Ok((
+                    id,
+                    document_id,
+                    chunk_start as usize,
+                    chunk_end as usize,
+                    embedding,
+                ))
+            })?;
+
+            let mut results = Vec::new();
+            let mut corrupt = 0;
+            for row in rows {
+                match row? {
+                    (id, document_id, start, end, Some(embedding)) => {
+                        results.push((id, document_id, start, end, embedding))
+                    }
+                    (_, _, _, _, None) => corrupt += 1,
+                }
+            }
+            if corrupt > 0 {
+                eprintln!("Skipped {} chunk embeddings that failed to load", corrupt);
+            }
+            Ok(results)
+        })
+        .await
+    }
+
+    pub async fn get_chunk_embeddings_for_document(
+        &self,
+        document_id: i64,
+    ) -> Result<Vec<(i64, usize, usize, Vec<f32>)>> {
+        self.execute_with_priority(OperationPriority::UserSearch, |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, chunk_start, chunk_end, embedding
+                 FROM embeddings WHERE document_id = ?1 AND model_id IS NULL
+                 ORDER BY chunk_start",
+            )?;
+
+            let rows = stmt.query_map(params![document_id], |row| {
+                let id: i64 = row.get(0)?;
+                let chunk_start: i64 = row.get(1)?;
+                let chunk_end: i64 = row.get(2)?;
+                let embedding_bytes: Vec<u8> = row.get(3)?;
+                let embedding: Vec<f32> = bincode::deserialize(&embedding_bytes)
+                    .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+                Ok((id, chunk_start as usize, chunk_end as usize, embedding))
+            })?;
+
+            let mut results = Vec::new();
+            for row in rows {
+                results.push(row?);
+            }
+            Ok(results)
+        })
+        .await
+    }
+
+    /// Chunk spans and provenance of a document's searched embeddings, to
+    /// embed the same chunks with another model.
+    pub async fn get_chunk_spans(
+        &self,
+        document_id: i64,
+    ) -> Result<Vec<(usize, usize, ChunkProvenance)>> {
+        self.execute_with_priority(OperationPriority::BackgroundIngest, move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT chunk_start, chunk_end, section_heading, section_anchor, page_number,
+                        timestamp_seconds
+                 FROM embeddings WHERE document_id = ?1 AND model_id IS NULL
+                 ORDER BY chunk_start",
+            )?;
+            let spans = stmt
+                .query_map(params![document_id], |row| {
+                    let start: i64 = row.get(0)?;
+                    let end: i64 = row.get(1)?;
+                    Ok((
+                        start as usize,
+                        end as usize,
+                        ChunkProvenance {
+                            section_heading: row.get(2)?,
+                            section_anchor: row.get(3)?,
+                            page_number: row.get(4)?,
+                            timestamp_seconds: row.get(5)?,
+                            content_hash: None,
+                        },
+                    ))
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok(spans)
+        })
+        .await
+    }
+
+    /// Documents (including those in the trash) with searched embeddings but
+    /// none yet from `model_id`, oldest first.
+    pub async fn get_documents_to_migrate(&self, model_id: &str, limit: usize) -> Result<Vec<i64>> {
+        let model_id = model_id.to_string();
+        self.execute_with_priority(OperationPriority::BackgroundIngest, move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT DISTINCT e.document_id FROM embeddings e
+                 JOIN documents d ON d.id = e.document_id
+                 WHERE e.model_id IS NULL
+                   AND NOT EXISTS (SELECT 1 FROM embeddings m
+                                   WHERE m.document_id = e.document_id AND m.model_id = ?1)
+                 ORDER BY e.document_id
+                 LIMIT ?2",
+            )?;
+            let ids = stmt
+                .query_map(params![model_id, limit as i64], |row| row.get(0))?
+                .collect::<std::result::Result<Vec<i64>, _>>()?;
+            Ok(ids)
+        })
+        .await
+    }
+
+    /// Documents with searched embeddings, and how many of them also have
+    /// embeddings from `model_id`.
+    pub async fn get_migration_counts(&self, model_id: &str) -> Result<(usize, usize)> {
+        let model_id = model_id.to_string();
+        self.execute_with_priority(OperationPriority::BackgroundIngest, move |conn| {
+            let (total, migrated): (i64, i64) = conn.query_row(
+                "SELECT COUNT(*),
+                        COALESCE(SUM(EXISTS (SELECT 1 FROM embeddings m
+                                             WHERE m.document_id = e.document_id
+                                               AND m.model_id = ?1)), 0)
+                 FROM (SELECT DISTINCT document_id FROM embeddings
+                       WHERE model_id IS NULL
+                         AND document_id IN (SELECT id FROM documents)) e",
+                params![model_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )?;
+            Ok((total as usize, migrated as usize))
+        })
+        .await
+    }
+
+    /// Replace a document's chunk embeddings from `model_id`, a model being
+    /// migrated to, leaving its searched embeddings alone.
+    pub async fn write_model_embeddings(
+        &self,
+        doc_id: i64,
+        model_id: &str,
+        chunks: &[ChunkRow],
+    ) -> Result<()> {
+        let model_id = model_id.to_string();
+        let chunks = chunks.to_vec();
+        self.execute_with_priority(OperationPriority::BackgroundIngest, move |conn| {
+            let tx = conn.unchecked_transaction()?;
+            tx.execute(
+                "DELETE FROM embeddings WHERE document_id = ?1 AND model_id = ?2",
+                params![doc_id, model_id],
+            )?;
+            {
+                let mut stmt = tx.prepare(
+                    "INSERT INTO embeddings (document_id, chunk_start, chunk_end, embedding,
+                                             content_hash, section_heading, page_number,
+                                             timestamp_seconds, section_anchor, model_id)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                )?;
+                for (chunk_start, chunk_end, embedding, provenance) in &chunks {
+                    stmt.execute(params![
+                        doc_id,
+                        *chunk_start as i64,
+                        *chunk_end as i64,
+                        embedding,
+                        provenance.content_hash,
+                        provenance.section_heading,
+                        provenance.page_number,
+                        provenance.timestamp_seconds,
+                        provenance.section_anchor,
+                        model_id
+                    ])?;
+                }
+            }
+            tx.commit()?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Delete the embeddings of models being migrated to, except `keep`'s.
+    pub async fn delete_migration_embeddings(&self, keep: Option<&str>) -> Result<usize> {
+        let keep = keep.map(str::to_string);
+        self.execute_with_priority(OperationPriority::BackgroundIngest, move |conn| {
+            let deleted = conn.execute(
+                "DELETE FROM embeddings WHERE model_id IS NOT NULL AND model_id IS NOT ?1",
+                params![keep],
+            )?;
+            Ok(deleted)
+        })
+        .await
+    }
+
+    /// Finish switching to `model_id` in one transaction: when every
+    /// document has its embeddings, they replace the searched ones and
+    /// `settings` become the embedding settings. Returns `false`, changing
+    /// nothing, while some document still lacks them.
+    pub async fn complete_embedding_migration(
+        &self,
+        model_id: &str,
+        settings: &crate::embedding_provider::EmbeddingSettings,
+    ) -> Result<bool> {
+        let model_id = model_id.to_string();
+        let config = [
+            ("embedding_provider", settings.provider.as_config_str()),
+            ("embedding_url", settings.url.trim()),
+            ("embedding_model", settings.model.trim()),
+        ]
+        .map(|(key, value)| (key, value.to_string()));
+        self.execute_with_priority(OperationPriority::BackgroundIngest, move |conn| {
+            let tx = conn.unchecked_transaction()?;
+            let remaining: i64 = tx.query_row(
+                "SELECT COUNT(DISTINCT document_id) FROM embeddings e
+                 WHERE model_id IS NULL
+                   AND document_id IN (SELECT id FROM documents)
+                   AND NOT EXISTS (SELECT 1 FROM embeddings m
+                                   WHERE m.document_id = e.document_id AND m.model_id = ?1)",
+                params![model_id],
+                |row| row.get(0),
+            )?;
+            if remaining > 0 {
+                return Ok(false);
+            }
+
+            tx.execute("DELETE FROM embeddings WHERE model_id IS NULL", [])?;
+            tx.execute(
+                "UPDATE embeddings SET model_id = NULL WHERE model_id = ?1",
+                params![model_id],
+            )?;
+            tx.execute("DELETE FROM embeddings WHERE model_id IS NOT NULL", [])?;
+            for (key, value) in &config {
+                tx.execute(
+                    "INSERT OR REPLACE INTO config (key, value, updated_at)
+                     VALUES (?1, ?2, CURRENT_TIMESTAMP)",
+                    params![key, value],
+                )?;
+            }
+            tx.execute("DELETE FROM config WHERE key = 'embedding_migration'", [])?;
+            tx.commit()?;
+            Ok(true)
+        })
+        .await
+    }
+
+    pub async fn delete_all_embeddings(&self) -> Result<()> {
+        self.execute_with_priority(OperationPriority::BackgroundIngest, |conn| {
+            // Drop and recreate the embeddings table to ensure correct schema
+            // (handles migration from old schema that had chunk_index column)
+            conn.execute("DROP TABLE IF EXISTS embeddings", [])?;
+            conn.execute(
+                "CREATE TABLE embeddings (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    document_id INTEGER NOT NULL,
+                    chunk_start INTEGER NOT NULL,
+                    chunk_end INTEGER NOT NULL,
+                    embedding BLOB NOT NULL,
+                    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                    FOREIGN KEY (document_id) REFERENCES documents (id) ON DELETE CASCADE
+                )",
+                [],
+            )?;
+            conn.execute(
+                "CREATE INDEX IF NOT EXISTS idx_embeddings_document_id ON embeddings(document_id)",
+                [],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn url_exists(&self, url: &str, priority: OperationPriority) -> Result<bool> {
+        let normalized = self.normalize_url(url);
+        self.execute_with_priority(priority, move |conn| {
+            let mut stmt = conn
+                .prepare("SELECT COUNT(*) FROM documents WHERE url = ?1 AND deleted_at IS NULL")?;
+            let count: i64 = stmt.query_row(params![normalized], |row| row.get(0))?;
+            Ok(count > 0)
+        })
+        .await
+    }
+
+    pub async fn count_documents(&self, priority: OperationPriority) -> Result<i64> {
+        self.execute_with_priority(priority, |conn| {
+            let count: i64 = conn.query_row(
+                "SELECT COUNT(*) FROM documents WHERE deleted_at IS NULL",
+                [],
+                |row| row.get(0),
+            )?;
+            Ok(count)
+        })
+        .await
+    }
+
+    /// Gather document, chunk, and link-state counts for the whole index.
+    pub async fn get_index_stats(&self) -> Result<IndexStats> {
+        self.execute_with_priority(OperationPriority::UserSearch, |conn| {
+            let (documents, dead, needs_auth): (i64, i64, i64) = conn.query_row(
+                "SELECT COUNT(*),
+                        COALESCE(SUM(CASE WHEN is_dead = 1 THEN 1 ELSE 0 END), 0),
+                        COALESCE(SUM(CASE WHEN needs_auth = 1 THEN 1 ELSE 0 END), 0)
+                 FROM documents WHERE deleted_at IS NULL",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )?;
+            let chunks: i64 = conn.query_row(
+                "SELECT COUNT(*) FROM embeddings
+                 WHERE model_id IS NULL
+                   AND document_id IN (SELECT id FROM documents WHERE deleted_at IS NULL)",
+                [],
+                |row| row.get(0),
+            )?;
+
+            let mut stmt = conn.prepare(
+                "SELECT source, COUNT(*) FROM documents WHERE deleted_at IS NULL
+                 GROUP BY source ORDER BY COUNT(*) DESC",
+            )?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            let mut by_source = Vec::new();
+            for row in rows {
+                by_source.push(row?);
+            }
+
+            Ok(IndexStats {
+                documents,
+                chunks,
+                dead,
+                needs_auth,
+                by_source,
+                chunks_needing_migration: 0,
+            })
+        })
+        .await
+    }
+
+    /// Record a search and how many results it returned.
+    pub async fn record_search(&self, query: &str, result_count: usize) -> Result<()> {
+        let query = query.trim().to_string();
+        let now = chrono_utc_now();
+        self.execute_with_priority(OperationPriority::BackgroundIngest, move |conn| {
+            conn.execute(
+                "INSERT INTO search_history (query, result_count, searched_at)
+                 VALUES (?1, ?2, ?3)",
+                params![query, result_count as i64, now],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Summarize recorded searches, document opens and ingestion activity,
+    /// keeping the top `limit` entries of each list.
+    pub async fn get_usage_insights(&self, limit: usize) -> Result<UsageInsights> {
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            let searches: i64 =
+                conn.query_row("SELECT COUNT(*) FROM search_history", [], |row| row.get(0))?;
+
+            let mut stmt = conn.prepare(
+                "SELECT id, title, view_count FROM documents
+                 WHERE view_count > 0 AND deleted_at IS NULL
+                 ORDER BY view_count DESC, last_viewed_at DESC
+                 LIMIT ?1",
+            )?;
+            let most_revisited = stmt
+                .query_map(params![limit as i64], |row| {
+                    Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            let mut stmt = conn.prepare(
+                "SELECT query, COUNT(*) FROM search_history
+                 WHERE result_count = 0
+                 GROUP BY LOWER(query)
+                 ORDER BY COUNT(*) DESC, MAX(id) DESC
+                 LIMIT ?1",
+            )?;
+            let zero_result_queries = stmt
+                .query_map(params![limit as i64], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            let mut stmt = conn.prepare(
+                "SELECT date(created_at), COUNT(*) FROM documents
+                 WHERE deleted_at IS NULL
+                 GROUP BY date(created_at)
+                 ORDER BY COUNT(*) DESC, date(created_at) DESC
+                 LIMIT ?1",
+            )?;
+            let busiest_ingest_days = stmt
+                .query_map(params![limit as i64], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            Ok(UsageInsights {
+                searches,
+                most_revisited,
+                zero_result_queries,
+                busiest_ingest_days,
+            })
+        })
+        .await
+    }
+
+    /// Forget recorded usage: the search history, saved search sessions and
+    /// document open counts.
+    pub async fn purge_usage_data(&self) -> Result<()> {
+        self.execute_with_priority(OperationPriority::UserSearch, |conn| {
+            let tx = conn.unchecked_transaction()?;
+            tx.execute("DELETE FROM search_history", [])?;
+            tx.execute("DELETE FROM search_session_hits", [])?;
+            tx.execute("DELETE FROM search_sessions", [])?;
+            tx.execute(
+                "UPDATE documents SET view_count = 0, last_viewed_at = NULL
+                 WHERE view_count > 0 OR last_viewed_at IS NOT NULL",
+                [],
+            )?;
+            tx.commit()?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Save the results of a search, replacing any earlier session for the
+    /// same query and mode, and keep only the last `SEARCH_SESSION_LIMIT`.
+    pub async fn save_search_session(&self, session: &SearchSession) -> Result<()> {
+        let now = chrono_utc_now();
+        self.execute_with_priority(OperationPriority::BackgroundIngest, |conn| {
+            let tx = conn.unchecked_transaction()?;
+            tx.execute(
+                "DELETE FROM search_sessions WHERE query = ?1 AND passages = ?2",
+                params![session.query, session.passages],
+            )?;
+            tx.execute(
+                "INSERT INTO search_sessions (query, corrected_query, passages, searched_at)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    session.query,
+                    session.corrected_query,
+                    session.passages,
+                    now
+                ],
+            )?;
+            let session_id = tx.last_insert_rowid();
+            {
+                let mut stmt = tx.prepare(
+                    "INSERT INTO search_session_hits
+                         (session_id, rank, doc_id, similarity, chunk_start, chunk_end)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                )?;
+                for (rank, hit) in session.hits.iter().enumerate() {
+                    stmt.execute(params![
+                        session_id,
+                        rank as i64,
+                        hit.doc_id,
+                        hit.similarity,
+                        hit.passage.map(|(start, _)| start as i64),
+                        hit.passage.map(|(_, end)| end as i64),
+                    ])?;
+                }
+            }
+            tx.execute(
+                "DELETE FROM search_sessions WHERE id NOT IN (
+                     SELECT id FROM search_sessions ORDER BY id DESC LIMIT ?1)",
+                params![SEARCH_SESSION_LIMIT as i64],
+            )?;
+            tx.execute(
+                "DELETE FROM search_session_hits
+                 WHERE session_id NOT IN (SELECT id FROM search_sessions)",
+                [],
+            )?;
+            tx.commit()?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// The most recent saved session for `query` in the given mode. Hits on
+    /// documents trashed or deleted since are left out.
+    pub async fn get_search_session(
+        &self,
+        query: &str,
+        passages: bool,
+    ) -> Result<Option<SearchSession>> {
+        let query = query.trim().to_string();
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            let session = conn.query_row(
+                "SELECT id, corrected_query FROM search_sessions
+                 WHERE query = ?1 AND passages = ?2
+                 ORDER BY id DESC LIMIT 1",
+                params![query, passages],
+                |row| Ok((row.get::<_, i64>(0)?, row.get::<_, Option<String>>(1)?)),
+            );
+            let (session_id, corrected_query) = match session {
+                Ok(session) => session,
+                Err(rusqlite::Error::QueryReturnedNoRows) => return Ok(None),
+                Err(e) => return Err(Box::new(e)),
+            };
+
+            let mut stmt = conn.prepare(
+                "SELECT h.doc_id, h.similarity, h.chunk_start, h.chunk_end
+                 FROM search_session_hits h
+                 JOIN documents d ON d.id = h.doc_id
+                 WHERE h.session_id = ?1 AND d.deleted_at IS NULL
+                 ORDER BY h.rank",
+            )?;
+            let hits = stmt
+                .query_map(params![session_id], |row| {
+                    let start: Option<i64> = row.get(2)?;
+                    let end: Option<i64> = row.get(3)?;
+                    Ok(SessionHit {
+                        doc_id: row.get(0)?,
+                        similarity: row.get(1)?,
+                        passage: start.zip(end).map(|(s, e)| (s as usize, e as usize)),
+                    })
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+
+            Ok(Some(SearchSession {
+                query,
+                corrected_query,
+                passages,
+                hits,
+            }))
+        })
+        .await
+    }
+
+    /// Whether restored search results are refreshed by re-running the
+    /// query in the background (default: on).
+    pub async fn get_refresh_restored_search(&self) -> Result<bool> {
+        Ok(self.get_config("refresh_restored_search").await?.as_deref() != Some("0"))
+    }
+
+    pub async fn set_refresh_restored_search(&self, enabled: bool) -> Result<()> {
+        self.set_config("refresh_restored_search", if enabled { "1" } else { "0" })
+            .await
+    }
+
+    /// Gather the home dashboard: index counts, the latest `per_source`
+    /// saves of the `sources` most recently used sources, and the last
+    /// `searches` distinct queries.
+    pub async fn get_home_summary(
+        &self,
+        per_source: usize,
+        sources: usize,
+        searches: usize,
+    ) -> Result<HomeSummary> {
+        let week_ago = chrono_utc_now().parse::<i64>().unwrap_or(0) - 7 * 24 * 60 * 60;
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            let (documents, saved_this_week, unread): (i64, i64, i64) = conn.query_row(
+                "SELECT COUNT(*),
+                        COALESCE(SUM(CASE WHEN created_at >= datetime('now', '-7 days') THEN 1 ELSE 0 END), 0),
+                        COALESCE(SUM(CASE WHEN COALESCE(is_read, 0) = 0 THEN 1 ELSE 0 END), 0)
+                 FROM documents
+                 WHERE deleted_at IS NULL",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )?;
+            let searches_this_week: i64 = conn.query_row(
+                "SELECT COUNT(*) FROM search_history WHERE CAST(searched_at AS INTEGER) >= ?1",
+                params![week_ago],
+                |row| row.get(0),
+            )?;
+
+            let mut stmt = conn.prepare(
+                "SELECT source, id, title, url, created_at FROM (
+                     SELECT source, id, title, url, created_at,
+                            ROW_NUMBER() OVER (
+                                PARTITION BY source ORDER BY created_at DESC, id DESC
+                            ) AS position,
+                            MAX(id) OVER (PARTITION BY source) AS latest
+                     FROM documents
+                     WHERE deleted_at IS NULL AND (is_dead = 0 OR is_dead IS NULL)
+                 )
+                 WHERE position <= ?1
+                 ORDER BY latest DESC, position",
+            )?;
+            let rows = stmt.query_map(params![per_source as i64], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    RecentSave {
+                        id: row.get(1)?,
+                        title: row.get(2)?,
+                        url: row.get(3)?,
+                        created_at: row.get(4)?,
+                    },
+                ))
+            })?;
+            let mut recent_by_source: Vec<(String, Vec<RecentSave>)> = Vec::new();
+            for row in rows {
+                let (source, save) = row?;
+                let same_source = recent_by_source
+                    .last()
+                    .is_some_and(|(last, _)| *last == source);
+                if same_source {
+                    if let Some((_, saves)) = recent_by_source.last_mut() {
+                        saves.push(save);
+                    }
+                } else if recent_by_source.len() == sources {
+                    break;
+                } else {
+                    recent_by_source.push((source, vec![save]));
+                }
+            }
+
+            let mut stmt = conn.prepare(
+                "SELECT query, MAX(id) AS last FROM search_history
+                 WHERE query != ''
+                 GROUP BY LOWER(query)
+                 ORDER BY last DESC
+                 LIMIT ?1",
+            )?;
+            let recent_searches = stmt
+                .query_map(params![searches as i64], |row| row.get(0))?
+                .collect::<std::result::Result<Vec<String>, _>>()?;
+
+            Ok(HomeSummary {
+                documents,
+                saved_this_week,
+                unread,
+                searches_this_week,
+                recent_by_source,
+                recent_searches,
+            })
+        })
+        .await
+    }
+
+    /// Insert several documents from one source and all of their chunk
+    /// embeddings in one transaction, as `insert_document_with_chunks` does
+    /// for one. Each entry is (title, content, url, chunks); returns each
+    /// document's ID and embedding IDs, in order.
+    #[allow(clippy::type_complexity)]
+    pub async fn batch_insert_documents(
+        &self,
+        source: &str,
+        documents: &[(&str, &str, Option<&str>, &[ChunkRow])],
+    ) -> Result<Vec<(i64, Vec<i64>)>> {
+        let tracking_params = self.tracking_params();
+        self.execute_with_priority(OperationPriority::BackgroundIngest, |conn| {
+            let tx = conn.unchecked_transaction()?;
+            let mut inserted = Vec::with_capacity(documents.len());
+            {
+                let mut stmt = tx.prepare(
+                    "INSERT INTO documents (title, content, url, source, chunk_count)
+                     VALUES (?1, ?2, ?3, ?4, ?5)",
+                )?;
+                for &(title, content, url, chunks) in documents {
+                    stmt.execute(params![
+                        title,
+                        content,
+                        url.map(|url| normalize_url(url, &tracking_params)),
+                        source,
+                        chunks.len() as i64
+                    ])?;
+                    let doc_id = tx.last_insert_rowid();
+                    inserted.push((doc_id, write_chunk_embeddings(&tx, doc_id, chunks)?));
+
+                    // Yield periodically during batch operations
+                    if inserted.len() % 10 == 0 {
+                        std::thread::yield_now();
+                    }
+                }
+            }
+            tx.commit()?;
+            Ok(inserted)
+        })
+        .await
+    }
+
+    pub async fn mark_url_as_dead(&self, url: &str) -> Result<()> {
+        let normalized = self.normalize_url(url);
+        let now = chrono_utc_now();
+        self.execute_with_priority(OperationPriority::BackgroundIngest, move |conn| {
+            conn.execute(
+                "UPDATE documents SET is_dead = 1, dead_since = COALESCE(dead_since, ?2)
+                 WHERE url = ?1",
+                params![normalized, now],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn mark_url_as_needs_auth(&self, url: &str) -> Result<()> {
+        let normalized = self.normalize_url(url);
+        self.execute_with_priority(OperationPriority::BackgroundIngest, move |conn| {
+            conn.execute(
+                "UPDATE documents SET needs_auth = 1 WHERE url = ?1",
+                params![normalized],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn get_document_by_url(&self, url: &str) -> Result<Option<Document>> {
+        let normalized = self.normalize_url(url);
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, title, content, url, source, created_at, embedding, is_dead, needs_auth, profile
+                 FROM documents WHERE url = ?1 AND deleted_at IS NULL LIMIT 1",
+            )?;
+
+            match stmt.query_row(params![normalized], |row| {
+                Ok(Document {
+                    id: row.get(0)?,
+                    title: row.get(1)?,
+                    content: row.get(2)?,
+                    url: row.get(3)?,
+                    source: row.get(4)?,
+                    created_at: row.get(5)?,
+                    embedding: row.get(6)?,
+                    is_dead: row.get(7)?,
+                    needs_auth: row.get(8)?,
+                    profile: row.get(9)?,
+                })
+            }) {
+                Ok(doc) => Ok(Some(doc)),
+                Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+                Err(e) => Err(Box::new(e)),
+            }
+        })
+        .await
+    }
+
+    pub async fn update_document_content(
+        &self,
+        doc_id: i64,
+        title: &str,
+        content: &str,
+    ) -> Result<()> {
+        self.execute_with_priority(OperationPriority::BackgroundIngest, |conn| {
+            conn.execute(
+                "UPDATE documents SET title = ?1, content = ?2, is_dead = 0, needs_auth = 0,
+                        dead_since = NULL
+                 WHERE id = ?3",
+                params![title, content, doc_id],
+            )?;
+            // Update FTS index
+            conn.execute(
+                "UPDATE documents_fts SET title = ?1, content = ?2 WHERE rowid = ?3",
+                params![title, content, doc_id],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Rename a document. Its content and embeddings are unchanged.
+    pub async fn update_document_title(&self, doc_id: i64, title: &str) -> Result<()> {
+        let title = title.trim().to_string();
+        if title.is_empty() {
+            return Err("Title cannot be empty".into());
+        }
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            let tx = conn.unchecked_transaction()?;
+            tx.execute(
+                "UPDATE documents SET title = ?1 WHERE id = ?2",
+                params![title, doc_id],
+            )?;
+            tx.execute(
+                "UPDATE documents_fts SET title = ?1 WHERE rowid = ?2",
+                params![title, doc_id],
+            )?;
+            tx.commit()?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Chunk, embedding and link-check details of a document, or `None` if
+    /// it does not exist.
+    pub async fn get_document_metadata(&self, doc_id: i64) -> Result<Option<DocumentMetadata>> {
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            let metadata = conn.query_row(
+                "SELECT (SELECT COUNT(*) FROM embeddings
+                         WHERE document_id = d.id AND model_id IS NULL),
+                        d.chunk_count, d.link_checked_at, COALESCE(d.view_count, 0), d.summary,
+                        NULLIF(d.language, '')
+                 FROM documents d WHERE d.id = ?1",
+                params![doc_id],
+                |row| {
+                    Ok(DocumentMetadata {
+                        embedded_chunks: row.get(0)?,
+                        chunk_count: row.get(1)?,
+                        link_checked_at: row.get(2)?,
+                        view_count: row.get(3)?,
+                        summary: row.get(4)?,
+                        language: row.get(5)?,
+                    })
+                },
+            );
+            match metadata {
+                Ok(metadata) => Ok(Some(metadata)),
+                Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+                Err(e) => Err(Box::new(e)),
+            }
+        })
+        .await
+    }
+
+    pub async fn set_document_summary(&self, doc_id: i64, summary: &str) -> Result<()> {
+        let summary = summary.to_string();
+        self.execute_with_priority(OperationPriority::BackgroundIngest, move |conn| {
+            conn.execute(
+                "UPDATE documents SET summary = ?1 WHERE id = ?2",
+                params![summary, doc_id],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Record a document's detected language; '' when it could not be told.
+    pub async fn set_document_language(&self, doc_id: i64, language: &str) -> Result<()> {
+        let language = language.to_string();
+        self.execute_with_priority(OperationPriority::BackgroundIngest, move |conn| {
+            conn.execute(
+                "UPDATE documents SET language = ?1 WHERE id = ?2",
+                params![language, doc_id],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Documents (not in the trash) whose language has not been detected
+    /// yet, as (id, title, start of content), at most `limit`.
+    pub async fn get_documents_without_language(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<(i64, String, String)>> {
+        self.execute_with_priority(OperationPriority::BackgroundIngest, |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, title, substr(content, 1, 4000) FROM documents
+                 WHERE language IS NULL AND deleted_at IS NULL
+                 ORDER BY id DESC LIMIT ?1",
+            )?;
+            let documents = stmt
+                .query_map(params![limit as i64], |row| {
+                    Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+                })?
+                .collect::<std::result::Result<Vec<(i64, String, String)>, _>>()?;
+            Ok(documents)
+        })
+        .await
+    }
+
+    /// Detected languages of the given documents; documents whose language
+    /// is unknown are left out.
+    pub async fn get_document_languages(
+        &self,
+        doc_ids: &[i64],
+    ) -> Result<std::collections::HashMap<i64, String>> {
+        self.execute_with_priority(OperationPriority::UserSearch, |conn| {
+            let mut stmt =
+                conn.prepare("SELECT language FROM documents WHERE id = ?1 AND language != ''")?;
+            let mut languages = std::collections::HashMap::new();
+            for &doc_id in doc_ids {
+                match stmt.query_row(params![doc_id], |row| row.get::<_, String>(0)) {
+                    Ok(language) => {
+                        languages.insert(doc_id, language);
+                    }
+                    Err(rusqlite::Error::QueryReturnedNoRows) => {}
+                    Err(e) => return Err(Box::new(e)),
+                }
+            }
+            Ok(languages)
+        })
+        .await
+    }
+
+    /// Detected languages with the number of documents (not in the trash)
+    /// in each, most common first.
+    pub async fn get_language_counts(&self) -> Result<Vec<(String, i64)>> {
+        self.execute_with_priority(OperationPriority::UserSearch, |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT language, COUNT(*) FROM documents
+                 WHERE language != '' AND deleted_at IS NULL
+                 GROUP BY language
+                 ORDER BY COUNT(*) DESC, language",
+            )?;
+            let counts = stmt
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<std::result::Result<Vec<(String, i64)>, _>>()?;
+            Ok(counts)
+        })
+        .await
+    }
+
+    /// Sources with the number of documents (not in the trash) saved from
+    /// each, most common first.
+    pub async fn get_source_counts(&self) -> Result<Vec<(String, i64)>> {
+        self.execute_with_priority(OperationPriority::UserSearch, |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT source, COUNT(*) FROM documents
+                 WHERE deleted_at IS NULL
+                 GROUP BY source
+                 ORDER BY COUNT(*) DESC, source",
+            )?;
+            let counts = stmt
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<std::result::Result<Vec<(String, i64)>, _>>()?;
+            Ok(counts)
+        })
+        .await
+    }
+
+    /// Documents (not in the trash) saved from `source`, newest first,
+    /// optionally filtered to a Chrome profile.
+    pub async fn get_documents_by_source(
+        &self,
+        source: &str,
+        profile: Option<String>,
+        limit: usize,
+    ) -> Result<Vec<Document>> {
+        let source = source.to_string();
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, title, content, url, source, created_at, embedding, is_dead, needs_auth, profile
+                 FROM documents
+                 WHERE source = ?1 AND deleted_at IS NULL AND (?2 IS NULL OR profile = ?2)
+                 ORDER BY created_at DESC, id DESC
+                 LIMIT ?3",
+            )?;
+            let docs = stmt
+                .query_map(params![source, profile, limit as i64], document_from_row)?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok(docs)
+        })
+        .await
+    }
+
+    /// The documents (not in the trash) among `ids`, newest first, up to
+    /// `limit`, optionally filtered to a Chrome profile.
+    pub async fn get_documents_newest_first(
+        &self,
+        ids: &std::collections::HashSet<i64>,
+        profile: Option<String>,
+        limit: usize,
+    ) -> Result<Vec<Document>> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let ids = ids.clone();
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            Ok(newest_documents(conn, Some(&ids), profile, 0, limit)?)
+        })
+        .await
+    }
+
+    /// One page of the timeline: the documents (not in the trash) in
+    /// `scope` (all of them when `None`), newest first, skipping `offset`
+    /// and returning up to `limit`, optionally filtered to a Chrome profile.
+    pub async fn get_timeline_page(
+        &self,
+        scope: Option<std::collections::HashSet<i64>>,
+        profile: Option<String>,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<Document>> {
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            Ok(newest_documents(
+                conn,
+                scope.as_ref(),
+                profile,
+                offset,
+                limit,
+            )?)
+        })
+        .await
+    }
+
+    /// IDs of the documents (not in the trash) saved from any of `urls`.
+    pub async fn get_document_ids_by_urls(
+        &self,
+        urls: &[String],
+    ) -> Result<std::collections::HashSet<i64>> {
+        let normalized: Vec<String> = urls.iter().map(|url| self.normalize_url(url)).collect();
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            let mut stmt =
+                conn.prepare("SELECT id FROM documents WHERE url = ?1 AND deleted_at IS NULL")?;
+            let mut ids = std::collections::HashSet::new();
+            for url in &normalized {
+                for id in stmt.query_map(params![url], |row| row.get::<_, i64>(0))? {
+                    ids.insert(id?);
+                }
+            }
+            Ok(ids)
+        })
+        .await
+    }
+
+    /// Query parameters stripped from URLs (see `tracking_params`): the
+    /// saved list, or the defaults.
+    pub async fn get_tracking_params(&self) -> Result<Vec<String>> {
+        match self.get_config("tracking_params").await? {
+            Some(json_str) => Ok(serde_json::from_str(&json_str)
+                .map_err(|e| format!("Failed to parse tracking parameters: {}", e))?),
+            None => Ok(crate::tracking_params::defaults()),
+        }
+    }
+
+    /// Save the query parameters stripped from URLs and apply them to this
+    /// database straight away.
+    pub async fn set_tracking_params(&self, params: &[String]) -> Result<()> {
+        let json_str = serde_json::to_string(params)
+            .map_err(|e| format!("Failed to serialize tracking parameters: {}", e))?;
+        self.set_config("tracking_params", &json_str).await?;
+        self.update_cached_settings(|cached| cached.tracking_params = params.to_vec());
+        Ok(())
+    }
+
+    /// Image caption settings (see `image_caption`), or the defaults.
+    pub async fn get_image_caption_settings(
+        &self,
+    ) -> Result<crate::image_caption::ImageCaptionSettings> {
+        match self.get_config("image_captions").await? {
+            Some(json_str) => Ok(serde_json::from_str(&json_str)
+                .map_err(|e| format!("Failed to parse image caption settings: {}", e))?),
+            None => Ok(crate::image_caption::ImageCaptionSettings::default()),
+        }
+    }
+
+    /// Save the image caption settings and apply them to this database
+    /// straight away.
+    pub async fn set_image_caption_settings(
+        &self,
+        settings: &crate::image_caption::ImageCaptionSettings,
+    ) -> Result<()> {
+        let json_str = serde_json::to_string(settings)
+            .map_err(|e| format!("Failed to serialize image caption settings: {}", e))?;
+        self.set_config("image_captions", &json_str).await?;
+        self.update_cached_settings(|cached| cached.image_captions = settings.clone());
+        Ok(())
+    }
+
+    /// Domains marked as high or low quality, by domain.
+    pub async fn get_domain_preferences(
+        &self,
+    ) -> Result<Vec<crate::domain_preferences::DomainPreference>> {
+        use crate::domain_preferences::{DomainPreference, DomainQuality};
+        self.execute_with_priority(OperationPriority::UserSearch, |conn| {
+            let mut stmt =
+                conn.prepare("SELECT domain, quality FROM domain_preferences ORDER BY domain")?;
+            let rows = stmt
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<std::result::Result<Vec<(String, String)>, _>>()?;
+            Ok(rows
+                .into_iter()
+                .filter_map(|(domain, quality)| {
+                    Some(DomainPreference {
+                        domain,
+                        quality: DomainQuality::parse(&quality)?,
+                    })
+                })
+                .collect())
+        })
+        .await
+    }
+
+    /// Mark a domain as high or low quality, or clear its mark with
+    /// `None`, and apply the marks to this database straight away.
+    pub async fn set_domain_preference(
+        &self,
+        domain: &str,
+        quality: Option<crate::domain_preferences::DomainQuality>,
+    ) -> Result<()> {
+        let domain = domain.to_string();
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            match quality {
+                Some(quality) => conn.execute(
+                    "INSERT INTO domain_preferences (domain, quality) VALUES (?1, ?2)
+                     ON CONFLICT(domain) DO UPDATE SET quality = excluded.quality",
+                    params![domain, quality.as_str()],
+                )?,
+                None => conn.execute(
+                    "DELETE FROM domain_preferences WHERE domain = ?1",
+                    params![domain],
+                )?,
+            };
+            Ok(())
+        })
+        .await?;
+        let preferences = self.get_domain_preferences().await?;
+        self.update_cached_settings(|cached| cached.domain_preferences = preferences);
+        Ok(())
+    }
+
+    /// Score multipliers for marked domains (see `domain_preferences`), or
+    /// the defaults.
+    pub async fn get_domain_boost(&self) -> Result<crate::domain_preferences::DomainBoost> {
+        match self.get_config("domain_boost").await? {
+            Some(json_str) => Ok(serde_json::from_str(&json_str)
+                .map_err(|e| format!("Failed to parse domain boost: {}", e))?),
+            None => Ok(crate::domain_preferences::DomainBoost::default()),
+        }
+    }
+
+    /// Save the score multipliers for marked domains and apply them to this
+    /// database straight away.
+    pub async fn set_domain_boost(
+        &self,
+        boost: &crate::domain_preferences::DomainBoost,
+    ) -> Result<()> {
+        let json_str = serde_json::to_string(boost)
+            .map_err(|e| format!("Failed to serialize domain boost: {}", e))?;
+        self.set_config("domain_boost", &json_str).await?;
+        self.update_cached_settings(|cached| cached.domain_boost = *boost);
+        Ok(())
+    }
+
+    /// URLs of the documents among `doc_ids` that have one.
+    pub async fn get_document_url_map(
+        &self,
+        doc_ids: &[i64],
+    ) -> Result<std::collections::HashMap<i64, String>> {
+        if doc_ids.is_empty() {
+            return Ok(Default::default());
+        }
+        self.execute_with_priority(OperationPriority::UserSearch, |conn| {
+            let placeholders = doc_ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+            let mut stmt = conn.prepare(&format!(
+                "SELECT id, url FROM documents WHERE url IS NOT NULL AND id IN ({})",
+                placeholders
+            ))?;
+            let params: Vec<_> = doc_ids
+                .iter()
+                .map(|id| id as &dyn rusqlite::ToSql)
+                .collect();
+            let urls = stmt
+                .query_map(&params[..], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<std::result::Result<_, _>>()?;
+            Ok(urls)
+        })
+        .await
+    }
+
+    /// Earlier versions of a document, newest first.
+    pub async fn get_document_versions(
+        &self,
+        doc_id: i64,
+    ) -> Result<Vec<crate::document_versions::DocumentVersion>> {
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, document_id, title, content, saved_at FROM document_versions
+                 WHERE document_id = ?1 ORDER BY id DESC",
+            )?;
+            let versions = stmt
+                .query_map(params![doc_id], document_version_from_row)?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok(versions)
+        })
+        .await
+    }
+
+    /// A version of a document by its ID.
+    pub async fn get_document_version(
+        &self,
+        version_id: i64,
+    ) -> Result<Option<crate::document_versions::DocumentVersion>> {
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            match conn.query_row(
+                "SELECT id, document_id, title, content, saved_at FROM document_versions
+                 WHERE id = ?1",
+                params![version_id],
+                document_version_from_row,
+            ) {
+                Ok(version) => Ok(Some(version)),
+                Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+                Err(e) => Err(e.into()),
+            }
+        })
+        .await
+    }
+
+    /// Save a highlight on a range of a document's content, returning it.
+    pub async fn insert_annotation(
+        &self,
+        doc_id: i64,
+        (start, end): (usize, usize),
+        quote: &str,
+        note: Option<&str>,
+        highlight_document_id: Option<i64>,
+    ) -> Result<crate::annotations::Annotation> {
+        let quote = quote.to_string();
+        let note = note.map(str::to_string);
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            conn.execute(
+                "INSERT INTO annotations
+                     (document_id, start_offset, end_offset, quote, note, highlight_document_id)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    doc_id,
+                    start as i64,
+                    end as i64,
+                    quote,
+                    note,
+                    highlight_document_id
+                ],
+            )?;
+            let id = conn.last_insert_rowid();
+            Ok(conn.query_row(
+                &format!("{} WHERE id = ?1", ANNOTATION_SELECT),
+                params![id],
+                annotation_from_row,
+            )?)
+        })
+        .await
+    }
+
+    /// Highlights on a document, in the order they appear.
+    pub async fn get_document_annotations(
+        &self,
+        doc_id: i64,
+    ) -> Result<Vec<crate::annotations::Annotation>> {
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            let mut stmt = conn.prepare(&format!(
+                "{} WHERE document_id = ?1 ORDER BY start_offset, id",
+                ANNOTATION_SELECT
+            ))?;
+            let annotations = stmt
+                .query_map(params![doc_id], annotation_from_row)?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok(annotations)
+        })
+        .await
+    }
+
+    /// The highlight a document was stored for, if it is a highlight's
+    /// document and the highlighted document is not in the trash.
+    pub async fn get_annotation_by_highlight_document(
+        &self,
+        highlight_document_id: i64,
+    ) -> Result<Option<crate::annotations::Annotation>> {
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            match conn.query_row(
+                &format!(
+                    "{} WHERE highlight_document_id = ?1
+                       AND document_id IN (SELECT id FROM documents WHERE deleted_at IS NULL)",
+                    ANNOTATION_SELECT
+                ),
+                params![highlight_document_id],
+                annotation_from_row,
+            ) {
+                Ok(annotation) => Ok(Some(annotation)),
+                Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+                Err(e) => Err(e.into()),
+            }
+        })
+        .await
+    }
+
+    /// Delete a highlight, returning the document it was found by so the
+    /// caller can delete that too.
+    pub async fn delete_annotation(&self, id: i64) -> Result<Option<i64>> {
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            match conn.query_row(
+                "DELETE FROM annotations WHERE id = ?1 RETURNING highlight_document_id",
+                params![id],
+                |row| row.get(0),
+            ) {
+                Ok(highlight_document_id) => Ok(highlight_document_id),
+                Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+                Err(e) => Err(e.into()),
+            }
+        })
+        .await
+    }
+
+    /// Remind about a document on a local date, replacing any reminder it
+    /// already has. Returns the date as `YYYY-MM-DD`.
+    pub async fn set_reminder(
+        &self,
+        doc_id: i64,
+        on: &crate::reminders::RemindOn,
+        notify: bool,
+    ) -> Result<String> {
+        let (date, days) = on.sql_args();
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            let changed = conn.execute(
+                "INSERT INTO reminders (document_id, remind_at, notify)
+                 SELECT ?1, datetime(COALESCE(?2, date('now', 'localtime')), ?3, 'utc'), ?4
+                 WHERE date(COALESCE(?2, 'now')) IS NOT NULL
+                 ON CONFLICT(document_id) DO UPDATE SET
+                     remind_at = excluded.remind_at,
+                     notify = excluded.notify,
+                     fired_at = NULL",
+                params![doc_id, date, days, notify],
+            )?;
+            if changed == 0 {
+                return Err(format!("Invalid reminder date: {:?}", date).into());
+            }
+            let remind_on = conn.query_row(
+                "SELECT date(remind_at, 'localtime') FROM reminders WHERE document_id = ?1",
+                params![doc_id],
+                |row| row.get(0),
+            )?;
+            Ok(remind_on)
+        })
+        .await
+    }
+
+    /// Mark reminders that have come due as fired and return them, so each
+    /// is announced once. Reminders of trashed documents wait until they are
+    /// restored.
+    pub async fn fire_due_reminders(&self) -> Result<Vec<crate::reminders::Reminder>> {
+        self.execute_with_priority(OperationPriority::BackgroundIngest, |conn| {
+            let tx = conn.unchecked_transaction()?;
+            let due =
+                query_reminders(&tx, "r.fired_at IS NULL AND r.remind_at <= datetime('now')")?;
+            for reminder in &due {
+                tx.execute(
+                    "UPDATE reminders SET fired_at = CURRENT_TIMESTAMP WHERE document_id = ?1",
+                    params![reminder.document_id],
+                )?;
+            }
+            tx.commit()?;
+            Ok(due)
+        })
+        .await
+    }
+
+    /// Reminders that have fired and not been dismissed, oldest first.
+    pub async fn get_fired_reminders(&self) -> Result<Vec<crate::reminders::Reminder>> {
+        self.execute_with_priority(OperationPriority::UserSearch, |conn| {
+            Ok(query_reminders(conn, "r.fired_at IS NOT NULL")?)
+        })
+        .await
+    }
+
+    /// Remove a document's reminder, whether or not it has fired.
+    pub async fn dismiss_reminder(&self, doc_id: i64) -> Result<()> {
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            conn.execute(
+                "DELETE FROM reminders WHERE document_id = ?1",
+                params![doc_id],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Replace the cached Explore map with `points` as (document ID, x, y),
+    /// recording the embedding model they were projected from.
+    pub async fn replace_projection(
+        &self,
+        points: Vec<(i64, f32, f32)>,
+        model: &str,
+    ) -> Result<()> {
+        self.execute_with_priority(OperationPriority::BackgroundIngest, move |conn| {
+            let tx = conn.unchecked_transaction()?;
+            tx.execute("DELETE FROM document_projection", [])?;
+            {
+                let mut stmt = tx.prepare(
+                    "INSERT INTO document_projection (document_id, x, y) VALUES (?1, ?2, ?3)",
+                )?;
+                for (document_id, x, y) in &points {
+                    stmt.execute(params![document_id, x, y])?;
+                }
+            }
+            tx.commit()?;
+            Ok(())
+        })
+        .await?;
+        self.set_config("projection_model", model).await
+    }
+
+    /// The cached Explore map: documents not in the trash with their
+    /// position, and the embedding model the map was projected from.
+    pub async fn get_projection(&self) -> Result<(Vec<ProjectionPoint>, Option<String>)> {
+        let points = self
+            .execute_with_priority(OperationPriority::UserSearch, |conn| {
+                let mut stmt = conn.prepare(
+                    "SELECT d.id, d.title, d.source, p.x, p.y
+                     FROM document_projection p
+                     JOIN documents d ON d.id = p.document_id
+                     WHERE d.deleted_at IS NULL",
+                )?;
+                let points = stmt
+                    .query_map([], |row| {
+                        Ok(ProjectionPoint {
+                            document_id: row.get(0)?,
+                            title: row.get(1)?,
+                            source: row.get(2)?,
+                            x: row.get::<_, f64>(3)? as f32,
+                            y: row.get::<_, f64>(4)? as f32,
+                        })
+                    })?
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+                Ok(points)
+            })
+            .await?;
+        Ok((points, self.get_config("projection_model").await?))
+    }
+
+    /// Languages (ISO 639-3) whose documents are not embedded.
+    pub async fn get_excluded_languages(&self) -> Result<Vec<String>> {
+        match self.get_config("excluded_languages").await? {
+            Some(json_str) => Ok(serde_json::from_str(&json_str)
+                .map_err(|e| format!("Failed to parse excluded languages: {}", e))?),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    pub async fn set_excluded_languages(&self, languages: &[String]) -> Result<()> {
+        let json_str = serde_json::to_string(languages)
+            .map_err(|e| format!("Failed to serialize excluded languages: {}", e))?;
+        self.set_config("excluded_languages", &json_str).await
+    }
+
+    /// Flag a document as a junk page, or clear the flag with `None`.
+    /// `Some("")` records that the user kept the page in full.
+    pub async fn set_document_quality_issue(&self, doc_id: i64, issue: Option<&str>) -> Result<()> {
+        let issue = issue.map(str::to_string);
+        self.execute_with_priority(OperationPriority::BackgroundIngest, move |conn| {
+            conn.execute(
+                "UPDATE documents SET quality_issue = ?1 WHERE id = ?2",
+                params![issue, doc_id],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// A document's quality issue code, '' when the user kept it in full, or
+    /// `None` when it was never flagged.
+    pub async fn get_document_quality_issue(&self, doc_id: i64) -> Result<Option<String>> {
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            match conn.query_row(
+                "SELECT quality_issue FROM documents WHERE id = ?1",
+                params![doc_id],
+                |row| row.get::<_, Option<String>>(0),
+            ) {
+                Ok(issue) => Ok(issue),
+                Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+                Err(e) => Err(Box::new(e)),
+            }
+        })
+        .await
+    }
+
+    /// Mark a document whose content is only a fetch placeholder, or clear
+    /// the mark once it has real content. Marked documents are left out of
+    /// search results.
+    pub async fn set_document_needs_refetch(&self, doc_id: i64, needs_refetch: bool) -> Result<()> {
+        self.execute_with_priority(OperationPriority::BackgroundIngest, move |conn| {
+            conn.execute(
+                "UPDATE documents SET needs_refetch = ?1 WHERE id = ?2",
+                params![needs_refetch, doc_id],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn document_needs_refetch(&self, doc_id: i64) -> Result<bool> {
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            match conn.query_row(
+                "SELECT needs_refetch FROM documents WHERE id = ?1",
+                params![doc_id],
+                |row| row.get::<_, bool>(0),
+            ) {
+                Ok(needs_refetch) => Ok(needs_refetch),
+                Err(rusqlite::Error::QueryReturnedNoRows) => Ok(false),
+                Err(e) => Err(Box::new(e)),
+            }
+        })
+        .await
+    }
+
+    /// Documents (not in the trash) marked as needing a refetch, newest
+    /// first, for the maintenance list in Settings.
+    pub async fn get_documents_needing_refetch(&self) -> Result<Vec<Document>> {
+        self.execute_with_priority(OperationPriority::UserSearch, |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, title, content, url, source, created_at, embedding, is_dead, needs_auth, profile
+                 FROM documents
+                 WHERE needs_refetch = 1 AND deleted_at IS NULL
+                 ORDER BY id DESC",
+            )?;
+
+            let docs = stmt.query_map([], |row| {
+                Ok(Document {
+                    id: row.get(0)?,
+                    title: row.get(1)?,
+                    content: row.get(2)?,
+                    url: row.get(3)?,
+                    source: row.get(4)?,
+                    created_at: row.get(5)?,
+                    embedding: row.get(6)?,
+                    is_dead: row.get(7)?,
+                    needs_auth: row.get(8)?,
+                    profile: row.get(9)?,
+                })
+            })?;
+
+            let mut results = Vec::new();
+            for doc in docs {
+                results.push(doc?);
+            }
+            Ok(results)
+        })
+        .await
+    }
+
+    /// Pages (not in the trash) indexed by title and URL only because they
+    /// looked like junk, newest first.
+    pub async fn get_low_quality_pages(&self) -> Result<Vec<crate::page_quality::LowQualityPage>> {
+        use crate::page_quality::{LowQualityPage, QualityIssue};
+        self.execute_with_priority(OperationPriority::UserSearch, |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, title, url, quality_issue, created_at FROM documents
+                 WHERE quality_issue != '' AND url IS NOT NULL AND deleted_at IS NULL
+                 ORDER BY id DESC",
+            )?;
+            let rows = stmt
+                .query_map([], |row| {
+                    Ok((
+                        row.get::<_, i64>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, String>(2)?,
+                        row.get::<_, String>(3)?,
+                        row.get::<_, String>(4)?,
+                    ))
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok(rows
+                .into_iter()
+                .filter_map(|(id, title, url, issue, created_at)| {
+                    Some(LowQualityPage {
+                        id,
+                        title,
+                        url,
+                        issue: QualityIssue::from_code(&issue)?,
+                        created_at,
+                    })
+                })
+                .collect())
+        })
+        .await
+    }
+
+    pub async fn delete_embeddings_for_document(&self, doc_id: i64) -> Result<()> {
+        self.execute_with_priority(OperationPriority::BackgroundIngest, |conn| {
+            conn.execute(
+                "DELETE FROM embeddings WHERE document_id = ?1",
+                params![doc_id],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn get_live_documents_with_urls(&self) -> Result<Vec<Document>> {
+        self.execute_with_priority(OperationPriority::BackgroundIngest, |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, title, content, url, source, created_at, embedding, is_dead, needs_auth, profile
+                 FROM documents
+                 WHERE url IS NOT NULL AND (is_dead IS NULL OR is_dead = 0)
+                   AND deleted_at IS NULL",
+            )?;
+
+            let docs = stmt.query_map([], |row| {
+                Ok(Document {
+                    id: row.get(0)?,
+                    title: row.get(1)?,
+                    content: row.get(2)?,
+                    url: row.get(3)?,
+                    source: row.get(4)?,
+                    created_at: row.get(5)?,
+                    embedding: row.get(6)?,
+                    is_dead: row.get(7)?,
+                    needs_auth: row.get(8)?,
+                    profile: row.get(9)?,
+                })
+            })?;
+
+            let mut results = Vec::new();
+            for doc in docs {
+                results.push(doc?);
+            }
+            Ok(results)
+        })
+        .await
+    }
+
+    /// Run a one-off dead-link check without progress reporting, returning the
+    /// number of documents newly marked dead or needing auth.
+    pub async fn check_and_mark_dead_urls(&self) -> Result<u32> {
+        let summary = crate::link_checker::run_link_check(self, None).await?;
+        Ok((summary.marked_dead + summary.marked_needs_auth) as u32)
+    }
+
+    /// Return every document with an http(s) URL, including dead ones so that
+    /// links which come back can be recovered.
+    pub async fn get_link_check_targets(
+        &self,
+    ) -> Result<Vec<crate::link_checker::LinkCheckTarget>> {
+        use crate::link_checker::LinkCheckTarget;
+        self.execute_with_priority(OperationPriority::BackgroundIngest, |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, url, COALESCE(is_dead, 0), COALESCE(link_check_failures, 0)
+                 FROM documents
+                 WHERE (url LIKE 'http://%' OR url LIKE 'https://%') AND deleted_at IS NULL
+                 ORDER BY id",
+            )?;
+            let rows = stmt.query_map([], |row| {
+                Ok(LinkCheckTarget {
+                    doc_id: row.get(0)?,
+                    url: row.get(1)?,
+                    is_dead: row.get(2)?,
+                    failures: row.get(3)?,
+                })
+            })?;
+            let mut out = Vec::new();
+            for row in rows {
+                out.push(row?);
+            }
+            Ok(out)
+        })
+        .await
+    }
+
+    /// Apply a link-check outcome to a document and report how its state changed.
+    ///
+    /// Transient failures only mark the document dead once `max_failures`
+    /// consecutive failures have been recorded; a live response resets the
+    /// counter and un-marks a dead document.
+    pub async fn apply_link_check_outcome(
+        &self,
+        doc_id: i64,
+        outcome: &crate::link_checker::LinkOutcome,
+        max_failures: i64,
+    ) -> Result<crate::link_checker::LinkChange> {
+        use crate::link_checker::{LinkChange, LinkOutcome};
+        let outcome = outcome.clone();
+        let now = chrono_utc_now();
+        self.execute_with_priority(OperationPriority::BackgroundIngest, move |conn| {
+            let (was_dead, was_needs_auth, failures): (bool, bool, i64) = conn.query_row(
+                "SELECT COALESCE(is_dead, 0), COALESCE(needs_auth, 0),
+                        COALESCE(link_check_failures, 0)
+                 FROM documents WHERE id = ?1",
+                params![doc_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )?;
+
+            let (is_dead, needs_auth, failures, change) = match outcome {
+                LinkOutcome::Alive if was_dead => (false, was_needs_auth, 0, LinkChange::Recovered),
+                LinkOutcome::Alive => (false, was_needs_auth, 0, LinkChange::Unchanged),
+                LinkOutcome::Gone if was_dead => (true, was_needs_auth, 0, LinkChange::Unchanged),
+                LinkOutcome::Gone => (true, was_needs_auth, 0, LinkChange::MarkedDead),
+                LinkOutcome::NeedsAuth if was_needs_auth && !was_dead => {
+                    (false, true, 0, LinkChange::Unchanged)
+                }
+                LinkOutcome::NeedsAuth => (false, true, 0, LinkChange::MarkedNeedsAuth),
+                LinkOutcome::Transient(_) => {
+                    let failures = failures + 1;
+                    if !was_dead && failures >= max_failures {
+                        (true, was_needs_auth, failures, LinkChange::MarkedDead)
+                    } else {
+                        (was_dead, was_needs_auth, failures, LinkChange::Unchanged)
+                    }
+                }
+            };
+
+            conn.execute(
+                "UPDATE documents
+                 SET is_dead = ?1, needs_auth = ?2, link_check_failures = ?3, link_checked_at = ?4,
+                     dead_since = CASE WHEN ?1 THEN COALESCE(dead_since, ?4) END
+                 WHERE id = ?5",
+                params![is_dead, needs_auth, failures, now, doc_id],
+            )?;
+            Ok(change)
+        })
+        .await
+    }
+
+    pub async fn get_dead_link_check_interval_hours(&self) -> Result<u64> {
+        match self.get_config("dead_link_check_interval_hours").await? {
+            Some(value) => Ok(value
+                .parse()
+                .unwrap_or(crate::link_checker::DEFAULT_INTERVAL_HOURS)),
+            None => Ok(crate::link_checker::DEFAULT_INTERVAL_HOURS),
+        }
+    }
+
+    pub async fn set_dead_link_check_interval_hours(&self, hours: u64) -> Result<()> {
+        self.set_config("dead_link_check_interval_hours", &hours.to_string())
+            .await
+    }
+
+    /// Whether native notifications are on for `event` (default: on).
+    pub async fn is_notification_enabled(
+        &self,
+        event: crate::notifications::NotificationEvent,
+    ) -> Result<bool> {
+        Ok(self.get_config(event.config_key()).await?.as_deref() != Some("0"))
+    }
+
+    pub async fn set_notification_enabled(
+        &self,
+        event: crate::notifications::NotificationEvent,
+        enabled: bool,
+    ) -> Result<()> {
+        self.set_config(event.config_key(), if enabled { "1" } else { "0" })
+            .await
+    }
+
+    pub async fn get_dead_link_check_last_run(&self) -> Result<Option<u64>> {
+        Ok(self
+            .get_config("dead_link_check_last_run")
+            .await?
+            .and_then(|v| v.parse().ok()))
+    }
+
+    pub async fn set_dead_link_check_last_run(&self) -> Result<()> {
+        self.set_config("dead_link_check_last_run", &chrono_utc_now())
+            .await
+    }
+
+    /// Size of the database file in bytes, free pages included.
+    pub async fn get_database_file_size(&self) -> Result<u64> {
+        self.execute_with_priority(OperationPriority::BackgroundIngest, |conn| {
+            let page_size: i64 = conn.query_row("PRAGMA page_size", [], |row| row.get(0))?;
+            let page_count: i64 = conn.query_row("PRAGMA page_count", [], |row| row.get(0))?;
+            Ok((page_size * page_count) as u64)
+        })
+        .await
+    }
+
+    /// Refresh the query planner statistics.
+    pub async fn optimize(&self) -> Result<()> {
+        self.execute_with_priority(OperationPriority::BackgroundIngest, |conn| {
+            conn.execute_batch("PRAGMA optimize;")?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Merge the segments of both full-text indexes.
+    pub async fn optimize_fts(&self) -> Result<()> {
+        self.execute_with_priority(OperationPriority::BackgroundIngest, |conn| {
+            conn.execute(
+                "INSERT INTO documents_fts(documents_fts) VALUES('optimize')",
+                [],
+            )?;
+            conn.execute("INSERT INTO titles_fts(titles_fts) VALUES('optimize')", [])?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Rebuild the database file without its free pages, then truncate the
+    /// write-ahead log so the space is returned to the file system.
+    pub async fn vacuum(&self) -> Result<()> {
+        self.execute_with_priority(OperationPriority::BackgroundIngest, |conn| {
+            conn.execute_batch("VACUUM;")?;
+            conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Write a consistent copy of the database to `path`, which must not
+    /// exist yet. Safe to run while the database is in use.
+    pub async fn backup_to(&self, path: &std::path::Path) -> Result<()> {
+        let path = path.to_string_lossy().into_owned();
+        self.execute_with_priority(OperationPriority::BackgroundIngest, move |conn| {
+            conn.execute("VACUUM INTO ?1", [&path])?;
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn get_maintenance_interval_days(&self) -> Result<u64> {
+        Ok(self
+            .get_config("maintenance_interval_days")
+            .await?
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(crate::maintenance::DEFAULT_INTERVAL_DAYS))
+    }
+
+    pub async fn set_maintenance_interval_days(&self, days: u64) -> Result<()> {
+        self.set_config("maintenance_interval_days", &days.to_string())
+            .await
+    }
+
+    pub async fn get_maintenance_last_run(&self) -> Result<Option<u64>> {
+        Ok(self
+            .get_config("maintenance_last_run")
+            .await?
+            .and_then(|v| v.parse().ok()))
+    }
+
+    pub async fn set_maintenance_last_run(&self) -> Result<()> {
+        self.set_config("maintenance_last_run", &chrono_utc_now())
+            .await
+    }
+
+    /// Row counts compared by the integrity audit: documents, full-text
+    /// index rows, and chunk rows whose document no longer exists.
+    pub async fn get_integrity_counts(&self) -> Result<(usize, usize, usize)> {
+        self.execute_with_priority(OperationPriority::BackgroundIngest, |conn| {
+            let documents: i64 =
+                conn.query_row("SELECT COUNT(*) FROM documents", [], |row| row.get(0))?;
+            let fts_rows: i64 =
+                conn.query_row("SELECT COUNT(*) FROM documents_fts", [], |row| row.get(0))?;
+            let orphan_chunks: i64 = conn.query_row(
+                "SELECT COUNT(*) FROM embeddings
+                 WHERE document_id NOT IN (SELECT id FROM documents)",
+                [],
+                |row| row.get(0),
+            )?;
+            Ok((
+                documents as usize,
+                fts_rows as usize,
+                orphan_chunks as usize,
+            ))
+        })
+        .await
+    }
+
+    /// Every chunk row of the searched model whose document exists, as
+    /// (chunk ID, document ID, dimension), with `None` for embedding blobs
+    /// that fail to deserialize. Orphaned rows are counted by
+    /// `get_integrity_counts` instead.
+    pub async fn get_chunk_dimensions(&self) -> Result<Vec<(i64, i64, Option<usize>)>> {
+        self.execute_with_priority(OperationPriority::BackgroundIngest, |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT e.id, e.document_id, e.embedding FROM embeddings e
+                 JOIN documents d ON d.id = e.document_id
+                 WHERE e.model_id IS NULL ORDER BY e.id",
+            )?;
+            let rows = stmt
+                .query_map([], |row| {
+                    let bytes: Vec<u8> = row.get(2)?;
+                    let dimension = bincode::deserialize::<Vec<f32>>(&bytes)
+                        .ok()
+                        .map(|v| v.len());
+                    Ok((row.get(0)?, row.get(1)?, dimension))
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok(rows)
+        })
+        .await
+    }
+
+    /// Delete chunk rows by ID, returning how many were removed.
+    pub async fn delete_chunks(&self, chunk_ids: &[i64]) -> Result<usize> {
+        let chunk_ids = chunk_ids.to_vec();
+        self.execute_with_priority(OperationPriority::BackgroundIngest, move |conn| {
+            let tx = conn.unchecked_transaction()?;
+            let mut deleted = 0;
+            for id in &chunk_ids {
+                deleted += tx.execute("DELETE FROM embeddings WHERE id = ?1", params![id])?;
+            }
+            tx.commit()?;
+            Ok(deleted)
+        })
+        .await
+    }
+
+    /// Delete chunk rows whose document no longer exists.
+    pub async fn delete_orphan_chunks(&self) -> Result<usize> {
+        self.execute_with_priority(OperationPriority::BackgroundIngest, |conn| {
+            let deleted = conn.execute(
+                "DELETE FROM embeddings WHERE document_id NOT IN (SELECT id FROM documents)",
+                [],
+            )?;
+            Ok(deleted)
+        })
+        .await
+    }
+
+    /// Rebuild both full-text indexes from the documents table.
+    pub async fn rebuild_fts(&self) -> Result<()> {
+        self.execute_with_priority(OperationPriority::BackgroundIngest, |conn| {
+            let tx = conn.unchecked_transaction()?;
+            tx.execute("DELETE FROM documents_fts", [])?;
+            tx.execute(
+                "INSERT INTO documents_fts(rowid, title, content)
+                 SELECT id, title, content FROM documents",
+                [],
+            )?;
+            tx.execute("INSERT INTO titles_fts(titles_fts) VALUES('rebuild')", [])?;
+            tx.commit()?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// URLs and sources of documents not in the trash that have a URL.
+    pub async fn get_document_urls(&self) -> Result<Vec<(String, String)>> {
+        self.execute_with_priority(OperationPriority::BackgroundIngest, |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT DISTINCT url, source FROM documents
+                 WHERE url IS NOT NULL AND deleted_at IS NULL",
+            )?;
+            let rows = stmt
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<std::result::Result<Vec<(String, String)>, _>>()?;
+            Ok(rows)
+        })
+        .await
+    }
+
+    /// Hosts with a cached icon, or with a failed fetch less than
+    /// `retry_after_secs` ago.
+    pub async fn get_cached_favicon_hosts(
+        &self,
+        retry_after_secs: i64,
+    ) -> Result<std::collections::HashSet<String>> {
+        let now: i64 = chrono_utc_now().parse().unwrap_or_default();
+        self.execute_with_priority(OperationPriority::BackgroundIngest, |conn| {
+            let mut stmt = conn
+                .prepare("SELECT host FROM favicons WHERE length(icon) > 0 OR fetched_at >= ?1")?;
+            let hosts = stmt
+                .query_map(params![now - retry_after_secs], |row| row.get(0))?
+                .collect::<std::result::Result<_, _>>()?;
+            Ok(hosts)
+        })
+        .await
+    }
+
+    /// Cache a host's icon; an empty icon records that it has none.
+    pub async fn set_favicon(&self, host: &str, icon: &[u8]) -> Result<()> {
+        let now: i64 = chrono_utc_now().parse().unwrap_or_default();
+        self.execute_with_priority(OperationPriority::BackgroundIngest, |conn| {
+            conn.execute(
+                "INSERT INTO favicons (host, icon, fetched_at) VALUES (?1, ?2, ?3)
+                 ON CONFLICT(host) DO UPDATE SET icon = excluded.icon,
+                                                 fetched_at = excluded.fetched_at",
+                params![host, icon, now],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Cached icons of the given hosts; hosts without one are left out.
+    pub async fn get_favicons(
+        &self,
+        hosts: &[String],
+    ) -> Result<std::collections::HashMap<String, Vec<u8>>> {
+        self.execute_with_priority(OperationPriority::UserSearch, |conn| {
+            let mut stmt =
+                conn.prepare("SELECT icon FROM favicons WHERE host = ?1 AND length(icon) > 0")?;
+            let mut icons = std::collections::HashMap::new();
+            for host in hosts {
+                match stmt.query_row(params![host], |row| row.get::<_, Vec<u8>>(0)) {
+                    Ok(icon) => {
+                        icons.insert(host.clone(), icon);
+                    }
+                    Err(rusqlite::Error::QueryReturnedNoRows) => {}
+                    Err(e) => return Err(Box::new(e)),
+                }
+            }
+            Ok(icons)
+        })
+        .await
+    }
+
+    /// Record a document's preview image. A changed image is fetched again.
+    pub async fn set_document_image_url(&self, doc_id: i64, image_url: &str) -> Result<()> {
+        self.execute_with_priority(OperationPriority::BackgroundIngest, |conn| {
+            conn.execute(
+                "INSERT INTO document_images (document_id, image_url) VALUES (?1, ?2)
+                 ON CONFLICT(document_id) DO UPDATE SET image_url = excluded.image_url,
+                                                        thumbnail = NULL
+                 WHERE image_url != excluded.image_url",
+                params![doc_id, image_url],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Documents (not in the trash) whose preview image has not been
+    /// fetched yet, as (document id, image URL), at most `limit`.
+    pub async fn get_pending_thumbnails(&self, limit: usize) -> Result<Vec<(i64, String)>> {
+        self.execute_with_priority(OperationPriority::BackgroundIngest, |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT i.document_id, i.image_url FROM document_images i
+                 JOIN documents d ON d.id = i.document_id
+                 WHERE i.thumbnail IS NULL AND d.deleted_at IS NULL
+                 ORDER BY i.document_id DESC LIMIT ?1",
+            )?;
+            let pending = stmt
+                .query_map(params![limit as i64], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<std::result::Result<Vec<(i64, String)>, _>>()?;
+            Ok(pending)
+        })
+        .await
+    }
+
+    /// Store a document's thumbnail; an empty one records a failed fetch.
+    pub async fn set_thumbnail(&self, doc_id: i64, thumbnail: &[u8]) -> Result<()> {
+        self.execute_with_priority(OperationPriority::BackgroundIngest, |conn| {
+            conn.execute(
+                "UPDATE document_images SET thumbnail = ?2 WHERE document_id = ?1",
+                params![doc_id, thumbnail],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Thumbnails of the given documents; ones without a thumbnail are left out.
+    pub async fn get_thumbnails(
+        &self,
+        doc_ids: &[i64],
+    ) -> Result<std::collections::HashMap<i64, Vec<u8>>> {
+        self.execute_with_priority(OperationPriority::UserSearch, |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT thumbnail FROM document_images
+                 WHERE document_id = ?1 AND length(thumbnail) > 0",
+            )?;
+            let mut thumbnails = std::collections::HashMap::new();
+            for &doc_id in doc_ids {
+                match stmt.query_row(params![doc_id], |row| row.get::<_, Vec<u8>>(0)) {
+                    Ok(thumbnail) => {
+                        thumbnails.insert(doc_id, thumbnail);
+                    }
+                    Err(rusqlite::Error::QueryReturnedNoRows) => {}
+                    Err(e) => return Err(Box::new(e)),
+                }
+            }
+            Ok(thumbnails)
+        })
+        .await
+    }
+
+    /// LAN remote access settings configured in Settings.
+    pub async fn get_remote_access_settings(
+        &self,
+    ) -> Result<crate::remote_access::RemoteAccessSettings> {
+        match self.get_config("remote_access").await? {
+            Some(json_str) => Ok(serde_json::from_str(&json_str)
+                .map_err(|e| format!("Failed to parse remote access settings: {}", e))?),
+            None => Ok(crate::remote_access::RemoteAccessSettings::default()),
+        }
+    }
+
+    pub async fn set_remote_access_settings(
+        &self,
+        settings: &crate::remote_access::RemoteAccessSettings,
+    ) -> Result<()> {
+        let json_str = serde_json::to_string(settings)
+            .map_err(|e| format!("Failed to serialize remote access settings: {}", e))?;
+        self.set_config("remote_access", &json_str).await
+    }
+
+    /// HTTP API port and bind address configured in Settings.
+    pub async fn get_http_server_settings(
+        &self,
+    ) -> Result<crate::http_settings::HttpServerSettings> {
+        match self.get_config("http_server").await? {
+            Some(json_str) => Ok(serde_json::from_str(&json_str)
+                .map_err(|e| format!("Failed to parse HTTP server settings: {}", e))?),
+            None => Ok(crate::http_settings::HttpServerSettings::default()),
+        }
+    }
+
+    pub async fn set_http_server_settings(
+        &self,
+        settings: &crate::http_settings::HttpServerSettings,
+    ) -> Result<()> {
+        let json_str = serde_json::to_string(settings)
+            .map_err(|e| format!("Failed to serialize HTTP server settings: {}", e))?;
+        self.set_config("http_server", &json_str).await
+    }
+
+    /// Sync settings configured in Settings.
+    pub async fn get_sync_settings(&self) -> Result<crate::sync::SyncSettings> {
+        match self.get_config("sync_settings").await? {
+            Some(json_str) => Ok(serde_json::from_str(&json_str)
+                .map_err(|e| format!("Failed to parse sync settings: {}", e))?),
+            None => Ok(crate::sync::SyncSettings::default()),
+        }
+    }
+
+    /// Save the sync settings. Files already merged are read again on the
+    /// next run, since the folder or passphrase may have changed.
+    pub async fn set_sync_settings(&self, settings: &crate::sync::SyncSettings) -> Result<()> {
+        let json_str = serde_json::to_string(settings)
+            .map_err(|e| format!("Failed to serialize sync settings: {}", e))?;
+        self.set_config("sync_settings", &json_str).await?;
+        self.set_config("sync_seen", "{}").await?;
+        self.set_config("sync_last_export", "").await
+    }
+
+    /// This machine's ID in the sync folder, created on first use.
+    pub async fn get_sync_device_id(&self) -> Result<String> {
+        if let Some(id) = self.get_config("sync_device_id").await? {
+            return Ok(id);
+        }
+        let id = uuid::Uuid::new_v4().to_string();
+        self.set_config("sync_device_id", &id).await?;
+        Ok(id)
+    }
+
+    /// Modification times of the sync files already merged, by file name.
+    pub async fn get_sync_seen(&self) -> Result<std::collections::HashMap<String, u64>> {
+        match self.get_config("sync_seen").await? {
+            Some(json_str) => Ok(serde_json::from_str(&json_str)
+                .map_err(|e| format!("Failed to parse sync state: {}", e))?),
+            None => Ok(std::collections::HashMap::new()),
+        }
+    }
+
+    pub async fn set_sync_seen(&self, seen: &std::collections::HashMap<String, u64>) -> Result<()> {
+        let json_str = serde_json::to_string(seen)
+            .map_err(|e| format!("Failed to serialize sync state: {}", e))?;
+        self.set_config("sync_seen", &json_str).await
+    }
+
+    /// Fingerprint of the index when this machine's sync file was last written.
+    pub async fn get_sync_last_export(&self) -> Result<Option<String>> {
+        Ok(self
+            .get_config("sync_last_export")
+            .await?
+            .filter(|v| !v.is_empty()))
+    }
+
+    pub async fn set_sync_last_export(&self, fingerprint: &str) -> Result<()> {
+        self.set_config("sync_last_export", fingerprint).await
+    }
+
+    pub async fn get_sync_last_run(&self) -> Result<Option<u64>> {
+        Ok(self
+            .get_config("sync_last_run")
+            .await?
+            .and_then(|v| v.parse().ok()))
+    }
+
+    pub async fn set_sync_last_run(&self) -> Result<()> {
+        self.set_config("sync_last_run", &chrono_utc_now()).await
+    }
+
+    /// Scheduled snapshot settings configured in Settings.
+    pub async fn get_snapshot_settings(&self) -> Result<crate::snapshot::SnapshotSettings> {
+        match self.get_config("snapshot_settings").await? {
+            Some(json_str) => Ok(serde_json::from_str(&json_str)
+                .map_err(|e| format!("Failed to parse snapshot settings: {}", e))?),
+            None => Ok(crate::snapshot::SnapshotSettings::default()),
+        }
+    }
+
+    pub async fn set_snapshot_settings(
+        &self,
+        settings: &crate::snapshot::SnapshotSettings,
+    ) -> Result<()> {
+        let json_str = serde_json::to_string(settings)
+            .map_err(|e| format!("Failed to serialize snapshot settings: {}", e))?;
+        self.set_config("snapshot_settings", &json_str).await
+    }
+
+    pub async fn get_snapshot_last_run(&self) -> Result<Option<u64>> {
+        Ok(self
+            .get_config("snapshot_last_run")
+            .await?
+            .and_then(|v| v.parse().ok()))
+    }
+
+    pub async fn set_snapshot_last_run(&self) -> Result<()> {
+        self.set_config("snapshot_last_run", &chrono_utc_now())
+            .await
+    }
+
+    /// A value that changes whenever a document, its tags or its chunks
+    /// change, so an unchanged index is not written out again.
+    pub async fn get_sync_fingerprint(&self) -> Result<String> {
+        self.execute_with_priority(OperationPriority::BackgroundIngest, |conn| {
+            let fingerprint = conn.query_row(
+                "SELECT COUNT(*),
+                        MAX(CAST(COALESCE(modified_at, strftime('%s', created_at)) AS INTEGER)),
+                        MAX(indexed_at),
+                        (SELECT COUNT(*) FROM embeddings WHERE model_id IS NULL)
+                 FROM documents",
+                [],
+                |row| {
+                    let count: i64 = row.get(0)?;
+                    let modified: Option<i64> = row.get(1)?;
+                    let indexed: Option<String> = row.get(2)?;
+                    let chunks: i64 = row.get(3)?;
+                    Ok(format!(
+                        "{}:{}:{}:{}",
+                        count,
+                        modified.unwrap_or(0),
+                        indexed.unwrap_or_default(),
+                        chunks
+                    ))
+                },
+            )?;
+            Ok(fingerprint)
+        })
+        .await
+    }
+
+    /// Every document, including those in the trash, with its tags and
+    /// chunk embeddings, as shared through the sync folder.
+    pub async fn get_sync_documents(&self) -> Result<Vec<(i64, crate::sync::SyncDocument)>> {
+        use crate::sync::{SyncChunk, SyncDocument};
+        use std::collections::HashMap;
+        self.execute_with_priority(OperationPriority::BackgroundIngest, |conn| {
+            let mut tags: HashMap<i64, Vec<String>> = HashMap::new();
+            {
+                let mut stmt =
+                    conn.prepare("SELECT document_id, tag FROM document_tags ORDER BY tag")?;
+                let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+                for row in rows {
+                    let (doc_id, tag): (i64, String) = row?;
+                    tags.entry(doc_id).or_default().push(tag);
+                }
+            }
+
+            let mut chunks: HashMap<i64, Vec<SyncChunk>> = HashMap::new();
+            {
+                let mut stmt = conn.prepare(
+                    "SELECT document_id, chunk_start, chunk_end, embedding, content_hash,
+                            section_heading, page_number, timestamp_seconds, section_anchor
+                     FROM embeddings WHERE model_id IS NULL
+                     ORDER BY document_id, chunk_start",
+                )?;
+                let rows = stmt.query_map([], |row| {
+                    let start: i64 = row.get(1)?;
+                    let end: i64 = row.get(2)?;
+                    Ok((
+                        row.get::<_, i64>(0)?,
+                        SyncChunk {
+                            start: start as usize,
+                            end: end as usize,
+                            embedding: row.get(3)?,
+                            content_hash: row.get(4)?,
+                            section_heading: row.get(5)?,
+                            page_number: row.get(6)?,
+                            timestamp_seconds: row.get(7)?,
+                            section_anchor: row.get(8)?,
+                        },
+                    ))
+                })?;
+                for row in rows {
+                    let (doc_id, chunk) = row?;
+                    chunks.entry(doc_id).or_default().push(chunk);
+                }
+            }
+
+            let mut stmt = conn.prepare(
+                "SELECT id, title, content, url, source,
+                        CAST(COALESCE(modified_at, strftime('%s', created_at)) AS INTEGER),
+                        deleted_at IS NOT NULL
+                 FROM documents ORDER BY id",
+            )?;
+            let documents = stmt
+                .query_map([], |row| {
+                    let id: i64 = row.get(0)?;
+                    let modified_at: Option<i64> = row.get(5)?;
+                    Ok((
+                        id,
+                        SyncDocument {
+                            title: row.get(1)?,
+                            content: row.get(2)?,
+                            url: row.get(3)?,
+                            source: row.get(4)?,
+                            tags: tags.remove(&id).unwrap_or_default(),
+                            modified_at: modified_at.unwrap_or(0).max(0) as u64,
+                            deleted: row.get(6)?,
+                            chunks: chunks.remove(&id).unwrap_or_default(),
+                        },
+                    ))
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok(documents)
+        })
+        .await
+    }
+
+    /// Write a document merged from another machine, as a new document or
+    /// over the local copy `doc_id` (taking it out of the trash). Its chunk
+    /// embeddings are copied when `with_chunks` is set, otherwise it is left
+    /// for `get_incomplete_documents` to re-embed. Keeps the other machine's
+    /// modification time so the two copies compare equal. Returns the ID.
+    pub async fn apply_sync_document(
+        &self,
+        doc_id: Option<i64>,
+        doc: &crate::sync::SyncDocument,
+        with_chunks: bool,
+    ) -> Result<i64> {
+        let doc = doc.clone();
+        let now = chrono_utc_now();
+        self.execute_with_priority(OperationPriority::BackgroundIngest, move |conn| {
+            let tx = conn.unchecked_transaction()?;
+            let doc_id = match doc_id {
+                Some(id) => {
+                    tx.execute(
+                        "UPDATE documents SET title = ?1, content = ?2, url = ?3, deleted_at = NULL
+                         WHERE id = ?4",
+                        params![doc.title, doc.content, doc.url, id],
+                    )?;
+                    tx.execute(
+                        "UPDATE documents_fts SET title = ?1, content = ?2 WHERE rowid = ?3",
+                        params![doc.title, doc.content, id],
+                    )?;
+                    id
+                }
+                None => {
+                    tx.execute(
+                        "INSERT INTO documents (title, content, url, source) VALUES (?1, ?2, ?3, ?4)",
+                        params![doc.title, doc.content, doc.url, doc.source],
+                    )?;
+                    tx.last_insert_rowid()
+                }
+            };
+
+            if with_chunks && !doc.chunks.is_empty() {
+                let rows: Vec<ChunkRow> = doc
+                    .chunks
+                    .iter()
+                    .map(|c| (c.start, c.end, c.embedding.clone(), c.provenance()))
+                    .collect();
+                write_chunk_embeddings(&tx, doc_id, &rows)?;
+            } else {
+                tx.execute(
+                    "DELETE FROM embeddings WHERE document_id = ?1",
+                    params![doc_id],
+                )?;
+                tx.execute(
+                    "UPDATE documents SET chunk_count = NULL WHERE id = ?1",
+                    params![doc_id],
+                )?;
+            }
+
+            tx.execute(
+                "DELETE FROM document_tags WHERE document_id = ?1",
+                params![doc_id],
+            )?;
+            {
+                let mut stmt = tx.prepare(
+                    "INSERT OR IGNORE INTO document_tags (document_id, tag, created_at)
+                     VALUES (?1, ?2, ?3)",
+                )?;
+                for tag in &doc.tags {
+                    stmt.execute(params![doc_id, tag, now])?;
+                }
+            }
+
+            tx.execute(
+                "UPDATE documents SET modified_at = ?1 WHERE id = ?2",
+                params![doc.modified_at.to_string(), doc_id],
+            )?;
+            tx.commit()?;
+            Ok(doc_id)
+        })
+        .await
+    }
+
+    /// Overwrite a document's modification time (unix seconds).
+    pub async fn set_document_modified_at(&self, doc_id: i64, modified_at: u64) -> Result<()> {
+        self.execute_with_priority(OperationPriority::BackgroundIngest, move |conn| {
+            conn.execute(
+                "UPDATE documents SET modified_at = ?1 WHERE id = ?2",
+                params![modified_at.to_string(), doc_id],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Retention rules configured in Settings.
+    pub async fn get_retention_rules(&self) -> Result<Vec<crate::retention::RetentionRule>> {
+        match self.get_config("retention_rules").await? {
+            Some(json_str) => Ok(serde_json::from_str(&json_str)
+                .map_err(|e| format!("Failed to parse retention rules: {}", e))?),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    pub async fn set_retention_rules(
+        &self,
+        rules: &[crate::retention::RetentionRule],
+    ) -> Result<()> {
+        let json_str = serde_json::to_string(rules)
+            .map_err(|e| format!("Failed to serialize retention rules: {}", e))?;
+        self.set_config("retention_rules", &json_str).await
+    }
+
+    pub async fn get_retention_last_run(&self) -> Result<Option<u64>> {
+        Ok(self
+            .get_config("retention_last_run")
+            .await?
+            .and_then(|v| v.parse().ok()))
+    }
+
+    pub async fn set_retention_last_run(&self) -> Result<()> {
+        self.set_config("retention_last_run", &chrono_utc_now())
+            .await
+    }
+
+    /// Documents outside the trash whose date for `condition` is before
+    /// `cutoff` (unix seconds), oldest first. Favorites are never returned.
+    /// Dead links found dead before `dead_since` was recorded count from
+    /// their last check, or from when they were saved.
+    pub async fn get_retention_candidates(
+        &self,
+        condition: crate::retention::RetentionCondition,
+        cutoff: i64,
+    ) -> Result<Vec<crate::retention::RetentionMatch>> {
+        use crate::retention::RetentionCondition;
+
+        let (filter, date) = match condition {
+            RetentionCondition::SavedBefore => ("1", "strftime('%s', created_at)"),
+            RetentionCondition::DeadFor => (
+                "COALESCE(is_dead, 0) = 1",
+                "COALESCE(dead_since, link_checked_at, strftime('%s', created_at))",
+            ),
+            RetentionCondition::NotOpenedFor => {
+                ("1", "COALESCE(last_viewed_at, strftime('%s', created_at))")
+            }
+        };
+        let sql = format!(
+            "SELECT id, title, url, source FROM documents
+             WHERE deleted_at IS NULL AND COALESCE(is_favorite, 0) = 0
+               AND {filter} AND CAST({date} AS INTEGER) < ?1
+             ORDER BY CAST({date} AS INTEGER) ASC, id ASC"
+        );
+        self.execute_with_priority(OperationPriority::BackgroundIngest, move |conn| {
+            let mut stmt = conn.prepare(&sql)?;
+            let matches = stmt
+                .query_map(params![cutoff], |row| {
+                    Ok(crate::retention::RetentionMatch {
+                        id: row.get(0)?,
+                        title: row.get(1)?,
+                        url: row.get(2)?,
+                        source: row.get(3)?,
+                    })
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(matches)
+        })
+        .await
+    }
+
+    pub async fn get_all_documents(&self) -> Result<Vec<Document>> {
+        self.execute_with_priority(OperationPriority::BackgroundIngest, |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, title, content, url, source, created_at, embedding, is_dead, needs_auth, profile
+                 FROM documents
+                 WHERE is_dead IS NULL OR is_dead = 0
+                 ORDER BY id",
+            )?;
+
+            let docs = stmt.query_map([], |row| {
+                Ok(Document {
+                    id: row.get(0)?,
+                    title: row.get(1)?,
+                    content: row.get(2)?,
+                    url: row.get(3)?,
+                    source: row.get(4)?,
+                    created_at: row.get(5)?,
+                    embedding: row.get(6)?,
+                    is_dead: row.get(7)?,
+                    needs_auth: row.get(8)?,
+                    profile: row.get(9)?,
+                })
+            })?;
+
+            let mut results = Vec::new();
+            for doc in docs {
+                results.push(doc?);
+            }
+            Ok(results)
+        })
+        .await
+    }
+
+    /// Documents not in the trash, dead ones included, for reindexing.
+    pub async fn get_documents_not_in_trash(&self) -> Result<Vec<Document>> {
+        self.execute_with_priority(OperationPriority::BackgroundIngest, |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, title, content, url, source, created_at, embedding, is_dead, needs_auth, profile
+                 FROM documents
+                 WHERE deleted_at IS NULL
+                 ORDER BY id",
+            )?;
+
+            let docs = stmt.query_map([], |row| {
+                Ok(Document {
+                    id: row.get(0)?,
+                    title: row.get(1)?,
+                    content: row.get(2)?,
+                    url: row.get(3)?,
+                    source: row.get(4)?,
+                    created_at: row.get(5)?,
+                    embedding: row.get(6)?,
+                    is_dead: row.get(7)?,
+                    needs_auth: row.get(8)?,
+                    profile: row.get(9)?,
+                })
+            })?;
+
+            let mut results = Vec::new();
+            for doc in docs {
+                results.push(doc?);
+            }
+            Ok(results)
+        })
+        .await
+    }
+
+    /// Every document, dead and trashed ones included, with when it was
+    /// moved to the trash (Unix seconds). Snapshots and `localmind-cli
+    /// export` are written from it.
+    pub async fn get_documents_for_export(&self) -> Result<Vec<(Document, Option<i64>)>> {
+        self.execute_with_priority(OperationPriority::BackgroundIngest, |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, title, content, url, source, created_at, embedding, is_dead, needs_auth, profile,
+                        CAST(deleted_at AS INTEGER)
+                 FROM documents
+                 ORDER BY id",
+            )?;
+
+            let docs = stmt.query_map([], |row| {
+                Ok((
+                    Document {
+                        id: row.get(0)?,
+                        title: row.get(1)?,
+                        content: row.get(2)?,
+                        url: row.get(3)?,
+                        source: row.get(4)?,
+                        created_at: row.get(5)?,
+                        embedding: row.get(6)?,
+                        is_dead: row.get(7)?,
+                        needs_auth: row.get(8)?,
+                        profile: row.get(9)?,
+                    },
+                    row.get(10)?,
+                ))
+            })?;
+
+            let mut results = Vec::new();
+            for doc in docs {
+                results.push(doc?);
+            }
+            Ok(results)
+        })
+        .await
+    }
+
+    pub async fn update_chunk_embedding(
+        &self,
+        embedding_id: i64,
+        embedding_bytes: &[u8],
+        priority: OperationPriority,
+    ) -> Result<()> {
+        self.execute_with_priority(priority, move |conn| {
+            conn.execute(
+                "UPDATE embeddings SET embedding = ?1 WHERE id = ?2",
+                params![embedding_bytes, embedding_id],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn set_config(&self, key: &str, value: &str) -> Result<()> {
+        self.execute_with_priority(OperationPriority::UserSearch, |conn| {
+            conn.execute(
+                "INSERT OR REPLACE INTO config (key, value, updated_at) VALUES (?1, ?2, CURRENT_TIMESTAMP)",
+                params![key, value],
+            )?;
+            Ok(())
+        }).await
+    }
+
+    pub async fn get_config(&self, key: &str) -> Result<Option<String>> {
+        self.execute_with_priority(OperationPriority::UserSearch, |conn| {
+            let mut stmt = conn.prepare("SELECT value FROM config WHERE key = ?1")?;
+
+            match stmt.query_row(params![key], |row| row.get(0)) {
+                Ok(value) => Ok(Some(value)),
+                Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+                Err(e) => Err(Box::new(e) as Box<dyn std::error::Error + Send + Sync>),
+            }
+        })
+        .await
+    }
+
+    pub async fn get_embedding_model(&self) -> Result<Option<String>> {
+        self.get_config("embedding_model").await
+    }
+
+    pub async fn set_embedding_model(&self, model: &str) -> Result<()> {
+        self.set_config("embedding_model", model).await
+    }
+
+    pub async fn get_embedding_url(&self) -> Result<Option<String>> {
+        self.get_config("embedding_url").await
+    }
+
+    pub async fn set_embedding_url(&self, url: &str) -> Result<()> {
+        self.set_config("embedding_url", url).await
+    }
+
+    /// Whether query embeddings are kept on disk across restarts (default: on).
+    pub async fn get_persist_query_embeddings(&self) -> Result<bool> {
+        Ok(self
+            .get_config("persist_query_embeddings")
+            .await?
+            .as_deref()
+            != Some("0"))
+    }
+
+    pub async fn set_persist_query_embeddings(&self, enabled: bool) -> Result<()> {
+        self.set_config("persist_query_embeddings", if enabled { "1" } else { "0" })
+            .await
+    }
+
+    /// Code of the UI language chosen in Settings, e.g. "es".
+    pub async fn get_ui_language(&self) -> Result<Option<String>> {
+        self.get_config("ui_language").await
+    }
+
+    pub async fn set_ui_language(&self, code: &str) -> Result<()> {
+        self.set_config("ui_language", code).await
+    }
+
+    /// UI zoom factor chosen in Settings, if any.
+    pub async fn get_ui_scale(&self) -> Result<Option<f32>> {
+        Ok(self
+            .get_config("ui_scale")
+            .await?
+            .and_then(|value| value.parse().ok()))
+    }
+
+    pub async fn set_ui_scale(&self, scale: f32) -> Result<()> {
+        self.set_config("ui_scale", &scale.to_string()).await
+    }
+
+    /// Whether the high-contrast theme is on (default: off).
+    pub async fn get_high_contrast(&self) -> Result<bool> {
+        Ok(self.get_config("high_contrast").await?.as_deref() == Some("1"))
+    }
+
+    pub async fn set_high_contrast(&self, enabled: bool) -> Result<()> {
+        self.set_config("high_contrast", if enabled { "1" } else { "0" })
+            .await
+    }
+
+    /// Whether result and document lists show thumbnails.
+    pub async fn get_card_layout(&self) -> Result<bool> {
+        Ok(self.get_config("card_layout").await?.as_deref() == Some("1"))
+    }
+
+    pub async fn set_card_layout(&self, enabled: bool) -> Result<()> {
+        self.set_config("card_layout", if enabled { "1" } else { "0" })
+            .await
+    }
+
+    /// Ingestion policy for one kind of source (default when never set).
+    pub async fn get_ingest_policy(
+        &self,
+        kind: crate::ingest_policy::SourceKind,
+    ) -> Result<crate::ingest_policy::IngestPolicy> {
+        match self.get_config(kind.config_key()).await? {
+            Some(json_str) => Ok(serde_json::from_str(&json_str)
+                .map_err(|e| format!("Failed to parse ingestion policy: {}", e))?),
+            None => Ok(Default::default()),
+        }
+    }
+
+    pub async fn set_ingest_policy(
+        &self,
+        kind: crate::ingest_policy::SourceKind,
+        policy: &crate::ingest_policy::IngestPolicy,
+    ) -> Result<()> {
+        let json_str = serde_json::to_string(policy)
+            .map_err(|e| format!("Failed to serialize ingestion policy: {}", e))?;
+        self.set_config(kind.config_key(), &json_str).await
+    }
+
+    /// Ingestion policies of every kind of source, for Settings.
+    pub async fn get_ingest_policies(&self) -> Result<crate::ingest_policy::IngestPolicies> {
+        let mut policies = crate::ingest_policy::IngestPolicies::default();
+        for kind in crate::ingest_policy::SourceKind::ALL {
+            policies.set(kind, self.get_ingest_policy(kind).await?);
+        }
+        Ok(policies)
+    }
+
+    /// Maximum index size in bytes; 0 (the default) means unlimited.
+    pub async fn get_max_index_bytes(&self) -> Result<u64> {
+        Ok(self
+            .get_config("max_index_bytes")
+            .await?
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(0))
+    }
+
+    pub async fn set_max_index_bytes(&self, bytes: u64) -> Result<()> {
+        self.set_config("max_index_bytes", &bytes.to_string()).await
+    }
+
+    /// Space taken by the database, as (bytes in use not counting free
+    /// pages, bytes of document content, number of documents).
+    pub async fn get_storage_usage(&self) -> Result<(u64, u64, u64)> {
+        self.execute_with_priority(OperationPriority::UserSearch, |conn| {
+            let page_size: i64 = conn.query_row("PRAGMA page_size", [], |row| row.get(0))?;
+            let page_count: i64 = conn.query_row("PRAGMA page_count", [], |row| row.get(0))?;
+            let free_pages: i64 = conn.query_row("PRAGMA freelist_count", [], |row| row.get(0))?;
+            let (content_bytes, document_count): (i64, i64) = conn.query_row(
+                "SELECT COALESCE(SUM(LENGTH(CAST(content AS BLOB))), 0), COUNT(*) FROM documents",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )?;
+            Ok((
+                ((page_count - free_pages) * page_size) as u64,
+                content_bytes as u64,
+                document_count as u64,
+            ))
+        })
+        .await
+    }
+
+    /// Documents to prune first when the index is over its size limit:
+    /// dead links, then documents never opened, then the least recently
+    /// used. Favorites and trashed documents are left out. Sizes count the
+    /// content twice (text and full-text index) plus the chunk embeddings.
+    pub async fn get_prune_candidates(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<crate::storage_budget::PruneCandidate>> {
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT d.id, d.title, d.url, COALESCE(d.is_dead, 0), COALESCE(d.view_count, 0),
+                        COALESCE(d.last_viewed_at, d.created_at),
+                        2 * LENGTH(CAST(d.content AS BLOB))
+                            + COALESCE((SELECT SUM(LENGTH(e.embedding)) FROM embeddings e
+                                        WHERE e.document_id = d.id), 0)
+                 FROM documents d
+                 WHERE d.deleted_at IS NULL AND COALESCE(d.is_favorite, 0) = 0
+                 ORDER BY COALESCE(d.is_dead, 0) DESC,
+                          COALESCE(d.view_count, 0) = 0 DESC,
+                          COALESCE(d.last_viewed_at, d.created_at) ASC,
+                          d.id ASC
+                 LIMIT ?1",
+            )?;
+            let candidates = stmt
+                .query_map(params![limit as i64], |row| {
+                    Ok(crate::storage_budget::PruneCandidate {
+                        id: row.get(0)?,
+                        title: row.get(1)?,
+                        url: row.get(2)?,
+                        is_dead: row.get(3)?,
+                        view_count: row.get(4)?,
+                        last_used_at: row.get(5)?,
+                        bytes: row.get::<_, i64>(6)? as u64,
+                    })
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(candidates)
+        })
+        .await
+    }
+
+    /// Stored embedding of a search query for `backend`, counting the hit.
+    pub async fn get_query_embedding(
+        &self,
+        query: &str,
+        backend: &str,
+    ) -> Result<Option<Vec<f32>>> {
+        let now = chrono_utc_now();
+        self.execute_with_priority(OperationPriority::UserSearch, |conn| {
+            let bytes: Vec<u8> = match conn.query_row(
+                "SELECT embedding FROM query_embeddings WHERE query = ?1 AND backend = ?2",
+                params![query, backend],
+                |row| row.get(0),
+            ) {
+                Ok(bytes) => bytes,
+                Err(rusqlite::Error::QueryReturnedNoRows) => return Ok(None),
+                Err(e) => return Err(Box::new(e)),
+            };
+            conn.execute(
+                "UPDATE query_embeddings SET hits = hits + 1, last_used_at = ?3
+                 WHERE query = ?1 AND backend = ?2",
+                params![query, backend, now],
+            )?;
+            Ok(Some(bincode::deserialize(&bytes)?))
+        })
+        .await
+    }
+
+    /// Store a search query's embedding, keeping only the `keep` most
+    /// searched queries.
+    pub async fn save_query_embedding(
+        &self,
+        query: &str,
+        backend: &str,
+        embedding: &[f32],
+        keep: usize,
+    ) -> Result<()> {
+        let bytes = bincode::serialize(embedding)?;
+        let now = chrono_utc_now();
+        self.execute_with_priority(OperationPriority::UserSearch, |conn| {
+            conn.execute(
+                "INSERT INTO query_embeddings (query, backend, embedding, last_used_at)
+                 VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT(query, backend) DO UPDATE SET
+                     embedding = excluded.embedding,
+                     hits = hits + 1,
+                     last_used_at = excluded.last_used_at",
+                params![query, backend, bytes, now],
+            )?;
+            conn.execute(
+                "DELETE FROM query_embeddings WHERE rowid NOT IN (
+                     SELECT rowid FROM query_embeddings
+                     ORDER BY hits DESC, last_used_at DESC, rowid DESC
+                     LIMIT ?1)",
+                params![keep as i64],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Forget all stored query embeddings.
+    pub async fn clear_query_embeddings(&self) -> Result<()> {
+        self.execute_with_priority(OperationPriority::UserSearch, |conn| {
+            conn.execute("DELETE FROM query_embeddings", [])?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Custom command that launches the local embedding server, if set.
+    pub async fn get_embedding_server_command(&self) -> Result<Option<String>> {
+        Ok(self
+            .get_config("embedding_server_command")
+            .await?
+            .filter(|command| !command.trim().is_empty()))
+    }
+
+    /// Set the launch command; empty restores the bundled server.
+    pub async fn set_embedding_server_command(&self, command: &str) -> Result<()> {
+        self.set_config("embedding_server_command", command.trim())
+            .await
+    }
+
+    /// Embedding backend chosen in Settings (default: the local server).
+    pub async fn get_embedding_settings(
+        &self,
+    ) -> Result<crate::embedding_provider::EmbeddingSettings> {
+        use crate::embedding_provider::{EmbeddingProvider, EmbeddingSettings};
+
+        let provider = match self.get_config("embedding_provider").await? {
+            Some(value) => EmbeddingProvider::from_config_str(&value),
+            None => EmbeddingProvider::LocalServer,
+        };
+        Ok(EmbeddingSettings {
+            provider,
+            url: self.get_embedding_url().await?.unwrap_or_default(),
+            model: self.get_embedding_model().await?.unwrap_or_default(),
+        })
+    }
+
+    pub async fn set_embedding_settings(
+        &self,
+        settings: &crate::embedding_provider::EmbeddingSettings,
+    ) -> Result<()> {
+        self.set_config("embedding_provider", settings.provider.as_config_str())
+            .await?;
+        self.set_embedding_url(settings.url.trim()).await?;
+        self.set_embedding_model(settings.model.trim()).await
+    }
+
+    /// Embedding settings being migrated to, if a switch of model is under way.
+    pub async fn get_embedding_migration(
+        &self,
+    ) -> Result<Option<crate::embedding_provider::EmbeddingSettings>> {
+        match self.get_config("embedding_migration").await? {
+            Some(json) => {
+                Ok(Some(serde_json::from_str(&json).map_err(|e| {
+                    format!("Failed to parse embedding migration: {}", e)
+                })?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    pub async fn set_embedding_migration(
+        &self,
+        settings: Option<&crate::embedding_provider::EmbeddingSettings>,
+    ) -> Result<()> {
+        match settings {
+            Some(settings) => {
+                let json = serde_json::to_string(settings)
+                    .map_err(|e| format!("Failed to serialize embedding migration: {}", e))?;
+                self.set_config("embedding_migration", &json).await
+            }
+            None => {
+                self.execute_with_priority(OperationPriority::UserSearch, |conn| {
+                    conn.execute("DELETE FROM config WHERE key = 'embedding_migration'", [])?;
+                    Ok(())
+                })
+                .await
+            }
+        }
+    }
+
+    pub async fn get_excluded_folders(&self) -> Result<Vec<String>> {
+        match self.get_config("bookmark_exclude_folders").await? {
+            Some(json_str) => {
+                let folders: Vec<String> = serde_json::from_str(&json_str)
+                    .map_err(|e| format!("Failed to parse excluded folders: {}", e))?;
+                Ok(folders)
+            }
+            None => Ok(Vec::new()),
+        }
+    }
+
+    pub async fn set_excluded_folders(&self, folders: &[String]) -> Result<()> {
+        let json_str = serde_json::to_string(folders)
+            .map_err(|e| format!("Failed to serialize excluded folders: {}", e))?;
+        self.set_config("bookmark_exclude_folders", &json_str).await
+    }
+
+    pub async fn get_excluded_domains(&self) -> Result<Vec<String>> {
+        match self.get_config("bookmark_exclude_domains").await? {
+            Some(json_str) => {
+                let domains: Vec<String> = serde_json::from_str(&json_str)
+                    .map_err(|e| format!("Failed to parse excluded domains: {}", e))?;
+                Ok(domains)
+            }
+            None => Ok(Vec::new()),
+        }
+    }
+
+    pub async fn set_excluded_domains(&self, domains: &[String]) -> Result<()> {
+        let json_str = serde_json::to_string(domains)
+            .map_err(|e| format!("Failed to serialize excluded domains: {}", e))?;
+        self.set_config("bookmark_exclude_domains", &json_str).await
+    }
+
+    /// Move documents whose URL matches an exclusion pattern to the trash,
+    /// returning their IDs.
+    pub async fn delete_bookmarks_by_url_pattern(&self, pattern: &str) -> Result<Vec<i64>> {
+        use crate::bookmark_exclusion::ExclusionRules;
+
+        let rules = ExclusionRules::new(vec![], vec![pattern.to_string()]);
+        let doc_ids: Vec<i64> = self
+            .get_live_documents_with_urls()
+            .await?
+            .into_iter()
+            .filter(|doc| {
+                doc.url
+                    .as_deref()
+                    .is_some_and(|url| rules.is_url_excluded(url))
+            })
+            .map(|doc| doc.id)
+            .collect();
+
+        self.trash_documents(&doc_ids).await?;
+        Ok(doc_ids)
+    }
+
+    /// Move the bookmarks in a Chrome or Safari folder (and its subfolders)
+    /// to the trash, returning their IDs.
+    pub async fn delete_bookmarks_by_folder(&self, folder_id: &str) -> Result<Vec<i64>> {
+        use crate::bookmark::BookmarkMonitor;
+
+        // Get all bookmarks from the browsers shown in the exclusion settings
+        let mut all_bookmarks = Vec::new();
+        for monitor in BookmarkMonitor::for_settings()? {
+            all_bookmarks.extend(monitor.get_bookmark_roots()?);
+        }
+
+        // Find the specific folder and collect all URLs in it
+        let mut urls_to_delete = Vec::new();
+
+        fn find_and_collect_urls(
+            item: &crate::bookmark::BookmarkItem,
+            target_folder_id: &str,
+            urls: &mut Vec<String>,
+        ) -> bool {
+            // Check if this is the target folder
+            if item.id == target_folder_id {
+                println!("Found folder '{}' (ID: {})", item.name, item.id);
+                // Collect all URLs in this folder and subfolders
+                collect_all_urls(item, urls);
+                return true;
+            }
+
+            // Recursively search in children
+            if let Some(children) = &item.children {
+                for child in children {
+                    if find_and_collect_urls(child, target_folder_id, urls) {
+                        return true;
+                    }
+                }
+            }
+
+            false
+        }
+
+        fn collect_all_urls(item: &crate::bookmark::BookmarkItem, urls: &mut Vec<String>) {
+            if let Some(url) = &item.url {
+                if !url.is_empty() {
+                    urls.push(url.clone());
+                }
+            }
+            if let Some(children) = &item.children {
+                for child in children {
+                    collect_all_urls(child, urls);
+                }
+            }
+        }
+
+        // Search through all bookmark roots
+        for item in &all_bookmarks {
+            if find_and_collect_urls(item, folder_id, &mut urls_to_delete) {
+                break;
+            }
+        }
+
+        println!(
+            "Found {} bookmarked URLs for folder {}",
+            urls_to_delete.len(),
+            folder_id
+        );
+
+        // Look up which of those URLs are indexed and not already trashed
+        let doc_ids: Vec<i64> = self
+            .execute_with_priority(OperationPriority::BackgroundIngest, move |conn| {
+                let mut stmt = conn.prepare(
+                    "SELECT id FROM documents
+                     WHERE url = ?1 AND source = 'chrome_bookmark' AND deleted_at IS NULL",
+                )?;
+                let mut ids = Vec::new();
+                for url in &urls_to_delete {
+                    let rows = stmt.query_map(params![url], |row| row.get(0))?;
+                    for id in rows {
+                        ids.push(id?);
+                    }
+                }
+                Ok(ids)
+            })
+            .await?;
+
+        println!(
+            "Of those, {} exist in database for folder {}",
+            doc_ids.len(),
+            folder_id
+        );
+
+        self.trash_documents(&doc_ids).await?;
+        Ok(doc_ids)
+    }
+
+    // -----------------------------------------------------------------------
+    // Folder-watch CRUD (T009-T016, T046)
+    // -----------------------------------------------------------------------
+
+    /// Create an in-memory database suitable for unit tests.
+    ///
+    /// Same schema as the live database; schema is initialized synchronously
+    /// within a temporary tokio runtime so callers don't need async.
+    #[cfg(test)]
+    pub fn new_in_memory_sync() -> Self {
+        let conn = Connection::open_in_memory().expect("in-memory SQLite");
+        let db = Self {
+            conn: Arc::new(Mutex::new(conn)),
+            search_semaphore: Arc::new(Semaphore::new(10)),
+            ingest_semaphore: Arc::new(Semaphore::new(1)),
+            cached_settings: Default::default(),
+        };
+        // Run schema init synchronously via a temporary runtime
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        rt.block_on(db.init_schema()).expect("init_schema");
+        db
+    }
+
+    /// Insert a watched folder, returning its new row ID.
+    ///
+    /// Returns `Err` (rusqlite unique constraint) if the path already exists.
+    pub async fn add_watched_folder(&self, path: &std::path::Path) -> Result<i64> {
+        let path_str = path.to_string_lossy().to_string();
+        let created_at = chrono_utc_now();
+        self.execute_with_priority(OperationPriority::BackgroundIngest, move |conn| {
+            conn.execute(
+                "INSERT INTO watched_folders (path, status, created_at)
+                 VALUES (?1, 'active', ?2)",
+                params![path_str, created_at],
+            )?;
+            Ok(conn.last_insert_rowid())
+        })
+        .await
+    }
+
+    /// Return all watched folders ordered by creation time.
+    pub async fn get_watched_folders(&self) -> Result<Vec<crate::folder_watcher::WatchedFolder>> {
+        use crate::folder_watcher::{FolderStatus, WatchedFolder};
+        self.execute_with_priority(OperationPriority::UserSearch, |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, path, status, created_at FROM watched_folders ORDER BY created_at",
+            )?;
+            let rows = stmt.query_map([], |row| {
+                let id: i64 = row.get(0)?;
+                let path: String = row.get(1)?;
+                let status_str: String = row.get(2)?;
+                let created_at: String = row.get(3)?;
+                Ok(WatchedFolder {
+                    id,
+                    path: std::path::PathBuf::from(path),
+                    status: FolderStatus::from_db_str(&status_str),
+                    created_at,
+                })
+            })?;
+            let mut out = Vec::new();
+            for row in rows {
+                out.push(row?);
+            }
+            Ok(out)
+        })
+        .await
+    }
+
+    /// Insert or update a watched-file record.
+    ///
+    /// Uses `INSERT OR REPLACE` so callers don't need to distinguish between
+    /// first-seen and update-on-re-ingest cases.
+    pub async fn upsert_watched_file(
+        &self,
+        folder_id: i64,
+        file_path: &std::path::Path,
+        modified_at: i64,
+        document_id: Option<i64>,
+        status: &crate::folder_watcher::IngestStatus,
+    ) -> Result<i64> {
+        let path_str = file_path.to_string_lossy().to_string();
+        let status_str = status.as_db_str().to_string();
+        self.execute_with_priority(OperationPriority::BackgroundIngest, move |conn| {
+            conn.execute(
+                "INSERT INTO watched_files
+                     (folder_id, file_path, modified_at, document_id, ingest_status)
+                 VALUES (?1, ?2, ?3, ?4, ?5)
+                 ON CONFLICT(file_path) DO UPDATE SET
+                     modified_at    = excluded.modified_at,
+                     document_id    = excluded.document_id,
+                     ingest_status  = excluded.ingest_status",
+                params![folder_id, path_str, modified_at, document_id, status_str],
+            )?;
+            Ok(conn.last_insert_rowid())
+        })
+        .await
+    }
+
+    /// Look up a single watched-file record by its filesystem path.
+    pub async fn get_watched_file_by_path(
+        &self,
+        path: &std::path::Path,
+    ) -> Result<Option<crate::folder_watcher::WatchedFile>> {
+        use crate::folder_watcher::{IngestStatus, WatchedFile};
+        let path_str = path.to_string_lossy().to_string();
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, folder_id, file_path, modified_at, document_id, ingest_status
+                 FROM watched_files WHERE file_path = ?1",
+            )?;
+            match stmt.query_row(params![path_str], |row| {
+                let path_val: String = row.get(2)?;
+                let status_str: String = row.get(5)?;
+                Ok(WatchedFile {
+                    id: row.get(0)?,
+                    folder_id: row.get(1)?,
+                    file_path: std::path::PathBuf::from(path_val),
+                    modified_at: row.get(3)?,
+                    document_id: row.get(4)?,
+                    ingest_status: IngestStatus::from_db_str(&status_str),
+                })
+            }) {
+                Ok(wf) => Ok(Some(wf)),
+                Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+                Err(e) => Err(Box::new(e)),
+            }
+        })
+        .await
+    }
+
+    /// Return all watched-file records belonging to a folder.
+    pub async fn get_watched_files_for_folder(
+        &self,
+        folder_id: i64,
+    ) -> Result<Vec<crate::folder_watcher::WatchedFile>> {
+        use crate::folder_watcher::{IngestStatus, WatchedFile};
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, folder_id, file_path, modified_at, document_id, ingest_status
+                 FROM watched_files WHERE folder_id = ?1 ORDER BY file_path",
+            )?;
+            let rows = stmt.query_map(params![folder_id], |row| {
+                let path_val: String = row.get(2)?;
+                let status_str: String = row.get(5)?;
+                Ok(WatchedFile {
+                    id: row.get(0)?,
+                    folder_id: row.get(1)?,
+                    file_path: std::path::PathBuf::from(path_val),
+                    modified_at: row.get(3)?,
+                    document_id: row.get(4)?,
+                    ingest_status: IngestStatus::from_db_str(&status_str),
+                })
+            })?;
+            let mut out = Vec::new();
+            for row in rows {
+                out.push(row?);
+            }
+            Ok(out)
+        })
+        .await
+    }
+
+    /// Delete a watched folder row (CASCADE removes all its watched_files).
+    pub async fn delete_watched_folder(&self, id: i64) -> Result<()> {
+        self.execute_with_priority(OperationPriority::BackgroundIngest, move |conn| {
+            conn.execute("DELETE FROM watched_folders WHERE id = ?1", params![id])?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Delete a single document and all its embeddings and FTS entries.
+    ///
+    /// Foreign-key CASCADE on `embeddings` handles chunk rows automatically.
+    /// The FTS `rowid` matches the document `id`.
+    pub async fn delete_document(&self, document_id: i64) -> Result<()> {
+        self.execute_with_priority(OperationPriority::BackgroundIngest, move |conn| {
+            // FTS must be deleted manually (virtual table, no FK cascade)
+            conn.execute(
+                "DELETE FROM documents_fts WHERE rowid = ?1",
+                params![document_id],
+            )?;
+            // Embeddings are CASCADE-deleted by FK when document is deleted
+            conn.execute("DELETE FROM documents WHERE id = ?1", params![document_id])?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Update the status column of a watched folder.
+    pub async fn update_watched_folder_status(
+        &self,
+        id: i64,
+        status: &crate::folder_watcher::FolderStatus,
+    ) -> Result<()> {
+        let status_str = status.as_db_str().to_string();
+        self.execute_with_priority(OperationPriority::BackgroundIngest, move |conn| {
+            conn.execute(
+                "UPDATE watched_folders SET status = ?1 WHERE id = ?2",
+                params![status_str, id],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Delete all documents whose `source` column equals `folder_path`, returning
+    /// the deleted document IDs so the caller can evict them from the VectorStore.
+    ///
+    /// Call this BEFORE `delete_watched_folder` so the document IDs are still
+    /// available (watched_files rows are CASCADE-deleted with the folder).
+    pub async fn delete_documents_by_source(
+        &self,
+        folder_path: &std::path::Path,
+    ) -> Result<Vec<i64>> {
+        let source = folder_path.to_string_lossy().to_string();
+        self.execute_with_priority(OperationPriority::BackgroundIngest, move |conn| {
+            // Collect IDs first so we can return them for VectorStore eviction
+            let mut stmt = conn.prepare("SELECT id FROM documents WHERE source = ?1")?;
+            let ids: Vec<i64> = stmt
+                .query_map(params![source], |row| row.get(0))?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            for &doc_id in &ids {
+                conn.execute(
+                    "DELETE FROM documents_fts WHERE rowid = ?1",
+                    params![doc_id],
+                )?;
+                conn.execute("DELETE FROM documents WHERE id = ?1", params![doc_id])?;
+            }
+            Ok(ids)
+        })
+        .await
+    }
+
+    // -----------------------------------------------------------------------
+    // Ingest queue CRUD
+    // -----------------------------------------------------------------------
+
+    /// Add bookmarks to the ingest queue, returning how many rows were queued.
+    ///
+    /// URLs already in the queue are left alone, except `done` rows whose
+    /// document has since been deleted (e.g. by an exclusion rule that was
+    /// later removed) — those are reset to `pending`.
+    pub async fn enqueue_bookmarks(
+        &self,
+        bookmarks: &[(String, String)],
+        profile: Option<&str>,
+    ) -> Result<usize> {
+        let rows: Vec<(String, String, String)> = bookmarks
+            .iter()
+            .map(|(title, url)| (title.clone(), url.clone(), self.normalize_url(url)))
+            .collect();
+        let profile = profile.map(|p| p.to_string());
+        let now = chrono_utc_now();
+        self.execute_with_priority(OperationPriority::BackgroundIngest, move |conn| {
+            let tx = conn.unchecked_transaction()?;
+            let mut queued = 0;
+            {
+                let mut stmt = tx.prepare(
+                    "INSERT INTO ingest_queue (url, title, profile, status, attempts, updated_at)
+                     VALUES (?1, ?2, ?3, 'pending', 0, ?4)
+                     ON CONFLICT(url) DO UPDATE SET
+                         status     = 'pending',
+                         attempts   = 0,
+                         last_error = NULL,
+                         updated_at = excluded.updated_at
+                     WHERE ingest_queue.status = 'done'
+                       AND NOT EXISTS (SELECT 1 FROM documents WHERE url = ?5)",
+                )?;
+                for (title, url, normalized) in &rows {
+                    queued += stmt.execute(params![url, title, profile, now, normalized])?;
+                }
+            }
+            tx.commit()?;
+            Ok(queued)
+        })
+        .await
+    }
+
+    /// Return queue items still waiting to be processed for a profile.
+    ///
+    /// Includes `in_progress` rows (interrupted by a previous shutdown) and
+    /// `failed` rows that have not yet reached `max_attempts`. URLs the user
+    /// chose to ignore in the failed-imports report are skipped.
+    pub async fn get_pending_queue_items(
+        &self,
+        profile: Option<&str>,
+        max_attempts: i64,
+    ) -> Result<Vec<crate::ingest_queue::QueueItem>> {
+        let profile = profile.map(|p| p.to_string());
+        self.execute_with_priority(OperationPriority::BackgroundIngest, move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, url, title, profile, status, attempts, last_error
+                 FROM ingest_queue
+                 WHERE profile IS ?1
+                   AND (status IN ('pending', 'in_progress')
+                        OR (status = 'failed' AND attempts < ?2))
+                   AND url NOT IN (SELECT url FROM ingest_errors WHERE ignored = 1)
+                 ORDER BY id",
+            )?;
+            let rows = stmt.query_map(params![profile, max_attempts], queue_item_from_row)?;
+            let mut out = Vec::new();
+            for row in rows {
+                out.push(row?);
+            }
+            Ok(out)
+        })
+        .await
+    }
+
+    /// Return all queue items with the given status, oldest first.
+    pub async fn get_queue_items_by_status(
+        &self,
+        status: &crate::ingest_queue::QueueStatus,
+    ) -> Result<Vec<crate::ingest_queue::QueueItem>> {
+        let status_str = status.as_db_str().to_string();
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, url, title, profile, status, attempts, last_error
+                 FROM ingest_queue WHERE status = ?1 ORDER BY id",
+            )?;
+            let rows = stmt.query_map(params![status_str], queue_item_from_row)?;
+            let mut out = Vec::new();
+            for row in rows {
+                out.push(row?);
+            }
+            Ok(out)
+        })
+        .await
+    }
+
+    /// Update a queue item's status. Moving to `failed` increments `attempts`
+    /// and records `error`; any other status clears the stored error.
+    pub async fn set_queue_item_status(
+        &self,
+        id: i64,
+        status: &crate::ingest_queue::QueueStatus,
+        error: Option<&str>,
+    ) -> Result<()> {
+        use crate::ingest_queue::QueueStatus;
+        let failed = *status == QueueStatus::Failed;
+        let status_str = status.as_db_str().to_string();
+        let error = error.map(|e| e.to_string());
+        let now = chrono_utc_now();
+        self.execute_with_priority(OperationPriority::BackgroundIngest, move |conn| {
+            if failed {
+                conn.execute(
+                    "UPDATE ingest_queue
+                     SET status = ?1, attempts = attempts + 1, last_error = ?2, updated_at = ?3
+                     WHERE id = ?4",
+                    params![status_str, error, now, id],
+                )?;
+            } else {
+                conn.execute(
+                    "UPDATE ingest_queue
+                     SET status = ?1, last_error = NULL, updated_at = ?2
+                     WHERE id = ?3",
+                    params![status_str, now, id],
+                )?;
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    /// Reset every failed queue item to `pending` with a fresh attempt count.
+    /// Returns the number of items reset.
+    pub async fn retry_failed_queue_items(&self) -> Result<usize> {
+        let now = chrono_utc_now();
+        self.execute_with_priority(OperationPriority::BackgroundIngest, move |conn| {
+            let n = conn.execute(
+                "UPDATE ingest_queue
+                 SET status = 'pending', attempts = 0, last_error = NULL, updated_at = ?1
+                 WHERE status = 'failed'",
+                params![now],
+            )?;
+            Ok(n)
+        })
+        .await
+    }
+
+    /// Record (or update) a failed bookmark fetch, incrementing its attempt count.
+    pub async fn record_ingest_error(
+        &self,
+        url: &str,
+        title: &str,
+        profile: Option<&str>,
+        error: &str,
+    ) -> Result<()> {
+        let url = url.to_string();
+        let title = title.to_string();
+        let profile = profile.map(|p| p.to_string());
+        let error = error.to_string();
+        let now = chrono_utc_now();
+        self.execute_with_priority(OperationPriority::BackgroundIngest, move |conn| {
+            conn.execute(
+                "INSERT INTO ingest_errors (url, title, profile, error, attempts, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, 1, ?5)
+                 ON CONFLICT(url) DO UPDATE SET
+                     error      = excluded.error,
+                     attempts   = ingest_errors.attempts + 1,
+                     updated_at = excluded.updated_at",
+                params![url, title, profile, error, now],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Remove the error record for a URL (called after a successful ingest).
+    pub async fn clear_ingest_error(&self, url: &str) -> Result<()> {
+        let url = url.to_string();
+        self.execute_with_priority(OperationPriority::BackgroundIngest, move |conn| {
+            conn.execute("DELETE FROM ingest_errors WHERE url = ?1", params![url])?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Return all failed imports that have not been ignored, most recent first.
+    pub async fn get_ingest_errors(&self) -> Result<Vec<crate::ingest_queue::IngestError>> {
+        use crate::ingest_queue::IngestError;
+        self.execute_with_priority(OperationPriority::UserSearch, |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, url, title, profile, error, attempts, updated_at
+                 FROM ingest_errors WHERE ignored = 0
+                 ORDER BY updated_at DESC, id DESC",
+            )?;
+            let rows = stmt.query_map([], |row| {
+                Ok(IngestError {
+                    id: row.get(0)?,
+                    url: row.get(1)?,
+                    title: row.get(2)?,
+                    profile: row.get(3)?,
+                    error: row.get(4)?,
+                    attempts: row.get(5)?,
+                    updated_at: row.get(6)?,
+                })
+            })?;
+            let mut out = Vec::new();
+            for row in rows {
+                out.push(row?);
+            }
+            Ok(out)
+        })
+        .await
+    }
+
+    /// Hide a failed import from the report and stop retrying it automatically.
+    pub async fn ignore_ingest_error(&self, id: i64) -> Result<()> {
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            conn.execute(
+                "UPDATE ingest_errors SET ignored = 1 WHERE id = ?1",
+                params![id],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Reset the queue row for a failed import to `pending` and return it so
+    /// the caller can process it immediately.
+    ///
+    /// If the URL was never queued (e.g. a migrated placeholder document), a
+    /// new queue row is created from the error record.
+    pub async fn requeue_ingest_error(
+        &self,
+        id: i64,
+    ) -> Result<Option<crate::ingest_queue::QueueItem>> {
+        let now = chrono_utc_now();
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            let inserted = conn.execute(
+                "INSERT INTO ingest_queue (url, title, profile, status, attempts, updated_at)
+                 SELECT url, title, profile, 'pending', 0, ?2
+                 FROM ingest_errors WHERE id = ?1
+                 ON CONFLICT(url) DO UPDATE SET
+                     status     = 'pending',
+                     attempts   = 0,
+                     last_error = NULL,
+                     updated_at = excluded.updated_at",
+                params![id, now],
+            )?;
+            if inserted == 0 {
+                return Ok(None);
+            }
+            let mut stmt = conn.prepare(
+                "SELECT q.id, q.url, q.title, q.profile, q.status, q.attempts, q.last_error
+                 FROM ingest_queue q JOIN ingest_errors e ON e.url = q.url
+                 WHERE e.id = ?1",
+            )?;
+            match stmt.query_row(params![id], queue_item_from_row) {
+                Ok(item) => Ok(Some(item)),
+                Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+                Err(e) => Err(Box::new(e)),
+            }
+        })
+        .await
+    }
+
+    // -----------------------------------------------------------------------
+    // Collections
+    // -----------------------------------------------------------------------
+
+    /// Create a collection, returning its ID. Names are unique, ignoring case.
+    pub async fn create_collection(&self, name: &str) -> Result<i64> {
+        self.insert_collection(name, None).await
+    }
+
+    /// Create a smart collection from the metadata operators in `query`
+    /// (e.g. `tag:rust source:bookmark after:2024`), returning its ID. Its
+    /// documents are worked out from the stored filter each time they are
+    /// needed, so it stays up to date as documents are saved and tagged.
+    pub async fn create_smart_collection(&self, name: &str, query: &str) -> Result<i64> {
+        let expression = crate::query_syntax::parse(query).filter_expression();
+        if expression.is_empty() {
+            return Err(
+                "A smart collection needs a filter such as tag:, source:, site: or after:".into(),
+            );
+        }
+        self.insert_collection(name, Some(expression)).await
+    }
+
+    async fn insert_collection(&self, name: &str, query: Option<String>) -> Result<i64> {
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            return Err("Collection name cannot be empty".into());
+        }
+        let now = chrono_utc_now();
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            match conn.execute(
+                "INSERT INTO collections (name, created_at, query) VALUES (?1, ?2, ?3)",
+                params![name, now, query],
+            ) {
+                Ok(_) => Ok(conn.last_insert_rowid()),
+                Err(rusqlite::Error::SqliteFailure(e, _))
+                    if e.code == rusqlite::ErrorCode::ConstraintViolation =>
+                {
+                    Err(format!("A collection named \"{}\" already exists", name).into())
+                }
+                Err(e) => Err(Box::new(e)),
+            }
+        })
+        .await
+    }
+
+    /// Delete a collection. Its documents are kept.
+    pub async fn delete_collection(&self, id: i64) -> Result<()> {
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            conn.execute("DELETE FROM collections WHERE id = ?1", params![id])?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// All collections with their document counts, sorted by name. Smart
+    /// collections count the documents currently matching their filter.
+    pub async fn get_collections(&self) -> Result<Vec<Collection>> {
+        self.execute_with_priority(OperationPriority::UserSearch, |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT c.id, c.name, COUNT(d.id), c.query
+                 FROM collections c
+                 LEFT JOIN collection_documents cd ON cd.collection_id = c.id
+                 LEFT JOIN documents d ON d.id = cd.document_id AND d.deleted_at IS NULL
+                 GROUP BY c.id
+                 ORDER BY c.name COLLATE NOCASE",
+            )?;
+            let mut collections = stmt
+                .query_map([], |row| {
+                    Ok(Collection {
+                        id: row.get(0)?,
+                        name: row.get(1)?,
+                        document_count: row.get(2)?,
+                        query: row.get(3)?,
+                    })
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            for collection in &mut collections {
+                if let Some(query) = &collection.query {
+                    let filters = crate::query_syntax::parse(query);
+                    collection.document_count = filtered_document_ids(conn, &filters)?.len() as i64;
+                }
+            }
+            Ok(collections)
+        })
+        .await
+    }
+
+    /// Add documents to a collection, returning how many were newly added.
+    /// Smart collections take no members.
+    pub async fn add_documents_to_collection(
+        &self,
+        collection_id: i64,
+        doc_ids: &[i64],
+    ) -> Result<usize> {
+        let doc_ids = doc_ids.to_vec();
+        let now = chrono_utc_now();
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            if collection_query(conn, collection_id)?.is_some() {
+                return Err("Documents cannot be added to a smart collection".into());
+            }
+            let tx = conn.unchecked_transaction()?;
+            let mut added = 0;
+            {
+                let mut stmt = tx.prepare(
+                    "INSERT OR IGNORE INTO collection_documents
+                         (collection_id, document_id, added_at)
+                     VALUES (?1, ?2, ?3)",
+                )?;
+                for doc_id in &doc_ids {
+                    added += stmt.execute(params![collection_id, doc_id, now])?;
+                }
+            }
+            tx.commit()?;
+            Ok(added)
+        })
+        .await
+    }
+
+    /// Remove a document from a collection (the document itself is kept).
+    pub async fn remove_document_from_collection(
+        &self,
+        collection_id: i64,
+        doc_id: i64,
+    ) -> Result<()> {
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            conn.execute(
+                "DELETE FROM collection_documents
+                 WHERE collection_id = ?1 AND document_id = ?2",
+                params![collection_id, doc_id],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// IDs of the documents in a collection. For a smart collection, the
+    /// documents its filter matches right now.
+    pub async fn get_collection_document_ids(
+        &self,
+        collection_id: i64,
+    ) -> Result<std::collections::HashSet<i64>> {
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            if let Some(query) = collection_query(conn, collection_id)? {
+                let filters = crate::query_syntax::parse(&query);
+                return Ok(filtered_document_ids(conn, &filters)?);
+            }
+            let mut stmt = conn
+                .prepare("SELECT document_id FROM collection_documents WHERE collection_id = ?1")?;
+            let ids = stmt
+                .query_map(params![collection_id], |row| row.get(0))?
+                .collect::<std::result::Result<_, _>>()?;
+            Ok(ids)
+        })
+        .await
+    }
+
+    /// IDs of the collections a document belongs to.
+    pub async fn get_document_collection_ids(&self, doc_id: i64) -> Result<Vec<i64>> {
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            let mut stmt = conn
+                .prepare("SELECT collection_id FROM collection_documents WHERE document_id = ?1")?;
+            let ids = stmt
+                .query_map(params![doc_id], |row| row.get(0))?
+                .collect::<std::result::Result<Vec<i64>, _>>()?;
+            Ok(ids)
+        })
+        .await
+    }
+
+    // -----------------------------------------------------------------------
+    // Tags and bulk actions
+    // -----------------------------------------------------------------------
+
+    /// Tag each document with `tag`, returning how many were newly tagged.
+    /// Tags are compared ignoring case.
+    pub async fn add_tag_to_documents(&self, doc_ids: &[i64], tag: &str) -> Result<usize> {
+        let tag = tag.trim().to_string();
+        if tag.is_empty() {
+            return Err("Tag cannot be empty".into());
+        }
+        let doc_ids = doc_ids.to_vec();
+        let now = chrono_utc_now();
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            let tx = conn.unchecked_transaction()?;
+            let mut added = 0;
+            {
+                let mut stmt = tx.prepare(
+                    "INSERT OR IGNORE INTO document_tags (document_id, tag, created_at)
+                     VALUES (?1, ?2, ?3)",
+                )?;
+                for doc_id in &doc_ids {
+                    added += stmt.execute(params![doc_id, tag, now])?;
+                }
+            }
+            tx.commit()?;
+            Ok(added)
+        })
+        .await
+    }
+
+    /// Remove a tag from a document.
+    pub async fn remove_document_tag(&self, doc_id: i64, tag: &str) -> Result<()> {
+        let tag = tag.to_string();
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            conn.execute(
+                "DELETE FROM document_tags WHERE document_id = ?1 AND tag = ?2",
+                params![doc_id, tag],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Replace a document's tags. Blank tags are dropped and duplicates
+    /// (ignoring case) kept once.
+    pub async fn set_document_tags(&self, doc_id: i64, tags: &[String]) -> Result<()> {
+        let tags: Vec<String> = tags
+            .iter()
+            .map(|tag| tag.trim().to_string())
+            .filter(|tag| !tag.is_empty())
+            .collect();
+        let now = chrono_utc_now();
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            let tx = conn.unchecked_transaction()?;
+            tx.execute(
+                "DELETE FROM document_tags WHERE document_id = ?1",
+                params![doc_id],
+            )?;
+            {
+                let mut stmt = tx.prepare(
+                    "INSERT OR IGNORE INTO document_tags (document_id, tag, created_at)
+                     VALUES (?1, ?2, ?3)",
+                )?;
+                for tag in &tags {
+                    stmt.execute(params![doc_id, tag, now])?;
+                }
+            }
+            tx.commit()?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Tags on a document, alphabetically.
+    pub async fn get_document_tags(&self, doc_id: i64) -> Result<Vec<String>> {
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT tag FROM document_tags WHERE document_id = ?1 ORDER BY tag COLLATE NOCASE",
+            )?;
+            let tags = stmt
+                .query_map(params![doc_id], |row| row.get(0))?
+                .collect::<std::result::Result<Vec<String>, _>>()?;
+            Ok(tags)
+        })
+        .await
+    }
+
+    /// Every tag in use with the number of documents carrying it, most used
+    /// first. Tags differing only in case are counted together.
+    pub async fn get_tag_counts(&self) -> Result<Vec<(String, i64)>> {
+        self.execute_with_priority(OperationPriority::UserSearch, |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT t.tag, COUNT(*) FROM document_tags t
+                 JOIN documents d ON d.id = t.document_id
+                 WHERE d.deleted_at IS NULL
+                 GROUP BY t.tag
+                 ORDER BY COUNT(*) DESC, t.tag",
+            )?;
+            let counts = stmt
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<std::result::Result<Vec<(String, i64)>, _>>()?;
+            Ok(counts)
+        })
+        .await
+    }
+
+    /// Tags on each of `doc_ids` that is not in the trash. Documents without
+    /// tags are left out.
+    pub async fn get_tags_for_documents(
+        &self,
+        doc_ids: &[i64],
+    ) -> Result<std::collections::HashMap<i64, Vec<String>>> {
+        if doc_ids.is_empty() {
+            return Ok(Default::default());
+        }
+
+        self.execute_with_priority(OperationPriority::UserSearch, |conn| {
+            let placeholders = doc_ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+            let mut stmt = conn.prepare(&format!(
+                "SELECT t.document_id, t.tag FROM document_tags t
+                 JOIN documents d ON d.id = t.document_id
+                 WHERE t.document_id IN ({}) AND d.deleted_at IS NULL
+                 ORDER BY t.tag COLLATE NOCASE",
+                placeholders
+            ))?;
+            let params: Vec<_> = doc_ids
+                .iter()
+                .map(|id| id as &dyn rusqlite::ToSql)
+                .collect();
+            let mut tags: std::collections::HashMap<i64, Vec<String>> = Default::default();
+            for row in stmt.query_map(&params[..], |row| Ok((row.get(0)?, row.get(1)?)))? {
+                let (doc_id, tag): (i64, String) = row?;
+                tags.entry(doc_id).or_default().push(tag);
+            }
+            Ok(tags)
+        })
+        .await
+    }
+
+    // -----------------------------------------------------------------------
+    // Document links
+    // -----------------------------------------------------------------------
+
+    /// Replace a document's outgoing links.
+    pub async fn set_document_links(&self, doc_id: i64, target_urls: &[String]) -> Result<()> {
+        let target_urls = target_urls.to_vec();
+        self.execute_with_priority(OperationPriority::BackgroundIngest, move |conn| {
+            let tx = conn.unchecked_transaction()?;
+            tx.execute(
+                "DELETE FROM document_links WHERE source_id = ?1",
+                params![doc_id],
+            )?;
+            {
+                let mut stmt = tx.prepare(
+                    "INSERT OR IGNORE INTO document_links (source_id, target_url)
+                     VALUES (?1, ?2)",
+                )?;
+                for url in &target_urls {
+                    stmt.execute(params![doc_id, url])?;
+                }
+            }
+            tx.commit()?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Replace a document's headings, anchors and PDF pages; an empty
+    /// structure removes them.
+    pub async fn set_document_structure(
+        &self,
+        doc_id: i64,
+        structure: &PageStructure,
+    ) -> Result<()> {
+        let stored = if structure.is_empty() {
+            None
+        } else {
+            let headings = serde_json::to_string(&structure.headings)
+                .map_err(|e| format!("Failed to serialize headings: {}", e))?;
+            let links = serde_json::to_string(&structure.links)
+                .map_err(|e| format!("Failed to serialize page links: {}", e))?;
+            let pages = serde_json::to_string(&structure.pages)
+                .map_err(|e| format!("Failed to serialize pages: {}", e))?;
+            Some((headings, links, pages))
+        };
+        self.execute_with_priority(OperationPriority::BackgroundIngest, move |conn| {
+            match stored {
+                Some((headings, links, pages)) => conn.execute(
+                    "INSERT OR REPLACE INTO document_structure (document_id, headings, links, pages)
+                     VALUES (?1, ?2, ?3, ?4)",
+                    params![doc_id, headings, links, pages],
+                )?,
+                None => conn.execute(
+                    "DELETE FROM document_structure WHERE document_id = ?1",
+                    params![doc_id],
+                )?,
+            };
+            Ok(())
+        })
+        .await
+    }
+
+    /// A document's headings, anchors and PDF pages; empty for documents
+    /// not ingested from HTML or PDF.
+    pub async fn get_document_structure(&self, doc_id: i64) -> Result<PageStructure> {
+        let stored = self
+            .execute_with_priority(OperationPriority::UserSearch, move |conn| {
+                match conn.query_row(
+                    "SELECT headings, links, pages FROM document_structure WHERE document_id = ?1",
+                    params![doc_id],
+                    |row| {
+                        Ok((
+                            row.get::<_, String>(0)?,
+                            row.get::<_, String>(1)?,
+                            row.get::<_, String>(2)?,
+                        ))
+                    },
+                ) {
+                    Ok(stored) => Ok(Some(stored)),
+                    Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+                    Err(e) => Err(Box::new(e)),
+                }
+            })
+            .await?;
+        let Some((headings, links, pages)) = stored else {
+            return Ok(PageStructure::default());
+        };
+        Ok(PageStructure {
+            headings: serde_json::from_str(&headings)
+                .map_err(|e| format!("Failed to parse headings: {}", e))?,
+            links: serde_json::from_str(&links)
+                .map_err(|e| format!("Failed to parse page links: {}", e))?,
+            pages: serde_json::from_str(&pages)
+                .map_err(|e| format!("Failed to parse pages: {}", e))?,
+        })
+    }
+
+    /// Indexed documents this document links to.
+    pub async fn get_outgoing_linked_documents(&self, doc_id: i64) -> Result<Vec<LinkedDocument>> {
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT DISTINCT d.id, d.title, d.url
+                 FROM document_links l
+                 JOIN documents d ON d.url IN (l.target_url, l.target_url || '/')
+                 WHERE l.source_id = ?1 AND d.id != ?1 AND d.deleted_at IS NULL
+                 ORDER BY d.title COLLATE NOCASE",
+            )?;
+            let docs = stmt
+                .query_map(params![doc_id], linked_document_from_row)?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok(docs)
+        })
+        .await
+    }
+
+    /// Indexed documents that link to this document.
+    pub async fn get_backlinked_documents(&self, doc_id: i64) -> Result<Vec<LinkedDocument>> {
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT DISTINCT d.id, d.title, d.url
+                 FROM documents t
+                 JOIN document_links l ON l.target_url IN (t.url, RTRIM(t.url, '/'))
+                 JOIN documents d ON d.id = l.source_id
+                 WHERE t.id = ?1 AND d.id != ?1 AND d.deleted_at IS NULL
+                 ORDER BY d.title COLLATE NOCASE",
+            )?;
+            let docs = stmt
+                .query_map(params![doc_id], linked_document_from_row)?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok(docs)
+        })
+        .await
+    }
+
+    /// IDs of the documents linked to or from any of `doc_ids`.
+    pub async fn get_linked_document_ids(
+        &self,
+        doc_ids: &[i64],
+    ) -> Result<std::collections::HashSet<i64>> {
+        let doc_ids = doc_ids.to_vec();
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            let mut outgoing = conn.prepare(
+                "SELECT d.id FROM document_links l
+                 JOIN documents d ON d.url IN (l.target_url, l.target_url || '/')
+                 WHERE l.source_id = ?1",
+            )?;
+            let mut incoming = conn.prepare(
+                "SELECT l.source_id FROM documents t
+                 JOIN document_links l ON l.target_url IN (t.url, RTRIM(t.url, '/'))
+                 WHERE t.id = ?1",
+            )?;
+            let mut linked = std::collections::HashSet::new();
+            for doc_id in &doc_ids {
+                for stmt in [&mut outgoing, &mut incoming] {
+                    for id in stmt.query_map(params![doc_id], |row| row.get::<_, i64>(0))? {
+                        linked.insert(id?);
+                    }
+                }
+            }
+            for doc_id in &doc_ids {
+                linked.remove(doc_id);
+            }
+            Ok(linked)
+        })
+        .await
+    }
+
+    /// URLs a document links to, alphabetically.
+    pub async fn get_document_links(&self, doc_id: i64) -> Result<Vec<String>> {
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT target_url FROM document_links WHERE source_id = ?1 ORDER BY target_url",
+            )?;
+            let urls = stmt
+                .query_map(params![doc_id], |row| row.get(0))?
+                .collect::<std::result::Result<Vec<String>, _>>()?;
+            Ok(urls)
+        })
+        .await
+    }
+
+    // -----------------------------------------------------------------------
+    // Trash (soft delete)
+    // -----------------------------------------------------------------------
+
+    /// Move documents to the trash, returning how many were moved. Callers
+    /// evict the IDs from the VectorStore afterwards.
+    pub async fn trash_documents(&self, doc_ids: &[i64]) -> Result<usize> {
+        let doc_ids = doc_ids.to_vec();
+        let now = chrono_utc_now();
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            let tx = conn.unchecked_transaction()?;
+            let mut trashed = 0;
+            {
+                let mut stmt = tx.prepare(
+                    "UPDATE documents SET deleted_at = ?2 WHERE id = ?1 AND deleted_at IS NULL",
+                )?;
+                for doc_id in &doc_ids {
+                    trashed += stmt.execute(params![doc_id, now])?;
+                }
+            }
+            tx.commit()?;
+            Ok(trashed)
+        })
+        .await
+    }
+
+    /// Take documents back out of the trash, returning how many were
+    /// restored. Callers reload their chunk vectors into the VectorStore.
+    pub async fn restore_documents(&self, doc_ids: &[i64]) -> Result<usize> {
+        let doc_ids = doc_ids.to_vec();
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            let tx = conn.unchecked_transaction()?;
+            let mut restored = 0;
+            {
+                let mut stmt = tx.prepare(
+                    "UPDATE documents SET deleted_at = NULL WHERE id = ?1 AND deleted_at IS NOT NULL",
+                )?;
+                for doc_id in &doc_ids {
+                    restored += stmt.execute(params![doc_id])?;
+                }
+            }
+            tx.commit()?;
+            Ok(restored)
+        })
+        .await
+    }
+
+    /// Documents in the trash, most recently trashed first.
+    pub async fn get_trashed_documents(&self) -> Result<Vec<TrashedDocument>> {
+        self.execute_with_priority(OperationPriority::UserSearch, |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, title, url, CAST(deleted_at AS INTEGER)
+                 FROM documents
+                 WHERE deleted_at IS NOT NULL
+                 ORDER BY CAST(deleted_at AS INTEGER) DESC, id DESC",
+            )?;
+            let docs = stmt
+                .query_map([], |row| {
+                    Ok(TrashedDocument {
+                        id: row.get(0)?,
+                        title: row.get(1)?,
+                        url: row.get(2)?,
+                        deleted_at: row.get(3)?,
+                    })
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok(docs)
+        })
+        .await
+    }
+
+    /// Permanently delete everything in the trash, returning how many
+    /// documents were purged.
+    pub async fn purge_trash(&self) -> Result<usize> {
+        let doc_ids: Vec<i64> = self
+            .execute_with_priority(OperationPriority::UserSearch, |conn| {
+                let mut stmt =
+                    conn.prepare("SELECT id FROM documents WHERE deleted_at IS NOT NULL")?;
+                let ids = stmt
+                    .query_map([], |row| row.get(0))?
+                    .collect::<std::result::Result<Vec<i64>, _>>()?;
+                Ok(ids)
+            })
+            .await?;
+        self.delete_documents(&doc_ids).await
+    }
+
+    /// Permanently delete several documents in one transaction, along with
+    /// their chunks and FTS rows, returning how many existed. Callers evict
+    /// the IDs from the VectorStore afterwards.
+    pub async fn delete_documents(&self, doc_ids: &[i64]) -> Result<usize> {
+        let doc_ids = doc_ids.to_vec();
+        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
+            let tx = conn.unchecked_transaction()?;
+            let mut deleted = 0;
+            for doc_id in &doc_ids {
+                // FTS must be deleted manually (virtual table, no FK cascade)
+                tx.execute(
+                    "DELETE FROM documents_fts WHERE rowid = ?1",
+                    params![doc_id],
+                )?;
+                deleted += tx.execute("DELETE FROM documents WHERE id = ?1", params![doc_id])?;
+            }
+            tx.commit()?;
+            Ok(deleted)
+        })
+        .await
+    }
+}
+
+/// Build a `Document` from a row selected as
+/// `id, title, content, url, source, created_at, embedding, is_dead, needs_auth, profile`.
+fn document_from_row(row: &rusqlite::Row) -> rusqlite::Result<Document> {
+    Ok(Document {
+        id: row.get(0)?,
+        title: row.get(1)?,
+        content: row.get(2)?,
+        url: row.get(3)?,
+        source: row.get(4)?,
+        created_at: row.get(5)?,
+        embedding: row.get(6)?,
+        is_dead: row.get(7)?,
+        needs_auth: row.get(8)?,
+        profile: row.get(9)?,
+    })
+}
+
+fn linked_document_from_row(row: &rusqlite::Row) -> rusqlite::Result<LinkedDocument> {
+    Ok(LinkedDocument {
+        id: row.get(0)?,
+        title: row.get(1)?,
+        url: row.get(2)?,
+    })
+}
+
+/// Build a `QueueItem` from a row selected as
+/// `id, url, title, profile, status, attempts, last_error`.
+fn queue_item_from_row(row: &rusqlite::Row) -> rusqlite::Result<crate::ingest_queue::QueueItem> {
+    use crate::ingest_queue::{QueueItem, QueueStatus};
+    let status_str: String = row.get(4)?;
+    Ok(QueueItem {
+        id: row.get(0)?,
+        url: row.get(1)?,
+        title: row.get(2)?,
+        profile: row.get(3)?,
+        status: QueueStatus::from_db_str(&status_str),
+        attempts: row.get(5)?,
+        last_error: row.get(6)?,
+    })
+}
+
+/// Replace all chunk embeddings for a document inside an open transaction and
+/// record the new chunk count and index time. Returns the embedding IDs in
+/// chunk order. Rows of a model being migrated to are dropped too, leaving
+/// the document to be migrated again.
+fn write_chunk_embeddings(
+    tx: &rusqlite::Transaction<'_>,
+    doc_id: i64,
+    chunks: &[ChunkRow],
+) -> Result<Vec<i64>> {
+    tx.execute(
+        "DELETE FROM embeddings WHERE document_id = ?1",
+        params![doc_id],
+    )?;
+
+    let mut ids = Vec::with_capacity(chunks.len());
+    {
+        let mut stmt = tx.prepare(
+            "INSERT INTO embeddings (document_id, chunk_start, chunk_end, embedding, content_hash,
+                                     section_heading, page_number, timestamp_seconds,
+                                     section_anchor)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        )?;
+        for (chunk_start, chunk_end, embedding, provenance) in chunks {
+            stmt.execute(params![
+                doc_id,
+                *chunk_start as i64,
+                *chunk_end as i64,
+                embedding,
+                provenance.content_hash,
+                provenance.section_heading,
+                provenance.page_number,
+                provenance.timestamp_seconds,
+                provenance.section_anchor
+            ])?;
+            ids.push(tx.last_insert_rowid());
+        }
+    }
+
+    tx.execute(
+        "UPDATE documents SET chunk_count = ?1, indexed_at = CURRENT_TIMESTAMP WHERE id = ?2",
+        params![chunks.len() as i64, doc_id],
+    )?;
+    Ok(ids)
+}
+
+/// IDs of the documents passing the metadata operators in `filters`, see
+/// `Database::get_filtered_document_ids`.
+fn filtered_document_ids(
+    conn: &Connection,
+    filters: &ParsedQuery,
+) -> rusqlite::Result<std::collections::HashSet<i64>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, url FROM documents
+         WHERE deleted_at IS NULL AND needs_refetch = 0
+           AND (?1 IS NULL OR source LIKE '%' || ?1 || '%')
+           AND (?2 IS NULL OR date(created_at) < ?2)
+           AND (?3 IS NULL OR date(created_at) > ?3)
+           AND (?4 IS NULL OR language = ?4)",
+    )?;
+    let rows = stmt
+        .query_map(
+            params![
+                filters.source,
+                filters.before,
+                filters.after,
+                filters.language
+            ],
+            |row| Ok((row.get::<_, i64>(0)?, row.get::<_, Option<String>>(1)?)),
+        )?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    let mut ids = std::collections::HashSet::new();
+    for (id, url) in rows {
+        if filters.sites.is_empty() {
+            ids.insert(id);
+            continue;
+        }
+        let host = url
+            .as_deref()
+            .and_then(|u| url::Url::parse(u).ok())
+            .and_then(|u| u.host_str().map(str::to_lowercase));
+        let Some(host) = host else {
+            continue;
+        };
+        let on_site = filters
+            .sites
+            .iter()
+            .any(|site| host == *site || host.ends_with(&format!(".{}", site)));
+        if on_site {
+            ids.insert(id);
+        }
+    }
+
+    let mut tagged = conn.prepare("SELECT document_id FROM document_tags WHERE tag = ?1")?;
+    for tag in &filters.tags {
+        let with_tag = tagged
+            .query_map(params![tag], |row| row.get::<_, i64>(0))?
+            .collect::<std::result::Result<std::collections::HashSet<_>, _>>()?;
+        ids.retain(|id| with_tag.contains(id));
+    }
+    Ok(ids)
+}
+
+/// Filter expression of a smart collection; `None` for an ordinary or
+/// missing collection.
+fn collection_query(conn: &Connection, collection_id: i64) -> rusqlite::Result<Option<String>> {
+    match conn.query_row(
+        "SELECT query FROM collections WHERE id = ?1",
+        params![collection_id],
+        |row| row.get(0),
+    ) {
+        Ok(query) => Ok(query),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Documents (not in the trash) in `scope` (all when `None`), newest first,
+/// skipping `offset` and taking up to `limit`, optionally from one profile.
+fn newest_documents(
+    conn: &Connection,
+    scope: Option<&std::collections::HashSet<i64>>,
+    profile: Option<String>,
+    offset: usize,
+    limit: usize,
+) -> rusqlite::Result<Vec<Document>> {
+    let mut newest = conn.prepare(
+        "SELECT id FROM documents
+         WHERE deleted_at IS NULL AND (?1 IS NULL OR profile = ?1)
+         ORDER BY created_at DESC, id DESC",
+    )?;
+    let listed = newest
+        .query_map(params![profile], |row| row.get::<_, i64>(0))?
+        .filter(|id| {
+            id.as_ref()
+                .map_or(true, |id| scope.is_none_or(|scope| scope.contains(id)))
+        })
+        .skip(offset)
+        .take(limit)
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut stmt = conn.prepare(
+        "SELECT id, title, content, url, source, created_at, embedding, is_dead, needs_auth, profile
+         FROM documents WHERE id = ?1",
+    )?;
+    listed
+        .iter()
+        .map(|id| stmt.query_row(params![id], document_from_row))
+        .collect()
+}
+
+fn document_version_from_row(
+    row: &rusqlite::Row,
+) -> rusqlite::Result<crate::document_versions::DocumentVersion> {
+    Ok(crate::document_versions::DocumentVersion {
+        id: row.get(0)?,
+        document_id: row.get(1)?,
+        title: row.get(2)?,
+        content: row.get(3)?,
+        saved_at: row.get(4)?,
+    })
+}
+
+/// Columns of an annotation as read by `annotation_from_row`
+const ANNOTATION_SELECT: &str =
+    "SELECT id, document_id, start_offset, end_offset, quote, note, highlight_document_id, created_at
+     FROM annotations";
+
+fn annotation_from_row(row: &rusqlite::Row) -> rusqlite::Result<crate::annotations::Annotation> {
+    Ok(crate::annotations::Annotation {
+        id: row.get(0)?,
+        document_id: row.get(1)?,
+        start: row.get::<_, i64>(2)? as usize,
+        end: row.get::<_, i64>(3)? as usize,
+        quote: row.get(4)?,
+        note: row.get(5)?,
+        highlight_document_id: row.get(6)?,
+        created_at: row.get(7)?,
+    })
+}
+
+/// Reminders matching `condition` (on `reminders r`) whose documents are
+/// not in the trash, oldest first.
+fn query_reminders(
+    conn: &Connection,
+    condition: &str,
+) -> rusqlite::Result<Vec<crate::reminders::Reminder>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT r.document_id, d.title, d.url, date(r.remind_at, 'localtime'), r.notify
+         FROM reminders r JOIN documents d ON d.id = r.document_id
+         WHERE d.deleted_at IS NULL AND {}
+         ORDER BY r.remind_at, r.document_id",
+        condition
+    ))?;
+    let reminders = stmt
+        .query_map([], |row| {
+            Ok(crate::reminders::Reminder {
+                document_id: row.get(0)?,
+                title: row.get(1)?,
+                url: row.get(2)?,
+                remind_on: row.get(3)?,
+                notify: row.get(4)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(reminders)
+}
+
+/// Return current UTC timestamp as an ISO 8601 string.
+fn chrono_utc_now() -> String {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    // Store as unix timestamp string — no chrono dependency needed
+    format!("{}", secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    async fn create_test_db() -> (Database, TempDir) {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("test_localmind.db");
+
+        let conn = Connection::open(&db_path).unwrap();
+        let db = Database {
+            conn: Arc::new(Mutex::new(conn)),
+            search_semaphore: Arc::new(Semaphore::new(10)),
+            ingest_semaphore: Arc::new(Semaphore::new(1)),
+            cached_settings: Default::default(),
+        };
+
+        db.init_schema().await.unwrap();
+        (db, temp_dir)
+    }
+
+    #[tokio::test]
+    async fn test_excluded_folders_config() {
+        let (db, _temp) = create_test_db().await;
+
+        let folders = db.get_excluded_folders().await.unwrap();
+        assert_eq!(
+            folders.len(),
+            0,
+            "Initially should have no excluded folders"
+        );
+
+        let test_folders = vec![
+            "folder_123".to_string(),
+            "folder_456".to_string(),
+            "folder_789".to_string(),
+        ];
+        db.set_excluded_folders(&test_folders).await.unwrap();
+
+        let retrieved = db.get_excluded_folders().await.unwrap();
+        assert_eq!(
+            retrieved, test_folders,
+            "Should retrieve same folders that were set"
+        );
+
+        let updated_folders = vec!["folder_abc".to_string(), "folder_xyz".to_string()];
+        db.set_excluded_folders(&updated_folders).await.unwrap();
+
+        let retrieved = db.get_excluded_folders().await.unwrap();
+        assert_eq!(
+            retrieved, updated_folders,
+            "Should update to new folder list"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_excluded_domains_config() {
+        let (db, _temp) = create_test_db().await;
+
+        let domains = db.get_excluded_domains().await.unwrap();
+        assert_eq!(
+            domains.len(),
+            0,
+            "Initially should have no excluded domains"
+        );
+
+        let test_domains = vec![
+            "*.internal.com".to_string(),
+            "private.example.org".to_string(),
+            "localhost:*".to_string(),
+        ];
+        db.set_excluded_domains(&test_domains).await.unwrap();
+
+        let retrieved = db.get_excluded_domains().await.unwrap();
+        assert_eq!(
+            retrieved, test_domains,
+            "Should retrieve same domains that were set"
+        );
+
+        let updated_domains = vec!["example.com".to_string()];
+        db.set_excluded_domains(&updated_domains).await.unwrap();
+
+        let retrieved = db.get_excluded_domains().await.unwrap();
+        assert_eq!(
+            retrieved, updated_domains,
+            "Should update to new domain list"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_delete_bookmarks_by_url_pattern() {
+        let (db, _temp) = create_test_db().await;
+
+        db.insert_document(
+            "Internal Site",
+            "Content from internal site",
+            Some("https://foo.internal.com/page"),
+            "chrome_bookmark",
+            None,
+            None,
+            OperationPriority::BackgroundIngest,
+            None,
+        )
+        .await
+        .unwrap();
+
+        db.insert_document(
+            "Public Site",
+            "Content from public site",
+            Some("https://example.com/page"),
+            "chrome_bookmark",
+            None,
+            None,
+            OperationPriority::BackgroundIngest,
+            None,
+        )
+        .await
+        .unwrap();
+
+        db.insert_document(
+            "Another Internal Site",
+            "More internal content",
+            Some("https://bar.internal.com/page"),
+            "chrome_bookmark",
+            None,
+            None,
+            OperationPriority::BackgroundIngest,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let deleted = db
+            .delete_bookmarks_by_url_pattern("*.internal.com")
+            .await
+            .unwrap();
+        assert_eq!(
+            deleted.len(),
+            2,
+            "Should delete 2 bookmarks matching *.internal.com"
+        );
+
+        let docs = db.get_live_documents_with_urls().await.unwrap();
+        let urls: Vec<String> = docs.iter().filter_map(|d| d.url.clone()).collect();
+
+        assert!(
+            urls.contains(&"https://example.com/page".to_string()),
+            "Public site should remain"
+        );
+        assert!(
+            !urls.contains(&"https://foo.internal.com/page".to_string()),
+            "foo.internal.com should be deleted"
+        );
+        assert!(
+            !urls.contains(&"https://bar.internal.com/page".to_string()),
+            "bar.internal.com should be deleted"
+        );
     }
 
-    /// Like `get_recent_documents` but optionally filtered to a specific Chrome profile.
-    pub async fn get_recent_documents_filtered(
-        &self,
-        limit: usize,
-        profile: Option<String>,
-    ) -> Result<Vec<Document>> {
-        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
-            let (sql, params_vec): (String, Vec<Box<dyn rusqlite::ToSql>>) =
-                if let Some(ref p) = profile {
-                    (
-                    "SELECT id, title, content, url, source, created_at, embedding, is_dead, needs_auth, profile
-                     FROM documents
-                     WHERE (is_dead = 0 OR is_dead IS NULL) AND profile = ?1
-                     ORDER BY created_at DESC
-                     LIMIT ?2".to_string(),
-                    vec![Box::new(p.clone()), Box::new(limit as i64)],
-                )
-                } else {
-                    (
-                    "SELECT id, title, content, url, source, created_at, embedding, is_dead, needs_auth, profile
-                     FROM documents
-                     WHERE is_dead = 0 OR is_dead IS NULL
-                     ORDER BY created_at DESC
-                     LIMIT ?1".to_string(),
-                    vec![Box::new(limit as i64)],
-                )
-                };
+    #[tokio::test]
+    async fn test_delete_bookmarks_by_url_pattern_wildcard_subdomain() {
+        let (db, _temp) = create_test_db().await;
 
-            let mut stmt = conn.prepare(&sql)?;
-            let param_refs: Vec<&dyn rusqlite::ToSql> =
-                params_vec.iter().map(|p| p.as_ref()).collect();
+        db.insert_document(
+            "Dev Site",
+            "Dev content",
+            Some("https://dev.mycompany.com/page"),
+            "chrome_bookmark",
+            None,
+            None,
+            OperationPriority::BackgroundIngest,
+            None,
+        )
+        .await
+        .unwrap();
 
-            let docs = stmt
-                .query_map(&param_refs[..], |row| {
-                    Ok(Document {
-                        id: row.get(0)?,
-                        title: row.get(1)?,
-                        content: row.get(2)?,
-                        url: row.get(3)?,
-                        source: row.get(4)?,
-                        created_at: row.get(5)?,
-                        embedding: row.get(6)?,
-                        is_dead: row.get(7)?,
-                        needs_auth: row.get(8)?,
-                        profile: row.get(9)?,
-                    })
-                })?
-                .collect::<std::result::Result<Vec<_>, _>>()?;
+        db.insert_document(
+            "Staging Site",
+            "Staging content",
+            Some("https://staging.mycompany.com/page"),
+            "chrome_bookmark",
+            None,
+            None,
+            OperationPriority::BackgroundIngest,
+            None,
+        )
+        .await
+        .unwrap();
 
-            Ok(docs)
-        })
+        db.insert_document(
+            "External Site",
+            "External content",
+            Some("https://external.com/page"),
+            "chrome_bookmark",
+            None,
+            None,
+            OperationPriority::BackgroundIngest,
+            None,
+        )
         .await
+        .unwrap();
+
+        let deleted = db
+            .delete_bookmarks_by_url_pattern("*.mycompany.com")
+            .await
+            .unwrap();
+        assert_eq!(deleted.len(), 2, "Should delete 2 mycompany.com bookmarks");
+
+        let docs = db.get_live_documents_with_urls().await.unwrap();
+        assert_eq!(docs.len(), 1, "Only 1 bookmark should remain");
+        assert_eq!(docs[0].url.as_ref().unwrap(), "https://external.com/page");
     }
 
-    pub async fn get_documents_batch(&self, ids: &[i64]) -> Result<Vec<Document>> {
-        if ids.is_empty() {
-            return Ok(Vec::new());
-        }
+    #[tokio::test]
+    async fn test_delete_bookmarks_by_url_pattern_exact_domain() {
+        let (db, _temp) = create_test_db().await;
 
-        self.execute_with_priority(OperationPriority::UserSearch, |conn| {
-            // Build the IN clause with placeholders
-            let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
-            let query = format!(
-                "SELECT id, title, content, url, source, created_at, embedding, is_dead, needs_auth, profile
-                 FROM documents WHERE id IN ({})",
-                placeholders
-            );
+        db.insert_document(
+            "Exact Match",
+            "Content",
+            Some("https://example.com/page"),
+            "chrome_bookmark",
+            None,
+            None,
+            OperationPriority::BackgroundIngest,
+            None,
+        )
+        .await
+        .unwrap();
 
-            let mut stmt = conn.prepare(&query)?;
+        db.insert_document(
+            "Subdomain",
+            "Content",
+            Some("https://sub.example.com/page"),
+            "chrome_bookmark",
+            None,
+            None,
+            OperationPriority::BackgroundIngest,
+            None,
+        )
+        .await
+        .unwrap();
 
-            // Convert ids to params
-            let params: Vec<_> = ids.iter().map(|id| id as &dyn rusqlite::ToSql).collect();
+        let deleted = db
+            .delete_bookmarks_by_url_pattern("example.com")
+            .await
+            .unwrap();
+        assert_eq!(deleted.len(), 1, "Should delete only exact domain match");
 
-            let docs = stmt
-                .query_map(&params[..], |row| {
-                    Ok(Document {
-                        id: row.get(0)?,
-                        title: row.get(1)?,
-                        content: row.get(2)?,
-                        url: row.get(3)?,
-                        source: row.get(4)?,
-                        created_at: row.get(5)?,
-                        embedding: row.get(6)?,
-                        is_dead: row.get(7)?,
-                        needs_auth: row.get(8)?,
-                        profile: row.get(9)?,
-                    })
-                })?
-                .collect::<std::result::Result<Vec<_>, _>>()?;
+        let docs = db.get_live_documents_with_urls().await.unwrap();
+        assert_eq!(docs.len(), 1, "Subdomain should remain");
+        assert_eq!(
+            docs[0].url.as_ref().unwrap(),
+            "https://sub.example.com/page"
+        );
+    }
 
-            Ok(docs)
-        })
-        .await
+    #[tokio::test]
+    async fn test_excluded_folders_persistence() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("test_persistence.db");
+
+        {
+            let conn = Connection::open(&db_path).unwrap();
+            let db = Database {
+                conn: Arc::new(Mutex::new(conn)),
+                search_semaphore: Arc::new(Semaphore::new(10)),
+                ingest_semaphore: Arc::new(Semaphore::new(1)),
+                cached_settings: Default::default(),
+            };
+            db.init_schema().await.unwrap();
+
+            let folders = vec!["folder_1".to_string(), "folder_2".to_string()];
+            db.set_excluded_folders(&folders).await.unwrap();
+        }
+
+        {
+            let conn = Connection::open(&db_path).unwrap();
+            let db = Database {
+                conn: Arc::new(Mutex::new(conn)),
+                search_semaphore: Arc::new(Semaphore::new(10)),
+                ingest_semaphore: Arc::new(Semaphore::new(1)),
+                cached_settings: Default::default(),
+            };
+
+            let retrieved = db.get_excluded_folders().await.unwrap();
+            assert_eq!(
+                retrieved.len(),
+                2,
+                "Folders should persist across database reopens"
+            );
+            assert_eq!(retrieved[0], "folder_1");
+            assert_eq!(retrieved[1], "folder_2");
+        }
     }
 
-    pub async fn search_documents(&self, query: &str, limit: i64) -> Result<Vec<Document>> {
-        self.execute_with_priority(OperationPriority::UserSearch, |conn| {
-            let mut stmt = conn.prepare(
-                "SELECT d.id, d.title, d.content, d.url, d.source, d.created_at, d.embedding, d.is_dead, d.needs_auth, d.profile
-                 FROM documents d
-                 JOIN documents_fts fts ON d.id = fts.rowid
-                 WHERE documents_fts MATCH ?1 AND (d.is_dead IS NULL OR d.is_dead = 0)
-                 ORDER BY rank
-                 LIMIT ?2"
-            )?;
+    #[tokio::test]
+    async fn test_excluded_domains_persistence() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("test_persistence.db");
+
+        {
+            let conn = Connection::open(&db_path).unwrap();
+            let db = Database {
+                conn: Arc::new(Mutex::new(conn)),
+                search_semaphore: Arc::new(Semaphore::new(10)),
+                ingest_semaphore: Arc::new(Semaphore::new(1)),
+                cached_settings: Default::default(),
+            };
+            db.init_schema().await.unwrap();
+
+            let domains = vec!["*.internal.com".to_string(), "localhost".to_string()];
+            db.set_excluded_domains(&domains).await.unwrap();
+        }
 
-            let docs = stmt.query_map(params![query, limit], |row| {
-                Ok(Document {
-                    id: row.get(0)?,
-                    title: row.get(1)?,
-                    content: row.get(2)?,
-                    url: row.get(3)?,
-                    source: row.get(4)?,
-                    created_at: row.get(5)?,
-                    embedding: row.get(6)?,
-                    is_dead: row.get(7)?,
-                    needs_auth: row.get(8)?,
-                    profile: row.get(9)?,
-                })
-            })?;
+        {
+            let conn = Connection::open(&db_path).unwrap();
+            let db = Database {
+                conn: Arc::new(Mutex::new(conn)),
+                search_semaphore: Arc::new(Semaphore::new(10)),
+                ingest_semaphore: Arc::new(Semaphore::new(1)),
+                cached_settings: Default::default(),
+            };
 
-            let mut results = Vec::new();
-            for doc in docs {
-                results.push(doc?);
-            }
-            Ok(results)
-        }).await
+            let retrieved = db.get_excluded_domains().await.unwrap();
+            assert_eq!(
+                retrieved.len(),
+                2,
+                "Domains should persist across database reopens"
+            );
+            assert_eq!(retrieved[0], "*.internal.com");
+            assert_eq!(retrieved[1], "localhost");
+        }
     }
 
-    /// FTS5 search returning each document alongside its positive BM25 score (-rank).
-    /// Higher score = better match. Results are ordered best-first.
-    pub async fn search_documents_scored(
-        &self,
-        query: &str,
-        limit: i64,
-    ) -> Result<Vec<(Document, f64)>> {
-        self.execute_with_priority(OperationPriority::UserSearch, |conn| {
-            let mut stmt = conn.prepare(
-                "SELECT d.id, d.title, d.content, d.url, d.source, d.created_at, d.embedding,
-                        d.is_dead, d.needs_auth, d.profile, -fts.rank AS bm25_score
-                 FROM documents d
-                 JOIN documents_fts fts ON d.id = fts.rowid
-                 WHERE documents_fts MATCH ?1 AND (d.is_dead IS NULL OR d.is_dead = 0)
-                 ORDER BY rank
-                 LIMIT ?2",
-            )?;
+    // -----------------------------------------------------------------------
+    // T006: add_watched_folder / get_watched_folders
+    // -----------------------------------------------------------------------
 
-            let rows = stmt.query_map(params![query, limit], |row| {
-                let doc = Document {
-                    id: row.get(0)?,
-                    title: row.get(1)?,
-                    content: row.get(2)?,
-                    url: row.get(3)?,
-                    source: row.get(4)?,
-                    created_at: row.get(5)?,
-                    embedding: row.get(6)?,
-                    is_dead: row.get(7)?,
-                    needs_auth: row.get(8)?,
-                    profile: row.get(9)?,
-                };
-                let bm25_score: f64 = row.get(10)?;
-                Ok((doc, bm25_score))
-            })?;
+    #[tokio::test]
+    async fn add_and_get_watched_folder() {
+        let (db, _tmp) = create_test_db().await;
+        let path = std::path::Path::new("/tmp/test_folder");
 
-            let mut results = Vec::new();
-            for row in rows {
-                results.push(row?);
-            }
-            Ok(results)
-        })
-        .await
+        let id = db.add_watched_folder(path).await.unwrap();
+        assert!(id > 0);
+
+        let folders = db.get_watched_folders().await.unwrap();
+        assert_eq!(folders.len(), 1);
+        assert_eq!(folders[0].path, path);
+        assert_eq!(
+            folders[0].status,
+            crate::folder_watcher::FolderStatus::Active
+        );
     }
 
-    pub async fn insert_chunk_embedding(
-        &self,
-        document_id: i64,
-        chunk_start: usize,
-        chunk_end: usize,
-        embedding: &[u8],
-        priority: OperationPriority,
-    ) -> Result<i64> {
-        self.execute_with_priority(priority, |conn| {
-            conn.execute(
-                "INSERT INTO embeddings (document_id, chunk_start, chunk_end, embedding)
-                 VALUES (?1, ?2, ?3, ?4)",
-                params![document_id, chunk_start as i64, chunk_end as i64, embedding],
-            )?;
-            Ok(conn.last_insert_rowid())
-        })
-        .await
+    #[tokio::test]
+    async fn add_duplicate_folder_fails() {
+        let (db, _tmp) = create_test_db().await;
+        let path = std::path::Path::new("/tmp/dup_folder");
+
+        db.add_watched_folder(path).await.unwrap();
+        let result = db.add_watched_folder(path).await;
+        assert!(
+            result.is_err(),
+            "duplicate path must fail with UNIQUE constraint"
+        );
     }
 
-    pub async fn get_all_chunk_embeddings(
-        &self,
-    ) -> Result<Vec<(i64, i64, usize, usize, Vec<f32>)>> {
-        self.execute_with_priority(OperationPriority::BackgroundIngest, |conn| {
-            let mut stmt = conn.prepare(
-                "SELECT id, document_id, chunk_start, chunk_end, embedding FROM embeddings ORDER BY document_id, chunk_start"
-            )?;
+    // -----------------------------------------------------------------------
+    // T007: upsert_watched_file / get_watched_file_by_path / get_watched_files_for_folder
+    // -----------------------------------------------------------------------
 
-            let rows = stmt.query_map([], |row| {
-                let id: i64 = row.get(0)?;
-                let document_id: i64 = row.get(1)?;
-                let chunk_start: i64 = row.get(2)?;
-                let chunk_end: i64 = row.get(3)?;
-                let embedding_bytes: Vec<u8> = row.get(4)?;
-                let embedding: Vec<f32> = bincode::deserialize(&embedding_bytes)
-                    .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
-                Ok((id, document_id, chunk_start as usize, chunk_end as usize, embedding))
-            })?;
+    #[tokio::test]
+    async fn upsert_and_get_watched_file() {
+        use crate::folder_watcher::IngestStatus;
+        let (db, _tmp) = create_test_db().await;
+        let folder_id = db
+            .add_watched_folder(std::path::Path::new("/tmp/f1"))
+            .await
+            .unwrap();
+        let file_path = std::path::Path::new("/tmp/f1/note.txt");
 
-            let mut results = Vec::new();
-            for row in rows {
-                results.push(row?);
-            }
-            Ok(results)
-        }).await
-    }
+        db.upsert_watched_file(folder_id, file_path, 1000, None, &IngestStatus::Pending)
+            .await
+            .unwrap();
 
-    pub async fn get_chunk_embeddings_for_document(
-        &self,
-        document_id: i64,
-    ) -> Result<Vec<(i64, usize, usize, Vec<f32>)>> {
-        self.execute_with_priority(OperationPriority::UserSearch, |conn| {
-            let mut stmt = conn.prepare(
-                "SELECT id, chunk_start, chunk_end, embedding
-                 FROM embeddings WHERE document_id = ?1 ORDER BY chunk_start",
-            )?;
+        let wf = db
+            .get_watched_file_by_path(file_path)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(wf.folder_id, folder_id);
+        assert_eq!(wf.file_path, file_path);
+        assert_eq!(wf.modified_at, 1000);
+        assert_eq!(wf.ingest_status, IngestStatus::Pending);
+    }
 
-            let rows = stmt.query_map(params![document_id], |row| {
-                let id: i64 = row.get(0)?;
-                let chunk_start: i64 = row.get(1)?;
-                let chunk_end: i64 = row.get(2)?;
-                let embedding_bytes: Vec<u8> = row.get(3)?;
-                let embedding: Vec<f32> = bincode::deserialize(&embedding_bytes)
-                    .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
-                Ok((id, chunk_start as usize, chunk_end as usize, embedding))
-            })?;
+    #[tokio::test]
+    async fn upsert_updates_existing_file() {
+        use crate::folder_watcher::IngestStatus;
+        let (db, _tmp) = create_test_db().await;
+        let folder_id = db
+            .add_watched_folder(std::path::Path::new("/tmp/f2"))
+            .await
+            .unwrap();
+        let file_path = std::path::Path::new("/tmp/f2/note.txt");
 
-            let mut results = Vec::new();
-            for row in rows {
-                results.push(row?);
-            }
-            Ok(results)
-        })
+        db.upsert_watched_file(folder_id, file_path, 1000, None, &IngestStatus::Pending)
+            .await
+            .unwrap();
+        db.upsert_watched_file(
+            folder_id,
+            file_path,
+            2000,
+            Some(42),
+            &IngestStatus::Ingested,
+        )
         .await
+        .unwrap();
+
+        let wf = db
+            .get_watched_file_by_path(file_path)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(wf.modified_at, 2000);
+        assert_eq!(wf.document_id, Some(42));
+        assert_eq!(wf.ingest_status, IngestStatus::Ingested);
     }
 
-    pub async fn delete_all_embeddings(&self) -> Result<()> {
-        self.execute_with_priority(OperationPriority::BackgroundIngest, |conn| {
-            // Drop and recreate the embeddings table to ensure correct schema
-            // (handles migration from old schema that had chunk_index column)
-            conn.execute("DROP TABLE IF EXISTS embeddings", [])?;
-            conn.execute(
-                "CREATE TABLE embeddings (
-                    id INTEGER PRIMARY KEY AUTOINCREMENT,
-                    document_id INTEGER NOT NULL,
-                    chunk_start INTEGER NOT NULL,
-                    chunk_end INTEGER NOT NULL,
-                    embedding BLOB NOT NULL,
-                    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                    FOREIGN KEY (document_id) REFERENCES documents (id) ON DELETE CASCADE
-                )",
-                [],
-            )?;
-            conn.execute(
-                "CREATE INDEX IF NOT EXISTS idx_embeddings_document_id ON embeddings(document_id)",
-                [],
-            )?;
-            Ok(())
-        })
+    #[tokio::test]
+    async fn get_watched_files_for_folder_returns_all() {
+        use crate::folder_watcher::IngestStatus;
+        let (db, _tmp) = create_test_db().await;
+        let fid = db
+            .add_watched_folder(std::path::Path::new("/tmp/f3"))
+            .await
+            .unwrap();
+
+        db.upsert_watched_file(
+            fid,
+            std::path::Path::new("/tmp/f3/a.txt"),
+            100,
+            None,
+            &IngestStatus::Pending,
+        )
         .await
-    }
-
-    pub async fn url_exists(&self, url: &str, priority: OperationPriority) -> Result<bool> {
-        let normalized = normalize_url(url);
-        self.execute_with_priority(priority, move |conn| {
-            let mut stmt = conn.prepare("SELECT COUNT(*) FROM documents WHERE url = ?1")?;
-            let count: i64 = stmt.query_row(params![normalized], |row| row.get(0))?;
-            Ok(count > 0)
-        })
+        .unwrap();
+        db.upsert_watched_file(
+            fid,
+            std::path::Path::new("/tmp/f3/b.md"),
+            200,
+            None,
+            &IngestStatus::Pending,
+        )
         .await
-    }
+        .unwrap();
 
-    pub async fn count_documents(&self, priority: OperationPriority) -> Result<i64> {
-        self.execute_with_priority(priority, |conn| {
-            let count: i64 =
-                conn.query_row("SELECT COUNT(*) FROM documents", [], |row| row.get(0))?;
-            Ok(count)
-        })
-        .await
+        let files = db.get_watched_files_for_folder(fid).await.unwrap();
+        assert_eq!(files.len(), 2);
     }
 
-    // Batch insert method for efficient bookmark ingestion
-    #[allow(clippy::type_complexity)]
-    pub async fn batch_insert_documents<'a>(
-        &self,
-        documents: &[(
-            &'a str,
-            &'a str,
-            Option<&'a str>,
-            &'a str,
-            Option<&'a [u8]>,
-            Option<bool>,
-        )],
-    ) -> Result<Vec<i64>> {
-        self.execute_with_priority(OperationPriority::BackgroundIngest, |conn| {
-            let transaction = conn.unchecked_transaction()?;
+    // -----------------------------------------------------------------------
+    // T008: delete_watched_folder CASCADE + delete_document
+    // -----------------------------------------------------------------------
 
-            let mut ids = Vec::new();
-            {
-                let mut stmt = transaction.prepare(
-                    "INSERT INTO documents (title, content, url, source, embedding, is_dead) VALUES (?1, ?2, ?3, ?4, ?5, ?6)"
-                )?;
+    #[tokio::test]
+    async fn delete_watched_folder_cascades_files() {
+        use crate::folder_watcher::IngestStatus;
+        let (db, _tmp) = create_test_db().await;
+        let fid = db
+            .add_watched_folder(std::path::Path::new("/tmp/del_f"))
+            .await
+            .unwrap();
 
-                for (title, content, url, source, embedding, is_dead) in documents {
-                    stmt.execute(params![title, content, url, source, embedding, is_dead])?;
-                    ids.push(transaction.last_insert_rowid());
+        db.upsert_watched_file(
+            fid,
+            std::path::Path::new("/tmp/del_f/a.txt"),
+            1,
+            None,
+            &IngestStatus::Pending,
+        )
+        .await
+        .unwrap();
 
-                    // Yield periodically during batch operations
-                    if ids.len() % 10 == 0 {
-                        std::thread::yield_now();
-                    }
-                }
-            } // stmt is dropped here
+        // Verify file exists
+        assert!(db
+            .get_watched_file_by_path(std::path::Path::new("/tmp/del_f/a.txt"))
+            .await
+            .unwrap()
+            .is_some());
 
-            transaction.commit()?;
-            Ok(ids)
-        }).await
-    }
+        db.delete_watched_folder(fid).await.unwrap();
 
-    pub async fn mark_url_as_dead(&self, url: &str) -> Result<()> {
-        let normalized = normalize_url(url);
-        self.execute_with_priority(OperationPriority::BackgroundIngest, move |conn| {
-            conn.execute(
-                "UPDATE documents SET is_dead = 1 WHERE url = ?1",
-                params![normalized],
-            )?;
-            Ok(())
-        })
-        .await
+        // CASCADE should have removed the file row
+        assert!(db
+            .get_watched_file_by_path(std::path::Path::new("/tmp/del_f/a.txt"))
+            .await
+            .unwrap()
+            .is_none());
+        assert!(db.get_watched_folders().await.unwrap().is_empty());
     }
 
-    pub async fn mark_url_as_needs_auth(&self, url: &str) -> Result<()> {
-        let normalized = normalize_url(url);
-        self.execute_with_priority(OperationPriority::BackgroundIngest, move |conn| {
-            conn.execute(
-                "UPDATE documents SET needs_auth = 1 WHERE url = ?1",
-                params![normalized],
-            )?;
-            Ok(())
-        })
-        .await
-    }
+    #[tokio::test]
+    async fn delete_document_removes_from_all_tables() {
+        let (db, _tmp) = create_test_db().await;
 
-    pub async fn get_document_by_url(&self, url: &str) -> Result<Option<Document>> {
-        let normalized = normalize_url(url);
-        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
-            let mut stmt = conn.prepare(
-                "SELECT id, title, content, url, source, created_at, embedding, is_dead, needs_auth, profile
-                 FROM documents WHERE url = ?1 LIMIT 1",
-            )?;
+        let doc_id = db
+            .insert_document(
+                "Test",
+                "content",
+                None,
+                "source",
+                None,
+                None,
+                OperationPriority::BackgroundIngest,
+                None,
+            )
+            .await
+            .unwrap();
 
-            match stmt.query_row(params![normalized], |row| {
-                Ok(Document {
-                    id: row.get(0)?,
-                    title: row.get(1)?,
-                    content: row.get(2)?,
-                    url: row.get(3)?,
-                    source: row.get(4)?,
-                    created_at: row.get(5)?,
-                    embedding: row.get(6)?,
-                    is_dead: row.get(7)?,
-                    needs_auth: row.get(8)?,
-                    profile: row.get(9)?,
-                })
-            }) {
-                Ok(doc) => Ok(Some(doc)),
-                Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-                Err(e) => Err(Box::new(e)),
-            }
-        })
-        .await
-    }
+        // Verify document exists
+        assert!(db.get_document(doc_id).await.unwrap().is_some());
 
-    pub async fn update_document_content(
-        &self,
-        doc_id: i64,
-        title: &str,
-        content: &str,
-    ) -> Result<()> {
-        self.execute_with_priority(OperationPriority::BackgroundIngest, |conn| {
-            conn.execute(
-                "UPDATE documents SET title = ?1, content = ?2, is_dead = 0, needs_auth = 0
-                 WHERE id = ?3",
-                params![title, content, doc_id],
-            )?;
-            // Update FTS index
-            conn.execute(
-                "UPDATE documents_fts SET title = ?1, content = ?2 WHERE rowid = ?3",
-                params![title, content, doc_id],
-            )?;
-            Ok(())
-        })
-        .await
-    }
+        db.delete_document(doc_id).await.unwrap();
 
-    pub async fn delete_embeddings_for_document(&self, doc_id: i64) -> Result<()> {
-        self.execute_with_priority(OperationPriority::BackgroundIngest, |conn| {
-            conn.execute(
-                "DELETE FROM embeddings WHERE document_id = ?1",
-                params![doc_id],
-            )?;
-            Ok(())
-        })
-        .await
+        assert!(db.get_document(doc_id).await.unwrap().is_none());
     }
 
-    pub async fn get_live_documents_with_urls(&self) -> Result<Vec<Document>> {
-        self.execute_with_priority(OperationPriority::BackgroundIngest, |conn| {
-            let mut stmt = conn.prepare(
-                "SELECT id, title, content, url, source, created_at, embedding, is_dead, needs_auth, profile
-                 FROM documents
-                 WHERE url IS NOT NULL AND (is_dead IS NULL OR is_dead = 0)",
-            )?;
+    // -----------------------------------------------------------------------
+    // T045: delete_documents_by_source
+    // -----------------------------------------------------------------------
 
-            let docs = stmt.query_map([], |row| {
-                Ok(Document {
-                    id: row.get(0)?,
-                    title: row.get(1)?,
-                    content: row.get(2)?,
-                    url: row.get(3)?,
-                    source: row.get(4)?,
-                    created_at: row.get(5)?,
-                    embedding: row.get(6)?,
-                    is_dead: row.get(7)?,
-                    needs_auth: row.get(8)?,
-                    profile: row.get(9)?,
-                })
-            })?;
+    #[tokio::test]
+    async fn delete_documents_by_source_removes_matching_docs() {
+        let (db, _tmp) = create_test_db().await;
+        let folder_path = std::path::Path::new("/tmp/source_folder");
 
-            let mut results = Vec::new();
-            for doc in docs {
-                results.push(doc?);
-            }
-            Ok(results)
-        })
+        db.insert_document(
+            "Doc1",
+            "content1",
+            None,
+            &folder_path.to_string_lossy(),
+            None,
+            None,
+            OperationPriority::BackgroundIngest,
+            None,
+        )
+        .await
+        .unwrap();
+        db.insert_document(
+            "Doc2",
+            "content2",
+            None,
+            &folder_path.to_string_lossy(),
+            None,
+            None,
+            OperationPriority::BackgroundIngest,
+            None,
+        )
+        .await
+        .unwrap();
+        db.insert_document(
+            "Other",
+            "other",
+            None,
+            "other_source",
+            None,
+            None,
+            OperationPriority::BackgroundIngest,
+            None,
+        )
         .await
+        .unwrap();
+
+        let deleted_ids = db.delete_documents_by_source(folder_path).await.unwrap();
+        assert_eq!(deleted_ids.len(), 2);
+
+        // The "Other" document must still exist
+        let remaining = db.get_all_documents().await.unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].source, "other_source");
     }
 
-    pub async fn check_and_mark_dead_urls(&self) -> Result<u32> {
-        let documents = self.get_live_documents_with_urls().await?;
-        let client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(10))
-            .user_agent("LocalMind/1.0")
-            .build()
-            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
-
-        let mut marked_count = 0;
-
-        for doc in documents {
-            if let Some(url) = &doc.url {
-                match client.head(url).send().await {
-                    Ok(response) => {
-                        let status = response.status();
-                        if status == reqwest::StatusCode::NOT_FOUND {
-                            println!("Marking {} as dead (404)", url);
-                            self.mark_url_as_dead(url).await?;
-                            marked_count += 1;
-                        } else if status == reqwest::StatusCode::UNAUTHORIZED
-                            || status == reqwest::StatusCode::FORBIDDEN
-                        {
-                            println!("Marking {} as needs auth ({})", url, status);
-                            self.mark_url_as_needs_auth(url).await?;
-                            marked_count += 1;
-                        }
-                    }
-                    Err(e) => {
-                        println!("Warning: error checking {}: {}", url, e);
-                        // Don't mark as dead for network errors, only for explicit status codes
-                    }
-                }
+    // -----------------------------------------------------------------------
+    // Ingest queue: enqueue / resume / failure tracking
+    // -----------------------------------------------------------------------
 
-                // Small delay to avoid overwhelming servers
-                tokio::time::sleep(std::time::Duration::from_millis(100)).await;
-            }
-        }
+    #[tokio::test]
+    async fn enqueue_bookmarks_skips_already_queued_urls() {
+        let (db, _tmp) = create_test_db().await;
+        let bookmarks = vec![
+            ("A".to_string(), "https://a.example".to_string()),
+            ("B".to_string(), "https://b.example".to_string()),
+        ];
+
+        assert_eq!(
+            db.enqueue_bookmarks(&bookmarks, Some("Will"))
+                .await
+                .unwrap(),
+            2
+        );
+        assert_eq!(
+            db.enqueue_bookmarks(&bookmarks, Some("Will"))
+                .await
+                .unwrap(),
+            0
+        );
 
-        Ok(marked_count)
+        let pending = db
+            .get_pending_queue_items(Some("Will"), crate::ingest_queue::MAX_ATTEMPTS)
+            .await
+            .unwrap();
+        assert_eq!(pending.len(), 2);
+        assert_eq!(pending[0].title, "A");
     }
 
-    pub async fn get_all_documents(&self) -> Result<Vec<Document>> {
-        self.execute_with_priority(OperationPriority::BackgroundIngest, |conn| {
-            let mut stmt = conn.prepare(
-                "SELECT id, title, content, url, source, created_at, embedding, is_dead, needs_auth, profile
-                 FROM documents
-                 WHERE is_dead IS NULL OR is_dead = 0
-                 ORDER BY id",
-            )?;
+    #[tokio::test]
+    async fn interrupted_and_done_items_resume_correctly() {
+        use crate::ingest_queue::{QueueStatus, MAX_ATTEMPTS};
+        let (db, _tmp) = create_test_db().await;
+        let bookmarks = vec![
+            ("A".to_string(), "https://a.example".to_string()),
+            ("B".to_string(), "https://b.example".to_string()),
+        ];
+        db.enqueue_bookmarks(&bookmarks, None).await.unwrap();
+        let items = db
+            .get_pending_queue_items(None, MAX_ATTEMPTS)
+            .await
+            .unwrap();
 
-            let docs = stmt.query_map([], |row| {
-                Ok(Document {
-                    id: row.get(0)?,
-                    title: row.get(1)?,
-                    content: row.get(2)?,
-                    url: row.get(3)?,
-                    source: row.get(4)?,
-                    created_at: row.get(5)?,
-                    embedding: row.get(6)?,
-                    is_dead: row.get(7)?,
-                    needs_auth: row.get(8)?,
-                    profile: row.get(9)?,
-                })
-            })?;
+        // A finished, B was mid-flight when the app closed
+        db.set_queue_item_status(items[0].id, &QueueStatus::Done, None)
+            .await
+            .unwrap();
+        db.set_queue_item_status(items[1].id, &QueueStatus::InProgress, None)
+            .await
+            .unwrap();
 
-            let mut results = Vec::new();
-            for doc in docs {
-                results.push(doc?);
-            }
-            Ok(results)
-        })
-        .await
+        let resumed = db
+            .get_pending_queue_items(None, MAX_ATTEMPTS)
+            .await
+            .unwrap();
+        assert_eq!(resumed.len(), 1);
+        assert_eq!(resumed[0].url, "https://b.example");
     }
 
-    pub async fn update_chunk_embedding(
-        &self,
-        embedding_id: i64,
-        embedding_bytes: &[u8],
-        priority: OperationPriority,
-    ) -> Result<()> {
-        self.execute_with_priority(priority, move |conn| {
-            conn.execute(
-                "UPDATE embeddings SET embedding = ?1 WHERE id = ?2",
-                params![embedding_bytes, embedding_id],
-            )?;
-            Ok(())
-        })
-        .await
-    }
+    #[tokio::test]
+    async fn failed_items_stop_after_max_attempts_and_can_be_retried() {
+        use crate::ingest_queue::QueueStatus;
+        let (db, _tmp) = create_test_db().await;
+        db.enqueue_bookmarks(&[("A".to_string(), "https://a.example".to_string())], None)
+            .await
+            .unwrap();
+        let id = db.get_pending_queue_items(None, 2).await.unwrap()[0].id;
 
-    pub async fn set_config(&self, key: &str, value: &str) -> Result<()> {
-        self.execute_with_priority(OperationPriority::UserSearch, |conn| {
-            conn.execute(
-                "INSERT OR REPLACE INTO config (key, value, updated_at) VALUES (?1, ?2, CURRENT_TIMESTAMP)",
-                params![key, value],
-            )?;
-            Ok(())
-        }).await
-    }
+        for _ in 0..2 {
+            db.set_queue_item_status(id, &QueueStatus::Failed, Some("timeout"))
+                .await
+                .unwrap();
+        }
+        assert!(db
+            .get_pending_queue_items(None, 2)
+            .await
+            .unwrap()
+            .is_empty());
 
-    pub async fn get_config(&self, key: &str) -> Result<Option<String>> {
-        self.execute_with_priority(OperationPriority::UserSearch, |conn| {
-            let mut stmt = conn.prepare("SELECT value FROM config WHERE key = ?1")?;
+        let failed = db
+            .get_queue_items_by_status(&QueueStatus::Failed)
+            .await
+            .unwrap();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].attempts, 2);
+        assert_eq!(failed[0].last_error.as_deref(), Some("timeout"));
 
-            match stmt.query_row(params![key], |row| row.get(0)) {
-                Ok(value) => Ok(Some(value)),
-                Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-                Err(e) => Err(Box::new(e) as Box<dyn std::error::Error + Send + Sync>),
-            }
-        })
-        .await
+        assert_eq!(db.retry_failed_queue_items().await.unwrap(), 1);
+        assert_eq!(db.get_pending_queue_items(None, 2).await.unwrap().len(), 1);
     }
 
-    pub async fn get_embedding_model(&self) -> Result<Option<String>> {
-        self.get_config("embedding_model").await
-    }
+    #[tokio::test]
+    async fn done_item_is_requeued_when_document_was_deleted() {
+        use crate::ingest_queue::{QueueStatus, MAX_ATTEMPTS};
+        let (db, _tmp) = create_test_db().await;
+        let bookmarks = vec![("A".to_string(), "https://a.example".to_string())];
+        db.enqueue_bookmarks(&bookmarks, None).await.unwrap();
+        let id = db
+            .get_pending_queue_items(None, MAX_ATTEMPTS)
+            .await
+            .unwrap()[0]
+            .id;
+        db.set_queue_item_status(id, &QueueStatus::Done, None)
+            .await
+            .unwrap();
 
-    pub async fn set_embedding_model(&self, model: &str) -> Result<()> {
-        self.set_config("embedding_model", model).await
+        // No matching document exists, so the done row is reset to pending
+        assert_eq!(db.enqueue_bookmarks(&bookmarks, None).await.unwrap(), 1);
+        assert_eq!(
+            db.get_pending_queue_items(None, MAX_ATTEMPTS)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
     }
 
-    pub async fn get_embedding_url(&self) -> Result<Option<String>> {
-        self.get_config("embedding_url").await
-    }
+    // -----------------------------------------------------------------------
+    // Ingest errors: failed-import report, ignore, retry, legacy migration
+    // -----------------------------------------------------------------------
 
-    pub async fn set_embedding_url(&self, url: &str) -> Result<()> {
-        self.set_config("embedding_url", url).await
-    }
+    #[tokio::test]
+    async fn record_ingest_error_increments_attempts_and_clears() {
+        let (db, _tmp) = create_test_db().await;
+        db.record_ingest_error("https://a.example", "A", None, "HTTP 500")
+            .await
+            .unwrap();
+        db.record_ingest_error("https://a.example", "A", None, "HTTP 502")
+            .await
+            .unwrap();
 
-    pub async fn get_excluded_folders(&self) -> Result<Vec<String>> {
-        match self.get_config("bookmark_exclude_folders").await? {
-            Some(json_str) => {
-                let folders: Vec<String> = serde_json::from_str(&json_str)
-                    .map_err(|e| format!("Failed to parse excluded folders: {}", e))?;
-                Ok(folders)
-            }
-            None => Ok(Vec::new()),
-        }
-    }
+        let errors = db.get_ingest_errors().await.unwrap();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].attempts, 2);
+        assert_eq!(errors[0].error, "HTTP 502");
 
-    pub async fn set_excluded_folders(&self, folders: &[String]) -> Result<()> {
-        let json_str = serde_json::to_string(folders)
-            .map_err(|e| format!("Failed to serialize excluded folders: {}", e))?;
-        self.set_config("bookmark_exclude_folders", &json_str).await
+        db.clear_ingest_error("https://a.example").await.unwrap();
+        assert!(db.get_ingest_errors().await.unwrap().is_empty());
     }
 
-    pub async fn get_excluded_domains(&self) -> Result<Vec<String>> {
-        match self.get_config("bookmark_exclude_domains").await? {
-            Some(json_str) => {
-                let domains: Vec<String> = serde_json::from_str(&json_str)
-                    .map_err(|e| format!("Failed to parse excluded domains: {}", e))?;
-                Ok(domains)
-            }
-            None => Ok(Vec::new()),
-        }
-    }
+    #[tokio::test]
+    async fn ignored_ingest_error_is_hidden_and_not_requeued() {
+        use crate::ingest_queue::{QueueStatus, MAX_ATTEMPTS};
+        let (db, _tmp) = create_test_db().await;
+        db.enqueue_bookmarks(&[("A".to_string(), "https://a.example".to_string())], None)
+            .await
+            .unwrap();
+        let item = db
+            .get_pending_queue_items(None, MAX_ATTEMPTS)
+            .await
+            .unwrap()
+            .remove(0);
+        db.set_queue_item_status(item.id, &QueueStatus::Failed, Some("HTTP 404"))
+            .await
+            .unwrap();
+        db.record_ingest_error(&item.url, &item.title, None, "HTTP 404")
+            .await
+            .unwrap();
 
-    pub async fn set_excluded_domains(&self, domains: &[String]) -> Result<()> {
-        let json_str = serde_json::to_string(domains)
-            .map_err(|e| format!("Failed to serialize excluded domains: {}", e))?;
-        self.set_config("bookmark_exclude_domains", &json_str).await
-    }
+        let error_id = db.get_ingest_errors().await.unwrap()[0].id;
+        db.ignore_ingest_error(error_id).await.unwrap();
 
-    pub async fn delete_bookmarks_by_url_pattern(&self, pattern: &str) -> Result<usize> {
-        use crate::bookmark_exclusion::ExclusionRules;
+        assert!(db.get_ingest_errors().await.unwrap().is_empty());
+        assert!(db
+            .get_pending_queue_items(None, MAX_ATTEMPTS)
+            .await
+            .unwrap()
+            .is_empty());
+    }
 
-        let rules = ExclusionRules::new(vec![], vec![pattern.to_string()]);
-        let documents = self.get_live_documents_with_urls().await?;
-
-        let mut deleted_count = 0;
-        for doc in documents {
-            if let Some(url) = &doc.url {
-                if rules.is_url_excluded(url) {
-                    self.execute_with_priority(OperationPriority::BackgroundIngest, |conn| {
-                        conn.execute("DELETE FROM documents WHERE id = ?1", params![doc.id])?;
-                        Ok(())
-                    })
-                    .await?;
-                    deleted_count += 1;
-                }
-            }
+    #[tokio::test]
+    async fn requeue_ingest_error_resets_queue_item() {
+        use crate::ingest_queue::{QueueStatus, MAX_ATTEMPTS};
+        let (db, _tmp) = create_test_db().await;
+        db.enqueue_bookmarks(&[("A".to_string(), "https://a.example".to_string())], None)
+            .await
+            .unwrap();
+        let item_id = db
+            .get_pending_queue_items(None, MAX_ATTEMPTS)
+            .await
+            .unwrap()[0]
+            .id;
+        for _ in 0..MAX_ATTEMPTS {
+            db.set_queue_item_status(item_id, &QueueStatus::Failed, Some("timeout"))
+                .await
+                .unwrap();
         }
+        db.record_ingest_error("https://a.example", "A", None, "timeout")
+            .await
+            .unwrap();
+        let error_id = db.get_ingest_errors().await.unwrap()[0].id;
 
-        Ok(deleted_count)
+        let item = db.requeue_ingest_error(error_id).await.unwrap().unwrap();
+        assert_eq!(item.id, item_id);
+        assert_eq!(item.status, QueueStatus::Pending);
+        assert_eq!(item.attempts, 0);
     }
 
-    pub async fn delete_bookmarks_by_folder(&self, folder_id: &str) -> Result<usize> {
-        use crate::bookmark::BookmarkMonitor;
+    #[tokio::test]
+    async fn init_schema_moves_error_placeholders_out_of_index() {
+        let (db, _tmp) = create_test_db().await;
+        db.insert_document(
+            "Broken",
+            "Broken\n\nBookmark: Broken\nURL: https://broken.example\n\n[Error fetching content: timed out]",
+            Some("https://broken.example"),
+            "chrome_bookmark",
+            None,
+            None,
+            OperationPriority::BackgroundIngest,
+            None,
+        )
+        .await
+        .unwrap();
+        db.insert_document(
+            "Good",
+            "Real content",
+            Some("https://good.example"),
+            "chrome_bookmark",
+            None,
+            None,
+            OperationPriority::BackgroundIngest,
+            None,
+        )
+        .await
+        .unwrap();
 
-        // Get bookmark monitor to parse Chrome bookmarks
-        let monitor = BookmarkMonitor::new()?.0;
+        // Migration runs on every schema init
+        db.init_schema().await.unwrap();
 
-        // Get all bookmarks from Chrome
-        let all_bookmarks = monitor.get_bookmark_roots()?;
+        let docs = db.get_all_documents().await.unwrap();
+        assert_eq!(docs.len(), 1);
+        assert_eq!(docs[0].title, "Good");
 
-        // Find the specific folder and collect all URLs in it
-        let mut urls_to_delete = Vec::new();
+        let errors = db.get_ingest_errors().await.unwrap();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].url, "https://broken.example");
+        assert_eq!(errors[0].error, "timed out");
+    }
 
-        fn find_and_collect_urls(
-            item: &crate::bookmark::BookmarkItem,
-            target_folder_id: &str,
-            urls: &mut Vec<String>,
-        ) -> bool {
-            // Check if this is the target folder
-            if item.id == target_folder_id {
-                println!("Found folder '{}' (ID: {})", item.name, item.id);
-                // Collect all URLs in this folder and subfolders
-                collect_all_urls(item, urls);
-                return true;
-            }
+    // -----------------------------------------------------------------------
+    // Dead-link checker: retry policy, recovery, scheduling config
+    // -----------------------------------------------------------------------
 
-            // Recursively search in children
-            if let Some(children) = &item.children {
-                for child in children {
-                    if find_and_collect_urls(child, target_folder_id, urls) {
-                        return true;
-                    }
-                }
-            }
+    async fn insert_link_doc(db: &Database, url: &str) -> i64 {
+        db.insert_document(
+            "Page",
+            "content",
+            Some(url),
+            "chrome_bookmark",
+            None,
+            None,
+            OperationPriority::BackgroundIngest,
+            None,
+        )
+        .await
+        .unwrap()
+    }
 
-            false
-        }
+    #[tokio::test]
+    async fn transient_failures_mark_dead_only_after_max() {
+        use crate::link_checker::{LinkChange, LinkOutcome};
+        let (db, _tmp) = create_test_db().await;
+        let id = insert_link_doc(&db, "https://flaky.example").await;
+        let timeout = LinkOutcome::Transient("timeout".to_string());
 
-        fn collect_all_urls(item: &crate::bookmark::BookmarkItem, urls: &mut Vec<String>) {
-            if let Some(url) = &item.url {
-                if !url.is_empty() {
-                    urls.push(url.clone());
-                }
-            }
-            if let Some(children) = &item.children {
-                for child in children {
-                    collect_all_urls(child, urls);
-                }
-            }
+        for _ in 0..2 {
+            let change = db.apply_link_check_outcome(id, &timeout, 3).await.unwrap();
+            assert_eq!(change, LinkChange::Unchanged);
         }
+        let change = db.apply_link_check_outcome(id, &timeout, 3).await.unwrap();
+        assert_eq!(change, LinkChange::MarkedDead);
+        assert_eq!(
+            db.get_document(id).await.unwrap().unwrap().is_dead,
+            Some(true)
+        );
+    }
+
+    #[tokio::test]
+    async fn alive_link_resets_failures_and_recovers() {
+        use crate::link_checker::{LinkChange, LinkOutcome};
+        let (db, _tmp) = create_test_db().await;
+        let id = insert_link_doc(&db, "https://moved.example").await;
+
+        let change = db
+            .apply_link_check_outcome(id, &LinkOutcome::Gone, 3)
+            .await
+            .unwrap();
+        assert_eq!(change, LinkChange::MarkedDead);
+
+        let change = db
+            .apply_link_check_outcome(id, &LinkOutcome::Alive, 3)
+            .await
+            .unwrap();
+        assert_eq!(change, LinkChange::Recovered);
+        assert_eq!(
+            db.get_document(id).await.unwrap().unwrap().is_dead,
+            Some(false)
+        );
 
-        // Search through all bookmark roots
-        for item in &all_bookmarks {
-            if find_and_collect_urls(item, folder_id, &mut urls_to_delete) {
-                break;
-            }
-        }
+        let targets = db.get_link_check_targets().await.unwrap();
+        assert_eq!(targets.len(), 1);
+        assert_eq!(targets[0].failures, 0);
+    }
 
-        println!(
-            "Found {} URLs in Chrome for folder {}",
-            urls_to_delete.len(),
-            folder_id
+    #[tokio::test]
+    async fn link_check_targets_include_dead_and_skip_local_files() {
+        use crate::link_checker::LinkOutcome;
+        let (db, _tmp) = create_test_db().await;
+        let dead = insert_link_doc(&db, "https://gone.example").await;
+        insert_link_doc(&db, "file:///home/user/notes.md").await;
+        db.apply_link_check_outcome(dead, &LinkOutcome::Gone, 3)
+            .await
+            .unwrap();
+
+        let targets = db.get_link_check_targets().await.unwrap();
+        assert_eq!(targets.len(), 1);
+        assert_eq!(targets[0].doc_id, dead);
+        assert!(targets[0].is_dead);
+    }
+
+    #[tokio::test]
+    async fn dead_link_interval_defaults_and_persists() {
+        use crate::link_checker::DEFAULT_INTERVAL_HOURS;
+        let (db, _tmp) = create_test_db().await;
+        assert_eq!(
+            db.get_dead_link_check_interval_hours().await.unwrap(),
+            DEFAULT_INTERVAL_HOURS
         );
+        assert!(db.get_dead_link_check_last_run().await.unwrap().is_none());
 
-        // Check how many actually exist in the database
-        let mut exists_count = 0;
-        for url in &urls_to_delete {
-            let count: i64 = self.execute_with_priority(OperationPriority::BackgroundIngest, {
-                let url = url.clone();
-                move |conn| {
-                    let count: i64 = conn.query_row(
-                        "SELECT COUNT(*) FROM documents WHERE url = ?1 AND source = 'chrome_bookmark'",
-                        params![&url],
-                        |row| row.get(0)
-                    )?;
-                    Ok(count)
-                }
-            }).await?;
+        db.set_dead_link_check_interval_hours(0).await.unwrap();
+        db.set_dead_link_check_last_run().await.unwrap();
+        assert_eq!(db.get_dead_link_check_interval_hours().await.unwrap(), 0);
+        assert!(db.get_dead_link_check_last_run().await.unwrap().is_some());
+    }
 
-            if count > 0 {
-                exists_count += 1;
-            }
-        }
+    #[tokio::test]
+    async fn embedding_settings_default_to_local_server_and_persist() {
+        use crate::embedding_provider::{EmbeddingProvider, EmbeddingSettings};
+        let (db, _tmp) = create_test_db().await;
+        assert_eq!(
+            db.get_embedding_settings().await.unwrap(),
+            EmbeddingSettings::default()
+        );
 
-        println!(
-            "Of those, {} URLs exist in database for folder {}",
-            exists_count, folder_id
-        );
-
-        // Delete each URL from the database
-        let mut deleted_count = 0;
-        for url in urls_to_delete {
-            let result = self
-                .execute_with_priority(OperationPriority::BackgroundIngest, move |conn| {
-                    conn.execute(
-                        "DELETE FROM documents WHERE url = ?1 AND source = 'chrome_bookmark'",
-                        params![&url],
-                    )?;
-                    Ok(())
-                })
-                .await;
+        let settings = EmbeddingSettings {
+            provider: EmbeddingProvider::Ollama,
+            url: "http://localhost:11434".to_string(),
+            model: "nomic-embed-text".to_string(),
+        };
+        db.set_embedding_settings(&settings).await.unwrap();
+        assert_eq!(db.get_embedding_settings().await.unwrap(), settings);
 
-            if result.is_ok() {
-                deleted_count += 1;
-            }
+        assert!(db.get_embedding_server_command().await.unwrap().is_none());
+        db.set_embedding_server_command(" python3 server.py ")
+            .await
+            .unwrap();
+        assert_eq!(
+            db.get_embedding_server_command().await.unwrap().as_deref(),
+            Some("python3 server.py")
+        );
+        db.set_embedding_server_command("").await.unwrap();
+        assert!(db.get_embedding_server_command().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn notification_opt_outs_default_on_and_persist() {
+        use crate::notifications::NotificationEvent;
+        let (db, _tmp) = create_test_db().await;
+        for event in NotificationEvent::ALL {
+            assert!(db.is_notification_enabled(event).await.unwrap());
         }
 
-        Ok(deleted_count)
+        db.set_notification_enabled(NotificationEvent::DiskSpaceLow, false)
+            .await
+            .unwrap();
+        assert!(!db
+            .is_notification_enabled(NotificationEvent::DiskSpaceLow)
+            .await
+            .unwrap());
+        assert!(db
+            .is_notification_enabled(NotificationEvent::IngestionComplete)
+            .await
+            .unwrap());
     }
 
     // -----------------------------------------------------------------------
-    // Folder-watch CRUD (T009-T016, T046)
+    // Transactional chunk writes and startup consistency check
     // -----------------------------------------------------------------------
 
-    /// Create an in-memory database suitable for unit tests.
-    ///
-    /// Same schema as the live database; schema is initialized synchronously
-    /// within a temporary tokio runtime so callers don't need async.
-    #[cfg(test)]
-    pub fn new_in_memory_sync() -> Self {
-        let conn = Connection::open_in_memory().expect("in-memory SQLite");
-        let db = Self {
-            conn: Arc::new(Mutex::new(conn)),
-            search_semaphore: Arc::new(Semaphore::new(10)),
-            ingest_semaphore: Arc::new(Semaphore::new(1)),
-        };
-        // Run schema init synchronously via a temporary runtime
-        let rt = tokio::runtime::Builder::new_current_thread()
-            .enable_all()
-            .build()
+    #[tokio::test]
+    async fn insert_document_with_chunks_is_complete() {
+        let (db, _tmp) = create_test_db().await;
+        let embedding = bincode::serialize(&vec![0.5f32, 0.25]).unwrap();
+        let chunks = vec![
+            (0, 10, embedding.clone(), ChunkProvenance::default()),
+            (10, 20, embedding, ChunkProvenance::default()),
+        ];
+        let (doc_id, embedding_ids) = db
+            .insert_document_with_chunks(
+                "Doc",
+                "content",
+                Some("https://a.example"),
+                "test",
+                None,
+                true,
+                &chunks,
+            )
+            .await
             .unwrap();
-        rt.block_on(db.init_schema()).expect("init_schema");
-        db
+
+        assert_eq!(embedding_ids.len(), 2);
+        assert_eq!(
+            db.get_chunk_embeddings_for_document(doc_id)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+        assert_eq!(
+            db.get_document(doc_id).await.unwrap().unwrap().needs_auth,
+            Some(true)
+        );
+        assert!(db.get_incomplete_documents().await.unwrap().is_empty());
     }
 
-    /// Insert a watched folder, returning its new row ID.
-    ///
-    /// Returns `Err` (rusqlite unique constraint) if the path already exists.
-    pub async fn add_watched_folder(&self, path: &std::path::Path) -> Result<i64> {
-        let path_str = path.to_string_lossy().to_string();
-        let created_at = chrono_utc_now();
-        self.execute_with_priority(OperationPriority::BackgroundIngest, move |conn| {
-            conn.execute(
-                "INSERT INTO watched_folders (path, status, created_at)
-                 VALUES (?1, 'active', ?2)",
-                params![path_str, created_at],
-            )?;
-            Ok(conn.last_insert_rowid())
-        })
-        .await
+    #[tokio::test]
+    async fn ingest_policies_round_trip() {
+        use crate::ingest_policy::{IngestPolicy, SourceKind};
+
+        let (db, _tmp) = create_test_db().await;
+        assert_eq!(
+            db.get_ingest_policy(SourceKind::Feeds).await.unwrap(),
+            IngestPolicy::default()
+        );
+
+        let policy = IngestPolicy {
+            fetch_remote: false,
+            max_content_chars: 5000,
+            auto_summarize: true,
+            default_tags: vec!["feeds".to_string()],
+        };
+        db.set_ingest_policy(SourceKind::Feeds, &policy)
+            .await
+            .unwrap();
+        let policies = db.get_ingest_policies().await.unwrap();
+        assert_eq!(policies.get(SourceKind::Feeds), policy);
+        assert_eq!(policies.get(SourceKind::Bookmarks), IngestPolicy::default());
+
+        let doc_id = insert_test_doc(&db, "Doc", "content").await;
+        db.set_document_summary(doc_id, "A summary.").await.unwrap();
+        assert_eq!(
+            db.get_document_metadata(doc_id)
+                .await
+                .unwrap()
+                .unwrap()
+                .summary
+                .as_deref(),
+            Some("A summary.")
+        );
     }
 
-    /// Return all watched folders ordered by creation time.
-    pub async fn get_watched_folders(&self) -> Result<Vec<crate::folder_watcher::WatchedFolder>> {
-        use crate::folder_watcher::{FolderStatus, WatchedFolder};
-        self.execute_with_priority(OperationPriority::UserSearch, |conn| {
-            let mut stmt = conn.prepare(
-                "SELECT id, path, status, created_at FROM watched_folders ORDER BY created_at",
-            )?;
-            let rows = stmt.query_map([], |row| {
-                let id: i64 = row.get(0)?;
-                let path: String = row.get(1)?;
-                let status_str: String = row.get(2)?;
-                let created_at: String = row.get(3)?;
-                Ok(WatchedFolder {
-                    id,
-                    path: std::path::PathBuf::from(path),
-                    status: FolderStatus::from_db_str(&status_str),
-                    created_at,
-                })
-            })?;
-            let mut out = Vec::new();
-            for row in rows {
-                out.push(row?);
-            }
-            Ok(out)
-        })
-        .await
+    #[tokio::test]
+    async fn storage_usage_and_prune_order() {
+        let (db, _tmp) = create_test_db().await;
+        assert_eq!(db.get_max_index_bytes().await.unwrap(), 0);
+        db.set_max_index_bytes(1 << 30).await.unwrap();
+        assert_eq!(db.get_max_index_bytes().await.unwrap(), 1 << 30);
+
+        let opened = insert_test_doc(&db, "Opened", "opened content").await;
+        let never_opened = insert_test_doc(&db, "Never opened", "unread content").await;
+        let favorite = insert_test_doc(&db, "Favorite", "kept content").await;
+        let dead = db
+            .insert_document(
+                "Dead",
+                "dead content",
+                Some("https://gone.example.com"),
+                "test",
+                None,
+                None,
+                OperationPriority::UserSearch,
+                None,
+            )
+            .await
+            .unwrap();
+        db.mark_url_as_dead("https://gone.example.com")
+            .await
+            .unwrap();
+        db.record_document_view(opened).await.unwrap();
+        db.record_document_view(dead).await.unwrap();
+        db.set_document_favorite(favorite, true).await.unwrap();
+
+        let (index_bytes, content_bytes, document_count) = db.get_storage_usage().await.unwrap();
+        assert!(index_bytes > 0);
+        assert_eq!(content_bytes, 52);
+        assert_eq!(document_count, 4);
+
+        let candidates = db.get_prune_candidates(10).await.unwrap();
+        let ids: Vec<i64> = candidates.iter().map(|c| c.id).collect();
+        assert_eq!(ids, vec![dead, never_opened, opened]);
+        assert!(candidates[0].is_dead);
+        assert_eq!(candidates[1].bytes, 2 * "unread content".len() as u64);
     }
 
-    /// Insert or update a watched-file record.
-    ///
-    /// Uses `INSERT OR REPLACE` so callers don't need to distinguish between
-    /// first-seen and update-on-re-ingest cases.
-    pub async fn upsert_watched_file(
-        &self,
-        folder_id: i64,
-        file_path: &std::path::Path,
-        modified_at: i64,
-        document_id: Option<i64>,
-        status: &crate::folder_watcher::IngestStatus,
-    ) -> Result<i64> {
-        let path_str = file_path.to_string_lossy().to_string();
-        let status_str = status.as_db_str().to_string();
-        self.execute_with_priority(OperationPriority::BackgroundIngest, move |conn| {
+    #[tokio::test]
+    async fn retention_candidates_by_condition() {
+        use crate::retention::{RetentionCondition, RetentionRule};
+
+        let (db, _tmp) = create_test_db().await;
+        assert!(db.get_retention_rules().await.unwrap().is_empty());
+        let rules = vec![RetentionRule::default()];
+        db.set_retention_rules(&rules).await.unwrap();
+        assert_eq!(db.get_retention_rules().await.unwrap(), rules);
+
+        let old = insert_test_doc(&db, "Old", "content").await;
+        let new = insert_test_doc(&db, "New", "content").await;
+        let old_favorite = insert_test_doc(&db, "Old favorite", "content").await;
+        let dead = db
+            .insert_document(
+                "Dead",
+                "content",
+                Some("https://gone.example.com"),
+                "test",
+                None,
+                None,
+                OperationPriority::UserSearch,
+                None,
+            )
+            .await
+            .unwrap();
+        db.execute_with_priority(OperationPriority::UserSearch, move |conn| {
             conn.execute(
-                "INSERT INTO watched_files
-                     (folder_id, file_path, modified_at, document_id, ingest_status)
-                 VALUES (?1, ?2, ?3, ?4, ?5)
-                 ON CONFLICT(file_path) DO UPDATE SET
-                     modified_at    = excluded.modified_at,
-                     document_id    = excluded.document_id,
-                     ingest_status  = excluded.ingest_status",
-                params![folder_id, path_str, modified_at, document_id, status_str],
+                "UPDATE documents SET created_at = datetime('now', '-2 years') WHERE id IN (?1, ?2)",
+                params![old, old_favorite],
             )?;
-            Ok(conn.last_insert_rowid())
+            Ok(())
         })
         .await
-    }
+        .unwrap();
+        db.set_document_favorite(old_favorite, true).await.unwrap();
+        db.mark_url_as_dead("https://gone.example.com")
+            .await
+            .unwrap();
+        db.record_document_view(new).await.unwrap();
 
-    /// Look up a single watched-file record by its filesystem path.
-    pub async fn get_watched_file_by_path(
-        &self,
-        path: &std::path::Path,
-    ) -> Result<Option<crate::folder_watcher::WatchedFile>> {
-        use crate::folder_watcher::{IngestStatus, WatchedFile};
-        let path_str = path.to_string_lossy().to_string();
-        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
-            let mut stmt = conn.prepare(
-                "SELECT id, folder_id, file_path, modified_at, document_id, ingest_status
-                 FROM watched_files WHERE file_path = ?1",
-            )?;
-            match stmt.query_row(params![path_str], |row| {
-                let path_val: String = row.get(2)?;
-                let status_str: String = row.get(5)?;
-                Ok(WatchedFile {
-                    id: row.get(0)?,
-                    folder_id: row.get(1)?,
-                    file_path: std::path::PathBuf::from(path_val),
-                    modified_at: row.get(3)?,
-                    document_id: row.get(4)?,
-                    ingest_status: IngestStatus::from_db_str(&status_str),
-                })
-            }) {
-                Ok(wf) => Ok(Some(wf)),
-                Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-                Err(e) => Err(Box::new(e)),
-            }
-        })
-        .await
-    }
+        let ids = |matches: Vec<crate::retention::RetentionMatch>| {
+            matches.iter().map(|m| m.id).collect::<Vec<_>>()
+        };
+        let now: i64 = chrono_utc_now().parse().unwrap();
+        let year_ago = now - 365 * 24 * 3600;
 
-    /// Return all watched-file records belonging to a folder.
-    pub async fn get_watched_files_for_folder(
-        &self,
-        folder_id: i64,
-    ) -> Result<Vec<crate::folder_watcher::WatchedFile>> {
-        use crate::folder_watcher::{IngestStatus, WatchedFile};
-        self.execute_with_priority(OperationPriority::UserSearch, move |conn| {
-            let mut stmt = conn.prepare(
-                "SELECT id, folder_id, file_path, modified_at, document_id, ingest_status
-                 FROM watched_files WHERE folder_id = ?1 ORDER BY file_path",
-            )?;
-            let rows = stmt.query_map(params![folder_id], |row| {
-                let path_val: String = row.get(2)?;
-                let status_str: String = row.get(5)?;
-                Ok(WatchedFile {
-                    id: row.get(0)?,
-                    folder_id: row.get(1)?,
-                    file_path: std::path::PathBuf::from(path_val),
-                    modified_at: row.get(3)?,
-                    document_id: row.get(4)?,
-                    ingest_status: IngestStatus::from_db_str(&status_str),
-                })
-            })?;
-            let mut out = Vec::new();
-            for row in rows {
-                out.push(row?);
-            }
-            Ok(out)
-        })
-        .await
+        let saved = db
+            .get_retention_candidates(RetentionCondition::SavedBefore, year_ago)
+            .await
+            .unwrap();
+        assert_eq!(ids(saved), vec![old]);
+
+        // Dead since just now: not yet past a cutoff in the past
+        let dead_for = db
+            .get_retention_candidates(RetentionCondition::DeadFor, year_ago)
+            .await
+            .unwrap();
+        assert!(dead_for.is_empty());
+        let dead_for = db
+            .get_retention_candidates(RetentionCondition::DeadFor, now + 60)
+            .await
+            .unwrap();
+        assert_eq!(ids(dead_for), vec![dead]);
+
+        let not_opened = db
+            .get_retention_candidates(RetentionCondition::NotOpenedFor, now + 60)
+            .await
+            .unwrap();
+        let mut not_opened = ids(not_opened);
+        not_opened.sort_unstable();
+        assert_eq!(not_opened, vec![old, new, dead]);
+
+        db.trash_documents(&[old]).await.unwrap();
+        let saved = db
+            .get_retention_candidates(RetentionCondition::SavedBefore, year_ago)
+            .await
+            .unwrap();
+        assert!(saved.is_empty());
     }
 
-    /// Delete a watched folder row (CASCADE removes all its watched_files).
-    pub async fn delete_watched_folder(&self, id: i64) -> Result<()> {
-        self.execute_with_priority(OperationPriority::BackgroundIngest, move |conn| {
-            conn.execute("DELETE FROM watched_folders WHERE id = ?1", params![id])?;
-            Ok(())
-        })
-        .await
+    #[tokio::test]
+    async fn maintenance_shrinks_the_file_after_deletions() {
+        let (db, _tmp) = create_test_db().await;
+        let content = "lorem ipsum dolor sit amet ".repeat(2000);
+        let mut ids = Vec::new();
+        for i in 0..20 {
+            ids.push(insert_test_doc(&db, &format!("Doc {}", i), &content).await);
+        }
+        db.delete_documents(&ids).await.unwrap();
+
+        let report = crate::maintenance::run_maintenance(&db, None)
+            .await
+            .unwrap();
+        assert!(report.size_after < report.size_before);
+        assert_eq!(
+            db.get_database_file_size().await.unwrap(),
+            report.size_after
+        );
+        assert!(db.get_maintenance_last_run().await.unwrap().is_some());
+        assert!(!crate::maintenance::is_maintenance_due(&db).await);
     }
 
-    /// Delete a single document and all its embeddings and FTS entries.
-    ///
-    /// Foreign-key CASCADE on `embeddings` handles chunk rows automatically.
-    /// The FTS `rowid` matches the document `id`.
-    pub async fn delete_document(&self, document_id: i64) -> Result<()> {
-        self.execute_with_priority(OperationPriority::BackgroundIngest, move |conn| {
-            // FTS must be deleted manually (virtual table, no FK cascade)
+    #[tokio::test]
+    async fn integrity_audit_finds_and_repairs_damage() {
+        let (db, _tmp) = create_test_db().await;
+        let a = insert_test_doc(&db, "Alpha", "alpha content").await;
+        let b = insert_test_doc(&db, "Beta", "beta content").await;
+        let good = bincode::serialize(&vec![0.5f32; 4]).unwrap();
+        let short = bincode::serialize(&vec![0.5f32; 2]).unwrap();
+        for (doc, blob) in [(a, &good), (a, &good), (b, &short)] {
+            db.insert_chunk_embedding(doc, 0, 5, blob, OperationPriority::BackgroundIngest)
+                .await
+                .unwrap();
+        }
+        let corrupt = db
+            .insert_chunk_embedding(b, 5, 9, &[1, 2, 3], OperationPriority::BackgroundIngest)
+            .await
+            .unwrap();
+        db.execute_with_priority(OperationPriority::BackgroundIngest, move |conn| {
+            conn.execute("DELETE FROM documents_fts WHERE rowid = ?1", params![a])?;
+            conn.execute_batch("PRAGMA foreign_keys = OFF;")?;
             conn.execute(
-                "DELETE FROM documents_fts WHERE rowid = ?1",
-                params![document_id],
+                "INSERT INTO embeddings (document_id, chunk_start, chunk_end, embedding)
+                 VALUES (999, 0, 1, x'00')",
+                [],
             )?;
-            // Embeddings are CASCADE-deleted by FK when document is deleted
-            conn.execute("DELETE FROM documents WHERE id = ?1", params![document_id])?;
+            conn.execute_batch("PRAGMA foreign_keys = ON;")?;
             Ok(())
         })
         .await
+        .unwrap();
+
+        // A corrupt blob no longer stops the vectors loading
+        assert_eq!(db.get_all_chunk_embeddings().await.unwrap().len(), 3);
+
+        let report = crate::integrity::audit(&db).await.unwrap();
+        assert_eq!(report.orphan_chunks, 1);
+        assert!(report.fts_out_of_sync());
+        assert_eq!(report.expected_dimension, Some(4));
+        // The orphan's blob is unreadable too, but it only counts as an orphan
+        // and its missing document is not reported as affected
+        assert_eq!(report.corrupt_chunks, vec![corrupt]);
+        assert_eq!(report.mismatched_chunks.len(), 1);
+        assert_eq!(report.affected_documents, vec![b]);
+        assert_eq!(report.problem_count(), 4);
+
+        let mut bad = report.corrupt_chunks.clone();
+        bad.extend(&report.mismatched_chunks);
+        db.delete_chunks(&bad).await.unwrap();
+        db.delete_orphan_chunks().await.unwrap();
+        db.rebuild_fts().await.unwrap();
+
+        let report = crate::integrity::audit(&db).await.unwrap();
+        assert!(!report.fts_out_of_sync());
+        assert_eq!(report.documents_without_chunks, vec![b]);
+        assert_eq!(report.repairs(), vec![crate::integrity::Repair::ReEmbed]);
+        assert_eq!(db.search_documents("alpha", 10).await.unwrap().len(), 1);
     }
 
-    /// Update the status column of a watched folder.
-    pub async fn update_watched_folder_status(
-        &self,
-        id: i64,
-        status: &crate::folder_watcher::FolderStatus,
-    ) -> Result<()> {
-        let status_str = status.as_db_str().to_string();
-        self.execute_with_priority(OperationPriority::BackgroundIngest, move |conn| {
+    #[tokio::test]
+    async fn sync_documents_merge_into_another_database() {
+        let (source, _tmp_source) = create_test_db().await;
+        let (target, _tmp_target) = create_test_db().await;
+        let id = insert_test_doc(&source, "Shared", "shared content").await;
+        source
+            .set_document_tags(id, &["rust".to_string()])
+            .await
+            .unwrap();
+        let vector = bincode::serialize(&vec![0.5f32; 4]).unwrap();
+        source
+            .insert_chunk_embedding(id, 0, 6, &vector, OperationPriority::BackgroundIngest)
+            .await
+            .unwrap();
+
+        let docs = source.get_sync_documents().await.unwrap();
+        assert_eq!(docs.len(), 1);
+        let doc = &docs[0].1;
+        assert_eq!(doc.tags, vec!["rust"]);
+        assert_eq!(doc.chunks.len(), 1);
+        assert!(doc.modified_at > 0 && !doc.deleted);
+
+        // Copied with its tags and embeddings, and the same modification time
+        let copy = target.apply_sync_document(None, doc, true).await.unwrap();
+        assert_eq!(target.get_document_tags(copy).await.unwrap(), vec!["rust"]);
+        assert_eq!(
+            target
+                .get_chunk_embeddings_for_document(copy)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+        assert_eq!(
+            target.search_documents("shared", 10).await.unwrap().len(),
+            1
+        );
+        assert_eq!(target.get_sync_documents().await.unwrap()[0].1, *doc);
+
+        // Without embeddings it is left to be re-embedded
+        let mut edited = doc.clone();
+        edited.title = "Edited".to_string();
+        edited.modified_at += 10;
+        target
+            .apply_sync_document(Some(copy), &edited, false)
+            .await
+            .unwrap();
+        assert_eq!(target.get_incomplete_documents().await.unwrap(), vec![copy]);
+        let merged = &target.get_sync_documents().await.unwrap()[0].1;
+        assert_eq!(merged.title, "Edited");
+        assert_eq!(merged.modified_at, edited.modified_at);
+
+        // Trashing is a change too
+        let fingerprint = target.get_sync_fingerprint().await.unwrap();
+        target.trash_documents(&[copy]).await.unwrap();
+        assert!(target.get_sync_documents().await.unwrap()[0].1.deleted);
+        assert_ne!(target.get_sync_fingerprint().await.unwrap(), fingerprint);
+    }
+
+    #[tokio::test]
+    async fn last_indexed_at_tracks_chunk_writes() {
+        let (db, _tmp) = create_test_db().await;
+        let pending = db
+            .insert_document_pending_embedding("Pending", "content", None, "test", None, false)
+            .await
+            .unwrap();
+        let created_at = db.get_document(pending).await.unwrap().unwrap().created_at;
+        assert_eq!(
+            db.get_last_indexed_at(pending).await.unwrap(),
+            Some(created_at)
+        );
+
+        let embedding = bincode::serialize(&vec![0.5f32, 0.25]).unwrap();
+        db.update_document_with_chunks(
+            pending,
+            "Pending",
+            "content",
+            &[(0, 7, embedding, ChunkProvenance::default())],
+        )
+        .await
+        .unwrap();
+        let indexed: Option<String> = db
+            .execute_with_priority(OperationPriority::UserSearch, move |conn| {
+                Ok(conn.query_row(
+                    "SELECT indexed_at FROM documents WHERE id = ?1",
+                    params![pending],
+                    |row| row.get(0),
+                )?)
+            })
+            .await
+            .unwrap();
+        assert!(indexed.is_some());
+        assert_eq!(db.get_last_indexed_at(pending).await.unwrap(), indexed);
+        assert_eq!(db.get_last_indexed_at(pending + 1).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn batch_insert_documents_writes_every_document_and_chunk() {
+        let (db, _tmp) = create_test_db().await;
+        let embedding = bincode::serialize(&vec![0.5f32, 0.25]).unwrap();
+        let one = vec![(0, 10, embedding.clone(), ChunkProvenance::default())];
+        let two = vec![
+            (0, 10, embedding.clone(), ChunkProvenance::default()),
+            (10, 20, embedding, ChunkProvenance::default()),
+        ];
+        let inserted = db
+            .batch_insert_documents(
+                "chrome_extension",
+                &[
+                    (
+                        "First",
+                        "first page",
+                        Some("https://a.example/#top"),
+                        one.as_slice(),
+                    ),
+                    ("Second", "second page", None, two.as_slice()),
+                ],
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(inserted.len(), 2);
+        assert_eq!((inserted[0].1.len(), inserted[1].1.len()), (1, 2));
+        let first = db
+            .get_document_by_url("https://a.example/")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(first.id, inserted[0].0);
+        assert_eq!(first.source, "chrome_extension");
+        assert_eq!(
+            db.get_chunk_embeddings_for_document(inserted[1].0)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+        assert!(db.get_incomplete_documents().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn embeddings_are_found_by_content_hash() {
+        let (db, _tmp) = create_test_db().await;
+        let embedding = bincode::serialize(&vec![0.5f32, 0.25]).unwrap();
+        db.insert_document_with_chunks(
+            "Doc",
+            "content",
+            None,
+            "test",
+            None,
+            false,
+            &[
+                (
+                    0,
+                    4,
+                    embedding.clone(),
+                    ChunkProvenance {
+                        content_hash: Some("hash-a".to_string()),
+                        ..Default::default()
+                    },
+                ),
+                (4, 7, embedding, ChunkProvenance::default()),
+            ],
+        )
+        .await
+        .unwrap();
+
+        let found = db
+            .get_embeddings_by_hash(&["hash-a".to_string(), "hash-b".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found["hash-a"], vec![0.5, 0.25]);
+    }
+
+    #[tokio::test]
+    async fn chunk_provenance_is_stored_with_embeddings() {
+        let (db, _tmp) = create_test_db().await;
+        let embedding = bincode::serialize(&vec![0.5f32, 0.25]).unwrap();
+        let intro = ChunkProvenance {
+            section_heading: Some("Intro".to_string()),
+            section_anchor: Some("intro".to_string()),
+            page_number: Some(3),
+            timestamp_seconds: None,
+            content_hash: Some("hash-a".to_string()),
+        };
+        let (doc_id, _) = db
+            .insert_document_with_chunks(
+                "Doc",
+                "content",
+                None,
+                "test",
+                None,
+                false,
+                &[
+                    (0, 4, embedding.clone(), intro.clone()),
+                    (4, 7, embedding, ChunkProvenance::default()),
+                ],
+            )
+            .await
+            .unwrap();
+
+        let provenance = db.get_chunk_provenance(&[doc_id, 999]).await.unwrap();
+        assert_eq!(provenance.len(), 2);
+        assert_eq!(provenance[&(doc_id, 0)], intro);
+        assert_eq!(provenance[&(doc_id, 4)], ChunkProvenance::default());
+        assert!(db.get_chunk_provenance(&[]).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn incomplete_documents_are_detected() {
+        let (db, _tmp) = create_test_db().await;
+
+        // Legacy row written before chunk_count existed, with no chunks
+        let legacy = db
+            .insert_document(
+                "Legacy",
+                "content",
+                None,
+                "test",
+                None,
+                None,
+                OperationPriority::BackgroundIngest,
+                None,
+            )
+            .await
+            .unwrap();
+
+        // Row that recorded three chunks but only has one
+        let (partial, embedding_ids) = db
+            .insert_document_with_chunks(
+                "Partial",
+                "content",
+                None,
+                "test",
+                None,
+                false,
+                &[
+                    (0, 5, vec![1u8], ChunkProvenance::default()),
+                    (5, 10, vec![2u8], ChunkProvenance::default()),
+                    (10, 15, vec![3u8], ChunkProvenance::default()),
+                ],
+            )
+            .await
+            .unwrap();
+        db.execute_with_priority(OperationPriority::BackgroundIngest, |conn| {
             conn.execute(
-                "UPDATE watched_folders SET status = ?1 WHERE id = ?2",
-                params![status_str, id],
+                "DELETE FROM embeddings WHERE id != ?1",
+                params![embedding_ids[0]],
             )?;
             Ok(())
         })
         .await
+        .unwrap();
+
+        assert_eq!(
+            db.get_incomplete_documents().await.unwrap(),
+            vec![legacy, partial]
+        );
+
+        db.replace_document_chunks(legacy, &[(0, 7, vec![9u8], ChunkProvenance::default())])
+            .await
+            .unwrap();
+        assert_eq!(db.get_incomplete_documents().await.unwrap(), vec![partial]);
     }
 
-    /// Delete all documents whose `source` column equals `folder_path`, returning
-    /// the deleted document IDs so the caller can evict them from the VectorStore.
-    ///
-    /// Call this BEFORE `delete_watched_folder` so the document IDs are still
-    /// available (watched_files rows are CASCADE-deleted with the folder).
-    pub async fn delete_documents_by_source(
-        &self,
-        folder_path: &std::path::Path,
-    ) -> Result<Vec<i64>> {
-        let source = folder_path.to_string_lossy().to_string();
-        self.execute_with_priority(OperationPriority::BackgroundIngest, move |conn| {
-            // Collect IDs first so we can return them for VectorStore eviction
-            let mut stmt = conn.prepare("SELECT id FROM documents WHERE source = ?1")?;
-            let ids: Vec<i64> = stmt
-                .query_map(params![source], |row| row.get(0))?
-                .collect::<std::result::Result<Vec<_>, _>>()?;
+    #[tokio::test]
+    async fn pending_embedding_documents_are_searchable_and_incomplete() {
+        let (db, _tmp) = create_test_db().await;
+        let doc_id = db
+            .insert_document_pending_embedding(
+                "Offline note",
+                "written while the embedding server was down",
+                Some("https://offline.example"),
+                "test",
+                None,
+                false,
+            )
+            .await
+            .unwrap();
 
-            for &doc_id in &ids {
-                conn.execute(
-                    "DELETE FROM documents_fts WHERE rowid = ?1",
-                    params![doc_id],
-                )?;
-                conn.execute("DELETE FROM documents WHERE id = ?1", params![doc_id])?;
-            }
-            Ok(ids)
+        let hits = db
+            .search_documents_scored("embedding", 10, false)
+            .await
+            .unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].0.id, doc_id);
+        assert_eq!(db.get_incomplete_documents().await.unwrap(), vec![doc_id]);
+    }
+
+    #[tokio::test]
+    async fn ui_preferences_round_trip() {
+        let (db, _tmp) = create_test_db().await;
+        assert_eq!(db.get_ui_language().await.unwrap(), None);
+        assert_eq!(db.get_ui_scale().await.unwrap(), None);
+        assert!(!db.get_high_contrast().await.unwrap());
+
+        db.set_ui_language("es").await.unwrap();
+        db.set_ui_scale(1.25).await.unwrap();
+        db.set_high_contrast(true).await.unwrap();
+        assert_eq!(db.get_ui_language().await.unwrap().as_deref(), Some("es"));
+        assert_eq!(db.get_ui_scale().await.unwrap(), Some(1.25));
+        assert!(db.get_high_contrast().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn search_sessions_restore_live_hits_in_rank_order() {
+        let (db, _tmp) = create_test_db().await;
+        let first = insert_test_doc(&db, "Tokio", "async runtime").await;
+        let second = insert_test_doc(&db, "Rayon", "data parallelism").await;
+        let trashed = insert_test_doc(&db, "Smol", "small runtime").await;
+        assert_eq!(db.get_search_session("runtime", false).await.unwrap(), None);
+
+        let hit = |doc_id, similarity| SessionHit {
+            doc_id,
+            similarity,
+            passage: Some((0, 5)),
+        };
+        let session = SearchSession {
+            query: "runtime".to_string(),
+            corrected_query: Some("runtimes".to_string()),
+            passages: false,
+            hits: vec![hit(second, 0.9), hit(trashed, 0.8), hit(first, 0.7)],
+        };
+        db.save_search_session(&session).await.unwrap();
+        db.trash_documents(&[trashed]).await.unwrap();
+
+        let restored = db
+            .get_search_session(" runtime ", false)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(restored.corrected_query.as_deref(), Some("runtimes"));
+        assert_eq!(restored.hits, vec![hit(second, 0.9), hit(first, 0.7)]);
+        assert_eq!(db.get_search_session("runtime", true).await.unwrap(), None);
+
+        // Saving the same query again replaces the session
+        db.save_search_session(&SearchSession {
+            hits: vec![hit(first, 0.5)],
+            ..session.clone()
         })
         .await
+        .unwrap();
+        let restored = db.get_search_session("runtime", false).await.unwrap();
+        assert_eq!(restored.unwrap().hits, vec![hit(first, 0.5)]);
+
+        // Only the last few searches are kept
+        for i in 0..SEARCH_SESSION_LIMIT {
+            db.save_search_session(&SearchSession {
+                query: format!("query {}", i),
+                ..session.clone()
+            })
+            .await
+            .unwrap();
+        }
+        assert_eq!(db.get_search_session("runtime", false).await.unwrap(), None);
+        assert!(db
+            .get_search_session("query 0", false)
+            .await
+            .unwrap()
+            .is_some());
     }
-}
 
-/// Return current UTC timestamp as an ISO 8601 string.
-fn chrono_utc_now() -> String {
-    use std::time::{SystemTime, UNIX_EPOCH};
-    let secs = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_secs();
-    // Store as unix timestamp string — no chrono dependency needed
-    format!("{}", secs)
-}
+    #[tokio::test]
+    async fn refresh_restored_search_defaults_on() {
+        let (db, _tmp) = create_test_db().await;
+        assert!(db.get_refresh_restored_search().await.unwrap());
+        db.set_refresh_restored_search(false).await.unwrap();
+        assert!(!db.get_refresh_restored_search().await.unwrap());
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use tempfile::TempDir;
+    #[tokio::test]
+    async fn query_embeddings_are_stored_per_backend_and_pruned() {
+        let (db, _tmp) = create_test_db().await;
+        assert!(db.get_persist_query_embeddings().await.unwrap());
 
-    async fn create_test_db() -> (Database, TempDir) {
-        let temp_dir = TempDir::new().unwrap();
-        let db_path = temp_dir.path().join("test_localmind.db");
+        db.save_query_embedding("rust", "local", &[0.5, 0.25], 2)
+            .await
+            .unwrap();
+        assert_eq!(
+            db.get_query_embedding("rust", "local").await.unwrap(),
+            Some(vec![0.5, 0.25])
+        );
+        assert!(db
+            .get_query_embedding("rust", "ollama|nomic")
+            .await
+            .unwrap()
+            .is_none());
 
-        let conn = Connection::open(&db_path).unwrap();
-        let db = Database {
-            conn: Arc::new(Mutex::new(conn)),
-            search_semaphore: Arc::new(Semaphore::new(10)),
-            ingest_semaphore: Arc::new(Semaphore::new(1)),
-        };
+        // "rust" has been searched twice, so it outlives one-off queries
+        db.save_query_embedding("python", "local", &[1.0], 2)
+            .await
+            .unwrap();
+        db.save_query_embedding("go", "local", &[2.0], 2)
+            .await
+            .unwrap();
+        assert!(db
+            .get_query_embedding("rust", "local")
+            .await
+            .unwrap()
+            .is_some());
+        assert!(db
+            .get_query_embedding("python", "local")
+            .await
+            .unwrap()
+            .is_none());
+
+        db.clear_query_embeddings().await.unwrap();
+        assert!(db
+            .get_query_embedding("go", "local")
+            .await
+            .unwrap()
+            .is_none());
+    }
 
-        db.init_schema().await.unwrap();
-        (db, temp_dir)
+    // -----------------------------------------------------------------------
+    // Collections: membership, scoped search, cascade on delete
+    // -----------------------------------------------------------------------
+
+    async fn insert_test_doc(db: &Database, title: &str, content: &str) -> i64 {
+        db.insert_document(
+            title,
+            content,
+            None,
+            "test",
+            None,
+            None,
+            OperationPriority::BackgroundIngest,
+            None,
+        )
+        .await
+        .unwrap()
     }
 
     #[tokio::test]
-    async fn test_excluded_folders_config() {
-        let (db, _temp) = create_test_db().await;
+    async fn collections_track_membership_and_counts() {
+        let (db, _tmp) = create_test_db().await;
+        let a = insert_test_doc(&db, "A", "alpha").await;
+        let b = insert_test_doc(&db, "B", "beta").await;
+
+        let jobs = db.create_collection("Job hunt").await.unwrap();
+        assert!(db.create_collection("job HUNT").await.is_err());
+        assert!(db.create_collection("  ").await.is_err());
 
-        let folders = db.get_excluded_folders().await.unwrap();
         assert_eq!(
-            folders.len(),
-            0,
-            "Initially should have no excluded folders"
+            db.add_documents_to_collection(jobs, &[a, b]).await.unwrap(),
+            2
         );
+        // Re-adding is a no-op
+        assert_eq!(db.add_documents_to_collection(jobs, &[a]).await.unwrap(), 0);
+        assert_eq!(db.get_document_collection_ids(a).await.unwrap(), vec![jobs]);
+
+        db.remove_document_from_collection(jobs, b).await.unwrap();
+        let collections = db.get_collections().await.unwrap();
+        assert_eq!(collections.len(), 1);
+        assert_eq!(collections[0].name, "Job hunt");
+        assert_eq!(collections[0].document_count, 1);
+
+        // Deleting a document drops its membership; deleting a collection keeps documents
+        db.delete_document(a).await.unwrap();
+        assert!(db
+            .get_collection_document_ids(jobs)
+            .await
+            .unwrap()
+            .is_empty());
+        db.delete_collection(jobs).await.unwrap();
+        assert!(db.get_collections().await.unwrap().is_empty());
+        assert!(db.get_document(b).await.unwrap().is_some());
+    }
 
-        let test_folders = vec![
-            "folder_123".to_string(),
-            "folder_456".to_string(),
-            "folder_789".to_string(),
-        ];
-        db.set_excluded_folders(&test_folders).await.unwrap();
+    #[tokio::test]
+    async fn library_lists_documents_by_source_ids_and_urls() {
+        let (db, _tmp) = create_test_db().await;
+        let a = insert_test_doc(&db, "A", "alpha").await;
+        let b = insert_test_doc(&db, "B", "beta").await;
+        let page = db
+            .insert_document(
+                "Page",
+                "gamma",
+                Some("https://example.com/page#top"),
+                "chrome_bookmark",
+                None,
+                None,
+                OperationPriority::BackgroundIngest,
+                Some("Work"),
+            )
+            .await
+            .unwrap();
 
-        let retrieved = db.get_excluded_folders().await.unwrap();
         assert_eq!(
-            retrieved, test_folders,
-            "Should retrieve same folders that were set"
+            db.get_source_counts().await.unwrap(),
+            vec![("test".to_string(), 2), ("chrome_bookmark".to_string(), 1)]
         );
-
-        let updated_folders = vec!["folder_abc".to_string(), "folder_xyz".to_string()];
-        db.set_excluded_folders(&updated_folders).await.unwrap();
-
-        let retrieved = db.get_excluded_folders().await.unwrap();
+        let ids = |docs: Vec<Document>| docs.into_iter().map(|d| d.id).collect::<Vec<_>>();
         assert_eq!(
-            retrieved, updated_folders,
-            "Should update to new folder list"
+            ids(db.get_documents_by_source("test", None, 10).await.unwrap()),
+            vec![b, a]
         );
-    }
-
-    #[tokio::test]
-    async fn test_excluded_domains_config() {
-        let (db, _temp) = create_test_db().await;
+        assert!(db
+            .get_documents_by_source("chrome_bookmark", Some("Home".to_string()), 10)
+            .await
+            .unwrap()
+            .is_empty());
 
-        let domains = db.get_excluded_domains().await.unwrap();
+        let all: std::collections::HashSet<i64> = [a, b, page].into();
         assert_eq!(
-            domains.len(),
-            0,
-            "Initially should have no excluded domains"
+            ids(db.get_documents_newest_first(&all, None, 2).await.unwrap()),
+            vec![page, b]
+        );
+        assert_eq!(
+            ids(db
+                .get_documents_newest_first(&all, Some("Work".to_string()), 10)
+                .await
+                .unwrap()),
+            vec![page]
         );
 
-        let test_domains = vec![
-            "*.internal.com".to_string(),
-            "private.example.org".to_string(),
-            "localhost:*".to_string(),
+        let urls = vec![
+            "https://example.com/page".to_string(),
+            "https://missing.dev".to_string(),
         ];
-        db.set_excluded_domains(&test_domains).await.unwrap();
-
-        let retrieved = db.get_excluded_domains().await.unwrap();
         assert_eq!(
-            retrieved, test_domains,
-            "Should retrieve same domains that were set"
+            db.get_document_ids_by_urls(&urls).await.unwrap(),
+            [page].into()
         );
 
-        let updated_domains = vec!["example.com".to_string()];
-        db.set_excluded_domains(&updated_domains).await.unwrap();
-
-        let retrieved = db.get_excluded_domains().await.unwrap();
+        // Trashed documents are left out
+        db.trash_documents(&[page]).await.unwrap();
+        assert!(db.get_document_ids_by_urls(&urls).await.unwrap().is_empty());
         assert_eq!(
-            retrieved, updated_domains,
-            "Should update to new domain list"
+            ids(db.get_documents_newest_first(&all, None, 10).await.unwrap()),
+            vec![b, a]
         );
+        assert_eq!(db.get_source_counts().await.unwrap().len(), 1);
     }
 
     #[tokio::test]
-    async fn test_delete_bookmarks_by_url_pattern() {
-        let (db, _temp) = create_test_db().await;
-
+    async fn tracking_parameters_are_stripped_before_storage_and_dedup() {
+        let (db, _tmp) = create_test_db().await;
         db.insert_document(
-            "Internal Site",
-            "Content from internal site",
-            Some("https://foo.internal.com/page"),
+            "Post",
+            "text",
+            Some("https://example.com/post?id=7&utm_source=news&fbclid=abc"),
             "chrome_bookmark",
             None,
             None,
@@ -1408,134 +8238,390 @@ mod tests {
         .await
         .unwrap();
 
-        db.insert_document(
-            "Public Site",
-            "Content from public site",
-            Some("https://example.com/page"),
-            "chrome_bookmark",
-            None,
-            None,
-            OperationPriority::BackgroundIngest,
-            None,
-        )
-        .await
-        .unwrap();
+        let doc = db
+            .get_document_by_url("https://example.com/post?id=7")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(doc.url.as_deref(), Some("https://example.com/post?id=7"));
+        assert!(db
+            .url_exists(
+                "https://example.com/post?utm_medium=email&id=7",
+                OperationPriority::UserSearch
+            )
+            .await
+            .unwrap());
 
-        db.insert_document(
-            "Another Internal Site",
-            "More internal content",
-            Some("https://bar.internal.com/page"),
-            "chrome_bookmark",
-            None,
-            None,
-            OperationPriority::BackgroundIngest,
-            None,
-        )
-        .await
-        .unwrap();
+        // The list belongs to the database: another library keeps its own
+        assert_eq!(
+            db.get_tracking_params().await.unwrap(),
+            crate::tracking_params::defaults()
+        );
+        let (other, _other_tmp) = create_test_db().await;
+        let mut params = crate::tracking_params::defaults();
+        params.push("ref_src".to_string());
+        db.set_tracking_params(&params).await.unwrap();
+        assert_eq!(db.get_tracking_params().await.unwrap(), params);
+        assert_eq!(db.tracking_params(), params);
+        let shared = "https://example.com/post?ref_src=twsrc";
+        assert_eq!(db.normalize_url(shared), "https://example.com/post");
+        assert_eq!(other.normalize_url(shared), shared);
+    }
 
-        let deleted = db
-            .delete_bookmarks_by_url_pattern("*.internal.com")
+    #[tokio::test]
+    async fn domain_preferences_are_marked_and_cleared() {
+        use crate::domain_preferences::{DomainPreference, DomainQuality};
+        let (db, _tmp) = create_test_db().await;
+        let a = db
+            .insert_document(
+                "A",
+                "alpha",
+                Some("https://domain-test.invalid/a"),
+                "test",
+                None,
+                None,
+                OperationPriority::BackgroundIngest,
+                None,
+            )
             .await
             .unwrap();
+        let b = insert_test_doc(&db, "B", "beta").await;
         assert_eq!(
-            deleted, 2,
-            "Should delete 2 bookmarks matching *.internal.com"
+            db.get_document_url_map(&[a, b]).await.unwrap(),
+            std::collections::HashMap::from([(a, "https://domain-test.invalid/a".to_string())])
         );
 
-        let docs = db.get_live_documents_with_urls().await.unwrap();
-        let urls: Vec<String> = docs.iter().filter_map(|d| d.url.clone()).collect();
+        db.set_domain_preference("domain-test.invalid", Some(DomainQuality::Low))
+            .await
+            .unwrap();
+        db.set_domain_preference("domain-test.invalid", Some(DomainQuality::High))
+            .await
+            .unwrap();
+        let marked = vec![DomainPreference {
+            domain: "domain-test.invalid".to_string(),
+            quality: DomainQuality::High,
+        }];
+        assert_eq!(db.get_domain_preferences().await.unwrap(), marked);
+        assert_eq!(db.cached_settings().domain_preferences, marked);
+        db.set_domain_preference("domain-test.invalid", None)
+            .await
+            .unwrap();
+        assert!(db.get_domain_preferences().await.unwrap().is_empty());
+        assert!(db.cached_settings().domain_preferences.is_empty());
+    }
 
-        assert!(
-            urls.contains(&"https://example.com/page".to_string()),
-            "Public site should remain"
+    #[tokio::test]
+    async fn refetched_content_keeps_earlier_versions() {
+        use crate::document_versions::MAX_VERSIONS;
+        let (db, _tmp) = create_test_db().await;
+        let doc = insert_test_doc(&db, "Page", "first").await;
+
+        db.update_document_with_chunks(doc, "Page", "second", &[])
+            .await
+            .unwrap();
+        // Unchanged content is not a new version
+        db.update_document_with_chunks(doc, "Page v3", "second", &[])
+            .await
+            .unwrap();
+        let versions = db.get_document_versions(doc).await.unwrap();
+        assert_eq!(versions.len(), 1);
+        assert_eq!(versions[0].content, "first");
+        assert_eq!(
+            db.get_document_version(versions[0].id).await.unwrap(),
+            Some(versions[0].clone())
         );
-        assert!(
-            !urls.contains(&"https://foo.internal.com/page".to_string()),
-            "foo.internal.com should be deleted"
+
+        for i in 0..MAX_VERSIONS + 2 {
+            db.update_document_with_chunks(doc, "Page", &format!("update {}", i), &[])
+                .await
+                .unwrap();
+        }
+        let versions = db.get_document_versions(doc).await.unwrap();
+        assert_eq!(versions.len(), MAX_VERSIONS);
+        assert_eq!(versions[0].content, format!("update {}", MAX_VERSIONS));
+        assert!(db.get_document_version(-1).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn annotations_are_listed_in_order_and_deleted() {
+        let (db, _tmp) = create_test_db().await;
+        let doc = insert_test_doc(&db, "Doc", "alpha beta gamma").await;
+        let found_by = insert_test_doc(&db, "Highlight: Doc", "gamma").await;
+
+        let gamma = db
+            .insert_annotation(doc, (11, 16), "gamma", Some("why"), Some(found_by))
+            .await
+            .unwrap();
+        let alpha = db
+            .insert_annotation(doc, (0, 5), "alpha", None, None)
+            .await
+            .unwrap();
+        assert_eq!((gamma.start, gamma.end), (11, 16));
+        assert_eq!(gamma.note.as_deref(), Some("why"));
+        assert_eq!(
+            db.get_document_annotations(doc).await.unwrap(),
+            vec![alpha.clone(), gamma.clone()]
         );
-        assert!(
-            !urls.contains(&"https://bar.internal.com/page".to_string()),
-            "bar.internal.com should be deleted"
+        assert_eq!(
+            db.get_annotation_by_highlight_document(found_by)
+                .await
+                .unwrap(),
+            Some(gamma.clone())
         );
+
+        // Not while the highlighted document is in the trash
+        db.trash_documents(&[doc]).await.unwrap();
+        assert_eq!(
+            db.get_annotation_by_highlight_document(found_by)
+                .await
+                .unwrap(),
+            None
+        );
+        db.restore_documents(&[doc]).await.unwrap();
+
+        assert_eq!(
+            db.delete_annotation(gamma.id).await.unwrap(),
+            Some(found_by)
+        );
+        assert_eq!(db.delete_annotation(alpha.id).await.unwrap(), None);
+        assert_eq!(db.delete_annotation(alpha.id).await.unwrap(), None);
+        assert!(db.get_document_annotations(doc).await.unwrap().is_empty());
     }
 
     #[tokio::test]
-    async fn test_delete_bookmarks_by_url_pattern_wildcard_subdomain() {
-        let (db, _temp) = create_test_db().await;
+    async fn reminders_fire_once_and_are_dismissed() {
+        use crate::reminders::RemindOn;
+        let (db, _tmp) = create_test_db().await;
+        let today = insert_test_doc(&db, "Today", "alpha").await;
+        let later = insert_test_doc(&db, "Later", "beta").await;
 
-        db.insert_document(
-            "Dev Site",
-            "Dev content",
-            Some("https://dev.mycompany.com/page"),
-            "chrome_bookmark",
-            None,
-            None,
-            OperationPriority::BackgroundIngest,
-            None,
-        )
-        .await
-        .unwrap();
+        // A reminder for today is due from the start of the day
+        db.set_reminder(today, &RemindOn::InDays(0), true)
+            .await
+            .unwrap();
+        assert_eq!(
+            db.set_reminder(later, &RemindOn::Date("2999-01-01".to_string()), false)
+                .await
+                .unwrap(),
+            "2999-01-01"
+        );
+        assert!(db
+            .set_reminder(later, &RemindOn::Date("someday".to_string()), false)
+            .await
+            .is_err());
+
+        let fired = db.fire_due_reminders().await.unwrap();
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].document_id, today);
+        assert_eq!(fired[0].title, "Today");
+        assert!(fired[0].notify);
+        assert!(db.fire_due_reminders().await.unwrap().is_empty());
+        assert_eq!(db.get_fired_reminders().await.unwrap(), fired);
+
+        // Snoozing again waits for the new date
+        db.set_reminder(today, &RemindOn::InDays(1), true)
+            .await
+            .unwrap();
+        assert!(db.get_fired_reminders().await.unwrap().is_empty());
+        db.set_reminder(today, &RemindOn::InDays(0), true)
+            .await
+            .unwrap();
+        assert_eq!(db.fire_due_reminders().await.unwrap().len(), 1);
+        db.dismiss_reminder(today).await.unwrap();
+        assert!(db.get_fired_reminders().await.unwrap().is_empty());
+    }
 
-        db.insert_document(
-            "Staging Site",
-            "Staging content",
-            Some("https://staging.mycompany.com/page"),
-            "chrome_bookmark",
-            None,
-            None,
-            OperationPriority::BackgroundIngest,
-            None,
-        )
-        .await
-        .unwrap();
+    #[tokio::test]
+    async fn projection_is_replaced_and_skips_trashed_documents() {
+        let (db, _tmp) = create_test_db().await;
+        let a = insert_test_doc(&db, "A", "alpha").await;
+        let b = insert_test_doc(&db, "B", "beta").await;
+        assert_eq!(db.get_projection().await.unwrap(), (Vec::new(), None));
+
+        db.replace_projection(vec![(a, 0.1, 0.2), (b, 0.9, 0.8)], "model-1")
+            .await
+            .unwrap();
+        db.replace_projection(vec![(a, 0.25, 0.5), (b, 0.75, 0.5)], "model-2")
+            .await
+            .unwrap();
+        db.trash_documents(&[b]).await.unwrap();
+
+        let (points, model) = db.get_projection().await.unwrap();
+        assert_eq!(model.as_deref(), Some("model-2"));
+        assert_eq!(
+            points,
+            vec![ProjectionPoint {
+                document_id: a,
+                title: "A".to_string(),
+                source: "test".to_string(),
+                x: 0.25,
+                y: 0.5,
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn timeline_pages_through_documents_newest_first() {
+        let (db, _tmp) = create_test_db().await;
+        let mut docs = Vec::new();
+        for i in 0..5 {
+            docs.push(insert_test_doc(&db, &format!("Doc {}", i), "text").await);
+        }
+        let ids = |docs: Vec<Document>| docs.into_iter().map(|d| d.id).collect::<Vec<_>>();
+
+        let first = db.get_timeline_page(None, None, 0, 2).await.unwrap();
+        let second = db.get_timeline_page(None, None, 2, 2).await.unwrap();
+        let last = db.get_timeline_page(None, None, 4, 2).await.unwrap();
+        assert_eq!(ids(first), vec![docs[4], docs[3]]);
+        assert_eq!(ids(second), vec![docs[2], docs[1]]);
+        assert_eq!(ids(last), vec![docs[0]]);
+
+        let scope: std::collections::HashSet<i64> = [docs[0], docs[2], docs[3]].into();
+        assert_eq!(
+            ids(db
+                .get_timeline_page(Some(scope.clone()), None, 1, 10)
+                .await
+                .unwrap()),
+            vec![docs[2], docs[0]]
+        );
+        db.trash_documents(&[docs[2]]).await.unwrap();
+        assert_eq!(
+            ids(db
+                .get_timeline_page(Some(scope), None, 0, 10)
+                .await
+                .unwrap()),
+            vec![docs[3], docs[0]]
+        );
+    }
+
+    #[tokio::test]
+    async fn smart_collections_follow_their_filter() {
+        let (db, _tmp) = create_test_db().await;
+        let a = insert_test_doc(&db, "A", "alpha").await;
+        let b = insert_test_doc(&db, "B", "beta").await;
+        db.add_tag_to_documents(&[a], "rust").await.unwrap();
+
+        assert!(db
+            .create_smart_collection("Words", "just words")
+            .await
+            .is_err());
+        let rust = db
+            .create_smart_collection("Rust", "async tag:rust AND source:test after:2000")
+            .await
+            .unwrap();
+        let collections = db.get_collections().await.unwrap();
+        assert_eq!(
+            collections[0].query.as_deref(),
+            Some("source:test tag:rust after:2000")
+        );
+        assert_eq!(collections[0].document_count, 1);
+        assert!(db.add_documents_to_collection(rust, &[b]).await.is_err());
 
-        db.insert_document(
-            "External Site",
-            "External content",
-            Some("https://external.com/page"),
-            "chrome_bookmark",
-            None,
-            None,
-            OperationPriority::BackgroundIngest,
-            None,
-        )
-        .await
-        .unwrap();
+        // Newly tagged documents join; trashed ones drop out
+        db.add_tag_to_documents(&[b], "rust").await.unwrap();
+        let mut ids: Vec<i64> = db
+            .get_collection_document_ids(rust)
+            .await
+            .unwrap()
+            .into_iter()
+            .collect();
+        ids.sort();
+        assert_eq!(ids, vec![a, b]);
+        db.trash_documents(&[a]).await.unwrap();
+        assert_eq!(db.get_collections().await.unwrap()[0].document_count, 1);
+    }
 
-        let deleted = db
-            .delete_bookmarks_by_url_pattern("*.mycompany.com")
+    #[tokio::test]
+    async fn fts_search_can_be_scoped_to_collection() {
+        let (db, _tmp) = create_test_db().await;
+        let a = insert_test_doc(&db, "Rust A", "rust async pitfalls").await;
+        let _b = insert_test_doc(&db, "Rust B", "rust async runtime").await;
+        let collection = db.create_collection("Rust").await.unwrap();
+        db.add_documents_to_collection(collection, &[a])
             .await
             .unwrap();
-        assert_eq!(deleted, 2, "Should delete 2 mycompany.com bookmarks");
 
-        let docs = db.get_live_documents_with_urls().await.unwrap();
-        assert_eq!(docs.len(), 1, "Only 1 bookmark should remain");
-        assert_eq!(docs[0].url.as_ref().unwrap(), "https://external.com/page");
+        let all = db
+            .search_documents_scored_in("\"rust\"", 10, false, None)
+            .await
+            .unwrap();
+        assert_eq!(all.len(), 2);
+
+        let scoped = db
+            .search_documents_scored_in("\"rust\"", 10, false, Some(collection))
+            .await
+            .unwrap();
+        assert_eq!(scoped.len(), 1);
+        assert_eq!(scoped[0].0.id, a);
     }
 
     #[tokio::test]
-    async fn test_delete_bookmarks_by_url_pattern_exact_domain() {
-        let (db, _temp) = create_test_db().await;
+    async fn spelling_candidates_come_from_indexed_terms() {
+        let (db, _tmp) = create_test_db().await;
+        insert_test_doc(&db, "Tokio", "tokio runtime tasks").await;
+        insert_test_doc(&db, "More tokio", "tokio timers").await;
 
-        db.insert_document(
-            "Exact Match",
-            "Content",
-            Some("https://example.com/page"),
-            "chrome_bookmark",
-            None,
-            None,
-            OperationPriority::BackgroundIngest,
-            None,
-        )
-        .await
-        .unwrap();
+        let mut candidates = db.get_spelling_candidates("tokoi").await.unwrap();
+        candidates.sort();
+        assert_eq!(
+            candidates,
+            vec![
+                ("tasks".to_string(), 1),
+                ("timers".to_string(), 1),
+                ("tokio".to_string(), 2),
+            ]
+        );
+        assert!(db.get_spelling_candidates("").await.unwrap().is_empty());
+        assert!(db.is_indexed_term("tokio").await.unwrap());
+        assert!(!db.is_indexed_term("tokoi").await.unwrap());
+    }
+
+    async fn filtered_ids(db: &Database, query: &str) -> Vec<i64> {
+        let parsed = crate::query_syntax::parse(query);
+        let mut ids: Vec<i64> = db
+            .get_filtered_document_ids(&parsed)
+            .await
+            .unwrap()
+            .into_iter()
+            .collect();
+        ids.sort();
+        ids
+    }
 
+    #[tokio::test]
+    async fn document_suggestions_match_title_words_then_urls() {
+        let (db, _tmp) = create_test_db().await;
+        let guide = db
+            .insert_document(
+                "Rust error handling",
+                "anyhow and thiserror",
+                Some("https://www.docs.rs/anyhow"),
+                "test",
+                None,
+                None,
+                OperationPriority::BackgroundIngest,
+                None,
+            )
+            .await
+            .unwrap();
+        let docs_site = db
+            .insert_document(
+                "Crate index",
+                "all crates",
+                Some("https://docs.rs/"),
+                "test",
+                None,
+                None,
+                OperationPriority::BackgroundIngest,
+                None,
+            )
+            .await
+            .unwrap();
         db.insert_document(
-            "Subdomain",
-            "Content",
-            Some("https://sub.example.com/page"),
-            "chrome_bookmark",
+            "Docker basics",
+            "containers",
+            Some("https://docker.com/basics"),
+            "test",
             None,
             None,
             OperationPriority::BackgroundIngest,
@@ -1543,316 +8629,572 @@ mod tests {
         )
         .await
         .unwrap();
-
-        let deleted = db
-            .delete_bookmarks_by_url_pattern("example.com")
+        db.mark_url_as_dead("https://docker.com/basics")
             .await
             .unwrap();
-        assert_eq!(deleted, 1, "Should delete only exact domain match");
 
-        let docs = db.get_live_documents_with_urls().await.unwrap();
-        assert_eq!(docs.len(), 1, "Subdomain should remain");
+        let ids = |suggestions: Vec<DocumentSuggestion>| -> Vec<i64> {
+            suggestions.into_iter().map(|s| s.id).collect()
+        };
         assert_eq!(
-            docs[0].url.as_ref().unwrap(),
-            "https://sub.example.com/page"
+            ids(db.get_document_suggestions("rust err", 5).await.unwrap()),
+            vec![guide]
+        );
+        assert_eq!(
+            ids(db.get_document_suggestions("docs.rs", 5).await.unwrap()),
+            vec![docs_site, guide]
+        );
+        assert_eq!(
+            ids(db.get_document_suggestions("do", 1).await.unwrap()).len(),
+            1
         );
+        assert!(db
+            .get_document_suggestions("docker", 5)
+            .await
+            .unwrap()
+            .is_empty());
     }
 
     #[tokio::test]
-    async fn test_excluded_folders_persistence() {
-        let temp_dir = TempDir::new().unwrap();
-        let db_path = temp_dir.path().join("test_persistence.db");
+    async fn autocomplete_suggests_titles_tags_and_domains() {
+        let (db, _tmp) = create_test_db().await;
+        let docs = db
+            .insert_document(
+                "Rust error handling",
+                "anyhow and thiserror",
+                Some("https://www.docs.rs/anyhow"),
+                "test",
+                None,
+                None,
+                OperationPriority::BackgroundIngest,
+                None,
+            )
+            .await
+            .unwrap();
+        let other = insert_test_doc(&db, "Rustic bread", "flour").await;
+        insert_test_doc(&db, "Docker basics", "containers").await;
+        db.add_tag_to_documents(&[docs, other], "Rusty")
+            .await
+            .unwrap();
 
-        {
-            let conn = Connection::open(&db_path).unwrap();
-            let db = Database {
-                conn: Arc::new(Mutex::new(conn)),
-                search_semaphore: Arc::new(Semaphore::new(10)),
-                ingest_semaphore: Arc::new(Semaphore::new(1)),
-            };
-            db.init_schema().await.unwrap();
+        let suggestions = db
+            .get_autocomplete_suggestions("rust err", 5)
+            .await
+            .unwrap();
+        assert_eq!(
+            suggestions,
+            vec![AutocompleteSuggestion::Document {
+                id: docs,
+                title: "Rust error handling".to_string()
+            }]
+        );
 
-            let folders = vec!["folder_1".to_string(), "folder_2".to_string()];
-            db.set_excluded_folders(&folders).await.unwrap();
+        let suggestions = db.get_autocomplete_suggestions("ru", 5).await.unwrap();
+        assert_eq!(suggestions.len(), 3);
+        assert!(suggestions.contains(&AutocompleteSuggestion::Tag {
+            tag: "Rusty".to_string(),
+            count: 2
+        }));
+        let suggestions = db.get_autocomplete_suggestions("ru", 2).await.unwrap();
+        assert_eq!(suggestions.len(), 2);
+
+        let suggestions = db.get_autocomplete_suggestions("do", 5).await.unwrap();
+        assert!(suggestions.contains(&AutocompleteSuggestion::Domain {
+            domain: "docs.rs".to_string(),
+            count: 1
+        }));
+
+        // Renamed and deleted documents leave the titles index
+        db.update_document_content(other, "Sourdough", "flour")
+            .await
+            .unwrap();
+        db.delete_document(docs).await.unwrap();
+        // The renamed document keeps its tag, now on one document
+        assert_eq!(
+            db.get_autocomplete_suggestions("rust", 5).await.unwrap(),
+            vec![AutocompleteSuggestion::Tag {
+                tag: "Rusty".to_string(),
+                count: 1
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn search_operators_filter_documents() {
+        let (db, _tmp) = create_test_db().await;
+        let mut ids = Vec::new();
+        for (url, source) in [
+            ("https://docs.rs/anyhow", "chrome_extension"),
+            ("https://blog.example.com/errors", "chrome_bookmark"),
+            ("https://notdocs.rs/page", "chrome_bookmark"),
+        ] {
+            let id = db
+                .insert_document(
+                    url,
+                    "error handling in rust",
+                    Some(url),
+                    source,
+                    None,
+                    None,
+                    OperationPriority::BackgroundIngest,
+                    None,
+                )
+                .await
+                .unwrap();
+            ids.push(id);
         }
+        let (docs, blog, fake) = (ids[0], ids[1], ids[2]);
+        db.add_tag_to_documents(&[docs, blog], "rust")
+            .await
+            .unwrap();
 
-        {
-            let conn = Connection::open(&db_path).unwrap();
-            let db = Database {
-                conn: Arc::new(Mutex::new(conn)),
-                search_semaphore: Arc::new(Semaphore::new(10)),
-                ingest_semaphore: Arc::new(Semaphore::new(1)),
-            };
+        assert_eq!(filtered_ids(&db, "site:docs.rs").await, vec![docs]);
+        assert_eq!(filtered_ids(&db, "site:example.com").await, vec![blog]);
+        assert_eq!(filtered_ids(&db, "source:bookmark").await, vec![blog, fake]);
+        assert_eq!(
+            filtered_ids(&db, "tag:RUST source:bookmark").await,
+            vec![blog]
+        );
+        assert_eq!(filtered_ids(&db, "after:2000-01-01").await, ids);
+        assert!(filtered_ids(&db, "before:2000-01-01").await.is_empty());
 
-            let retrieved = db.get_excluded_folders().await.unwrap();
-            assert_eq!(
-                retrieved.len(),
-                2,
-                "Folders should persist across database reopens"
-            );
-            assert_eq!(retrieved[0], "folder_1");
-            assert_eq!(retrieved[1], "folder_2");
-        }
+        let mut phrase: Vec<i64> = db
+            .get_fts_document_ids("\"error handling\"")
+            .await
+            .unwrap()
+            .into_iter()
+            .collect();
+        phrase.sort();
+        assert_eq!(phrase, ids);
     }
 
-    #[tokio::test]
-    async fn test_excluded_domains_persistence() {
-        let temp_dir = TempDir::new().unwrap();
-        let db_path = temp_dir.path().join("test_persistence.db");
-
-        {
-            let conn = Connection::open(&db_path).unwrap();
-            let db = Database {
-                conn: Arc::new(Mutex::new(conn)),
-                search_semaphore: Arc::new(Semaphore::new(10)),
-                ingest_semaphore: Arc::new(Semaphore::new(1)),
-            };
-            db.init_schema().await.unwrap();
+    // -----------------------------------------------------------------------
+    // Favorites: pin, unpin, ordering
+    // -----------------------------------------------------------------------
 
-            let domains = vec!["*.internal.com".to_string(), "localhost".to_string()];
-            db.set_excluded_domains(&domains).await.unwrap();
-        }
+    #[tokio::test]
+    async fn favorites_are_listed_most_recently_pinned_first() {
+        let (db, _tmp) = create_test_db().await;
+        let a = insert_test_doc(&db, "A", "alpha").await;
+        let b = insert_test_doc(&db, "B", "beta").await;
+        let _c = insert_test_doc(&db, "C", "gamma").await;
 
-        {
-            let conn = Connection::open(&db_path).unwrap();
-            let db = Database {
-                conn: Arc::new(Mutex::new(conn)),
-                search_semaphore: Arc::new(Semaphore::new(10)),
-                ingest_semaphore: Arc::new(Semaphore::new(1)),
-            };
+        assert!(db.get_favorite_documents(None).await.unwrap().is_empty());
 
-            let retrieved = db.get_excluded_domains().await.unwrap();
-            assert_eq!(
-                retrieved.len(),
-                2,
-                "Domains should persist across database reopens"
-            );
-            assert_eq!(retrieved[0], "*.internal.com");
-            assert_eq!(retrieved[1], "localhost");
-        }
+        db.set_document_favorite(a, true).await.unwrap();
+        db.set_document_favorite(b, true).await.unwrap();
+        let ids: Vec<i64> = db
+            .get_favorite_documents(None)
+            .await
+            .unwrap()
+            .iter()
+            .map(|d| d.id)
+            .collect();
+        // Pinned in the same second, so the later ID breaks the tie
+        assert_eq!(ids, vec![b, a]);
+
+        db.set_document_favorite(b, false).await.unwrap();
+        let favorites = db.get_favorite_documents(None).await.unwrap();
+        assert_eq!(favorites.len(), 1);
+        assert_eq!(favorites[0].id, a);
+        assert!(db
+            .get_favorite_documents(Some("Other".to_string()))
+            .await
+            .unwrap()
+            .is_empty());
     }
 
     // -----------------------------------------------------------------------
-    // T006: add_watched_folder / get_watched_folders
+    // Reading list: read/unread state
     // -----------------------------------------------------------------------
 
     #[tokio::test]
-    async fn add_and_get_watched_folder() {
+    async fn new_documents_are_unread_until_marked() {
         let (db, _tmp) = create_test_db().await;
-        let path = std::path::Path::new("/tmp/test_folder");
+        let a = insert_test_doc(&db, "A", "alpha").await;
+        let b = insert_test_doc(&db, "B", "beta").await;
 
-        let id = db.add_watched_folder(path).await.unwrap();
-        assert!(id > 0);
+        let unread = db.get_unread_document_ids().await.unwrap();
+        assert!(unread.contains(&a) && unread.contains(&b));
 
-        let folders = db.get_watched_folders().await.unwrap();
-        assert_eq!(folders.len(), 1);
-        assert_eq!(folders[0].path, path);
+        assert_eq!(db.set_documents_read(&[a, b], true).await.unwrap(), 2);
+        // Already read: nothing changes
+        assert_eq!(db.set_documents_read(&[a], true).await.unwrap(), 0);
+        assert!(db.get_unread_document_ids().await.unwrap().is_empty());
+
+        db.set_documents_read(&[b], false).await.unwrap();
+        let reading_list = db.get_unread_documents(10, None).await.unwrap();
+        assert_eq!(reading_list.len(), 1);
+        assert_eq!(reading_list[0].id, b);
+    }
+
+    #[tokio::test]
+    async fn old_documents_are_resurfaced() {
+        let (db, _tmp) = create_test_db().await;
+        let year_ago = insert_test_doc(&db, "Year ago", "alpha").await;
+        let opened = insert_test_doc(&db, "Opened", "beta").await;
+        let pinned = insert_test_doc(&db, "Pinned", "gamma").await;
+        let fresh = insert_test_doc(&db, "Fresh", "delta").await;
+        db.execute_with_priority(OperationPriority::BackgroundIngest, move |conn| {
+            conn.execute(
+                "UPDATE documents SET created_at = datetime('now', '-1 year', '+1 day')
+                 WHERE id = ?1",
+                params![year_ago],
+            )?;
+            conn.execute(
+                "UPDATE documents SET created_at = datetime('now', '-90 days')
+                 WHERE id IN (?1, ?2)",
+                params![opened, pinned],
+            )?;
+            Ok(())
+        })
+        .await
+        .unwrap();
+        db.set_document_favorite(pinned, true).await.unwrap();
+
+        let on_this_day = db.get_documents_saved_year_ago(10, None).await.unwrap();
         assert_eq!(
-            folders[0].status,
-            crate::folder_watcher::FolderStatus::Active
+            on_this_day.iter().map(|d| d.id).collect::<Vec<_>>(),
+            vec![year_ago]
         );
+
+        db.record_document_view(opened).await.unwrap();
+        db.record_document_view(opened).await.unwrap();
+        let view_count: i64 = db
+            .execute_with_priority(OperationPriority::UserSearch, move |conn| {
+                Ok(conn.query_row(
+                    "SELECT view_count FROM documents WHERE id = ?1",
+                    params![opened],
+                    |row| row.get(0),
+                )?)
+            })
+            .await
+            .unwrap();
+        assert_eq!(view_count, 2);
+
+        // Pinned and recently saved documents are never "forgotten"
+        let mut forgotten: Vec<i64> = db
+            .get_forgotten_documents(10, None)
+            .await
+            .unwrap()
+            .iter()
+            .map(|d| d.id)
+            .collect();
+        forgotten.sort();
+        assert_eq!(forgotten, vec![year_ago, opened]);
+        assert!(!forgotten.contains(&fresh));
     }
 
     #[tokio::test]
-    async fn add_duplicate_folder_fails() {
+    async fn usage_insights_are_recorded_and_purged() {
         let (db, _tmp) = create_test_db().await;
-        let path = std::path::Path::new("/tmp/dup_folder");
-
-        db.add_watched_folder(path).await.unwrap();
-        let result = db.add_watched_folder(path).await;
-        assert!(
-            result.is_err(),
-            "duplicate path must fail with UNIQUE constraint"
+        let a = insert_test_doc(&db, "A", "alpha").await;
+        let b = insert_test_doc(&db, "B", "beta").await;
+
+        db.record_document_view(b).await.unwrap();
+        db.record_document_view(b).await.unwrap();
+        db.record_document_view(a).await.unwrap();
+        db.record_search("rust async", 4).await.unwrap();
+        db.record_search("obscure thing", 0).await.unwrap();
+        db.record_search("Obscure thing ", 0).await.unwrap();
+        db.record_search("other miss", 0).await.unwrap();
+
+        let insights = db.get_usage_insights(10).await.unwrap();
+        assert_eq!(insights.searches, 4);
+        assert_eq!(
+            insights.most_revisited,
+            vec![(b, "B".to_string(), 2), (a, "A".to_string(), 1)]
         );
+        assert_eq!(insights.zero_result_queries.len(), 2);
+        assert_eq!(insights.zero_result_queries[0].1, 2);
+        assert_eq!(insights.busiest_ingest_days.len(), 1);
+        assert_eq!(insights.busiest_ingest_days[0].1, 2);
+
+        db.purge_usage_data().await.unwrap();
+        let insights = db.get_usage_insights(10).await.unwrap();
+        assert_eq!(insights.searches, 0);
+        assert!(insights.most_revisited.is_empty());
+        assert!(insights.zero_result_queries.is_empty());
+        // Ingestion activity comes from the documents themselves
+        assert_eq!(insights.busiest_ingest_days.len(), 1);
     }
 
     // -----------------------------------------------------------------------
-    // T007: upsert_watched_file / get_watched_file_by_path / get_watched_files_for_folder
+    // Tags and bulk delete
     // -----------------------------------------------------------------------
 
     #[tokio::test]
-    async fn upsert_and_get_watched_file() {
-        use crate::folder_watcher::IngestStatus;
+    async fn tags_are_case_insensitive_and_removed_with_document() {
         let (db, _tmp) = create_test_db().await;
-        let folder_id = db
-            .add_watched_folder(std::path::Path::new("/tmp/f1"))
-            .await
-            .unwrap();
-        let file_path = std::path::Path::new("/tmp/f1/note.txt");
+        let a = insert_test_doc(&db, "A", "alpha").await;
+        let b = insert_test_doc(&db, "B", "beta").await;
 
-        db.upsert_watched_file(folder_id, file_path, 1000, None, &IngestStatus::Pending)
-            .await
-            .unwrap();
+        assert_eq!(db.add_tag_to_documents(&[a, b], "rust").await.unwrap(), 2);
+        assert_eq!(db.add_tag_to_documents(&[a], "Rust").await.unwrap(), 0);
+        db.add_tag_to_documents(&[a], "async").await.unwrap();
+        assert!(db.add_tag_to_documents(&[a], "  ").await.is_err());
+        assert_eq!(
+            db.get_document_tags(a).await.unwrap(),
+            vec!["async", "rust"]
+        );
 
-        let wf = db
-            .get_watched_file_by_path(file_path)
-            .await
-            .unwrap()
-            .unwrap();
-        assert_eq!(wf.folder_id, folder_id);
-        assert_eq!(wf.file_path, file_path);
-        assert_eq!(wf.modified_at, 1000);
-        assert_eq!(wf.ingest_status, IngestStatus::Pending);
+        db.remove_document_tag(a, "RUST").await.unwrap();
+        assert_eq!(db.get_document_tags(a).await.unwrap(), vec!["async"]);
+
+        assert_eq!(db.delete_documents(&[a, b, 9999]).await.unwrap(), 2);
+        assert!(db.get_document(a).await.unwrap().is_none());
+        assert!(db.get_document_tags(a).await.unwrap().is_empty());
     }
 
     #[tokio::test]
-    async fn upsert_updates_existing_file() {
-        use crate::folder_watcher::IngestStatus;
+    async fn tag_counts_and_per_document_tags_skip_the_trash() {
         let (db, _tmp) = create_test_db().await;
-        let folder_id = db
-            .add_watched_folder(std::path::Path::new("/tmp/f2"))
-            .await
-            .unwrap();
-        let file_path = std::path::Path::new("/tmp/f2/note.txt");
+        let a = insert_test_doc(&db, "A", "alpha").await;
+        let b = insert_test_doc(&db, "B", "beta").await;
+        let c = insert_test_doc(&db, "C", "gamma").await;
+        db.add_tag_to_documents(&[a, b, c], "rust").await.unwrap();
+        db.add_tag_to_documents(&[a, c], "async").await.unwrap();
+        db.add_tag_to_documents(&[b], "Web").await.unwrap();
 
-        db.upsert_watched_file(folder_id, file_path, 1000, None, &IngestStatus::Pending)
-            .await
-            .unwrap();
-        db.upsert_watched_file(
-            folder_id,
-            file_path,
-            2000,
-            Some(42),
-            &IngestStatus::Ingested,
-        )
-        .await
-        .unwrap();
+        assert_eq!(
+            db.get_tag_counts().await.unwrap(),
+            vec![
+                ("rust".to_string(), 3),
+                ("async".to_string(), 2),
+                ("Web".to_string(), 1)
+            ]
+        );
 
-        let wf = db
-            .get_watched_file_by_path(file_path)
-            .await
-            .unwrap()
-            .unwrap();
-        assert_eq!(wf.modified_at, 2000);
-        assert_eq!(wf.document_id, Some(42));
-        assert_eq!(wf.ingest_status, IngestStatus::Ingested);
+        db.trash_documents(&[c]).await.unwrap();
+        let tags = db.get_tags_for_documents(&[a, b, c, 9999]).await.unwrap();
+        assert_eq!(tags.len(), 2);
+        assert_eq!(tags[&a], vec!["async", "rust"]);
+        assert_eq!(tags[&b], vec!["rust", "Web"]);
+        assert_eq!(
+            db.get_tag_counts().await.unwrap()[0],
+            ("rust".to_string(), 2)
+        );
+        assert!(db.get_tags_for_documents(&[]).await.unwrap().is_empty());
     }
 
     #[tokio::test]
-    async fn get_watched_files_for_folder_returns_all() {
-        use crate::folder_watcher::IngestStatus;
+    async fn document_title_tags_and_metadata_are_editable() {
         let (db, _tmp) = create_test_db().await;
-        let fid = db
-            .add_watched_folder(std::path::Path::new("/tmp/f3"))
+        let doc_id = insert_test_doc(&db, "Old name", "tokio runtime internals").await;
+
+        db.update_document_title(doc_id, "  Async runtimes ")
+            .await
+            .unwrap();
+        assert!(db.update_document_title(doc_id, " ").await.is_err());
+        let doc = db.get_document(doc_id).await.unwrap().unwrap();
+        assert_eq!(doc.title, "Async runtimes");
+        assert_eq!(doc.content, "tokio runtime internals");
+        let hits = db
+            .search_documents_scored("runtimes", 10, false)
             .await
             .unwrap();
+        assert_eq!(hits.len(), 1);
 
-        db.upsert_watched_file(
-            fid,
-            std::path::Path::new("/tmp/f3/a.txt"),
-            100,
-            None,
-            &IngestStatus::Pending,
-        )
-        .await
-        .unwrap();
-        db.upsert_watched_file(
-            fid,
-            std::path::Path::new("/tmp/f3/b.md"),
-            200,
-            None,
-            &IngestStatus::Pending,
-        )
-        .await
-        .unwrap();
+        db.add_tag_to_documents(&[doc_id], "old").await.unwrap();
+        let tags = ["rust", " Async ", "", "RUST"].map(String::from);
+        db.set_document_tags(doc_id, &tags).await.unwrap();
+        assert_eq!(
+            db.get_document_tags(doc_id).await.unwrap(),
+            vec!["Async", "rust"]
+        );
 
-        let files = db.get_watched_files_for_folder(fid).await.unwrap();
-        assert_eq!(files.len(), 2);
+        let metadata = db.get_document_metadata(doc_id).await.unwrap().unwrap();
+        assert_eq!(metadata.embedded_chunks, 0);
+        assert_eq!(metadata.view_count, 0);
+        assert!(db.get_document_metadata(9999).await.unwrap().is_none());
     }
 
     // -----------------------------------------------------------------------
-    // T008: delete_watched_folder CASCADE + delete_document
+    // Document links
     // -----------------------------------------------------------------------
 
     #[tokio::test]
-    async fn delete_watched_folder_cascades_files() {
-        use crate::folder_watcher::IngestStatus;
+    async fn document_links_are_replaced_and_cascade_on_delete() {
         let (db, _tmp) = create_test_db().await;
-        let fid = db
-            .add_watched_folder(std::path::Path::new("/tmp/del_f"))
-            .await
-            .unwrap();
-
-        db.upsert_watched_file(
-            fid,
-            std::path::Path::new("/tmp/del_f/a.txt"),
-            1,
-            None,
-            &IngestStatus::Pending,
+        let a = insert_test_doc(&db, "Alpha", "links out").await;
+
+        db.set_document_links(
+            a,
+            &[
+                "https://b.example/".to_string(),
+                "https://a.example/".to_string(),
+            ],
         )
         .await
         .unwrap();
+        assert_eq!(
+            db.get_document_links(a).await.unwrap(),
+            vec!["https://a.example/", "https://b.example/"]
+        );
 
-        // Verify file exists
-        assert!(db
-            .get_watched_file_by_path(std::path::Path::new("/tmp/del_f/a.txt"))
+        db.set_document_links(a, &["https://c.example/".to_string()])
             .await
-            .unwrap()
-            .is_some());
-
-        db.delete_watched_folder(fid).await.unwrap();
+            .unwrap();
+        assert_eq!(
+            db.get_document_links(a).await.unwrap(),
+            vec!["https://c.example/"]
+        );
 
-        // CASCADE should have removed the file row
-        assert!(db
-            .get_watched_file_by_path(std::path::Path::new("/tmp/del_f/a.txt"))
-            .await
-            .unwrap()
-            .is_none());
-        assert!(db.get_watched_folders().await.unwrap().is_empty());
+        db.delete_document(a).await.unwrap();
+        assert!(db.get_document_links(a).await.unwrap().is_empty());
     }
 
     #[tokio::test]
-    async fn delete_document_removes_from_all_tables() {
+    async fn document_structure_roundtrips_and_clears() {
+        use crate::html_text::{Heading, PageLink, PageStart};
+
         let (db, _tmp) = create_test_db().await;
+        let a = insert_test_doc(&db, "Alpha", "Intro\nBody").await;
+        assert!(db.get_document_structure(a).await.unwrap().is_empty());
+
+        let structure = PageStructure {
+            headings: vec![Heading {
+                level: 2,
+                text: "Intro".to_string(),
+                offset: 0,
+                anchor: Some("intro".to_string()),
+            }],
+            links: vec![PageLink {
+                text: "docs".to_string(),
+                href: "https://docs.example/".to_string(),
+            }],
+            pages: vec![PageStart {
+                number: 1,
+                first_line: "Intro".to_string(),
+                offset: 0,
+            }],
+        };
+        db.set_document_structure(a, &structure).await.unwrap();
+        assert_eq!(db.get_document_structure(a).await.unwrap(), structure);
 
-        let doc_id = db
-            .insert_document(
-                "Test",
+        db.set_document_structure(a, &PageStructure::default())
+            .await
+            .unwrap();
+        assert!(db.get_document_structure(a).await.unwrap().is_empty());
+
+        db.set_document_structure(a, &structure).await.unwrap();
+        db.delete_document(a).await.unwrap();
+        assert!(db.get_document_structure(a).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn links_resolve_to_indexed_documents_in_both_directions() {
+        async fn insert(db: &Database, title: &str, url: &str) -> i64 {
+            db.insert_document(
+                title,
                 "content",
-                None,
-                "source",
+                Some(url),
+                "test",
                 None,
                 None,
                 OperationPriority::BackgroundIngest,
                 None,
             )
             .await
-            .unwrap();
+            .unwrap()
+        }
 
-        // Verify document exists
-        assert!(db.get_document(doc_id).await.unwrap().is_some());
+        let (db, _tmp) = create_test_db().await;
+        let a = insert(&db, "Alpha", "https://a.example/").await;
+        let b = insert(&db, "Beta", "https://b.example/post").await;
+
+        // Links are stored normalized (no trailing slash); "c" is not indexed yet
+        db.set_document_links(
+            b,
+            &[
+                "https://a.example".to_string(),
+                "https://c.example".to_string(),
+            ],
+        )
+        .await
+        .unwrap();
 
-        db.delete_document(doc_id).await.unwrap();
+        let outgoing = db.get_outgoing_linked_documents(b).await.unwrap();
+        assert_eq!(outgoing.iter().map(|d| d.id).collect::<Vec<_>>(), vec![a]);
+        let backlinks = db.get_backlinked_documents(a).await.unwrap();
+        assert_eq!(backlinks.iter().map(|d| d.id).collect::<Vec<_>>(), vec![b]);
 
-        assert!(db.get_document(doc_id).await.unwrap().is_none());
+        // The edge appears once the target is indexed
+        let c = insert(&db, "Gamma", "https://c.example").await;
+        assert_eq!(db.get_backlinked_documents(c).await.unwrap().len(), 1);
+
+        let linked = db.get_linked_document_ids(&[a]).await.unwrap();
+        assert_eq!(linked, std::collections::HashSet::from([b]));
     }
 
     // -----------------------------------------------------------------------
-    // T045: delete_documents_by_source
+    // Trash
     // -----------------------------------------------------------------------
 
     #[tokio::test]
-    async fn delete_documents_by_source_removes_matching_docs() {
+    async fn trashed_documents_are_hidden_until_restored_or_purged() {
         let (db, _tmp) = create_test_db().await;
-        let folder_path = std::path::Path::new("/tmp/source_folder");
+        let a = insert_test_doc(&db, "Alpha", "searchable alpha").await;
+        let b = insert_test_doc(&db, "Beta", "searchable beta").await;
+        let vector = bincode::serialize(&vec![0.5f32; 4]).unwrap();
+        db.insert_chunk_embedding(a, 0, 10, &vector, OperationPriority::BackgroundIngest)
+            .await
+            .unwrap();
 
+        assert_eq!(db.trash_documents(&[a]).await.unwrap(), 1);
+        assert_eq!(db.trash_documents(&[a]).await.unwrap(), 0);
+        let recent = db.get_recent_documents(10).await.unwrap();
+        assert_eq!(recent.iter().map(|d| d.id).collect::<Vec<_>>(), vec![b]);
+        assert_eq!(
+            db.search_documents("searchable", 10).await.unwrap().len(),
+            1
+        );
+        assert!(db.get_all_chunk_embeddings().await.unwrap().is_empty());
+        // Still known, so bookmark sync does not re-ingest it
+        assert!(db.get_document(a).await.unwrap().is_some());
+
+        let trash = db.get_trashed_documents().await.unwrap();
+        assert_eq!(trash.len(), 1);
+        assert_eq!(trash[0].title, "Alpha");
+
+        let exported = db.get_documents_for_export().await.unwrap();
+        assert_eq!(exported.len(), 2);
+        assert_eq!(exported[0].0.id, a);
+        assert_eq!(exported[0].1, Some(trash[0].deleted_at));
+        assert_eq!(exported[1].1, None);
+        let live = db.get_documents_not_in_trash().await.unwrap();
+        assert_eq!(live.iter().map(|d| d.id).collect::<Vec<_>>(), vec![b]);
+
+        assert_eq!(db.restore_documents(&[a]).await.unwrap(), 1);
+        assert_eq!(
+            db.search_documents("searchable", 10).await.unwrap().len(),
+            2
+        );
+        assert_eq!(db.get_all_chunk_embeddings().await.unwrap().len(), 1);
+
+        db.trash_documents(&[a]).await.unwrap();
+        assert_eq!(db.purge_trash().await.unwrap(), 1);
+        assert!(db.get_document(a).await.unwrap().is_none());
+        assert!(db.get_trashed_documents().await.unwrap().is_empty());
+        assert!(db
+            .get_chunk_embeddings_for_document(a)
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            db.search_documents("searchable", 10).await.unwrap().len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn favicons_are_cached_per_host() {
+        let (db, _temp) = create_test_db().await;
         db.insert_document(
-            "Doc1",
-            "content1",
-            None,
-            &folder_path.to_string_lossy(),
-            None,
-            None,
-            OperationPriority::BackgroundIngest,
-            None,
-        )
-        .await
-        .unwrap();
-        db.insert_document(
-            "Doc2",
-            "content2",
-            None,
-            &folder_path.to_string_lossy(),
+            "Serde",
+            "Serialization framework",
+            Some("https://docs.rs/serde"),
+            "chrome_bookmark",
             None,
             None,
             OperationPriority::BackgroundIngest,
@@ -1861,10 +9203,10 @@ mod tests {
         .await
         .unwrap();
         db.insert_document(
-            "Other",
-            "other",
-            None,
-            "other_source",
+            "Tokio",
+            "Async runtime",
+            Some("https://tokio.rs/"),
+            "chrome_bookmark",
             None,
             None,
             OperationPriority::BackgroundIngest,
@@ -1873,12 +9215,268 @@ mod tests {
         .await
         .unwrap();
 
-        let deleted_ids = db.delete_documents_by_source(folder_path).await.unwrap();
-        assert_eq!(deleted_ids.len(), 2);
+        let missing = crate::favicon::hosts_missing_icons(&db, 10).await.unwrap();
+        assert_eq!(missing.len(), 2);
 
-        // The "Other" document must still exist
-        let remaining = db.get_all_documents().await.unwrap();
-        assert_eq!(remaining.len(), 1);
-        assert_eq!(remaining[0].source, "other_source");
+        // A found icon and a host without one are both cached
+        db.set_favicon("docs.rs", b"png").await.unwrap();
+        db.set_favicon("tokio.rs", b"").await.unwrap();
+        assert!(crate::favicon::hosts_missing_icons(&db, 10)
+            .await
+            .unwrap()
+            .is_empty());
+        // Hosts without an icon are retried once the retry period has passed
+        assert!(!db
+            .get_cached_favicon_hosts(-1)
+            .await
+            .unwrap()
+            .contains("tokio.rs"));
+
+        let icons = db.get_favicons(&missing).await.unwrap();
+        assert_eq!(icons.len(), 1);
+        assert_eq!(icons["docs.rs"], b"png".to_vec());
+    }
+
+    #[tokio::test]
+    async fn thumbnails_follow_the_preview_image() {
+        let (db, _temp) = create_test_db().await;
+        let doc_id = insert_test_doc(&db, "Post", "A post with a cover image").await;
+
+        db.set_document_image_url(doc_id, "https://example.com/cover.jpg")
+            .await
+            .unwrap();
+        assert_eq!(
+            db.get_pending_thumbnails(10).await.unwrap(),
+            vec![(doc_id, "https://example.com/cover.jpg".to_string())]
+        );
+
+        db.set_thumbnail(doc_id, b"jpeg").await.unwrap();
+        assert!(db.get_pending_thumbnails(10).await.unwrap().is_empty());
+        // Noting the same image again keeps the thumbnail
+        db.set_document_image_url(doc_id, "https://example.com/cover.jpg")
+            .await
+            .unwrap();
+        assert_eq!(
+            db.get_thumbnails(&[doc_id]).await.unwrap()[&doc_id],
+            b"jpeg"
+        );
+
+        // A new image is fetched again
+        db.set_document_image_url(doc_id, "https://example.com/new.jpg")
+            .await
+            .unwrap();
+        assert_eq!(db.get_pending_thumbnails(10).await.unwrap().len(), 1);
+        assert!(db.get_thumbnails(&[doc_id]).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn home_summary_groups_recent_saves_by_source() {
+        let (db, _tmp) = create_test_db().await;
+        let mut ids = Vec::new();
+        for (title, source) in [
+            ("Old bookmark", "chrome_bookmark"),
+            ("Note", "note"),
+            ("New bookmark", "chrome_bookmark"),
+            ("Newest bookmark", "chrome_bookmark"),
+        ] {
+            ids.push(
+                db.insert_document(
+                    title,
+                    "content",
+                    None,
+                    source,
+                    None,
+                    None,
+                    OperationPriority::BackgroundIngest,
+                    None,
+                )
+                .await
+                .unwrap(),
+            );
+        }
+        db.set_documents_read(&[ids[1]], true).await.unwrap();
+        db.record_search("rust", 3).await.unwrap();
+        db.record_search("egui", 0).await.unwrap();
+        db.record_search("Rust", 3).await.unwrap();
+
+        let summary = db.get_home_summary(2, 5, 5).await.unwrap();
+        assert_eq!(summary.documents, 4);
+        assert_eq!(summary.saved_this_week, 4);
+        assert_eq!(summary.unread, 3);
+        assert_eq!(summary.searches_this_week, 3);
+        assert_eq!(summary.recent_searches, vec!["Rust", "egui"]);
+
+        let groups: Vec<(&str, Vec<&str>)> = summary
+            .recent_by_source
+            .iter()
+            .map(|(source, saves)| {
+                (
+                    source.as_str(),
+                    saves.iter().map(|save| save.title.as_str()).collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            groups,
+            vec![
+                ("chrome_bookmark", vec!["Newest bookmark", "New bookmark"]),
+                ("note", vec!["Note"]),
+            ]
+        );
+
+        let summary = db.get_home_summary(2, 1, 5).await.unwrap();
+        assert_eq!(summary.recent_by_source.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn documents_filter_by_detected_language() {
+        let (db, _tmp) = create_test_db().await;
+        let english = insert_test_doc(&db, "Bread", "flour water salt").await;
+        let spanish = insert_test_doc(&db, "Pan", "harina agua sal").await;
+        let unknown = insert_test_doc(&db, "x", "y").await;
+
+        let pending: Vec<i64> = db
+            .get_documents_without_language(10)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|(id, _, _)| id)
+            .collect();
+        assert_eq!(pending, vec![unknown, spanish, english]);
+
+        db.set_document_language(english, "eng").await.unwrap();
+        db.set_document_language(spanish, "spa").await.unwrap();
+        db.set_document_language(unknown, "").await.unwrap();
+        assert!(db
+            .get_documents_without_language(10)
+            .await
+            .unwrap()
+            .is_empty());
+
+        assert_eq!(filtered_ids(&db, "lang:es").await, vec![spanish]);
+        assert_eq!(filtered_ids(&db, "lang:english").await, vec![english]);
+        assert_eq!(
+            db.get_language_counts().await.unwrap(),
+            vec![("eng".to_string(), 1), ("spa".to_string(), 1)]
+        );
+
+        let languages = db
+            .get_document_languages(&[english, unknown])
+            .await
+            .unwrap();
+        assert_eq!(languages.len(), 1);
+        assert_eq!(languages[&english], "eng");
+    }
+
+    #[tokio::test]
+    async fn low_quality_pages_are_listed_for_review() {
+        use crate::page_quality::QualityIssue;
+        let (db, _tmp) = create_test_db().await;
+        let mut ids = Vec::new();
+        for url in [
+            "https://a.example/",
+            "https://b.example/",
+            "https://c.example/",
+        ] {
+            let id = db
+                .insert_document(
+                    url,
+                    url,
+                    Some(url),
+                    "test",
+                    None,
+                    None,
+                    OperationPriority::BackgroundIngest,
+                    None,
+                )
+                .await
+                .unwrap();
+            ids.push(id);
+        }
+        let (login, kept, trashed) = (ids[0], ids[1], ids[2]);
+
+        assert_eq!(db.get_document_quality_issue(login).await.unwrap(), None);
+        db.set_document_quality_issue(login, Some("login_wall"))
+            .await
+            .unwrap();
+        db.set_document_quality_issue(kept, Some("")).await.unwrap();
+        db.set_document_quality_issue(trashed, Some("not_found"))
+            .await
+            .unwrap();
+        db.trash_documents(&[trashed]).await.unwrap();
+
+        let pages = db.get_low_quality_pages().await.unwrap();
+        assert_eq!(pages.len(), 1);
+        assert_eq!(pages[0].id, login);
+        assert_eq!(pages[0].url, "https://a.example/");
+        assert_eq!(pages[0].issue, QualityIssue::LoginWall);
+        assert_eq!(
+            db.get_document_quality_issue(kept)
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("")
+        );
+
+        db.set_document_quality_issue(login, None).await.unwrap();
+        assert!(db.get_low_quality_pages().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn placeholder_documents_are_marked_and_left_out_of_search() {
+        let (db, _tmp) = create_test_db().await;
+        let placeholder = db
+            .insert_document(
+                "Widget missing",
+                "Widget missing\n\nBookmark: https://m.example\nURL: https://m.example\n\n[No content extracted]",
+                Some("https://m.example"),
+                "chrome_extension",
+                None,
+                None,
+                OperationPriority::BackgroundIngest,
+                None,
+            )
+            .await
+            .unwrap();
+        let good = db
+            .insert_document(
+                "Widget guide",
+                "Widget guide\n\nEverything about building widgets by hand.",
+                Some("https://g.example"),
+                "chrome_extension",
+                None,
+                None,
+                OperationPriority::BackgroundIngest,
+                None,
+            )
+            .await
+            .unwrap();
+
+        // Migration runs on every schema init
+        db.init_schema().await.unwrap();
+        assert!(db.document_needs_refetch(placeholder).await.unwrap());
+        assert!(!db.document_needs_refetch(good).await.unwrap());
+
+        let listed: Vec<i64> = db
+            .get_documents_needing_refetch()
+            .await
+            .unwrap()
+            .iter()
+            .map(|doc| doc.id)
+            .collect();
+        assert_eq!(listed, vec![placeholder]);
+        let hits: Vec<i64> = db
+            .search_documents_scored("widget", 10, false)
+            .await
+            .unwrap()
+            .iter()
+            .map(|(doc, _)| doc.id)
+            .collect();
+        assert_eq!(hits, vec![good]);
+
+        db.set_document_needs_refetch(placeholder, false)
+            .await
+            .unwrap();
+        assert!(db.get_documents_needing_refetch().await.unwrap().is_empty());
     }
 }
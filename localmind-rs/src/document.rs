@@ -5,6 +5,25 @@ pub struct DocumentChunk {
     pub content: String,
     pub start_pos: usize,
     pub end_pos: usize,
+    pub provenance: ChunkProvenance,
+}
+
+/// Where a chunk comes from within its document, stored with the chunk's
+/// embedding so search results can name the section, page or moment of a
+/// recording they matched. Every field is optional: most documents only
+/// have some of them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChunkProvenance {
+    /// Heading of the section the chunk starts in
+    pub section_heading: Option<String>,
+    /// That section's anchor on the page, to open it at the section
+    pub section_anchor: Option<String>,
+    /// Page of a PDF the chunk starts on, from 1
+    pub page_number: Option<u32>,
+    /// Time into a transcript where the chunk starts, in seconds
+    pub timestamp_seconds: Option<u32>,
+    /// Hash of the chunk text and embedding model (see `rag::chunk_hash`)
+    pub content_hash: Option<String>,
 }
 
 pub struct DocumentProcessor {
@@ -40,6 +59,7 @@ impl DocumentProcessor {
                 content: text.to_string(),
                 start_pos: 0,
                 end_pos: text_len,
+                provenance: ChunkProvenance::default(),
             }]);
         }
 
@@ -78,6 +98,7 @@ impl DocumentProcessor {
                             content: chunk_content,
                             start_pos: safe_start,
                             end_pos: safe_end,
+                            provenance: ChunkProvenance::default(),
                         });
                     }
                 }
@@ -122,6 +143,44 @@ impl DocumentProcessor {
         Ok(chunks)
     }
 
+    /// Split text like `chunk_text`, but without letting a chunk run across
+    /// a section start (e.g. a heading's offset, see `html_text`). Sections
+    /// shorter than half a chunk are kept with the following one, so short
+    /// headings do not become chunks of their own.
+    pub fn chunk_sections(
+        &self,
+        text: &str,
+        section_starts: &[usize],
+    ) -> Result<Vec<DocumentChunk>> {
+        let min_section = self.chunk_size / 2;
+        let mut bounds = vec![0];
+        for &start in section_starts {
+            let last = *bounds.last().unwrap_or(&0);
+            if start > last
+                && start - last >= min_section
+                && text.len() - start >= min_section
+                && text.is_char_boundary(start)
+            {
+                bounds.push(start);
+            }
+        }
+        bounds.push(text.len());
+
+        let mut chunks = Vec::new();
+        for section in bounds.windows(2) {
+            let (start, end) = (section[0], section[1]);
+            for chunk in self.chunk_text(&text[start..end])? {
+                chunks.push(DocumentChunk {
+                    content: chunk.content,
+                    start_pos: start + chunk.start_pos,
+                    end_pos: start + chunk.end_pos,
+                    provenance: chunk.provenance,
+                });
+            }
+        }
+        Ok(chunks)
+    }
+
     /// Adjust a byte position to be on a valid UTF-8 character boundary
     fn adjust_to_char_boundary(&self, text: &str, pos: usize, forward: bool) -> usize {
         let mut adjusted = pos.min(text.len());
@@ -362,7 +421,7 @@ mod tests {
         }
 
         // Verify we got reasonable chunks
-        assert!(chunks.len() > 0);
+        assert!(!chunks.is_empty());
     }
 
     #[test]
@@ -676,4 +735,24 @@ mod tests {
             assert!(!chunk.content.contains('�'));
         }
     }
+
+    #[test]
+    fn test_chunks_break_at_sections() {
+        let processor = DocumentProcessor::new(100, 10);
+        let intro =
+            "Intro\nA short introduction that is long enough to stand as its own section.\n";
+        let usage = "Usage\nHow to use it, described in a few words that fill most of a chunk.";
+        let text = format!("{}{}", intro, usage);
+        let chunks = processor.chunk_sections(&text, &[0, intro.len()]).unwrap();
+
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].content, intro);
+        assert_eq!(chunks[1].start_pos, intro.len());
+        assert_eq!(chunks[1].content, usage);
+
+        // A section start too close to the previous one is ignored
+        let chunks = processor.chunk_sections(&text, &[0, 6]).unwrap();
+        assert_eq!(chunks[0].start_pos, 0);
+        assert!(chunks[0].content.contains("introduction"));
+    }
 }
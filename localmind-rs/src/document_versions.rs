@@ -0,0 +1,132 @@
+//! Earlier versions of re-fetched documents
+//!
+//! When a page is fetched again and its content changes, the content it
+//! replaces is kept in the `document_versions` table, up to `MAX_VERSIONS`
+//! per document. The document view lists them, shows a line diff against
+//! the current content and can restore one, for when the new fetch is junk.
+
+/// Most versions kept per document; older ones are dropped.
+pub const MAX_VERSIONS: usize = 10;
+
+/// Line-by-line comparison is quadratic, so above this many line pairs the
+/// changed middle of two versions is shown as wholly replaced.
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+/// Content a document had before it was fetched again
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocumentVersion {
+    pub id: i64,
+    pub document_id: i64,
+    pub title: String,
+    pub content: String,
+    /// When it was replaced
+    pub saved_at: String,
+}
+
+/// A line of a diff between two versions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    Same(String),
+    Removed(String),
+    Added(String),
+}
+
+/// Line diff turning `old` into `new`, by longest common subsequence.
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // Only the middle between a common prefix and suffix needs comparing
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (old_middle, new_middle) = (
+        &old[prefix..old.len() - suffix],
+        &new[prefix..new.len() - suffix],
+    );
+
+    let mut diff: Vec<DiffLine> = old[..prefix]
+        .iter()
+        .map(|l| DiffLine::Same(l.to_string()))
+        .collect();
+    if old_middle.len() * new_middle.len() > MAX_DIFF_CELLS {
+        diff.extend(old_middle.iter().map(|l| DiffLine::Removed(l.to_string())));
+        diff.extend(new_middle.iter().map(|l| DiffLine::Added(l.to_string())));
+    } else {
+        diff.extend(lcs_diff(old_middle, new_middle));
+    }
+    diff.extend(
+        old[old.len() - suffix..]
+            .iter()
+            .map(|l| DiffLine::Same(l.to_string())),
+    );
+    diff
+}
+
+fn lcs_diff(old: &[&str], new: &[&str]) -> Vec<DiffLine> {
+    // lengths[i][j]: longest common subsequence of old[i..] and new[j..]
+    let width = new.len() + 1;
+    let mut lengths = vec![0u32; (old.len() + 1) * width];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i * width + j] = if old[i] == new[j] {
+                lengths[(i + 1) * width + j + 1] + 1
+            } else {
+                lengths[(i + 1) * width + j].max(lengths[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut diff = Vec::with_capacity(old.len() + new.len());
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            diff.push(DiffLine::Same(old[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if lengths[(i + 1) * width + j] >= lengths[i * width + j + 1] {
+            diff.push(DiffLine::Removed(old[i].to_string()));
+            i += 1;
+        } else {
+            diff.push(DiffLine::Added(new[j].to_string()));
+            j += 1;
+        }
+    }
+    diff.extend(old[i..].iter().map(|l| DiffLine::Removed(l.to_string())));
+    diff.extend(new[j..].iter().map(|l| DiffLine::Added(l.to_string())));
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use DiffLine::*;
+
+    #[test]
+    fn diffs_changed_lines() {
+        let old = "Title\n\nintro\nold line\nshared\nend";
+        let new = "Title\n\nintro\nshared\nnew line\nend";
+        assert_eq!(
+            diff_lines(old, new),
+            vec![
+                Same("Title".into()),
+                Same("".into()),
+                Same("intro".into()),
+                Removed("old line".into()),
+                Same("shared".into()),
+                Added("new line".into()),
+                Same("end".into()),
+            ]
+        );
+        assert_eq!(
+            diff_lines("a\nb", "a\nb"),
+            vec![Same("a".into()), Same("b".into())]
+        );
+        assert_eq!(diff_lines("", "a"), vec![Added("a".into())]);
+        assert_eq!(diff_lines("a", ""), vec![Removed("a".into())]);
+    }
+}
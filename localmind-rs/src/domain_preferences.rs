@@ -0,0 +1,157 @@
+//! Domain quality preferences
+//!
+//! Domains can be marked as high or low quality in Settings. At ranking
+//! time a document's score is multiplied by the multiplier for its
+//! domain's quality, so a trusted reference outranks a content farm with
+//! the same words, and results show a small badge saying why. A domain
+//! covers its subdomains; the most specific marked domain wins. The marks
+//! are stored in the `domain_preferences` table and the multipliers in the
+//! config table, and each database keeps them in memory (see
+//! `db::CachedSettings`).
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// Quality given to a domain
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DomainQuality {
+    High,
+    Low,
+}
+
+impl DomainQuality {
+    /// Name stored in the database.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DomainQuality::High => "high",
+            DomainQuality::Low => "low",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "high" => Some(DomainQuality::High),
+            "low" => Some(DomainQuality::Low),
+            _ => None,
+        }
+    }
+}
+
+/// A domain marked in Settings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DomainPreference {
+    pub domain: String,
+    pub quality: DomainQuality,
+}
+
+/// Score multipliers for each quality, edited in Settings
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DomainBoost {
+    pub high: f32,
+    pub low: f32,
+}
+
+impl Default for DomainBoost {
+    fn default() -> Self {
+        Self {
+            high: 1.5,
+            low: 0.5,
+        }
+    }
+}
+
+impl DomainBoost {
+    /// Largest multiplier Settings allows.
+    pub const MAX: f32 = 5.0;
+
+    pub fn multiplier(&self, quality: DomainQuality) -> f32 {
+        match quality {
+            DomainQuality::High => self.high,
+            DomainQuality::Low => self.low,
+        }
+    }
+}
+
+/// The domain typed in Settings, lowercased and without scheme, path,
+/// port or leading `www.`; a pasted URL works too. `None` when it is not a
+/// host name.
+pub fn normalize_domain(input: &str) -> Option<String> {
+    let input = input.trim();
+    let host = match Url::parse(input) {
+        Ok(url) if url.has_host() => url.host_str()?.to_string(),
+        _ => input
+            .split(['/', '?', '#'])
+            .next()?
+            .split(':')
+            .next()?
+            .to_string(),
+    };
+    let host = host.to_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host).trim_matches('.');
+    let valid = host.contains('.')
+        && host.split('.').all(|label| {
+            !label.is_empty() && label.chars().all(|c| c.is_alphanumeric() || c == '-')
+        });
+    valid.then(|| host.to_string())
+}
+
+/// The quality of `url`'s domain among `preferences`: the mark of the most
+/// specific domain it is on.
+pub fn quality_in(url: &str, preferences: &[DomainPreference]) -> Option<DomainQuality> {
+    let host = Url::parse(url).ok()?.host_str()?.to_lowercase();
+    preferences
+        .iter()
+        .filter(|preference| {
+            host.strip_suffix(preference.domain.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.ends_with('.'))
+        })
+        .max_by_key(|preference| preference.domain.len())
+        .map(|preference| preference.quality)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_typed_domains() {
+        assert_eq!(
+            normalize_domain(" https://www.Docs.rs/tokio?x=1 ").as_deref(),
+            Some("docs.rs")
+        );
+        assert_eq!(
+            normalize_domain("blog.example.com:8080/a").as_deref(),
+            Some("blog.example.com")
+        );
+        assert_eq!(normalize_domain("localhost"), None);
+        assert_eq!(normalize_domain("not a domain.com"), None);
+        assert_eq!(normalize_domain(""), None);
+    }
+
+    #[test]
+    fn the_most_specific_domain_wins() {
+        let preferences = vec![
+            DomainPreference {
+                domain: "example.com".to_string(),
+                quality: DomainQuality::Low,
+            },
+            DomainPreference {
+                domain: "docs.example.com".to_string(),
+                quality: DomainQuality::High,
+            },
+        ];
+        let quality = |url: &str| quality_in(url, &preferences);
+        assert_eq!(quality("https://example.com/a"), Some(DomainQuality::Low));
+        assert_eq!(
+            quality("https://www.example.com/a"),
+            Some(DomainQuality::Low)
+        );
+        assert_eq!(
+            quality("https://docs.example.com/a"),
+            Some(DomainQuality::High)
+        );
+        assert_eq!(quality("https://notexample.com/a"), None);
+        assert_eq!(quality("file:///notes/a.md"), None);
+    }
+}
@@ -0,0 +1,63 @@
+//! Switching embedding model without interrupting search
+//!
+//! Vectors from different models are not comparable, so a new model needs
+//! every document re-embedded. Instead of dropping the old vectors first,
+//! the new model's are written next to them in the `embeddings` table,
+//! tagged with its model id, a batch of documents at a time in the
+//! background. Searches keep using the old model and its vectors meanwhile.
+//! Once every document has both, the old vectors are deleted and the new
+//! ones become the searched set in one transaction, and the pipeline
+//! switches model. The target is kept in the `config` table, so a migration
+//! carries on after a restart.
+
+use crate::embedding_provider::{EmbeddingBackend, EmbeddingSettings};
+
+/// Documents re-embedded per batch.
+pub const BATCH: usize = 20;
+
+/// A switch to another embedding model under way.
+pub struct EmbeddingMigration {
+    /// Settings that become the embedding settings once it completes
+    pub settings: EmbeddingSettings,
+    pub backend: Box<dyn EmbeddingBackend>,
+}
+
+impl EmbeddingMigration {
+    /// Tags the new model's chunk rows until they are searched.
+    pub fn model_id(&self) -> String {
+        self.backend.cache_key()
+    }
+}
+
+/// How far a migration has got, in documents.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationProgress {
+    /// Model being migrated to, for display
+    pub model: String,
+    pub migrated: usize,
+    pub total: usize,
+}
+
+impl MigrationProgress {
+    pub fn is_complete(&self) -> bool {
+        self.migrated >= self.total
+    }
+
+    pub fn fraction(&self) -> f32 {
+        if self.total == 0 {
+            1.0
+        } else {
+            self.migrated as f32 / self.total as f32
+        }
+    }
+}
+
+/// Name of the model in `settings`, e.g. "Ollama nomic-embed-text".
+pub fn model_label(settings: &EmbeddingSettings) -> String {
+    let model = settings.model.trim();
+    if settings.provider.needs_model() && !model.is_empty() {
+        format!("{} {}", settings.provider.label(), model)
+    } else {
+        settings.provider.label().to_string()
+    }
+}
@@ -0,0 +1,428 @@
+//! Selectable embedding backends
+//!
+//! LocalMind embeds text with the bundled Python server by default, but can
+//! use a model served by Ollama or LM Studio instead. The choice (provider,
+//! server URL and model) is stored in the `config` table and read by
+//! `RagPipeline` at startup; Settings can swap it at runtime.
+//!
+//! Vectors from different models are not comparable, so switching model
+//! means re-embedding existing documents, which `embedding_migration` does
+//! in the background while searches keep using the old model.
+//!
+//! `RagPipeline` talks to its backend through the `EmbeddingBackend` trait,
+//! so tests can build one over `MockEmbedding` without an embedding server.
+
+use crate::lmstudio::{LmStudioClient, DEFAULT_LMSTUDIO_URL};
+use crate::local_embedding::{self, LocalEmbeddingClient};
+use crate::ollama::{OllamaClient, DEFAULT_OLLAMA_URL};
+use futures_util::future::{self, BoxFuture, FutureExt};
+use futures_util::{StreamExt, TryStreamExt};
+use serde::{Deserialize, Serialize};
+
+/// Embedding requests in flight at once for backends without a batch
+/// endpoint (Ollama and LM Studio serve parallel requests).
+const EMBED_CONCURRENCY: usize = 4;
+
+/// Where embeddings come from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EmbeddingProvider {
+    /// The bundled Python embedding server
+    #[serde(rename = "local")]
+    LocalServer,
+    Ollama,
+    LmStudio,
+}
+
+impl EmbeddingProvider {
+    pub const ALL: [EmbeddingProvider; 3] = [
+        EmbeddingProvider::LocalServer,
+        EmbeddingProvider::Ollama,
+        EmbeddingProvider::LmStudio,
+    ];
+
+    /// Label shown in the Settings picker.
+    pub fn label(self) -> &'static str {
+        match self {
+            EmbeddingProvider::LocalServer => "Local server",
+            EmbeddingProvider::Ollama => "Ollama",
+            EmbeddingProvider::LmStudio => "LM Studio",
+        }
+    }
+
+    /// Value stored in the `config` table.
+    pub fn as_config_str(self) -> &'static str {
+        match self {
+            EmbeddingProvider::LocalServer => "local",
+            EmbeddingProvider::Ollama => "ollama",
+            EmbeddingProvider::LmStudio => "lmstudio",
+        }
+    }
+
+    /// Parse a stored value, falling back to the local server.
+    pub fn from_config_str(value: &str) -> Self {
+        match value {
+            "ollama" => EmbeddingProvider::Ollama,
+            "lmstudio" => EmbeddingProvider::LmStudio,
+            _ => EmbeddingProvider::LocalServer,
+        }
+    }
+
+    /// Server address used when none is configured.
+    pub fn default_url(self) -> String {
+        match self {
+            EmbeddingProvider::LocalServer => local_embedding::default_base_url(),
+            EmbeddingProvider::Ollama => DEFAULT_OLLAMA_URL.to_string(),
+            EmbeddingProvider::LmStudio => DEFAULT_LMSTUDIO_URL.to_string(),
+        }
+    }
+
+    /// Whether the user has to pick a model (the local server has one).
+    pub fn needs_model(self) -> bool {
+        self != EmbeddingProvider::LocalServer
+    }
+}
+
+/// Stored embedding backend choice.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EmbeddingSettings {
+    pub provider: EmbeddingProvider,
+    /// Server address; empty means the provider's default
+    pub url: String,
+    /// Model name; ignored by the local server
+    pub model: String,
+}
+
+impl Default for EmbeddingSettings {
+    fn default() -> Self {
+        Self {
+            provider: EmbeddingProvider::LocalServer,
+            url: String::new(),
+            model: String::new(),
+        }
+    }
+}
+
+impl EmbeddingSettings {
+    /// The configured URL, or the provider's default when blank.
+    pub fn effective_url(&self) -> String {
+        let url = self.url.trim();
+        if url.is_empty() {
+            self.provider.default_url()
+        } else {
+            url.to_string()
+        }
+    }
+
+    /// Check the settings can be used before saving them.
+    pub fn validate(&self) -> crate::Result<()> {
+        let url = self.effective_url();
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err(format!("Server URL must start with http:// or https://: {}", url).into());
+        }
+        if self.provider.needs_model() && self.model.trim().is_empty() {
+            return Err(format!("Choose a {} model", self.provider.label()).into());
+        }
+        Ok(())
+    }
+}
+
+/// Embedding client for whichever provider is configured.
+#[derive(Debug, Clone)]
+pub enum EmbeddingClient {
+    Local(LocalEmbeddingClient),
+    Ollama(OllamaClient),
+    LmStudio(LmStudioClient),
+}
+
+impl EmbeddingClient {
+    pub fn from_settings(settings: &EmbeddingSettings) -> Self {
+        let url = settings.effective_url();
+        let model = settings.model.trim();
+        match settings.provider {
+            EmbeddingProvider::LocalServer => {
+                EmbeddingClient::Local(LocalEmbeddingClient::with_base_url(&url))
+            }
+            EmbeddingProvider::Ollama => EmbeddingClient::Ollama(OllamaClient::new(&url, model)),
+            EmbeddingProvider::LmStudio => {
+                EmbeddingClient::LmStudio(LmStudioClient::new(&url, model))
+            }
+        }
+    }
+
+    /// Generate an embedding for the given text.
+    pub async fn generate_embedding(&self, text: &str) -> anyhow::Result<Vec<f32>> {
+        let _timer = crate::monitoring::Timer::start(crate::monitoring::EMBEDDING_SECONDS);
+        match self {
+            EmbeddingClient::Local(client) => client.generate_embedding(text).await,
+            EmbeddingClient::Ollama(client) => client.generate_embedding(text).await,
+            EmbeddingClient::LmStudio(client) => client.generate_embedding(text).await,
+        }
+    }
+
+    /// Generate embeddings for several texts, in input order.
+    ///
+    /// The local server embeds them in batch requests; other backends get
+    /// up to `EMBED_CONCURRENCY` requests at a time.
+    pub async fn generate_embeddings(&self, texts: &[String]) -> anyhow::Result<Vec<Vec<f32>>> {
+        match self {
+            EmbeddingClient::Local(client) => {
+                let _timer = crate::monitoring::Timer::start(crate::monitoring::EMBEDDING_SECONDS);
+                client.generate_embeddings(texts).await
+            }
+            _ => {
+                // Collected first: a closure inside the stream makes the
+                // future too lifetime-specific to box as `BoxFuture`
+                let requests: Vec<_> = texts
+                    .iter()
+                    .map(|text| self.generate_embedding(text))
+                    .collect();
+                futures_util::stream::iter(requests)
+                    .buffered(EMBED_CONCURRENCY)
+                    .try_collect()
+                    .await
+            }
+        }
+    }
+
+    /// `Ok(true)` when the backend can embed now, `Ok(false)` while it is up
+    /// but not ready (model loading or missing).
+    pub async fn health_check(&self) -> anyhow::Result<bool> {
+        match self {
+            EmbeddingClient::Local(client) => client.health_check().await,
+            EmbeddingClient::Ollama(client) => client.health_check().await,
+            EmbeddingClient::LmStudio(client) => client.health_check().await,
+        }
+    }
+
+    /// Identifies the model producing the vectors, for caches of them.
+    pub fn cache_key(&self) -> String {
+        match self {
+            EmbeddingClient::Local(_) => "local".to_string(),
+            EmbeddingClient::Ollama(client) => format!("ollama:{}", client.model()),
+            EmbeddingClient::LmStudio(client) => format!("lmstudio:{}", client.model()),
+        }
+    }
+
+    /// Human-readable name of the backend.
+    pub fn service_name(&self) -> &'static str {
+        match self {
+            EmbeddingClient::Local(_) => "Local Python Embedding Server",
+            EmbeddingClient::Ollama(_) => "Ollama",
+            EmbeddingClient::LmStudio(_) => "LM Studio",
+        }
+    }
+}
+
+/// What `RagPipeline` needs from an embedding backend.
+///
+/// Implemented by `EmbeddingClient` for the configured provider and by
+/// `MockEmbedding` for tests.
+pub trait EmbeddingBackend: Send + Sync {
+    /// Generate an embedding for the given text.
+    fn generate_embedding<'a>(&'a self, text: &'a str) -> BoxFuture<'a, anyhow::Result<Vec<f32>>>;
+
+    /// Generate embeddings for several texts, in input order.
+    fn generate_embeddings<'a>(
+        &'a self,
+        texts: &'a [String],
+    ) -> BoxFuture<'a, anyhow::Result<Vec<Vec<f32>>>>;
+
+    /// `Ok(true)` when the backend can embed now.
+    fn health_check(&self) -> BoxFuture<'_, anyhow::Result<bool>>;
+
+    /// Identifies the model producing the vectors, for caches of them.
+    fn cache_key(&self) -> String;
+
+    /// Human-readable name of the backend.
+    fn service_name(&self) -> &'static str;
+
+    /// Hint logged when the backend is not ready at startup.
+    fn startup_hint(&self) -> &'static str {
+        "Check the server is running and the model is available."
+    }
+}
+
+impl EmbeddingBackend for EmbeddingClient {
+    fn generate_embedding<'a>(&'a self, text: &'a str) -> BoxFuture<'a, anyhow::Result<Vec<f32>>> {
+        EmbeddingClient::generate_embedding(self, text).boxed()
+    }
+
+    fn generate_embeddings<'a>(
+        &'a self,
+        texts: &'a [String],
+    ) -> BoxFuture<'a, anyhow::Result<Vec<Vec<f32>>>> {
+        EmbeddingClient::generate_embeddings(self, texts).boxed()
+    }
+
+    fn health_check(&self) -> BoxFuture<'_, anyhow::Result<bool>> {
+        EmbeddingClient::health_check(self).boxed()
+    }
+
+    fn cache_key(&self) -> String {
+        EmbeddingClient::cache_key(self)
+    }
+
+    fn service_name(&self) -> &'static str {
+        EmbeddingClient::service_name(self)
+    }
+
+    fn startup_hint(&self) -> &'static str {
+        match self {
+            EmbeddingClient::Local(_) => {
+                "Ensure the Python venv is set up (run start_localmind.sh)."
+            }
+            _ => "Check the server is running and the model is available.",
+        }
+    }
+}
+
+/// Deterministic embedding backend for tests: no server, no model.
+///
+/// Each word of the text (lowercased) is hashed into one of `dimensions`
+/// buckets and the counts are normalized, so the same text always gets the
+/// same vector and texts sharing words get similar ones.
+#[derive(Debug, Clone)]
+pub struct MockEmbedding {
+    dimensions: usize,
+}
+
+impl MockEmbedding {
+    pub const DEFAULT_DIMENSIONS: usize = 64;
+
+    pub fn new(dimensions: usize) -> Self {
+        Self {
+            dimensions: dimensions.max(1),
+        }
+    }
+
+    /// The vector for `text`; text without words maps to the first axis.
+    pub fn embed(&self, text: &str) -> Vec<f32> {
+        let mut vector = vec![0.0f32; self.dimensions];
+        for word in text
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+        {
+            // FNV-1a: stable across runs and Rust versions, unlike DefaultHasher
+            let hash = word
+                .to_lowercase()
+                .bytes()
+                .fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| {
+                    (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+                });
+            vector[(hash % self.dimensions as u64) as usize] += 1.0;
+        }
+
+        let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+        if norm == 0.0 {
+            vector[0] = 1.0;
+        } else {
+            vector.iter_mut().for_each(|v| *v /= norm);
+        }
+        vector
+    }
+}
+
+impl Default for MockEmbedding {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_DIMENSIONS)
+    }
+}
+
+impl EmbeddingBackend for MockEmbedding {
+    fn generate_embedding<'a>(&'a self, text: &'a str) -> BoxFuture<'a, anyhow::Result<Vec<f32>>> {
+        future::ready(Ok(self.embed(text))).boxed()
+    }
+
+    fn generate_embeddings<'a>(
+        &'a self,
+        texts: &'a [String],
+    ) -> BoxFuture<'a, anyhow::Result<Vec<Vec<f32>>>> {
+        future::ready(Ok(texts.iter().map(|text| self.embed(text)).collect())).boxed()
+    }
+
+    fn health_check(&self) -> BoxFuture<'_, anyhow::Result<bool>> {
+        future::ready(Ok(true)).boxed()
+    }
+
+    fn cache_key(&self) -> String {
+        format!("mock:{}", self.dimensions)
+    }
+
+    fn service_name(&self) -> &'static str {
+        "Mock Embedding"
+    }
+}
+
+/// Models available from the provider's server, for the Settings picker.
+///
+/// The local server serves a single built-in model, so this is empty for it.
+pub async fn list_models(settings: &EmbeddingSettings) -> anyhow::Result<Vec<String>> {
+    let url = settings.effective_url();
+    match settings.provider {
+        EmbeddingProvider::LocalServer => Ok(Vec::new()),
+        EmbeddingProvider::Ollama => OllamaClient::new(&url, "").list_models().await,
+        EmbeddingProvider::LmStudio => LmStudioClient::new(&url, "").list_models().await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_provider_config_round_trip() {
+        for provider in EmbeddingProvider::ALL {
+            assert_eq!(
+                EmbeddingProvider::from_config_str(provider.as_config_str()),
+                provider
+            );
+        }
+        assert_eq!(
+            EmbeddingProvider::from_config_str("unknown"),
+            EmbeddingProvider::LocalServer
+        );
+        // Stored as JSON (e.g. the target of a model migration) with the same names
+        for provider in EmbeddingProvider::ALL {
+            assert_eq!(
+                serde_json::to_string(&provider).unwrap(),
+                format!("\"{}\"", provider.as_config_str())
+            );
+        }
+    }
+
+    #[test]
+    fn test_mock_embedding_is_deterministic_and_word_based() {
+        let mock = MockEmbedding::new(32);
+        let tokio = mock.embed("Tokio async runtime");
+        assert_eq!(tokio.len(), 32);
+        assert_eq!(tokio, mock.embed("tokio ASYNC, runtime!"));
+        let norm: f32 = tokio.iter().map(|v| v * v).sum::<f32>().sqrt();
+        assert!((norm - 1.0).abs() < 1e-5);
+
+        let cosine = |a: &[f32], b: &[f32]| a.iter().zip(b).map(|(x, y)| x * y).sum::<f32>();
+        let related = mock.embed("the tokio runtime");
+        let unrelated = mock.embed("sourdough bread recipe");
+        assert!(cosine(&tokio, &related) > cosine(&tokio, &unrelated));
+        assert_eq!(mock.embed("").iter().sum::<f32>(), 1.0);
+    }
+
+    #[test]
+    fn test_settings_validation_and_default_url() {
+        let mut settings = EmbeddingSettings {
+            provider: EmbeddingProvider::Ollama,
+            url: String::new(),
+            model: String::new(),
+        };
+        assert_eq!(settings.effective_url(), DEFAULT_OLLAMA_URL);
+        assert!(settings.validate().is_err());
+
+        settings.model = "nomic-embed-text".to_string();
+        assert!(settings.validate().is_ok());
+
+        settings.url = "localhost:11434".to_string();
+        assert!(settings.validate().is_err());
+
+        assert!(EmbeddingSettings::default().validate().is_ok());
+    }
+}
@@ -0,0 +1,238 @@
+//! Embedding server supervisor
+//!
+//! Launches the bundled Python embedding server as a child process when it
+//! is not already running, checks on it periodically, restarts it if it
+//! crashes, and stops it when LocalMind exits. The launch command can be
+//! overridden in Settings (`embedding_server_command` in the `config` table),
+//! e.g. to use a different Python or extra arguments.
+
+use crate::local_embedding::{spawn_embedding_server, LocalEmbeddingClient};
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+/// How often the child process and server health are checked.
+const CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Consecutive restarts before giving up until the user intervenes.
+pub const MAX_RESTARTS: u32 = 5;
+
+/// Longest wait between restart attempts.
+const MAX_RESTART_DELAY: Duration = Duration::from_secs(60);
+
+/// What the supervisor is doing, shown in the GUI status bar.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EmbeddingServerStatus {
+    /// Child process launched; waiting for the model to load
+    Starting,
+    /// Server answers and the model is loaded
+    Running,
+    /// A server we did not start is answering; it is watched but not owned
+    External,
+    /// The child exited and is being restarted
+    Restarting { attempt: u32 },
+    /// The server could not be (re)started
+    Failed(String),
+}
+
+impl EmbeddingServerStatus {
+    /// Short text for the headless server's log; the GUI shows a
+    /// translated one.
+    pub fn label(&self) -> String {
+        match self {
+            EmbeddingServerStatus::Starting => "Embedding server starting".to_string(),
+            EmbeddingServerStatus::Running => "Embedding server running".to_string(),
+            EmbeddingServerStatus::External => "Embedding server running (external)".to_string(),
+            EmbeddingServerStatus::Restarting { attempt } => {
+                format!("Embedding server restarting ({}/{})", attempt, MAX_RESTARTS)
+            }
+            EmbeddingServerStatus::Failed(e) => format!("Embedding server failed: {}", e),
+        }
+    }
+}
+
+/// Owns the embedding server child process while LocalMind runs.
+///
+/// `shutdown` (or dropping the supervisor) stops the health loop and kills
+/// the child if this process started it.
+pub struct EmbeddingServerSupervisor {
+    child: Arc<Mutex<Option<Child>>>,
+    shutdown: CancellationToken,
+}
+
+impl EmbeddingServerSupervisor {
+    /// Start supervising the server at `base_url`. Must be called from within
+    /// a tokio runtime.
+    ///
+    /// `command` overrides the bundled launch command. Status changes are sent
+    /// on `status_tx`.
+    pub async fn start(
+        base_url: &str,
+        command: Option<String>,
+        status_tx: std::sync::mpsc::Sender<EmbeddingServerStatus>,
+    ) -> Self {
+        let client = LocalEmbeddingClient::with_base_url(base_url);
+        let child = Arc::new(Mutex::new(None));
+
+        if client.is_running().await {
+            println!("Embedding server already running");
+            let _ = status_tx.send(EmbeddingServerStatus::External);
+        } else {
+            println!("Embedding server not running, attempting to start it...");
+            match launch(command.as_deref()) {
+                Ok(process) => {
+                    println!("Embedding server spawned (PID {})", process.id());
+                    *child.lock().unwrap() = Some(process);
+                    let _ = status_tx.send(EmbeddingServerStatus::Starting);
+                }
+                Err(e) => {
+                    eprintln!(
+                        "Could not spawn embedding server: {}. Continuing anyway (may time out).",
+                        e
+                    );
+                    let _ = status_tx.send(EmbeddingServerStatus::Failed(e.to_string()));
+                }
+            }
+        }
+
+        let shutdown = CancellationToken::new();
+        let task_shutdown = shutdown.clone();
+        let task_child = child.clone();
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = task_shutdown.cancelled() => {}
+                _ = supervise(client, command, task_child, status_tx) => {}
+            }
+        });
+
+        Self { child, shutdown }
+    }
+
+    /// Stop supervising and kill the child process, if we started one.
+    pub fn shutdown(&self) {
+        self.shutdown.cancel();
+        if let Some(mut child) = self.child.lock().unwrap().take() {
+            let _ = child.kill();
+            let _ = child.wait();
+            println!("Embedding server process stopped");
+        }
+    }
+}
+
+impl Drop for EmbeddingServerSupervisor {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// Health loop: restart the child when it exits, report status changes.
+async fn supervise(
+    client: LocalEmbeddingClient,
+    command: Option<String>,
+    child: Arc<Mutex<Option<Child>>>,
+    status_tx: std::sync::mpsc::Sender<EmbeddingServerStatus>,
+) {
+    let mut restarts = 0;
+    let mut last_status = None;
+
+    loop {
+        tokio::time::sleep(CHECK_INTERVAL).await;
+
+        // Has the child we started exited?
+        let exited = {
+            let mut guard = child.lock().unwrap();
+            match guard.as_mut().map(|c| c.try_wait()) {
+                Some(Ok(Some(exit_status))) => {
+                    eprintln!("Embedding server exited: {}", exit_status);
+                    *guard = None;
+                    true
+                }
+                _ => false,
+            }
+        };
+        let owned = child.lock().unwrap().is_some();
+
+        let status = if exited || (!owned && !client.is_running().await) {
+            // Crashed, or an external server went away: (re)start our own
+            if restarts >= MAX_RESTARTS {
+                EmbeddingServerStatus::Failed(format!("gave up after {} restarts", MAX_RESTARTS))
+            } else {
+                restarts += 1;
+                let _ = status_tx.send(EmbeddingServerStatus::Restarting { attempt: restarts });
+                tokio::time::sleep(restart_delay(restarts)).await;
+                match launch(command.as_deref()) {
+                    Ok(process) => {
+                        println!(
+                            "Embedding server restarted (PID {}, attempt {})",
+                            process.id(),
+                            restarts
+                        );
+                        *child.lock().unwrap() = Some(process);
+                        EmbeddingServerStatus::Starting
+                    }
+                    Err(e) => EmbeddingServerStatus::Failed(e.to_string()),
+                }
+            }
+        } else {
+            match client.health_check().await {
+                Ok(true) => {
+                    restarts = 0;
+                    if owned {
+                        EmbeddingServerStatus::Running
+                    } else {
+                        EmbeddingServerStatus::External
+                    }
+                }
+                _ => EmbeddingServerStatus::Starting,
+            }
+        };
+
+        if last_status.as_ref() != Some(&status) {
+            if status_tx.send(status.clone()).is_err() {
+                return;
+            }
+            last_status = Some(status);
+        }
+    }
+}
+
+/// Wait before restart attempt `attempt` (1-based): 2s, 4s, 8s, ... up to
+/// `MAX_RESTART_DELAY`.
+fn restart_delay(attempt: u32) -> Duration {
+    Duration::from_secs(2u64.saturating_pow(attempt)).min(MAX_RESTART_DELAY)
+}
+
+/// Launch the server with `command` (whitespace-separated program and
+/// arguments), or the bundled venv Python when none is configured.
+fn launch(command: Option<&str>) -> std::io::Result<Child> {
+    let Some(command) = command.map(str::trim).filter(|c| !c.is_empty()) else {
+        return spawn_embedding_server();
+    };
+
+    let mut parts = command.split_whitespace();
+    let program = parts.next().unwrap_or_default();
+    log::info!("Spawning embedding server with custom command: {}", command);
+    Command::new(program)
+        .args(parts)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restart_delay_backs_off_and_caps() {
+        assert_eq!(restart_delay(1), Duration::from_secs(2));
+        assert_eq!(restart_delay(3), Duration::from_secs(8));
+        assert_eq!(restart_delay(MAX_RESTARTS + 10), MAX_RESTART_DELAY);
+    }
+
+    #[test]
+    fn test_launch_reports_missing_program() {
+        assert!(launch(Some("localmind-no-such-embedding-server --port 1")).is_err());
+    }
+}
@@ -0,0 +1,246 @@
+//! Retrieval quality evaluation
+//!
+//! Runs a labeled set of `(query, expected_doc_url)` pairs through the search
+//! pipeline and reports mean reciprocal rank and recall@k, so chunking
+//! strategies, hybrid search weighting, and rerankers can be compared on the
+//! same query set.
+
+use crate::db::normalize_url;
+use crate::rag::{escape_fts_query, RagPipeline, SearchOptions};
+use crate::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// A labeled query and the URL of the document that should be retrieved.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvalCase {
+    pub query: String,
+    pub expected_doc_url: String,
+}
+
+/// Which retrieval path to evaluate.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum SearchMode {
+    /// Vector + BM25 fused with RRF (what the GUI uses)
+    Fused,
+    /// Fused search with synonym/paraphrase query expansion
+    Expanded,
+    /// Chunk vector search only
+    Vector,
+    /// FTS5 / BM25 only
+    Fts,
+}
+
+impl SearchMode {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "fused" => Some(SearchMode::Fused),
+            "expanded" => Some(SearchMode::Expanded),
+            "vector" => Some(SearchMode::Vector),
+            "fts" => Some(SearchMode::Fts),
+            _ => None,
+        }
+    }
+}
+
+/// Outcome for a single case.
+#[derive(Debug, Clone, Serialize)]
+pub struct CaseResult {
+    pub query: String,
+    pub expected_doc_url: String,
+    /// 1-based rank of the expected document, if retrieved
+    pub rank: Option<usize>,
+}
+
+/// Aggregate metrics over a query set.
+#[derive(Debug, Clone, Serialize)]
+pub struct EvalReport {
+    pub mode: SearchMode,
+    pub cases: Vec<CaseResult>,
+    pub mrr: f64,
+    /// (k, recall@k) for each requested cutoff
+    pub recall_at: Vec<(usize, f64)>,
+}
+
+/// The on-disk format: either a bare array of cases or `{ "cases": [...] }`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum CaseFile {
+    List(Vec<EvalCase>),
+    Wrapped { cases: Vec<EvalCase> },
+}
+
+/// Load labeled cases from a JSON file.
+pub fn load_cases(path: &Path) -> Result<Vec<EvalCase>> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+    parse_cases(&text)
+}
+
+fn parse_cases(text: &str) -> Result<Vec<EvalCase>> {
+    let cases = match serde_json::from_str::<CaseFile>(text)? {
+        CaseFile::List(cases) => cases,
+        CaseFile::Wrapped { cases } => cases,
+    };
+    if cases.is_empty() {
+        return Err("Eval file contains no cases".into());
+    }
+    Ok(cases)
+}
+
+/// 1-based rank of `expected` in `ranked_urls`, comparing URLs normalized
+/// with `tracking_params` removed.
+pub fn rank_of(
+    ranked_urls: &[String],
+    expected: &str,
+    tracking_params: &[String],
+) -> Option<usize> {
+    let expected = normalize_url(expected, tracking_params);
+    ranked_urls
+        .iter()
+        .position(|url| normalize_url(url, tracking_params) == expected)
+        .map(|i| i + 1)
+}
+
+/// Mean reciprocal rank; misses contribute 0.
+pub fn mean_reciprocal_rank(ranks: &[Option<usize>]) -> f64 {
+    if ranks.is_empty() {
+        return 0.0;
+    }
+    let total: f64 = ranks
+        .iter()
+        .map(|r| r.map_or(0.0, |r| 1.0 / r as f64))
+        .sum();
+    total / ranks.len() as f64
+}
+
+/// Fraction of cases whose expected document ranked within the top `k`.
+pub fn recall_at_k(ranks: &[Option<usize>], k: usize) -> f64 {
+    if ranks.is_empty() {
+        return 0.0;
+    }
+    let hits = ranks
+        .iter()
+        .filter(|r| matches!(r, Some(r) if *r <= k))
+        .count();
+    hits as f64 / ranks.len() as f64
+}
+
+/// Retrieve ranked document URLs for a query using the given mode.
+async fn ranked_urls(rag: &RagPipeline, query: &str, mode: SearchMode) -> Result<Vec<String>> {
+    let doc_ids: Vec<i64> = match mode {
+        SearchMode::Fused | SearchMode::Expanded => {
+            let options = SearchOptions {
+                include_dead: false,
+                expand_query: mode == SearchMode::Expanded,
+                collection_id: None,
+                boost_linked: false,
+                correct_spelling: false,
+            };
+            rag.get_search_hits_fused(query, options)
+                .await?
+                .into_iter()
+                .map(|hit| hit.doc_id)
+                .collect()
+        }
+        SearchMode::Vector => rag
+            .get_search_hits_with_cutoff(query, 0.0)
+            .await?
+            .into_iter()
+            .map(|hit| hit.doc_id)
+            .collect(),
+        SearchMode::Fts => {
+            let escaped = escape_fts_query(query);
+            if escaped.is_empty() {
+                return Ok(Vec::new());
+            }
+            return Ok(rag
+                .db
+                .search_documents_scored(&escaped, 20, false)
+                .await?
+                .into_iter()
+                .filter_map(|(doc, _)| doc.url)
+                .collect());
+        }
+    };
+
+    // Search hits carry IDs only; look up URLs and keep the ranking order
+    let urls: HashMap<i64, String> = rag
+        .db
+        .get_documents_batch(&doc_ids)
+        .await?
+        .into_iter()
+        .filter_map(|doc| doc.url.map(|url| (doc.id, url)))
+        .collect();
+
+    Ok(doc_ids
+        .iter()
+        .filter_map(|id| urls.get(id).cloned())
+        .collect())
+}
+
+/// Run every case through the pipeline and compute MRR and recall@k.
+pub async fn run_eval(
+    rag: &RagPipeline,
+    cases: &[EvalCase],
+    mode: SearchMode,
+    ks: &[usize],
+) -> Result<EvalReport> {
+    let tracking_params = rag.db.tracking_params();
+    let mut results = Vec::with_capacity(cases.len());
+    for case in cases {
+        let urls = ranked_urls(rag, &case.query, mode).await?;
+        results.push(CaseResult {
+            query: case.query.clone(),
+            expected_doc_url: case.expected_doc_url.clone(),
+            rank: rank_of(&urls, &case.expected_doc_url, &tracking_params),
+        });
+    }
+
+    let ranks: Vec<Option<usize>> = results.iter().map(|r| r.rank).collect();
+    Ok(EvalReport {
+        mode,
+        mrr: mean_reciprocal_rank(&ranks),
+        recall_at: ks.iter().map(|&k| (k, recall_at_k(&ranks, k))).collect(),
+        cases: results,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rank_matches_normalized_urls() {
+        let urls = [
+            "https://a.example/".to_string(),
+            "https://b.example/page".to_string(),
+        ];
+        let params = crate::tracking_params::defaults();
+        assert_eq!(
+            rank_of(&urls, "https://b.example/page?utm_source=x#intro", &params),
+            Some(2)
+        );
+        assert_eq!(rank_of(&urls, "https://c.example/", &params), None);
+    }
+
+    #[test]
+    fn mrr_and_recall() {
+        let ranks = [Some(1), Some(2), None, Some(5)];
+        let expected_mrr = (1.0 + 0.5 + 0.0 + 0.2) / 4.0;
+        assert!((mean_reciprocal_rank(&ranks) - expected_mrr).abs() < 1e-9);
+        assert_eq!(recall_at_k(&ranks, 1), 0.25);
+        assert_eq!(recall_at_k(&ranks, 5), 0.75);
+        assert_eq!(mean_reciprocal_rank(&[]), 0.0);
+    }
+
+    #[test]
+    fn parses_bare_and_wrapped_case_files() {
+        let bare = r#"[{"query": "q", "expected_doc_url": "https://a.example"}]"#;
+        let wrapped = r#"{"cases": [{"query": "q", "expected_doc_url": "https://a.example"}]}"#;
+        assert_eq!(parse_cases(bare).unwrap().len(), 1);
+        assert_eq!(parse_cases(wrapped).unwrap().len(), 1);
+        assert!(parse_cases("[]").is_err());
+    }
+}
@@ -0,0 +1,172 @@
+//! Site favicons
+//!
+//! Icons of the sites documents come from are fetched in the background
+//! after ingestion and cached per host in the database, as small PNGs, so
+//! result lists can show them without going to the network. A host that has
+//! no usable icon is cached as an empty icon and retried after a while.
+//! Documents without a site icon show an icon for their source instead.
+
+use crate::db::Database;
+use crate::ingest_policy::SourceKind;
+use crate::Result;
+use regex::Regex;
+use std::collections::HashSet;
+use std::io::Cursor;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Icons are stored at this size (pixels, square).
+pub const ICON_SIZE: u32 = 32;
+
+/// Hosts without an icon are tried again after this long.
+pub const RETRY_AFTER_SECS: i64 = 30 * 24 * 60 * 60;
+
+/// Largest icon file downloaded.
+const MAX_ICON_BYTES: usize = 512 * 1024;
+
+/// Host of an http(s) URL, lowercased; `None` for other URLs.
+pub fn site_host(url: &str) -> Option<String> {
+    let parsed = url::Url::parse(url).ok()?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return None;
+    }
+    parsed.host_str().map(|host| host.to_ascii_lowercase())
+}
+
+/// `href` of the first `<link rel="icon">` (or "shortcut icon") in a page.
+pub fn icon_href(html: &str) -> Option<String> {
+    static LINK: OnceLock<Regex> = OnceLock::new();
+    static HREF: OnceLock<Regex> = OnceLock::new();
+    let link = LINK.get_or_init(|| {
+        Regex::new(r#"(?i)<link\b[^>]*\brel\s*=\s*["']?(?:shortcut\s+)?icon["']?[^>]*>"#).unwrap()
+    });
+    let href = HREF.get_or_init(|| Regex::new(r#"(?i)\bhref\s*=\s*["']([^"']+)["']"#).unwrap());
+    let tag = link.find(html)?;
+    Some(href.captures(tag.as_str())?[1].to_string())
+}
+
+/// Decode an icon (ICO or PNG) and re-encode it as a PNG of at most
+/// `ICON_SIZE` pixels square. `None` when it cannot be decoded.
+pub fn normalize_icon(bytes: &[u8]) -> Option<Vec<u8>> {
+    let image = image::load_from_memory(bytes).ok()?;
+    let image = if image.width() > ICON_SIZE || image.height() > ICON_SIZE {
+        image.thumbnail(ICON_SIZE, ICON_SIZE)
+    } else {
+        image
+    };
+    let mut png = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+        .ok()?;
+    Some(png)
+}
+
+/// HTTP client for icon and thumbnail downloads.
+pub fn client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(5))
+        .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36")
+        .build()
+        .unwrap_or_else(|_| reqwest::Client::new())
+}
+
+async fn download(client: &reqwest::Client, url: &str) -> Option<Vec<u8>> {
+    let response = client.get(url).send().await.ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    let bytes = response.bytes().await.ok()?;
+    (bytes.len() <= MAX_ICON_BYTES).then(|| bytes.to_vec())
+}
+
+/// Fetch a host's icon as a normalized PNG: `/favicon.ico` first, then the
+/// icon linked from its home page. Empty when the host has none.
+pub async fn fetch_favicon(client: &reqwest::Client, host: &str) -> Vec<u8> {
+    let base = format!("https://{}/", host);
+    if let Some(icon) = download(client, &format!("{}favicon.ico", base))
+        .await
+        .and_then(|bytes| normalize_icon(&bytes))
+    {
+        return icon;
+    }
+
+    let Some(page) = download(client, &base).await else {
+        return Vec::new();
+    };
+    let Some(href) = icon_href(&String::from_utf8_lossy(&page)) else {
+        return Vec::new();
+    };
+    let Ok(icon_url) = url::Url::parse(&base).and_then(|base| base.join(&href)) else {
+        return Vec::new();
+    };
+    download(client, icon_url.as_str())
+        .await
+        .and_then(|bytes| normalize_icon(&bytes))
+        .unwrap_or_default()
+}
+
+/// Hosts of stored documents that have no cached icon yet, at most `limit`.
+/// Documents whose source has remote fetching turned off in its ingestion
+/// policy are skipped.
+pub async fn hosts_missing_icons(db: &Database, limit: usize) -> Result<Vec<String>> {
+    let cached = db.get_cached_favicon_hosts(RETRY_AFTER_SECS).await?;
+    let policies = db.get_ingest_policies().await?;
+
+    let mut seen = HashSet::new();
+    let mut hosts = Vec::new();
+    for (url, source) in db.get_document_urls().await? {
+        if let Some(kind) = SourceKind::of(&source) {
+            if kind.fetches_remote() && !policies.get(kind).fetch_remote {
+                continue;
+            }
+        }
+        let Some(host) = site_host(&url) else {
+            continue;
+        };
+        if cached.contains(&host) || !seen.insert(host.clone()) {
+            continue;
+        }
+        hosts.push(host);
+        if hosts.len() == limit {
+            break;
+        }
+    }
+    Ok(hosts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_hosts_and_linked_icons() {
+        assert_eq!(
+            site_host("https://Docs.RS/serde/latest"),
+            Some("docs.rs".to_string())
+        );
+        assert_eq!(site_host("file:///home/me/notes.md"), None);
+        assert_eq!(site_host("not a url"), None);
+
+        let html = r#"<head><link rel="stylesheet" href="/a.css">
+            <link rel="shortcut icon" type="image/png" href="/static/icon.png"></head>"#;
+        assert_eq!(icon_href(html), Some("/static/icon.png".to_string()));
+        assert_eq!(
+            icon_href("<link rel='apple-touch-icon' href='/x.png'>"),
+            None
+        );
+    }
+
+    #[test]
+    fn icons_are_shrunk_to_png() {
+        let mut source = Vec::new();
+        image::DynamicImage::new_rgba8(64, 64)
+            .write_to(&mut Cursor::new(&mut source), image::ImageFormat::Png)
+            .unwrap();
+
+        let icon = normalize_icon(&source).unwrap();
+        let decoded = image::load_from_memory(&icon).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (ICON_SIZE, ICON_SIZE));
+
+        assert_eq!(normalize_icon(b"<html>not an icon</html>"), None);
+    }
+}
@@ -1,17 +1,80 @@
+use crate::html_text::{self, HtmlText, PageStart, PageStructure};
 use pdf_extract;
 use readability::extractor;
 use reqwest;
 use std::time::Duration;
 use url::Url;
 
+/// Most characters of a PDF's text kept; later pages are left out.
+pub const MAX_PDF_CHARS: usize = 100_000;
+
+/// Most characters kept of a page read by a site extractor, which keeps
+/// only the useful part of the page.
+pub const MAX_SITE_CHARS: usize = 20_000;
+
+/// Pulls the useful text out of one site's pages where readability keeps
+/// the wrong parts; `None` for pages it does not handle.
+type SiteExtractor = fn(&str, &Url) -> Option<HtmlText>;
+
+/// Site extractors by domain; a domain matches its subdomains too.
+const SITE_EXTRACTORS: &[(&str, SiteExtractor)] = &[
+    ("stackoverflow.com", crate::stack_exchange::extract),
+    ("stackexchange.com", crate::stack_exchange::extract),
+    ("superuser.com", crate::stack_exchange::extract),
+    ("serverfault.com", crate::stack_exchange::extract),
+    ("askubuntu.com", crate::stack_exchange::extract),
+    ("mathoverflow.net", crate::stack_exchange::extract),
+    ("stackapps.com", crate::stack_exchange::extract),
+];
+
+/// The extractor registered for `url`'s domain.
+fn site_extractor(url: &Url) -> Option<SiteExtractor> {
+    let host = url.host_str()?.to_ascii_lowercase();
+    SITE_EXTRACTORS
+        .iter()
+        .find(|(domain, _)| {
+            host.strip_suffix(*domain)
+                .is_some_and(|rest| rest.is_empty() || rest.ends_with('.'))
+        })
+        .map(|(_, extractor)| *extractor)
+}
+
+/// `url` read through its site's API: GitHub repositories and gists (see
+/// `github`) and posts on X or Mastodon (see `social`). `None` for other
+/// URLs.
+async fn fetch_through_api(url: &str) -> Option<crate::Result<FetchResult>> {
+    if let Some(target) = crate::github::GitHubTarget::parse(url) {
+        return Some(crate::github::fetch(&target).await);
+    }
+    if let Some(post) = crate::social::PostUrl::parse(url) {
+        return Some(crate::social::fetch(&post).await);
+    }
+    None
+}
+
+/// Text of a page from the extractor registered for its site, if any.
+pub fn site_text(html: &str, url: &str) -> Option<HtmlText> {
+    let url = Url::parse(url).ok()?;
+    site_extractor(&url)?(html, &url)
+}
+
 /// Result of a fetch attempt, including whether auth was required
 pub struct FetchResult {
     pub content: String,
     pub needs_auth: bool,
+    /// Headings and outgoing links of the page's main content, or where
+    /// each page of a PDF starts
+    pub structure: PageStructure,
+    /// The page's preview image, for its thumbnail
+    pub image_url: Option<String>,
+    /// The page's `<title>`, for pages saved without one
+    pub title: Option<String>,
 }
 
 pub struct WebFetcher {
     client: reqwest::Client,
+    /// Describes linked images when active (see `image_caption`)
+    image_captions: crate::image_caption::ImageCaptionSettings,
 }
 
 #[allow(clippy::new_without_default)]
@@ -23,11 +86,24 @@ impl WebFetcher {
             .build()
             .unwrap_or_else(|_| reqwest::Client::new());
 
-        Self { client }
+        Self {
+            client,
+            image_captions: Default::default(),
+        }
+    }
+
+    /// Caption linked images with `settings`, usually the library's.
+    pub fn with_image_captions(
+        mut self,
+        settings: crate::image_caption::ImageCaptionSettings,
+    ) -> Self {
+        self.image_captions = settings;
+        self
     }
 
     /// Fetch page content with auth status detection.
-    /// Returns a FetchResult indicating whether auth was required.
+    /// Returns a FetchResult indicating whether auth was required, or an error
+    /// if the request failed or the server returned a non-success status.
     pub async fn fetch_page_content_with_status(
         &self,
         url: &str,
@@ -38,18 +114,30 @@ impl WebFetcher {
             return Ok(FetchResult {
                 content: String::new(),
                 needs_auth: false,
+                structure: PageStructure::default(),
+                image_url: None,
+                title: None,
             });
         }
 
+        // Some sites are read through their APIs, which give what the page
+        // buries (a README) or leaves to JavaScript (a thread)
+        if let Some(fetched) = fetch_through_api(url).await {
+            match fetched {
+                Ok(result) => {
+                    println!("Fetched {} through the site's API", url);
+                    return Ok(result);
+                }
+                Err(e) => println!("API fetch failed for {}: {}, fetching the page", url, e),
+            }
+        }
+
         // Fetch the page
         let response = match self.client.get(url).send().await {
             Ok(resp) => resp,
             Err(e) => {
                 println!("Failed to fetch {}: {}", url, e);
-                return Ok(FetchResult {
-                    content: String::new(),
-                    needs_auth: false,
-                });
+                return Err(format!("Request failed: {}", e).into());
             }
         };
 
@@ -60,21 +148,29 @@ impl WebFetcher {
             return Ok(FetchResult {
                 content: String::new(),
                 needs_auth: true,
+                structure: PageStructure::default(),
+                image_url: None,
+                title: None,
             });
         }
 
         if !status.is_success() {
             println!("HTTP {} for {}", status, url);
-            return Ok(FetchResult {
-                content: String::new(),
-                needs_auth: false,
-            });
+            return Err(format!("HTTP {}", status).into());
         }
 
-        let content = self.extract_content(url, response).await?;
+        let mut structure = PageStructure::default();
+        let mut image_url = None;
+        let mut title = None;
+        let content = self
+            .extract_content(url, response, &mut structure, &mut image_url, &mut title)
+            .await?;
         Ok(FetchResult {
             content,
             needs_auth: false,
+            structure,
+            image_url,
+            title,
         })
     }
 
@@ -90,6 +186,9 @@ impl WebFetcher {
         &self,
         url: &str,
         response: reqwest::Response,
+        structure: &mut PageStructure,
+        image_url: &mut Option<String>,
+        title: &mut Option<String>,
     ) -> Result<String, Box<dyn std::error::Error>> {
         // Check content type to handle different file types properly
         let content_type = response
@@ -116,39 +215,24 @@ impl WebFetcher {
             let filename = url.split('/').last().unwrap_or("document.pdf");
 
             // Use catch_unwind to prevent panics from the pdf_extract library
-            let pdf_result =
-                std::panic::catch_unwind(|| pdf_extract::extract_text_from_mem(&pdf_bytes));
+            let pdf_result = std::panic::catch_unwind(|| {
+                pdf_extract::extract_text_from_mem_by_pages(&pdf_bytes)
+            });
 
             match pdf_result {
-                Ok(Ok(text)) if !text.trim().is_empty() => {
-                    let cleaned_text = text
-                        .lines()
-                        .map(|line| line.trim())
-                        .filter(|line| !line.is_empty())
-                        .collect::<Vec<_>>()
-                        .join("\n");
-
-                    // REQUIREMENT: Limit PDF content to 2000 chars max to ensure ~4 chunks per document
-                    // This prevents excessive embedding generation and maintains search quality
-                    let result = if cleaned_text.len() > 2000 {
-                        // Ensure UTF-8 boundary safety when truncating
-                        let mut boundary = 2000;
-                        while boundary > 0 && !cleaned_text.is_char_boundary(boundary) {
-                            boundary -= 1;
-                        }
-                        if boundary == 0 {
-                            format!("PDF Document: {}\nURL: {}\n\n[PDF content too large and unable to find safe UTF-8 boundary]", filename, url)
-                        } else {
-                            format!("PDF Document: {}\nURL: {}\n\n{}...\n\n[PDF content truncated at {} chars]", filename, url, &cleaned_text[..boundary], boundary)
-                        }
-                    } else {
-                        format!(
-                            "PDF Document: {}\nURL: {}\n\n{}",
-                            filename, url, cleaned_text
-                        )
-                    };
-
-                    println!("Extracted {} chars of text from PDF: {}", result.len(), url);
+                Ok(Ok(pages)) if pages.iter().any(|page| !page.trim().is_empty()) => {
+                    // Whole PDFs are kept, page by page, so chunks and
+                    // search results can cite their page
+                    let header = format!("PDF Document: {}\nURL: {}", filename, url);
+                    let (result, pages) = pdf_text(&header, &pages);
+                    *structure = pages;
+
+                    println!(
+                        "Extracted {} chars of text from {} PDF pages: {}",
+                        result.len(),
+                        structure.pages.len(),
+                        url
+                    );
                     return Ok(result);
                 }
                 Ok(Ok(_)) => {
@@ -181,6 +265,31 @@ impl WebFetcher {
             }
         }
 
+        // Images linked directly are described by a vision model when image
+        // captions are on (see `image_caption`)
+        if content_type.contains("image/") && self.image_captions.is_active() {
+            let content_type = content_type.to_string();
+            #[allow(clippy::double_ended_iterator_last)]
+            let filename = url.split('/').last().unwrap_or("image");
+            match response.bytes().await {
+                Ok(bytes) => {
+                    match crate::image_caption::caption(&bytes, &self.image_captions).await {
+                        Ok(Some(caption)) => {
+                            println!("Captioned image: {}", url);
+                            return Ok(format!("Image: {}\nURL: {}\n\n{}", filename, url, caption));
+                        }
+                        Ok(None) => {}
+                        Err(e) => println!("⚠️ Image caption failed for {}: {}", url, e),
+                    }
+                }
+                Err(e) => println!("Failed to get image bytes from {}: {}", url, e),
+            }
+            return Ok(format!(
+                "Binary file: {} ({})\nURL: {}",
+                filename, content_type, url
+            ));
+        }
+
         // Handle other binary content types that should not be processed as text
         if content_type.contains("image/")
             || content_type.contains("video/")
@@ -214,22 +323,15 @@ impl WebFetcher {
             let filename = url.split('/').last().unwrap_or("document.pdf");
 
             // Try to extract text from the PDF content with panic protection
-            let pdf_result =
-                std::panic::catch_unwind(|| pdf_extract::extract_text_from_mem(html.as_bytes()));
+            let pdf_result = std::panic::catch_unwind(|| {
+                pdf_extract::extract_text_from_mem_by_pages(html.as_bytes())
+            });
 
             match pdf_result {
-                Ok(Ok(text)) if !text.trim().is_empty() => {
-                    let cleaned_text = text
-                        .lines()
-                        .map(|line| line.trim())
-                        .filter(|line| !line.is_empty())
-                        .collect::<Vec<_>>()
-                        .join("\n");
-
-                    let result = format!(
-                        "PDF Document: {}\nURL: {}\n\n{}",
-                        filename, url, cleaned_text
-                    );
+                Ok(Ok(pages)) if pages.iter().any(|page| !page.trim().is_empty()) => {
+                    let header = format!("PDF Document: {}\nURL: {}", filename, url);
+                    let (result, pages) = pdf_text(&header, &pages);
+                    *structure = pages;
                     println!("Extracted text from PDF served as text: {}", url);
                     return Ok(result);
                 }
@@ -257,62 +359,20 @@ impl WebFetcher {
             }
         }
 
-        // Use readability to extract clean content
-        let text_content = match Url::parse(url) {
-            Ok(parsed_url) => {
-                match extractor::extract(&mut html.as_bytes(), &parsed_url) {
-                    Ok(product) => {
-                        let mut content = String::new();
-
-                        // Add title if available
-                        if !product.title.trim().is_empty() {
-                            content.push_str(&product.title);
-                            content.push_str("\n\n");
-                        }
-
-                        // Add main text content
-                        content.push_str(&product.text);
-                        content
-                    }
-                    Err(e) => {
-                        println!("⚠️ Readability extraction failed for {}, falling back to basic text: {}", url, e);
-                        // Fallback to basic text extraction if readability fails
-                        html.chars()
-                            .filter(|c| c.is_ascii_graphic() || c.is_whitespace())
-                            .collect::<String>()
-                            .lines()
-                            .map(|line| line.trim())
-                            .filter(|line| !line.is_empty() && line.len() > 3)
-                            .collect::<Vec<_>>()
-                            .join("\n")
-                    }
-                }
-            }
-            Err(_) => {
-                println!("⚠️ Invalid URL for readability: {}, using fallback", url);
-                // Fallback if URL parsing fails
-                html.chars()
-                    .filter(|c| c.is_ascii_graphic() || c.is_whitespace())
-                    .collect::<String>()
-                    .lines()
-                    .map(|line| line.trim())
-                    .filter(|line| !line.is_empty() && line.len() > 3)
-                    .collect::<Vec<_>>()
-                    .join("\n")
-            }
-        };
-
-        // Clean up whitespace
-        let cleaned = text_content
-            .lines()
-            .map(|line| line.trim())
-            .filter(|line| !line.is_empty())
-            .collect::<Vec<_>>()
-            .join("\n");
+        *image_url = crate::thumbnail::preview_image_url(&html, url);
+        *title = page_title(&html);
+        let site_page = site_text(&html, url);
 
         // REQUIREMENT: Limit content to 2000 chars max to ensure ~4 chunks per document
-        // This prevents excessive embedding generation and maintains search quality
-        let max_chars = 2000;
+        // This prevents excessive embedding generation and maintains search quality.
+        // Site extractors keep only the useful part, so more of it is kept.
+        let max_chars = if site_page.is_some() {
+            MAX_SITE_CHARS
+        } else {
+            2000
+        };
+        let page = site_page.unwrap_or_else(|| extract_readable_text(&html, url));
+        let cleaned = page.text;
         let result = if cleaned.len() > max_chars {
             // Make sure we don't cut in the middle of a UTF-8 character
             let mut boundary = max_chars;
@@ -331,7 +391,168 @@ impl WebFetcher {
         } else {
             cleaned
         };
+        *structure = page.structure.located(&result);
 
         Ok(result)
     }
 }
+
+/// Text of a PDF from the text of its pages: `header`, then each page with
+/// blank lines and surrounding whitespace stripped, a blank line apart,
+/// with where each page starts. Empty pages are skipped, and pages past
+/// `MAX_PDF_CHARS` are left out with a note saying so.
+///
+/// Shared by fetched PDFs and PDFs in watched folders.
+pub fn pdf_text(header: &str, pages: &[String]) -> (String, PageStructure) {
+    let mut text = header.to_string();
+    let mut structure = PageStructure::default();
+    for (index, page) in pages.iter().enumerate() {
+        let cleaned = page
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let Some(first_line) = cleaned.lines().next() else {
+            continue;
+        };
+        if let Some(last) = structure.pages.last() {
+            if text.len() + cleaned.len() > MAX_PDF_CHARS {
+                text.push_str(&format!(
+                    "\n\n[PDF content truncated after page {}]",
+                    last.number
+                ));
+                break;
+            }
+        }
+        if !text.is_empty() {
+            text.push_str("\n\n");
+        }
+        structure.pages.push(PageStart {
+            number: index as u32 + 1,
+            first_line: first_line.to_string(),
+            offset: text.len(),
+        });
+        text.push_str(&cleaned);
+    }
+    (text, structure)
+}
+
+/// Text of a page's `<title>` element, if it has a non-empty one.
+pub fn page_title(html: &str) -> Option<String> {
+    let title = regex::Regex::new(r"(?is)<title[^>]*>(.*?)</title>")
+        .ok()?
+        .captures(html)?[1]
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    let title = crate::bookmark_import::unescape(&title);
+    (!title.is_empty()).then_some(title)
+}
+
+/// Readable text of an HTML page: readability's article extraction (with
+/// the title as the first line), falling back to the page's raw text when
+/// that fails, with blank lines and surrounding whitespace stripped. The
+/// article's headings and anchors come with it (see `html_text`), located
+/// in the returned text.
+///
+/// Shared by the bookmark fetcher and HTML submitted to the HTTP API, so
+/// both extract the same way.
+pub fn extract_readable_text(html: &str, url: &str) -> HtmlText {
+    // Use readability to extract clean content
+    let (text_content, structure) = match Url::parse(url) {
+        Ok(parsed_url) => {
+            match extractor::extract(&mut html.as_bytes(), &parsed_url) {
+                Ok(product) => {
+                    let article = html_text::extract(&product.content, Some(&parsed_url));
+                    let mut content = String::new();
+
+                    // Add title if available
+                    if !product.title.trim().is_empty() {
+                        content.push_str(&product.title);
+                        content.push_str("\n\n");
+                    }
+
+                    // Add main text content
+                    content.push_str(&article.text);
+                    (content, article.structure)
+                }
+                Err(e) => {
+                    println!(
+                        "⚠️ Readability extraction failed for {}, falling back to basic text: {}",
+                        url, e
+                    );
+                    // Fallback to basic text extraction if readability fails
+                    (fallback_text(html), PageStructure::default())
+                }
+            }
+        }
+        Err(_) => {
+            println!("⚠️ Invalid URL for readability: {}, using fallback", url);
+            // Fallback if URL parsing fails
+            (fallback_text(html), PageStructure::default())
+        }
+    };
+
+    // Clean up whitespace
+    let text = text_content
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let structure = structure.located(&text);
+    HtmlText { text, structure }
+}
+
+/// Printable lines of raw markup, for pages readability cannot handle.
+fn fallback_text(html: &str) -> String {
+    html.chars()
+        .filter(|c| c.is_ascii_graphic() || c.is_whitespace())
+        .collect::<String>()
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && line.len() > 3)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn site_extractors_match_subdomains() {
+        let registered = |url: &str| site_extractor(&Url::parse(url).unwrap()).is_some();
+        assert!(registered("https://stackoverflow.com/questions/1"));
+        assert!(registered("https://es.stackoverflow.com/questions/1"));
+        assert!(registered("https://unix.stackexchange.com/questions/1"));
+        assert!(!registered("https://notstackoverflow.com/questions/1"));
+        assert!(!registered("https://example.com/questions/1"));
+    }
+
+    #[test]
+    fn pdf_text_records_where_pages_start() {
+        let pages = vec![
+            "  First page\n\n body one ".to_string(),
+            "\n \n".to_string(),
+            "Third page\nbody three".to_string(),
+        ];
+        let (text, structure) = pdf_text("PDF Document: a.pdf", &pages);
+        assert_eq!(
+            text,
+            "PDF Document: a.pdf\n\nFirst page\nbody one\n\nThird page\nbody three"
+        );
+        let numbers: Vec<u32> = structure.pages.iter().map(|page| page.number).collect();
+        assert_eq!(numbers, vec![1, 3]);
+        assert!(text[structure.pages[1].offset..].starts_with("Third page"));
+        assert_eq!(structure.located(&text), structure);
+        assert_eq!(structure.page_at(0), None);
+        assert_eq!(structure.page_at(text.len() - 1), Some(3));
+
+        let long = vec!["x".repeat(MAX_PDF_CHARS), "Too far".to_string()];
+        let (text, structure) = pdf_text("", &long);
+        assert_eq!(structure.pages.len(), 1);
+        assert!(text.ends_with("[PDF content truncated after page 1]"));
+    }
+}
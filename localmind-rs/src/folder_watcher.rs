@@ -4,6 +4,7 @@
 //! automatic ingestion of PDF, Markdown, and plain-text files.
 
 use crate::gui::state::FolderWatchEvent;
+use crate::html_text::PageStructure;
 use notify::{Event, EventKind, RecursiveMode, Watcher};
 use std::collections::HashMap;
 use std::fmt;
@@ -280,6 +281,11 @@ impl FolderWatchService {
     pub fn stop_watching(&mut self, folder_path: &Path) {
         self.watchers.remove(folder_path);
     }
+
+    /// Stop every watcher, e.g. before switching to another library.
+    pub fn stop_all(&mut self) {
+        self.watchers.clear();
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -291,6 +297,12 @@ impl FolderWatchService {
 /// Supported extensions: `.txt`, `.md` (UTF-8 read), `.pdf` (pdf-extract).
 /// Returns `Err(FolderWatchError::UnsupportedType)` for other extensions.
 pub fn read_file_content(path: &Path) -> Result<String, FolderWatchError> {
+    read_file(path).map(|(content, _)| content)
+}
+
+/// Read a supported file like `read_file_content`, with where each page
+/// starts for PDFs (see `fetcher::pdf_text`).
+pub fn read_file(path: &Path) -> Result<(String, PageStructure), FolderWatchError> {
     let ext = path
         .extension()
         .and_then(|e| e.to_str())
@@ -299,7 +311,12 @@ pub fn read_file_content(path: &Path) -> Result<String, FolderWatchError> {
 
     match ext.as_str() {
         "txt" | "md" => std::fs::read_to_string(path)
-            .map(|s| strip_data_url_images(strip_yaml_frontmatter(&s)))
+            .map(|s| {
+                (
+                    strip_data_url_images(strip_yaml_frontmatter(&s)),
+                    PageStructure::default(),
+                )
+            })
             .map_err(|e| {
                 eprintln!(
                     "[folder_watcher] failed to read file: path={}, error={}",
@@ -308,14 +325,16 @@ pub fn read_file_content(path: &Path) -> Result<String, FolderWatchError> {
                 );
                 FolderWatchError::IoError(e.to_string())
             }),
-        "pdf" => pdf_extract::extract_text(path).map_err(|e| {
-            eprintln!(
-                "[folder_watcher] failed to extract PDF text: path={}, error={}",
-                path.display(),
-                e
-            );
-            FolderWatchError::IngestError(e.to_string())
-        }),
+        "pdf" => pdf_extract::extract_text_by_pages(path)
+            .map(|pages| crate::fetcher::pdf_text("", &pages))
+            .map_err(|e| {
+                eprintln!(
+                    "[folder_watcher] failed to extract PDF text: path={}, error={}",
+                    path.display(),
+                    e
+                );
+                FolderWatchError::IngestError(e.to_string())
+            }),
         _ => Err(FolderWatchError::UnsupportedType),
     }
 }
@@ -470,9 +489,7 @@ pub async fn scan_and_ingest_folder(
     ui_tx: std::sync::mpsc::SyncSender<crate::gui::state::FolderWatchEvent>,
     service_ref: std::sync::Arc<std::sync::Mutex<FolderWatchService>>,
 ) {
-    use crate::folder_watcher::{
-        collect_supported_files, get_mtime, read_file_content, IngestStatus,
-    };
+    use crate::folder_watcher::{collect_supported_files, get_mtime, read_file, IngestStatus};
     use crate::gui::state::FolderWatchEvent;
     use std::collections::HashMap;
 
@@ -529,8 +546,8 @@ pub async fn scan_and_ingest_folder(
             }
         }
 
-        let content = match read_file_content(file_path) {
-            Ok(c) => c,
+        let (content, structure) = match read_file(file_path) {
+            Ok(read) => read,
             Err(e) => {
                 eprintln!(
                     "[folder_watcher] failed to read file during scan: path={}, error={}",
@@ -558,7 +575,15 @@ pub async fn scan_and_ingest_folder(
         let rag_lock = rag.read().await;
         if let Some(ref rag_pipeline) = *rag_lock {
             match rag_pipeline
-                .ingest_document_with_auth(&title, &content, Some(&url), &source, None, false)
+                .ingest_document_with_auth(
+                    &title,
+                    &content,
+                    Some(&url),
+                    &source,
+                    None,
+                    false,
+                    Some(&structure),
+                )
                 .await
             {
                 Ok(doc_id) => {
@@ -691,7 +716,7 @@ pub async fn handle_file_event(
     rag: crate::gui::app::RagState,
     ui_tx: std::sync::mpsc::SyncSender<crate::gui::state::FolderWatchEvent>,
 ) {
-    use crate::folder_watcher::{get_mtime, is_mtime_changed, read_file_content, IngestStatus};
+    use crate::folder_watcher::{get_mtime, is_mtime_changed, read_file, IngestStatus};
     use crate::gui::state::FolderWatchEvent;
     use notify::event::EventKind;
 
@@ -755,8 +780,8 @@ pub async fn handle_file_event(
                 }
             }
 
-            let content = match read_file_content(&event.file_path) {
-                Ok(c) => c,
+            let (content, structure) = match read_file(&event.file_path) {
+                Ok(read) => read,
                 Err(e) => {
                     eprintln!(
                         "[folder_watcher] read failed on change: path={}, error={}",
@@ -782,7 +807,15 @@ pub async fn handle_file_event(
             let source = event.folder_path.to_string_lossy().to_string();
 
             match rag_pipeline
-                .ingest_document_with_auth(&title, &content, Some(&url), &source, None, false)
+                .ingest_document_with_auth(
+                    &title,
+                    &content,
+                    Some(&url),
+                    &source,
+                    None,
+                    false,
+                    Some(&structure),
+                )
                 .await
             {
                 Ok(doc_id) => {
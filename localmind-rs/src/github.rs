@@ -0,0 +1,579 @@
+//! GitHub repositories and gists
+//!
+//! A repository page is mostly navigation, file lists and buttons around the
+//! README, and the README itself is often cut off by the page text limit.
+//! Repository and gist URLs are therefore read through the GitHub API: a
+//! repository is indexed as its description, stars, language and topics,
+//! then its README and the Markdown files in its `docs/` folder; a gist as
+//! its description and files. Markdown headings become the document's
+//! headings and its links go to the link graph, as for web pages.
+//!
+//! The API allows 60 requests an hour without a token; set `GITHUB_TOKEN`
+//! to raise the limit. When the API fails the page is fetched as usual.
+
+use crate::fetcher::FetchResult;
+use crate::html_text::{Heading, PageLink, PageStructure, MAX_HEADINGS};
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+use std::time::Duration;
+use url::Url;
+
+const API_URL: &str = "https://api.github.com";
+
+/// Longest text indexed for a repository or gist, in bytes. Longer text
+/// is cut with a note saying so.
+pub const MAX_GITHUB_CHARS: usize = 50_000;
+
+/// Most files read from a repository's `docs/` folder.
+const MAX_DOC_FILES: usize = 10;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// First path segments of github.com pages that are not repositories.
+const RESERVED_OWNERS: [&str; 20] = [
+    "about",
+    "collections",
+    "customer-stories",
+    "enterprise",
+    "explore",
+    "features",
+    "issues",
+    "login",
+    "marketplace",
+    "new",
+    "notifications",
+    "orgs",
+    "pricing",
+    "pulls",
+    "search",
+    "settings",
+    "sponsors",
+    "topics",
+    "trending",
+    "users",
+];
+
+/// What a GitHub URL points at
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GitHubTarget {
+    Repository { owner: String, repo: String },
+    Gist { id: String },
+}
+
+impl GitHubTarget {
+    /// The repository or gist a URL is the home page of. Links to a file,
+    /// issue or other page inside a repository are not matched.
+    pub fn parse(url: &str) -> Option<Self> {
+        let parsed = Url::parse(url).ok()?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return None;
+        }
+        let segments: Vec<&str> = parsed
+            .path_segments()?
+            .filter(|segment| !segment.is_empty())
+            .collect();
+        match parsed.host_str()? {
+            "github.com" | "www.github.com" => match segments.as_slice() {
+                [owner, repo] if !RESERVED_OWNERS.contains(owner) => {
+                    let repo = repo.strip_suffix(".git").unwrap_or(repo);
+                    (is_name(owner) && is_name(repo)).then(|| GitHubTarget::Repository {
+                        owner: owner.to_string(),
+                        repo: repo.to_string(),
+                    })
+                }
+                _ => None,
+            },
+            "gist.github.com" => match segments.as_slice() {
+                [id] | [_, id] if id.chars().all(|c| c.is_ascii_hexdigit()) => {
+                    Some(GitHubTarget::Gist { id: id.to_string() })
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+/// Whether a URL is a GitHub repository or gist home page.
+pub fn is_github_url(url: &str) -> bool {
+    GitHubTarget::parse(url).is_some()
+}
+
+/// Whether `name` can be a GitHub owner or repository name.
+fn is_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+#[derive(Debug, Deserialize)]
+struct Owner {
+    login: String,
+    avatar_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Repository {
+    full_name: String,
+    description: Option<String>,
+    stargazers_count: u64,
+    language: Option<String>,
+    #[serde(default)]
+    topics: Vec<String>,
+    #[serde(default)]
+    archived: bool,
+    owner: Owner,
+}
+
+#[derive(Debug, Deserialize)]
+struct ContentEntry {
+    name: String,
+    path: String,
+    #[serde(rename = "type")]
+    kind: String,
+    download_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Gist {
+    description: Option<String>,
+    owner: Option<Owner>,
+    files: BTreeMap<String, GistFile>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GistFile {
+    content: Option<String>,
+}
+
+fn client() -> crate::Result<reqwest::Client> {
+    let mut headers = reqwest::header::HeaderMap::new();
+    headers.insert(
+        reqwest::header::ACCEPT,
+        reqwest::header::HeaderValue::from_static("application/vnd.github+json"),
+    );
+    if let Ok(token) = std::env::var("GITHUB_TOKEN") {
+        if let Ok(value) = format!("Bearer {}", token.trim()).parse() {
+            headers.insert(reqwest::header::AUTHORIZATION, value);
+        }
+    }
+    Ok(reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .user_agent("LocalMind/1.0")
+        .default_headers(headers)
+        .build()?)
+}
+
+/// GET `url`; `Ok(None)` when it does not exist.
+async fn get(
+    client: &reqwest::Client,
+    url: &str,
+    accept: Option<&str>,
+) -> crate::Result<Option<reqwest::Response>> {
+    let mut request = client.get(url);
+    if let Some(accept) = accept {
+        request = request.header(reqwest::header::ACCEPT, accept);
+    }
+    let response = request.send().await?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !response.status().is_success() {
+        return Err(format!("GitHub API returned {} for {}", response.status(), url).into());
+    }
+    Ok(Some(response))
+}
+
+/// Fetch a repository or gist through the API.
+pub async fn fetch(target: &GitHubTarget) -> crate::Result<FetchResult> {
+    let client = client()?;
+    match target {
+        GitHubTarget::Repository { owner, repo } => fetch_repository(&client, owner, repo).await,
+        GitHubTarget::Gist { id } => fetch_gist(&client, id).await,
+    }
+}
+
+async fn fetch_repository(
+    client: &reqwest::Client,
+    owner: &str,
+    repo: &str,
+) -> crate::Result<FetchResult> {
+    let api = format!("{}/repos/{}/{}", API_URL, owner, repo);
+    let info: Repository = get(client, &api, None)
+        .await?
+        .ok_or_else(|| format!("Repository {}/{} not found", owner, repo))?
+        .json()
+        .await?;
+
+    let mut text = GitHubText::new(repository_header(&info));
+    let base = Url::parse(&format!("https://github.com/{}/blob/HEAD/", info.full_name))?;
+    if let Some(readme) = get(
+        client,
+        &format!("{}/readme", api),
+        Some("application/vnd.github.raw"),
+    )
+    .await?
+    {
+        text.push_markdown(&readme.text().await?, &base, true);
+    }
+
+    // Markdown files at the top of docs/, in name order
+    if let Some(listing) = get(client, &format!("{}/contents/docs", api), None).await? {
+        let mut entries: Vec<ContentEntry> = listing.json().await.unwrap_or_default();
+        entries.retain(|entry| entry.kind == "file" && is_markdown(&entry.name));
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+        for entry in entries.into_iter().take(MAX_DOC_FILES) {
+            let Some(download_url) = entry.download_url else {
+                continue;
+            };
+            if text.is_full() {
+                break;
+            }
+            if let Some(file) = get(client, &download_url, None).await? {
+                let base = base.join(&entry.path).unwrap_or_else(|_| base.clone());
+                text.push_file(&entry.path, &file.text().await?, &base);
+            }
+        }
+    }
+
+    let description = info
+        .description
+        .as_deref()
+        .map(str::trim)
+        .filter(|d| !d.is_empty());
+    Ok(FetchResult {
+        title: Some(match description {
+            Some(description) => format!("{}: {}", info.full_name, description),
+            None => info.full_name.clone(),
+        }),
+        image_url: info.owner.avatar_url.clone(),
+        ..text.finish()
+    })
+}
+
+async fn fetch_gist(client: &reqwest::Client, id: &str) -> crate::Result<FetchResult> {
+    let gist: Gist = get(client, &format!("{}/gists/{}", API_URL, id), None)
+        .await?
+        .ok_or_else(|| format!("Gist {} not found", id))?
+        .json()
+        .await?;
+
+    let description = gist
+        .description
+        .as_deref()
+        .map(str::trim)
+        .filter(|d| !d.is_empty());
+    let mut header = String::from("Gist");
+    if let Some(owner) = &gist.owner {
+        header.push_str(&format!(" by {}", owner.login));
+    }
+    if let Some(description) = description {
+        header.push_str(&format!("\nDescription: {}", description));
+    }
+
+    let mut text = GitHubText::new(header);
+    let base = Url::parse(&format!("https://gist.github.com/{}/", id))?;
+    for (name, file) in &gist.files {
+        if text.is_full() {
+            break;
+        }
+        if let Some(content) = &file.content {
+            text.push_file(name, content, &base);
+        }
+    }
+
+    let first_file = gist.files.keys().next().cloned();
+    Ok(FetchResult {
+        title: description.map(str::to_string).or(first_file),
+        image_url: gist.owner.and_then(|owner| owner.avatar_url),
+        ..text.finish()
+    })
+}
+
+/// Description, stars, language and topics of a repository.
+fn repository_header(info: &Repository) -> String {
+    let mut header = format!("Repository: {}", info.full_name);
+    if let Some(description) = info.description.as_deref().filter(|d| !d.trim().is_empty()) {
+        header.push_str(&format!("\nDescription: {}", description.trim()));
+    }
+    header.push_str(&format!("\nStars: {}", info.stargazers_count));
+    if let Some(language) = &info.language {
+        header.push_str(&format!("\nLanguage: {}", language));
+    }
+    if !info.topics.is_empty() {
+        header.push_str(&format!("\nTopics: {}", info.topics.join(", ")));
+    }
+    if info.archived {
+        header.push_str("\nArchived");
+    }
+    header
+}
+
+fn is_markdown(name: &str) -> bool {
+    let name = name.to_lowercase();
+    name.ends_with(".md") || name.ends_with(".markdown")
+}
+
+/// Text of a repository or gist being put together, with its structure
+struct GitHubText {
+    text: String,
+    structure: PageStructure,
+    /// Heading anchors given out, to number repeats as GitHub does
+    anchors: Vec<String>,
+}
+
+impl GitHubText {
+    fn new(header: String) -> Self {
+        Self {
+            text: header,
+            structure: PageStructure::default(),
+            anchors: Vec::new(),
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        self.text.len() >= MAX_GITHUB_CHARS
+    }
+
+    /// A file under its name as a heading: Markdown files as text, others
+    /// as they are.
+    fn push_file(&mut self, name: &str, content: &str, base: &Url) {
+        self.text.push_str("\n\n");
+        self.push_heading(1, name, None);
+        if is_markdown(name) {
+            self.push_markdown(content, base, false);
+        } else {
+            self.push_block(content.trim());
+        }
+    }
+
+    fn push_heading(&mut self, level: u8, text: &str, anchor: Option<String>) {
+        if self.structure.headings.len() < MAX_HEADINGS {
+            self.structure.headings.push(Heading {
+                level,
+                text: text.to_string(),
+                offset: self.text.len(),
+                anchor,
+            });
+        }
+        self.text.push_str(text);
+    }
+
+    /// The anchor GitHub gives a heading on the page rendering the
+    /// Markdown: lowercased, punctuation dropped, spaces as dashes, and
+    /// numbered when repeated.
+    fn anchor(&mut self, heading: &str) -> String {
+        let slug: String = heading
+            .to_lowercase()
+            .chars()
+            .filter(|c| c.is_alphanumeric() || matches!(c, ' ' | '-' | '_'))
+            .map(|c| if c == ' ' { '-' } else { c })
+            .collect();
+        let repeats = self
+            .anchors
+            .iter()
+            .filter(|anchor| **anchor == slug)
+            .count();
+        self.anchors.push(slug.clone());
+        match repeats {
+            0 => slug,
+            n => format!("{}-{}", slug, n),
+        }
+    }
+
+    /// Markdown as text: headings without their `#`s, links and images as
+    /// their text, HTML tags dropped. Code blocks are kept as they are.
+    /// Headings get GitHub's anchors when `anchored`, for the README shown
+    /// on the repository page.
+    fn push_markdown(&mut self, markdown: &str, base: &Url, anchored: bool) {
+        static IMAGE: OnceLock<Regex> = OnceLock::new();
+        static LINK: OnceLock<Regex> = OnceLock::new();
+        static TAG: OnceLock<Regex> = OnceLock::new();
+        let image = IMAGE.get_or_init(|| Regex::new(r"!\[([^\]]*)\]\([^)]*\)").unwrap());
+        let link = LINK.get_or_init(|| Regex::new(r"\[([^\]]+)\]\(([^)\s]+)[^)]*\)").unwrap());
+        let tag = TAG.get_or_init(|| Regex::new(r"</?[a-zA-Z][^>]*>").unwrap());
+
+        let mut in_code = false;
+        let mut blank = true;
+        for line in markdown.lines() {
+            if self.is_full() {
+                break;
+            }
+            let trimmed = line.trim();
+            if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                in_code = !in_code;
+                continue;
+            }
+            if in_code {
+                self.push_line(line.trim_end(), &mut blank);
+                continue;
+            }
+
+            let without_images = image.replace_all(trimmed, "$1");
+            for caps in link.captures_iter(&without_images) {
+                if let Ok(href) = base.join(&caps[2]) {
+                    if matches!(href.scheme(), "http" | "https") {
+                        self.structure.links.push(PageLink {
+                            text: caps[1].trim().to_string(),
+                            href: href.to_string(),
+                        });
+                    }
+                }
+            }
+            let plain = link.replace_all(&without_images, "$1");
+            let plain = tag.replace_all(&plain, "");
+            let plain = crate::html_text::decode_entities(plain.trim());
+
+            match heading(&plain) {
+                Some((level, text)) => {
+                    self.separate(&mut blank);
+                    let anchor = anchored.then(|| self.anchor(text));
+                    self.push_heading(level, text, anchor);
+                    blank = false;
+                }
+                None => self.push_line(&plain, &mut blank),
+            }
+        }
+    }
+
+    /// A line of text; runs of blank lines are kept to one.
+    fn push_line(&mut self, line: &str, blank: &mut bool) {
+        if line.is_empty() {
+            *blank = true;
+            return;
+        }
+        self.separate(blank);
+        self.text.push_str(line);
+        *blank = false;
+    }
+
+    fn separate(&mut self, blank: &mut bool) {
+        if *blank {
+            self.text.push_str("\n\n");
+        } else {
+            self.text.push('\n');
+        }
+        *blank = false;
+    }
+
+    fn push_block(&mut self, block: &str) {
+        if !block.is_empty() {
+            self.text.push_str("\n\n");
+            self.text.push_str(block);
+        }
+    }
+
+    /// The text, cut at `MAX_GITHUB_CHARS` with a note when it is longer.
+    fn finish(mut self) -> FetchResult {
+        if self.text.len() > MAX_GITHUB_CHARS {
+            let mut boundary = MAX_GITHUB_CHARS;
+            while !self.text.is_char_boundary(boundary) {
+                boundary -= 1;
+            }
+            self.text.truncate(boundary);
+            self.text.push_str("\n\n[GitHub content truncated]");
+        }
+        let structure = self.structure.located(&self.text);
+        FetchResult {
+            content: self.text,
+            needs_auth: false,
+            structure,
+            image_url: None,
+            title: None,
+        }
+    }
+}
+
+/// Level and text of a Markdown ATX heading line.
+fn heading(line: &str) -> Option<(u8, &str)> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    let rest = &line[level..];
+    if !(1..=6).contains(&level) || !rest.starts_with(' ') {
+        return None;
+    }
+    let text = rest.trim().trim_end_matches('#').trim_end();
+    (!text.is_empty()).then_some((level as u8, text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_repository_and_gist_urls() {
+        assert_eq!(
+            GitHubTarget::parse("https://github.com/rust-lang/rust"),
+            Some(GitHubTarget::Repository {
+                owner: "rust-lang".to_string(),
+                repo: "rust".to_string()
+            })
+        );
+        assert_eq!(
+            GitHubTarget::parse("https://www.github.com/serde-rs/json.git/?tab=readme#usage"),
+            Some(GitHubTarget::Repository {
+                owner: "serde-rs".to_string(),
+                repo: "json".to_string()
+            })
+        );
+        assert_eq!(
+            GitHubTarget::parse("https://gist.github.com/octocat/6cad326836d38bd3a7ae"),
+            Some(GitHubTarget::Gist {
+                id: "6cad326836d38bd3a7ae".to_string()
+            })
+        );
+        assert!(!is_github_url("https://github.com/rust-lang/rust/issues/1"));
+        assert!(!is_github_url("https://github.com/rust-lang"));
+        assert!(!is_github_url("https://github.com/topics/rust"));
+        assert!(!is_github_url("https://gitlab.com/rust-lang/rust"));
+    }
+
+    #[test]
+    fn markdown_keeps_headings_and_links() {
+        let base = Url::parse("https://github.com/o/r/blob/HEAD/").unwrap();
+        let mut text = GitHubText::new("Repository: o/r".to_string());
+        text.push_markdown(
+            "# Tool\n\n[![CI](https://x/badge.svg)](https://ci.example.com)\n\n\
+             Read the [guide](docs/guide.md).\n\n```sh\n# not a heading\n```\n\n## Install ##\n\
+             ## What's new?\n## Install\n",
+            &base,
+            true,
+        );
+        let result = text.finish();
+        assert_eq!(
+            result.content,
+            "Repository: o/r\n\nTool\n\nCI\n\nRead the guide.\n\n# not a heading\n\n\
+             Install\nWhat's new?\nInstall"
+        );
+        let headings: Vec<(u8, &str, Option<&str>)> = result
+            .structure
+            .headings
+            .iter()
+            .map(|heading| {
+                (
+                    heading.level,
+                    heading.text.as_str(),
+                    heading.anchor.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            headings,
+            vec![
+                (1, "Tool", Some("tool")),
+                (2, "Install", Some("install")),
+                (2, "What's new?", Some("whats-new")),
+                (2, "Install", Some("install-1"))
+            ]
+        );
+        assert_eq!(
+            result.structure.hrefs(),
+            vec![
+                "https://ci.example.com/",
+                "https://github.com/o/r/blob/HEAD/docs/guide.md"
+            ]
+        );
+    }
+}
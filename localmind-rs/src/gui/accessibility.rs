@@ -0,0 +1,149 @@
+//! Accessibility: UI scale, the high-contrast theme and screen reader names
+//!
+//! The scale and theme are chosen in Settings and applied to the egui context
+//! by `apply`. Widgets that only show an icon get a readable name through
+//! `Labeled`, which AccessKit passes on to screen readers.
+
+use egui::{Color32, Response, Stroke, Visuals, WidgetInfo, WidgetType};
+
+/// Text size presets offered in Settings, as zoom factors.
+pub const UI_SCALES: [f32; 8] = [0.8, 0.9, 1.0, 1.1, 1.25, 1.5, 1.75, 2.0];
+
+/// How the window looks; persisted in the config table.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Appearance {
+    /// Zoom factor applied to all text and widgets
+    pub ui_scale: f32,
+    /// Black background, white text and bright widget outlines
+    pub high_contrast: bool,
+}
+
+impl Default for Appearance {
+    fn default() -> Self {
+        Self {
+            ui_scale: 1.0,
+            high_contrast: false,
+        }
+    }
+}
+
+/// Keep a stored scale within the presets' range; unreadable values reset
+/// to 100%.
+pub fn clamp_scale(scale: f32) -> f32 {
+    if scale.is_finite() {
+        scale.clamp(UI_SCALES[0], UI_SCALES[UI_SCALES.len() - 1])
+    } else {
+        1.0
+    }
+}
+
+/// Label for a scale in the Settings selector, e.g. "125%".
+pub fn scale_label(scale: f32) -> String {
+    format!("{:.0}%", scale * 100.0)
+}
+
+/// The app theme: dark blue, or high contrast.
+pub fn visuals(high_contrast: bool) -> Visuals {
+    let mut visuals = Visuals::dark();
+    if !high_contrast {
+        visuals.window_fill = Color32::from_rgb(20, 30, 50); // Dark blue
+        visuals.panel_fill = Color32::from_rgb(20, 30, 50); // Dark blue
+        visuals.extreme_bg_color = Color32::from_rgb(15, 25, 40); // Darker blue
+        visuals.faint_bg_color = Color32::from_rgb(25, 35, 55); // Slightly lighter blue
+        visuals.code_bg_color = Color32::from_rgb(20, 30, 50); // Dark blue
+        return visuals;
+    }
+
+    visuals.override_text_color = Some(Color32::WHITE);
+    visuals.hyperlink_color = Color32::from_rgb(120, 200, 255);
+    visuals.window_fill = Color32::BLACK;
+    visuals.panel_fill = Color32::BLACK;
+    visuals.extreme_bg_color = Color32::BLACK;
+    visuals.faint_bg_color = Color32::from_gray(24);
+    visuals.code_bg_color = Color32::BLACK;
+    visuals.window_stroke = Stroke::new(2.0, Color32::WHITE);
+    visuals.selection.bg_fill = Color32::from_rgb(0, 80, 200);
+    visuals.selection.stroke = Stroke::new(2.0, Color32::WHITE);
+
+    let widgets = &mut visuals.widgets;
+    for state in [
+        &mut widgets.noninteractive,
+        &mut widgets.inactive,
+        &mut widgets.hovered,
+        &mut widgets.active,
+        &mut widgets.open,
+    ] {
+        state.fg_stroke = Stroke::new(1.5, Color32::WHITE);
+    }
+    widgets.noninteractive.bg_stroke = Stroke::new(1.0, Color32::from_gray(160));
+    widgets.inactive.bg_fill = Color32::BLACK;
+    widgets.inactive.weak_bg_fill = Color32::BLACK;
+    widgets.inactive.bg_stroke = Stroke::new(1.0, Color32::WHITE);
+    widgets.hovered.bg_stroke = Stroke::new(2.0, Color32::YELLOW);
+    widgets.active.bg_stroke = Stroke::new(2.0, Color32::YELLOW);
+    widgets.open.bg_stroke = Stroke::new(2.0, Color32::YELLOW);
+    visuals
+}
+
+/// Apply an appearance to the whole UI.
+pub fn apply(ctx: &egui::Context, appearance: &Appearance) {
+    ctx.set_visuals(visuals(appearance.high_contrast));
+    ctx.set_zoom_factor(clamp_scale(appearance.ui_scale));
+}
+
+/// Screen reader names for widgets whose visible text is an icon or a hint.
+pub trait Labeled {
+    /// Name an icon button; the name is also shown as its tooltip.
+    fn labeled(self, label: &str) -> Self;
+
+    /// Name an on/off toggle drawn as a selectable icon.
+    fn labeled_toggle(self, selected: bool, label: &str) -> Self;
+
+    /// Name a text field that has no visible label next to it.
+    fn labeled_field(self, label: &str) -> Self;
+}
+
+impl Labeled for Response {
+    fn labeled(self, label: &str) -> Self {
+        let enabled = self.enabled();
+        self.widget_info(|| WidgetInfo::labeled(WidgetType::Button, enabled, label));
+        self.on_hover_text(label)
+    }
+
+    fn labeled_toggle(self, selected: bool, label: &str) -> Self {
+        let enabled = self.enabled();
+        self.widget_info(|| {
+            WidgetInfo::selected(WidgetType::SelectableLabel, enabled, selected, label)
+        });
+        self.on_hover_text(label)
+    }
+
+    fn labeled_field(self, label: &str) -> Self {
+        let enabled = self.enabled();
+        self.widget_info(|| WidgetInfo {
+            enabled,
+            label: Some(label.to_string()),
+            ..WidgetInfo::new(WidgetType::TextEdit)
+        });
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clamps_scales_and_switches_themes() {
+        assert_eq!(clamp_scale(1.25), 1.25);
+        assert_eq!(clamp_scale(0.1), 0.8);
+        assert_eq!(clamp_scale(9.0), 2.0);
+        assert_eq!(clamp_scale(f32::NAN), 1.0);
+        assert_eq!(scale_label(1.25), "125%");
+
+        assert_eq!(visuals(false).panel_fill, Color32::from_rgb(20, 30, 50));
+        let contrast = visuals(true);
+        assert_eq!(contrast.panel_fill, Color32::BLACK);
+        assert_eq!(contrast.override_text_color, Some(Color32::WHITE));
+    }
+}
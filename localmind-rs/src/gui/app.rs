@@ -1,20 +1,87 @@
 //! Main application state and eframe App implementation
 
-use crate::db::Database;
-use crate::rag::RagPipeline;
 use std::collections::HashSet;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+use super::accessibility::{self, Appearance, Labeled};
+use super::i18n::{self, tr, tr_args, Language};
+use super::presenter;
 use super::state::{
-    BookmarkFolderView, ChromeProfileInfo, DocumentView, InitStatus, SearchResultView, Toast,
-    ToastType, View,
+    BookmarkFolderView, BookmarkProgressToasts, BrowseFilter, ChromeProfileInfo, DocumentFind,
+    DocumentImages, DocumentView, InitStatus, MarkdownExport, SearchResultView, SidebarData,
+    TimelineGrouping, Toast, ToastAction, ToastQueue, ToastType, UiLayout, View,
 };
 use super::views;
 use super::widgets;
+use egui_remixicon::icons;
 
-/// Type alias for shared RAG state
-pub type RagState = Arc<RwLock<Option<RagPipeline>>>;
+use crate::db::{
+    AutocompleteSuggestion, DocumentMetadata, HomeSummary, IndexStats, LinkedDocument,
+    ProjectionPoint, TrashedDocument, UsageInsights,
+};
+use crate::embedding_provider::EmbeddingSettings;
+use crate::embedding_supervisor::{EmbeddingServerStatus, EmbeddingServerSupervisor};
+use crate::html_text::PageStructure;
+use crate::ingest_policy::{IngestPolicies, SourceKind};
+use crate::notifications::NotificationEvent;
+use crate::rag::{PassageHit, SearchFallback, TagSuggestions};
+use crate::request_log::RequestLog;
+use crate::services::{
+    ingest_imported_bookmarks, init_rag_system, start_bookmark_monitoring, start_consistency_check,
+    start_embedding_migration, start_favicon_fetcher, start_health_monitor, start_http_server,
+    start_integrity_audit, start_language_detector, start_link_check_scheduler,
+    start_maintenance_scheduler, start_reminder_scheduler, start_remote_access_server,
+    start_retention_scheduler, start_snapshot_scheduler, start_sync_scheduler,
+    start_thumbnail_fetcher, watch_bookmark_profiles,
+};
+pub use crate::services::{BookmarkProgress, RagState, SaveUrlProgress};
+
+/// egui ID of the top-bar search box, so shortcuts can focus it
+const SEARCH_INPUT_ID: &str = "search_input";
+
+/// egui ID of the document view's find box, so Ctrl+F can focus it
+pub(crate) const DOCUMENT_FIND_INPUT_ID: &str = "document_find_input";
+
+/// How long the "Undo" button is offered after documents are moved to the trash
+const UNDO_WINDOW: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// eframe storage key of the layout restored on the next launch
+const UI_LAYOUT_KEY: &str = "ui_layout";
+
+/// Search results restored on launch, their corrected query and whether to
+/// refresh them
+type RestoredSearch = (Vec<SearchResultView>, Option<String>, bool);
+
+/// Stored UI language, appearance, layout and search restore setting
+type UiPreferences = (Option<Language>, Appearance, bool, bool);
+
+/// A document opened from the library, with the byte offsets of the
+/// highlight it was opened from
+type OpenedDocument = (DocumentView, Option<(usize, usize)>);
+
+/// Toast message of a document action and the IDs of any documents it moved
+/// to the trash
+type DocumentActionResult = Result<(String, Vec<i64>), String>;
+
+/// A document's links to and from other documents, and its structure
+type DocumentLinks = (Vec<LinkedDocument>, Vec<LinkedDocument>, PageStructure);
+
+/// Hosts whose icons were asked for, and the icons found in the cache
+type LoadedFavicons = (Vec<String>, std::collections::HashMap<String, Vec<u8>>);
+
+/// Documents whose thumbnails were asked for, and the thumbnails found
+type LoadedThumbnails = (Vec<i64>, std::collections::HashMap<i64, Vec<u8>>);
+
+/// Languages in the library with their document counts, and the ones
+/// excluded from embedding
+type LanguageSettings = (Vec<(String, i64)>, Vec<String>);
+
+/// A flagged page fetched again: its ID and the issue still found, if any
+type PageRefetch = (
+    i64,
+    Result<Option<crate::page_quality::QualityIssue>, String>,
+);
 
 /// Main application state holding all UI and backend references
 pub struct LocalMindApp {
@@ -39,6 +106,16 @@ pub struct LocalMindApp {
     /// Currently viewed document
     pub selected_document: Option<DocumentView>,
 
+    /// Question typed into the "Ask this document" box
+    pub document_question: String,
+
+    /// Answer to the last question about the selected document
+    pub document_answer: Option<crate::rag::DocumentAnswer>,
+
+    /// Receiver for a pending document question
+    pub(crate) document_answer_receiver:
+        Option<std::sync::mpsc::Receiver<Result<crate::rag::DocumentAnswer, String>>>,
+
     /// Reusable cache for the Markdown renderer (must persist across frames)
     pub markdown_cache: egui_commonmark::CommonMarkCache,
 
@@ -60,48 +137,197 @@ pub struct LocalMindApp {
     /// Folder tree for settings
     pub bookmark_folders: Vec<BookmarkFolderView>,
 
-    /// Active toast notifications
-    pub toasts: Vec<Toast>,
+    /// Active toast notifications and their history
+    pub toasts: ToastQueue,
+
+    /// Notification history drawer visibility
+    pub show_toast_history: bool,
 
     /// Application initialization state
     pub init_status: InitStatus,
 
-    /// Counter for generating unique toast IDs
-    pub next_toast_id: u64,
-
     /// Tokio runtime handle for async operations (kept alive)
     #[allow(dead_code)]
-    runtime: tokio::runtime::Handle,
+    pub(crate) runtime: tokio::runtime::Handle,
 
-    /// Receiver for RAG initialization completion (carries optional child process handle)
-    init_receiver: Option<std::sync::mpsc::Receiver<Result<Option<std::process::Child>, String>>>,
+    /// Receiver for RAG initialization completion (carries the server supervisor, if any)
+    init_receiver:
+        Option<std::sync::mpsc::Receiver<Result<Option<EmbeddingServerSupervisor>, String>>>,
 
-    /// Child process handle for the embedding server, if we spawned it
-    embedding_server_child: Option<std::process::Child>,
+    /// Supervisor for the local embedding server process
+    embedding_server: Option<EmbeddingServerSupervisor>,
+
+    /// Receiver for embedding server status changes
+    embedding_server_status_receiver: Option<std::sync::mpsc::Receiver<EmbeddingServerStatus>>,
+
+    /// Latest embedding server status, shown in the status bar
+    pub embedding_server_status: Option<EmbeddingServerStatus>,
 
     /// Receiver for recent documents
-    recent_docs_receiver: Option<std::sync::mpsc::Receiver<Vec<DocumentView>>>,
+    pub(crate) recent_docs_receiver: Option<std::sync::mpsc::Receiver<Vec<DocumentView>>>,
+
+    /// Keyboard shortcut help overlay visibility
+    pub show_shortcuts_help: bool,
+
+    /// Keyboard-selected entry in the current list (see `listed_doc_ids`)
+    pub selected_index: Option<usize>,
+
+    /// Scroll the selected entry into view on the next frame
+    pub scroll_to_selection: bool,
+
+    /// IDs of unread documents (the reading list)
+    pub unread_ids: HashSet<i64>,
+
+    /// Receiver for unread IDs loading
+    pub(crate) unread_receiver: Option<std::sync::mpsc::Receiver<HashSet<i64>>>,
+
+    /// Show only unread documents on the home view and in search results
+    pub unread_only: bool,
+
+    /// Pinned (favorite) documents for the home screen and star buttons
+    pub pinned_documents: Vec<DocumentView>,
+
+    /// Receiver for pinned documents loading (also sent after a pin/unpin)
+    pub(crate) pinned_docs_receiver: Option<std::sync::mpsc::Receiver<Vec<DocumentView>>>,
+
+    /// Documents saved around this date a year ago, for the home view
+    pub year_ago_documents: Vec<DocumentView>,
+
+    /// Random older documents, weighted towards never-opened ones
+    pub forgotten_documents: Vec<DocumentView>,
+
+    /// Receiver for the resurfaced (year-ago, forgotten) documents
+    pub(crate) resurfaced_receiver:
+        Option<std::sync::mpsc::Receiver<(Vec<DocumentView>, Vec<DocumentView>)>>,
+
+    /// Reminders that have come due, listed at the top of the home view
+    pub reminders: Vec<crate::reminders::Reminder>,
+
+    /// Receiver for the due reminders loading
+    pub(crate) reminders_receiver:
+        Option<std::sync::mpsc::Receiver<Vec<crate::reminders::Reminder>>>,
+
+    /// Receiver for reminders fired by the scheduler
+    pub(crate) reminder_due_receiver:
+        Option<std::sync::mpsc::Receiver<Vec<crate::reminders::Reminder>>>,
+
+    /// Reminder being set in the "Remind me" dialog
+    pub reminder_draft: Option<widgets::reminders::ReminderDraft>,
+
+    /// Receiver for the date a reminder was set for, or the error
+    pub(crate) reminder_set_receiver:
+        Option<std::sync::mpsc::Receiver<std::result::Result<String, String>>>,
+
+    /// Counts, recent saves by source and recent searches for the home view
+    pub home_summary: HomeSummary,
+
+    /// Receiver for the home dashboard summary
+    pub(crate) home_summary_receiver: Option<std::sync::mpsc::Receiver<HomeSummary>>,
 
     /// Receiver for search results
-    search_receiver: Option<std::sync::mpsc::Receiver<Vec<SearchResultView>>>,
+    pub(crate) search_receiver:
+        Option<std::sync::mpsc::Receiver<(Vec<SearchResultView>, Option<String>)>>,
+
+    /// Receiver for the results saved for the query restored at launch, with
+    /// their corrected query and whether to refresh them; `None` when no
+    /// results were saved
+    pub(crate) restored_search_receiver: Option<std::sync::mpsc::Receiver<Option<RestoredSearch>>>,
+
+    /// Whether the pending search re-runs restored results in the background,
+    /// keeping them on screen until it finishes
+    pub(crate) refreshing_search: bool,
+
+    /// Re-run a search restored at launch in the background
+    pub refresh_restored_search: bool,
+
+    /// Respelled query the current results are for, when the typed one had
+    /// words the index does not contain
+    pub corrected_query: Option<String>,
+
+    /// Query the user chose to search as typed, skipping spelling correction
+    pub(crate) exact_query: Option<String>,
+
+    /// Weak matches, spelling suggestions and web search for a search that
+    /// found nothing
+    pub search_fallback: Option<SearchFallback>,
+
+    /// Receiver for the fallback of an empty search
+    pub(crate) search_fallback_receiver: Option<std::sync::mpsc::Receiver<SearchFallback>>,
+
+    /// Completions for the text in the search box
+    pub autocomplete: Vec<AutocompleteSuggestion>,
+
+    /// Completion picked with the arrow keys
+    pub autocomplete_index: Option<usize>,
+
+    /// Receiver for completions, tagged with the text they are for
+    pub(crate) autocomplete_receiver:
+        Option<std::sync::mpsc::Receiver<(String, Vec<AutocompleteSuggestion>)>>,
 
     /// Receiver for document loading
-    document_receiver: Option<std::sync::mpsc::Receiver<Option<DocumentView>>>,
+    /// (a highlight's document opens the highlighted one, with the
+    /// highlight's byte offsets)
+    pub(crate) document_receiver: Option<std::sync::mpsc::Receiver<Option<OpenedDocument>>>,
 
     /// Previous view for back navigation
-    previous_view: View,
+    pub(crate) previous_view: View,
 
     /// Receiver for exclusion rules loading
     exclusion_rules_receiver: Option<std::sync::mpsc::Receiver<(Vec<String>, Vec<String>)>>,
 
     /// Receiver for saving exclusion rules
-    save_exclusion_receiver: Option<std::sync::mpsc::Receiver<Result<usize, String>>>,
+    save_exclusion_receiver: Option<std::sync::mpsc::Receiver<Result<Vec<i64>, String>>>,
 
     /// Receiver for bookmark progress events
     bookmark_progress_receiver: Option<std::sync::mpsc::Receiver<BookmarkProgress>>,
 
-    /// ID of the current bookmark progress toast (for replacing)
-    bookmark_progress_toast_id: Option<u64>,
+    /// Progress toast for the current bookmark ingestion
+    bookmark_progress_toasts: BookmarkProgressToasts,
+
+    /// Sender cloned into bookmark file imports
+    pub(crate) bookmark_progress_tx: std::sync::mpsc::Sender<BookmarkProgress>,
+
+    /// Bookmark file import dialog visibility
+    pub show_import_dialog: bool,
+
+    /// Path typed into the import dialog
+    pub import_path_input: String,
+
+    /// Validation or parse error shown in the import dialog
+    pub import_error: Option<String>,
+
+    /// Receiver for the number of bookmarks queued from an import file
+    pub(crate) bookmark_import_receiver: Option<std::sync::mpsc::Receiver<Result<usize, String>>>,
+
+    /// "Save a page" dialog visibility (opened from the add menu)
+    pub show_paste_url_dialog: bool,
+
+    /// URL pasted into the home view's quick-add box or the dialog
+    pub paste_url_input: String,
+
+    /// Why the last pasted URL could not be saved
+    pub paste_url_error: Option<String>,
+
+    /// Receiver for the progress of saving a pasted URL
+    pub(crate) paste_url_receiver: Option<std::sync::mpsc::Receiver<SaveUrlProgress>>,
+
+    /// ID of the progress toast for the URL being saved
+    pub(crate) paste_url_toast_id: Option<u64>,
+
+    /// "New note" dialog visibility
+    pub show_note_dialog: bool,
+
+    /// Title typed into the "New note" dialog
+    pub note_title: String,
+
+    /// Text typed into the "New note" dialog
+    pub note_text: String,
+
+    /// Error shown in the "New note" dialog
+    pub note_error: Option<String>,
+
+    /// Receiver for the ID of a saved note
+    pub(crate) note_receiver: Option<std::sync::mpsc::Receiver<Result<i64, String>>>,
 
     /// Chrome profiles discovered at startup (only populated when >1 exists)
     pub available_profiles: Vec<ChromeProfileInfo>,
@@ -157,21 +383,600 @@ pub struct LocalMindApp {
 
     /// Query logger for shadow logging of search queries and click outcomes
     pub query_logger: crate::query_logger::QueryLogger,
-}
 
-/// Bookmark ingestion progress event
-///
-/// Sent through a channel to update the UI during bookmark processing.
-#[derive(Debug, Clone)]
-pub struct BookmarkProgress {
-    pub current: usize,
-    pub total: usize,
-    pub current_title: String,
-    pub completed: bool,
+    /// Bookmarks whose fetch failed, shown in the Failed Imports settings section
+    pub ingest_errors: Vec<crate::ingest_queue::IngestError>,
+
+    /// Receiver for failed-imports list loading
+    pub(crate) ingest_errors_receiver:
+        Option<std::sync::mpsc::Receiver<Vec<crate::ingest_queue::IngestError>>>,
+
+    /// Receiver for retry completion: (ingested, still failing)
+    pub(crate) retry_receiver: Option<std::sync::mpsc::Receiver<(usize, usize)>>,
+
+    /// Include documents marked dead by the link checker in search results
+    pub show_dead_documents: bool,
+
+    /// Expand search queries with synonyms and paraphrases
+    pub expand_query: bool,
+
+    /// Rank results linked to or from recently viewed documents higher
+    pub boost_linked_results: bool,
+
+    /// Group search results from the same site under one header
+    pub group_by_site: bool,
+
+    /// List each matching passage as its own result instead of one result
+    /// per document
+    pub show_passages: bool,
+
+    /// Sites whose grouped results are all shown, by group label
+    pub expanded_result_groups: HashSet<String>,
+
+    /// All collections, for the search scope dropdown and settings
+    pub collections: Vec<crate::db::Collection>,
+
+    /// Receiver for collections list loading
+    pub(crate) collections_receiver: Option<std::sync::mpsc::Receiver<Vec<crate::db::Collection>>>,
+
+    /// Collection the search is scoped to (None = all documents)
+    pub search_collection: Option<i64>,
+
+    /// Name typed for a new collection
+    pub new_collection_name: String,
+
+    /// Receiver for collection create/delete/membership results (toast message)
+    pub(crate) collection_action_receiver:
+        Option<std::sync::mpsc::Receiver<Result<String, String>>>,
+
+    /// Collections the selected document belongs to
+    pub document_collection_ids: Vec<i64>,
+
+    /// Receiver for the selected document's collection memberships
+    pub(crate) document_collections_receiver: Option<std::sync::mpsc::Receiver<Vec<i64>>>,
+
+    /// Documents checked for bulk actions
+    pub checked_doc_ids: HashSet<i64>,
+
+    /// Tag typed in a context menu or the bulk action bar
+    pub tag_input: String,
+
+    /// Receiver for delete/tag/exclude/restore results: the toast message and
+    /// the IDs of any documents moved to the trash, which can be undone
+    pub(crate) document_action_receiver: Option<std::sync::mpsc::Receiver<DocumentActionResult>>,
+
+    /// Receiver for a document converted to Markdown, copied or saved
+    pub(crate) markdown_export_receiver:
+        Option<std::sync::mpsc::Receiver<Result<MarkdownExport, String>>>,
+
+    /// Documents in the trash, shown in the Trash view
+    pub trashed_documents: Vec<TrashedDocument>,
+
+    /// Receiver for the trash listing
+    pub(crate) trashed_documents_receiver: Option<std::sync::mpsc::Receiver<Vec<TrashedDocument>>>,
+
+    /// Whether the navigation sidebar is shown
+    pub show_sidebar: bool,
+
+    /// Sources, tags, bookmark folders and counts listed in the sidebar
+    pub sidebar: SidebarData,
+
+    /// Receiver for the sidebar entries
+    pub(crate) sidebar_receiver: Option<std::sync::mpsc::Receiver<SidebarData>>,
+
+    /// Filter of the browse view
+    pub browse_filter: BrowseFilter,
+
+    /// Documents listed in the browse view
+    pub browse_documents: Vec<DocumentView>,
+
+    /// Receiver for the browse view's documents
+    pub(crate) browse_receiver: Option<std::sync::mpsc::Receiver<Vec<DocumentView>>>,
+
+    /// How the timeline groups documents
+    pub timeline_grouping: TimelineGrouping,
+
+    /// Search operators and words filtering the timeline
+    pub timeline_filter: String,
+
+    /// Timeline documents loaded so far, newest first
+    pub timeline_documents: Vec<DocumentView>,
+
+    /// Whether the last timeline page came back short, so there is no more
+    pub(crate) timeline_exhausted: bool,
+
+    /// Receiver for a timeline page, flagged when it replaces the list
+    pub(crate) timeline_receiver: Option<std::sync::mpsc::Receiver<(bool, Vec<DocumentView>)>>,
+
+    /// Documents on the Explore map
+    pub projection: Vec<ProjectionPoint>,
+
+    /// Whether the map was projected with another embedding model than the
+    /// current one
+    pub projection_stale: bool,
+
+    /// Receiver for the cached map, and whether it is stale
+    pub(crate) projection_receiver: Option<std::sync::mpsc::Receiver<(Vec<ProjectionPoint>, bool)>>,
+
+    /// Receiver for a map projection run: documents mapped or the error
+    pub(crate) projection_job_receiver: Option<std::sync::mpsc::Receiver<Result<usize, String>>>,
+
+    /// Queued and in-progress bookmark fetches, shown in the Jobs view
+    pub job_items: Vec<crate::ingest_queue::QueueItem>,
+
+    /// Receiver for the Jobs view's queue items
+    pub(crate) jobs_receiver:
+        Option<std::sync::mpsc::Receiver<Vec<crate::ingest_queue::QueueItem>>>,
+
+    /// Requests served by the extension API, listed in the Jobs view
+    pub request_log: RequestLog,
+
+    /// Whether the activity list leaves out lookups and status polls
+    pub activity_saves_only: bool,
+
+    /// Index counts shown in the Stats view
+    pub index_stats: Option<IndexStats>,
+
+    /// Local usage insights shown in the Stats view
+    pub usage_insights: Option<UsageInsights>,
+
+    /// Receiver for the Stats view data (also sent after a usage purge)
+    pub(crate) stats_receiver:
+        Option<std::sync::mpsc::Receiver<(Option<IndexStats>, Option<UsageInsights>)>>,
+
+    /// Tags on the selected document
+    pub document_tags: Vec<String>,
+
+    /// Receiver for the selected document's tags
+    pub(crate) document_tags_receiver: Option<std::sync::mpsc::Receiver<Vec<String>>>,
+
+    /// Indexed documents the selected document links to
+    pub document_links_to: Vec<LinkedDocument>,
+
+    /// Indexed documents linking to the selected document
+    pub document_linked_from: Vec<LinkedDocument>,
+
+    /// Headings and anchors of the selected document, when it came from HTML
+    pub document_structure: PageStructure,
+
+    /// Receiver for the selected document's links and structure
+    pub(crate) document_links_receiver: Option<std::sync::mpsc::Receiver<DocumentLinks>>,
+
+    /// Passages of the selected document matching the search it was opened from
+    pub document_passages: Vec<PassageHit>,
+
+    /// Receiver for the selected document's matching passages
+    pub(crate) document_passages_receiver: Option<std::sync::mpsc::Receiver<Vec<PassageHit>>>,
+
+    /// Byte offsets of a passage the document view should scroll to
+    pub jump_to_passage: Option<(usize, usize)>,
+
+    /// Byte offsets of the passage the document was opened at, highlighted
+    pub highlighted_passage: Option<(usize, usize)>,
+
+    /// Highlights saved on the selected document
+    pub document_annotations: Vec<crate::annotations::Annotation>,
+
+    /// Receiver for the selected document's highlights
+    pub(crate) document_annotations_receiver:
+        Option<std::sync::mpsc::Receiver<Vec<crate::annotations::Annotation>>>,
+
+    /// Byte offsets, in the stored content, of the text selected in the
+    /// document view, which the highlight bar saves
+    pub annotation_selection: Option<(usize, usize)>,
+
+    /// Note typed in the highlight bar
+    pub annotation_note: String,
+
+    /// Set to clear the document view's text selection on the next frame
+    pub clear_document_selection: bool,
+
+    /// Receiver for a highlight being saved
+    pub(crate) annotation_save_receiver: Option<
+        std::sync::mpsc::Receiver<std::result::Result<crate::annotations::Annotation, String>>,
+    >,
+
+    /// Earlier versions of the selected document, newest first
+    pub document_versions: Vec<crate::document_versions::DocumentVersion>,
+
+    /// Receiver for the selected document's earlier versions
+    pub(crate) document_versions_receiver:
+        Option<std::sync::mpsc::Receiver<Vec<crate::document_versions::DocumentVersion>>>,
+
+    /// Earlier version being compared with the current content
+    pub version_diff: Option<widgets::document_versions::VersionDiff>,
+
+    /// Receiver for a version being restored: (doc_id, result)
+    pub(crate) version_restore_receiver:
+        Option<std::sync::mpsc::Receiver<(i64, std::result::Result<(), String>)>>,
+
+    /// In-document find bar (Ctrl+F)
+    pub document_find: DocumentFind,
+
+    /// Whether the document view's metadata panel is shown
+    pub show_metadata_panel: bool,
+
+    /// Chunk, embedding and link-check details of the selected document
+    pub document_metadata: Option<DocumentMetadata>,
+
+    /// Bookmark folder (or directory, for files) of the selected document
+    pub document_folder: Option<String>,
+
+    /// Receiver for the selected document's metadata and folder
+    pub(crate) document_metadata_receiver:
+        Option<std::sync::mpsc::Receiver<(Option<DocumentMetadata>, Option<String>)>>,
+
+    /// Title being edited in the metadata panel
+    pub title_edit: Option<String>,
+
+    /// Tags being edited in the metadata panel, comma-separated
+    pub tags_edit: Option<String>,
+
+    /// Existing and related tags offered while tags are edited
+    pub tag_suggestions: TagSuggestions,
+
+    /// Receiver for tag suggestions
+    pub(crate) tag_suggestions_receiver: Option<std::sync::mpsc::Receiver<TagSuggestions>>,
+
+    /// Tag edit text the current suggestions were requested for
+    pub(crate) tag_suggestions_for: Option<String>,
+
+    /// Receiver for the first-ingestion preview (closed once none is needed)
+    pub(crate) preview_request_receiver:
+        Option<std::sync::mpsc::Receiver<crate::ingestion_preview::PreviewRequest>>,
+
+    /// First-ingestion preview waiting for the user's choice
+    pub ingestion_preview: Option<crate::ingestion_preview::IngestionPreview>,
+
+    /// Reply channel for the shown preview
+    pub(crate) preview_reply:
+        Option<tokio::sync::oneshot::Sender<crate::ingestion_preview::PreviewDecision>>,
+
+    /// Folder IDs unchecked in the preview
+    pub preview_skipped_folders: HashSet<String>,
+
+    /// Domains unchecked in the preview
+    pub preview_skipped_domains: HashSet<String>,
+
+    /// Receiver for background health events (embedding server down, low disk)
+    notification_event_receiver: Option<std::sync::mpsc::Receiver<NotificationEvent>>,
+
+    /// Receiver for switches between semantic and keyword-only search
+    semantic_status_receiver: Option<std::sync::mpsc::Receiver<bool>>,
+
+    /// False while the embedding backend is down and search is keyword-only
+    pub semantic_search_available: bool,
+
+    /// Receiver for bookmark file watcher failures
+    bookmark_watch_error_receiver: Option<std::sync::mpsc::Receiver<String>>,
+
+    /// Latest bookmark watcher failure, shown in the status bar
+    pub bookmark_watch_error: Option<String>,
+
+    /// Event types with native notifications turned off in Settings
+    pub muted_notifications: HashSet<NotificationEvent>,
+
+    /// Receiver for the stored notification opt-outs
+    pub(crate) notification_settings_receiver:
+        Option<std::sync::mpsc::Receiver<HashSet<NotificationEvent>>>,
+
+    /// Per-source ingestion policies edited in Settings
+    pub ingest_policies: IngestPolicies,
+
+    /// Default-tags fields of the ingestion policy editor, as typed
+    pub ingest_policy_tag_inputs: std::collections::HashMap<SourceKind, String>,
+
+    /// Receiver for the stored ingestion policies
+    pub(crate) ingest_policies_receiver: Option<std::sync::mpsc::Receiver<IngestPolicies>>,
+
+    /// Text size and contrast chosen in Settings
+    pub appearance: Appearance,
+
+    /// Appearance last applied to the egui context
+    applied_appearance: Option<Appearance>,
+
+    /// Receiver for the stored UI language, appearance, layout and search
+    /// restore setting
+    ui_preferences_receiver: Option<std::sync::mpsc::Receiver<UiPreferences>>,
+
+    /// Window is minimized or unfocused, so events also raise native notifications
+    window_in_background: bool,
+
+    /// Embedding backend settings being edited
+    pub embedding_settings: EmbeddingSettings,
+
+    /// Command that launches the local embedding server; empty for the bundled one
+    pub embedding_server_command: String,
+
+    /// Keep search query embeddings on disk across restarts
+    pub persist_query_embeddings: bool,
+
+    /// Receiver for the stored embedding backend settings, server command and
+    /// query cache persistence
+    pub(crate) embedding_settings_receiver:
+        Option<std::sync::mpsc::Receiver<(EmbeddingSettings, String, bool)>>,
+
+    /// Models offered by the selected provider's server
+    pub embedding_models: Vec<String>,
+
+    /// Receiver for the provider's model list
+    pub(crate) embedding_models_receiver:
+        Option<std::sync::mpsc::Receiver<Result<Vec<String>, String>>>,
+
+    /// Receiver for the result of switching embedding backend: whether
+    /// documents are being re-embedded before the switch
+    pub(crate) embedding_apply_receiver: Option<std::sync::mpsc::Receiver<Result<bool, String>>>,
+
+    /// Progress of re-embedding with a newly chosen model, while under way
+    pub embedding_migration: Option<crate::embedding_migration::MigrationProgress>,
+
+    /// Receiver for re-embedding progress; `None` once the model switched
+    pub(crate) embedding_migration_receiver:
+        Option<std::sync::mpsc::Receiver<Option<crate::embedding_migration::MigrationProgress>>>,
+
+    /// Error from loading models or switching backend, shown in Settings
+    pub embedding_settings_error: Option<String>,
+
+    /// Receiver for link-check progress (scheduled and manual runs)
+    pub(crate) link_check_progress_receiver:
+        Option<std::sync::mpsc::Receiver<crate::link_checker::LinkCheckProgress>>,
+
+    /// Receiver for database maintenance progress (scheduled and manual runs)
+    pub(crate) maintenance_progress_receiver:
+        Option<std::sync::mpsc::Receiver<crate::maintenance::MaintenanceProgress>>,
+
+    /// Sender cloned into manual maintenance runs
+    pub(crate) maintenance_progress_tx:
+        std::sync::mpsc::Sender<crate::maintenance::MaintenanceProgress>,
+
+    /// ID of the current maintenance progress toast (for replacing)
+    pub(crate) maintenance_toast_id: Option<u64>,
+
+    /// Progress of the current or most recent maintenance run
+    pub maintenance_progress: Option<crate::maintenance::MaintenanceProgress>,
+
+    /// Days between scheduled maintenance runs (0 = disabled)
+    pub maintenance_interval_days: u64,
+
+    /// Receiver for the stored maintenance interval
+    pub(crate) maintenance_interval_receiver: Option<std::sync::mpsc::Receiver<u64>>,
+
+    /// Receiver for integrity reports (startup audit and re-audits after repairs)
+    pub(crate) integrity_report_receiver:
+        Option<std::sync::mpsc::Receiver<crate::integrity::IntegrityReport>>,
+
+    /// Sender cloned into integrity repairs for the re-audit
+    pub(crate) integrity_report_tx: std::sync::mpsc::Sender<crate::integrity::IntegrityReport>,
+
+    /// Most recent integrity report
+    pub integrity_report: Option<crate::integrity::IntegrityReport>,
+
+    /// Whether the integrity report window is open
+    pub show_integrity_report: bool,
+
+    /// Receiver for the result of a running integrity repair
+    pub(crate) integrity_repair_receiver: Option<
+        std::sync::mpsc::Receiver<std::result::Result<(crate::integrity::Repair, usize), String>>,
+    >,
+
+    /// Cached site icons (PNG) by host, for document and result lists
+    pub(crate) favicons: std::collections::HashMap<String, Arc<[u8]>>,
+
+    /// Hosts whose icons were asked for, so each is loaded once
+    pub(crate) favicons_requested: HashSet<String>,
+
+    /// Sender and receiver for site icons loaded from the cache: the hosts
+    /// asked for and the icons found
+    pub(crate) favicon_tx: std::sync::mpsc::Sender<LoadedFavicons>,
+    pub(crate) favicon_rx: std::sync::mpsc::Receiver<LoadedFavicons>,
+
+    /// Icon loads still running
+    pub(crate) favicon_loads_pending: usize,
+
+    /// Show result and document cards with thumbnails
+    pub card_layout: bool,
+
+    /// Loaded thumbnails (JPEG) by document
+    pub(crate) thumbnails: std::collections::HashMap<i64, Arc<[u8]>>,
+
+    /// Documents whose thumbnails were asked for, so each is loaded once
+    pub(crate) thumbnails_requested: HashSet<i64>,
+
+    /// Sender and receiver for thumbnails loaded from the database: the
+    /// documents asked for and the thumbnails found
+    pub(crate) thumbnail_tx: std::sync::mpsc::Sender<LoadedThumbnails>,
+    pub(crate) thumbnail_rx: std::sync::mpsc::Receiver<LoadedThumbnails>,
+
+    /// Thumbnail loads still running
+    pub(crate) thumbnail_loads_pending: usize,
+
+    /// Detected languages (ISO 639-3) by document, for language badges
+    document_languages: std::collections::HashMap<i64, String>,
+
+    /// Documents whose languages were asked for, so each is loaded once
+    languages_requested: HashSet<i64>,
+
+    /// Sender and receiver for document languages loaded from the
+    /// database: the documents asked for and the languages found
+    language_tx: std::sync::mpsc::Sender<(Vec<i64>, std::collections::HashMap<i64, String>)>,
+    language_rx: std::sync::mpsc::Receiver<(Vec<i64>, std::collections::HashMap<i64, String>)>,
+
+    /// Language loads still running
+    language_loads_pending: usize,
+
+    /// Languages found in the library with their document counts, and the
+    /// ones excluded from embedding, edited in Settings
+    pub language_counts: Vec<(String, i64)>,
+    pub excluded_languages: Vec<String>,
+
+    /// Tracking parameters stripped from URLs shown in the library, and
+    /// the list as edited in Settings
+    pub tracking_params: Vec<String>,
+    pub tracking_params_edit: String,
+
+    /// Image caption settings, as edited in Settings
+    pub image_caption_settings: crate::image_caption::ImageCaptionSettings,
+
+    /// Domains marked as high or low quality, the domain being typed to
+    /// mark another, and the score multipliers, as edited in Settings
+    pub domain_preferences: Vec<crate::domain_preferences::DomainPreference>,
+    pub domain_input: String,
+    pub domain_boost: crate::domain_preferences::DomainBoost,
+
+    /// Receiver for the library's tracking, image caption and domain settings
+    cached_settings_receiver: Option<std::sync::mpsc::Receiver<crate::db::CachedSettings>>,
+
+    /// Receiver for the language counts and excluded languages
+    pub(crate) language_settings_receiver: Option<std::sync::mpsc::Receiver<LanguageSettings>>,
+
+    /// Pages indexed by title and URL only because they looked like junk,
+    /// listed in Settings for review
+    pub low_quality_pages: Vec<crate::page_quality::LowQualityPage>,
+
+    /// Receiver for the low-quality page list
+    pub(crate) low_quality_receiver:
+        Option<std::sync::mpsc::Receiver<Vec<crate::page_quality::LowQualityPage>>>,
+
+    /// Documents whose content is only a fetch placeholder, left out of
+    /// search and listed in Settings until refetched
+    pub refetch_documents: Vec<crate::db::Document>,
+
+    /// Receiver for the documents needing a refetch
+    pub(crate) refetch_documents_receiver:
+        Option<std::sync::mpsc::Receiver<Vec<crate::db::Document>>>,
+
+    /// Receiver for a flagged page fetched again: its ID and the issue still
+    /// found, if any
+    pub(crate) page_refetch_receiver: Option<std::sync::mpsc::Receiver<PageRefetch>>,
+
+    /// Known libraries and the open one
+    pub libraries: crate::library::LibraryRegistry,
+
+    /// Name typed for a new library in the library menu
+    pub new_library_name: String,
+
+    /// Error from the last library create, switch or delete
+    pub library_error: Option<String>,
+
+    /// Library whose deletion is waiting for confirmation
+    pub library_delete_pending: Option<String>,
+
+    /// Receiver for the result of switching library: its name and whether
+    /// semantic search is available in it
+    pub(crate) library_switch_receiver:
+        Option<std::sync::mpsc::Receiver<std::result::Result<(String, bool), String>>>,
+
+    /// LAN remote access settings edited in Settings
+    pub remote_access_settings: crate::remote_access::RemoteAccessSettings,
+
+    /// Receiver for the stored remote access settings
+    pub(crate) remote_access_receiver:
+        Option<std::sync::mpsc::Receiver<crate::remote_access::RemoteAccessSettings>>,
+
+    /// HTTP API port and bind address edited in Settings
+    pub http_server_settings: crate::http_settings::HttpServerSettings,
+
+    /// Bind address as typed, saved once it parses
+    pub http_bind_input: String,
+
+    /// Receiver for the stored HTTP API settings
+    pub(crate) http_server_receiver:
+        Option<std::sync::mpsc::Receiver<crate::http_settings::HttpServerSettings>>,
+
+    /// Where the HTTP API is listening, or why it could not
+    pub http_server_status: crate::http_settings::HttpServerStatus,
+
+    /// Receiver for reports of scheduled sync runs
+    pub(crate) sync_report_receiver: Option<std::sync::mpsc::Receiver<crate::sync::SyncReport>>,
+
+    /// Sync settings edited in Settings
+    pub sync_settings: crate::sync::SyncSettings,
+
+    /// Receiver for the stored sync settings
+    pub(crate) sync_settings_receiver: Option<std::sync::mpsc::Receiver<crate::sync::SyncSettings>>,
+
+    /// Receiver for the result of a manual sync run
+    pub(crate) sync_run_receiver:
+        Option<std::sync::mpsc::Receiver<std::result::Result<crate::sync::SyncReport, String>>>,
+
+    /// Result of the most recent sync run
+    pub sync_last_result: Option<std::result::Result<crate::sync::SyncReport, String>>,
+
+    /// Receiver for reports of scheduled snapshots
+    pub(crate) snapshot_report_receiver:
+        Option<std::sync::mpsc::Receiver<crate::snapshot::SnapshotReport>>,
+
+    /// Snapshot settings edited in Settings
+    pub snapshot_settings: crate::snapshot::SnapshotSettings,
+
+    /// Receiver for the stored snapshot settings
+    pub(crate) snapshot_settings_receiver:
+        Option<std::sync::mpsc::Receiver<crate::snapshot::SnapshotSettings>>,
+
+    /// Receiver for the result of a manual snapshot
+    pub(crate) snapshot_run_receiver: Option<
+        std::sync::mpsc::Receiver<std::result::Result<crate::snapshot::SnapshotReport, String>>,
+    >,
+
+    /// Result of the most recent snapshot
+    pub snapshot_last_result: Option<std::result::Result<crate::snapshot::SnapshotReport, String>>,
+
+    /// Receiver for documents trashed by scheduled retention runs
+    pub(crate) retention_run_receiver: Option<std::sync::mpsc::Receiver<Vec<i64>>>,
+
+    /// Retention rules edited in Settings
+    pub retention_rules: Vec<crate::retention::RetentionRule>,
+
+    /// Receiver for the stored retention rules
+    pub(crate) retention_rules_receiver:
+        Option<std::sync::mpsc::Receiver<Vec<crate::retention::RetentionRule>>>,
+
+    /// Documents the retention rules would remove, once previewed
+    pub retention_preview: Option<Vec<crate::retention::RetentionMatch>>,
+
+    /// Receiver for a retention preview
+    pub(crate) retention_preview_receiver:
+        Option<std::sync::mpsc::Receiver<Vec<crate::retention::RetentionMatch>>>,
+
+    /// Sender cloned into manual "Check now" runs
+    pub(crate) link_check_progress_tx:
+        std::sync::mpsc::Sender<crate::link_checker::LinkCheckProgress>,
+
+    /// ID of the current link-check progress toast (for replacing)
+    pub(crate) link_check_toast_id: Option<u64>,
+
+    /// Progress of the current or most recent link check
+    pub link_check_progress: Option<crate::link_checker::LinkCheckProgress>,
+
+    /// Hours between scheduled link checks (0 = disabled)
+    pub dead_link_interval_hours: u64,
+
+    /// Receiver for the stored link-check interval
+    pub(crate) dead_link_interval_receiver: Option<std::sync::mpsc::Receiver<u64>>,
+
+    /// Index size, its limit and the free disk space, for Settings
+    pub storage_status: Option<crate::storage_budget::StorageStatus>,
+
+    /// Receiver for the measured storage status
+    pub(crate) storage_status_receiver:
+        Option<std::sync::mpsc::Receiver<crate::storage_budget::StorageStatus>>,
+
+    /// Index size limit in megabytes as edited in Settings (0 = unlimited)
+    pub max_index_mb: u64,
+
+    /// Documents suggested for pruning, in pruning order
+    pub prune_suggestions: Vec<crate::storage_budget::PruneCandidate>,
+
+    /// Receiver for pruning suggestions
+    pub(crate) prune_suggestions_receiver:
+        Option<std::sync::mpsc::Receiver<Vec<crate::storage_budget::PruneCandidate>>>,
+
+    /// Layout saved at the last exit, until it is restored
+    saved_layout: Option<UiLayout>,
 }
 
 /// Build a tree structure from a flat list of folders based on their paths
-fn build_folder_tree(mut folders: Vec<BookmarkFolderView>) -> Vec<BookmarkFolderView> {
+pub(crate) fn build_folder_tree(mut folders: Vec<BookmarkFolderView>) -> Vec<BookmarkFolderView> {
     // Sort by path depth (shallowest first) so parents are processed before children
     folders.sort_by_key(|f| f.path.len());
 
@@ -253,6 +1058,10 @@ impl LocalMindApp {
     pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
         println!("LocalMindApp::new() starting");
 
+        let saved_layout = cc
+            .storage
+            .and_then(|storage| eframe::get_value::<UiLayout>(storage, UI_LAYOUT_KEY));
+
         // Create tokio runtime for async operations
         let runtime = tokio::runtime::Builder::new_multi_thread()
             .enable_all()
@@ -261,16 +1070,54 @@ impl LocalMindApp {
 
         let runtime_handle = runtime.handle().clone();
 
+        // Site icons are shown from PNG bytes
+        egui_extras::install_image_loaders(&cc.egui_ctx);
+
         // Create shared RAG state
         let rag_state: RagState = Arc::new(RwLock::new(None));
         let rag_state_clone = rag_state.clone();
 
         // Create channel for RAG initialization notification
         let (init_tx, init_rx) = std::sync::mpsc::channel();
+        let (server_status_tx, server_status_rx) = std::sync::mpsc::channel();
 
         // Create channel for bookmark progress
         let (bookmark_progress_tx, bookmark_progress_rx) = std::sync::mpsc::channel();
 
+        // Create channel for link-check progress
+        let (link_check_tx, link_check_rx) = std::sync::mpsc::channel();
+
+        // Create channel for documents trashed by scheduled retention runs
+        let (retention_tx, retention_rx) = std::sync::mpsc::channel();
+
+        // Create channel for database maintenance progress
+        let (maintenance_tx, maintenance_rx) = std::sync::mpsc::channel();
+        let maintenance_tx_clone = maintenance_tx.clone();
+
+        // Create channel for reports of scheduled sync runs
+        let (sync_tx, sync_rx) = std::sync::mpsc::channel();
+
+        // Create channel for progress of re-embedding with a new model
+        let (migration_tx, migration_rx) = std::sync::mpsc::channel();
+
+        // Create channel for reports of scheduled snapshots
+        let (snapshot_tx, snapshot_rx) = std::sync::mpsc::channel();
+
+        // Create channel for reminders fired by the scheduler
+        let (reminder_tx, reminder_rx) = std::sync::mpsc::channel();
+
+        // Create channel for integrity reports
+        let (integrity_tx, integrity_rx) = std::sync::mpsc::channel();
+        let integrity_tx_clone = integrity_tx.clone();
+
+        // Create channel for the first-ingestion preview
+        let (preview_tx, preview_rx) = std::sync::mpsc::channel();
+
+        // Create channel for background health events
+        let (notification_event_tx, notification_event_rx) = std::sync::mpsc::channel();
+        let (bookmark_watch_error_tx, bookmark_watch_error_rx) = std::sync::mpsc::channel();
+        let (semantic_status_tx, semantic_status_rx) = std::sync::mpsc::channel();
+
         // Create folder-watch service and its channels (T023)
         let (folder_watch_svc, folder_file_rx, folder_watch_event_rx) =
             crate::folder_watcher::FolderWatchService::new();
@@ -284,11 +1131,12 @@ impl LocalMindApp {
         let ctx = cc.egui_ctx.clone();
         let bookmark_progress_tx_clone = bookmark_progress_tx.clone();
         let runtime_handle_for_bookmarks = runtime_handle.clone();
+        let link_check_tx_clone = link_check_tx.clone();
         runtime_handle.spawn(async move {
             println!("Starting RAG initialization task");
 
-            match init_rag_system().await {
-                Ok((rag, child_opt)) => {
+            match init_rag_system(server_status_tx).await {
+                Ok((rag, supervisor)) => {
                     println!("RAG system initialized successfully");
                     {
                         let mut rag_lock = rag_state_clone.write().await;
@@ -296,22 +1144,112 @@ impl LocalMindApp {
                         println!("RAG stored in state");
                     }
 
-                    // Signal success, passing back any spawned child handle
-                    let _ = init_tx.send(Ok(child_opt));
+                    // Signal success, handing over the server supervisor
+                    let _ = init_tx.send(Ok(supervisor));
 
                     // Start bookmark monitoring with progress reporting
                     let rag_for_bookmarks = rag_state_clone.clone();
                     let bookmark_progress_tx_for_monitor = bookmark_progress_tx_clone.clone();
+                    let rag_for_import = rag_state_clone.clone();
+                    let bookmark_progress_tx_for_import = bookmark_progress_tx_clone.clone();
                     runtime_handle_for_bookmarks.spawn(async move {
-                        if let Err(e) = start_bookmark_monitoring(
-                            rag_for_bookmarks,
-                            bookmark_progress_tx_for_monitor,
+                        let watch = match start_bookmark_monitoring(
+                            rag_for_bookmarks.clone(),
+                            bookmark_progress_tx_for_monitor.clone(),
+                            Some(preview_tx),
                         )
                         .await
                         {
-                            eprintln!("Failed to start bookmark monitoring: {}", e);
-                        }
-                    });
+                            Ok(watch) => watch,
+                            Err(e) => {
+                                eprintln!("Failed to start bookmark monitoring: {}", e);
+                                false
+                            }
+                        };
+                        // Resume a bookmark file import interrupted by the last shutdown
+                        ingest_imported_bookmarks(rag_for_import, bookmark_progress_tx_for_import)
+                            .await;
+                        if watch {
+                            // The GUI process exits without stopping the watchers
+                            watch_bookmark_profiles(
+                                rag_for_bookmarks,
+                                bookmark_progress_tx_for_monitor,
+                                bookmark_watch_error_tx,
+                                tokio_util::sync::CancellationToken::new(),
+                            )
+                            .await;
+                        }
+                    });
+
+                    // Repair documents left incomplete by an earlier crash,
+                    // then audit the database for any other damage
+                    let rag_for_audit = rag_state_clone.clone();
+                    runtime_handle_for_bookmarks.spawn(async move {
+                        start_consistency_check(rag_for_audit.clone()).await;
+                        start_integrity_audit(rag_for_audit, integrity_tx_clone).await;
+                    });
+
+                    // Watch the embedding server and disk space
+                    runtime_handle_for_bookmarks.spawn(start_health_monitor(
+                        rag_state_clone.clone(),
+                        notification_event_tx,
+                        semantic_status_tx,
+                    ));
+
+                    // Start scheduled dead-link checking
+                    let rag_for_link_check = rag_state_clone.clone();
+                    runtime_handle_for_bookmarks.spawn(start_link_check_scheduler(
+                        rag_for_link_check,
+                        link_check_tx_clone,
+                    ));
+
+                    // Start scheduled database maintenance
+                    runtime_handle_for_bookmarks.spawn(start_maintenance_scheduler(
+                        rag_state_clone.clone(),
+                        maintenance_tx_clone,
+                    ));
+
+                    // Start scheduled retention runs
+                    runtime_handle_for_bookmarks.spawn(start_retention_scheduler(
+                        rag_state_clone.clone(),
+                        retention_tx,
+                    ));
+
+                    // Start scheduled sync with other machines
+                    runtime_handle_for_bookmarks
+                        .spawn(start_sync_scheduler(rag_state_clone.clone(), sync_tx));
+
+                    // Start scheduled snapshots of the library
+                    runtime_handle_for_bookmarks.spawn(start_snapshot_scheduler(
+                        rag_state_clone.clone(),
+                        snapshot_tx,
+                    ));
+
+                    // Start firing document reminders as they come due
+                    runtime_handle_for_bookmarks.spawn(start_reminder_scheduler(
+                        rag_state_clone.clone(),
+                        reminder_tx,
+                    ));
+
+                    // Fetch icons of the sites documents came from
+                    runtime_handle_for_bookmarks
+                        .spawn(start_favicon_fetcher(rag_state_clone.clone()));
+                    runtime_handle_for_bookmarks
+                        .spawn(start_thumbnail_fetcher(rag_state_clone.clone()));
+
+                    // Detect the language of documents saved before it was recorded
+                    runtime_handle_for_bookmarks
+                        .spawn(start_language_detector(rag_state_clone.clone()));
+
+                    // Re-embed documents after a switch of embedding model
+                    runtime_handle_for_bookmarks.spawn(start_embedding_migration(
+                        rag_state_clone.clone(),
+                        migration_tx,
+                    ));
+
+                    // Serve read-only searches on the LAN while enabled
+                    runtime_handle_for_bookmarks
+                        .spawn(start_remote_access_server(rag_state_clone.clone()));
 
                     // Request repaint to update UI
                     ctx.request_repaint();
@@ -326,11 +1264,23 @@ impl LocalMindApp {
 
         // Spawn HTTP server in background
         let rag_state_for_http = rag_state.clone();
+        let request_log = RequestLog::new();
+        let request_log_for_http = request_log.clone();
+        let http_server_status = crate::http_settings::HttpServerStatus::new();
+        let http_server_status_for_http = http_server_status.clone();
         runtime_handle.spawn(async move {
             // Wait a moment for RAG to initialize
             tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
 
-            if let Err(e) = start_http_server(rag_state_for_http).await {
+            // The GUI process exits without a graceful shutdown of the server
+            if let Err(e) = start_http_server(
+                rag_state_for_http,
+                request_log_for_http,
+                http_server_status_for_http,
+                std::future::pending(),
+            )
+            .await
+            {
                 eprintln!("Failed to start HTTP server: {}", e);
             }
         });
@@ -339,10 +1289,10 @@ impl LocalMindApp {
         // We leak the runtime intentionally - it will live for the app's lifetime
         std::mem::forget(runtime);
 
-        // Discover Chrome profiles at startup
+        // Discover Chrome (and Safari) profiles at startup
         let chrome_profiles: Vec<ChromeProfileInfo> = {
-            use crate::bookmark::get_all_chrome_profiles;
-            get_all_chrome_profiles()
+            use crate::bookmark::get_all_bookmark_profiles;
+            get_all_bookmark_profiles()
                 .into_iter()
                 .map(|p| ChromeProfileInfo {
                     dir_name: p.dir_name,
@@ -353,14 +1303,64 @@ impl LocalMindApp {
 
         println!("LocalMindApp::new() complete");
 
+        let mut app = Self::detached(runtime_handle);
+        app.rag = rag_state;
+        app.init_receiver = Some(init_rx);
+        app.reminder_due_receiver = Some(reminder_rx);
+        app.bookmark_progress_receiver = Some(bookmark_progress_rx);
+        app.bookmark_progress_tx = bookmark_progress_tx;
+        app.embedding_server_status_receiver = Some(server_status_rx);
+        app.available_profiles = chrome_profiles;
+        app.folder_watch_rx = Some(folder_watch_event_rx);
+        app.folder_file_event_rx = Some(folder_file_rx);
+        app.folder_watch_service = Some(folder_watch_service);
+        app.add_folder_tx = Some(add_folder_tx);
+        app.add_folder_rx = Some(add_folder_rx);
+        app.remove_folder_tx = Some(remove_folder_tx);
+        app.remove_folder_rx = Some(remove_folder_rx);
+        app.request_log = request_log;
+        app.preview_request_receiver = Some(preview_rx);
+        app.notification_event_receiver = Some(notification_event_rx);
+        app.semantic_status_receiver = Some(semantic_status_rx);
+        app.bookmark_watch_error_receiver = Some(bookmark_watch_error_rx);
+        app.embedding_migration_receiver = Some(migration_rx);
+        app.link_check_progress_receiver = Some(link_check_rx);
+        app.maintenance_progress_receiver = Some(maintenance_rx);
+        app.maintenance_progress_tx = maintenance_tx;
+        app.integrity_report_receiver = Some(integrity_rx);
+        app.integrity_report_tx = integrity_tx;
+        app.http_server_status = http_server_status;
+        app.sync_report_receiver = Some(sync_rx);
+        app.snapshot_report_receiver = Some(snapshot_rx);
+        app.retention_run_receiver = Some(retention_rx);
+        app.link_check_progress_tx = link_check_tx;
+        app.saved_layout = saved_layout;
+        app
+    }
+
+    /// App state with no backend behind it: nothing is loaded or spawned,
+    /// and the RAG system stays uninitialized. `new` wires the background
+    /// tasks into it; tests drive its state transitions directly.
+    pub(crate) fn detached(runtime: tokio::runtime::Handle) -> Self {
+        let (bookmark_progress_tx, bookmark_progress_rx) = std::sync::mpsc::channel();
+        let (link_check_tx, link_check_rx) = std::sync::mpsc::channel();
+        let (maintenance_tx, maintenance_rx) = std::sync::mpsc::channel();
+        let (integrity_tx, integrity_rx) = std::sync::mpsc::channel();
+        let (favicon_tx, favicon_rx) = std::sync::mpsc::channel();
+        let (thumbnail_tx, thumbnail_rx) = std::sync::mpsc::channel();
+        let (language_tx, language_rx) = std::sync::mpsc::channel();
+
         Self {
-            rag: rag_state,
+            rag: Arc::new(RwLock::new(None)),
             current_view: View::Home,
             search_query: String::new(),
             search_results: Vec::new(),
             all_results: Vec::new(),
             similarity_cutoff: 0.3,
             selected_document: None,
+            document_question: String::new(),
+            document_answer: None,
+            document_answer_receiver: None,
             markdown_cache: egui_commonmark::CommonMarkCache::default(),
             recent_documents: Vec::new(),
             settings_open: false,
@@ -368,33 +1368,78 @@ impl LocalMindApp {
             excluded_domains: Vec::new(),
             pending_domain: String::new(),
             bookmark_folders: Vec::new(),
-            toasts: Vec::new(),
+            toasts: ToastQueue::default(),
+            show_toast_history: false,
             init_status: InitStatus::WaitingForEmbedding,
-            next_toast_id: 0,
-            runtime: runtime_handle,
-            init_receiver: Some(init_rx),
+            runtime,
+            init_receiver: None,
             recent_docs_receiver: None,
+            show_shortcuts_help: false,
+            selected_index: None,
+            scroll_to_selection: false,
+            unread_ids: HashSet::new(),
+            unread_receiver: None,
+            unread_only: false,
+            pinned_documents: Vec::new(),
+            pinned_docs_receiver: None,
+            year_ago_documents: Vec::new(),
+            forgotten_documents: Vec::new(),
+            resurfaced_receiver: None,
+            reminders: Vec::new(),
+            reminders_receiver: None,
+            reminder_due_receiver: None,
+            reminder_draft: None,
+            reminder_set_receiver: None,
+            home_summary: HomeSummary::default(),
+            home_summary_receiver: None,
             search_receiver: None,
+            restored_search_receiver: None,
+            refreshing_search: false,
+            refresh_restored_search: true,
+            corrected_query: None,
+            exact_query: None,
+            search_fallback: None,
+            search_fallback_receiver: None,
+            autocomplete: Vec::new(),
+            autocomplete_index: None,
+            autocomplete_receiver: None,
             document_receiver: None,
             previous_view: View::Home,
             bookmark_progress_receiver: Some(bookmark_progress_rx),
-            bookmark_progress_toast_id: None,
+            bookmark_progress_toasts: BookmarkProgressToasts::default(),
+            bookmark_progress_tx,
+            show_import_dialog: false,
+            import_path_input: String::new(),
+            import_error: None,
+            bookmark_import_receiver: None,
+            show_paste_url_dialog: false,
+            paste_url_input: String::new(),
+            paste_url_error: None,
+            paste_url_receiver: None,
+            paste_url_toast_id: None,
+            show_note_dialog: false,
+            note_title: String::new(),
+            note_text: String::new(),
+            note_error: None,
+            note_receiver: None,
             exclusion_rules_receiver: None,
             save_exclusion_receiver: None,
-            embedding_server_child: None,
-            available_profiles: chrome_profiles,
+            embedding_server: None,
+            embedding_server_status_receiver: None,
+            embedding_server_status: None,
+            available_profiles: Vec::new(),
             selected_profile: None,
             // Folder-watch fields (T023)
             watched_folders: Vec::new(),
             folder_watch_progress: std::collections::HashMap::new(),
             folder_file_errors: std::collections::HashMap::new(),
-            folder_watch_rx: Some(folder_watch_event_rx),
-            folder_file_event_rx: Some(folder_file_rx),
-            folder_watch_service: Some(folder_watch_service),
-            add_folder_tx: Some(add_folder_tx),
-            add_folder_rx: Some(add_folder_rx),
-            remove_folder_tx: Some(remove_folder_tx),
-            remove_folder_rx: Some(remove_folder_rx),
+            folder_watch_rx: None,
+            folder_file_event_rx: None,
+            folder_watch_service: None,
+            add_folder_tx: None,
+            add_folder_rx: None,
+            remove_folder_tx: None,
+            remove_folder_rx: None,
             add_folder_input: String::new(),
             add_folder_error: None,
             _watched_folders_loader: None,
@@ -404,42 +1449,246 @@ impl LocalMindApp {
                     .join("localmind");
                 crate::query_logger::QueryLogger::new(log_dir.join("query_log.jsonl"))
             },
+            ingest_errors: Vec::new(),
+            ingest_errors_receiver: None,
+            retry_receiver: None,
+            show_dead_documents: false,
+            expand_query: false,
+            boost_linked_results: true,
+            group_by_site: true,
+            show_passages: false,
+            expanded_result_groups: HashSet::new(),
+            collections: Vec::new(),
+            collections_receiver: None,
+            search_collection: None,
+            new_collection_name: String::new(),
+            collection_action_receiver: None,
+            document_collection_ids: Vec::new(),
+            document_collections_receiver: None,
+            checked_doc_ids: HashSet::new(),
+            tag_input: String::new(),
+            document_action_receiver: None,
+            markdown_export_receiver: None,
+            trashed_documents: Vec::new(),
+            trashed_documents_receiver: None,
+            show_sidebar: true,
+            sidebar: SidebarData::default(),
+            sidebar_receiver: None,
+            browse_filter: BrowseFilter::All,
+            browse_documents: Vec::new(),
+            browse_receiver: None,
+            timeline_grouping: TimelineGrouping::Month,
+            timeline_filter: String::new(),
+            timeline_documents: Vec::new(),
+            timeline_exhausted: false,
+            timeline_receiver: None,
+            projection: Vec::new(),
+            projection_stale: false,
+            projection_receiver: None,
+            projection_job_receiver: None,
+            job_items: Vec::new(),
+            jobs_receiver: None,
+            request_log: RequestLog::new(),
+            activity_saves_only: true,
+            index_stats: None,
+            usage_insights: None,
+            stats_receiver: None,
+            document_tags: Vec::new(),
+            document_tags_receiver: None,
+            document_links_to: Vec::new(),
+            document_linked_from: Vec::new(),
+            document_structure: PageStructure::default(),
+            document_links_receiver: None,
+            document_passages: Vec::new(),
+            document_passages_receiver: None,
+            document_annotations: Vec::new(),
+            document_annotations_receiver: None,
+            annotation_selection: None,
+            annotation_note: String::new(),
+            clear_document_selection: false,
+            annotation_save_receiver: None,
+            document_versions: Vec::new(),
+            document_versions_receiver: None,
+            version_diff: None,
+            version_restore_receiver: None,
+            jump_to_passage: None,
+            highlighted_passage: None,
+            document_find: DocumentFind::default(),
+            show_metadata_panel: true,
+            document_metadata: None,
+            document_folder: None,
+            document_metadata_receiver: None,
+            title_edit: None,
+            tags_edit: None,
+            tag_suggestions: TagSuggestions::default(),
+            tag_suggestions_receiver: None,
+            tag_suggestions_for: None,
+            preview_request_receiver: None,
+            ingestion_preview: None,
+            preview_reply: None,
+            preview_skipped_folders: HashSet::new(),
+            preview_skipped_domains: HashSet::new(),
+            notification_event_receiver: None,
+            semantic_status_receiver: None,
+            semantic_search_available: true,
+            bookmark_watch_error_receiver: None,
+            bookmark_watch_error: None,
+            muted_notifications: HashSet::new(),
+            notification_settings_receiver: None,
+            ingest_policies: IngestPolicies::default(),
+            ingest_policy_tag_inputs: std::collections::HashMap::new(),
+            ingest_policies_receiver: None,
+            appearance: Appearance::default(),
+            applied_appearance: None,
+            ui_preferences_receiver: None,
+            window_in_background: false,
+            embedding_settings: EmbeddingSettings::default(),
+            embedding_server_command: String::new(),
+            persist_query_embeddings: true,
+            embedding_settings_receiver: None,
+            embedding_models: Vec::new(),
+            embedding_models_receiver: None,
+            embedding_apply_receiver: None,
+            embedding_migration: None,
+            embedding_migration_receiver: None,
+            embedding_settings_error: None,
+            link_check_progress_receiver: Some(link_check_rx),
+            maintenance_progress_receiver: Some(maintenance_rx),
+            maintenance_progress_tx: maintenance_tx,
+            maintenance_toast_id: None,
+            maintenance_progress: None,
+            maintenance_interval_days: crate::maintenance::DEFAULT_INTERVAL_DAYS,
+            maintenance_interval_receiver: None,
+            integrity_report_receiver: Some(integrity_rx),
+            integrity_report_tx: integrity_tx,
+            integrity_report: None,
+            show_integrity_report: false,
+            integrity_repair_receiver: None,
+            favicons: std::collections::HashMap::new(),
+            favicons_requested: HashSet::new(),
+            favicon_tx,
+            favicon_rx,
+            favicon_loads_pending: 0,
+            card_layout: false,
+            thumbnails: std::collections::HashMap::new(),
+            thumbnails_requested: HashSet::new(),
+            thumbnail_tx,
+            thumbnail_rx,
+            thumbnail_loads_pending: 0,
+            document_languages: std::collections::HashMap::new(),
+            languages_requested: HashSet::new(),
+            language_tx,
+            language_rx,
+            language_loads_pending: 0,
+            language_counts: Vec::new(),
+            excluded_languages: Vec::new(),
+            tracking_params: crate::tracking_params::defaults(),
+            tracking_params_edit: String::new(),
+            image_caption_settings: Default::default(),
+            domain_preferences: Vec::new(),
+            domain_input: String::new(),
+            domain_boost: Default::default(),
+            cached_settings_receiver: None,
+            language_settings_receiver: None,
+            low_quality_pages: Vec::new(),
+            low_quality_receiver: None,
+            refetch_documents: Vec::new(),
+            refetch_documents_receiver: None,
+            page_refetch_receiver: None,
+            libraries: crate::library::LibraryRegistry::load(),
+            new_library_name: String::new(),
+            library_error: None,
+            library_delete_pending: None,
+            library_switch_receiver: None,
+            remote_access_settings: crate::remote_access::RemoteAccessSettings::default(),
+            remote_access_receiver: None,
+            http_server_settings: crate::http_settings::HttpServerSettings::default(),
+            http_bind_input: crate::http_settings::HttpServerSettings::default().bind_address,
+            http_server_receiver: None,
+            http_server_status: crate::http_settings::HttpServerStatus::new(),
+            sync_report_receiver: None,
+            sync_settings: crate::sync::SyncSettings::default(),
+            sync_settings_receiver: None,
+            sync_run_receiver: None,
+            sync_last_result: None,
+            snapshot_report_receiver: None,
+            snapshot_settings: crate::snapshot::SnapshotSettings::default(),
+            snapshot_settings_receiver: None,
+            snapshot_run_receiver: None,
+            snapshot_last_result: None,
+            retention_run_receiver: None,
+            retention_rules: Vec::new(),
+            retention_rules_receiver: None,
+            retention_preview: None,
+            retention_preview_receiver: None,
+            link_check_progress_tx: link_check_tx,
+            link_check_toast_id: None,
+            link_check_progress: None,
+            dead_link_interval_hours: crate::link_checker::DEFAULT_INTERVAL_HOURS,
+            dead_link_interval_receiver: None,
+            storage_status: None,
+            storage_status_receiver: None,
+            max_index_mb: 0,
+            prune_suggestions: Vec::new(),
+            prune_suggestions_receiver: None,
+            saved_layout: None,
         }
     }
 
     /// Add a toast notification
     pub fn add_toast(&mut self, toast: Toast) {
-        self.toasts.push(toast);
+        self.toasts.add(toast);
+    }
+
+    /// Remove a toast before it expires
+    pub fn dismiss_toast(&mut self, id: u64) {
+        self.toasts.dismiss(id);
+    }
+
+    /// Run the action behind a toast button
+    pub fn run_toast_action(&mut self, action: ToastAction) {
+        match action {
+            ToastAction::OpenSettings => self.open_settings(),
+            ToastAction::RestoreDocuments(doc_ids) => self.restore_documents(doc_ids),
+            ToastAction::OpenDocument(doc_id) => self.load_document(doc_id),
+        }
     }
 
     /// Remove expired toasts
     pub fn cleanup_toasts(&mut self) {
-        self.toasts.retain(|t| !t.is_expired());
+        self.toasts.cleanup(std::time::Instant::now());
     }
 
     /// Get next unique toast ID
     pub fn next_toast_id(&mut self) -> u64 {
-        let id = self.next_toast_id;
-        self.next_toast_id += 1;
-        id
+        self.toasts.next_id()
     }
 
     /// Check for RAG initialization completion
     fn check_init_status(&mut self) {
         if let Some(ref rx) = self.init_receiver {
             match rx.try_recv() {
-                Ok(Ok(child_opt)) => {
+                Ok(Ok(supervisor)) => {
                     println!("RAG initialization confirmed");
-                    self.embedding_server_child = child_opt;
+                    self.embedding_server = supervisor;
                     self.init_status = InitStatus::Ready;
                     self.init_receiver = None;
 
                     // Add success toast
                     let id = self.next_toast_id();
-                    self.add_toast(Toast::success(id, "System ready"));
+                    self.add_toast(Toast::success(id, tr("home.ready")));
 
-                    // Trigger loading recent documents
+                    // Trigger loading recent and pinned documents
                     self.load_recent_documents();
+                    self.load_pinned_documents();
+                    self.load_resurfaced_documents();
+                    self.load_reminders();
+                    self.load_collections();
+                    self.load_sidebar();
+                    self.load_notification_settings();
+                    self.load_ui_preferences();
+                    self.load_cached_settings();
+                    self.restore_layout();
 
                     // Load watched folders and resume any active watchers (T040)
                     self.load_watched_folders();
@@ -452,7 +1701,10 @@ impl LocalMindApp {
 
                     // Add error toast
                     let id = self.next_toast_id();
-                    self.add_toast(Toast::error(id, format!("Initialization failed: {}", e)));
+                    self.add_toast(Toast::error(
+                        id,
+                        tr_args("home.init_failed", &[("error", &e)]),
+                    ));
                 }
                 Err(std::sync::mpsc::TryRecvError::Empty) => {
                     // Still waiting
@@ -460,7 +1712,7 @@ impl LocalMindApp {
                 Err(std::sync::mpsc::TryRecvError::Disconnected) => {
                     // Channel closed unexpectedly
                     self.init_status =
-                        InitStatus::Error("Initialization channel closed".to_string());
+                        InitStatus::Error(tr("home.init_channel_closed").to_string());
                     self.init_receiver = None;
                 }
             }
@@ -468,7 +1720,7 @@ impl LocalMindApp {
     }
 
     /// Load the list of watched folders from DB and update UI state (T040 startup check).
-    fn load_watched_folders(&mut self) {
+    pub(crate) fn load_watched_folders(&mut self) {
         let rag = self.rag.clone();
         let (tx, rx) = std::sync::mpsc::channel();
         let runtime = self.runtime.clone();
@@ -489,7 +1741,7 @@ impl LocalMindApp {
 
     /// On startup, resume watchers for all active folders and mark unavailable
     /// any folder whose path no longer exists (T040).
-    fn resume_watchers_on_startup(&mut self) {
+    pub(crate) fn resume_watchers_on_startup(&mut self) {
         use crate::folder_watcher::FolderStatus;
         use crate::gui::state::FolderWatchEvent;
 
@@ -717,176 +1969,236 @@ impl LocalMindApp {
         }
     }
 
-    /// Load recent documents for home screen
-    fn load_recent_documents(&mut self) {
-        if self.recent_docs_receiver.is_some() {
-            return; // Already loading
+    // -----------------------------------------------------------------------
+    // Document actions (context menu and bulk action bar)
+    // -----------------------------------------------------------------------
+
+    /// Run an action chosen from a document's context menu
+    pub fn run_document_action(
+        &mut self,
+        ctx: &egui::Context,
+        doc_id: i64,
+        url: Option<String>,
+        action: widgets::document_actions::DocumentAction,
+    ) {
+        use widgets::document_actions::DocumentAction;
+
+        match action {
+            DocumentAction::Open => self.load_document(doc_id),
+            DocumentAction::OpenInBrowser => self.open_in_browser(doc_id, url),
+            DocumentAction::CopyLink => {
+                if let Some(url) = url {
+                    ctx.copy_text(url);
+                    let id = self.next_toast_id();
+                    self.add_toast(Toast::success(id, tr("actions.link_copied")));
+                }
+            }
+            DocumentAction::Tag(tag) => self.tag_documents(vec![doc_id], tag),
+            DocumentAction::RemindMe => {
+                self.reminder_draft = Some(widgets::reminders::ReminderDraft {
+                    doc_id,
+                    ..Default::default()
+                });
+            }
+            DocumentAction::Delete => self.delete_documents(vec![doc_id]),
+            DocumentAction::ExcludeDomain => {
+                if let Some(url) = url {
+                    self.exclude_domain(&url);
+                }
+            }
+        }
+    }
+
+    /// Delete documents and their vectors
+    ///
+    /// They disappear from every list immediately; the toast reports the
+    /// outcome.
+    pub fn delete_documents(&mut self, doc_ids: Vec<i64>) {
+        if doc_ids.is_empty() || self.document_action_receiver.is_some() {
+            return;
+        }
+
+        let deleted: HashSet<i64> = doc_ids.iter().copied().collect();
+        self.search_results.retain(|r| !deleted.contains(&r.doc_id));
+        self.all_results.retain(|r| !deleted.contains(&r.doc_id));
+        self.recent_documents.retain(|d| !deleted.contains(&d.id));
+        self.pinned_documents.retain(|d| !deleted.contains(&d.id));
+        self.year_ago_documents.retain(|d| !deleted.contains(&d.id));
+        self.forgotten_documents
+            .retain(|d| !deleted.contains(&d.id));
+        self.reminders.retain(|r| !deleted.contains(&r.document_id));
+        self.checked_doc_ids.retain(|id| !deleted.contains(id));
+        self.selected_index = None;
+        if self.current_view == View::DocumentDetail
+            && self
+                .selected_document
+                .as_ref()
+                .is_some_and(|d| deleted.contains(&d.id))
+        {
+            self.navigate_back();
         }
 
         let rag = self.rag.clone();
         let (tx, rx) = std::sync::mpsc::channel();
         let runtime_handle = self.runtime.clone();
-        let selected_profile = self.selected_profile.clone();
 
         runtime_handle.spawn(async move {
             let rag_lock = rag.read().await;
-            let docs = if let Some(ref rag) = *rag_lock {
-                match rag
-                    .db
-                    .get_recent_documents_filtered(10, selected_profile)
-                    .await
-                {
-                    Ok(docs) => docs
-                        .into_iter()
-                        .map(|doc| DocumentView {
-                            id: doc.id,
-                            title: doc.title,
-                            content: prepare_content(&doc.content, doc.url.as_deref()),
-                            url: doc.url,
-                            source: doc.source,
-                            created_at: doc.created_at,
-                            profile: doc.profile,
-                            is_needs_auth: doc.needs_auth.unwrap_or(false),
-                        })
-                        .collect(),
-                    Err(e) => {
-                        eprintln!("Failed to load recent documents: {}", e);
-                        Vec::new()
+            let result = if let Some(ref rag) = *rag_lock {
+                match rag.db.trash_documents(&doc_ids).await {
+                    Ok(count) => {
+                        for doc_id in &doc_ids {
+                            rag.remove_document_vectors(*doc_id).await;
+                        }
+                        Ok((tr_args("trash.moved", &[("count", &count)]), doc_ids))
                     }
+                    Err(e) => Err(e.to_string()),
                 }
             } else {
-                Vec::new()
+                Err(tr("common.initializing").to_string())
             };
-            let _ = tx.send(docs);
+            let _ = tx.send(result);
         });
 
-        self.recent_docs_receiver = Some(rx);
+        self.document_action_receiver = Some(rx);
     }
 
-    /// Check if recent documents have loaded
-    fn check_recent_documents(&mut self) {
-        if let Some(ref rx) = self.recent_docs_receiver {
-            match rx.try_recv() {
-                Ok(docs) => {
-                    self.recent_documents = docs;
-                    println!("Loaded {} recent documents", self.recent_documents.len());
-                    self.recent_docs_receiver = None;
-                }
-                Err(std::sync::mpsc::TryRecvError::Empty) => {
-                    // Still loading
-                }
-                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
-                    // Channel closed, clear receiver
-                    self.recent_docs_receiver = None;
-                }
-            }
+    /// Bring documents back from the trash
+    pub fn restore_documents(&mut self, doc_ids: Vec<i64>) {
+        if doc_ids.is_empty() || self.document_action_receiver.is_some() {
+            return;
         }
+        self.trashed_documents.retain(|d| !doc_ids.contains(&d.id));
+
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let runtime_handle = self.runtime.clone();
+
+        runtime_handle.spawn(async move {
+            let rag_lock = rag.read().await;
+            let result = if let Some(ref rag) = *rag_lock {
+                match rag.db.restore_documents(&doc_ids).await {
+                    Ok(count) => {
+                        for doc_id in &doc_ids {
+                            if let Err(e) = rag.restore_document_vectors(*doc_id).await {
+                                eprintln!("Failed to reload vectors for {}: {}", doc_id, e);
+                            }
+                        }
+                        Ok((tr_args("trash.restored", &[("count", &count)]), Vec::new()))
+                    }
+                    Err(e) => Err(e.to_string()),
+                }
+            } else {
+                Err(tr("common.initializing").to_string())
+            };
+            let _ = tx.send(result);
+        });
+
+        self.document_action_receiver = Some(rx);
     }
 
-    /// Trigger a search with the current query
-    pub fn trigger_search(&mut self) {
-        let query = self.search_query.trim().to_string();
-        if query.is_empty() {
+    /// Permanently delete trashed documents, with their chunks and FTS rows.
+    /// `None` empties the whole trash.
+    pub fn purge_documents(&mut self, doc_ids: Option<Vec<i64>>) {
+        if self.document_action_receiver.is_some() {
             return;
         }
-
-        if self.search_receiver.is_some() {
-            return; // Already searching
+        match &doc_ids {
+            Some(ids) => self.trashed_documents.retain(|d| !ids.contains(&d.id)),
+            None => self.trashed_documents.clear(),
         }
 
-        println!("Triggering search for: {}", query);
-
         let rag = self.rag.clone();
         let (tx, rx) = std::sync::mpsc::channel();
         let runtime_handle = self.runtime.clone();
 
         runtime_handle.spawn(async move {
             let rag_lock = rag.read().await;
-            let results = if let Some(ref rag) = *rag_lock {
-                match rag.get_search_hits_fused(&query).await {
-                    Ok(hits) => hits
-                        .into_iter()
-                        .map(|hit| SearchResultView {
-                            doc_id: hit.doc_id,
-                            title: hit.title,
-                            snippet: create_snippet(&hit.content_snippet, 200),
-                            similarity: hit.similarity,
-                            url: None,
-                            profile: hit.profile,
-                            is_needs_auth: hit.needs_auth,
-                        })
-                        .collect(),
-                    Err(e) => {
-                        eprintln!("Search failed: {}", e);
-                        Vec::new()
-                    }
-                }
+            let result = if let Some(ref rag) = *rag_lock {
+                let purged = match doc_ids {
+                    Some(ids) => rag.db.delete_documents(&ids).await,
+                    None => rag.db.purge_trash().await,
+                };
+                purged
+                    .map(|count| {
+                        (
+                            tr_args("trash.deleted_count", &[("count", &count)]),
+                            Vec::new(),
+                        )
+                    })
+                    .map_err(|e| e.to_string())
             } else {
-                Vec::new()
+                Err(tr("common.initializing").to_string())
             };
-            let _ = tx.send(results);
+            let _ = tx.send(result);
         });
 
-        self.search_receiver = Some(rx);
-        self.current_view = View::SearchResults;
+        self.document_action_receiver = Some(rx);
     }
 
-    /// Check if search results have arrived
-    fn check_search_results(&mut self) {
-        if let Some(ref rx) = self.search_receiver {
-            match rx.try_recv() {
-                Ok(results) => {
-                    println!("Search returned {} results", results.len());
-                    self.all_results = results;
-                    self.apply_search_filters();
-                    self.search_receiver = None;
-                    self.query_logger.record_search(&self.search_query, &self.search_results);
-                }
-                Err(std::sync::mpsc::TryRecvError::Empty) => {
-                    // Still searching
-                }
-                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
-                    // Channel closed, clear receiver
-                    self.search_receiver = None;
-                }
-            }
+    /// Add a toast for documents just moved to the trash, with an "Undo"
+    /// button for `UNDO_WINDOW`
+    pub(crate) fn add_undo_toast(&mut self, message: String, trashed: Vec<i64>) {
+        let id = self.next_toast_id();
+        if trashed.is_empty() {
+            self.add_toast(Toast::success(id, message));
+        } else {
+            self.add_toast(
+                Toast::new(id, message, ToastType::Success, UNDO_WINDOW)
+                    .with_button(tr("common.undo"), ToastAction::RestoreDocuments(trashed)),
+            );
         }
     }
 
-    /// Apply similarity cutoff, profile filter, and deduplication to produce search_results.
-    pub fn apply_search_filters(&mut self) {
-        let mut seen_ids = std::collections::HashSet::new();
-        self.search_results = self
-            .all_results
-            .iter()
-            .filter(|r| {
-                if !seen_ids.insert(r.doc_id) {
-                    return false;
-                }
-                if r.similarity < self.similarity_cutoff {
-                    return false;
-                }
-                if let Some(ref selected) = self.selected_profile {
-                    return r.profile.as_deref() == Some(selected.as_str());
-                }
-                true
-            })
-            .cloned()
-            .collect();
-    }
+    /// Add a tag to documents
+    pub fn tag_documents(&mut self, doc_ids: Vec<i64>, tag: String) {
+        let tag = tag.trim().to_string();
+        if doc_ids.is_empty() || tag.is_empty() || self.document_action_receiver.is_some() {
+            return;
+        }
+
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let runtime_handle = self.runtime.clone();
+
+        runtime_handle.spawn(async move {
+            let rag_lock = rag.read().await;
+            let result = if let Some(ref rag) = *rag_lock {
+                rag.db
+                    .add_tag_to_documents(&doc_ids, &tag)
+                    .await
+                    .map(|added| {
+                        (
+                            tr_args("actions.tagged", &[("count", &added), ("tag", &tag)]),
+                            Vec::new(),
+                        )
+                    })
+                    .map_err(|e| e.to_string())
+            } else {
+                Err(tr("common.initializing").to_string())
+            };
+            let _ = tx.send(result);
+        });
 
-    /// Check if a search is in progress
-    pub fn is_search_pending(&self) -> bool {
-        self.search_receiver.is_some()
+        self.tag_input.clear();
+        self.document_action_receiver = Some(rx);
     }
 
-    /// Load a document by ID for viewing
-    pub fn load_document(&mut self, doc_id: i64) {
-        if self.document_receiver.is_some() {
-            return; // Already loading
+    /// Add a URL's domain to the exclusion rules and remove its bookmarks
+    pub fn exclude_domain(&mut self, url: &str) {
+        if self.document_action_receiver.is_some() {
+            return;
         }
+        let Some(pattern) = crate::bookmark_exclusion::ExclusionRules::pattern_for_url(url) else {
+            let id = self.next_toast_id();
+            self.add_toast(Toast::error(id, tr("actions.no_domain")));
+            return;
+        };
 
-        println!("Loading document: {}", doc_id);
+        // Keep the settings list in step if it is already loaded
+        if !self.excluded_domains.contains(&pattern) {
+            self.excluded_domains.push(pattern.clone());
+        }
 
         let rag = self.rag.clone();
         let (tx, rx) = std::sync::mpsc::channel();
@@ -894,67 +2206,85 @@ impl LocalMindApp {
 
         runtime_handle.spawn(async move {
             let rag_lock = rag.read().await;
-            let doc = if let Some(ref rag) = *rag_lock {
-                match rag.db.get_document(doc_id).await {
-                    Ok(Some(doc)) => Some(DocumentView {
-                        id: doc.id,
-                        title: doc.title,
-                        content: prepare_content(&doc.content, doc.url.as_deref()),
-                        url: doc.url,
-                        source: doc.source,
-                        created_at: doc.created_at,
-                        profile: doc.profile,
-                        is_needs_auth: doc.needs_auth.unwrap_or(false),
-                    }),
-                    Ok(None) => {
-                        eprintln!("Document not found: {}", doc_id);
-                        None
+            let result = if let Some(ref rag) = *rag_lock {
+                // Read the stored rules: the settings list may not be loaded
+                let outcome = async {
+                    let mut domains = rag.db.get_excluded_domains().await?;
+                    if !domains.contains(&pattern) {
+                        domains.push(pattern.clone());
+                        rag.db.set_excluded_domains(&domains).await?;
                     }
-                    Err(e) => {
-                        eprintln!("Failed to load document: {}", e);
-                        None
+                    rag.db.delete_bookmarks_by_url_pattern(&pattern).await
+                };
+                match outcome.await {
+                    Ok(trashed) => {
+                        for doc_id in &trashed {
+                            rag.remove_document_vectors(*doc_id).await;
+                        }
+                        let message = tr_args(
+                            "actions.excluded",
+                            &[("pattern", &pattern), ("count", &trashed.len())],
+                        );
+                        Ok((message, trashed))
                     }
+                    Err(e) => Err(e.to_string()),
                 }
             } else {
-                None
+                Err(tr("common.initializing").to_string())
             };
-            let _ = tx.send(doc);
+            let _ = tx.send(result);
         });
 
-        self.document_receiver = Some(rx);
-        self.previous_view = self.current_view.clone();
-        self.current_view = View::DocumentDetail;
+        self.document_action_receiver = Some(rx);
     }
 
-    /// Check if a document has been loaded
-    fn check_document_loaded(&mut self) {
-        if let Some(ref rx) = self.document_receiver {
+    /// Check if a document action has completed
+    fn check_document_actions(&mut self) {
+        if let Some(ref rx) = self.document_action_receiver {
             match rx.try_recv() {
-                Ok(Some(doc)) => {
-                    println!("Document loaded: {}", doc.title);
-                    self.selected_document = Some(doc);
-                    self.document_receiver = None;
-                }
-                Ok(None) => {
-                    // Document not found, go back
-                    eprintln!("Document not found, going back");
-                    self.current_view = self.previous_view.clone();
-                    self.document_receiver = None;
-
-                    let id = self.next_toast_id();
-                    self.add_toast(Toast::error(id, "Document not found"));
-                }
-                Err(std::sync::mpsc::TryRecvError::Empty) => {
-                    // Still loading
+                Ok(result) => {
+                    self.document_action_receiver = None;
+                    match result {
+                        Ok((message, trashed)) => self.add_undo_toast(message, trashed),
+                        Err(e) => {
+                            eprintln!("Document action failed: {}", e);
+                            let id = self.next_toast_id();
+                            self.add_toast(Toast::error(id, e));
+                        }
+                    }
+                    // Deletions, restores, exclusions and tags change the lists and counts
+                    self.load_recent_documents();
+                    self.load_pinned_documents();
+                    self.load_resurfaced_documents();
+                    self.load_reminders();
+                    self.load_collections();
+                    self.load_sidebar();
+                    if self.current_view == View::Trash {
+                        self.load_trashed_documents();
+                    }
+                    if self.current_view == View::Browse {
+                        self.load_browse_documents();
+                    }
+                    if self.current_view == View::Timeline {
+                        self.load_timeline(true);
+                    }
+                    if let Some(doc_id) = self.selected_document.as_ref().map(|d| d.id) {
+                        self.load_document_tags(doc_id);
+                    }
                 }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
                 Err(std::sync::mpsc::TryRecvError::Disconnected) => {
-                    // Channel closed, clear receiver
-                    self.document_receiver = None;
+                    self.document_action_receiver = None;
                 }
             }
         }
     }
 
+    /// Check if a document action is in progress
+    pub fn is_document_action_pending(&self) -> bool {
+        self.document_action_receiver.is_some()
+    }
+
     /// Navigate back from document view
     pub fn navigate_back(&mut self) {
         match self.current_view {
@@ -966,6 +2296,16 @@ impl LocalMindApp {
                 self.current_view = View::Home;
                 self.search_results.clear();
                 self.all_results.clear();
+                self.selected_index = None;
+                self.checked_doc_ids.clear();
+            }
+            View::Trash
+            | View::Stats
+            | View::Browse
+            | View::Jobs
+            | View::Timeline
+            | View::Explore => {
+                self.current_view = View::Home;
             }
             View::Home => {
                 // Already at home, nothing to do
@@ -973,92 +2313,362 @@ impl LocalMindApp {
         }
     }
 
-    /// Check if a document is currently loading
-    pub fn is_document_loading(&self) -> bool {
-        self.document_receiver.is_some()
+    /// Open the settings modal and load the data its sections show
+    pub fn open_settings(&mut self) {
+        self.settings_open = true;
+        self.load_bookmark_folders();
+        self.load_exclusion_rules();
+        self.load_ingest_errors();
+        self.load_dead_link_settings();
+        self.load_notification_settings();
+        self.load_ingest_policies();
+        self.load_storage_status();
+        self.load_retention_rules();
+        self.load_maintenance_settings();
+        self.load_sync_settings();
+        self.load_snapshot_settings();
+        self.load_language_settings();
+        self.load_low_quality_pages();
+        self.load_refetch_documents();
+        self.load_remote_access_settings();
+        self.load_http_server_settings();
+        self.load_embedding_settings();
+        self.load_cached_settings();
     }
 
-    /// Check if exclusion rules save is in progress
-    pub fn is_saving_exclusion_rules(&self) -> bool {
-        self.save_exclusion_receiver.is_some()
+    /// Where the user is now, saved in eframe storage for the next launch
+    fn current_layout(&self) -> UiLayout {
+        UiLayout {
+            view: self.current_view.clone(),
+            document_id: self
+                .selected_document
+                .as_ref()
+                .filter(|_| self.current_view == View::DocumentDetail)
+                .map(|doc| doc.id),
+            search_query: self.search_query.clone(),
+            similarity_cutoff: self.similarity_cutoff,
+            unread_only: self.unread_only,
+            selected_profile: self.selected_profile.clone(),
+            search_collection: self.search_collection,
+            show_dead_documents: self.show_dead_documents,
+            expand_query: self.expand_query,
+            boost_linked_results: self.boost_linked_results,
+            group_by_site: self.group_by_site,
+            show_passages: self.show_passages,
+            settings_open: self.settings_open,
+            show_sidebar: self.show_sidebar,
+            browse_filter: self.browse_filter.clone(),
+            timeline_grouping: self.timeline_grouping,
+            timeline_filter: self.timeline_filter.clone(),
+        }
     }
 
-    /// Load bookmark folders for settings
-    pub fn load_bookmark_folders(&mut self) {
-        use crate::bookmark::BookmarkMonitor;
-
-        match BookmarkMonitor::new() {
-            Ok((monitor, _)) => {
-                let folders = monitor.get_bookmark_folders();
-                // Convert BookmarkFolder to BookmarkFolderView and build tree structure
-                let flat_views: Vec<BookmarkFolderView> = folders
-                    .into_iter()
-                    .map(|f| BookmarkFolderView {
-                        id: f.id,
-                        name: f.name,
-                        path: f.path,
-                        children: Vec::new(),
-                        bookmark_count: f.bookmark_count,
-                    })
-                    .collect();
+    /// Restore the view, search and filters saved at the last exit. Runs
+    /// once the backend is ready, since it may search or open a document.
+    fn restore_layout(&mut self) {
+        let Some(layout) = self.saved_layout.take() else {
+            return;
+        };
 
-                // Build tree structure from flat list based on paths
-                self.bookmark_folders = build_folder_tree(flat_views);
-                println!("Loaded {} bookmark folders", self.bookmark_folders.len());
-            }
-            Err(e) => {
-                eprintln!("Failed to load bookmark folders: {}", e);
-                let id = self.next_toast_id();
-                self.add_toast(Toast::error(
-                    id,
-                    format!("Failed to load bookmark folders: {}", e),
-                ));
+        self.search_query = layout.search_query;
+        self.similarity_cutoff = layout.similarity_cutoff.clamp(0.0, 1.0);
+        self.unread_only = layout.unread_only;
+        self.selected_profile = layout.selected_profile;
+        self.search_collection = layout.search_collection;
+        self.show_dead_documents = layout.show_dead_documents;
+        self.expand_query = layout.expand_query;
+        self.boost_linked_results = layout.boost_linked_results;
+        self.group_by_site = layout.group_by_site;
+        self.show_passages = layout.show_passages;
+        self.show_sidebar = layout.show_sidebar;
+        self.browse_filter = layout.browse_filter.clone();
+        self.timeline_grouping = layout.timeline_grouping;
+        self.timeline_filter = layout.timeline_filter;
+
+        match layout.view {
+            View::SearchResults => self.restore_search(),
+            View::DocumentDetail => {
+                if let Some(doc_id) = layout.document_id {
+                    self.load_document(doc_id);
+                }
             }
+            View::Trash => self.open_trash(),
+            View::Stats => self.open_stats(),
+            View::Browse => self.open_browse(layout.browse_filter),
+            View::Jobs => self.open_jobs(),
+            View::Timeline => self.open_timeline(),
+            View::Explore => self.open_explore(),
+            View::Home => {}
+        }
+        if layout.settings_open {
+            self.open_settings();
         }
     }
 
-    /// Load current exclusion rules from database
-    pub fn load_exclusion_rules(&mut self) {
-        if self.exclusion_rules_receiver.is_some() {
-            return; // Already loading
+    /// Open a document's URL in the default browser and mark it read
+    pub fn open_in_browser(&mut self, doc_id: i64, url: Option<String>) {
+        self.set_read(vec![doc_id], true);
+
+        if let Some(url) = url {
+            if let Err(e) = open::that(&url) {
+                eprintln!("Failed to open URL: {}", e);
+            }
+            return;
         }
 
+        // Search results carry no URL, so look it up first
         let rag = self.rag.clone();
-        let (tx, rx) = std::sync::mpsc::channel();
-        let runtime_handle = self.runtime.clone();
-
-        runtime_handle.spawn(async move {
+        self.runtime.spawn(async move {
             let rag_lock = rag.read().await;
-            let result = if let Some(ref rag) = *rag_lock {
-                let folders = rag.db.get_excluded_folders().await.unwrap_or_default();
-                let domains = rag.db.get_excluded_domains().await.unwrap_or_default();
-                (folders, domains)
-            } else {
-                (Vec::new(), Vec::new())
-            };
-            let _ = tx.send(result);
+            if let Some(ref rag) = *rag_lock {
+                match rag.db.get_document(doc_id).await {
+                    Ok(Some(doc)) => {
+                        if let Some(url) = doc.url {
+                            if let Err(e) = open::that(&url) {
+                                eprintln!("Failed to open URL: {}", e);
+                            }
+                        }
+                    }
+                    Ok(None) => eprintln!("Document not found: {}", doc_id),
+                    Err(e) => eprintln!("Failed to load document: {}", e),
+                }
+            }
         });
+    }
 
-        self.exclusion_rules_receiver = Some(rx);
+    /// Document IDs in the order the current view lists them, for keyboard
+    /// selection. The home view lists pinned documents before recent ones.
+    pub fn listed_doc_ids(&self) -> Vec<i64> {
+        match self.current_view {
+            View::SearchResults => presenter::visible_result_ids(
+                &self.search_results,
+                self.is_grouping_results(),
+                &self.expanded_result_groups,
+            ),
+            View::Home => self
+                .pinned_documents
+                .iter()
+                .map(|d| d.id)
+                .chain(
+                    self.recent_documents
+                        .iter()
+                        .filter(|d| !self.unread_only || self.is_unread(d.id))
+                        .map(|d| d.id),
+                )
+                .collect(),
+            View::Browse => self.browse_documents.iter().map(|d| d.id).collect(),
+            View::Timeline => self.timeline_documents.iter().map(|d| d.id).collect(),
+            View::DocumentDetail | View::Trash | View::Stats | View::Jobs | View::Explore => {
+                Vec::new()
+            }
+        }
     }
 
-    /// Check if exclusion rules have loaded
-    fn check_exclusion_rules_loaded(&mut self) {
-        if let Some(ref rx) = self.exclusion_rules_receiver {
-            match rx.try_recv() {
-                Ok((folders, domains)) => {
-                    println!(
-                        "Loaded exclusion rules: {} folders, {} domains",
-                        folders.len(),
-                        domains.len()
-                    );
-                    self.excluded_folders = folders.into_iter().collect();
-                    self.excluded_domains = domains;
-                    self.exclusion_rules_receiver = None;
-                }
-                Err(std::sync::mpsc::TryRecvError::Empty) => {
-                    // Still loading
-                }
+    /// Central keyboard shortcut handler, run once per frame before rendering.
+    ///
+    /// Single-key shortcuts are skipped while a text field has focus so typing
+    /// is unaffected; Escape, Ctrl+, and Ctrl+F always apply. The list is shown
+    /// in the help overlay (`widgets::shortcuts::SHORTCUTS`).
+    fn handle_shortcuts(&mut self, ctx: &egui::Context) {
+        use egui::{Key, Modifiers};
+
+        if ctx.input_mut(|i| i.consume_key(Modifiers::COMMAND, Key::Comma)) {
+            if self.settings_open {
+                self.settings_open = false;
+            } else {
+                self.open_settings();
+            }
+            return;
+        }
+
+        if self.current_view == View::DocumentDetail
+            && ctx.input_mut(|i| i.consume_key(Modifiers::COMMAND, Key::F))
+        {
+            self.open_document_find(ctx);
+            return;
+        }
+
+        // Escape closes overlays first, then navigates back
+        if ctx.input(|i| i.key_pressed(Key::Escape)) {
+            if self.show_shortcuts_help {
+                self.show_shortcuts_help = false;
+            } else if self.show_integrity_report {
+                self.show_integrity_report = false;
+            } else if self.settings_open {
+                self.settings_open = false;
+            } else if self.show_toast_history {
+                self.show_toast_history = false;
+            } else if !self.autocomplete.is_empty() {
+                self.close_autocomplete();
+            } else if self.title_edit.is_some() || self.tags_edit.is_some() {
+                self.title_edit = None;
+                self.tags_edit = None;
+            } else if self.current_view == View::DocumentDetail && self.document_find.open {
+                self.document_find.open = false;
+            } else {
+                self.navigate_back();
+            }
+            return;
+        }
+
+        if ctx.wants_keyboard_input() || self.settings_open {
+            return;
+        }
+
+        let pressed = |key: Key| ctx.input(|i| i.key_pressed(key));
+        let shift = ctx.input(|i| i.modifiers.shift);
+
+        if pressed(Key::Questionmark) || (shift && pressed(Key::Slash)) {
+            self.show_shortcuts_help = !self.show_shortcuts_help;
+            return;
+        }
+
+        if pressed(Key::Slash) {
+            // Drop the "/" so it is not typed into the newly focused search box
+            ctx.input_mut(|i| {
+                i.events
+                    .retain(|e| !matches!(e, egui::Event::Text(t) if t == "/"))
+            });
+            ctx.memory_mut(|m| m.request_focus(egui::Id::new(SEARCH_INPUT_ID)));
+            return;
+        }
+
+        if pressed(Key::Backspace) {
+            self.navigate_back();
+            return;
+        }
+
+        if self.current_view == View::DocumentDetail {
+            if pressed(Key::O) {
+                if let Some(doc) = &self.selected_document {
+                    let (doc_id, url) = (doc.id, doc.url.clone());
+                    self.open_in_browser(doc_id, url);
+                }
+            }
+            return;
+        }
+
+        let ids = self.listed_doc_ids();
+        if ids.is_empty() {
+            return;
+        }
+
+        if pressed(Key::J) || pressed(Key::ArrowDown) {
+            self.selected_index = Some(
+                self.selected_index
+                    .map_or(0, |i| (i + 1).min(ids.len() - 1)),
+            );
+            self.scroll_to_selection = true;
+        } else if pressed(Key::K) || pressed(Key::ArrowUp) {
+            self.selected_index = Some(self.selected_index.map_or(0, |i| i.saturating_sub(1)));
+            self.scroll_to_selection = true;
+        } else if let Some(&doc_id) = self.selected_index.and_then(|i| ids.get(i)) {
+            if pressed(Key::Enter) {
+                let mut passage = None;
+                if self.current_view == View::SearchResults {
+                    self.query_logger.finalize("clicked", Some(doc_id));
+                    // Ungrouped, the selection indexes the results directly;
+                    // grouped results list each document once
+                    passage = if self.is_grouping_results() {
+                        self.search_results.iter().find(|r| r.doc_id == doc_id)
+                    } else {
+                        self.selected_index.and_then(|i| self.search_results.get(i))
+                    }
+                    .and_then(|r| r.passage);
+                }
+                self.load_document_at(doc_id, passage);
+            } else if pressed(Key::O) {
+                let url = self
+                    .pinned_documents
+                    .iter()
+                    .chain(&self.recent_documents)
+                    .find(|d| d.id == doc_id)
+                    .and_then(|d| d.url.clone());
+                self.open_in_browser(doc_id, url);
+            }
+        }
+    }
+
+    /// Check if exclusion rules save is in progress
+    pub fn is_saving_exclusion_rules(&self) -> bool {
+        self.save_exclusion_receiver.is_some()
+    }
+
+    /// Load bookmark folders for settings
+    pub fn load_bookmark_folders(&mut self) {
+        use crate::bookmark::BookmarkMonitor;
+
+        match BookmarkMonitor::for_settings() {
+            Ok(monitors) => {
+                let folders = monitors.iter().flat_map(|m| m.get_bookmark_folders());
+                // Convert BookmarkFolder to BookmarkFolderView and build tree structure
+                let flat_views: Vec<BookmarkFolderView> = folders
+                    .map(|f| BookmarkFolderView {
+                        id: f.id,
+                        name: f.name,
+                        path: f.path,
+                        children: Vec::new(),
+                        bookmark_count: f.bookmark_count,
+                    })
+                    .collect();
+
+                // Build tree structure from flat list based on paths
+                self.bookmark_folders = build_folder_tree(flat_views);
+                println!("Loaded {} bookmark folders", self.bookmark_folders.len());
+            }
+            Err(e) => {
+                eprintln!("Failed to load bookmark folders: {}", e);
+                let id = self.next_toast_id();
+                self.add_toast(Toast::error(
+                    id,
+                    tr_args("folders.load_failed", &[("error", &e)]),
+                ));
+            }
+        }
+    }
+
+    /// Load current exclusion rules from database
+    pub fn load_exclusion_rules(&mut self) {
+        if self.exclusion_rules_receiver.is_some() {
+            return; // Already loading
+        }
+
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let runtime_handle = self.runtime.clone();
+
+        runtime_handle.spawn(async move {
+            let rag_lock = rag.read().await;
+            let result = if let Some(ref rag) = *rag_lock {
+                let folders = rag.db.get_excluded_folders().await.unwrap_or_default();
+                let domains = rag.db.get_excluded_domains().await.unwrap_or_default();
+                (folders, domains)
+            } else {
+                (Vec::new(), Vec::new())
+            };
+            let _ = tx.send(result);
+        });
+
+        self.exclusion_rules_receiver = Some(rx);
+    }
+
+    /// Check if exclusion rules have loaded
+    fn check_exclusion_rules_loaded(&mut self) {
+        if let Some(ref rx) = self.exclusion_rules_receiver {
+            match rx.try_recv() {
+                Ok((folders, domains)) => {
+                    println!(
+                        "Loaded exclusion rules: {} folders, {} domains",
+                        folders.len(),
+                        domains.len()
+                    );
+                    self.excluded_folders = folders.into_iter().collect();
+                    self.excluded_domains = domains;
+                    self.exclusion_rules_receiver = None;
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {
+                    // Still loading
+                }
                 Err(std::sync::mpsc::TryRecvError::Disconnected) => {
                     // Channel closed, clear receiver
                     self.exclusion_rules_receiver = None;
@@ -1070,7 +2680,7 @@ impl LocalMindApp {
     /// Save exclusion rules to database and remove matching bookmarks
     pub fn save_exclusion_rules(&mut self) -> crate::Result<()> {
         if self.save_exclusion_receiver.is_some() {
-            return Err("Save already in progress".into());
+            return Err(tr("common.save_in_progress").into());
         }
 
         let rag = self.rag.clone();
@@ -1092,11 +2702,11 @@ impl LocalMindApp {
                     return;
                 }
 
-                // Remove matching bookmarks
-                let mut removed_count = 0;
+                // Move matching bookmarks to the trash
+                let mut trashed = Vec::new();
                 for domain in &domains {
                     match rag.db.delete_bookmarks_by_url_pattern(domain).await {
-                        Ok(count) => removed_count += count,
+                        Ok(ids) => trashed.extend(ids),
                         Err(e) => {
                             let _ = tx.send(Err(e.to_string()));
                             return;
@@ -1106,7 +2716,7 @@ impl LocalMindApp {
 
                 for folder_id in &folders {
                     match rag.db.delete_bookmarks_by_folder(folder_id).await {
-                        Ok(count) => removed_count += count,
+                        Ok(ids) => trashed.extend(ids),
                         Err(e) => {
                             let _ = tx.send(Err(e.to_string()));
                             return;
@@ -1114,9 +2724,13 @@ impl LocalMindApp {
                     }
                 }
 
-                Ok(removed_count)
+                for doc_id in &trashed {
+                    rag.remove_document_vectors(*doc_id).await;
+                }
+
+                Ok(trashed)
             } else {
-                Err("RAG not initialized".to_string())
+                Err(tr("common.not_ready").to_string())
             };
             let _ = tx.send(result);
         });
@@ -1125,29 +2739,236 @@ impl LocalMindApp {
         Ok(())
     }
 
-    /// Check if save exclusion rules has completed
-    fn check_save_exclusion_rules(&mut self) -> Option<Result<usize, String>> {
-        if let Some(ref rx) = self.save_exclusion_receiver {
-            match rx.try_recv() {
-                Ok(result) => {
-                    self.save_exclusion_receiver = None;
-                    Some(result)
+    /// Check if saving the exclusion rules has finished and report the
+    /// result; a successful save closes Settings
+    fn check_save_exclusion_rules(&mut self) {
+        let Some(ref rx) = self.save_exclusion_receiver else {
+            return;
+        };
+        let result = match rx.try_recv() {
+            Ok(result) => result,
+            Err(std::sync::mpsc::TryRecvError::Empty) => return,
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                self.save_exclusion_receiver = None;
+                return;
+            }
+        };
+        self.save_exclusion_receiver = None;
+
+        match result {
+            Ok(trashed) => {
+                let removed_count = trashed.len();
+                if removed_count > 0 {
+                    println!(
+                        "Moved {} bookmarks matching exclusion rules to the trash",
+                        removed_count
+                    );
+                    self.load_recent_documents();
+                    self.load_pinned_documents();
                 }
-                Err(std::sync::mpsc::TryRecvError::Empty) => {
-                    // Still saving
-                    None
+                // Show success toast, with "Undo" if bookmarks were removed
+                let message = presenter::exclusion_rules_saved_message(removed_count);
+                self.add_undo_toast(message, trashed);
+                // Close settings modal
+                self.settings_open = false;
+            }
+            Err(e) => {
+                let id = self.next_toast_id();
+                self.add_toast(Toast::error(
+                    id,
+                    tr_args("common.save_failed", &[("error", &e)]),
+                ));
+            }
+        }
+    }
+
+    /// Raise a native notification for `event` if the window is in the
+    /// background and the event type is not muted
+    fn notify_native(&self, event: NotificationEvent, body: &str) {
+        if !self.window_in_background || self.muted_notifications.contains(&event) {
+            return;
+        }
+        let title = tr_args(
+            "notifications.title",
+            &[("event", &widgets::notifications::event_label(event))],
+        );
+        let body = body.to_string();
+        // Some platforms block while the notification is delivered
+        self.runtime.spawn_blocking(move || {
+            if let Err(e) = crate::notifications::show_native(&title, &body) {
+                eprintln!("Failed to show notification: {}", e);
+            }
+        });
+    }
+
+    /// Check for embedding server status changes from the supervisor
+    fn check_embedding_server_status(&mut self) {
+        let mut statuses = Vec::new();
+        if let Some(ref rx) = self.embedding_server_status_receiver {
+            while let Ok(status) = rx.try_recv() {
+                statuses.push(status);
+            }
+        }
+
+        for status in statuses {
+            if let EmbeddingServerStatus::Failed(ref e) = status {
+                let id = self.next_toast_id();
+                self.add_toast(Toast::error(
+                    id,
+                    tr_args("embedding.server_failed", &[("error", e)]),
+                ));
+            }
+            self.embedding_server_status = Some(status);
+        }
+    }
+
+    /// Check for switches between semantic and keyword-only search
+    fn check_semantic_status(&mut self) {
+        let mut updates = Vec::new();
+        if let Some(ref rx) = self.semantic_status_receiver {
+            while let Ok(available) = rx.try_recv() {
+                updates.push(available);
+            }
+        }
+
+        for available in updates {
+            if available && !self.semantic_search_available {
+                let id = self.next_toast_id();
+                self.add_toast(Toast::success(id, tr("embedding.server_back")));
+            }
+            self.semantic_search_available = available;
+        }
+    }
+
+    /// Check for bookmark watcher failures
+    fn check_bookmark_watch_errors(&mut self) {
+        let mut errors = Vec::new();
+        if let Some(ref rx) = self.bookmark_watch_error_receiver {
+            while let Ok(error) = rx.try_recv() {
+                errors.push(error);
+            }
+        }
+
+        for error in errors {
+            let id = self.next_toast_id();
+            self.add_toast(Toast::error(
+                id,
+                tr_args("import.watch_failed", &[("error", &error)]),
+            ));
+            self.bookmark_watch_error = Some(error);
+        }
+    }
+
+    /// Check for health events from the background monitor
+    fn check_notification_events(&mut self) {
+        let mut events = Vec::new();
+        if let Some(ref rx) = self.notification_event_receiver {
+            while let Ok(event) = rx.try_recv() {
+                events.push(event);
+            }
+        }
+
+        for event in events {
+            let message = match event {
+                NotificationEvent::EmbeddingServerDown => tr("notifications.server_down"),
+                NotificationEvent::DiskSpaceLow => tr("notifications.disk_low"),
+                NotificationEvent::IngestionComplete => continue,
+            };
+            let id = self.next_toast_id();
+            self.add_toast(Toast::error(id, message));
+            self.notify_native(event, message);
+        }
+    }
+
+    /// Load the UI language and appearance chosen in Settings
+    pub(crate) fn load_ui_preferences(&mut self) {
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            let mut language = None;
+            let mut appearance = Appearance::default();
+            let mut card_layout = false;
+            let mut refresh_restored_search = true;
+            if let Some(ref rag) = *rag_lock {
+                card_layout = rag.db.get_card_layout().await.unwrap_or(false);
+                refresh_restored_search =
+                    rag.db.get_refresh_restored_search().await.unwrap_or(true);
+                language = rag.db.get_ui_language().await.ok().flatten();
+                if let Ok(Some(scale)) = rag.db.get_ui_scale().await {
+                    appearance.ui_scale = accessibility::clamp_scale(scale);
+                }
+                appearance.high_contrast = rag.db.get_high_contrast().await.unwrap_or(false);
+            }
+            let _ = tx.send((
+                language.as_deref().and_then(Language::from_code),
+                appearance,
+                card_layout,
+                refresh_restored_search,
+            ));
+        });
+
+        self.ui_preferences_receiver = Some(rx);
+    }
+
+    /// Check if the stored UI language and appearance have loaded
+    fn check_ui_preferences_loaded(&mut self) {
+        if let Some(ref rx) = self.ui_preferences_receiver {
+            match rx.try_recv() {
+                Ok((language, appearance, card_layout, refresh_restored_search)) => {
+                    if let Some(language) = language {
+                        i18n::set_language(language);
+                    }
+                    self.appearance = appearance;
+                    self.refresh_restored_search = refresh_restored_search;
+                    if card_layout != self.card_layout {
+                        self.card_layout = card_layout;
+                        self.load_document_images();
+                    }
+                    self.ui_preferences_receiver = None;
                 }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
                 Err(std::sync::mpsc::TryRecvError::Disconnected) => {
-                    // Channel closed, clear receiver
-                    self.save_exclusion_receiver = None;
-                    None
+                    self.ui_preferences_receiver = None;
                 }
             }
-        } else {
-            None
         }
     }
 
+    /// Switch the UI language and remember it
+    pub fn set_ui_language(&mut self, language: Language) {
+        i18n::set_language(language);
+
+        let rag = self.rag.clone();
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            if let Some(ref rag) = *rag_lock {
+                if let Err(e) = rag.db.set_ui_language(language.code()).await {
+                    eprintln!("Failed to save UI language: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Change the text size or contrast and remember it
+    pub fn set_appearance(&mut self, appearance: Appearance) {
+        self.appearance = appearance;
+
+        let rag = self.rag.clone();
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            if let Some(ref rag) = *rag_lock {
+                if let Err(e) = rag.db.set_ui_scale(appearance.ui_scale).await {
+                    eprintln!("Failed to save UI scale: {}", e);
+                }
+                if let Err(e) = rag.db.set_high_contrast(appearance.high_contrast).await {
+                    eprintln!("Failed to save high contrast setting: {}", e);
+                }
+            }
+        });
+    }
+
     /// Set the bookmark progress receiver
     /// Set the bookmark progress receiver
     ///
@@ -1160,7 +2981,6 @@ impl LocalMindApp {
         self.bookmark_progress_receiver = Some(receiver);
     }
 
-    /// Check for bookmark progress events and update toasts
     fn check_bookmark_progress(&mut self) {
         // Collect all pending messages first to avoid borrow checker issues
         let mut pending_progress = Vec::new();
@@ -1172,69 +2992,155 @@ impl LocalMindApp {
 
         // Process collected messages
         for progress in pending_progress {
-            if progress.completed {
-                // Remove progress toast if it exists
-                if let Some(progress_id) = self.bookmark_progress_toast_id.take() {
-                    self.toasts.retain(|t| t.id != progress_id);
+            if self
+                .bookmark_progress_toasts
+                .apply(&mut self.toasts, &progress)
+            {
+                self.notify_native(
+                    NotificationEvent::IngestionComplete,
+                    &tr_args("notifications.ingested", &[("count", &progress.current)]),
+                );
+                if progress.failed > 0 {
+                    self.load_ingest_errors();
                 }
+            }
+        }
+    }
 
-                // Add completion toast
-                let id = self.next_toast_id();
-                self.add_toast(Toast::success(
-                    id,
-                    format!("Completed! {} bookmarks ingested", progress.current),
-                ));
-            } else {
-                // Update or create progress toast
-                let percentage = if progress.total > 0 {
-                    (progress.current as f32 / progress.total as f32 * 100.0) as usize
-                } else {
-                    0
-                };
+    /// Load the library's tracking parameters, image caption settings and
+    /// domain preferences
+    pub(crate) fn load_cached_settings(&mut self) {
+        if self.cached_settings_receiver.is_some() {
+            return; // Already loading
+        }
 
-                let message = format!(
-                    "Processing bookmarks... {}/{} ({}%)",
-                    progress.current, progress.total, percentage
-                );
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
 
-                // Remove old progress toast if it exists
-                if let Some(progress_id) = self.bookmark_progress_toast_id.take() {
-                    self.toasts.retain(|t| t.id != progress_id);
-                }
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            if let Some(ref rag) = *rag_lock {
+                let _ = tx.send(rag.db.cached_settings());
+            }
+        });
 
-                // Create new progress toast (persistent until completion)
-                let id = self.next_toast_id();
-                self.bookmark_progress_toast_id = Some(id);
-                self.add_toast(Toast::new(
-                    id,
-                    message,
-                    ToastType::Info,
-                    std::time::Duration::ZERO, // Persistent until replaced
-                ));
+        self.cached_settings_receiver = Some(rx);
+    }
+
+    /// Check if the library's tracking, image caption and domain settings
+    /// have loaded
+    fn check_cached_settings_loaded(&mut self) {
+        if let Some(ref rx) = self.cached_settings_receiver {
+            match rx.try_recv() {
+                Ok(settings) => {
+                    self.cached_settings_receiver = None;
+                    self.tracking_params_edit = settings.tracking_params.join(", ");
+                    self.tracking_params = settings.tracking_params;
+                    self.image_caption_settings = settings.image_captions;
+                    self.domain_preferences = settings.domain_preferences;
+                    self.domain_boost = settings.domain_boost;
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.cached_settings_receiver = None;
+                }
             }
         }
     }
-}
 
-/// Create a content snippet, truncating at word boundaries.
-/// Strips YAML frontmatter so `---\n{}\n---` never leaks into the UI.
-fn create_snippet(content: &str, max_len: usize) -> String {
-    let content = strip_frontmatter_snippet(content.trim());
-    if content.len() <= max_len {
-        return content.to_string();
+    /// Images for a document card, once loaded: its site icon, and its
+    /// thumbnail when the thumbnail layout is on
+    pub fn document_images(&self, doc_id: i64, url: Option<&str>) -> DocumentImages {
+        let favicon = url.and_then(crate::favicon::site_host).and_then(|host| {
+            let icon = self.favicons.get(&host)?.clone();
+            Some((host, icon))
+        });
+        let thumbnail = if self.card_layout {
+            self.thumbnails.get(&doc_id).cloned()
+        } else {
+            None
+        };
+        let language = self.document_languages.get(&doc_id).cloned();
+        DocumentImages {
+            favicon,
+            thumbnail,
+            language,
+        }
+    }
+
+    /// Load the site icons, and thumbnails in the thumbnail layout, of the
+    /// listed documents and results
+    pub(crate) fn load_document_images(&mut self) {
+        let documents = self
+            .recent_documents
+            .iter()
+            .chain(&self.pinned_documents)
+            .map(|doc| (doc.id, doc.url.clone()));
+        let results = self
+            .all_results
+            .iter()
+            .map(|result| (result.doc_id, result.url.clone()));
+        let (doc_ids, urls): (Vec<i64>, Vec<Option<String>>) = documents.chain(results).unzip();
+
+        let urls: Vec<String> = urls.into_iter().flatten().collect();
+        self.load_favicons(&urls);
+        self.load_languages(&doc_ids);
+        if self.card_layout {
+            self.load_thumbnails(&doc_ids);
+        }
     }
 
-    // Find a good break point (whitespace)
-    let truncated = &content[..max_len];
-    if let Some(last_space) = truncated.rfind(char::is_whitespace) {
-        format!("{}...", &content[..last_space])
-    } else {
-        format!("{}...", truncated)
+    /// Load the detected languages of documents not asked for yet
+    fn load_languages(&mut self, doc_ids: &[i64]) {
+        let doc_ids: Vec<i64> = doc_ids
+            .iter()
+            .copied()
+            .filter(|id| self.languages_requested.insert(*id))
+            .collect();
+        if doc_ids.is_empty() {
+            return;
+        }
+
+        let rag = self.rag.clone();
+        let tx = self.language_tx.clone();
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            let languages = match *rag_lock {
+                Some(ref rag) => rag
+                    .db
+                    .get_document_languages(&doc_ids)
+                    .await
+                    .unwrap_or_else(|e| {
+                        eprintln!("Failed to load document languages: {}", e);
+                        Default::default()
+                    }),
+                None => Default::default(),
+            };
+            let _ = tx.send((doc_ids, languages));
+        });
+        self.language_loads_pending += 1;
+    }
+
+    /// Check if document languages have loaded
+    fn check_languages_loaded(&mut self) {
+        while let Ok((doc_ids, mut languages)) = self.language_rx.try_recv() {
+            self.language_loads_pending = self.language_loads_pending.saturating_sub(1);
+            for doc_id in doc_ids {
+                match languages.remove(&doc_id) {
+                    Some(language) => {
+                        self.document_languages.insert(doc_id, language);
+                    }
+                    None => {
+                        self.languages_requested.remove(&doc_id);
+                    }
+                }
+            }
+        }
     }
 }
 
 /// Strip YAML frontmatter (`---` … `---`) from the start of a string.
-fn strip_frontmatter_snippet(s: &str) -> &str {
+pub(crate) fn strip_frontmatter_snippet(s: &str) -> &str {
     if !s.starts_with("---") {
         return s;
     }
@@ -1248,22 +3154,116 @@ fn strip_frontmatter_snippet(s: &str) -> &str {
 
 impl Drop for LocalMindApp {
     fn drop(&mut self) {
-        if let Some(mut child) = self.embedding_server_child.take() {
-            let _ = child.kill();
-            log::info!("Embedding server process killed on app exit");
+        if let Some(supervisor) = self.embedding_server.take() {
+            supervisor.shutdown();
+            log::info!("Embedding server process stopped on app exit");
         }
     }
 }
 
 impl eframe::App for LocalMindApp {
+    /// Save the layout for the next launch; called on exit and periodically.
+    /// Until the saved layout has been restored it is kept as it was.
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        let layout = self
+            .saved_layout
+            .clone()
+            .unwrap_or_else(|| self.current_layout());
+        eframe::set_value(storage, UI_LAYOUT_KEY, &layout);
+    }
+
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        // Events raise native notifications only while the window is out of view
+        self.window_in_background = ctx.input(|i| {
+            let viewport = i.viewport();
+            viewport.minimized == Some(true) || viewport.focused == Some(false)
+        });
+
         // Check for async updates
         self.check_init_status();
         self.check_recent_documents();
+        self.check_pinned_documents();
+        self.check_resurfaced_documents();
+        self.check_reminders_loaded();
+        self.check_reminder_set();
+        self.check_due_reminders();
+        self.check_home_summary();
+        self.check_unread_loaded();
         self.check_search_results();
+        self.check_restored_search();
+        self.check_search_fallback();
+        self.check_autocomplete();
         self.check_document_loaded();
+        self.check_document_answer();
+        self.check_collections_loaded();
+        self.check_collection_actions();
+        self.check_document_collections_loaded();
+        self.check_document_actions();
+        self.check_markdown_export(ctx);
+        self.check_trashed_documents_loaded();
+        self.check_sidebar_loaded();
+        self.check_browse_loaded();
+        self.check_timeline_loaded();
+        self.check_projection_loaded();
+        self.check_projection_computed();
+        self.check_jobs_loaded();
+        self.check_stats_loaded();
+        self.check_document_tags_loaded();
+        self.check_document_annotations_loaded();
+        self.check_document_versions_loaded();
+        self.check_version_restored();
+        self.check_highlight_saved();
+        self.check_tag_suggestions();
+        self.check_document_links_loaded();
+        self.check_document_passages_loaded();
+        self.check_document_metadata_loaded();
+        self.check_ingestion_preview();
         self.check_bookmark_progress();
+        self.check_bookmark_import();
+        self.check_pasted_url();
+        self.check_note_saved();
+        self.check_notification_events();
+        self.check_bookmark_watch_errors();
+        self.check_embedding_server_status();
+        self.check_semantic_status();
+        self.check_notification_settings_loaded();
+        self.check_ingest_policies_loaded();
+        self.check_ui_preferences_loaded();
+        self.check_embedding_settings_loaded();
+        self.check_cached_settings_loaded();
+        self.check_embedding_models_loaded();
+        self.check_embedding_settings_applied();
+        self.check_embedding_migration();
         self.check_exclusion_rules_loaded();
+        self.check_ingest_errors_loaded();
+        self.check_retry_results();
+        self.check_link_check_progress();
+        self.check_retention_runs();
+        self.check_maintenance_progress();
+        self.check_maintenance_settings_loaded();
+        self.check_integrity_reports();
+        self.check_integrity_repair();
+        self.check_sync_settings_loaded();
+        self.check_remote_access_settings_loaded();
+        self.check_http_server_settings_loaded();
+        self.check_library_switch();
+        self.check_favicons_loaded();
+        self.check_thumbnails_loaded();
+        self.check_sync_reports();
+        self.check_sync_run();
+        self.check_languages_loaded();
+        self.check_language_settings_loaded();
+        self.check_low_quality_pages_loaded();
+        self.check_refetch_documents_loaded();
+        self.check_page_refetch();
+        self.check_snapshot_settings_loaded();
+        self.check_snapshot_reports();
+        self.check_snapshot_run();
+        self.check_retention_rules_loaded();
+        self.check_retention_preview_loaded();
+        self.check_dead_link_settings_loaded();
+        self.check_storage_status_loaded();
+        self.check_prune_suggestions_loaded();
         // Folder-watch polling (T024, T037, T048)
         self.check_folder_watch_events();
         self.check_add_folder_requests();
@@ -1272,65 +3272,73 @@ impl eframe::App for LocalMindApp {
         self.check_watched_folders_loaded();
         self.cleanup_toasts();
 
-        // Handle Escape key for back navigation or closing settings
-        if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
-            if self.settings_open {
-                self.settings_open = false;
-            } else {
-                self.navigate_back();
-            }
+        // Text size and contrast from Settings
+        if self.applied_appearance != Some(self.appearance) {
+            accessibility::apply(ctx, &self.appearance);
+            self.applied_appearance = Some(self.appearance);
         }
 
-        // Check for save completion
-        if let Some(result) = self.check_save_exclusion_rules() {
-            match result {
-                Ok(removed_count) => {
-                    if removed_count > 0 {
-                        println!(
-                            "Removed {} bookmarks matching exclusion rules",
-                            removed_count
-                        );
-                    }
-                    // Show success toast
-                    let id = self.next_toast_id();
-                    self.add_toast(Toast::success(
-                        id,
-                        format!(
-                            "Exclusion rules saved{}",
-                            if removed_count > 0 {
-                                format!(" ({} bookmarks removed)", removed_count)
-                            } else {
-                                String::new()
-                            }
-                        ),
-                    ));
-                    // Close settings modal
-                    self.settings_open = false;
-                }
-                Err(e) => {
-                    let id = self.next_toast_id();
-                    self.add_toast(Toast::error(id, format!("Failed to save: {}", e)));
-                }
-            }
-        }
+        // Keyboard shortcuts (Escape, "/", j/k, Enter, o, Ctrl+, and "?")
+        self.handle_shortcuts(ctx);
+
+        self.check_save_exclusion_rules();
 
         // Top panel with search bar and status
         egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
             ui.add_space(8.0);
             ui.horizontal(|ui| {
+                // Sidebar toggle
+                if ui
+                    .selectable_label(self.show_sidebar, icons::MENU_LINE)
+                    .labeled_toggle(self.show_sidebar, tr("sidebar.toggle"))
+                    .clicked()
+                {
+                    self.set_show_sidebar(!self.show_sidebar);
+                }
+
                 // App title
                 ui.heading("LocalMind");
+                widgets::library::render_library_menu(ui, self);
+                let ready = self.init_status == InitStatus::Ready;
+                ui.add_enabled_ui(ready, |ui| {
+                    widgets::quick_actions::render_add_menu(ui, self);
+                });
 
                 ui.add_space(20.0);
 
                 // Search input (disabled until ready)
                 let search_enabled = matches!(self.init_status, InitStatus::Ready);
                 let mut should_search = false;
+                let mut query_changed = false;
+                let mut search_rect = None;
+                let autocomplete_len = self.autocomplete.len();
                 ui.add_enabled_ui(search_enabled, |ui| {
+                    // Arrow keys move through the autocomplete dropdown
+                    // instead of the cursor
+                    let search_id = egui::Id::new(SEARCH_INPUT_ID);
+                    if autocomplete_len > 0 && ui.memory(|m| m.has_focus(search_id)) {
+                        let (down, up) = ui.input_mut(|i| {
+                            (
+                                i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+                                i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+                            )
+                        });
+                        if down {
+                            self.autocomplete_index = Some(
+                                self.autocomplete_index
+                                    .map_or(0, |i| (i + 1).min(autocomplete_len - 1)),
+                            );
+                        } else if up {
+                            self.autocomplete_index =
+                                self.autocomplete_index.and_then(|i| i.checked_sub(1));
+                        }
+                    }
+
                     let response = ui.add_sized(
                         [400.0, 32.0],
                         egui::TextEdit::singleline(&mut self.search_query)
-                            .hint_text("Search documents...")
+                            .id(search_id)
+                            .hint_text(tr("top.search_hint"))
                             .margin(egui::Margin {
                                 left: 8.0,
                                 right: 8.0,
@@ -1338,6 +3346,11 @@ impl eframe::App for LocalMindApp {
                                 bottom: 5.0,
                             }),
                     );
+                    let response = response
+                        .labeled_field(tr("top.search"))
+                        .on_hover_text(tr("top.operators"));
+                    query_changed = response.changed();
+                    search_rect = Some(response.rect);
 
                     // Handle Enter key for search
                     if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
@@ -1345,26 +3358,49 @@ impl eframe::App for LocalMindApp {
                     }
                 });
 
-                // Trigger search outside the closure to avoid borrow issues
+                if query_changed {
+                    self.load_autocomplete();
+                }
+
+                // Enter on a highlighted completion picks it instead of searching
                 if should_search {
-                    self.trigger_search();
+                    match self.autocomplete_index {
+                        Some(index) => self.apply_autocomplete(index),
+                        None => self.trigger_search(),
+                    }
+                } else if let Some(rect) = search_rect.filter(|_| !self.autocomplete.is_empty()) {
+                    let (dropdown, chosen) = widgets::autocomplete::render_autocomplete(
+                        ctx,
+                        rect,
+                        &self.autocomplete,
+                        self.autocomplete_index,
+                    );
+                    if let Some(index) = chosen {
+                        self.apply_autocomplete(index);
+                    } else if ctx.input(|i| i.pointer.any_click())
+                        && !dropdown.contains_pointer()
+                        && !ctx.memory(|m| m.has_focus(egui::Id::new(SEARCH_INPUT_ID)))
+                    {
+                        // Clicked somewhere else
+                        self.close_autocomplete();
+                    }
                 }
 
                 // Profile filter dropdown (only shown when multiple profiles exist)
                 if self.available_profiles.len() > 1 {
                     ui.add_space(10.0);
-                    ui.label("Profile:");
+                    let profile_label = ui.label(tr("top.profile"));
                     let selected_label = self
                         .selected_profile
                         .as_deref()
-                        .unwrap_or("All")
+                        .unwrap_or(tr("top.all"))
                         .to_string();
-                    let profile_changed = egui::ComboBox::from_id_salt("profile_selector")
+                    let profile_combo = egui::ComboBox::from_id_salt("profile_selector")
                         .selected_text(&selected_label)
                         .show_ui(ui, |ui| {
                             let mut changed = false;
                             if ui
-                                .selectable_label(self.selected_profile.is_none(), "All")
+                                .selectable_label(self.selected_profile.is_none(), tr("top.all"))
                                 .clicked()
                                 && self.selected_profile.is_some()
                             {
@@ -1382,44 +3418,202 @@ impl eframe::App for LocalMindApp {
                                 }
                             }
                             changed
-                        })
-                        .inner
-                        .unwrap_or(false);
+                        });
+                    profile_combo.response.labelled_by(profile_label.id);
+                    let profile_changed = profile_combo.inner.unwrap_or(false);
 
                     if profile_changed {
                         // Reload recent docs with new filter
                         self.recent_docs_receiver = None;
                         self.load_recent_documents();
+                        self.load_pinned_documents();
+                        self.load_resurfaced_documents();
+                        if self.current_view == View::Browse {
+                            self.load_browse_documents();
+                        }
+                        if self.current_view == View::Timeline {
+                            self.load_timeline(true);
+                        }
                         // Re-apply filter on existing search results
                         self.apply_search_filters();
                     }
                 }
 
+                // Collection scope dropdown (only shown once collections exist)
+                if !self.collections.is_empty() {
+                    ui.add_space(10.0);
+                    let collection_label = ui.label(tr("top.collection"));
+                    let selected_label = self
+                        .search_collection
+                        .and_then(|id| self.collections.iter().find(|c| c.id == id))
+                        .map_or(tr("top.all"), |c| c.name.as_str())
+                        .to_string();
+                    let previous = self.search_collection;
+                    egui::ComboBox::from_id_salt("collection_selector")
+                        .selected_text(&selected_label)
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut self.search_collection, None, tr("top.all"));
+                            for c in &self.collections {
+                                ui.selectable_value(
+                                    &mut self.search_collection,
+                                    Some(c.id),
+                                    format!("{} ({})", c.name, c.document_count),
+                                );
+                            }
+                        })
+                        .response
+                        .labelled_by(collection_label.id);
+                    if self.search_collection != previous {
+                        self.trigger_search();
+                    }
+                }
+
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                     // Settings button
-                    if ui.button("⚙").clicked() {
-                        self.settings_open = !self.settings_open;
+                    if ui.button("⚙").labeled(tr("top.settings")).clicked() {
                         if self.settings_open {
-                            // Load bookmark folders and exclusion rules when opening
-                            self.load_bookmark_folders();
-                            self.load_exclusion_rules();
+                            self.settings_open = false;
+                        } else {
+                            self.open_settings();
+                        }
+                    }
+
+                    // Timeline view (in the sidebar when it is shown)
+                    if !self.show_sidebar
+                        && ui
+                            .selectable_label(self.current_view == View::Timeline, icons::TIME_LINE)
+                            .labeled_toggle(
+                                self.current_view == View::Timeline,
+                                tr("timeline.title"),
+                            )
+                            .clicked()
+                    {
+                        if self.current_view == View::Timeline {
+                            self.navigate_back();
+                        } else {
+                            self.open_timeline();
                         }
                     }
 
+                    // Explore map (in the sidebar when it is shown)
+                    if !self.show_sidebar
+                        && ui
+                            .selectable_label(
+                                self.current_view == View::Explore,
+                                icons::BUBBLE_CHART_LINE,
+                            )
+                            .labeled_toggle(self.current_view == View::Explore, tr("explore.title"))
+                            .clicked()
+                    {
+                        if self.current_view == View::Explore {
+                            self.navigate_back();
+                        } else {
+                            self.open_explore();
+                        }
+                    }
+
+                    // Stats view (in the sidebar when it is shown)
+                    if !self.show_sidebar
+                        && ui
+                            .selectable_label(
+                                self.current_view == View::Stats,
+                                icons::BAR_CHART_2_LINE,
+                            )
+                            .labeled_toggle(self.current_view == View::Stats, tr("stats.title"))
+                            .clicked()
+                    {
+                        if self.current_view == View::Stats {
+                            self.navigate_back();
+                        } else {
+                            self.open_stats();
+                        }
+                    }
+
+                    // Trash view (in the sidebar when it is shown)
+                    if !self.show_sidebar
+                        && ui
+                            .selectable_label(
+                                self.current_view == View::Trash,
+                                icons::DELETE_BIN_LINE,
+                            )
+                            .labeled_toggle(self.current_view == View::Trash, tr("trash.title"))
+                            .clicked()
+                    {
+                        if self.current_view == View::Trash {
+                            self.navigate_back();
+                        } else {
+                            self.open_trash();
+                        }
+                    }
+
+                    // Notification history drawer
+                    if ui
+                        .selectable_label(self.show_toast_history, icons::NOTIFICATION_3_LINE)
+                        .labeled_toggle(self.show_toast_history, tr("top.notification_history"))
+                        .clicked()
+                    {
+                        self.show_toast_history = !self.show_toast_history;
+                    }
+
                     ui.add_space(10.0);
 
                     // Status indicator
+                    match &self.embedding_server_status {
+                        Some(
+                            status @ (EmbeddingServerStatus::Starting
+                            | EmbeddingServerStatus::Restarting { .. }),
+                        ) if self.init_status == InitStatus::Ready => {
+                            ui.spinner();
+                            ui.label(widgets::embedding_provider::status_label(status));
+                        }
+                        Some(status @ EmbeddingServerStatus::Failed(_)) => {
+                            ui.colored_label(egui::Color32::RED, icons::ERROR_WARNING_LINE)
+                                .on_hover_text(widgets::embedding_provider::status_label(status));
+                        }
+                        _ => {}
+                    }
+                    if let Some(error) = &self.bookmark_watch_error {
+                        ui.colored_label(egui::Color32::YELLOW, icons::ERROR_WARNING_LINE)
+                            .on_hover_text(tr_args("top.watch_failed", &[("error", error)]));
+                    }
+                    let integrity_problems = self
+                        .integrity_report
+                        .as_ref()
+                        .map_or(0, |report| report.problem_count());
+                    if integrity_problems > 0 {
+                        let label =
+                            tr_args("integrity.problems", &[("count", &integrity_problems)]);
+                        if ui
+                            .add(egui::Button::new(
+                                egui::RichText::new(format!(
+                                    "{} {}",
+                                    icons::ERROR_WARNING_LINE,
+                                    integrity_problems
+                                ))
+                                .color(egui::Color32::YELLOW),
+                            ))
+                            .labeled(&label)
+                            .on_hover_text(&label)
+                            .clicked()
+                        {
+                            self.show_integrity_report = !self.show_integrity_report;
+                        }
+                    }
                     match &self.init_status {
                         InitStatus::Starting => {
                             ui.spinner();
-                            ui.label("Starting...");
+                            ui.label(tr("top.starting"));
                         }
                         InitStatus::WaitingForEmbedding => {
                             ui.spinner();
-                            ui.label("Starting embedding server...");
+                            ui.label(tr("top.starting_server"));
+                        }
+                        InitStatus::Ready if !self.semantic_search_available => {
+                            ui.colored_label(egui::Color32::YELLOW, tr("top.keyword_only"))
+                                .on_hover_text(tr("top.keyword_only_hint"));
                         }
                         InitStatus::Ready => {
-                            ui.label("✓ Ready");
+                            ui.label(tr("top.ready"));
                         }
                         InitStatus::Error(msg) => {
                             ui.colored_label(egui::Color32::RED, format!("✗ {}", msg));
@@ -1430,6 +3624,20 @@ impl eframe::App for LocalMindApp {
             ui.add_space(8.0);
         });
 
+        // Notification history drawer (right side)
+        widgets::toast::render_toast_history(ctx, self);
+
+        // Navigation sidebar (left side)
+        if self.show_sidebar && self.init_status == InitStatus::Ready {
+            egui::SidePanel::left("sidebar")
+                .resizable(true)
+                .default_width(220.0)
+                .width_range(160.0..=400.0)
+                .show(ctx, |ui| {
+                    widgets::sidebar::render_sidebar(ui, self);
+                });
+        }
+
         // Main content area
         egui::CentralPanel::default().show(ctx, |ui| {
             match self.current_view {
@@ -1439,6 +3647,24 @@ impl eframe::App for LocalMindApp {
                 View::SearchResults => {
                     views::search::render_search_results(ui, self);
                 }
+                View::Trash => {
+                    views::trash::render_trash_view(ui, self);
+                }
+                View::Stats => {
+                    views::stats::render_stats_view(ui, self);
+                }
+                View::Browse => {
+                    views::browse::render_browse_view(ui, self);
+                }
+                View::Jobs => {
+                    views::jobs::render_jobs_view(ui, self);
+                }
+                View::Timeline => {
+                    views::timeline::render_timeline_view(ui, self);
+                }
+                View::Explore => {
+                    views::explore::render_explore_view(ui, self);
+                }
                 View::DocumentDetail => {
                     if self.is_document_loading() {
                         // Show loading state
@@ -1446,7 +3672,7 @@ impl eframe::App for LocalMindApp {
                             ui.add_space(100.0);
                             ui.spinner();
                             ui.add_space(10.0);
-                            ui.label("Loading document...");
+                            ui.label(tr("top.loading_document"));
                         });
                     } else {
                         views::document::render_document_view(ui, self);
@@ -1458,7 +3684,7 @@ impl eframe::App for LocalMindApp {
         // Settings modal
         if self.settings_open {
             use widgets::settings;
-            egui::Window::new("Settings")
+            egui::Window::new(tr("settings.title"))
                 .collapsible(false)
                 .resizable(true)
                 .default_size([600.0, 500.0])
@@ -1469,26 +3695,133 @@ impl eframe::App for LocalMindApp {
                 });
         }
 
+        // First-ingestion preview
+        widgets::ingestion_preview::render_ingestion_preview(ctx, self);
+
+        // Bookmark file import dialog
+        widgets::bookmark_import::render_import_dialog(ctx, self);
+
+        // "Paste URL" and "New note" dialogs (opened from the home view)
+        widgets::quick_actions::render_paste_url_dialog(ctx, self);
+        widgets::quick_actions::render_note_dialog(ctx, self);
+
+        // "Remind me" dialog (opened from a document's context menu)
+        widgets::reminders::render_reminder_dialog(ctx, self);
+        widgets::document_versions::render_version_diff(ctx, self);
+
+        // Integrity report and repairs (opened from the status bar)
+        widgets::integrity::render_integrity_report(ctx, self);
+
+        // Keyboard shortcut help (toggled with "?")
+        widgets::shortcuts::render_shortcuts_help(ctx, self);
+
         // Toast overlay (bottom-right)
-        widgets::toast::render_toasts(ctx, &self.toasts);
+        widgets::toast::render_toasts(ctx, self);
 
         // Request repaint while initializing, loading, or searching
         if !matches!(self.init_status, InitStatus::Ready | InitStatus::Error(_))
             || self.recent_docs_receiver.is_some()
+            || self.pinned_docs_receiver.is_some()
+            || self.resurfaced_receiver.is_some()
+            || self.home_summary_receiver.is_some()
+            || self.unread_receiver.is_some()
             || self.search_receiver.is_some()
+            || self.restored_search_receiver.is_some()
+            || self.search_fallback_receiver.is_some()
+            || self.autocomplete_receiver.is_some()
             || self.document_receiver.is_some()
+            || self.document_answer_receiver.is_some()
+            || self.collections_receiver.is_some()
+            || self.collection_action_receiver.is_some()
+            || self.document_collections_receiver.is_some()
+            || self.document_action_receiver.is_some()
+            || self.markdown_export_receiver.is_some()
+            || self.document_tags_receiver.is_some()
+            || self.tag_suggestions_receiver.is_some()
+            || self.document_links_receiver.is_some()
+            || self.document_passages_receiver.is_some()
+            || self.document_metadata_receiver.is_some()
+            || self.trashed_documents_receiver.is_some()
+            || self.sidebar_receiver.is_some()
+            || self.browse_receiver.is_some()
+            || self.timeline_receiver.is_some()
+            || self.projection_receiver.is_some()
+            || self.projection_job_receiver.is_some()
+            || self.jobs_receiver.is_some()
+            || self.stats_receiver.is_some()
+            || self.preview_request_receiver.is_some()
+            || self.bookmark_import_receiver.is_some()
+            || self.paste_url_receiver.is_some()
+            || self.note_receiver.is_some()
             || self.exclusion_rules_receiver.is_some()
             || self.save_exclusion_receiver.is_some()
+            || self.ingest_errors_receiver.is_some()
+            || self.retry_receiver.is_some()
+            || self.dead_link_interval_receiver.is_some()
+            || self.storage_status_receiver.is_some()
+            || self.retention_rules_receiver.is_some()
+            || self.maintenance_interval_receiver.is_some()
+            || self.integrity_repair_receiver.is_some()
+            || self.sync_settings_receiver.is_some()
+            || self.remote_access_receiver.is_some()
+            || self.http_server_receiver.is_some()
+            || self.library_switch_receiver.is_some()
+            || self.favicon_loads_pending > 0
+            || self.thumbnail_loads_pending > 0
+            || self.language_loads_pending > 0
+            || self.language_settings_receiver.is_some()
+            || self.low_quality_receiver.is_some()
+            || self.refetch_documents_receiver.is_some()
+            || self.page_refetch_receiver.is_some()
+            || self.sync_run_receiver.is_some()
+            || self.snapshot_settings_receiver.is_some()
+            || self.snapshot_run_receiver.is_some()
+            || self.retention_preview_receiver.is_some()
+            || self.prune_suggestions_receiver.is_some()
+            || self.embedding_settings_receiver.is_some()
+            || self.cached_settings_receiver.is_some()
+            || self.ingest_policies_receiver.is_some()
+            || self.embedding_models_receiver.is_some()
+            || self.embedding_apply_receiver.is_some()
+            || self.ui_preferences_receiver.is_some()
+            || self.is_link_check_running()
         {
             ctx.request_repaint();
         }
+
+        // Keep polling background events while minimized or unfocused, and
+        // while the embedding server is coming up
+        let server_starting = matches!(
+            self.embedding_server_status,
+            Some(EmbeddingServerStatus::Starting | EmbeddingServerStatus::Restarting { .. })
+        );
+        if self.window_in_background || server_starting {
+            ctx.request_repaint_after(std::time::Duration::from_secs(1));
+        }
+    }
+}
+
+/// Convert a database document into its UI representation.
+pub(crate) fn document_view(doc: crate::db::Document) -> DocumentView {
+    let content = prepare_content(&doc.content, doc.url.as_deref());
+    DocumentView {
+        id: doc.id,
+        title: doc.title,
+        word_count: content.split_whitespace().count(),
+        content,
+        url: doc.url,
+        source: doc.source,
+        created_at: doc.created_at,
+        profile: doc.profile,
+        is_needs_auth: doc.needs_auth.unwrap_or(false),
+        is_dead: doc.is_dead.unwrap_or(false),
     }
 }
 
 /// Choose the right content preparation for a document based on its URL.
 ///
-/// Local `.md` files must NOT go through `html2text` — they are plain text /
-/// Markdown, not HTML. Running them through `html2text` collapses newlines into
+/// Local `.md` files must NOT go through `strip_html` — they are plain text /
+/// Markdown, not HTML. Running them through `strip_html` collapses newlines into
 /// spaces, which breaks frontmatter stripping and degrades snippet quality.
 ///
 /// Everything else (web pages, bookmarks) goes through the normal `strip_html` path.
@@ -1506,8 +3839,8 @@ fn prepare_content(content: &str, url: Option<&str>) -> String {
 
 /// Strip HTML tags from content and return plain text
 ///
-/// Uses `html2text` crate to convert HTML to readable plain text.
-/// Wraps text at 80 characters per line.
+/// Uses `html_text` to convert HTML to readable plain text, one line per
+/// block.
 ///
 /// # Arguments
 /// * `content` - HTML content to convert
@@ -1522,8 +3855,8 @@ pub fn strip_html(content: &str) -> String {
             let metadata = &content[..content_start + 2]; // Keep metadata with its newlines
             let actual_content = &content[content_start + 2..];
 
-            // Only process the actual content through html2text, preserve metadata as-is
-            let processed_content = html2text::from_read(actual_content.as_bytes(), 80);
+            // Only process the actual content, preserve metadata as-is
+            let processed_content = crate::html_text::extract(actual_content, None).text;
             format!("{}{}", metadata, processed_content)
         } else {
             // No actual content after metadata
@@ -1531,378 +3864,10 @@ pub fn strip_html(content: &str) -> String {
         }
     } else {
         // Regular content, process normally
-        html2text::from_read(content.as_bytes(), 80)
+        crate::html_text::extract(content, None).text
     }
 }
 
-/// Initialize the RAG system
-async fn init_rag_system() -> crate::Result<(RagPipeline, Option<std::process::Child>)> {
-    use crate::local_embedding::{spawn_embedding_server, LocalEmbeddingClient};
-
-    // Check if the embedding server is already running; if not, start it.
-    let temp_client = LocalEmbeddingClient::new();
-    let child_opt: Option<std::process::Child> = if temp_client.is_running().await {
-        println!("Embedding server already running");
-        None
-    } else {
-        println!("Embedding server not running, attempting to start it...");
-        match spawn_embedding_server() {
-            Ok(child) => {
-                println!("Embedding server spawned (PID {})", child.id());
-                Some(child)
-            }
-            Err(e) => {
-                eprintln!(
-                    "Could not spawn embedding server: {}. Continuing anyway (may time out).",
-                    e
-                );
-                None
-            }
-        }
-    };
-
-    println!("Initializing database...");
-
-    let db = match Database::new().await {
-        Ok(database) => {
-            println!("Database initialized successfully");
-            database
-        }
-        Err(e) => {
-            eprintln!("Database initialization failed: {}", e);
-            return Err(e);
-        }
-    };
-
-    println!("Initializing RAG pipeline...");
-    let rag = match RagPipeline::new(db).await {
-        Ok(rag_pipeline) => {
-            println!("RAG pipeline initialized successfully");
-            rag_pipeline
-        }
-        Err(e) => {
-            eprintln!("RAG pipeline initialization failed: {}", e);
-            return Err(e);
-        }
-    };
-
-    Ok((rag, child_opt))
-}
-
-/// Start bookmark monitoring with progress reporting
-async fn start_bookmark_monitoring(
-    rag_state: RagState,
-    progress_tx: std::sync::mpsc::Sender<BookmarkProgress>,
-) -> crate::Result<()> {
-    use crate::bookmark::{get_all_chrome_profiles, BookmarkMonitor};
-    use crate::bookmark_exclusion::ExclusionRules;
-
-    println!("Initializing bookmark monitor...");
-
-    // Discover all Chrome profiles
-    let profiles = get_all_chrome_profiles();
-    if profiles.is_empty() {
-        println!("No Chrome profiles found, skipping bookmark monitoring");
-        return Ok(());
-    }
-    println!("Found {} Chrome profile(s) to index", profiles.len());
-
-    // Load exclusion rules from database (shared across all profiles)
-    let exclusion_rules = {
-        let rag_lock = rag_state.read().await;
-        if let Some(ref rag) = *rag_lock {
-            let folders = rag.db.get_excluded_folders().await.unwrap_or_default();
-            let domains = rag.db.get_excluded_domains().await.unwrap_or_default();
-            ExclusionRules::new(folders, domains)
-        } else {
-            ExclusionRules::empty()
-        }
-    };
-
-    let mut total_ingested = 0;
-
-    for profile in &profiles {
-        println!(
-            "Processing profile: {} ({})",
-            profile.display_name, profile.dir_name
-        );
-
-        let (monitor, _rx) = match BookmarkMonitor::for_profile(profile) {
-            Ok(m) => m,
-            Err(e) => {
-                eprintln!(
-                    "Failed to create monitor for profile {}: {}",
-                    profile.display_name, e
-                );
-                continue;
-            }
-        };
-
-        let bookmark_metadata = match monitor
-            .get_bookmarks_metadata_with_exclusion(&exclusion_rules)
-            .await
-        {
-            Ok(m) => m,
-            Err(e) => {
-                eprintln!(
-                    "Failed to get bookmarks for profile {}: {}",
-                    profile.display_name, e
-                );
-                continue;
-            }
-        };
-
-        if bookmark_metadata.is_empty() {
-            println!("No bookmarks found in profile {}", profile.display_name);
-            continue;
-        }
-
-        println!(
-            "Processing {} bookmarks from profile {}",
-            bookmark_metadata.len(),
-            profile.display_name
-        );
-
-        let total = bookmark_metadata.len();
-        let profile_name = profile.display_name.clone();
-
-        for (title, url) in bookmark_metadata.into_iter() {
-            {
-                let rag_lock = rag_state.read().await;
-                if let Some(ref rag) = *rag_lock {
-                    // Skip bookmarks that are already indexed
-                    if rag.document_exists(&url).await.unwrap_or(false) {
-                        continue;
-                    }
-
-                    let _ = progress_tx.send(BookmarkProgress {
-                        current: total_ingested + 1,
-                        total,
-                        current_title: title.clone(),
-                        completed: false,
-                    });
-
-                    // Fetch content (returns content + auth status)
-                    let (fetched_content, needs_auth) =
-                        match monitor.fetch_bookmark_content(&url).await {
-                            Ok(result) => result,
-                            Err(e) => {
-                                eprintln!("Failed to fetch content for '{}': {}", title, e);
-                                (
-                                    format!(
-                                        "Bookmark: {}\nURL: {}\n\n[Error fetching content: {}]",
-                                        title, url, e
-                                    ),
-                                    false,
-                                )
-                            }
-                        };
-
-                    // Always prepend title so it gets embedded and is searchable
-                    let content = format!("{}\n\n{}", title, fetched_content);
-
-                    match rag
-                        .ingest_document_with_auth(
-                            &title,
-                            &content,
-                            Some(&url),
-                            "chrome_bookmark",
-                            Some(&profile_name),
-                            needs_auth,
-                        )
-                        .await
-                    {
-                        Ok(_) => {
-                            total_ingested += 1;
-                            println!("Ingested bookmark: {} (profile: {})", title, profile_name);
-                        }
-                        Err(e) => {
-                            eprintln!("Failed to ingest bookmark '{}': {}", title, e);
-                        }
-                    }
-                }
-            }
-
-            // Small delay to prevent overwhelming the system
-            tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
-        }
-    }
-
-    // Send completion notification
-    let _ = progress_tx.send(BookmarkProgress {
-        current: total_ingested,
-        total: total_ingested,
-        current_title: if total_ingested > 0 {
-            format!("{} new bookmarks ingested", total_ingested)
-        } else {
-            "All bookmarks already indexed".to_string()
-        },
-        completed: true,
-    });
-
-    println!(
-        "Bookmark ingestion complete: {} new bookmarks ingested",
-        total_ingested
-    );
-
-    Ok(())
-}
-
-/// Start the HTTP server for Chrome extension compatibility
-async fn start_http_server(rag_state: RagState) -> crate::Result<()> {
-    use axum::{
-        extract::State,
-        http::{header, Method, StatusCode},
-        response::{IntoResponse, Json, Response},
-        routing::post,
-        Router,
-    };
-    use serde::{Deserialize, Serialize};
-    use serde_json::json;
-    use tokio::net::TcpListener;
-    use tower::ServiceBuilder;
-    use tower_http::cors::{Any, CorsLayer};
-
-    #[derive(Clone)]
-    struct AppState {
-        rag_state: RagState,
-    }
-
-    struct ApiError {
-        status: StatusCode,
-        message: String,
-    }
-
-    impl IntoResponse for ApiError {
-        fn into_response(self) -> Response {
-            let body = Json(json!({ "message": self.message }));
-            (self.status, body).into_response()
-        }
-    }
-
-    #[derive(Deserialize)]
-    struct DocumentRequest {
-        title: String,
-        content: String,
-        url: Option<String>,
-        #[serde(default = "default_extraction_method", rename = "extractionMethod")]
-        extraction_method: String,
-    }
-
-    fn default_extraction_method() -> String {
-        "dom".to_string()
-    }
-
-    #[derive(Serialize)]
-    struct SuccessResponse {
-        message: String,
-        #[serde(rename = "extractionMethod")]
-        extraction_method: String,
-    }
-
-    async fn handle_post_documents(
-        State(state): State<AppState>,
-        Json(request): Json<DocumentRequest>,
-    ) -> Result<Json<SuccessResponse>, ApiError> {
-        if request.title.is_empty() || request.content.is_empty() {
-            return Err(ApiError {
-                status: StatusCode::BAD_REQUEST,
-                message: "Title and content are required.".to_string(),
-            });
-        }
-
-        let rag_lock = state.rag_state.read().await;
-        let rag = rag_lock.as_ref().ok_or_else(|| ApiError {
-            status: StatusCode::SERVICE_UNAVAILABLE,
-            message: "System initializing. Please wait.".to_string(),
-        })?;
-
-        println!(
-            "Processing document: title='{}', url={:?}",
-            request.title.chars().take(60).collect::<String>(),
-            request.url.as_deref()
-        );
-
-        // Check if this URL already exists (update instead of duplicate)
-        if let Some(ref url) = request.url {
-            if let Ok(Some(existing_doc)) = rag.db.get_document_by_url(url).await {
-                println!(
-                    "Document already exists for URL {}, updating (id={})",
-                    url, existing_doc.id
-                );
-
-                rag.update_document(existing_doc.id, &request.title, &request.content)
-                    .await
-                    .map_err(|e| ApiError {
-                        status: StatusCode::INTERNAL_SERVER_ERROR,
-                        message: format!("Failed to update document: {}", e),
-                    })?;
-
-                return Ok(Json(SuccessResponse {
-                    message: "Document updated successfully.".to_string(),
-                    extraction_method: request.extraction_method,
-                }));
-            }
-        }
-
-        rag.ingest_document(
-            &request.title,
-            &request.content,
-            request.url.as_deref(),
-            "chrome_extension",
-            None,
-        )
-        .await
-        .map_err(|e| ApiError {
-            status: StatusCode::INTERNAL_SERVER_ERROR,
-            message: format!("Failed to add document: {}", e),
-        })?;
-
-        Ok(Json(SuccessResponse {
-            message: "Document added successfully.".to_string(),
-            extraction_method: request.extraction_method,
-        }))
-    }
-
-    // Find available port
-    let mut port = None;
-    for p in 3000..=3010 {
-        match TcpListener::bind(format!("127.0.0.1:{}", p)).await {
-            Ok(_) => {
-                port = Some(p);
-                break;
-            }
-            Err(_) => continue,
-        }
-    }
-
-    let port = port.ok_or("No available ports in range 3000-3010")?;
-    println!("Starting HTTP server on port {}", port);
-
-    let cors = CorsLayer::new()
-        .allow_origin(Any)
-        .allow_methods([Method::POST, Method::OPTIONS])
-        .allow_headers([header::CONTENT_TYPE]);
-
-    let app_state = AppState { rag_state };
-
-    let app = Router::new()
-        .route("/documents", post(handle_post_documents))
-        .layer(
-            ServiceBuilder::new()
-                .layer(axum::extract::DefaultBodyLimit::max(10 * 1024 * 1024))
-                .layer(cors),
-        )
-        .with_state(app_state);
-
-    let listener = TcpListener::bind(format!("127.0.0.1:{}", port)).await?;
-    println!("HTTP server listening on http://localhost:{}", port);
-
-    axum::serve(listener, app).await?;
-
-    Ok(())
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1923,4 +3888,85 @@ mod tests {
         let result = strip_html(plain);
         assert_eq!(result.trim(), plain);
     }
+
+    #[test]
+    fn failed_init_is_shown_as_an_error() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let mut app = LocalMindApp::detached(runtime.handle().clone());
+        let (tx, rx) = std::sync::mpsc::channel();
+        app.init_receiver = Some(rx);
+
+        app.check_init_status();
+        assert_eq!(app.init_status, InitStatus::WaitingForEmbedding);
+        assert!(app.init_receiver.is_some());
+
+        tx.send(Err("no embedding server".to_string())).unwrap();
+        app.check_init_status();
+        assert_eq!(
+            app.init_status,
+            InitStatus::Error("no embedding server".to_string())
+        );
+        assert!(app.init_receiver.is_none());
+        assert_eq!(app.toasts.active.len(), 1);
+        assert_eq!(app.toasts.active[0].toast_type, ToastType::Error);
+    }
+
+    #[test]
+    fn closed_init_channel_is_an_error() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let mut app = LocalMindApp::detached(runtime.handle().clone());
+        let (tx, rx) = std::sync::mpsc::channel();
+        app.init_receiver = Some(rx);
+        drop(tx);
+
+        app.check_init_status();
+        assert!(matches!(app.init_status, InitStatus::Error(_)));
+        assert!(app.init_receiver.is_none());
+    }
+
+    #[test]
+    fn saved_exclusion_rules_are_stored_and_close_settings() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let dir = tempfile::TempDir::new().unwrap();
+        let rag = runtime.block_on(async {
+            let db = crate::db::Database::open(&dir.path().join("localmind.db"))
+                .await
+                .unwrap();
+            crate::rag::RagPipeline::with_embedding_backend(
+                db,
+                Box::new(crate::embedding_provider::MockEmbedding::default()),
+            )
+            .await
+            .unwrap()
+        });
+        let mut app = LocalMindApp::detached(runtime.handle().clone());
+        app.rag = Arc::new(RwLock::new(Some(rag)));
+        app.settings_open = true;
+        app.excluded_domains = vec!["ads.example".to_string()];
+
+        app.save_exclusion_rules().unwrap();
+        assert!(app.is_saving_exclusion_rules());
+        assert!(app.save_exclusion_rules().is_err());
+
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+        while app.is_saving_exclusion_rules() {
+            assert!(std::time::Instant::now() < deadline, "save never finished");
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            app.check_save_exclusion_rules();
+        }
+        assert!(!app.settings_open);
+        assert_eq!(app.toasts.active.len(), 1);
+        assert_eq!(app.toasts.active[0].toast_type, ToastType::Success);
+
+        let stored = runtime.block_on(async {
+            let rag = app.rag.read().await;
+            rag.as_ref()
+                .unwrap()
+                .db
+                .get_excluded_domains()
+                .await
+                .unwrap()
+        });
+        assert_eq!(stored, vec!["ads.example".to_string()]);
+    }
 }
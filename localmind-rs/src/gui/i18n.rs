@@ -0,0 +1,167 @@
+//! Localized UI strings
+//!
+//! Views look up user-facing text by key, e.g. `tr("home.no_documents")`.
+//! Each language is a `key = value` file under `locales/`, compiled into the
+//! binary. Values may contain `{name}` placeholders, filled in by `tr_args`.
+//! A key missing from a translation falls back to English, and a key missing
+//! from English is shown as is, so an untranslated string is visible but
+//! never breaks the UI.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::OnceLock;
+
+/// A language the UI is translated into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Language {
+    #[default]
+    English,
+    Spanish,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::Spanish];
+
+    /// Code stored in the settings, e.g. "en".
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::Spanish => "es",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|lang| lang.code() == code)
+    }
+
+    /// Name of the language in that language, for the selector.
+    pub fn native_name(self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::Spanish => "Español",
+        }
+    }
+
+    fn source(self) -> &'static str {
+        match self {
+            Language::English => include_str!("../../locales/en.txt"),
+            Language::Spanish => include_str!("../../locales/es.txt"),
+        }
+    }
+
+    fn index(self) -> usize {
+        match self {
+            Language::English => 0,
+            Language::Spanish => 1,
+        }
+    }
+
+    /// Parsed strings of this language, loaded on first use.
+    fn strings(self) -> &'static HashMap<&'static str, &'static str> {
+        static TABLES: [OnceLock<HashMap<&'static str, &'static str>>; 2] =
+            [OnceLock::new(), OnceLock::new()];
+        TABLES[self.index()].get_or_init(|| parse(self.source()))
+    }
+}
+
+/// Index of the current language in `Language::ALL`.
+static CURRENT: AtomicU8 = AtomicU8::new(0);
+
+/// Switch the UI language; takes effect on the next frame.
+pub fn set_language(language: Language) {
+    CURRENT.store(language.index() as u8, Ordering::Relaxed);
+}
+
+pub fn language() -> Language {
+    Language::ALL[CURRENT.load(Ordering::Relaxed) as usize]
+}
+
+/// Parse `key = value` lines; blank lines and `#` comments are skipped.
+fn parse(source: &'static str) -> HashMap<&'static str, &'static str> {
+    source
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim(), value.trim()))
+        .collect()
+}
+
+fn lookup(language: Language, key: &'static str) -> &'static str {
+    language
+        .strings()
+        .get(key)
+        .or_else(|| Language::English.strings().get(key))
+        .copied()
+        .unwrap_or(key)
+}
+
+/// The text for `key` in the current language.
+pub fn tr(key: &'static str) -> &'static str {
+    lookup(language(), key)
+}
+
+/// The text for `key` with each `{name}` placeholder replaced by its value.
+pub fn tr_args(key: &'static str, args: &[(&str, &dyn std::fmt::Display)]) -> String {
+    let mut text = tr(key).to_string();
+    for (name, value) in args {
+        text = text.replace(&format!("{{{}}}", name), &value.to_string());
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `{name}` placeholders in a value, sorted.
+    fn placeholders(value: &str) -> Vec<&str> {
+        let mut names: Vec<&str> = value
+            .split('{')
+            .skip(1)
+            .filter_map(|rest| rest.split_once('}').map(|(name, _)| name))
+            .collect();
+        names.sort_unstable();
+        names
+    }
+
+    #[test]
+    fn translations_cover_every_english_key() {
+        let english = Language::English.strings();
+        assert!(!english.is_empty());
+        for language in Language::ALL {
+            let strings = language.strings();
+            for (key, value) in english {
+                let translated = strings
+                    .get(key)
+                    .unwrap_or_else(|| panic!("{} is missing {}", language.code(), key));
+                assert_eq!(
+                    placeholders(translated),
+                    placeholders(value),
+                    "{} has different placeholders in {}",
+                    language.code(),
+                    key
+                );
+            }
+            for key in strings.keys() {
+                assert!(
+                    english.contains_key(key),
+                    "{} has unknown key {}",
+                    language.code(),
+                    key
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn falls_back_to_english_then_the_key() {
+        assert_eq!(lookup(Language::Spanish, "no.such.key"), "no.such.key");
+        assert_eq!(Language::from_code("es"), Some(Language::Spanish));
+        assert_eq!(Language::from_code("xx"), None);
+
+        let parsed = parse("# comment\n\n a.b = Hello {name} = friend \nbroken line\n");
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed["a.b"], "Hello {name} = friend");
+    }
+}
@@ -0,0 +1,728 @@
+//! Presentation logic for the views, kept free of egui
+//!
+//! Decisions about what to show and in which order live here so views only
+//! draw, and so the decisions can be tested without a UI.
+
+use std::collections::HashSet;
+
+use super::i18n::{tr, tr_args};
+use super::state::{SearchResultView, TimelineGrouping};
+use crate::bookmark_exclusion::ExclusionRules;
+use crate::db::DocumentMetadata;
+use crate::document::ChunkProvenance;
+
+/// Search results from one site (or, without a web URL, one source).
+#[derive(Debug, Clone)]
+pub struct ResultGroup {
+    /// Domain without "www.", or the document source for local files
+    pub label: String,
+    /// Results in rank order; the first is the best hit
+    pub results: Vec<SearchResultView>,
+}
+
+impl ResultGroup {
+    /// Results beyond the best hit, shown when the group is expanded.
+    pub fn hidden_count(&self) -> usize {
+        self.results.len().saturating_sub(1)
+    }
+}
+
+/// The site a result belongs to: its URL host without "www.", falling back
+/// to the document source for results without a web URL.
+pub fn group_label(result: &SearchResultView) -> String {
+    result
+        .url
+        .as_deref()
+        .and_then(|url| url::Url::parse(url).ok())
+        .and_then(|url| url.host_str().map(str::to_lowercase))
+        .map(|host| host.trim_start_matches("www.").to_string())
+        .unwrap_or_else(|| result.source.clone())
+}
+
+/// Group results by site. Groups are ordered by their best hit, so the top
+/// result stays on top, and results keep their rank order within a group.
+pub fn group_by_site(results: &[SearchResultView]) -> Vec<ResultGroup> {
+    let mut groups: Vec<ResultGroup> = Vec::new();
+    for result in results {
+        let label = group_label(result);
+        match groups.iter_mut().find(|group| group.label == label) {
+            Some(group) => group.results.push(result.clone()),
+            None => groups.push(ResultGroup {
+                label,
+                results: vec![result.clone()],
+            }),
+        }
+    }
+    groups
+}
+
+/// Document IDs in the order the results view shows them, for keyboard
+/// navigation. Grouped, only each group's best hit is listed unless the
+/// group is in `expanded`.
+pub fn visible_result_ids(
+    results: &[SearchResultView],
+    grouped: bool,
+    expanded: &HashSet<String>,
+) -> Vec<i64> {
+    if !grouped {
+        return results.iter().map(|r| r.doc_id).collect();
+    }
+    group_by_site(results)
+        .into_iter()
+        .flat_map(|group| {
+            let shown = if expanded.contains(&group.label) {
+                group.results.len()
+            } else {
+                1
+            };
+            group.results.into_iter().take(shown).map(|r| r.doc_id)
+        })
+        .collect()
+}
+
+/// Filters applied to the results of a search before they are shown.
+#[derive(Debug, Clone, Copy)]
+pub struct ResultFilters<'a> {
+    /// Results scoring below this are hidden
+    pub similarity_cutoff: f32,
+    /// Show only documents in `unread_ids`
+    pub unread_only: bool,
+    pub unread_ids: &'a HashSet<i64>,
+    /// Show only bookmarks from this Chrome profile
+    pub profile: Option<&'a str>,
+}
+
+/// The results to show: duplicates of the same passage dropped, then the
+/// similarity cutoff, unread and profile filters applied, in rank order.
+pub fn filter_results(
+    results: &[SearchResultView],
+    filters: &ResultFilters,
+) -> Vec<SearchResultView> {
+    let mut seen = HashSet::new();
+    results
+        .iter()
+        .filter(|r| seen.insert((r.doc_id, r.passage)))
+        .filter(|r| r.similarity >= filters.similarity_cutoff)
+        .filter(|r| !filters.unread_only || filters.unread_ids.contains(&r.doc_id))
+        .filter(|r| {
+            filters
+                .profile
+                .is_none_or(|profile| r.profile.as_deref() == Some(profile))
+        })
+        .cloned()
+        .collect()
+}
+
+/// What adding a domain pattern in Settings does.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DomainPatternCheck {
+    /// The trimmed pattern is valid and new
+    Add(String),
+    /// The pattern is already listed (case-insensitively)
+    Duplicate(String),
+    /// The pattern is rejected, with the reason
+    Invalid(String),
+}
+
+/// Check a domain pattern typed in Settings against the listed ones.
+/// Returns `None` for blank input.
+pub fn check_domain_pattern(input: &str, listed: &[String]) -> Option<DomainPatternCheck> {
+    let pattern = input.trim();
+    if pattern.is_empty() {
+        return None;
+    }
+    if let Err(e) = ExclusionRules::validate_pattern(pattern) {
+        return Some(DomainPatternCheck::Invalid(e.to_string()));
+    }
+    let lower = pattern.to_lowercase();
+    Some(if listed.iter().any(|d| d.to_lowercase() == lower) {
+        DomainPatternCheck::Duplicate(pattern.to_string())
+    } else {
+        DomainPatternCheck::Add(pattern.to_string())
+    })
+}
+
+/// Toast shown once exclusion rules are saved, noting how many bookmarks
+/// they moved to the trash.
+pub fn exclusion_rules_saved_message(trashed: usize) -> String {
+    if trashed > 0 {
+        format!(
+            "Exclusion rules saved ({} bookmarks moved to the trash)",
+            trashed
+        )
+    } else {
+        "Exclusion rules saved".to_string()
+    }
+}
+
+/// The tag being typed in a comma-separated tag list: the text after the
+/// last comma, trimmed.
+pub fn typed_tag(edit: &str) -> &str {
+    edit.rsplit(',').next().unwrap_or("").trim()
+}
+
+/// The tags already entered before the one being typed.
+pub fn entered_tags(edit: &str) -> Vec<String> {
+    let mut tags: Vec<&str> = edit.split(',').collect();
+    tags.pop();
+    tags.into_iter()
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Put a suggested tag in place of the one being typed, ready for the next.
+pub fn complete_tag(edit: &str, tag: &str) -> String {
+    let mut tags = entered_tags(edit);
+    tags.push(tag.to_string());
+    format!("{}, ", tags.join(", "))
+}
+
+/// How much of a document is embedded, e.g. "Partially embedded (3 of 8
+/// chunks)". Documents indexed before chunk counts were recorded count as
+/// fully embedded when they have any chunks.
+pub fn embedding_status(metadata: &DocumentMetadata) -> String {
+    let embedded = metadata.embedded_chunks;
+    match metadata.chunk_count {
+        Some(0) => tr("document.nothing_to_embed").to_string(),
+        _ if embedded == 0 => tr("document.not_embedded").to_string(),
+        Some(total) if embedded < total => tr_args(
+            "document.partially_embedded",
+            &[("embedded", &embedded), ("total", &total)],
+        ),
+        _ if embedded == 1 => tr("document.embedded_one").to_string(),
+        _ => tr_args("document.embedded", &[("count", &embedded)]),
+    }
+}
+
+/// Byte offsets of the non-overlapping, case-insensitive occurrences of
+/// `needle` in `text`, in order. Offsets always fall on character
+/// boundaries.
+pub fn find_matches(text: &str, needle: &str) -> Vec<(usize, usize)> {
+    let lower = |c: char| c.to_lowercase().next().unwrap_or(c);
+    let needle: Vec<char> = needle.chars().map(lower).collect();
+    if needle.is_empty() {
+        return Vec::new();
+    }
+
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let mut matches = Vec::new();
+    let mut i = 0;
+    while i + needle.len() <= chars.len() {
+        let found = chars[i..i + needle.len()]
+            .iter()
+            .zip(&needle)
+            .all(|(&(_, c), &n)| lower(c) == n);
+        if found {
+            let end = chars
+                .get(i + needle.len())
+                .map(|&(offset, _)| offset)
+                .unwrap_or(text.len());
+            matches.push((chars[i].0, end));
+            i += needle.len();
+        } else {
+            i += 1;
+        }
+    }
+    matches
+}
+
+/// How a span of document text is highlighted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Highlight {
+    None,
+    /// Inside the passage the document was opened at
+    Passage,
+    /// A find match
+    Match,
+    /// The find match the find bar is on
+    CurrentMatch,
+    /// A saved highlight
+    Annotation,
+}
+
+/// Split `len` bytes of text into consecutive spans by highlight. Find
+/// matches (sorted, non-overlapping) take precedence over the passage, and
+/// the passage over saved highlights (which may overlap).
+pub fn highlight_spans(
+    len: usize,
+    passage: Option<(usize, usize)>,
+    matches: &[(usize, usize)],
+    current: Option<usize>,
+    annotations: &[(usize, usize)],
+) -> Vec<(usize, usize, Highlight)> {
+    let mut bounds = vec![0, len];
+    bounds.extend(passage.iter().flat_map(|&(start, end)| [start, end]));
+    bounds.extend(matches.iter().flat_map(|&(start, end)| [start, end]));
+    bounds.extend(annotations.iter().flat_map(|&(start, end)| [start, end]));
+    bounds.retain(|&b| b <= len);
+    bounds.sort_unstable();
+    bounds.dedup();
+
+    let mut spans: Vec<(usize, usize, Highlight)> = Vec::new();
+    for pair in bounds.windows(2) {
+        let (start, end) = (pair[0], pair[1]);
+        let index = matches.partition_point(|&(_, match_end)| match_end <= start);
+        let highlight = match matches.get(index) {
+            Some(&(match_start, _)) if match_start <= start => {
+                if current == Some(index) {
+                    Highlight::CurrentMatch
+                } else {
+                    Highlight::Match
+                }
+            }
+            _ if passage
+                .map(|(s, e)| s <= start && end <= e)
+                .unwrap_or(false) =>
+            {
+                Highlight::Passage
+            }
+            _ if annotations.iter().any(|&(s, e)| s <= start && end <= e) => Highlight::Annotation,
+            _ => Highlight::None,
+        };
+        match spans.last_mut() {
+            Some(last) if last.2 == highlight && last.1 == start => last.1 = end,
+            _ => spans.push((start, end, highlight)),
+        }
+    }
+    spans
+}
+
+/// Translation keys of the month names, January first.
+const MONTH_KEYS: [&str; 12] = [
+    "month.january",
+    "month.february",
+    "month.march",
+    "month.april",
+    "month.may",
+    "month.june",
+    "month.july",
+    "month.august",
+    "month.september",
+    "month.october",
+    "month.november",
+    "month.december",
+];
+
+/// Days since 1970-01-01 of a proleptic Gregorian date.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// The date `days` days after 1970-01-01, as (year, month, day).
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// The (year, month, day) a `YYYY-MM-DD HH:MM:SS` timestamp falls on.
+fn timestamp_date(created_at: &str) -> Option<(i64, i64, i64)> {
+    let date = created_at.get(..10)?;
+    let mut parts = date.split('-').map(|part| part.parse::<i64>().ok());
+    let (year, month, day) = (parts.next()??, parts.next()??, parts.next()??);
+    ((1..=12).contains(&month) && (1..=31).contains(&day)).then_some((year, month, day))
+}
+
+/// The period a document saved at `created_at` falls in: its day
+/// (`YYYY-MM-DD`), the Monday starting its week, or its month (`YYYY-MM`).
+/// Timestamps that are not dates are their own period.
+pub fn timeline_period(created_at: &str, grouping: TimelineGrouping) -> String {
+    let Some((year, month, day)) = timestamp_date(created_at) else {
+        return created_at.to_string();
+    };
+    match grouping {
+        TimelineGrouping::Day => format!("{:04}-{:02}-{:02}", year, month, day),
+        TimelineGrouping::Week => {
+            let days = days_from_civil(year, month, day);
+            // 1970-01-01 was a Thursday
+            let monday = days - (days + 3).rem_euclid(7);
+            let (year, month, day) = civil_from_days(monday);
+            format!("{:04}-{:02}-{:02}", year, month, day)
+        }
+        TimelineGrouping::Month => format!("{:04}-{:02}", year, month),
+    }
+}
+
+/// Heading for a timeline period, e.g. "March 2024" or "Week of 2024-03-11".
+pub fn timeline_period_label(period: &str, grouping: TimelineGrouping) -> String {
+    match grouping {
+        TimelineGrouping::Day => period.to_string(),
+        TimelineGrouping::Week => tr_args("timeline.week_of", &[("date", &period)]),
+        TimelineGrouping::Month => {
+            let month = period
+                .get(5..7)
+                .and_then(|month| month.parse::<usize>().ok())
+                .and_then(|month| MONTH_KEYS.get(month.wrapping_sub(1)));
+            match (period.get(..4), month) {
+                (Some(year), Some(&key)) => {
+                    tr_args("timeline.month", &[("month", &tr(key)), ("year", &year)])
+                }
+                _ => period.to_string(),
+            }
+        }
+    }
+}
+
+/// Split timeline documents (newest first) into runs saved in the same
+/// period: each period's heading with the range of its documents.
+pub fn timeline_groups(
+    created_at: &[&str],
+    grouping: TimelineGrouping,
+) -> Vec<(String, std::ops::Range<usize>)> {
+    let mut groups: Vec<(String, String, std::ops::Range<usize>)> = Vec::new();
+    for (i, created_at) in created_at.iter().enumerate() {
+        let period = timeline_period(created_at, grouping);
+        match groups.last_mut() {
+            Some((last, _, range)) if *last == period => range.end = i + 1,
+            _ => {
+                let label = timeline_period_label(&period, grouping);
+                groups.push((period, label, i..i + 1));
+            }
+        }
+    }
+    groups
+        .into_iter()
+        .map(|(_, label, range)| (label, range))
+        .collect()
+}
+
+/// Where a passage sits in its document, e.g. "Usage · p. 4", or `None`
+/// when its chunk has no provenance to show.
+pub fn passage_location(provenance: &ChunkProvenance) -> Option<String> {
+    let mut parts = Vec::new();
+    if let Some(heading) = &provenance.section_heading {
+        parts.push(heading.clone());
+    }
+    if let Some(page) = provenance.page_number {
+        parts.push(tr_args("document.passage_page", &[("page", &page)]));
+    }
+    if let Some(seconds) = provenance.timestamp_seconds {
+        parts.push(format!("{}:{:02}", seconds / 60, seconds % 60));
+    }
+    (!parts.is_empty()).then(|| parts.join(" · "))
+}
+
+/// `url` opened at the section a passage matched in, when its chunk
+/// knows the section's anchor and the URL is a web page without a
+/// fragment of its own.
+pub fn section_url(url: &str, provenance: &ChunkProvenance) -> String {
+    match &provenance.section_anchor {
+        Some(anchor)
+            if (url.starts_with("http://") || url.starts_with("https://"))
+                && !url.contains('#') =>
+        {
+            format!("{}#{}", url, anchor)
+        }
+        _ => url.to_string(),
+    }
+}
+
+/// Relative time such as "just now", "5 minutes ago" or "3 days ago".
+pub fn format_ago(seconds: i64) -> String {
+    match seconds {
+        s if s < 60 => tr("time.just_now").to_string(),
+        s if s < 3600 => tr_args("time.minutes_ago", &[("count", &(s / 60))]),
+        s if s < 86_400 => tr_args("time.hours_ago", &[("count", &(s / 3600))]),
+        s => tr_args("time.days_ago", &[("count", &(s / 86_400))]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(doc_id: i64, url: Option<&str>, source: &str) -> SearchResultView {
+        SearchResultView {
+            doc_id,
+            title: format!("Doc {}", doc_id),
+            snippet: String::new(),
+            similarity: 1.0,
+            url: url.map(str::to_string),
+            source: source.to_string(),
+            profile: None,
+            is_needs_auth: false,
+            is_dead: false,
+            passage: None,
+            location: None,
+        }
+    }
+
+    #[test]
+    fn groups_results_by_site_in_rank_order() {
+        let results = vec![
+            result(1, Some("https://docs.rs/tokio"), "chrome_bookmark"),
+            result(2, Some("https://blog.example.com/a"), "chrome_extension"),
+            result(3, Some("https://www.docs.rs/serde"), "chrome_bookmark"),
+            result(4, None, "file"),
+            result(5, Some("https://docs.rs/axum"), "chrome_bookmark"),
+        ];
+
+        let groups = group_by_site(&results);
+        let labels: Vec<&str> = groups.iter().map(|g| g.label.as_str()).collect();
+        assert_eq!(labels, vec!["docs.rs", "blog.example.com", "file"]);
+        let docs_rs: Vec<i64> = groups[0].results.iter().map(|r| r.doc_id).collect();
+        assert_eq!(docs_rs, vec![1, 3, 5]);
+        assert_eq!(groups[0].hidden_count(), 2);
+
+        let mut expanded = HashSet::new();
+        assert_eq!(visible_result_ids(&results, true, &expanded), vec![1, 2, 4]);
+        expanded.insert("docs.rs".to_string());
+        assert_eq!(
+            visible_result_ids(&results, true, &expanded),
+            vec![1, 3, 5, 2, 4]
+        );
+        assert_eq!(
+            visible_result_ids(&results, false, &expanded),
+            vec![1, 2, 3, 4, 5]
+        );
+    }
+
+    #[test]
+    fn finds_matches_and_splits_highlights() {
+        let text = "Tokio runs tasks. Ünïcode TOKIO; tokio";
+        let matches = find_matches(text, "tokio");
+        assert_eq!(matches, vec![(0, 5), (28, 33), (35, 40)]);
+        assert_eq!(&text[28..33], "TOKIO");
+        assert_eq!(find_matches(text, "ünï"), vec![(18, 23)]);
+        assert_eq!(find_matches("aaa", "aa"), vec![(0, 2)]);
+        assert!(find_matches(text, "").is_empty());
+
+        let spans = highlight_spans(text.len(), Some((0, 17)), &matches, Some(0), &[]);
+        assert_eq!(
+            spans,
+            vec![
+                (0, 5, Highlight::CurrentMatch),
+                (5, 17, Highlight::Passage),
+                (17, 28, Highlight::None),
+                (28, 33, Highlight::Match),
+                (33, 35, Highlight::None),
+                (35, 40, Highlight::Match),
+            ]
+        );
+        assert_eq!(
+            highlight_spans(10, None, &[], None, &[]),
+            vec![(0, 10, Highlight::None)]
+        );
+    }
+
+    #[test]
+    fn saved_highlights_sit_under_the_passage_and_matches() {
+        let spans = highlight_spans(20, Some((8, 12)), &[(2, 4)], None, &[(0, 10), (6, 14)]);
+        assert_eq!(
+            spans,
+            vec![
+                (0, 2, Highlight::Annotation),
+                (2, 4, Highlight::Match),
+                (4, 8, Highlight::Annotation),
+                (8, 12, Highlight::Passage),
+                (12, 14, Highlight::Annotation),
+                (14, 20, Highlight::None),
+            ]
+        );
+    }
+
+    #[test]
+    fn filters_search_results() {
+        let mut results = vec![
+            result(1, Some("https://a.example/1"), "chrome_bookmark"),
+            result(1, Some("https://a.example/1"), "chrome_bookmark"),
+            result(2, Some("https://b.example/2"), "chrome_bookmark"),
+            result(3, None, "file"),
+        ];
+        results[1].passage = Some((10, 20));
+        results[2].profile = Some("Work".to_string());
+        results[3].similarity = 0.2;
+        let unread = HashSet::from([2, 3]);
+        let mut filters = ResultFilters {
+            similarity_cutoff: 0.0,
+            unread_only: false,
+            unread_ids: &unread,
+            profile: None,
+        };
+        let ids = |filters: &ResultFilters| -> Vec<i64> {
+            filter_results(&results, filters)
+                .iter()
+                .map(|r| r.doc_id)
+                .collect()
+        };
+
+        // Distinct passages of one document are kept, repeats are not
+        assert_eq!(ids(&filters), vec![1, 1, 2, 3]);
+        let repeated = [results[0].clone(), results[0].clone()];
+        assert_eq!(filter_results(&repeated, &filters).len(), 1);
+
+        filters.similarity_cutoff = 0.5;
+        assert_eq!(ids(&filters), vec![1, 1, 2]);
+        filters.unread_only = true;
+        assert_eq!(ids(&filters), vec![2]);
+        filters.unread_only = false;
+        filters.profile = Some("Work");
+        assert_eq!(ids(&filters), vec![2]);
+    }
+
+    #[test]
+    fn checks_domain_patterns_before_adding() {
+        let listed = vec!["*.Example.com".to_string()];
+        assert_eq!(check_domain_pattern("   ", &listed), None);
+        assert_eq!(
+            check_domain_pattern(" news.site.org ", &listed),
+            Some(DomainPatternCheck::Add("news.site.org".to_string()))
+        );
+        assert_eq!(
+            check_domain_pattern("*.example.COM", &listed),
+            Some(DomainPatternCheck::Duplicate("*.example.COM".to_string()))
+        );
+        assert!(matches!(
+            check_domain_pattern("bad pattern", &listed),
+            Some(DomainPatternCheck::Invalid(_))
+        ));
+
+        assert_eq!(exclusion_rules_saved_message(0), "Exclusion rules saved");
+        assert_eq!(
+            exclusion_rules_saved_message(2),
+            "Exclusion rules saved (2 bookmarks moved to the trash)"
+        );
+    }
+
+    #[test]
+    fn tag_edits_split_into_entered_and_typed_tags() {
+        assert_eq!(typed_tag("rust, async, we"), "we");
+        assert_eq!(typed_tag("rust, "), "");
+        assert_eq!(typed_tag(""), "");
+        assert_eq!(entered_tags("rust, , async, we"), vec!["rust", "async"]);
+        assert!(entered_tags("we").is_empty());
+
+        assert_eq!(complete_tag("rust, we", "web"), "rust, web, ");
+        assert_eq!(complete_tag("", "rust"), "rust, ");
+        assert_eq!(complete_tag("rust, ", "async"), "rust, async, ");
+    }
+
+    #[test]
+    fn describes_embedding_status() {
+        let status = |embedded_chunks, chunk_count| {
+            embedding_status(&DocumentMetadata {
+                embedded_chunks,
+                chunk_count,
+                ..Default::default()
+            })
+        };
+        assert_eq!(status(8, Some(8)), "Embedded (8 chunks)");
+        assert_eq!(status(1, None), "Embedded (1 chunk)");
+        assert_eq!(status(3, Some(8)), "Partially embedded (3 of 8 chunks)");
+        assert_eq!(status(0, None), "Not embedded");
+        assert_eq!(status(0, Some(0)), "No content to embed");
+    }
+
+    #[test]
+    fn groups_timeline_by_day_week_and_month() {
+        assert_eq!(
+            timeline_period("2024-03-13 09:30:00", TimelineGrouping::Day),
+            "2024-03-13"
+        );
+        // Weeks start on Monday, across month and year ends
+        assert_eq!(
+            timeline_period("2024-03-13 09:30:00", TimelineGrouping::Week),
+            "2024-03-11"
+        );
+        assert_eq!(
+            timeline_period("2024-03-11 00:00:00", TimelineGrouping::Week),
+            "2024-03-11"
+        );
+        assert_eq!(
+            timeline_period("2025-01-01 12:00:00", TimelineGrouping::Week),
+            "2024-12-30"
+        );
+        assert_eq!(
+            timeline_period("2024-03-01 10:00:00", TimelineGrouping::Week),
+            "2024-02-26"
+        );
+        assert_eq!(
+            timeline_period("2024-03-13 09:30:00", TimelineGrouping::Month),
+            "2024-03"
+        );
+        assert_eq!(
+            timeline_period("1700000000", TimelineGrouping::Month),
+            "1700000000"
+        );
+
+        let dates = [
+            "2024-03-13 09:30:00",
+            "2024-03-11 08:00:00",
+            "2024-03-08 20:00:00",
+            "2024-02-28 10:00:00",
+        ];
+        let groups = timeline_groups(&dates, TimelineGrouping::Week);
+        assert_eq!(
+            groups,
+            vec![
+                ("Week of 2024-03-11".to_string(), 0..2),
+                ("Week of 2024-03-04".to_string(), 2..3),
+                ("Week of 2024-02-26".to_string(), 3..4),
+            ]
+        );
+        let groups = timeline_groups(&dates, TimelineGrouping::Month);
+        assert_eq!(
+            groups,
+            vec![
+                ("March 2024".to_string(), 0..3),
+                ("February 2024".to_string(), 3..4),
+            ]
+        );
+        assert!(timeline_groups(&[], TimelineGrouping::Day).is_empty());
+    }
+
+    #[test]
+    fn passage_location_joins_known_parts() {
+        assert_eq!(passage_location(&ChunkProvenance::default()), None);
+        let provenance = ChunkProvenance {
+            section_heading: Some("Usage".to_string()),
+            section_anchor: Some("usage".to_string()),
+            page_number: Some(14),
+            timestamp_seconds: Some(75),
+            content_hash: Some("hash".to_string()),
+        };
+        assert_eq!(
+            passage_location(&provenance).as_deref(),
+            Some("Usage · p. 14 · 1:15")
+        );
+    }
+
+    #[test]
+    fn section_url_adds_the_anchor_to_web_pages() {
+        let provenance = ChunkProvenance {
+            section_anchor: Some("History".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            section_url("https://en.wikipedia.org/wiki/Rust", &provenance),
+            "https://en.wikipedia.org/wiki/Rust#History"
+        );
+        assert_eq!(
+            section_url("https://example.com/a#top", &provenance),
+            "https://example.com/a#top"
+        );
+        assert_eq!(
+            section_url("file:///notes/a.md", &provenance),
+            "file:///notes/a.md"
+        );
+        assert_eq!(
+            section_url("https://example.com/a", &ChunkProvenance::default()),
+            "https://example.com/a"
+        );
+    }
+}
@@ -1,9 +1,18 @@
 //! Application state types for the egui frontend
 
+use std::collections::VecDeque;
 use std::time::{Duration, Instant};
 
+use serde::{Deserialize, Serialize};
+
+use crate::gui::i18n::{tr, tr_args};
+use crate::services::BookmarkProgress;
+
+/// Number of past toasts kept for the notification history drawer
+const TOAST_HISTORY_LIMIT: usize = 100;
+
 /// Navigation state for the main content area
-#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum View {
     /// Default view showing recent documents
     #[default]
@@ -12,6 +21,111 @@ pub enum View {
     SearchResults,
     /// Full document view
     DocumentDetail,
+    /// Deleted documents waiting to be restored or purged
+    Trash,
+    /// Index counts and local usage insights
+    Stats,
+    /// All documents, or those matching the filter picked in the sidebar
+    Browse,
+    /// Queued bookmark fetches and failed imports
+    Jobs,
+    /// Everything saved, newest first, grouped by day, week or month
+    Timeline,
+    /// Documents plotted on a 2D map of their embeddings
+    Explore,
+}
+
+/// How the timeline groups documents by the date they were saved
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TimelineGrouping {
+    Day,
+    /// Weeks start on Monday
+    Week,
+    #[default]
+    Month,
+}
+
+/// What the browse view lists, picked in the sidebar
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum BrowseFilter {
+    /// Every document, newest first
+    #[default]
+    All,
+    /// Documents saved from one source, e.g. "chrome_bookmark" or a watched folder
+    Source(String),
+    /// Documents with a tag
+    Tag(String),
+    /// Documents in a collection (or matching a smart collection's filter)
+    Collection(i64),
+    /// Bookmarked documents in a bookmark folder or its subfolders, by the
+    /// folder's path
+    Folder(Vec<String>),
+}
+
+/// Entries listed in the sidebar besides collections, loaded in the background
+#[derive(Debug, Clone, Default)]
+pub struct SidebarData {
+    /// Document sources with their counts, most documents first
+    pub sources: Vec<(String, i64)>,
+    /// Tags with their counts, most used first
+    pub tags: Vec<(String, i64)>,
+    /// Bookmark folder tree
+    pub folders: Vec<BookmarkFolderView>,
+    /// Documents in the trash
+    pub trash_count: usize,
+    /// Bookmark fetches queued or in progress
+    pub job_count: usize,
+}
+
+/// Where the user left off, saved in eframe storage on exit and restored
+/// once the backend is ready. eframe keeps the window size and position.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiLayout {
+    pub view: View,
+    /// Document shown in the document view
+    pub document_id: Option<i64>,
+    pub search_query: String,
+    pub similarity_cutoff: f32,
+    pub unread_only: bool,
+    pub selected_profile: Option<String>,
+    pub search_collection: Option<i64>,
+    pub show_dead_documents: bool,
+    pub expand_query: bool,
+    pub boost_linked_results: bool,
+    pub group_by_site: bool,
+    pub show_passages: bool,
+    pub settings_open: bool,
+    pub show_sidebar: bool,
+    /// Filter of the browse view
+    pub browse_filter: BrowseFilter,
+    pub timeline_grouping: TimelineGrouping,
+    /// Search operators and words filtering the timeline
+    pub timeline_filter: String,
+}
+
+impl Default for UiLayout {
+    fn default() -> Self {
+        Self {
+            view: View::Home,
+            document_id: None,
+            search_query: String::new(),
+            similarity_cutoff: 0.3,
+            unread_only: false,
+            selected_profile: None,
+            search_collection: None,
+            show_dead_documents: false,
+            expand_query: false,
+            boost_linked_results: true,
+            group_by_site: true,
+            show_passages: false,
+            settings_open: false,
+            show_sidebar: true,
+            browse_filter: BrowseFilter::All,
+            timeline_grouping: TimelineGrouping::Month,
+            timeline_filter: String::new(),
+        }
+    }
 }
 
 /// Application initialization progress
@@ -39,6 +153,24 @@ pub enum ToastType {
     Error,
 }
 
+/// What happens when a toast's action button is clicked
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ToastAction {
+    /// Open the Settings window
+    OpenSettings,
+    /// Take documents back out of the trash ("Undo")
+    RestoreDocuments(Vec<i64>),
+    /// Open a document
+    OpenDocument(i64),
+}
+
+/// A button shown on a toast
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToastButton {
+    pub label: String,
+    pub action: ToastAction,
+}
+
 /// Notification message with auto-dismiss
 #[derive(Debug, Clone)]
 pub struct Toast {
@@ -52,6 +184,10 @@ pub struct Toast {
     pub created_at: Instant,
     /// Auto-dismiss after (Duration::ZERO = persistent)
     pub duration: Duration,
+    /// Determinate progress (0.0-1.0) shown as a bar under the message
+    pub progress: Option<f32>,
+    /// Action buttons shown next to the message
+    pub buttons: Vec<ToastButton>,
 }
 
 impl Toast {
@@ -68,9 +204,31 @@ impl Toast {
             toast_type,
             created_at: Instant::now(),
             duration,
+            progress: None,
+            buttons: Vec::new(),
         }
     }
 
+    /// Create a persistent info toast with a progress bar
+    pub fn progress(id: u64, message: impl Into<String>, progress: f32) -> Self {
+        Self::new(id, message, ToastType::Info, Duration::ZERO).with_progress(progress)
+    }
+
+    /// Set the progress bar value (clamped to 0.0-1.0)
+    pub fn with_progress(mut self, progress: f32) -> Self {
+        self.progress = Some(progress.clamp(0.0, 1.0));
+        self
+    }
+
+    /// Add an action button
+    pub fn with_button(mut self, label: impl Into<String>, action: ToastAction) -> Self {
+        self.buttons.push(ToastButton {
+            label: label.into(),
+            action,
+        });
+        self
+    }
+
     /// Create an info toast with default 5 second duration
     pub fn info(id: u64, message: impl Into<String>) -> Self {
         Self::new(id, message, ToastType::Info, Duration::from_secs(5))
@@ -88,10 +246,156 @@ impl Toast {
 
     /// Check if this toast should be dismissed
     pub fn is_expired(&self) -> bool {
+        self.is_expired_at(Instant::now())
+    }
+
+    /// Check if this toast should be dismissed at `now`
+    pub fn is_expired_at(&self, now: Instant) -> bool {
         if self.duration == Duration::ZERO {
             return false; // Persistent toast
         }
-        self.created_at.elapsed() >= self.duration
+        now.saturating_duration_since(self.created_at) >= self.duration
+    }
+}
+
+/// A past toast kept for the notification history drawer
+#[derive(Debug, Clone)]
+pub struct ToastRecord {
+    pub message: String,
+    pub toast_type: ToastType,
+    pub created_at: Instant,
+}
+
+impl From<&Toast> for ToastRecord {
+    fn from(toast: &Toast) -> Self {
+        Self {
+            message: toast.message.clone(),
+            toast_type: toast.toast_type,
+            created_at: toast.created_at,
+        }
+    }
+}
+
+/// Active toasts and the notification history
+///
+/// Kept apart from the app so the toast lifecycle (add, update in place,
+/// dismiss, expire) can be tested without a UI.
+#[derive(Debug, Default)]
+pub struct ToastQueue {
+    /// Active toasts, oldest first
+    pub active: Vec<Toast>,
+    /// Past toasts, oldest first, for the notification history drawer
+    pub history: VecDeque<ToastRecord>,
+    /// Counter for generating unique toast IDs
+    next_id: u64,
+}
+
+impl ToastQueue {
+    /// Get next unique toast ID
+    pub fn next_id(&mut self) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    /// Add a toast notification
+    ///
+    /// Progress toasts are kept out of the history; the toast that reports
+    /// the finished operation is recorded instead.
+    pub fn add(&mut self, toast: Toast) {
+        if toast.progress.is_none() {
+            self.history.push_back(ToastRecord::from(&toast));
+            while self.history.len() > TOAST_HISTORY_LIMIT {
+                self.history.pop_front();
+            }
+        }
+        self.active.push(toast);
+    }
+
+    /// Update an active toast's message and progress in place.
+    ///
+    /// Returns false if the toast is no longer shown.
+    pub fn update(&mut self, id: u64, message: impl Into<String>, progress: f32) -> bool {
+        match self.active.iter_mut().find(|t| t.id == id) {
+            Some(toast) => {
+                toast.message = message.into();
+                toast.progress = Some(progress.clamp(0.0, 1.0));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Remove a toast before it expires
+    pub fn dismiss(&mut self, id: u64) {
+        self.active.retain(|t| t.id != id);
+    }
+
+    /// Remove toasts expired at `now`
+    pub fn cleanup(&mut self, now: Instant) {
+        self.active.retain(|t| !t.is_expired_at(now));
+    }
+
+    /// Show progress in the toast whose ID is kept in `slot`, creating it on
+    /// the first event or if it was dismissed
+    pub fn show_progress(&mut self, slot: &mut Option<u64>, message: String, progress: f32) {
+        let updated = slot.is_some_and(|id| self.update(id, message.clone(), progress));
+        if !updated {
+            let id = self.next_id();
+            *slot = Some(id);
+            self.add(Toast::progress(id, message, progress));
+        }
+    }
+
+    /// Remove the progress toast whose ID is kept in `slot`, if any
+    pub fn finish_progress(&mut self, slot: &mut Option<u64>) {
+        if let Some(id) = slot.take() {
+            self.dismiss(id);
+        }
+    }
+}
+
+/// Toasts for bookmark ingestion: one progress toast updated in place while
+/// bookmarks are processed, replaced by a summary when ingestion completes.
+#[derive(Debug, Default)]
+pub struct BookmarkProgressToasts {
+    toast_id: Option<u64>,
+}
+
+impl BookmarkProgressToasts {
+    /// Show a progress event. Returns true when it reports completion.
+    pub fn apply(&mut self, toasts: &mut ToastQueue, progress: &BookmarkProgress) -> bool {
+        if !progress.completed {
+            let fraction = if progress.total > 0 {
+                progress.current as f32 / progress.total as f32
+            } else {
+                0.0
+            };
+            let message = tr_args(
+                "import.processing",
+                &[("current", &progress.current), ("total", &progress.total)],
+            );
+            toasts.show_progress(&mut self.toast_id, message, fraction);
+            return false;
+        }
+
+        toasts.finish_progress(&mut self.toast_id);
+        let id = toasts.next_id();
+        toasts.add(Toast::success(
+            id,
+            tr_args("import.completed", &[("count", &progress.current)]),
+        ));
+        if progress.failed > 0 {
+            let id = toasts.next_id();
+            toasts.add(
+                Toast::error(
+                    id,
+                    tr_args("import.fetch_failed", &[("count", &progress.failed)]),
+                )
+                .with_button(tr("common.view"), ToastAction::OpenSettings),
+            );
+        }
+        true
     }
 }
 
@@ -104,6 +408,16 @@ pub struct ChromeProfileInfo {
     pub display_name: String,
 }
 
+/// Images shown on a document card: the site's favicon (host and PNG) and,
+/// in the thumbnail layout, the page's preview (JPEG). Also carries the
+/// detected language (ISO 639-3) for the badge by the title.
+#[derive(Debug, Clone, Default)]
+pub struct DocumentImages {
+    pub favicon: Option<(String, std::sync::Arc<[u8]>)>,
+    pub thumbnail: Option<std::sync::Arc<[u8]>>,
+    pub language: Option<String>,
+}
+
 /// UI representation of a search result
 #[derive(Debug, Clone)]
 pub struct SearchResultView {
@@ -117,10 +431,65 @@ pub struct SearchResultView {
     pub similarity: f32,
     /// Source URL if available
     pub url: Option<String>,
+    /// Where the document came from (e.g. "chrome_extension", "file")
+    pub source: String,
     /// Chrome profile this bookmark came from
     pub profile: Option<String>,
     /// Whether this document requires authentication to access
     pub is_needs_auth: bool,
+    /// Whether the link checker has marked this document's URL as dead
+    pub is_dead: bool,
+    /// Byte offsets of the matching passage: each hit's own passage for
+    /// passage-level results, the best-matching chunk otherwise
+    pub passage: Option<(usize, usize)>,
+    /// Where a passage-level result sits in its document, e.g. "p. 14"
+    pub location: Option<String>,
+}
+
+/// In-document find bar (Ctrl+F) in the document view
+#[derive(Debug, Clone, Default)]
+pub struct DocumentFind {
+    /// Whether the find bar is shown
+    pub open: bool,
+    /// Text to find, case-insensitively
+    pub query: String,
+    /// Byte offsets of the matches in the displayed content
+    pub matches: Vec<(usize, usize)>,
+    /// Index of the current match
+    pub current: usize,
+    /// Scroll to the current match on the next frame
+    pub scroll_pending: bool,
+    /// Document and query the matches were found for
+    matched: Option<(i64, String)>,
+}
+
+impl DocumentFind {
+    /// Find the query in a document's displayed content, unless the matches
+    /// for this document and query are already known.
+    pub fn refresh(&mut self, doc_id: i64, content: &str) {
+        let key = (doc_id, self.query.clone());
+        if self.matched.as_ref() == Some(&key) {
+            return;
+        }
+        self.matches = super::presenter::find_matches(content, &self.query);
+        self.current = 0;
+        self.scroll_pending = !self.matches.is_empty();
+        self.matched = Some(key);
+    }
+
+    /// Move to the next match, or the previous one, wrapping around.
+    pub fn step(&mut self, forward: bool) {
+        let count = self.matches.len();
+        if count == 0 {
+            return;
+        }
+        self.current = if forward {
+            (self.current + 1) % count
+        } else {
+            (self.current + count - 1) % count
+        };
+        self.scroll_pending = true;
+    }
 }
 
 /// UI representation of a full document
@@ -142,6 +511,19 @@ pub struct DocumentView {
     pub profile: Option<String>,
     /// Whether this document requires authentication to access
     pub is_needs_auth: bool,
+    /// Whether the link checker has marked this document's URL as dead
+    pub is_dead: bool,
+    /// Words in the content
+    pub word_count: usize,
+}
+
+/// Where a document converted to Markdown went
+#[derive(Debug, Clone, PartialEq)]
+pub enum MarkdownExport {
+    /// Markdown to put on the clipboard
+    Clipboard(String),
+    /// File the Markdown was saved to
+    File(std::path::PathBuf),
 }
 
 // ---------------------------------------------------------------------------
@@ -209,3 +591,125 @@ pub struct BookmarkFolderView {
     /// Number of bookmarks in folder
     pub bookmark_count: usize,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn progress(current: usize, total: usize, completed: bool, failed: usize) -> BookmarkProgress {
+        BookmarkProgress {
+            current,
+            total,
+            current_title: String::new(),
+            completed,
+            failed,
+        }
+    }
+
+    #[test]
+    fn toasts_expire_and_progress_stays_out_of_history() {
+        let mut toasts = ToastQueue::default();
+        let info = toasts.next_id();
+        toasts.add(Toast::info(info, "Saved"));
+        let running = toasts.next_id();
+        toasts.add(Toast::progress(running, "Working... 0/2", 0.0));
+        assert_ne!(info, running);
+        assert_eq!(toasts.history.len(), 1);
+
+        assert!(toasts.update(running, "Working... 1/2", 1.5));
+        assert_eq!(toasts.active[1].progress, Some(1.0));
+        assert_eq!(toasts.active[1].message, "Working... 1/2");
+
+        // Info toasts expire, progress toasts stay until dismissed
+        toasts.cleanup(Instant::now() + Duration::from_secs(60));
+        assert_eq!(toasts.active.len(), 1);
+        toasts.dismiss(running);
+        assert!(toasts.active.is_empty());
+        assert!(!toasts.update(running, "Working... 2/2", 1.0));
+    }
+
+    #[test]
+    fn saved_layout_fills_in_missing_fields() {
+        let layout = UiLayout {
+            view: View::DocumentDetail,
+            document_id: Some(42),
+            search_query: "tokio".to_string(),
+            similarity_cutoff: 0.5,
+            settings_open: true,
+            browse_filter: BrowseFilter::Folder(vec!["Bookmarks bar".to_string()]),
+            timeline_grouping: TimelineGrouping::Week,
+            timeline_filter: "tag:rust after:2024-03".to_string(),
+            ..UiLayout::default()
+        };
+        let json = serde_json::to_string(&layout).unwrap();
+        assert_eq!(serde_json::from_str::<UiLayout>(&json).unwrap(), layout);
+
+        // Layouts saved by older versions lack newer fields
+        let old: UiLayout = serde_json::from_str(r#"{"view":"Trash"}"#).unwrap();
+        assert_eq!(old.view, View::Trash);
+        assert_eq!(old.similarity_cutoff, 0.3);
+        assert!(old.group_by_site);
+        assert!(old.show_sidebar);
+        assert_eq!(old.browse_filter, BrowseFilter::All);
+        assert_eq!(old.timeline_grouping, TimelineGrouping::Month);
+    }
+
+    #[test]
+    fn history_is_capped() {
+        let mut toasts = ToastQueue::default();
+        for _ in 0..TOAST_HISTORY_LIMIT + 5 {
+            let id = toasts.next_id();
+            toasts.add(Toast::success(id, format!("Toast {}", id)));
+        }
+        assert_eq!(toasts.history.len(), TOAST_HISTORY_LIMIT);
+        assert_eq!(toasts.history[0].message, "Toast 5");
+    }
+
+    #[test]
+    fn progress_toast_is_reused_and_recreated_after_dismissal() {
+        let mut toasts = ToastQueue::default();
+        let mut slot = None;
+        toasts.show_progress(&mut slot, "Step 1".into(), 0.5);
+        let first = slot.unwrap();
+        toasts.show_progress(&mut slot, "Step 2".into(), 0.75);
+        assert_eq!(slot, Some(first));
+        assert_eq!(toasts.active.len(), 1);
+
+        toasts.dismiss(first);
+        toasts.show_progress(&mut slot, "Step 3".into(), 1.0);
+        assert_ne!(slot, Some(first));
+        assert_eq!(toasts.active.len(), 1);
+
+        toasts.finish_progress(&mut slot);
+        assert_eq!(slot, None);
+        assert!(toasts.active.is_empty());
+    }
+
+    #[test]
+    fn bookmark_progress_ends_in_a_summary() {
+        let mut toasts = ToastQueue::default();
+        let mut bookmarks = BookmarkProgressToasts::default();
+
+        assert!(!bookmarks.apply(&mut toasts, &progress(0, 0, false, 0)));
+        assert_eq!(toasts.active[0].progress, Some(0.0));
+        assert!(!bookmarks.apply(&mut toasts, &progress(3, 4, false, 0)));
+        assert_eq!(toasts.active.len(), 1);
+        assert_eq!(toasts.active[0].message, "Processing bookmarks... 3/4");
+        assert_eq!(toasts.active[0].progress, Some(0.75));
+
+        assert!(bookmarks.apply(&mut toasts, &progress(4, 4, true, 1)));
+        let types: Vec<ToastType> = toasts.active.iter().map(|t| t.toast_type).collect();
+        assert_eq!(types, [ToastType::Success, ToastType::Error]);
+        assert_eq!(
+            toasts.active[1].buttons[0].action,
+            ToastAction::OpenSettings
+        );
+
+        // A clean run adds only the success toast
+        let mut toasts = ToastQueue::default();
+        bookmarks.apply(&mut toasts, &progress(1, 2, false, 0));
+        bookmarks.apply(&mut toasts, &progress(2, 2, true, 0));
+        assert_eq!(toasts.active.len(), 1);
+        assert_eq!(toasts.active[0].message, "Completed! 2 bookmarks ingested");
+    }
+}
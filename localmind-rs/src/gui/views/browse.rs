@@ -0,0 +1,272 @@
+//! Browse view: every document, or those from the source, tag, collection
+//! or bookmark folder picked in the sidebar, newest first
+
+use egui::Ui;
+use egui_remixicon::icons;
+
+use crate::gui::accessibility::Labeled;
+use crate::gui::app::{document_view, LocalMindApp};
+use crate::gui::i18n::{tr, tr_args};
+use crate::gui::state::{BrowseFilter, View};
+use crate::gui::views::home::render_document_card;
+use crate::gui::widgets::document_actions::{self, DocumentAction};
+use crate::gui::widgets::{favicon, thumbnail};
+
+/// Most documents the browse view lists
+const BROWSE_LIMIT: usize = 500;
+
+/// Documents matching a browse filter, newest first, optionally filtered
+/// to a Chrome profile.
+async fn browse_documents(
+    rag: &crate::rag::RagPipeline,
+    filter: &BrowseFilter,
+    profile: Option<String>,
+) -> crate::Result<Vec<crate::db::Document>> {
+    let ids = match filter {
+        BrowseFilter::All => {
+            return rag
+                .db
+                .get_recent_documents_filtered(BROWSE_LIMIT, profile)
+                .await;
+        }
+        BrowseFilter::Source(source) => {
+            return rag
+                .db
+                .get_documents_by_source(source, profile, BROWSE_LIMIT)
+                .await;
+        }
+        BrowseFilter::Tag(tag) => {
+            let filters = crate::query_syntax::ParsedQuery {
+                tags: vec![tag.clone()],
+                ..Default::default()
+            };
+            rag.db.get_filtered_document_ids(&filters).await?
+        }
+        BrowseFilter::Collection(id) => rag.db.get_collection_document_ids(*id).await?,
+        BrowseFilter::Folder(path) => {
+            let urls: Vec<String> = crate::bookmark::BookmarkMonitor::for_settings()
+                .map(|monitors| {
+                    monitors
+                        .iter()
+                        .flat_map(|monitor| monitor.urls_in_folder(path))
+                        .collect()
+                })
+                .unwrap_or_default();
+            rag.db.get_document_ids_by_urls(&urls).await?
+        }
+    };
+    rag.db
+        .get_documents_newest_first(&ids, profile, BROWSE_LIMIT)
+        .await
+}
+
+/// Heading for a browse filter, e.g. "Tag: rust"
+pub fn filter_title(app: &LocalMindApp, filter: &BrowseFilter) -> String {
+    match filter {
+        BrowseFilter::All => tr("browse.all").to_string(),
+        BrowseFilter::Source(source) => {
+            tr_args("browse.source", &[("name", &favicon::source_label(source))])
+        }
+        BrowseFilter::Tag(tag) => tr_args("browse.tag", &[("tag", tag)]),
+        BrowseFilter::Collection(id) => {
+            let name = app
+                .collections
+                .iter()
+                .find(|c| c.id == *id)
+                .map_or("", |c| c.name.as_str());
+            tr_args("browse.collection", &[("name", &name)])
+        }
+        BrowseFilter::Folder(path) => tr_args("browse.folder", &[("path", &path.join(" / "))]),
+    }
+}
+
+/// Render the browse view
+pub fn render_browse_view(ui: &mut Ui, app: &mut LocalMindApp) {
+    ui.add_space(10.0);
+
+    let docs = app.browse_documents.clone();
+    let filter = app.browse_filter.clone();
+
+    // Header with back button, filter and count
+    ui.horizontal(|ui| {
+        let back_button = ui.button(icons::ARROW_LEFT_LINE).labeled(tr("common.back"));
+
+        if back_button.hovered() {
+            ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
+        }
+
+        if back_button.clicked() {
+            app.navigate_back();
+        }
+
+        ui.add_space(10.0);
+        ui.heading(filter_title(app, &filter));
+
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            ui.weak(tr_args("common.documents_count", &[("count", &docs.len())]));
+            if app.is_browse_loading() && !docs.is_empty() {
+                ui.spinner();
+            }
+            thumbnail::layout_toggle(ui, app);
+        });
+    });
+
+    // A smart collection's documents are whatever matches its filter
+    if let BrowseFilter::Collection(id) = filter {
+        if let Some(query) = app
+            .collections
+            .iter()
+            .find(|c| c.id == id)
+            .and_then(|c| c.query.as_deref())
+        {
+            ui.weak(tr_args("collections.smart_filter", &[("filters", &query)]));
+        }
+    }
+
+    ui.add_space(10.0);
+    ui.separator();
+    ui.add_space(10.0);
+
+    if app.is_browse_loading() && docs.is_empty() {
+        ui.vertical_centered(|ui| {
+            ui.add_space(50.0);
+            ui.spinner();
+        });
+        return;
+    }
+
+    if docs.is_empty() {
+        ui.vertical_centered(|ui| {
+            ui.add_space(50.0);
+            ui.label(tr("browse.empty"));
+        });
+        return;
+    }
+
+    // Bulk actions for checked documents
+    document_actions::render_bulk_bar(ui, app);
+
+    let mut clicked_doc_id: Option<i64> = None;
+    let mut star_doc_id: Option<i64> = None;
+    let mut check_doc_id: Option<i64> = None;
+    let mut menu_action: Option<(i64, Option<String>, DocumentAction)> = None;
+
+    egui::ScrollArea::vertical()
+        .id_salt("browse_documents")
+        .auto_shrink([false, false])
+        .show(ui, |ui| {
+            for (i, doc) in docs.iter().enumerate() {
+                let selected = app.selected_index == Some(i);
+                ui.push_id(doc.id, |ui| {
+                    let card = render_document_card(
+                        ui,
+                        doc,
+                        app.document_images(doc.id, doc.url.as_deref()),
+                        &app.tracking_params,
+                        app.is_favorite(doc.id),
+                        app.is_unread(doc.id),
+                        selected,
+                        app.checked_doc_ids.contains(&doc.id),
+                    );
+                    if selected && app.scroll_to_selection {
+                        ui.scroll_to_rect(card.response.rect, None);
+                        app.scroll_to_selection = false;
+                    }
+                    if let Some(action) = document_actions::context_menu(
+                        &card.response,
+                        doc.url.is_some(),
+                        &mut app.tag_input,
+                    ) {
+                        menu_action = Some((doc.id, doc.url.clone(), action));
+                    } else if card.check_toggled {
+                        check_doc_id = Some(doc.id);
+                    } else if card.star_clicked {
+                        star_doc_id = Some(doc.id);
+                    } else if card.response.clicked() {
+                        clicked_doc_id = Some(doc.id);
+                    }
+                });
+                ui.add_space(8.0);
+            }
+        });
+
+    // Handle clicks outside the loop to avoid borrow issues
+    if let Some((doc_id, url, action)) = menu_action {
+        app.run_document_action(ui.ctx(), doc_id, url, action);
+    } else if let Some(doc_id) = check_doc_id {
+        if !app.checked_doc_ids.remove(&doc_id) {
+            app.checked_doc_ids.insert(doc_id);
+        }
+    } else if let Some(doc_id) = star_doc_id {
+        app.toggle_favorite(doc_id);
+    } else if let Some(doc_id) = clicked_doc_id {
+        app.load_document(doc_id);
+    }
+}
+
+impl LocalMindApp {
+    /// Show the browse view listing the documents matching `filter`
+    pub fn open_browse(&mut self, filter: BrowseFilter) {
+        if self.current_view != View::Browse {
+            self.previous_view = self.current_view.clone();
+            self.current_view = View::Browse;
+        }
+        if self.browse_filter != filter {
+            self.browse_documents.clear();
+            self.checked_doc_ids.clear();
+        }
+        self.browse_filter = filter;
+        self.selected_index = None;
+        self.load_browse_documents();
+        self.load_sidebar();
+    }
+
+    /// Load the documents matching the browse filter, newest first,
+    /// replacing any load still running for an earlier filter
+    pub(crate) fn load_browse_documents(&mut self) {
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let runtime_handle = self.runtime.clone();
+        let filter = self.browse_filter.clone();
+        let profile = self.selected_profile.clone();
+
+        runtime_handle.spawn(async move {
+            let rag_lock = rag.read().await;
+            let docs = if let Some(ref rag) = *rag_lock {
+                match browse_documents(rag, &filter, profile).await {
+                    Ok(docs) => docs.into_iter().map(document_view).collect(),
+                    Err(e) => {
+                        eprintln!("Failed to load documents: {}", e);
+                        Vec::new()
+                    }
+                }
+            } else {
+                Vec::new()
+            };
+            let _ = tx.send(docs);
+        });
+
+        self.browse_receiver = Some(rx);
+    }
+
+    /// Check if the browse view's documents have loaded
+    pub(crate) fn check_browse_loaded(&mut self) {
+        if let Some(ref rx) = self.browse_receiver {
+            match rx.try_recv() {
+                Ok(docs) => {
+                    self.browse_documents = docs;
+                    self.browse_receiver = None;
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.browse_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Check if the browse view's documents are loading
+    pub fn is_browse_loading(&self) -> bool {
+        self.browse_receiver.is_some()
+    }
+}
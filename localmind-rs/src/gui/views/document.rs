@@ -4,8 +4,33 @@ use egui::Ui;
 use egui_commonmark::CommonMarkViewer;
 use egui_remixicon::icons;
 
-use crate::gui::app::LocalMindApp;
-use crate::gui::state::View;
+use crate::gui::accessibility::Labeled;
+use crate::gui::app::{document_view, LocalMindApp, DOCUMENT_FIND_INPUT_ID};
+use crate::gui::i18n::{tr, tr_args};
+use crate::gui::presenter::{self, Highlight};
+use crate::gui::state::{DocumentView, MarkdownExport, Toast, View};
+use crate::gui::widgets::{favorite, language, reading_list};
+use crate::html_text::PageStructure;
+use crate::rag::TagSuggestions;
+use crate::services::{delete_highlight, save_highlight};
+
+/// Tags offered in each suggestion list while tags are edited.
+const TAG_SUGGESTION_LIMIT: usize = 6;
+
+/// Where a document was saved from: the directory of a local file, or the
+/// bookmark folder path of a bookmarked URL.
+fn document_folder(url: &str) -> Option<String> {
+    if let Some(path) = url.strip_prefix("file://") {
+        return std::path::Path::new(path)
+            .parent()
+            .map(|dir| dir.display().to_string());
+    }
+    crate::bookmark::BookmarkMonitor::for_settings()
+        .ok()?
+        .iter()
+        .find_map(|monitor| monitor.folder_path_of_url(url))
+        .map(|path| path.join(" / "))
+}
 
 /// Render the document detail view
 pub fn render_document_view(ui: &mut Ui, app: &mut LocalMindApp) {
@@ -16,8 +41,8 @@ pub fn render_document_view(ui: &mut Ui, app: &mut LocalMindApp) {
         Some(doc) => doc.clone(),
         None => {
             // Shouldn't happen, but handle gracefully
-            ui.label("No document selected");
-            let back_button = ui.button(icons::ARROW_LEFT_LINE);
+            ui.label(tr("document.none_selected"));
+            let back_button = ui.button(icons::ARROW_LEFT_LINE).labeled(tr("common.back"));
 
             if back_button.hovered() {
                 ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
@@ -30,10 +55,17 @@ pub fn render_document_view(ui: &mut Ui, app: &mut LocalMindApp) {
         }
     };
 
+    egui::SidePanel::right("document_metadata_panel")
+        .resizable(true)
+        .default_width(260.0)
+        .show_animated_inside(ui, app.show_metadata_panel, |ui| {
+            render_metadata_panel(ui, app, &doc);
+        });
+
     // Header with back button
     ui.horizontal(|ui| {
         // Back button with icon
-        let back_button = ui.button(icons::ARROW_LEFT_LINE);
+        let back_button = ui.button(icons::ARROW_LEFT_LINE).labeled(tr("common.back"));
 
         if back_button.hovered() {
             ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
@@ -48,6 +80,40 @@ pub fn render_document_view(ui: &mut Ui, app: &mut LocalMindApp) {
 
         // Title
         ui.heading(&doc.title);
+
+        if favorite::star_button(ui, app.is_favorite(doc.id)).clicked() {
+            app.toggle_favorite(doc.id);
+        }
+
+        let is_unread = app.is_unread(doc.id);
+        if reading_list::read_toggle_button(ui, is_unread) {
+            app.set_read(vec![doc.id], is_unread);
+        }
+
+        if ui
+            .selectable_label(app.show_metadata_panel, icons::INFORMATION_LINE)
+            .labeled_toggle(app.show_metadata_panel, tr("document.toggle_details"))
+            .clicked()
+        {
+            app.show_metadata_panel = !app.show_metadata_panel;
+        }
+
+        // Clean Markdown with the title and source URL as front matter
+        let exporting = app.is_markdown_export_pending();
+        if ui
+            .add_enabled(!exporting, egui::Button::new(icons::MARKDOWN_LINE))
+            .labeled(tr("document.copy_markdown"))
+            .clicked()
+        {
+            app.export_document_markdown(doc.id, false);
+        }
+        if ui
+            .add_enabled(!exporting, egui::Button::new(icons::FILE_DOWNLOAD_LINE))
+            .labeled(tr("document.export_markdown"))
+            .clicked()
+        {
+            app.export_document_markdown(doc.id, true);
+        }
     });
 
     ui.add_space(10.0);
@@ -62,12 +128,12 @@ pub fn render_document_view(ui: &mut Ui, app: &mut LocalMindApp) {
                 ui.horizontal_wrapped(|ui| {
                     ui.colored_label(
                         egui::Color32::from_rgb(150, 100, 0),
-                        format!("{} This page requires authentication.", icons::LOCK_LINE),
+                        format!("{} {}", icons::LOCK_LINE, tr("document.auth_required")),
                     );
                 });
                 ui.colored_label(
                     egui::Color32::from_rgb(150, 100, 0),
-                    "Open the link below in your browser, log in, then use the Chrome extension to re-capture the content.",
+                    tr("document.auth_help"),
                 );
             });
         ui.add_space(8.0);
@@ -76,12 +142,13 @@ pub fn render_document_view(ui: &mut Ui, app: &mut LocalMindApp) {
     // URL as clickable link
     if let Some(ref url) = doc.url {
         ui.horizontal(|ui| {
-            ui.weak("Source: ");
-            if ui.link(url).clicked() {
-                // Open URL in default browser
-                if let Err(e) = open::that(url) {
-                    eprintln!("Failed to open URL: {}", e);
-                }
+            ui.weak(tr("document.source_label"));
+            if ui
+                .link(url)
+                .on_hover_text(tr("document.open_in_browser"))
+                .clicked()
+            {
+                app.open_in_browser(doc.id, Some(url.clone()));
             }
         });
     }
@@ -93,6 +160,52 @@ pub fn render_document_view(ui: &mut Ui, app: &mut LocalMindApp) {
         ui.weak(&doc.created_at);
     });
 
+    // Collection membership (smart collections pick their own documents)
+    if app.collections.iter().any(|c| !c.is_smart()) {
+        ui.horizontal_wrapped(|ui| {
+            ui.weak(tr("document.collections_label"));
+            let mut toggled = None;
+            for collection in app.collections.iter().filter(|c| !c.is_smart()) {
+                let mut member = app.document_collection_ids.contains(&collection.id);
+                if ui.checkbox(&mut member, &collection.name).changed() {
+                    toggled = Some((collection.id, member));
+                }
+            }
+            if let Some((collection_id, member)) = toggled {
+                app.set_document_in_collection(collection_id, member);
+            }
+        });
+    }
+
+    // Tags (added from the result context menus), each removable
+    if !app.document_tags.is_empty() {
+        ui.horizontal_wrapped(|ui| {
+            ui.weak(tr("document.tags_label"));
+            let mut removed = None;
+            for tag in &app.document_tags {
+                if ui
+                    .small_button(format!("{} x", tag))
+                    .on_hover_text(tr("document.remove_tag"))
+                    .clicked()
+                {
+                    removed = Some(tag.clone());
+                }
+            }
+            if let Some(tag) = removed {
+                app.remove_document_tag(&tag);
+            }
+        });
+    }
+
+    render_linked_documents(ui, app);
+    render_page_structure(ui, app);
+    render_annotations(ui, app);
+    render_versions(ui, app);
+
+    ui.add_space(10.0);
+    render_ask_document(ui, app);
+    render_matching_passages(ui, app);
+
     ui.add_space(10.0);
     ui.separator();
     ui.add_space(10.0);
@@ -115,6 +228,43 @@ pub fn render_document_view(ui: &mut Ui, app: &mut LocalMindApp) {
         None
     };
 
+    // Extract actual content, skipping bookmark metadata if present, with
+    // its byte offset in the stored content
+    let display_content = if markdown_source.is_some() {
+        None
+    } else if doc.content.starts_with("Bookmark:") {
+        doc.content.find("\n\n").and_then(|content_start| {
+            let rest = &doc.content[content_start + 2..];
+            let actual_content = rest.trim();
+            let offset = content_start + 2 + rest.len() - rest.trim_start().len();
+            (!actual_content.is_empty()).then(|| (actual_content.to_string(), offset))
+        })
+    } else {
+        Some((doc.content.clone(), 0))
+    };
+
+    // Find works on the plain-text view only
+    if app.document_find.open {
+        if let Some((content, _)) = &display_content {
+            app.document_find.refresh(doc.id, content);
+            render_find_bar(ui, app);
+            ui.add_space(6.0);
+        }
+    }
+
+    // Text selected on the plain-text view can be saved as a highlight
+    if display_content.is_some() && app.annotation_selection.is_some() {
+        render_highlight_bar(ui, app);
+        ui.add_space(6.0);
+    }
+
+    // A passage to scroll to, once this document (not the previous one) shows
+    let jump = if app.is_document_loading() {
+        None
+    } else {
+        app.jump_to_passage.take()
+    };
+
     // Scrollable content area
     egui::ScrollArea::vertical()
         .auto_shrink([false, false])
@@ -122,33 +272,776 @@ pub fn render_document_view(ui: &mut Ui, app: &mut LocalMindApp) {
             if let Some(md) = markdown_source {
                 // Render Markdown for local .md files
                 CommonMarkViewer::new().show(ui, &mut app.markdown_cache, &md);
+            } else if let Some((content, offset)) = display_content {
+                // Passage offsets are bytes in the stored content; shift them
+                // onto the displayed text
+                let to_display = |(start, end): (usize, usize)| {
+                    let start = start.saturating_sub(offset);
+                    let end = end.checked_sub(offset)?.min(content.len());
+                    (content.is_char_boundary(start) && content.is_char_boundary(end))
+                        .then_some((start, end))
+                };
+                let passage = app.highlighted_passage.and_then(to_display);
+                let jump = jump.and_then(to_display);
+
+                let find = &mut app.document_find;
+                let (matches, current) = if find.open {
+                    (find.matches.as_slice(), Some(find.current))
+                } else {
+                    (&[][..], None)
+                };
+                let annotations: Vec<(usize, usize)> = app
+                    .document_annotations
+                    .iter()
+                    .filter_map(|a| to_display((a.start, a.end)))
+                    .collect();
+                let spans = presenter::highlight_spans(
+                    content.len(),
+                    passage,
+                    matches,
+                    current,
+                    &annotations,
+                );
+                let mut layouter = |ui: &Ui, text: &str, wrap_width: f32| {
+                    let mut job = highlighted_job(ui, text, &spans);
+                    job.wrap.max_width = wrap_width;
+                    ui.fonts(|f| f.layout_job(job))
+                };
+
+                // Read-only, but the text can be selected to highlight it
+                let content_id = ui.make_persistent_id(("document_content", doc.id));
+                if std::mem::take(&mut app.clear_document_selection) {
+                    if let Some(mut state) = egui::TextEdit::load_state(ui.ctx(), content_id) {
+                        state.cursor.set_char_range(None);
+                        state.store(ui.ctx(), content_id);
+                    }
+                }
+                let output = egui::TextEdit::multiline(&mut content.as_str())
+                    .id(content_id)
+                    .desired_width(f32::INFINITY)
+                    .font(egui::TextStyle::Body)
+                    .layouter(&mut layouter)
+                    .show(ui);
+                if let Some(range) = output.cursor_range {
+                    let [start, end] = range.sorted_cursors();
+                    // The galley counts characters, not bytes
+                    let to_byte = |index: usize| {
+                        content
+                            .char_indices()
+                            .nth(index)
+                            .map_or(content.len(), |(byte, _)| byte)
+                    };
+                    let (start, end) = (to_byte(start.ccursor.index), to_byte(end.ccursor.index));
+                    app.annotation_selection =
+                        (start < end).then_some((start + offset, end + offset));
+                }
+
+                // Scroll to the passage opened at, or else to the find match
+                let target = match jump {
+                    Some((start, _)) => Some(start),
+                    None if find.open && find.scroll_pending => {
+                        find.matches.get(find.current).map(|&(start, _)| start)
+                    }
+                    None => None,
+                };
+                find.scroll_pending = false;
+                if let Some(before) = target.and_then(|start| content.get(..start)) {
+                    // The galley counts characters, not bytes
+                    let char_index = before.chars().count();
+                    let cursor = output
+                        .galley
+                        .from_ccursor(egui::text::CCursor::new(char_index));
+                    let rect = output
+                        .galley
+                        .pos_from_cursor(&cursor)
+                        .translate(output.galley_pos.to_vec2());
+                    ui.scroll_to_rect(rect, Some(egui::Align::Center));
+                }
             } else {
-                // Extract actual content, skipping bookmark metadata if present
-                let display_content = if doc.content.starts_with("Bookmark:") {
-                    if let Some(content_start) = doc.content.find("\n\n") {
-                        let actual_content = doc.content[content_start + 2..].trim();
-                        if actual_content.is_empty() {
-                            None
+                ui.label(tr("document.no_content"));
+            }
+        });
+}
+
+/// Lay out document text with highlighted spans as background colors.
+fn highlighted_job(
+    ui: &Ui,
+    text: &str,
+    spans: &[(usize, usize, Highlight)],
+) -> egui::text::LayoutJob {
+    let font_id = egui::TextStyle::Body.resolve(ui.style());
+    let color = ui.visuals().text_color();
+    let mut job = egui::text::LayoutJob::default();
+    for &(start, end, highlight) in spans {
+        // The text is the displayed content the spans were computed on
+        let Some(span) = text.get(start..end) else {
+            continue;
+        };
+        let background = match highlight {
+            Highlight::None => egui::Color32::TRANSPARENT,
+            Highlight::Passage => ui.visuals().selection.bg_fill.gamma_multiply(0.35),
+            Highlight::Match => egui::Color32::from_rgba_unmultiplied(255, 210, 0, 90),
+            Highlight::CurrentMatch => egui::Color32::from_rgba_unmultiplied(255, 140, 0, 170),
+            Highlight::Annotation => egui::Color32::from_rgba_unmultiplied(120, 200, 90, 80),
+        };
+        job.append(
+            span,
+            0.0,
+            egui::TextFormat {
+                font_id: font_id.clone(),
+                color,
+                background,
+                ..Default::default()
+            },
+        );
+    }
+    job
+}
+
+/// Highlight bar for the selected text: an optional note, save and cancel
+fn render_highlight_bar(ui: &mut Ui, app: &mut LocalMindApp) {
+    let saving = app.is_highlight_saving();
+    ui.horizontal(|ui| {
+        ui.label(format!(
+            "{} {}",
+            icons::MARK_PEN_LINE,
+            tr("document.highlight")
+        ));
+        let response = ui
+            .add(
+                egui::TextEdit::singleline(&mut app.annotation_note)
+                    .hint_text(tr("document.highlight_note_hint"))
+                    .desired_width(280.0),
+            )
+            .labeled_field(tr("document.highlight_note"));
+        let enter_pressed = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+        if ui
+            .add_enabled(!saving, egui::Button::new(tr("document.save_highlight")))
+            .clicked()
+            || (enter_pressed && !saving)
+        {
+            app.save_highlight();
+        }
+        if saving {
+            ui.spinner();
+        }
+        if ui
+            .small_button(icons::CLOSE_LINE)
+            .labeled(tr("common.cancel"))
+            .clicked()
+        {
+            app.clear_highlight_selection();
+        }
+    });
+}
+
+/// Saved highlights with their notes; clicking one scrolls the content to
+/// it
+fn render_annotations(ui: &mut Ui, app: &mut LocalMindApp) {
+    if app.document_annotations.is_empty() {
+        return;
+    }
+
+    let mut jump = None;
+    let mut deleted = None;
+    egui::CollapsingHeader::new(tr_args(
+        "document.highlights",
+        &[("count", &app.document_annotations.len())],
+    ))
+    .id_salt("document_highlights")
+    .default_open(true)
+    .show(ui, |ui| {
+        for annotation in &app.document_annotations {
+            ui.horizontal(|ui| {
+                let preview = crate::snippet::centered_snippet(&annotation.quote, "", 100);
+                if ui
+                    .link(preview)
+                    .on_hover_text(tr("document.jump_to_highlight"))
+                    .clicked()
+                {
+                    jump = Some((annotation.start, annotation.end));
+                }
+                if ui
+                    .small_button(icons::DELETE_BIN_LINE)
+                    .labeled(tr("document.delete_highlight"))
+                    .clicked()
+                {
+                    deleted = Some(annotation.id);
+                }
+            });
+            if let Some(ref note) = annotation.note {
+                ui.horizontal_wrapped(|ui| {
+                    ui.add_space(12.0);
+                    ui.label(egui::RichText::new(note).italics().weak());
+                });
+            }
+        }
+    });
+
+    if jump.is_some() {
+        app.jump_to_passage = jump;
+    }
+    if let Some(id) = deleted {
+        app.delete_highlight(id);
+    }
+}
+
+/// Earlier versions of the document, kept when it was fetched again
+fn render_versions(ui: &mut Ui, app: &mut LocalMindApp) {
+    if app.document_versions.is_empty() {
+        return;
+    }
+
+    let mut compared = None;
+    let mut restored = None;
+    let restoring = app.is_version_restoring();
+    egui::CollapsingHeader::new(tr_args(
+        "versions.header",
+        &[("count", &app.document_versions.len())],
+    ))
+    .id_salt("document_versions")
+    .show(ui, |ui| {
+        for version in &app.document_versions {
+            ui.horizontal(|ui| {
+                ui.weak(tr_args("versions.saved_at", &[("date", &version.saved_at)]));
+                if ui.small_button(tr("versions.compare")).clicked() {
+                    compared = Some(version.clone());
+                }
+                if ui
+                    .add_enabled(
+                        !restoring,
+                        egui::Button::new(tr("versions.restore")).small(),
+                    )
+                    .clicked()
+                {
+                    restored = Some(version.id);
+                }
+            });
+        }
+    });
+
+    if let Some(version) = compared {
+        app.compare_version(version);
+    }
+    if let Some(id) = restored {
+        app.restore_version(id);
+    }
+}
+
+/// Find bar: query, match count, previous/next and close
+fn render_find_bar(ui: &mut Ui, app: &mut LocalMindApp) {
+    let find = &mut app.document_find;
+    ui.horizontal(|ui| {
+        let response = ui
+            .add(
+                egui::TextEdit::singleline(&mut find.query)
+                    .id(egui::Id::new(DOCUMENT_FIND_INPUT_ID))
+                    .hint_text(tr("document.find_hint"))
+                    .desired_width(240.0),
+            )
+            .labeled_field(tr("document.find_hint"));
+
+        // Enter (Shift+Enter) moves to the next (previous) match, keeping focus
+        if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+            let backward = ui.input(|i| i.modifiers.shift);
+            find.step(!backward);
+            response.request_focus();
+        }
+
+        if find.query.is_empty() {
+            // Nothing to count
+        } else if find.matches.is_empty() {
+            ui.weak(tr("document.no_matches"));
+        } else {
+            ui.weak(tr_args(
+                "document.match_position",
+                &[
+                    ("current", &(find.current + 1)),
+                    ("total", &find.matches.len()),
+                ],
+            ));
+        }
+
+        let has_matches = !find.matches.is_empty();
+        if ui
+            .add_enabled(has_matches, egui::Button::new(icons::ARROW_UP_S_LINE))
+            .labeled(tr("document.previous_match"))
+            .clicked()
+        {
+            find.step(false);
+        }
+        if ui
+            .add_enabled(has_matches, egui::Button::new(icons::ARROW_DOWN_S_LINE))
+            .labeled(tr("document.next_match"))
+            .clicked()
+        {
+            find.step(true);
+        }
+        if ui
+            .button(icons::CLOSE_LINE)
+            .labeled(tr("document.close_find"))
+            .clicked()
+        {
+            find.open = false;
+        }
+    });
+}
+
+/// Metadata sidebar: where the document came from and how it is indexed,
+/// with inline editing of the title and tags
+fn render_metadata_panel(ui: &mut Ui, app: &mut LocalMindApp, doc: &DocumentView) {
+    let mut search_language = None;
+    ui.add_space(4.0);
+    ui.strong(tr("document.details"));
+    ui.add_space(6.0);
+
+    egui::ScrollArea::vertical()
+        .id_salt("document_metadata_scroll")
+        .show(ui, |ui| {
+            egui::Grid::new("document_metadata_grid")
+                .num_columns(2)
+                .spacing([8.0, 6.0])
+                .show(ui, |ui| {
+                    ui.weak(tr("document.title"));
+                    render_title_edit(ui, app, doc);
+                    ui.end_row();
+
+                    ui.weak(tr("document.url"));
+                    match doc.url {
+                        Some(ref url) => {
+                            let link = egui::Label::new(
+                                egui::RichText::new(crate::tracking_params::strip(
+                                    url,
+                                    &app.tracking_params,
+                                ))
+                                .color(ui.visuals().hyperlink_color),
+                            )
+                            .truncate()
+                            .sense(egui::Sense::click());
+                            if ui.add(link).on_hover_text(url).clicked() {
+                                app.open_in_browser(doc.id, Some(url.clone()));
+                            }
+                        }
+                        None => {
+                            ui.weak("-");
+                        }
+                    }
+                    ui.end_row();
+
+                    ui.weak(tr("document.source"));
+                    match doc.profile {
+                        Some(ref profile) => ui.label(format!("{} ({})", doc.source, profile)),
+                        None => ui.label(&doc.source),
+                    };
+                    ui.end_row();
+
+                    ui.weak(tr("document.saved"));
+                    ui.label(&doc.created_at);
+                    ui.end_row();
+
+                    ui.weak(tr("document.folder"));
+                    match app.document_folder {
+                        Some(ref folder) => ui.label(folder),
+                        None => ui.weak("-"),
+                    };
+                    ui.end_row();
+
+                    ui.weak(tr("document.tags"));
+                    render_tags_edit(ui, app);
+                    ui.end_row();
+
+                    ui.weak(tr("document.words"));
+                    ui.label(doc.word_count.to_string());
+                    ui.end_row();
+
+                    if let Some(ref metadata) = app.document_metadata {
+                        ui.weak(tr("document.chunks"));
+                        match metadata.chunk_count {
+                            Some(count) => ui.label(count.to_string()),
+                            None => ui.weak("-"),
+                        };
+                        ui.end_row();
+
+                        ui.weak(tr("document.embedding"));
+                        ui.label(presenter::embedding_status(metadata));
+                        ui.end_row();
+
+                        ui.weak(tr("document.views"));
+                        ui.label(metadata.view_count.to_string());
+                        ui.end_row();
+
+                        ui.weak(tr("document.language"));
+                        match metadata.language {
+                            Some(ref code) => {
+                                let language = language::language_name(code);
+                                if ui
+                                    .link(language)
+                                    .on_hover_text(tr("document.language_hint"))
+                                    .clicked()
+                                {
+                                    search_language = Some(code.clone());
+                                }
+                            }
+                            None => {
+                                ui.weak("-");
+                            }
+                        }
+                        ui.end_row();
+
+                        if let Some(ref summary) = metadata.summary {
+                            ui.weak(tr("document.summary"));
+                            ui.add(egui::Label::new(summary).wrap());
+                            ui.end_row();
+                        }
+                    }
+
+                    ui.weak(tr("document.link"));
+                    ui.vertical(|ui| {
+                        if doc.is_dead {
+                            ui.colored_label(ui.visuals().error_fg_color, tr("document.link_dead"));
                         } else {
-                            Some(actual_content.to_string())
+                            ui.label(tr("document.link_alive"));
+                        }
+                        if let Some(checked) = app
+                            .document_metadata
+                            .as_ref()
+                            .and_then(|m| m.link_checked_at.as_ref())
+                        {
+                            ui.weak(tr_args("document.link_checked", &[("time", checked)]));
                         }
+                    });
+                    ui.end_row();
+                });
+        });
+
+    if let Some(code) = search_language {
+        app.search_instead(format!("lang:{}", code));
+    }
+}
+
+/// Title cell of the metadata panel: the title with a rename button, or an
+/// input while renaming
+fn render_title_edit(ui: &mut Ui, app: &mut LocalMindApp, doc: &DocumentView) {
+    let (mut save, mut cancel) = (false, false);
+    ui.horizontal_wrapped(|ui| match app.title_edit {
+        Some(ref mut title) => {
+            let response = ui
+                .add(egui::TextEdit::singleline(title).desired_width(150.0))
+                .labeled_field(tr("document.title"));
+            save = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            save |= ui
+                .small_button(icons::CHECK_LINE)
+                .labeled(tr("common.save"))
+                .clicked();
+            cancel = ui
+                .small_button(icons::CLOSE_LINE)
+                .labeled(tr("common.cancel"))
+                .clicked();
+        }
+        None => {
+            ui.label(&doc.title);
+            if ui
+                .small_button(icons::PENCIL_LINE)
+                .labeled(tr("document.rename"))
+                .clicked()
+            {
+                app.title_edit = Some(doc.title.clone());
+            }
+        }
+    });
+
+    if save {
+        app.save_document_title();
+    } else if cancel {
+        app.title_edit = None;
+    }
+}
+
+/// Tags cell of the metadata panel: the tags with an edit button, or a
+/// comma-separated input while editing, with existing tags matching the one
+/// being typed and tags from similar documents offered below it
+fn render_tags_edit(ui: &mut Ui, app: &mut LocalMindApp) {
+    app.refresh_tag_suggestions();
+    let (mut save, mut cancel) = (false, false);
+    let mut chosen: Option<String> = None;
+    ui.vertical(|ui| {
+        ui.horizontal_wrapped(|ui| match app.tags_edit {
+            Some(ref mut tags) => {
+                let response = ui
+                    .add(
+                        egui::TextEdit::singleline(tags)
+                            .hint_text(tr("document.tags_hint"))
+                            .desired_width(150.0),
+                    )
+                    .labeled_field(tr("document.tags"));
+                save = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                save |= ui
+                    .small_button(icons::CHECK_LINE)
+                    .labeled(tr("common.save"))
+                    .clicked();
+                cancel = ui
+                    .small_button(icons::CLOSE_LINE)
+                    .labeled(tr("common.cancel"))
+                    .clicked();
+            }
+            None => {
+                if app.document_tags.is_empty() {
+                    ui.weak(tr("common.none"));
+                } else {
+                    ui.label(app.document_tags.join(", "));
+                }
+                if ui
+                    .small_button(icons::PENCIL_LINE)
+                    .labeled(tr("document.edit_tags"))
+                    .clicked()
+                {
+                    // Start after the existing tags, ready for a new one
+                    let tags = app.document_tags.join(", ");
+                    app.tags_edit = Some(if tags.is_empty() {
+                        tags
                     } else {
-                        None
+                        format!("{}, ", tags)
+                    });
+                }
+            }
+        });
+
+        if app.tags_edit.is_none() {
+            return;
+        }
+        let suggestions = &app.tag_suggestions;
+        if !suggestions.matching.is_empty() {
+            ui.horizontal_wrapped(|ui| {
+                for (tag, count) in &suggestions.matching {
+                    if ui
+                        .small_button(format!("{} ({})", tag, count))
+                        .on_hover_text(tr("document.use_tag"))
+                        .clicked()
+                    {
+                        chosen = Some(tag.clone());
                     }
-                } else {
-                    Some(doc.content.clone())
+                }
+            });
+        }
+        if !suggestions.related.is_empty() {
+            ui.horizontal_wrapped(|ui| {
+                ui.weak(tr("document.suggested_tags"));
+                for tag in &suggestions.related {
+                    if ui
+                        .small_button(tag)
+                        .on_hover_text(tr("document.suggested_tags_hint"))
+                        .clicked()
+                    {
+                        chosen = Some(tag.clone());
+                    }
+                }
+            });
+        }
+    });
+
+    if save {
+        app.save_document_tags();
+    } else if cancel {
+        app.tags_edit = None;
+    } else if let (Some(tag), Some(edit)) = (chosen, app.tags_edit.as_mut()) {
+        *edit = presenter::complete_tag(edit, &tag);
+    }
+}
+
+/// "Links to" / "Linked from" rows; clicking a title opens that document
+fn render_linked_documents(ui: &mut Ui, app: &mut LocalMindApp) {
+    let mut open = None;
+    for (label, docs) in [
+        (tr("document.links_to"), &app.document_links_to),
+        (tr("document.linked_from"), &app.document_linked_from),
+    ] {
+        if docs.is_empty() {
+            continue;
+        }
+        ui.horizontal_wrapped(|ui| {
+            ui.weak(label);
+            for doc in docs {
+                let response = ui.link(&doc.title);
+                let response = match doc.url {
+                    Some(ref url) => response.on_hover_text(url),
+                    None => response,
                 };
+                if response.clicked() {
+                    open = Some(doc.id);
+                }
+            }
+        });
+    }
+    if let Some(doc_id) = open {
+        app.load_document(doc_id);
+    }
+}
 
-                if let Some(mut content) = display_content {
-                    ui.add(
-                        egui::TextEdit::multiline(&mut content)
-                            .desired_width(f32::INFINITY)
-                            .font(egui::TextStyle::Body)
-                            .interactive(false),
-                    );
+/// Outline and links of a page saved from HTML, or the pages of a PDF;
+/// clicking a heading or page scrolls the content to it, clicking a link
+/// opens it in the browser
+fn render_page_structure(ui: &mut Ui, app: &mut LocalMindApp) {
+    let structure = &app.document_structure;
+    let mut jump = None;
+
+    if !structure.headings.is_empty() {
+        egui::CollapsingHeader::new(tr_args(
+            "document.outline",
+            &[("count", &structure.headings.len())],
+        ))
+        .id_salt("document_outline")
+        .default_open(false)
+        .show(ui, |ui| {
+            for heading in &structure.headings {
+                ui.horizontal(|ui| {
+                    ui.add_space(f32::from(heading.level.saturating_sub(1)) * 12.0);
+                    if ui
+                        .link(&heading.text)
+                        .on_hover_text(tr("document.jump_to_heading"))
+                        .clicked()
+                    {
+                        jump = Some((heading.offset, heading.offset + heading.text.len()));
+                    }
+                });
+            }
+        });
+    }
+
+    if !structure.pages.is_empty() {
+        egui::CollapsingHeader::new(tr_args(
+            "document.pages",
+            &[("count", &structure.pages.len())],
+        ))
+        .id_salt("document_pages")
+        .default_open(false)
+        .show(ui, |ui| {
+            ui.horizontal_wrapped(|ui| {
+                for page in &structure.pages {
+                    if ui
+                        .link(page.number.to_string())
+                        .on_hover_text(tr("document.jump_to_page"))
+                        .clicked()
+                    {
+                        jump = Some((page.offset, page.offset + page.first_line.len()));
+                    }
+                }
+            });
+        });
+    }
+
+    if !structure.links.is_empty() {
+        egui::CollapsingHeader::new(tr_args(
+            "document.page_links",
+            &[("count", &structure.links.len())],
+        ))
+        .id_salt("document_page_links")
+        .default_open(false)
+        .show(ui, |ui| {
+            for link in &structure.links {
+                let text = if link.text.is_empty() {
+                    &link.href
                 } else {
-                    ui.label("No content available for this bookmark.");
+                    &link.text
+                };
+                ui.hyperlink_to(text, &link.href).on_hover_text(&link.href);
+            }
+        });
+    }
+
+    if jump.is_some() {
+        app.jump_to_passage = jump;
+    }
+}
+
+/// Passages matching the search the document was opened from; clicking one
+/// scrolls the content to it
+fn render_matching_passages(ui: &mut Ui, app: &mut LocalMindApp) {
+    if app.document_passages.is_empty() {
+        return;
+    }
+
+    let mut jump = None;
+    ui.add_space(6.0);
+    egui::CollapsingHeader::new(tr_args(
+        "document.matching_passages",
+        &[("count", &app.document_passages.len())],
+    ))
+    .id_salt("document_matching_passages")
+    .default_open(true)
+    .show(ui, |ui| {
+        for passage in &app.document_passages {
+            ui.horizontal(|ui| {
+                ui.weak(format!("{:.0}%", passage.similarity * 100.0));
+                if let Some(location) = presenter::passage_location(&passage.provenance) {
+                    ui.weak(location);
+                }
+                let preview =
+                    crate::snippet::centered_snippet(&passage.text, &app.search_query, 100);
+                if ui
+                    .link(preview)
+                    .on_hover_text(tr("document.jump_to_passage"))
+                    .clicked()
+                {
+                    jump = Some((passage.chunk_start, passage.chunk_end));
                 }
+            });
+        }
+    });
+
+    if jump.is_some() {
+        app.jump_to_passage = jump;
+        app.highlighted_passage = jump;
+    }
+}
+
+/// "Ask this document" box: question input, answer, and cited passages
+fn render_ask_document(ui: &mut Ui, app: &mut LocalMindApp) {
+    ui.horizontal(|ui| {
+        let response = ui
+            .add(
+                egui::TextEdit::singleline(&mut app.document_question)
+                    .hint_text(tr("document.ask_hint"))
+                    .desired_width(ui.available_width() - 80.0),
+            )
+            .labeled_field(tr("document.ask_hint"));
+        let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+
+        if app.is_answering_document() {
+            ui.spinner();
+        } else if ui.button(tr("document.ask")).clicked() || submitted {
+            app.ask_document();
+        }
+    });
+
+    let Some(answer) = &app.document_answer else {
+        return;
+    };
+
+    ui.add_space(6.0);
+    egui::Frame::none()
+        .fill(ui.visuals().faint_bg_color)
+        .rounding(4.0)
+        .inner_margin(10.0)
+        .show(ui, |ui| {
+            ui.label(&answer.answer);
+
+            if !answer.citations.is_empty() {
+                ui.add_space(6.0);
+                egui::CollapsingHeader::new(tr_args(
+                    "document.answer_passages",
+                    &[("count", &answer.citations.len())],
+                ))
+                .id_salt("document_answer_citations")
+                .show(ui, |ui| {
+                    for (i, citation) in answer.citations.iter().enumerate() {
+                        ui.horizontal_wrapped(|ui| {
+                            ui.strong(format!("[{}]", i + 1));
+                            ui.weak(format!("{:.0}%", citation.similarity * 100.0));
+                        });
+                        ui.label(&citation.text);
+                        ui.add_space(4.0);
+                    }
+                });
             }
         });
 }
@@ -181,3 +1074,750 @@ fn strip_frontmatter(content: &str) -> &str {
         content
     }
 }
+
+impl LocalMindApp {
+    /// Load a document by ID for viewing
+    pub fn load_document(&mut self, doc_id: i64) {
+        if self.document_receiver.is_some() {
+            return; // Already loading
+        }
+
+        println!("Loading document: {}", doc_id);
+
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let runtime_handle = self.runtime.clone();
+
+        runtime_handle.spawn(async move {
+            let rag_lock = rag.read().await;
+            let doc = if let Some(ref rag) = *rag_lock {
+                // A highlight's document opens the highlighted one at it
+                let (doc_id, highlight) =
+                    match rag.db.get_annotation_by_highlight_document(doc_id).await {
+                        Ok(Some(annotation)) => (
+                            annotation.document_id,
+                            Some((annotation.start, annotation.end)),
+                        ),
+                        Ok(None) => (doc_id, None),
+                        Err(e) => {
+                            eprintln!("Failed to look up highlight: {}", e);
+                            (doc_id, None)
+                        }
+                    };
+                rag.note_document_viewed(doc_id);
+                if let Err(e) = rag.db.record_document_view(doc_id).await {
+                    eprintln!("Failed to record document view: {}", e);
+                }
+                match rag.db.get_document(doc_id).await {
+                    Ok(Some(doc)) => Some((document_view(doc), highlight)),
+                    Ok(None) => {
+                        eprintln!("Document not found: {}", doc_id);
+                        None
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to load document: {}", e);
+                        None
+                    }
+                }
+            } else {
+                None
+            };
+            let _ = tx.send(doc);
+        });
+
+        // Opened from search results: list the passages matching the search
+        if self.current_view == View::SearchResults {
+            let query = self
+                .corrected_query
+                .clone()
+                .unwrap_or_else(|| self.search_query.trim().to_string());
+            self.load_document_passages(doc_id, query);
+        } else {
+            self.document_passages.clear();
+            self.document_passages_receiver = None;
+            self.jump_to_passage = None;
+        }
+        self.highlighted_passage = None;
+
+        self.document_receiver = Some(rx);
+        self.previous_view = self.current_view.clone();
+        self.current_view = View::DocumentDetail;
+    }
+
+    /// Open a document and scroll to one of its passages (byte offsets),
+    /// highlighting it
+    pub fn load_document_at(&mut self, doc_id: i64, passage: Option<(usize, usize)>) {
+        self.load_document(doc_id);
+        self.jump_to_passage = passage;
+        self.highlighted_passage = passage;
+    }
+
+    /// Show the in-document find bar and focus its input
+    pub(crate) fn open_document_find(&mut self, ctx: &egui::Context) {
+        self.document_find.open = true;
+        self.document_find.scroll_pending = !self.document_find.matches.is_empty();
+        ctx.memory_mut(|m| m.request_focus(egui::Id::new(DOCUMENT_FIND_INPUT_ID)));
+    }
+
+    /// Load the passages of a document matching `query`
+    pub(crate) fn load_document_passages(&mut self, doc_id: i64, query: String) {
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let runtime_handle = self.runtime.clone();
+
+        runtime_handle.spawn(async move {
+            let rag_lock = rag.read().await;
+            if let Some(ref rag) = *rag_lock {
+                match rag.document_passages(doc_id, &query).await {
+                    Ok(passages) => {
+                        let _ = tx.send(passages);
+                    }
+                    Err(e) => eprintln!("Failed to load matching passages: {}", e),
+                }
+            }
+        });
+
+        self.document_passages.clear();
+        self.document_passages_receiver = Some(rx);
+    }
+
+    /// Check if the selected document's matching passages have loaded
+    pub(crate) fn check_document_passages_loaded(&mut self) {
+        if let Some(ref rx) = self.document_passages_receiver {
+            match rx.try_recv() {
+                Ok(passages) => {
+                    self.document_passages = passages;
+                    self.document_passages_receiver = None;
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.document_passages_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Check if a document has been loaded
+    pub(crate) fn check_document_loaded(&mut self) {
+        if let Some(ref rx) = self.document_receiver {
+            match rx.try_recv() {
+                Ok(Some((doc, highlight))) => {
+                    println!("Document loaded: {}", doc.title);
+                    let doc_id = doc.id;
+                    let url = doc.url.clone();
+                    self.selected_document = Some(doc);
+                    if highlight.is_some() {
+                        self.jump_to_passage = highlight;
+                        self.highlighted_passage = highlight;
+                    }
+                    self.load_document_collections(doc_id);
+                    self.load_document_tags(doc_id);
+                    self.load_document_annotations(doc_id);
+                    self.load_document_versions(doc_id);
+                    self.load_document_links(doc_id);
+                    self.load_document_metadata(doc_id, url);
+                    self.title_edit = None;
+                    self.tags_edit = None;
+                    // Opening a document marks it read
+                    self.set_read(vec![doc_id], true);
+                    self.document_question.clear();
+                    self.document_answer = None;
+                    self.document_answer_receiver = None;
+                    self.document_receiver = None;
+                }
+                Ok(None) => {
+                    // Document not found, go back
+                    eprintln!("Document not found, going back");
+                    self.current_view = self.previous_view.clone();
+                    self.document_receiver = None;
+
+                    let id = self.next_toast_id();
+                    self.add_toast(Toast::error(id, tr("document.not_found")));
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {
+                    // Still loading
+                }
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    // Channel closed, clear receiver
+                    self.document_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Ask a question about the selected document
+    pub fn ask_document(&mut self) {
+        let question = self.document_question.trim().to_string();
+        let Some(doc_id) = self.selected_document.as_ref().map(|d| d.id) else {
+            return;
+        };
+        if question.is_empty() || self.document_answer_receiver.is_some() {
+            return;
+        }
+
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let runtime_handle = self.runtime.clone();
+
+        runtime_handle.spawn(async move {
+            let rag_lock = rag.read().await;
+            let result = if let Some(ref rag) = *rag_lock {
+                rag.ask_document(doc_id, &question)
+                    .await
+                    .map_err(|e| e.to_string())
+            } else {
+                Err(tr("common.initializing").to_string())
+            };
+            let _ = tx.send(result);
+        });
+
+        self.document_answer_receiver = Some(rx);
+    }
+
+    /// Check if a document question has been answered
+    pub(crate) fn check_document_answer(&mut self) {
+        if let Some(ref rx) = self.document_answer_receiver {
+            match rx.try_recv() {
+                Ok(Ok(answer)) => {
+                    self.document_answer = Some(answer);
+                    self.document_answer_receiver = None;
+                }
+                Ok(Err(e)) => {
+                    eprintln!("Document question failed: {}", e);
+                    self.document_answer_receiver = None;
+
+                    let id = self.next_toast_id();
+                    self.add_toast(Toast::error(
+                        id,
+                        tr_args("document.answer_failed", &[("error", &e)]),
+                    ));
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {
+                    // Still answering
+                }
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.document_answer_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Check if a document question is being answered
+    pub fn is_answering_document(&self) -> bool {
+        self.document_answer_receiver.is_some()
+    }
+
+    /// Convert a document's stored content to Markdown with its title and
+    /// source URL as front matter, then copy it to the clipboard or save it
+    /// to a file in the Downloads folder
+    pub fn export_document_markdown(&mut self, doc_id: i64, to_file: bool) {
+        if self.markdown_export_receiver.is_some() {
+            return;
+        }
+
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            let result = match *rag_lock {
+                Some(ref rag) => match rag.db.get_document(doc_id).await {
+                    Ok(Some(doc)) => {
+                        let markdown = crate::markdown::document_markdown(
+                            &doc.title,
+                            doc.url.as_deref(),
+                            &doc.source,
+                            &doc.created_at,
+                            &doc.content,
+                        );
+                        if to_file {
+                            let dir = crate::markdown::export_dir();
+                            crate::markdown::export_to(&dir, &doc.title, &markdown)
+                                .map(MarkdownExport::File)
+                                .map_err(|e| e.to_string())
+                        } else {
+                            Ok(MarkdownExport::Clipboard(markdown))
+                        }
+                    }
+                    Ok(None) => Err(tr_args("document.not_found_id", &[("id", &doc_id)])),
+                    Err(e) => Err(e.to_string()),
+                },
+                None => Err(tr("common.not_ready").to_string()),
+            };
+            let _ = tx.send(result);
+        });
+
+        self.markdown_export_receiver = Some(rx);
+    }
+
+    /// Check if a Markdown copy or export has finished
+    pub(crate) fn check_markdown_export(&mut self, ctx: &egui::Context) {
+        if let Some(ref rx) = self.markdown_export_receiver {
+            match rx.try_recv() {
+                Ok(result) => {
+                    self.markdown_export_receiver = None;
+                    let id = self.next_toast_id();
+                    match result {
+                        Ok(MarkdownExport::Clipboard(markdown)) => {
+                            ctx.copy_text(markdown);
+                            self.add_toast(Toast::success(id, tr("document.markdown_copied")));
+                        }
+                        Ok(MarkdownExport::File(path)) => {
+                            self.add_toast(Toast::success(
+                                id,
+                                tr_args("document.markdown_saved", &[("path", &path.display())]),
+                            ));
+                        }
+                        Err(e) => {
+                            eprintln!("Markdown export failed: {}", e);
+                            self.add_toast(Toast::error(
+                                id,
+                                tr_args("document.markdown_failed", &[("error", &e)]),
+                            ));
+                        }
+                    }
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.markdown_export_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Check if a Markdown copy or export is in progress
+    pub fn is_markdown_export_pending(&self) -> bool {
+        self.markdown_export_receiver.is_some()
+    }
+
+    /// Load the tags on a document
+    pub(crate) fn load_document_tags(&mut self, doc_id: i64) {
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let runtime_handle = self.runtime.clone();
+
+        runtime_handle.spawn(async move {
+            let rag_lock = rag.read().await;
+            let tags = if let Some(ref rag) = *rag_lock {
+                rag.db.get_document_tags(doc_id).await.unwrap_or_else(|e| {
+                    eprintln!("Failed to load document tags: {}", e);
+                    Vec::new()
+                })
+            } else {
+                Vec::new()
+            };
+            let _ = tx.send(tags);
+        });
+
+        self.document_tags.clear();
+        self.document_tags_receiver = Some(rx);
+    }
+
+    /// Load the highlights saved on a document
+    pub(crate) fn load_document_annotations(&mut self, doc_id: i64) {
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let runtime_handle = self.runtime.clone();
+
+        runtime_handle.spawn(async move {
+            let rag_lock = rag.read().await;
+            let annotations = if let Some(ref rag) = *rag_lock {
+                rag.db
+                    .get_document_annotations(doc_id)
+                    .await
+                    .unwrap_or_else(|e| {
+                        eprintln!("Failed to load document highlights: {}", e);
+                        Vec::new()
+                    })
+            } else {
+                Vec::new()
+            };
+            let _ = tx.send(annotations);
+        });
+
+        self.document_annotations.clear();
+        self.clear_highlight_selection();
+        self.document_annotations_receiver = Some(rx);
+    }
+
+    /// Check if the selected document's highlights have been loaded
+    pub(crate) fn check_document_annotations_loaded(&mut self) {
+        if let Some(ref rx) = self.document_annotations_receiver {
+            match rx.try_recv() {
+                Ok(annotations) => {
+                    self.document_annotations = annotations;
+                    self.document_annotations_receiver = None;
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.document_annotations_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Drop the document view's text selection and the note typed for it
+    pub fn clear_highlight_selection(&mut self) {
+        self.annotation_selection = None;
+        self.annotation_note.clear();
+        self.clear_document_selection = true;
+    }
+
+    /// Save the text selected in the document view as a highlight, with the
+    /// note typed in the highlight bar
+    pub fn save_highlight(&mut self) {
+        let (Some(doc_id), Some(range)) = (
+            self.selected_document.as_ref().map(|d| d.id),
+            self.annotation_selection,
+        ) else {
+            return;
+        };
+        if self.annotation_save_receiver.is_some() {
+            return;
+        }
+
+        let rag = self.rag.clone();
+        let note = self.annotation_note.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.runtime.spawn(async move {
+            let result = save_highlight(&rag, doc_id, range, &note)
+                .await
+                .map_err(|e| e.to_string());
+            let _ = tx.send(result);
+        });
+        self.annotation_save_receiver = Some(rx);
+    }
+
+    /// Check if a highlight is being saved
+    pub fn is_highlight_saving(&self) -> bool {
+        self.annotation_save_receiver.is_some()
+    }
+
+    /// Check if a highlight has been saved and show it on the document
+    pub(crate) fn check_highlight_saved(&mut self) {
+        if let Some(ref rx) = self.annotation_save_receiver {
+            match rx.try_recv() {
+                Ok(Ok(annotation)) => {
+                    self.annotation_save_receiver = None;
+                    if self
+                        .selected_document
+                        .as_ref()
+                        .is_some_and(|d| d.id == annotation.document_id)
+                    {
+                        let index = self
+                            .document_annotations
+                            .partition_point(|a| a.start <= annotation.start);
+                        self.document_annotations.insert(index, annotation);
+                    }
+                    self.clear_highlight_selection();
+                    let id = self.next_toast_id();
+                    self.add_toast(Toast::success(id, tr("document.highlight_saved")));
+                }
+                Ok(Err(e)) => {
+                    self.annotation_save_receiver = None;
+                    let id = self.next_toast_id();
+                    self.add_toast(Toast::error(
+                        id,
+                        tr_args("document.highlight_failed", &[("error", &e)]),
+                    ));
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.annotation_save_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Delete a highlight from the selected document
+    pub fn delete_highlight(&mut self, annotation_id: i64) {
+        self.document_annotations.retain(|a| a.id != annotation_id);
+
+        let rag = self.rag.clone();
+        self.runtime.spawn(async move {
+            if let Err(e) = delete_highlight(&rag, annotation_id).await {
+                eprintln!("Failed to delete highlight: {}", e);
+            }
+        });
+    }
+
+    /// Check if the selected document's tags have been loaded
+    pub(crate) fn check_document_tags_loaded(&mut self) {
+        if let Some(ref rx) = self.document_tags_receiver {
+            match rx.try_recv() {
+                Ok(tags) => {
+                    self.document_tags = tags;
+                    self.document_tags_receiver = None;
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.document_tags_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Load the documents linked to and from a document, and its headings
+    /// and anchors
+    pub(crate) fn load_document_links(&mut self, doc_id: i64) {
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let runtime_handle = self.runtime.clone();
+
+        runtime_handle.spawn(async move {
+            let rag_lock = rag.read().await;
+            let links = if let Some(ref rag) = *rag_lock {
+                let links_to = rag
+                    .db
+                    .get_outgoing_linked_documents(doc_id)
+                    .await
+                    .unwrap_or_else(|e| {
+                        eprintln!("Failed to load document links: {}", e);
+                        Vec::new()
+                    });
+                let linked_from = rag
+                    .db
+                    .get_backlinked_documents(doc_id)
+                    .await
+                    .unwrap_or_else(|e| {
+                        eprintln!("Failed to load document backlinks: {}", e);
+                        Vec::new()
+                    });
+                let structure = rag
+                    .db
+                    .get_document_structure(doc_id)
+                    .await
+                    .unwrap_or_else(|e| {
+                        eprintln!("Failed to load document structure: {}", e);
+                        PageStructure::default()
+                    });
+                (links_to, linked_from, structure)
+            } else {
+                (Vec::new(), Vec::new(), PageStructure::default())
+            };
+            let _ = tx.send(links);
+        });
+
+        self.document_links_to.clear();
+        self.document_linked_from.clear();
+        self.document_structure = PageStructure::default();
+        self.document_links_receiver = Some(rx);
+    }
+
+    /// Check if the selected document's links have been loaded
+    pub(crate) fn check_document_links_loaded(&mut self) {
+        if let Some(ref rx) = self.document_links_receiver {
+            match rx.try_recv() {
+                Ok((links_to, linked_from, structure)) => {
+                    self.document_links_to = links_to;
+                    self.document_linked_from = linked_from;
+                    self.document_structure = structure;
+                    self.document_links_receiver = None;
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.document_links_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Remove a tag from the selected document
+    pub fn remove_document_tag(&mut self, tag: &str) {
+        let Some(doc_id) = self.selected_document.as_ref().map(|d| d.id) else {
+            return;
+        };
+        self.document_tags.retain(|t| t != tag);
+
+        let rag = self.rag.clone();
+        let tag = tag.to_string();
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            if let Some(ref rag) = *rag_lock {
+                if let Err(e) = rag.db.remove_document_tag(doc_id, &tag).await {
+                    eprintln!("Failed to remove tag: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Load the selected document's metadata, and the bookmark folder or
+    /// directory it was saved from
+    pub(crate) fn load_document_metadata(&mut self, doc_id: i64, url: Option<String>) {
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let runtime_handle = self.runtime.clone();
+
+        runtime_handle.spawn(async move {
+            let rag_lock = rag.read().await;
+            let metadata = if let Some(ref rag) = *rag_lock {
+                rag.db
+                    .get_document_metadata(doc_id)
+                    .await
+                    .unwrap_or_else(|e| {
+                        eprintln!("Failed to load document metadata: {}", e);
+                        None
+                    })
+            } else {
+                None
+            };
+            drop(rag_lock);
+
+            let folder = url.as_deref().and_then(document_folder);
+            let _ = tx.send((metadata, folder));
+        });
+
+        self.document_metadata = None;
+        self.document_folder = None;
+        self.document_metadata_receiver = Some(rx);
+    }
+
+    /// Check if the selected document's metadata has been loaded
+    pub(crate) fn check_document_metadata_loaded(&mut self) {
+        if let Some(ref rx) = self.document_metadata_receiver {
+            match rx.try_recv() {
+                Ok((metadata, folder)) => {
+                    self.document_metadata = metadata;
+                    self.document_folder = folder;
+                    self.document_metadata_receiver = None;
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.document_metadata_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Save the title being edited in the metadata panel
+    pub fn save_document_title(&mut self) {
+        let Some(title) = self.title_edit.take() else {
+            return;
+        };
+        let title = title.trim().to_string();
+        let Some(doc) = self.selected_document.as_mut() else {
+            return;
+        };
+        if title.is_empty() || title == doc.title {
+            return;
+        }
+        let doc_id = doc.id;
+        doc.title = title.clone();
+
+        // Keep the lists the document appears in consistent
+        for result in self.search_results.iter_mut().chain(&mut self.all_results) {
+            if result.doc_id == doc_id {
+                result.title = title.clone();
+            }
+        }
+        for listed in self
+            .recent_documents
+            .iter_mut()
+            .chain(&mut self.pinned_documents)
+        {
+            if listed.id == doc_id {
+                listed.title = title.clone();
+            }
+        }
+
+        let rag = self.rag.clone();
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            if let Some(ref rag) = *rag_lock {
+                if let Err(e) = rag.db.update_document_title(doc_id, &title).await {
+                    eprintln!("Failed to rename document: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Ask for tag suggestions when the tags being edited have changed since
+    /// the last request, and drop them once editing ends
+    pub fn refresh_tag_suggestions(&mut self) {
+        let Some(edit) = self.tags_edit.clone() else {
+            self.tag_suggestions = TagSuggestions::default();
+            self.tag_suggestions_for = None;
+            return;
+        };
+        if self.tag_suggestions_receiver.is_some()
+            || self.tag_suggestions_for.as_deref() == Some(edit.as_str())
+        {
+            return;
+        }
+        let Some(doc_id) = self.selected_document.as_ref().map(|d| d.id) else {
+            return;
+        };
+
+        let prefix = presenter::typed_tag(&edit).to_string();
+        let current = presenter::entered_tags(&edit);
+        self.tag_suggestions_for = Some(edit);
+
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            let suggestions = if let Some(ref rag) = *rag_lock {
+                rag.suggest_tags(doc_id, &prefix, &current, TAG_SUGGESTION_LIMIT)
+                    .await
+                    .unwrap_or_else(|e| {
+                        eprintln!("Failed to suggest tags: {}", e);
+                        TagSuggestions::default()
+                    })
+            } else {
+                TagSuggestions::default()
+            };
+            let _ = tx.send(suggestions);
+        });
+
+        self.tag_suggestions_receiver = Some(rx);
+    }
+
+    /// Check if tag suggestions have arrived
+    pub(crate) fn check_tag_suggestions(&mut self) {
+        if let Some(ref rx) = self.tag_suggestions_receiver {
+            match rx.try_recv() {
+                Ok(suggestions) => {
+                    self.tag_suggestions = suggestions;
+                    self.tag_suggestions_receiver = None;
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.tag_suggestions_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Save the comma-separated tags being edited in the metadata panel
+    pub fn save_document_tags(&mut self) {
+        let Some(edited) = self.tags_edit.take() else {
+            return;
+        };
+        let Some(doc_id) = self.selected_document.as_ref().map(|d| d.id) else {
+            return;
+        };
+
+        let mut tags: Vec<String> = Vec::new();
+        for tag in edited.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+            if !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                tags.push(tag.to_string());
+            }
+        }
+        tags.sort_by_key(|t| t.to_lowercase());
+        self.document_tags = tags.clone();
+
+        let rag = self.rag.clone();
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            if let Some(ref rag) = *rag_lock {
+                if let Err(e) = rag.db.set_document_tags(doc_id, &tags).await {
+                    eprintln!("Failed to save tags: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Check if a document is currently loading
+    pub fn is_document_loading(&self) -> bool {
+        self.document_receiver.is_some()
+    }
+}
@@ -0,0 +1,354 @@
+//! Explore view: documents plotted on a 2D map of their embeddings, so
+//! similar documents sit together (see `projection`)
+
+use egui::{Color32, Pos2, Sense, Ui, Vec2};
+use egui_remixicon::icons;
+
+use crate::db::ProjectionPoint;
+use crate::gui::accessibility::Labeled;
+use crate::gui::app::LocalMindApp;
+use crate::gui::i18n::{tr, tr_args};
+use crate::gui::state::{Toast, View};
+use crate::gui::widgets::favicon;
+
+/// Colors of the most common sources, in order; other sources are gray.
+const SOURCE_COLORS: [Color32; 8] = [
+    Color32::from_rgb(66, 133, 244),
+    Color32::from_rgb(234, 67, 53),
+    Color32::from_rgb(52, 168, 83),
+    Color32::from_rgb(251, 188, 5),
+    Color32::from_rgb(171, 71, 188),
+    Color32::from_rgb(0, 172, 193),
+    Color32::from_rgb(255, 112, 67),
+    Color32::from_rgb(158, 157, 36),
+];
+
+const OTHER_COLOR: Color32 = Color32::GRAY;
+
+const POINT_RADIUS: f32 = 3.0;
+
+/// How close the pointer must be to a point to pick it, in points.
+const PICK_RADIUS: f32 = 8.0;
+
+/// Pan and zoom of the map, kept in egui memory
+#[derive(Debug, Clone, Copy)]
+struct Camera {
+    zoom: f32,
+    offset: Vec2,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self {
+            zoom: 1.0,
+            offset: Vec2::ZERO,
+        }
+    }
+}
+
+/// Sources by how many documents they have on the map, most first.
+fn sources_by_count(points: &[ProjectionPoint]) -> Vec<(String, usize)> {
+    let mut counts: Vec<(String, usize)> = Vec::new();
+    for point in points {
+        match counts
+            .iter_mut()
+            .find(|(source, _)| *source == point.source)
+        {
+            Some((_, count)) => *count += 1,
+            None => counts.push((point.source.clone(), 1)),
+        }
+    }
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts
+}
+
+/// Render the Explore view
+pub fn render_explore_view(ui: &mut Ui, app: &mut LocalMindApp) {
+    ui.add_space(10.0);
+
+    // Header with back button, count and recompute
+    ui.horizontal(|ui| {
+        let back_button = ui.button(icons::ARROW_LEFT_LINE).labeled(tr("common.back"));
+
+        if back_button.hovered() {
+            ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
+        }
+
+        if back_button.clicked() {
+            app.navigate_back();
+        }
+
+        ui.add_space(10.0);
+        ui.heading(tr("explore.title"));
+
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            let computing = app.is_projection_computing();
+            let label = if app.projection.is_empty() {
+                tr("explore.compute")
+            } else {
+                tr("explore.recompute")
+            };
+            if ui
+                .add_enabled(!computing, egui::Button::new(label))
+                .on_hover_text(tr_args(
+                    "explore.compute_hint",
+                    &[("max", &crate::projection::MAX_POINTS)],
+                ))
+                .clicked()
+            {
+                app.compute_projection();
+            }
+            if computing {
+                ui.spinner();
+                ui.weak(tr("explore.computing"));
+            } else if !app.projection.is_empty() {
+                ui.weak(tr_args(
+                    "common.documents_count",
+                    &[("count", &app.projection.len())],
+                ));
+            }
+        });
+    });
+
+    if app.projection_stale {
+        ui.colored_label(ui.visuals().warn_fg_color, tr("explore.stale"));
+    }
+
+    ui.add_space(10.0);
+    ui.separator();
+    ui.add_space(10.0);
+
+    if app.projection.is_empty() {
+        ui.vertical_centered(|ui| {
+            ui.add_space(50.0);
+            if app.is_projection_loading() {
+                ui.spinner();
+            } else {
+                ui.label(tr("explore.empty"));
+            }
+        });
+        return;
+    }
+
+    let sources = sources_by_count(&app.projection);
+    let color_of = |source: &str| {
+        sources
+            .iter()
+            .position(|(s, _)| s == source)
+            .and_then(|i| SOURCE_COLORS.get(i))
+            .copied()
+            .unwrap_or(OTHER_COLOR)
+    };
+
+    // Legend: the most common sources and their colors
+    ui.horizontal_wrapped(|ui| {
+        for (source, count) in sources.iter().take(SOURCE_COLORS.len()) {
+            let (rect, _) = ui.allocate_exact_size(Vec2::splat(10.0), Sense::hover());
+            ui.painter()
+                .circle_filled(rect.center(), 4.0, color_of(source));
+            ui.label(format!("{} ({})", favicon::source_label(source), count));
+            ui.add_space(8.0);
+        }
+        if sources.len() > SOURCE_COLORS.len() {
+            let (rect, _) = ui.allocate_exact_size(Vec2::splat(10.0), Sense::hover());
+            ui.painter().circle_filled(rect.center(), 4.0, OTHER_COLOR);
+            ui.label(tr("explore.other_sources"));
+        }
+    });
+    ui.weak(tr("explore.hint"));
+    ui.add_space(6.0);
+
+    // The map
+    let (rect, response) = ui.allocate_exact_size(ui.available_size(), Sense::click_and_drag());
+    let camera_id = ui.id().with("explore_camera");
+    let mut camera: Camera = ui.data(|d| d.get_temp(camera_id)).unwrap_or_default();
+
+    if response.dragged() {
+        camera.offset += response.drag_delta();
+    }
+    if response.double_clicked() {
+        camera = Camera::default();
+    }
+    if response.hovered() {
+        let (scroll, pinch) = ui.input(|i| (i.smooth_scroll_delta.y, i.zoom_delta()));
+        let factor = pinch * (scroll * 0.002).exp();
+        if factor != 1.0 {
+            // Zoom around the pointer
+            let anchor = response
+                .hover_pos()
+                .map_or(Vec2::ZERO, |pos| pos - rect.center());
+            let zoom = (camera.zoom * factor).clamp(0.5, 50.0);
+            let applied = zoom / camera.zoom;
+            camera.offset = anchor - (anchor - camera.offset) * applied;
+            camera.zoom = zoom;
+        }
+    }
+    ui.data_mut(|d| d.insert_temp(camera_id, camera));
+
+    let side = rect.width().min(rect.height()) * 0.95 * camera.zoom;
+    let to_screen = |point: &ProjectionPoint| -> Pos2 {
+        rect.center() + camera.offset + Vec2::new(point.x - 0.5, point.y - 0.5) * side
+    };
+
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 4.0, ui.visuals().extreme_bg_color);
+
+    let mut hovered: Option<(&ProjectionPoint, f32)> = None;
+    let pointer = response.hover_pos();
+    for point in &app.projection {
+        let pos = to_screen(point);
+        if !rect.expand(POINT_RADIUS).contains(pos) {
+            continue;
+        }
+        painter.circle_filled(pos, POINT_RADIUS, color_of(&point.source));
+        if let Some(pointer) = pointer {
+            let distance = pos.distance(pointer);
+            if distance <= PICK_RADIUS && hovered.is_none_or(|(_, best)| distance < best) {
+                hovered = Some((point, distance));
+            }
+        }
+    }
+
+    let mut open_doc_id = None;
+    if let Some((point, _)) = hovered {
+        painter.circle_stroke(
+            to_screen(point),
+            POINT_RADIUS + 3.0,
+            egui::Stroke::new(2.0, ui.visuals().strong_text_color()),
+        );
+        ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
+        let clicked = response.clicked();
+        response.on_hover_text_at_pointer(format!(
+            "{}\n{}",
+            point.title,
+            favicon::source_label(&point.source)
+        ));
+        if clicked {
+            open_doc_id = Some(point.document_id);
+        }
+    }
+
+    if let Some(doc_id) = open_doc_id {
+        app.load_document(doc_id);
+    }
+}
+
+impl LocalMindApp {
+    /// Show the Explore map
+    pub fn open_explore(&mut self) {
+        if self.current_view != View::Explore {
+            self.previous_view = self.current_view.clone();
+            self.current_view = View::Explore;
+        }
+        self.selected_index = None;
+        self.load_projection();
+    }
+
+    /// Load the cached map positions
+    pub(crate) fn load_projection(&mut self) {
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let runtime_handle = self.runtime.clone();
+
+        runtime_handle.spawn(async move {
+            let rag_lock = rag.read().await;
+            let loaded = if let Some(ref rag) = *rag_lock {
+                match rag.db.get_projection().await {
+                    Ok((points, model)) => {
+                        let stale = model.is_some_and(|m| m != rag.embedding_model_key());
+                        (points, stale)
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to load document map: {}", e);
+                        (Vec::new(), false)
+                    }
+                }
+            } else {
+                (Vec::new(), false)
+            };
+            let _ = tx.send(loaded);
+        });
+
+        self.projection_receiver = Some(rx);
+    }
+
+    /// Check if the cached map has loaded
+    pub(crate) fn check_projection_loaded(&mut self) {
+        if let Some(ref rx) = self.projection_receiver {
+            match rx.try_recv() {
+                Ok((points, stale)) => {
+                    self.projection = points;
+                    self.projection_stale = stale;
+                    self.projection_receiver = None;
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.projection_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Check if the cached map is loading
+    pub fn is_projection_loading(&self) -> bool {
+        self.projection_receiver.is_some()
+    }
+
+    /// Project document embeddings to a new map in the background
+    pub fn compute_projection(&mut self) {
+        if self.projection_job_receiver.is_some() {
+            return;
+        }
+
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let runtime_handle = self.runtime.clone();
+
+        runtime_handle.spawn(async move {
+            let rag_lock = rag.read().await;
+            let result = match *rag_lock {
+                Some(ref rag) => {
+                    crate::projection::compute(&rag.db, &rag.embedding_model_key()).await
+                }
+                None => Err(tr("common.not_ready").into()),
+            };
+            let _ = tx.send(result.map_err(|e| e.to_string()));
+        });
+
+        self.projection_job_receiver = Some(rx);
+    }
+
+    /// Check if a map projection run has finished
+    pub(crate) fn check_projection_computed(&mut self) {
+        if let Some(ref rx) = self.projection_job_receiver {
+            match rx.try_recv() {
+                Ok(result) => {
+                    self.projection_job_receiver = None;
+                    let id = self.next_toast_id();
+                    match result {
+                        Ok(count) => {
+                            self.add_toast(Toast::success(
+                                id,
+                                tr_args("explore.computed", &[("count", &count)]),
+                            ));
+                            self.load_projection();
+                        }
+                        Err(e) => self.add_toast(Toast::error(
+                            id,
+                            tr_args("explore.compute_failed", &[("error", &e)]),
+                        )),
+                    }
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.projection_job_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Check if a map projection run is in progress
+    pub fn is_projection_computing(&self) -> bool {
+        self.projection_job_receiver.is_some()
+    }
+}
@@ -1,12 +1,20 @@
-//! Home view showing recent documents
+//! Home view: a dashboard of index counts, shortcuts and recent searches,
+//! then due reminders and pinned, recently saved, resurfaced and recent
+//! documents
 
 use egui::Ui;
 use egui_remixicon::icons;
 
-use crate::gui::app::LocalMindApp;
-use crate::gui::state::InitStatus;
+use crate::gui::accessibility::Labeled;
+use crate::gui::app::{document_view, LocalMindApp};
+use crate::gui::i18n::{tr, tr_args};
+use crate::gui::state::{DocumentImages, DocumentView, InitStatus, View};
+use crate::gui::widgets::document_actions::{self, DocumentAction};
+use crate::gui::widgets::{favicon, favorite, language, quick_actions, reading_list, thumbnail};
+use std::collections::HashSet;
 
-/// Render the home view with recent documents
+/// Render the home view: the dashboard, then due reminders and pinned,
+/// recently saved, resurfaced and recent documents
 pub fn render_home_view(ui: &mut Ui, app: &mut LocalMindApp) {
     ui.add_space(20.0);
 
@@ -17,179 +25,568 @@ pub fn render_home_view(ui: &mut Ui, app: &mut LocalMindApp) {
                 ui.add_space(100.0);
                 ui.spinner();
                 ui.add_space(10.0);
-                ui.label("Initializing LocalMind...");
+                ui.label(tr("home.initializing"));
                 ui.add_space(5.0);
-                ui.weak("Connecting to embedding server");
+                ui.weak(tr("home.connecting"));
             });
         }
         InitStatus::Error(msg) => {
             ui.vertical_centered(|ui| {
                 ui.add_space(100.0);
-                ui.colored_label(egui::Color32::RED, "⚠ Initialization Error");
+                ui.colored_label(egui::Color32::RED, tr("home.init_error"));
                 ui.add_space(10.0);
                 ui.label(msg);
                 ui.add_space(20.0);
-                ui.weak("Please check that the Python embedding server is running.");
+                ui.weak(tr("home.check_server"));
             });
         }
         InitStatus::Ready => {
+            let pinned = app.pinned_documents.clone();
+            let mut docs = app.recent_documents.clone();
+            if app.unread_only {
+                // Drop documents marked read since the list was loaded
+                docs.retain(|d| app.is_unread(d.id));
+            }
+            let mut clicked_doc_id: Option<i64> = None;
+            let mut star_doc_id: Option<i64> = None;
+            let mut check_doc_id: Option<i64> = None;
+            let mut menu_action: Option<(i64, Option<String>, DocumentAction)> = None;
+
+            render_overview(ui, app);
+
+            // Documents snoozed until now, above everything else
+            if let Some(doc_id) = render_reminders(ui, app) {
+                clicked_doc_id = Some(doc_id);
+            }
+
+            // Pinned documents, shown above recent ones
+            if !pinned.is_empty() {
+                ui.horizontal(|ui| {
+                    ui.heading(format!("{} {}", icons::STAR_FILL, tr("home.pinned")));
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        ui.weak(tr_args(
+                            "common.documents_count",
+                            &[("count", &pinned.len())],
+                        ));
+                    });
+                });
+
+                ui.add_space(10.0);
+
+                egui::ScrollArea::vertical()
+                    .id_salt("pinned_documents")
+                    .max_height(240.0)
+                    .auto_shrink([false, true])
+                    .show(ui, |ui| {
+                        for (i, doc) in pinned.iter().enumerate() {
+                            let selected = app.selected_index == Some(i);
+                            ui.push_id(("pinned", doc.id), |ui| {
+                                let card = render_document_card(
+                                    ui,
+                                    doc,
+                                    app.document_images(doc.id, doc.url.as_deref()),
+                                    &app.tracking_params,
+                                    true,
+                                    app.is_unread(doc.id),
+                                    selected,
+                                    app.checked_doc_ids.contains(&doc.id),
+                                );
+                                if selected && app.scroll_to_selection {
+                                    ui.scroll_to_rect(card.response.rect, None);
+                                    app.scroll_to_selection = false;
+                                }
+                                if let Some(action) = document_actions::context_menu(
+                                    &card.response,
+                                    doc.url.is_some(),
+                                    &mut app.tag_input,
+                                ) {
+                                    menu_action = Some((doc.id, doc.url.clone(), action));
+                                } else if card.check_toggled {
+                                    check_doc_id = Some(doc.id);
+                                } else if card.star_clicked {
+                                    star_doc_id = Some(doc.id);
+                                } else if card.response.clicked() {
+                                    clicked_doc_id = Some(doc.id);
+                                }
+                            });
+                            ui.add_space(8.0);
+                        }
+                    });
+
+                ui.add_space(10.0);
+            }
+
+            // Latest saves from each source
+            if !app.unread_only {
+                if let Some(doc_id) = render_recent_by_source(ui, app) {
+                    clicked_doc_id = Some(doc_id);
+                }
+            }
+
+            // Older documents worth another look
+            if !app.unread_only {
+                if let Some(doc_id) = render_resurfaced(ui, app) {
+                    clicked_doc_id = Some(doc_id);
+                }
+            }
+
             // Header
+            let mut unread_only = app.unread_only;
+            let mut mark_all_read = false;
             ui.horizontal(|ui| {
-                ui.heading("Recent Documents");
+                ui.heading(if unread_only {
+                    tr("home.reading_list")
+                } else {
+                    tr("home.recent")
+                });
 
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                    ui.weak(format!("{} documents", app.recent_documents.len()));
+                    ui.weak(tr_args("common.documents_count", &[("count", &docs.len())]));
+                    thumbnail::layout_toggle(ui, app);
+                    ui.checkbox(&mut unread_only, tr("home.unread_only"));
+                    if unread_only
+                        && !docs.is_empty()
+                        && ui.button(tr("home.mark_all_read")).clicked()
+                    {
+                        mark_all_read = true;
+                    }
                 });
             });
+            if unread_only != app.unread_only {
+                app.set_unread_only(unread_only);
+            }
+            if mark_all_read {
+                app.set_read(docs.iter().map(|d| d.id).collect(), true);
+            }
 
             ui.add_space(10.0);
             ui.separator();
             ui.add_space(10.0);
 
-            if app.recent_documents.is_empty() {
+            // Bulk actions for checked documents
+            document_actions::render_bulk_bar(ui, app);
+
+            if docs.is_empty() && unread_only {
+                ui.vertical_centered(|ui| {
+                    ui.add_space(50.0);
+                    ui.label(tr("home.nothing_to_read"));
+                });
+            } else if docs.is_empty() {
                 ui.vertical_centered(|ui| {
                     ui.add_space(50.0);
-                    ui.label("No documents yet");
+                    ui.label(tr("home.no_documents"));
                     ui.add_space(10.0);
-                    ui.weak("Save bookmarks in Chrome to get started, or use the extension to capture pages.");
+                    ui.weak(tr("home.get_started"));
                 });
             } else {
-                // Clone documents to avoid borrow issues
-                let docs = app.recent_documents.clone();
-                let mut clicked_doc_id: Option<i64> = None;
-
                 // Scrollable list of recent documents
                 egui::ScrollArea::vertical()
+                    .id_salt("recent_documents")
                     .auto_shrink([false, false])
                     .show(ui, |ui| {
-                        for doc in &docs {
+                        for (i, doc) in docs.iter().enumerate() {
+                            // Keyboard selection indexes pinned documents first
+                            let selected = app.selected_index == Some(pinned.len() + i);
                             ui.push_id(doc.id, |ui| {
-                                // Clickable frame for the document card
-                                let card_fill = if doc.is_needs_auth {
-                                    if ui.visuals().dark_mode {
-                                        egui::Color32::from_rgb(50, 40, 20) // Dark amber tint
-                                    } else {
-                                        egui::Color32::from_rgb(255, 248, 230) // Light amber tint
-                                    }
-                                } else if ui.visuals().dark_mode {
-                                    egui::Color32::from_rgb(30, 40, 60) // Dark blue-gray
-                                } else {
-                                    egui::Color32::from_gray(245)
-                                };
-
-                                let response = egui::Frame::none()
-                                    .fill(card_fill)
-                                    .rounding(4.0)
-                                    .inner_margin(12.0)
-                                    .show(ui, |ui| {
-                                        ui.set_width(ui.available_width());
-
-                                        // Title row with optional auth badge
-                                        ui.horizontal(|ui| {
-                                            if doc.is_needs_auth {
-                                                ui.colored_label(
-                                                    egui::Color32::from_rgb(200, 150, 0),
-                                                    icons::LOCK_LINE,
-                                                );
-                                            }
-                                            ui.strong(&doc.title);
-                                        });
-
-                                        // URL if present
-                                        if let Some(ref url) = doc.url {
-                                            ui.weak(truncate_url(url, 80));
-                                        }
-
-                                        ui.add_space(4.0);
-
-                                        // Content snippet (extract after bookmark metadata if present)
-                                        let snippet_text = if doc.content.starts_with("Bookmark:") {
-                                            // Find the first double newline (end of metadata section)
-                                            if let Some(content_start) = doc.content.find("\n\n") {
-                                                let actual_content =
-                                                    doc.content[content_start + 2..].trim();
-                                                if !actual_content.is_empty() {
-                                                    Some(create_snippet(actual_content, 150))
-                                                } else {
-                                                    None
-                                                }
-                                            } else {
-                                                None
-                                            }
-                                        } else {
-                                            Some(create_snippet(&doc.content, 150))
-                                        };
-
-                                        if let Some(snippet) = snippet_text {
-                                            ui.label(&snippet);
-                                        }
-
-                                        ui.add_space(4.0);
-
-                                        // Metadata
-                                        ui.horizontal(|ui| {
-                                            ui.weak(&doc.source);
-                                            ui.weak("•");
-                                            ui.weak(&doc.created_at);
-                                        });
-
-                                        if doc.is_needs_auth {
-                                            ui.add_space(4.0);
-                                            ui.colored_label(
-                                                egui::Color32::from_rgb(200, 150, 0),
-                                                "Login required - open link and use extension to re-capture",
-                                            );
-                                        }
-                                    });
-
-                                // Handle click to view document
-                                if response.response.interact(egui::Sense::click()).clicked() {
-                                    clicked_doc_id = Some(doc.id);
+                                let card = render_document_card(
+                                    ui,
+                                    doc,
+                                    app.document_images(doc.id, doc.url.as_deref()),
+                                    &app.tracking_params,
+                                    app.is_favorite(doc.id),
+                                    app.is_unread(doc.id),
+                                    selected,
+                                    app.checked_doc_ids.contains(&doc.id),
+                                );
+                                if selected && app.scroll_to_selection {
+                                    ui.scroll_to_rect(card.response.rect, None);
+                                    app.scroll_to_selection = false;
                                 }
-
-                                // Show pointer cursor on hover
-                                if response.response.hovered() {
-                                    ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
+                                if let Some(action) = document_actions::context_menu(
+                                    &card.response,
+                                    doc.url.is_some(),
+                                    &mut app.tag_input,
+                                ) {
+                                    menu_action = Some((doc.id, doc.url.clone(), action));
+                                } else if card.check_toggled {
+                                    check_doc_id = Some(doc.id);
+                                } else if card.star_clicked {
+                                    star_doc_id = Some(doc.id);
+                                } else if card.response.clicked() {
+                                    clicked_doc_id = Some(doc.id);
                                 }
                             });
 
                             ui.add_space(8.0);
                         }
                     });
+            }
 
-                // Handle click outside the loop to avoid borrow issues
-                if let Some(doc_id) = clicked_doc_id {
-                    app.load_document(doc_id);
+            // Handle clicks outside the loops to avoid borrow issues
+            if let Some((doc_id, url, action)) = menu_action {
+                app.run_document_action(ui.ctx(), doc_id, url, action);
+            } else if let Some(doc_id) = check_doc_id {
+                if !app.checked_doc_ids.remove(&doc_id) {
+                    app.checked_doc_ids.insert(doc_id);
                 }
+            } else if let Some(doc_id) = star_doc_id {
+                app.toggle_favorite(doc_id);
+            } else if let Some(doc_id) = clicked_doc_id {
+                app.load_document(doc_id);
             }
         }
     }
 }
 
-/// Create a content snippet, truncating at char boundaries.
-/// Strips YAML frontmatter so `---\n{}\n---` never leaks into the UI.
-fn create_snippet(content: &str, max_len: usize) -> String {
-    let content = strip_frontmatter(content);
-    if content.len() <= max_len {
-        return content.to_string();
-    }
-
-    // Walk back to a valid char boundary so we never slice mid-codepoint.
-    let boundary = content
-        .char_indices()
-        .map(|(i, _)| i)
-        .take_while(|&i| i <= max_len)
-        .last()
-        .unwrap_or(0);
-
-    let truncated = &content[..boundary];
-    if let Some(last_space) = truncated.rfind(char::is_whitespace) {
-        format!("{}...", &content[..last_space])
+/// Dashboard header: stat tiles, shortcut buttons and recent searches.
+fn render_overview(ui: &mut Ui, app: &mut LocalMindApp) {
+    let summary = &app.home_summary;
+    let tiles = [
+        (summary.documents, tr("home.tile_documents")),
+        (summary.saved_this_week, tr("home.tile_saved_this_week")),
+        (summary.unread, tr("home.tile_unread")),
+        (
+            summary.searches_this_week,
+            tr("home.tile_searches_this_week"),
+        ),
+    ];
+    ui.horizontal_wrapped(|ui| {
+        for (value, label) in tiles {
+            stat_tile(ui, value, label);
+        }
+    });
+    ui.add_space(10.0);
+
+    quick_actions::render_quick_actions(ui, app);
+    ui.add_space(10.0);
+
+    let recent_searches = app.home_summary.recent_searches.clone();
+    if !recent_searches.is_empty() {
+        let mut search = None;
+        ui.horizontal_wrapped(|ui| {
+            ui.weak(format!(
+                "{} {}",
+                icons::HISTORY_LINE,
+                tr("home.recent_searches")
+            ));
+            for query in &recent_searches {
+                if ui.small_button(query).clicked() {
+                    search = Some(query.clone());
+                }
+            }
+        });
+        if let Some(query) = search {
+            app.search_instead(query);
+        }
+        ui.add_space(10.0);
+    }
+}
+
+/// A tile with a large count over its label.
+fn stat_tile(ui: &mut Ui, value: i64, label: &str) {
+    egui::Frame::none()
+        .fill(ui.visuals().faint_bg_color)
+        .stroke(ui.visuals().widgets.noninteractive.bg_stroke)
+        .rounding(6.0)
+        .inner_margin(egui::vec2(14.0, 8.0))
+        .show(ui, |ui| {
+            ui.set_min_width(110.0);
+            ui.vertical(|ui| {
+                ui.heading(value.to_string());
+                ui.weak(label);
+            });
+        });
+}
+
+/// "Reminders" section: documents whose reminders have come due, each with
+/// a button to dismiss it. Returns the id of a clicked document.
+fn render_reminders(ui: &mut Ui, app: &mut LocalMindApp) -> Option<i64> {
+    if app.reminders.is_empty() {
+        return None;
+    }
+
+    let mut clicked = None;
+    let mut dismissed = None;
+    egui::Frame::group(ui.style())
+        .stroke(ui.visuals().selection.stroke)
+        .show(ui, |ui| {
+            ui.set_width(ui.available_width());
+            ui.heading(format!("{} {}", icons::ALARM_LINE, tr("home.reminders")));
+            ui.add_space(4.0);
+            for reminder in &app.reminders {
+                ui.horizontal(|ui| {
+                    if ui.link(&reminder.title).clicked() {
+                        clicked = Some(reminder.document_id);
+                    }
+                    ui.weak(&reminder.remind_on);
+                    if ui
+                        .small_button(icons::CHECK_LINE)
+                        .labeled(tr("home.dismiss_reminder"))
+                        .clicked()
+                    {
+                        dismissed = Some(reminder.document_id);
+                    }
+                });
+            }
+        });
+    ui.add_space(10.0);
+
+    if let Some(doc_id) = dismissed {
+        app.dismiss_reminder(doc_id);
+    }
+    clicked
+}
+
+/// "Recently saved" section: the latest saves of each source, side by side.
+/// Returns the id of a clicked document.
+fn render_recent_by_source(ui: &mut Ui, app: &LocalMindApp) -> Option<i64> {
+    let groups = &app.home_summary.recent_by_source;
+    if groups.is_empty() {
+        return None;
+    }
+
+    let mut clicked = None;
+    egui::CollapsingHeader::new(
+        egui::RichText::new(format!(
+            "{} {}",
+            icons::TIME_LINE,
+            tr("home.recently_saved")
+        ))
+        .heading(),
+    )
+    .id_salt("recently_saved")
+    .default_open(true)
+    .show(ui, |ui| {
+        ui.columns(groups.len(), |columns| {
+            for (column, (source, saves)) in columns.iter_mut().zip(groups) {
+                column.strong(format!(
+                    "{} {}",
+                    favicon::source_icon(source, saves.iter().any(|save| save.url.is_some())),
+                    favicon::source_label(source)
+                ));
+                for save in saves {
+                    if column
+                        .link(&save.title)
+                        .on_hover_text(&save.created_at)
+                        .clicked()
+                    {
+                        clicked = Some(save.id);
+                    }
+                }
+            }
+        });
+    });
+    ui.add_space(10.0);
+    clicked
+}
+
+/// "Rediscover" section: documents saved a year ago and a random pick of
+/// forgotten ones. Returns the id of a clicked document.
+fn render_resurfaced(ui: &mut Ui, app: &mut LocalMindApp) -> Option<i64> {
+    let year_ago = app.year_ago_documents.clone();
+    let forgotten = app.forgotten_documents.clone();
+    if year_ago.is_empty() && forgotten.is_empty() {
+        return None;
+    }
+
+    let mut clicked = None;
+    let mut shuffle = false;
+    egui::CollapsingHeader::new(
+        egui::RichText::new(format!("{} {}", icons::HISTORY_LINE, tr("home.rediscover"))).heading(),
+    )
+    .id_salt("rediscover")
+    .default_open(true)
+    .show(ui, |ui| {
+        if !year_ago.is_empty() {
+            ui.weak(tr("home.year_ago"));
+            for doc in &year_ago {
+                if resurfaced_row(ui, doc).clicked() {
+                    clicked = Some(doc.id);
+                }
+            }
+            ui.add_space(6.0);
+        }
+        if !forgotten.is_empty() {
+            ui.horizontal(|ui| {
+                ui.weak(tr("home.from_archive"));
+                if ui
+                    .small_button(icons::SHUFFLE_LINE)
+                    .labeled(tr("home.shuffle"))
+                    .clicked()
+                {
+                    shuffle = true;
+                }
+            });
+            for doc in &forgotten {
+                if resurfaced_row(ui, doc).clicked() {
+                    clicked = Some(doc.id);
+                }
+            }
+        }
+    });
+    ui.add_space(10.0);
+
+    if shuffle {
+        app.load_resurfaced_documents();
+    }
+    clicked
+}
+
+/// One-line entry for a resurfaced document: title and save date.
+fn resurfaced_row(ui: &mut Ui, doc: &DocumentView) -> egui::Response {
+    ui.horizontal(|ui| {
+        let link = ui.link(&doc.title);
+        let saved = doc.created_at.split(' ').next().unwrap_or(&doc.created_at);
+        ui.weak(saved);
+        link
+    })
+    .inner
+}
+
+/// Interactions with a document card
+pub(crate) struct CardResponse {
+    /// Click/right-click response covering the whole card
+    pub response: egui::Response,
+    pub star_clicked: bool,
+    pub check_toggled: bool,
+}
+
+/// Render a clickable document card with a bulk-select checkbox and a pin
+/// (star) toggle. `selected` outlines the card as the keyboard selection.
+/// The browse view lists documents with the same cards.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn render_document_card(
+    ui: &mut Ui,
+    doc: &DocumentView,
+    images: DocumentImages,
+    tracking_params: &[String],
+    is_favorite: bool,
+    is_unread: bool,
+    selected: bool,
+    checked: bool,
+) -> CardResponse {
+    let mut star_clicked = false;
+    let mut check_toggled = false;
+
+    // Clickable frame for the document card
+    let card_fill = if doc.is_needs_auth {
+        if ui.visuals().dark_mode {
+            egui::Color32::from_rgb(50, 40, 20) // Dark amber tint
+        } else {
+            egui::Color32::from_rgb(255, 248, 230) // Light amber tint
+        }
+    } else if ui.visuals().dark_mode {
+        egui::Color32::from_rgb(30, 40, 60) // Dark blue-gray
+    } else {
+        egui::Color32::from_gray(245)
+    };
+
+    let stroke = if selected {
+        ui.visuals().selection.stroke
     } else {
-        format!("{}...", truncated)
+        egui::Stroke::NONE
+    };
+
+    let response = egui::Frame::none()
+        .fill(card_fill)
+        .stroke(stroke)
+        .rounding(4.0)
+        .inner_margin(12.0)
+        .show(ui, |ui| {
+            ui.set_width(ui.available_width());
+
+            // Title row with checkbox, unread/auth badges and star toggle
+            ui.horizontal(|ui| {
+                let mut checked = checked;
+                check_toggled = ui.checkbox(&mut checked, "").changed();
+                if is_unread {
+                    reading_list::unread_dot(ui);
+                }
+                if doc.is_needs_auth {
+                    ui.colored_label(egui::Color32::from_rgb(200, 150, 0), icons::LOCK_LINE);
+                }
+                favicon::document_icon(ui, images.favicon, &doc.source, doc.url.is_some());
+                ui.strong(&doc.title);
+                if let Some(ref code) = images.language {
+                    language::language_badge(ui, code);
+                }
+
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    star_clicked = favorite::star_button(ui, is_favorite).clicked();
+                });
+            });
+
+            // URL if present
+            if let Some(ref url) = doc.url {
+                ui.weak(truncate_url(url, tracking_params, 80));
+            }
+
+            if let Some(jpeg) = images.thumbnail {
+                ui.add_space(4.0);
+                thumbnail::render_thumbnail(ui, doc.id, jpeg);
+            }
+
+            ui.add_space(4.0);
+
+            // Content snippet (extract after bookmark metadata if present)
+            let snippet_text = if doc.content.starts_with("Bookmark:") {
+                // Find the first double newline (end of metadata section)
+                if let Some(content_start) = doc.content.find("\n\n") {
+                    let actual_content = doc.content[content_start + 2..].trim();
+                    if !actual_content.is_empty() {
+                        Some(create_snippet(actual_content, 150))
+                    } else {
+                        None
+                    }
+                } else {
+                    None
+                }
+            } else {
+                Some(create_snippet(&doc.content, 150))
+            };
+
+            if let Some(snippet) = snippet_text {
+                ui.label(&snippet);
+            }
+
+            ui.add_space(4.0);
+
+            // Metadata
+            ui.horizontal(|ui| {
+                ui.weak(&doc.source);
+                ui.weak("•");
+                ui.weak(&doc.created_at);
+            });
+
+            if doc.is_needs_auth {
+                ui.add_space(4.0);
+                ui.colored_label(
+                    egui::Color32::from_rgb(200, 150, 0),
+                    tr("home.login_required"),
+                );
+            }
+        });
+
+    let card = response.response.interact(egui::Sense::click());
+
+    // Show pointer cursor on hover
+    if card.hovered() {
+        ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
+    }
+
+    CardResponse {
+        response: card,
+        star_clicked,
+        check_toggled,
     }
 }
 
+/// Create a content snippet from the start of the document, cut at a word
+/// boundary. Strips YAML frontmatter so `---\n{}\n---` never leaks into the UI.
+fn create_snippet(content: &str, max_len: usize) -> String {
+    crate::snippet::centered_snippet(strip_frontmatter(content), "", max_len)
+}
+
 fn strip_frontmatter(s: &str) -> &str {
     if !s.starts_with("---") {
         return s;
@@ -202,11 +599,365 @@ fn strip_frontmatter(s: &str) -> &str {
     }
 }
 
-/// Truncate a URL for display
-fn truncate_url(url: &str, max_len: usize) -> String {
+/// Truncate a URL for display, without its tracking parameters
+fn truncate_url(url: &str, tracking_params: &[String], max_len: usize) -> String {
+    let url = crate::tracking_params::strip(url, tracking_params);
     if url.len() <= max_len {
-        url.to_string()
+        url
     } else {
         format!("{}...", &url[..max_len])
     }
 }
+
+impl LocalMindApp {
+    /// Load the home dashboard's counts, recent saves and recent searches
+    pub(crate) fn load_home_summary(&mut self) {
+        if self.home_summary_receiver.is_some() {
+            return; // Already loading
+        }
+
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            if let Some(ref rag) = *rag_lock {
+                match rag.db.get_home_summary(4, 4, 8).await {
+                    Ok(summary) => {
+                        let _ = tx.send(summary);
+                    }
+                    Err(e) => eprintln!("Failed to load home summary: {}", e),
+                }
+            }
+        });
+
+        self.home_summary_receiver = Some(rx);
+    }
+
+    /// Check if the home dashboard summary has loaded
+    pub(crate) fn check_home_summary(&mut self) {
+        if let Some(ref rx) = self.home_summary_receiver {
+            match rx.try_recv() {
+                Ok(summary) => {
+                    self.home_summary = summary;
+                    self.home_summary_receiver = None;
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.home_summary_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Load the set of unread document IDs
+    pub(crate) fn load_unread_ids(&mut self) {
+        if self.unread_receiver.is_some() {
+            return; // Already loading
+        }
+
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let runtime_handle = self.runtime.clone();
+
+        runtime_handle.spawn(async move {
+            let rag_lock = rag.read().await;
+            let ids = if let Some(ref rag) = *rag_lock {
+                rag.db.get_unread_document_ids().await.unwrap_or_else(|e| {
+                    eprintln!("Failed to load unread documents: {}", e);
+                    HashSet::new()
+                })
+            } else {
+                HashSet::new()
+            };
+            let _ = tx.send(ids);
+        });
+
+        self.unread_receiver = Some(rx);
+    }
+
+    /// Check if unread IDs have loaded
+    pub(crate) fn check_unread_loaded(&mut self) {
+        if let Some(ref rx) = self.unread_receiver {
+            match rx.try_recv() {
+                Ok(ids) => {
+                    self.unread_ids = ids;
+                    self.unread_receiver = None;
+                    if self.unread_only {
+                        self.apply_search_filters();
+                    }
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.unread_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Whether a document is unread
+    pub fn is_unread(&self, doc_id: i64) -> bool {
+        self.unread_ids.contains(&doc_id)
+    }
+
+    /// Mark documents read or unread
+    ///
+    /// The in-memory set updates immediately so filters and badges react on
+    /// the next frame; the database write happens in the background.
+    pub fn set_read(&mut self, doc_ids: Vec<i64>, read: bool) {
+        let changed = doc_ids
+            .iter()
+            .filter(|id| self.unread_ids.contains(*id) == read)
+            .count();
+        if changed == 0 {
+            return;
+        }
+        for id in &doc_ids {
+            if read {
+                self.unread_ids.remove(id);
+            } else {
+                self.unread_ids.insert(*id);
+            }
+        }
+        if self.unread_only {
+            self.apply_search_filters();
+        }
+
+        let rag = self.rag.clone();
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            if let Some(ref rag) = *rag_lock {
+                if let Err(e) = rag.db.set_documents_read(&doc_ids, read).await {
+                    eprintln!("Failed to update read state: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Toggle the unread-only filter and reload the home list
+    pub fn set_unread_only(&mut self, unread_only: bool) {
+        self.unread_only = unread_only;
+        self.apply_search_filters();
+        self.recent_docs_receiver = None;
+        self.load_recent_documents();
+        if self.current_view == View::Timeline {
+            self.load_timeline(true);
+        }
+    }
+
+    /// Check if recent documents have loaded
+    pub(crate) fn check_recent_documents(&mut self) {
+        if let Some(ref rx) = self.recent_docs_receiver {
+            match rx.try_recv() {
+                Ok(docs) => {
+                    self.recent_documents = docs;
+                    println!("Loaded {} recent documents", self.recent_documents.len());
+                    self.recent_docs_receiver = None;
+                    self.load_document_images();
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {
+                    // Still loading
+                }
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    // Channel closed, clear receiver
+                    self.recent_docs_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Load pinned documents for the home screen
+    pub(crate) fn load_pinned_documents(&mut self) {
+        if self.pinned_docs_receiver.is_some() {
+            return; // Already loading
+        }
+        self.spawn_pinned_update(None);
+    }
+
+    /// Pin or unpin a document, then reload the pinned list
+    pub fn toggle_favorite(&mut self, doc_id: i64) {
+        if self.pinned_docs_receiver.is_some() {
+            return; // Wait for the previous update to land
+        }
+        let favorite = !self.is_favorite(doc_id);
+        self.spawn_pinned_update(Some((doc_id, favorite)));
+    }
+
+    /// Whether a document is pinned
+    pub fn is_favorite(&self, doc_id: i64) -> bool {
+        self.pinned_documents.iter().any(|d| d.id == doc_id)
+    }
+
+    /// Apply an optional pin change, then send the refreshed pinned list
+    pub(crate) fn spawn_pinned_update(&mut self, change: Option<(i64, bool)>) {
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let runtime_handle = self.runtime.clone();
+        let selected_profile = self.selected_profile.clone();
+
+        runtime_handle.spawn(async move {
+            let rag_lock = rag.read().await;
+            let docs = if let Some(ref rag) = *rag_lock {
+                if let Some((doc_id, favorite)) = change {
+                    if let Err(e) = rag.db.set_document_favorite(doc_id, favorite).await {
+                        eprintln!("Failed to update favorite: {}", e);
+                    }
+                }
+                match rag.db.get_favorite_documents(selected_profile).await {
+                    Ok(docs) => docs.into_iter().map(document_view).collect(),
+                    Err(e) => {
+                        eprintln!("Failed to load pinned documents: {}", e);
+                        Vec::new()
+                    }
+                }
+            } else {
+                Vec::new()
+            };
+            let _ = tx.send(docs);
+        });
+
+        self.pinned_docs_receiver = Some(rx);
+    }
+
+    /// Check if pinned documents have loaded
+    pub(crate) fn check_pinned_documents(&mut self) {
+        if let Some(ref rx) = self.pinned_docs_receiver {
+            match rx.try_recv() {
+                Ok(docs) => {
+                    self.pinned_documents = docs;
+                    self.pinned_docs_receiver = None;
+                    self.load_document_images();
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {
+                    // Still loading
+                }
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.pinned_docs_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Load documents to resurface on the home screen: ones saved a year ago
+    /// and a fresh random pick of forgotten ones
+    pub fn load_resurfaced_documents(&mut self) {
+        if self.resurfaced_receiver.is_some() {
+            return; // Already loading
+        }
+
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let runtime_handle = self.runtime.clone();
+        let selected_profile = self.selected_profile.clone();
+
+        runtime_handle.spawn(async move {
+            let rag_lock = rag.read().await;
+            let docs = if let Some(ref rag) = *rag_lock {
+                let year_ago = rag
+                    .db
+                    .get_documents_saved_year_ago(5, selected_profile.clone())
+                    .await
+                    .unwrap_or_else(|e| {
+                        eprintln!("Failed to load documents saved a year ago: {}", e);
+                        Vec::new()
+                    });
+                let forgotten = rag
+                    .db
+                    .get_forgotten_documents(3, selected_profile)
+                    .await
+                    .unwrap_or_else(|e| {
+                        eprintln!("Failed to load forgotten documents: {}", e);
+                        Vec::new()
+                    });
+                (
+                    year_ago.into_iter().map(document_view).collect(),
+                    forgotten.into_iter().map(document_view).collect(),
+                )
+            } else {
+                (Vec::new(), Vec::new())
+            };
+            let _ = tx.send(docs);
+        });
+
+        self.resurfaced_receiver = Some(rx);
+    }
+
+    /// Check if resurfaced documents have loaded
+    pub(crate) fn check_resurfaced_documents(&mut self) {
+        if let Some(ref rx) = self.resurfaced_receiver {
+            match rx.try_recv() {
+                Ok((year_ago, forgotten)) => {
+                    self.year_ago_documents = year_ago;
+                    self.forgotten_documents = forgotten;
+                    self.resurfaced_receiver = None;
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {
+                    // Still loading
+                }
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.resurfaced_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Load recent documents for home screen
+    pub(crate) fn load_recent_documents(&mut self) {
+        if self.recent_docs_receiver.is_some() {
+            return; // Already loading
+        }
+
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let runtime_handle = self.runtime.clone();
+        let selected_profile = self.selected_profile.clone();
+        let unread_only = self.unread_only;
+
+        runtime_handle.spawn(async move {
+            let rag_lock = rag.read().await;
+            let docs = if let Some(ref rag) = *rag_lock {
+                let docs = if unread_only {
+                    rag.db.get_unread_documents(50, selected_profile).await
+                } else {
+                    rag.db
+                        .get_recent_documents_filtered(10, selected_profile)
+                        .await
+                };
+                match docs {
+                    Ok(docs) => docs.into_iter().map(document_view).collect(),
+                    Err(e) => {
+                        eprintln!("Failed to load recent documents: {}", e);
+                        Vec::new()
+                    }
+                }
+            } else {
+                Vec::new()
+            };
+            let _ = tx.send(docs);
+        });
+
+        self.recent_docs_receiver = Some(rx);
+        self.load_unread_ids();
+        self.load_home_summary();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unread_ids_load_into_the_badges() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let mut app = LocalMindApp::detached(runtime.handle().clone());
+        let (tx, rx) = std::sync::mpsc::channel();
+        app.unread_receiver = Some(rx);
+        assert!(!app.is_unread(7));
+
+        tx.send(HashSet::from([7, 9])).unwrap();
+        app.check_unread_loaded();
+        assert!(app.is_unread(7));
+        assert!(app.is_unread(9));
+        assert!(!app.is_unread(8));
+        assert!(app.unread_receiver.is_none());
+    }
+}
@@ -0,0 +1,172 @@
+//! Jobs view: bookmark fetches queued or in progress, the imports that
+//! failed and recent requests from the extension
+
+use egui::Ui;
+use egui_remixicon::icons;
+
+use crate::gui::accessibility::Labeled;
+use crate::gui::app::LocalMindApp;
+use crate::gui::i18n::{tr, tr_args};
+use crate::gui::state::View;
+use crate::gui::widgets::{activity, ingest_errors};
+use crate::ingest_queue::QueueStatus;
+
+/// Render the Jobs view
+pub fn render_jobs_view(ui: &mut Ui, app: &mut LocalMindApp) {
+    ui.add_space(10.0);
+
+    // Header with back button and refresh
+    ui.horizontal(|ui| {
+        let back_button = ui.button(icons::ARROW_LEFT_LINE).labeled(tr("common.back"));
+
+        if back_button.hovered() {
+            ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
+        }
+
+        if back_button.clicked() {
+            app.navigate_back();
+        }
+
+        ui.add_space(10.0);
+        ui.heading(tr("jobs.title"));
+
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            if ui
+                .add_enabled(
+                    !app.is_jobs_loading(),
+                    egui::Button::new(tr("jobs.refresh")),
+                )
+                .clicked()
+            {
+                app.load_jobs();
+                app.load_ingest_errors();
+                app.load_sidebar();
+            }
+        });
+    });
+
+    ui.add_space(10.0);
+    ui.separator();
+    ui.add_space(10.0);
+
+    egui::ScrollArea::vertical()
+        .id_salt("jobs")
+        .auto_shrink([false, false])
+        .show(ui, |ui| {
+            ui.strong(tr_args("jobs.queue", &[("count", &app.job_items.len())]));
+            ui.add_space(6.0);
+
+            if app.is_jobs_loading() && app.job_items.is_empty() {
+                ui.spinner();
+            } else if app.job_items.is_empty() {
+                ui.weak(tr("jobs.queue_empty"));
+            }
+
+            for item in &app.job_items {
+                ui.push_id(item.id, |ui| {
+                    ui.horizontal(|ui| {
+                        if item.status == QueueStatus::InProgress {
+                            ui.spinner();
+                        }
+                        ui.vertical(|ui| {
+                            ui.label(&item.title);
+                            ui.weak(&item.url);
+                            let status = match item.status {
+                                QueueStatus::InProgress => tr("jobs.in_progress"),
+                                _ => tr("jobs.pending"),
+                            };
+                            if item.attempts > 0 {
+                                ui.weak(tr_args(
+                                    "jobs.retrying",
+                                    &[("status", &status), ("attempts", &item.attempts)],
+                                ));
+                            } else {
+                                ui.weak(status);
+                            }
+                        });
+                    });
+                });
+                ui.add_space(4.0);
+            }
+
+            ui.add_space(10.0);
+            ui.separator();
+            ui.add_space(10.0);
+
+            ui.strong(tr("jobs.failed_imports"));
+            ui.add_space(6.0);
+            ingest_errors::render_ingest_errors(ui, app);
+
+            ui.add_space(10.0);
+            ui.separator();
+            ui.add_space(10.0);
+
+            ui.strong(tr("activity.title"));
+            ui.add_space(6.0);
+            activity::render_activity(ui, app);
+        });
+}
+
+impl LocalMindApp {
+    /// Show the Jobs view
+    pub fn open_jobs(&mut self) {
+        if self.current_view != View::Jobs {
+            self.previous_view = self.current_view.clone();
+            self.current_view = View::Jobs;
+        }
+        self.selected_index = None;
+        self.load_jobs();
+        self.load_ingest_errors();
+    }
+
+    /// Load the bookmark fetches that are queued or in progress
+    pub fn load_jobs(&mut self) {
+        if self.jobs_receiver.is_some() {
+            return;
+        }
+
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let runtime_handle = self.runtime.clone();
+
+        runtime_handle.spawn(async move {
+            let rag_lock = rag.read().await;
+            let mut items = Vec::new();
+            if let Some(ref rag) = *rag_lock {
+                for status in [
+                    crate::ingest_queue::QueueStatus::InProgress,
+                    crate::ingest_queue::QueueStatus::Pending,
+                ] {
+                    match rag.db.get_queue_items_by_status(&status).await {
+                        Ok(found) => items.extend(found),
+                        Err(e) => eprintln!("Failed to load queued bookmarks: {}", e),
+                    }
+                }
+            }
+            let _ = tx.send(items);
+        });
+
+        self.jobs_receiver = Some(rx);
+    }
+
+    /// Check if the Jobs view's queue items have loaded
+    pub(crate) fn check_jobs_loaded(&mut self) {
+        if let Some(ref rx) = self.jobs_receiver {
+            match rx.try_recv() {
+                Ok(items) => {
+                    self.job_items = items;
+                    self.jobs_receiver = None;
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.jobs_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Check if the Jobs view's queue items are loading
+    pub fn is_jobs_loading(&self) -> bool {
+        self.jobs_receiver.is_some()
+    }
+}
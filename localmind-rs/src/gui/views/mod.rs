@@ -2,6 +2,12 @@
 //!
 //! Each view module contains render functions for a specific screen.
 
+pub mod browse;
 pub mod document;
+pub mod explore;
 pub mod home;
+pub mod jobs;
 pub mod search;
+pub mod stats;
+pub mod timeline;
+pub mod trash;
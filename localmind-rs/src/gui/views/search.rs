@@ -3,8 +3,19 @@
 use egui::Ui;
 use egui_remixicon::icons;
 
-use crate::gui::app::LocalMindApp;
-use crate::gui::state::View;
+use crate::db::AutocompleteSuggestion;
+use crate::gui::accessibility::Labeled;
+use crate::gui::app::{strip_frontmatter_snippet, LocalMindApp};
+use crate::gui::i18n::{tr, tr_args};
+use crate::gui::presenter::{self, ResultGroup};
+use crate::gui::state::{SearchResultView, View};
+use crate::gui::widgets::{
+    document_actions, domain_preferences, favicon, favorite, language, reading_list, thumbnail,
+};
+use crate::rag::FusedSearch;
+
+/// Suggestions of each kind (titles, tags, domains) shown under the search box
+const AUTOCOMPLETE_LIMIT: usize = 5;
 
 /// Render the search results view
 pub fn render_search_results(ui: &mut Ui, app: &mut LocalMindApp) {
@@ -13,7 +24,7 @@ pub fn render_search_results(ui: &mut Ui, app: &mut LocalMindApp) {
     // Header with back button and query
     ui.horizontal(|ui| {
         // Back button with icon
-        let back_button = ui.button(icons::ARROW_LEFT_LINE);
+        let back_button = ui.button(icons::ARROW_LEFT_LINE).labeled(tr("common.back"));
 
         if back_button.hovered() {
             ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
@@ -27,160 +38,204 @@ pub fn render_search_results(ui: &mut Ui, app: &mut LocalMindApp) {
         }
 
         ui.add_space(10.0);
-        ui.heading(format!("Results for \"{}\"", app.search_query));
+        ui.heading(tr_args(
+            "search.results_for",
+            &[("query", &app.search_query)],
+        ));
 
         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-            ui.weak(format!("{} results", app.search_results.len()));
+            ui.weak(tr_args(
+                "common.results_count",
+                &[("count", &app.search_results.len())],
+            ));
+            if app.is_refreshing_search() {
+                ui.spinner().on_hover_text(tr("search.refreshing"));
+            }
+            thumbnail::layout_toggle(ui, app);
+
+            let unread: Vec<i64> = app
+                .search_results
+                .iter()
+                .map(|r| r.doc_id)
+                .filter(|id| app.is_unread(*id))
+                .collect();
+            if !unread.is_empty() && ui.button(tr("home.mark_all_read")).clicked() {
+                app.set_read(unread, true);
+            }
         });
     });
 
+    // Spelling correction notice, with a way back to the query as typed
+    if let Some(corrected) = app.corrected_query.clone() {
+        if !app.is_search_pending() {
+            let mut search_exactly = false;
+            ui.horizontal(|ui| {
+                ui.label(tr("search.showing_results_for"));
+                ui.strong(&corrected);
+                ui.weak("·");
+                search_exactly = ui
+                    .link(tr_args(
+                        "search.search_instead",
+                        &[("query", &app.search_query.trim())],
+                    ))
+                    .clicked();
+            });
+            if search_exactly {
+                app.search_exactly();
+            }
+        }
+    }
+
     ui.add_space(10.0);
 
     // Relevance cutoff slider (filters fused RRF scores, normalised 0-1)
     ui.horizontal(|ui| {
-        ui.label("Relevance threshold:");
+        ui.label(tr("search.threshold"));
         let old_cutoff = app.similarity_cutoff;
         ui.add(egui::Slider::new(&mut app.similarity_cutoff, 0.0..=1.0).step_by(0.05));
 
         if (old_cutoff - app.similarity_cutoff).abs() > 0.001 {
             app.apply_search_filters();
         }
+
+        ui.add_space(20.0);
+
+        // Dead documents are filtered in the search itself, so re-run on toggle
+        if ui
+            .checkbox(&mut app.show_dead_documents, tr("search.show_dead"))
+            .changed()
+        {
+            app.trigger_search();
+        }
+
+        let mut unread_only = app.unread_only;
+        if ui
+            .checkbox(&mut unread_only, tr("home.unread_only"))
+            .changed()
+        {
+            app.set_unread_only(unread_only);
+        }
+
+        if ui
+            .checkbox(&mut app.expand_query, tr("search.expand_query"))
+            .on_hover_text(tr("search.expand_query_hint"))
+            .changed()
+        {
+            app.trigger_search();
+        }
+
+        if ui
+            .checkbox(&mut app.show_passages, tr("search.passages"))
+            .on_hover_text(tr("search.passages_hint"))
+            .changed()
+        {
+            app.trigger_search();
+        }
+
+        if ui
+            .add_enabled(
+                !app.show_passages,
+                egui::Checkbox::new(&mut app.group_by_site, tr("search.group_by_site")),
+            )
+            .on_hover_text(tr("search.group_by_site_hint"))
+            .changed()
+        {
+            app.selected_index = None;
+        }
+
+        if ui
+            .checkbox(&mut app.boost_linked_results, tr("search.boost_linked"))
+            .on_hover_text(tr("search.boost_linked_hint"))
+            .changed()
+        {
+            app.trigger_search();
+        }
     });
 
     ui.add_space(10.0);
     ui.separator();
     ui.add_space(10.0);
 
-    // Check if search is in progress
-    if app.is_search_pending() {
+    // Check if search is in progress; restored results stay on screen while
+    // they are refreshed
+    if app.is_search_pending() && !app.is_refreshing_search() {
         ui.vertical_centered(|ui| {
             ui.add_space(50.0);
             ui.spinner();
             ui.add_space(10.0);
-            ui.label("Searching...");
+            ui.label(tr("search.searching"));
         });
         return;
     }
 
-    // No results message
+    // No results: offer weak matches, respellings and a web search
     if app.search_results.is_empty() {
-        ui.vertical_centered(|ui| {
-            ui.add_space(50.0);
-            ui.label("No results found");
-            ui.add_space(10.0);
-            ui.weak("Try a different search query or lower the relevance threshold.");
-
-            if app.similarity_cutoff > 0.1 {
-                ui.add_space(20.0);
-                if ui.button("Lower threshold and retry").clicked() {
-                    app.similarity_cutoff = (app.similarity_cutoff - 0.1).max(0.0);
-                    app.apply_search_filters();
-                }
-            }
-        });
+        render_no_results(ui, app);
         return;
     }
 
-    // Scrollable results list
-    egui::ScrollArea::vertical()
-        .auto_shrink([false, false])
-        .show(ui, |ui| {
-            for result in &app.search_results.clone() {
-                ui.push_id(result.doc_id, |ui| {
-                    // Clickable result card
-                    let card_fill = if result.is_needs_auth {
-                        if ui.visuals().dark_mode {
-                            egui::Color32::from_rgb(50, 40, 20) // Dark amber tint
-                        } else {
-                            egui::Color32::from_rgb(255, 248, 230) // Light amber tint
-                        }
-                    } else if ui.visuals().dark_mode {
-                        egui::Color32::from_rgb(30, 40, 60) // Dark blue-gray
-                    } else {
-                        egui::Color32::from_gray(245)
-                    };
+    // Save the current results as a new collection
+    ui.horizontal(|ui| {
+        ui.add(
+            egui::TextEdit::singleline(&mut app.new_collection_name)
+                .hint_text(tr("search.collection_name"))
+                .desired_width(200.0),
+        )
+        .labeled_field(tr("search.collection_name"));
+        let can_save =
+            !app.is_collection_action_pending() && !app.new_collection_name.trim().is_empty();
+        if ui
+            .add_enabled(can_save, egui::Button::new(tr("search.save_collection")))
+            .clicked()
+        {
+            let name = app.new_collection_name.clone();
+            let doc_ids = app.search_results.iter().map(|r| r.doc_id).collect();
+            app.create_collection(&name, doc_ids);
+        }
 
-                    let response = egui::Frame::none()
-                        .fill(card_fill)
-                        .rounding(4.0)
-                        .inner_margin(12.0)
-                        .show(ui, |ui| {
-                            ui.set_width(ui.available_width());
+        // Operators can be kept as a smart collection that follows new documents
+        let filters = crate::query_syntax::parse(&app.search_query).filter_expression();
+        if !filters.is_empty()
+            && ui
+                .add_enabled(
+                    can_save,
+                    egui::Button::new(tr("search.save_smart_collection")),
+                )
+                .on_hover_text(tr_args(
+                    "search.save_smart_collection_hint",
+                    &[("filters", &filters)],
+                ))
+                .clicked()
+        {
+            let name = app.new_collection_name.clone();
+            app.create_smart_collection(&name, &filters);
+        }
+    });
 
-                            // Title row with similarity score
-                            ui.horizontal(|ui| {
-                                if result.is_needs_auth {
-                                    ui.colored_label(
-                                        egui::Color32::from_rgb(200, 150, 0),
-                                        icons::LOCK_LINE,
-                                    );
-                                }
-                                ui.strong(&result.title);
-
-                                ui.with_layout(
-                                    egui::Layout::right_to_left(egui::Align::Center),
-                                    |ui| {
-                                        let score_color = similarity_color(result.similarity);
-                                        egui::Frame::none()
-                                            .fill(score_color)
-                                            .rounding(3.0)
-                                            .inner_margin(egui::vec2(6.0, 2.0))
-                                            .show(ui, |ui| {
-                                                ui.colored_label(
-                                                    egui::Color32::WHITE,
-                                                    format!("{:.0}%", result.similarity * 100.0),
-                                                );
-                                            });
-                                    },
-                                );
-                            });
+    ui.add_space(10.0);
 
-                            // URL if present
-                            if let Some(ref url) = result.url {
-                                ui.weak(truncate_url(url, 70));
-                            }
-
-                            ui.add_space(4.0);
-
-                            // Content snippet (extract after bookmark metadata if present)
-                            let display_snippet = if result.snippet.starts_with("Bookmark:") {
-                                // Find the first double newline (end of metadata section)
-                                if let Some(content_start) = result.snippet.find("\n\n") {
-                                    let actual_content = result.snippet[content_start + 2..].trim();
-                                    if !actual_content.is_empty() {
-                                        Some(actual_content)
-                                    } else {
-                                        None
-                                    }
-                                } else {
-                                    None
-                                }
-                            } else {
-                                Some(result.snippet.as_str())
-                            };
-
-                            if let Some(snippet) = display_snippet {
-                                ui.label(snippet);
-                            }
-                        });
-
-                    // Handle click to view document
-                    if response.response.interact(egui::Sense::click()).clicked() {
-                        println!(
-                            "Clicked search result: {} (id={})",
-                            result.title, result.doc_id
-                        );
-                        app.query_logger.finalize("clicked", Some(result.doc_id));
-                        app.load_document(result.doc_id);
-                    }
-
-                    // Hover effect
-                    if response.response.hovered() {
-                        ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
-                    }
-                });
+    // Bulk actions for checked results
+    document_actions::render_bulk_bar(ui, app);
+
+    // Keyboard selection, by position in the displayed order
+    let selected_doc = app
+        .selected_index
+        .and_then(|i| app.listed_doc_ids().get(i).copied());
 
-                ui.add_space(8.0);
+    // Scrollable results list
+    egui::ScrollArea::vertical()
+        .auto_shrink([false, false])
+        .show(ui, |ui| {
+            if app.is_grouping_results() {
+                for group in presenter::group_by_site(&app.search_results) {
+                    render_result_group(ui, app, &group, selected_doc);
+                }
+            } else {
+                // Passage results repeat documents, so select by position
+                for (i, result) in app.search_results.clone().iter().enumerate() {
+                    render_result_card(ui, app, result, app.selected_index == Some(i));
+                    ui.add_space(8.0);
+                }
             }
 
             // Load more button if there are hidden results
@@ -189,10 +244,7 @@ pub fn render_search_results(ui: &mut Ui, app: &mut LocalMindApp) {
                 ui.add_space(10.0);
                 ui.vertical_centered(|ui| {
                     if ui
-                        .button(format!(
-                            "Show {} more results (lower threshold)",
-                            hidden_count
-                        ))
+                        .button(tr_args("search.show_more", &[("count", &hidden_count)]))
                         .clicked()
                     {
                         app.similarity_cutoff = (app.similarity_cutoff - 0.1).max(0.0);
@@ -203,6 +255,354 @@ pub fn render_search_results(ui: &mut Ui, app: &mut LocalMindApp) {
         });
 }
 
+/// Render one result card and handle its clicks, checkbox, star and
+/// context menu. `selected` outlines the keyboard selection.
+fn render_result_card(
+    ui: &mut Ui,
+    app: &mut LocalMindApp,
+    result: &SearchResultView,
+    selected: bool,
+) {
+    let is_favorite = app.is_favorite(result.doc_id);
+    let is_unread = app.is_unread(result.doc_id);
+    let images = app.document_images(result.doc_id, result.url.as_deref());
+    let mut checked = app.checked_doc_ids.contains(&result.doc_id);
+    let mut check_toggled = false;
+    let mut star_clicked = false;
+    ui.push_id(result.doc_id, |ui| {
+        // Clickable result card
+        let card_fill = if result.is_needs_auth {
+            if ui.visuals().dark_mode {
+                egui::Color32::from_rgb(50, 40, 20) // Dark amber tint
+            } else {
+                egui::Color32::from_rgb(255, 248, 230) // Light amber tint
+            }
+        } else if ui.visuals().dark_mode {
+            egui::Color32::from_rgb(30, 40, 60) // Dark blue-gray
+        } else {
+            egui::Color32::from_gray(245)
+        };
+
+        // Outline the keyboard selection
+        let stroke = if selected {
+            ui.visuals().selection.stroke
+        } else {
+            egui::Stroke::NONE
+        };
+
+        let response = egui::Frame::none()
+            .fill(card_fill)
+            .stroke(stroke)
+            .rounding(4.0)
+            .inner_margin(12.0)
+            .show(ui, |ui| {
+                ui.set_width(ui.available_width());
+
+                // Title row with similarity score
+                ui.horizontal(|ui| {
+                    check_toggled = ui.checkbox(&mut checked, "").changed();
+                    if is_unread {
+                        reading_list::unread_dot(ui);
+                    }
+                    if result.is_needs_auth {
+                        ui.colored_label(egui::Color32::from_rgb(200, 150, 0), icons::LOCK_LINE);
+                    }
+                    if result.is_dead {
+                        ui.colored_label(egui::Color32::from_rgb(200, 60, 60), icons::LINK_UNLINK)
+                            .on_hover_text(tr("search.dead_link"));
+                    }
+                    favicon::document_icon(
+                        ui,
+                        images.favicon,
+                        &result.source,
+                        result.url.is_some(),
+                    );
+                    ui.strong(&result.title);
+                    if let Some(quality) = result.url.as_deref().and_then(|url| {
+                        crate::domain_preferences::quality_in(url, &app.domain_preferences)
+                    }) {
+                        domain_preferences::quality_badge(ui, quality);
+                    }
+                    if let Some(ref code) = images.language {
+                        language::language_badge(ui, code);
+                    }
+
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        star_clicked = favorite::star_button(ui, is_favorite).clicked();
+
+                        let score_color = similarity_color(result.similarity);
+                        egui::Frame::none()
+                            .fill(score_color)
+                            .rounding(3.0)
+                            .inner_margin(egui::vec2(6.0, 2.0))
+                            .show(ui, |ui| {
+                                ui.colored_label(
+                                    egui::Color32::WHITE,
+                                    format!("{:.0}%", result.similarity * 100.0),
+                                );
+                            });
+                    });
+                });
+
+                // URL if present
+                if let Some(ref url) = result.url {
+                    ui.weak(truncate_url(url, &app.tracking_params, 70));
+                }
+
+                if let Some(jpeg) = images.thumbnail {
+                    ui.add_space(4.0);
+                    thumbnail::render_thumbnail(ui, result.doc_id, jpeg);
+                }
+
+                ui.add_space(4.0);
+
+                // Content snippet (extract after bookmark metadata if present)
+                let display_snippet = if result.snippet.starts_with("Bookmark:") {
+                    // Find the first double newline (end of metadata section)
+                    if let Some(content_start) = result.snippet.find("\n\n") {
+                        let actual_content = result.snippet[content_start + 2..].trim();
+                        if !actual_content.is_empty() {
+                            Some(actual_content)
+                        } else {
+                            None
+                        }
+                    } else {
+                        None
+                    }
+                } else {
+                    Some(result.snippet.as_str())
+                };
+
+                if let Some(location) = &result.location {
+                    ui.weak(location);
+                }
+                if let Some(snippet) = display_snippet {
+                    ui.label(snippet);
+                }
+            });
+
+        if selected && app.scroll_to_selection {
+            response.response.scroll_to_me(None);
+            app.scroll_to_selection = false;
+        }
+
+        // Right-click menu; the checkbox and star take precedence
+        // over opening the document
+        let card = response.response.interact(egui::Sense::click());
+        if let Some(action) =
+            document_actions::context_menu(&card, result.url.is_some(), &mut app.tag_input)
+        {
+            app.run_document_action(ui.ctx(), result.doc_id, result.url.clone(), action);
+        } else if check_toggled {
+            if checked {
+                app.checked_doc_ids.insert(result.doc_id);
+            } else {
+                app.checked_doc_ids.remove(&result.doc_id);
+            }
+        } else if star_clicked {
+            app.toggle_favorite(result.doc_id);
+        } else if card.clicked() {
+            println!(
+                "Clicked search result: {} (id={})",
+                result.title, result.doc_id
+            );
+            app.query_logger.finalize("clicked", Some(result.doc_id));
+            app.load_document_at(result.doc_id, result.passage);
+        }
+
+        // Hover effect
+        if card.hovered() {
+            ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
+        }
+    });
+}
+
+/// Render a site's results: a single result as a plain card, several under
+/// a collapsible header showing the best hit and a "show N more" expander.
+fn render_result_group(
+    ui: &mut Ui,
+    app: &mut LocalMindApp,
+    group: &ResultGroup,
+    selected_doc: Option<i64>,
+) {
+    if group.results.len() == 1 {
+        let result = &group.results[0];
+        render_result_card(ui, app, result, selected_doc == Some(result.doc_id));
+        ui.add_space(8.0);
+        return;
+    }
+
+    let expanded = app.expanded_result_groups.contains(&group.label);
+    let mut toggle = false;
+
+    ui.horizontal(|ui| {
+        let icon = if expanded {
+            icons::ARROW_DOWN_S_LINE
+        } else {
+            icons::ARROW_RIGHT_S_LINE
+        };
+        let header = ui.add(
+            egui::Button::new(egui::RichText::new(format!("{} {}", icon, group.label)).strong())
+                .frame(false),
+        );
+        if header.hovered() {
+            ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
+        }
+        toggle = header.clicked();
+        ui.weak(tr_args(
+            "common.results_count",
+            &[("count", &group.results.len())],
+        ));
+    });
+    ui.add_space(4.0);
+
+    let shown = if expanded { group.results.len() } else { 1 };
+    for result in group.results.iter().take(shown) {
+        render_result_card(ui, app, result, selected_doc == Some(result.doc_id));
+        ui.add_space(8.0);
+    }
+
+    let expander = if expanded {
+        tr("search.show_fewer_site").to_string()
+    } else {
+        tr_args("search.show_more_site", &[("count", &group.hidden_count())])
+    };
+    if ui.link(expander).clicked() {
+        toggle = true;
+    }
+    if toggle {
+        if expanded {
+            app.expanded_result_groups.remove(&group.label);
+        } else {
+            app.expanded_result_groups.insert(group.label.clone());
+        }
+        // Positions in the keyboard order shift when a group opens or closes
+        app.selected_index = None;
+    }
+    ui.add_space(12.0);
+}
+
+/// Empty-results screen. Results hidden by the relevance threshold are shown
+/// first as low-relevance matches; otherwise the fallback keyword matches.
+fn render_no_results(ui: &mut Ui, app: &mut LocalMindApp) {
+    let mut low_relevance: Vec<(i64, String, String)> = app
+        .all_results
+        .iter()
+        .filter(|r| {
+            app.selected_profile
+                .as_deref()
+                .map(|p| r.profile.as_deref() == Some(p))
+                .unwrap_or(true)
+        })
+        .take(5)
+        .map(|r| (r.doc_id, r.title.clone(), r.snippet.clone()))
+        .collect();
+    if low_relevance.is_empty() {
+        if let Some(fallback) = &app.search_fallback {
+            low_relevance = fallback
+                .weak_matches
+                .iter()
+                .map(|m| (m.doc_id, m.title.clone(), m.content_snippet.clone()))
+                .collect();
+        }
+    }
+    let suggestions = app
+        .search_fallback
+        .as_ref()
+        .map(|f| f.suggestions.clone())
+        .unwrap_or_default();
+    let web_search_url = app
+        .search_fallback
+        .as_ref()
+        .map(|f| f.web_search_url.clone());
+
+    let mut open_doc_id = None;
+    let mut search_instead = None;
+
+    ui.vertical_centered(|ui| {
+        ui.add_space(30.0);
+        ui.label(tr("search.no_results"));
+        ui.add_space(10.0);
+        ui.weak(tr("search.no_results_hint"));
+
+        if app.similarity_cutoff > 0.1 {
+            ui.add_space(20.0);
+            if ui.button(tr("search.lower_and_retry")).clicked() {
+                app.similarity_cutoff = (app.similarity_cutoff - 0.1).max(0.0);
+                app.apply_search_filters();
+            }
+        }
+
+        if !suggestions.is_empty() {
+            ui.add_space(20.0);
+            ui.horizontal_wrapped(|ui| {
+                ui.label(tr("search.did_you_mean"));
+                for suggestion in &suggestions {
+                    if ui.link(format!("\"{}\"", suggestion)).clicked() {
+                        search_instead = Some(suggestion.clone());
+                    }
+                }
+            });
+        }
+
+        if let Some(url) = &web_search_url {
+            ui.add_space(10.0);
+            if ui
+                .button(format!(
+                    "{} {}",
+                    icons::GLOBAL_LINE,
+                    tr("search.search_web")
+                ))
+                .on_hover_text(url)
+                .clicked()
+            {
+                if let Err(e) = open::that(url) {
+                    eprintln!("Failed to open web search: {}", e);
+                }
+            }
+        }
+    });
+
+    if !low_relevance.is_empty() {
+        ui.add_space(30.0);
+        ui.separator();
+        ui.add_space(10.0);
+        ui.horizontal(|ui| {
+            ui.strong(tr("search.low_relevance"));
+            ui.weak(tr("search.low_relevance_hint"));
+        });
+        ui.add_space(10.0);
+        egui::ScrollArea::vertical()
+            .id_salt("low_relevance_results")
+            .show(ui, |ui| {
+                for (doc_id, title, snippet) in &low_relevance {
+                    let response = egui::Frame::group(ui.style())
+                        .show(ui, |ui| {
+                            ui.set_width(ui.available_width());
+                            ui.label(title);
+                            ui.weak(snippet);
+                        })
+                        .response
+                        .interact(egui::Sense::click());
+                    if response.hovered() {
+                        ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
+                    }
+                    if response.clicked() {
+                        open_doc_id = Some(*doc_id);
+                    }
+                    ui.add_space(6.0);
+                }
+            });
+    }
+
+    if let Some(query) = search_instead {
+        app.search_instead(query);
+    } else if let Some(doc_id) = open_doc_id {
+        app.query_logger.finalize("clicked", Some(doc_id));
+        app.load_document(doc_id);
+    }
+}
+
 /// Get color based on similarity score
 fn similarity_color(score: f32) -> egui::Color32 {
     if score >= 0.5 {
@@ -216,11 +616,629 @@ fn similarity_color(score: f32) -> egui::Color32 {
     }
 }
 
-/// Truncate a URL for display
-fn truncate_url(url: &str, max_len: usize) -> String {
+/// Truncate a URL for display, without its tracking parameters
+fn truncate_url(url: &str, tracking_params: &[String], max_len: usize) -> String {
+    let url = crate::tracking_params::strip(url, tracking_params);
     if url.len() <= max_len {
-        url.to_string()
+        url
     } else {
         format!("{}...", &url[..max_len])
     }
 }
+
+/// URL and source of each document, for the result link actions and for
+/// grouping results by site. Search hits carry IDs only.
+async fn result_locations(
+    rag: &crate::rag::RagPipeline,
+    ids: &[i64],
+) -> std::collections::HashMap<i64, (Option<String>, String)> {
+    rag.db
+        .get_documents_batch(ids)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .map(|doc| (doc.id, (doc.url, doc.source)))
+        .collect()
+}
+
+/// Search result views for a saved search session, hydrated from the
+/// documents it found, in its rank order. Snippets come from each hit's
+/// passage when it still fits the document.
+async fn session_results(
+    rag: &crate::rag::RagPipeline,
+    session: &crate::db::SearchSession,
+) -> Vec<SearchResultView> {
+    let searched = session.corrected_query.as_deref().unwrap_or(&session.query);
+    let snippet_query = crate::query_syntax::parse(searched).free_text();
+    let snippet_len = if session.passages { 300 } else { 200 };
+    let ids: Vec<i64> = session.hits.iter().map(|hit| hit.doc_id).collect();
+    let docs: std::collections::HashMap<i64, crate::db::Document> = rag
+        .db
+        .get_documents_batch(&ids)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .map(|doc| (doc.id, doc))
+        .collect();
+
+    session
+        .hits
+        .iter()
+        .filter_map(|hit| {
+            let doc = docs.get(&hit.doc_id)?;
+            let text = hit
+                .passage
+                .and_then(|(start, end)| doc.content.get(start..end))
+                .unwrap_or(&doc.content);
+            Some(SearchResultView {
+                doc_id: hit.doc_id,
+                snippet: create_snippet(text, &snippet_query, snippet_len),
+                title: doc.title.clone(),
+                similarity: hit.similarity,
+                url: doc.url.clone(),
+                source: doc.source.clone(),
+                profile: doc.profile.clone(),
+                is_needs_auth: doc.needs_auth.unwrap_or(false),
+                is_dead: doc.is_dead.unwrap_or(false),
+                passage: hit.passage,
+                location: None,
+            })
+        })
+        .collect()
+}
+
+/// Create a content snippet of at most `max_len` characters, centered on
+/// the words of `query` (see `snippet::centered_snippet`).
+/// Strips YAML frontmatter so `---\n{}\n---` never leaks into the UI.
+fn create_snippet(content: &str, query: &str, max_len: usize) -> String {
+    let content = strip_frontmatter_snippet(content.trim());
+    crate::snippet::centered_snippet(content, query, max_len)
+}
+
+impl LocalMindApp {
+    /// Trigger a search with the current query
+    pub fn trigger_search(&mut self) {
+        let query = self.search_query.trim().to_string();
+        if query.is_empty() {
+            return;
+        }
+
+        if self.search_receiver.is_some() {
+            if !self.refreshing_search {
+                return; // Already searching
+            }
+            // A new search replaces the background refresh of restored results
+            self.search_receiver = None;
+            self.refreshing_search = false;
+        }
+        self.restored_search_receiver = None;
+
+        println!("Triggering search for: {}", query);
+        self.close_autocomplete();
+
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let runtime_handle = self.runtime.clone();
+        let options = crate::rag::SearchOptions {
+            include_dead: self.show_dead_documents,
+            expand_query: self.expand_query,
+            collection_id: self.search_collection,
+            boost_linked: self.boost_linked_results,
+            correct_spelling: self.exact_query.as_deref() != Some(query.as_str()),
+        };
+
+        let passages = self.show_passages;
+
+        runtime_handle.spawn(async move {
+            let rag_lock = rag.read().await;
+            let results = if let Some(ref rag) = *rag_lock {
+                if passages {
+                    match rag.search_passages(&query, options).await {
+                        Ok(hits) => {
+                            let snippet_query = crate::query_syntax::parse(&query).free_text();
+                            let ids: Vec<i64> = hits.iter().map(|hit| hit.doc_id).collect();
+                            let docs = result_locations(rag, &ids).await;
+                            let results = hits
+                                .into_iter()
+                                .map(|hit| {
+                                    let (url, source) =
+                                        docs.get(&hit.doc_id).cloned().unwrap_or_default();
+                                    // Passages open the page at their section
+                                    let url = url
+                                        .map(|url| presenter::section_url(&url, &hit.provenance));
+                                    SearchResultView {
+                                        url,
+                                        source,
+                                        doc_id: hit.doc_id,
+                                        title: hit.title,
+                                        snippet: create_snippet(&hit.text, &snippet_query, 300),
+                                        similarity: hit.similarity,
+                                        profile: hit.profile,
+                                        is_needs_auth: hit.needs_auth,
+                                        is_dead: hit.is_dead,
+                                        passage: Some((hit.chunk_start, hit.chunk_end)),
+                                        location: presenter::passage_location(&hit.provenance),
+                                    }
+                                })
+                                .collect();
+                            (results, None)
+                        }
+                        Err(e) => {
+                            eprintln!("Passage search failed: {}", e);
+                            (Vec::new(), None)
+                        }
+                    }
+                } else {
+                    match rag.search_fused(&query, options).await {
+                        Ok(FusedSearch {
+                            hits,
+                            corrected_query,
+                        }) => {
+                            let searched = corrected_query.as_deref().unwrap_or(&query);
+                            let snippet_query = crate::query_syntax::parse(searched).free_text();
+                            let ids: Vec<i64> = hits.iter().map(|hit| hit.doc_id).collect();
+                            let mut docs = result_locations(rag, &ids).await;
+                            let results = hits
+                                .into_iter()
+                                .map(|hit| {
+                                    let (url, source) =
+                                        docs.remove(&hit.doc_id).unwrap_or_default();
+                                    SearchResultView {
+                                        url,
+                                        source,
+                                        doc_id: hit.doc_id,
+                                        title: hit.title,
+                                        snippet: create_snippet(
+                                            &hit.content_snippet,
+                                            &snippet_query,
+                                            200,
+                                        ),
+                                        similarity: hit.similarity,
+                                        profile: hit.profile,
+                                        is_needs_auth: hit.needs_auth,
+                                        is_dead: hit.is_dead,
+                                        passage: hit.chunk,
+                                        location: None,
+                                    }
+                                })
+                                .collect();
+                            (results, corrected_query)
+                        }
+                        Err(e) => {
+                            eprintln!("Search failed: {}", e);
+                            (Vec::new(), None)
+                        }
+                    }
+                }
+            } else {
+                (Vec::new(), None)
+            };
+            let _ = tx.send(results);
+        });
+
+        self.search_receiver = Some(rx);
+        self.search_fallback = None;
+        self.current_view = View::SearchResults;
+    }
+
+    /// Load weak matches and suggestions for a search that found nothing
+    pub(crate) fn load_search_fallback(&mut self) {
+        let query = self.search_query.trim().to_string();
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let runtime_handle = self.runtime.clone();
+        let options = crate::rag::SearchOptions {
+            include_dead: self.show_dead_documents,
+            expand_query: false,
+            collection_id: self.search_collection,
+            boost_linked: false,
+            correct_spelling: false,
+        };
+
+        runtime_handle.spawn(async move {
+            let rag_lock = rag.read().await;
+            if let Some(ref rag) = *rag_lock {
+                let _ = tx.send(rag.search_fallback(&query, options).await);
+            }
+        });
+
+        self.search_fallback_receiver = Some(rx);
+    }
+
+    /// Check if the empty-search fallback has loaded
+    pub(crate) fn check_search_fallback(&mut self) {
+        if let Some(ref rx) = self.search_fallback_receiver {
+            match rx.try_recv() {
+                Ok(fallback) => {
+                    self.search_fallback = Some(fallback);
+                    self.search_fallback_receiver = None;
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.search_fallback_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Look up completions for the text in the search box
+    pub(crate) fn load_autocomplete(&mut self) {
+        let prefix = self.search_query.trim().to_string();
+        if prefix.is_empty() {
+            self.close_autocomplete();
+            return;
+        }
+
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let runtime_handle = self.runtime.clone();
+
+        runtime_handle.spawn(async move {
+            let rag_lock = rag.read().await;
+            if let Some(ref rag) = *rag_lock {
+                match rag
+                    .db
+                    .get_autocomplete_suggestions(&prefix, AUTOCOMPLETE_LIMIT)
+                    .await
+                {
+                    Ok(suggestions) => {
+                        let _ = tx.send((prefix, suggestions));
+                    }
+                    Err(e) => eprintln!("Autocomplete failed: {}", e),
+                }
+            }
+        });
+
+        self.autocomplete_receiver = Some(rx);
+    }
+
+    /// Check if completions have loaded, dropping any for text that has
+    /// since changed
+    pub(crate) fn check_autocomplete(&mut self) {
+        if let Some(ref rx) = self.autocomplete_receiver {
+            match rx.try_recv() {
+                Ok((prefix, suggestions)) => {
+                    if prefix == self.search_query.trim() {
+                        self.autocomplete = suggestions;
+                        self.autocomplete_index = None;
+                    }
+                    self.autocomplete_receiver = None;
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.autocomplete_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Hide the autocomplete dropdown
+    pub fn close_autocomplete(&mut self) {
+        self.autocomplete.clear();
+        self.autocomplete_index = None;
+        self.autocomplete_receiver = None;
+    }
+
+    /// Act on a completion: open the document, or search the tag or domain
+    pub fn apply_autocomplete(&mut self, index: usize) {
+        let Some(suggestion) = self.autocomplete.get(index).cloned() else {
+            return;
+        };
+        self.close_autocomplete();
+        match suggestion {
+            AutocompleteSuggestion::Document { id, .. } => self.load_document(id),
+            AutocompleteSuggestion::Tag { tag, .. } => {
+                self.search_instead(format!("tag:{}", tag));
+            }
+            AutocompleteSuggestion::Domain { domain, .. } => {
+                self.search_instead(format!("site:{}", domain));
+            }
+        }
+    }
+
+    /// Run a suggested query in place of the current one
+    pub fn search_instead(&mut self, query: String) {
+        self.search_query = query;
+        self.trigger_search();
+    }
+
+    /// Re-run the current query as typed, without spelling correction
+    pub fn search_exactly(&mut self) {
+        self.exact_query = Some(self.search_query.trim().to_string());
+        self.trigger_search();
+    }
+
+    /// Check if search results have arrived
+    pub(crate) fn check_search_results(&mut self) {
+        if let Some(ref rx) = self.search_receiver {
+            match rx.try_recv() {
+                Ok((results, corrected_query)) => {
+                    println!("Search returned {} results", results.len());
+                    self.all_results = results;
+                    self.load_document_images();
+                    self.expanded_result_groups.clear();
+                    self.corrected_query = corrected_query;
+                    self.apply_search_filters();
+                    self.search_receiver = None;
+                    self.selected_index = None;
+                    self.checked_doc_ids.clear();
+                    // A background refresh is not a search the user ran
+                    if !std::mem::take(&mut self.refreshing_search) {
+                        self.query_logger
+                            .record_search(&self.search_query, &self.search_results);
+                        self.record_search_usage();
+                    }
+                    self.save_search_session();
+                    if self.search_results.is_empty() {
+                        self.load_search_fallback();
+                    }
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {
+                    // Still searching
+                }
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    // Channel closed, clear receiver
+                    self.search_receiver = None;
+                    self.refreshing_search = false;
+                }
+            }
+        }
+    }
+
+    /// Record the finished search and its result count for usage insights
+    pub(crate) fn record_search_usage(&self) {
+        let query = self.search_query.trim().to_string();
+        let result_count = self.all_results.len();
+        let rag = self.rag.clone();
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            if let Some(ref rag) = *rag_lock {
+                if let Err(e) = rag.db.record_search(&query, result_count).await {
+                    eprintln!("Failed to record search: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Apply similarity cutoff, profile filter, and deduplication to produce search_results.
+    pub fn apply_search_filters(&mut self) {
+        let filters = presenter::ResultFilters {
+            similarity_cutoff: self.similarity_cutoff,
+            unread_only: self.unread_only,
+            unread_ids: &self.unread_ids,
+            profile: self.selected_profile.as_deref(),
+        };
+        self.search_results = presenter::filter_results(&self.all_results, &filters);
+    }
+
+    /// Save the finished search's results so they can be restored on the
+    /// next launch
+    pub(crate) fn save_search_session(&self) {
+        let session = crate::db::SearchSession {
+            query: self.search_query.trim().to_string(),
+            corrected_query: self.corrected_query.clone(),
+            passages: self.show_passages,
+            hits: self
+                .all_results
+                .iter()
+                .map(|r| crate::db::SessionHit {
+                    doc_id: r.doc_id,
+                    similarity: r.similarity,
+                    passage: r.passage,
+                })
+                .collect(),
+        };
+        let rag = self.rag.clone();
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            if let Some(ref rag) = *rag_lock {
+                if let Err(e) = rag.db.save_search_session(&session).await {
+                    eprintln!("Failed to save search session: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Show the results saved for the restored query straight away, hydrated
+    /// from the database, then re-run the query in the background if
+    /// `refresh_restored_search` is on. Searches normally when nothing was
+    /// saved for the query.
+    pub(crate) fn restore_search(&mut self) {
+        let query = self.search_query.trim().to_string();
+        if query.is_empty() {
+            return;
+        }
+
+        let passages = self.show_passages;
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            let restored = match *rag_lock {
+                Some(ref rag) => match rag.db.get_search_session(&query, passages).await {
+                    Ok(Some(session)) if !session.hits.is_empty() => {
+                        let refresh = rag.db.get_refresh_restored_search().await.unwrap_or(true);
+                        let results = session_results(rag, &session).await;
+                        Some((results, session.corrected_query, refresh))
+                    }
+                    Ok(_) => None,
+                    Err(e) => {
+                        eprintln!("Failed to load search session: {}", e);
+                        None
+                    }
+                },
+                None => None,
+            };
+            let _ = tx.send(restored);
+        });
+
+        self.restored_search_receiver = Some(rx);
+        self.search_fallback = None;
+        self.current_view = View::SearchResults;
+    }
+
+    /// Check if the results restored at launch have loaded
+    pub(crate) fn check_restored_search(&mut self) {
+        if let Some(ref rx) = self.restored_search_receiver {
+            match rx.try_recv() {
+                // Left the results view before they loaded
+                Ok(_) if self.current_view != View::SearchResults => {
+                    self.restored_search_receiver = None;
+                }
+                Ok(Some((results, corrected_query, refresh))) if !results.is_empty() => {
+                    self.restored_search_receiver = None;
+                    self.all_results = results;
+                    self.load_document_images();
+                    self.expanded_result_groups.clear();
+                    self.corrected_query = corrected_query;
+                    self.apply_search_filters();
+                    self.selected_index = None;
+                    self.checked_doc_ids.clear();
+                    if refresh {
+                        self.trigger_search();
+                        self.refreshing_search = self.search_receiver.is_some();
+                    }
+                }
+                Ok(_) => {
+                    self.restored_search_receiver = None;
+                    self.trigger_search();
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.restored_search_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Check if a search is in progress, including one restored at launch
+    /// that has not loaded yet
+    pub fn is_search_pending(&self) -> bool {
+        self.search_receiver.is_some() || self.restored_search_receiver.is_some()
+    }
+
+    /// Check if restored results are being refreshed in the background
+    pub fn is_refreshing_search(&self) -> bool {
+        self.refreshing_search
+    }
+
+    /// Turn the background refresh of restored search results on or off
+    pub fn set_refresh_restored_search(&mut self, enabled: bool) {
+        self.refresh_restored_search = enabled;
+        let rag = self.rag.clone();
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            if let Some(ref rag) = *rag_lock {
+                if let Err(e) = rag.db.set_refresh_restored_search(enabled).await {
+                    eprintln!("Failed to save search restore setting: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Whether search results are shown grouped by site. Passage results are
+    /// never grouped, so passages of one document stay in score order.
+    pub fn is_grouping_results(&self) -> bool {
+        self.group_by_site && !self.show_passages
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn autocomplete_keeps_only_suggestions_for_the_current_query() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let mut app = LocalMindApp::detached(runtime.handle().clone());
+        let suggestion = AutocompleteSuggestion::Tag {
+            tag: "rust".to_string(),
+            count: 3,
+        };
+
+        app.search_query = "rus".to_string();
+        let (tx, rx) = std::sync::mpsc::channel();
+        app.autocomplete_receiver = Some(rx);
+        tx.send(("ru".to_string(), vec![suggestion.clone()]))
+            .unwrap();
+        app.check_autocomplete();
+        assert!(app.autocomplete.is_empty());
+        assert!(app.autocomplete_receiver.is_none());
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        app.autocomplete_receiver = Some(rx);
+        tx.send(("rus".to_string(), vec![suggestion.clone()]))
+            .unwrap();
+        app.check_autocomplete();
+        assert_eq!(app.autocomplete, vec![suggestion]);
+
+        app.close_autocomplete();
+        assert!(app.autocomplete.is_empty());
+        assert_eq!(app.autocomplete_index, None);
+    }
+
+    fn result(doc_id: i64, similarity: f32) -> SearchResultView {
+        SearchResultView {
+            doc_id,
+            title: format!("Document {}", doc_id),
+            snippet: String::new(),
+            similarity,
+            url: None,
+            source: "test".to_string(),
+            profile: None,
+            is_needs_auth: false,
+            is_dead: false,
+            passage: None,
+            location: None,
+        }
+    }
+
+    #[test]
+    fn search_results_are_filtered_into_the_results_view() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut app = LocalMindApp::detached(runtime.handle().clone());
+        app.query_logger = crate::query_logger::QueryLogger::new(dir.path().join("log.jsonl"));
+        app.similarity_cutoff = 0.3;
+        app.selected_index = Some(4);
+
+        app.search_query = "rust".to_string();
+        app.trigger_search();
+        assert_eq!(app.current_view, View::SearchResults);
+        assert!(app.is_search_pending());
+
+        // Stand in for the search itself, which needs the RAG pipeline
+        let (tx, rx) = std::sync::mpsc::channel();
+        app.search_receiver = Some(rx);
+        tx.send((
+            vec![result(1, 0.9), result(2, 0.1), result(3, 0.5)],
+            Some("rust lang".to_string()),
+        ))
+        .unwrap();
+        app.check_search_results();
+
+        assert!(!app.is_search_pending());
+        assert_eq!(app.all_results.len(), 3);
+        let shown: Vec<i64> = app.search_results.iter().map(|r| r.doc_id).collect();
+        assert_eq!(shown, vec![1, 3]);
+        assert_eq!(app.corrected_query.as_deref(), Some("rust lang"));
+        assert_eq!(app.selected_index, None);
+        assert_eq!(app.current_view, View::SearchResults);
+        assert!(app.search_fallback_receiver.is_none());
+    }
+
+    #[test]
+    fn search_is_pending_until_its_results_arrive() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let mut app = LocalMindApp::detached(runtime.handle().clone());
+        let (tx, rx) = std::sync::mpsc::channel();
+        app.search_receiver = Some(rx);
+        app.refreshing_search = true;
+        assert!(app.is_search_pending());
+
+        drop(tx);
+        app.check_search_results();
+        assert!(!app.is_search_pending());
+        assert!(!app.refreshing_search);
+    }
+}
@@ -0,0 +1,230 @@
+//! Stats view with index counts and locally recorded usage insights
+
+use egui::Ui;
+use egui_remixicon::icons;
+
+use crate::gui::accessibility::Labeled;
+use crate::gui::app::LocalMindApp;
+use crate::gui::i18n::{tr, tr_args};
+use crate::gui::state::View;
+
+/// Render the stats view
+pub fn render_stats_view(ui: &mut Ui, app: &mut LocalMindApp) {
+    ui.add_space(10.0);
+
+    let loading = app.is_stats_loading();
+
+    // Header with back button and the usage purge
+    ui.horizontal(|ui| {
+        let back_button = ui.button(icons::ARROW_LEFT_LINE).labeled(tr("common.back"));
+
+        if back_button.hovered() {
+            ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
+        }
+
+        if back_button.clicked() {
+            app.navigate_back();
+        }
+
+        ui.add_space(10.0);
+        ui.heading(tr("stats.title"));
+
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            if ui
+                .add_enabled(!loading, egui::Button::new(tr("stats.purge")))
+                .on_hover_text(tr("stats.purge_hint"))
+                .clicked()
+            {
+                app.purge_usage_data();
+            }
+        });
+    });
+
+    ui.add_space(10.0);
+
+    if loading && app.index_stats.is_none() && app.usage_insights.is_none() {
+        ui.vertical_centered(|ui| {
+            ui.add_space(50.0);
+            ui.spinner();
+        });
+        return;
+    }
+
+    let mut open_doc_id = None;
+
+    egui::ScrollArea::vertical()
+        .id_salt("stats_view")
+        .auto_shrink([false, false])
+        .show(ui, |ui| {
+            if let Some(stats) = &app.index_stats {
+                ui.strong(tr("stats.index"));
+                egui::Grid::new("index_stats")
+                    .num_columns(2)
+                    .spacing([20.0, 4.0])
+                    .show(ui, |ui| {
+                        ui.label(tr("stats.documents"));
+                        ui.label(stats.documents.to_string());
+                        ui.end_row();
+                        ui.label(tr("document.chunks"));
+                        ui.label(stats.chunks.to_string());
+                        ui.end_row();
+                        if stats.chunks_needing_migration > 0 {
+                            ui.label(tr("stats.needs_migration"));
+                            ui.colored_label(
+                                ui.visuals().warn_fg_color,
+                                stats.chunks_needing_migration.to_string(),
+                            )
+                            .on_hover_text(tr("stats.needs_migration_hint"));
+                            ui.end_row();
+                        }
+                        ui.label(tr("stats.dead_links"));
+                        ui.label(stats.dead.to_string());
+                        ui.end_row();
+                        ui.label(tr("stats.login_required"));
+                        ui.label(stats.needs_auth.to_string());
+                        ui.end_row();
+                        for (source, count) in &stats.by_source {
+                            ui.weak(source);
+                            ui.weak(count.to_string());
+                            ui.end_row();
+                        }
+                    });
+                ui.add_space(16.0);
+            }
+
+            let Some(insights) = &app.usage_insights else {
+                return;
+            };
+
+            ui.horizontal(|ui| {
+                ui.strong(tr("stats.usage"));
+                ui.weak(tr_args(
+                    "stats.searches_recorded",
+                    &[("count", &insights.searches)],
+                ));
+            });
+            ui.add_space(6.0);
+
+            ui.label(tr("stats.most_revisited"));
+            if insights.most_revisited.is_empty() {
+                ui.weak(tr("stats.no_opens"));
+            }
+            for (doc_id, title, opens) in &insights.most_revisited {
+                ui.horizontal(|ui| {
+                    if ui.link(title).clicked() {
+                        open_doc_id = Some(*doc_id);
+                    }
+                    ui.weak(tr_args("stats.opens", &[("count", opens)]));
+                });
+            }
+            ui.add_space(10.0);
+
+            ui.label(tr("stats.zero_results"));
+            ui.weak(tr("stats.zero_results_hint"));
+            if insights.zero_result_queries.is_empty() {
+                ui.weak(tr("common.none"));
+            }
+            for (query, times) in &insights.zero_result_queries {
+                ui.horizontal(|ui| {
+                    ui.label(format!("\"{}\"", query));
+                    if *times > 1 {
+                        ui.weak(tr_args("stats.times", &[("count", times)]));
+                    }
+                });
+            }
+            ui.add_space(10.0);
+
+            ui.label(tr("stats.busiest_days"));
+            if insights.busiest_ingest_days.is_empty() {
+                ui.weak(tr("home.no_documents"));
+            }
+            for (day, documents) in &insights.busiest_ingest_days {
+                ui.horizontal(|ui| {
+                    ui.label(day);
+                    ui.weak(tr_args("common.documents_count", &[("count", documents)]));
+                });
+            }
+        });
+
+    if let Some(doc_id) = open_doc_id {
+        app.load_document(doc_id);
+    }
+}
+
+impl LocalMindApp {
+    /// Show the Stats view
+    pub fn open_stats(&mut self) {
+        if self.current_view != View::Stats {
+            self.previous_view = self.current_view.clone();
+            self.current_view = View::Stats;
+        }
+        self.selected_index = None;
+        self.spawn_stats_update(false);
+    }
+
+    /// Forget recorded searches and document open counts, then reload the
+    /// Stats view
+    pub fn purge_usage_data(&mut self) {
+        if self.stats_receiver.is_some() {
+            return;
+        }
+        self.spawn_stats_update(true);
+    }
+
+    /// Optionally purge usage data, then send the Stats view data
+    pub(crate) fn spawn_stats_update(&mut self, purge: bool) {
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let runtime_handle = self.runtime.clone();
+
+        runtime_handle.spawn(async move {
+            let rag_lock = rag.read().await;
+            let stats = if let Some(ref rag) = *rag_lock {
+                if purge {
+                    if let Err(e) = rag.db.purge_usage_data().await {
+                        eprintln!("Failed to purge usage data: {}", e);
+                    }
+                }
+                let index_stats = rag
+                    .get_index_stats()
+                    .await
+                    .map_err(|e| eprintln!("Failed to load index stats: {}", e))
+                    .ok();
+                let insights = rag
+                    .db
+                    .get_usage_insights(10)
+                    .await
+                    .map_err(|e| eprintln!("Failed to load usage insights: {}", e))
+                    .ok();
+                (index_stats, insights)
+            } else {
+                (None, None)
+            };
+            let _ = tx.send(stats);
+        });
+
+        self.stats_receiver = Some(rx);
+    }
+
+    /// Check if the Stats view data has loaded
+    pub(crate) fn check_stats_loaded(&mut self) {
+        if let Some(ref rx) = self.stats_receiver {
+            match rx.try_recv() {
+                Ok((index_stats, insights)) => {
+                    self.index_stats = index_stats;
+                    self.usage_insights = insights;
+                    self.stats_receiver = None;
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.stats_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Check if the Stats view data is loading
+    pub fn is_stats_loading(&self) -> bool {
+        self.stats_receiver.is_some()
+    }
+}
@@ -0,0 +1,292 @@
+//! Timeline view: everything saved, newest first, grouped by day, week or
+//! month, loading older documents as it scrolls
+
+use egui::Ui;
+use egui_remixicon::icons;
+
+use crate::gui::accessibility::Labeled;
+use crate::gui::app::{document_view, LocalMindApp};
+use crate::gui::i18n::{tr, tr_args};
+use crate::gui::presenter;
+use crate::gui::state::{TimelineGrouping, View};
+use crate::gui::views::home::render_document_card;
+use crate::gui::widgets::document_actions::{self, DocumentAction};
+use crate::gui::widgets::thumbnail;
+
+/// Documents loaded per timeline page as it scrolls
+const TIMELINE_PAGE_SIZE: usize = 100;
+
+/// Render the timeline view
+pub fn render_timeline_view(ui: &mut Ui, app: &mut LocalMindApp) {
+    ui.add_space(10.0);
+
+    let docs = app.timeline_documents.clone();
+
+    // Header with back button, grouping and count
+    ui.horizontal(|ui| {
+        let back_button = ui.button(icons::ARROW_LEFT_LINE).labeled(tr("common.back"));
+
+        if back_button.hovered() {
+            ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
+        }
+
+        if back_button.clicked() {
+            app.navigate_back();
+        }
+
+        ui.add_space(10.0);
+        ui.heading(tr("timeline.title"));
+
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            let count = if app.timeline_has_more() && !docs.is_empty() {
+                tr_args("timeline.count_more", &[("count", &docs.len())])
+            } else {
+                tr_args("common.documents_count", &[("count", &docs.len())])
+            };
+            ui.weak(count);
+            thumbnail::layout_toggle(ui, app);
+            ui.add_space(10.0);
+            for (grouping, key) in [
+                (TimelineGrouping::Month, "timeline.by_month"),
+                (TimelineGrouping::Week, "timeline.by_week"),
+                (TimelineGrouping::Day, "timeline.by_day"),
+            ] {
+                ui.selectable_value(&mut app.timeline_grouping, grouping, tr(key));
+            }
+        });
+    });
+
+    ui.add_space(6.0);
+
+    // Filter with the same operators as search
+    ui.horizontal(|ui| {
+        let label = ui.label(tr("timeline.filter"));
+        let response = ui
+            .add(
+                egui::TextEdit::singleline(&mut app.timeline_filter)
+                    .hint_text(tr("timeline.filter_hint"))
+                    .desired_width(320.0),
+            )
+            .labelled_by(label.id);
+        let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+        if submitted || ui.button(tr("timeline.apply")).clicked() {
+            app.load_timeline(true);
+        }
+        if !app.timeline_filter.is_empty() && ui.button(tr("common.clear")).clicked() {
+            app.timeline_filter.clear();
+            app.load_timeline(true);
+        }
+
+        let mut unread_only = app.unread_only;
+        if ui
+            .checkbox(&mut unread_only, tr("home.unread_only"))
+            .changed()
+        {
+            app.set_unread_only(unread_only);
+        }
+    });
+
+    ui.add_space(10.0);
+    ui.separator();
+    ui.add_space(10.0);
+
+    if app.is_timeline_loading() && docs.is_empty() {
+        ui.vertical_centered(|ui| {
+            ui.add_space(50.0);
+            ui.spinner();
+        });
+        return;
+    }
+
+    if docs.is_empty() {
+        ui.vertical_centered(|ui| {
+            ui.add_space(50.0);
+            ui.label(tr("timeline.empty"));
+        });
+        return;
+    }
+
+    // Bulk actions for checked documents
+    document_actions::render_bulk_bar(ui, app);
+
+    let created_at: Vec<&str> = docs.iter().map(|d| d.created_at.as_str()).collect();
+    let groups = presenter::timeline_groups(&created_at, app.timeline_grouping);
+
+    let mut clicked_doc_id: Option<i64> = None;
+    let mut star_doc_id: Option<i64> = None;
+    let mut check_doc_id: Option<i64> = None;
+    let mut menu_action: Option<(i64, Option<String>, DocumentAction)> = None;
+    let mut load_more = false;
+
+    egui::ScrollArea::vertical()
+        .id_salt("timeline_documents")
+        .auto_shrink([false, false])
+        .show(ui, |ui| {
+            for (label, range) in groups {
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    ui.strong(label);
+                    ui.weak(tr_args("timeline.group_count", &[("count", &range.len())]));
+                });
+                ui.add_space(6.0);
+
+                for i in range {
+                    let doc = &docs[i];
+                    let selected = app.selected_index == Some(i);
+                    ui.push_id(doc.id, |ui| {
+                        let card = render_document_card(
+                            ui,
+                            doc,
+                            app.document_images(doc.id, doc.url.as_deref()),
+                            &app.tracking_params,
+                            app.is_favorite(doc.id),
+                            app.is_unread(doc.id),
+                            selected,
+                            app.checked_doc_ids.contains(&doc.id),
+                        );
+                        if selected && app.scroll_to_selection {
+                            ui.scroll_to_rect(card.response.rect, None);
+                            app.scroll_to_selection = false;
+                        }
+                        if let Some(action) = document_actions::context_menu(
+                            &card.response,
+                            doc.url.is_some(),
+                            &mut app.tag_input,
+                        ) {
+                            menu_action = Some((doc.id, doc.url.clone(), action));
+                        } else if card.check_toggled {
+                            check_doc_id = Some(doc.id);
+                        } else if card.star_clicked {
+                            star_doc_id = Some(doc.id);
+                        } else if card.response.clicked() {
+                            clicked_doc_id = Some(doc.id);
+                        }
+                    });
+                    ui.add_space(8.0);
+                }
+                ui.add_space(6.0);
+            }
+
+            // Older documents load once the end of the list scrolls into view
+            if app.timeline_has_more() {
+                ui.vertical_centered(|ui| {
+                    let end = ui.spinner();
+                    load_more = ui.is_rect_visible(end.rect);
+                });
+            }
+        });
+
+    if load_more {
+        app.load_timeline(false);
+    }
+
+    // Handle clicks outside the loop to avoid borrow issues
+    if let Some((doc_id, url, action)) = menu_action {
+        app.run_document_action(ui.ctx(), doc_id, url, action);
+    } else if let Some(doc_id) = check_doc_id {
+        if !app.checked_doc_ids.remove(&doc_id) {
+            app.checked_doc_ids.insert(doc_id);
+        }
+    } else if let Some(doc_id) = star_doc_id {
+        app.toggle_favorite(doc_id);
+    } else if let Some(doc_id) = clicked_doc_id {
+        app.load_document(doc_id);
+    }
+}
+
+impl LocalMindApp {
+    /// Show the timeline of everything saved
+    pub fn open_timeline(&mut self) {
+        if self.current_view != View::Timeline {
+            self.previous_view = self.current_view.clone();
+            self.current_view = View::Timeline;
+        }
+        self.selected_index = None;
+        self.load_timeline(true);
+    }
+
+    /// Load the next timeline page, or with `reset` the first one again
+    /// (after the filter changed). A reset replaces any page still loading.
+    pub fn load_timeline(&mut self, reset: bool) {
+        if !reset && (self.timeline_receiver.is_some() || self.timeline_exhausted) {
+            return;
+        }
+
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let runtime_handle = self.runtime.clone();
+        let filter = self.timeline_filter.trim().to_string();
+        let profile = self.selected_profile.clone();
+        let unread_ids = self.unread_only.then(|| self.unread_ids.clone());
+        let offset = if reset {
+            0
+        } else {
+            self.timeline_documents.len()
+        };
+
+        runtime_handle.spawn(async move {
+            let rag_lock = rag.read().await;
+            let docs = if let Some(ref rag) = *rag_lock {
+                let page = async {
+                    let mut scope = rag.filter_document_ids(&filter).await?;
+                    if let Some(unread_ids) = unread_ids {
+                        scope = Some(match scope {
+                            Some(scope) => scope.intersection(&unread_ids).copied().collect(),
+                            None => unread_ids,
+                        });
+                    }
+                    rag.db
+                        .get_timeline_page(scope, profile, offset, TIMELINE_PAGE_SIZE)
+                        .await
+                };
+                match page.await {
+                    Ok(docs) => docs.into_iter().map(document_view).collect(),
+                    Err(e) => {
+                        eprintln!("Failed to load timeline: {}", e);
+                        Vec::new()
+                    }
+                }
+            } else {
+                Vec::new()
+            };
+            let _ = tx.send((reset, docs));
+        });
+
+        if reset {
+            self.timeline_exhausted = false;
+        }
+        self.timeline_receiver = Some(rx);
+    }
+
+    /// Check if a timeline page has loaded
+    pub(crate) fn check_timeline_loaded(&mut self) {
+        if let Some(ref rx) = self.timeline_receiver {
+            match rx.try_recv() {
+                Ok((reset, docs)) => {
+                    self.timeline_exhausted = docs.len() < TIMELINE_PAGE_SIZE;
+                    if reset {
+                        self.timeline_documents = docs;
+                        self.selected_index = None;
+                    } else {
+                        self.timeline_documents.extend(docs);
+                    }
+                    self.timeline_receiver = None;
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.timeline_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Check if a timeline page is loading
+    pub fn is_timeline_loading(&self) -> bool {
+        self.timeline_receiver.is_some()
+    }
+
+    /// Whether older documents remain to be loaded into the timeline
+    pub fn timeline_has_more(&self) -> bool {
+        !self.timeline_exhausted
+    }
+}
@@ -0,0 +1,178 @@
+//! Trash view listing deleted documents, which can be restored or purged
+
+use egui::Ui;
+use egui_remixicon::icons;
+
+use crate::gui::accessibility::Labeled;
+use crate::gui::app::LocalMindApp;
+use crate::gui::i18n::{tr, tr_args};
+use crate::gui::presenter;
+use crate::gui::state::View;
+
+/// Render the trash view
+pub fn render_trash_view(ui: &mut Ui, app: &mut LocalMindApp) {
+    ui.add_space(10.0);
+
+    let pending = app.is_document_action_pending();
+
+    // Header with back button and "Empty trash"
+    ui.horizontal(|ui| {
+        let back_button = ui.button(icons::ARROW_LEFT_LINE).labeled(tr("common.back"));
+
+        if back_button.hovered() {
+            ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
+        }
+
+        if back_button.clicked() {
+            app.navigate_back();
+        }
+
+        ui.add_space(10.0);
+        ui.heading(tr("trash.title"));
+
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            if ui
+                .add_enabled(
+                    !pending && !app.trashed_documents.is_empty(),
+                    egui::Button::new(tr("trash.empty_trash")),
+                )
+                .on_hover_text(tr("trash.empty_trash_hint"))
+                .clicked()
+            {
+                app.purge_documents(None);
+            }
+            ui.weak(tr_args(
+                "common.documents_count",
+                &[("count", &app.trashed_documents.len())],
+            ));
+        });
+    });
+
+    ui.add_space(10.0);
+
+    if app.is_trash_loading() && app.trashed_documents.is_empty() {
+        ui.vertical_centered(|ui| {
+            ui.add_space(50.0);
+            ui.spinner();
+        });
+        return;
+    }
+
+    if app.trashed_documents.is_empty() {
+        ui.vertical_centered(|ui| {
+            ui.add_space(50.0);
+            ui.label(tr("trash.is_empty"));
+            ui.weak(tr("trash.is_empty_hint"));
+        });
+        return;
+    }
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default();
+    let mut restore_id = None;
+    let mut purge_id = None;
+
+    egui::ScrollArea::vertical()
+        .id_salt("trash_list")
+        .show(ui, |ui| {
+            for doc in &app.trashed_documents {
+                egui::Frame::group(ui.style()).show(ui, |ui| {
+                    ui.set_width(ui.available_width());
+                    ui.horizontal(|ui| {
+                        ui.vertical(|ui| {
+                            ui.strong(&doc.title);
+                            if let Some(url) = &doc.url {
+                                ui.weak(url);
+                            }
+                            ui.weak(tr_args(
+                                "trash.deleted",
+                                &[("when", &presenter::format_ago(now - doc.deleted_at))],
+                            ));
+                        });
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            if ui
+                                .add_enabled(
+                                    !pending,
+                                    egui::Button::new(tr("trash.delete_permanently")),
+                                )
+                                .clicked()
+                            {
+                                purge_id = Some(doc.id);
+                            }
+                            if ui
+                                .add_enabled(!pending, egui::Button::new(tr("trash.restore")))
+                                .clicked()
+                            {
+                                restore_id = Some(doc.id);
+                            }
+                        });
+                    });
+                });
+                ui.add_space(6.0);
+            }
+        });
+
+    if let Some(id) = restore_id {
+        app.restore_documents(vec![id]);
+    }
+    if let Some(id) = purge_id {
+        app.purge_documents(Some(vec![id]));
+    }
+}
+
+impl LocalMindApp {
+    /// Show the Trash view
+    pub fn open_trash(&mut self) {
+        if self.current_view != View::Trash {
+            self.previous_view = self.current_view.clone();
+            self.current_view = View::Trash;
+        }
+        self.selected_index = None;
+        self.load_trashed_documents();
+    }
+
+    /// Load the documents in the trash
+    pub(crate) fn load_trashed_documents(&mut self) {
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let runtime_handle = self.runtime.clone();
+
+        runtime_handle.spawn(async move {
+            let rag_lock = rag.read().await;
+            let docs = if let Some(ref rag) = *rag_lock {
+                rag.db.get_trashed_documents().await.unwrap_or_else(|e| {
+                    eprintln!("Failed to load trash: {}", e);
+                    Vec::new()
+                })
+            } else {
+                Vec::new()
+            };
+            let _ = tx.send(docs);
+        });
+
+        self.trashed_documents_receiver = Some(rx);
+    }
+
+    /// Check if the trash listing has loaded
+    pub(crate) fn check_trashed_documents_loaded(&mut self) {
+        if let Some(ref rx) = self.trashed_documents_receiver {
+            match rx.try_recv() {
+                Ok(docs) => {
+                    self.trashed_documents = docs;
+                    self.trashed_documents_receiver = None;
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.trashed_documents_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Check if the trash listing is loading
+    pub fn is_trash_loading(&self) -> bool {
+        self.trashed_documents_receiver.is_some()
+    }
+}
@@ -0,0 +1,69 @@
+//! Recent requests to the extension API, to see why a save didn't appear.
+
+use egui::Ui;
+use egui_remixicon::icons;
+
+use crate::gui::app::LocalMindApp;
+use crate::gui::i18n::{tr, tr_args};
+use crate::gui::presenter;
+
+/// Requests listed at most
+const MAX_SHOWN: usize = 50;
+
+/// Render the activity list from the request log, newest first
+pub fn render_activity(ui: &mut Ui, app: &mut LocalMindApp) {
+    ui.horizontal(|ui| {
+        ui.weak(tr("activity.intro"));
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            ui.checkbox(&mut app.activity_saves_only, tr("activity.saves_only"));
+        });
+    });
+    ui.add_space(6.0);
+
+    let records: Vec<_> = app
+        .request_log
+        .recent()
+        .into_iter()
+        .filter(|record| !app.activity_saves_only || record.is_save_or_search())
+        .take(MAX_SHOWN)
+        .collect();
+    if records.is_empty() {
+        ui.weak(tr("activity.empty"));
+    }
+
+    let now = std::time::SystemTime::now();
+    let green = egui::Color32::from_rgb(60, 160, 80);
+    let red = egui::Color32::from_rgb(200, 60, 60);
+    for record in &records {
+        ui.horizontal(|ui| {
+            if record.succeeded() {
+                ui.colored_label(green, icons::CHECK_LINE);
+            } else {
+                ui.colored_label(red, icons::ERROR_WARNING_LINE);
+            }
+            ui.monospace(format!("{} {}", record.method, record.path));
+            ui.weak(format!("{}", record.status));
+            ui.weak(tr_args(
+                "activity.duration",
+                &[("ms", &record.duration.as_millis())],
+            ));
+            let origin = record.origin.as_deref().unwrap_or(tr("activity.no_origin"));
+            ui.weak(origin);
+            let seconds = now
+                .duration_since(record.at)
+                .map(|elapsed| elapsed.as_secs() as i64)
+                .unwrap_or(0);
+            ui.weak(presenter::format_ago(seconds));
+        });
+        if let Some(ref error) = record.error {
+            ui.horizontal_wrapped(|ui| {
+                ui.add_space(24.0);
+                ui.colored_label(red, error);
+            });
+        }
+    }
+
+    // Requests arrive from the extension while the view is open
+    ui.ctx()
+        .request_repaint_after(std::time::Duration::from_secs(1));
+}
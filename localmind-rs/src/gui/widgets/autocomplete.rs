@@ -0,0 +1,59 @@
+//! Autocomplete dropdown under the search box.
+
+use egui_remixicon::icons;
+
+use crate::db::AutocompleteSuggestion;
+
+/// Render suggestions in a popup below `anchor` (the search box), with the
+/// `highlighted` one (chosen with the arrow keys) selected.
+///
+/// Returns the area response, so the caller can tell clicks on the dropdown
+/// from clicks elsewhere, and the index of a clicked suggestion.
+pub fn render_autocomplete(
+    ctx: &egui::Context,
+    anchor: egui::Rect,
+    suggestions: &[AutocompleteSuggestion],
+    highlighted: Option<usize>,
+) -> (egui::Response, Option<usize>) {
+    let mut chosen = None;
+
+    let area = egui::Area::new(egui::Id::new("search_autocomplete"))
+        .order(egui::Order::Foreground)
+        .fixed_pos(anchor.left_bottom() + egui::vec2(0.0, 2.0))
+        .show(ctx, |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                ui.set_width(anchor.width());
+                for (index, suggestion) in suggestions.iter().enumerate() {
+                    let (icon, label, detail) = match suggestion {
+                        AutocompleteSuggestion::Document { title, .. } => {
+                            (icons::FILE_TEXT_LINE, title.clone(), String::new())
+                        }
+                        AutocompleteSuggestion::Tag { tag, count } => {
+                            (icons::PRICE_TAG_3_LINE, tag.clone(), count.to_string())
+                        }
+                        AutocompleteSuggestion::Domain { domain, count } => {
+                            (icons::GLOBAL_LINE, domain.clone(), count.to_string())
+                        }
+                    };
+
+                    ui.horizontal(|ui| {
+                        let response = ui.selectable_label(
+                            highlighted == Some(index),
+                            format!("{}  {}", icon, label),
+                        );
+                        if response.hovered() {
+                            ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
+                        }
+                        if response.clicked() {
+                            chosen = Some(index);
+                        }
+                        if !detail.is_empty() {
+                            ui.weak(detail);
+                        }
+                    });
+                }
+            });
+        });
+
+    (area.response, chosen)
+}
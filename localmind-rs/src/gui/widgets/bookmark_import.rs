@@ -0,0 +1,158 @@
+//! "Import bookmarks" dialog for Netscape HTML exports, OPML files and
+//! Notion / Confluence exports.
+
+use crate::gui::app::LocalMindApp;
+use crate::gui::i18n::{tr, tr_args};
+use crate::gui::state::Toast;
+use crate::services::{import_workspace_export, ingest_imported_bookmarks, queue_bookmark_import};
+use crate::workspace_import::is_workspace_export;
+
+/// Render the import dialog while `app.show_import_dialog` is set.
+pub fn render_import_dialog(ctx: &egui::Context, app: &mut LocalMindApp) {
+    if !app.show_import_dialog {
+        return;
+    }
+
+    let mut open = true;
+    let mut import_path = None;
+
+    egui::Window::new(tr("import.title"))
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .default_width(480.0)
+        .show(ctx, |ui| {
+            ui.label(tr("import.intro"));
+            ui.weak(tr("import.background"));
+            ui.add_space(4.0);
+            ui.label(tr("import.exports"));
+            ui.add_space(10.0);
+
+            ui.horizontal(|ui| {
+                let label = ui.label(tr("import.file_path"));
+                let response = ui
+                    .add(
+                        egui::TextEdit::singleline(&mut app.import_path_input)
+                            .hint_text("/path/to/bookmarks.html")
+                            .desired_width(300.0),
+                    )
+                    .labelled_by(label.id);
+                let enter_pressed =
+                    response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                let import_clicked = ui
+                    .add_enabled(
+                        !app.is_bookmark_import_pending(),
+                        egui::Button::new(tr("import.import")),
+                    )
+                    .clicked();
+
+                if import_clicked || enter_pressed {
+                    let raw = app.import_path_input.trim().trim_matches('\'').to_string();
+                    let path = std::path::PathBuf::from(&raw);
+                    if raw.is_empty() {
+                        app.import_error = Some(tr("import.enter_path").to_string());
+                    } else if !path.is_file() && !path.is_dir() {
+                        app.import_error = Some(tr("import.not_found").to_string());
+                    } else {
+                        app.import_error = None;
+                        import_path = Some(path);
+                    }
+                }
+            });
+
+            if app.is_bookmark_import_pending() {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label(tr("import.reading"));
+                });
+            }
+
+            if let Some(ref err) = app.import_error {
+                ui.add_space(2.0);
+                ui.colored_label(egui::Color32::from_rgb(200, 60, 60), err);
+            }
+        });
+
+    if let Some(path) = import_path {
+        app.import_bookmark_file(path);
+    }
+    if !open {
+        app.show_import_dialog = false;
+        app.import_error = None;
+    }
+}
+
+impl LocalMindApp {
+    /// Check for bookmark progress events and update toasts
+    /// Queue the bookmarks in an exported bookmark file, then fetch and
+    /// ingest them in the background with the usual progress toast.
+    /// Notion and Confluence exports (a zip or folder) are imported directly.
+    pub fn import_bookmark_file(&mut self, path: std::path::PathBuf) {
+        if self.bookmark_import_receiver.is_some() {
+            return;
+        }
+
+        let rag = self.rag.clone();
+        let progress_tx = self.bookmark_progress_tx.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let runtime_handle = self.runtime.clone();
+
+        if is_workspace_export(&path) {
+            runtime_handle.spawn(import_workspace_export(rag, path, tx, progress_tx));
+            self.bookmark_import_receiver = Some(rx);
+            return;
+        }
+
+        runtime_handle.spawn(async move {
+            let queued = queue_bookmark_import(&rag, &path)
+                .await
+                .map_err(|e| e.to_string());
+            let start_ingest = queued.is_ok();
+            let _ = tx.send(queued);
+            if start_ingest {
+                ingest_imported_bookmarks(rag, progress_tx).await;
+            }
+        });
+
+        self.bookmark_import_receiver = Some(rx);
+    }
+
+    /// Check if a bookmark file is being read and queued
+    pub fn is_bookmark_import_pending(&self) -> bool {
+        self.bookmark_import_receiver.is_some()
+    }
+
+    /// Check if a bookmark file import has been queued
+    pub(crate) fn check_bookmark_import(&mut self) {
+        if let Some(ref rx) = self.bookmark_import_receiver {
+            match rx.try_recv() {
+                Ok(result) => {
+                    self.bookmark_import_receiver = None;
+                    match result {
+                        Ok(queued) => {
+                            self.show_import_dialog = false;
+                            self.import_path_input.clear();
+                            let id = self.next_toast_id();
+                            self.add_toast(Toast::info(
+                                id,
+                                if queued > 0 {
+                                    tr_args("import.importing", &[("count", &queued)])
+                                } else {
+                                    tr("import.nothing_new").to_string()
+                                },
+                            ));
+                        }
+                        Err(e) => {
+                            eprintln!("Bookmark import failed: {}", e);
+                            self.import_error = Some(e);
+                        }
+                    }
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.bookmark_import_receiver = None;
+                }
+            }
+        }
+    }
+}
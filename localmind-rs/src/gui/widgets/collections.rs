@@ -0,0 +1,398 @@
+//! Collections widget — create, list and delete named document collections.
+
+use crate::gui::accessibility::Labeled;
+use crate::gui::app::LocalMindApp;
+use crate::gui::i18n::{tr, tr_args};
+use crate::gui::state::{BrowseFilter, Toast, View};
+use egui::Ui;
+
+/// Render the collections manager.
+///
+/// Lists every collection with its document count and a delete button, and
+/// offers a field to create an empty collection. Documents are added from
+/// the document view or by saving a set of search results. Smart collections
+/// show their filter and an Open button that lists what matches it now.
+pub fn render_collections(ui: &mut Ui, app: &mut LocalMindApp) {
+    ui.weak(tr("collections.intro"));
+    ui.add_space(6.0);
+
+    let busy = app.is_collection_action_pending();
+
+    ui.horizontal(|ui| {
+        ui.add(
+            egui::TextEdit::singleline(&mut app.new_collection_name)
+                .hint_text(tr("collections.new_hint"))
+                .desired_width(220.0),
+        )
+        .labeled_field(tr("collections.new_hint"));
+        let can_create = !busy && !app.new_collection_name.trim().is_empty();
+        if ui
+            .add_enabled(can_create, egui::Button::new(tr("collections.create")))
+            .clicked()
+        {
+            let name = app.new_collection_name.clone();
+            app.create_collection(&name, Vec::new());
+        }
+    });
+
+    ui.add_space(6.0);
+
+    if app.collections.is_empty() {
+        ui.weak(tr("collections.none"));
+        return;
+    }
+
+    let mut to_delete = None;
+    let mut to_open = None;
+    for collection in &app.collections {
+        ui.horizontal(|ui| {
+            ui.label(&collection.name);
+            ui.weak(tr_args(
+                "common.documents_count",
+                &[("count", &collection.document_count)],
+            ));
+            if let Some(query) = &collection.query {
+                ui.weak(tr_args("collections.smart_filter", &[("filters", query)]));
+            }
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui
+                    .add_enabled(!busy, egui::Button::new(tr("common.delete")))
+                    .on_hover_text(tr("collections.delete_hint"))
+                    .clicked()
+                {
+                    to_delete = Some(collection.id);
+                }
+                if collection.is_smart() && ui.button(tr("collections.open")).clicked() {
+                    to_open = Some(collection.id);
+                }
+            });
+        });
+    }
+
+    if let Some(id) = to_delete {
+        app.delete_collection(id);
+    }
+    if let Some(id) = to_open {
+        app.open_smart_collection(id);
+    }
+}
+
+impl LocalMindApp {
+    /// Load all collections
+    pub fn load_collections(&mut self) {
+        if self.collections_receiver.is_some() {
+            return; // Already loading
+        }
+
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let runtime_handle = self.runtime.clone();
+
+        runtime_handle.spawn(async move {
+            let rag_lock = rag.read().await;
+            let collections = if let Some(ref rag) = *rag_lock {
+                rag.db.get_collections().await.unwrap_or_else(|e| {
+                    eprintln!("Failed to load collections: {}", e);
+                    Vec::new()
+                })
+            } else {
+                Vec::new()
+            };
+            let _ = tx.send(collections);
+        });
+
+        self.collections_receiver = Some(rx);
+    }
+
+    /// Check if collections have been loaded
+    pub(crate) fn check_collections_loaded(&mut self) {
+        if let Some(ref rx) = self.collections_receiver {
+            match rx.try_recv() {
+                Ok(collections) => {
+                    // Drop the search scope if its collection was deleted
+                    if let Some(id) = self.search_collection {
+                        if !collections.iter().any(|c| c.id == id) {
+                            self.search_collection = None;
+                        }
+                    }
+                    if let BrowseFilter::Collection(id) = self.browse_filter {
+                        if !collections.iter().any(|c| c.id == id) {
+                            self.browse_filter = BrowseFilter::All;
+                            if self.current_view == View::Browse {
+                                self.load_browse_documents();
+                            }
+                        }
+                    }
+                    self.collections = collections;
+                    self.collections_receiver = None;
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.collections_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Create a collection containing `doc_ids` (may be empty)
+    pub fn create_collection(&mut self, name: &str, doc_ids: Vec<i64>) {
+        let name = name.trim().to_string();
+        if name.is_empty() || self.collection_action_receiver.is_some() {
+            return;
+        }
+
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let runtime_handle = self.runtime.clone();
+
+        runtime_handle.spawn(async move {
+            let rag_lock = rag.read().await;
+            let result = if let Some(ref rag) = *rag_lock {
+                match rag.db.create_collection(&name).await {
+                    Ok(id) => rag
+                        .db
+                        .add_documents_to_collection(id, &doc_ids)
+                        .await
+                        .map(|added| {
+                            tr_args("collections.created", &[("name", &name), ("count", &added)])
+                        })
+                        .map_err(|e| e.to_string()),
+                    Err(e) => Err(e.to_string()),
+                }
+            } else {
+                Err(tr("common.initializing").to_string())
+            };
+            let _ = tx.send(result);
+        });
+
+        self.new_collection_name.clear();
+        self.collection_action_receiver = Some(rx);
+    }
+
+    /// Save the metadata operators of `query` as a smart collection
+    pub fn create_smart_collection(&mut self, name: &str, query: &str) {
+        let name = name.trim().to_string();
+        if name.is_empty() || self.collection_action_receiver.is_some() {
+            return;
+        }
+
+        let rag = self.rag.clone();
+        let query = query.to_string();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let runtime_handle = self.runtime.clone();
+
+        runtime_handle.spawn(async move {
+            let rag_lock = rag.read().await;
+            let result = if let Some(ref rag) = *rag_lock {
+                rag.db
+                    .create_smart_collection(&name, &query)
+                    .await
+                    .map(|_| tr_args("collections.created_smart", &[("name", &name)]))
+                    .map_err(|e| e.to_string())
+            } else {
+                Err(tr("common.initializing").to_string())
+            };
+            let _ = tx.send(result);
+        });
+
+        self.new_collection_name.clear();
+        self.collection_action_receiver = Some(rx);
+    }
+
+    /// Show a smart collection: search for its filter expression, which
+    /// lists the newest matching documents
+    pub fn open_smart_collection(&mut self, collection_id: i64) {
+        let Some(query) = self
+            .collections
+            .iter()
+            .find(|c| c.id == collection_id)
+            .and_then(|c| c.query.clone())
+        else {
+            return;
+        };
+        self.settings_open = false;
+        self.search_collection = None;
+        self.search_query = query;
+        self.trigger_search();
+    }
+
+    /// Delete a collection (its documents are kept)
+    pub fn delete_collection(&mut self, id: i64) {
+        if self.collection_action_receiver.is_some() {
+            return;
+        }
+
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let runtime_handle = self.runtime.clone();
+
+        runtime_handle.spawn(async move {
+            let rag_lock = rag.read().await;
+            let result = if let Some(ref rag) = *rag_lock {
+                rag.db
+                    .delete_collection(id)
+                    .await
+                    .map(|_| tr("collections.deleted").to_string())
+                    .map_err(|e| e.to_string())
+            } else {
+                Err(tr("common.initializing").to_string())
+            };
+            let _ = tx.send(result);
+        });
+
+        self.collection_action_receiver = Some(rx);
+    }
+
+    /// Add the selected document to a collection, or remove it
+    pub fn set_document_in_collection(&mut self, collection_id: i64, member: bool) {
+        let Some(doc_id) = self.selected_document.as_ref().map(|d| d.id) else {
+            return;
+        };
+        if self.collection_action_receiver.is_some() {
+            return;
+        }
+
+        // Update the checkbox immediately; the toast reports failures
+        if member {
+            self.document_collection_ids.push(collection_id);
+        } else {
+            self.document_collection_ids
+                .retain(|id| *id != collection_id);
+        }
+
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let runtime_handle = self.runtime.clone();
+
+        runtime_handle.spawn(async move {
+            let rag_lock = rag.read().await;
+            let result = if let Some(ref rag) = *rag_lock {
+                let outcome = if member {
+                    rag.db
+                        .add_documents_to_collection(collection_id, &[doc_id])
+                        .await
+                        .map(|_| tr("collections.added_one").to_string())
+                } else {
+                    rag.db
+                        .remove_document_from_collection(collection_id, doc_id)
+                        .await
+                        .map(|_| tr("collections.removed_one").to_string())
+                };
+                outcome.map_err(|e| e.to_string())
+            } else {
+                Err(tr("common.initializing").to_string())
+            };
+            let _ = tx.send(result);
+        });
+
+        self.collection_action_receiver = Some(rx);
+    }
+
+    /// Check if a collection action has completed
+    pub(crate) fn check_collection_actions(&mut self) {
+        if let Some(ref rx) = self.collection_action_receiver {
+            match rx.try_recv() {
+                Ok(result) => {
+                    self.collection_action_receiver = None;
+                    let id = self.next_toast_id();
+                    match result {
+                        Ok(message) => self.add_toast(Toast::success(id, message)),
+                        Err(e) => {
+                            eprintln!("Collection update failed: {}", e);
+                            self.add_toast(Toast::error(id, e));
+                            if let Some(doc_id) = self.selected_document.as_ref().map(|d| d.id) {
+                                self.load_document_collections(doc_id);
+                            }
+                        }
+                    }
+                    // Document counts (and possibly the list) changed
+                    self.load_collections();
+                    if self.current_view == View::Browse
+                        && matches!(self.browse_filter, BrowseFilter::Collection(_))
+                    {
+                        self.load_browse_documents();
+                    }
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.collection_action_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Check if a collection action is in progress
+    pub fn is_collection_action_pending(&self) -> bool {
+        self.collection_action_receiver.is_some()
+    }
+
+    /// Load which collections a document belongs to
+    pub(crate) fn load_document_collections(&mut self, doc_id: i64) {
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let runtime_handle = self.runtime.clone();
+
+        runtime_handle.spawn(async move {
+            let rag_lock = rag.read().await;
+            let ids = if let Some(ref rag) = *rag_lock {
+                rag.db
+                    .get_document_collection_ids(doc_id)
+                    .await
+                    .unwrap_or_else(|e| {
+                        eprintln!("Failed to load document collections: {}", e);
+                        Vec::new()
+                    })
+            } else {
+                Vec::new()
+            };
+            let _ = tx.send(ids);
+        });
+
+        self.document_collection_ids.clear();
+        self.document_collections_receiver = Some(rx);
+    }
+
+    /// Check if the selected document's collections have been loaded
+    pub(crate) fn check_document_collections_loaded(&mut self) {
+        if let Some(ref rx) = self.document_collections_receiver {
+            match rx.try_recv() {
+                Ok(ids) => {
+                    self.document_collection_ids = ids;
+                    self.document_collections_receiver = None;
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.document_collections_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Add documents to a collection (bulk action bar)
+    pub fn add_documents_to_collection(&mut self, collection_id: i64, doc_ids: Vec<i64>) {
+        if doc_ids.is_empty() || self.collection_action_receiver.is_some() {
+            return;
+        }
+
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let runtime_handle = self.runtime.clone();
+
+        runtime_handle.spawn(async move {
+            let rag_lock = rag.read().await;
+            let result = if let Some(ref rag) = *rag_lock {
+                rag.db
+                    .add_documents_to_collection(collection_id, &doc_ids)
+                    .await
+                    .map(|added| tr_args("collections.added", &[("count", &added)]))
+                    .map_err(|e| e.to_string())
+            } else {
+                Err(tr("common.initializing").to_string())
+            };
+            let _ = tx.send(result);
+        });
+
+        self.checked_doc_ids.clear();
+        self.collection_action_receiver = Some(rx);
+    }
+}
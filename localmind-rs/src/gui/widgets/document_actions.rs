@@ -0,0 +1,164 @@
+//! Per-document context menu and the bulk action bar for checked documents.
+
+use egui::{Response, Ui};
+
+use crate::gui::accessibility::Labeled;
+use crate::gui::app::LocalMindApp;
+use crate::gui::i18n::{tr, tr_args};
+
+/// An action chosen from a document's context menu
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DocumentAction {
+    Open,
+    OpenInBrowser,
+    CopyLink,
+    Tag(String),
+    RemindMe,
+    Delete,
+    ExcludeDomain,
+}
+
+/// Attach a right-click menu to a document card.
+///
+/// `tag_input` holds the tag being typed in the menu between frames.
+/// Link actions are only offered when the document has a URL.
+pub fn context_menu(
+    response: &Response,
+    has_url: bool,
+    tag_input: &mut String,
+) -> Option<DocumentAction> {
+    let mut action = None;
+
+    response.context_menu(|ui| {
+        if ui.button(tr("actions.open")).clicked() {
+            action = Some(DocumentAction::Open);
+        }
+        if has_url {
+            if ui.button(tr("actions.open_in_browser")).clicked() {
+                action = Some(DocumentAction::OpenInBrowser);
+            }
+            if ui.button(tr("actions.copy_link")).clicked() {
+                action = Some(DocumentAction::CopyLink);
+            }
+        }
+
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            let edit = ui
+                .add(
+                    egui::TextEdit::singleline(tag_input)
+                        .hint_text(tr("actions.tag_hint"))
+                        .desired_width(120.0),
+                )
+                .labeled_field(tr("actions.add_tag"));
+            let submitted = edit.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            let tag = tag_input.trim();
+            let add_clicked = ui
+                .add_enabled(!tag.is_empty(), egui::Button::new(tr("actions.add_tag")))
+                .clicked();
+            if (add_clicked || submitted) && !tag.is_empty() {
+                action = Some(DocumentAction::Tag(tag.to_string()));
+            }
+        });
+
+        if ui.button(tr("actions.remind_me")).clicked() {
+            action = Some(DocumentAction::RemindMe);
+        }
+
+        ui.separator();
+
+        if has_url && ui.button(tr("actions.exclude_domain")).clicked() {
+            action = Some(DocumentAction::ExcludeDomain);
+        }
+        if ui
+            .button(
+                egui::RichText::new(tr("common.delete"))
+                    .color(egui::Color32::from_rgb(200, 60, 60)),
+            )
+            .clicked()
+        {
+            action = Some(DocumentAction::Delete);
+        }
+
+        if action.is_some() {
+            ui.close_menu();
+        }
+    });
+
+    action
+}
+
+/// Render the bulk action bar while any documents are checked.
+pub fn render_bulk_bar(ui: &mut Ui, app: &mut LocalMindApp) {
+    if app.checked_doc_ids.is_empty() {
+        return;
+    }
+
+    let count = app.checked_doc_ids.len();
+    let pending = app.is_document_action_pending();
+
+    egui::Frame::group(ui.style()).show(ui, |ui| {
+        ui.horizontal_wrapped(|ui| {
+            ui.strong(tr_args("actions.selected", &[("count", &count)]));
+
+            if ui
+                .add_enabled(
+                    !pending,
+                    egui::Button::new(tr_args("actions.delete_count", &[("count", &count)])),
+                )
+                .clicked()
+            {
+                let ids = app.checked_doc_ids.iter().copied().collect();
+                app.delete_documents(ids);
+            }
+
+            ui.separator();
+
+            ui.add(
+                egui::TextEdit::singleline(&mut app.tag_input)
+                    .hint_text(tr("actions.tag_hint"))
+                    .desired_width(120.0),
+            )
+            .labeled_field(tr("actions.add_tag"));
+            let tag = app.tag_input.trim().to_string();
+            if ui
+                .add_enabled(
+                    !pending && !tag.is_empty(),
+                    egui::Button::new(tr_args("actions.tag_count", &[("count", &count)])),
+                )
+                .clicked()
+            {
+                let ids = app.checked_doc_ids.iter().copied().collect();
+                app.tag_documents(ids, tag);
+            }
+
+            if app.collections.iter().any(|c| !c.is_smart()) {
+                ui.separator();
+
+                let mut chosen = None;
+                egui::ComboBox::from_id_salt("bulk_add_to_collection")
+                    .selected_text(tr("actions.add_to_collection"))
+                    .show_ui(ui, |ui| {
+                        for collection in app.collections.iter().filter(|c| !c.is_smart()) {
+                            if ui.selectable_label(false, &collection.name).clicked() {
+                                chosen = Some(collection.id);
+                            }
+                        }
+                    });
+                if let Some(collection_id) = chosen {
+                    let ids = app.checked_doc_ids.iter().copied().collect();
+                    app.add_documents_to_collection(collection_id, ids);
+                }
+            }
+
+            ui.separator();
+
+            if ui.button(tr("actions.clear_selection")).clicked() {
+                app.checked_doc_ids.clear();
+            }
+        });
+    });
+
+    ui.add_space(8.0);
+}
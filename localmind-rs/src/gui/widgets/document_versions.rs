@@ -0,0 +1,243 @@
+//! Diff between an earlier version of a document and its current content.
+
+use egui_remixicon::icons;
+
+use crate::document_versions::{DiffLine, DocumentVersion};
+use crate::gui::app::LocalMindApp;
+use crate::gui::i18n::{tr, tr_args};
+use crate::gui::state::{Toast, View};
+use crate::gui::widgets;
+
+/// Unchanged lines shown around each change; longer runs are folded.
+const CONTEXT_LINES: usize = 3;
+
+/// An earlier version compared with the current content
+pub struct VersionDiff {
+    pub version: DocumentVersion,
+    pub lines: Vec<DiffLine>,
+}
+
+/// Render the diff window while `app.version_diff` is set.
+pub fn render_version_diff(ctx: &egui::Context, app: &mut LocalMindApp) {
+    let Some(diff) = app.version_diff.as_ref() else {
+        return;
+    };
+
+    let mut open = true;
+    let mut restore = None;
+
+    egui::Window::new(tr("versions.diff_title"))
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(true)
+        .default_size([640.0, 480.0])
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.weak(tr_args(
+                    "versions.saved_at",
+                    &[("date", &diff.version.saved_at)],
+                ));
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui
+                        .add_enabled(
+                            !app.is_version_restoring(),
+                            egui::Button::new(format!(
+                                "{} {}",
+                                icons::HISTORY_LINE,
+                                tr("versions.restore")
+                            )),
+                        )
+                        .clicked()
+                    {
+                        restore = Some(diff.version.id);
+                    }
+                });
+            });
+            ui.add_space(6.0);
+
+            egui::ScrollArea::vertical()
+                .auto_shrink([false, false])
+                .show(ui, |ui| render_lines(ui, &diff.lines));
+        });
+
+    if let Some(version_id) = restore {
+        app.restore_version(version_id);
+    }
+    if !open || restore.is_some() {
+        app.version_diff = None;
+    }
+}
+
+/// Changed lines with their context, folding long unchanged runs.
+fn render_lines(ui: &mut egui::Ui, lines: &[DiffLine]) {
+    let changed: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| !matches!(line, DiffLine::Same(_)))
+        .map(|(i, _)| i)
+        .collect();
+    if changed.is_empty() {
+        ui.weak(tr("versions.no_changes"));
+        return;
+    }
+    let near_change = |i: usize| {
+        let next = changed.partition_point(|&c| c < i);
+        changed.get(next).is_some_and(|&c| c - i <= CONTEXT_LINES)
+            || next
+                .checked_sub(1)
+                .is_some_and(|prev| i - changed[prev] <= CONTEXT_LINES)
+    };
+
+    let added = egui::Color32::from_rgba_unmultiplied(60, 170, 80, 60);
+    let removed = egui::Color32::from_rgba_unmultiplied(220, 60, 60, 60);
+    let mut folded = 0;
+    for (i, line) in lines.iter().enumerate() {
+        let (marker, text, background) = match line {
+            DiffLine::Same(_) if !near_change(i) => {
+                folded += 1;
+                continue;
+            }
+            DiffLine::Same(text) => (" ", text, egui::Color32::TRANSPARENT),
+            DiffLine::Removed(text) => ("-", text, removed),
+            DiffLine::Added(text) => ("+", text, added),
+        };
+        if folded > 0 {
+            ui.weak(tr_args("versions.unchanged_lines", &[("count", &folded)]));
+            folded = 0;
+        }
+        ui.label(
+            egui::RichText::new(format!("{} {}", marker, text))
+                .monospace()
+                .background_color(background),
+        );
+    }
+    if folded > 0 {
+        ui.weak(tr_args("versions.unchanged_lines", &[("count", &folded)]));
+    }
+}
+
+impl LocalMindApp {
+    /// Load the earlier versions of a document
+    pub(crate) fn load_document_versions(&mut self, doc_id: i64) {
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let runtime_handle = self.runtime.clone();
+
+        runtime_handle.spawn(async move {
+            let rag_lock = rag.read().await;
+            let versions = if let Some(ref rag) = *rag_lock {
+                rag.db
+                    .get_document_versions(doc_id)
+                    .await
+                    .unwrap_or_else(|e| {
+                        eprintln!("Failed to load document versions: {}", e);
+                        Vec::new()
+                    })
+            } else {
+                Vec::new()
+            };
+            let _ = tx.send(versions);
+        });
+
+        self.document_versions.clear();
+        self.version_diff = None;
+        self.document_versions_receiver = Some(rx);
+    }
+
+    /// Check if the selected document's earlier versions have been loaded
+    pub(crate) fn check_document_versions_loaded(&mut self) {
+        if let Some(ref rx) = self.document_versions_receiver {
+            match rx.try_recv() {
+                Ok(versions) => {
+                    self.document_versions = versions;
+                    self.document_versions_receiver = None;
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.document_versions_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Compare an earlier version with the selected document's content
+    pub fn compare_version(&mut self, version: crate::document_versions::DocumentVersion) {
+        let Some(doc) = self.selected_document.as_ref() else {
+            return;
+        };
+        let lines = crate::document_versions::diff_lines(&version.content, &doc.content);
+        self.version_diff = Some(widgets::document_versions::VersionDiff { version, lines });
+    }
+
+    /// Put an earlier version back as the document's content; the content
+    /// it replaces is kept as a version in turn
+    pub fn restore_version(&mut self, version_id: i64) {
+        if self.version_restore_receiver.is_some() {
+            return;
+        }
+        let Some(doc_id) = self.selected_document.as_ref().map(|d| d.id) else {
+            return;
+        };
+
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            let result = match *rag_lock {
+                Some(ref rag) => rag
+                    .restore_version(version_id)
+                    .await
+                    .map(|_| ())
+                    .map_err(|e| e.to_string()),
+                None => Err(tr("common.not_ready").to_string()),
+            };
+            let _ = tx.send((doc_id, result));
+        });
+        self.version_restore_receiver = Some(rx);
+    }
+
+    /// Check if a version is being restored
+    pub fn is_version_restoring(&self) -> bool {
+        self.version_restore_receiver.is_some()
+    }
+
+    /// Check for a restored version and show the document with it
+    pub(crate) fn check_version_restored(&mut self) {
+        let Some(ref rx) = self.version_restore_receiver else {
+            return;
+        };
+        let (doc_id, result) = match rx.try_recv() {
+            Ok(outcome) => outcome,
+            Err(std::sync::mpsc::TryRecvError::Empty) => return,
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                self.version_restore_receiver = None;
+                return;
+            }
+        };
+        self.version_restore_receiver = None;
+
+        let id = self.next_toast_id();
+        match result {
+            Ok(()) => {
+                self.add_toast(Toast::success(id, tr("versions.restored")));
+                let still_open = self.current_view == View::DocumentDetail
+                    && self
+                        .selected_document
+                        .as_ref()
+                        .is_some_and(|d| d.id == doc_id);
+                if still_open {
+                    // Reopen in place so Back still leads where it did
+                    let previous = self.previous_view.clone();
+                    self.load_document(doc_id);
+                    self.previous_view = previous;
+                }
+            }
+            Err(e) => {
+                self.add_toast(Toast::error(
+                    id,
+                    tr_args("versions.restore_failed", &[("error", &e)]),
+                ));
+            }
+        }
+    }
+}
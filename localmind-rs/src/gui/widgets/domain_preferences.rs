@@ -0,0 +1,171 @@
+//! Settings section for domains marked as high or low quality, and the
+//! badge search results on them show
+
+use crate::domain_preferences::{DomainBoost, DomainQuality};
+use crate::gui::app::LocalMindApp;
+use crate::gui::i18n::tr;
+use crate::gui::state::Toast;
+use egui::{Color32, Ui};
+use egui_remixicon::icons;
+
+const HIGH_COLOR: Color32 = Color32::from_rgb(52, 168, 83);
+
+/// Small badge for a result on a marked domain.
+pub fn quality_badge(ui: &mut Ui, quality: DomainQuality) {
+    match quality {
+        DomainQuality::High => ui
+            .colored_label(HIGH_COLOR, icons::ARROW_UP_S_LINE)
+            .on_hover_text(tr("domains.high_badge")),
+        DomainQuality::Low => ui
+            .weak(icons::ARROW_DOWN_S_LINE)
+            .on_hover_text(tr("domains.low_badge")),
+    };
+}
+
+fn quality_label(quality: DomainQuality) -> &'static str {
+    match quality {
+        DomainQuality::High => tr("domains.high"),
+        DomainQuality::Low => tr("domains.low"),
+    }
+}
+
+/// Render the marked domains, a field to mark another and the multipliers.
+///
+/// Marks are saved as they are made; the multipliers with the button.
+pub fn render_domain_preferences(ui: &mut Ui, app: &mut LocalMindApp) {
+    ui.weak(tr("domains.intro"));
+    ui.add_space(6.0);
+
+    let mut changed = None;
+    if app.domain_preferences.is_empty() {
+        ui.weak(tr("domains.none"));
+    }
+    egui::Grid::new("domain_preferences")
+        .num_columns(3)
+        .show(ui, |ui| {
+            for preference in &app.domain_preferences {
+                ui.label(&preference.domain);
+                let mut quality = preference.quality;
+                egui::ComboBox::from_id_salt(("domain_quality", &preference.domain))
+                    .selected_text(quality_label(quality))
+                    .show_ui(ui, |ui| {
+                        for option in [DomainQuality::High, DomainQuality::Low] {
+                            ui.selectable_value(&mut quality, option, quality_label(option));
+                        }
+                    });
+                if quality != preference.quality {
+                    changed = Some((preference.domain.clone(), Some(quality)));
+                }
+                if ui
+                    .small_button(icons::CLOSE_LINE)
+                    .on_hover_text(tr("domains.remove"))
+                    .clicked()
+                {
+                    changed = Some((preference.domain.clone(), None));
+                }
+                ui.end_row();
+            }
+        });
+
+    ui.add_space(6.0);
+    ui.horizontal(|ui| {
+        let label = ui.label(tr("domains.add"));
+        ui.add(
+            egui::TextEdit::singleline(&mut app.domain_input)
+                .hint_text("example.com")
+                .desired_width(200.0),
+        )
+        .labelled_by(label.id);
+        let domain = crate::domain_preferences::normalize_domain(&app.domain_input);
+        for quality in [DomainQuality::High, DomainQuality::Low] {
+            if ui
+                .add_enabled(domain.is_some(), egui::Button::new(quality_label(quality)))
+                .clicked()
+            {
+                changed = domain.clone().map(|domain| (domain, Some(quality)));
+                app.domain_input.clear();
+            }
+        }
+    });
+
+    if let Some((domain, quality)) = changed {
+        app.set_domain_preference(domain, quality);
+    }
+
+    ui.add_space(10.0);
+    egui::Grid::new("domain_boost")
+        .num_columns(2)
+        .show(ui, |ui| {
+            let label = ui.label(tr("domains.high_multiplier"));
+            ui.add(
+                egui::DragValue::new(&mut app.domain_boost.high)
+                    .range(1.0..=DomainBoost::MAX)
+                    .speed(0.05)
+                    .fixed_decimals(2),
+            )
+            .labelled_by(label.id);
+            ui.end_row();
+
+            let label = ui.label(tr("domains.low_multiplier"));
+            ui.add(
+                egui::DragValue::new(&mut app.domain_boost.low)
+                    .range(0.0..=1.0)
+                    .speed(0.05)
+                    .fixed_decimals(2),
+            )
+            .labelled_by(label.id);
+            ui.end_row();
+        });
+    ui.add_space(6.0);
+    if ui.button(tr("common.save")).clicked() {
+        app.save_domain_boost();
+    }
+}
+
+impl LocalMindApp {
+    /// Mark a domain as high or low quality, or clear its mark with `None`;
+    /// searches from then on rank its documents with it.
+    pub fn set_domain_preference(
+        &mut self,
+        domain: String,
+        quality: Option<crate::domain_preferences::DomainQuality>,
+    ) {
+        use crate::domain_preferences::DomainPreference;
+        self.domain_preferences.retain(|p| p.domain != domain);
+        if let Some(quality) = quality {
+            self.domain_preferences.push(DomainPreference {
+                domain: domain.clone(),
+                quality,
+            });
+            self.domain_preferences
+                .sort_by(|a, b| a.domain.cmp(&b.domain));
+        }
+
+        let rag = self.rag.clone();
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            if let Some(ref rag) = *rag_lock {
+                if let Err(e) = rag.db.set_domain_preference(&domain, quality).await {
+                    eprintln!("Failed to save domain preference: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Save the score multipliers for marked domains edited in Settings.
+    pub fn save_domain_boost(&mut self) {
+        let boost = self.domain_boost;
+        let rag = self.rag.clone();
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            if let Some(ref rag) = *rag_lock {
+                if let Err(e) = rag.db.set_domain_boost(&boost).await {
+                    eprintln!("Failed to save domain multipliers: {}", e);
+                }
+            }
+        });
+
+        let id = self.next_toast_id();
+        self.add_toast(Toast::success(id, tr("domains.saved")));
+    }
+}
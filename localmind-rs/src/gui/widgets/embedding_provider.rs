@@ -0,0 +1,398 @@
+//! Embedding provider settings — backend, server URL and model.
+
+use crate::embedding_provider::{list_models, EmbeddingClient, EmbeddingProvider};
+use crate::embedding_supervisor::{EmbeddingServerStatus, MAX_RESTARTS};
+use crate::gui::app::LocalMindApp;
+use crate::gui::i18n::{tr, tr_args};
+use crate::gui::state::Toast;
+use egui::Ui;
+
+/// Short text for the embedding server's state in the status bar.
+pub fn status_label(status: &EmbeddingServerStatus) -> String {
+    match status {
+        EmbeddingServerStatus::Starting => tr("embedding.status_starting").to_string(),
+        EmbeddingServerStatus::Running => tr("embedding.status_running").to_string(),
+        EmbeddingServerStatus::External => tr("embedding.status_external").to_string(),
+        EmbeddingServerStatus::Restarting { attempt } => tr_args(
+            "embedding.status_restarting",
+            &[("attempt", attempt), ("max", &MAX_RESTARTS)],
+        ),
+        EmbeddingServerStatus::Failed(e) => tr_args("embedding.server_failed", &[("error", e)]),
+    }
+}
+
+/// Render the embedding backend picker.
+///
+/// The model list is fetched from the selected server; Apply checks the
+/// backend can embed before saving and switching to it, re-embedding the
+/// documents first when the model changes.
+pub fn render_embedding_settings(ui: &mut Ui, app: &mut LocalMindApp) {
+    ui.weak(tr("embedding.intro"));
+    ui.add_space(6.0);
+
+    let mut provider_changed = false;
+    ui.horizontal(|ui| {
+        ui.label(tr("embedding.provider"));
+        egui::ComboBox::from_id_salt("embedding_provider")
+            .selected_text(app.embedding_settings.provider.label())
+            .show_ui(ui, |ui| {
+                for provider in EmbeddingProvider::ALL {
+                    if ui
+                        .selectable_value(
+                            &mut app.embedding_settings.provider,
+                            provider,
+                            provider.label(),
+                        )
+                        .changed()
+                    {
+                        provider_changed = true;
+                    }
+                }
+            });
+    });
+
+    if provider_changed {
+        app.embedding_settings.url.clear();
+        app.embedding_settings.model.clear();
+        app.embedding_models.clear();
+        app.embedding_settings_error = None;
+        if app.embedding_settings.provider.needs_model() {
+            app.refresh_embedding_models();
+        }
+    }
+
+    let provider = app.embedding_settings.provider;
+    ui.horizontal(|ui| {
+        let label = ui.label(tr("embedding.server_url"));
+        ui.add(
+            egui::TextEdit::singleline(&mut app.embedding_settings.url)
+                .hint_text(provider.default_url())
+                .desired_width(260.0),
+        )
+        .labelled_by(label.id);
+    });
+
+    if provider == EmbeddingProvider::LocalServer {
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("embedding.server_command"));
+            ui.add(
+                egui::TextEdit::singleline(&mut app.embedding_server_command)
+                    .hint_text(tr("embedding.server_command_hint"))
+                    .desired_width(260.0),
+            )
+            .labelled_by(label.id);
+        });
+        ui.weak(tr("embedding.server_managed"));
+    }
+
+    if provider.needs_model() {
+        ui.horizontal(|ui| {
+            ui.label(tr("embedding.model"));
+            let selected = if app.embedding_settings.model.is_empty() {
+                tr("embedding.choose_model").to_string()
+            } else {
+                app.embedding_settings.model.clone()
+            };
+            egui::ComboBox::from_id_salt("embedding_model")
+                .selected_text(selected)
+                .width(260.0)
+                .show_ui(ui, |ui| {
+                    for model in &app.embedding_models {
+                        ui.selectable_value(
+                            &mut app.embedding_settings.model,
+                            model.clone(),
+                            model,
+                        );
+                    }
+                });
+
+            let loading = app.is_loading_embedding_models();
+            if ui
+                .add_enabled(!loading, egui::Button::new(tr("common.refresh")))
+                .on_hover_text(tr("embedding.refresh_hint"))
+                .clicked()
+            {
+                app.refresh_embedding_models();
+            }
+            if loading {
+                ui.spinner();
+            }
+        });
+    }
+
+    ui.add_space(6.0);
+
+    let applying = app.is_applying_embedding_settings();
+    ui.horizontal(|ui| {
+        if ui
+            .add_enabled(!applying, egui::Button::new(tr("embedding.apply")))
+            .clicked()
+        {
+            app.apply_embedding_settings();
+        }
+        if applying {
+            ui.spinner();
+            ui.label(tr("embedding.testing"));
+        }
+    });
+
+    if let Some(ref progress) = app.embedding_migration {
+        ui.add_space(4.0);
+        ui.label(tr_args(
+            "embedding.migrating",
+            &[("model", &progress.model)],
+        ));
+        ui.add(egui::ProgressBar::new(progress.fraction()).text(tr_args(
+            "embedding.migration_progress",
+            &[("migrated", &progress.migrated), ("total", &progress.total)],
+        )));
+        ui.weak(tr("embedding.migration_hint"));
+    }
+
+    if let Some(ref err) = app.embedding_settings_error {
+        ui.add_space(2.0);
+        ui.colored_label(egui::Color32::from_rgb(200, 60, 60), err);
+    }
+
+    ui.add_space(6.0);
+
+    let mut persist = app.persist_query_embeddings;
+    if ui
+        .checkbox(&mut persist, tr("embedding.remember"))
+        .on_hover_text(tr("embedding.remember_hint"))
+        .changed()
+    {
+        app.set_persist_query_embeddings(persist);
+    }
+}
+
+impl LocalMindApp {
+    /// Load the stored embedding backend settings
+    pub fn load_embedding_settings(&mut self) {
+        if self.embedding_settings_receiver.is_some() {
+            return; // Already loading
+        }
+
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            if let Some(ref rag) = *rag_lock {
+                if let Ok(settings) = rag.db.get_embedding_settings().await {
+                    let command = rag
+                        .db
+                        .get_embedding_server_command()
+                        .await
+                        .unwrap_or_default()
+                        .unwrap_or_default();
+                    let persist = rag.db.get_persist_query_embeddings().await.unwrap_or(true);
+                    let _ = tx.send((settings, command, persist));
+                }
+            }
+        });
+
+        self.embedding_settings_receiver = Some(rx);
+    }
+
+    /// Check if the stored embedding backend settings have loaded
+    pub(crate) fn check_embedding_settings_loaded(&mut self) {
+        if let Some(ref rx) = self.embedding_settings_receiver {
+            match rx.try_recv() {
+                Ok((settings, command, persist)) => {
+                    self.embedding_settings_receiver = None;
+                    let needs_models = settings.provider.needs_model();
+                    self.embedding_settings = settings;
+                    self.embedding_server_command = command;
+                    self.persist_query_embeddings = persist;
+                    if needs_models {
+                        self.refresh_embedding_models();
+                    }
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.embedding_settings_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Turn on-disk caching of search query embeddings on or off
+    pub fn set_persist_query_embeddings(&mut self, enabled: bool) {
+        self.persist_query_embeddings = enabled;
+        let rag = self.rag.clone();
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            if let Some(ref rag) = *rag_lock {
+                if let Err(e) = rag.set_persist_query_embeddings(enabled).await {
+                    eprintln!("Failed to save query cache setting: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Ask the selected provider's server which models it has
+    pub fn refresh_embedding_models(&mut self) {
+        if self.embedding_models_receiver.is_some() {
+            return; // Already loading
+        }
+
+        let settings = self.embedding_settings.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        self.runtime.spawn(async move {
+            let _ = tx.send(list_models(&settings).await.map_err(|e| e.to_string()));
+        });
+
+        self.embedding_models.clear();
+        self.embedding_models_receiver = Some(rx);
+    }
+
+    /// Check if the provider's model list is loading
+    pub fn is_loading_embedding_models(&self) -> bool {
+        self.embedding_models_receiver.is_some()
+    }
+
+    /// Check if the provider's model list has loaded
+    pub(crate) fn check_embedding_models_loaded(&mut self) {
+        if let Some(ref rx) = self.embedding_models_receiver {
+            match rx.try_recv() {
+                Ok(result) => {
+                    self.embedding_models_receiver = None;
+                    match result {
+                        Ok(models) => {
+                            self.embedding_settings_error = None;
+                            self.embedding_models = models;
+                        }
+                        Err(e) => self.embedding_settings_error = Some(e),
+                    }
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.embedding_models_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Save the edited embedding backend and switch the pipeline to it, after
+    /// checking the backend is reachable and can embed.
+    pub fn apply_embedding_settings(&mut self) {
+        if self.embedding_apply_receiver.is_some() {
+            return; // Already switching
+        }
+        if let Err(e) = self.embedding_settings.validate() {
+            self.embedding_settings_error = Some(e.to_string());
+            return;
+        }
+
+        let rag = self.rag.clone();
+        let settings = self.embedding_settings.clone();
+        let command = self.embedding_server_command.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        self.runtime.spawn(async move {
+            let client = EmbeddingClient::from_settings(&settings);
+            let result = match client.health_check().await {
+                Ok(true) => match client.generate_embedding("LocalMind").await {
+                    Ok(_) => Ok(()),
+                    Err(e) => Err(tr_args("embedding.test_failed", &[("error", &e)])),
+                },
+                Ok(false) => Err(tr_args(
+                    "embedding.model_unavailable",
+                    &[("service", &client.service_name())],
+                )),
+                Err(e) => Err(e.to_string()),
+            };
+
+            let result = match result {
+                Ok(()) => {
+                    let mut rag_lock = rag.write().await;
+                    match *rag_lock {
+                        Some(ref mut rag) => {
+                            // The server command is only used at the next start
+                            match rag.db.set_embedding_server_command(&command).await {
+                                Ok(()) => {
+                                    rag.switch_embedding_model(settings, client).await.map_err(
+                                        |e| tr_args("embedding.save_failed", &[("error", &e)]),
+                                    )
+                                }
+                                Err(e) => Err(tr_args("embedding.save_failed", &[("error", &e)])),
+                            }
+                        }
+                        None => Err(tr("common.initializing").to_string()),
+                    }
+                }
+                Err(e) => Err(e),
+            };
+            let _ = tx.send(result);
+        });
+
+        self.embedding_settings_error = None;
+        self.embedding_apply_receiver = Some(rx);
+    }
+
+    /// Check if the embedding backend is being switched
+    pub fn is_applying_embedding_settings(&self) -> bool {
+        self.embedding_apply_receiver.is_some()
+    }
+
+    /// Check if switching embedding backend has finished
+    pub(crate) fn check_embedding_settings_applied(&mut self) {
+        if let Some(ref rx) = self.embedding_apply_receiver {
+            match rx.try_recv() {
+                Ok(result) => {
+                    self.embedding_apply_receiver = None;
+                    let model = crate::embedding_migration::model_label(&self.embedding_settings);
+                    match result {
+                        Ok(true) => {
+                            let id = self.next_toast_id();
+                            self.add_toast(Toast::success(
+                                id,
+                                tr_args("embedding.migration_started", &[("model", &model)]),
+                            ));
+                        }
+                        Ok(false) => {
+                            self.embedding_migration = None;
+                            let id = self.next_toast_id();
+                            self.add_toast(Toast::success(
+                                id,
+                                tr_args("embedding.now_using", &[("model", &model)]),
+                            ));
+                        }
+                        Err(e) => self.embedding_settings_error = Some(e),
+                    }
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.embedding_apply_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Check for progress of re-embedding with a new model
+    pub(crate) fn check_embedding_migration(&mut self) {
+        let mut updates = Vec::new();
+        if let Some(ref rx) = self.embedding_migration_receiver {
+            while let Ok(update) = rx.try_recv() {
+                updates.push(update);
+            }
+        }
+
+        for update in updates {
+            match update {
+                Some(progress) => self.embedding_migration = Some(progress),
+                None => {
+                    if let Some(progress) = self.embedding_migration.take() {
+                        let id = self.next_toast_id();
+                        self.add_toast(Toast::success(
+                            id,
+                            tr_args("embedding.now_using", &[("model", &progress.model)]),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+}
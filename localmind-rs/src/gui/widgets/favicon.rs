@@ -0,0 +1,114 @@
+//! Document icon — the site's favicon, or an icon for where the document
+//! came from.
+
+use crate::gui::app::LocalMindApp;
+use crate::gui::i18n::tr;
+use crate::gui::widgets::ingest_policy;
+use crate::ingest_policy::SourceKind;
+use crate::services::{HIGHLIGHT_SOURCE, NOTE_SOURCE, PASTED_URL_SOURCE};
+use egui::Ui;
+use egui_remixicon::icons;
+use std::sync::Arc;
+
+/// Icon for a document's source, used when its site has no favicon.
+pub fn source_icon(source: &str, has_url: bool) -> &'static str {
+    match SourceKind::of(source) {
+        Some(SourceKind::Bookmarks) => icons::BOOKMARK_LINE,
+        Some(SourceKind::Extension) => icons::GLOBAL_LINE,
+        Some(SourceKind::Feeds) => icons::RSS_LINE,
+        Some(SourceKind::LocalFiles) => icons::FILE_TEXT_LINE,
+        None if has_url => icons::GLOBAL_LINE,
+        None => icons::FILE_TEXT_LINE,
+    }
+}
+
+/// Display name of a document source: its kind, or a watched folder's name.
+pub fn source_label(source: &str) -> String {
+    if source == NOTE_SOURCE {
+        return tr("home.notes").to_string();
+    }
+    if source == PASTED_URL_SOURCE {
+        return tr("home.pasted").to_string();
+    }
+    if source == HIGHLIGHT_SOURCE {
+        return tr("home.highlights").to_string();
+    }
+    let path = std::path::Path::new(source);
+    if path.is_absolute() {
+        if let Some(name) = path.file_name() {
+            return name.to_string_lossy().into_owned();
+        }
+    }
+    match SourceKind::of(source) {
+        Some(kind) => ingest_policy::kind_label(kind).to_string(),
+        None => source.to_string(),
+    }
+}
+
+/// Render a small icon in front of a document title: `favicon` (host and
+/// PNG bytes) when the site has one, otherwise the source's icon.
+pub fn document_icon(
+    ui: &mut Ui,
+    favicon: Option<(String, Arc<[u8]>)>,
+    source: &str,
+    has_url: bool,
+) {
+    match favicon {
+        Some((host, png)) => {
+            ui.add(
+                egui::Image::from_bytes(format!("bytes://favicon/{}.png", host), png)
+                    .fit_to_exact_size(egui::vec2(16.0, 16.0)),
+            );
+        }
+        None => {
+            ui.weak(source_icon(source, has_url));
+        }
+    }
+}
+
+impl LocalMindApp {
+    /// Load the cached icons of the sites behind `urls` not asked for yet
+    pub(crate) fn load_favicons(&mut self, urls: &[String]) {
+        let hosts: Vec<String> = urls
+            .iter()
+            .filter_map(|url| crate::favicon::site_host(url))
+            .filter(|host| self.favicons_requested.insert(host.clone()))
+            .collect();
+        if hosts.is_empty() {
+            return;
+        }
+
+        let rag = self.rag.clone();
+        let tx = self.favicon_tx.clone();
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            let icons = match *rag_lock {
+                Some(ref rag) => rag.db.get_favicons(&hosts).await.unwrap_or_else(|e| {
+                    eprintln!("Failed to load site icons: {}", e);
+                    Default::default()
+                }),
+                None => Default::default(),
+            };
+            let _ = tx.send((hosts, icons));
+        });
+        self.favicon_loads_pending += 1;
+    }
+
+    /// Store site icons that have loaded. Hosts without a cached icon yet
+    /// are asked for again next time, as it may have been fetched since.
+    pub(crate) fn check_favicons_loaded(&mut self) {
+        while let Ok((hosts, mut icons)) = self.favicon_rx.try_recv() {
+            self.favicon_loads_pending = self.favicon_loads_pending.saturating_sub(1);
+            for host in hosts {
+                match icons.remove(&host) {
+                    Some(icon) => {
+                        self.favicons.insert(host, Arc::from(icon));
+                    }
+                    None => {
+                        self.favicons_requested.remove(&host);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,29 @@
+//! Star toggle for pinning documents to the home view.
+
+use egui::Ui;
+use egui_remixicon::icons;
+
+use crate::gui::accessibility::Labeled;
+use crate::gui::i18n::tr;
+
+/// Render a frameless star button; filled when the document is pinned.
+///
+/// The caller toggles the pin via `LocalMindApp::toggle_favorite` when the
+/// returned response is clicked.
+pub fn star_button(ui: &mut Ui, is_favorite: bool) -> egui::Response {
+    let (icon, color, hover) = if is_favorite {
+        (
+            icons::STAR_FILL,
+            egui::Color32::from_rgb(230, 180, 30),
+            tr("favorite.unpin"),
+        )
+    } else {
+        (
+            icons::STAR_LINE,
+            ui.visuals().weak_text_color(),
+            tr("favorite.pin"),
+        )
+    };
+    ui.add(egui::Button::new(egui::RichText::new(icon).color(color)).frame(false))
+        .labeled(hover)
+}
@@ -0,0 +1,140 @@
+//! HTTP API widget — port, bind address and where the server listens.
+
+use crate::gui::app::LocalMindApp;
+use crate::gui::i18n::{tr, tr_args};
+use crate::http_settings::{parse_bind_address, DEFAULT_PORTS};
+use egui::Ui;
+
+/// Render the HTTP API settings.
+///
+/// The port is saved when its field is released and the bind address once
+/// it parses and loses focus; the server rebinds within a few seconds.
+pub fn render_http_server_settings(ui: &mut Ui, app: &mut LocalMindApp) {
+    ui.weak(tr("http_server.intro"));
+    ui.add_space(6.0);
+
+    let mut changed = false;
+    let mut fixed = app.http_server_settings.port.is_some();
+    if ui
+        .checkbox(&mut fixed, tr("http_server.fixed_port"))
+        .changed()
+    {
+        app.http_server_settings.port = fixed.then_some(*DEFAULT_PORTS.start());
+        changed = true;
+    }
+    if let Some(ref mut port) = app.http_server_settings.port {
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("http_server.port"));
+            let response = ui
+                .add(egui::DragValue::new(port).range(1024..=65535))
+                .labelled_by(label.id);
+            changed |= response.drag_stopped() || response.lost_focus();
+        });
+    }
+
+    ui.horizontal(|ui| {
+        let label = ui.label(tr("http_server.bind_address"));
+        let response = ui
+            .add(
+                egui::TextEdit::singleline(&mut app.http_bind_input)
+                    .hint_text("127.0.0.1")
+                    .desired_width(140.0),
+            )
+            .labelled_by(label.id);
+        if response.lost_focus() && parse_bind_address(&app.http_bind_input).is_some() {
+            let address = app.http_bind_input.trim().to_string();
+            if address != app.http_server_settings.bind_address {
+                app.http_server_settings.bind_address = address;
+                changed = true;
+            }
+        }
+    });
+    if parse_bind_address(&app.http_bind_input).is_none() {
+        ui.colored_label(
+            ui.visuals().error_fg_color,
+            tr("http_server.invalid_address"),
+        );
+    }
+
+    if changed {
+        app.save_http_server_settings();
+    }
+
+    ui.add_space(6.0);
+    match app.http_server_status.get() {
+        Some(Ok(address)) => {
+            ui.label(tr_args(
+                "http_server.listening",
+                &[
+                    ("scheme", &app.http_server_settings.scheme()),
+                    ("address", &address),
+                ],
+            ));
+        }
+        Some(Err(e)) => {
+            ui.colored_label(ui.visuals().error_fg_color, e);
+        }
+        None => {}
+    }
+    if !app.http_server_settings.is_local_only() {
+        ui.colored_label(ui.visuals().warn_fg_color, tr("http_server.warning"));
+    }
+}
+
+impl LocalMindApp {
+    /// Load the HTTP API port and bind address settings
+    pub fn load_http_server_settings(&mut self) {
+        if self.http_server_receiver.is_some() {
+            return;
+        }
+
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            if let Some(ref rag) = *rag_lock {
+                match rag.db.get_http_server_settings().await {
+                    Ok(settings) => {
+                        let _ = tx.send(settings);
+                    }
+                    Err(e) => eprintln!("Failed to load HTTP server settings: {}", e),
+                }
+            }
+        });
+
+        self.http_server_receiver = Some(rx);
+    }
+
+    /// Check if the HTTP API settings have loaded
+    pub(crate) fn check_http_server_settings_loaded(&mut self) {
+        if let Some(ref rx) = self.http_server_receiver {
+            match rx.try_recv() {
+                Ok(settings) => {
+                    self.http_bind_input = settings.bind_address.clone();
+                    self.http_server_settings = settings;
+                    self.http_server_receiver = None;
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.http_server_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Save the HTTP API settings edited in Settings. The server rebinds
+    /// with them within a few seconds.
+    pub fn save_http_server_settings(&mut self) {
+        let settings = self.http_server_settings.clone();
+        let rag = self.rag.clone();
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            if let Some(ref rag) = *rag_lock {
+                if let Err(e) = rag.db.set_http_server_settings(&settings).await {
+                    eprintln!("Failed to save HTTP server settings: {}", e);
+                }
+            }
+        });
+    }
+}
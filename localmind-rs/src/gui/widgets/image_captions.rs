@@ -0,0 +1,61 @@
+//! Settings section for captioning bookmarked images with a vision model
+
+use crate::gui::app::LocalMindApp;
+use crate::gui::i18n::tr;
+use crate::gui::state::Toast;
+use egui::Ui;
+
+/// Render the image caption toggle, Ollama address and model.
+///
+/// Saved with the button; images already stored as placeholders are
+/// captioned when they are fetched again.
+pub fn render_image_caption_settings(ui: &mut Ui, app: &mut LocalMindApp) {
+    ui.weak(tr("image_captions.intro"));
+    ui.add_space(6.0);
+
+    ui.checkbox(
+        &mut app.image_caption_settings.enabled,
+        tr("image_captions.enabled"),
+    );
+
+    ui.add_enabled_ui(app.image_caption_settings.enabled, |ui| {
+        egui::Grid::new("image_caption_settings")
+            .num_columns(2)
+            .show(ui, |ui| {
+                let label = ui.label(tr("image_captions.url"));
+                ui.text_edit_singleline(&mut app.image_caption_settings.url)
+                    .labelled_by(label.id);
+                ui.end_row();
+
+                let label = ui.label(tr("image_captions.model"));
+                ui.text_edit_singleline(&mut app.image_caption_settings.model)
+                    .labelled_by(label.id);
+                ui.end_row();
+            });
+    });
+
+    ui.add_space(6.0);
+    if ui.button(tr("common.save")).clicked() {
+        app.save_image_caption_settings();
+    }
+}
+
+impl LocalMindApp {
+    /// Save the image caption settings edited in Settings; images fetched
+    /// from then on are captioned with them.
+    pub fn save_image_caption_settings(&mut self) {
+        let settings = self.image_caption_settings.clone();
+        let rag = self.rag.clone();
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            if let Some(ref rag) = *rag_lock {
+                if let Err(e) = rag.db.set_image_caption_settings(&settings).await {
+                    eprintln!("Failed to save image caption settings: {}", e);
+                }
+            }
+        });
+
+        let id = self.next_toast_id();
+        self.add_toast(Toast::success(id, tr("image_captions.saved")));
+    }
+}
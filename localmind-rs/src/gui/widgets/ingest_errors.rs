@@ -0,0 +1,220 @@
+//! Failed Imports widget — bookmarks whose fetch failed, with retry controls.
+
+use crate::gui::app::LocalMindApp;
+use crate::gui::i18n::{tr, tr_args};
+use crate::gui::state::Toast;
+use egui::Ui;
+
+/// Render the failed-imports report.
+///
+/// Lists bookmarks that could not be fetched (kept out of the search index)
+/// with per-row Retry / Ignore buttons and a Retry All action.
+pub fn render_ingest_errors(ui: &mut Ui, app: &mut LocalMindApp) {
+    ui.weak(tr("failed.intro"));
+    ui.add_space(6.0);
+
+    if app.ingest_errors.is_empty() {
+        ui.weak(tr("failed.none"));
+        return;
+    }
+
+    let retrying = app.is_retrying_ingest_errors();
+    let errors = app.ingest_errors.clone();
+    let mut retry_ids: Vec<i64> = Vec::new();
+    let mut ignore_id: Option<i64> = None;
+
+    ui.horizontal(|ui| {
+        ui.label(tr_args("failed.count", &[("count", &errors.len())]));
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            ui.add_enabled_ui(!retrying, |ui| {
+                if ui.button(tr("failed.retry_all")).clicked() {
+                    retry_ids = errors.iter().map(|e| e.id).collect();
+                }
+            });
+            if retrying {
+                ui.spinner();
+            }
+        });
+    });
+
+    ui.add_space(4.0);
+
+    egui::ScrollArea::vertical()
+        .id_salt("ingest_errors_scroll")
+        .auto_shrink([false, true])
+        .max_height(200.0)
+        .show(ui, |ui| {
+            for error in &errors {
+                ui.push_id(error.id, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.vertical(|ui| {
+                            ui.label(&error.title);
+                            ui.weak(&error.url);
+                            ui.colored_label(
+                                egui::Color32::from_rgb(200, 60, 60),
+                                tr_args(
+                                    "failed.error",
+                                    &[("error", &error.error), ("attempts", &error.attempts)],
+                                ),
+                            );
+                        });
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            if ui.button(tr("failed.ignore")).clicked() {
+                                ignore_id = Some(error.id);
+                            }
+                            ui.add_enabled_ui(!retrying, |ui| {
+                                if ui.button(tr("failed.retry")).clicked() {
+                                    retry_ids = vec![error.id];
+                                }
+                            });
+                        });
+                    });
+                });
+                ui.add_space(4.0);
+            }
+        });
+
+    // Apply actions outside the loop to avoid borrow issues
+    if let Some(id) = ignore_id {
+        app.ignore_ingest_error(id);
+    }
+    if !retry_ids.is_empty() {
+        app.retry_ingest_errors(retry_ids);
+    }
+}
+
+impl LocalMindApp {
+    /// Load failed bookmark imports from the database
+    pub fn load_ingest_errors(&mut self) {
+        if self.ingest_errors_receiver.is_some() {
+            return; // Already loading
+        }
+
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            let errors = if let Some(ref rag) = *rag_lock {
+                rag.db.get_ingest_errors().await.unwrap_or_default()
+            } else {
+                Vec::new()
+            };
+            let _ = tx.send(errors);
+        });
+
+        self.ingest_errors_receiver = Some(rx);
+    }
+
+    /// Check if the failed-imports list has loaded
+    pub(crate) fn check_ingest_errors_loaded(&mut self) {
+        if let Some(ref rx) = self.ingest_errors_receiver {
+            match rx.try_recv() {
+                Ok(errors) => {
+                    self.ingest_errors = errors;
+                    self.ingest_errors_receiver = None;
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {
+                    // Still loading
+                }
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.ingest_errors_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Re-fetch and ingest the given failed imports in the background
+    pub fn retry_ingest_errors(&mut self, ids: Vec<i64>) {
+        use crate::ingest_queue::{process_queue_item, QueueOutcome};
+
+        if self.retry_receiver.is_some() || ids.is_empty() {
+            return; // Retry already running
+        }
+
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            let mut ingested = 0;
+            let mut failed = 0;
+            if let Some(ref rag) = *rag_lock {
+                for id in ids {
+                    let item = match rag.db.requeue_ingest_error(id).await {
+                        Ok(Some(item)) => item,
+                        Ok(None) => continue,
+                        Err(e) => {
+                            eprintln!("Failed to requeue import {}: {}", id, e);
+                            failed += 1;
+                            continue;
+                        }
+                    };
+                    match process_queue_item(rag, &item).await {
+                        QueueOutcome::Ingested | QueueOutcome::AlreadyIndexed => ingested += 1,
+                        QueueOutcome::Failed(_) => failed += 1,
+                    }
+                }
+            }
+            let _ = tx.send((ingested, failed));
+        });
+
+        self.retry_receiver = Some(rx);
+    }
+
+    /// Check if a retry is in progress
+    pub fn is_retrying_ingest_errors(&self) -> bool {
+        self.retry_receiver.is_some()
+    }
+
+    /// Check for retry completion and refresh the failed-imports list
+    pub(crate) fn check_retry_results(&mut self) {
+        if let Some(ref rx) = self.retry_receiver {
+            match rx.try_recv() {
+                Ok((ingested, failed)) => {
+                    self.retry_receiver = None;
+                    let id = self.next_toast_id();
+                    if failed == 0 {
+                        self.add_toast(Toast::success(
+                            id,
+                            tr_args("failed.retry_done", &[("count", &ingested)]),
+                        ));
+                    } else {
+                        self.add_toast(Toast::error(
+                            id,
+                            tr_args(
+                                "failed.retry_partial",
+                                &[("ingested", &ingested), ("failed", &failed)],
+                            ),
+                        ));
+                    }
+                    self.load_ingest_errors();
+                    if ingested > 0 {
+                        self.load_recent_documents();
+                    }
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {
+                    // Still retrying
+                }
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.retry_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Hide a failed import from the report and stop retrying it
+    pub fn ignore_ingest_error(&mut self, id: i64) {
+        self.ingest_errors.retain(|e| e.id != id);
+
+        let rag = self.rag.clone();
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            if let Some(ref rag) = *rag_lock {
+                if let Err(e) = rag.db.ignore_ingest_error(id).await {
+                    eprintln!("Failed to ignore import {}: {}", id, e);
+                }
+            }
+        });
+    }
+}
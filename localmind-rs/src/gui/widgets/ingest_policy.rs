@@ -0,0 +1,133 @@
+//! Ingestion policy widget — per-source fetching, size limit, summaries and tags.
+
+use crate::gui::app::LocalMindApp;
+use crate::gui::i18n::tr;
+use crate::ingest_policy::{IngestPolicy, SourceKind};
+use egui::Ui;
+
+/// Localized name of a source kind.
+pub fn kind_label(kind: SourceKind) -> &'static str {
+    match kind {
+        SourceKind::Bookmarks => tr("policy.bookmarks"),
+        SourceKind::Extension => tr("policy.extension"),
+        SourceKind::Feeds => tr("policy.feeds"),
+        SourceKind::LocalFiles => tr("policy.local_files"),
+    }
+}
+
+/// Render one group of policy settings per source kind.
+///
+/// Changes are saved as they are made; default tags are saved when the
+/// field loses focus.
+pub fn render_ingest_policies(ui: &mut Ui, app: &mut LocalMindApp) {
+    ui.weak(tr("policy.intro"));
+    ui.add_space(6.0);
+
+    for kind in SourceKind::ALL {
+        let current = app.ingest_policies.get(kind);
+        let mut policy = current.clone();
+
+        ui.collapsing(kind_label(kind), |ui| {
+            if kind.fetches_remote() {
+                ui.checkbox(&mut policy.fetch_remote, tr("policy.fetch_remote"))
+                    .on_hover_text(tr("policy.fetch_remote_hint"));
+            }
+
+            ui.horizontal(|ui| {
+                let label = ui.label(tr("policy.max_chars"));
+                ui.add(
+                    egui::DragValue::new(&mut policy.max_content_chars)
+                        .range(0..=10_000_000)
+                        .speed(100),
+                )
+                .labelled_by(label.id);
+                if policy.max_content_chars == 0 {
+                    ui.weak(tr("policy.unlimited"));
+                }
+            });
+
+            ui.checkbox(&mut policy.auto_summarize, tr("policy.auto_summarize"));
+
+            ui.horizontal(|ui| {
+                let label = ui.label(tr("policy.default_tags"));
+                let input = app.ingest_policy_tag_inputs.entry(kind).or_default();
+                let response = ui
+                    .add(
+                        egui::TextEdit::singleline(input)
+                            .hint_text(tr("policy.default_tags_hint"))
+                            .desired_width(220.0),
+                    )
+                    .labelled_by(label.id);
+                if response.lost_focus() {
+                    policy.default_tags = IngestPolicy::parse_tags(input);
+                    *input = policy.default_tags.join(", ");
+                }
+            });
+        });
+
+        if policy != current {
+            app.set_ingest_policy(kind, policy);
+        }
+    }
+}
+
+impl LocalMindApp {
+    /// Load the per-source ingestion policies
+    pub fn load_ingest_policies(&mut self) {
+        if self.ingest_policies_receiver.is_some() {
+            return;
+        }
+
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            if let Some(ref rag) = *rag_lock {
+                match rag.db.get_ingest_policies().await {
+                    Ok(policies) => {
+                        let _ = tx.send(policies);
+                    }
+                    Err(e) => eprintln!("Failed to load ingestion policies: {}", e),
+                }
+            }
+        });
+
+        self.ingest_policies_receiver = Some(rx);
+    }
+
+    /// Check if the ingestion policies have loaded
+    pub(crate) fn check_ingest_policies_loaded(&mut self) {
+        if let Some(ref rx) = self.ingest_policies_receiver {
+            match rx.try_recv() {
+                Ok(policies) => {
+                    self.ingest_policy_tag_inputs = SourceKind::ALL
+                        .into_iter()
+                        .map(|kind| (kind, policies.get(kind).default_tags.join(", ")))
+                        .collect();
+                    self.ingest_policies = policies;
+                    self.ingest_policies_receiver = None;
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.ingest_policies_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Change the ingestion policy for one source kind
+    pub fn set_ingest_policy(&mut self, kind: SourceKind, policy: IngestPolicy) {
+        self.ingest_policies.set(kind, policy.clone());
+
+        let rag = self.rag.clone();
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            if let Some(ref rag) = *rag_lock {
+                if let Err(e) = rag.db.set_ingest_policy(kind, &policy).await {
+                    eprintln!("Failed to save ingestion policy: {}", e);
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,194 @@
+//! First-ingestion preview window: what will be imported, with folder and
+//! domain checkboxes to skip parts of it.
+
+use crate::gui::app::LocalMindApp;
+use crate::gui::i18n::{tr, tr_args};
+use crate::gui::state::Toast;
+use crate::ingestion_preview::{estimated_duration, format_estimate};
+use crate::storage_budget::format_bytes;
+
+/// Render the preview window while a preview is waiting for an answer.
+pub fn render_ingestion_preview(ctx: &egui::Context, app: &mut LocalMindApp) {
+    let Some(preview) = app.ingestion_preview.clone() else {
+        return;
+    };
+
+    let folders = preview.folders();
+    let domains = preview.domains();
+    let selected =
+        preview.selected_count(&app.preview_skipped_folders, &app.preview_skipped_domains);
+    let mut answer = None;
+
+    egui::Window::new(tr("import.title"))
+        .collapsible(false)
+        .resizable(true)
+        .default_size([560.0, 480.0])
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(ctx, |ui| {
+            ui.label(tr_args(
+                "preview.summary",
+                &[
+                    ("count", &preview.bookmarks.len()),
+                    ("folders", &folders.len()),
+                    (
+                        "estimate",
+                        &format_estimate(estimated_duration(preview.bookmarks.len())),
+                    ),
+                ],
+            ));
+            if let Some(storage) = preview.storage {
+                let growth = storage.estimate_documents(selected);
+                match storage.free_bytes {
+                    Some(free) => ui.label(tr_args(
+                        "preview.storage_free",
+                        &[
+                            ("size", &format_bytes(growth)),
+                            ("free", &format_bytes(free)),
+                        ],
+                    )),
+                    None => ui.label(tr_args(
+                        "preview.storage",
+                        &[("size", &format_bytes(growth))],
+                    )),
+                };
+                if storage.bytes_over_budget(growth) > 0 {
+                    ui.colored_label(
+                        ui.visuals().error_fg_color,
+                        tr_args(
+                            "preview.over_budget",
+                            &[("limit", &format_bytes(storage.max_index_bytes))],
+                        ),
+                    );
+                } else if storage.low_disk(growth) {
+                    ui.colored_label(ui.visuals().warn_fg_color, tr("preview.low_disk"));
+                }
+            }
+            ui.weak(tr("preview.uncheck"));
+
+            ui.add_space(10.0);
+
+            ui.columns(2, |columns| {
+                columns[0].strong(tr_args("preview.folders", &[("count", &folders.len())]));
+                egui::ScrollArea::vertical()
+                    .id_salt("preview_folders")
+                    .max_height(300.0)
+                    .show(&mut columns[0], |ui| {
+                        for folder in &folders {
+                            let mut included = !app.preview_skipped_folders.contains(&folder.id);
+                            let label = format!("{} ({})", folder.path.join(" / "), folder.count);
+                            if ui
+                                .checkbox(&mut included, label)
+                                .on_hover_text(&folder.profile)
+                                .changed()
+                            {
+                                if included {
+                                    app.preview_skipped_folders.remove(&folder.id);
+                                } else {
+                                    app.preview_skipped_folders.insert(folder.id.clone());
+                                }
+                            }
+                        }
+                    });
+
+                columns[1].strong(tr_args("preview.domains", &[("count", &domains.len())]));
+                egui::ScrollArea::vertical()
+                    .id_salt("preview_domains")
+                    .max_height(300.0)
+                    .show(&mut columns[1], |ui| {
+                        for (domain, count) in &domains {
+                            let mut included = !app.preview_skipped_domains.contains(domain);
+                            if ui
+                                .checkbox(&mut included, format!("{} ({})", domain, count))
+                                .changed()
+                            {
+                                if included {
+                                    app.preview_skipped_domains.remove(domain);
+                                } else {
+                                    app.preview_skipped_domains.insert(domain.clone());
+                                }
+                            }
+                        }
+                    });
+            });
+
+            ui.add_space(10.0);
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                ui.label(tr_args(
+                    "preview.selected",
+                    &[
+                        ("selected", &selected),
+                        ("total", &preview.bookmarks.len()),
+                        ("estimate", &format_estimate(estimated_duration(selected))),
+                    ],
+                ));
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.button(tr("preview.skip")).clicked() {
+                        answer = Some(false);
+                    }
+                    if ui.button(tr("preview.start")).clicked() {
+                        answer = Some(true);
+                    }
+                });
+            });
+        });
+
+    if let Some(ingest) = answer {
+        app.answer_ingestion_preview(ingest);
+    }
+}
+
+impl LocalMindApp {
+    /// Check for a first-ingestion preview from the bookmark monitor
+    pub(crate) fn check_ingestion_preview(&mut self) {
+        if let Some(ref rx) = self.preview_request_receiver {
+            match rx.try_recv() {
+                Ok(request) => {
+                    // Only one preview is ever sent
+                    self.preview_request_receiver = None;
+                    self.preview_skipped_folders.clear();
+                    self.preview_skipped_domains.clear();
+                    self.ingestion_preview = Some(request.preview);
+                    self.preview_reply = Some(request.reply);
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.preview_request_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Answer the first-ingestion preview: import the selected bookmarks, or
+    /// skip ingestion until the next launch
+    pub fn answer_ingestion_preview(&mut self, ingest: bool) {
+        use crate::ingestion_preview::PreviewDecision;
+
+        let Some(preview) = self.ingestion_preview.take() else {
+            return;
+        };
+        let decision = if ingest {
+            PreviewDecision::Ingest {
+                skipped_folders: self.preview_skipped_folders.drain().collect(),
+                skipped_domains: self.preview_skipped_domains.drain().collect(),
+            }
+        } else {
+            PreviewDecision::Skip
+        };
+
+        let id = self.next_toast_id();
+        if ingest {
+            self.add_toast(Toast::info(id, tr("import.starting")));
+        } else {
+            self.add_toast(Toast::info(
+                id,
+                tr_args("import.skipped", &[("count", &preview.bookmarks.len())]),
+            ));
+        }
+
+        if let Some(reply) = self.preview_reply.take() {
+            let _ = reply.send(decision);
+        }
+    }
+}
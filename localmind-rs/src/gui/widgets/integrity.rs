@@ -0,0 +1,199 @@
+//! Integrity report window — problems found by the startup audit, each with
+//! a one-click repair.
+
+use crate::gui::app::LocalMindApp;
+use crate::gui::i18n::{tr, tr_args};
+use crate::gui::state::Toast;
+use crate::integrity::Repair;
+
+/// Localized label of a repair button.
+fn repair_label(repair: Repair) -> &'static str {
+    match repair {
+        Repair::ReEmbed => tr("integrity.re_embed"),
+        Repair::RebuildFts => tr("integrity.rebuild_fts"),
+        Repair::DropCorruptRows => tr("integrity.drop_rows"),
+    }
+}
+
+/// Render the integrity report window while `app.show_integrity_report` is set.
+pub fn render_integrity_report(ctx: &egui::Context, app: &mut LocalMindApp) {
+    if !app.show_integrity_report {
+        return;
+    }
+    let Some(report) = app.integrity_report.clone() else {
+        return;
+    };
+
+    let mut open = true;
+    egui::Window::new(tr("integrity.title"))
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(ctx, |ui| {
+            ui.weak(tr("integrity.intro"));
+            ui.add_space(6.0);
+
+            if !report.documents_without_chunks.is_empty() {
+                ui.label(tr_args(
+                    "integrity.without_chunks",
+                    &[("count", &report.documents_without_chunks.len())],
+                ));
+            }
+            if report.orphan_chunks > 0 {
+                ui.label(tr_args(
+                    "integrity.orphan_chunks",
+                    &[("count", &report.orphan_chunks)],
+                ));
+            }
+            if report.fts_out_of_sync() {
+                ui.label(tr_args(
+                    "integrity.fts_mismatch",
+                    &[
+                        ("rows", &report.fts_count),
+                        ("count", &report.document_count),
+                    ],
+                ));
+            }
+            if !report.corrupt_chunks.is_empty() {
+                ui.label(tr_args(
+                    "integrity.corrupt_chunks",
+                    &[("count", &report.corrupt_chunks.len())],
+                ));
+            }
+            if !report.mismatched_chunks.is_empty() {
+                let expected = report.expected_dimension.unwrap_or_default();
+                ui.label(tr_args(
+                    "integrity.mismatched_chunks",
+                    &[
+                        ("count", &report.mismatched_chunks.len()),
+                        ("dimension", &expected),
+                    ],
+                ));
+            }
+
+            ui.add_space(6.0);
+
+            let running = app.is_integrity_repair_running();
+            ui.horizontal(|ui| {
+                ui.add_enabled_ui(!running, |ui| {
+                    for repair in report.repairs() {
+                        let enabled = repair != Repair::ReEmbed || app.semantic_search_available;
+                        let response = ui
+                            .add_enabled(enabled, egui::Button::new(repair_label(repair)))
+                            .on_disabled_hover_text(tr("integrity.re_embed_unavailable"));
+                        if response.clicked() {
+                            app.run_integrity_repair(repair);
+                        }
+                    }
+                });
+                if running {
+                    ui.spinner();
+                }
+            });
+            if report.repairs().contains(&Repair::DropCorruptRows) {
+                ui.weak(tr("integrity.drop_rows_hint"));
+            }
+        });
+
+    if !open {
+        app.show_integrity_report = false;
+    }
+}
+
+impl LocalMindApp {
+    /// Check for integrity reports from the startup audit or a repair
+    pub(crate) fn check_integrity_reports(&mut self) {
+        if let Some(ref rx) = self.integrity_report_receiver {
+            while let Ok(report) = rx.try_recv() {
+                self.integrity_report = Some(report);
+            }
+        }
+        if self
+            .integrity_report
+            .as_ref()
+            .is_some_and(|report| report.is_healthy())
+        {
+            self.show_integrity_report = false;
+        }
+    }
+
+    /// Check if an integrity repair has finished and report the result
+    pub(crate) fn check_integrity_repair(&mut self) {
+        if let Some(ref rx) = self.integrity_repair_receiver {
+            match rx.try_recv() {
+                Ok(result) => {
+                    self.integrity_repair_receiver = None;
+                    let id = self.next_toast_id();
+                    match result {
+                        Ok((repair, count)) => {
+                            let message = match repair {
+                                crate::integrity::Repair::ReEmbed => {
+                                    tr_args("integrity.re_embedded", &[("count", &count)])
+                                }
+                                crate::integrity::Repair::RebuildFts => {
+                                    tr_args("integrity.fts_rebuilt", &[("count", &count)])
+                                }
+                                crate::integrity::Repair::DropCorruptRows => {
+                                    tr_args("integrity.rows_dropped", &[("count", &count)])
+                                }
+                            };
+                            self.add_toast(Toast::success(id, message));
+                        }
+                        Err(e) => {
+                            self.add_toast(Toast::error(
+                                id,
+                                tr_args("integrity.repair_failed", &[("error", &e)]),
+                            ));
+                        }
+                    }
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.integrity_repair_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Check if an integrity repair is currently running
+    pub fn is_integrity_repair_running(&self) -> bool {
+        self.integrity_repair_receiver.is_some()
+    }
+
+    /// Run one integrity repair, then audit again so the report reflects it
+    pub fn run_integrity_repair(&mut self, repair: crate::integrity::Repair) {
+        if self.is_integrity_repair_running() {
+            return;
+        }
+        let Some(report) = self.integrity_report.clone() else {
+            return;
+        };
+
+        let rag = self.rag.clone();
+        let report_tx = self.integrity_report_tx.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            let result = match *rag_lock {
+                Some(ref rag) => crate::integrity::repair(rag, repair, &report)
+                    .await
+                    .map(|count| (repair, count))
+                    .map_err(|e| e.to_string()),
+                None => Err(tr("common.not_ready").to_string()),
+            };
+            if let Some(ref rag) = *rag_lock {
+                match crate::integrity::audit(&rag.db).await {
+                    Ok(report) => {
+                        let _ = report_tx.send(report);
+                    }
+                    Err(e) => eprintln!("Integrity audit failed: {}", e),
+                }
+            }
+            let _ = tx.send(result);
+        });
+
+        self.integrity_repair_receiver = Some(rx);
+    }
+}
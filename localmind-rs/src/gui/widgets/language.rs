@@ -0,0 +1,137 @@
+//! Language badge — the detected language of a document, next to its
+//! title — and the document language settings.
+
+use crate::gui::app::LocalMindApp;
+use crate::gui::i18n::{tr, tr_args};
+use egui::Ui;
+
+/// English name of a language given by its ISO 639-3 code, or the code
+/// itself for languages without one.
+pub fn language_name(code: &str) -> String {
+    crate::language::english_name(code)
+        .unwrap_or(code)
+        .to_string()
+}
+
+/// Render a small badge such as "ES", naming the language on hover.
+pub fn language_badge(ui: &mut Ui, code: &str) {
+    egui::Frame::none()
+        .stroke(ui.visuals().widgets.noninteractive.bg_stroke)
+        .rounding(3.0)
+        .inner_margin(egui::vec2(4.0, 0.0))
+        .show(ui, |ui| {
+            ui.label(
+                egui::RichText::new(crate::language::badge(code))
+                    .small()
+                    .weak(),
+            );
+        })
+        .response
+        .on_hover_text(tr_args(
+            "language.badge_hint",
+            &[("language", &language_name(code)), ("code", &code)],
+        ));
+}
+
+/// Render the languages found in the library, each with its document count
+/// and a checkbox to embed it.
+pub fn render_language_settings(ui: &mut Ui, app: &mut LocalMindApp) {
+    ui.weak(tr("language.intro"));
+    ui.add_space(6.0);
+
+    if app.language_counts.is_empty() {
+        ui.weak(tr("language.none"));
+        return;
+    }
+
+    let mut toggled = None;
+    egui::Grid::new("document_languages_grid")
+        .num_columns(3)
+        .spacing([12.0, 4.0])
+        .show(ui, |ui| {
+            for (code, count) in &app.language_counts {
+                ui.label(language_name(code))
+                    .on_hover_text(tr_args("language.search_hint", &[("code", code)]));
+                ui.weak(tr_args("common.documents_count", &[("count", count)]));
+
+                let mut embedded = !app.excluded_languages.contains(code);
+                if ui.checkbox(&mut embedded, tr("language.embed")).changed() {
+                    toggled = Some((code.clone(), !embedded));
+                }
+                ui.end_row();
+            }
+        });
+
+    if let Some((code, excluded)) = toggled {
+        app.set_language_excluded(&code, excluded);
+    }
+}
+
+impl LocalMindApp {
+    /// Load the languages found in the library and the ones excluded from
+    /// embedding
+    pub fn load_language_settings(&mut self) {
+        if self.language_settings_receiver.is_some() {
+            return;
+        }
+
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            if let Some(ref rag) = *rag_lock {
+                match (
+                    rag.db.get_language_counts().await,
+                    rag.db.get_excluded_languages().await,
+                ) {
+                    (Ok(counts), Ok(excluded)) => {
+                        let _ = tx.send((counts, excluded));
+                    }
+                    (Err(e), _) | (_, Err(e)) => {
+                        eprintln!("Failed to load language settings: {}", e)
+                    }
+                }
+            }
+        });
+
+        self.language_settings_receiver = Some(rx);
+    }
+
+    /// Check if the language settings have loaded
+    pub(crate) fn check_language_settings_loaded(&mut self) {
+        if let Some(ref rx) = self.language_settings_receiver {
+            match rx.try_recv() {
+                Ok((counts, excluded)) => {
+                    self.language_counts = counts;
+                    self.excluded_languages = excluded;
+                    self.language_settings_receiver = None;
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.language_settings_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Include or exclude a language from embedding and save the choice
+    pub fn set_language_excluded(&mut self, code: &str, excluded: bool) {
+        self.excluded_languages.retain(|existing| existing != code);
+        if excluded {
+            self.excluded_languages.push(code.to_string());
+            self.excluded_languages.sort();
+        }
+
+        let languages = self.excluded_languages.clone();
+        let rag = self.rag.clone();
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            if let Some(ref rag) = *rag_lock {
+                if let Err(e) = rag.db.set_excluded_languages(&languages).await {
+                    eprintln!("Failed to save excluded languages: {}", e);
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,242 @@
+//! Library menu — switch between, create and delete named libraries.
+
+use crate::gui::accessibility::Labeled;
+use crate::gui::app::LocalMindApp;
+use crate::gui::i18n::{tr, tr_args};
+use crate::gui::state::{InitStatus, Toast, View};
+use crate::library::DEFAULT_LIBRARY;
+use crate::services::{start_consistency_check, start_integrity_audit, switch_library};
+use egui::Ui;
+use egui_remixicon::icons;
+
+/// Render the library menu button next to the app title.
+pub fn render_library_menu(ui: &mut Ui, app: &mut LocalMindApp) {
+    let switching = app.is_switching_library();
+    let title = format!("{} {}", icons::BOOK_2_LINE, app.libraries.active);
+    let ready = app.init_status == InitStatus::Ready;
+
+    ui.add_enabled_ui(ready && !switching, |ui| {
+        ui.menu_button(title, |ui| {
+            ui.set_min_width(240.0);
+            ui.weak(tr("library.intro"));
+            ui.add_space(4.0);
+
+            for name in app.libraries.libraries.clone() {
+                let active = name == app.libraries.active;
+                ui.horizontal(|ui| {
+                    if ui.selectable_label(active, &name).clicked() && !active {
+                        app.switch_library(name.clone());
+                        ui.close_menu();
+                    }
+                    if active || name == DEFAULT_LIBRARY {
+                        return;
+                    }
+                    if app.library_delete_pending.as_deref() == Some(name.as_str()) {
+                        if ui.button(tr("library.confirm_delete")).clicked() {
+                            app.delete_library(&name);
+                        }
+                        if ui.button(tr("library.cancel")).clicked() {
+                            app.library_delete_pending = None;
+                        }
+                    } else if ui
+                        .small_button(icons::DELETE_BIN_LINE)
+                        .labeled(&tr_args("library.delete", &[("name", &name)]))
+                        .clicked()
+                    {
+                        app.library_delete_pending = Some(name.clone());
+                    }
+                });
+            }
+            if let Some(name) = &app.library_delete_pending {
+                ui.colored_label(
+                    ui.visuals().warn_fg_color,
+                    tr_args("library.delete_warning", &[("name", name)]),
+                );
+            }
+
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                let label = ui.label(tr("library.new"));
+                let response = ui
+                    .add(
+                        egui::TextEdit::singleline(&mut app.new_library_name)
+                            .hint_text(tr("library.new_hint"))
+                            .desired_width(140.0),
+                    )
+                    .labelled_by(label.id);
+                let submitted =
+                    response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                if ui.button(tr("library.create")).clicked() || submitted {
+                    app.create_library();
+                    if app.library_error.is_none() {
+                        ui.close_menu();
+                    }
+                }
+            });
+
+            if let Some(error) = &app.library_error {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+            }
+        });
+    })
+    .response
+    .on_hover_text(tr("library.menu"));
+
+    if switching {
+        ui.spinner();
+    }
+}
+
+impl LocalMindApp {
+    /// Check if a library is being opened
+    pub fn is_switching_library(&self) -> bool {
+        self.library_switch_receiver.is_some()
+    }
+
+    /// Open another library in place of the current one. Search is disabled
+    /// until it is ready; the startup checks then run on it.
+    pub fn switch_library(&mut self, name: String) {
+        if self.is_switching_library()
+            || name == self.libraries.active
+            || self.init_status != InitStatus::Ready
+        {
+            return;
+        }
+
+        // Watched folders belong to the library being closed
+        if let Some(service) = &self.folder_watch_service {
+            if let Ok(mut service) = service.lock() {
+                service.stop_all();
+            }
+        }
+
+        let rag = self.rag.clone();
+        let report_tx = self.integrity_report_tx.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.runtime.spawn(async move {
+            match switch_library(rag.clone(), name.clone()).await {
+                Ok(()) => {
+                    let semantic = match *rag.read().await {
+                        Some(ref rag) => rag.is_semantic_search_available(),
+                        None => false,
+                    };
+                    let _ = tx.send(Ok((name, semantic)));
+                    start_consistency_check(rag.clone()).await;
+                    start_integrity_audit(rag, report_tx).await;
+                }
+                Err(e) => {
+                    eprintln!("Failed to open library: {}", e);
+                    let _ = tx.send(Err(e.to_string()));
+                }
+            }
+        });
+
+        self.settings_open = false;
+        self.library_error = None;
+        self.init_status = InitStatus::Starting;
+        self.library_switch_receiver = Some(rx);
+    }
+
+    /// Check if switching library has finished, and reset the views to the
+    /// newly opened library
+    pub(crate) fn check_library_switch(&mut self) {
+        let Some(ref rx) = self.library_switch_receiver else {
+            return;
+        };
+        match rx.try_recv() {
+            Ok(result) => {
+                self.library_switch_receiver = None;
+                self.init_status = InitStatus::Ready;
+                self.libraries = crate::library::LibraryRegistry::load();
+                let id = self.next_toast_id();
+                match result {
+                    Ok((name, semantic)) => {
+                        self.semantic_search_available = semantic;
+                        self.current_view = View::Home;
+                        self.previous_view = View::Home;
+                        self.selected_document = None;
+                        self.search_query.clear();
+                        self.search_results.clear();
+                        self.all_results.clear();
+                        self.selected_index = None;
+                        self.checked_doc_ids.clear();
+                        self.selected_profile = None;
+                        self.search_collection = None;
+                        self.integrity_report = None;
+                        self.show_integrity_report = false;
+
+                        self.load_recent_documents();
+                        self.load_pinned_documents();
+                        self.load_resurfaced_documents();
+                        self.load_reminders();
+                        self.load_collections();
+                        self.load_notification_settings();
+                        self.load_ui_preferences();
+                        self.load_cached_settings();
+                        self.load_watched_folders();
+                        self.resume_watchers_on_startup();
+
+                        self.add_toast(Toast::success(
+                            id,
+                            tr_args("library.opened", &[("name", &name)]),
+                        ));
+                    }
+                    Err(e) => {
+                        // The previous library is still open, but its
+                        // watchers were stopped
+                        self.resume_watchers_on_startup();
+                        self.add_toast(Toast::error(
+                            id,
+                            tr_args("library.open_failed", &[("error", &e)]),
+                        ));
+                    }
+                }
+            }
+            Err(std::sync::mpsc::TryRecvError::Empty) => {}
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                self.library_switch_receiver = None;
+                self.init_status = InitStatus::Ready;
+            }
+        }
+    }
+
+    /// Create a library with the name typed in the library menu and open it
+    pub fn create_library(&mut self) {
+        let mut registry = self.libraries.clone();
+        match registry.add(&self.new_library_name) {
+            Ok(name) => {
+                if let Err(e) = registry.save() {
+                    self.library_error = Some(tr_args("library.save_failed", &[("error", &e)]));
+                    return;
+                }
+                self.libraries = registry;
+                self.new_library_name.clear();
+                self.switch_library(name);
+            }
+            Err(e) => self.library_error = Some(e),
+        }
+    }
+
+    /// Delete a library that is not open, including its database
+    pub fn delete_library(&mut self, name: &str) {
+        let mut registry = self.libraries.clone();
+        let result = registry
+            .remove(name)
+            .and_then(|()| crate::library::delete_database(name).map_err(|e| e.to_string()))
+            .and_then(|()| registry.save().map_err(|e| e.to_string()));
+        match result {
+            Ok(()) => {
+                self.libraries = registry;
+                self.library_error = None;
+                self.library_delete_pending = None;
+                let id = self.next_toast_id();
+                self.add_toast(Toast::success(
+                    id,
+                    tr_args("library.deleted", &[("name", &name)]),
+                ));
+            }
+            Err(e) => self.library_error = Some(e),
+        }
+    }
+}
@@ -0,0 +1,196 @@
+//! Dead Link Checker widget — schedule interval, manual run and progress.
+
+use crate::gui::app::LocalMindApp;
+use crate::gui::i18n::{tr, tr_args};
+use crate::gui::state::Toast;
+use egui::Ui;
+
+/// Render the dead-link checker settings.
+///
+/// Shows the scheduling interval (0 disables scheduled runs), a Check Now
+/// button, and progress of the current or most recent run.
+pub fn render_link_checker(ui: &mut Ui, app: &mut LocalMindApp) {
+    ui.weak(tr("links.intro"));
+    ui.add_space(6.0);
+
+    ui.horizontal(|ui| {
+        ui.label(tr("links.check_every"));
+        let response = ui.add(
+            egui::DragValue::new(&mut app.dead_link_interval_hours)
+                .range(0..=24 * 30)
+                .suffix(" h"),
+        );
+        if response.changed() {
+            app.save_dead_link_interval();
+        }
+        if app.dead_link_interval_hours == 0 {
+            ui.weak(tr("links.disabled"));
+        }
+    });
+
+    ui.add_space(6.0);
+
+    let running = app.is_link_check_running();
+    ui.horizontal(|ui| {
+        ui.add_enabled_ui(!running, |ui| {
+            if ui.button(tr("links.check_now")).clicked() {
+                app.run_link_check_now();
+            }
+        });
+        if running {
+            ui.spinner();
+        }
+    });
+
+    if let Some(progress) = app.link_check_progress.clone() {
+        ui.add_space(6.0);
+        let fraction = if progress.total > 0 {
+            progress.checked as f32 / progress.total as f32
+        } else {
+            1.0
+        };
+        ui.add(egui::ProgressBar::new(fraction).text(tr_args(
+            "links.progress",
+            &[("checked", &progress.checked), ("total", &progress.total)],
+        )));
+        ui.weak(tr_args(
+            "links.outcome",
+            &[
+                ("dead", &progress.marked_dead),
+                ("auth", &progress.marked_needs_auth),
+                ("recovered", &progress.recovered),
+            ],
+        ));
+    }
+}
+
+impl LocalMindApp {
+    /// Check for link-check progress events and update the progress toast
+    pub(crate) fn check_link_check_progress(&mut self) {
+        // Collect all pending messages first to avoid borrow checker issues
+        let mut pending_progress = Vec::new();
+        if let Some(ref rx) = self.link_check_progress_receiver {
+            while let Ok(progress) = rx.try_recv() {
+                pending_progress.push(progress);
+            }
+        }
+
+        for progress in pending_progress {
+            if progress.completed {
+                self.toasts.finish_progress(&mut self.link_check_toast_id);
+                let id = self.next_toast_id();
+                self.add_toast(Toast::success(
+                    id,
+                    tr_args(
+                        "links.done",
+                        &[
+                            ("dead", &progress.marked_dead),
+                            ("auth", &progress.marked_needs_auth),
+                            ("recovered", &progress.recovered),
+                        ],
+                    ),
+                ));
+            } else {
+                let fraction = if progress.total > 0 {
+                    progress.checked as f32 / progress.total as f32
+                } else {
+                    0.0
+                };
+                let message = tr_args(
+                    "links.checking",
+                    &[("checked", &progress.checked), ("total", &progress.total)],
+                );
+                self.toasts
+                    .show_progress(&mut self.link_check_toast_id, message, fraction);
+            }
+            self.link_check_progress = Some(progress);
+        }
+    }
+
+    /// Check if a link check is currently running
+    pub fn is_link_check_running(&self) -> bool {
+        self.link_check_progress
+            .as_ref()
+            .is_some_and(|p| !p.completed)
+    }
+
+    /// Start a link check immediately, regardless of the schedule
+    pub fn run_link_check_now(&mut self) {
+        if self.is_link_check_running() {
+            return;
+        }
+
+        // Show progress right away; the first event replaces it
+        self.link_check_progress = Some(crate::link_checker::LinkCheckProgress::default());
+
+        let rag = self.rag.clone();
+        let tx = self.link_check_progress_tx.clone();
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            let result = match *rag_lock {
+                Some(ref rag) => crate::link_checker::run_link_check(&rag.db, Some(&tx)).await,
+                None => Err(tr("common.not_ready").into()),
+            };
+            if let Err(e) = result {
+                eprintln!("Link check failed: {}", e);
+                let _ = tx.send(crate::link_checker::LinkCheckProgress {
+                    completed: true,
+                    ..Default::default()
+                });
+            }
+        });
+    }
+
+    /// Load the stored link-check interval from the database
+    pub fn load_dead_link_settings(&mut self) {
+        if self.dead_link_interval_receiver.is_some() {
+            return; // Already loading
+        }
+
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            if let Some(ref rag) = *rag_lock {
+                if let Ok(hours) = rag.db.get_dead_link_check_interval_hours().await {
+                    let _ = tx.send(hours);
+                }
+            }
+        });
+
+        self.dead_link_interval_receiver = Some(rx);
+    }
+
+    /// Check if the stored link-check interval has loaded
+    pub(crate) fn check_dead_link_settings_loaded(&mut self) {
+        if let Some(ref rx) = self.dead_link_interval_receiver {
+            match rx.try_recv() {
+                Ok(hours) => {
+                    self.dead_link_interval_hours = hours;
+                    self.dead_link_interval_receiver = None;
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {
+                    // Still loading
+                }
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.dead_link_interval_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Persist the link-check interval edited in settings
+    pub fn save_dead_link_interval(&mut self) {
+        let hours = self.dead_link_interval_hours;
+        let rag = self.rag.clone();
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            if let Some(ref rag) = *rag_lock {
+                if let Err(e) = rag.db.set_dead_link_check_interval_hours(hours).await {
+                    eprintln!("Failed to save link-check interval: {}", e);
+                }
+            }
+        });
+    }
+}
@@ -2,7 +2,40 @@
 //!
 //! Widgets are self-contained UI elements used across multiple views.
 
+pub mod activity;
+pub mod autocomplete;
+pub mod bookmark_import;
+pub mod collections;
+pub mod document_actions;
+pub mod document_versions;
+pub mod domain_preferences;
+pub mod embedding_provider;
+pub mod favicon;
+pub mod favorite;
 pub mod folder_tree;
+pub mod http_server;
+pub mod image_captions;
+pub mod ingest_errors;
+pub mod ingest_policy;
+pub mod ingestion_preview;
+pub mod integrity;
+pub mod language;
+pub mod library;
+pub mod link_checker;
+pub mod notifications;
+pub mod page_quality;
+pub mod quick_actions;
+pub mod reading_list;
+pub mod reminders;
+pub mod remote_access;
+pub mod retention;
 pub mod settings;
+pub mod shortcuts;
+pub mod sidebar;
+pub mod snapshots;
+pub mod storage;
+pub mod sync;
+pub mod thumbnail;
 pub mod toast;
+pub mod url_cleaning;
 pub mod watched_folders;
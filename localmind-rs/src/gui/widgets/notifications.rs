@@ -0,0 +1,93 @@
+//! Notification settings widget — per-event opt-out for native notifications.
+
+use crate::gui::app::LocalMindApp;
+use crate::gui::i18n::tr;
+use crate::notifications::NotificationEvent;
+use egui::Ui;
+use std::collections::HashSet;
+
+/// Name of an event type, shown next to its checkbox and as the title of
+/// its notifications.
+pub fn event_label(event: NotificationEvent) -> &'static str {
+    tr(match event {
+        NotificationEvent::IngestionComplete => "notifications.event_ingestion_complete",
+        NotificationEvent::EmbeddingServerDown => "notifications.event_server_down",
+        NotificationEvent::DiskSpaceLow => "notifications.event_disk_low",
+    })
+}
+
+/// Render one checkbox per notification event type.
+pub fn render_notification_settings(ui: &mut Ui, app: &mut LocalMindApp) {
+    ui.weak(tr("notifications.intro"));
+    ui.add_space(6.0);
+
+    for event in NotificationEvent::ALL {
+        let mut enabled = !app.muted_notifications.contains(&event);
+        if ui.checkbox(&mut enabled, event_label(event)).changed() {
+            app.set_notification_enabled(event, enabled);
+        }
+    }
+}
+
+impl LocalMindApp {
+    /// Load which event types have native notifications turned off
+    pub fn load_notification_settings(&mut self) {
+        if self.notification_settings_receiver.is_some() {
+            return;
+        }
+
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let runtime_handle = self.runtime.clone();
+
+        runtime_handle.spawn(async move {
+            let rag_lock = rag.read().await;
+            let mut muted = HashSet::new();
+            if let Some(ref rag) = *rag_lock {
+                for event in NotificationEvent::ALL {
+                    if !rag.db.is_notification_enabled(event).await.unwrap_or(true) {
+                        muted.insert(event);
+                    }
+                }
+            }
+            let _ = tx.send(muted);
+        });
+
+        self.notification_settings_receiver = Some(rx);
+    }
+
+    /// Check if the notification opt-outs have loaded
+    pub(crate) fn check_notification_settings_loaded(&mut self) {
+        if let Some(ref rx) = self.notification_settings_receiver {
+            match rx.try_recv() {
+                Ok(muted) => {
+                    self.muted_notifications = muted;
+                    self.notification_settings_receiver = None;
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.notification_settings_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Turn native notifications for an event type on or off
+    pub fn set_notification_enabled(&mut self, event: NotificationEvent, enabled: bool) {
+        if enabled {
+            self.muted_notifications.remove(&event);
+        } else {
+            self.muted_notifications.insert(event);
+        }
+
+        let rag = self.rag.clone();
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            if let Some(ref rag) = *rag_lock {
+                if let Err(e) = rag.db.set_notification_enabled(event, enabled).await {
+                    eprintln!("Failed to save notification setting: {}", e);
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,315 @@
+//! Low-quality pages widget — pages that looked like login walls, cookie
+//! banners, "not found" pages or boilerplate, indexed by title and URL only —
+//! and the maintenance list of documents whose content is only a fetch
+//! placeholder.
+
+use crate::gui::app::LocalMindApp;
+use crate::gui::i18n::{tr, tr_args};
+use crate::gui::state::Toast;
+use crate::page_quality::QualityIssue;
+use egui::Ui;
+
+/// Short description of why a page was flagged.
+pub fn issue_label(issue: QualityIssue) -> &'static str {
+    tr(match issue {
+        QualityIssue::NotFound => "quality.issue_not_found",
+        QualityIssue::LoginWall => "quality.issue_login_wall",
+        QualityIssue::CookieWall => "quality.issue_cookie_wall",
+        QualityIssue::Boilerplate => "quality.issue_boilerplate",
+    })
+}
+
+/// Render the low-quality page review list.
+///
+/// Each page can be fetched again (and checked again), kept in full
+/// whatever it holds, or moved to the trash.
+pub fn render_low_quality_pages(ui: &mut Ui, app: &mut LocalMindApp) {
+    ui.weak(tr("quality.intro"));
+    ui.add_space(6.0);
+
+    if app.low_quality_pages.is_empty() {
+        ui.weak(tr("quality.none"));
+        return;
+    }
+
+    let refetching = app.is_refetching_page();
+    let pages = app.low_quality_pages.clone();
+    let mut refetch: Option<(i64, bool)> = None;
+    let mut trash_id: Option<i64> = None;
+
+    if refetching {
+        ui.horizontal(|ui| {
+            ui.spinner();
+            ui.weak(tr("quality.refetching"));
+        });
+        ui.add_space(4.0);
+    }
+
+    egui::ScrollArea::vertical()
+        .id_salt("low_quality_scroll")
+        .auto_shrink([false, true])
+        .max_height(200.0)
+        .show(ui, |ui| {
+            for page in &pages {
+                ui.push_id(page.id, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.vertical(|ui| {
+                            ui.label(&page.title);
+                            ui.weak(&page.url);
+                            ui.colored_label(
+                                egui::Color32::from_rgb(200, 140, 40),
+                                issue_label(page.issue),
+                            );
+                        });
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            if ui.button(tr("quality.trash")).clicked() {
+                                trash_id = Some(page.id);
+                            }
+                            ui.add_enabled_ui(!refetching, |ui| {
+                                if ui
+                                    .button(tr("quality.keep"))
+                                    .on_hover_text(tr("quality.keep_hint"))
+                                    .clicked()
+                                {
+                                    refetch = Some((page.id, true));
+                                }
+                                if ui.button(tr("quality.refetch")).clicked() {
+                                    refetch = Some((page.id, false));
+                                }
+                            });
+                        });
+                    });
+                });
+                ui.add_space(4.0);
+            }
+        });
+
+    // Apply actions outside the loop to avoid borrow issues
+    if let Some(id) = trash_id {
+        app.trash_low_quality_page(id);
+    }
+    if let Some((id, keep)) = refetch {
+        app.refetch_page(id, keep);
+    }
+}
+
+/// Render the documents needing a refetch, each with Refetch and trash
+/// buttons. They are left out of search until a refetch finds content.
+pub fn render_refetch_documents(ui: &mut Ui, app: &mut LocalMindApp) {
+    ui.weak(tr("refetch.intro"));
+    ui.add_space(6.0);
+
+    if app.refetch_documents.is_empty() {
+        ui.weak(tr("refetch.none"));
+        return;
+    }
+
+    let refetching = app.is_refetching_page();
+    let documents: Vec<(i64, String, Option<String>)> = app
+        .refetch_documents
+        .iter()
+        .map(|doc| (doc.id, doc.title.clone(), doc.url.clone()))
+        .collect();
+    let mut refetch_id: Option<i64> = None;
+    let mut trash_id: Option<i64> = None;
+
+    if refetching {
+        ui.horizontal(|ui| {
+            ui.spinner();
+            ui.weak(tr("quality.refetching"));
+        });
+        ui.add_space(4.0);
+    }
+
+    egui::ScrollArea::vertical()
+        .id_salt("refetch_documents_scroll")
+        .auto_shrink([false, true])
+        .max_height(200.0)
+        .show(ui, |ui| {
+            for (id, title, url) in &documents {
+                ui.push_id(id, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.vertical(|ui| {
+                            ui.label(title);
+                            if let Some(url) = url {
+                                ui.weak(url);
+                            }
+                        });
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            if ui.button(tr("quality.trash")).clicked() {
+                                trash_id = Some(*id);
+                            }
+                            ui.add_enabled_ui(!refetching && url.is_some(), |ui| {
+                                if ui.button(tr("quality.refetch")).clicked() {
+                                    refetch_id = Some(*id);
+                                }
+                            });
+                        });
+                    });
+                });
+                ui.add_space(4.0);
+            }
+        });
+
+    // Apply actions outside the loop to avoid borrow issues
+    if let Some(id) = trash_id {
+        app.trash_low_quality_page(id);
+    }
+    if let Some(id) = refetch_id {
+        app.refetch_page(id, false);
+    }
+}
+
+impl LocalMindApp {
+    /// Load the pages flagged as junk for review
+    pub fn load_low_quality_pages(&mut self) {
+        if self.low_quality_receiver.is_some() {
+            return;
+        }
+
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            if let Some(ref rag) = *rag_lock {
+                match rag.db.get_low_quality_pages().await {
+                    Ok(pages) => {
+                        let _ = tx.send(pages);
+                    }
+                    Err(e) => eprintln!("Failed to load low-quality pages: {}", e),
+                }
+            }
+        });
+
+        self.low_quality_receiver = Some(rx);
+    }
+
+    /// Check if the low-quality page list has loaded
+    pub(crate) fn check_low_quality_pages_loaded(&mut self) {
+        if let Some(ref rx) = self.low_quality_receiver {
+            match rx.try_recv() {
+                Ok(pages) => {
+                    self.low_quality_pages = pages;
+                    self.low_quality_receiver = None;
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.low_quality_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Load the documents whose content is only a fetch placeholder
+    pub fn load_refetch_documents(&mut self) {
+        if self.refetch_documents_receiver.is_some() {
+            return;
+        }
+
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            if let Some(ref rag) = *rag_lock {
+                match rag.db.get_documents_needing_refetch().await {
+                    Ok(documents) => {
+                        let _ = tx.send(documents);
+                    }
+                    Err(e) => eprintln!("Failed to load documents needing a refetch: {}", e),
+                }
+            }
+        });
+
+        self.refetch_documents_receiver = Some(rx);
+    }
+
+    /// Check if the documents needing a refetch have loaded
+    pub(crate) fn check_refetch_documents_loaded(&mut self) {
+        if let Some(ref rx) = self.refetch_documents_receiver {
+            match rx.try_recv() {
+                Ok(documents) => {
+                    self.refetch_documents = documents;
+                    self.refetch_documents_receiver = None;
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.refetch_documents_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Fetch a flagged page or a document needing a refetch again in the
+    /// background; with `keep` it is indexed in full whatever it holds
+    pub fn refetch_page(&mut self, doc_id: i64, keep: bool) {
+        if self.page_refetch_receiver.is_some() {
+            return;
+        }
+
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        self.runtime.spawn(async move {
+            let result = crate::services::refetch_page(&rag, doc_id, keep)
+                .await
+                .map_err(|e| e.to_string());
+            let _ = tx.send((doc_id, result));
+        });
+
+        self.page_refetch_receiver = Some(rx);
+    }
+
+    /// Check if a page is being fetched again
+    pub fn is_refetching_page(&self) -> bool {
+        self.page_refetch_receiver.is_some()
+    }
+
+    /// Check for a finished refetch and report it
+    pub(crate) fn check_page_refetch(&mut self) {
+        let Some(ref rx) = self.page_refetch_receiver else {
+            return;
+        };
+        let (doc_id, result) = match rx.try_recv() {
+            Ok(outcome) => outcome,
+            Err(std::sync::mpsc::TryRecvError::Empty) => return,
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                self.page_refetch_receiver = None;
+                return;
+            }
+        };
+        self.page_refetch_receiver = None;
+
+        let id = self.next_toast_id();
+        match result {
+            Ok(None) => {
+                self.low_quality_pages.retain(|page| page.id != doc_id);
+                self.refetch_documents.retain(|doc| doc.id != doc_id);
+                self.add_toast(Toast::success(id, tr("quality.indexed")));
+            }
+            Ok(Some(issue)) => {
+                let issue = crate::gui::widgets::page_quality::issue_label(issue);
+                self.add_toast(Toast::error(
+                    id,
+                    tr_args("quality.still_flagged", &[("issue", &issue)]),
+                ));
+            }
+            Err(e) => {
+                self.add_toast(Toast::error(
+                    id,
+                    tr_args("quality.refetch_failed", &[("error", &e)]),
+                ));
+            }
+        }
+        self.load_low_quality_pages();
+        self.load_refetch_documents();
+    }
+
+    /// Move a flagged page to the trash and drop it from the review lists
+    pub fn trash_low_quality_page(&mut self, doc_id: i64) {
+        self.low_quality_pages.retain(|page| page.id != doc_id);
+        self.refetch_documents.retain(|doc| doc.id != doc_id);
+        self.delete_documents(vec![doc_id]);
+    }
+}
@@ -0,0 +1,334 @@
+//! Shortcuts to add to the library — save a pasted URL, import a file, or
+//! write a note — on the home view and in the add menu, and their dialogs.
+
+use crate::gui::accessibility::Labeled;
+use crate::gui::app::LocalMindApp;
+use crate::gui::i18n::{tr, tr_args};
+use crate::gui::state::{Toast, ToastAction};
+use crate::services::{save_note, save_url, SaveUrlProgress};
+use egui::Ui;
+use egui_remixicon::icons;
+
+/// Render the home view's quick-add box for a pasted URL, followed by the
+/// other shortcut buttons.
+pub fn render_quick_actions(ui: &mut Ui, app: &mut LocalMindApp) {
+    ui.horizontal(|ui| {
+        let pending = app.is_paste_url_pending();
+        let response = ui
+            .add_enabled(
+                !pending,
+                egui::TextEdit::singleline(&mut app.paste_url_input)
+                    .hint_text(format!("{} {}", icons::LINK, tr("quick.paste_url_hint")))
+                    .desired_width(320.0),
+            )
+            .labeled_field(tr("quick.paste_url"));
+        let enter_pressed = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+        let save_clicked = ui
+            .add_enabled(
+                !pending && !app.paste_url_input.trim().is_empty(),
+                egui::Button::new(tr("quick.save")),
+            )
+            .clicked();
+        if save_clicked || enter_pressed {
+            app.save_pasted_url();
+        }
+        if pending {
+            ui.spinner();
+        }
+
+        ui.add_space(10.0);
+        if ui
+            .button(format!(
+                "{} {}",
+                icons::FILE_UPLOAD_LINE,
+                tr("quick.import_file")
+            ))
+            .clicked()
+        {
+            app.show_import_dialog = true;
+        }
+        if ui
+            .button(format!(
+                "{} {}",
+                icons::STICKY_NOTE_ADD_LINE,
+                tr("quick.new_note")
+            ))
+            .clicked()
+        {
+            app.show_note_dialog = true;
+        }
+    });
+
+    if !app.show_paste_url_dialog {
+        if let Some(ref err) = app.paste_url_error {
+            ui.colored_label(egui::Color32::from_rgb(200, 60, 60), err);
+        }
+    }
+}
+
+/// Render the add menu button in the top bar.
+pub fn render_add_menu(ui: &mut Ui, app: &mut LocalMindApp) {
+    ui.menu_button(icons::ADD_LINE, |ui| {
+        if ui.button(tr("quick.save_page")).clicked() {
+            app.show_paste_url_dialog = true;
+            ui.close_menu();
+        }
+        if ui.button(tr("quick.import_file_menu")).clicked() {
+            app.show_import_dialog = true;
+            ui.close_menu();
+        }
+        if ui.button(tr("quick.new_note_menu")).clicked() {
+            app.show_note_dialog = true;
+            ui.close_menu();
+        }
+    })
+    .response
+    .labeled(tr("quick.add_menu"));
+}
+
+/// Render the "Paste URL" dialog while `app.show_paste_url_dialog` is set.
+pub fn render_paste_url_dialog(ctx: &egui::Context, app: &mut LocalMindApp) {
+    if !app.show_paste_url_dialog {
+        return;
+    }
+
+    let mut open = true;
+    let mut save = false;
+
+    egui::Window::new(tr("quick.paste_url_title"))
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .default_width(480.0)
+        .show(ctx, |ui| {
+            ui.label(tr("quick.paste_url_intro"));
+            ui.add_space(10.0);
+
+            ui.horizontal(|ui| {
+                let label = ui.label(tr("quick.url"));
+                let response = ui
+                    .add(
+                        egui::TextEdit::singleline(&mut app.paste_url_input)
+                            .hint_text("https://")
+                            .desired_width(300.0),
+                    )
+                    .labelled_by(label.id);
+                let enter_pressed =
+                    response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                let save_clicked = ui
+                    .add_enabled(
+                        !app.is_paste_url_pending(),
+                        egui::Button::new(tr("quick.save")),
+                    )
+                    .clicked();
+                save = save_clicked || enter_pressed;
+            });
+
+            if let Some(ref err) = app.paste_url_error {
+                ui.add_space(2.0);
+                ui.colored_label(egui::Color32::from_rgb(200, 60, 60), err);
+            }
+        });
+
+    if save {
+        app.save_pasted_url();
+    }
+    if !open {
+        app.show_paste_url_dialog = false;
+        app.paste_url_error = None;
+    }
+}
+
+/// Render the "New note" dialog while `app.show_note_dialog` is set.
+pub fn render_note_dialog(ctx: &egui::Context, app: &mut LocalMindApp) {
+    if !app.show_note_dialog {
+        return;
+    }
+
+    let mut open = true;
+    let mut save = false;
+
+    egui::Window::new(tr("quick.new_note"))
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(true)
+        .default_width(480.0)
+        .show(ctx, |ui| {
+            let label = ui.label(tr("quick.note_title"));
+            ui.add(
+                egui::TextEdit::singleline(&mut app.note_title)
+                    .hint_text(tr("quick.note_title_hint"))
+                    .desired_width(f32::INFINITY),
+            )
+            .labelled_by(label.id);
+            ui.add_space(6.0);
+
+            let label = ui.label(tr("quick.note_text"));
+            ui.add(
+                egui::TextEdit::multiline(&mut app.note_text)
+                    .desired_rows(10)
+                    .desired_width(f32::INFINITY),
+            )
+            .labelled_by(label.id);
+            ui.add_space(6.0);
+
+            ui.horizontal(|ui| {
+                save = ui
+                    .add_enabled(
+                        !app.is_note_saving() && !app.note_text.trim().is_empty(),
+                        egui::Button::new(tr("quick.save")),
+                    )
+                    .clicked();
+                if app.is_note_saving() {
+                    ui.spinner();
+                }
+            });
+
+            if let Some(ref err) = app.note_error {
+                ui.add_space(2.0);
+                ui.colored_label(egui::Color32::from_rgb(200, 60, 60), err);
+            }
+        });
+
+    if save {
+        app.save_note();
+    }
+    if !open {
+        app.show_note_dialog = false;
+        app.note_error = None;
+    }
+}
+
+impl LocalMindApp {
+    /// Fetch and save the pasted URL now, with a progress toast
+    pub fn save_pasted_url(&mut self) {
+        if self.paste_url_receiver.is_some() {
+            return;
+        }
+        let url = self.paste_url_input.trim().to_string();
+        if url.is_empty() {
+            return;
+        }
+
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.runtime.spawn(save_url(rag, url, tx));
+
+        self.paste_url_error = None;
+        self.paste_url_receiver = Some(rx);
+    }
+
+    /// Check if a pasted URL is being saved
+    pub fn is_paste_url_pending(&self) -> bool {
+        self.paste_url_receiver.is_some()
+    }
+
+    /// Follow the progress of a pasted URL, updating its toast
+    pub(crate) fn check_pasted_url(&mut self) {
+        let mut events = Vec::new();
+        if let Some(ref rx) = self.paste_url_receiver {
+            while let Ok(event) = rx.try_recv() {
+                events.push(event);
+            }
+        }
+
+        for event in events {
+            let (message, progress) = match event {
+                SaveUrlProgress::Fetching => (tr("quick.fetching").to_string(), 0.3),
+                SaveUrlProgress::Indexing(title) => {
+                    (tr_args("quick.saving", &[("title", &title)]), 0.7)
+                }
+                SaveUrlProgress::Saved(doc_id, title) => {
+                    self.finish_pasted_url();
+                    let id = self.next_toast_id();
+                    self.add_toast(
+                        Toast::success(id, tr_args("quick.saved", &[("title", &title)]))
+                            .with_button(tr("common.open"), ToastAction::OpenDocument(doc_id)),
+                    );
+                    self.load_recent_documents();
+                    continue;
+                }
+                SaveUrlProgress::AlreadySaved(doc_id) => {
+                    self.finish_pasted_url();
+                    let id = self.next_toast_id();
+                    self.add_toast(
+                        Toast::info(id, tr("quick.already_saved"))
+                            .with_button(tr("common.open"), ToastAction::OpenDocument(doc_id)),
+                    );
+                    continue;
+                }
+                SaveUrlProgress::Failed(e) => {
+                    eprintln!("Failed to save pasted URL: {}", e);
+                    self.paste_url_receiver = None;
+                    self.toasts.finish_progress(&mut self.paste_url_toast_id);
+                    self.paste_url_error = Some(e);
+                    continue;
+                }
+            };
+            self.toasts
+                .show_progress(&mut self.paste_url_toast_id, message, progress);
+        }
+    }
+
+    /// Clear the quick-add box and progress toast once a URL is saved
+    pub(crate) fn finish_pasted_url(&mut self) {
+        self.paste_url_receiver = None;
+        self.toasts.finish_progress(&mut self.paste_url_toast_id);
+        self.paste_url_input.clear();
+        self.paste_url_error = None;
+        self.show_paste_url_dialog = false;
+    }
+
+    /// Save the note in the "New note" dialog as a document
+    pub fn save_note(&mut self) {
+        if self.note_receiver.is_some() {
+            return;
+        }
+
+        let title = self.note_title.clone();
+        let text = self.note_text.clone();
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.runtime.spawn(async move {
+            let saved = save_note(&rag, &title, &text)
+                .await
+                .map_err(|e| e.to_string());
+            let _ = tx.send(saved);
+        });
+
+        self.note_receiver = Some(rx);
+    }
+
+    /// Check if a note is being saved
+    pub fn is_note_saving(&self) -> bool {
+        self.note_receiver.is_some()
+    }
+
+    /// Check if a note has been saved
+    pub(crate) fn check_note_saved(&mut self) {
+        let Some(ref rx) = self.note_receiver else {
+            return;
+        };
+        match rx.try_recv() {
+            Ok(result) => {
+                self.note_receiver = None;
+                match result {
+                    Ok(_) => {
+                        self.show_note_dialog = false;
+                        self.note_title.clear();
+                        self.note_text.clear();
+                        self.note_error = None;
+                        let id = self.next_toast_id();
+                        self.add_toast(Toast::success(id, tr("quick.note_saved")));
+                        self.load_recent_documents();
+                    }
+                    Err(e) => self.note_error = Some(e),
+                }
+            }
+            Err(std::sync::mpsc::TryRecvError::Empty) => {}
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                self.note_receiver = None;
+            }
+        }
+    }
+}
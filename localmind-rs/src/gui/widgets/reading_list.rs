@@ -0,0 +1,32 @@
+//! Reading-list indicators — unread dot and read/unread toggle.
+
+use egui::Ui;
+use egui_remixicon::icons;
+
+use crate::gui::accessibility::Labeled;
+use crate::gui::i18n::tr;
+
+/// Accent used for the unread marker.
+const UNREAD_COLOR: egui::Color32 = egui::Color32::from_rgb(66, 133, 244);
+
+/// Render a small dot marking a document as unread.
+pub fn unread_dot(ui: &mut Ui) {
+    let (rect, response) = ui.allocate_exact_size(egui::vec2(8.0, 8.0), egui::Sense::hover());
+    ui.painter().circle_filled(rect.center(), 4.0, UNREAD_COLOR);
+    response.on_hover_text(tr("reading.unread"));
+}
+
+/// Render a frameless button that flips a document between read and unread.
+///
+/// Returns true when clicked; the caller applies the change with
+/// `LocalMindApp::set_read`.
+pub fn read_toggle_button(ui: &mut Ui, is_unread: bool) -> bool {
+    let (icon, hover) = if is_unread {
+        (icons::MAIL_UNREAD_LINE, tr("reading.mark_read"))
+    } else {
+        (icons::MAIL_OPEN_LINE, tr("reading.mark_unread"))
+    };
+    ui.add(egui::Button::new(icon).frame(false))
+        .labeled(hover)
+        .clicked()
+}
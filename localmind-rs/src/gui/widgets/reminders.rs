@@ -0,0 +1,220 @@
+//! "Remind me" dialog for snoozing a document until a date.
+
+use crate::gui::app::LocalMindApp;
+use crate::gui::i18n::{tr, tr_args};
+use crate::gui::state::Toast;
+use crate::reminders::{parse_date, RemindOn};
+
+/// A reminder being set in the dialog
+#[derive(Debug, Clone, Default)]
+pub struct ReminderDraft {
+    pub doc_id: i64,
+    /// Date typed as `YYYY-MM-DD`
+    pub date: String,
+    /// Also raise a desktop notification when it is due
+    pub notify: bool,
+}
+
+/// Render the "Remind me" dialog while `app.reminder_draft` is set.
+pub fn render_reminder_dialog(ctx: &egui::Context, app: &mut LocalMindApp) {
+    let Some(draft) = app.reminder_draft.as_mut() else {
+        return;
+    };
+
+    let mut open = true;
+    let mut chosen = None;
+
+    egui::Window::new(tr("reminders.title"))
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .default_width(360.0)
+        .show(ctx, |ui| {
+            ui.label(tr("reminders.intro"));
+            ui.add_space(10.0);
+
+            ui.horizontal(|ui| {
+                for (days, label) in [
+                    (1, tr("reminders.tomorrow")),
+                    (7, tr("reminders.next_week")),
+                    (30, tr("reminders.next_month")),
+                ] {
+                    if ui.button(label).clicked() {
+                        chosen = Some(RemindOn::InDays(days));
+                    }
+                }
+            });
+            ui.add_space(6.0);
+
+            ui.horizontal(|ui| {
+                let label = ui.label(tr("reminders.on_date"));
+                let response = ui
+                    .add(
+                        egui::TextEdit::singleline(&mut draft.date)
+                            .hint_text("YYYY-MM-DD")
+                            .desired_width(110.0),
+                    )
+                    .labelled_by(label.id);
+                let date = parse_date(&draft.date);
+                let enter_pressed =
+                    response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                let set_clicked = ui
+                    .add_enabled(date.is_some(), egui::Button::new(tr("reminders.set")))
+                    .clicked();
+                if set_clicked || enter_pressed {
+                    chosen = date.map(RemindOn::Date);
+                }
+            });
+            if !draft.date.trim().is_empty() && parse_date(&draft.date).is_none() {
+                ui.colored_label(
+                    egui::Color32::from_rgb(200, 60, 60),
+                    tr("reminders.invalid_date"),
+                );
+            }
+            ui.add_space(6.0);
+
+            ui.checkbox(&mut draft.notify, tr("reminders.notify"));
+        });
+
+    if let Some(on) = chosen {
+        let draft = app.reminder_draft.take().unwrap_or_default();
+        app.set_reminder(draft.doc_id, on, draft.notify);
+    } else if !open {
+        app.reminder_draft = None;
+    }
+}
+
+impl LocalMindApp {
+    /// Load the reminders that have come due for the home view
+    pub fn load_reminders(&mut self) {
+        if self.reminders_receiver.is_some() {
+            return; // Already loading
+        }
+
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let runtime_handle = self.runtime.clone();
+
+        runtime_handle.spawn(async move {
+            let rag_lock = rag.read().await;
+            let reminders = if let Some(ref rag) = *rag_lock {
+                rag.db.get_fired_reminders().await.unwrap_or_else(|e| {
+                    eprintln!("Failed to load reminders: {}", e);
+                    Vec::new()
+                })
+            } else {
+                Vec::new()
+            };
+            let _ = tx.send(reminders);
+        });
+
+        self.reminders_receiver = Some(rx);
+    }
+
+    /// Check if the due reminders have loaded
+    pub(crate) fn check_reminders_loaded(&mut self) {
+        if let Some(ref rx) = self.reminders_receiver {
+            match rx.try_recv() {
+                Ok(reminders) => {
+                    self.reminders = reminders;
+                    self.reminders_receiver = None;
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.reminders_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Check for reminders fired by the scheduler: list them on the home
+    /// view and announce them, with a desktop notification if asked for
+    pub(crate) fn check_due_reminders(&mut self) {
+        let mut due = Vec::new();
+        if let Some(ref rx) = self.reminder_due_receiver {
+            while let Ok(reminders) = rx.try_recv() {
+                due.extend(reminders);
+            }
+        }
+
+        for reminder in due {
+            let message = tr_args("reminders.due", &[("title", &reminder.title)]);
+            let id = self.next_toast_id();
+            self.add_toast(Toast::info(id, message.clone()));
+            if reminder.notify {
+                let title = format!("LocalMind: {}", tr("reminders.title"));
+                // Some platforms block while the notification is delivered
+                self.runtime.spawn_blocking(move || {
+                    if let Err(e) = crate::notifications::show_native(&title, &message) {
+                        eprintln!("Failed to show notification: {}", e);
+                    }
+                });
+            }
+            self.reminders
+                .retain(|r| r.document_id != reminder.document_id);
+            self.reminders.push(reminder);
+        }
+    }
+
+    /// Snooze a document until `on`, replacing any reminder it already has
+    pub fn set_reminder(&mut self, doc_id: i64, on: crate::reminders::RemindOn, notify: bool) {
+        // A snoozed document leaves the home view until it is due again
+        self.reminders.retain(|r| r.document_id != doc_id);
+
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            if let Some(ref rag) = *rag_lock {
+                let result = rag
+                    .db
+                    .set_reminder(doc_id, &on, notify)
+                    .await
+                    .map_err(|e| e.to_string());
+                let _ = tx.send(result);
+            }
+        });
+        self.reminder_set_receiver = Some(rx);
+    }
+
+    /// Check if a reminder has been saved and report the date
+    pub(crate) fn check_reminder_set(&mut self) {
+        if let Some(ref rx) = self.reminder_set_receiver {
+            match rx.try_recv() {
+                Ok(result) => {
+                    self.reminder_set_receiver = None;
+                    let id = self.next_toast_id();
+                    match result {
+                        Ok(date) => self.add_toast(Toast::success(
+                            id,
+                            tr_args("reminders.set_for", &[("date", &date)]),
+                        )),
+                        Err(e) => self.add_toast(Toast::error(
+                            id,
+                            tr_args("reminders.set_failed", &[("error", &e)]),
+                        )),
+                    }
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.reminder_set_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Remove a reminder from the home view once it has been dealt with
+    pub fn dismiss_reminder(&mut self, doc_id: i64) {
+        self.reminders.retain(|r| r.document_id != doc_id);
+
+        let rag = self.rag.clone();
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            if let Some(ref rag) = *rag_lock {
+                if let Err(e) = rag.db.dismiss_reminder(doc_id).await {
+                    eprintln!("Failed to dismiss reminder: {}", e);
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,199 @@
+//! Remote access widget — LAN search page toggle, port, TLS, link and token.
+
+use crate::gui::app::LocalMindApp;
+use crate::gui::i18n::tr;
+use crate::tls::TlsMode;
+use egui::Ui;
+
+/// Render the remote access settings.
+///
+/// The enabled flag is saved when toggled and the port when its field is
+/// released; the LAN server picks up the change within a few seconds.
+pub fn render_remote_access_settings(ui: &mut Ui, app: &mut LocalMindApp) {
+    ui.weak(tr("remote_access.intro"));
+    ui.add_space(6.0);
+
+    let mut changed = ui
+        .checkbox(
+            &mut app.remote_access_settings.enabled,
+            tr("remote_access.enabled"),
+        )
+        .changed();
+
+    ui.horizontal(|ui| {
+        let label = ui.label(tr("remote_access.port"));
+        let response = ui
+            .add(egui::DragValue::new(&mut app.remote_access_settings.port).range(1024..=65535))
+            .labelled_by(label.id);
+        changed |= response.drag_stopped() || response.lost_focus();
+    });
+
+    changed |= render_tls_settings(ui, app);
+
+    if changed {
+        app.save_remote_access_settings();
+    }
+
+    if !app.remote_access_settings.is_active() {
+        return;
+    }
+
+    ui.add_space(6.0);
+
+    match app.remote_access_settings.page_url() {
+        Some(url) => {
+            ui.label(tr("remote_access.open_on_phone"));
+            ui.horizontal(|ui| {
+                ui.monospace(&url);
+                if ui.button(tr("remote_access.copy_link")).clicked() {
+                    ui.ctx().copy_text(url);
+                }
+            });
+        }
+        None => {
+            ui.colored_label(ui.visuals().warn_fg_color, tr("remote_access.no_network"));
+        }
+    }
+
+    if ui.button(tr("remote_access.new_token")).clicked() {
+        app.regenerate_remote_access_token();
+    }
+    ui.colored_label(ui.visuals().warn_fg_color, tr("remote_access.warning"));
+}
+
+/// HTTPS choice and, for a custom certificate, its files; whether the
+/// settings should be saved.
+fn render_tls_settings(ui: &mut Ui, app: &mut LocalMindApp) -> bool {
+    let tls = &mut app.remote_access_settings.tls;
+    let mut changed = false;
+
+    ui.horizontal(|ui| {
+        let label = ui.label(tr("remote_access.tls"));
+        let selected = match tls {
+            TlsMode::Off => tr("remote_access.tls_off"),
+            TlsMode::SelfSigned => tr("remote_access.tls_self_signed"),
+            TlsMode::Custom { .. } => tr("remote_access.tls_custom"),
+        };
+        egui::ComboBox::from_id_salt("remote_access_tls")
+            .selected_text(selected)
+            .show_ui(ui, |ui| {
+                let choices = [
+                    (TlsMode::Off, tr("remote_access.tls_off")),
+                    (TlsMode::SelfSigned, tr("remote_access.tls_self_signed")),
+                    (
+                        TlsMode::Custom {
+                            cert_path: String::new(),
+                            key_path: String::new(),
+                        },
+                        tr("remote_access.tls_custom"),
+                    ),
+                ];
+                for (mode, text) in choices {
+                    let current = std::mem::discriminant(tls) == std::mem::discriminant(&mode);
+                    if ui.selectable_label(current, text).clicked() && !current {
+                        *tls = mode;
+                        changed = true;
+                    }
+                }
+            })
+            .response
+            .labelled_by(label.id);
+    });
+
+    match tls {
+        TlsMode::Off => {}
+        TlsMode::SelfSigned => {
+            ui.weak(tr("remote_access.tls_self_signed_hint"));
+        }
+        TlsMode::Custom {
+            cert_path,
+            key_path,
+        } => {
+            for (text, path) in [
+                (tr("remote_access.tls_cert"), cert_path),
+                (tr("remote_access.tls_key"), key_path),
+            ] {
+                ui.horizontal(|ui| {
+                    let label = ui.label(text);
+                    let response = ui
+                        .add(
+                            egui::TextEdit::singleline(path)
+                                .hint_text("/path/to/file.pem")
+                                .desired_width(280.0),
+                        )
+                        .labelled_by(label.id);
+                    changed |= response.lost_focus();
+                });
+            }
+        }
+    }
+    changed
+}
+
+impl LocalMindApp {
+    /// Load the LAN remote access settings
+    pub fn load_remote_access_settings(&mut self) {
+        if self.remote_access_receiver.is_some() {
+            return;
+        }
+
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            if let Some(ref rag) = *rag_lock {
+                match rag.db.get_remote_access_settings().await {
+                    Ok(settings) => {
+                        let _ = tx.send(settings);
+                    }
+                    Err(e) => eprintln!("Failed to load remote access settings: {}", e),
+                }
+            }
+        });
+
+        self.remote_access_receiver = Some(rx);
+    }
+
+    /// Check if the remote access settings have loaded
+    pub(crate) fn check_remote_access_settings_loaded(&mut self) {
+        if let Some(ref rx) = self.remote_access_receiver {
+            match rx.try_recv() {
+                Ok(settings) => {
+                    self.remote_access_settings = settings;
+                    self.remote_access_receiver = None;
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.remote_access_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Save the remote access settings edited in Settings, creating a token
+    /// the first time access is enabled. The LAN server restarts with them
+    /// within a few seconds.
+    pub fn save_remote_access_settings(&mut self) {
+        if self.remote_access_settings.enabled && self.remote_access_settings.token.is_empty() {
+            self.remote_access_settings.token = crate::remote_access::generate_token();
+        }
+
+        let settings = self.remote_access_settings.clone();
+        let rag = self.rag.clone();
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            if let Some(ref rag) = *rag_lock {
+                if let Err(e) = rag.db.set_remote_access_settings(&settings).await {
+                    eprintln!("Failed to save remote access settings: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Replace the remote access token, disconnecting devices using the old one
+    pub fn regenerate_remote_access_token(&mut self) {
+        self.remote_access_settings.token = crate::remote_access::generate_token();
+        self.save_remote_access_settings();
+    }
+}
@@ -0,0 +1,298 @@
+//! Retention rules widget — rule editor, preview and manual run.
+
+use crate::gui::accessibility::Labeled;
+use crate::gui::app::LocalMindApp;
+use crate::gui::i18n::{tr, tr_args};
+use crate::gui::widgets::ingest_policy::kind_label;
+use crate::ingest_policy::SourceKind;
+use crate::retention::{RetentionCondition, RetentionRule};
+use egui::Ui;
+use egui_remixicon::icons;
+
+/// Documents listed in a preview before the rest are only counted.
+const PREVIEW_ROWS: usize = 50;
+
+/// Localized description of a retention condition.
+fn condition_label(condition: RetentionCondition) -> &'static str {
+    match condition {
+        RetentionCondition::SavedBefore => tr("retention.saved_before"),
+        RetentionCondition::DeadFor => tr("retention.dead_for"),
+        RetentionCondition::NotOpenedFor => tr("retention.not_opened_for"),
+    }
+}
+
+/// Localized name of a rule's source scope.
+fn source_label(source: Option<SourceKind>) -> &'static str {
+    match source {
+        Some(kind) => kind_label(kind),
+        None => tr("retention.all_sources"),
+    }
+}
+
+/// Render the retention rules.
+///
+/// Each rule is one row: enabled, source, condition and days. Edits are
+/// saved as they are made. Preview lists what the rules would move to the
+/// Trash; Run now applies them immediately, with undo.
+pub fn render_retention_rules(ui: &mut Ui, app: &mut LocalMindApp) {
+    ui.weak(tr("retention.intro"));
+    ui.add_space(6.0);
+
+    let mut changed = false;
+    let mut remove = None;
+    for (i, rule) in app.retention_rules.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            changed |= ui
+                .checkbox(&mut rule.enabled, tr("retention.enabled"))
+                .changed();
+
+            egui::ComboBox::from_id_salt(("retention_source", i))
+                .selected_text(source_label(rule.source))
+                .show_ui(ui, |ui| {
+                    let scopes = std::iter::once(None).chain(SourceKind::ALL.map(Some));
+                    for source in scopes {
+                        changed |= ui
+                            .selectable_value(&mut rule.source, source, source_label(source))
+                            .changed();
+                    }
+                })
+                .response
+                .labeled(tr("retention.source"));
+
+            egui::ComboBox::from_id_salt(("retention_condition", i))
+                .selected_text(condition_label(rule.condition))
+                .show_ui(ui, |ui| {
+                    for condition in RetentionCondition::ALL {
+                        changed |= ui
+                            .selectable_value(
+                                &mut rule.condition,
+                                condition,
+                                condition_label(condition),
+                            )
+                            .changed();
+                    }
+                })
+                .response
+                .labeled(tr("retention.condition"));
+
+            changed |= ui
+                .add(
+                    egui::DragValue::new(&mut rule.days)
+                        .range(1..=36500)
+                        .suffix(format!(" {}", tr("retention.days_unit"))),
+                )
+                .labeled_field(tr("retention.days"))
+                .changed();
+
+            if ui
+                .small_button(icons::DELETE_BIN_LINE)
+                .labeled(tr("retention.remove"))
+                .clicked()
+            {
+                remove = Some(i);
+            }
+        });
+    }
+    if let Some(i) = remove {
+        app.retention_rules.remove(i);
+        changed = true;
+    }
+    if changed {
+        app.save_retention_rules();
+    }
+
+    ui.add_space(6.0);
+
+    ui.horizontal(|ui| {
+        if ui.button(tr("retention.add")).clicked() {
+            app.retention_rules.push(RetentionRule::default());
+            app.save_retention_rules();
+        }
+        let has_rules = app.retention_rules.iter().any(|r| r.enabled);
+        ui.add_enabled_ui(has_rules, |ui| {
+            if ui.button(tr("retention.preview")).clicked() {
+                app.preview_retention();
+            }
+            if ui.button(tr("retention.run_now")).clicked() {
+                app.run_retention_now();
+            }
+        });
+    });
+
+    if let Some(ref matches) = app.retention_preview {
+        ui.add_space(6.0);
+        if matches.is_empty() {
+            ui.weak(tr("retention.nothing"));
+        } else {
+            ui.label(tr_args(
+                "retention.would_remove",
+                &[("count", &matches.len())],
+            ));
+            egui::ScrollArea::vertical()
+                .id_salt("retention_preview")
+                .max_height(200.0)
+                .show(ui, |ui| {
+                    for m in matches.iter().take(PREVIEW_ROWS) {
+                        ui.label(&m.title)
+                            .on_hover_text(m.url.as_deref().unwrap_or(&m.source));
+                    }
+                    if matches.len() > PREVIEW_ROWS {
+                        ui.weak(tr_args(
+                            "retention.more",
+                            &[("count", &(matches.len() - PREVIEW_ROWS))],
+                        ));
+                    }
+                });
+        }
+    }
+}
+
+impl LocalMindApp {
+    /// Report documents trashed by a scheduled retention run, with undo
+    pub(crate) fn check_retention_runs(&mut self) {
+        let mut runs = Vec::new();
+        if let Some(ref rx) = self.retention_run_receiver {
+            while let Ok(trashed) = rx.try_recv() {
+                runs.push(trashed);
+            }
+        }
+
+        for trashed in runs {
+            self.add_undo_toast(
+                tr_args("retention.moved", &[("count", &trashed.len())]),
+                trashed,
+            );
+            self.load_recent_documents();
+            self.load_pinned_documents();
+            self.load_resurfaced_documents();
+            self.load_reminders();
+        }
+    }
+
+    /// Load the retention rules
+    pub fn load_retention_rules(&mut self) {
+        if self.retention_rules_receiver.is_some() {
+            return;
+        }
+
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            if let Some(ref rag) = *rag_lock {
+                match rag.db.get_retention_rules().await {
+                    Ok(rules) => {
+                        let _ = tx.send(rules);
+                    }
+                    Err(e) => eprintln!("Failed to load retention rules: {}", e),
+                }
+            }
+        });
+
+        self.retention_rules_receiver = Some(rx);
+    }
+
+    /// Check if the retention rules have loaded
+    pub(crate) fn check_retention_rules_loaded(&mut self) {
+        if let Some(ref rx) = self.retention_rules_receiver {
+            match rx.try_recv() {
+                Ok(rules) => {
+                    self.retention_rules = rules;
+                    self.retention_rules_receiver = None;
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.retention_rules_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Save the retention rules edited in Settings; an earlier preview no
+    /// longer applies
+    pub fn save_retention_rules(&mut self) {
+        self.retention_preview = None;
+
+        let rules = self.retention_rules.clone();
+        let rag = self.rag.clone();
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            if let Some(ref rag) = *rag_lock {
+                if let Err(e) = rag.db.set_retention_rules(&rules).await {
+                    eprintln!("Failed to save retention rules: {}", e);
+                }
+            }
+        });
+    }
+
+    /// List the documents the retention rules would remove
+    pub fn preview_retention(&mut self) {
+        if self.retention_preview_receiver.is_some() {
+            return;
+        }
+
+        let rules = self.retention_rules.clone();
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            if let Some(ref rag) = *rag_lock {
+                match crate::retention::preview(rag, &rules).await {
+                    Ok(matches) => {
+                        let _ = tx.send(matches);
+                    }
+                    Err(e) => eprintln!("Failed to preview retention rules: {}", e),
+                }
+            }
+        });
+
+        self.retention_preview_receiver = Some(rx);
+    }
+
+    /// Check if a retention preview has loaded
+    pub(crate) fn check_retention_preview_loaded(&mut self) {
+        if let Some(ref rx) = self.retention_preview_receiver {
+            match rx.try_recv() {
+                Ok(matches) => {
+                    self.retention_preview = Some(matches);
+                    self.retention_preview_receiver = None;
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.retention_preview_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Apply the retention rules now instead of waiting for the schedule
+    pub fn run_retention_now(&mut self) {
+        if self.document_action_receiver.is_some() {
+            return;
+        }
+        self.retention_preview = None;
+
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            let result = if let Some(ref rag) = *rag_lock {
+                match crate::retention::run(rag).await {
+                    Ok(trashed) => Ok((
+                        tr_args("retention.moved", &[("count", &trashed.len())]),
+                        trashed,
+                    )),
+                    Err(e) => Err(e.to_string()),
+                }
+            } else {
+                Err(tr("common.initializing").to_string())
+            };
+            let _ = tx.send(result);
+        });
+
+        self.document_action_receiver = Some(rx);
+    }
+}
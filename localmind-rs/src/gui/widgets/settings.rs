@@ -1,7 +1,9 @@
 //! Settings modal widget for managing exclusion rules
 
-use crate::bookmark_exclusion::ExclusionRules;
+use crate::gui::accessibility;
 use crate::gui::app::LocalMindApp;
+use crate::gui::i18n::{self, tr, tr_args, Language};
+use crate::gui::presenter::{check_domain_pattern, DomainPatternCheck};
 use egui::Ui;
 
 /// Render the settings modal content
@@ -11,8 +13,80 @@ use egui::Ui;
 pub fn render_settings_modal(ui: &mut Ui, app: &mut LocalMindApp) -> bool {
     let mut should_close = false;
     ui.vertical(|ui| {
+        // Interface language
+        ui.collapsing(tr("settings.language"), |ui| {
+            ui.horizontal(|ui| {
+                let label = ui.label(tr("settings.language_label"));
+                let current = i18n::language();
+                egui::ComboBox::from_id_salt("ui_language")
+                    .selected_text(current.native_name())
+                    .show_ui(ui, |ui| {
+                        for language in Language::ALL {
+                            if ui
+                                .selectable_label(language == current, language.native_name())
+                                .clicked()
+                            {
+                                app.set_ui_language(language);
+                            }
+                        }
+                    })
+                    .response
+                    .labelled_by(label.id);
+            });
+        });
+
+        ui.add_space(10.0);
+        ui.separator();
+        ui.add_space(10.0);
+
+        // Text size and high contrast
+        ui.collapsing(tr("settings.accessibility"), |ui| {
+            let mut appearance = app.appearance;
+            ui.horizontal(|ui| {
+                let label = ui.label(tr("settings.text_size"));
+                egui::ComboBox::from_id_salt("ui_scale")
+                    .selected_text(accessibility::scale_label(appearance.ui_scale))
+                    .show_ui(ui, |ui| {
+                        for scale in accessibility::UI_SCALES {
+                            ui.selectable_value(
+                                &mut appearance.ui_scale,
+                                scale,
+                                accessibility::scale_label(scale),
+                            );
+                        }
+                    })
+                    .response
+                    .labelled_by(label.id);
+            });
+            ui.checkbox(&mut appearance.high_contrast, tr("settings.high_contrast"))
+                .on_hover_text(tr("settings.high_contrast_hint"));
+            if appearance != app.appearance {
+                app.set_appearance(appearance);
+            }
+        });
+
+        ui.add_space(10.0);
+        ui.separator();
+        ui.add_space(10.0);
+
+        // Restoring the last search at launch
+        ui.collapsing(tr("settings.search"), |ui| {
+            let mut refresh = app.refresh_restored_search;
+            if ui
+                .checkbox(&mut refresh, tr("settings.refresh_restored_search"))
+                .on_hover_text(tr("settings.refresh_restored_search_hint"))
+                .changed()
+            {
+                app.set_refresh_restored_search(refresh);
+            }
+        });
+
+        ui.add_space(10.0);
+        ui.separator();
+        ui.add_space(10.0);
+
         // Watched Folders section (T027)
-        ui.collapsing("Watched Folders", |ui| {
+        ui.collapsing(tr("folders.title"), |ui| {
             crate::gui::widgets::watched_folders::render_watched_folders(ui, app);
         });
 
@@ -20,15 +94,210 @@ pub fn render_settings_modal(ui: &mut Ui, app: &mut LocalMindApp) -> bool {
         ui.separator();
         ui.add_space(10.0);
 
-        ui.heading("Exclusion Rules");
+        // Bookmark file import
+        ui.collapsing(tr("import.title"), |ui| {
+            ui.label(tr("settings.import_intro"));
+            if ui.button(tr("settings.import_button")).clicked() {
+                app.show_import_dialog = true;
+            }
+        });
+
+        ui.add_space(10.0);
+        ui.separator();
+        ui.add_space(10.0);
+
+        // Failed bookmark imports
+        ui.collapsing(
+            tr_args(
+                "settings.failed_imports",
+                &[("count", &app.ingest_errors.len())],
+            ),
+            |ui| {
+                crate::gui::widgets::ingest_errors::render_ingest_errors(ui, app);
+            },
+        );
+
+        ui.add_space(10.0);
+        ui.separator();
+        ui.add_space(10.0);
+
+        // Pages indexed by title and URL only because they looked like junk
+        ui.collapsing(
+            tr_args(
+                "settings.low_quality",
+                &[("count", &app.low_quality_pages.len())],
+            ),
+            |ui| {
+                crate::gui::widgets::page_quality::render_low_quality_pages(ui, app);
+            },
+        );
+
+        ui.add_space(10.0);
+        ui.separator();
+        ui.add_space(10.0);
+
+        // Placeholder-only documents, left out of search until refetched
+        ui.collapsing(
+            tr_args(
+                "settings.needs_refetch",
+                &[("count", &app.refetch_documents.len())],
+            ),
+            |ui| {
+                crate::gui::widgets::page_quality::render_refetch_documents(ui, app);
+            },
+        );
+
+        ui.add_space(10.0);
+        ui.separator();
+        ui.add_space(10.0);
+
+        // Named document collections
+        ui.collapsing(
+            tr_args("settings.collections", &[("count", &app.collections.len())]),
+            |ui| {
+                crate::gui::widgets::collections::render_collections(ui, app);
+            },
+        );
+
+        ui.add_space(10.0);
+        ui.separator();
+        ui.add_space(10.0);
+
+        // Scheduled dead-link checking
+        ui.collapsing(tr("settings.dead_links"), |ui| {
+            crate::gui::widgets::link_checker::render_link_checker(ui, app);
+        });
+
+        ui.add_space(10.0);
+        ui.separator();
+        ui.add_space(10.0);
+
+        // Native OS notifications per event type
+        ui.collapsing(tr("settings.notifications"), |ui| {
+            crate::gui::widgets::notifications::render_notification_settings(ui, app);
+        });
+
+        ui.add_space(10.0);
+        ui.separator();
+        ui.add_space(10.0);
+
+        // Index size limit and pruning
+        ui.collapsing(tr("settings.storage"), |ui| {
+            crate::gui::widgets::storage::render_storage_settings(ui, app);
+        });
+
+        ui.add_space(10.0);
+        ui.separator();
+        ui.add_space(10.0);
+
+        // Scheduled retention rules
+        ui.collapsing(tr("settings.retention"), |ui| {
+            crate::gui::widgets::retention::render_retention_rules(ui, app);
+        });
+
+        ui.add_space(10.0);
+        ui.separator();
+        ui.add_space(10.0);
+
+        // Encrypted sync with other machines
+        ui.collapsing(tr("settings.sync"), |ui| {
+            crate::gui::widgets::sync::render_sync_settings(ui, app);
+        });
+
+        ui.add_space(10.0);
+        ui.separator();
+        ui.add_space(10.0);
+
+        // Scheduled JSONL and database snapshots
+        ui.collapsing(tr("settings.snapshots"), |ui| {
+            crate::gui::widgets::snapshots::render_snapshot_settings(ui, app);
+        });
+
+        ui.add_space(10.0);
+        ui.separator();
+        ui.add_space(10.0);
+
+        // Port and bind address of the extension API
+        ui.collapsing(tr("settings.http_server"), |ui| {
+            crate::gui::widgets::http_server::render_http_server_settings(ui, app);
+        });
+
+        ui.add_space(10.0);
+        ui.separator();
+        ui.add_space(10.0);
+
+        // Read-only search page for other devices on the LAN
+        ui.collapsing(tr("settings.remote_access"), |ui| {
+            crate::gui::widgets::remote_access::render_remote_access_settings(ui, app);
+        });
+
+        ui.add_space(10.0);
+        ui.separator();
+        ui.add_space(10.0);
+
+        // Per-source ingestion policies
+        ui.collapsing(tr("settings.ingest_policies"), |ui| {
+            crate::gui::widgets::ingest_policy::render_ingest_policies(ui, app);
+        });
+
+        ui.add_space(10.0);
+        ui.separator();
+        ui.add_space(10.0);
+
+        // Tracking parameters stripped from URLs
+        ui.collapsing(tr("settings.url_cleaning"), |ui| {
+            crate::gui::widgets::url_cleaning::render_url_cleaning_settings(ui, app);
+        });
+
+        ui.add_space(10.0);
+        ui.separator();
+        ui.add_space(10.0);
+
+        // Domains ranked higher or lower
+        ui.collapsing(tr("settings.domain_preferences"), |ui| {
+            crate::gui::widgets::domain_preferences::render_domain_preferences(ui, app);
+        });
+
+        ui.add_space(10.0);
+        ui.separator();
+        ui.add_space(10.0);
+
+        // Vision model describing bookmarked images
+        ui.collapsing(tr("settings.image_captions"), |ui| {
+            crate::gui::widgets::image_captions::render_image_caption_settings(ui, app);
+        });
+
+        ui.add_space(10.0);
+        ui.separator();
+        ui.add_space(10.0);
+
+        // Detected document languages and which are embedded
+        ui.collapsing(tr("settings.document_languages"), |ui| {
+            crate::gui::widgets::language::render_language_settings(ui, app);
+        });
+
+        ui.add_space(10.0);
+        ui.separator();
+        ui.add_space(10.0);
+
+        // Embedding backend (local server, Ollama, LM Studio)
+        ui.collapsing(tr("settings.embedding"), |ui| {
+            crate::gui::widgets::embedding_provider::render_embedding_settings(ui, app);
+        });
+
+        ui.add_space(10.0);
+        ui.separator();
+        ui.add_space(10.0);
+
+        ui.heading(tr("settings.exclusion_rules"));
         ui.add_space(10.0);
 
         // Folder exclusions section
-        ui.collapsing("Exclude Bookmark Folders", |ui| {
+        ui.collapsing(tr("settings.exclude_folders"), |ui| {
             ui.add_space(5.0);
 
             if app.bookmark_folders.is_empty() {
-                ui.weak("No bookmark folders found. Make sure Chrome bookmarks are available.");
+                ui.weak(tr("settings.no_folders"));
             } else {
                 egui::ScrollArea::vertical()
                     .auto_shrink([false, false])
@@ -49,47 +318,36 @@ pub fn render_settings_modal(ui: &mut Ui, app: &mut LocalMindApp) -> bool {
         ui.add_space(10.0);
 
         // Domain exclusions section
-        ui.collapsing("Exclude Domain Patterns", |ui| {
+        ui.collapsing(tr("settings.exclude_domains"), |ui| {
             ui.add_space(5.0);
 
             // Domain input field
             ui.horizontal(|ui| {
-                ui.label("Domain pattern:");
-                ui.text_edit_singleline(&mut app.pending_domain);
-
-                if ui.button("Add").clicked() {
-                    let pattern = app.pending_domain.trim().to_string();
-                    if !pattern.is_empty() {
-                        // Validate pattern
-                        match ExclusionRules::validate_pattern(&pattern) {
-                            Ok(_) => {
-                                // Check for duplicates (case-insensitive)
-                                let pattern_lower = pattern.to_lowercase();
-                                if !app
-                                    .excluded_domains
-                                    .iter()
-                                    .any(|d| d.to_lowercase() == pattern_lower)
-                                {
-                                    app.excluded_domains.push(pattern);
-                                    app.pending_domain.clear();
-                                } else {
-                                    // Show error toast for duplicate
-                                    let id = app.next_toast_id();
-                                    app.add_toast(crate::gui::state::Toast::error(
-                                        id,
-                                        format!("Domain pattern '{}' already exists", pattern),
-                                    ));
-                                }
-                            }
-                            Err(e) => {
-                                // Show validation error toast
-                                let id = app.next_toast_id();
-                                app.add_toast(crate::gui::state::Toast::error(
-                                    id,
-                                    format!("Invalid pattern: {}", e),
-                                ));
-                            }
+                let label = ui.label(tr("settings.domain_pattern"));
+                ui.text_edit_singleline(&mut app.pending_domain)
+                    .labelled_by(label.id);
+
+                if ui.button(tr("common.add")).clicked() {
+                    match check_domain_pattern(&app.pending_domain, &app.excluded_domains) {
+                        Some(DomainPatternCheck::Add(pattern)) => {
+                            app.excluded_domains.push(pattern);
+                            app.pending_domain.clear();
+                        }
+                        Some(DomainPatternCheck::Duplicate(pattern)) => {
+                            let id = app.next_toast_id();
+                            app.add_toast(crate::gui::state::Toast::error(
+                                id,
+                                tr_args("settings.duplicate_pattern", &[("pattern", &pattern)]),
+                            ));
+                        }
+                        Some(DomainPatternCheck::Invalid(e)) => {
+                            let id = app.next_toast_id();
+                            app.add_toast(crate::gui::state::Toast::error(
+                                id,
+                                tr_args("settings.invalid_pattern", &[("error", &e)]),
+                            ));
                         }
+                        None => {}
                     }
                 }
             });
@@ -98,7 +356,7 @@ pub fn render_settings_modal(ui: &mut Ui, app: &mut LocalMindApp) -> bool {
 
             // Domain pattern list
             if app.excluded_domains.is_empty() {
-                ui.weak("No domain patterns excluded");
+                ui.weak(tr("settings.no_domains"));
             } else {
                 egui::ScrollArea::vertical()
                     .auto_shrink([false, false])
@@ -111,7 +369,7 @@ pub fn render_settings_modal(ui: &mut Ui, app: &mut LocalMindApp) -> bool {
                                 ui.with_layout(
                                     egui::Layout::right_to_left(egui::Align::Center),
                                     |ui| {
-                                        if ui.button("Remove").clicked() {
+                                        if ui.button(tr("common.remove")).clicked() {
                                             to_remove = Some(idx);
                                         }
                                     },
@@ -126,7 +384,7 @@ pub fn render_settings_modal(ui: &mut Ui, app: &mut LocalMindApp) -> bool {
             }
 
             ui.add_space(5.0);
-            ui.weak("Examples: example.com, *.internal.com, localhost:*");
+            ui.weak(tr("settings.examples"));
         });
 
         ui.add_space(20.0);
@@ -136,7 +394,7 @@ pub fn render_settings_modal(ui: &mut Ui, app: &mut LocalMindApp) -> bool {
         // Action buttons
         ui.horizontal(|ui| {
             ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                if ui.button("Cancel").clicked() {
+                if ui.button(tr("common.cancel")).clicked() {
                     should_close = true;
                     // Reset to original values (will be reloaded on next open)
                 }
@@ -149,9 +407,9 @@ pub fn render_settings_modal(ui: &mut Ui, app: &mut LocalMindApp) -> bool {
                 ui.add_enabled_ui(!save_in_progress, |ui| {
                     if ui
                         .button(if save_in_progress {
-                            "Saving..."
+                            tr("common.saving")
                         } else {
-                            "Save"
+                            tr("common.save")
                         })
                         .clicked()
                     {
@@ -160,7 +418,7 @@ pub fn render_settings_modal(ui: &mut Ui, app: &mut LocalMindApp) -> bool {
                             let id = app.next_toast_id();
                             app.add_toast(crate::gui::state::Toast::error(
                                 id,
-                                format!("Failed to start save: {}", e),
+                                tr_args("settings.save_failed", &[("error", &e)]),
                             ));
                         }
                     }
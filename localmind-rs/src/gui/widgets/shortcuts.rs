@@ -0,0 +1,51 @@
+//! Keyboard shortcut reference overlay (toggled with `?`).
+
+use crate::gui::app::LocalMindApp;
+use crate::gui::i18n::tr;
+
+/// Shortcuts handled by `LocalMindApp::handle_shortcuts`, as (keys, action
+/// string key).
+pub const SHORTCUTS: &[(&str, &str)] = &[
+    ("/", "shortcuts.focus_search"),
+    ("Down / Up", "shortcuts.pick_suggestion"),
+    ("j / Down", "shortcuts.next_result"),
+    ("k / Up", "shortcuts.previous_result"),
+    ("Enter", "shortcuts.open_document"),
+    ("o", "shortcuts.open_in_browser"),
+    ("Ctrl + F", "shortcuts.find"),
+    ("Backspace / Esc", "shortcuts.back"),
+    ("Ctrl + ,", "shortcuts.settings"),
+    ("?", "shortcuts.help"),
+];
+
+/// Render the shortcut help window while `app.show_shortcuts_help` is set.
+pub fn render_shortcuts_help(ctx: &egui::Context, app: &mut LocalMindApp) {
+    if !app.show_shortcuts_help {
+        return;
+    }
+
+    let mut open = true;
+    egui::Window::new(tr("shortcuts.title"))
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(ctx, |ui| {
+            egui::Grid::new("shortcuts_grid")
+                .num_columns(2)
+                .spacing([24.0, 6.0])
+                .show(ui, |ui| {
+                    for (keys, action) in SHORTCUTS {
+                        ui.strong(*keys);
+                        ui.label(tr(action));
+                        ui.end_row();
+                    }
+                });
+            ui.add_space(6.0);
+            ui.weak(tr("shortcuts.typing"));
+        });
+
+    if !open {
+        app.show_shortcuts_help = false;
+    }
+}
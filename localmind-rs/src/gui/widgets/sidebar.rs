@@ -0,0 +1,301 @@
+//! Navigation sidebar — sources, tags, collections and bookmark folders that
+//! filter the browse view, plus the timeline, the Explore map, Trash, Jobs
+//! and Stats.
+
+use crate::gui::app::{build_folder_tree, LocalMindApp};
+use crate::gui::i18n::{tr, tr_args};
+use crate::gui::state::{BookmarkFolderView, BrowseFilter, SidebarData, View};
+use crate::gui::widgets::favicon;
+use egui::Ui;
+use egui_remixicon::icons;
+
+/// Where a sidebar click leads
+enum Destination {
+    Browse(BrowseFilter),
+    Timeline,
+    Explore,
+    Trash,
+    Jobs,
+    Stats,
+}
+
+/// One selectable sidebar entry: its label, then its count if any.
+fn entry(ui: &mut Ui, selected: bool, label: String, count: Option<i64>) -> egui::Response {
+    let text = match count {
+        Some(count) => format!("{}  ({})", label, count),
+        None => label,
+    };
+    ui.selectable_label(selected, text)
+}
+
+/// Render the sidebar panel content.
+///
+/// Each section is collapsible; clicking an entry opens the browse view
+/// filtered to it. The entry behind the current view is highlighted.
+pub fn render_sidebar(ui: &mut Ui, app: &mut LocalMindApp) {
+    let browsing = (app.current_view == View::Browse).then(|| app.browse_filter.clone());
+    let is_browsing = |filter: &BrowseFilter| browsing.as_ref() == Some(filter);
+    let mut destination = None;
+
+    egui::ScrollArea::vertical()
+        .id_salt("sidebar")
+        .auto_shrink([false, false])
+        .show(ui, |ui| {
+            ui.add_space(8.0);
+            if entry(
+                ui,
+                is_browsing(&BrowseFilter::All),
+                format!("{} {}", icons::BOOK_2_LINE, tr("browse.all")),
+                None,
+            )
+            .clicked()
+            {
+                destination = Some(Destination::Browse(BrowseFilter::All));
+            }
+            let timeline_label = format!("{} {}", icons::TIME_LINE, tr("timeline.title"));
+            if entry(ui, app.current_view == View::Timeline, timeline_label, None).clicked() {
+                destination = Some(Destination::Timeline);
+            }
+            let explore_label = format!("{} {}", icons::BUBBLE_CHART_LINE, tr("explore.title"));
+            if entry(ui, app.current_view == View::Explore, explore_label, None).clicked() {
+                destination = Some(Destination::Explore);
+            }
+            ui.add_space(6.0);
+
+            // Sources
+            egui::CollapsingHeader::new(tr("sidebar.sources"))
+                .id_salt("sidebar_sources")
+                .default_open(true)
+                .show(ui, |ui| {
+                    for (source, count) in &app.sidebar.sources {
+                        let filter = BrowseFilter::Source(source.clone());
+                        let label = format!(
+                            "{} {}",
+                            favicon::source_icon(source, false),
+                            favicon::source_label(source)
+                        );
+                        if entry(ui, is_browsing(&filter), label, Some(*count)).clicked() {
+                            destination = Some(Destination::Browse(filter));
+                        }
+                    }
+                });
+
+            // Tags
+            egui::CollapsingHeader::new(tr("sidebar.tags"))
+                .id_salt("sidebar_tags")
+                .default_open(true)
+                .show(ui, |ui| {
+                    if app.sidebar.tags.is_empty() {
+                        ui.weak(tr("sidebar.no_tags"));
+                    }
+                    for (tag, count) in &app.sidebar.tags {
+                        let filter = BrowseFilter::Tag(tag.clone());
+                        let label = format!("{} {}", icons::PRICE_TAG_3_LINE, tag);
+                        if entry(ui, is_browsing(&filter), label, Some(*count)).clicked() {
+                            destination = Some(Destination::Browse(filter));
+                        }
+                    }
+                });
+
+            // Collections, smart ones marked with a filter icon
+            egui::CollapsingHeader::new(tr("sidebar.collections"))
+                .id_salt("sidebar_collections")
+                .default_open(true)
+                .show(ui, |ui| {
+                    if app.collections.is_empty() {
+                        ui.weak(tr("collections.none"));
+                    }
+                    for collection in &app.collections {
+                        let filter = BrowseFilter::Collection(collection.id);
+                        let icon = if collection.is_smart() {
+                            icons::FILTER_LINE
+                        } else {
+                            icons::STACK_LINE
+                        };
+                        let label = format!("{} {}", icon, collection.name);
+                        let count = Some(collection.document_count);
+                        let mut response = entry(ui, is_browsing(&filter), label, count);
+                        if let Some(query) = &collection.query {
+                            response = response.on_hover_text(tr_args(
+                                "collections.smart_filter",
+                                &[("filters", query)],
+                            ));
+                        }
+                        if response.clicked() {
+                            destination = Some(Destination::Browse(filter));
+                        }
+                    }
+                });
+
+            // Bookmark folders
+            if !app.sidebar.folders.is_empty() {
+                egui::CollapsingHeader::new(tr("sidebar.folders"))
+                    .id_salt("sidebar_folders")
+                    .default_open(false)
+                    .show(ui, |ui| {
+                        if let Some(path) =
+                            render_folders(ui, &app.sidebar.folders, browsing.as_ref())
+                        {
+                            destination = Some(Destination::Browse(BrowseFilter::Folder(path)));
+                        }
+                    });
+            }
+
+            ui.add_space(6.0);
+            ui.separator();
+            ui.add_space(6.0);
+
+            let trash_label = format!("{} {}", icons::DELETE_BIN_LINE, tr("trash.title"));
+            let trash_count = Some(app.sidebar.trash_count as i64).filter(|count| *count > 0);
+            if entry(
+                ui,
+                app.current_view == View::Trash,
+                trash_label,
+                trash_count,
+            )
+            .clicked()
+            {
+                destination = Some(Destination::Trash);
+            }
+            let jobs_label = format!("{} {}", icons::TASK_LINE, tr("jobs.title"));
+            let job_count = Some(app.sidebar.job_count as i64).filter(|count| *count > 0);
+            if entry(ui, app.current_view == View::Jobs, jobs_label, job_count).clicked() {
+                destination = Some(Destination::Jobs);
+            }
+            let stats_label = format!("{} {}", icons::BAR_CHART_2_LINE, tr("stats.title"));
+            if entry(ui, app.current_view == View::Stats, stats_label, None).clicked() {
+                destination = Some(Destination::Stats);
+            }
+        });
+
+    // Navigate outside the panel to avoid borrow issues
+    match destination {
+        Some(Destination::Browse(filter)) => app.open_browse(filter),
+        Some(Destination::Timeline) => app.open_timeline(),
+        Some(Destination::Explore) => app.open_explore(),
+        Some(Destination::Trash) => app.open_trash(),
+        Some(Destination::Jobs) => app.open_jobs(),
+        Some(Destination::Stats) => app.open_stats(),
+        None => {}
+    }
+}
+
+/// Render the bookmark folder tree; folders with subfolders expand. Returns
+/// the path of a clicked folder.
+fn render_folders(
+    ui: &mut Ui,
+    folders: &[BookmarkFolderView],
+    browsing: Option<&BrowseFilter>,
+) -> Option<Vec<String>> {
+    let mut clicked = None;
+    for folder in folders {
+        let selected = matches!(browsing, Some(BrowseFilter::Folder(path)) if *path == folder.path);
+        let label = format!("{} {}", icons::FOLDER_LINE, folder.name);
+        let count = Some(folder.bookmark_count as i64);
+        if folder.children.is_empty() {
+            if entry(ui, selected, label, count).clicked() {
+                clicked = Some(folder.path.clone());
+            }
+            continue;
+        }
+        let id = ui.make_persistent_id(("sidebar_folder", &folder.path));
+        egui::collapsing_header::CollapsingState::load_with_default_open(ui.ctx(), id, false)
+            .show_header(ui, |ui| {
+                if entry(ui, selected, label, count).clicked() {
+                    clicked = Some(folder.path.clone());
+                }
+            })
+            .body(|ui| {
+                if let Some(path) = render_folders(ui, &folder.children, browsing) {
+                    clicked = Some(path);
+                }
+            });
+    }
+    clicked
+}
+
+impl LocalMindApp {
+    /// Show or hide the navigation sidebar
+    pub fn set_show_sidebar(&mut self, show: bool) {
+        self.show_sidebar = show;
+        if show {
+            self.load_sidebar();
+        }
+    }
+
+    /// Load the sources, tags, bookmark folders and counts listed in the
+    /// sidebar. Collections come from `load_collections`.
+    pub fn load_sidebar(&mut self) {
+        if self.sidebar_receiver.is_some() {
+            return;
+        }
+
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let runtime_handle = self.runtime.clone();
+
+        runtime_handle.spawn(async move {
+            let rag_lock = rag.read().await;
+            let mut sidebar = SidebarData::default();
+            if let Some(ref rag) = *rag_lock {
+                sidebar.sources = rag.db.get_source_counts().await.unwrap_or_else(|e| {
+                    eprintln!("Failed to load sources: {}", e);
+                    Vec::new()
+                });
+                sidebar.tags = rag.db.get_tag_counts().await.unwrap_or_else(|e| {
+                    eprintln!("Failed to load tags: {}", e);
+                    Vec::new()
+                });
+                sidebar.trash_count = rag
+                    .db
+                    .get_trashed_documents()
+                    .await
+                    .map_or(0, |docs| docs.len());
+                for status in [
+                    crate::ingest_queue::QueueStatus::Pending,
+                    crate::ingest_queue::QueueStatus::InProgress,
+                ] {
+                    sidebar.job_count += rag
+                        .db
+                        .get_queue_items_by_status(&status)
+                        .await
+                        .map_or(0, |items| items.len());
+                }
+            }
+            // No bookmarks file (e.g. Chrome not installed) just means no folders
+            if let Ok(monitors) = crate::bookmark::BookmarkMonitor::for_settings() {
+                let folders = monitors
+                    .iter()
+                    .flat_map(|m| m.get_bookmark_folders())
+                    .map(|f| BookmarkFolderView {
+                        id: f.id,
+                        name: f.name,
+                        path: f.path,
+                        children: Vec::new(),
+                        bookmark_count: f.bookmark_count,
+                    })
+                    .collect();
+                sidebar.folders = build_folder_tree(folders);
+            }
+            let _ = tx.send(sidebar);
+        });
+
+        self.sidebar_receiver = Some(rx);
+    }
+
+    /// Check if the sidebar entries have loaded
+    pub(crate) fn check_sidebar_loaded(&mut self) {
+        if let Some(ref rx) = self.sidebar_receiver {
+            match rx.try_recv() {
+                Ok(sidebar) => {
+                    self.sidebar = sidebar;
+                    self.sidebar_receiver = None;
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.sidebar_receiver = None;
+                }
+            }
+        }
+    }
+}
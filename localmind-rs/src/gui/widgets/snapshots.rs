@@ -0,0 +1,237 @@
+//! Snapshots widget — folder, schedule, what to include, retention, manual
+//! run and last result.
+
+use crate::gui::app::LocalMindApp;
+use crate::gui::i18n::{tr, tr_args};
+use crate::gui::state::Toast;
+use crate::snapshot::SnapshotFrequency;
+use egui::Ui;
+
+fn frequency_label(frequency: SnapshotFrequency) -> &'static str {
+    match frequency {
+        SnapshotFrequency::Daily => tr("snapshots.daily"),
+        SnapshotFrequency::Weekly => tr("snapshots.weekly"),
+    }
+}
+
+/// Render the snapshot settings.
+///
+/// Toggles, the schedule and the number kept are saved when changed; the
+/// folder when its field loses focus.
+pub fn render_snapshot_settings(ui: &mut Ui, app: &mut LocalMindApp) {
+    ui.weak(tr("snapshots.intro"));
+    ui.add_space(6.0);
+
+    let settings = &mut app.snapshot_settings;
+    let mut changed = ui
+        .checkbox(&mut settings.enabled, tr("snapshots.enabled"))
+        .changed();
+
+    ui.horizontal(|ui| {
+        let label = ui.label(tr("snapshots.folder"));
+        changed |= ui
+            .add(
+                egui::TextEdit::singleline(&mut settings.folder)
+                    .hint_text(tr("snapshots.folder_hint"))
+                    .desired_width(320.0),
+            )
+            .labelled_by(label.id)
+            .lost_focus();
+    });
+
+    ui.horizontal(|ui| {
+        ui.label(tr("snapshots.frequency"));
+        for frequency in SnapshotFrequency::ALL {
+            changed |= ui
+                .radio_value(
+                    &mut settings.frequency,
+                    frequency,
+                    frequency_label(frequency),
+                )
+                .changed();
+        }
+    });
+
+    ui.horizontal(|ui| {
+        changed |= ui
+            .checkbox(&mut settings.jsonl, tr("snapshots.jsonl"))
+            .changed();
+        changed |= ui
+            .checkbox(&mut settings.database, tr("snapshots.database"))
+            .changed();
+    });
+
+    ui.horizontal(|ui| {
+        let label = ui.label(tr("snapshots.keep"));
+        changed |= ui
+            .add(egui::DragValue::new(&mut settings.keep).range(1..=365))
+            .labelled_by(label.id)
+            .changed();
+    });
+
+    if changed {
+        app.save_snapshot_settings();
+    }
+
+    ui.add_space(6.0);
+
+    let running = app.is_snapshot_running();
+    ui.horizontal(|ui| {
+        ui.add_enabled_ui(!running && app.snapshot_settings.is_configured(), |ui| {
+            if ui.button(tr("snapshots.run_now")).clicked() {
+                app.run_snapshot_now();
+            }
+        });
+        if running {
+            ui.spinner();
+        }
+    });
+
+    match &app.snapshot_last_result {
+        Some(Ok(report)) => {
+            ui.weak(tr_args(
+                "snapshots.report",
+                &[
+                    ("files", &report.files.len()),
+                    ("documents", &report.documents),
+                    ("removed", &report.removed),
+                ],
+            ));
+        }
+        Some(Err(e)) => {
+            ui.colored_label(
+                ui.visuals().error_fg_color,
+                tr_args("snapshots.failed", &[("error", e)]),
+            );
+        }
+        None => {}
+    }
+}
+
+impl LocalMindApp {
+    /// Load the snapshot settings
+    pub fn load_snapshot_settings(&mut self) {
+        if self.snapshot_settings_receiver.is_some() {
+            return;
+        }
+
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            if let Some(ref rag) = *rag_lock {
+                match rag.db.get_snapshot_settings().await {
+                    Ok(settings) => {
+                        let _ = tx.send(settings);
+                    }
+                    Err(e) => eprintln!("Failed to load snapshot settings: {}", e),
+                }
+            }
+        });
+
+        self.snapshot_settings_receiver = Some(rx);
+    }
+
+    /// Check if the snapshot settings have loaded
+    pub(crate) fn check_snapshot_settings_loaded(&mut self) {
+        if let Some(ref rx) = self.snapshot_settings_receiver {
+            match rx.try_recv() {
+                Ok(settings) => {
+                    self.snapshot_settings = settings;
+                    self.snapshot_settings_receiver = None;
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.snapshot_settings_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Save the snapshot settings edited in Settings
+    pub fn save_snapshot_settings(&mut self) {
+        let settings = self.snapshot_settings.clone();
+        let rag = self.rag.clone();
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            if let Some(ref rag) = *rag_lock {
+                if let Err(e) = rag.db.set_snapshot_settings(&settings).await {
+                    eprintln!("Failed to save snapshot settings: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Check if a manual snapshot is being taken
+    pub fn is_snapshot_running(&self) -> bool {
+        self.snapshot_run_receiver.is_some()
+    }
+
+    /// Take a snapshot now with the settings shown in Settings
+    pub fn run_snapshot_now(&mut self) {
+        if self.is_snapshot_running() {
+            return;
+        }
+
+        let settings = self.snapshot_settings.clone();
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            let result = match *rag_lock {
+                Some(ref rag) => crate::snapshot::run_snapshot(&rag.db, &settings)
+                    .await
+                    .map_err(|e| e.to_string()),
+                None => Err(tr("common.not_ready").to_string()),
+            };
+            let _ = tx.send(result);
+        });
+
+        self.snapshot_run_receiver = Some(rx);
+    }
+
+    /// Check for reports of scheduled snapshots
+    pub(crate) fn check_snapshot_reports(&mut self) {
+        if let Some(ref rx) = self.snapshot_report_receiver {
+            while let Ok(report) = rx.try_recv() {
+                self.snapshot_last_result = Some(Ok(report));
+            }
+        }
+    }
+
+    /// Check if a manual snapshot has finished and report the result
+    pub(crate) fn check_snapshot_run(&mut self) {
+        if let Some(ref rx) = self.snapshot_run_receiver {
+            match rx.try_recv() {
+                Ok(result) => {
+                    self.snapshot_run_receiver = None;
+                    let id = self.next_toast_id();
+                    match &result {
+                        Ok(report) => {
+                            self.add_toast(Toast::success(
+                                id,
+                                tr_args(
+                                    "snapshots.saved",
+                                    &[("files", &report.files.len()), ("removed", &report.removed)],
+                                ),
+                            ));
+                        }
+                        Err(e) => {
+                            self.add_toast(Toast::error(
+                                id,
+                                tr_args("snapshots.failed", &[("error", &e)]),
+                            ));
+                        }
+                    }
+                    self.snapshot_last_result = Some(result);
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.snapshot_run_receiver = None;
+                }
+            }
+        }
+    }
+}
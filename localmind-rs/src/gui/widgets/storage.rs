@@ -0,0 +1,435 @@
+//! Storage widget — index size, size limit, pruning suggestions and
+//! database maintenance.
+
+use crate::gui::app::LocalMindApp;
+use crate::gui::i18n::{tr, tr_args};
+use crate::gui::state::Toast;
+use crate::storage_budget::format_bytes;
+use egui::Ui;
+
+/// Bytes in a megabyte, the unit of the index size limit in Settings
+const BYTES_PER_MB: u64 = 1024 * 1024;
+
+/// Documents suggested for pruning when the index is within its limit
+const PRUNE_SUGGESTIONS: usize = 20;
+
+/// Most documents considered when pruning back under the limit
+const MAX_PRUNE_CANDIDATES: usize = 5000;
+
+/// Render the storage settings.
+///
+/// Shows how much space the index uses, the free disk space and the index
+/// size limit (0 disables it), and lists documents suggested for pruning.
+pub fn render_storage_settings(ui: &mut Ui, app: &mut LocalMindApp) {
+    ui.weak(tr("storage.intro"));
+    ui.add_space(6.0);
+
+    match app.storage_status {
+        Some(status) => {
+            ui.label(tr_args(
+                "storage.index_size",
+                &[
+                    ("size", &format_bytes(status.index_bytes)),
+                    ("count", &status.document_count),
+                ],
+            ));
+            if let Some(free) = status.free_bytes {
+                ui.label(tr_args(
+                    "storage.free_space",
+                    &[("free", &format_bytes(free))],
+                ));
+                if status.low_disk(0) {
+                    ui.colored_label(ui.visuals().warn_fg_color, tr("storage.low_disk"));
+                }
+            }
+            let over = status.bytes_over_budget(0);
+            if over > 0 {
+                ui.colored_label(
+                    ui.visuals().error_fg_color,
+                    tr_args("storage.over_budget", &[("size", &format_bytes(over))]),
+                );
+            }
+        }
+        None => {
+            ui.spinner();
+        }
+    }
+
+    ui.add_space(6.0);
+
+    ui.horizontal(|ui| {
+        let label = ui.label(tr("storage.limit"));
+        let response = ui
+            .add(
+                egui::DragValue::new(&mut app.max_index_mb)
+                    .range(0..=10 * 1024 * 1024)
+                    .speed(10)
+                    .suffix(" MB"),
+            )
+            .labelled_by(label.id);
+        if response.changed() {
+            app.save_max_index_size();
+        }
+        if app.max_index_mb == 0 {
+            ui.weak(tr("storage.unlimited"));
+        }
+    });
+
+    ui.add_space(6.0);
+
+    if ui.button(tr("storage.suggest")).clicked() {
+        app.load_prune_suggestions();
+    }
+
+    if !app.prune_suggestions.is_empty() {
+        ui.add_space(6.0);
+        let total: u64 = app.prune_suggestions.iter().map(|c| c.bytes).sum();
+        ui.label(tr_args(
+            "storage.suggestions",
+            &[
+                ("count", &app.prune_suggestions.len()),
+                ("size", &format_bytes(total)),
+            ],
+        ));
+        egui::ScrollArea::vertical()
+            .id_salt("prune_suggestions")
+            .max_height(200.0)
+            .show(ui, |ui| {
+                for candidate in &app.prune_suggestions {
+                    let reason = if candidate.is_dead {
+                        tr("storage.reason_dead").to_string()
+                    } else if candidate.view_count == 0 {
+                        tr("storage.reason_never_opened").to_string()
+                    } else {
+                        tr_args(
+                            "storage.reason_last_opened",
+                            &[("time", &candidate.last_used_at)],
+                        )
+                    };
+                    ui.horizontal(|ui| {
+                        ui.label(&candidate.title);
+                        ui.weak(format!("{} - {}", reason, format_bytes(candidate.bytes)));
+                    });
+                }
+            });
+        ui.horizontal(|ui| {
+            if ui.button(tr("storage.prune")).clicked() {
+                app.prune_suggested_documents();
+            }
+            ui.weak(tr("storage.prune_hint"));
+        });
+    }
+
+    ui.add_space(10.0);
+    render_maintenance(ui, app);
+}
+
+/// Maintenance part of the storage settings: schedule, manual run,
+/// progress and the size report of the last run.
+fn render_maintenance(ui: &mut Ui, app: &mut LocalMindApp) {
+    ui.strong(tr("maintenance.title"));
+    ui.weak(tr("maintenance.intro"));
+    ui.add_space(6.0);
+
+    ui.horizontal(|ui| {
+        let label = ui.label(tr("maintenance.every"));
+        let response = ui
+            .add(
+                egui::DragValue::new(&mut app.maintenance_interval_days)
+                    .range(0..=365)
+                    .suffix(format!(" {}", tr("retention.days_unit"))),
+            )
+            .labelled_by(label.id);
+        if response.changed() {
+            app.save_maintenance_interval();
+        }
+        if app.maintenance_interval_days == 0 {
+            ui.weak(tr("maintenance.disabled"));
+        }
+    });
+
+    let running = app.is_maintenance_running();
+    ui.horizontal(|ui| {
+        ui.add_enabled_ui(!running, |ui| {
+            if ui.button(tr("maintenance.run_now")).clicked() {
+                app.run_maintenance_now();
+            }
+        });
+        if running {
+            ui.spinner();
+        }
+    });
+
+    if let Some(progress) = app.maintenance_progress.clone() {
+        if progress.is_finished() {
+            if let Some(report) = progress.report {
+                ui.weak(tr_args(
+                    "maintenance.report",
+                    &[
+                        ("before", &format_bytes(report.size_before)),
+                        ("after", &format_bytes(report.size_after)),
+                    ],
+                ));
+            }
+        } else {
+            let fraction = progress.done as f32 / progress.total.max(1) as f32;
+            ui.add(egui::ProgressBar::new(fraction).text(tr_args(
+                "maintenance.progress",
+                &[("done", &progress.done), ("total", &progress.total)],
+            )));
+        }
+    }
+}
+
+impl LocalMindApp {
+    /// Measure the index size and free disk space
+    pub fn load_storage_status(&mut self) {
+        if self.storage_status_receiver.is_some() {
+            return;
+        }
+
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            if let Some(ref rag) = *rag_lock {
+                match rag.storage_status().await {
+                    Ok(status) => {
+                        let _ = tx.send(status);
+                    }
+                    Err(e) => eprintln!("Failed to measure index size: {}", e),
+                }
+            }
+        });
+
+        self.storage_status_receiver = Some(rx);
+    }
+
+    /// Check if the storage status has been measured
+    pub(crate) fn check_storage_status_loaded(&mut self) {
+        if let Some(ref rx) = self.storage_status_receiver {
+            match rx.try_recv() {
+                Ok(status) => {
+                    self.max_index_mb = status.max_index_bytes / BYTES_PER_MB;
+                    self.storage_status = Some(status);
+                    self.storage_status_receiver = None;
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.storage_status_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Save the index size limit edited in Settings
+    pub fn save_max_index_size(&mut self) {
+        let bytes = self.max_index_mb * BYTES_PER_MB;
+        if let Some(ref mut status) = self.storage_status {
+            status.max_index_bytes = bytes;
+        }
+
+        let rag = self.rag.clone();
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            if let Some(ref rag) = *rag_lock {
+                if let Err(e) = rag.db.set_max_index_bytes(bytes).await {
+                    eprintln!("Failed to save index size limit: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Suggest documents to prune: enough to get back under the index size
+    /// limit, or the first few candidates when within it
+    pub fn load_prune_suggestions(&mut self) {
+        if self.prune_suggestions_receiver.is_some() {
+            return;
+        }
+
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            if let Some(ref rag) = *rag_lock {
+                let over = match rag.storage_status().await {
+                    Ok(status) => status.bytes_over_budget(0),
+                    Err(e) => {
+                        eprintln!("Failed to measure index size: {}", e);
+                        0
+                    }
+                };
+                let limit = if over > 0 {
+                    MAX_PRUNE_CANDIDATES
+                } else {
+                    PRUNE_SUGGESTIONS
+                };
+                match rag.db.get_prune_candidates(limit).await {
+                    Ok(candidates) if over > 0 => {
+                        let _ = tx.send(crate::storage_budget::suggest_pruning(candidates, over));
+                    }
+                    Ok(candidates) => {
+                        let _ = tx.send(candidates);
+                    }
+                    Err(e) => eprintln!("Failed to load pruning suggestions: {}", e),
+                }
+            }
+        });
+
+        self.prune_suggestions_receiver = Some(rx);
+    }
+
+    /// Check if pruning suggestions have loaded
+    pub(crate) fn check_prune_suggestions_loaded(&mut self) {
+        if let Some(ref rx) = self.prune_suggestions_receiver {
+            match rx.try_recv() {
+                Ok(suggestions) => {
+                    self.prune_suggestions = suggestions;
+                    self.prune_suggestions_receiver = None;
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.prune_suggestions_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Move the suggested documents to the trash
+    pub fn prune_suggested_documents(&mut self) {
+        if self.document_action_receiver.is_some() {
+            return;
+        }
+        let doc_ids: Vec<i64> = self.prune_suggestions.drain(..).map(|c| c.id).collect();
+        self.delete_documents(doc_ids);
+    }
+
+    /// Check for maintenance progress events and update the progress toast
+    pub(crate) fn check_maintenance_progress(&mut self) {
+        let mut pending_progress = Vec::new();
+        if let Some(ref rx) = self.maintenance_progress_receiver {
+            while let Ok(progress) = rx.try_recv() {
+                pending_progress.push(progress);
+            }
+        }
+
+        for progress in pending_progress {
+            if progress.is_finished() {
+                self.toasts.finish_progress(&mut self.maintenance_toast_id);
+                let id = self.next_toast_id();
+                match progress.report {
+                    Some(report) => self.add_toast(Toast::success(
+                        id,
+                        tr_args(
+                            "maintenance.optimized",
+                            &[
+                                (
+                                    "before",
+                                    &crate::storage_budget::format_bytes(report.size_before),
+                                ),
+                                (
+                                    "after",
+                                    &crate::storage_budget::format_bytes(report.size_after),
+                                ),
+                            ],
+                        ),
+                    )),
+                    None => self.add_toast(Toast::error(id, tr("maintenance.failed"))),
+                }
+            } else {
+                let fraction = progress.done as f32 / progress.total.max(1) as f32;
+                let message = tr_args(
+                    "maintenance.optimizing",
+                    &[("step", &(progress.done + 1)), ("total", &progress.total)],
+                );
+                self.toasts
+                    .show_progress(&mut self.maintenance_toast_id, message, fraction);
+            }
+            self.maintenance_progress = Some(progress);
+        }
+    }
+
+    /// Check if database maintenance is currently running
+    pub fn is_maintenance_running(&self) -> bool {
+        self.maintenance_progress
+            .as_ref()
+            .is_some_and(|p| !p.is_finished())
+    }
+
+    /// Run database maintenance immediately, regardless of the schedule
+    pub fn run_maintenance_now(&mut self) {
+        if self.is_maintenance_running() {
+            return;
+        }
+
+        // Show progress right away; the first event replaces it
+        self.maintenance_progress = Some(crate::maintenance::MaintenanceProgress::starting());
+
+        let rag = self.rag.clone();
+        let tx = self.maintenance_progress_tx.clone();
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            let result = match *rag_lock {
+                Some(ref rag) => crate::maintenance::run_maintenance(&rag.db, Some(&tx)).await,
+                None => Err(tr("common.not_ready").into()),
+            };
+            if let Err(e) = result {
+                eprintln!("Database maintenance failed: {}", e);
+                let _ = tx.send(crate::maintenance::MaintenanceProgress::default());
+            }
+        });
+    }
+
+    /// Load the stored maintenance interval from the database
+    pub fn load_maintenance_settings(&mut self) {
+        if self.maintenance_interval_receiver.is_some() {
+            return;
+        }
+
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            if let Some(ref rag) = *rag_lock {
+                if let Ok(days) = rag.db.get_maintenance_interval_days().await {
+                    let _ = tx.send(days);
+                }
+            }
+        });
+
+        self.maintenance_interval_receiver = Some(rx);
+    }
+
+    /// Check if the stored maintenance interval has loaded
+    pub(crate) fn check_maintenance_settings_loaded(&mut self) {
+        if let Some(ref rx) = self.maintenance_interval_receiver {
+            match rx.try_recv() {
+                Ok(days) => {
+                    self.maintenance_interval_days = days;
+                    self.maintenance_interval_receiver = None;
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.maintenance_interval_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Save the maintenance interval edited in Settings
+    pub fn save_maintenance_interval(&mut self) {
+        let days = self.maintenance_interval_days;
+        let rag = self.rag.clone();
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            if let Some(ref rag) = *rag_lock {
+                if let Err(e) = rag.db.set_maintenance_interval_days(days).await {
+                    eprintln!("Failed to save maintenance interval: {}", e);
+                }
+            }
+        });
+    }
+}
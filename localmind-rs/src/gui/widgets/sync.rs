@@ -0,0 +1,318 @@
+//! Sync widget — shared folder, passphrase, manual run and last result.
+
+use crate::gui::app::LocalMindApp;
+use crate::gui::i18n::{tr, tr_args};
+use crate::gui::state::Toast;
+use egui::Ui;
+
+/// Render the sync settings.
+///
+/// The enabled flag is saved when toggled; the folder and passphrase when
+/// their field loses focus.
+pub fn render_sync_settings(ui: &mut Ui, app: &mut LocalMindApp) {
+    ui.weak(tr("sync.intro"));
+    ui.add_space(6.0);
+
+    let mut changed = ui
+        .checkbox(&mut app.sync_settings.enabled, tr("sync.enabled"))
+        .changed();
+
+    ui.horizontal(|ui| {
+        let label = ui.label(tr("sync.folder"));
+        changed |= ui
+            .add(
+                egui::TextEdit::singleline(&mut app.sync_settings.folder)
+                    .hint_text(tr("sync.folder_hint"))
+                    .desired_width(320.0),
+            )
+            .labelled_by(label.id)
+            .lost_focus();
+    });
+
+    ui.horizontal(|ui| {
+        let label = ui.label(tr("sync.passphrase"));
+        changed |= ui
+            .add(
+                egui::TextEdit::singleline(&mut app.sync_settings.passphrase)
+                    .password(true)
+                    .desired_width(220.0),
+            )
+            .labelled_by(label.id)
+            .lost_focus();
+    });
+    ui.weak(tr("sync.passphrase_hint"));
+
+    if changed {
+        app.save_sync_settings();
+    }
+
+    ui.add_space(6.0);
+
+    let running = app.is_sync_running();
+    ui.horizontal(|ui| {
+        ui.add_enabled_ui(!running && app.sync_settings.is_configured(), |ui| {
+            if ui.button(tr("sync.run_now")).clicked() {
+                app.run_sync_now();
+            }
+        });
+        if running {
+            ui.spinner();
+        }
+    });
+
+    match &app.sync_last_result {
+        Some(Ok(report)) => {
+            ui.weak(tr_args(
+                "sync.report",
+                &[
+                    ("devices", &report.devices),
+                    ("added", &report.inserted),
+                    ("updated", &report.updated),
+                    ("trashed", &report.trashed),
+                ],
+            ));
+            if !report.failed.is_empty() {
+                ui.colored_label(
+                    ui.visuals().warn_fg_color,
+                    tr_args("sync.unreadable", &[("files", &report.failed.join(", "))]),
+                );
+            }
+        }
+        Some(Err(e)) => {
+            ui.colored_label(
+                ui.visuals().error_fg_color,
+                tr_args("sync.failed", &[("error", e)]),
+            );
+        }
+        None => {}
+    }
+}
+
+impl LocalMindApp {
+    /// Load the sync settings
+    pub fn load_sync_settings(&mut self) {
+        if self.sync_settings_receiver.is_some() {
+            return;
+        }
+
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            if let Some(ref rag) = *rag_lock {
+                match rag.db.get_sync_settings().await {
+                    Ok(settings) => {
+                        let _ = tx.send(settings);
+                    }
+                    Err(e) => eprintln!("Failed to load sync settings: {}", e),
+                }
+            }
+        });
+
+        self.sync_settings_receiver = Some(rx);
+    }
+
+    /// Check if the sync settings have loaded
+    pub(crate) fn check_sync_settings_loaded(&mut self) {
+        if let Some(ref rx) = self.sync_settings_receiver {
+            match rx.try_recv() {
+                Ok(settings) => {
+                    self.sync_settings = settings;
+                    self.sync_settings_receiver = None;
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.sync_settings_receiver = None;
+                }
+            }
+        }
+    }
+
+    /// Save the sync settings edited in Settings
+    pub fn save_sync_settings(&mut self) {
+        let settings = self.sync_settings.clone();
+        let rag = self.rag.clone();
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            if let Some(ref rag) = *rag_lock {
+                if let Err(e) = rag.db.set_sync_settings(&settings).await {
+                    eprintln!("Failed to save sync settings: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Check if a sync run is currently running
+    pub fn is_sync_running(&self) -> bool {
+        self.sync_run_receiver.is_some()
+    }
+
+    /// Sync with the other machines now instead of waiting for the schedule
+    pub fn run_sync_now(&mut self) {
+        if self.is_sync_running() {
+            return;
+        }
+
+        let rag = self.rag.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            let result = match *rag_lock {
+                Some(ref rag) => crate::sync::run_sync(rag).await.map_err(|e| e.to_string()),
+                None => Err(tr("common.not_ready").to_string()),
+            };
+            let _ = tx.send(result);
+        });
+
+        self.sync_run_receiver = Some(rx);
+    }
+
+    /// Refresh the document lists after a sync run that changed the index
+    pub(crate) fn apply_sync_report(&mut self, report: &crate::sync::SyncReport) {
+        if report.changed() > 0 {
+            self.load_recent_documents();
+            self.load_pinned_documents();
+            self.load_resurfaced_documents();
+        }
+    }
+
+    /// Check for reports of scheduled sync runs
+    pub(crate) fn check_sync_reports(&mut self) {
+        let mut reports = Vec::new();
+        if let Some(ref rx) = self.sync_report_receiver {
+            while let Ok(report) = rx.try_recv() {
+                reports.push(report);
+            }
+        }
+
+        for report in reports {
+            self.apply_sync_report(&report);
+            self.sync_last_result = Some(Ok(report));
+        }
+    }
+
+    /// Check if a manual sync run has finished and report the result
+    pub(crate) fn check_sync_run(&mut self) {
+        if let Some(ref rx) = self.sync_run_receiver {
+            match rx.try_recv() {
+                Ok(result) => {
+                    self.sync_run_receiver = None;
+                    let id = self.next_toast_id();
+                    match &result {
+                        Ok(report) if !report.failed.is_empty() => {
+                            self.add_toast(Toast::error(
+                                id,
+                                tr_args(
+                                    "sync.unreadable_count",
+                                    &[("count", &report.failed.len())],
+                                ),
+                            ));
+                        }
+                        Ok(report) => {
+                            self.add_toast(Toast::success(
+                                id,
+                                tr_args(
+                                    "sync.done",
+                                    &[
+                                        ("added", &report.inserted),
+                                        ("updated", &report.updated),
+                                        ("trashed", &report.trashed),
+                                    ],
+                                ),
+                            ));
+                        }
+                        Err(e) => {
+                            self.add_toast(Toast::error(
+                                id,
+                                tr_args("sync.failed", &[("error", &e)]),
+                            ));
+                        }
+                    }
+                    if let Ok(report) = &result {
+                        self.apply_sync_report(report);
+                    }
+                    self.sync_last_result = Some(result);
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.sync_run_receiver = None;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gui::state::ToastType;
+    use crate::sync::{SyncReport, SyncSettings};
+
+    #[test]
+    fn loaded_sync_settings_replace_the_defaults() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let mut app = LocalMindApp::detached(runtime.handle().clone());
+        let (tx, rx) = std::sync::mpsc::channel();
+        app.sync_settings_receiver = Some(rx);
+        let settings = SyncSettings {
+            enabled: true,
+            folder: "/shared/localmind".to_string(),
+            passphrase: "secret".to_string(),
+        };
+
+        tx.send(settings.clone()).unwrap();
+        app.check_sync_settings_loaded();
+        assert_eq!(app.sync_settings, settings);
+        assert!(app.sync_settings_receiver.is_none());
+    }
+
+    #[test]
+    fn manual_sync_run_reports_its_outcome() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let mut app = LocalMindApp::detached(runtime.handle().clone());
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        app.sync_run_receiver = Some(rx);
+        assert!(app.is_sync_running());
+        app.check_sync_run();
+        assert!(app.is_sync_running());
+
+        tx.send(Ok(SyncReport::default())).unwrap();
+        app.check_sync_run();
+        assert!(!app.is_sync_running());
+        assert_eq!(app.sync_last_result, Some(Ok(SyncReport::default())));
+        assert_eq!(
+            app.toasts.active.last().unwrap().toast_type,
+            ToastType::Success
+        );
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        app.sync_run_receiver = Some(rx);
+        let report = SyncReport {
+            failed: vec!["laptop.sync".to_string()],
+            ..SyncReport::default()
+        };
+        tx.send(Ok(report)).unwrap();
+        app.check_sync_run();
+        assert_eq!(
+            app.toasts.active.last().unwrap().toast_type,
+            ToastType::Error
+        );
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        app.sync_run_receiver = Some(rx);
+        tx.send(Err("folder missing".to_string())).unwrap();
+        app.check_sync_run();
+        assert_eq!(
+            app.sync_last_result,
+            Some(Err("folder missing".to_string()))
+        );
+        assert_eq!(
+            app.toasts.active.last().unwrap().toast_type,
+            ToastType::Error
+        );
+        assert_eq!(app.toasts.active.len(), 3);
+    }
+}
@@ -0,0 +1,94 @@
+//! Thumbnail layout — the preview image on a document card and the toggle
+//! that turns the layout on.
+
+use crate::gui::accessibility::Labeled;
+use crate::gui::app::LocalMindApp;
+use crate::gui::i18n::tr;
+use egui::Ui;
+use egui_remixicon::icons;
+use std::sync::Arc;
+
+/// Render a document's thumbnail (JPEG bytes) at card size.
+pub fn render_thumbnail(ui: &mut Ui, doc_id: i64, jpeg: Arc<[u8]>) {
+    ui.add(
+        egui::Image::from_bytes(format!("bytes://thumbnail/{}.jpg", doc_id), jpeg)
+            .max_size(egui::vec2(240.0, 135.0))
+            .rounding(4.0),
+    );
+}
+
+/// Render the toggle between the plain list and cards with thumbnails.
+pub fn layout_toggle(ui: &mut Ui, app: &mut LocalMindApp) {
+    let enabled = app.card_layout;
+    if ui
+        .selectable_label(enabled, icons::LAYOUT_GRID_LINE)
+        .labeled_toggle(enabled, tr("layout.thumbnails"))
+        .clicked()
+    {
+        app.set_card_layout(!enabled);
+    }
+}
+
+impl LocalMindApp {
+    /// Load the thumbnails of documents not asked for yet
+    pub(crate) fn load_thumbnails(&mut self, doc_ids: &[i64]) {
+        let doc_ids: Vec<i64> = doc_ids
+            .iter()
+            .copied()
+            .filter(|id| self.thumbnails_requested.insert(*id))
+            .collect();
+        if doc_ids.is_empty() {
+            return;
+        }
+
+        let rag = self.rag.clone();
+        let tx = self.thumbnail_tx.clone();
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            let thumbnails = match *rag_lock {
+                Some(ref rag) => rag.db.get_thumbnails(&doc_ids).await.unwrap_or_else(|e| {
+                    eprintln!("Failed to load thumbnails: {}", e);
+                    Default::default()
+                }),
+                None => Default::default(),
+            };
+            let _ = tx.send((doc_ids, thumbnails));
+        });
+        self.thumbnail_loads_pending += 1;
+    }
+
+    /// Store thumbnails that have loaded. Documents without one yet are
+    /// asked for again next time, as it may have been fetched since.
+    pub(crate) fn check_thumbnails_loaded(&mut self) {
+        while let Ok((doc_ids, mut thumbnails)) = self.thumbnail_rx.try_recv() {
+            self.thumbnail_loads_pending = self.thumbnail_loads_pending.saturating_sub(1);
+            for doc_id in doc_ids {
+                match thumbnails.remove(&doc_id) {
+                    Some(thumbnail) => {
+                        self.thumbnails.insert(doc_id, Arc::from(thumbnail));
+                    }
+                    None => {
+                        self.thumbnails_requested.remove(&doc_id);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Switch between the plain list and cards with thumbnails, and
+    /// remember the choice
+    pub fn set_card_layout(&mut self, enabled: bool) {
+        self.card_layout = enabled;
+        self.load_document_images();
+
+        let rag = self.rag.clone();
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            if let Some(ref rag) = *rag_lock {
+                if let Err(e) = rag.db.set_card_layout(enabled).await {
+                    eprintln!("Failed to save layout: {}", e);
+                }
+            }
+        });
+    }
+}
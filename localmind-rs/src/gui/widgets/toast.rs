@@ -1,25 +1,38 @@
 //! Toast notification widget
 //!
-//! Provides rendering and styling for toast notifications.
+//! Provides rendering and styling for toast notifications and the
+//! notification history drawer.
 
-use crate::gui::state::{Toast, ToastType};
+use crate::gui::accessibility::Labeled;
+use crate::gui::app::LocalMindApp;
+use crate::gui::i18n::{tr, tr_args};
+use crate::gui::state::{ToastAction, ToastType};
 use egui::{Color32, Context};
 
+/// Maximum number of toasts stacked on screen at once
+const MAX_VISIBLE_TOASTS: usize = 4;
+
 /// Render toast notifications in the bottom-right corner
 ///
-/// Displays up to 5 toasts, with the most recent on top.
-/// Toasts are automatically styled based on their type.
-pub fn render_toasts(ctx: &Context, toasts: &[Toast]) {
-    if toasts.is_empty() {
+/// Displays up to `MAX_VISIBLE_TOASTS` toasts, with the most recent on top,
+/// and a "+N more" link to the history drawer for the rest. Toasts are
+/// styled based on their type and can show a progress bar, action buttons
+/// and a dismiss button.
+pub fn render_toasts(ctx: &Context, app: &mut LocalMindApp) {
+    if app.toasts.active.is_empty() {
         return;
     }
 
+    let mut dismissed = None;
+    let mut action: Option<ToastAction> = None;
+    let mut open_history = false;
+
     egui::Area::new(egui::Id::new("toast_area"))
         .anchor(egui::Align2::RIGHT_BOTTOM, [-10.0, -10.0])
         .show(ctx, |ui| {
             ui.vertical(|ui| {
                 // Show most recent toasts first (reverse order)
-                for toast in toasts.iter().rev().take(5) {
+                for toast in app.toasts.active.iter().rev().take(MAX_VISIBLE_TOASTS) {
                     let color = get_toast_color(toast.toast_type);
 
                     egui::Frame::none()
@@ -27,12 +40,110 @@ pub fn render_toasts(ctx: &Context, toasts: &[Toast]) {
                         .rounding(4.0)
                         .inner_margin(8.0)
                         .show(ui, |ui| {
-                            ui.colored_label(Color32::WHITE, &toast.message);
+                            ui.set_max_width(360.0);
+                            ui.horizontal(|ui| {
+                                ui.colored_label(Color32::WHITE, &toast.message);
+                                for button in &toast.buttons {
+                                    if ui.button(&button.label).clicked() {
+                                        action = Some(button.action.clone());
+                                        dismissed = Some(toast.id);
+                                    }
+                                }
+                                if ui.small_button("x").labeled(tr("toast.dismiss")).clicked() {
+                                    dismissed = Some(toast.id);
+                                }
+                            });
+                            if let Some(progress) = toast.progress {
+                                ui.add(
+                                    egui::ProgressBar::new(progress)
+                                        .desired_width(300.0)
+                                        .show_percentage(),
+                                );
+                            }
                         });
                     ui.add_space(4.0);
                 }
+
+                let hidden = app.toasts.active.len().saturating_sub(MAX_VISIBLE_TOASTS);
+                if hidden > 0
+                    && ui
+                        .link(tr_args("toast.more", &[("count", &hidden)]))
+                        .clicked()
+                {
+                    open_history = true;
+                }
             });
         });
+
+    if let Some(id) = dismissed {
+        app.dismiss_toast(id);
+    }
+    if let Some(action) = action {
+        app.run_toast_action(action);
+    }
+    if open_history {
+        app.show_toast_history = true;
+    }
+}
+
+/// Render the notification history drawer while `app.show_toast_history` is set.
+///
+/// Must be called before the central panel so the drawer takes its space.
+pub fn render_toast_history(ctx: &Context, app: &mut LocalMindApp) {
+    if !app.show_toast_history {
+        return;
+    }
+
+    egui::SidePanel::right("toast_history")
+        .resizable(true)
+        .default_width(320.0)
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.heading(tr("settings.notifications"));
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.button(tr("common.close")).clicked() {
+                        app.show_toast_history = false;
+                    }
+                    if ui
+                        .add_enabled(
+                            !app.toasts.history.is_empty(),
+                            egui::Button::new(tr("common.clear")),
+                        )
+                        .clicked()
+                    {
+                        app.toasts.history.clear();
+                    }
+                });
+            });
+            ui.separator();
+
+            if app.toasts.history.is_empty() {
+                ui.weak(tr("toast.none"));
+                return;
+            }
+
+            egui::ScrollArea::vertical()
+                .id_salt("toast_history_scroll")
+                .show(ui, |ui| {
+                    for record in app.toasts.history.iter().rev() {
+                        ui.horizontal_wrapped(|ui| {
+                            ui.colored_label(get_toast_color(record.toast_type), "●");
+                            ui.label(&record.message);
+                        });
+                        ui.weak(format_age(record.created_at.elapsed().as_secs()));
+                        ui.add_space(6.0);
+                    }
+                });
+        });
+}
+
+/// Short relative time such as "just now" or "5 min ago".
+fn format_age(seconds: u64) -> String {
+    match seconds {
+        0..=59 => tr("time.just_now").to_string(),
+        60..=3599 => tr_args("time.minutes_short", &[("count", &(seconds / 60))]),
+        _ => tr_args("time.hours_short", &[("count", &(seconds / 3600))]),
+    }
 }
 
 /// Get the color for a toast based on its type
@@ -0,0 +1,83 @@
+//! Settings section for the tracking parameters stripped from URLs
+
+use crate::gui::app::LocalMindApp;
+use crate::gui::i18n::{tr, tr_args};
+use crate::gui::state::Toast;
+use crate::tracking_params;
+use egui::Ui;
+
+/// URL the preview cleans with the list being edited
+const EXAMPLE_URL: &str = "https://example.com/post?id=7&utm_source=newsletter&fbclid=IwAR0";
+
+/// Render the editable list of stripped parameters with a preview
+pub fn render_url_cleaning_settings(ui: &mut Ui, app: &mut LocalMindApp) {
+    ui.weak(tr("url_cleaning.intro"));
+    ui.add_space(6.0);
+
+    let label = ui.label(tr("url_cleaning.params"));
+    ui.add(
+        egui::TextEdit::multiline(&mut app.tracking_params_edit)
+            .desired_rows(3)
+            .desired_width(f32::INFINITY),
+    )
+    .labelled_by(label.id);
+
+    let parsed = tracking_params::parse_list(&app.tracking_params_edit);
+    match &parsed {
+        Ok(params) => {
+            ui.weak(tr_args(
+                "url_cleaning.example",
+                &[
+                    ("before", &EXAMPLE_URL),
+                    ("after", &tracking_params::strip(EXAMPLE_URL, params)),
+                ],
+            ));
+        }
+        Err(e) => {
+            ui.colored_label(ui.visuals().error_fg_color, e);
+        }
+    }
+
+    ui.add_space(6.0);
+    ui.horizontal(|ui| {
+        if ui
+            .add_enabled(parsed.is_ok(), egui::Button::new(tr("common.save")))
+            .clicked()
+        {
+            app.save_tracking_params();
+        }
+        if ui.button(tr("url_cleaning.reset")).clicked() {
+            app.tracking_params_edit = tracking_params::defaults().join(", ");
+        }
+    });
+}
+
+impl LocalMindApp {
+    /// Save the tracking parameters edited in Settings. New URLs are
+    /// cleaned with them at once; stored URLs are cleaned when shown.
+    pub fn save_tracking_params(&mut self) {
+        let params = match crate::tracking_params::parse_list(&self.tracking_params_edit) {
+            Ok(params) => params,
+            Err(e) => {
+                let id = self.next_toast_id();
+                self.add_toast(Toast::error(id, e));
+                return;
+            }
+        };
+        self.tracking_params_edit = params.join(", ");
+        self.tracking_params = params.clone();
+
+        let rag = self.rag.clone();
+        self.runtime.spawn(async move {
+            let rag_lock = rag.read().await;
+            if let Some(ref rag) = *rag_lock {
+                if let Err(e) = rag.db.set_tracking_params(&params).await {
+                    eprintln!("Failed to save tracking parameters: {}", e);
+                }
+            }
+        });
+
+        let id = self.next_toast_id();
+        self.add_toast(Toast::success(id, tr("url_cleaning.saved")));
+    }
+}
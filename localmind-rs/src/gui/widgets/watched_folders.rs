@@ -3,6 +3,7 @@
 //! Filled in during Phase 3 (T025-T026, T036, T038-T039).
 
 use crate::gui::app::LocalMindApp;
+use crate::gui::i18n::{tr, tr_args};
 use egui::Ui;
 
 /// Render the watched-folders management UI.
@@ -11,30 +12,30 @@ use egui::Ui;
 /// per-folder error lists, a progress bar during scanning, and
 /// Add / Remove controls.
 pub fn render_watched_folders(ui: &mut Ui, app: &mut LocalMindApp) {
-    ui.heading("Watched Folders");
+    ui.heading(tr("folders.title"));
     ui.add_space(6.0);
-    ui.weak("Add local folders to automatically index PDF, Markdown, and text files.");
+    ui.weak(tr("folders.intro"));
     ui.add_space(10.0);
 
     // --- Add folder input row ---
     ui.horizontal(|ui| {
-        ui.label("Folder path:");
-        let response = ui.text_edit_singleline(&mut app.add_folder_input);
-        let add_clicked = ui.button("Add").clicked();
+        let label = ui.label(tr("folders.path"));
+        let response = ui
+            .text_edit_singleline(&mut app.add_folder_input)
+            .labelled_by(label.id);
+        let add_clicked = ui.button(tr("common.add")).clicked();
         let enter_pressed = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
 
         if add_clicked || enter_pressed {
             let raw = app.add_folder_input.trim().trim_matches('\'').to_string();
             if raw.is_empty() {
-                app.add_folder_error = Some("Please enter a folder path.".to_string());
+                app.add_folder_error = Some(tr("folders.enter_path").to_string());
             } else {
                 let path = std::path::PathBuf::from(&raw);
                 if !path.exists() {
-                    app.add_folder_error =
-                        Some("Path does not exist. Check the path and try again.".to_string());
+                    app.add_folder_error = Some(tr("folders.not_found").to_string());
                 } else if !path.is_dir() {
-                    app.add_folder_error =
-                        Some("Path is not a directory. Select a folder, not a file.".to_string());
+                    app.add_folder_error = Some(tr("folders.not_directory").to_string());
                 } else {
                     // Clear error, send add command to service
                     app.add_folder_error = None;
@@ -59,7 +60,7 @@ pub fn render_watched_folders(ui: &mut Ui, app: &mut LocalMindApp) {
 
     // --- Watched folder list ---
     if app.watched_folders.is_empty() {
-        ui.weak("No folders are being watched. Add a folder above to get started.");
+        ui.weak(tr("folders.none"));
     } else {
         let folders = app.watched_folders.clone();
         for folder in &folders {
@@ -84,18 +85,23 @@ fn render_folder_row(
             ui.horizontal(|ui| {
                 // Status badge (T038)
                 let (badge_color, badge_text) = match &folder.status {
-                    FolderStatus::Active => (egui::Color32::from_rgb(60, 180, 80), "active"),
-                    FolderStatus::Unavailable => {
-                        (egui::Color32::from_rgb(200, 140, 40), "unavailable")
+                    FolderStatus::Active => {
+                        (egui::Color32::from_rgb(60, 180, 80), tr("folders.active"))
+                    }
+                    FolderStatus::Unavailable => (
+                        egui::Color32::from_rgb(200, 140, 40),
+                        tr("folders.unavailable"),
+                    ),
+                    FolderStatus::Error(_) => {
+                        (egui::Color32::from_rgb(200, 60, 60), tr("folders.error"))
                     }
-                    FolderStatus::Error(_) => (egui::Color32::from_rgb(200, 60, 60), "error"),
                 };
                 ui.colored_label(badge_color, format!("[{}]", badge_text));
                 ui.label(folder.path.display().to_string());
 
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                     // Remove button (T036)
-                    if ui.button("Remove").clicked() {
+                    if ui.button(tr("common.remove")).clicked() {
                         if let Some(ref tx) = app.remove_folder_tx {
                             let _ = tx.send(folder.path.clone());
                         }
@@ -112,7 +118,7 @@ fn render_folder_row(
                 };
                 ui.add(egui::ProgressBar::new(frac).show_percentage());
                 if let Some(ref cur) = progress.current_file {
-                    ui.weak(format!("Indexing: {}", cur.display()));
+                    ui.weak(tr_args("folders.indexing", &[("file", &cur.display())]));
                 }
             }
 
@@ -123,17 +129,20 @@ fn render_folder_row(
                 .cloned()
                 .unwrap_or_default();
             if !error_files.is_empty() {
-                ui.collapsing(format!("{} file error(s)", error_files.len()), |ui| {
-                    for (file, err) in &error_files {
-                        ui.horizontal(|ui| {
-                            ui.colored_label(
-                                egui::Color32::from_rgb(200, 60, 60),
-                                file.display().to_string(),
-                            );
-                            ui.weak(format!(": {}", err));
-                        });
-                    }
-                });
+                ui.collapsing(
+                    tr_args("folders.file_errors", &[("count", &error_files.len())]),
+                    |ui| {
+                        for (file, err) in &error_files {
+                            ui.horizontal(|ui| {
+                                ui.colored_label(
+                                    egui::Color32::from_rgb(200, 60, 60),
+                                    file.display().to_string(),
+                                );
+                                ui.weak(format!(": {}", err));
+                            });
+                        }
+                    },
+                );
             }
         });
 }
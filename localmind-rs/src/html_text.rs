@@ -0,0 +1,769 @@
+//! HTML to text, keeping links and headings
+//!
+//! Pages are indexed as plain text, but their markup says more than the
+//! words: anchors are the edges of the document link graph and headings
+//! mark where a page's sections start. `extract` walks the markup once and
+//! returns the text (one line per block, not wrapped, images as their alt
+//! text) together with a `PageStructure`: each anchor's text and target,
+//! and each heading's level, text, anchor and byte offset in the text. The
+//! structure is stored next to the document; chunking breaks at headings
+//! where it can, the document view shows them as an outline and passages
+//! found by search open the page at their section's anchor.
+//!
+//! Extracted text usually goes through more cleanup before it is stored, so
+//! `PageStructure::located` finds the headings again in the final content.
+//! PDFs have no markup but keep where each page starts (see
+//! `fetcher::pdf_text`), located the same way.
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// Most headings kept per page.
+pub const MAX_HEADINGS: usize = 200;
+
+/// Longest anchor or heading text kept, in characters.
+const MAX_LABEL_CHARS: usize = 200;
+
+/// A heading of the page
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Heading {
+    /// 1 for `<h1>` to 6 for `<h6>`
+    pub level: u8,
+    pub text: String,
+    /// Byte offset of the heading's line in the text
+    pub offset: usize,
+    /// Fragment linking to the heading on its page, e.g. `History` for
+    /// `#History`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub anchor: Option<String>,
+}
+
+/// An anchor of the page
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PageLink {
+    /// What the anchor reads; an image's alt text for image-only anchors
+    pub text: String,
+    /// Target, resolved against the page URL when it is known
+    pub href: String,
+}
+
+/// Where a page of a PDF starts
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PageStart {
+    /// Page number, from 1
+    pub number: u32,
+    /// The page's first line, to find it again after cleanup
+    pub first_line: String,
+    /// Byte offset of the page's first line in the text
+    pub offset: usize,
+}
+
+/// Headings and anchors of a page, or the pages of a PDF, stored as
+/// document metadata
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PageStructure {
+    pub headings: Vec<Heading>,
+    pub links: Vec<PageLink>,
+    #[serde(default)]
+    pub pages: Vec<PageStart>,
+}
+
+impl PageStructure {
+    pub fn is_empty(&self) -> bool {
+        self.headings.is_empty() && self.links.is_empty() && self.pages.is_empty()
+    }
+
+    /// Targets of the anchors, for the link graph.
+    pub fn hrefs(&self) -> Vec<String> {
+        self.links.iter().map(|link| link.href.clone()).collect()
+    }
+
+    /// The structure with each heading's and page's offset found in
+    /// `content`, where it must start a line; they are searched in order and
+    /// the ones cleaned away or cut off are dropped. Links are kept as they
+    /// are, up to `links::MAX_LINKS_PER_DOCUMENT`.
+    pub fn located(&self, content: &str) -> PageStructure {
+        let mut headings = Vec::new();
+        let mut from = 0;
+        for heading in &self.headings {
+            if let Some(offset) = find_line(content, from, &heading.text) {
+                from = offset + heading.text.len();
+                headings.push(Heading {
+                    offset,
+                    ..heading.clone()
+                });
+            }
+        }
+        headings.truncate(MAX_HEADINGS);
+
+        let mut pages = Vec::new();
+        let mut from = 0;
+        for page in &self.pages {
+            if let Some(offset) = find_line(content, from, &page.first_line) {
+                from = offset + page.first_line.len();
+                pages.push(PageStart {
+                    offset,
+                    ..page.clone()
+                });
+            }
+        }
+
+        let mut links = self.links.clone();
+        links.truncate(crate::links::MAX_LINKS_PER_DOCUMENT);
+        PageStructure {
+            headings,
+            links,
+            pages,
+        }
+    }
+
+    /// Offsets where the page's sections (or the PDF's pages) start, in
+    /// order, for chunking.
+    pub fn section_starts(&self) -> Vec<usize> {
+        let mut starts: Vec<usize> = self
+            .headings
+            .iter()
+            .map(|heading| heading.offset)
+            .chain(self.pages.iter().map(|page| page.offset))
+            .collect();
+        starts.sort_unstable();
+        starts.dedup();
+        starts
+    }
+
+    /// The number of the PDF page containing `offset`, if known.
+    pub fn page_at(&self, offset: usize) -> Option<u32> {
+        self.pages
+            .iter()
+            .take_while(|page| page.offset <= offset)
+            .last()
+            .map(|page| page.number)
+    }
+
+    /// The heading of the section containing `offset`: the last one
+    /// starting at or before it.
+    pub fn heading_at(&self, offset: usize) -> Option<&Heading> {
+        self.headings
+            .iter()
+            .take_while(|heading| heading.offset <= offset)
+            .last()
+    }
+}
+
+/// Offset of the first line of `content` at or after `from` that starts
+/// with `text`.
+fn find_line(content: &str, from: usize, text: &str) -> Option<usize> {
+    content[from..]
+        .match_indices(text)
+        .map(|(i, _)| from + i)
+        .find(|&offset| offset == 0 || content[..offset].ends_with('\n'))
+}
+
+/// Text of an HTML page or fragment, with its structure
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HtmlText {
+    pub text: String,
+    pub structure: PageStructure,
+}
+
+/// Elements whose content is never text.
+const SKIPPED: [&str; 8] = [
+    "script", "style", "noscript", "template", "head", "svg", "iframe", "object",
+];
+
+/// Elements starting a new paragraph.
+const PARAGRAPHS: [&str; 13] = [
+    "p",
+    "blockquote",
+    "pre",
+    "table",
+    "ul",
+    "ol",
+    "dl",
+    "figure",
+    "section",
+    "article",
+    "header",
+    "footer",
+    "aside",
+];
+
+/// Elements starting a new line.
+const LINES: [&str; 16] = [
+    "br",
+    "div",
+    "li",
+    "tr",
+    "dt",
+    "dd",
+    "hr",
+    "main",
+    "nav",
+    "form",
+    "details",
+    "summary",
+    "figcaption",
+    "address",
+    "caption",
+    "fieldset",
+];
+
+/// A heading being read
+struct OpenHeading {
+    level: u8,
+    /// Offset of its first text
+    start: Option<usize>,
+    text: String,
+    anchor: Option<String>,
+}
+
+/// Text built up while walking the markup
+#[derive(Default)]
+struct Builder {
+    text: String,
+    /// Newlines owed before the next text: 1 for a line, 2 for a paragraph
+    pending_break: usize,
+    pending_space: bool,
+    heading: Option<OpenHeading>,
+    /// Open anchor: target, text so far
+    link: Option<(String, String)>,
+    structure: PageStructure,
+}
+
+impl Builder {
+    fn block(&mut self, newlines: usize) {
+        self.pending_break = self.pending_break.max(newlines);
+        self.pending_space = false;
+    }
+
+    /// Append text, collapsing whitespace unless `preformatted`.
+    fn push(&mut self, text: &str, preformatted: bool) {
+        let trailing_space = !preformatted && text.ends_with(char::is_whitespace);
+        let collapsed;
+        let text = if preformatted {
+            text
+        } else {
+            if text.starts_with(char::is_whitespace) {
+                self.pending_space = true;
+            }
+            collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+            &collapsed
+        };
+        if text.is_empty() {
+            return;
+        }
+
+        if !self.text.is_empty() {
+            if self.pending_break > 0 {
+                let trimmed = self.text.trim_end_matches([' ', '\t']).len();
+                self.text.truncate(trimmed);
+                for _ in 0..self.pending_break {
+                    self.text.push('\n');
+                }
+            } else if self.pending_space && !self.text.ends_with(char::is_whitespace) {
+                self.text.push(' ');
+            }
+        }
+        let separated = self.pending_space && !self.text.is_empty();
+        self.pending_break = 0;
+        self.pending_space = false;
+
+        if let Some(heading) = self.heading.as_mut() {
+            heading.start.get_or_insert(self.text.len());
+            if separated && !heading.text.is_empty() {
+                heading.text.push(' ');
+            }
+            heading.text.push_str(text);
+        }
+        if let Some((_, link_text)) = self.link.as_mut() {
+            if separated && !link_text.is_empty() {
+                link_text.push(' ');
+            }
+            link_text.push_str(text);
+        }
+        self.text.push_str(text);
+        self.pending_space = trailing_space;
+    }
+
+    /// Add an image's alt text: inline within an anchor, which it names,
+    /// and otherwise on a line of its own, marked as an image.
+    fn image(&mut self, alt: &str) {
+        let alt = alt.split_whitespace().collect::<Vec<_>>().join(" ");
+        if alt.is_empty() {
+            return;
+        }
+        if self.link.is_some() {
+            self.pending_space = true;
+            self.push(&alt, false);
+        } else {
+            self.block(1);
+            self.push(&format!("[Image: {}]", alt), false);
+            self.block(1);
+        }
+    }
+
+    fn close_heading(&mut self) {
+        let Some(heading) = self.heading.take() else {
+            return;
+        };
+        let text = heading.text.trim();
+        if let (Some(offset), false) = (heading.start, text.is_empty()) {
+            self.structure.headings.push(Heading {
+                level: heading.level,
+                text: truncate_label(text),
+                offset,
+                anchor: heading.anchor,
+            });
+        }
+    }
+
+    fn close_link(&mut self) {
+        if let Some((href, text)) = self.link.take() {
+            self.structure.links.push(PageLink {
+                text: truncate_label(text.trim()),
+                href,
+            });
+        }
+    }
+}
+
+fn truncate_label(text: &str) -> String {
+    text.chars().take(MAX_LABEL_CHARS).collect()
+}
+
+/// Value of attribute `name` in a tag's attribute text.
+pub fn attribute(attributes: &str, name: &str) -> Option<String> {
+    let pattern = format!(
+        r#"(?is)(?:^|\s){}\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'>]+))"#,
+        regex::escape(name)
+    );
+    let caps = regex::Regex::new(&pattern).ok()?.captures(attributes)?;
+    let value = caps
+        .get(1)
+        .or_else(|| caps.get(2))
+        .or_else(|| caps.get(3))?
+        .as_str();
+    Some(decode_entities(value))
+}
+
+/// Where an anchor's `href` leads: resolved against `base` when known.
+/// Fragments within the page and script or mail links are skipped.
+fn link_target(href: &str, base: Option<&Url>) -> Option<String> {
+    let href = href.trim();
+    let scheme = href.split(':').next().unwrap_or_default().to_lowercase();
+    if href.is_empty()
+        || href.starts_with('#')
+        || matches!(scheme.as_str(), "javascript" | "mailto" | "tel" | "data")
+    {
+        return None;
+    }
+    match base {
+        Some(base) => base.join(href).ok().map(|url| url.to_string()),
+        None => Some(href.to_string()),
+    }
+}
+
+/// Decode named and numeric character references.
+pub fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        decoded.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let entity = rest[1..]
+            .find(';')
+            .filter(|&end| end > 0 && end <= 10)
+            .map(|end| &rest[1..=end]);
+        let character = entity.and_then(|entity| match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some('\u{a0}'),
+            "ndash" => Some('\u{2013}'),
+            "mdash" => Some('\u{2014}'),
+            "hellip" => Some('\u{2026}'),
+            "lsquo" => Some('\u{2018}'),
+            "rsquo" => Some('\u{2019}'),
+            "ldquo" => Some('\u{201c}'),
+            "rdquo" => Some('\u{201d}'),
+            "copy" => Some('\u{a9}'),
+            "reg" => Some('\u{ae}'),
+            "trade" => Some('\u{2122}'),
+            _ => {
+                let number = entity.strip_prefix('#')?;
+                let code = match number.strip_prefix(['x', 'X']) {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                    None => number.parse().ok()?,
+                };
+                char::from_u32(code)
+            }
+        });
+        match (entity, character) {
+            (Some(entity), Some(character)) => {
+                decoded.push(character);
+                rest = &rest[entity.len() + 2..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+/// Whether a tag's attribute text gives it class `class`.
+pub fn has_class(attributes: &str, class: &str) -> bool {
+    attribute(attributes, "class")
+        .is_some_and(|classes| classes.split_whitespace().any(|c| c == class))
+}
+
+/// An element of the markup
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Element<'a> {
+    /// Attribute text of the opening tag
+    pub attributes: &'a str,
+    /// Markup between the opening and closing tags; the rest of the page
+    /// when the element is never closed
+    pub inner: &'a str,
+}
+
+/// The `tag` elements whose attributes pass `wanted`, in document order;
+/// nested ones are found too. The closing tag is found by counting nested
+/// elements of the same name, so site extractors can pick out one `<div>`
+/// of a page without parsing all of it.
+pub fn elements<'a>(html: &'a str, tag: &str, wanted: impl Fn(&str) -> bool) -> Vec<Element<'a>> {
+    // ASCII lowercasing keeps byte offsets
+    let lower = html.to_ascii_lowercase();
+    let open = format!("<{}", tag.to_ascii_lowercase());
+    let close = format!("</{}", tag.to_ascii_lowercase());
+    let is_tag_end = |at: usize| {
+        lower[at..]
+            .chars()
+            .next()
+            .is_none_or(|c| c.is_ascii_whitespace() || c == '>' || c == '/')
+    };
+
+    let mut found = Vec::new();
+    let mut pos = 0;
+    while let Some(i) = lower[pos..].find(&open) {
+        let after = pos + i + open.len();
+        pos = after;
+        if !is_tag_end(after) {
+            continue;
+        }
+        let Some(gt) = html[after..].find('>') else {
+            break;
+        };
+        let attributes = &html[after..after + gt];
+        if !wanted(attributes) {
+            continue;
+        }
+
+        let start = after + gt + 1;
+        let mut depth = 1;
+        let mut at = start;
+        let mut end = html.len();
+        while let Some(j) = lower[at..].find('<') {
+            let lt = at + j;
+            at = lt + 1;
+            if lower[lt..].starts_with(&close) && is_tag_end(lt + close.len()) {
+                depth -= 1;
+                if depth == 0 {
+                    end = lt;
+                    break;
+                }
+            } else if lower[lt..].starts_with(&open) && is_tag_end(lt + open.len()) {
+                depth += 1;
+            }
+        }
+        found.push(Element {
+            attributes,
+            inner: &html[start..end],
+        });
+    }
+    found
+}
+
+/// Convert HTML to text with its headings and anchors. Block elements start
+/// new lines and paragraphs a blank line; list items start with "- ";
+/// `<pre>` keeps its line breaks. Images become their alt text, so pages
+/// made mostly of images (recipes, infographics) still have words to
+/// index; figure captions are kept as text. Scripts, styles and the
+/// `<head>` are dropped. Anchor targets are resolved against `base` when
+/// given.
+pub fn extract(html: &str, base: Option<&Url>) -> HtmlText {
+    // ASCII lowercasing keeps byte offsets, so tags are matched on `lower`
+    // and text is taken from `html`
+    let lower = html.to_ascii_lowercase();
+    let mut builder = Builder::default();
+    let mut preformatted = 0usize;
+    let mut pos = 0;
+
+    while pos < html.len() {
+        let Some(lt) = html[pos..].find('<').map(|i| pos + i) else {
+            builder.push(&decode_entities(&html[pos..]), preformatted > 0);
+            break;
+        };
+        if lt > pos {
+            builder.push(&decode_entities(&html[pos..lt]), preformatted > 0);
+        }
+
+        // Comments, doctypes and processing instructions
+        if lower[lt..].starts_with("<!--") {
+            pos = lower[lt..]
+                .find("-->")
+                .map_or(html.len(), |end| lt + end + 3);
+            continue;
+        }
+        let next = html[lt + 1..].chars().next();
+        if matches!(next, Some('!') | Some('?')) {
+            pos = html[lt..].find('>').map_or(html.len(), |end| lt + end + 1);
+            continue;
+        }
+        if !next.is_some_and(|c| c.is_ascii_alphabetic() || c == '/') {
+            // A bare '<' in text
+            builder.push("<", preformatted > 0);
+            pos = lt + 1;
+            continue;
+        }
+
+        let Some(gt) = html[lt..].find('>').map(|i| lt + i) else {
+            break;
+        };
+        let tag = &html[lt + 1..gt];
+        let closing = tag.starts_with('/');
+        let tag = tag.trim_start_matches('/');
+        let name_end = tag
+            .find(|c: char| !c.is_ascii_alphanumeric())
+            .unwrap_or(tag.len());
+        let name = tag[..name_end].to_ascii_lowercase();
+        let attributes = &tag[name_end..];
+        pos = gt + 1;
+
+        if !closing && SKIPPED.contains(&name.as_str()) {
+            let close = format!("</{}", name);
+            pos = lower[pos..]
+                .find(&close)
+                .and_then(|start| {
+                    lower[pos + start..]
+                        .find('>')
+                        .map(|end| pos + start + end + 1)
+                })
+                .unwrap_or(html.len());
+            continue;
+        }
+
+        // A heading's anchor is its own id, or else the first one inside
+        // it (Wikipedia's headline spans) or the first "#..." link inside
+        // it (the permalink icons of documentation generators)
+        if let (Some(heading), false) = (builder.heading.as_mut(), closing) {
+            if heading.anchor.is_none() {
+                heading.anchor = attribute(attributes, "id")
+                    .or_else(|| match name.as_str() {
+                        "a" => attribute(attributes, "name").or_else(|| {
+                            attribute(attributes, "href")?
+                                .strip_prefix('#')
+                                .map(str::to_string)
+                        }),
+                        _ => None,
+                    })
+                    .filter(|anchor| !anchor.trim().is_empty());
+            }
+        }
+
+        let heading_level = match name.as_bytes() {
+            [b'h', level @ b'1'..=b'6'] => Some(level - b'0'),
+            _ => None,
+        };
+        match (name.as_str(), closing) {
+            (_, false) if heading_level.is_some() => {
+                builder.close_heading();
+                builder.block(2);
+                builder.heading = Some(OpenHeading {
+                    level: heading_level.unwrap_or(1),
+                    start: None,
+                    text: String::new(),
+                    anchor: attribute(attributes, "id"),
+                });
+            }
+            (_, true) if heading_level.is_some() => {
+                builder.close_heading();
+                builder.block(2);
+            }
+            ("a", false) => {
+                builder.close_link();
+                builder.link = attribute(attributes, "href")
+                    .and_then(|href| link_target(&href, base))
+                    .map(|href| (href, String::new()));
+            }
+            ("a", true) => builder.close_link(),
+            ("img", false) => {
+                if let Some(alt) = attribute(attributes, "alt") {
+                    builder.image(&alt);
+                }
+            }
+            ("pre", false) => {
+                builder.block(2);
+                preformatted += 1;
+            }
+            ("pre", true) => {
+                builder.block(2);
+                preformatted = preformatted.saturating_sub(1);
+            }
+            ("li", false) => {
+                builder.block(1);
+                builder.push("- ", true);
+            }
+            ("td" | "th", _) => builder.pending_space = true,
+            (name, _) if PARAGRAPHS.contains(&name) => builder.block(2),
+            (name, _) if LINES.contains(&name) => builder.block(1),
+            _ => {}
+        }
+    }
+    builder.close_link();
+    builder.close_heading();
+
+    HtmlText {
+        text: builder.text.trim_end().to_string(),
+        structure: builder.structure,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_elements_by_attribute() {
+        let html = r#"<div id="a"><div class="post body">one<div>two</div></div><divider></div>
+            <DIV class="post">three</DIV>"#;
+        let posts = elements(html, "div", |attributes| has_class(attributes, "post"));
+        let inner: Vec<&str> = posts.iter().map(|post| post.inner).collect();
+        assert_eq!(inner, vec!["one<div>two</div>", "three"]);
+        assert_eq!(
+            elements(html, "div", |attributes| attribute(attributes, "id")
+                .as_deref()
+                == Some("a"))[0]
+                .inner,
+            r#"<div class="post body">one<div>two</div></div><divider>"#
+        );
+    }
+
+    #[test]
+    fn extracts_text_headings_and_links() {
+        let base = Url::parse("https://blog.example/posts/one").unwrap();
+        let html = r##"<html><head><title>Ignored</title><style>p{}</style></head>
+            <body>
+            <h1>Getting   started</h1>
+            <p>Read the <a href="/docs/install">install
+               guide</a> first &amp; then <a href="#top">go up</a>.</p>
+            <script>alert('x')</script>
+            <h2>Usage <small>v2</small></h2>
+            <ul><li>One</li><li>Two <a href='https://other.example/'>other</a></li></ul>
+            <pre>let x = 1;
+let y = 2;</pre>
+            </body></html>"##;
+
+        let page = extract(html, Some(&base));
+        assert_eq!(
+            page.text,
+            "Getting started\n\nRead the install guide first & then go up.\n\n\
+             Usage v2\n\n- One\n- Two other\n\nlet x = 1;\nlet y = 2;"
+        );
+        let headings: Vec<(u8, &str)> = page
+            .structure
+            .headings
+            .iter()
+            .map(|h| (h.level, h.text.as_str()))
+            .collect();
+        assert_eq!(headings, vec![(1, "Getting started"), (2, "Usage v2")]);
+        for heading in &page.structure.headings {
+            assert!(page.text[heading.offset..].starts_with(&heading.text));
+        }
+        assert_eq!(
+            page.structure.links,
+            vec![
+                PageLink {
+                    text: "install guide".to_string(),
+                    href: "https://blog.example/docs/install".to_string(),
+                },
+                PageLink {
+                    text: "other".to_string(),
+                    href: "https://other.example/".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_image_alt_text_and_captions() {
+        let page = extract(
+            r#"<p>Chocolate cake</p><figure><img src="cake.jpg" alt="A  sliced cake">
+            <figcaption>Serves eight</figcaption></figure>
+            <a href="/home"><img src="logo.png" alt="Home"/></a><img src="spacer.gif" alt="">"#,
+            None,
+        );
+        assert_eq!(
+            page.text,
+            "Chocolate cake\n\n[Image: A sliced cake]\nServes eight\n\nHome"
+        );
+        assert_eq!(
+            page.structure.links,
+            vec![PageLink {
+                text: "Home".to_string(),
+                href: "/home".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn decodes_entities() {
+        assert_eq!(
+            decode_entities("a &lt;b&gt; &#233;t&#xE9; &rsquo; &bogus; & c"),
+            "a <b> été \u{2019} &bogus; & c"
+        );
+    }
+
+    #[test]
+    fn locates_headings_in_cleaned_content() {
+        let page = extract(
+            "<h1>Intro</h1><p>Intro text</p><h2>Details</h2><p>More</p><h2>Gone</h2>",
+            None,
+        );
+        let content = "Title\nIntro\nIntro text\nDetails\nMore";
+        let located = page.structure.located(content);
+        assert_eq!(located.section_starts(), vec![6, 23]);
+        assert_eq!(located.headings[1].text, "Details");
+        assert!(located.heading_at(2).is_none());
+        assert_eq!(located.heading_at(12).unwrap().text, "Intro");
+        assert_eq!(located.heading_at(23).unwrap().text, "Details");
+    }
+
+    #[test]
+    fn records_heading_anchors() {
+        let page = extract(
+            r##"<h2 id="usage">Usage</h2>
+            <h2><span class="mw-headline" id="History">History</span><a href="/edit">edit</a></h2>
+            <h3>Install<a class="headerlink" href="#install">¶</a></h3>
+            <h3>Plain</h3>"##,
+            None,
+        );
+        let anchors: Vec<Option<&str>> = page
+            .structure
+            .headings
+            .iter()
+            .map(|heading| heading.anchor.as_deref())
+            .collect();
+        assert_eq!(
+            anchors,
+            vec![Some("usage"), Some("History"), Some("install"), None]
+        );
+    }
+}
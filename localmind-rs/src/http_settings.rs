@@ -0,0 +1,150 @@
+//! Port and bind address of the HTTP API
+//!
+//! By default the API listens on localhost at the first free port from 3000
+//! to 3010, where the extension looks for it. Settings can fix the port and
+//! change the address it binds to, in which case it is served over HTTPS
+//! with the self-signed certificate (see `tls`); a fixed port that is taken is reported
+//! instead of falling back to another one. The server checks the settings
+//! every few seconds and rebinds when they change. `HttpServerStatus` tells
+//! the GUI where it is listening, or why it could not. Browsers may only
+//! call the API from the extension: requests from any other origin are
+//! refused.
+
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::ops::RangeInclusive;
+use std::sync::{Arc, Mutex};
+
+/// Ports tried in turn when no port is fixed.
+pub const DEFAULT_PORTS: RangeInclusive<u16> = 3000..=3010;
+
+/// HTTP API settings edited in Settings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HttpServerSettings {
+    /// Port to listen on; `None` scans `DEFAULT_PORTS`
+    pub port: Option<u16>,
+    /// Address to bind to, e.g. "127.0.0.1" or "0.0.0.0"
+    pub bind_address: String,
+}
+
+impl Default for HttpServerSettings {
+    fn default() -> Self {
+        Self {
+            port: None,
+            bind_address: Ipv4Addr::LOCALHOST.to_string(),
+        }
+    }
+}
+
+impl HttpServerSettings {
+    /// The bind address, or localhost when it does not parse.
+    pub fn ip(&self) -> IpAddr {
+        parse_bind_address(&self.bind_address).unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST))
+    }
+
+    /// Ports to try, in order.
+    pub fn ports(&self) -> RangeInclusive<u16> {
+        match self.port {
+            Some(port) => port..=port,
+            None => DEFAULT_PORTS,
+        }
+    }
+
+    /// Whether only this machine can reach the API.
+    pub fn is_local_only(&self) -> bool {
+        self.ip().is_loopback()
+    }
+
+    /// Whether the API is served over HTTPS, which it is whenever other
+    /// machines can reach it.
+    pub fn uses_tls(&self) -> bool {
+        !self.is_local_only()
+    }
+
+    /// URL scheme the API is served with.
+    pub fn scheme(&self) -> &'static str {
+        if self.uses_tls() {
+            "https"
+        } else {
+            "http"
+        }
+    }
+}
+
+/// An IP address typed in Settings; `None` when it is not one.
+pub fn parse_bind_address(input: &str) -> Option<IpAddr> {
+    input.trim().parse().ok()
+}
+
+/// Whether a browser request with this `Origin` header may use the API;
+/// only the extension's pages may.
+pub fn is_allowed_origin(origin: &str) -> bool {
+    origin
+        .strip_prefix("chrome-extension://")
+        .is_some_and(|id| !id.is_empty())
+}
+
+/// Where the HTTP API is listening, shared between the server and the GUI.
+#[derive(Clone, Default)]
+pub struct HttpServerStatus {
+    state: Arc<Mutex<Option<Result<SocketAddr, String>>>>,
+}
+
+impl HttpServerStatus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_listening(&self, address: SocketAddr) {
+        *self.state.lock().unwrap() = Some(Ok(address));
+    }
+
+    pub fn set_failed(&self, error: String) {
+        *self.state.lock().unwrap() = Some(Err(error));
+    }
+
+    /// The address listened on or why binding failed; `None` before the
+    /// server first tried.
+    pub fn get(&self) -> Option<Result<SocketAddr, String>> {
+        self.state.lock().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_to_scanning_localhost() {
+        let settings = HttpServerSettings::default();
+        assert!(settings.is_local_only());
+        assert_eq!(settings.ports(), DEFAULT_PORTS);
+
+        let fixed = HttpServerSettings {
+            port: Some(4000),
+            bind_address: "0.0.0.0".to_string(),
+        };
+        assert!(!fixed.is_local_only());
+        assert_eq!(fixed.scheme(), "https");
+        assert_eq!(settings.scheme(), "http");
+        assert_eq!(fixed.ports(), 4000..=4000);
+
+        let stored: HttpServerSettings = serde_json::from_str(r#"{"port":4000}"#).unwrap();
+        assert_eq!(stored.bind_address, "127.0.0.1");
+        assert_eq!(
+            parse_bind_address(" ::1 "),
+            Some(IpAddr::from([0u16, 0, 0, 0, 0, 0, 0, 1]))
+        );
+        assert_eq!(parse_bind_address("localhost"), None);
+    }
+
+    #[test]
+    fn only_the_extension_is_an_allowed_origin() {
+        assert!(is_allowed_origin("chrome-extension://abcdefghijklmnop"));
+        assert!(!is_allowed_origin("chrome-extension://"));
+        assert!(!is_allowed_origin("https://example.com"));
+        assert!(!is_allowed_origin("http://localhost:3000"));
+        assert!(!is_allowed_origin("null"));
+    }
+}
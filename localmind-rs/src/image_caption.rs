@@ -0,0 +1,151 @@
+//! Captions for bookmarked images
+//!
+//! A URL pointing straight at an image has no text to index, so it used to
+//! be stored as a "Binary file" placeholder. When image captions are turned
+//! on in Settings, the image is described by a local vision model served by
+//! Ollama (e.g. `moondream` or `llava`) and the description is indexed
+//! instead. Images inside web pages are indexed by their alt text and
+//! captions (see `html_text`) and never sent to the model.
+
+use crate::ollama::DEFAULT_OLLAMA_URL;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Vision model suggested in Settings.
+pub const DEFAULT_CAPTION_MODEL: &str = "moondream";
+
+/// Largest image sent to the model, in bytes.
+pub const MAX_IMAGE_BYTES: usize = 20 * 1024 * 1024;
+
+/// Vision models are slow on a CPU, so a caption may take a while.
+const CAPTION_TIMEOUT: Duration = Duration::from_secs(120);
+
+const PROMPT: &str = "Describe this image in a few sentences for a search index. \
+Mention any text it contains.";
+
+/// Image caption settings edited in Settings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImageCaptionSettings {
+    pub enabled: bool,
+    /// Ollama server running the vision model
+    pub url: String,
+    pub model: String,
+}
+
+impl Default for ImageCaptionSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: DEFAULT_OLLAMA_URL.to_string(),
+            model: DEFAULT_CAPTION_MODEL.to_string(),
+        }
+    }
+}
+
+impl ImageCaptionSettings {
+    /// Whether captions are on and have a model to ask.
+    pub fn is_active(&self) -> bool {
+        self.enabled && !self.url.trim().is_empty() && !self.model.trim().is_empty()
+    }
+}
+
+/// Request payload for `POST /api/generate`
+#[derive(Debug, Serialize)]
+struct GenerateRequest<'a> {
+    model: &'a str,
+    prompt: &'a str,
+    images: Vec<String>,
+    stream: bool,
+}
+
+/// Response payload from `POST /api/generate`
+#[derive(Debug, Deserialize)]
+struct GenerateResponse {
+    response: String,
+}
+
+/// Describe an image with the vision model in `settings`. Returns
+/// `Ok(None)` when captions are off or the image is too large.
+pub async fn caption(
+    image: &[u8],
+    settings: &ImageCaptionSettings,
+) -> crate::Result<Option<String>> {
+    if !settings.is_active() || image.is_empty() || image.len() > MAX_IMAGE_BYTES {
+        return Ok(None);
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(CAPTION_TIMEOUT)
+        .build()?;
+    let url = format!("{}/api/generate", settings.url.trim_end_matches('/'));
+    let response = client
+        .post(&url)
+        .json(&GenerateRequest {
+            model: settings.model.trim(),
+            prompt: PROMPT,
+            images: vec![base64(image)],
+            stream: false,
+        })
+        .send()
+        .await
+        .map_err(|e| format!("Failed to connect to Ollama at {}: {}", settings.url, e))?;
+    if !response.status().is_success() {
+        return Err(format!("Image caption failed with status: {}", response.status()).into());
+    }
+    let generated: GenerateResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse image caption: {}", e))?;
+    let caption = generated.response.trim();
+    Ok((!caption.is_empty()).then(|| caption.to_string()))
+}
+
+/// Standard base64 with padding, as Ollama expects images.
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for group in bytes.chunks(3) {
+        let n = group
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | (u32::from(b) << (16 - 8 * i)));
+        for i in 0..4 {
+            if i <= group.len() {
+                encoded.push(ALPHABET[((n >> (18 - 6 * i)) & 63) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base64_pads_partial_groups() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+        assert_eq!(base64(&[0xff, 0xfe]), "//4=");
+    }
+
+    #[test]
+    fn captions_need_a_model() {
+        assert!(!ImageCaptionSettings::default().is_active());
+        let settings = ImageCaptionSettings {
+            enabled: true,
+            ..Default::default()
+        };
+        assert!(settings.is_active());
+        assert!(!ImageCaptionSettings {
+            model: " ".to_string(),
+            ..settings
+        }
+        .is_active());
+    }
+}
@@ -0,0 +1,260 @@
+//! Background ingestion jobs for the HTTP API
+//!
+//! `POST /api/v1/documents?async=true` validates the request, records a job
+//! here and returns 202 straight away; the document is then chunked and
+//! embedded in the background. The extension polls `GET /api/v1/jobs/:id`
+//! for the outcome.
+//! Jobs live in memory only: a restart forgets them, which is fine for a
+//! status that is polled for a few seconds.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// How long a finished job can still be polled.
+const FINISHED_RETENTION: Duration = Duration::from_secs(60 * 60);
+
+/// Upper bound on remembered jobs; the oldest finished ones go first.
+const MAX_JOBS: usize = 500;
+
+/// Where a job is in its lifecycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Done,
+    Failed,
+}
+
+impl JobStatus {
+    pub fn is_finished(self) -> bool {
+        matches!(self, JobStatus::Done | JobStatus::Failed)
+    }
+}
+
+/// What saving a document did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SaveStatus {
+    /// A new document was stored
+    Created,
+    /// The URL was already saved; its title or content changed and it was
+    /// re-indexed
+    Updated,
+    /// The URL was already saved with the same title and content
+    AlreadyExists,
+}
+
+/// The document a save landed on, so clients can say e.g. "already saved
+/// 3 weeks ago". Times are UTC, "YYYY-MM-DD HH:MM:SS".
+#[derive(Debug, Clone, PartialEq, Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedDocument {
+    pub status: SaveStatus,
+    pub document_id: i64,
+    /// When the document was first saved, for an existing URL
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub saved_at: Option<String>,
+    /// When the existing document was last indexed, before this save
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_indexed_at: Option<String>,
+}
+
+/// Status of one ingestion job, as returned by `GET /api/v1/jobs/:id`.
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct IngestJob {
+    pub id: String,
+    pub status: JobStatus,
+    pub title: String,
+    pub extraction_method: String,
+    /// Outcome message once the job is done
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// Failure reason once the job has failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// The stored document once the job is done
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub saved: Option<SavedDocument>,
+    #[serde(skip)]
+    updated_at: Instant,
+}
+
+/// Shared, cloneable registry of ingestion jobs.
+#[derive(Clone, Default)]
+pub struct JobRegistry {
+    jobs: Arc<Mutex<HashMap<String, IngestJob>>>,
+}
+
+impl JobRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a queued job and return its id.
+    pub fn create(&self, title: &str, extraction_method: &str) -> String {
+        let id = uuid::Uuid::new_v4().to_string();
+        let job = IngestJob {
+            id: id.clone(),
+            status: JobStatus::Queued,
+            title: title.chars().take(200).collect(),
+            extraction_method: extraction_method.to_string(),
+            message: None,
+            error: None,
+            saved: None,
+            updated_at: Instant::now(),
+        };
+
+        let mut jobs = self.jobs.lock().unwrap();
+        prune(&mut jobs);
+        jobs.insert(id.clone(), job);
+        id
+    }
+
+    pub fn get(&self, id: &str) -> Option<IngestJob> {
+        self.jobs.lock().unwrap().get(id).cloned()
+    }
+
+    pub fn mark_running(&self, id: &str) {
+        self.update(id, |job| job.status = JobStatus::Running);
+    }
+
+    pub fn mark_done(&self, id: &str, message: &str) {
+        self.update(id, |job| {
+            job.status = JobStatus::Done;
+            job.message = Some(message.to_string());
+        });
+    }
+
+    /// Mark a job done with the document it saved.
+    pub fn mark_saved(&self, id: &str, message: &str, saved: SavedDocument) {
+        self.update(id, |job| {
+            job.status = JobStatus::Done;
+            job.message = Some(message.to_string());
+            job.saved = Some(saved);
+        });
+    }
+
+    pub fn mark_failed(&self, id: &str, error: &str) {
+        self.update(id, |job| {
+            job.status = JobStatus::Failed;
+            job.error = Some(error.to_string());
+        });
+    }
+
+    /// Jobs not finished yet.
+    pub fn pending_count(&self) -> usize {
+        self.jobs
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|job| !job.status.is_finished())
+            .count()
+    }
+
+    fn update(&self, id: &str, apply: impl FnOnce(&mut IngestJob)) {
+        if let Some(job) = self.jobs.lock().unwrap().get_mut(id) {
+            apply(job);
+            job.updated_at = Instant::now();
+        }
+    }
+}
+
+/// Drop finished jobs past their retention, then the oldest finished jobs
+/// while over `MAX_JOBS`. Unfinished jobs are never dropped.
+fn prune(jobs: &mut HashMap<String, IngestJob>) {
+    jobs.retain(|_, job| {
+        !job.status.is_finished() || job.updated_at.elapsed() < FINISHED_RETENTION
+    });
+
+    while jobs.len() >= MAX_JOBS {
+        let oldest = jobs
+            .values()
+            .filter(|job| job.status.is_finished())
+            .min_by_key(|job| job.updated_at)
+            .map(|job| job.id.clone());
+        match oldest {
+            Some(id) => {
+                jobs.remove(&id);
+            }
+            None => break,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracks_job_lifecycle() {
+        let registry = JobRegistry::new();
+        let id = registry.create("A page", "readability");
+
+        let job = registry.get(&id).unwrap();
+        assert_eq!(job.status, JobStatus::Queued);
+        assert_eq!(registry.pending_count(), 1);
+
+        registry.mark_running(&id);
+        assert_eq!(registry.get(&id).unwrap().status, JobStatus::Running);
+
+        registry.mark_done(&id, "Document added successfully.");
+        let job = registry.get(&id).unwrap();
+        assert_eq!(job.status, JobStatus::Done);
+        assert_eq!(job.message.as_deref(), Some("Document added successfully."));
+        assert_eq!(registry.pending_count(), 0);
+
+        let json = serde_json::to_value(&job).unwrap();
+        assert_eq!(json["status"], "done");
+        assert_eq!(json["extractionMethod"], "readability");
+        assert!(json.get("error").is_none());
+
+        let failed = registry.create("Other", "dom");
+        registry.mark_failed(&failed, "embedding failed");
+        assert_eq!(
+            registry.get(&failed).unwrap().error.as_deref(),
+            Some("embedding failed")
+        );
+        assert!(registry.get("unknown").is_none());
+    }
+
+    #[test]
+    fn saved_jobs_report_the_document() {
+        let registry = JobRegistry::new();
+        let id = registry.create("A page", "dom");
+        registry.mark_saved(
+            &id,
+            "Already saved.",
+            SavedDocument {
+                status: SaveStatus::AlreadyExists,
+                document_id: 7,
+                saved_at: Some("2026-09-25 10:00:00".to_string()),
+                last_indexed_at: None,
+            },
+        );
+
+        let json = serde_json::to_value(registry.get(&id).unwrap()).unwrap();
+        assert_eq!(json["status"], "done");
+        assert_eq!(json["saved"]["status"], "already_exists");
+        assert_eq!(json["saved"]["documentId"], 7);
+        assert_eq!(json["saved"]["savedAt"], "2026-09-25 10:00:00");
+        assert!(json["saved"].get("lastIndexedAt").is_none());
+    }
+
+    #[test]
+    fn prunes_oldest_finished_jobs_only() {
+        let registry = JobRegistry::new();
+        let pending = registry.create("Pending", "dom");
+        for i in 0..MAX_JOBS + 10 {
+            let id = registry.create(&format!("Job {}", i), "dom");
+            registry.mark_done(&id, "ok");
+        }
+
+        let jobs = registry.jobs.lock().unwrap();
+        assert!(jobs.len() <= MAX_JOBS);
+        assert!(jobs.contains_key(&pending));
+    }
+}
@@ -0,0 +1,254 @@
+//! Per-source ingestion policies
+//!
+//! Each kind of source (bookmarks, the browser extension, feeds, local
+//! files) has an `IngestPolicy` saying whether pages are fetched, how much
+//! content is kept, whether a summary is stored and which tags new documents
+//! get. Policies are edited in Settings, stored in the config table and
+//! consulted by `RagPipeline::ingest_document`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Sentences kept in an automatic summary.
+pub const SUMMARY_SENTENCES: usize = 3;
+
+/// A kind of source with its own ingestion policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SourceKind {
+    /// Chrome and Safari bookmarks
+    Bookmarks,
+    /// Pages saved from the browser extension
+    Extension,
+    /// RSS and Atom feeds
+    Feeds,
+    /// Watched folders and workspace exports
+    LocalFiles,
+}
+
+impl SourceKind {
+    pub const ALL: [SourceKind; 4] = [
+        SourceKind::Bookmarks,
+        SourceKind::Extension,
+        SourceKind::Feeds,
+        SourceKind::LocalFiles,
+    ];
+
+    /// The kind of a document's `source` column value, if it has one.
+    /// Watched folders record their path as the source.
+    pub fn of(source: &str) -> Option<Self> {
+        match source {
+            "chrome_bookmark" => Some(SourceKind::Bookmarks),
+            "chrome_extension" => Some(SourceKind::Extension),
+            crate::workspace_import::NOTION_SOURCE | crate::workspace_import::CONFLUENCE_SOURCE => {
+                Some(SourceKind::LocalFiles)
+            }
+            s if s.starts_with("feed") || s.starts_with("rss") => Some(SourceKind::Feeds),
+            s if std::path::Path::new(s).is_absolute() => Some(SourceKind::LocalFiles),
+            _ => None,
+        }
+    }
+
+    /// Key the policy is stored under in the config table.
+    pub fn config_key(self) -> &'static str {
+        match self {
+            SourceKind::Bookmarks => "ingest_policy_bookmarks",
+            SourceKind::Extension => "ingest_policy_extension",
+            SourceKind::Feeds => "ingest_policy_feeds",
+            SourceKind::LocalFiles => "ingest_policy_local_files",
+        }
+    }
+
+    /// Whether documents of this kind are fetched from the web, so the
+    /// fetch setting applies.
+    pub fn fetches_remote(self) -> bool {
+        matches!(self, SourceKind::Bookmarks | SourceKind::Feeds)
+    }
+}
+
+/// How documents from one kind of source are ingested.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IngestPolicy {
+    /// Fetch the page behind a URL; otherwise only its title and URL are
+    /// indexed
+    pub fetch_remote: bool,
+    /// Content is cut off after this many characters; 0 keeps everything
+    pub max_content_chars: usize,
+    /// Store an extractive summary with each new document
+    pub auto_summarize: bool,
+    /// Tags given to each new document
+    pub default_tags: Vec<String>,
+}
+
+impl Default for IngestPolicy {
+    fn default() -> Self {
+        Self {
+            fetch_remote: true,
+            max_content_chars: 0,
+            auto_summarize: false,
+            default_tags: Vec::new(),
+        }
+    }
+}
+
+impl IngestPolicy {
+    /// `content` cut to `max_content_chars`, on a character boundary.
+    pub fn limit_content<'a>(&self, content: &'a str) -> &'a str {
+        if self.max_content_chars == 0 {
+            return content;
+        }
+        match content.char_indices().nth(self.max_content_chars) {
+            Some((end, _)) => &content[..end],
+            None => content,
+        }
+    }
+
+    /// Parse a comma-separated tag list as typed in Settings.
+    pub fn parse_tags(text: &str) -> Vec<String> {
+        let mut tags: Vec<String> = Vec::new();
+        for tag in text.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+            if !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                tags.push(tag.to_string());
+            }
+        }
+        tags
+    }
+}
+
+/// The policy of every source kind; kinds never configured use the default.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IngestPolicies {
+    policies: HashMap<SourceKind, IngestPolicy>,
+}
+
+impl IngestPolicies {
+    pub fn get(&self, kind: SourceKind) -> IngestPolicy {
+        self.policies.get(&kind).cloned().unwrap_or_default()
+    }
+
+    pub fn set(&mut self, kind: SourceKind, policy: IngestPolicy) {
+        self.policies.insert(kind, policy);
+    }
+}
+
+/// Extractive summary: the `SUMMARY_SENTENCES` sentences whose words are
+/// most frequent across the document, in document order. Empty for text
+/// with no sentences.
+pub fn summarize(content: &str) -> String {
+    let sentences: Vec<&str> = content
+        .split_inclusive(['.', '!', '?', '\n'])
+        .map(str::trim)
+        .filter(|s| s.split_whitespace().count() >= 4)
+        .collect();
+    if sentences.len() <= SUMMARY_SENTENCES {
+        return sentences.join(" ");
+    }
+
+    let words = |text: &str| -> Vec<String> {
+        text.split(|c: char| !c.is_alphanumeric())
+            .filter(|w| w.chars().count() >= 4)
+            .map(str::to_lowercase)
+            .collect()
+    };
+    let mut frequency: HashMap<String, usize> = HashMap::new();
+    for word in words(content) {
+        *frequency.entry(word).or_insert(0) += 1;
+    }
+
+    let mut scored: Vec<(usize, f32)> = sentences
+        .iter()
+        .enumerate()
+        .map(|(i, sentence)| {
+            let sentence_words = words(sentence);
+            let total: usize = sentence_words.iter().map(|w| frequency[w]).sum();
+            (
+                i,
+                total as f32 / (sentence_words.len().max(1) as f32).sqrt(),
+            )
+        })
+        .collect();
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    let mut chosen: Vec<usize> = scored
+        .into_iter()
+        .take(SUMMARY_SENTENCES)
+        .map(|(i, _)| i)
+        .collect();
+    chosen.sort_unstable();
+    chosen
+        .into_iter()
+        .map(|i| sentences[i])
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_sources() {
+        assert_eq!(
+            SourceKind::of("chrome_bookmark"),
+            Some(SourceKind::Bookmarks)
+        );
+        assert_eq!(
+            SourceKind::of("chrome_extension"),
+            Some(SourceKind::Extension)
+        );
+        assert_eq!(SourceKind::of("rss"), Some(SourceKind::Feeds));
+        assert_eq!(
+            SourceKind::of("notion_import"),
+            Some(SourceKind::LocalFiles)
+        );
+        #[cfg(unix)]
+        assert_eq!(
+            SourceKind::of("/home/me/notes"),
+            Some(SourceKind::LocalFiles)
+        );
+        assert_eq!(SourceKind::of("cli"), None);
+    }
+
+    #[test]
+    fn limits_content_and_parses_tags() {
+        let mut policy = IngestPolicy::default();
+        assert_eq!(policy.limit_content("Café au lait"), "Café au lait");
+        policy.max_content_chars = 4;
+        assert_eq!(policy.limit_content("Café au lait"), "Café");
+        assert_eq!(policy.limit_content("Caf"), "Caf");
+
+        assert_eq!(
+            IngestPolicy::parse_tags(" reading, Rust ,, rust,web "),
+            vec!["reading", "Rust", "web"]
+        );
+
+        let stored: IngestPolicy = serde_json::from_str(r#"{"auto_summarize":true}"#).unwrap();
+        assert!(stored.fetch_remote);
+        assert!(stored.auto_summarize);
+
+        let mut policies = IngestPolicies::default();
+        policies.set(SourceKind::Feeds, stored.clone());
+        assert_eq!(policies.get(SourceKind::Feeds), stored);
+        assert_eq!(policies.get(SourceKind::Bookmarks), IngestPolicy::default());
+    }
+
+    #[test]
+    fn summary_keeps_the_most_representative_sentences_in_order() {
+        let text = "Tokio is an async runtime for Rust programs. \
+                    The weather was pleasant that afternoon outside. \
+                    Tokio schedules async tasks across worker threads. \
+                    Async tasks in Tokio yield at await points. \
+                    Lunch was a sandwich with some cheese inside.";
+        assert_eq!(
+            summarize(text),
+            "Tokio is an async runtime for Rust programs. \
+             Tokio schedules async tasks across worker threads. \
+             Async tasks in Tokio yield at await points."
+        );
+        assert_eq!(summarize("Too short."), "");
+        assert_eq!(
+            summarize("Only one real sentence here."),
+            "Only one real sentence here."
+        );
+    }
+}
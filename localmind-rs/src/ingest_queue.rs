@@ -0,0 +1,205 @@
+//! Persistent bookmark ingestion queue
+//!
+//! Bookmarks discovered at startup are written to the `ingest_queue` table
+//! before any network work happens. Each item then moves through
+//! `pending -> in_progress -> done | failed`, so closing the app partway
+//! through a large import resumes from the first unfinished item instead of
+//! re-checking every URL.
+
+use crate::bookmark::{fetch_bookmark_page, BookmarkContent};
+use crate::html_text::PageStructure;
+use crate::ingest_policy::SourceKind;
+use crate::rag::RagPipeline;
+
+/// Number of attempts after which a failed item is no longer picked up
+/// automatically. Failed items remain in the queue for inspection.
+pub const MAX_ATTEMPTS: i64 = 3;
+
+/// Processing state of a single queue item.
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueueStatus {
+    Pending,
+    InProgress,
+    Done,
+    Failed,
+}
+
+impl QueueStatus {
+    /// Convert to the string stored in the database.
+    pub fn as_db_str(&self) -> &str {
+        match self {
+            QueueStatus::Pending => "pending",
+            QueueStatus::InProgress => "in_progress",
+            QueueStatus::Done => "done",
+            QueueStatus::Failed => "failed",
+        }
+    }
+
+    /// Parse from a database status string. Unknown values are treated as pending.
+    pub fn from_db_str(s: &str) -> Self {
+        match s {
+            "in_progress" => QueueStatus::InProgress,
+            "done" => QueueStatus::Done,
+            "failed" => QueueStatus::Failed,
+            _ => QueueStatus::Pending,
+        }
+    }
+}
+
+/// A bookmark waiting to be (or already) fetched and ingested.
+#[derive(Debug, Clone)]
+pub struct QueueItem {
+    pub id: i64,
+    pub url: String,
+    pub title: String,
+    /// Chrome profile display name the bookmark came from
+    pub profile: Option<String>,
+    pub status: QueueStatus,
+    pub attempts: i64,
+    pub last_error: Option<String>,
+}
+
+/// A bookmark that could not be fetched, kept out of the index and shown in
+/// the failed-imports report so the user can retry or ignore it.
+#[derive(Debug, Clone)]
+pub struct IngestError {
+    pub id: i64,
+    pub url: String,
+    pub title: String,
+    pub profile: Option<String>,
+    pub error: String,
+    pub attempts: i64,
+    pub updated_at: String,
+}
+
+/// Result of processing a single queue item.
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueueOutcome {
+    /// A document with this URL was already indexed
+    AlreadyIndexed,
+    /// The bookmark was fetched and ingested
+    Ingested,
+    /// Fetching or ingesting failed; the error is recorded in `ingest_errors`
+    Failed(String),
+}
+
+/// Fetch and ingest one queued bookmark, updating its queue status.
+///
+/// Fetch failures are recorded in `ingest_errors` rather than being indexed as
+/// placeholder documents. A successful ingest clears any previous error.
+pub async fn process_queue_item(rag: &RagPipeline, item: &QueueItem) -> QueueOutcome {
+    if rag.document_exists(&item.url).await.unwrap_or(false) {
+        let _ = rag
+            .db
+            .set_queue_item_status(item.id, &QueueStatus::Done, None)
+            .await;
+        let _ = rag.db.clear_ingest_error(&item.url).await;
+        return QueueOutcome::AlreadyIndexed;
+    }
+
+    let _ = rag
+        .db
+        .set_queue_item_status(item.id, &QueueStatus::InProgress, None)
+        .await;
+
+    // With fetching turned off in the bookmarks policy, the bookmark is
+    // indexed by its title and URL alone
+    let fetch_remote = rag
+        .db
+        .get_ingest_policy(SourceKind::Bookmarks)
+        .await
+        .map(|policy| policy.fetch_remote)
+        .unwrap_or(true);
+    let page = if fetch_remote {
+        fetch_bookmark_page(&item.url, &rag.db.cached_settings().image_captions).await
+    } else {
+        Ok(BookmarkContent {
+            content: item.url.clone(),
+            needs_auth: false,
+            structure: PageStructure::default(),
+            image_url: None,
+            title: None,
+        })
+    };
+
+    let result = match page {
+        Ok(page) => {
+            // Always prepend title so it gets embedded and is searchable
+            let content = format!("{}\n\n{}", item.title, page.content);
+            let ingested = rag
+                .ingest_document_with_auth(
+                    &item.title,
+                    &content,
+                    Some(&item.url),
+                    "chrome_bookmark",
+                    item.profile.as_deref(),
+                    page.needs_auth,
+                    Some(&page.structure),
+                )
+                .await
+                .map_err(|e| format!("Ingest failed: {}", e));
+            if let Ok(doc_id) = ingested {
+                if let Err(e) = rag
+                    .store_document_links(
+                        doc_id,
+                        Some(&item.url),
+                        &content,
+                        &page.structure.hrefs(),
+                    )
+                    .await
+                {
+                    eprintln!("Failed to store links for '{}': {}", item.title, e);
+                }
+                crate::thumbnail::record_preview_image(&rag.db, doc_id, page.image_url.as_deref())
+                    .await;
+            }
+            ingested
+        }
+        Err(e) => Err(format!("Fetch failed: {}", e)),
+    };
+
+    match result {
+        Ok(_) => {
+            let _ = rag
+                .db
+                .set_queue_item_status(item.id, &QueueStatus::Done, None)
+                .await;
+            let _ = rag.db.clear_ingest_error(&item.url).await;
+            QueueOutcome::Ingested
+        }
+        Err(error) => {
+            eprintln!("Failed to ingest bookmark '{}': {}", item.title, error);
+            let _ = rag
+                .db
+                .set_queue_item_status(item.id, &QueueStatus::Failed, Some(&error))
+                .await;
+            let _ = rag
+                .db
+                .record_ingest_error(&item.url, &item.title, item.profile.as_deref(), &error)
+                .await;
+            QueueOutcome::Failed(error)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_round_trips_through_db_str() {
+        for status in [
+            QueueStatus::Pending,
+            QueueStatus::InProgress,
+            QueueStatus::Done,
+            QueueStatus::Failed,
+        ] {
+            assert_eq!(QueueStatus::from_db_str(status.as_db_str()), status);
+        }
+    }
+
+    #[test]
+    fn unknown_status_is_pending() {
+        assert_eq!(QueueStatus::from_db_str("bogus"), QueueStatus::Pending);
+    }
+}
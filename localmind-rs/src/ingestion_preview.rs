@@ -0,0 +1,197 @@
+//! Dry-run preview of the first bookmark ingestion
+//!
+//! Importing a large bookmark collection takes a long time, so before the
+//! first run the GUI is shown what would be ingested: how many bookmarks,
+//! which folders and domains they come from, and a rough time estimate.
+//! Folders and domains the user unchecks are saved as exclusion rules, so
+//! the choice also applies to every later sync.
+
+use crate::bookmark_exclusion::ExclusionRules;
+use crate::storage_budget::StorageStatus;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+/// Only preview first ingestions with at least this many bookmarks.
+pub const PREVIEW_MIN_BOOKMARKS: usize = 50;
+
+/// Rough per-bookmark cost of fetching, chunking and embedding a page.
+const SECONDS_PER_BOOKMARK: f64 = 1.5;
+
+/// A bookmark found by the dry run.
+#[derive(Debug, Clone)]
+pub struct PreviewBookmark {
+    pub url: String,
+    /// ID of the folder directly containing the bookmark
+    pub folder_id: String,
+    /// Folder names from the root down to the containing folder
+    pub folder_path: Vec<String>,
+    /// Chrome profile the bookmark belongs to
+    pub profile: String,
+}
+
+/// A folder in the preview with the number of bookmarks directly inside it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PreviewFolder {
+    pub id: String,
+    pub path: Vec<String>,
+    pub profile: String,
+    pub count: usize,
+}
+
+/// Everything the first ingestion would import, across all profiles.
+#[derive(Debug, Clone, Default)]
+pub struct IngestionPreview {
+    pub bookmarks: Vec<PreviewBookmark>,
+    /// Index size and free disk space, for estimating the import's growth
+    pub storage: Option<StorageStatus>,
+}
+
+/// The user's answer to a preview.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PreviewDecision {
+    /// Ingest, after adding these folder IDs and domain patterns to the
+    /// exclusion rules
+    Ingest {
+        skipped_folders: Vec<String>,
+        skipped_domains: Vec<String>,
+    },
+    /// Do not ingest bookmarks this session
+    Skip,
+}
+
+/// A preview sent to the GUI together with the channel for its answer.
+pub struct PreviewRequest {
+    pub preview: IngestionPreview,
+    pub reply: tokio::sync::oneshot::Sender<PreviewDecision>,
+}
+
+impl IngestionPreview {
+    /// Folders holding bookmarks, largest first.
+    pub fn folders(&self) -> Vec<PreviewFolder> {
+        let mut by_id: HashMap<(&str, &str), PreviewFolder> = HashMap::new();
+        for bookmark in &self.bookmarks {
+            by_id
+                .entry((bookmark.profile.as_str(), bookmark.folder_id.as_str()))
+                .or_insert_with(|| PreviewFolder {
+                    id: bookmark.folder_id.clone(),
+                    path: bookmark.folder_path.clone(),
+                    profile: bookmark.profile.clone(),
+                    count: 0,
+                })
+                .count += 1;
+        }
+        let mut folders: Vec<PreviewFolder> = by_id.into_values().collect();
+        folders.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.path.cmp(&b.path)));
+        folders
+    }
+
+    /// Domains (as exclusion patterns) with their bookmark counts, largest first.
+    pub fn domains(&self) -> Vec<(String, usize)> {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for bookmark in &self.bookmarks {
+            if let Some(domain) = ExclusionRules::pattern_for_url(&bookmark.url) {
+                *counts.entry(domain).or_default() += 1;
+            }
+        }
+        let mut domains: Vec<(String, usize)> = counts.into_iter().collect();
+        domains.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        domains
+    }
+
+    /// Number of bookmarks left after skipping folders and domains.
+    ///
+    /// Skipping a folder also skips its subfolders, matching how folder
+    /// exclusion rules apply.
+    pub fn selected_count(
+        &self,
+        skipped_folders: &HashSet<String>,
+        skipped_domains: &HashSet<String>,
+    ) -> usize {
+        let skipped: Vec<PreviewFolder> = self
+            .folders()
+            .into_iter()
+            .filter(|f| skipped_folders.contains(&f.id))
+            .collect();
+
+        self.bookmarks
+            .iter()
+            .filter(|b| {
+                !skipped
+                    .iter()
+                    .any(|f| f.profile == b.profile && b.folder_path.starts_with(&f.path))
+            })
+            .filter(|b| {
+                !matches!(ExclusionRules::pattern_for_url(&b.url),
+                    Some(domain) if skipped_domains.contains(&domain))
+            })
+            .count()
+    }
+}
+
+/// Rough time to ingest `count` bookmarks.
+pub fn estimated_duration(count: usize) -> Duration {
+    Duration::from_secs_f64(count as f64 * SECONDS_PER_BOOKMARK)
+}
+
+/// Human-readable duration such as "about 12 minutes".
+pub fn format_estimate(duration: Duration) -> String {
+    let minutes = duration.as_secs().div_ceil(60);
+    match minutes {
+        0 | 1 => "about a minute".to_string(),
+        m if m < 90 => format!("about {} minutes", m),
+        m => format!("about {:.1} hours", m as f64 / 60.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bookmark(url: &str, folder_id: &str, path: &[&str]) -> PreviewBookmark {
+        PreviewBookmark {
+            url: url.to_string(),
+            folder_id: folder_id.to_string(),
+            folder_path: path.iter().map(|s| s.to_string()).collect(),
+            profile: "Default".to_string(),
+        }
+    }
+
+    #[test]
+    fn groups_and_counts_with_skips() {
+        let preview = IngestionPreview {
+            bookmarks: vec![
+                bookmark("https://a.com/1", "1", &["Bar"]),
+                bookmark("https://a.com/2", "2", &["Bar", "Work"]),
+                bookmark("https://www.b.com/", "2", &["Bar", "Work"]),
+                bookmark("https://c.com/", "3", &["Other"]),
+            ],
+            storage: None,
+        };
+
+        let folders = preview.folders();
+        assert_eq!(folders[0].id, "2");
+        assert_eq!(folders[0].count, 2);
+        assert_eq!(preview.domains()[0], ("a.com".to_string(), 2));
+        assert!(preview.domains().contains(&("b.com".to_string(), 1)));
+
+        let none = HashSet::new();
+        assert_eq!(preview.selected_count(&none, &none), 4);
+
+        // Skipping "Bar" also skips its "Work" subfolder
+        let bar: HashSet<String> = ["1".to_string()].into();
+        assert_eq!(preview.selected_count(&bar, &none), 1);
+
+        let a_com: HashSet<String> = ["a.com".to_string()].into();
+        assert_eq!(preview.selected_count(&none, &a_com), 2);
+    }
+
+    #[test]
+    fn formats_estimates() {
+        assert_eq!(format_estimate(estimated_duration(10)), "about a minute");
+        assert_eq!(format_estimate(estimated_duration(400)), "about 10 minutes");
+        assert_eq!(
+            format_estimate(Duration::from_secs(3 * 3600)),
+            "about 3.0 hours"
+        );
+    }
+}
@@ -0,0 +1,213 @@
+//! Startup integrity audit
+//!
+//! A quick pass over the database after startup looks for the damage a crash,
+//! a full disk or an old bug can leave behind: documents without chunk
+//! embeddings, chunk rows whose document is gone, a full-text index out of
+//! step with the documents table, embedding blobs that no longer deserialize,
+//! and embeddings whose dimension differs from the rest. Problems are shown
+//! in the status bar, each with a one-click repair.
+
+use crate::db::Database;
+use crate::rag::RagPipeline;
+use crate::Result;
+use std::collections::{HashMap, HashSet};
+
+/// Problems found by an integrity audit.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IntegrityReport {
+    /// Documents (not in the trash) with fewer chunks than expected
+    pub documents_without_chunks: Vec<i64>,
+    /// Chunk rows whose document no longer exists
+    pub orphan_chunks: usize,
+    pub document_count: usize,
+    /// Rows in the full-text index; should equal `document_count`
+    pub fts_count: usize,
+    /// Chunk rows of existing documents whose embedding fails to deserialize
+    pub corrupt_chunks: Vec<i64>,
+    /// Chunk rows whose embedding dimension differs from `expected_dimension`
+    pub mismatched_chunks: Vec<i64>,
+    /// Most common embedding dimension, if any embeddings could be read
+    pub expected_dimension: Option<usize>,
+    /// Documents owning the corrupt and mismatched chunks
+    pub affected_documents: Vec<i64>,
+}
+
+impl IntegrityReport {
+    pub fn fts_out_of_sync(&self) -> bool {
+        self.document_count != self.fts_count
+    }
+
+    /// Number of bad rows found, counting an out-of-sync full-text index once.
+    pub fn problem_count(&self) -> usize {
+        self.documents_without_chunks.len()
+            + self.orphan_chunks
+            + self.corrupt_chunks.len()
+            + self.mismatched_chunks.len()
+            + usize::from(self.fts_out_of_sync())
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        self.problem_count() == 0
+    }
+
+    /// Repairs that would fix the problems found, in the order to run them.
+    /// Dropping bad chunk rows leaves their documents to be re-embedded.
+    pub fn repairs(&self) -> Vec<Repair> {
+        let mut repairs = Vec::new();
+        if self.orphan_chunks > 0
+            || !self.corrupt_chunks.is_empty()
+            || !self.mismatched_chunks.is_empty()
+        {
+            repairs.push(Repair::DropCorruptRows);
+        }
+        if !self.documents_without_chunks.is_empty() {
+            repairs.push(Repair::ReEmbed);
+        }
+        if self.fts_out_of_sync() {
+            repairs.push(Repair::RebuildFts);
+        }
+        repairs
+    }
+}
+
+/// A one-click fix for a class of problems.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Repair {
+    /// Re-embed documents with missing chunks
+    ReEmbed,
+    /// Rebuild the full-text indexes from the documents table
+    RebuildFts,
+    /// Delete orphaned, corrupt and mismatched chunk rows
+    DropCorruptRows,
+}
+
+/// Split chunk rows into corrupt ones (`None` dimension) and ones whose
+/// dimension differs from the most common one, which is returned as the
+/// expected dimension. Ties go to the larger dimension.
+pub fn classify_dimensions(chunks: &[(i64, Option<usize>)]) -> (Vec<i64>, Vec<i64>, Option<usize>) {
+    let mut counts: HashMap<usize, usize> = HashMap::new();
+    for dimension in chunks.iter().filter_map(|(_, d)| *d) {
+        *counts.entry(dimension).or_default() += 1;
+    }
+    let expected = counts
+        .into_iter()
+        .max_by_key(|&(dimension, count)| (count, dimension))
+        .map(|(dimension, _)| dimension);
+
+    let mut corrupt = Vec::new();
+    let mut mismatched = Vec::new();
+    for &(id, dimension) in chunks {
+        match dimension {
+            None => corrupt.push(id),
+            Some(d) if Some(d) != expected => mismatched.push(id),
+            Some(_) => {}
+        }
+    }
+    (corrupt, mismatched, expected)
+}
+
+/// Audit the database.
+pub async fn audit(db: &Database) -> Result<IntegrityReport> {
+    let documents_without_chunks = db.get_incomplete_documents().await?;
+    let (document_count, fts_count, orphan_chunks) = db.get_integrity_counts().await?;
+
+    let chunks = db.get_chunk_dimensions().await?;
+    let dimensions: Vec<(i64, Option<usize>)> = chunks.iter().map(|&(id, _, d)| (id, d)).collect();
+    let (corrupt_chunks, mismatched_chunks, expected_dimension) = classify_dimensions(&dimensions);
+
+    let bad_chunks: HashSet<i64> = corrupt_chunks
+        .iter()
+        .chain(&mismatched_chunks)
+        .copied()
+        .collect();
+    let mut affected_documents: Vec<i64> = chunks
+        .iter()
+        .filter(|(id, _, _)| bad_chunks.contains(id))
+        .map(|&(_, document_id, _)| document_id)
+        .collect();
+    affected_documents.sort_unstable();
+    affected_documents.dedup();
+
+    Ok(IntegrityReport {
+        documents_without_chunks,
+        orphan_chunks,
+        document_count,
+        fts_count,
+        corrupt_chunks,
+        mismatched_chunks,
+        expected_dimension,
+        affected_documents,
+    })
+}
+
+/// Run one repair for the problems in `report`, returning how many rows or
+/// documents it fixed.
+pub async fn repair(rag: &RagPipeline, repair: Repair, report: &IntegrityReport) -> Result<usize> {
+    match repair {
+        Repair::ReEmbed => {
+            if !rag.is_semantic_search_available() {
+                return Err("The embedding backend is not available".into());
+            }
+            rag.repair_incomplete_documents().await
+        }
+        Repair::RebuildFts => {
+            rag.db.rebuild_fts().await?;
+            Ok(report.document_count)
+        }
+        Repair::DropCorruptRows => {
+            let mut bad_chunks = report.corrupt_chunks.clone();
+            bad_chunks.extend(&report.mismatched_chunks);
+            let mut dropped = rag.db.delete_chunks(&bad_chunks).await?;
+            dropped += rag.db.delete_orphan_chunks().await?;
+            // Mismatched vectors were loaded into memory, so drop them there
+            // too; their documents are now incomplete and get fresh vectors
+            // when re-embedded
+            rag.remove_chunk_vectors(&report.mismatched_chunks).await;
+            Ok(dropped)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn most_common_dimension_is_expected() {
+        let chunks = [
+            (1, Some(768)),
+            (2, Some(768)),
+            (3, None),
+            (4, Some(384)),
+            (5, Some(768)),
+        ];
+        let (corrupt, mismatched, expected) = classify_dimensions(&chunks);
+        assert_eq!(corrupt, vec![3]);
+        assert_eq!(mismatched, vec![4]);
+        assert_eq!(expected, Some(768));
+
+        let (corrupt, mismatched, expected) = classify_dimensions(&[(1, None)]);
+        assert_eq!(corrupt, vec![1]);
+        assert!(mismatched.is_empty());
+        assert_eq!(expected, None);
+    }
+
+    #[test]
+    fn repairs_follow_the_problems_found() {
+        assert!(IntegrityReport::default().is_healthy());
+        assert!(IntegrityReport::default().repairs().is_empty());
+
+        let report = IntegrityReport {
+            documents_without_chunks: vec![7],
+            document_count: 10,
+            fts_count: 9,
+            corrupt_chunks: vec![3],
+            ..Default::default()
+        };
+        assert_eq!(report.problem_count(), 3);
+        assert_eq!(
+            report.repairs(),
+            vec![Repair::DropCorruptRows, Repair::ReEmbed, Repair::RebuildFts]
+        );
+    }
+}
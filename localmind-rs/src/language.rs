@@ -0,0 +1,172 @@
+//! Document languages
+//!
+//! The language of each document is detected when it is stored, with
+//! whatlang, and kept as an ISO 639-3 code such as "eng", or as an empty
+//! string when the text is too short or too mixed to tell. Searches filter
+//! by it with `lang:`, which takes the three-letter code, the two-letter
+//! code ("en") or the English name. Languages can be excluded from
+//! embedding in Settings; documents in them are found by keyword search
+//! only.
+
+use whatlang::Lang;
+
+/// Characters of a document looked at to detect its language.
+const SAMPLE_CHARS: usize = 2000;
+
+/// Two-letter (ISO 639-1) codes of the languages whatlang detects, by their
+/// three-letter code. YouTube names transcript tracks by these.
+const TWO_LETTER_CODES: [(&str, &str); 69] = [
+    ("afr", "af"),
+    ("aka", "ak"),
+    ("amh", "am"),
+    ("ara", "ar"),
+    ("aze", "az"),
+    ("bel", "be"),
+    ("ben", "bn"),
+    ("bul", "bg"),
+    ("cat", "ca"),
+    ("ces", "cs"),
+    ("cmn", "zh"),
+    ("dan", "da"),
+    ("deu", "de"),
+    ("ell", "el"),
+    ("eng", "en"),
+    ("epo", "eo"),
+    ("est", "et"),
+    ("fin", "fi"),
+    ("fra", "fr"),
+    ("guj", "gu"),
+    ("heb", "he"),
+    ("hin", "hi"),
+    ("hrv", "hr"),
+    ("hun", "hu"),
+    ("hye", "hy"),
+    ("ind", "id"),
+    ("ita", "it"),
+    ("jav", "jv"),
+    ("jpn", "ja"),
+    ("kan", "kn"),
+    ("kat", "ka"),
+    ("khm", "km"),
+    ("kor", "ko"),
+    ("lat", "la"),
+    ("lav", "lv"),
+    ("lit", "lt"),
+    ("mal", "ml"),
+    ("mar", "mr"),
+    ("mkd", "mk"),
+    ("mya", "my"),
+    ("nep", "ne"),
+    ("nld", "nl"),
+    ("nob", "no"),
+    ("ori", "or"),
+    ("pan", "pa"),
+    ("pes", "fa"),
+    ("pol", "pl"),
+    ("por", "pt"),
+    ("ron", "ro"),
+    ("rus", "ru"),
+    ("sin", "si"),
+    ("slk", "sk"),
+    ("slv", "sl"),
+    ("sna", "sn"),
+    ("spa", "es"),
+    ("srp", "sr"),
+    ("swe", "sv"),
+    ("tam", "ta"),
+    ("tel", "te"),
+    ("tgl", "tl"),
+    ("tha", "th"),
+    ("tuk", "tk"),
+    ("tur", "tr"),
+    ("ukr", "uk"),
+    ("urd", "ur"),
+    ("uzb", "uz"),
+    ("vie", "vi"),
+    ("yid", "yi"),
+    ("zul", "zu"),
+];
+
+/// Language of a document, as a three-letter code; `None` when it cannot
+/// be told reliably.
+pub fn detect(title: &str, content: &str) -> Option<&'static str> {
+    let sample: String = title
+        .chars()
+        .chain(std::iter::once('\n'))
+        .chain(content.chars())
+        .take(SAMPLE_CHARS)
+        .collect();
+    let info = whatlang::detect(&sample)?;
+    info.is_reliable().then(|| info.lang().code())
+}
+
+/// Language of a short text such as a title, as a three-letter code, when
+/// whatlang's confidence in it is at least `min_confidence` (0 to 1).
+pub fn guess(text: &str, min_confidence: f64) -> Option<&'static str> {
+    let info = whatlang::detect(text)?;
+    (info.confidence() >= min_confidence).then(|| info.lang().code())
+}
+
+/// Two-letter code of a language given by its three-letter code.
+pub fn two_letter_code(code: &str) -> Option<&'static str> {
+    TWO_LETTER_CODES
+        .iter()
+        .find(|(three, _)| *three == code)
+        .map(|(_, two)| *two)
+}
+
+/// English name of a language given by its three-letter code.
+pub fn english_name(code: &str) -> Option<&'static str> {
+    Lang::from_code(code).map(|lang| lang.eng_name())
+}
+
+/// Short label for a language badge, e.g. "EN".
+pub fn badge(code: &str) -> String {
+    two_letter_code(code).unwrap_or(code).to_uppercase()
+}
+
+/// Three-letter code of a language named in a `lang:` operator: a two- or
+/// three-letter code or an English name, in any case.
+pub fn parse(value: &str) -> Option<&'static str> {
+    let value = value.trim().to_lowercase();
+    if let Some((three, _)) = TWO_LETTER_CODES
+        .iter()
+        .find(|(three, two)| *three == value || *two == value)
+    {
+        return Some(*three);
+    }
+    TWO_LETTER_CODES
+        .iter()
+        .map(|(three, _)| *three)
+        .find(|three| english_name(three).is_some_and(|name| name.to_lowercase() == value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_the_language_of_longer_text() {
+        let english = "The quick brown fox jumps over the lazy dog. This sentence is \
+            written in plain English so that the detector has enough words to go on.";
+        assert_eq!(detect("A note", english), Some("eng"));
+
+        let spanish = "El rápido zorro marrón salta sobre el perro perezoso. Esta frase \
+            está escrita en español para que el detector tenga suficientes palabras.";
+        assert_eq!(detect("Una nota", spanish), Some("spa"));
+
+        assert_eq!(detect("", ""), None);
+    }
+
+    #[test]
+    fn operators_accept_codes_and_names() {
+        assert_eq!(parse("en"), Some("eng"));
+        assert_eq!(parse("ENG"), Some("eng"));
+        assert_eq!(parse("Spanish"), Some("spa"));
+        assert_eq!(parse("klingon"), None);
+
+        assert_eq!(two_letter_code("deu"), Some("de"));
+        assert_eq!(badge("cmn"), "ZH");
+        assert_eq!(english_name("fra"), Some("French"));
+    }
+}
@@ -1,14 +1,65 @@
+pub mod annotations;
 pub mod bookmark;
 pub mod query_logger;
 pub mod bookmark_exclusion;
+pub mod bookmark_import;
+pub mod content_cleaner;
 pub mod db;
 pub mod document;
+pub mod document_versions;
+pub mod domain_preferences;
+pub mod embedding_migration;
+pub mod embedding_provider;
+pub mod embedding_supervisor;
+pub mod eval;
+pub mod favicon;
 pub mod fetcher;
 pub mod folder_watcher;
+pub mod github;
 pub mod gui;
+pub mod html_text;
+pub mod http_settings;
+pub mod image_caption;
+pub mod ingest_jobs;
+pub mod ingest_policy;
+pub mod ingest_queue;
+pub mod ingestion_preview;
+pub mod integrity;
+pub mod language;
+pub mod library;
+pub mod link_checker;
+pub mod links;
+pub mod lmstudio;
 pub mod local_embedding;
+pub mod maintenance;
+pub mod markdown;
+pub mod monitoring;
+pub mod notifications;
+pub mod ollama;
+pub mod page_quality;
+pub mod projection;
+pub mod query_cache;
+pub mod query_expansion;
+pub mod query_syntax;
 pub mod rag;
+pub mod reminders;
+pub mod remote_access;
+pub mod request_log;
+pub mod retention;
+pub mod safari;
+pub mod services;
+pub mod snapshot;
+pub mod snippet;
+pub mod social;
+pub mod spelling;
+pub mod stack_exchange;
+pub mod storage_budget;
+pub mod sync;
+pub mod thumbnail;
+pub mod tls;
+pub mod tracking_params;
 pub mod vector;
+pub mod workspace_import;
 pub mod youtube;
 
 use std::error::Error;
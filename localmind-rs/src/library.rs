@@ -0,0 +1,203 @@
+//! Named libraries
+//!
+//! A library is a separate index ("Work", "Personal") with its own SQLite
+//! database and therefore its own documents, collections and settings. The
+//! list of libraries and the active one live in `libraries.json` next to the
+//! databases, since they are needed before any database is opened. The
+//! original database stays the default library, so existing installs keep
+//! their index.
+//!
+//! Not to be confused with browser profiles, which are a filter within one
+//! library.
+
+use crate::db::data_dir;
+use crate::Result;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Name of the library backed by the original database.
+pub const DEFAULT_LIBRARY: &str = "Default";
+
+/// Longest library name accepted.
+const MAX_NAME_CHARS: usize = 40;
+
+/// The known libraries and which one is open.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LibraryRegistry {
+    pub active: String,
+    pub libraries: Vec<String>,
+}
+
+impl Default for LibraryRegistry {
+    fn default() -> Self {
+        Self {
+            active: DEFAULT_LIBRARY.to_string(),
+            libraries: vec![DEFAULT_LIBRARY.to_string()],
+        }
+    }
+}
+
+fn registry_path() -> PathBuf {
+    data_dir().join("libraries.json")
+}
+
+/// File name of a library's database; the default library keeps the
+/// original file name.
+fn database_file_name(name: &str) -> String {
+    if name == DEFAULT_LIBRARY {
+        return "localmind.db".to_string();
+    }
+    let slug: String = name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    format!("localmind-{}.db", slug)
+}
+
+/// Path of a library's database.
+pub fn database_path(name: &str) -> PathBuf {
+    data_dir().join(database_file_name(name))
+}
+
+impl LibraryRegistry {
+    /// Read the registry, falling back to just the default library.
+    pub fn load() -> Self {
+        let registry = std::fs::read_to_string(registry_path())
+            .ok()
+            .and_then(|json| serde_json::from_str::<Self>(&json).ok())
+            .unwrap_or_default();
+        registry.normalized()
+    }
+
+    pub fn save(&self) -> Result<()> {
+        std::fs::create_dir_all(data_dir())?;
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(registry_path(), json)?;
+        Ok(())
+    }
+
+    /// Make sure the default library is listed and the active one exists.
+    fn normalized(mut self) -> Self {
+        if !self.libraries.iter().any(|name| name == DEFAULT_LIBRARY) {
+            self.libraries.insert(0, DEFAULT_LIBRARY.to_string());
+        }
+        if !self.libraries.contains(&self.active) {
+            self.active = DEFAULT_LIBRARY.to_string();
+        }
+        self
+    }
+
+    /// Check a name for a new library, returning it trimmed.
+    ///
+    /// Names must be unique ignoring case and must not map to the same
+    /// database file as an existing library.
+    pub fn validate_name(&self, name: &str) -> std::result::Result<String, String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("Enter a name for the library".to_string());
+        }
+        if name.chars().count() > MAX_NAME_CHARS {
+            return Err(format!(
+                "Library names can be at most {} characters",
+                MAX_NAME_CHARS
+            ));
+        }
+        let file_name = database_file_name(name);
+        if self.libraries.iter().any(|existing| {
+            existing.eq_ignore_ascii_case(name) || database_file_name(existing) == file_name
+        }) {
+            return Err(format!("A library named \"{}\" already exists", name));
+        }
+        Ok(name.to_string())
+    }
+
+    /// Add a library after validating its name, returning the stored name.
+    pub fn add(&mut self, name: &str) -> std::result::Result<String, String> {
+        let name = self.validate_name(name)?;
+        self.libraries.push(name.clone());
+        Ok(name)
+    }
+
+    /// Remove a library from the list. The default and the open library
+    /// cannot be removed.
+    pub fn remove(&mut self, name: &str) -> std::result::Result<(), String> {
+        if name == DEFAULT_LIBRARY {
+            return Err("The default library cannot be deleted".to_string());
+        }
+        if name == self.active {
+            return Err("Switch to another library before deleting this one".to_string());
+        }
+        let before = self.libraries.len();
+        self.libraries.retain(|existing| existing != name);
+        if self.libraries.len() == before {
+            return Err(format!("No library named \"{}\"", name));
+        }
+        Ok(())
+    }
+}
+
+/// Delete a library's database files, including the WAL side files.
+pub fn delete_database(name: &str) -> Result<()> {
+    let path = database_path(name);
+    for suffix in ["", "-wal", "-shm"] {
+        let mut file = path.clone().into_os_string();
+        file.push(suffix);
+        match std::fs::remove_file(&file) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_library_keeps_the_original_database() {
+        assert_eq!(database_file_name(DEFAULT_LIBRARY), "localmind.db");
+        assert_eq!(database_file_name("Work"), "localmind-work.db");
+        assert_eq!(
+            database_file_name("Side Projects"),
+            "localmind-side-projects.db"
+        );
+
+        let registry = LibraryRegistry {
+            active: "Gone".to_string(),
+            libraries: vec!["Work".to_string()],
+        }
+        .normalized();
+        assert_eq!(registry.active, DEFAULT_LIBRARY);
+        assert_eq!(registry.libraries, vec![DEFAULT_LIBRARY, "Work"]);
+    }
+
+    #[test]
+    fn names_must_be_unique() {
+        let mut registry = LibraryRegistry::default();
+        assert_eq!(registry.add("  Work "), Ok("Work".to_string()));
+        assert!(registry.add("work").is_err());
+        assert_eq!(
+            registry.add("Side Projects"),
+            Ok("Side Projects".to_string())
+        );
+        // Maps to the same database file as "Side Projects"
+        assert!(registry.add("side-projects").is_err());
+        assert!(registry.add("   ").is_err());
+        assert!(registry.add("default").is_err());
+
+        assert!(registry.remove(DEFAULT_LIBRARY).is_err());
+        registry.active = "Work".to_string();
+        assert!(registry.remove("Work").is_err());
+        registry.active = DEFAULT_LIBRARY.to_string();
+        assert!(registry.remove("Work").is_ok());
+        assert_eq!(registry.libraries, vec![DEFAULT_LIBRARY, "Side Projects"]);
+    }
+}
@@ -0,0 +1,250 @@
+//! Scheduled dead-link checker
+//!
+//! Periodically sends a HEAD request (falling back to GET when HEAD is not
+//! allowed) to every bookmarked URL. Explicit 404/410 responses mark a
+//! document dead immediately; timeouts, DNS/connection failures, and 5xx
+//! responses only count as transient failures and mark the document dead once
+//! they repeat `MAX_TRANSIENT_FAILURES` times in a row. Documents that respond
+//! again are un-marked.
+
+use crate::db::Database;
+use crate::Result;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+/// Default interval between scheduled runs (hours). 0 disables scheduling.
+pub const DEFAULT_INTERVAL_HOURS: u64 = 24;
+
+/// Consecutive transient failures before a document is considered dead
+pub const MAX_TRANSIENT_FAILURES: i64 = 3;
+
+/// Delay between requests to avoid overwhelming servers
+const REQUEST_DELAY_MS: u64 = 100;
+
+/// Guards against the scheduler and a manual "Check now" running concurrently
+static CHECK_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// A document URL to be checked, with its current link state.
+#[derive(Debug, Clone)]
+pub struct LinkCheckTarget {
+    pub doc_id: i64,
+    pub url: String,
+    pub is_dead: bool,
+    pub failures: i64,
+}
+
+/// Result of checking a single URL.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LinkOutcome {
+    /// The server responded with a success or redirect status
+    Alive,
+    /// The server says the page no longer exists (404 / 410)
+    Gone,
+    /// The page requires authentication (401 / 403)
+    NeedsAuth,
+    /// Timeout, DNS/connection failure, rate limit, or server error
+    Transient(String),
+}
+
+/// How a document's stored state changed after applying an outcome.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LinkChange {
+    Unchanged,
+    MarkedDead,
+    MarkedNeedsAuth,
+    Recovered,
+}
+
+/// Progress of a link-check run, sent to the UI after every URL.
+#[derive(Debug, Clone, Default)]
+pub struct LinkCheckProgress {
+    pub checked: usize,
+    pub total: usize,
+    pub marked_dead: usize,
+    pub marked_needs_auth: usize,
+    pub recovered: usize,
+    pub completed: bool,
+}
+
+/// Map an HTTP status to a link outcome.
+pub fn classify_status(status: reqwest::StatusCode) -> LinkOutcome {
+    use reqwest::StatusCode;
+    match status {
+        StatusCode::NOT_FOUND | StatusCode::GONE => LinkOutcome::Gone,
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => LinkOutcome::NeedsAuth,
+        s if s.is_success() || s.is_redirection() => LinkOutcome::Alive,
+        s => LinkOutcome::Transient(format!("HTTP {}", s)),
+    }
+}
+
+/// Check a single URL, retrying with GET if the server rejects HEAD.
+pub async fn check_url(client: &reqwest::Client, url: &str) -> LinkOutcome {
+    let response = match client.head(url).send().await {
+        Ok(r) => r,
+        Err(e) => return LinkOutcome::Transient(describe_error(&e)),
+    };
+
+    let status = response.status();
+    if status == reqwest::StatusCode::METHOD_NOT_ALLOWED
+        || status == reqwest::StatusCode::NOT_IMPLEMENTED
+    {
+        return match client.get(url).send().await {
+            Ok(r) => classify_status(r.status()),
+            Err(e) => LinkOutcome::Transient(describe_error(&e)),
+        };
+    }
+
+    classify_status(status)
+}
+
+fn describe_error(e: &reqwest::Error) -> String {
+    if e.is_timeout() {
+        "timeout".to_string()
+    } else if e.is_connect() {
+        format!("connection failed: {}", e)
+    } else {
+        e.to_string()
+    }
+}
+
+/// Return `true` if a scheduled run is due based on the configured interval.
+pub async fn is_check_due(db: &Database) -> bool {
+    let interval_hours = db
+        .get_dead_link_check_interval_hours()
+        .await
+        .unwrap_or(DEFAULT_INTERVAL_HOURS);
+    if interval_hours == 0 {
+        return false;
+    }
+
+    let last_run: u64 = db
+        .get_dead_link_check_last_run()
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or(0);
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+
+    now.saturating_sub(last_run) >= interval_hours * 3600
+}
+
+/// Check every document URL (live and dead), updating dead/auth flags.
+///
+/// Progress is sent after each URL if `progress_tx` is provided. Returns the
+/// final progress as a summary. Fails if another run is already in progress.
+pub async fn run_link_check(
+    db: &Database,
+    progress_tx: Option<&std::sync::mpsc::Sender<LinkCheckProgress>>,
+) -> Result<LinkCheckProgress> {
+    if CHECK_RUNNING.swap(true, Ordering::SeqCst) {
+        return Err("A link check is already running".into());
+    }
+
+    let result = run_link_check_inner(db, progress_tx).await;
+    CHECK_RUNNING.store(false, Ordering::SeqCst);
+    result
+}
+
+async fn run_link_check_inner(
+    db: &Database,
+    progress_tx: Option<&std::sync::mpsc::Sender<LinkCheckProgress>>,
+) -> Result<LinkCheckProgress> {
+    let targets = db.get_link_check_targets().await?;
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .user_agent("LocalMind/1.0")
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let mut progress = LinkCheckProgress {
+        total: targets.len(),
+        ..Default::default()
+    };
+    println!("Checking {} document links", progress.total);
+
+    for target in targets {
+        let outcome = check_url(&client, &target.url).await;
+        match db
+            .apply_link_check_outcome(target.doc_id, &outcome, MAX_TRANSIENT_FAILURES)
+            .await?
+        {
+            LinkChange::MarkedDead => {
+                println!("Marking {} as dead ({:?})", target.url, outcome);
+                progress.marked_dead += 1;
+            }
+            LinkChange::MarkedNeedsAuth => {
+                println!("Marking {} as needs auth", target.url);
+                progress.marked_needs_auth += 1;
+            }
+            LinkChange::Recovered => {
+                println!("Link {} is alive again", target.url);
+                progress.recovered += 1;
+            }
+            LinkChange::Unchanged => {}
+        }
+
+        progress.checked += 1;
+        if let Some(tx) = progress_tx {
+            let _ = tx.send(progress.clone());
+        }
+
+        tokio::time::sleep(Duration::from_millis(REQUEST_DELAY_MS)).await;
+    }
+
+    db.set_dead_link_check_last_run().await?;
+
+    progress.completed = true;
+    if let Some(tx) = progress_tx {
+        let _ = tx.send(progress.clone());
+    }
+
+    Ok(progress)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::StatusCode;
+
+    #[test]
+    fn not_found_and_gone_are_dead() {
+        assert_eq!(classify_status(StatusCode::NOT_FOUND), LinkOutcome::Gone);
+        assert_eq!(classify_status(StatusCode::GONE), LinkOutcome::Gone);
+    }
+
+    #[test]
+    fn auth_statuses_need_auth() {
+        assert_eq!(
+            classify_status(StatusCode::UNAUTHORIZED),
+            LinkOutcome::NeedsAuth
+        );
+        assert_eq!(
+            classify_status(StatusCode::FORBIDDEN),
+            LinkOutcome::NeedsAuth
+        );
+    }
+
+    #[test]
+    fn success_and_redirects_are_alive() {
+        assert_eq!(classify_status(StatusCode::OK), LinkOutcome::Alive);
+        assert_eq!(
+            classify_status(StatusCode::MOVED_PERMANENTLY),
+            LinkOutcome::Alive
+        );
+    }
+
+    #[test]
+    fn server_errors_are_transient() {
+        assert!(matches!(
+            classify_status(StatusCode::SERVICE_UNAVAILABLE),
+            LinkOutcome::Transient(_)
+        ));
+        assert!(matches!(
+            classify_status(StatusCode::TOO_MANY_REQUESTS),
+            LinkOutcome::Transient(_)
+        ));
+    }
+}
@@ -0,0 +1,99 @@
+//! Links between documents
+//!
+//! Outgoing hyperlinks are extracted when a document is ingested and stored
+//! by URL in the `document_links` table. A link becomes a document-to-document
+//! edge once a document with the target URL is indexed, so edges appear
+//! whichever end is saved first. The document view lists both directions,
+//! and search can favour documents linked to or from recently viewed ones.
+
+use regex::Regex;
+use url::Url;
+
+/// Outgoing links kept per document; navigation-heavy pages beyond this are
+/// truncated.
+pub const MAX_LINKS_PER_DOCUMENT: usize = 200;
+
+/// Score multiplier added for results linked to a recently viewed document.
+pub const LINK_BOOST: f32 = 0.15;
+
+/// Recently viewed documents whose links boost search results.
+pub const RECENTLY_VIEWED_LIMIT: usize = 10;
+
+/// Canonical form of a link target: http(s) only, without the fragment or a
+/// trailing slash. Other schemes (mailto:, javascript:, ...) are dropped.
+pub fn normalize_link(link: &str) -> Option<String> {
+    let mut url = Url::parse(link.trim()).ok()?;
+    if url.scheme() != "http" && url.scheme() != "https" {
+        return None;
+    }
+    url.set_fragment(None);
+    Some(url.as_str().trim_end_matches('/').to_string())
+}
+
+/// `href` targets of the anchors in an HTML fragment, resolved against the
+/// page they came from.
+pub fn extract_html_links(html: &str, base: &Url) -> Vec<String> {
+    let href = Regex::new(r#"(?i)<a\s[^>]*?href\s*=\s*["']([^"']+)["']"#).expect("valid regex");
+    href.captures_iter(html)
+        .filter_map(|caps| base.join(caps[1].trim()).ok())
+        .map(|url| url.to_string())
+        .collect()
+}
+
+/// Bare URLs written in plain text.
+pub fn extract_text_links(text: &str) -> Vec<String> {
+    let bare = Regex::new(r#"https?://[^\s<>"'`]+"#).expect("valid regex");
+    bare.find_iter(text)
+        .map(|m| {
+            m.as_str()
+                .trim_end_matches(['.', ',', ';', ':', '!', '?', ')', ']', '}'])
+                .to_string()
+        })
+        .collect()
+}
+
+/// Normalize and deduplicate candidate links for a page, dropping links back
+/// to the page itself, capped at `MAX_LINKS_PER_DOCUMENT`.
+pub fn collect_links(
+    page_url: Option<&str>,
+    candidates: impl IntoIterator<Item = String>,
+) -> Vec<String> {
+    let own = page_url.and_then(normalize_link);
+    let mut seen = std::collections::HashSet::new();
+    candidates
+        .into_iter()
+        .filter_map(|link| normalize_link(&link))
+        .filter(|link| own.as_ref() != Some(link))
+        .filter(|link| seen.insert(link.clone()))
+        .take(MAX_LINKS_PER_DOCUMENT)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_and_normalizes_links() {
+        let base = Url::parse("https://blog.example/posts/one").unwrap();
+        let html = r##"<p>See <a href="/posts/two#intro">two</a>,
+            <a class="x" href='https://other.example/'>other</a>,
+            <a href="mailto:me@example.com">mail</a> and <a href="#top">top</a>.</p>"##;
+        let text = "Also https://third.example/page. and (https://other.example/)";
+
+        let links = collect_links(
+            Some("https://blog.example/posts/one"),
+            extract_html_links(html, &base)
+                .into_iter()
+                .chain(extract_text_links(text)),
+        );
+        assert_eq!(
+            links,
+            vec![
+                "https://blog.example/posts/two",
+                "https://other.example",
+                "https://third.example/page",
+            ]
+        );
+    }
+}
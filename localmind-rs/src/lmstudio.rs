@@ -0,0 +1,165 @@
+//! LM Studio embedding client.
+//!
+//! LM Studio's local server exposes an OpenAI-compatible API; this client
+//! uses its `/v1/embeddings` and `/v1/models` endpoints.
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Default LM Studio server address
+pub const DEFAULT_LMSTUDIO_URL: &str = "http://localhost:1234";
+
+/// Request payload for `POST /v1/embeddings`
+#[derive(Debug, Clone, Serialize)]
+struct EmbeddingsRequest<'a> {
+    model: &'a str,
+    input: &'a str,
+}
+
+/// Response payload from `POST /v1/embeddings`
+#[derive(Debug, Clone, Deserialize)]
+struct EmbeddingsResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct EmbeddingData {
+    embedding: Vec<f32>,
+}
+
+/// Response payload from `GET /v1/models`
+#[derive(Debug, Clone, Deserialize)]
+struct ModelsResponse {
+    data: Vec<ModelEntry>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ModelEntry {
+    id: String,
+}
+
+/// HTTP client for an LM Studio server
+#[derive(Debug, Clone)]
+pub struct LmStudioClient {
+    client: Client,
+    base_url: String,
+    model: String,
+}
+
+impl LmStudioClient {
+    /// Create a client for `base_url` (e.g. `http://localhost:1234`) that
+    /// embeds with `model`.
+    pub fn new(base_url: &str, model: &str) -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            model: model.to_string(),
+        }
+    }
+
+    /// Model used for embeddings.
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// Generate an embedding for the given text.
+    pub async fn generate_embedding(&self, text: &str) -> anyhow::Result<Vec<f32>> {
+        let url = format!("{}/v1/embeddings", self.base_url);
+        let response = self
+            .client
+            .post(&url)
+            .json(&EmbeddingsRequest {
+                model: &self.model,
+                input: text,
+            })
+            .send()
+            .await
+            .map_err(|e| {
+                anyhow::anyhow!(
+                    "Failed to connect to LM Studio at {}: {}. Make sure its local server is started.",
+                    self.base_url,
+                    e
+                )
+            })?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(anyhow::anyhow!(
+                "LM Studio returned status {}: {}",
+                status,
+                error_text
+            ));
+        }
+
+        let body: EmbeddingsResponse = response
+            .json()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to parse LM Studio embedding response: {}", e))?;
+
+        body.data
+            .into_iter()
+            .next()
+            .map(|d| d.embedding)
+            .filter(|embedding| !embedding.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("LM Studio returned no embedding"))
+    }
+
+    /// Check that the server answers and lists the configured model.
+    ///
+    /// Returns `Ok(false)` if the server is up but the model is not available.
+    pub async fn health_check(&self) -> anyhow::Result<bool> {
+        let models = self.list_models().await?;
+        Ok(models.iter().any(|id| id == &self.model))
+    }
+
+    /// IDs of the models the server can load.
+    pub async fn list_models(&self) -> anyhow::Result<Vec<String>> {
+        let url = format!("{}/v1/models", self.base_url);
+        let response = self.client.get(&url).send().await.map_err(|e| {
+            anyhow::anyhow!("Failed to connect to LM Studio at {}: {}", self.base_url, e)
+        })?;
+
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!(
+                "LM Studio model list failed with status: {}",
+                response.status()
+            ));
+        }
+
+        let models: ModelsResponse = response
+            .json()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to parse LM Studio model list: {}", e))?;
+
+        Ok(models.data.into_iter().map(|m| m.id).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_responses_deserialize() {
+        let embeddings: EmbeddingsResponse = serde_json::from_str(
+            r#"{"object":"list","data":[{"object":"embedding","embedding":[0.5,0.25],"index":0}]}"#,
+        )
+        .unwrap();
+        assert_eq!(embeddings.data[0].embedding, vec![0.5, 0.25]);
+
+        let models: ModelsResponse = serde_json::from_str(
+            r#"{"data":[{"id":"text-embedding-nomic-embed-text-v1.5","object":"model"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(models.data[0].id, "text-embedding-nomic-embed-text-v1.5");
+    }
+}
@@ -21,6 +21,9 @@ const MAX_RETRIES: u32 = 10;
 /// Base delay for exponential backoff (milliseconds)
 const BASE_DELAY_MS: u64 = 500;
 
+/// Most texts the server accepts in one batch request
+const MAX_BATCH_SIZE: usize = 64;
+
 /// Request payload for embedding generation
 #[derive(Debug, Clone, Serialize)]
 pub struct EmbeddingRequest {
@@ -35,6 +38,20 @@ pub struct EmbeddingResponse {
     pub dimension: usize,
 }
 
+/// Request payload for embedding several texts at once
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchEmbeddingRequest {
+    pub texts: Vec<String>,
+}
+
+/// Response payload with one embedding per requested text
+#[derive(Debug, Clone, Deserialize)]
+pub struct BatchEmbeddingResponse {
+    pub embeddings: Vec<Vec<f32>>,
+    pub model: String,
+    pub dimension: usize,
+}
+
 /// Error response from the embedding server
 #[derive(Debug, Clone, Deserialize)]
 pub struct ErrorResponse {
@@ -59,19 +76,21 @@ impl LocalEmbeddingClient {
     ///
     /// A new `LocalEmbeddingClient` configured to connect to the embedding server.
     pub fn new() -> Self {
-        let port = env::var("EMBEDDING_SERVER_PORT")
-            .ok()
-            .and_then(|p| p.parse::<u16>().ok())
-            .unwrap_or(DEFAULT_PORT);
-
-        let base_url = format!("http://localhost:{}", port);
+        Self::with_base_url(&default_base_url())
+    }
 
+    /// Create a client for an embedding server at `base_url`
+    /// (e.g. `http://localhost:8000`).
+    pub fn with_base_url(base_url: &str) -> Self {
         let client = Client::builder()
             .timeout(Duration::from_secs(30))
             .build()
             .expect("Failed to create HTTP client");
 
-        Self { client, base_url }
+        Self {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
     }
 
     /// Generate an embedding for the given text.
@@ -114,105 +133,141 @@ impl LocalEmbeddingClient {
             text: text.to_string(),
         };
 
-        let mut attempts = 0;
+        let response = self.post_with_retry(&url, &request_body).await?;
+        if !response.status().is_success() {
+            return Err(error_from_response(response).await);
+        }
 
-        loop {
-            attempts += 1;
+        // Parse successful response
+        let embedding_response: EmbeddingResponse = response
+            .json()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to parse embedding response: {}", e))?;
 
-            let response = self
-                .client
-                .post(&url)
-                .json(&request_body)
-                .send()
-                .await
-                .map_err(|e| {
-                    anyhow::anyhow!(
-                        "Failed to connect to embedding server at {}: {}. \
-                         Make sure the Python embedding server is running.",
-                        self.base_url,
-                        e
-                    )
-                })?;
+        // Validate dimension
+        if embedding_response.dimension != EXPECTED_DIMENSION {
+            return Err(anyhow::anyhow!(
+                "Embedding dimension mismatch: expected {}, got {}",
+                EXPECTED_DIMENSION,
+                embedding_response.dimension
+            ));
+        }
 
-            let status = response.status();
+        if embedding_response.embedding.len() != EXPECTED_DIMENSION {
+            return Err(anyhow::anyhow!(
+                "Embedding vector length mismatch: expected {}, got {}",
+                EXPECTED_DIMENSION,
+                embedding_response.embedding.len()
+            ));
+        }
 
-            // Handle 503 Service Unavailable (model still loading)
-            if status == reqwest::StatusCode::SERVICE_UNAVAILABLE {
-                if attempts >= MAX_RETRIES {
-                    return Err(anyhow::anyhow!(
-                        "Embedding server still loading after {} attempts. \
-                         Please wait for the model to finish loading and try again.",
-                        MAX_RETRIES
-                    ));
-                }
+        log::debug!(
+            "Successfully generated {}-dimensional embedding from model '{}'",
+            embedding_response.dimension,
+            embedding_response.model
+        );
 
-                // Exponential backoff
-                let delay = Duration::from_millis(BASE_DELAY_MS * 2_u64.pow(attempts - 1));
-                log::info!(
-                    "Embedding server is loading (attempt {}/{}), retrying in {:?}...",
-                    attempts,
-                    MAX_RETRIES,
-                    delay
-                );
-                sleep(delay).await;
-                continue;
-            }
+        Ok(embedding_response.embedding)
+    }
 
-            // Handle other non-success status codes
-            if !status.is_success() {
-                let error_text = response
-                    .text()
-                    .await
-                    .unwrap_or_else(|_| "Unknown error".to_string());
-
-                // Try to parse as ErrorResponse
-                if let Ok(error_response) = serde_json::from_str::<ErrorResponse>(&error_text) {
-                    return Err(anyhow::anyhow!(
-                        "Embedding server error: {} ({})",
-                        error_response.error,
-                        error_response
-                            .detail
-                            .unwrap_or_else(|| "No details provided".to_string())
-                    ));
+    /// Generate embeddings for several texts with one request to the server's
+    /// batch endpoint, in input order.
+    ///
+    /// Texts are sent in groups of `MAX_BATCH_SIZE`. Servers without the batch
+    /// endpoint (older versions) are asked one text at a time instead.
+    pub async fn generate_embeddings(&self, texts: &[String]) -> anyhow::Result<Vec<Vec<f32>>> {
+        let url = format!("{}/embed_batch", self.base_url);
+        let mut embeddings = Vec::with_capacity(texts.len());
+
+        for batch in texts.chunks(MAX_BATCH_SIZE) {
+            let request_body = BatchEmbeddingRequest {
+                texts: batch.to_vec(),
+            };
+            let response = self.post_with_retry(&url, &request_body).await?;
+
+            if response.status() == reqwest::StatusCode::NOT_FOUND {
+                log::info!("Embedding server has no batch endpoint, embedding one text at a time");
+                for text in &texts[embeddings.len()..] {
+                    embeddings.push(self.generate_embedding(text).await?);
                 }
-
-                return Err(anyhow::anyhow!(
-                    "Embedding server returned status {}: {}",
-                    status,
-                    error_text
-                ));
+                return Ok(embeddings);
+            }
+            if !response.status().is_success() {
+                return Err(error_from_response(response).await);
             }
 
-            // Parse successful response
-            let embedding_response: EmbeddingResponse = response
+            let batch_response: BatchEmbeddingResponse = response
                 .json()
                 .await
-                .map_err(|e| anyhow::anyhow!("Failed to parse embedding response: {}", e))?;
+                .map_err(|e| anyhow::anyhow!("Failed to parse batch embedding response: {}", e))?;
 
-            // Validate dimension
-            if embedding_response.dimension != EXPECTED_DIMENSION {
+            if batch_response.embeddings.len() != batch.len() {
                 return Err(anyhow::anyhow!(
-                    "Embedding dimension mismatch: expected {}, got {}",
-                    EXPECTED_DIMENSION,
-                    embedding_response.dimension
+                    "Batch embedding count mismatch: sent {} texts, got {} embeddings",
+                    batch.len(),
+                    batch_response.embeddings.len()
                 ));
             }
-
-            if embedding_response.embedding.len() != EXPECTED_DIMENSION {
+            if let Some(bad) = batch_response
+                .embeddings
+                .iter()
+                .find(|e| e.len() != EXPECTED_DIMENSION)
+            {
                 return Err(anyhow::anyhow!(
                     "Embedding vector length mismatch: expected {}, got {}",
                     EXPECTED_DIMENSION,
-                    embedding_response.embedding.len()
+                    bad.len()
                 ));
             }
 
-            log::debug!(
-                "Successfully generated {}-dimensional embedding from model '{}'",
-                embedding_response.dimension,
-                embedding_response.model
-            );
+            embeddings.extend(batch_response.embeddings);
+        }
+
+        Ok(embeddings)
+    }
+
+    /// POST a JSON body, retrying with exponential backoff while the server
+    /// answers 503 (model still loading). Returns the first other response.
+    async fn post_with_retry<T: Serialize>(
+        &self,
+        url: &str,
+        body: &T,
+    ) -> anyhow::Result<reqwest::Response> {
+        let mut attempts = 0;
+
+        loop {
+            attempts += 1;
+
+            let response = self.client.post(url).json(body).send().await.map_err(|e| {
+                anyhow::anyhow!(
+                    "Failed to connect to embedding server at {}: {}. \
+                     Make sure the Python embedding server is running.",
+                    self.base_url,
+                    e
+                )
+            })?;
+
+            // Handle 503 Service Unavailable (model still loading)
+            if response.status() != reqwest::StatusCode::SERVICE_UNAVAILABLE {
+                return Ok(response);
+            }
+            if attempts >= MAX_RETRIES {
+                return Err(anyhow::anyhow!(
+                    "Embedding server still loading after {} attempts. \
+                     Please wait for the model to finish loading and try again.",
+                    MAX_RETRIES
+                ));
+            }
 
-            return Ok(embedding_response.embedding);
+            // Exponential backoff
+            let delay = Duration::from_millis(BASE_DELAY_MS * 2_u64.pow(attempts - 1));
+            log::info!(
+                "Embedding server is loading (attempt {}/{}), retrying in {:?}...",
+                attempts,
+                MAX_RETRIES,
+                delay
+            );
+            sleep(delay).await;
         }
     }
 
@@ -268,6 +323,42 @@ impl LocalEmbeddingClient {
     }
 }
 
+/// Turn a non-success response from the embedding server into an error.
+async fn error_from_response(response: reqwest::Response) -> anyhow::Error {
+    let status = response.status();
+    let error_text = response
+        .text()
+        .await
+        .unwrap_or_else(|_| "Unknown error".to_string());
+
+    // Try to parse as ErrorResponse
+    if let Ok(error_response) = serde_json::from_str::<ErrorResponse>(&error_text) {
+        return anyhow::anyhow!(
+            "Embedding server error: {} ({})",
+            error_response.error,
+            error_response
+                .detail
+                .unwrap_or_else(|| "No details provided".to_string())
+        );
+    }
+
+    anyhow::anyhow!(
+        "Embedding server returned status {}: {}",
+        status,
+        error_text
+    )
+}
+
+/// Embedding server address from `EMBEDDING_SERVER_PORT`, or port 8000.
+pub fn default_base_url() -> String {
+    let port = env::var("EMBEDDING_SERVER_PORT")
+        .ok()
+        .and_then(|p| p.parse::<u16>().ok())
+        .unwrap_or(DEFAULT_PORT);
+
+    format!("http://localhost:{}", port)
+}
+
 impl Default for LocalEmbeddingClient {
     fn default() -> Self {
         Self::new()
@@ -373,6 +464,19 @@ mod tests {
         assert!(json.contains("\"test\""));
     }
 
+    #[test]
+    fn test_batch_embedding_response_deserialization() {
+        let json = r#"{
+            "embeddings": [[0.1, 0.2], [0.3, 0.4]],
+            "model": "google/embeddinggemma-300M",
+            "dimension": 2
+        }"#;
+
+        let response: BatchEmbeddingResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(response.embeddings.len(), 2);
+        assert_eq!(response.embeddings[1], vec![0.3, 0.4]);
+    }
+
     #[test]
     fn test_embedding_response_deserialization() {
         let json = r#"{
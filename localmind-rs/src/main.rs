@@ -7,15 +7,26 @@
 //! Provides semantic search over bookmarks and documents.
 
 use eframe::egui;
-use localmind_rs::gui::LocalMindApp;
+use localmind_rs::gui::{accessibility, LocalMindApp};
 
 fn main() -> eframe::Result<()> {
+    // Headless mode: run the backend services and HTTP server without a window
+    if std::env::args().any(|arg| arg == "--headless") {
+        println!("Starting LocalMind in headless mode");
+        if let Err(e) = localmind_rs::services::run_headless() {
+            eprintln!("LocalMind headless mode failed: {}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
     println!("Starting LocalMind application");
 
     // Try to load icon from embedded bytes (gracefully handle errors)
     let icon = load_icon(include_bytes!("../icons/icon.png"));
 
-    // Configure window options
+    // Configure window options; eframe restores the size and position the
+    // window had at the last exit
     let mut viewport_builder = egui::ViewportBuilder::default()
         .with_title("LocalMind")
         .with_inner_size([1024.0, 768.0])
@@ -39,15 +50,8 @@ fn main() -> eframe::Result<()> {
         "LocalMind",
         options,
         Box::new(|cc| {
-            // Apply dark blue theme
-            let mut visuals = egui::Visuals::dark();
-            // Set dark blue backgrounds
-            visuals.window_fill = egui::Color32::from_rgb(20, 30, 50); // Dark blue
-            visuals.panel_fill = egui::Color32::from_rgb(20, 30, 50); // Dark blue
-            visuals.extreme_bg_color = egui::Color32::from_rgb(15, 25, 40); // Darker blue
-            visuals.faint_bg_color = egui::Color32::from_rgb(25, 35, 55); // Slightly lighter blue
-            visuals.code_bg_color = egui::Color32::from_rgb(20, 30, 50); // Dark blue
-            cc.egui_ctx.set_visuals(visuals);
+            // Apply the dark blue theme until the saved appearance loads
+            accessibility::apply(&cc.egui_ctx, &accessibility::Appearance::default());
 
             // Initialize icon fonts
             let mut fonts = egui::FontDefinitions::default();
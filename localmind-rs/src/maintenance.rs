@@ -0,0 +1,167 @@
+//! Database maintenance: optimize and VACUUM
+//!
+//! SQLite never gives space back to the file system on its own, so after
+//! large deletions the database file keeps its size. A maintenance run
+//! refreshes the query planner statistics (`PRAGMA optimize`), merges the
+//! full-text index segments (FTS5 `optimize`) and rebuilds the file with
+//! `VACUUM`, reporting the size before and after. It runs from Settings or
+//! on a schedule, on the background-ingest priority path so searches keep
+//! going while it waits for the database.
+
+use crate::db::Database;
+use crate::Result;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Default days between scheduled runs. 0 disables scheduling.
+pub const DEFAULT_INTERVAL_DAYS: u64 = 0;
+
+/// Guards against the scheduler and a manual run overlapping
+static MAINTENANCE_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// One step of a maintenance run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaintenanceStep {
+    /// Refresh query planner statistics
+    Optimize,
+    /// Merge full-text index segments
+    OptimizeFts,
+    /// Rebuild the database file without free pages
+    Vacuum,
+}
+
+impl MaintenanceStep {
+    pub const ALL: [MaintenanceStep; 3] = [
+        MaintenanceStep::Optimize,
+        MaintenanceStep::OptimizeFts,
+        MaintenanceStep::Vacuum,
+    ];
+}
+
+/// Database file size before and after a run.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MaintenanceReport {
+    pub size_before: u64,
+    pub size_after: u64,
+}
+
+impl MaintenanceReport {
+    /// Bytes given back to the file system.
+    pub fn freed(&self) -> u64 {
+        self.size_before.saturating_sub(self.size_after)
+    }
+}
+
+/// Progress of a maintenance run, sent to the UI before each step and once
+/// more with the report when it finishes.
+#[derive(Debug, Clone, Default)]
+pub struct MaintenanceProgress {
+    /// Step about to run; `None` once finished
+    pub step: Option<MaintenanceStep>,
+    /// Steps already done
+    pub done: usize,
+    pub total: usize,
+    pub report: Option<MaintenanceReport>,
+}
+
+impl MaintenanceProgress {
+    /// Progress of a run that is about to start.
+    pub fn starting() -> Self {
+        Self {
+            step: Some(MaintenanceStep::Optimize),
+            done: 0,
+            total: MaintenanceStep::ALL.len(),
+            report: None,
+        }
+    }
+
+    /// Whether the run has ended; without a report it failed.
+    pub fn is_finished(&self) -> bool {
+        self.step.is_none()
+    }
+}
+
+/// Run every maintenance step, sending progress if `progress_tx` is given.
+/// Fails if another run is already in progress.
+pub async fn run_maintenance(
+    db: &Database,
+    progress_tx: Option<&std::sync::mpsc::Sender<MaintenanceProgress>>,
+) -> Result<MaintenanceReport> {
+    if MAINTENANCE_RUNNING.swap(true, Ordering::SeqCst) {
+        return Err("Database maintenance is already running".into());
+    }
+
+    let result = run_maintenance_inner(db, progress_tx).await;
+    MAINTENANCE_RUNNING.store(false, Ordering::SeqCst);
+    result
+}
+
+async fn run_maintenance_inner(
+    db: &Database,
+    progress_tx: Option<&std::sync::mpsc::Sender<MaintenanceProgress>>,
+) -> Result<MaintenanceReport> {
+    let send = |progress: MaintenanceProgress| {
+        if let Some(tx) = progress_tx {
+            let _ = tx.send(progress);
+        }
+    };
+
+    let size_before = db.get_database_file_size().await?;
+    let total = MaintenanceStep::ALL.len();
+    for (done, step) in MaintenanceStep::ALL.into_iter().enumerate() {
+        send(MaintenanceProgress {
+            step: Some(step),
+            done,
+            total,
+            report: None,
+        });
+        match step {
+            MaintenanceStep::Optimize => db.optimize().await?,
+            MaintenanceStep::OptimizeFts => db.optimize_fts().await?,
+            MaintenanceStep::Vacuum => db.vacuum().await?,
+        }
+    }
+
+    let report = MaintenanceReport {
+        size_before,
+        size_after: db.get_database_file_size().await?,
+    };
+    db.set_maintenance_last_run().await?;
+    println!(
+        "Database maintenance freed {} bytes ({} -> {})",
+        report.freed(),
+        report.size_before,
+        report.size_after
+    );
+
+    send(MaintenanceProgress {
+        step: None,
+        done: total,
+        total,
+        report: Some(report),
+    });
+    Ok(report)
+}
+
+/// Return `true` if a scheduled run is due based on the configured interval.
+pub async fn is_maintenance_due(db: &Database) -> bool {
+    let interval_days = db
+        .get_maintenance_interval_days()
+        .await
+        .unwrap_or(DEFAULT_INTERVAL_DAYS);
+    if interval_days == 0 {
+        return false;
+    }
+
+    let last_run: u64 = db
+        .get_maintenance_last_run()
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or(0);
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+
+    now.saturating_sub(last_run) >= interval_days * 24 * 3600
+}
@@ -0,0 +1,269 @@
+//! Documents as clean Markdown
+//!
+//! The document view can copy a document to the clipboard or save it to a
+//! `.md` file. Stored content is converted from HTML when it holds markup,
+//! the "Bookmark: ... / URL: ..." header added at ingestion is dropped, and
+//! the title, source URL, source and save date go in YAML front matter.
+
+use regex::Regex;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Lines are not wrapped: Markdown readers reflow paragraphs themselves.
+const MARKDOWN_WIDTH: usize = 10_000;
+
+/// Longest file name stem an exported document gets.
+const MAX_FILE_STEM: usize = 80;
+
+/// Characters not allowed in file names on some platform.
+const RESERVED_FILE_CHARS: [char; 9] = ['/', '\\', ':', '*', '?', '"', '<', '>', '|'];
+
+/// Whether stored content holds HTML markup rather than text.
+fn looks_like_html(content: &str) -> bool {
+    static TAG: OnceLock<Regex> = OnceLock::new();
+    TAG.get_or_init(|| {
+        Regex::new(
+            r"(?i)</?(html|body|article|section|div|p|br|h[1-6]|ul|ol|li|a|span|pre|code|table|blockquote|strong|em)\b[^>]*>",
+        )
+        .unwrap()
+    })
+    .is_match(content)
+}
+
+/// `content` as Markdown, without the ingestion header.
+fn body_markdown(title: &str, content: &str, url: Option<&str>) -> String {
+    let is_local_md = url.is_some_and(|u| u.starts_with("file://") && u.ends_with(".md"));
+    if is_local_md {
+        return strip_front_matter(content.trim()).to_string();
+    }
+
+    let text = strip_ingestion_header(title, content);
+    if looks_like_html(text) {
+        static NON_TEXT: OnceLock<Regex> = OnceLock::new();
+        let non_text = NON_TEXT.get_or_init(|| {
+            Regex::new(
+                r"(?is)<(script|style|noscript|template)\b[^>]*>.*?</(script|style|noscript|template)>",
+            )
+            .unwrap()
+        });
+        let html = non_text.replace_all(text, "");
+        html2text::from_read(html.as_bytes(), MARKDOWN_WIDTH)
+    } else {
+        text.to_string()
+    }
+}
+
+/// Drop the title line and "Bookmark: ... / URL: ..." lines that ingestion
+/// puts before a page's own text.
+fn strip_ingestion_header<'a>(title: &str, content: &'a str) -> &'a str {
+    let title = title.trim();
+    let mut rest = content;
+    loop {
+        let trimmed = rest.trim_start_matches(['\r', '\n']);
+        let (line, after) = trimmed.split_once('\n').unwrap_or((trimmed, ""));
+        let line = line.trim();
+        let is_header = (!title.is_empty() && line == title)
+            || line.starts_with("Bookmark: ")
+            || line.starts_with("URL: ");
+        if !is_header || trimmed.is_empty() {
+            return trimmed;
+        }
+        rest = after;
+    }
+}
+
+/// Remove YAML front matter (`---` … `---`) from the start of a file.
+fn strip_front_matter(s: &str) -> &str {
+    let Some(after_open) = s.strip_prefix("---") else {
+        return s;
+    };
+    match after_open.find("\n---") {
+        Some(close) => after_open[close + "\n---".len()..].trim_start_matches(['\r', '\n']),
+        None => s,
+    }
+}
+
+/// Trim trailing spaces and collapse runs of blank lines.
+fn tidy(markdown: &str) -> String {
+    let mut out = String::with_capacity(markdown.len());
+    let mut blank = true;
+    for line in markdown.lines().map(str::trim_end) {
+        if line.is_empty() {
+            if !blank {
+                out.push('\n');
+            }
+            blank = true;
+        } else {
+            out.push_str(line);
+            out.push('\n');
+            blank = false;
+        }
+    }
+    while out.ends_with("\n\n") {
+        out.pop();
+    }
+    out
+}
+
+/// A YAML double-quoted scalar.
+fn yaml_string(value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', " ");
+    format!("\"{}\"", escaped)
+}
+
+/// A document as Markdown: front matter with its title, source URL,
+/// source and save date, a heading, then its content.
+pub fn document_markdown(
+    title: &str,
+    url: Option<&str>,
+    source: &str,
+    created_at: &str,
+    content: &str,
+) -> String {
+    let mut front_matter = vec![format!("title: {}", yaml_string(title))];
+    if let Some(url) = url {
+        front_matter.push(format!("url: {}", yaml_string(url)));
+    }
+    front_matter.push(format!("source: {}", yaml_string(source)));
+    front_matter.push(format!("saved: {}", yaml_string(created_at)));
+
+    let body = tidy(&body_markdown(title, content, url));
+    let mut markdown = format!(
+        "---\n{}\n---\n\n# {}\n",
+        front_matter.join("\n"),
+        title.trim()
+    );
+    if !body.is_empty() {
+        markdown.push('\n');
+        markdown.push_str(&body);
+    }
+    markdown
+}
+
+/// File name for an exported document: its title without characters that
+/// are not allowed in file names, shortened, with a `.md` extension.
+pub fn export_file_name(title: &str) -> String {
+    let cleaned: String = title
+        .chars()
+        .map(|c| {
+            if c.is_control() || RESERVED_FILE_CHARS.contains(&c) {
+                ' '
+            } else {
+                c
+            }
+        })
+        .collect();
+    let stem: String = cleaned
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_matches('.')
+        .chars()
+        .take(MAX_FILE_STEM)
+        .collect();
+    let stem = stem.trim();
+    format!("{}.md", if stem.is_empty() { "document" } else { stem })
+}
+
+/// Folder exported documents are saved to: the Downloads folder, or an
+/// `exports` folder in the data directory when there is none.
+pub fn export_dir() -> PathBuf {
+    dirs::download_dir().unwrap_or_else(|| crate::db::data_dir().join("exports"))
+}
+
+/// Write `markdown` to a new file named after `title` in `dir`, adding a
+/// number when the name is taken. Returns the file's path.
+pub fn export_to(dir: &Path, title: &str, markdown: &str) -> std::io::Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    let name = export_file_name(title);
+    let stem = name.strip_suffix(".md").unwrap_or(&name);
+    let mut path = dir.join(&name);
+    let mut n = 2;
+    while path.exists() {
+        path = dir.join(format!("{} ({}).md", stem, n));
+        n += 1;
+    }
+    std::fs::write(&path, markdown)?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const URL: &str = "https://example.com/post";
+
+    #[test]
+    fn html_is_converted_with_front_matter() {
+        let content = format!(
+            "Post\n\nBookmark: {}\nURL: {}\n\n<h2>Intro</h2><p>Some <strong>bold</strong> text.</p>\
+             <script>track()</script><ul><li>one</li><li>two</li></ul>",
+            URL, URL
+        );
+        let markdown =
+            document_markdown("Post", Some(URL), "chrome_bookmark", "2024-05-01", &content);
+
+        assert!(markdown.starts_with(
+            "---\ntitle: \"Post\"\nurl: \"https://example.com/post\"\n\
+             source: \"chrome_bookmark\"\nsaved: \"2024-05-01\"\n---\n\n# Post\n\n"
+        ));
+        assert!(markdown.contains("## Intro"));
+        assert!(markdown.contains("**bold**"));
+        assert!(markdown.contains("* one"));
+        assert!(!markdown.contains("<p>"));
+        assert!(!markdown.contains("track()"));
+        assert!(!markdown.contains("Bookmark: "));
+        assert!(markdown.ends_with('\n'));
+    }
+
+    #[test]
+    fn text_keeps_its_lines_without_the_ingestion_header() {
+        let content = format!(
+            "Notes\n\nBookmark: {}\nURL: {}\n\nFirst line.\n\n\n\nSecond line.   \n",
+            URL, URL
+        );
+        let markdown = document_markdown("Notes", Some(URL), "note", "today", &content);
+        assert!(markdown.ends_with("# Notes\n\nFirst line.\n\nSecond line.\n"));
+    }
+
+    #[test]
+    fn local_markdown_files_lose_only_their_front_matter() {
+        let content = "---\ntags: [a]\n---\n# Heading\n\n- item\n";
+        let markdown = document_markdown(
+            "Heading",
+            Some("file:///notes/a.md"),
+            "file",
+            "today",
+            content,
+        );
+        assert!(markdown.ends_with("# Heading\n\n# Heading\n\n- item\n"));
+        assert!(!markdown.contains("tags: [a]"));
+    }
+
+    #[test]
+    fn front_matter_values_are_quoted_and_escaped() {
+        let markdown = document_markdown("Say \"hi\": a\\b", None, "note", "today", "");
+        assert!(markdown.starts_with("---\ntitle: \"Say \\\"hi\\\": a\\\\b\"\nsource:"));
+        assert!(markdown.ends_with("# Say \"hi\": a\\b\n"));
+    }
+
+    #[test]
+    fn file_names_are_safe_and_unique() {
+        assert_eq!(
+            export_file_name("Rust: async/await?"),
+            "Rust async await.md"
+        );
+        assert_eq!(export_file_name("  ...  "), "document.md");
+        assert_eq!(export_file_name(&"x".repeat(200)).len(), MAX_FILE_STEM + 3);
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let first = export_to(dir.path(), "Post", "one").unwrap();
+        let second = export_to(dir.path(), "Post", "two").unwrap();
+        assert_eq!(first.file_name().unwrap(), "Post.md");
+        assert_eq!(second.file_name().unwrap(), "Post (2).md");
+        assert_eq!(std::fs::read_to_string(second).unwrap(), "two");
+    }
+}
@@ -0,0 +1,106 @@
+//! Prometheus metrics
+//!
+//! Ingestion, embedding, search and database timings are recorded through
+//! the `metrics` crate and served in the Prometheus text format at
+//! `GET /metrics` on the HTTP API, so a headless install can be scraped by
+//! an existing monitoring stack. Nothing is kept until `handle` first
+//! installs the recorder, which the HTTP server does when it starts.
+
+use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+/// Documents stored, by source.
+pub const DOCUMENTS_INGESTED: &str = "localmind_documents_ingested_total";
+/// One call to the embedding backend, for a text or a batch.
+pub const EMBEDDING_SECONDS: &str = "localmind_embedding_duration_seconds";
+/// A fused keyword and semantic search.
+pub const SEARCH_SECONDS: &str = "localmind_search_duration_seconds";
+/// Bookmark fetches waiting or in progress, and queued API saves.
+pub const QUEUE_DEPTH: &str = "localmind_ingest_queue_depth";
+/// One database operation, by priority.
+pub const DB_OPERATION_SECONDS: &str = "localmind_db_operation_duration_seconds";
+
+/// Histogram buckets, in seconds.
+const BUCKETS: &[f64] = &[
+    0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0,
+];
+
+/// How often the recorder drains histogram samples between scrapes.
+pub const UPKEEP_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The installed recorder, installing it on first use; `None` when another
+/// recorder was installed first.
+pub fn handle() -> Option<&'static PrometheusHandle> {
+    static HANDLE: OnceLock<Option<PrometheusHandle>> = OnceLock::new();
+    HANDLE
+        .get_or_init(|| {
+            let installed = PrometheusBuilder::new()
+                .set_buckets(BUCKETS)
+                .and_then(|builder| builder.install_recorder());
+            match installed {
+                Ok(handle) => {
+                    describe();
+                    Some(handle)
+                }
+                Err(e) => {
+                    eprintln!("Failed to install metrics recorder: {}", e);
+                    None
+                }
+            }
+        })
+        .as_ref()
+}
+
+fn describe() {
+    metrics::describe_counter!(DOCUMENTS_INGESTED, "Documents stored, by source");
+    metrics::describe_histogram!(
+        EMBEDDING_SECONDS,
+        metrics::Unit::Seconds,
+        "Embedding backend calls"
+    );
+    metrics::describe_histogram!(
+        SEARCH_SECONDS,
+        metrics::Unit::Seconds,
+        "Fused keyword and semantic searches"
+    );
+    metrics::describe_gauge!(QUEUE_DEPTH, "Documents waiting to be ingested, by queue");
+    metrics::describe_histogram!(
+        DB_OPERATION_SECONDS,
+        metrics::Unit::Seconds,
+        "Database operations, by priority"
+    );
+}
+
+pub fn record_ingested(source: &str, count: usize) {
+    metrics::counter!(DOCUMENTS_INGESTED, "source" => source.to_string()).increment(count as u64);
+}
+
+pub fn record_db_operation(priority: &'static str, elapsed: Duration) {
+    metrics::histogram!(DB_OPERATION_SECONDS, "priority" => priority).record(elapsed.as_secs_f64());
+}
+
+pub fn set_queue_depth(queue: &'static str, depth: usize) {
+    metrics::gauge!(QUEUE_DEPTH, "queue" => queue).set(depth as f64);
+}
+
+/// Records the time until it is dropped in a histogram.
+pub struct Timer {
+    histogram: &'static str,
+    started: Instant,
+}
+
+impl Timer {
+    pub fn start(histogram: &'static str) -> Self {
+        Self {
+            histogram,
+            started: Instant::now(),
+        }
+    }
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        metrics::histogram!(self.histogram).record(self.started.elapsed().as_secs_f64());
+    }
+}
@@ -0,0 +1,51 @@
+//! Native OS notifications for important background events
+//!
+//! In-app toasts are easy to miss while LocalMind is minimized, so a few
+//! events also raise a desktop notification. Each event type can be turned
+//! off in Settings; the choice is stored in the `config` table.
+
+use crate::Result;
+use std::path::Path;
+
+/// Free space below which the disk counts as nearly full.
+pub const LOW_DISK_SPACE_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// Events that can raise a native notification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NotificationEvent {
+    IngestionComplete,
+    EmbeddingServerDown,
+    DiskSpaceLow,
+}
+
+impl NotificationEvent {
+    pub const ALL: [NotificationEvent; 3] = [
+        NotificationEvent::IngestionComplete,
+        NotificationEvent::EmbeddingServerDown,
+        NotificationEvent::DiskSpaceLow,
+    ];
+
+    /// `config` table key holding the opt-out for this event.
+    pub fn config_key(self) -> &'static str {
+        match self {
+            NotificationEvent::IngestionComplete => "notify_ingestion_complete",
+            NotificationEvent::EmbeddingServerDown => "notify_embedding_server_down",
+            NotificationEvent::DiskSpaceLow => "notify_disk_space_low",
+        }
+    }
+}
+
+/// Show a desktop notification.
+pub fn show_native(title: &str, body: &str) -> Result<()> {
+    notify_rust::Notification::new()
+        .appname("LocalMind")
+        .summary(title)
+        .body(body)
+        .show()?;
+    Ok(())
+}
+
+/// Free bytes on the disk holding `path`, if it can be determined.
+pub fn available_space(path: &Path) -> Option<u64> {
+    fs2::available_space(path).ok()
+}
@@ -0,0 +1,175 @@
+//! Ollama embedding client.
+//!
+//! Talks to a local Ollama server (`ollama serve`) to embed text with any
+//! pulled embedding model, e.g. `nomic-embed-text` or `embeddinggemma`.
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Default Ollama server address
+pub const DEFAULT_OLLAMA_URL: &str = "http://localhost:11434";
+
+/// Request payload for `POST /api/embed`
+#[derive(Debug, Clone, Serialize)]
+struct EmbedRequest<'a> {
+    model: &'a str,
+    input: &'a str,
+}
+
+/// Response payload from `POST /api/embed`
+#[derive(Debug, Clone, Deserialize)]
+struct EmbedResponse {
+    embeddings: Vec<Vec<f32>>,
+}
+
+/// Response payload from `GET /api/tags`
+#[derive(Debug, Clone, Deserialize)]
+struct TagsResponse {
+    models: Vec<ModelTag>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ModelTag {
+    name: String,
+}
+
+/// HTTP client for an Ollama server
+#[derive(Debug, Clone)]
+pub struct OllamaClient {
+    client: Client,
+    base_url: String,
+    model: String,
+}
+
+impl OllamaClient {
+    /// Create a client for `base_url` (e.g. `http://localhost:11434`) that
+    /// embeds with `model`.
+    pub fn new(base_url: &str, model: &str) -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            model: model.to_string(),
+        }
+    }
+
+    /// Model used for embeddings.
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// Generate an embedding for the given text.
+    pub async fn generate_embedding(&self, text: &str) -> anyhow::Result<Vec<f32>> {
+        let url = format!("{}/api/embed", self.base_url);
+        let response = self
+            .client
+            .post(&url)
+            .json(&EmbedRequest {
+                model: &self.model,
+                input: text,
+            })
+            .send()
+            .await
+            .map_err(|e| {
+                anyhow::anyhow!(
+                    "Failed to connect to Ollama at {}: {}. Make sure `ollama serve` is running.",
+                    self.base_url,
+                    e
+                )
+            })?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(anyhow::anyhow!(
+                "Ollama returned status {}: {}",
+                status,
+                error_text
+            ));
+        }
+
+        let body: EmbedResponse = response
+            .json()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to parse Ollama embedding response: {}", e))?;
+
+        body.embeddings
+            .into_iter()
+            .next()
+            .filter(|embedding| !embedding.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("Ollama returned no embedding"))
+    }
+
+    /// Check that the server answers and has the configured model pulled.
+    ///
+    /// Returns `Ok(false)` if the server is up but the model is missing.
+    pub async fn health_check(&self) -> anyhow::Result<bool> {
+        let models = self.list_models().await?;
+        Ok(models.iter().any(|name| model_matches(name, &self.model)))
+    }
+
+    /// Names of the models pulled on the server.
+    pub async fn list_models(&self) -> anyhow::Result<Vec<String>> {
+        let url = format!("{}/api/tags", self.base_url);
+        let response = self.client.get(&url).send().await.map_err(|e| {
+            anyhow::anyhow!("Failed to connect to Ollama at {}: {}", self.base_url, e)
+        })?;
+
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!(
+                "Ollama model list failed with status: {}",
+                response.status()
+            ));
+        }
+
+        let tags: TagsResponse = response
+            .json()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to parse Ollama model list: {}", e))?;
+
+        Ok(tags.models.into_iter().map(|m| m.name).collect())
+    }
+}
+
+/// Ollama lists models with a tag (`nomic-embed-text:latest`) but accepts the
+/// bare name too.
+fn model_matches(listed: &str, configured: &str) -> bool {
+    listed == configured || listed.strip_suffix(":latest") == Some(configured)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_responses_deserialize() {
+        let embed: EmbedResponse =
+            serde_json::from_str(r#"{"model":"m","embeddings":[[0.1,0.2,0.3]]}"#).unwrap();
+        assert_eq!(embed.embeddings[0].len(), 3);
+
+        let tags: TagsResponse =
+            serde_json::from_str(r#"{"models":[{"name":"nomic-embed-text:latest","size":1}]}"#)
+                .unwrap();
+        assert_eq!(tags.models[0].name, "nomic-embed-text:latest");
+    }
+
+    #[test]
+    fn test_model_matches_latest_tag() {
+        assert!(model_matches("nomic-embed-text:latest", "nomic-embed-text"));
+        assert!(model_matches("bge-m3:567m", "bge-m3:567m"));
+        assert!(!model_matches("bge-m3:567m", "bge-m3"));
+    }
+
+    #[test]
+    fn test_new_trims_trailing_slash() {
+        let client = OllamaClient::new("http://localhost:11434/", "m");
+        assert_eq!(client.base_url, DEFAULT_OLLAMA_URL);
+    }
+}
@@ -0,0 +1,374 @@
+//! Junk-page detection
+//!
+//! Some web pages come back with status 200 but hold nothing worth
+//! searching: login walls, cookie-consent interstitials, "404 page not
+//! found" pages and pages that are all navigation. Before a page is indexed
+//! its text is checked against signature phrases, gated by how short the
+//! page is, and against the share of its lines that are menu-sized. A page
+//! that looks like junk is indexed by its title and URL only and listed in
+//! Settings for review, where it can be fetched again, kept in full or
+//! moved to the trash.
+//!
+//! Pages whose text is only a fetch placeholder ("[No content extracted]",
+//! "[Error fetching content: ...]") or a handful of words are not embedded
+//! at all: they are marked as needing a refetch, left out of search results
+//! and listed in Settings until a refetch finds real content.
+
+/// Pages with at least this many words are never flagged by a signature
+/// phrase; real articles mention "sign in" and "cookies" too.
+const MAX_WALL_WORDS: usize = 300;
+
+/// Below this many words a single signature phrase is enough; above it, two
+/// different phrases are needed.
+const SHORT_PAGE_WORDS: usize = 80;
+
+/// Lines with fewer words than this count as navigation or boilerplate.
+const PROSE_LINE_WORDS: usize = 8;
+
+/// A page needs at least this many lines to be judged on its boilerplate
+/// ratio.
+const MIN_BOILERPLATE_LINES: usize = 15;
+
+/// Web pages with fewer words of their own than this have no real content.
+const MIN_CONTENT_WORDS: usize = 5;
+
+/// Text put in place of a page's content when fetching it failed.
+const PLACEHOLDERS: [&str; 2] = ["[No content extracted", "[Error fetching content"];
+
+/// Share of short lines above which a page counts as boilerplate...
+const BOILERPLATE_RATIO: f32 = 0.85;
+
+/// ...provided its prose lines hold fewer words than this.
+const MIN_PROSE_WORDS: usize = 60;
+
+const NOT_FOUND_PHRASES: [&str; 10] = [
+    "page not found",
+    "404 not found",
+    "404 error",
+    "error 404",
+    "page does not exist",
+    "page doesn't exist",
+    "page could not be found",
+    "page you requested could not be found",
+    "page you were looking for",
+    "page you are looking for",
+];
+
+const LOGIN_PHRASES: [&str; 14] = [
+    "sign in to continue",
+    "log in to continue",
+    "login to continue",
+    "please sign in",
+    "please log in",
+    "you must be logged in",
+    "you need to sign in",
+    "you need to log in",
+    "sign in to view",
+    "log in to view",
+    "sign in to your account",
+    "log in to your account",
+    "forgot your password",
+    "forgot password",
+];
+
+const COOKIE_PHRASES: [&str; 12] = [
+    "we use cookies",
+    "this site uses cookies",
+    "this website uses cookies",
+    "accept all cookies",
+    "accept cookies",
+    "cookie settings",
+    "cookie preferences",
+    "manage cookies",
+    "manage consent",
+    "consent preferences",
+    "your privacy choices",
+    "before you continue",
+];
+
+/// Why a page was judged not worth indexing in full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QualityIssue {
+    /// A "page not found" page served with status 200
+    NotFound,
+    /// A sign-in form instead of the content
+    LoginWall,
+    /// A cookie-consent interstitial instead of the content
+    CookieWall,
+    /// Navigation and boilerplate with next to no prose
+    Boilerplate,
+}
+
+impl QualityIssue {
+    pub const ALL: [QualityIssue; 4] = [
+        QualityIssue::NotFound,
+        QualityIssue::LoginWall,
+        QualityIssue::CookieWall,
+        QualityIssue::Boilerplate,
+    ];
+
+    /// Stored in `documents.quality_issue`
+    pub fn code(self) -> &'static str {
+        match self {
+            QualityIssue::NotFound => "not_found",
+            QualityIssue::LoginWall => "login_wall",
+            QualityIssue::CookieWall => "cookie_wall",
+            QualityIssue::Boilerplate => "boilerplate",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|issue| issue.code() == code)
+    }
+}
+
+/// A page indexed by title and URL only, listed for review.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LowQualityPage {
+    pub id: i64,
+    pub title: String,
+    pub url: String,
+    pub issue: QualityIssue,
+    pub created_at: String,
+}
+
+/// Whether documents with this URL are checked: only fetched web pages are,
+/// not local files or notes.
+fn is_web_page(url: &str) -> bool {
+    url.starts_with("http://") || url.starts_with("https://")
+}
+
+/// The lines of a page's own text: ingestion prepends the title and a
+/// "Bookmark: ... / URL: ..." header, which say nothing about the page.
+fn page_lines<'a>(title: &str, content: &'a str, url: &str) -> Vec<&'a str> {
+    let title = title.trim();
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| {
+            !line.is_empty()
+                && *line != title
+                && *line != url
+                && !line.starts_with("Bookmark: ")
+                && !line.starts_with("URL: ")
+        })
+        .collect()
+}
+
+/// Number of different `phrases` found in `text` (lowercase).
+fn phrase_hits(text: &str, phrases: &[&str]) -> usize {
+    phrases
+        .iter()
+        .filter(|phrase| text.contains(*phrase))
+        .count()
+}
+
+/// Check a page before it is indexed. Returns the issue when it looks like
+/// junk, or `None` when it should be indexed in full. Pages with no text
+/// of their own are left alone.
+pub fn assess(title: &str, content: &str, url: &str) -> Option<QualityIssue> {
+    let lines = page_lines(title, content, url);
+    let line_words: Vec<usize> = lines
+        .iter()
+        .map(|line| line.split_whitespace().count())
+        .collect();
+    let words: usize = line_words.iter().sum();
+    if words == 0 {
+        return None;
+    }
+
+    if words < MAX_WALL_WORDS {
+        let title = title.to_lowercase();
+        let text = lines.join("\n").to_lowercase();
+        let signature = |phrases: &[&str]| {
+            let hits = phrase_hits(&text, phrases);
+            hits >= 2 || (hits == 1 && words < SHORT_PAGE_WORDS)
+        };
+
+        if phrase_hits(&title, &NOT_FOUND_PHRASES) > 0
+            || title.starts_with("404")
+            || signature(&NOT_FOUND_PHRASES)
+        {
+            return Some(QualityIssue::NotFound);
+        }
+        if signature(&LOGIN_PHRASES) {
+            return Some(QualityIssue::LoginWall);
+        }
+        if signature(&COOKIE_PHRASES) {
+            return Some(QualityIssue::CookieWall);
+        }
+    }
+
+    if lines.len() >= MIN_BOILERPLATE_LINES {
+        let short_lines = line_words
+            .iter()
+            .filter(|&&count| count < PROSE_LINE_WORDS)
+            .count();
+        let prose_words: usize = line_words
+            .iter()
+            .filter(|&&count| count >= PROSE_LINE_WORDS)
+            .sum();
+        if short_lines as f32 / lines.len() as f32 >= BOILERPLATE_RATIO
+            && prose_words < MIN_PROSE_WORDS
+        {
+            return Some(QualityIssue::Boilerplate);
+        }
+    }
+
+    None
+}
+
+/// `assess` a document about to be indexed if it is a web page.
+pub fn check(title: &str, content: &str, url: Option<&str>) -> Option<QualityIssue> {
+    url.filter(|url| is_web_page(url))
+        .and_then(|url| assess(title, content, url))
+}
+
+/// Whether a web page about to be indexed has no real content: its text is
+/// a fetch placeholder, or fewer than `MIN_CONTENT_WORDS` words. Pages with
+/// no text of their own at all (bookmarks indexed without fetching, pages
+/// behind a login) are not placeholders.
+pub fn is_placeholder(title: &str, content: &str, url: Option<&str>) -> bool {
+    let Some(url) = url.filter(|url| is_web_page(url)) else {
+        return false;
+    };
+    let lines = page_lines(title, content, url);
+    let (markers, text): (Vec<&str>, Vec<&str>) = lines
+        .into_iter()
+        .partition(|line| PLACEHOLDERS.iter().any(|p| line.starts_with(p)));
+    let words: usize = text
+        .iter()
+        .map(|line| line.split_whitespace().count())
+        .sum();
+    (!markers.is_empty() || words > 0) && words < MIN_CONTENT_WORDS
+}
+
+/// What is indexed for a flagged page: its title and URL.
+pub fn metadata_only(title: &str, url: &str) -> String {
+    format!("{}\nURL: {}", title, url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const URL: &str = "https://example.com/post";
+
+    fn bookmark(title: &str, page: &str) -> String {
+        format!("{}\n\nBookmark: {}\nURL: {}\n\n{}", title, URL, URL, page)
+    }
+
+    #[test]
+    fn status_200_not_found_pages_are_flagged() {
+        let content = bookmark("Oops", "Sorry, the page you were looking for\nGo home");
+        assert_eq!(assess("Oops", &content, URL), Some(QualityIssue::NotFound));
+        assert_eq!(
+            assess("404 - Example", &bookmark("404 - Example", "Home"), URL),
+            Some(QualityIssue::NotFound)
+        );
+    }
+
+    #[test]
+    fn login_and_cookie_walls_are_flagged() {
+        let login = bookmark(
+            "Example",
+            "Please sign in\nEmail\nPassword\nForgot your password?\nSign in",
+        );
+        assert_eq!(
+            assess("Example", &login, URL),
+            Some(QualityIssue::LoginWall)
+        );
+
+        let cookies = bookmark(
+            "Example",
+            "Before you continue\nWe use cookies and data to deliver our services.\nAccept all\nReject all",
+        );
+        assert_eq!(
+            assess("Example", &cookies, URL),
+            Some(QualityIssue::CookieWall)
+        );
+    }
+
+    #[test]
+    fn articles_mentioning_signature_phrases_are_kept() {
+        let sentence = "This guide explains how the login flow works and why a forgot password link matters for users. ";
+        let article = bookmark("Designing login forms", &sentence.repeat(20));
+        assert_eq!(assess("Designing login forms", &article, URL), None);
+
+        let short_note = bookmark(
+            "Cookies",
+            "A recipe for chocolate chip cookies with brown butter.",
+        );
+        assert_eq!(assess("Cookies", &short_note, URL), None);
+    }
+
+    #[test]
+    fn navigation_only_pages_are_boilerplate() {
+        let menu = ["Home", "About", "Products", "Blog", "Contact", "Careers"].join("\n");
+        let page = bookmark("Example", &format!("{}\n{}\n{}", menu, menu, menu));
+        assert_eq!(
+            assess("Example", &page, URL),
+            Some(QualityIssue::Boilerplate)
+        );
+
+        let article = format!(
+            "{}\n{}",
+            menu,
+            "A long paragraph of real prose that explains something useful in detail. ".repeat(10)
+        );
+        assert_eq!(assess("Example", &bookmark("Example", &article), URL), None);
+    }
+
+    #[test]
+    fn pages_without_text_of_their_own_are_left_alone() {
+        assert_eq!(assess("Example", &bookmark("Example", ""), URL), None);
+        assert_eq!(assess("Example", &format!("Example\n\n{}", URL), URL), None);
+    }
+
+    #[test]
+    fn fetch_placeholders_and_near_empty_pages_need_a_refetch() {
+        let missing = bookmark("Example", "[No content extracted]");
+        assert!(is_placeholder("Example", &missing, Some(URL)));
+        let failed = bookmark("Example", "[Error fetching content: timed out]");
+        assert!(is_placeholder("Example", &failed, Some(URL)));
+        assert!(is_placeholder(
+            "Example",
+            &bookmark("Example", "Loading..."),
+            Some(URL)
+        ));
+
+        let article = bookmark("Example", "Five words of real text.");
+        assert!(!is_placeholder("Example", &article, Some(URL)));
+        // Bookmarks indexed without fetching and auth-blocked pages
+        assert!(!is_placeholder(
+            "Example",
+            &format!("Example\n\n{}", URL),
+            Some(URL)
+        ));
+        assert!(!is_placeholder("Example", "Example\n\n", Some(URL)));
+        // Notes and local files are never placeholders
+        assert!(!is_placeholder("Todo", "[No content extracted]", None));
+    }
+
+    #[test]
+    fn issue_codes_round_trip() {
+        for issue in QualityIssue::ALL {
+            assert_eq!(QualityIssue::from_code(issue.code()), Some(issue));
+        }
+        assert_eq!(QualityIssue::from_code("fine"), None);
+    }
+
+    #[test]
+    fn only_web_pages_are_checked() {
+        let not_found = bookmark("Page not found", "Home");
+        assert_eq!(
+            check("Page not found", &not_found, Some(URL)),
+            Some(QualityIssue::NotFound)
+        );
+        assert_eq!(
+            check("Page not found", &not_found, Some("file:///notes/404.md")),
+            None
+        );
+        assert_eq!(check("Page not found", &not_found, None), None);
+    }
+}
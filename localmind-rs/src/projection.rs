@@ -0,0 +1,333 @@
+//! 2D map of the knowledge base for the Explore view
+//!
+//! Each document's vector (the mean of its chunk embeddings) is projected
+//! to two dimensions with t-SNE, which keeps documents that are similar
+//! close together, so topics show up as clusters. Exact t-SNE compares
+//! every pair of documents, so only the `MAX_POINTS` newest documents are
+//! mapped. A run takes seconds to minutes: it happens in a background task
+//! and the coordinates are cached in the database with the embedding model
+//! they came from, so the view opens instantly and offers to recompute when
+//! documents or the model changed.
+
+use crate::db::Database;
+use crate::Result;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Most documents mapped; the newest ones are kept.
+pub const MAX_POINTS: usize = 2000;
+
+/// Effective number of neighbours each document is compared with.
+const PERPLEXITY: f32 = 30.0;
+
+/// Gradient descent steps.
+const ITERATIONS: usize = 500;
+
+/// Steps during which attraction is exaggerated so clusters form early.
+const EXAGGERATION_STEPS: usize = 100;
+
+const EXAGGERATION: f32 = 12.0;
+
+/// Fixed so the same documents give the same map.
+const SEED: u64 = 42;
+
+/// Guards against two projections running at once
+static PROJECTION_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Mean of each document's chunk vectors, scaled to unit length. `chunks`
+/// are (document ID, vector) grouped by document; documents come out in
+/// the order first seen.
+pub fn document_vectors(chunks: impl IntoIterator<Item = (i64, Vec<f32>)>) -> Vec<(i64, Vec<f32>)> {
+    let mut documents: Vec<(i64, Vec<f32>)> = Vec::new();
+    for (document_id, vector) in chunks {
+        match documents.last_mut() {
+            Some((last, sum)) if *last == document_id && sum.len() == vector.len() => {
+                for (s, v) in sum.iter_mut().zip(&vector) {
+                    *s += v;
+                }
+            }
+            _ => documents.push((document_id, vector)),
+        }
+    }
+    for (_, vector) in documents.iter_mut() {
+        let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            vector.iter_mut().for_each(|v| *v /= norm);
+        }
+    }
+    documents
+}
+
+/// Squared Euclidean distances between every pair of vectors, row by row.
+fn squared_distances(vectors: &[Vec<f32>]) -> Vec<f32> {
+    let n = vectors.len();
+    let mut distances = vec![0.0; n * n];
+    for i in 0..n {
+        for j in i + 1..n {
+            let d: f32 = vectors[i]
+                .iter()
+                .zip(&vectors[j])
+                .map(|(a, b)| (a - b) * (a - b))
+                .sum();
+            distances[i * n + j] = d;
+            distances[j * n + i] = d;
+        }
+    }
+    distances
+}
+
+/// Symmetric neighbour probabilities: each row's Gaussian bandwidth is
+/// searched so its distribution has the wanted perplexity.
+fn affinities(distances: &[f32], n: usize, perplexity: f32) -> Vec<f32> {
+    let target_entropy = perplexity.ln();
+    let mut conditional = vec![0.0f32; n * n];
+    for i in 0..n {
+        let row = &distances[i * n..(i + 1) * n];
+        let (mut beta, mut low, mut high) = (1.0f32, 0.0f32, f32::INFINITY);
+        for _ in 0..50 {
+            let mut sum = 0.0;
+            let mut weighted = 0.0;
+            for j in (0..n).filter(|&j| j != i) {
+                let p = (-row[j] * beta).exp();
+                sum += p;
+                weighted += row[j] * p;
+            }
+            let sum = sum.max(f32::MIN_POSITIVE);
+            let entropy = sum.ln() + beta * weighted / sum;
+            if (entropy - target_entropy).abs() < 1e-4 {
+                break;
+            }
+            // Too spread out: narrow the Gaussian, and the other way round
+            if entropy > target_entropy {
+                low = beta;
+                beta = if high.is_finite() {
+                    (beta + high) / 2.0
+                } else {
+                    beta * 2.0
+                };
+            } else {
+                high = beta;
+                beta = (beta + low) / 2.0;
+            }
+        }
+        let mut sum = 0.0;
+        for j in (0..n).filter(|&j| j != i) {
+            let p = (-row[j] * beta).exp();
+            conditional[i * n + j] = p;
+            sum += p;
+        }
+        if sum > 0.0 {
+            conditional[i * n..(i + 1) * n]
+                .iter_mut()
+                .for_each(|p| *p /= sum);
+        }
+    }
+
+    let mut joint = vec![0.0f32; n * n];
+    for i in 0..n {
+        for j in 0..n {
+            joint[i * n + j] =
+                ((conditional[i * n + j] + conditional[j * n + i]) / (2.0 * n as f32)).max(1e-12);
+        }
+    }
+    joint
+}
+
+/// Project unit vectors to 2D with exact t-SNE. Coordinates are scaled into
+/// 0..=1 keeping the aspect ratio.
+pub fn tsne(vectors: &[Vec<f32>]) -> Vec<[f32; 2]> {
+    let n = vectors.len();
+    if n < 3 {
+        return (0..n).map(|i| [0.25 + i as f32 * 0.5, 0.5]).collect();
+    }
+
+    let perplexity = PERPLEXITY.min((n - 1) as f32 / 3.0).max(1.0);
+    let p = affinities(&squared_distances(vectors), n, perplexity);
+
+    // Small random start, as in the original paper
+    let mut rng = StdRng::seed_from_u64(SEED);
+    let mut y: Vec<[f32; 2]> = (0..n)
+        .map(|_| [rng.gen_range(-1e-4..1e-4), rng.gen_range(-1e-4..1e-4)])
+        .collect();
+    let mut velocity = vec![[0.0f32; 2]; n];
+    let mut gains = vec![[1.0f32; 2]; n];
+    let learning_rate = (n as f32 / EXAGGERATION / 4.0).max(50.0);
+    let mut q = vec![0.0f32; n * n];
+
+    for step in 0..ITERATIONS {
+        let exaggeration = if step < EXAGGERATION_STEPS {
+            EXAGGERATION
+        } else {
+            1.0
+        };
+        let momentum = if step < 250 { 0.5 } else { 0.8 };
+
+        // Student-t similarities in the map
+        let mut z = 0.0;
+        for i in 0..n {
+            for j in i + 1..n {
+                let dx = y[i][0] - y[j][0];
+                let dy = y[i][1] - y[j][1];
+                let t = 1.0 / (1.0 + dx * dx + dy * dy);
+                q[i * n + j] = t;
+                q[j * n + i] = t;
+                z += 2.0 * t;
+            }
+        }
+        let z = z.max(f32::MIN_POSITIVE);
+
+        for i in 0..n {
+            let mut gradient = [0.0f32; 2];
+            for j in (0..n).filter(|&j| j != i) {
+                let t = q[i * n + j];
+                let force = (exaggeration * p[i * n + j] - t / z) * t;
+                gradient[0] += 4.0 * force * (y[i][0] - y[j][0]);
+                gradient[1] += 4.0 * force * (y[i][1] - y[j][1]);
+            }
+            for (d, &g) in gradient.iter().enumerate() {
+                // Speed up along dimensions whose gradient keeps its sign
+                gains[i][d] = if (g > 0.0) != (velocity[i][d] > 0.0) {
+                    gains[i][d] + 0.2
+                } else {
+                    (gains[i][d] * 0.8).max(0.01)
+                };
+                velocity[i][d] = momentum * velocity[i][d] - learning_rate * gains[i][d] * g;
+            }
+        }
+        for (point, v) in y.iter_mut().zip(&velocity) {
+            point[0] += v[0];
+            point[1] += v[1];
+        }
+    }
+
+    normalize(&y)
+}
+
+/// Scale points into 0..=1, centered, keeping the aspect ratio.
+fn normalize(points: &[[f32; 2]]) -> Vec<[f32; 2]> {
+    let min = |d: usize| points.iter().map(|p| p[d]).fold(f32::INFINITY, f32::min);
+    let max = |d: usize| {
+        points
+            .iter()
+            .map(|p| p[d])
+            .fold(f32::NEG_INFINITY, f32::max)
+    };
+    let (min_x, min_y) = (min(0), min(1));
+    let (width, height) = (max(0) - min_x, max(1) - min_y);
+    let scale = width.max(height);
+    if !scale.is_finite() || scale <= 0.0 {
+        return vec![[0.5, 0.5]; points.len()];
+    }
+    let (pad_x, pad_y) = ((1.0 - width / scale) / 2.0, (1.0 - height / scale) / 2.0);
+    points
+        .iter()
+        .map(|p| {
+            [
+                (p[0] - min_x) / scale + pad_x,
+                (p[1] - min_y) / scale + pad_y,
+            ]
+        })
+        .collect()
+}
+
+/// Project the newest `MAX_POINTS` embedded documents and cache their map
+/// positions, replacing the previous map. Returns how many were mapped.
+/// `model` is the embedding model key the vectors came from.
+pub async fn compute(db: &Database, model: &str) -> Result<usize> {
+    if PROJECTION_RUNNING.swap(true, Ordering::SeqCst) {
+        return Err("The map is already being computed".into());
+    }
+    let result = compute_inner(db, model).await;
+    PROJECTION_RUNNING.store(false, Ordering::SeqCst);
+    result
+}
+
+async fn compute_inner(db: &Database, model: &str) -> Result<usize> {
+    let chunks = db.get_all_chunk_embeddings().await?;
+    let mut documents = document_vectors(
+        chunks
+            .into_iter()
+            .map(|(_, document_id, _, _, vector)| (document_id, vector)),
+    );
+
+    // Newest documents first; vectors of another size (from an earlier
+    // model) cannot be compared and are left out
+    documents.sort_by_key(|(created, _)| std::cmp::Reverse(*created));
+    let dimensions = documents.first().map_or(0, |(_, v)| v.len());
+    documents.retain(|(_, v)| v.len() == dimensions);
+    documents.truncate(MAX_POINTS);
+
+    let (ids, vectors): (Vec<i64>, Vec<Vec<f32>>) = documents.into_iter().unzip();
+    let points = tokio::task::spawn_blocking(move || tsne(&vectors))
+        .await
+        .map_err(|e| format!("Projection failed: {}", e))?;
+
+    let count = ids.len();
+    let points = ids
+        .into_iter()
+        .zip(points)
+        .map(|(id, [x, y])| (id, x, y))
+        .collect();
+    db.replace_projection(points, model).await?;
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn document_vectors_average_chunks() {
+        let chunks = vec![
+            (1, vec![1.0, 0.0]),
+            (1, vec![0.0, 1.0]),
+            (2, vec![0.0, 3.0]),
+        ];
+        let documents = document_vectors(chunks);
+        assert_eq!(documents.len(), 2);
+        let half = std::f32::consts::FRAC_1_SQRT_2;
+        assert_eq!(documents[0].0, 1);
+        assert!((documents[0].1[0] - half).abs() < 1e-6);
+        assert!((documents[0].1[1] - half).abs() < 1e-6);
+        assert_eq!(documents[1], (2, vec![0.0, 1.0]));
+    }
+
+    #[test]
+    fn similar_documents_end_up_close() {
+        // Two topics: vectors near one axis or the other
+        let mut vectors = Vec::new();
+        for i in 0..12 {
+            let wobble = i as f32 * 0.01;
+            vectors.push(vec![1.0, wobble, 0.0]);
+            vectors.push(vec![0.0, wobble, 1.0]);
+        }
+        let points = tsne(&vectors);
+        assert_eq!(points.len(), vectors.len());
+        assert!(points
+            .iter()
+            .all(|p| (0.0..=1.0).contains(&p[0]) && (0.0..=1.0).contains(&p[1])));
+
+        let distance =
+            |a: [f32; 2], b: [f32; 2]| ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2)).sqrt();
+        let mean = |pairs: Vec<f32>| pairs.iter().sum::<f32>() / pairs.len() as f32;
+        let (mut same, mut other) = (Vec::new(), Vec::new());
+        for i in 0..points.len() {
+            for j in i + 1..points.len() {
+                let d = distance(points[i], points[j]);
+                if i % 2 == j % 2 {
+                    same.push(d);
+                } else {
+                    other.push(d);
+                }
+            }
+        }
+        assert!(mean(same) * 3.0 < mean(other));
+    }
+
+    #[test]
+    fn tiny_inputs_get_fixed_positions() {
+        assert!(tsne(&[]).is_empty());
+        assert_eq!(tsne(&[vec![1.0]]), vec![[0.25, 0.5]]);
+    }
+}
@@ -0,0 +1,100 @@
+//! Query embedding cache
+//!
+//! Embedding a search query is a round-trip to the embedding backend, and
+//! people repeat searches. Recent query embeddings are kept in an in-memory
+//! LRU; with persistence on (the default), they are also stored in the
+//! `query_embeddings` table so common searches skip the backend across
+//! restarts. Entries are keyed by backend as well as query text, since
+//! vectors from different models are not comparable.
+
+use std::collections::HashMap;
+
+/// Query embeddings kept in memory.
+pub const MEMORY_CAPACITY: usize = 256;
+
+/// Query embeddings kept on disk; the most searched survive pruning.
+pub const PERSISTED_LIMIT: usize = 1000;
+
+/// Least-recently-used map from query text to its embedding.
+///
+/// Eviction scans for the oldest entry, which is cheap at this size.
+pub struct QueryEmbeddingCache {
+    capacity: usize,
+    entries: HashMap<String, (Vec<f32>, u64)>,
+    clock: u64,
+}
+
+impl QueryEmbeddingCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            entries: HashMap::new(),
+            clock: 0,
+        }
+    }
+
+    /// Look up a query, marking it as recently used.
+    pub fn get(&mut self, query: &str) -> Option<Vec<f32>> {
+        self.clock += 1;
+        let clock = self.clock;
+        self.entries.get_mut(query).map(|(embedding, last_used)| {
+            *last_used = clock;
+            embedding.clone()
+        })
+    }
+
+    /// Add a query, evicting the least recently used entry when full.
+    pub fn insert(&mut self, query: &str, embedding: Vec<f32>) {
+        self.clock += 1;
+        if !self.entries.contains_key(query) && self.entries.len() >= self.capacity {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, last_used))| *last_used)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.entries
+            .insert(query.to_string(), (embedding, self.clock));
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let mut cache = QueryEmbeddingCache::new(2);
+        cache.insert("rust", vec![1.0]);
+        cache.insert("python", vec![2.0]);
+
+        // Touch "rust" so "python" is the oldest
+        assert_eq!(cache.get("rust"), Some(vec![1.0]));
+        cache.insert("go", vec![3.0]);
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get("python").is_none());
+        assert_eq!(cache.get("rust"), Some(vec![1.0]));
+        assert_eq!(cache.get("go"), Some(vec![3.0]));
+
+        // Re-inserting an existing key does not evict anything
+        cache.insert("go", vec![4.0]);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get("go"), Some(vec![4.0]));
+    }
+}
@@ -0,0 +1,150 @@
+//! Synonym-based query expansion
+//!
+//! Short queries often miss documents that use different wording for the
+//! same idea ("pitfalls" vs "gotchas"). When expansion is enabled for a
+//! search, the FTS arm matches any synonym of each term and the vector arm
+//! also searches a couple of paraphrases built by swapping in synonyms.
+//! There is no completion model in LocalMind, so expansion uses a small
+//! built-in thesaurus rather than generated paraphrases.
+
+/// Groups of interchangeable terms. Any term expands to the rest of its group.
+const SYNONYM_GROUPS: &[&[&str]] = &[
+    &["pitfalls", "gotchas", "mistakes", "traps"],
+    &["error", "bug", "issue", "problem"],
+    &["fix", "solve", "resolve", "workaround"],
+    &["guide", "tutorial", "walkthrough", "howto"],
+    &["fast", "quick", "performant", "speedy"],
+    &["performance", "speed", "latency", "throughput"],
+    &["slow", "sluggish", "laggy"],
+    &["async", "asynchronous", "concurrent"],
+    &["parallel", "multithreaded", "concurrent"],
+    &["example", "sample", "demo"],
+    &["setup", "install", "installation", "configure"],
+    &["config", "configuration", "settings"],
+    &["test", "testing", "tests"],
+    &["deploy", "deployment", "release", "ship"],
+    &["database", "db", "datastore"],
+    &["js", "javascript"],
+    &["ts", "typescript"],
+    &["py", "python"],
+    &["ml", "machine learning"],
+    &["ai", "artificial intelligence"],
+    &["llm", "language model"],
+    &["k8s", "kubernetes"],
+    &["auth", "authentication", "login"],
+    &["docs", "documentation", "reference"],
+    &["cheap", "inexpensive", "affordable", "budget"],
+    &["recipe", "dish", "meal"],
+    &["house", "home"],
+    &["renovation", "remodel", "refurbishment"],
+    &["job", "career", "employment"],
+    &["car", "vehicle", "automobile"],
+    &["buy", "purchase"],
+    &["best", "top", "recommended"],
+    &["compare", "comparison", "versus", "vs"],
+    &["begin", "start", "beginner", "introduction"],
+];
+
+/// Maximum paraphrases generated for the vector arm (in addition to the query).
+const MAX_PARAPHRASES: usize = 2;
+
+/// Synonyms for a single lowercase term, excluding the term itself.
+pub fn synonyms(term: &str) -> Vec<&'static str> {
+    let term = term.to_lowercase();
+    let mut out: Vec<&'static str> = Vec::new();
+    for group in SYNONYM_GROUPS {
+        if group.contains(&term.as_str()) {
+            for &syn in group.iter() {
+                if syn != term && !out.contains(&syn) {
+                    out.push(syn);
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Build an FTS5 query where every term matches itself or any synonym.
+///
+/// Each term becomes `("term" OR "syn1" OR ...)` and terms are joined with an
+/// explicit `AND`, since FTS5 does not allow implicit AND next to a group.
+pub fn expand_fts_query(query: &str) -> String {
+    query
+        .split_whitespace()
+        .map(|w| w.replace('"', ""))
+        .filter(|w| !w.is_empty())
+        .map(|w| {
+            let syns = synonyms(&w);
+            if syns.is_empty() {
+                format!("\"{}\"", w)
+            } else {
+                let alternatives: Vec<String> = std::iter::once(w.clone())
+                    .chain(syns.iter().map(|s| s.to_string()))
+                    .map(|t| format!("\"{}\"", t))
+                    .collect();
+                format!("({})", alternatives.join(" OR "))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" AND ")
+}
+
+/// Paraphrases of `query` with terms replaced by their synonyms.
+///
+/// The n-th paraphrase uses each term's n-th synonym where one exists. The
+/// original query is not included; duplicates are removed.
+pub fn paraphrases(query: &str) -> Vec<String> {
+    let words: Vec<&str> = query.split_whitespace().collect();
+    let word_synonyms: Vec<Vec<&'static str>> = words.iter().map(|w| synonyms(w)).collect();
+
+    let mut out: Vec<String> = Vec::new();
+    for n in 0..MAX_PARAPHRASES {
+        let mut changed = false;
+        let paraphrase: Vec<&str> = words
+            .iter()
+            .zip(&word_synonyms)
+            .map(|(word, syns)| match syns.get(n) {
+                Some(syn) => {
+                    changed = true;
+                    *syn
+                }
+                None => word,
+            })
+            .collect();
+        let paraphrase = paraphrase.join(" ");
+        if changed && !out.contains(&paraphrase) {
+            out.push(paraphrase);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn synonyms_are_symmetric_and_exclude_term() {
+        assert!(synonyms("pitfalls").contains(&"gotchas"));
+        assert!(synonyms("Gotchas").contains(&"pitfalls"));
+        assert!(!synonyms("gotchas").contains(&"gotchas"));
+        assert!(synonyms("rust").is_empty());
+    }
+
+    #[test]
+    fn fts_query_ors_synonyms_per_term() {
+        assert_eq!(
+            expand_fts_query("rust js"),
+            "\"rust\" AND (\"js\" OR \"javascript\")"
+        );
+    }
+
+    #[test]
+    fn paraphrases_swap_in_synonyms() {
+        let p = paraphrases("rust async pitfalls");
+        assert_eq!(p.len(), 2);
+        assert_eq!(p[0], "rust asynchronous gotchas");
+        assert_eq!(p[1], "rust concurrent mistakes");
+        assert!(paraphrases("rust borrow checker").is_empty());
+    }
+}
@@ -0,0 +1,266 @@
+//! Search operators
+//!
+//! Besides free text, the search box accepts `"exact phrases"`, `-word` and
+//! `-"phrase"` exclusions, and `site:`, `source:`, `lang:`, `tag:`, `before:`
+//! and `after:` operators. They are parsed into filters that restrict both the
+//! keyword and vector arms of a search; the remaining free text (plus any
+//! phrase words) is what gets embedded. Operators with an unusable value,
+//! such as `before:soon`, are searched as plain text. Dates may be a day,
+//! month or year (`after:2024` is from 2024 on), and a bare `AND` between
+//! terms is ignored since every term is required anyway.
+
+/// A search query split into free text and structured filters.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParsedQuery {
+    /// Free text outside phrases and operators
+    pub text: String,
+    /// Quoted phrases that must appear verbatim
+    pub phrases: Vec<String>,
+    /// Words or phrases that must not appear
+    pub excluded: Vec<String>,
+    /// Domains the document URL must be on (any of them); subdomains match
+    pub sites: Vec<String>,
+    /// Part of the document source, e.g. "extension" for "chrome_extension"
+    pub source: Option<String>,
+    /// Detected document language (ISO 639-3), see `language::parse`
+    pub language: Option<String>,
+    /// Tags the document must all have
+    pub tags: Vec<String>,
+    /// Saved before this day, month or year (YYYY-MM-DD, YYYY-MM or YYYY)
+    pub before: Option<String>,
+    /// Saved on or after this month or year, or after this day
+    pub after: Option<String>,
+}
+
+impl ParsedQuery {
+    /// Filters that pick documents by metadata (not by text).
+    pub fn has_metadata_filters(&self) -> bool {
+        !self.sites.is_empty()
+            || self.source.is_some()
+            || self.language.is_some()
+            || !self.tags.is_empty()
+            || self.before.is_some()
+            || self.after.is_some()
+    }
+
+    /// Free text and phrase words, for embedding and snippets.
+    pub fn free_text(&self) -> String {
+        std::iter::once(self.text.as_str())
+            .chain(self.phrases.iter().map(String::as_str))
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// The query written out again with `text` as its free text, e.g. to
+    /// show a spelling-corrected query with its operators.
+    pub fn with_text(&self, text: &str) -> String {
+        let mut parts: Vec<String> = Vec::new();
+        if !text.is_empty() {
+            parts.push(text.to_string());
+        }
+        parts.extend(self.phrases.iter().map(|p| format!("\"{}\"", p)));
+        parts.extend(self.excluded.iter().map(|e| {
+            if e.contains(' ') {
+                format!("-\"{}\"", e)
+            } else {
+                format!("-{}", e)
+            }
+        }));
+        parts.extend(self.sites.iter().map(|s| format!("site:{}", s)));
+        parts.extend(self.source.iter().map(|s| format!("source:{}", s)));
+        parts.extend(self.language.iter().map(|l| format!("lang:{}", l)));
+        parts.extend(self.tags.iter().map(|t| format!("tag:{}", t)));
+        parts.extend(self.before.iter().map(|d| format!("before:{}", d)));
+        parts.extend(self.after.iter().map(|d| format!("after:{}", d)));
+        parts.join(" ")
+    }
+
+    /// Just the metadata operators, written out as a filter expression,
+    /// e.g. `source:bookmark tag:rust after:2024` for a smart collection.
+    pub fn filter_expression(&self) -> String {
+        let filters = ParsedQuery {
+            sites: self.sites.clone(),
+            source: self.source.clone(),
+            language: self.language.clone(),
+            tags: self.tags.clone(),
+            before: self.before.clone(),
+            after: self.after.clone(),
+            ..ParsedQuery::default()
+        };
+        filters.with_text("")
+    }
+}
+
+/// Whether `value` is a `YYYY-MM-DD` date, a `YYYY-MM` month or a `YYYY`
+/// year. They compare as strings against the day a document was saved.
+fn is_date(value: &str) -> bool {
+    let bytes = value.as_bytes();
+    matches!(bytes.len(), 4 | 7 | 10)
+        && bytes.iter().enumerate().all(|(i, b)| match i {
+            4 | 7 => *b == b'-',
+            _ => b.is_ascii_digit(),
+        })
+}
+
+/// Split a query into whitespace-separated terms, keeping quoted phrases
+/// together. Each term is `(excluded, quoted, text)`; an unclosed quote runs
+/// to the end of the query.
+fn terms(query: &str) -> Vec<(bool, bool, String)> {
+    let mut terms = Vec::new();
+    let mut chars = query.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+
+        let mut excluded = false;
+        if c == '-' {
+            chars.next();
+            excluded = true;
+        }
+
+        if chars.peek() == Some(&'"') {
+            chars.next();
+            let phrase: String = chars.by_ref().take_while(|c| *c != '"').collect();
+            terms.push((excluded, true, phrase.trim().to_string()));
+        } else {
+            let mut word = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() {
+                    break;
+                }
+                word.push(c);
+                chars.next();
+            }
+            terms.push((excluded, false, word));
+        }
+    }
+    terms
+}
+
+/// Parse a search box query.
+pub fn parse(query: &str) -> ParsedQuery {
+    let mut parsed = ParsedQuery::default();
+    let mut text: Vec<String> = Vec::new();
+
+    for (excluded, quoted, term) in terms(query) {
+        if term.is_empty() || (!excluded && !quoted && term == "AND") {
+            continue;
+        }
+        if excluded {
+            parsed.excluded.push(term);
+            continue;
+        }
+        if quoted {
+            parsed.phrases.push(term);
+            continue;
+        }
+
+        let operator = term
+            .split_once(':')
+            .map(|(key, value)| (key.to_lowercase(), value.to_string()));
+        match operator {
+            Some((key, value)) if key == "site" && !value.is_empty() => {
+                let domain = value
+                    .trim_start_matches("https://")
+                    .trim_start_matches("http://")
+                    .trim_end_matches('/')
+                    .to_lowercase();
+                parsed.sites.push(domain);
+            }
+            Some((key, value)) if key == "source" && !value.is_empty() => {
+                parsed.source = Some(value.to_lowercase());
+            }
+            Some((key, value)) if key == "lang" && crate::language::parse(&value).is_some() => {
+                parsed.language = crate::language::parse(&value).map(str::to_string);
+            }
+            Some((key, value)) if key == "tag" && !value.is_empty() => {
+                parsed.tags.push(value);
+            }
+            Some((key, value)) if key == "before" && is_date(&value) => {
+                parsed.before = Some(value);
+            }
+            Some((key, value)) if key == "after" && is_date(&value) => {
+                parsed.after = Some(value);
+            }
+            _ => text.push(term),
+        }
+    }
+
+    parsed.text = text.join(" ");
+    parsed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_phrases_exclusions_and_operators() {
+        let parsed = parse(
+            r#"rust "error handling" -python -"hello world" site:docs.rs source:extension tag:Rust before:2024-06-01 after:2023-01-31"#,
+        );
+        assert_eq!(parsed.text, "rust");
+        assert_eq!(parsed.phrases, vec!["error handling"]);
+        assert_eq!(parsed.excluded, vec!["python", "hello world"]);
+        assert_eq!(parsed.sites, vec!["docs.rs"]);
+        assert_eq!(parsed.source.as_deref(), Some("extension"));
+        assert_eq!(parsed.tags, vec!["Rust"]);
+        assert_eq!(parsed.before.as_deref(), Some("2024-06-01"));
+        assert_eq!(parsed.after.as_deref(), Some("2023-01-31"));
+        assert!(parsed.has_metadata_filters());
+        assert_eq!(parsed.free_text(), "rust error handling");
+        assert_eq!(
+            parsed.with_text("rust"),
+            r#"rust "error handling" -python -"hello world" site:docs.rs source:extension tag:Rust before:2024-06-01 after:2023-01-31"#
+        );
+    }
+
+    #[test]
+    fn unusable_operators_stay_free_text() {
+        let parsed = parse(r#"before:soon note: http://x.dev "unclosed phrase"#);
+        assert_eq!(parsed.text, "before:soon note: http://x.dev");
+        assert_eq!(parsed.phrases, vec!["unclosed phrase"]);
+        assert!(!parsed.has_metadata_filters());
+
+        let unknown = parse("lang:klingon");
+        assert_eq!(unknown.text, "lang:klingon");
+        assert_eq!(unknown.language, None);
+
+        let plain = parse("  plain   words ");
+        assert_eq!(plain.text, "plain words");
+        assert_eq!(plain, parse("plain words"));
+    }
+
+    #[test]
+    fn languages_are_stored_as_three_letter_codes() {
+        let parsed = parse("recetas lang:es");
+        assert_eq!(parsed.text, "recetas");
+        assert_eq!(parsed.language.as_deref(), Some("spa"));
+        assert!(parsed.has_metadata_filters());
+        assert_eq!(parsed.with_text("recetas"), "recetas lang:spa");
+        assert_eq!(parse("LANG:German").language.as_deref(), Some("deu"));
+    }
+
+    #[test]
+    fn filter_expressions_keep_only_operators() {
+        let parsed = parse("tag:rust AND source:bookmark AND after:2024 async -python");
+        assert_eq!(parsed.text, "async");
+        assert_eq!(parsed.after.as_deref(), Some("2024"));
+        assert_eq!(
+            parsed.filter_expression(),
+            "source:bookmark tag:rust after:2024"
+        );
+        assert_eq!(
+            parse(&parsed.filter_expression()).filter_expression(),
+            parsed.filter_expression()
+        );
+
+        assert_eq!(parse("before:2024-06").before.as_deref(), Some("2024-06"));
+        assert_eq!(parse("after:24").text, "after:24");
+        assert_eq!(parse("rust and tokio").text, "rust and tokio");
+        assert!(parse("just words").filter_expression().is_empty());
+    }
+}
@@ -1,19 +1,77 @@
 use crate::{
-    db::{Database, Document, OperationPriority},
-    document::DocumentProcessor,
-    local_embedding::LocalEmbeddingClient,
-    vector::VectorStore,
+    db::{ChunkRow, Database, Document, IndexStats, OperationPriority},
+    document::{ChunkProvenance, DocumentChunk, DocumentProcessor},
+    domain_preferences,
+    embedding_migration::{self, EmbeddingMigration, MigrationProgress},
+    embedding_provider::{EmbeddingBackend, EmbeddingClient, EmbeddingSettings},
+    html_text::PageStructure,
+    ingest_policy::{self, IngestPolicy, SourceKind},
+    links,
+    page_quality::{self, QualityIssue},
+    query_cache::{self, QueryEmbeddingCache},
+    query_expansion,
+    query_syntax::{self, ParsedQuery},
+    snippet, spelling,
+    storage_budget::StorageStatus,
+    vector::{ChunkSearchResult, DimensionMismatch, VectorStore},
     Result,
 };
-use std::collections::{HashMap, HashSet};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
 use tokio::sync::Mutex;
 
+/// How long startup waits for the embedding backend before continuing in
+/// keyword-only mode. The health monitor upgrades to semantic search once
+/// the backend answers.
+const EMBEDDING_STARTUP_WAIT_SECS: u32 = 30;
+
+/// Length of the passage shown for keyword hits, in characters.
+const SNIPPET_CHARS: usize = 400;
+
+/// Passages returned by a passage-level search.
+const MAX_PASSAGES: usize = 30;
+
+/// Passages listed for one document in the document view.
+const MAX_DOCUMENT_PASSAGES: usize = 10;
+
+/// A document's passages are listed when they score at least this fraction
+/// of its best passage.
+const DOCUMENT_PASSAGE_RATIO: f32 = 0.75;
+
+/// Keyword matches fetched for a filtered search, before filtering.
+const FILTERED_FTS_LIMIT: i64 = 200;
+
+/// Web search offered when nothing saved matches a query.
+const WEB_SEARCH_URL: &str = "https://duckduckgo.com/";
+
+/// Best-effort matches shown for a search with no results.
+const WEAK_MATCH_LIMIT: i64 = 5;
+
+/// Page text embedded to find related documents, in characters.
+const RELATED_TEXT_CHARS: usize = 1000;
+
+/// Words of a page's text matched when finding related documents by keyword.
+const RELATED_MAX_WORDS: usize = 20;
+
+/// Nearest documents whose tags are suggested for a document being tagged.
+const TAG_NEIGHBORS: usize = 10;
+
 pub struct RagPipeline {
     pub db: Database,
     vector_store: Mutex<VectorStore>,
-    embedding_client: LocalEmbeddingClient,
+    embedding_client: Box<dyn EmbeddingBackend>,
     document_processor: DocumentProcessor,
-    query_embedding_cache: Mutex<HashMap<String, Vec<f32>>>,
+    query_embedding_cache: Mutex<QueryEmbeddingCache>,
+    /// Also keep query embeddings in the database across restarts
+    persist_query_embeddings: AtomicBool,
+    /// False while the embedding backend is unavailable (degraded mode):
+    /// search uses FTS only and new documents are stored unembedded
+    semantic_available: AtomicBool,
+    /// Most recently viewed documents first, for the linked-result boost
+    recently_viewed: std::sync::Mutex<VecDeque<i64>>,
+    /// Model being re-embedded with in the background, see `embedding_migration`
+    migration: Option<EmbeddingMigration>,
 }
 
 #[derive(Debug)]
@@ -22,7 +80,7 @@ pub struct RagResponse {
     pub sources: Vec<DocumentSource>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 pub struct DocumentSource {
     pub doc_id: i64,
     pub title: String,
@@ -30,20 +88,341 @@ pub struct DocumentSource {
     pub similarity: f32,
     pub profile: Option<String>,
     pub needs_auth: bool,
+    pub is_dead: bool,
+    /// Byte offsets of the best-matching chunk in the document content
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Vec<usize>>)]
+    pub chunk: Option<(usize, usize)>,
+}
+
+/// A matching passage of a document, for passage-level search results and
+/// the document view's list of matches.
+#[derive(Debug, Clone)]
+pub struct PassageHit {
+    pub doc_id: i64,
+    pub title: String,
+    /// Byte offsets of the passage within the document content
+    pub chunk_start: usize,
+    pub chunk_end: usize,
+    pub text: String,
+    pub similarity: f32,
+    pub profile: Option<String>,
+    pub needs_auth: bool,
+    pub is_dead: bool,
+    /// Section, page and so on of the passage, when it is a stored chunk
+    pub provenance: ChunkProvenance,
+}
+
+impl PassageHit {
+    /// The passage of `doc` between byte offsets `start` and `end`, or `None`
+    /// when the offsets do not fall on the document's character boundaries.
+    fn from_document(doc: &Document, start: usize, end: usize, similarity: f32) -> Option<Self> {
+        let text = doc.content.get(start..end)?.trim();
+        if text.is_empty() {
+            return None;
+        }
+        Some(Self {
+            doc_id: doc.id,
+            title: doc.title.clone(),
+            chunk_start: start,
+            chunk_end: end,
+            text: text.to_string(),
+            similarity,
+            profile: doc.profile.clone(),
+            needs_auth: doc.needs_auth.unwrap_or(false),
+            is_dead: doc.is_dead.unwrap_or(false),
+            provenance: ChunkProvenance::default(),
+        })
+    }
+}
+
+/// A passage from a document cited by an answer.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Citation {
+    /// Byte offsets of the passage within the document content
+    pub chunk_start: usize,
+    pub chunk_end: usize,
+    pub text: String,
+    pub similarity: f32,
+}
+
+/// An answer to a question about a single document.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct DocumentAnswer {
+    /// Answer text with `[n]` markers referring to `citations[n - 1]`
+    pub answer: String,
+    pub citations: Vec<Citation>,
+}
+
+/// Help for a search that found nothing: weak matches, spelling-corrected
+/// queries and a web search link.
+#[derive(Debug, Clone, Default)]
+pub struct SearchFallback {
+    /// Documents matching any query word or word prefix, best first. Their
+    /// `similarity` is 0, since they did not match the query as a whole.
+    pub weak_matches: Vec<DocumentSource>,
+    /// Respellings of the query using words found in saved documents
+    pub suggestions: Vec<String>,
+    /// Web search for the query
+    pub web_search_url: String,
+}
+
+/// Tags offered while a document is being tagged.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TagSuggestions {
+    /// Existing tags starting with the typed text, as (tag, documents), most
+    /// used first
+    pub matching: Vec<(String, i64)>,
+    /// Tags on the most similar documents, best first
+    pub related: Vec<String>,
+}
+
+/// Per-search options for fused retrieval.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SearchOptions {
+    /// Include documents marked dead by the link checker
+    pub include_dead: bool,
+    /// Expand the query with synonyms (FTS arm) and paraphrases (vector arm)
+    pub expand_query: bool,
+    /// Only return documents in this collection
+    pub collection_id: Option<i64>,
+    /// Rank documents linked to or from recently viewed documents higher
+    pub boost_linked: bool,
+    /// Search for a respelled query when some of its words are not in the
+    /// index, e.g. "kubenetes" becomes "kubernetes"
+    pub correct_spelling: bool,
+}
+
+/// Fused search results, with the query actually searched when misspelled
+/// words were corrected.
+#[derive(Debug, Clone, Default)]
+pub struct FusedSearch {
+    pub hits: Vec<DocumentSource>,
+    /// Respelled query, when it differs from the one typed
+    pub corrected_query: Option<String>,
+}
+
+/// Hash identifying a chunk's text as embedded by the model behind `backend`
+/// (see `EmbeddingClient::cache_key`).
+fn chunk_hash(backend: &str, text: &str) -> String {
+    use sha2::{Digest, Sha256};
+    let mut hasher = Sha256::new();
+    hasher.update(backend.as_bytes());
+    hasher.update([0u8]);
+    hasher.update(text.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// Record in each chunk's provenance the heading and anchor of the section
+/// and the PDF page it starts in, if `structure` has them.
+fn label_sections(chunks: &mut [DocumentChunk], structure: &PageStructure) {
+    for chunk in chunks {
+        let heading = structure.heading_at(chunk.start_pos);
+        chunk.provenance.section_heading = heading.map(|heading| heading.text.clone());
+        chunk.provenance.section_anchor = heading.and_then(|heading| heading.anchor.clone());
+        chunk.provenance.page_number = structure.page_at(chunk.start_pos);
+    }
+}
+
+/// Check a web page for junk before it is indexed (see `page_quality`),
+/// returning the issue found and the content to store: the title and URL
+/// alone for a flagged page. Auth-blocked pages are flagged separately.
+fn screen_page<'a>(
+    title: &str,
+    content: &'a str,
+    url: Option<&str>,
+    needs_auth: bool,
+) -> (Option<QualityIssue>, Cow<'a, str>) {
+    if needs_auth {
+        return (None, Cow::Borrowed(content));
+    }
+    match (page_quality::check(title, content, url), url) {
+        (Some(issue), Some(url)) => (
+            Some(issue),
+            Cow::Owned(page_quality::metadata_only(title, url)),
+        ),
+        _ => (None, Cow::Borrowed(content)),
+    }
+}
+
+/// Pick the sentence of `passage` sharing the most words with `question`.
+///
+/// Falls back to the start of the passage when no sentence overlaps.
+fn best_sentence(passage: &str, question: &str) -> String {
+    let terms: HashSet<String> = question
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.len() > 2)
+        .map(|w| w.to_lowercase())
+        .collect();
+
+    let sentences: Vec<&str> = passage
+        .split_inclusive(['.', '?', '!', '\n'])
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    let mut best: Option<(&str, usize)> = None;
+    for sentence in &sentences {
+        let overlap = sentence
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| terms.contains(&w.to_lowercase()))
+            .count();
+        if overlap > best.map_or(0, |(_, n)| n) {
+            best = Some((sentence, overlap));
+        }
+    }
+
+    match best {
+        Some((sentence, _)) => sentence.to_string(),
+        None => sentences
+            .first()
+            .map(|s| s.chars().take(300).collect())
+            .unwrap_or_default(),
+    }
+}
+
+/// Intersect the current search scope (all documents when `None`) with `ids`.
+fn narrow_scope(scope: Option<HashSet<i64>>, ids: HashSet<i64>) -> HashSet<i64> {
+    match scope {
+        Some(scope) => scope.intersection(&ids).copied().collect(),
+        None => ids,
+    }
+}
+
+/// Chunk matches from the vector store, or none when every stored vector
+/// came from a model with other dimensions than the query's: the search then
+/// relies on keywords until the documents are re-embedded.
+fn comparable_chunks(results: Result<Vec<ChunkSearchResult>>) -> Result<Vec<ChunkSearchResult>> {
+    match results {
+        Err(e) if e.is::<DimensionMismatch>() => {
+            eprintln!("Semantic search skipped: {}", e);
+            Ok(Vec::new())
+        }
+        other => other,
+    }
+}
+
+/// The quoted phrases of a query as an FTS5 query requiring all of them.
+fn fts_phrases(parsed: &ParsedQuery) -> String {
+    parsed
+        .phrases
+        .iter()
+        .map(|p| format!("\"{}\"", p.replace('"', "")))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Scale passage scores so the best one is 1.0.
+fn normalize_passage_scores(chunks: &mut [(i64, usize, usize, f32)]) {
+    let best = chunks.iter().map(|c| c.3).fold(0.0, f32::max);
+    if best > 0.0 {
+        for chunk in chunks.iter_mut() {
+            chunk.3 /= best;
+        }
+    }
+}
+
+/// Escape a free-text query for FTS5 by wrapping each token in double quotes.
+pub fn escape_fts_query(query: &str) -> String {
+    query
+        .split_whitespace()
+        .map(|w| format!("\"{}\"", w.replace('"', "")))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// FTS5 query matching any of the distinct words of `text` that are at
+/// least three characters long, up to `RELATED_MAX_WORDS` of them.
+fn any_word_fts_query(text: &str) -> String {
+    let mut seen = HashSet::new();
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.chars().count() >= 3)
+        .map(str::to_lowercase)
+        .filter(|w| seen.insert(w.clone()))
+        .take(RELATED_MAX_WORDS)
+        .map(|w| format!("\"{}\"", w))
+        .collect::<Vec<_>>()
+        .join(" OR ")
+}
+
+/// Rank tag suggestions. `counts` is every tag in use with its document
+/// count; `neighbors` the tags of similar documents with their similarity.
+/// Tags in `current` are never suggested, and both lists only hold tags
+/// starting with `prefix` (ignoring case). Reusing existing tags keeps the
+/// tag list from sprawling, so no new tag is ever made up.
+fn rank_tag_suggestions(
+    counts: &[(String, i64)],
+    neighbors: &[(f32, Vec<String>)],
+    prefix: &str,
+    current: &[String],
+    limit: usize,
+) -> TagSuggestions {
+    let prefix = prefix.trim().to_lowercase();
+    let current: HashSet<String> = current.iter().map(|tag| tag.to_lowercase()).collect();
+    let offered = |tag: &str| {
+        let tag = tag.to_lowercase();
+        tag.starts_with(&prefix) && !current.contains(&tag)
+    };
+
+    let matching: Vec<(String, i64)> = if prefix.is_empty() {
+        Vec::new()
+    } else {
+        counts
+            .iter()
+            .filter(|(tag, _)| offered(tag))
+            .take(limit)
+            .cloned()
+            .collect()
+    };
+
+    let count_of = |tag: &str| {
+        counts
+            .iter()
+            .find(|(t, _)| t.eq_ignore_ascii_case(tag))
+            .map_or(0, |(_, count)| *count)
+    };
+    let mut scores: Vec<(String, f32)> = Vec::new();
+    for (similarity, tags) in neighbors {
+        for tag in tags.iter().filter(|tag| offered(tag)) {
+            match scores.iter_mut().find(|(t, _)| t.eq_ignore_ascii_case(tag)) {
+                Some((_, score)) => *score += similarity,
+                None => scores.push((tag.clone(), *similarity)),
+            }
+        }
+    }
+    scores.retain(|(tag, _)| !matching.iter().any(|(t, _)| t.eq_ignore_ascii_case(tag)));
+    scores.sort_by(|a, b| {
+        b.1.partial_cmp(&a.1)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| count_of(&b.0).cmp(&count_of(&a.0)))
+            .then_with(|| a.0.cmp(&b.0))
+    });
+
+    TagSuggestions {
+        matching,
+        related: scores.into_iter().take(limit).map(|(tag, _)| tag).collect(),
+    }
 }
 
 impl RagPipeline {
-    /// Initialize RAG pipeline with local Python embedding server.
+    /// Initialize RAG pipeline with the embedding backend chosen in Settings.
     ///
-    /// The embedding server should be running on localhost (default port 8000,
-    /// configurable via EMBEDDING_SERVER_PORT environment variable).
+    /// By default this is the local Python embedding server on localhost
+    /// (default port 8000, configurable via EMBEDDING_SERVER_PORT environment
+    /// variable); Ollama and LM Studio can be selected instead.
+    ///
+    /// If the backend is not ready within `EMBEDDING_STARTUP_WAIT_SECS` the
+    /// pipeline starts in degraded keyword-only mode instead of failing.
     pub async fn new(db: Database) -> Result<Self> {
-        let embedding_client = LocalEmbeddingClient::new();
+        let embedding_settings = db.get_embedding_settings().await.unwrap_or_default();
+        let embedding_client = EmbeddingClient::from_settings(&embedding_settings);
 
-        // Wait for embedding server to be ready (handles both startup delay and model loading).
-        // Timeout matches start_localmind.sh (180 seconds).
-        println!("Waiting for embedding server...");
-        let max_wait_secs: u32 = 180;
+        // Wait for embedding server to be ready (handles both startup delay and model loading)
+        println!(
+            "Waiting for embedding server ({})...",
+            embedding_client.service_name()
+        );
+        let max_wait_secs = EMBEDDING_STARTUP_WAIT_SECS;
         let mut ready = false;
         for i in 0..max_wait_secs {
             match embedding_client.health_check().await {
@@ -72,22 +451,52 @@ impl RagPipeline {
             tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
         }
         if !ready {
-            return Err(format!(
-                "Embedding server not ready after {} seconds. \
-                 Ensure the Python venv is set up (run start_localmind.sh).",
-                max_wait_secs
-            )
-            .into());
+            println!(
+                "WARNING: {} not ready after {} seconds. {} Starting in keyword-only mode; \
+                 semantic search resumes when it is available.",
+                embedding_client.service_name(),
+                max_wait_secs,
+                embedding_client.startup_hint()
+            );
         }
 
+        Self::build(db, Box::new(embedding_client), ready).await
+    }
+
+    /// Initialize RAG pipeline with a given embedding backend, e.g.
+    /// `MockEmbedding` in tests. The backend is checked once; if it is not
+    /// ready the pipeline starts in keyword-only mode.
+    pub async fn with_embedding_backend(
+        db: Database,
+        embedding_client: Box<dyn EmbeddingBackend>,
+    ) -> Result<Self> {
+        let ready = embedding_client.health_check().await.unwrap_or(false);
+        Self::build(db, embedding_client, ready).await
+    }
+
+    /// Load the stored chunk vectors and assemble the pipeline.
+    async fn build(
+        db: Database,
+        embedding_client: Box<dyn EmbeddingBackend>,
+        ready: bool,
+    ) -> Result<Self> {
         let document_processor = DocumentProcessor::default();
         let mut vector_store = VectorStore::new();
+        let persist_query_embeddings = db.get_persist_query_embeddings().await.unwrap_or(true);
 
         // Load existing chunk embeddings from database
         let chunk_embeddings = db.get_all_chunk_embeddings().await?;
         let chunk_count = chunk_embeddings.len();
         vector_store.load_chunk_vectors(chunk_embeddings)?;
         println!("Loaded {} chunk embeddings from database", chunk_count);
+        let dimension_counts = vector_store.dimension_counts();
+        if dimension_counts.len() > 1 {
+            println!(
+                "WARNING: Chunk embeddings from several models ((dimensions, chunks): {:?}); \
+                 only those matching the current model are searched until the rest are re-embedded.",
+                dimension_counts
+            );
+        }
 
         // Check total document count
         let total_docs = db
@@ -103,31 +512,296 @@ impl RagPipeline {
             println!("INFO: No documents in database. Add documents to enable search.");
         }
 
-        println!("RAG pipeline initialized with local Python embedding server");
+        println!(
+            "RAG pipeline initialized with {}",
+            embedding_client.service_name()
+        );
+
+        let migration = match db.get_embedding_migration().await {
+            Ok(settings) => settings.map(|settings| {
+                println!(
+                    "Re-embedding documents with {} in the background",
+                    embedding_migration::model_label(&settings)
+                );
+                EmbeddingMigration {
+                    backend: Box::new(EmbeddingClient::from_settings(&settings)),
+                    settings,
+                }
+            }),
+            Err(e) => {
+                eprintln!("Failed to load embedding migration: {}", e);
+                None
+            }
+        };
 
         Ok(Self {
             db,
             vector_store: Mutex::new(vector_store),
             embedding_client,
             document_processor,
-            query_embedding_cache: Mutex::new(HashMap::new()),
+            query_embedding_cache: Mutex::new(QueryEmbeddingCache::new(
+                query_cache::MEMORY_CAPACITY,
+            )),
+            persist_query_embeddings: AtomicBool::new(persist_query_embeddings),
+            semantic_available: AtomicBool::new(ready),
+            recently_viewed: std::sync::Mutex::new(VecDeque::new()),
+            migration,
         })
     }
 
     pub fn get_embedding_service_name(&self) -> &str {
-        "Local Python Embedding Server"
+        self.embedding_client.service_name()
     }
 
-    async fn get_cached_query_embedding(&self, query: &str) -> Result<Vec<f32>> {
-        // Check cache first
+    /// Switch to a different embedding backend, e.g. after a Settings change.
+    ///
+    /// The caller has checked the new backend can embed, so this leaves
+    /// keyword-only mode. Cached query embeddings came from the old backend
+    /// and are dropped.
+    pub fn set_embedding_client(&mut self, client: EmbeddingClient) {
+        self.set_embedding_backend(Box::new(client));
+    }
+
+    fn set_embedding_backend(&mut self, backend: Box<dyn EmbeddingBackend>) {
+        println!("Embedding backend switched to {}", backend.service_name());
+        self.embedding_client = backend;
+        self.query_embedding_cache.get_mut().clear();
+        self.vector_store.get_mut().reset_active_dimensions();
+        *self.semantic_available.get_mut() = true;
+    }
+
+    /// Apply new embedding settings whose backend the caller has checked.
+    ///
+    /// Another model with documents already embedded starts a migration
+    /// and returns `true`: search stays on the current model until it
+    /// completes. Otherwise (same model, e.g. a new server URL, or nothing
+    /// embedded yet) the settings are saved and used at once, cancelling
+    /// any migration.
+    pub async fn switch_embedding_model(
+        &mut self,
+        settings: EmbeddingSettings,
+        client: EmbeddingClient,
+    ) -> Result<bool> {
+        let embedded = self.vector_store.get_mut().chunk_vector_count() > 0;
+        if embedded && client.cache_key() != self.embedding_model_key() {
+            self.start_embedding_migration(settings, Box::new(client))
+                .await?;
+            return Ok(true);
+        }
+
+        self.cancel_embedding_migration().await?;
+        self.db.set_embedding_settings(&settings).await?;
+        self.set_embedding_client(client);
+        Ok(false)
+    }
+
+    /// Start re-embedding every document with `backend` in the background.
+    /// Vectors a previous migration to the same model stored are kept.
+    pub async fn start_embedding_migration(
+        &mut self,
+        settings: EmbeddingSettings,
+        backend: Box<dyn EmbeddingBackend>,
+    ) -> Result<()> {
+        let migration = EmbeddingMigration { settings, backend };
+        self.db
+            .delete_migration_embeddings(Some(&migration.model_id()))
+            .await?;
+        self.db
+            .set_embedding_migration(Some(&migration.settings))
+            .await?;
+        println!(
+            "Re-embedding documents with {} in the background",
+            embedding_migration::model_label(&migration.settings)
+        );
+        self.migration = Some(migration);
+        Ok(())
+    }
+
+    /// Stop a migration and delete the vectors it stored.
+    pub async fn cancel_embedding_migration(&mut self) -> Result<()> {
+        if self.migration.take().is_some() {
+            println!("Embedding model migration cancelled");
+        }
+        self.db.set_embedding_migration(None).await?;
+        self.db.delete_migration_embeddings(None).await?;
+        Ok(())
+    }
+
+    /// The migration under way, if any.
+    pub fn embedding_migration(&self) -> Option<&EmbeddingMigration> {
+        self.migration.as_ref()
+    }
+
+    /// Embed up to `batch` more documents with the model being migrated to,
+    /// returning the progress; `None` when no migration is under way.
+    pub async fn migrate_embeddings(&self, batch: usize) -> Result<Option<MigrationProgress>> {
+        let Some(ref migration) = self.migration else {
+            return Ok(None);
+        };
+        let model_id = migration.model_id();
+
+        for doc_id in self.db.get_documents_to_migrate(&model_id, batch).await? {
+            let Some(doc) = self.db.get_document(doc_id).await? else {
+                continue;
+            };
+            let chunks: Vec<DocumentChunk> = self
+                .db
+                .get_chunk_spans(doc_id)
+                .await?
+                .into_iter()
+                .map(|(start, end, provenance)| DocumentChunk {
+                    content: doc.content.get(start..end).unwrap_or("").trim().to_string(),
+                    start_pos: start,
+                    end_pos: end,
+                    provenance,
+                })
+                .collect();
+            let (_, rows) = self
+                .embed_chunks_with(migration.backend.as_ref(), &chunks)
+                .await?;
+            self.db
+                .write_model_embeddings(doc_id, &model_id, &rows)
+                .await?;
+        }
+
+        let (total, migrated) = self.db.get_migration_counts(&model_id).await?;
+        Ok(Some(MigrationProgress {
+            model: embedding_migration::model_label(&migration.settings),
+            migrated,
+            total,
+        }))
+    }
+
+    /// Switch to the migrated model once every document has its vectors:
+    /// they replace the old ones, in the database and in memory, and its
+    /// backend embeds from now on. Returns `false` while documents remain.
+    pub async fn finish_embedding_migration(&mut self) -> Result<bool> {
+        let Some(ref migration) = self.migration else {
+            return Ok(false);
+        };
+        if !self
+            .db
+            .complete_embedding_migration(&migration.model_id(), &migration.settings)
+            .await?
         {
-            let cache = self.query_embedding_cache.lock().await;
-            if let Some(cached_embedding) = cache.get(query) {
-                println!(
-                    "Using cached embedding for query: {}",
-                    query.chars().take(50).collect::<String>()
-                );
-                return Ok(cached_embedding.clone());
+            return Ok(false);
+        }
+
+        let mut vector_store = VectorStore::new();
+        vector_store.load_chunk_vectors(self.db.get_all_chunk_embeddings().await?)?;
+        *self.vector_store.get_mut() = vector_store;
+        if let Some(migration) = self.migration.take() {
+            println!(
+                "Finished re-embedding documents with {}",
+                embedding_migration::model_label(&migration.settings)
+            );
+            self.set_embedding_backend(migration.backend);
+        }
+        Ok(true)
+    }
+
+    /// Record that a document was opened, for the linked-result boost.
+    pub fn note_document_viewed(&self, doc_id: i64) {
+        let mut recent = self.recently_viewed.lock().unwrap();
+        recent.retain(|id| *id != doc_id);
+        recent.push_front(doc_id);
+        recent.truncate(links::RECENTLY_VIEWED_LIMIT);
+    }
+
+    /// Store a document's outgoing links: URLs written in `content` plus
+    /// `extra_links` (e.g. hrefs from the fetched HTML).
+    pub async fn store_document_links(
+        &self,
+        doc_id: i64,
+        url: Option<&str>,
+        content: &str,
+        extra_links: &[String],
+    ) -> Result<()> {
+        let links = links::collect_links(
+            url,
+            extra_links
+                .iter()
+                .cloned()
+                .chain(links::extract_text_links(content)),
+        );
+        self.db.set_document_links(doc_id, &links).await
+    }
+
+    /// Whether the embedding backend can embed right now.
+    pub async fn embedding_backend_ready(&self) -> bool {
+        self.embedding_client.health_check().await.unwrap_or(false)
+    }
+
+    /// Backend and model the chunk embeddings are made with, e.g.
+    /// `ollama:nomic-embed-text`; embeddings from another key are not comparable.
+    pub fn embedding_model_key(&self) -> String {
+        self.embedding_client.cache_key()
+    }
+
+    /// Whether semantic search is on, or the pipeline is in degraded
+    /// keyword-only mode because the embedding backend is unavailable.
+    pub fn is_semantic_search_available(&self) -> bool {
+        self.semantic_available.load(Ordering::Relaxed)
+    }
+
+    /// Health-check the embedding backend and switch between semantic and
+    /// keyword-only mode to match. Returns whether the backend is ready.
+    pub async fn check_embedding_backend(&self) -> bool {
+        let ready = self.embedding_backend_ready().await;
+        let was_ready = self.semantic_available.swap(ready, Ordering::Relaxed);
+        if ready && !was_ready {
+            println!("Embedding backend available, semantic search restored");
+        } else if !ready && was_ready {
+            eprintln!("Embedding backend unavailable, falling back to keyword-only search");
+        }
+        ready
+    }
+
+    /// Turn on-disk query embedding caching on or off. Turning it off
+    /// forgets the stored embeddings.
+    pub async fn set_persist_query_embeddings(&self, enabled: bool) -> Result<()> {
+        self.db.set_persist_query_embeddings(enabled).await?;
+        if !enabled {
+            self.db.clear_query_embeddings().await?;
+        }
+        self.persist_query_embeddings
+            .store(enabled, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Embedding for a search query: from the in-memory LRU, then the
+    /// database (when persistence is on), then the embedding backend.
+    async fn get_cached_query_embedding(&self, query: &str) -> Result<Vec<f32>> {
+        let embedding = self.lookup_query_embedding(query).await?;
+        self.vector_store
+            .lock()
+            .await
+            .set_active_dimensions(embedding.len());
+        Ok(embedding)
+    }
+
+    async fn lookup_query_embedding(&self, query: &str) -> Result<Vec<f32>> {
+        if let Some(cached_embedding) = self.query_embedding_cache.lock().await.get(query) {
+            println!(
+                "Using cached embedding for query: {}",
+                query.chars().take(50).collect::<String>()
+            );
+            return Ok(cached_embedding);
+        }
+
+        let persist = self.persist_query_embeddings.load(Ordering::Relaxed);
+        let backend = self.embedding_client.cache_key();
+        if persist {
+            match self.db.get_query_embedding(query, &backend).await {
+                Ok(Some(embedding)) => {
+                    self.query_embedding_cache
+                        .lock()
+                        .await
+                        .insert(query, embedding.clone());
+                    return Ok(embedding);
+                }
+                Ok(None) => {}
+                Err(e) => eprintln!("Failed to read stored query embedding: {}", e),
             }
         }
 
@@ -142,16 +816,17 @@ impl RagPipeline {
             .await
             .map_err(|e| format!("Failed to generate embedding: {}", e))?;
 
-        // Cache the embedding
-        {
-            let mut cache = self.query_embedding_cache.lock().await;
-            cache.insert(query.to_string(), embedding.clone());
-
-            // Keep cache size reasonable (last 20 queries)
-            if cache.len() > 20 {
-                if let Some(oldest_key) = cache.keys().next().cloned() {
-                    cache.remove(&oldest_key);
-                }
+        self.query_embedding_cache
+            .lock()
+            .await
+            .insert(query, embedding.clone());
+        if persist {
+            if let Err(e) = self
+                .db
+                .save_query_embedding(query, &backend, &embedding, query_cache::PERSISTED_LIMIT)
+                .await
+            {
+                eprintln!("Failed to store query embedding: {}", e);
             }
         }
 
@@ -166,10 +841,22 @@ impl RagPipeline {
         source: &str,
         profile: Option<&str>,
     ) -> Result<i64> {
-        self.ingest_document_with_auth(title, content, url, source, profile, false)
+        self.ingest_document_with_auth(title, content, url, source, profile, false, None)
             .await
     }
 
+    /// Ingest a document under its source's policy (see `ingest_policy`):
+    /// the content is cut to the policy's size limit, and once stored the
+    /// document gets the policy's default tags and, if asked, a summary.
+    /// Its language is detected and stored, and it is not embedded when the
+    /// language is excluded in Settings (see `language`). Web pages that look
+    /// like junk are stored by title and URL only, and pages whose content is
+    /// only a fetch placeholder are not embedded and are marked as needing a
+    /// refetch (see `page_quality`). A page's `structure`, when it came from
+    /// HTML, is stored with it and its chunks break at headings. Fails
+    /// without storing anything when the document would take the index past
+    /// its size limit (see `storage_budget`).
+    #[allow(clippy::too_many_arguments)]
     pub async fn ingest_document_with_auth(
         &self,
         title: &str,
@@ -178,97 +865,460 @@ impl RagPipeline {
         source: &str,
         profile: Option<&str>,
         needs_auth: bool,
+        structure: Option<&PageStructure>,
     ) -> Result<i64> {
-        // Chunk the document
-        let chunks = self.document_processor.chunk_text(content)?;
-
-        if chunks.is_empty() {
-            println!("Document produced no chunks, returning error");
-            return Err("Document produced no chunks".into());
-        }
-
-        println!(
-            "Processing document: '{}' -> {} chunks (content: {} chars)",
-            title.chars().take(60).collect::<String>(),
-            chunks.len(),
-            content.len()
-        );
-
-        // Store the full document (without embedding in document table)
+        let policy = self.ingest_policy(source).await;
+        let (issue, content) = screen_page(title, policy.limit_content(content), url, needs_auth);
+        let content = content.as_ref();
+        let placeholder = page_quality::is_placeholder(title, content, url);
+        self.check_storage(content.len() as u64).await?;
+        let (language, embed) = self.detect_languages(&[(title, content)]).await[0];
+        let embed = embed && issue.is_none() && !placeholder;
+        let structure = structure
+            .map(|structure| structure.located(content))
+            .unwrap_or_default();
         let doc_id = self
-            .db
-            .insert_document(
-                title,
-                content,
-                url,
-                source,
-                None, // No embedding at document level
-                None, // is_dead defaults to false
-                OperationPriority::BackgroundIngest,
-                profile,
+            .index_new_document(
+                title, content, url, source, profile, needs_auth, embed, &structure,
             )
             .await?;
-
-        // Mark as needs_auth if the URL required authentication
-        if needs_auth {
-            if let Some(url) = url {
-                self.db.mark_url_as_needs_auth(url).await?;
-            }
+        if !structure.is_empty() {
+            self.record_structure(doc_id, &structure).await;
+        }
+        self.record_language(doc_id, language).await;
+        self.record_quality_issue(doc_id, issue).await;
+        if placeholder {
+            self.mark_needs_refetch(doc_id).await;
         }
+        self.apply_ingest_policy(doc_id, content, &policy).await;
+        crate::monitoring::record_ingested(source, 1);
+        Ok(doc_id)
+    }
 
-        // Generate and store embeddings for each chunk
-        for chunk in chunks.iter() {
-            // Generate embedding for this chunk with document formatting
-            let chunk_embedding = self
-                .embedding_client
-                .generate_embedding(&chunk.content)
-                .await
-                .map_err(|e| format!("Failed to generate embedding for chunk: {}", e))?;
-            let embedding_bytes = bincode::serialize(&chunk_embedding)?;
+    /// Detect the language of each (title, content) and whether to embed
+    /// it; documents in languages excluded in Settings are kept for keyword
+    /// search only.
+    async fn detect_languages(
+        &self,
+        documents: &[(&str, &str)],
+    ) -> Vec<(Option<&'static str>, bool)> {
+        let excluded = self.db.get_excluded_languages().await.unwrap_or_else(|e| {
+            eprintln!("Failed to load excluded languages: {}", e);
+            Vec::new()
+        });
+        documents
+            .iter()
+            .map(|&(title, content)| {
+                let language = crate::language::detect(title, content);
+                let embed = !language.is_some_and(|code| excluded.iter().any(|l| l == code));
+                (language, embed)
+            })
+            .collect()
+    }
 
-            // Use actual chunk boundaries from DocumentChunk
-            let chunk_start = chunk.start_pos;
-            let chunk_end = chunk.end_pos;
+    /// Store a page's headings and anchors. Failures are logged; the page
+    /// then simply has no outline.
+    async fn record_structure(&self, doc_id: i64, structure: &PageStructure) {
+        if let Err(e) = self.db.set_document_structure(doc_id, structure).await {
+            eprintln!("Failed to store structure of document {}: {}", doc_id, e);
+        }
+    }
 
-            // Insert chunk embedding
-            let embedding_id = self
-                .db
-                .insert_chunk_embedding(
-                    doc_id,
-                    chunk_start,
-                    chunk_end,
-                    &embedding_bytes,
-                    OperationPriority::BackgroundIngest,
-                )
-                .await?;
+    /// Store a page's headings and anchors, found in its `content`, before
+    /// it is updated or re-indexed with that content: `update_document` and
+    /// `reindex_document` break chunks at the stored headings.
+    pub async fn store_document_structure(
+        &self,
+        doc_id: i64,
+        content: &str,
+        structure: &PageStructure,
+    ) -> Result<()> {
+        self.db
+            .set_document_structure(doc_id, &structure.located(content))
+            .await
+    }
 
-            // Add to vector store
-            {
-                let mut vector_store = self.vector_store.lock().await;
-                vector_store.add_chunk_vector(
-                    embedding_id,
-                    doc_id,
-                    chunk_start,
-                    chunk_end,
-                    chunk_embedding,
-                )?;
-            }
+    /// Chunk a stored document's `content`, breaking at the headings stored
+    /// for it if any and labelling each chunk with its section.
+    async fn chunk_stored_document(
+        &self,
+        doc_id: i64,
+        content: &str,
+    ) -> Result<Vec<DocumentChunk>> {
+        let structure = self
+            .db
+            .get_document_structure(doc_id)
+            .await
+            .unwrap_or_else(|e| {
+                eprintln!("Failed to load structure of document {}: {}", doc_id, e);
+                PageStructure::default()
+            });
+        let structure = structure.located(content);
+        let mut chunks = self
+            .document_processor
+            .chunk_sections(content, &structure.section_starts())?;
+        label_sections(&mut chunks, &structure);
+        Ok(chunks)
+    }
+
+    /// Store a document's detected language. Failures are logged; the
+    /// document is detected again in the background.
+    async fn record_language(&self, doc_id: i64, language: Option<&str>) {
+        if let Err(e) = self
+            .db
+            .set_document_language(doc_id, language.unwrap_or(""))
+            .await
+        {
+            eprintln!("Failed to store language of document {}: {}", doc_id, e);
         }
+    }
 
+    /// Store why a page was indexed by title and URL only. Failures are
+    /// logged; the page is then simply missing from the review list.
+    async fn record_quality_issue(&self, doc_id: i64, issue: Option<QualityIssue>) {
+        let Some(issue) = issue else {
+            return;
+        };
+        println!(
+            "Document {} looks like a junk page ({}); indexed by title and URL only",
+            doc_id,
+            issue.code()
+        );
+        if let Err(e) = self
+            .db
+            .set_document_quality_issue(doc_id, Some(issue.code()))
+            .await
         {
-            let vector_store = self.vector_store.lock().await;
-            let total_vectors = vector_store.chunk_vector_count();
-            println!(
-                "ingest_document completed successfully for: {} ({} chunks indexed, {} total vectors in memory)",
-                title,
-                chunks.len(),
-                total_vectors
-            );
+            eprintln!("Failed to flag document {} as low quality: {}", doc_id, e);
         }
-        Ok(doc_id)
     }
 
-    pub async fn query(&self, input: &str) -> Result<RagResponse> {
+    /// Mark a document whose content is only a fetch placeholder. Failures
+    /// are logged; the document is then found by search like any other.
+    async fn mark_needs_refetch(&self, doc_id: i64) {
+        println!(
+            "Document {} has no content beyond a placeholder; not embedded until refetched",
+            doc_id
+        );
+        if let Err(e) = self.db.set_document_needs_refetch(doc_id, true).await {
+            eprintln!("Failed to mark document {} for refetching: {}", doc_id, e);
+        }
+    }
+
+    /// Space used by the index, its size limit and the free disk space.
+    pub async fn storage_status(&self) -> Result<StorageStatus> {
+        let (index_bytes, content_bytes, document_count) = self.db.get_storage_usage().await?;
+        Ok(StorageStatus {
+            index_bytes,
+            content_bytes,
+            document_count,
+            free_bytes: crate::notifications::available_space(&crate::db::data_dir()),
+            max_index_bytes: self.db.get_max_index_bytes().await?,
+        })
+    }
+
+    /// Refuse to index `content_bytes` more content past the index size
+    /// limit, warning when the disk is nearly full.
+    async fn check_storage(&self, content_bytes: u64) -> Result<()> {
+        let status = self.storage_status().await?;
+        status.check(status.estimate_growth(content_bytes))
+    }
+
+    /// The ingestion policy for documents from `source`.
+    async fn ingest_policy(&self, source: &str) -> IngestPolicy {
+        let Some(kind) = SourceKind::of(source) else {
+            return IngestPolicy::default();
+        };
+        self.db.get_ingest_policy(kind).await.unwrap_or_else(|e| {
+            eprintln!("Failed to load ingestion policy: {}", e);
+            IngestPolicy::default()
+        })
+    }
+
+    /// Give a newly stored document its policy's default tags and summary.
+    /// Failures are logged; the document stays stored either way.
+    async fn apply_ingest_policy(&self, doc_id: i64, content: &str, policy: &IngestPolicy) {
+        for tag in &policy.default_tags {
+            if let Err(e) = self.db.add_tag_to_documents(&[doc_id], tag).await {
+                eprintln!("Failed to tag document {} with '{}': {}", doc_id, tag, e);
+            }
+        }
+        if policy.auto_summarize {
+            let summary = ingest_policy::summarize(content);
+            if !summary.is_empty() {
+                if let Err(e) = self.db.set_document_summary(doc_id, &summary).await {
+                    eprintln!("Failed to store summary for document {}: {}", doc_id, e);
+                }
+            }
+        }
+    }
+
+    /// Chunk, embed and store a new document; with `embed` false it is
+    /// stored for keyword search only. Chunks do not run across the
+    /// headings of `structure`, located in `content`, and are labelled with
+    /// their section.
+    #[allow(clippy::too_many_arguments)]
+    async fn index_new_document(
+        &self,
+        title: &str,
+        content: &str,
+        url: Option<&str>,
+        source: &str,
+        profile: Option<&str>,
+        needs_auth: bool,
+        embed: bool,
+        structure: &PageStructure,
+    ) -> Result<i64> {
+        // Chunk the document
+        let mut chunks = self
+            .document_processor
+            .chunk_sections(content, &structure.section_starts())?;
+        label_sections(&mut chunks, structure);
+
+        if chunks.is_empty() {
+            println!("Document produced no chunks, returning error");
+            return Err("Document produced no chunks".into());
+        }
+
+        println!(
+            "Processing document: '{}' -> {} chunks (content: {} chars)",
+            title.chars().take(60).collect::<String>(),
+            chunks.len(),
+            content.len()
+        );
+
+        if !embed {
+            return self
+                .store_keyword_only(title, content, url, source, profile, needs_auth)
+                .await;
+        }
+
+        // Without an embedding backend, store the document for keyword search
+        // and leave it incomplete; it is embedded when the backend returns
+        if !self.is_semantic_search_available() {
+            return self
+                .store_unembedded(title, content, url, source, profile, needs_auth)
+                .await;
+        }
+
+        // Embed every chunk before touching the database so the document and
+        // its chunks can be written in a single transaction
+        let (embedded, rows) = match self.embed_chunks(&chunks).await {
+            Ok(result) => result,
+            Err(e) if !self.check_embedding_backend().await => {
+                eprintln!("Embedding failed with the backend down: {}", e);
+                return self
+                    .store_unembedded(title, content, url, source, profile, needs_auth)
+                    .await;
+            }
+            Err(e) => return Err(e),
+        };
+
+        let (doc_id, embedding_ids) = self
+            .db
+            .insert_document_with_chunks(title, content, url, source, profile, needs_auth, &rows)
+            .await?;
+
+        self.add_chunk_vectors(doc_id, embedded, embedding_ids)
+            .await?;
+
+        {
+            let vector_store = self.vector_store.lock().await;
+            let total_vectors = vector_store.chunk_vector_count();
+            println!(
+                "ingest_document completed successfully for: {} ({} chunks indexed, {} total vectors in memory)",
+                title,
+                chunks.len(),
+                total_vectors
+            );
+        }
+        Ok(doc_id)
+    }
+
+    /// Ingest several new documents from one source together: all their
+    /// chunks are embedded in one batch and the documents are written in a
+    /// single transaction (`Database::batch_insert_documents`). Each entry
+    /// is (title, content, url); returns the document IDs in order.
+    ///
+    /// Fails as a whole if any document produces no chunks. In keyword-only
+    /// mode the documents are stored unembedded one by one. The source's
+    /// policy and the junk-page check apply as in `ingest_document_with_auth`.
+    pub async fn ingest_documents(
+        &self,
+        documents: &[(&str, &str, Option<&str>)],
+        source: &str,
+    ) -> Result<Vec<i64>> {
+        let policy = self.ingest_policy(source).await;
+        let screened: Vec<(Option<QualityIssue>, Cow<str>)> = documents
+            .iter()
+            .map(|&(title, content, url)| {
+                screen_page(title, policy.limit_content(content), url, false)
+            })
+            .collect();
+        let documents: Vec<(&str, &str, Option<&str>)> = documents
+            .iter()
+            .zip(&screened)
+            .map(|(&(title, _, url), (_, content))| (title, content.as_ref(), url))
+            .collect();
+        let content_bytes = documents.iter().map(|&(_, c, _)| c.len() as u64).sum();
+        self.check_storage(content_bytes).await?;
+        let texts: Vec<(&str, &str)> = documents.iter().map(|&(t, c, _)| (t, c)).collect();
+        let languages = self.detect_languages(&texts).await;
+        let placeholders: Vec<bool> = documents
+            .iter()
+            .map(|&(title, content, url)| page_quality::is_placeholder(title, content, url))
+            .collect();
+        let embed: Vec<bool> = languages
+            .iter()
+            .zip(&screened)
+            .zip(&placeholders)
+            .map(|((&(_, embed), (issue, _)), &placeholder)| {
+                embed && issue.is_none() && !placeholder
+            })
+            .collect();
+        let ids = self.index_new_documents(&documents, &embed, source).await?;
+        for ((((&doc_id, &(_, content, _)), &(language, _)), &(issue, _)), &placeholder) in ids
+            .iter()
+            .zip(&documents)
+            .zip(&languages)
+            .zip(&screened)
+            .zip(&placeholders)
+        {
+            self.record_language(doc_id, language).await;
+            self.record_quality_issue(doc_id, issue).await;
+            if placeholder {
+                self.mark_needs_refetch(doc_id).await;
+            }
+            self.apply_ingest_policy(doc_id, content, &policy).await;
+        }
+        crate::monitoring::record_ingested(source, ids.len());
+        Ok(ids)
+    }
+
+    /// Chunk, embed and store several new documents in one batch. Documents
+    /// whose `embed` flag is false are stored for keyword search only.
+    async fn index_new_documents(
+        &self,
+        documents: &[(&str, &str, Option<&str>)],
+        embed: &[bool],
+        source: &str,
+    ) -> Result<Vec<i64>> {
+        let mut chunks = Vec::new();
+        let mut chunk_counts = Vec::with_capacity(documents.len());
+        for ((title, content, _), &embed) in documents.iter().zip(embed) {
+            let document_chunks = self.document_processor.chunk_text(content)?;
+            if document_chunks.is_empty() {
+                return Err(format!("Document '{}' produced no chunks", title).into());
+            }
+            if !embed {
+                chunk_counts.push(0);
+                continue;
+            }
+            chunk_counts.push(document_chunks.len());
+            chunks.extend(document_chunks);
+        }
+
+        let embedded = if self.is_semantic_search_available() {
+            match self.embed_chunks(&chunks).await {
+                Ok(result) => Some(result),
+                Err(e) if !self.check_embedding_backend().await => {
+                    eprintln!("Embedding failed with the backend down: {}", e);
+                    None
+                }
+                Err(e) => return Err(e),
+            }
+        } else {
+            None
+        };
+        let Some((mut embedded, mut rows)) = embedded else {
+            let mut ids = Vec::with_capacity(documents.len());
+            for (&(title, content, url), &embed) in documents.iter().zip(embed) {
+                let doc_id = if embed {
+                    self.store_unembedded(title, content, url, source, None, false)
+                        .await?
+                } else {
+                    self.store_keyword_only(title, content, url, source, None, false)
+                        .await?
+                };
+                ids.push(doc_id);
+            }
+            return Ok(ids);
+        };
+
+        // Split the batch's chunks back into per-document runs
+        let mut per_document = Vec::with_capacity(documents.len());
+        for count in chunk_counts.iter().rev() {
+            let split_at = rows.len() - count;
+            per_document.push((embedded.split_off(split_at), rows.split_off(split_at)));
+        }
+        per_document.reverse();
+
+        let entries: Vec<(&str, &str, Option<&str>, &[ChunkRow])> = documents
+            .iter()
+            .zip(&per_document)
+            .map(|(&(title, content, url), (_, rows))| (title, content, url, rows.as_slice()))
+            .collect();
+        let inserted = self.db.batch_insert_documents(source, &entries).await?;
+
+        let mut ids = Vec::with_capacity(inserted.len());
+        for ((doc_id, embedding_ids), (embedded, _)) in inserted.into_iter().zip(per_document) {
+            self.add_chunk_vectors(doc_id, embedded, embedding_ids)
+                .await?;
+            ids.push(doc_id);
+        }
+        println!(
+            "ingest_documents stored {} documents ({} chunks) in one batch",
+            ids.len(),
+            chunks.len()
+        );
+        Ok(ids)
+    }
+
+    /// Store a document for keyword search only, with no chunks to embed,
+    /// so `repair_incomplete_documents` leaves it alone.
+    async fn store_keyword_only(
+        &self,
+        title: &str,
+        content: &str,
+        url: Option<&str>,
+        source: &str,
+        profile: Option<&str>,
+        needs_auth: bool,
+    ) -> Result<i64> {
+        let (doc_id, _) = self
+            .db
+            .insert_document_with_chunks(title, content, url, source, profile, needs_auth, &[])
+            .await?;
+        println!(
+            "Stored '{}' for keyword search only",
+            title.chars().take(60).collect::<String>()
+        );
+        Ok(doc_id)
+    }
+
+    /// Store a document without chunk embeddings, queued for embedding by
+    /// `repair_incomplete_documents` once the backend is available.
+    async fn store_unembedded(
+        &self,
+        title: &str,
+        content: &str,
+        url: Option<&str>,
+        source: &str,
+        profile: Option<&str>,
+        needs_auth: bool,
+    ) -> Result<i64> {
+        let doc_id = self
+            .db
+            .insert_document_pending_embedding(title, content, url, source, profile, needs_auth)
+            .await?;
+        println!(
+            "Stored '{}' for keyword search; embedding deferred until the backend is available",
+            title.chars().take(60).collect::<String>()
+        );
+        Ok(doc_id)
+    }
+
+    pub async fn query(&self, input: &str) -> Result<RagResponse> {
         self.query_with_cutoff(input, 0.2).await // Use more permissive default
     }
 
@@ -322,6 +1372,21 @@ impl RagPipeline {
             query, limit, cutoff
         );
 
+        if !self.is_semantic_search_available() {
+            let escaped = escape_fts_query(query);
+            if escaped.is_empty() {
+                return Ok(vec![]);
+            }
+            let hits = self
+                .db
+                .search_documents_scored(&escaped, limit as i64, false)
+                .await?;
+            return Ok(hits
+                .into_iter()
+                .map(|(doc, score)| (doc, score as f32))
+                .collect());
+        }
+
         // Use cached embedding for the query
         let query_embedding = self.get_cached_query_embedding(query).await?;
         println!(
@@ -337,7 +1402,11 @@ impl RagPipeline {
                 "Searching in vector store: {} chunk vectors available",
                 chunk_count
             );
-            vector_store.search_chunks_with_cutoff(&query_embedding, limit * 2, cutoff)?
+            comparable_chunks(vector_store.search_chunks_with_cutoff(
+                &query_embedding,
+                limit * 2,
+                cutoff,
+            ))?
         };
 
         println!("Found {} chunk results", chunk_results.len());
@@ -352,7 +1421,7 @@ impl RagPipeline {
             }
             seen_docs.insert(chunk_result.doc_id);
 
-            if let Some(doc) = self.db.get_document(chunk_result.doc_id).await? {
+            if let Some(doc) = self.db.get_live_document(chunk_result.doc_id).await? {
                 results.push((doc, chunk_result.similarity));
 
                 if results.len() >= limit {
@@ -370,37 +1439,261 @@ impl RagPipeline {
         Ok(response.answer)
     }
 
+    /// Passage of `content` around the best match for `query`, for hits
+    /// that have no retrieved chunk.
     fn extract_snippet(&self, content: &str, query: &str) -> String {
-        let query_lower = query.to_lowercase();
-        let query_words: Vec<&str> = query_lower.split_whitespace().collect();
-        let content_lower = content.to_lowercase();
-
-        // Find the position of the first query word
-        let mut best_position = 0;
-        for word in &query_words {
-            if let Some(pos) = content_lower.find(word) {
-                best_position = pos;
-                break;
+        snippet::centered_snippet(content, query, SNIPPET_CHARS)
+    }
+
+    /// Fused search hits; see `search_fused`.
+    pub async fn get_search_hits_fused(
+        &self,
+        query: &str,
+        options: SearchOptions,
+    ) -> Result<Vec<DocumentSource>> {
+        Ok(self.search_fused(query, options).await?.hits)
+    }
+
+    /// Keyword hits alone, scored relative to the best one: the quick first
+    /// stage of a streamed search. Operators apply as in `search_fused`.
+    pub async fn get_keyword_hits(
+        &self,
+        query: &str,
+        options: SearchOptions,
+    ) -> Result<Vec<DocumentSource>> {
+        let parsed = query_syntax::parse(query);
+        let free_text = parsed.free_text();
+        let escaped = [escape_fts_query(&parsed.text), fts_phrases(&parsed)]
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        if escaped.is_empty() {
+            return Ok(vec![]);
+        }
+        let (scope, excluded) = self.search_scope(&parsed, options.collection_id).await?;
+        let hits: Vec<(Document, f64)> = self
+            .db
+            .search_documents_scored_in(
+                &escaped,
+                FILTERED_FTS_LIMIT,
+                options.include_dead,
+                options.collection_id,
+            )
+            .await?
+            .into_iter()
+            .filter(|(doc, _)| {
+                !excluded.contains(&doc.id)
+                    && scope.as_ref().map(|s| s.contains(&doc.id)).unwrap_or(true)
+            })
+            .take(10)
+            .collect();
+        let max_score = hits.iter().map(|(_, s)| *s).fold(0.0, f64::max);
+        Ok(hits
+            .into_iter()
+            .map(|(doc, score)| DocumentSource {
+                doc_id: doc.id,
+                content_snippet: self.extract_snippet(&doc.content, &free_text),
+                chunk: self.best_keyword_passage(&doc.content, &free_text),
+                title: doc.title,
+                similarity: if max_score > 0.0 {
+                    (score / max_score) as f32
+                } else {
+                    0.0
+                },
+                profile: doc.profile,
+                needs_auth: doc.needs_auth.unwrap_or(false),
+                is_dead: doc.is_dead.unwrap_or(false),
+            })
+            .collect())
+    }
+
+    /// Saved documents similar to the page at `url`, judged by `text` (its
+    /// title and summary), or by the saved copy when `text` is empty.
+    /// Vector hits when the embedding backend is up, otherwise keyword
+    /// matches on any of the words. The page itself is left out.
+    pub async fn get_related_to_page(
+        &self,
+        url: &str,
+        text: &str,
+        limit: usize,
+    ) -> Result<Vec<DocumentSource>> {
+        let saved = self.db.get_document_by_url(url).await?;
+        let saved_id = saved.as_ref().map(|doc| doc.id);
+        let text: String = match saved {
+            Some(doc) if text.trim().is_empty() => format!("{} {}", doc.title, doc.content),
+            _ => text.trim().to_string(),
+        }
+        .chars()
+        .take(RELATED_TEXT_CHARS)
+        .collect();
+        if text.trim().is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut hits = if self.is_semantic_search_available() {
+            self.vector_hits(&text, 0.0, false, None).await?
+        } else {
+            let any_word = any_word_fts_query(&text);
+            if any_word.is_empty() {
+                return Ok(vec![]);
             }
+            let matches = self
+                .db
+                .search_documents_scored(&any_word, limit as i64 + 1, false)
+                .await?;
+            let max_score = matches.iter().map(|(_, s)| *s).fold(0.0, f64::max);
+            matches
+                .into_iter()
+                .map(|(doc, score)| DocumentSource {
+                    doc_id: doc.id,
+                    content_snippet: self.extract_snippet(&doc.content, &text),
+                    title: doc.title,
+                    similarity: if max_score > 0.0 {
+                        (score / max_score) as f32
+                    } else {
+                        0.0
+                    },
+                    profile: doc.profile,
+                    needs_auth: doc.needs_auth.unwrap_or(false),
+                    is_dead: doc.is_dead.unwrap_or(false),
+                    chunk: None,
+                })
+                .collect()
+        };
+        hits.retain(|hit| Some(hit.doc_id) != saved_id);
+        hits.truncate(limit);
+        Ok(hits)
+    }
+
+    /// Tags to offer while document `doc_id` is tagged: existing tags starting
+    /// with `prefix`, and the tags of its nearest neighbours by vector
+    /// similarity. Without embeddings only the matching tags are offered.
+    pub async fn suggest_tags(
+        &self,
+        doc_id: i64,
+        prefix: &str,
+        current: &[String],
+        limit: usize,
+    ) -> Result<TagSuggestions> {
+        let counts = self.db.get_tag_counts().await?;
+        let nearest = self
+            .vector_store
+            .lock()
+            .await
+            .nearest_documents(doc_id, TAG_NEIGHBORS);
+        let ids: Vec<i64> = nearest.iter().map(|hit| hit.doc_id).collect();
+        let mut tags = self.db.get_tags_for_documents(&ids).await?;
+        let neighbors: Vec<(f32, Vec<String>)> = nearest
+            .into_iter()
+            .filter_map(|hit| Some((hit.similarity, tags.remove(&hit.doc_id)?)))
+            .collect();
+        Ok(rank_tag_suggestions(
+            &counts, &neighbors, prefix, current, limit,
+        ))
+    }
+
+    /// Vector hits alone: the second stage of a streamed search. Empty in
+    /// keyword-only mode. Operators apply as in `search_fused`.
+    pub async fn get_semantic_hits(
+        &self,
+        query: &str,
+        options: SearchOptions,
+    ) -> Result<Vec<DocumentSource>> {
+        let parsed = query_syntax::parse(query);
+        let free_text = parsed.free_text();
+        if free_text.is_empty() || !self.is_semantic_search_available() {
+            return Ok(vec![]);
         }
+        let (scope, _) = self.search_scope(&parsed, options.collection_id).await?;
+        self.vector_hits(&free_text, 0.0, options.include_dead, scope.as_ref())
+            .await
+    }
 
-        // Extract snippet around that position
-        let start = best_position.saturating_sub(100);
-        let end = std::cmp::min(best_position + 300, content.len());
+    /// Respell query words the full-text index has never seen as the closest
+    /// indexed terms. `None` when every word is indexed or has no close match.
+    pub async fn correct_spelling(&self, query: &str) -> Option<String> {
+        let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+        let mut corrected = Vec::with_capacity(words.len());
+        for word in &words {
+            let mut options = Vec::new();
+            if spelling::is_correctable(word)
+                && !self.db.is_indexed_term(word).await.unwrap_or(true)
+            {
+                match self.db.get_spelling_candidates(word).await {
+                    Ok(candidates) => {
+                        options = spelling::corrections(word, &candidates);
+                        options.truncate(1);
+                    }
+                    Err(e) => eprintln!("Spelling lookup failed for '{}': {}", word, e),
+                }
+            }
+            corrected.push(options);
+        }
+        spelling::suggest_queries(&words, &corrected)
+            .into_iter()
+            .next()
+    }
 
-        // Ensure start and end are on UTF-8 character boundaries
-        let mut safe_start = start;
-        while safe_start > 0 && !content.is_char_boundary(safe_start) {
-            safe_start -= 1;
+    /// Documents a parsed query may return (`None` for any) and the ones its
+    /// exclusions rule out. The scope combines the collection, the metadata
+    /// operators and the quoted phrases, and never contains excluded ones.
+    async fn search_scope(
+        &self,
+        parsed: &ParsedQuery,
+        collection_id: Option<i64>,
+    ) -> Result<(Option<HashSet<i64>>, HashSet<i64>)> {
+        let mut scope = match collection_id {
+            Some(collection_id) => Some(self.db.get_collection_document_ids(collection_id).await?),
+            None => None,
+        };
+        if parsed.has_metadata_filters() {
+            let filtered = self.db.get_filtered_document_ids(parsed).await?;
+            scope = Some(narrow_scope(scope, filtered));
+        }
+        let phrases = fts_phrases(parsed);
+        if !phrases.is_empty() {
+            let with_phrases = self.db.get_fts_document_ids(&phrases).await?;
+            scope = Some(narrow_scope(scope, with_phrases));
+        }
+        let excluded = if parsed.excluded.is_empty() {
+            HashSet::new()
+        } else {
+            let any_excluded = parsed
+                .excluded
+                .iter()
+                .map(|e| format!("\"{}\"", e.replace('"', "")))
+                .collect::<Vec<_>>()
+                .join(" OR ");
+            self.db.get_fts_document_ids(&any_excluded).await?
+        };
+        if let Some(scope) = scope.as_mut() {
+            scope.retain(|id| !excluded.contains(id));
         }
+        Ok((scope, excluded))
+    }
 
-        let mut safe_end = end;
-        while safe_end > safe_start && !content.is_char_boundary(safe_end) {
-            safe_end -= 1;
+    /// Documents matching a query without ranking them, for listing: those
+    /// passing its operators, phrases and exclusions and containing every
+    /// free word. `None` when the query filters nothing.
+    pub async fn filter_document_ids(&self, query: &str) -> Result<Option<HashSet<i64>>> {
+        let parsed = query_syntax::parse(query);
+        let (mut scope, excluded) = self.search_scope(&parsed, None).await?;
+        let words = escape_fts_query(&parsed.text);
+        if !words.is_empty() {
+            let mut with_words = self.db.get_fts_document_ids(&words).await?;
+            with_words.retain(|id| !excluded.contains(id));
+            scope = Some(narrow_scope(scope, with_words));
+        }
+        if scope.is_none() && !excluded.is_empty() {
+            let mut all = self
+                .db
+                .get_filtered_document_ids(&ParsedQuery::default())
+                .await?;
+            all.retain(|id| !excluded.contains(id));
+            scope = Some(all);
         }
-        // Make sure we don't cut in the middle of a word
-        let snippet = &content[safe_start..safe_end];
-        format!("...{}\n...", snippet.trim())
+        Ok(scope)
     }
 
     /// Fuse vector and BM25 results using thresholded Reciprocal Rank Fusion (RRF).
@@ -410,29 +1703,131 @@ impl RagPipeline {
     /// so the existing similarity_cutoff slider remains meaningful.
     ///
     /// If vector search fails (e.g. embedding server down), BM25-only results are returned.
-    pub async fn get_search_hits_fused(&self, query: &str) -> Result<Vec<DocumentSource>> {
+    /// Documents marked dead by the link checker are dropped unless `include_dead` is set.
+    /// With `expand_query`, the FTS arm matches synonyms of each term and the vector arm
+    /// also searches paraphrases of the query; hits are merged per document before fusion.
+    /// With `correct_spelling`, a query with unindexed words is respelled first
+    /// (see `correct_spelling`) and both arms search the corrected query.
+    ///
+    /// Search operators (see `query_syntax`) restrict both arms to the documents that
+    /// pass them; only the free text and phrase words are embedded. A query made of
+    /// operators alone lists the newest matching documents.
+    pub async fn search_fused(&self, query: &str, options: SearchOptions) -> Result<FusedSearch> {
         const BM25_PERCENT_THRESHOLD: f64 = 0.5;
         const K: f32 = 60.0;
+        let _timer = crate::monitoring::Timer::start(crate::monitoring::SEARCH_SECONDS);
+
+        let mut parsed = query_syntax::parse(query);
+        let mut corrected_query = None;
+        if options.correct_spelling && !parsed.text.is_empty() {
+            if let Some(corrected) = self.correct_spelling(&parsed.text).await {
+                corrected_query = Some(parsed.with_text(&corrected));
+                parsed.text = corrected;
+            }
+        }
+        let free_text = parsed.free_text();
+        let query = free_text.as_str();
+
+        // Operators narrow both arms to a set of candidate documents
+        let include_dead = options.include_dead;
+        let (scope, excluded) = self.search_scope(&parsed, options.collection_id).await?;
+        let phrases = fts_phrases(&parsed);
+
+        // Operators alone: list the newest matching documents
+        if query.is_empty() {
+            let mut ids: Vec<i64> = scope.unwrap_or_default().into_iter().collect();
+            ids.sort_unstable_by(|a, b| b.cmp(a));
+            let mut hits = Vec::new();
+            for doc_id in ids {
+                let Some(doc) = self.db.get_live_document(doc_id).await? else {
+                    continue;
+                };
+                let is_dead = doc.is_dead.unwrap_or(false);
+                if is_dead && !include_dead {
+                    continue;
+                }
+                hits.push(DocumentSource {
+                    doc_id,
+                    content_snippet: doc.content.chars().take(300).collect(),
+                    title: doc.title,
+                    similarity: 1.0,
+                    profile: doc.profile,
+                    needs_auth: doc.needs_auth.unwrap_or(false),
+                    is_dead,
+                    chunk: None,
+                });
+                if hits.len() >= 10 {
+                    break;
+                }
+            }
+            return Ok(FusedSearch {
+                hits,
+                corrected_query,
+            });
+        }
 
-        // Escape query for FTS5 (wrap each token in double quotes)
-        let escaped = query
-            .split_whitespace()
-            .map(|w| format!("\"{}\"", w.replace('"', "")))
+        let scope = scope.as_ref();
+        let text = if options.expand_query {
+            query_expansion::expand_fts_query(&parsed.text)
+        } else {
+            escape_fts_query(&parsed.text)
+        };
+        let escaped = [text, phrases]
+            .into_iter()
+            .filter(|part| !part.is_empty())
             .collect::<Vec<_>>()
             .join(" ");
+        // Filtered searches fetch more keyword matches to filter afterwards
+        let fts_limit = if scope.is_some() || !excluded.is_empty() {
+            FILTERED_FTS_LIMIT
+        } else {
+            20
+        };
 
-        // Run both searches concurrently
-        let (vector_result, fts_result) =
-            tokio::join!(self.get_search_hits_with_cutoff(query, 0.0), async {
+        // Run both searches concurrently; the vector arm is skipped in
+        // keyword-only mode
+        let semantic = self.is_semantic_search_available();
+        let (vector_result, fts_result) = tokio::join!(
+            async {
+                if !semantic {
+                    Ok(vec![])
+                } else if options.expand_query {
+                    self.get_search_hits_expanded(query, include_dead, scope)
+                        .await
+                } else {
+                    self.vector_hits(query, 0.0, include_dead, scope).await
+                }
+            },
+            async {
                 if escaped.is_empty() {
                     Ok(vec![])
                 } else {
-                    self.db.search_documents_scored(&escaped, 20).await
+                    self.db
+                        .search_documents_scored_in(
+                            &escaped,
+                            fts_limit,
+                            include_dead,
+                            options.collection_id,
+                        )
+                        .await
                 }
-            });
+            }
+        );
 
-        let vector_sources = vector_result.unwrap_or_default();
-        let fts_scored = fts_result.unwrap_or_default();
+        let in_scope = |doc_id: &i64| {
+            !excluded.contains(doc_id) && scope.map(|s| s.contains(doc_id)).unwrap_or(true)
+        };
+        let vector_sources: Vec<DocumentSource> = vector_result
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|source| in_scope(&source.doc_id))
+            .collect();
+        let mut fts_scored: Vec<(Document, f64)> = fts_result
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|(doc, _)| in_scope(&doc.id))
+            .collect();
+        fts_scored.truncate(20);
 
         // Sort vector results descending by similarity
         let mut sorted_vector = vector_sources.clone();
@@ -473,6 +1868,8 @@ impl RagPipeline {
                 similarity: 0.0,
                 profile: doc.profile.clone(),
                 needs_auth: doc.needs_auth.unwrap_or(false),
+                is_dead: doc.is_dead.unwrap_or(false),
+                chunk: self.best_keyword_passage(&doc.content, query),
             });
         }
 
@@ -485,9 +1882,41 @@ impl RagPipeline {
             *rrf_scores.entry(doc.id).or_insert(0.0) += 1.0 / (K + rank as f32 + 1.0);
         }
 
-        // Normalise RRF scores to 0-1
-        let max_rrf = rrf_scores
-            .values()
+        // Favour documents linked to or from what the user just read
+        if options.boost_linked {
+            let recent: Vec<i64> = self
+                .recently_viewed
+                .lock()
+                .unwrap()
+                .iter()
+                .copied()
+                .collect();
+            if !recent.is_empty() {
+                let linked = self
+                    .db
+                    .get_linked_document_ids(&recent)
+                    .await
+                    .unwrap_or_default();
+                for (doc_id, score) in rrf_scores.iter_mut() {
+                    if linked.contains(doc_id) {
+                        *score *= 1.0 + links::LINK_BOOST;
+                    }
+                }
+            }
+        }
+
+        // Raise or lower documents by their domain's quality
+        let doc_ids: Vec<i64> = rrf_scores.keys().copied().collect();
+        let multipliers = self.domain_multipliers(&doc_ids).await;
+        for (doc_id, score) in rrf_scores.iter_mut() {
+            if let Some(multiplier) = multipliers.get(doc_id) {
+                *score *= multiplier;
+            }
+        }
+
+        // Normalise RRF scores to 0-1
+        let max_rrf = rrf_scores
+            .values()
             .cloned()
             .fold(f32::NEG_INFINITY, f32::max);
         if max_rrf > 0.0 {
@@ -511,7 +1940,342 @@ impl RagPipeline {
             })
             .collect();
 
-        Ok(results)
+        Ok(FusedSearch {
+            hits: results,
+            corrected_query,
+        })
+    }
+
+    /// Search for passages rather than documents: every matching chunk is a
+    /// separate hit with its own score, so one document can appear several
+    /// times. Operators apply as in `search_fused`.
+    ///
+    /// In keyword-only mode the passages come from the best keyword matches,
+    /// scored by how often the query words occur in each chunk.
+    pub async fn search_passages(
+        &self,
+        query: &str,
+        options: SearchOptions,
+    ) -> Result<Vec<PassageHit>> {
+        let parsed = query_syntax::parse(query);
+        let query = parsed.free_text();
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+        let (scope, excluded) = self.search_scope(&parsed, options.collection_id).await?;
+
+        let mut chunks: Vec<(i64, usize, usize, f32)> = Vec::new();
+        if self.is_semantic_search_available() {
+            let query_embedding = self.get_cached_query_embedding(&query).await?;
+            let vector_store = self.vector_store.lock().await;
+            for chunk in comparable_chunks(vector_store.search_chunks_scoped(
+                &query_embedding,
+                MAX_PASSAGES * 2,
+                0.0,
+                scope.as_ref(),
+            ))? {
+                chunks.push((
+                    chunk.doc_id,
+                    chunk.chunk_start,
+                    chunk.chunk_end,
+                    chunk.similarity,
+                ));
+            }
+        } else {
+            let escaped = [escape_fts_query(&parsed.text), fts_phrases(&parsed)]
+                .into_iter()
+                .filter(|part| !part.is_empty())
+                .collect::<Vec<_>>()
+                .join(" ");
+            let docs = self
+                .db
+                .search_documents_scored_in(
+                    &escaped,
+                    FILTERED_FTS_LIMIT,
+                    options.include_dead,
+                    options.collection_id,
+                )
+                .await?;
+            for (doc, _) in docs {
+                if scope.as_ref().is_some_and(|scope| !scope.contains(&doc.id)) {
+                    continue;
+                }
+                for (start, end, score) in self.keyword_passages(&doc.content, &query) {
+                    chunks.push((doc.id, start, end, score));
+                }
+            }
+            normalize_passage_scores(&mut chunks);
+            chunks.sort_by(|a, b| b.3.partial_cmp(&a.3).unwrap_or(std::cmp::Ordering::Equal));
+        }
+
+        // Rank by domain quality too; hits keep their own similarity
+        let mut doc_ids: Vec<i64> = chunks.iter().map(|chunk| chunk.0).collect();
+        doc_ids.sort_unstable();
+        doc_ids.dedup();
+        let multipliers = self.domain_multipliers(&doc_ids).await;
+        if !multipliers.is_empty() {
+            let ranked = |chunk: &(i64, usize, usize, f32)| {
+                chunk.3 * multipliers.get(&chunk.0).copied().unwrap_or(1.0)
+            };
+            chunks.sort_by(|a, b| {
+                ranked(b)
+                    .partial_cmp(&ranked(a))
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            });
+        }
+
+        let mut docs: HashMap<i64, Option<Document>> = HashMap::new();
+        let mut hits = Vec::new();
+        for (doc_id, start, end, similarity) in chunks {
+            if excluded.contains(&doc_id) {
+                continue;
+            }
+            if let std::collections::hash_map::Entry::Vacant(entry) = docs.entry(doc_id) {
+                entry.insert(self.db.get_live_document(doc_id).await?);
+            }
+            let Some(doc) = docs.get(&doc_id).and_then(Option::as_ref) else {
+                continue;
+            };
+            if doc.is_dead.unwrap_or(false) && !options.include_dead {
+                continue;
+            }
+            if let Some(hit) = PassageHit::from_document(doc, start, end, similarity) {
+                hits.push(hit);
+            }
+            if hits.len() >= MAX_PASSAGES {
+                break;
+            }
+        }
+        self.attach_provenance(&mut hits).await;
+        Ok(hits)
+    }
+
+    /// Score multipliers of the documents among `doc_ids` on a domain
+    /// marked as high or low quality (see `domain_preferences`). Failures
+    /// are logged; the documents are then ranked unchanged.
+    async fn domain_multipliers(&self, doc_ids: &[i64]) -> HashMap<i64, f32> {
+        let settings = self.db.cached_settings();
+        let preferences = settings.domain_preferences;
+        if preferences.is_empty() {
+            return HashMap::new();
+        }
+        let urls = match self.db.get_document_url_map(doc_ids).await {
+            Ok(urls) => urls,
+            Err(e) => {
+                eprintln!("Failed to load URLs for domain preferences: {}", e);
+                return HashMap::new();
+            }
+        };
+        let boost = settings.domain_boost;
+        urls.into_iter()
+            .filter_map(|(doc_id, url)| {
+                let quality = domain_preferences::quality_in(&url, &preferences)?;
+                Some((doc_id, boost.multiplier(quality)))
+            })
+            .collect()
+    }
+
+    /// Fill in the provenance of hits that are stored chunks. Failures are
+    /// logged; the hits are then shown without it.
+    async fn attach_provenance(&self, hits: &mut [PassageHit]) {
+        let mut doc_ids: Vec<i64> = hits.iter().map(|hit| hit.doc_id).collect();
+        doc_ids.sort_unstable();
+        doc_ids.dedup();
+        let provenance = match self.db.get_chunk_provenance(&doc_ids).await {
+            Ok(provenance) => provenance,
+            Err(e) => {
+                eprintln!("Failed to load chunk provenance: {}", e);
+                return;
+            }
+        };
+        for hit in hits {
+            if let Some(found) = provenance.get(&(hit.doc_id, hit.chunk_start)) {
+                hit.provenance = found.clone();
+            }
+        }
+    }
+
+    /// Passages of one document matching `query`, in document order: its
+    /// best passages scoring within `DOCUMENT_PASSAGE_RATIO` of the top one.
+    pub async fn document_passages(&self, doc_id: i64, query: &str) -> Result<Vec<PassageHit>> {
+        let query = query_syntax::parse(query).free_text();
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+        let doc = self
+            .db
+            .get_document(doc_id)
+            .await?
+            .ok_or_else(|| format!("Document {} not found", doc_id))?;
+
+        let mut chunks: Vec<(i64, usize, usize, f32)> = if self.is_semantic_search_available() {
+            let query_embedding = self.get_cached_query_embedding(&query).await?;
+            let vector_store = self.vector_store.lock().await;
+            comparable_chunks(vector_store.search_document_chunks(
+                &query_embedding,
+                doc_id,
+                MAX_DOCUMENT_PASSAGES,
+            ))?
+            .into_iter()
+            .map(|chunk| (doc_id, chunk.chunk_start, chunk.chunk_end, chunk.similarity))
+            .collect()
+        } else {
+            let mut chunks: Vec<_> = self
+                .keyword_passages(&doc.content, &query)
+                .into_iter()
+                .map(|(start, end, score)| (doc_id, start, end, score))
+                .collect();
+            normalize_passage_scores(&mut chunks);
+            chunks
+        };
+
+        let best = chunks.iter().map(|c| c.3).fold(0.0, f32::max);
+        chunks.retain(|c| c.3 > 0.0 && c.3 >= best * DOCUMENT_PASSAGE_RATIO);
+        chunks.sort_by_key(|c| c.1);
+        let mut hits: Vec<PassageHit> = chunks
+            .into_iter()
+            .filter_map(|(_, start, end, similarity)| {
+                PassageHit::from_document(&doc, start, end, similarity)
+            })
+            .collect();
+        self.attach_provenance(&mut hits).await;
+        Ok(hits)
+    }
+
+    /// Chunks of `content` containing query words, as byte offsets with the
+    /// number of query word occurrences.
+    fn keyword_passages(&self, content: &str, query: &str) -> Vec<(usize, usize, f32)> {
+        let words: Vec<String> = query
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| w.chars().count() >= 2)
+            .map(str::to_lowercase)
+            .collect();
+        let chunks = self
+            .document_processor
+            .chunk_text(content)
+            .unwrap_or_default();
+        chunks
+            .into_iter()
+            .filter_map(|chunk| {
+                let text = chunk.content.to_lowercase();
+                let occurrences: usize =
+                    words.iter().map(|w| text.matches(w.as_str()).count()).sum();
+                (occurrences > 0).then_some((chunk.start_pos, chunk.end_pos, occurrences as f32))
+            })
+            .collect()
+    }
+
+    /// The chunk of `content` with the most query word occurrences (the
+    /// first on ties), as byte offsets.
+    fn best_keyword_passage(&self, content: &str, query: &str) -> Option<(usize, usize)> {
+        self.keyword_passages(content, query)
+            .into_iter()
+            .min_by(|a, b| b.2.total_cmp(&a.2))
+            .map(|(start, end, _)| (start, end))
+    }
+
+    /// Fallback for a search with no results above the cutoff: keyword
+    /// matches on any single word (prefix match), spelling suggestions drawn
+    /// from the index vocabulary, and a web search link.
+    pub async fn search_fallback(&self, query: &str, options: SearchOptions) -> SearchFallback {
+        let parsed = query_syntax::parse(query);
+        let free_text = parsed.free_text();
+        let query = free_text.as_str();
+        let split_words = |text: &str| -> Vec<String> {
+            text.split(|c: char| !c.is_alphanumeric())
+                .filter(|w| !w.is_empty())
+                .map(str::to_lowercase)
+                .collect()
+        };
+
+        let any_word = split_words(query)
+            .iter()
+            .filter(|w| w.chars().count() >= 3)
+            .map(|w| format!("\"{}\"*", w))
+            .collect::<Vec<_>>()
+            .join(" OR ");
+        let weak_matches = if any_word.is_empty() {
+            Vec::new()
+        } else {
+            self.db
+                .search_documents_scored_in(
+                    &any_word,
+                    WEAK_MATCH_LIMIT,
+                    options.include_dead,
+                    options.collection_id,
+                )
+                .await
+                .unwrap_or_else(|e| {
+                    eprintln!("Fallback keyword search failed: {}", e);
+                    Vec::new()
+                })
+                .into_iter()
+                .map(|(doc, _)| DocumentSource {
+                    doc_id: doc.id,
+                    content_snippet: self.extract_snippet(&doc.content, query),
+                    title: doc.title,
+                    similarity: 0.0,
+                    profile: doc.profile,
+                    needs_auth: doc.needs_auth.unwrap_or(false),
+                    is_dead: doc.is_dead.unwrap_or(false),
+                    chunk: None,
+                })
+                .collect()
+        };
+
+        // Suggestions respell the free text and keep the operators as typed
+        let words = split_words(&parsed.text);
+        let mut corrected = Vec::with_capacity(words.len());
+        for word in &words {
+            let options = if spelling::is_correctable(word) {
+                match self.db.get_spelling_candidates(word).await {
+                    Ok(candidates) => spelling::corrections(word, &candidates),
+                    Err(e) => {
+                        eprintln!("Spelling lookup failed for '{}': {}", word, e);
+                        Vec::new()
+                    }
+                }
+            } else {
+                Vec::new()
+            };
+            corrected.push(options);
+        }
+
+        let web_search_url = url::Url::parse_with_params(WEB_SEARCH_URL, &[("q", query)])
+            .map(String::from)
+            .unwrap_or_else(|_| WEB_SEARCH_URL.to_string());
+
+        SearchFallback {
+            weak_matches,
+            suggestions: spelling::suggest_queries(&words, &corrected)
+                .iter()
+                .map(|suggestion| parsed.with_text(suggestion))
+                .collect(),
+            web_search_url,
+        }
+    }
+
+    /// Vector search over the query and its paraphrases, keeping each document's best hit.
+    async fn get_search_hits_expanded(
+        &self,
+        query: &str,
+        include_dead: bool,
+        scope: Option<&HashSet<i64>>,
+    ) -> Result<Vec<DocumentSource>> {
+        let mut best: HashMap<i64, DocumentSource> = HashMap::new();
+        let variants =
+            std::iter::once(query.to_string()).chain(query_expansion::paraphrases(query));
+        for variant in variants {
+            for source in self.vector_hits(&variant, 0.0, include_dead, scope).await? {
+                match best.get(&source.doc_id) {
+                    Some(existing) if existing.similarity >= source.similarity => {}
+                    _ => {
+                        best.insert(source.doc_id, source);
+                    }
+                }
+            }
+        }
+        Ok(best.into_values().collect())
     }
 
     pub async fn document_exists(&self, url: &str) -> Result<bool> {
@@ -537,6 +2301,64 @@ impl RagPipeline {
         &self,
         query: &str,
         cutoff: f32,
+    ) -> Result<Vec<DocumentSource>> {
+        self.get_search_hits_filtered(query, cutoff, false).await
+    }
+
+    /// Vector search over chunk embeddings, optionally including dead documents.
+    ///
+    /// In keyword-only mode this returns FTS matches instead, scored
+    /// relative to the best one.
+    pub async fn get_search_hits_filtered(
+        &self,
+        query: &str,
+        cutoff: f32,
+        include_dead: bool,
+    ) -> Result<Vec<DocumentSource>> {
+        if self.is_semantic_search_available() {
+            self.vector_hits(query, cutoff, include_dead, None).await
+        } else {
+            self.keyword_hits(query, include_dead).await
+        }
+    }
+
+    /// FTS search shaped like vector hits, for keyword-only mode.
+    async fn keyword_hits(&self, query: &str, include_dead: bool) -> Result<Vec<DocumentSource>> {
+        let escaped = escape_fts_query(query);
+        if escaped.is_empty() {
+            return Ok(vec![]);
+        }
+        let hits = self
+            .db
+            .search_documents_scored(&escaped, 10, include_dead)
+            .await?;
+        let max_score = hits.iter().map(|(_, s)| *s).fold(0.0, f64::max);
+        Ok(hits
+            .into_iter()
+            .map(|(doc, score)| DocumentSource {
+                doc_id: doc.id,
+                content_snippet: self.extract_snippet(&doc.content, query),
+                title: doc.title,
+                similarity: if max_score > 0.0 {
+                    (score / max_score) as f32
+                } else {
+                    0.0
+                },
+                profile: doc.profile,
+                needs_auth: doc.needs_auth.unwrap_or(false),
+                is_dead: doc.is_dead.unwrap_or(false),
+                chunk: None,
+            })
+            .collect())
+    }
+
+    /// Vector search restricted to the documents in `scope`, when given.
+    async fn vector_hits(
+        &self,
+        query: &str,
+        cutoff: f32,
+        include_dead: bool,
+        scope: Option<&HashSet<i64>>,
     ) -> Result<Vec<DocumentSource>> {
         // Use cached embedding for the query
         let query_embedding = self.get_cached_query_embedding(query).await?;
@@ -544,7 +2366,12 @@ impl RagPipeline {
         // Search chunk embeddings instead of document embeddings
         let chunk_results = {
             let vector_store = self.vector_store.lock().await;
-            vector_store.search_chunks_with_cutoff(&query_embedding, 20, cutoff)?
+            comparable_chunks(vector_store.search_chunks_scoped(
+                &query_embedding,
+                20,
+                cutoff,
+                scope,
+            ))?
         };
 
         let mut sources = Vec::new();
@@ -558,16 +2385,18 @@ impl RagPipeline {
             }
             seen_docs.insert(chunk_result.doc_id);
 
-            if let Some(doc) = self.db.get_document(chunk_result.doc_id).await? {
+            if let Some(doc) = self.db.get_live_document(chunk_result.doc_id).await? {
+                let is_dead = doc.is_dead.unwrap_or(false);
+                if is_dead && !include_dead {
+                    continue;
+                }
+
                 // Extract the actual chunk content from the document using BYTE positions (not char indices!)
-                let chunk_content = if chunk_result.chunk_end <= doc.content.len()
-                    && doc.content.is_char_boundary(chunk_result.chunk_start)
-                    && doc.content.is_char_boundary(chunk_result.chunk_end)
-                {
-                    doc.content[chunk_result.chunk_start..chunk_result.chunk_end].to_string()
-                } else {
+                let chunk = (chunk_result.chunk_start, chunk_result.chunk_end);
+                let (chunk_content, chunk) = match doc.content.get(chunk.0..chunk.1) {
+                    Some(text) => (text.to_string(), Some(chunk)),
                     // Fallback to snippet extraction if chunk boundaries are off
-                    self.extract_snippet(&doc.content, query)
+                    None => (self.extract_snippet(&doc.content, query), None),
                 };
 
                 sources.push(DocumentSource {
@@ -577,6 +2406,8 @@ impl RagPipeline {
                     similarity: chunk_result.similarity,
                     profile: doc.profile,
                     needs_auth: doc.needs_auth.unwrap_or(false),
+                    is_dead,
+                    chunk,
                 });
 
                 // Limit to 10 documents
@@ -589,66 +2420,324 @@ impl RagPipeline {
         Ok(sources)
     }
 
+    /// Answer a question using only the passages of one document.
+    ///
+    /// Ranks the document's chunks against the question and builds an extractive
+    /// answer from the most relevant sentence of each top passage, citing the
+    /// passage it came from. There is no completion model, so the answer is
+    /// quoted text rather than generated prose.
+    pub async fn ask_document(&self, doc_id: i64, question: &str) -> Result<DocumentAnswer> {
+        const MAX_CITATIONS: usize = 3;
+
+        let doc = self
+            .db
+            .get_document(doc_id)
+            .await?
+            .ok_or_else(|| format!("Document {} not found", doc_id))?;
+
+        let query_embedding = self.get_cached_query_embedding(question).await?;
+        let chunk_results = {
+            let vector_store = self.vector_store.lock().await;
+            comparable_chunks(vector_store.search_document_chunks(
+                &query_embedding,
+                doc_id,
+                MAX_CITATIONS,
+            ))?
+        };
+
+        let mut citations = Vec::new();
+        let mut sentences = Vec::new();
+        for chunk in chunk_results {
+            if chunk.chunk_end > doc.content.len()
+                || !doc.content.is_char_boundary(chunk.chunk_start)
+                || !doc.content.is_char_boundary(chunk.chunk_end)
+            {
+                continue;
+            }
+            let text = doc.content[chunk.chunk_start..chunk.chunk_end]
+                .trim()
+                .to_string();
+            if text.is_empty() {
+                continue;
+            }
+            sentences.push(format!(
+                "{} [{}]",
+                best_sentence(&text, question),
+                citations.len() + 1
+            ));
+            citations.push(Citation {
+                chunk_start: chunk.chunk_start,
+                chunk_end: chunk.chunk_end,
+                text,
+                similarity: chunk.similarity,
+            });
+        }
+
+        let answer = if sentences.is_empty() {
+            "No indexed passages found for this document.".to_string()
+        } else {
+            sentences.join(" ")
+        };
+
+        Ok(DocumentAnswer { answer, citations })
+    }
+
     // Completion methods removed - this is an embedding-only service
 
     /// Update an existing document by URL: replace content, clear auth/dead flags, re-embed.
+    /// The language is detected again; content in an excluded language is not embedded.
+    /// Web pages are checked for junk again unless the user kept the page in full,
+    /// and are marked as needing a refetch while their content is only a placeholder.
+    /// Chunks break at the headings stored for the page (see `store_document_structure`).
     pub async fn update_document(&self, doc_id: i64, title: &str, content: &str) -> Result<i64> {
-        // Update document content in DB (clears is_dead and needs_auth)
+        let kept = self
+            .db
+            .get_document_quality_issue(doc_id)
+            .await?
+            .is_some_and(|issue| issue.is_empty());
+        let url = self.db.get_document(doc_id).await?.and_then(|doc| doc.url);
+        let (issue, content) = if kept {
+            (None, Cow::Borrowed(content))
+        } else {
+            screen_page(title, content, url.as_deref(), false)
+        };
+        let content = content.as_ref();
+        let placeholder = page_quality::is_placeholder(title, content, url.as_deref());
+        let (language, embed) = self.detect_languages(&[(title, content)]).await[0];
+        let embed = embed && issue.is_none() && !placeholder;
+        let chunks = if embed {
+            self.chunk_stored_document(doc_id, content).await?
+        } else {
+            Vec::new()
+        };
+        if chunks.is_empty() {
+            println!("Updated document produced no chunks");
+        } else {
+            println!(
+                "Re-indexing document id={}: '{}' -> {} chunks",
+                doc_id,
+                title.chars().take(60).collect::<String>(),
+                chunks.len()
+            );
+        }
+
+        let (embedded, rows) = self.embed_chunks(&chunks).await?;
+        let embedding_ids = self
+            .db
+            .update_document_with_chunks(doc_id, title, content, &rows)
+            .await?;
+        self.replace_chunk_vectors(doc_id, embedded, embedding_ids)
+            .await?;
+        self.record_language(doc_id, language).await;
+        if issue.is_some() {
+            self.record_quality_issue(doc_id, issue).await;
+        } else if !kept {
+            self.db.set_document_quality_issue(doc_id, None).await?;
+        }
+        if placeholder {
+            self.mark_needs_refetch(doc_id).await;
+        } else {
+            self.db.set_document_needs_refetch(doc_id, false).await?;
+        }
+
+        Ok(doc_id)
+    }
+
+    /// Index a page flagged as junk in full after all, e.g. with `content`
+    /// fetched again, and stop checking it on later updates.
+    pub async fn keep_page(&self, doc_id: i64, title: &str, content: &str) -> Result<i64> {
+        self.db.set_document_quality_issue(doc_id, Some("")).await?;
+        self.update_document(doc_id, title, content).await
+    }
+
+    /// Put back an earlier version of a document (see `document_versions`),
+    /// keeping the content it replaces as a version in turn. Its outline is
+    /// dropped, since it was found in the newer content. Returns the
+    /// document ID.
+    pub async fn restore_version(&self, version_id: i64) -> Result<i64> {
+        let version = self
+            .db
+            .get_document_version(version_id)
+            .await?
+            .ok_or_else(|| format!("Version {} not found", version_id))?;
         self.db
-            .update_document_content(doc_id, title, content)
+            .set_document_structure(version.document_id, &PageStructure::default())
+            .await?;
+        self.update_document(version.document_id, &version.title, &version.content)
             .await?;
+        Ok(version.document_id)
+    }
 
-        // Remove old embeddings from DB and vector store
-        self.db.delete_embeddings_for_document(doc_id).await?;
-        {
-            let mut vector_store = self.vector_store.lock().await;
-            vector_store.remove_vectors_for_document(doc_id);
+    /// Re-chunk and re-embed a stored document without changing its content or flags.
+    /// Documents in a language excluded from embedding, pages flagged as junk
+    /// and documents needing a refetch are left with no chunks. Returns the
+    /// number of chunks indexed.
+    pub async fn reindex_document(&self, doc_id: i64) -> Result<usize> {
+        let doc = self
+            .db
+            .get_document(doc_id)
+            .await?
+            .ok_or_else(|| format!("Document {} not found", doc_id))?;
+
+        let (_, embed) = self
+            .detect_languages(&[(doc.title.as_str(), doc.content.as_str())])
+            .await[0];
+        let flagged = self
+            .db
+            .get_document_quality_issue(doc_id)
+            .await?
+            .is_some_and(|issue| !issue.is_empty())
+            || self.db.document_needs_refetch(doc_id).await?;
+        let chunks = if embed && !flagged {
+            self.chunk_stored_document(doc_id, &doc.content).await?
+        } else {
+            Vec::new()
+        };
+        let (embedded, rows) = self.embed_chunks(&chunks).await?;
+        let chunk_count = embedded.len();
+        let embedding_ids = self.db.replace_document_chunks(doc_id, &rows).await?;
+        self.replace_chunk_vectors(doc_id, embedded, embedding_ids)
+            .await?;
+
+        Ok(chunk_count)
+    }
+
+    /// Re-embed documents left with missing chunk embeddings, e.g. by a crash
+    /// during ingestion before writes were transactional, or stored while in
+    /// keyword-only mode. Returns the number of documents repaired.
+    pub async fn repair_incomplete_documents(&self) -> Result<usize> {
+        if !self.is_semantic_search_available() {
+            return Ok(0); // Retried when the backend comes back
         }
 
-        // Re-chunk and re-embed
-        let chunks = self.document_processor.chunk_text(content)?;
-        if chunks.is_empty() {
-            println!("Updated document produced no chunks");
-            return Ok(doc_id);
+        let doc_ids = self.db.get_incomplete_documents().await?;
+        if doc_ids.is_empty() {
+            return Ok(0);
         }
 
         println!(
-            "Re-indexing document id={}: '{}' -> {} chunks",
-            doc_id,
-            title.chars().take(60).collect::<String>(),
-            chunks.len()
+            "Found {} documents with missing chunk embeddings, re-embedding",
+            doc_ids.len()
         );
+        let mut repaired = 0;
+        for doc_id in doc_ids {
+            match self.reindex_document(doc_id).await {
+                Ok(_) => repaired += 1,
+                Err(e) => eprintln!("Failed to repair document {}: {}", doc_id, e),
+            }
+        }
+        Ok(repaired)
+    }
 
-        for chunk in chunks.iter() {
-            let chunk_embedding = self
-                .embedding_client
-                .generate_embedding(&chunk.content)
+    /// Get an embedding for each chunk, returning (start, end, vector) for the
+    /// vector store and the matching rows for the database.
+    ///
+    /// Chunks whose text was embedded before by the same model (e.g. the
+    /// unchanged parts of a refreshed page) reuse the stored vector; the rest
+    /// are embedded together, batched or concurrently depending on backend.
+    async fn embed_chunks(
+        &self,
+        chunks: &[DocumentChunk],
+    ) -> Result<(Vec<(usize, usize, Vec<f32>)>, Vec<ChunkRow>)> {
+        self.embed_chunks_with(self.embedding_client.as_ref(), chunks)
+            .await
+    }
+
+    /// `embed_chunks` with a given backend, e.g. a model being migrated to.
+    async fn embed_chunks_with(
+        &self,
+        client: &dyn EmbeddingBackend,
+        chunks: &[DocumentChunk],
+    ) -> Result<(Vec<(usize, usize, Vec<f32>)>, Vec<ChunkRow>)> {
+        let backend = client.cache_key();
+        let hashes: Vec<String> = chunks
+            .iter()
+            .map(|chunk| chunk_hash(&backend, &chunk.content))
+            .collect();
+        let mut known = self
+            .db
+            .get_embeddings_by_hash(&hashes)
+            .await
+            .unwrap_or_else(|e| {
+                eprintln!("Failed to look up stored chunk embeddings: {}", e);
+                HashMap::new()
+            });
+        let reused = hashes.iter().filter(|h| known.contains_key(*h)).count();
+
+        // Embed each distinct new chunk once, all in one batch
+        let mut missing: Vec<(String, String)> = Vec::new();
+        for (chunk, hash) in chunks.iter().zip(&hashes) {
+            if !known.contains_key(hash) && !missing.iter().any(|(h, _)| h == hash) {
+                missing.push((hash.clone(), chunk.content.clone()));
+            }
+        }
+        if !missing.is_empty() {
+            let texts: Vec<String> = missing.iter().map(|(_, text)| text.clone()).collect();
+            let vectors = client
+                .generate_embeddings(&texts)
                 .await
                 .map_err(|e| format!("Failed to generate embedding for chunk: {}", e))?;
-            let embedding_bytes = bincode::serialize(&chunk_embedding)?;
-
-            let embedding_id = self
-                .db
-                .insert_chunk_embedding(
-                    doc_id,
-                    chunk.start_pos,
-                    chunk.end_pos,
-                    &embedding_bytes,
-                    OperationPriority::BackgroundIngest,
-                )
-                .await?;
+            known.extend(missing.into_iter().map(|(hash, _)| hash).zip(vectors));
+        }
 
-            let mut vector_store = self.vector_store.lock().await;
-            vector_store.add_chunk_vector(
-                embedding_id,
-                doc_id,
+        let mut embedded = Vec::with_capacity(chunks.len());
+        let mut rows = Vec::with_capacity(chunks.len());
+        for (chunk, hash) in chunks.iter().zip(hashes) {
+            let chunk_embedding = known
+                .get(&hash)
+                .cloned()
+                .ok_or("Embedding backend returned too few embeddings")?;
+            rows.push((
                 chunk.start_pos,
                 chunk.end_pos,
-                chunk_embedding,
-            )?;
+                bincode::serialize(&chunk_embedding)?,
+                ChunkProvenance {
+                    content_hash: Some(hash),
+                    ..chunk.provenance.clone()
+                },
+            ));
+            embedded.push((chunk.start_pos, chunk.end_pos, chunk_embedding));
         }
 
-        Ok(doc_id)
+        if reused > 0 {
+            println!(
+                "Reused stored embeddings for {} of {} chunks",
+                reused,
+                chunks.len()
+            );
+        }
+        Ok((embedded, rows))
+    }
+
+    /// Add freshly stored chunk vectors for a document to the in-memory store.
+    async fn add_chunk_vectors(
+        &self,
+        doc_id: i64,
+        embedded: Vec<(usize, usize, Vec<f32>)>,
+        embedding_ids: Vec<i64>,
+    ) -> Result<()> {
+        let mut vector_store = self.vector_store.lock().await;
+        if let Some((_, _, vector)) = embedded.first() {
+            vector_store.set_active_dimensions(vector.len());
+        }
+        for ((chunk_start, chunk_end, vector), embedding_id) in
+            embedded.into_iter().zip(embedding_ids)
+        {
+            vector_store.add_chunk_vector(embedding_id, doc_id, chunk_start, chunk_end, vector)?;
+        }
+        Ok(())
+    }
+
+    /// Swap a document's in-memory chunk vectors for newly stored ones.
+    async fn replace_chunk_vectors(
+        &self,
+        doc_id: i64,
+        embedded: Vec<(usize, usize, Vec<f32>)>,
+        embedding_ids: Vec<i64>,
+    ) -> Result<()> {
+        self.vector_store.lock().await.remove_document(doc_id);
+        self.add_chunk_vectors(doc_id, embedded, embedding_ids)
+            .await
     }
 
     pub fn vector_store_stats(&self) -> (usize, bool) {
@@ -661,14 +2750,168 @@ impl RagPipeline {
         }
     }
 
+    /// Index-wide counts, with the chunks still embedded by a previous model.
+    ///
+    /// Which chunks those are depends on the current model's dimension; when
+    /// no query or document has been embedded yet, a probe text is.
+    pub async fn get_index_stats(&self) -> Result<IndexStats> {
+        let mut stats = self.db.get_index_stats().await?;
+        if self.vector_store.lock().await.active_dimensions().is_none()
+            && self.is_semantic_search_available()
+        {
+            match self
+                .embedding_client
+                .generate_embedding("dimension probe")
+                .await
+            {
+                Ok(probe) => self
+                    .vector_store
+                    .lock()
+                    .await
+                    .set_active_dimensions(probe.len()),
+                Err(e) => eprintln!("Failed to probe embedding dimensions: {}", e),
+            }
+        }
+        if let Some(pending) = self.vector_store.lock().await.chunks_needing_migration() {
+            stats.chunks_needing_migration = pending as i64;
+        }
+        Ok(stats)
+    }
+
     /// Remove all in-memory vector entries for a document.
     ///
-    /// Called after `db.delete_document` (or `db.delete_documents_by_source`)
-    /// to keep the VectorStore consistent with the database.
+    /// Called after `db.delete_document`, `db.delete_documents_by_source` or
+    /// `db.trash_documents` to keep the VectorStore consistent with the database.
     pub async fn remove_document_vectors(&self, document_id: i64) {
         let mut vs = self.vector_store.lock().await;
-        vs.remove_vectors_for_document(document_id);
+        vs.remove_document(document_id);
+    }
+
+    /// Remove single chunk vectors from the VectorStore, e.g. after
+    /// `db.delete_chunks` dropped their rows.
+    pub async fn remove_chunk_vectors(&self, embedding_ids: &[i64]) {
+        let mut vs = self.vector_store.lock().await;
+        for embedding_id in embedding_ids {
+            vs.remove_chunk(*embedding_id);
+        }
+    }
+
+    /// Reload a document's stored chunk vectors into the VectorStore.
+    ///
+    /// Called after `db.restore_documents` brings a document back from the trash.
+    pub async fn restore_document_vectors(&self, document_id: i64) -> Result<()> {
+        let chunks = self
+            .db
+            .get_chunk_embeddings_for_document(document_id)
+            .await?;
+        let mut vs = self.vector_store.lock().await;
+        vs.remove_document(document_id);
+        for (embedding_id, chunk_start, chunk_end, vector) in chunks {
+            vs.add_chunk_vector(embedding_id, document_id, chunk_start, chunk_end, vector)?;
+        }
+        Ok(())
     }
 
     // Streaming completion methods removed - this is an embedding-only service
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunk_hash_depends_on_text_and_model() {
+        let hash = chunk_hash("local", "Rust is a systems language.");
+        assert_eq!(hash, chunk_hash("local", "Rust is a systems language."));
+        assert_ne!(hash, chunk_hash("local", "Rust is a systems language!"));
+        assert_ne!(
+            hash,
+            chunk_hash("ollama:nomic-embed-text", "Rust is a systems language.")
+        );
+        assert_eq!(hash.len(), 64);
+    }
+
+    #[test]
+    fn tag_suggestions_reuse_existing_tags() {
+        let counts: Vec<(String, i64)> = [("rust", 9), ("async", 4), ("Rustacean", 2), ("web", 1)]
+            .into_iter()
+            .map(|(tag, count)| (tag.to_string(), count))
+            .collect();
+        let tags = |list: &[&str]| list.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+        let neighbors = vec![
+            (0.9, tags(&["web", "async"])),
+            (0.8, tags(&["Web"])),
+            (0.7, tags(&["async", "rust"])),
+        ];
+
+        let typed = rank_tag_suggestions(&counts, &neighbors, "RU", &[], 5);
+        assert_eq!(
+            typed.matching,
+            vec![("rust".to_string(), 9), ("Rustacean".to_string(), 2)]
+        );
+        // Neighbour tags are filtered by the prefix and not repeated
+        assert!(typed.related.is_empty());
+
+        let empty = rank_tag_suggestions(&counts, &neighbors, "", &tags(&["Async"]), 5);
+        assert!(empty.matching.is_empty());
+        // web: 0.9 + 0.8, rust: 0.7; async is already on the document
+        assert_eq!(empty.related, vec!["web", "rust"]);
+
+        let limited = rank_tag_suggestions(&counts, &neighbors, "", &[], 1);
+        assert_eq!(limited.related, vec!["web"]);
+    }
+
+    #[test]
+    fn related_pages_match_any_distinct_long_word() {
+        assert_eq!(
+            any_word_fts_query("Rust: the Rust \"book\" on it"),
+            "\"rust\" OR \"the\" OR \"book\""
+        );
+        assert_eq!(any_word_fts_query("a of"), "");
+        let many: Vec<String> = (0..30).map(|i| format!("word{}", i)).collect();
+        assert_eq!(
+            any_word_fts_query(&many.join(" ")).matches(" OR ").count(),
+            RELATED_MAX_WORDS - 1
+        );
+    }
+
+    #[test]
+    fn best_sentence_prefers_most_overlap() {
+        let passage = "Tokio is a runtime. Holding a mutex across an await point can deadlock. \
+                       Use spawn_blocking for CPU work.";
+        assert_eq!(
+            best_sentence(passage, "why does my mutex deadlock across await?"),
+            "Holding a mutex across an await point can deadlock."
+        );
+        assert_eq!(best_sentence(passage, "xyz"), "Tokio is a runtime.");
+    }
+
+    #[test]
+    fn passages_are_cut_on_character_boundaries() {
+        let doc = Document {
+            id: 7,
+            title: "Café notes".to_string(),
+            content: "Café au lait.  Second passage here. ".to_string(),
+            url: None,
+            source: "test".to_string(),
+            created_at: String::new(),
+            embedding: None,
+            is_dead: Some(true),
+            needs_auth: None,
+            profile: None,
+        };
+
+        let hit = PassageHit::from_document(&doc, 14, 36, 0.5).unwrap();
+        assert_eq!(hit.text, "Second passage here.");
+        assert_eq!((hit.chunk_start, hit.chunk_end), (14, 36));
+        assert!(hit.is_dead);
+        // Inside the two-byte "é", past the end, or only whitespace
+        assert!(PassageHit::from_document(&doc, 4, 10, 0.5).is_none());
+        assert!(PassageHit::from_document(&doc, 14, 99, 0.5).is_none());
+        assert!(PassageHit::from_document(&doc, 14, 16, 0.5).is_none());
+
+        let mut chunks = vec![(7, 0, 5, 2.0), (7, 5, 9, 4.0)];
+        normalize_passage_scores(&mut chunks);
+        assert_eq!(chunks, vec![(7, 0, 5, 0.5), (7, 5, 9, 1.0)]);
+    }
+}
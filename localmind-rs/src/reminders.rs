@@ -0,0 +1,83 @@
+//! Document reminders ("remind me")
+//!
+//! A document can be snoozed until a chosen date from its context menu. When
+//! the date comes the reminder scheduler in `services` fires it: the document
+//! is listed at the top of the home view until dismissed and, if asked for,
+//! a desktop notification is raised. Reminders are stored in the `reminders`
+//! table, one per document; setting another replaces it.
+
+/// How often the scheduler checks for due reminders.
+pub const CHECK_INTERVAL_SECS: u64 = 60;
+
+/// When a reminder is due: the start of a local date.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RemindOn {
+    /// This many days from today
+    InDays(u32),
+    /// A date as `YYYY-MM-DD` (see `parse_date`)
+    Date(String),
+}
+
+impl RemindOn {
+    /// The date, if fixed, and the days to add to it (or to today) as a
+    /// SQLite date modifier.
+    pub(crate) fn sql_args(&self) -> (Option<String>, String) {
+        match self {
+            RemindOn::InDays(days) => (None, format!("+{} days", days)),
+            RemindOn::Date(date) => (Some(date.clone()), "+0 days".to_string()),
+        }
+    }
+}
+
+/// A reminder with the document it brings back
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reminder {
+    pub document_id: i64,
+    pub title: String,
+    pub url: Option<String>,
+    /// Local date it is due, as `YYYY-MM-DD`
+    pub remind_on: String,
+    /// Whether to raise a desktop notification when it fires
+    pub notify: bool,
+}
+
+/// A date typed as `YYYY-MM-DD` (single-digit months and days allowed),
+/// normalized; `None` when it is not a real date.
+pub fn parse_date(input: &str) -> Option<String> {
+    let mut parts = input.trim().split('-');
+    let year: u32 = parts.next()?.parse().ok()?;
+    let month: u32 = parts.next()?.parse().ok()?;
+    let day: u32 = parts.next()?.parse().ok()?;
+    if parts.next().is_some() || !(1000..=9999).contains(&year) {
+        return None;
+    }
+    let leap = year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400));
+    let days_in_month = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return None,
+    };
+    (1..=days_in_month)
+        .contains(&day)
+        .then(|| format!("{:04}-{:02}-{:02}", year, month, day))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_typed_dates() {
+        assert_eq!(parse_date(" 2026-11-03 ").as_deref(), Some("2026-11-03"));
+        assert_eq!(parse_date("2026-1-5").as_deref(), Some("2026-01-05"));
+        assert_eq!(parse_date("2028-02-29").as_deref(), Some("2028-02-29"));
+        assert_eq!(parse_date("2026-02-29"), None);
+        assert_eq!(parse_date("2026-13-01"), None);
+        assert_eq!(parse_date("2026-04-31"), None);
+        assert_eq!(parse_date("26-04-01"), None);
+        assert_eq!(parse_date("2026-04-01-01"), None);
+        assert_eq!(parse_date("tomorrow"), None);
+    }
+}
@@ -0,0 +1,294 @@
+//! Read-only remote access over the LAN
+//!
+//! An optional second HTTP server, bound to every network interface, serves a
+//! small built-in search page and a search endpoint so the index can be
+//! queried from a phone on the same network. Searches need the access token
+//! shown in Settings, and nothing can be added, changed or deleted through
+//! it; the full API stays on localhost. It can be served over HTTPS (see
+//! `tls`) so the token does not cross the network in cleartext.
+
+use crate::services::RagState;
+use axum::{
+    extract::{Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{Html, IntoResponse, Json, Response},
+    routing::get,
+    Router,
+};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+/// Port the LAN server listens on unless configured otherwise.
+pub const DEFAULT_PORT: u16 = 3100;
+
+/// Most results a remote search returns.
+const MAX_RESULTS: usize = 20;
+
+/// Remote access settings edited in Settings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RemoteAccessSettings {
+    pub enabled: bool,
+    pub port: u16,
+    /// Token every search must carry as `Authorization: Bearer <token>`
+    pub token: String,
+    /// Whether the page and searches are served over HTTPS
+    #[serde(default)]
+    pub tls: crate::tls::TlsMode,
+}
+
+impl Default for RemoteAccessSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: DEFAULT_PORT,
+            token: String::new(),
+            tls: crate::tls::TlsMode::Off,
+        }
+    }
+}
+
+impl RemoteAccessSettings {
+    /// Whether the LAN server should be running.
+    pub fn is_active(&self) -> bool {
+        self.enabled && !self.token.is_empty()
+    }
+
+    /// Address to open on another device, carrying the token in the URL
+    /// fragment so the page can use it without sending it to the server.
+    pub fn page_url(&self) -> Option<String> {
+        let ip = lan_address()?;
+        let scheme = if self.tls.is_enabled() {
+            "https"
+        } else {
+            "http"
+        };
+        Some(format!(
+            "{}://{}:{}/#token={}",
+            scheme, ip, self.port, self.token
+        ))
+    }
+}
+
+/// A new random access token.
+pub fn generate_token() -> String {
+    let mut bytes = [0u8; 16];
+    rand::rngs::OsRng.fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Whether an `Authorization` header value carries `token`. Compares in
+/// constant time so the token cannot be guessed byte by byte.
+pub fn token_matches(token: &str, authorization: Option<&str>) -> bool {
+    let Some(given) = authorization.and_then(|value| value.strip_prefix("Bearer ")) else {
+        return false;
+    };
+    let (given, token) = (given.trim().as_bytes(), token.as_bytes());
+    !token.is_empty()
+        && given.len() == token.len()
+        && given
+            .iter()
+            .zip(token)
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+/// This machine's address on the local network, if it has one.
+pub fn lan_address() -> Option<std::net::IpAddr> {
+    // Connecting a UDP socket sends nothing; it only picks the interface
+    // that would route to the address
+    let socket = std::net::UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("192.168.0.1:80").ok()?;
+    let ip = socket.local_addr().ok()?.ip();
+    (!ip.is_loopback() && !ip.is_unspecified()).then_some(ip)
+}
+
+#[derive(Clone)]
+struct RemoteState {
+    rag_state: RagState,
+    token: String,
+}
+
+#[derive(Deserialize)]
+struct SearchParams {
+    q: String,
+}
+
+/// A search result as shown on the remote search page.
+#[derive(Debug, Serialize)]
+struct RemoteHit {
+    title: String,
+    url: Option<String>,
+    snippet: String,
+}
+
+fn error(status: StatusCode, message: &str) -> Response {
+    (status, Json(serde_json::json!({ "message": message }))).into_response()
+}
+
+async fn handle_page() -> Html<&'static str> {
+    Html(SEARCH_PAGE)
+}
+
+async fn handle_search(
+    State(state): State<RemoteState>,
+    headers: HeaderMap,
+    Query(params): Query<SearchParams>,
+) -> Response {
+    let authorization = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok());
+    if !token_matches(&state.token, authorization) {
+        return error(StatusCode::UNAUTHORIZED, "Missing or wrong access token.");
+    }
+    if params.q.trim().is_empty() {
+        return error(StatusCode::BAD_REQUEST, "Query parameter 'q' is required.");
+    }
+
+    let rag_lock = state.rag_state.read().await;
+    let Some(rag) = rag_lock.as_ref() else {
+        return error(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "System initializing. Please wait.",
+        );
+    };
+    let hits = match rag
+        .get_search_hits_fused(
+            params.q.trim(),
+            crate::rag::SearchOptions {
+                correct_spelling: true,
+                ..Default::default()
+            },
+        )
+        .await
+    {
+        Ok(hits) => hits,
+        Err(e) => {
+            eprintln!("Remote search failed: {}", e);
+            return error(StatusCode::INTERNAL_SERVER_ERROR, "Search failed.");
+        }
+    };
+
+    let mut results = Vec::new();
+    for hit in hits.into_iter().take(MAX_RESULTS) {
+        let url = match rag.db.get_live_document(hit.doc_id).await {
+            Ok(Some(doc)) => doc.url,
+            _ => None,
+        };
+        results.push(RemoteHit {
+            title: hit.title,
+            url,
+            snippet: hit.content_snippet,
+        });
+    }
+    Json(results).into_response()
+}
+
+/// Routes of the LAN server: the search page and the search endpoint.
+pub fn router(rag_state: RagState, token: String) -> Router {
+    Router::new()
+        .route("/", get(handle_page))
+        .route("/search", get(handle_search))
+        .with_state(RemoteState { rag_state, token })
+}
+
+/// The built-in search page. The token comes from the URL fragment the
+/// first time and is remembered by the browser.
+const SEARCH_PAGE: &str = r##"<!DOCTYPE html>
+<html>
+<head>
+  <meta charset="utf-8">
+  <meta name="viewport" content="width=device-width, initial-scale=1">
+  <title>LocalMind</title>
+  <style>
+    body { font-family: system-ui, sans-serif; margin: 0 auto; max-width: 720px; padding: 16px; }
+    form { display: flex; gap: 8px; }
+    input { flex: 1; font-size: 1.1em; padding: 8px; }
+    button { font-size: 1.1em; padding: 8px 16px; }
+    .result { margin: 16px 0; }
+    .result a { font-size: 1.1em; }
+    .snippet { color: #555; margin-top: 4px; }
+    .status { color: #777; margin-top: 16px; }
+  </style>
+</head>
+<body>
+  <h1>LocalMind</h1>
+  <form id="search">
+    <input id="q" type="search" placeholder="Search your knowledge" autofocus>
+    <button type="submit">Search</button>
+  </form>
+  <div id="status" class="status"></div>
+  <div id="results"></div>
+  <script>
+    const match = location.hash.match(/token=([0-9a-f]+)/);
+    if (match) {
+      localStorage.setItem("localmind-token", match[1]);
+      history.replaceState(null, "", location.pathname);
+    }
+    const token = localStorage.getItem("localmind-token");
+    const status = document.getElementById("status");
+    const results = document.getElementById("results");
+    if (!token) {
+      status.textContent = "Open the link shown in LocalMind's settings to connect.";
+    }
+
+    document.getElementById("search").addEventListener("submit", async (event) => {
+      event.preventDefault();
+      const q = document.getElementById("q").value.trim();
+      if (!q) return;
+      status.textContent = "Searching...";
+      results.replaceChildren();
+      const response = await fetch("/search?q=" + encodeURIComponent(q), {
+        headers: { "Authorization": "Bearer " + token },
+      });
+      const body = await response.json();
+      if (!response.ok) {
+        status.textContent = body.message;
+        return;
+      }
+      status.textContent = body.length ? "" : "No results.";
+      for (const hit of body) {
+        const item = document.createElement("div");
+        item.className = "result";
+        const link = hit.url && /^https?:/i.test(hit.url);
+        const title = document.createElement(link ? "a" : "strong");
+        title.textContent = hit.title;
+        if (link) title.href = hit.url;
+        const snippet = document.createElement("div");
+        snippet.className = "snippet";
+        snippet.textContent = hit.snippet;
+        item.append(title, snippet);
+        results.append(item);
+      }
+    });
+  </script>
+</body>
+</html>"##;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn searches_need_the_token() {
+        let token = generate_token();
+        assert_eq!(token.len(), 32);
+        assert_ne!(token, generate_token());
+
+        let header = format!("Bearer {}", token);
+        assert!(token_matches(&token, Some(&header)));
+        assert!(!token_matches(&token, Some(&format!("Bearer {}0", token))));
+        assert!(!token_matches(&token, Some(&token)));
+        assert!(!token_matches(&token, None));
+        assert!(!token_matches("", Some("Bearer ")));
+    }
+
+    #[test]
+    fn disabled_until_enabled_with_a_token() {
+        let mut settings = RemoteAccessSettings::default();
+        assert!(!settings.is_active());
+        settings.enabled = true;
+        assert!(!settings.is_active());
+        settings.token = generate_token();
+        assert!(settings.is_active());
+    }
+}
@@ -0,0 +1,106 @@
+//! Recent HTTP API requests
+//!
+//! Middleware on the extension API records every request it serves here:
+//! endpoint, origin, duration and outcome, with the error message of failed
+//! ones. The Jobs view lists them as recent activity, to find out why a save
+//! from the extension didn't appear. The log lives in memory only and keeps
+//! the last `CAPACITY` requests.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+/// Most requests remembered; the oldest go first.
+pub const CAPACITY: usize = 200;
+
+/// One request the API served
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestRecord {
+    pub method: String,
+    pub path: String,
+    /// `Origin` header, e.g. `chrome-extension://<id>`; scripts send none
+    pub origin: Option<String>,
+    /// HTTP status of the response
+    pub status: u16,
+    pub duration: Duration,
+    /// Message of a failed request
+    pub error: Option<String>,
+    pub at: SystemTime,
+}
+
+impl RequestRecord {
+    pub fn succeeded(&self) -> bool {
+        self.status < 400
+    }
+
+    /// Whether the request saved, deleted or searched documents, as opposed
+    /// to as-you-type lookups and status polls.
+    pub fn is_save_or_search(&self) -> bool {
+        let path = self.path.strip_prefix("/api/v1").unwrap_or(&self.path);
+        path.starts_with("/documents") || path == "/search" || path == "/ws"
+    }
+}
+
+/// Shared, cloneable ring buffer of recent requests.
+#[derive(Clone, Default)]
+pub struct RequestLog {
+    records: Arc<Mutex<VecDeque<RequestRecord>>>,
+}
+
+impl RequestLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, record: RequestRecord) {
+        let mut records = self.records.lock().unwrap();
+        if records.len() == CAPACITY {
+            records.pop_front();
+        }
+        records.push_back(record);
+    }
+
+    /// Remembered requests, newest first.
+    pub fn recent(&self) -> Vec<RequestRecord> {
+        self.records.lock().unwrap().iter().rev().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(method: &str, path: &str, status: u16) -> RequestRecord {
+        RequestRecord {
+            method: method.to_string(),
+            path: path.to_string(),
+            origin: None,
+            status,
+            duration: Duration::from_millis(5),
+            error: None,
+            at: SystemTime::now(),
+        }
+    }
+
+    #[test]
+    fn keeps_the_most_recent_requests() {
+        let log = RequestLog::new();
+        for i in 0..CAPACITY + 5 {
+            log.record(request("GET", &format!("/documents/{}", i), 200));
+        }
+        let recent = log.recent();
+        assert_eq!(recent.len(), CAPACITY);
+        assert_eq!(recent[0].path, format!("/documents/{}", CAPACITY + 4));
+        assert_eq!(recent[CAPACITY - 1].path, "/documents/5");
+    }
+
+    #[test]
+    fn tells_saves_and_searches_apart() {
+        assert!(request("POST", "/api/v1/documents", 200).is_save_or_search());
+        assert!(request("POST", "/documents/batch", 200).is_save_or_search());
+        assert!(request("GET", "/api/v1/search", 200).is_save_or_search());
+        assert!(!request("GET", "/api/v1/suggest", 200).is_save_or_search());
+        assert!(!request("GET", "/api/v1/jobs/abc", 200).is_save_or_search());
+        assert!(!request("POST", "/api/v1/documents", 503).succeeded());
+    }
+}
@@ -0,0 +1,184 @@
+//! Retention rules
+//!
+//! Rules such as "bookmarks saved more than a year ago" or "links dead for
+//! 90 days" are stored in the config table and evaluated once a day by a
+//! scheduled maintenance job. Matching documents are moved to the Trash, so
+//! a run can be undone and nothing is lost until the trash is emptied.
+//! Favorites are never removed. Settings previews what the rules would
+//! remove before they run.
+
+use crate::ingest_policy::SourceKind;
+use crate::rag::RagPipeline;
+use crate::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Seconds between scheduled runs.
+pub const RUN_INTERVAL_SECS: u64 = 24 * 3600;
+
+/// What makes a document expire.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RetentionCondition {
+    /// Saved more than `days` ago
+    SavedBefore,
+    /// Its link has been dead for more than `days`
+    DeadFor,
+    /// Not opened (or, if never opened, saved) in the last `days`
+    NotOpenedFor,
+}
+
+impl RetentionCondition {
+    pub const ALL: [RetentionCondition; 3] = [
+        RetentionCondition::SavedBefore,
+        RetentionCondition::DeadFor,
+        RetentionCondition::NotOpenedFor,
+    ];
+}
+
+/// One retention rule.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RetentionRule {
+    pub condition: RetentionCondition,
+    pub days: u32,
+    /// Only documents from this kind of source; all documents when `None`
+    pub source: Option<SourceKind>,
+    pub enabled: bool,
+}
+
+impl Default for RetentionRule {
+    fn default() -> Self {
+        Self {
+            condition: RetentionCondition::DeadFor,
+            days: 90,
+            source: None,
+            enabled: true,
+        }
+    }
+}
+
+impl RetentionRule {
+    /// Unix time before which a document's date counts as expired.
+    pub fn cutoff(&self, now: u64) -> i64 {
+        now.saturating_sub(self.days as u64 * 24 * 3600) as i64
+    }
+
+    /// Whether the rule covers documents with this `source` column value.
+    pub fn applies_to(&self, source: &str) -> bool {
+        match self.source {
+            Some(kind) => SourceKind::of(source) == Some(kind),
+            None => true,
+        }
+    }
+}
+
+/// A document that the retention rules would remove.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetentionMatch {
+    pub id: i64,
+    pub title: String,
+    pub url: Option<String>,
+    pub source: String,
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Documents matched by any enabled rule, each listed once.
+pub async fn preview(rag: &RagPipeline, rules: &[RetentionRule]) -> Result<Vec<RetentionMatch>> {
+    let now = now_secs();
+    let mut seen = HashSet::new();
+    let mut matches = Vec::new();
+    for rule in rules.iter().filter(|r| r.enabled) {
+        for candidate in rag
+            .db
+            .get_retention_candidates(rule.condition, rule.cutoff(now))
+            .await?
+        {
+            if rule.applies_to(&candidate.source) && seen.insert(candidate.id) {
+                matches.push(candidate);
+            }
+        }
+    }
+    Ok(matches)
+}
+
+/// Move every document matched by the stored rules to the Trash, returning
+/// their IDs so the run can be undone.
+pub async fn run(rag: &RagPipeline) -> Result<Vec<i64>> {
+    let rules = rag.db.get_retention_rules().await?;
+    let ids: Vec<i64> = preview(rag, &rules)
+        .await?
+        .into_iter()
+        .map(|m| m.id)
+        .collect();
+    if !ids.is_empty() {
+        rag.db.trash_documents(&ids).await?;
+        for doc_id in &ids {
+            rag.remove_document_vectors(*doc_id).await;
+        }
+    }
+    rag.db.set_retention_last_run().await?;
+    Ok(ids)
+}
+
+/// Whether a scheduled run is due: there are enabled rules and the last
+/// run was at least `RUN_INTERVAL_SECS` ago.
+pub async fn is_run_due(rag: &RagPipeline) -> bool {
+    let has_rules = rag
+        .db
+        .get_retention_rules()
+        .await
+        .is_ok_and(|rules| rules.iter().any(|r| r.enabled));
+    let last_run = rag
+        .db
+        .get_retention_last_run()
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or(0);
+    has_rules && now_secs().saturating_sub(last_run) >= RUN_INTERVAL_SECS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rules_scope_by_source_and_date() {
+        let rule = RetentionRule {
+            condition: RetentionCondition::SavedBefore,
+            days: 365,
+            source: Some(SourceKind::Bookmarks),
+            enabled: true,
+        };
+        assert!(rule.applies_to("chrome_bookmark"));
+        assert!(!rule.applies_to("chrome_extension"));
+        assert!(RetentionRule::default().applies_to("cli"));
+
+        assert_eq!(rule.cutoff(400 * 24 * 3600), 35 * 24 * 3600);
+        assert_eq!(rule.cutoff(10), 0);
+    }
+
+    #[test]
+    fn rules_round_trip_through_json() {
+        let rules = vec![
+            RetentionRule::default(),
+            RetentionRule {
+                condition: RetentionCondition::NotOpenedFor,
+                days: 30,
+                source: Some(SourceKind::Feeds),
+                enabled: false,
+            },
+        ];
+        let json = serde_json::to_string(&rules).unwrap();
+        assert!(json.contains("\"not_opened_for\""));
+        assert!(json.contains("\"feeds\""));
+        let parsed: Vec<RetentionRule> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, rules);
+    }
+}
@@ -0,0 +1,183 @@
+//! Safari bookmark support (macOS)
+//!
+//! Safari keeps its bookmarks in `~/Library/Safari/Bookmarks.plist`, a binary
+//! property list. The tree is converted into the same `BookmarkItem` shape as
+//! Chrome's `Bookmarks` JSON so folder exclusion, previews and ingestion work
+//! unchanged; Safari then shows up as one more bookmark profile.
+
+use crate::bookmark::{BookmarkItem, ChromeProfile};
+use crate::Result;
+use plist::{Dictionary, Value};
+use std::path::{Path, PathBuf};
+
+/// Profile directory name used for Safari.
+pub const SAFARI_PROFILE_DIR: &str = "Safari";
+
+/// Path to Safari's bookmarks file, if this platform has one.
+pub fn get_safari_bookmarks_path() -> Option<PathBuf> {
+    #[cfg(target_os = "macos")]
+    {
+        dirs::home_dir().map(|home| home.join("Library").join("Safari").join("Bookmarks.plist"))
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        None
+    }
+}
+
+/// The Safari bookmark profile, when the bookmarks file exists.
+pub fn get_safari_profile() -> Option<ChromeProfile> {
+    let bookmarks_path = get_safari_bookmarks_path().filter(|p| p.exists())?;
+    Some(ChromeProfile {
+        dir_name: SAFARI_PROFILE_DIR.to_string(),
+        display_name: SAFARI_PROFILE_DIR.to_string(),
+        bookmarks_path,
+    })
+}
+
+/// Whether a bookmarks file is a Safari property list rather than Chrome JSON.
+pub fn is_safari_bookmarks_file(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("plist"))
+}
+
+/// Parse a Safari bookmarks file (binary or XML plist) into its top-level
+/// folders: Favorites, the Bookmarks Menu and the Reading List.
+pub fn parse_safari_bookmarks(path: &Path) -> Result<Vec<BookmarkItem>> {
+    let root = Value::from_file(path).map_err(|e| {
+        format!(
+            "Cannot read Safari bookmarks at {:?}: {} (LocalMind may need Full Disk Access in System Settings > Privacy & Security)",
+            path, e
+        )
+    })?;
+    let root = root
+        .as_dictionary()
+        .ok_or("Safari bookmarks file has no root dictionary")?;
+
+    Ok(children(root).filter_map(convert_item).collect())
+}
+
+fn children(dict: &Dictionary) -> impl Iterator<Item = &Dictionary> {
+    dict.get("Children")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_dictionary)
+}
+
+fn string_field<'a>(dict: &'a Dictionary, key: &str) -> Option<&'a str> {
+    dict.get(key).and_then(Value::as_string)
+}
+
+/// Convert one plist entry. History proxies and entries without a URL are
+/// dropped.
+fn convert_item(dict: &Dictionary) -> Option<BookmarkItem> {
+    let id = string_field(dict, "WebBookmarkUUID")
+        .unwrap_or_default()
+        .to_string();
+
+    match string_field(dict, "WebBookmarkType")? {
+        "WebBookmarkTypeList" => {
+            let name = match string_field(dict, "Title").unwrap_or_default() {
+                "BookmarksBar" => "Favorites",
+                "BookmarksMenu" => "Bookmarks Menu",
+                "com.apple.ReadingList" => "Reading List",
+                title => title,
+            };
+            Some(BookmarkItem {
+                date_added: String::new(),
+                date_modified: None,
+                id,
+                name: name.to_string(),
+                url: None,
+                children: Some(children(dict).filter_map(convert_item).collect()),
+            })
+        }
+        "WebBookmarkTypeLeaf" => {
+            let url = string_field(dict, "URLString")?;
+            let title = dict
+                .get("URIDictionary")
+                .and_then(Value::as_dictionary)
+                .and_then(|uri| string_field(uri, "title"))
+                .unwrap_or(url);
+            Some(BookmarkItem {
+                date_added: String::new(),
+                date_modified: None,
+                id,
+                name: title.to_string(),
+                url: Some(url.to_string()),
+                children: None,
+            })
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(title: &str, uuid: &str, items: Vec<Value>) -> Value {
+        let mut dict = Dictionary::new();
+        dict.insert("WebBookmarkType".into(), "WebBookmarkTypeList".into());
+        dict.insert("Title".into(), title.into());
+        dict.insert("WebBookmarkUUID".into(), uuid.into());
+        dict.insert("Children".into(), Value::Array(items));
+        Value::Dictionary(dict)
+    }
+
+    fn leaf(title: &str, url: &str) -> Value {
+        let mut uri = Dictionary::new();
+        uri.insert("title".into(), title.into());
+        let mut dict = Dictionary::new();
+        dict.insert("WebBookmarkType".into(), "WebBookmarkTypeLeaf".into());
+        dict.insert("WebBookmarkUUID".into(), format!("uuid-{}", title).into());
+        dict.insert("URLString".into(), url.into());
+        dict.insert("URIDictionary".into(), Value::Dictionary(uri));
+        Value::Dictionary(dict)
+    }
+
+    #[test]
+    fn parses_binary_plist_into_bookmark_tree() {
+        let mut history = Dictionary::new();
+        history.insert("WebBookmarkType".into(), "WebBookmarkTypeProxy".into());
+        history.insert("Title".into(), "History".into());
+
+        let root = list(
+            "",
+            "root",
+            vec![
+                Value::Dictionary(history),
+                list(
+                    "BookmarksBar",
+                    "bar",
+                    vec![
+                        leaf("Rust", "https://www.rust-lang.org/"),
+                        list("Work", "work", vec![leaf("Docs", "https://docs.rs/")]),
+                    ],
+                ),
+                list("BookmarksMenu", "menu", vec![]),
+            ],
+        );
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("Bookmarks.plist");
+        root.to_file_binary(&path).unwrap();
+        assert!(is_safari_bookmarks_file(&path));
+
+        let roots = parse_safari_bookmarks(&path).unwrap();
+        assert_eq!(roots.len(), 2);
+        assert_eq!(roots[0].name, "Favorites");
+        assert_eq!(roots[1].name, "Bookmarks Menu");
+
+        let favorites = roots[0].children.as_ref().unwrap();
+        assert_eq!(favorites[0].name, "Rust");
+        assert_eq!(
+            favorites[0].url.as_deref(),
+            Some("https://www.rust-lang.org/")
+        );
+        assert_eq!(favorites[1].id, "work");
+        assert_eq!(favorites[1].children.as_ref().unwrap()[0].name, "Docs");
+    }
+}
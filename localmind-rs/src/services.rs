@@ -0,0 +1,3112 @@
+//! Background services shared by the GUI and headless mode
+//!
+//! Initializes the database and RAG pipeline, runs bookmark ingestion and the
+//! dead-link scheduler, and serves the HTTP API used by the Chrome extension.
+//! `run_headless` wires these together without opening a window.
+
+use crate::db::Database;
+use crate::embedding_supervisor::{EmbeddingServerStatus, EmbeddingServerSupervisor};
+use crate::rag::RagPipeline;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Type alias for shared RAG state
+pub type RagState = Arc<RwLock<Option<RagPipeline>>>;
+
+/// Source of notes written in the app.
+pub const NOTE_SOURCE: &str = "note";
+
+/// Source of the documents highlights are found by (see `annotations`).
+pub const HIGHLIGHT_SOURCE: &str = "highlight";
+
+/// Source of pages saved by pasting their URL into the app.
+pub const PASTED_URL_SOURCE: &str = "pasted_url";
+
+/// Progress of saving a pasted URL
+///
+/// Sent through a channel so the UI can show a progress toast.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SaveUrlProgress {
+    /// Downloading the page (or the video's transcript)
+    Fetching,
+    /// Embedding and storing the page, by title
+    Indexing(String),
+    /// Saved as the document with this ID and title
+    Saved(i64, String),
+    /// The URL is already saved as the document with this ID
+    AlreadySaved(i64),
+    Failed(String),
+}
+
+/// Bookmark ingestion progress event
+///
+/// Sent through a channel to update the UI during bookmark processing.
+#[derive(Debug, Clone)]
+pub struct BookmarkProgress {
+    pub current: usize,
+    pub total: usize,
+    pub current_title: String,
+    pub completed: bool,
+    /// Bookmarks that could not be fetched (only meaningful on completion)
+    pub failed: usize,
+}
+
+/// Initialize the RAG system
+///
+/// When the local embedding server is the configured provider, it is started
+/// (if not already running) and supervised; the supervisor is returned so
+/// the caller can stop the server on exit. Supervisor status changes are
+/// sent on `server_status_tx`.
+pub async fn init_rag_system(
+    server_status_tx: std::sync::mpsc::Sender<EmbeddingServerStatus>,
+) -> crate::Result<(RagPipeline, Option<EmbeddingServerSupervisor>)> {
+    use crate::embedding_provider::EmbeddingProvider;
+
+    println!("Initializing database...");
+
+    let db = match Database::new().await {
+        Ok(database) => {
+            println!("Database initialized successfully");
+            database
+        }
+        Err(e) => {
+            eprintln!("Database initialization failed: {}", e);
+            return Err(e);
+        }
+    };
+
+    // Start and supervise the embedding server unless one is already running.
+    // Ollama and LM Studio are started by the user.
+    let embedding_settings = db.get_embedding_settings().await.unwrap_or_default();
+    let supervisor = if embedding_settings.provider == EmbeddingProvider::LocalServer {
+        let command = db.get_embedding_server_command().await.unwrap_or_default();
+        Some(
+            EmbeddingServerSupervisor::start(
+                &embedding_settings.effective_url(),
+                command,
+                server_status_tx,
+            )
+            .await,
+        )
+    } else {
+        println!(
+            "Using {} for embeddings",
+            embedding_settings.provider.label()
+        );
+        None
+    };
+
+    println!("Initializing RAG pipeline...");
+    let rag = match RagPipeline::new(db).await {
+        Ok(rag_pipeline) => {
+            println!("RAG pipeline initialized successfully");
+            rag_pipeline
+        }
+        Err(e) => {
+            eprintln!("RAG pipeline initialization failed: {}", e);
+            return Err(e);
+        }
+    };
+
+    Ok((rag, supervisor))
+}
+
+/// Switch to another library without restarting.
+///
+/// Opens the library's database (creating it for a new library), builds a
+/// pipeline on it with that library's embedding settings and swaps it into
+/// `rag_state`, so the HTTP server and background tasks follow the switch.
+/// The library becomes the one opened at the next start. The embedding
+/// server started for the first library keeps running.
+pub async fn switch_library(rag_state: RagState, name: String) -> crate::Result<()> {
+    let mut registry = crate::library::LibraryRegistry::load();
+    if !registry.libraries.contains(&name) {
+        return Err(format!("No library named \"{}\"", name).into());
+    }
+
+    println!("Opening library \"{}\"...", name);
+    let db = Database::open(&crate::library::database_path(&name)).await?;
+    let rag = RagPipeline::new(db).await?;
+    *rag_state.write().await = Some(rag);
+
+    registry.active = name;
+    registry.save()?;
+    Ok(())
+}
+
+/// Re-embed any documents left with missing chunk embeddings by an earlier crash.
+pub async fn start_consistency_check(rag_state: RagState) {
+    let rag_lock = rag_state.read().await;
+    if let Some(ref rag) = *rag_lock {
+        match rag.repair_incomplete_documents().await {
+            Ok(0) => {}
+            Ok(repaired) => println!("Repaired {} incomplete documents", repaired),
+            Err(e) => eprintln!("Startup consistency check failed: {}", e),
+        }
+    }
+}
+
+/// Audit the database for damage and send the report to the UI.
+///
+/// Run after `start_consistency_check` so documents it just re-embedded are
+/// not reported. Problems are also logged for headless mode.
+pub async fn start_integrity_audit(
+    rag_state: RagState,
+    report_tx: std::sync::mpsc::Sender<crate::integrity::IntegrityReport>,
+) {
+    let rag_lock = rag_state.read().await;
+    if let Some(ref rag) = *rag_lock {
+        match crate::integrity::audit(&rag.db).await {
+            Ok(report) => {
+                if !report.is_healthy() {
+                    eprintln!(
+                        "Integrity audit found problems: {} documents without chunks, \
+                         {} orphaned chunks, {} corrupt chunks, {} chunks with the wrong \
+                         dimension, full-text index {} rows for {} documents",
+                        report.documents_without_chunks.len(),
+                        report.orphan_chunks,
+                        report.corrupt_chunks.len(),
+                        report.mismatched_chunks.len(),
+                        report.fts_count,
+                        report.document_count
+                    );
+                }
+                let _ = report_tx.send(report);
+            }
+            Err(e) => eprintln!("Startup integrity audit failed: {}", e),
+        }
+    }
+}
+
+/// Periodically run the dead-link checker when its configured interval has elapsed.
+///
+/// Waits a few minutes after startup so the checker does not compete with
+/// bookmark ingestion, then re-checks whether a run is due every 10 minutes.
+pub async fn start_link_check_scheduler(
+    rag_state: RagState,
+    progress_tx: std::sync::mpsc::Sender<crate::link_checker::LinkCheckProgress>,
+) {
+    use crate::link_checker::{is_check_due, run_link_check};
+
+    tokio::time::sleep(tokio::time::Duration::from_secs(5 * 60)).await;
+
+    loop {
+        {
+            let rag_lock = rag_state.read().await;
+            if let Some(ref rag) = *rag_lock {
+                if is_check_due(&rag.db).await {
+                    if let Err(e) = run_link_check(&rag.db, Some(&progress_tx)).await {
+                        eprintln!("Scheduled link check failed: {}", e);
+                    }
+                }
+            }
+        }
+
+        tokio::time::sleep(tokio::time::Duration::from_secs(10 * 60)).await;
+    }
+}
+
+/// Fetch the icons of sites documents came from that have none cached yet.
+///
+/// Starts a minute after startup and then looks for new sites every five
+/// minutes, a batch at a time. The pipeline lock is only held to read and
+/// store, not while downloading, so a library switch is not held up.
+pub async fn start_favicon_fetcher(rag_state: RagState) {
+    use crate::favicon::{client, fetch_favicon, hosts_missing_icons};
+
+    const BATCH: usize = 50;
+    let client = client();
+
+    tokio::time::sleep(tokio::time::Duration::from_secs(60)).await;
+
+    loop {
+        let hosts = match *rag_state.read().await {
+            Some(ref rag) => hosts_missing_icons(&rag.db, BATCH)
+                .await
+                .unwrap_or_else(|e| {
+                    eprintln!("Failed to list sites without icons: {}", e);
+                    Vec::new()
+                }),
+            None => Vec::new(),
+        };
+
+        for host in hosts {
+            let icon = fetch_favicon(&client, &host).await;
+            if let Some(ref rag) = *rag_state.read().await {
+                if let Err(e) = rag.db.set_favicon(&host, &icon).await {
+                    eprintln!("Failed to store icon for {}: {}", host, e);
+                }
+            }
+        }
+
+        tokio::time::sleep(tokio::time::Duration::from_secs(5 * 60)).await;
+    }
+}
+
+/// Fetch and shrink the preview images noted when pages were ingested.
+///
+/// Runs like the favicon fetcher: a batch every five minutes, downloading
+/// without holding the pipeline lock.
+pub async fn start_thumbnail_fetcher(rag_state: RagState) {
+    use crate::thumbnail::fetch_thumbnail;
+
+    const BATCH: usize = 50;
+    let client = crate::favicon::client();
+
+    tokio::time::sleep(tokio::time::Duration::from_secs(90)).await;
+
+    loop {
+        let pending = match *rag_state.read().await {
+            Some(ref rag) => rag
+                .db
+                .get_pending_thumbnails(BATCH)
+                .await
+                .unwrap_or_else(|e| {
+                    eprintln!("Failed to list documents without thumbnails: {}", e);
+                    Vec::new()
+                }),
+            None => Vec::new(),
+        };
+
+        for (doc_id, image_url) in pending {
+            let thumbnail = fetch_thumbnail(&client, &image_url).await;
+            if let Some(ref rag) = *rag_state.read().await {
+                if let Err(e) = rag.db.set_thumbnail(doc_id, &thumbnail).await {
+                    eprintln!("Failed to store thumbnail of document {}: {}", doc_id, e);
+                }
+            }
+        }
+
+        tokio::time::sleep(tokio::time::Duration::from_secs(5 * 60)).await;
+    }
+}
+
+/// Detect the language of documents that have none recorded: those saved
+/// before languages were detected or merged in by sync.
+///
+/// Works through a batch at a time, going straight on to the next batch
+/// while there is a backlog and checking again every five minutes after.
+pub async fn start_language_detector(rag_state: RagState) {
+    const BATCH: usize = 200;
+
+    tokio::time::sleep(tokio::time::Duration::from_secs(30)).await;
+
+    loop {
+        let pending = match *rag_state.read().await {
+            Some(ref rag) => rag
+                .db
+                .get_documents_without_language(BATCH)
+                .await
+                .unwrap_or_else(|e| {
+                    eprintln!("Failed to list documents without a language: {}", e);
+                    Vec::new()
+                }),
+            None => Vec::new(),
+        };
+        let backlog = pending.len() == BATCH;
+
+        for (doc_id, title, content) in pending {
+            let language = crate::language::detect(&title, &content).unwrap_or("");
+            if let Some(ref rag) = *rag_state.read().await {
+                if let Err(e) = rag.db.set_document_language(doc_id, language).await {
+                    eprintln!("Failed to store language of document {}: {}", doc_id, e);
+                }
+            }
+        }
+
+        let pause = if backlog { 1 } else { 5 * 60 };
+        tokio::time::sleep(tokio::time::Duration::from_secs(pause)).await;
+    }
+}
+
+/// Re-embed documents while switching embedding model (see
+/// `embedding_migration`) and switch once all are done.
+///
+/// Goes straight on to the next batch while a migration is under way and
+/// checks for one every minute otherwise. Progress is sent on `progress_tx`
+/// after each batch, then `None` once the pipeline has switched model.
+pub async fn start_embedding_migration(
+    rag_state: RagState,
+    progress_tx: std::sync::mpsc::Sender<Option<crate::embedding_migration::MigrationProgress>>,
+) {
+    use crate::embedding_migration::BATCH;
+
+    tokio::time::sleep(tokio::time::Duration::from_secs(20)).await;
+
+    loop {
+        let step = match *rag_state.read().await {
+            Some(ref rag) => rag.migrate_embeddings(BATCH).await,
+            None => Ok(None),
+        };
+
+        let pause = match step {
+            Ok(Some(progress)) if progress.is_complete() => {
+                // Under the write lock no document is added meanwhile
+                let finished = match *rag_state.write().await {
+                    Some(ref mut rag) => rag.finish_embedding_migration().await,
+                    None => Ok(false),
+                };
+                match finished {
+                    Ok(true) => {
+                        if progress_tx.send(None).is_err() {
+                            return;
+                        }
+                        60
+                    }
+                    Ok(false) => 1,
+                    Err(e) => {
+                        eprintln!("Failed to finish embedding migration: {}", e);
+                        60
+                    }
+                }
+            }
+            Ok(Some(progress)) => {
+                if progress_tx.send(Some(progress)).is_err() {
+                    return;
+                }
+                1
+            }
+            Ok(None) => 60,
+            Err(e) => {
+                eprintln!("Embedding migration failed, retrying: {}", e);
+                60
+            }
+        };
+        tokio::time::sleep(tokio::time::Duration::from_secs(pause)).await;
+    }
+}
+
+/// Run database maintenance when its configured interval has elapsed.
+///
+/// Like the link checker it waits a few minutes after startup, then checks
+/// every hour whether a run is due.
+pub async fn start_maintenance_scheduler(
+    rag_state: RagState,
+    progress_tx: std::sync::mpsc::Sender<crate::maintenance::MaintenanceProgress>,
+) {
+    use crate::maintenance::{is_maintenance_due, run_maintenance};
+
+    tokio::time::sleep(tokio::time::Duration::from_secs(5 * 60)).await;
+
+    loop {
+        {
+            let rag_lock = rag_state.read().await;
+            if let Some(ref rag) = *rag_lock {
+                if is_maintenance_due(&rag.db).await {
+                    if let Err(e) = run_maintenance(&rag.db, Some(&progress_tx)).await {
+                        eprintln!("Scheduled database maintenance failed: {}", e);
+                    }
+                }
+            }
+        }
+
+        tokio::time::sleep(tokio::time::Duration::from_secs(60 * 60)).await;
+    }
+}
+
+/// Apply the retention rules when a run is due (once a day).
+///
+/// Waits a few minutes after startup like the link checker, then checks
+/// hourly. The IDs of documents moved to the Trash are sent on `trashed_tx`
+/// so the GUI can offer to undo the run.
+pub async fn start_retention_scheduler(
+    rag_state: RagState,
+    trashed_tx: std::sync::mpsc::Sender<Vec<i64>>,
+) {
+    use crate::retention::{is_run_due, run};
+
+    tokio::time::sleep(tokio::time::Duration::from_secs(5 * 60)).await;
+
+    loop {
+        {
+            let rag_lock = rag_state.read().await;
+            if let Some(ref rag) = *rag_lock {
+                if is_run_due(rag).await {
+                    match run(rag).await {
+                        Ok(ids) if ids.is_empty() => {}
+                        Ok(ids) => {
+                            println!("Retention rules moved {} documents to the trash", ids.len());
+                            let _ = trashed_tx.send(ids);
+                        }
+                        Err(e) => eprintln!("Scheduled retention run failed: {}", e),
+                    }
+                }
+            }
+        }
+
+        tokio::time::sleep(tokio::time::Duration::from_secs(60 * 60)).await;
+    }
+}
+
+/// Sync with the other machines through the sync folder when a run is due.
+///
+/// Waits a minute after startup, then checks every few minutes. Reports of
+/// runs are sent on `report_tx` so the GUI can refresh its lists.
+pub async fn start_sync_scheduler(
+    rag_state: RagState,
+    report_tx: std::sync::mpsc::Sender<crate::sync::SyncReport>,
+) {
+    use crate::sync::{is_sync_due, run_sync};
+
+    tokio::time::sleep(tokio::time::Duration::from_secs(60)).await;
+
+    loop {
+        {
+            let rag_lock = rag_state.read().await;
+            if let Some(ref rag) = *rag_lock {
+                if is_sync_due(rag).await {
+                    match run_sync(rag).await {
+                        Ok(report) => {
+                            let _ = report_tx.send(report);
+                        }
+                        Err(e) => eprintln!("Scheduled sync failed: {}", e),
+                    }
+                }
+            }
+        }
+
+        tokio::time::sleep(tokio::time::Duration::from_secs(5 * 60)).await;
+    }
+}
+
+/// Take scheduled snapshots of the library when they are due, sending the
+/// report of each one.
+pub async fn start_snapshot_scheduler(
+    rag_state: RagState,
+    report_tx: std::sync::mpsc::Sender<crate::snapshot::SnapshotReport>,
+) {
+    use crate::snapshot::{is_snapshot_due, run_snapshot};
+
+    tokio::time::sleep(tokio::time::Duration::from_secs(60)).await;
+
+    loop {
+        {
+            let rag_lock = rag_state.read().await;
+            if let Some(ref rag) = *rag_lock {
+                if is_snapshot_due(&rag.db).await {
+                    let result = match rag.db.get_snapshot_settings().await {
+                        Ok(settings) => run_snapshot(&rag.db, &settings).await,
+                        Err(e) => Err(e),
+                    };
+                    match result {
+                        Ok(report) => {
+                            let _ = report_tx.send(report);
+                        }
+                        Err(e) => eprintln!("Scheduled snapshot failed: {}", e),
+                    }
+                }
+            }
+        }
+
+        tokio::time::sleep(tokio::time::Duration::from_secs(5 * 60)).await;
+    }
+}
+
+/// Fire document reminders as they come due, sending each batch on
+/// `due_tx` so the GUI can bring the documents back on the home view.
+///
+/// Reminders that came due while LocalMind was closed fire shortly after
+/// startup; after that it checks every minute.
+pub async fn start_reminder_scheduler(
+    rag_state: RagState,
+    due_tx: std::sync::mpsc::Sender<Vec<crate::reminders::Reminder>>,
+) {
+    use crate::reminders::CHECK_INTERVAL_SECS;
+
+    tokio::time::sleep(tokio::time::Duration::from_secs(10)).await;
+
+    loop {
+        {
+            let rag_lock = rag_state.read().await;
+            if let Some(ref rag) = *rag_lock {
+                match rag.db.fire_due_reminders().await {
+                    Ok(due) if due.is_empty() => {}
+                    Ok(due) => {
+                        if due_tx.send(due).is_err() {
+                            return;
+                        }
+                    }
+                    Err(e) => eprintln!("Failed to check reminders: {}", e),
+                }
+            }
+        }
+
+        tokio::time::sleep(tokio::time::Duration::from_secs(CHECK_INTERVAL_SECS)).await;
+    }
+}
+
+/// Watch the embedding backend and free disk space in the background.
+///
+/// Sends an event when the backend stops responding or the disk holding the
+/// database drops below `LOW_DISK_SPACE_BYTES`; each condition is reported
+/// once when it starts, not on every check while it lasts. Whether semantic
+/// search is available is sent on `semantic_tx` whenever it changes; when the
+/// backend comes back, documents stored in keyword-only mode are embedded.
+/// Returns when a receiver is dropped.
+pub async fn start_health_monitor(
+    rag_state: RagState,
+    event_tx: std::sync::mpsc::Sender<crate::notifications::NotificationEvent>,
+    semantic_tx: std::sync::mpsc::Sender<bool>,
+) {
+    use crate::notifications::{available_space, NotificationEvent, LOW_DISK_SPACE_BYTES};
+
+    let data_dir = crate::db::data_dir();
+    let mut server_down = false;
+    let mut disk_low = false;
+
+    loop {
+        let down = match *rag_state.read().await {
+            Some(ref rag) => !rag.check_embedding_backend().await,
+            None => false,
+        };
+        if down && !server_down {
+            eprintln!("Embedding server is not responding");
+            if event_tx
+                .send(NotificationEvent::EmbeddingServerDown)
+                .is_err()
+                || semantic_tx.send(false).is_err()
+            {
+                return;
+            }
+        } else if !down && server_down {
+            if semantic_tx.send(true).is_err() {
+                return;
+            }
+            tokio::spawn(start_consistency_check(rag_state.clone()));
+        }
+        server_down = down;
+
+        let low = available_space(&data_dir).is_some_and(|free| free < LOW_DISK_SPACE_BYTES);
+        if low && !disk_low {
+            eprintln!("Disk space is low at {}", data_dir.display());
+            if event_tx.send(NotificationEvent::DiskSpaceLow).is_err() {
+                return;
+            }
+        }
+        disk_low = low;
+
+        // Check more often in keyword-only mode so semantic search returns quickly
+        let interval = if server_down { 10 } else { 60 };
+        tokio::time::sleep(tokio::time::Duration::from_secs(interval)).await;
+    }
+}
+
+/// Before the first ingestion of a large bookmark collection, send a
+/// dry-run preview to the GUI and wait for the user's choice.
+///
+/// Returns the exclusion rules to ingest with (including any folders and
+/// domains the user unchecked, which are saved), or `None` if the user
+/// skipped ingestion.
+async fn preview_first_ingestion(
+    rag_state: &RagState,
+    profiles: &[crate::bookmark::ChromeProfile],
+    exclusion_rules: crate::bookmark_exclusion::ExclusionRules,
+    preview_tx: std::sync::mpsc::Sender<crate::ingestion_preview::PreviewRequest>,
+) -> crate::Result<Option<crate::bookmark_exclusion::ExclusionRules>> {
+    use crate::bookmark::BookmarkMonitor;
+    use crate::bookmark_exclusion::ExclusionRules;
+    use crate::db::OperationPriority;
+    use crate::ingestion_preview::{
+        IngestionPreview, PreviewBookmark, PreviewDecision, PreviewRequest, PREVIEW_MIN_BOOKMARKS,
+    };
+
+    // Only the first ingestion (empty database) is previewed
+    let is_first_run = {
+        let rag_lock = rag_state.read().await;
+        match *rag_lock {
+            Some(ref rag) => {
+                rag.db
+                    .count_documents(OperationPriority::BackgroundIngest)
+                    .await?
+                    == 0
+            }
+            None => false,
+        }
+    };
+    if !is_first_run {
+        return Ok(Some(exclusion_rules));
+    }
+
+    let mut preview = IngestionPreview::default();
+    for profile in profiles {
+        let Ok((monitor, _rx)) = BookmarkMonitor::for_profile(profile) else {
+            continue;
+        };
+        let bookmarks = match monitor.get_bookmarks_with_paths_with_exclusion(&exclusion_rules) {
+            Ok(b) => b,
+            Err(e) => {
+                eprintln!(
+                    "Failed to preview bookmarks for profile {}: {}",
+                    profile.display_name, e
+                );
+                continue;
+            }
+        };
+        preview
+            .bookmarks
+            .extend(bookmarks.into_iter().filter_map(|b| {
+                b.item.url.map(|url| PreviewBookmark {
+                    url,
+                    folder_id: b.folder_id,
+                    folder_path: b.folder_path,
+                    profile: profile.display_name.clone(),
+                })
+            }));
+    }
+
+    if preview.bookmarks.len() < PREVIEW_MIN_BOOKMARKS {
+        return Ok(Some(exclusion_rules));
+    }
+
+    // Let the preview warn when the import would not fit on the disk or
+    // in the index size limit
+    if let Some(ref rag) = *rag_state.read().await {
+        match rag.storage_status().await {
+            Ok(storage) => preview.storage = Some(storage),
+            Err(e) => eprintln!("Failed to measure index size: {}", e),
+        }
+    }
+
+    println!(
+        "First ingestion: previewing {} bookmarks before importing",
+        preview.bookmarks.len()
+    );
+    let (reply, decision) = tokio::sync::oneshot::channel();
+    if preview_tx.send(PreviewRequest { preview, reply }).is_err() {
+        // No one to ask (window closed); ingest as configured
+        return Ok(Some(exclusion_rules));
+    }
+
+    match decision.await.unwrap_or(PreviewDecision::Skip) {
+        PreviewDecision::Skip => Ok(None),
+        PreviewDecision::Ingest {
+            skipped_folders,
+            skipped_domains,
+        } => {
+            if skipped_folders.is_empty() && skipped_domains.is_empty() {
+                return Ok(Some(exclusion_rules));
+            }
+
+            // Save the unchecked folders and domains as exclusion rules
+            let rag_lock = rag_state.read().await;
+            let Some(ref rag) = *rag_lock else {
+                return Ok(Some(exclusion_rules));
+            };
+            let mut folders = rag.db.get_excluded_folders().await?;
+            let mut domains = rag.db.get_excluded_domains().await?;
+            for folder in skipped_folders {
+                if !folders.contains(&folder) {
+                    folders.push(folder);
+                }
+            }
+            for domain in skipped_domains {
+                if !domains.contains(&domain) {
+                    domains.push(domain);
+                }
+            }
+            rag.db.set_excluded_folders(&folders).await?;
+            rag.db.set_excluded_domains(&domains).await?;
+            Ok(Some(ExclusionRules::new(folders, domains)))
+        }
+    }
+}
+
+/// Load the bookmark exclusion rules from the database (shared across all
+/// profiles).
+async fn load_exclusion_rules(rag_state: &RagState) -> crate::bookmark_exclusion::ExclusionRules {
+    use crate::bookmark_exclusion::ExclusionRules;
+
+    let rag_lock = rag_state.read().await;
+    if let Some(ref rag) = *rag_lock {
+        let folders = rag.db.get_excluded_folders().await.unwrap_or_default();
+        let domains = rag.db.get_excluded_domains().await.unwrap_or_default();
+        ExclusionRules::new(folders, domains)
+    } else {
+        ExclusionRules::empty()
+    }
+}
+
+/// Start bookmark monitoring with progress reporting
+///
+/// When `preview_tx` is set, the first ingestion of a large collection waits
+/// for the user to confirm a preview (see `crate::ingestion_preview`).
+///
+/// Returns whether bookmarks are being indexed: `false` when no browser
+/// profile was found or the user skipped ingestion from the preview, in which
+/// case the profiles should not be watched either.
+pub async fn start_bookmark_monitoring(
+    rag_state: RagState,
+    progress_tx: std::sync::mpsc::Sender<BookmarkProgress>,
+    preview_tx: Option<std::sync::mpsc::Sender<crate::ingestion_preview::PreviewRequest>>,
+) -> crate::Result<bool> {
+    use crate::bookmark::get_all_bookmark_profiles;
+
+    println!("Initializing bookmark monitor...");
+
+    // Discover all Chrome profiles, plus Safari on macOS
+    let profiles = get_all_bookmark_profiles();
+    if profiles.is_empty() {
+        println!("No browser profiles found, skipping bookmark monitoring");
+        return Ok(false);
+    }
+    println!("Found {} browser profile(s) to index", profiles.len());
+
+    let exclusion_rules = load_exclusion_rules(&rag_state).await;
+
+    // Dropping the sender afterwards tells the GUI no preview is coming
+    let exclusion_rules = match preview_tx {
+        Some(preview_tx) => {
+            match preview_first_ingestion(&rag_state, &profiles, exclusion_rules, preview_tx)
+                .await?
+            {
+                Some(rules) => rules,
+                None => {
+                    println!("Bookmark ingestion skipped from the preview");
+                    return Ok(false);
+                }
+            }
+        }
+        None => exclusion_rules,
+    };
+
+    let mut total_ingested = 0;
+    let mut total_failed = 0;
+
+    for profile in &profiles {
+        ingest_profile_bookmarks(
+            &rag_state,
+            profile,
+            &exclusion_rules,
+            &progress_tx,
+            &mut total_ingested,
+            &mut total_failed,
+        )
+        .await;
+    }
+
+    send_ingestion_complete(&progress_tx, total_ingested, total_failed);
+
+    println!(
+        "Bookmark ingestion complete: {} new bookmarks ingested",
+        total_ingested
+    );
+
+    Ok(true)
+}
+
+/// Queue a profile's bookmarks that are not yet indexed, then fetch and
+/// ingest everything pending for it. `ingested` and `failed` are running
+/// totals across calls.
+async fn ingest_profile_bookmarks(
+    rag_state: &RagState,
+    profile: &crate::bookmark::ChromeProfile,
+    exclusion_rules: &crate::bookmark_exclusion::ExclusionRules,
+    progress_tx: &std::sync::mpsc::Sender<BookmarkProgress>,
+    ingested: &mut usize,
+    failed: &mut usize,
+) {
+    use crate::bookmark::BookmarkMonitor;
+    use crate::ingest_queue::MAX_ATTEMPTS;
+
+    println!(
+        "Processing profile: {} ({})",
+        profile.display_name, profile.dir_name
+    );
+
+    let (monitor, _rx) = match BookmarkMonitor::for_profile(profile) {
+        Ok(m) => m,
+        Err(e) => {
+            eprintln!(
+                "Failed to create monitor for profile {}: {}",
+                profile.display_name, e
+            );
+            return;
+        }
+    };
+
+    let bookmark_metadata = match monitor
+        .get_bookmarks_metadata_with_exclusion(exclusion_rules)
+        .await
+    {
+        Ok(m) => m,
+        Err(e) => {
+            eprintln!(
+                "Failed to get bookmarks for profile {}: {}",
+                profile.display_name, e
+            );
+            return;
+        }
+    };
+
+    if bookmark_metadata.is_empty() {
+        println!("No bookmarks found in profile {}", profile.display_name);
+        return;
+    }
+
+    let profile_name = profile.display_name.clone();
+
+    // Persist the bookmark list first so an interrupted run can resume
+    // from the queue instead of re-checking every URL.
+    let pending = {
+        let rag_lock = rag_state.read().await;
+        match *rag_lock {
+            Some(ref rag) => {
+                let queued = rag
+                    .db
+                    .enqueue_bookmarks(&bookmark_metadata, Some(&profile_name))
+                    .await
+                    .unwrap_or_else(|e| {
+                        eprintln!("Failed to enqueue bookmarks: {}", e);
+                        0
+                    });
+                if queued > 0 {
+                    println!("Queued {} new bookmarks for ingestion", queued);
+                }
+                rag.db
+                    .get_pending_queue_items(Some(&profile_name), MAX_ATTEMPTS)
+                    .await
+                    .unwrap_or_default()
+            }
+            None => Vec::new(),
+        }
+    };
+
+    if pending.is_empty() {
+        println!("No pending bookmarks in profile {}", profile.display_name);
+        return;
+    }
+
+    println!(
+        "Processing {} queued bookmarks from profile {}",
+        pending.len(),
+        profile.display_name
+    );
+
+    ingest_queue_items(rag_state, pending, progress_tx, ingested, failed).await;
+}
+
+/// Watch every browser profile's bookmarks file after the initial ingestion,
+/// and ingest bookmarks added while LocalMind is running.
+///
+/// Watcher failures are sent on `error_tx` so the GUI can show them in its
+/// status bar. Runs until `shutdown` is cancelled, which stops all watchers.
+pub async fn watch_bookmark_profiles(
+    rag_state: RagState,
+    progress_tx: std::sync::mpsc::Sender<BookmarkProgress>,
+    error_tx: std::sync::mpsc::Sender<String>,
+    shutdown: tokio_util::sync::CancellationToken,
+) {
+    use crate::bookmark::{
+        diff_bookmarks, get_all_bookmark_profiles, BookmarkMonitor, BookmarkWatchEvent,
+    };
+
+    let profiles = get_all_bookmark_profiles();
+    let (event_tx, mut event_rx) = tokio::sync::mpsc::unbounded_channel();
+    let mut watchers = Vec::new();
+    // Last read of each profile's bookmarks, to tell what a save added
+    let mut snapshots = vec![Vec::new(); profiles.len()];
+
+    for (index, profile) in profiles.iter().enumerate() {
+        let started = match BookmarkMonitor::for_profile(profile) {
+            Ok((monitor, rx)) => monitor
+                .start_monitoring()
+                .map(|watcher| (watcher, rx, monitor.parse_bookmarks().unwrap_or_default())),
+            Err(e) => Err(e),
+        };
+        match started {
+            Ok((watcher, mut rx, bookmarks)) => {
+                watchers.push(watcher);
+                snapshots[index] = bookmarks;
+                let event_tx = event_tx.clone();
+                tokio::spawn(async move {
+                    while let Some(event) = rx.recv().await {
+                        if event_tx.send((index, event)).is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+            Err(e) => {
+                let message = format!("Cannot watch {} bookmarks: {}", profile.display_name, e);
+                eprintln!("{}", message);
+                let _ = error_tx.send(message);
+            }
+        }
+    }
+    drop(event_tx);
+
+    if watchers.is_empty() {
+        return;
+    }
+    println!("Watching {} bookmark file(s) for changes", watchers.len());
+
+    loop {
+        let (index, event) = tokio::select! {
+            _ = shutdown.cancelled() => break,
+            event = event_rx.recv() => match event {
+                Some(event) => event,
+                None => break,
+            },
+        };
+        let profile = &profiles[index];
+
+        match event {
+            BookmarkWatchEvent::Changed(bookmarks) => {
+                let diff = diff_bookmarks(&snapshots[index], &bookmarks);
+                snapshots[index] = bookmarks;
+                println!(
+                    "Bookmarks changed in profile {}: {} added, {} removed",
+                    profile.display_name,
+                    diff.added.len(),
+                    diff.removed.len()
+                );
+                if diff.added.is_empty() {
+                    continue;
+                }
+                let exclusion_rules = load_exclusion_rules(&rag_state).await;
+                let mut ingested = 0;
+                let mut failed = 0;
+                ingest_profile_bookmarks(
+                    &rag_state,
+                    profile,
+                    &exclusion_rules,
+                    &progress_tx,
+                    &mut ingested,
+                    &mut failed,
+                )
+                .await;
+                // Stay quiet for saves that added nothing (edits, reordering)
+                if ingested > 0 || failed > 0 {
+                    send_ingestion_complete(&progress_tx, ingested, failed);
+                }
+            }
+            BookmarkWatchEvent::Error(e) => {
+                let message = format!("{} bookmarks: {}", profile.display_name, e);
+                eprintln!("{}", message);
+                let _ = error_tx.send(message);
+            }
+        }
+    }
+
+    for watcher in watchers {
+        watcher.shutdown();
+    }
+    println!("Bookmark watchers stopped");
+}
+
+/// Fetch and ingest queued bookmarks one by one, sending a progress event
+/// before each. `ingested` and `failed` are running totals across calls.
+async fn ingest_queue_items(
+    rag_state: &RagState,
+    items: Vec<crate::ingest_queue::QueueItem>,
+    progress_tx: &std::sync::mpsc::Sender<BookmarkProgress>,
+    ingested: &mut usize,
+    failed: &mut usize,
+) {
+    use crate::ingest_queue::{process_queue_item, QueueOutcome};
+
+    let total = items.len();
+
+    for item in items.into_iter() {
+        {
+            let rag_lock = rag_state.read().await;
+            if let Some(ref rag) = *rag_lock {
+                let _ = progress_tx.send(BookmarkProgress {
+                    current: *ingested + 1,
+                    total,
+                    current_title: item.title.clone(),
+                    completed: false,
+                    failed: *failed,
+                });
+
+                match process_queue_item(rag, &item).await {
+                    QueueOutcome::Ingested => {
+                        *ingested += 1;
+                        println!(
+                            "Ingested bookmark: {} (profile: {})",
+                            item.title,
+                            item.profile.as_deref().unwrap_or("none")
+                        );
+                    }
+                    QueueOutcome::AlreadyIndexed => continue,
+                    QueueOutcome::Failed(_) => *failed += 1,
+                }
+            }
+        }
+
+        // Small delay to prevent overwhelming the system
+        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+    }
+}
+
+/// Send the final progress event for an ingestion run.
+fn send_ingestion_complete(
+    progress_tx: &std::sync::mpsc::Sender<BookmarkProgress>,
+    ingested: usize,
+    failed: usize,
+) {
+    let _ = progress_tx.send(BookmarkProgress {
+        current: ingested,
+        total: ingested,
+        current_title: if ingested > 0 {
+            format!("{} new bookmarks ingested", ingested)
+        } else {
+            "All bookmarks already indexed".to_string()
+        },
+        completed: true,
+        failed,
+    });
+}
+
+/// Queue the bookmarks in an exported bookmark file (Netscape HTML or OPML)
+/// for ingestion, returning how many were newly queued.
+///
+/// Domain exclusion rules are applied first. Call `ingest_imported_bookmarks`
+/// afterwards to fetch them.
+pub async fn queue_bookmark_import(
+    rag_state: &RagState,
+    path: &std::path::Path,
+) -> crate::Result<usize> {
+    use crate::bookmark_exclusion::ExclusionRules;
+    use crate::bookmark_import::{apply_exclusion_rules, read_bookmark_file, IMPORT_PROFILE};
+
+    let bookmarks = read_bookmark_file(path)?;
+
+    let rag_lock = rag_state.read().await;
+    let rag = rag_lock
+        .as_ref()
+        .ok_or("System initializing. Please wait.")?;
+
+    let rules = ExclusionRules::new(
+        Vec::new(),
+        rag.db.get_excluded_domains().await.unwrap_or_default(),
+    );
+    let kept = apply_exclusion_rules(&bookmarks, &rules);
+    println!(
+        "Importing {} bookmarks from {} ({} excluded)",
+        kept.len(),
+        path.display(),
+        bookmarks.len() - kept.len()
+    );
+
+    rag.db.enqueue_bookmarks(&kept, Some(IMPORT_PROFILE)).await
+}
+
+/// Fetch and ingest every pending imported bookmark, reporting progress on
+/// the same channel as Chrome bookmark ingestion. Also resumes imports that
+/// were interrupted by closing the app.
+pub async fn ingest_imported_bookmarks(
+    rag_state: RagState,
+    progress_tx: std::sync::mpsc::Sender<BookmarkProgress>,
+) {
+    use crate::bookmark_import::IMPORT_PROFILE;
+    use crate::ingest_queue::MAX_ATTEMPTS;
+
+    let pending = {
+        let rag_lock = rag_state.read().await;
+        match *rag_lock {
+            Some(ref rag) => rag
+                .db
+                .get_pending_queue_items(Some(IMPORT_PROFILE), MAX_ATTEMPTS)
+                .await
+                .unwrap_or_default(),
+            None => Vec::new(),
+        }
+    };
+    if pending.is_empty() {
+        return;
+    }
+
+    let mut ingested = 0;
+    let mut failed = 0;
+    ingest_queue_items(
+        &rag_state,
+        pending,
+        &progress_tx,
+        &mut ingested,
+        &mut failed,
+    )
+    .await;
+    send_ingestion_complete(&progress_tx, ingested, failed);
+
+    println!(
+        "Bookmark import complete: {} new bookmarks ingested",
+        ingested
+    );
+}
+
+/// Fetch a pasted URL and ingest it right away, reporting each stage on
+/// `progress_tx` and finishing with `Saved`, `AlreadySaved` or `Failed`.
+///
+/// YouTube videos are saved with their transcript when one is available.
+/// The page's title comes from the page itself (or YouTube), falling back
+/// to the URL. The rag lock is not held while the page downloads.
+pub async fn save_url(
+    rag_state: RagState,
+    url: String,
+    progress_tx: std::sync::mpsc::Sender<SaveUrlProgress>,
+) {
+    let outcome = match save_url_inner(&rag_state, url.trim(), &progress_tx).await {
+        Ok(outcome) => outcome,
+        Err(e) => SaveUrlProgress::Failed(e.to_string()),
+    };
+    let _ = progress_tx.send(outcome);
+}
+
+async fn save_url_inner(
+    rag_state: &RagState,
+    url: &str,
+    progress_tx: &std::sync::mpsc::Sender<SaveUrlProgress>,
+) -> crate::Result<SaveUrlProgress> {
+    use crate::bookmark::fetch_bookmark_page;
+    use crate::youtube::YouTubeProcessor;
+
+    match url::Url::parse(url) {
+        Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => {}
+        _ => return Err("Enter a web address starting with http:// or https://".into()),
+    }
+
+    let image_captions = {
+        let rag_lock = rag_state.read().await;
+        let rag = rag_lock
+            .as_ref()
+            .ok_or("System initializing. Please wait.")?;
+        if let Some(doc) = rag.db.get_document_by_url(url).await? {
+            return Ok(SaveUrlProgress::AlreadySaved(doc.id));
+        }
+        rag.db.cached_settings().image_captions
+    };
+
+    let _ = progress_tx.send(SaveUrlProgress::Fetching);
+    let page = fetch_bookmark_page(url, &image_captions).await?;
+    let title = match page.title.clone() {
+        Some(title) => Some(title),
+        None if YouTubeProcessor::is_youtube_url(url) => YouTubeProcessor::fetch_title(url).await,
+        None => None,
+    }
+    .unwrap_or_else(|| url.to_string());
+
+    let _ = progress_tx.send(SaveUrlProgress::Indexing(title.clone()));
+    let rag_lock = rag_state.read().await;
+    let rag = rag_lock
+        .as_ref()
+        .ok_or("System initializing. Please wait.")?;
+    // Title first so it gets embedded, as for bookmarks
+    let content = format!("{}\n\n{}", title, page.content);
+    let doc_id = rag
+        .ingest_document_with_auth(
+            &title,
+            &content,
+            Some(url),
+            PASTED_URL_SOURCE,
+            None,
+            page.needs_auth,
+            Some(&page.structure),
+        )
+        .await?;
+    if let Err(e) = rag
+        .store_document_links(doc_id, Some(url), &content, &page.structure.hrefs())
+        .await
+    {
+        eprintln!("Failed to store links for '{}': {}", title, e);
+    }
+    crate::thumbnail::record_preview_image(&rag.db, doc_id, page.image_url.as_deref()).await;
+
+    println!("Saved pasted URL: {}", url);
+    Ok(SaveUrlProgress::Saved(doc_id, title))
+}
+
+/// Fetch a page flagged as junk or needing a refetch again and re-index it.
+/// The page is checked for junk again unless `keep`, in which case it is
+/// indexed in full whatever it holds. Returns the issue still found, or
+/// `None` when the page is now indexed in full; fails if it still has no
+/// content. The rag lock is not held while the page downloads.
+pub async fn refetch_page(
+    rag_state: &RagState,
+    doc_id: i64,
+    keep: bool,
+) -> crate::Result<Option<crate::page_quality::QualityIssue>> {
+    use crate::bookmark::fetch_bookmark_page;
+    use crate::page_quality::QualityIssue;
+
+    let (doc, image_captions) = {
+        let rag_lock = rag_state.read().await;
+        let rag = rag_lock
+            .as_ref()
+            .ok_or("System initializing. Please wait.")?;
+        let doc = rag
+            .db
+            .get_document(doc_id)
+            .await?
+            .ok_or_else(|| format!("Document {} not found", doc_id))?;
+        (doc, rag.db.cached_settings().image_captions)
+    };
+    let url = doc.url.ok_or("The page has no URL to fetch")?;
+
+    let page = fetch_bookmark_page(&url, &image_captions).await?;
+    if page.needs_auth {
+        return Err("The page still asks to sign in".into());
+    }
+
+    let rag_lock = rag_state.read().await;
+    let rag = rag_lock
+        .as_ref()
+        .ok_or("System initializing. Please wait.")?;
+    // Title first so it gets embedded, as for bookmarks
+    let content = format!("{}\n\n{}", doc.title, page.content);
+    if let Err(e) = rag
+        .store_document_structure(doc_id, &content, &page.structure)
+        .await
+    {
+        eprintln!("Failed to store structure of '{}': {}", doc.title, e);
+    }
+    if keep {
+        rag.keep_page(doc_id, &doc.title, &content).await?;
+    } else {
+        rag.update_document(doc_id, &doc.title, &content).await?;
+    }
+    if let Err(e) = rag
+        .store_document_links(doc_id, Some(&url), &content, &page.structure.hrefs())
+        .await
+    {
+        eprintln!("Failed to store links for '{}': {}", doc.title, e);
+    }
+    if rag.db.document_needs_refetch(doc_id).await? {
+        return Err("The page still has no content".into());
+    }
+    Ok(rag
+        .db
+        .get_document_quality_issue(doc_id)
+        .await?
+        .as_deref()
+        .and_then(QualityIssue::from_code))
+}
+
+/// Ingest a note written in the app, returning its document ID.
+pub async fn save_note(rag_state: &RagState, title: &str, text: &str) -> crate::Result<i64> {
+    let (title, text) = (title.trim(), text.trim());
+    if text.is_empty() {
+        return Err("Write something before saving the note".into());
+    }
+    let title = if title.is_empty() {
+        text.lines()
+            .next()
+            .unwrap_or(text)
+            .chars()
+            .take(80)
+            .collect()
+    } else {
+        title.to_string()
+    };
+
+    let rag_lock = rag_state.read().await;
+    let rag = rag_lock
+        .as_ref()
+        .ok_or("System initializing. Please wait.")?;
+    rag.ingest_document(&title, text, None, NOTE_SOURCE, None)
+        .await
+}
+
+/// Save a highlight on a byte range of a document's stored content, with
+/// an optional note, and index it as a document of its own.
+pub async fn save_highlight(
+    rag_state: &RagState,
+    doc_id: i64,
+    (start, end): (usize, usize),
+    note: &str,
+) -> crate::Result<crate::annotations::Annotation> {
+    use crate::annotations::{highlight_content, highlight_title, trim_range};
+
+    let rag_lock = rag_state.read().await;
+    let rag = rag_lock
+        .as_ref()
+        .ok_or("System initializing. Please wait.")?;
+    let doc = rag
+        .db
+        .get_document(doc_id)
+        .await?
+        .ok_or("Document not found")?;
+    let (start, end) =
+        trim_range(&doc.content, start, end).ok_or("Select some text to highlight")?;
+    let quote = &doc.content[start..end];
+    let note = Some(note.trim()).filter(|note| !note.is_empty());
+
+    let highlight_doc_id = rag
+        .ingest_document(
+            &highlight_title(&doc.title),
+            &highlight_content(quote, note),
+            None,
+            HIGHLIGHT_SOURCE,
+            None,
+        )
+        .await?;
+    rag.db
+        .insert_annotation(doc_id, (start, end), quote, note, Some(highlight_doc_id))
+        .await
+}
+
+/// Delete a highlight and the document it was found by.
+pub async fn delete_highlight(rag_state: &RagState, annotation_id: i64) -> crate::Result<()> {
+    let rag_lock = rag_state.read().await;
+    let rag = rag_lock
+        .as_ref()
+        .ok_or("System initializing. Please wait.")?;
+    if let Some(highlight_doc_id) = rag.db.delete_annotation(annotation_id).await? {
+        rag.db.delete_document(highlight_doc_id).await?;
+        rag.remove_document_vectors(highlight_doc_id).await;
+    }
+    Ok(())
+}
+
+/// Ingest the pages of a Notion or Confluence export (zip or folder),
+/// reporting progress on the bookmark progress channel.
+///
+/// `queued_tx` receives the page count once the export has been read, or
+/// the reason it could not be. Pages already imported are updated in place,
+/// and each page's links to other pages in the export are stored.
+pub async fn import_workspace_export(
+    rag_state: RagState,
+    path: std::path::PathBuf,
+    queued_tx: std::sync::mpsc::Sender<Result<usize, String>>,
+    progress_tx: std::sync::mpsc::Sender<BookmarkProgress>,
+) {
+    use crate::workspace_import::read_workspace_export;
+
+    let read = tokio::task::spawn_blocking(move || {
+        read_workspace_export(&path).map_err(|e| e.to_string())
+    })
+    .await
+    .unwrap_or_else(|e| Err(e.to_string()));
+    let (kind, pages) = match read {
+        Ok(export) => export,
+        Err(e) => {
+            let _ = queued_tx.send(Err(e));
+            return;
+        }
+    };
+    let _ = queued_tx.send(Ok(pages.len()));
+    println!(
+        "Importing {} pages from {} export",
+        pages.len(),
+        kind.label()
+    );
+
+    let total = pages.len();
+    let mut imported = 0;
+    for (i, page) in pages.into_iter().enumerate() {
+        let rag_lock = rag_state.read().await;
+        let Some(ref rag) = *rag_lock else {
+            return;
+        };
+        let _ = progress_tx.send(BookmarkProgress {
+            current: i + 1,
+            total,
+            current_title: page.title.clone(),
+            completed: false,
+            failed: 0,
+        });
+
+        let text = page.document_text();
+        let result = match rag.db.get_document_by_url(&page.url).await {
+            Ok(Some(existing)) => rag
+                .update_document(existing.id, &page.title, &text)
+                .await
+                .map(|_| existing.id),
+            _ => {
+                rag.ingest_document(&page.title, &text, Some(&page.url), kind.source(), None)
+                    .await
+            }
+        };
+        match result {
+            Ok(doc_id) => {
+                imported += 1;
+                if let Err(e) = rag.db.set_document_links(doc_id, &page.links).await {
+                    eprintln!("Failed to store links for '{}': {}", page.title, e);
+                }
+            }
+            Err(e) => eprintln!("Failed to import '{}': {}", page.title, e),
+        }
+    }
+
+    let _ = progress_tx.send(BookmarkProgress {
+        current: imported,
+        total: imported,
+        current_title: format!("{} pages imported from {}", imported, kind.label()),
+        completed: true,
+        failed: 0,
+    });
+    println!(
+        "{} import complete: {}/{} pages imported",
+        kind.label(),
+        imported,
+        total
+    );
+}
+
+/// Start the HTTP server for Chrome extension compatibility
+///
+/// Listens where the HTTP server settings say (see `http_settings`) and
+/// rebinds when they change, reporting the address or the failure to bind
+/// in `status`. Every request served is recorded in `request_log`. Runs
+/// until `shutdown` resolves, then stops accepting connections and lets
+/// in-flight requests finish.
+pub async fn start_http_server<F>(
+    rag_state: RagState,
+    request_log: crate::request_log::RequestLog,
+    status: crate::http_settings::HttpServerStatus,
+    shutdown: F,
+) -> crate::Result<()>
+where
+    F: std::future::Future<Output = ()> + Send + 'static,
+{
+    use crate::content_cleaner::{CleanerPipeline, ExtractionMethod};
+    use crate::html_text::PageStructure;
+    use crate::ingest_jobs::{IngestJob, JobRegistry, JobStatus, SaveStatus, SavedDocument};
+    use crate::request_log::{RequestLog, RequestRecord};
+    use axum::{
+        extract::{
+            ws::{Message, WebSocket, WebSocketUpgrade},
+            Path, Query, Request, State,
+        },
+        http::{header, Method, StatusCode},
+        middleware::{self, Next},
+        response::{Html, IntoResponse, Json, Response},
+        routing::{delete, get, post},
+        Router,
+    };
+    use serde::{Deserialize, Serialize};
+    use tokio::net::TcpListener;
+    use tower::ServiceBuilder;
+    use tower_http::cors::{AllowOrigin, CorsLayer};
+    use utoipa::{IntoParams, OpenApi, ToSchema};
+
+    #[derive(Clone)]
+    struct AppState {
+        rag_state: RagState,
+        jobs: JobRegistry,
+        cleaners: Arc<CleanerPipeline>,
+    }
+
+    struct ApiError {
+        status: StatusCode,
+        message: String,
+    }
+
+    impl IntoResponse for ApiError {
+        fn into_response(self) -> Response {
+            let error = ErrorMessage(self.message.clone());
+            let body = Json(MessageResponse {
+                message: self.message,
+            });
+            let mut response = (self.status, body).into_response();
+            response.extensions_mut().insert(error);
+            response
+        }
+    }
+
+    /// Message of an `ApiError`, kept on its response for the request log
+    #[derive(Clone)]
+    struct ErrorMessage(String);
+
+    /// Whether a request comes from the extension or from outside a browser;
+    /// browsers send `Origin` with every cross-origin request.
+    fn origin_allowed(headers: &axum::http::HeaderMap) -> bool {
+        match headers.get(header::ORIGIN) {
+            Some(origin) => origin
+                .to_str()
+                .is_ok_and(crate::http_settings::is_allowed_origin),
+            None => true,
+        }
+    }
+
+    /// Refuse requests sent by other web pages. CORS keeps a page from
+    /// reading the response, but not from sending the request.
+    async fn require_allowed_origin(request: Request, next: Next) -> Response {
+        if !origin_allowed(request.headers()) {
+            return ApiError {
+                status: StatusCode::FORBIDDEN,
+                message: "Origin not allowed".to_string(),
+            }
+            .into_response();
+        }
+        next.run(request).await
+    }
+
+    /// Record each request, its duration and outcome in the request log
+    async fn log_request(State(log): State<RequestLog>, request: Request, next: Next) -> Response {
+        let method = request.method().to_string();
+        let path = request.uri().path().to_string();
+        let origin = request
+            .headers()
+            .get(header::ORIGIN)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let started = std::time::Instant::now();
+
+        let response = next.run(request).await;
+
+        log.record(RequestRecord {
+            method,
+            path,
+            origin,
+            status: response.status().as_u16(),
+            duration: started.elapsed(),
+            error: response
+                .extensions()
+                .get::<ErrorMessage>()
+                .map(|error| error.0.clone()),
+            at: std::time::SystemTime::now(),
+        });
+        response
+    }
+
+    /// Outcome or error message
+    #[derive(Serialize, ToSchema)]
+    struct MessageResponse {
+        message: String,
+    }
+
+    #[derive(Deserialize, ToSchema)]
+    struct DocumentRequest {
+        title: String,
+        content: String,
+        url: Option<String>,
+        #[serde(default = "default_extraction_method", rename = "extractionMethod")]
+        extraction_method: String,
+        /// Hrefs of the page's anchors, for the document link graph
+        #[serde(default)]
+        links: Vec<String>,
+        /// What `content` holds: "text/plain" (the default) or "text/html",
+        /// which is run through the fetcher's readability extraction
+        #[serde(default, alias = "contentType")]
+        content_type: Option<String>,
+        /// URL of the page's preview image (e.g. its og:image), shown as a
+        /// thumbnail; found in the page when `content` is HTML
+        #[serde(default, alias = "imageUrl")]
+        image_url: Option<String>,
+        /// Headings and anchors found when `content` is HTML
+        #[serde(skip)]
+        structure: PageStructure,
+    }
+
+    fn default_extraction_method() -> String {
+        "dom".to_string()
+    }
+
+    /// Turn a request's content into the text to index: HTML goes through
+    /// the same readability extraction as fetched pages, then every request
+    /// through the cleaners for its extraction method. The headings and
+    /// anchors of HTML content are kept in `structure`.
+    fn extract_content(
+        cleaners: &CleanerPipeline,
+        request: &mut DocumentRequest,
+    ) -> Result<(), ApiError> {
+        let media_type = request
+            .content_type
+            .as_deref()
+            .and_then(|value| value.split(';').next())
+            .unwrap_or_default()
+            .trim()
+            .to_lowercase();
+        match media_type.as_str() {
+            "" | "text/plain" => {}
+            "text/html" => {
+                let page_url = request.url.as_deref().unwrap_or_default();
+                if request.image_url.is_none() {
+                    request.image_url =
+                        crate::thumbnail::preview_image_url(&request.content, page_url);
+                }
+                let page = crate::fetcher::extract_readable_text(&request.content, page_url);
+                request.content = page.text;
+                request.links.extend(page.structure.hrefs());
+                request.structure = page.structure;
+            }
+            other => {
+                return Err(ApiError {
+                    status: StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                    message: format!(
+                        "Unsupported content_type '{}'; use text/plain or text/html.",
+                        other
+                    ),
+                })
+            }
+        }
+
+        let method = ExtractionMethod::parse(&request.extraction_method);
+        if method == ExtractionMethod::Html && request.structure.is_empty() {
+            let base = request
+                .url
+                .as_deref()
+                .and_then(|url| url::Url::parse(url).ok());
+            let page = crate::html_text::extract(&request.content, base.as_ref());
+            request.links.extend(page.structure.hrefs());
+            request.structure = page.structure;
+        }
+        request.content = cleaners.clean(method, &request.content);
+        if request.content.is_empty() {
+            return Err(ApiError {
+                status: StatusCode::BAD_REQUEST,
+                message: "No text content left after cleaning.".to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    #[derive(Serialize, ToSchema)]
+    struct SuccessResponse {
+        message: String,
+        #[serde(rename = "extractionMethod")]
+        extraction_method: String,
+        #[serde(flatten)]
+        saved: SavedDocument,
+    }
+
+    #[derive(Deserialize, IntoParams)]
+    #[into_params(parameter_in = Query)]
+    struct PostDocumentParams {
+        /// Return 202 with a job id instead of waiting for ingestion
+        #[serde(default, rename = "async")]
+        run_async: bool,
+    }
+
+    #[derive(Serialize, ToSchema)]
+    #[serde(rename_all = "camelCase")]
+    struct AcceptedResponse {
+        job_id: String,
+        status: JobStatus,
+        status_url: String,
+    }
+
+    /// Add or update a document
+    #[utoipa::path(
+        post,
+        path = "/api/v1/documents",
+        params(PostDocumentParams),
+        request_body = DocumentRequest,
+        responses(
+            (status = 200, description = "Document chunked and embedded", body = SuccessResponse),
+            (status = 202, description = "Queued (with `async=true`)", body = AcceptedResponse),
+            (status = 400, description = "Missing title or content", body = MessageResponse),
+            (status = 415, description = "Unsupported `content_type`", body = MessageResponse),
+            (status = 503, description = "Still starting up", body = MessageResponse),
+        )
+    )]
+    async fn handle_post_documents(
+        State(state): State<AppState>,
+        Query(params): Query<PostDocumentParams>,
+        Json(mut request): Json<DocumentRequest>,
+    ) -> Result<Response, ApiError> {
+        if request.title.is_empty() || request.content.is_empty() {
+            return Err(ApiError {
+                status: StatusCode::BAD_REQUEST,
+                message: "Title and content are required.".to_string(),
+            });
+        }
+
+        extract_content(&state.cleaners, &mut request)?;
+
+        if !params.run_async {
+            let rag_lock = state.rag_state.read().await;
+            let rag = rag_lock.as_ref().ok_or_else(not_ready)?;
+            let response = save_document(rag, request).await?;
+            return Ok(Json(response).into_response());
+        }
+
+        // Refuse up front rather than accepting a job that cannot run
+        if state.rag_state.read().await.is_none() {
+            return Err(not_ready());
+        }
+
+        let job_id = state
+            .jobs
+            .create(&request.title, &request.extraction_method);
+        let status_url = format!("/api/v1/jobs/{}", job_id);
+
+        let jobs = state.jobs.clone();
+        let rag_state = state.rag_state.clone();
+        let id = job_id.clone();
+        tokio::spawn(async move {
+            jobs.mark_running(&id);
+            let rag_lock = rag_state.read().await;
+            let result = match rag_lock.as_ref() {
+                Some(rag) => save_document(rag, request).await,
+                None => Err(not_ready()),
+            };
+            match result {
+                Ok(response) => jobs.mark_saved(&id, &response.message, response.saved),
+                Err(e) => {
+                    eprintln!("Ingestion job {} failed: {}", id, e.message);
+                    jobs.mark_failed(&id, &e.message);
+                }
+            }
+        });
+
+        Ok((
+            StatusCode::ACCEPTED,
+            [(header::LOCATION, status_url.clone())],
+            Json(AcceptedResponse {
+                job_id,
+                status: JobStatus::Queued,
+                status_url,
+            }),
+        )
+            .into_response())
+    }
+
+    /// Add or update a document, waiting for chunking and embedding.
+    async fn save_document(
+        rag: &RagPipeline,
+        request: DocumentRequest,
+    ) -> Result<SuccessResponse, ApiError> {
+        println!(
+            "Processing document: title='{}', url={:?}",
+            request.title.chars().take(60).collect::<String>(),
+            request.url.as_deref()
+        );
+
+        // Check if this URL already exists (update instead of duplicate)
+        if let Some(saved) = update_existing(rag, &request).await? {
+            let message = match saved.status {
+                SaveStatus::AlreadyExists => "Document already saved.",
+                _ => "Document updated successfully.",
+            };
+            return Ok(SuccessResponse {
+                message: message.to_string(),
+                extraction_method: request.extraction_method,
+                saved,
+            });
+        }
+
+        let doc_id = rag
+            .ingest_document_with_auth(
+                &request.title,
+                &request.content,
+                request.url.as_deref(),
+                "chrome_extension",
+                None,
+                false,
+                Some(&request.structure),
+            )
+            .await
+            .map_err(|e| ApiError {
+                status: StatusCode::INTERNAL_SERVER_ERROR,
+                message: format!("Failed to add document: {}", e),
+            })?;
+        store_page_metadata(rag, doc_id, &request).await;
+
+        Ok(SuccessResponse {
+            message: "Document added successfully.".to_string(),
+            extraction_method: request.extraction_method,
+            saved: SavedDocument {
+                status: SaveStatus::Created,
+                document_id: doc_id,
+                saved_at: None,
+                last_indexed_at: None,
+            },
+        })
+    }
+
+    /// Update the document already saved from the request's URL, if any:
+    /// it is re-indexed when its title or content changed and left alone
+    /// otherwise. `None` for a URL not saved yet, or only in the trash: the
+    /// save then creates a new document and the trashed one stays put.
+    async fn update_existing(
+        rag: &RagPipeline,
+        request: &DocumentRequest,
+    ) -> Result<Option<SavedDocument>, ApiError> {
+        let Some(ref url) = request.url else {
+            return Ok(None);
+        };
+        let Ok(Some(existing_doc)) = rag.db.get_document_by_url(url).await else {
+            return Ok(None);
+        };
+        let last_indexed_at = rag
+            .db
+            .get_last_indexed_at(existing_doc.id)
+            .await
+            .unwrap_or_else(|e| {
+                eprintln!("Failed to look up index time: {}", e);
+                None
+            });
+
+        let status =
+            if existing_doc.title == request.title && existing_doc.content == request.content {
+                println!(
+                    "Document already exists for URL {}, unchanged (id={})",
+                    url, existing_doc.id
+                );
+                SaveStatus::AlreadyExists
+            } else {
+                println!(
+                    "Document already exists for URL {}, updating (id={})",
+                    url, existing_doc.id
+                );
+                if let Err(e) = rag
+                    .store_document_structure(existing_doc.id, &request.content, &request.structure)
+                    .await
+                {
+                    eprintln!(
+                        "Failed to store structure of document {}: {}",
+                        existing_doc.id, e
+                    );
+                }
+                rag.update_document(existing_doc.id, &request.title, &request.content)
+                    .await
+                    .map_err(|e| ApiError {
+                        status: StatusCode::INTERNAL_SERVER_ERROR,
+                        message: format!("Failed to update document: {}", e),
+                    })?;
+                store_page_metadata(rag, existing_doc.id, request).await;
+                SaveStatus::Updated
+            };
+
+        Ok(Some(SavedDocument {
+            status,
+            document_id: existing_doc.id,
+            saved_at: Some(existing_doc.created_at),
+            last_indexed_at,
+        }))
+    }
+
+    /// Record the saved page's outgoing links and preview image; a failure
+    /// here does not fail the save.
+    async fn store_page_metadata(rag: &RagPipeline, doc_id: i64, request: &DocumentRequest) {
+        if let Err(e) = rag
+            .store_document_links(
+                doc_id,
+                request.url.as_deref(),
+                &request.content,
+                &request.links,
+            )
+            .await
+        {
+            eprintln!("Failed to store links for document {}: {}", doc_id, e);
+        }
+        crate::thumbnail::record_preview_image(&rag.db, doc_id, request.image_url.as_deref()).await;
+    }
+
+    /// Most documents accepted in one batch request
+    const MAX_BATCH_DOCUMENTS: usize = 100;
+
+    /// What happened to one document of a batch
+    #[derive(Serialize, ToSchema)]
+    #[serde(rename_all = "snake_case")]
+    enum BatchItemStatus {
+        Created,
+        Updated,
+        AlreadyExists,
+        Failed,
+    }
+
+    /// Outcome for one document of a batch, at its position in the request
+    #[derive(Serialize, ToSchema)]
+    struct BatchItemResult {
+        index: usize,
+        status: BatchItemStatus,
+        /// Document id, unless the item failed
+        id: Option<i64>,
+        /// Why the item failed
+        error: Option<String>,
+    }
+
+    impl BatchItemResult {
+        fn saved(index: usize, status: BatchItemStatus, id: i64) -> Self {
+            Self {
+                index,
+                status,
+                id: Some(id),
+                error: None,
+            }
+        }
+
+        fn failed(index: usize, error: impl Into<String>) -> Self {
+            Self {
+                index,
+                status: BatchItemStatus::Failed,
+                id: None,
+                error: Some(error.into()),
+            }
+        }
+    }
+
+    /// Add or update several documents at once, e.g. a whole tab group
+    #[utoipa::path(
+        post,
+        path = "/api/v1/documents/batch",
+        request_body = [DocumentRequest],
+        responses(
+            (status = 200, description = "Per-document outcome, in request order", body = [BatchItemResult]),
+            (status = 400, description = "Empty or oversized batch", body = MessageResponse),
+            (status = 503, description = "Still starting up", body = MessageResponse),
+        )
+    )]
+    async fn handle_post_documents_batch(
+        State(state): State<AppState>,
+        Json(requests): Json<Vec<DocumentRequest>>,
+    ) -> Result<Json<Vec<BatchItemResult>>, ApiError> {
+        if requests.is_empty() || requests.len() > MAX_BATCH_DOCUMENTS {
+            return Err(ApiError {
+                status: StatusCode::BAD_REQUEST,
+                message: format!(
+                    "A batch holds between 1 and {} documents.",
+                    MAX_BATCH_DOCUMENTS
+                ),
+            });
+        }
+
+        let rag_lock = state.rag_state.read().await;
+        let rag = rag_lock.as_ref().ok_or_else(not_ready)?;
+
+        // Updates and invalid items are settled one by one; new documents
+        // are kept for a single batched ingest
+        let mut results = Vec::with_capacity(requests.len());
+        let mut new_documents: Vec<(usize, DocumentRequest)> = Vec::new();
+        let mut seen_urls = std::collections::HashSet::new();
+        for (index, mut request) in requests.into_iter().enumerate() {
+            if request.title.is_empty() || request.content.is_empty() {
+                results.push(BatchItemResult::failed(
+                    index,
+                    "Title and content are required.",
+                ));
+                continue;
+            }
+            if let Err(e) = extract_content(&state.cleaners, &mut request) {
+                results.push(BatchItemResult::failed(index, e.message));
+                continue;
+            }
+            if let Some(ref url) = request.url {
+                if !seen_urls.insert(rag.db.normalize_url(url)) {
+                    results.push(BatchItemResult::failed(
+                        index,
+                        "Same URL as an earlier document in this batch.",
+                    ));
+                    continue;
+                }
+            }
+            match update_existing(rag, &request).await {
+                Ok(Some(saved)) => {
+                    let status = match saved.status {
+                        SaveStatus::AlreadyExists => BatchItemStatus::AlreadyExists,
+                        _ => BatchItemStatus::Updated,
+                    };
+                    results.push(BatchItemResult::saved(index, status, saved.document_id))
+                }
+                Ok(None) => new_documents.push((index, request)),
+                Err(e) => results.push(BatchItemResult::failed(index, e.message)),
+            }
+        }
+
+        if !new_documents.is_empty() {
+            let entries: Vec<(&str, &str, Option<&str>)> = new_documents
+                .iter()
+                .map(|(_, r)| (r.title.as_str(), r.content.as_str(), r.url.as_deref()))
+                .collect();
+            match rag.ingest_documents(&entries, "chrome_extension").await {
+                Ok(ids) => {
+                    for ((index, request), id) in new_documents.iter().zip(ids) {
+                        if let Err(e) = rag
+                            .store_document_structure(id, &request.content, &request.structure)
+                            .await
+                        {
+                            eprintln!("Failed to store structure of document {}: {}", id, e);
+                        }
+                        store_page_metadata(rag, id, request).await;
+                        results.push(BatchItemResult::saved(*index, BatchItemStatus::Created, id));
+                    }
+                }
+                Err(e) => {
+                    // One bad document fails the batch; retry each on its own
+                    eprintln!("Batch ingest failed, adding one by one: {}", e);
+                    for (index, request) in &new_documents {
+                        match rag
+                            .ingest_document_with_auth(
+                                &request.title,
+                                &request.content,
+                                request.url.as_deref(),
+                                "chrome_extension",
+                                None,
+                                false,
+                                Some(&request.structure),
+                            )
+                            .await
+                        {
+                            Ok(id) => {
+                                store_page_metadata(rag, id, request).await;
+                                results.push(BatchItemResult::saved(
+                                    *index,
+                                    BatchItemStatus::Created,
+                                    id,
+                                ));
+                            }
+                            Err(e) => results.push(BatchItemResult::failed(
+                                *index,
+                                format!("Failed to add document: {}", e),
+                            )),
+                        }
+                    }
+                }
+            }
+        }
+
+        results.sort_by_key(|result| result.index);
+        Ok(Json(results))
+    }
+
+    /// Status of a queued document
+    #[utoipa::path(
+        get,
+        path = "/api/v1/jobs/{id}",
+        params(("id" = String, Path, description = "Job id from the 202 response")),
+        responses(
+            (status = 200, description = "Job status", body = IngestJob),
+            (status = 404, description = "Unknown or expired job", body = MessageResponse),
+        )
+    )]
+    async fn handle_get_job(
+        State(state): State<AppState>,
+        Path(id): Path<String>,
+    ) -> Result<Json<IngestJob>, ApiError> {
+        state.jobs.get(&id).map(Json).ok_or_else(|| ApiError {
+            status: StatusCode::NOT_FOUND,
+            message: format!("Job {} not found.", id),
+        })
+    }
+
+    #[derive(Deserialize, IntoParams)]
+    #[into_params(parameter_in = Query)]
+    struct SearchParams {
+        /// Query; accepts the same operators as the search box
+        q: String,
+        #[serde(default)]
+        include_dead: bool,
+        #[serde(default)]
+        expand: bool,
+        /// Collection ID to scope the search to
+        collection: Option<i64>,
+        /// Search the query as typed, without spelling correction
+        #[serde(default)]
+        exact: bool,
+    }
+
+    fn not_ready() -> ApiError {
+        ApiError {
+            status: StatusCode::SERVICE_UNAVAILABLE,
+            message: "System initializing. Please wait.".to_string(),
+        }
+    }
+
+    fn internal_error(context: &str, e: impl std::fmt::Display) -> ApiError {
+        ApiError {
+            status: StatusCode::INTERNAL_SERVER_ERROR,
+            message: format!("{}: {}", context, e),
+        }
+    }
+
+    /// Largest request body accepted
+    const MAX_BODY_BYTES: usize = 10 * 1024 * 1024;
+
+    /// Routes clients can call, as (method, path); those under `/api/v1` are
+    /// also served unprefixed
+    const ENDPOINTS: &[(&str, &str)] = &[
+        ("GET", "/api/v1/capabilities"),
+        ("POST", "/api/v1/documents"),
+        ("POST", "/api/v1/documents/batch"),
+        ("DELETE", "/api/v1/documents/:id"),
+        ("GET", "/api/v1/search"),
+        ("GET", "/api/v1/suggest"),
+        ("GET", "/api/v1/related"),
+        ("GET", "/api/v1/ws"),
+        ("GET", "/api/v1/stats"),
+        ("GET", "/api/v1/jobs/:id"),
+        ("GET", "/metrics"),
+    ];
+
+    /// What this server supports, for clients to adapt to it
+    #[derive(Serialize, ToSchema)]
+    #[serde(rename_all = "camelCase")]
+    struct Capabilities {
+        /// Version of the desktop app
+        app_version: String,
+        /// Newest API version, the prefix of `endpoints`
+        api_version: String,
+        endpoints: Vec<Endpoint>,
+        auth: AuthRequirements,
+        limits: Limits,
+        /// Values `content_type` of a saved document may take
+        content_types: Vec<String>,
+    }
+
+    #[derive(Serialize, ToSchema)]
+    struct Endpoint {
+        method: String,
+        path: String,
+    }
+
+    /// How requests authenticate
+    #[derive(Serialize, ToSchema)]
+    struct AuthRequirements {
+        required: bool,
+        /// Scheme to use when required, e.g. "bearer"
+        scheme: Option<String>,
+    }
+
+    #[derive(Serialize, ToSchema)]
+    #[serde(rename_all = "camelCase")]
+    struct Limits {
+        max_body_bytes: usize,
+        max_batch_documents: usize,
+        /// Results `suggest` and `related` return without a `limit`
+        default_popup_results: usize,
+        max_popup_results: usize,
+    }
+
+    /// App version, endpoints, authentication and limits, so clients can
+    /// check what the server supports instead of assuming it
+    #[utoipa::path(
+        get,
+        path = "/api/v1/capabilities",
+        responses(
+            (status = 200, description = "What this server supports", body = Capabilities),
+        )
+    )]
+    async fn handle_capabilities() -> Json<Capabilities> {
+        Json(Capabilities {
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            api_version: "v1".to_string(),
+            endpoints: ENDPOINTS
+                .iter()
+                .map(|(method, path)| Endpoint {
+                    method: method.to_string(),
+                    path: path.to_string(),
+                })
+                .collect(),
+            // The full API only listens on localhost
+            auth: AuthRequirements {
+                required: false,
+                scheme: None,
+            },
+            limits: Limits {
+                max_body_bytes: MAX_BODY_BYTES,
+                max_batch_documents: MAX_BATCH_DOCUMENTS,
+                default_popup_results: POPUP_DEFAULT_LIMIT,
+                max_popup_results: POPUP_MAX_LIMIT,
+            },
+            content_types: vec!["text/plain".to_string(), "text/html".to_string()],
+        })
+    }
+
+    /// Index counts
+    #[utoipa::path(
+        get,
+        path = "/api/v1/stats",
+        responses(
+            (status = 200, description = "Document, chunk, dead-link, per-source and migration counts", body = IndexStats),
+            (status = 503, description = "Still starting up", body = MessageResponse),
+        )
+    )]
+    async fn handle_get_stats(
+        State(state): State<AppState>,
+    ) -> Result<Json<crate::db::IndexStats>, ApiError> {
+        let rag_lock = state.rag_state.read().await;
+        let rag = rag_lock.as_ref().ok_or_else(not_ready)?;
+        let stats = rag
+            .get_index_stats()
+            .await
+            .map_err(|e| internal_error("Failed to read stats", e))?;
+        Ok(Json(stats))
+    }
+
+    /// Fused keyword and semantic search
+    #[utoipa::path(
+        get,
+        path = "/api/v1/search",
+        params(SearchParams),
+        responses(
+            (status = 200, description = "Results, best first", body = [DocumentSource]),
+            (status = 400, description = "Empty query", body = MessageResponse),
+            (status = 503, description = "Still starting up", body = MessageResponse),
+        )
+    )]
+    async fn handle_search(
+        State(state): State<AppState>,
+        Query(params): Query<SearchParams>,
+    ) -> Result<Json<Vec<crate::rag::DocumentSource>>, ApiError> {
+        if params.q.trim().is_empty() {
+            return Err(ApiError {
+                status: StatusCode::BAD_REQUEST,
+                message: "Query parameter 'q' is required.".to_string(),
+            });
+        }
+
+        let rag_lock = state.rag_state.read().await;
+        let rag = rag_lock.as_ref().ok_or_else(not_ready)?;
+        let options = crate::rag::SearchOptions {
+            include_dead: params.include_dead,
+            expand_query: params.expand,
+            collection_id: params.collection,
+            boost_linked: false,
+            correct_spelling: !params.exact,
+        };
+        let hits = rag
+            .get_search_hits_fused(params.q.trim(), options)
+            .await
+            .map_err(|e| internal_error("Search failed", e))?;
+        if let Err(e) = rag.db.record_search(params.q.trim(), hits.len()).await {
+            eprintln!("Failed to record search: {}", e);
+        }
+        Ok(Json(hits))
+    }
+
+    /// Results returned by the popup endpoints when no limit is given
+    const POPUP_DEFAULT_LIMIT: usize = 8;
+    /// Most results the popup endpoints return
+    const POPUP_MAX_LIMIT: usize = 20;
+
+    #[derive(Deserialize, IntoParams)]
+    #[into_params(parameter_in = Query)]
+    struct SuggestParams {
+        /// Start of a title word or URL, as typed
+        q: String,
+        limit: Option<usize>,
+    }
+
+    /// Saved documents whose title or URL starts with the typed text
+    #[utoipa::path(
+        get,
+        path = "/api/v1/suggest",
+        params(SuggestParams),
+        responses(
+            (status = 200, description = "Title matches, then URL matches", body = [DocumentSuggestion]),
+            (status = 503, description = "Still starting up", body = MessageResponse),
+        )
+    )]
+    async fn handle_suggest(
+        State(state): State<AppState>,
+        Query(params): Query<SuggestParams>,
+    ) -> Result<Json<Vec<crate::db::DocumentSuggestion>>, ApiError> {
+        let limit = params
+            .limit
+            .unwrap_or(POPUP_DEFAULT_LIMIT)
+            .min(POPUP_MAX_LIMIT);
+        let rag_lock = state.rag_state.read().await;
+        let rag = rag_lock.as_ref().ok_or_else(not_ready)?;
+        let suggestions = rag
+            .db
+            .get_document_suggestions(&params.q, limit)
+            .await
+            .map_err(|e| internal_error("Suggestions failed", e))?;
+        Ok(Json(suggestions))
+    }
+
+    #[derive(Deserialize, IntoParams)]
+    #[into_params(parameter_in = Query)]
+    struct RelatedParams {
+        /// URL of the page being viewed
+        url: String,
+        /// Page title; with `summary`, what related documents are judged by
+        #[serde(default)]
+        title: String,
+        /// Page description or first paragraph
+        #[serde(default)]
+        summary: String,
+        limit: Option<usize>,
+    }
+
+    /// Saved documents similar to the page being viewed
+    #[utoipa::path(
+        get,
+        path = "/api/v1/related",
+        params(RelatedParams),
+        responses(
+            (status = 200, description = "Related documents, best first", body = [DocumentSource]),
+            (status = 400, description = "Missing URL", body = MessageResponse),
+            (status = 503, description = "Still starting up", body = MessageResponse),
+        )
+    )]
+    async fn handle_related(
+        State(state): State<AppState>,
+        Query(params): Query<RelatedParams>,
+    ) -> Result<Json<Vec<crate::rag::DocumentSource>>, ApiError> {
+        if params.url.trim().is_empty() {
+            return Err(ApiError {
+                status: StatusCode::BAD_REQUEST,
+                message: "Query parameter 'url' is required.".to_string(),
+            });
+        }
+        let limit = params
+            .limit
+            .unwrap_or(POPUP_DEFAULT_LIMIT)
+            .min(POPUP_MAX_LIMIT);
+        let text = format!("{}\n{}", params.title.trim(), params.summary.trim());
+
+        let rag_lock = state.rag_state.read().await;
+        let rag = rag_lock.as_ref().ok_or_else(not_ready)?;
+        let related = rag
+            .get_related_to_page(params.url.trim(), &text, limit)
+            .await
+            .map_err(|e| internal_error("Related search failed", e))?;
+        Ok(Json(related))
+    }
+
+    /// A query sent over the live search socket; options as for `/search`
+    #[derive(Deserialize)]
+    struct LiveSearchRequest {
+        q: String,
+        #[serde(default)]
+        include_dead: bool,
+        collection: Option<i64>,
+        #[serde(default)]
+        exact: bool,
+    }
+
+    /// One message streamed back for a live search, tagged by `stage`.
+    /// Each carries the query it answers so clients can drop stale results.
+    #[derive(Serialize)]
+    #[serde(tag = "stage", rename_all = "lowercase")]
+    enum LiveSearchEvent {
+        /// Full-text matches, available almost at once
+        Keyword {
+            q: String,
+            results: Vec<crate::rag::DocumentSource>,
+        },
+        /// Vector matches, once the query is embedded
+        Semantic {
+            q: String,
+            results: Vec<crate::rag::DocumentSource>,
+        },
+        /// The fused ranking, as `/search` returns it
+        Final {
+            q: String,
+            results: Vec<crate::rag::DocumentSource>,
+            corrected_query: Option<String>,
+        },
+        Error {
+            q: Option<String>,
+            message: String,
+        },
+    }
+
+    /// Live search: send JSON queries, receive results stage by stage.
+    /// Browsers do not apply CORS to WebSockets, so the origin is checked
+    /// before upgrading.
+    async fn handle_ws(
+        ws: WebSocketUpgrade,
+        headers: axum::http::HeaderMap,
+        State(state): State<AppState>,
+    ) -> Response {
+        if !origin_allowed(&headers) {
+            return StatusCode::FORBIDDEN.into_response();
+        }
+        ws.on_upgrade(move |socket| live_search(socket, state.rag_state))
+    }
+
+    async fn live_search(mut socket: WebSocket, rag_state: RagState) {
+        let (events_tx, mut events_rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut search: Option<tokio::task::JoinHandle<()>> = None;
+        loop {
+            tokio::select! {
+                message = socket.recv() => {
+                    let text = match message {
+                        Some(Ok(Message::Text(text))) => text,
+                        Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                        Some(Ok(_)) => continue,
+                    };
+                    // Each keystroke supersedes the search still running
+                    if let Some(previous) = search.take() {
+                        previous.abort();
+                    }
+                    match serde_json::from_str::<LiveSearchRequest>(&text) {
+                        Ok(request) => {
+                            search = Some(tokio::spawn(run_live_search(
+                                rag_state.clone(),
+                                request,
+                                events_tx.clone(),
+                            )));
+                        }
+                        Err(e) => {
+                            let _ = events_tx.send(LiveSearchEvent::Error {
+                                q: None,
+                                message: format!("Invalid search request: {}", e),
+                            });
+                        }
+                    }
+                }
+                Some(event) = events_rx.recv() => {
+                    let Ok(json) = serde_json::to_string(&event) else {
+                        continue;
+                    };
+                    if socket.send(Message::Text(json)).await.is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+        if let Some(search) = search {
+            search.abort();
+        }
+    }
+
+    async fn run_live_search(
+        rag_state: RagState,
+        request: LiveSearchRequest,
+        events: tokio::sync::mpsc::UnboundedSender<LiveSearchEvent>,
+    ) {
+        let q = request.q.trim().to_string();
+        let send = |event| {
+            let _ = events.send(event);
+        };
+        if q.is_empty() {
+            send(LiveSearchEvent::Error {
+                q: Some(q),
+                message: "Field 'q' is required.".to_string(),
+            });
+            return;
+        }
+
+        let rag_lock = rag_state.read().await;
+        let Some(rag) = rag_lock.as_ref() else {
+            send(LiveSearchEvent::Error {
+                q: Some(q),
+                message: not_ready().message,
+            });
+            return;
+        };
+        let options = crate::rag::SearchOptions {
+            include_dead: request.include_dead,
+            expand_query: false,
+            collection_id: request.collection,
+            boost_linked: false,
+            correct_spelling: !request.exact,
+        };
+
+        match rag.get_keyword_hits(&q, options).await {
+            Ok(results) => send(LiveSearchEvent::Keyword {
+                q: q.clone(),
+                results,
+            }),
+            Err(e) => eprintln!("Live keyword search failed: {}", e),
+        }
+        match rag.get_semantic_hits(&q, options).await {
+            Ok(results) => send(LiveSearchEvent::Semantic {
+                q: q.clone(),
+                results,
+            }),
+            Err(e) => eprintln!("Live semantic search failed: {}", e),
+        }
+
+        // The query embedding is cached by now, so fusion mostly re-ranks
+        match rag.search_fused(&q, options).await {
+            Ok(fused) => {
+                if let Err(e) = rag.db.record_search(&q, fused.hits.len()).await {
+                    eprintln!("Failed to record search: {}", e);
+                }
+                send(LiveSearchEvent::Final {
+                    q,
+                    results: fused.hits,
+                    corrected_query: fused.corrected_query,
+                });
+            }
+            Err(e) => send(LiveSearchEvent::Error {
+                q: Some(q),
+                message: format!("Search failed: {}", e),
+            }),
+        }
+    }
+
+    /// Delete a document and its vectors
+    #[utoipa::path(
+        delete,
+        path = "/api/v1/documents/{id}",
+        params(("id" = i64, Path, description = "Document id")),
+        responses(
+            (status = 200, description = "Deleted", body = MessageResponse),
+            (status = 404, description = "No such document", body = MessageResponse),
+        )
+    )]
+    async fn handle_delete_document(
+        State(state): State<AppState>,
+        Path(id): Path<i64>,
+    ) -> Result<Json<MessageResponse>, ApiError> {
+        let rag_lock = state.rag_state.read().await;
+        let rag = rag_lock.as_ref().ok_or_else(not_ready)?;
+
+        let exists = rag
+            .db
+            .get_document(id)
+            .await
+            .map_err(|e| internal_error("Failed to look up document", e))?
+            .is_some();
+        if !exists {
+            return Err(ApiError {
+                status: StatusCode::NOT_FOUND,
+                message: format!("Document {} not found.", id),
+            });
+        }
+
+        rag.db
+            .delete_document(id)
+            .await
+            .map_err(|e| internal_error("Failed to delete document", e))?;
+        rag.remove_document_vectors(id).await;
+
+        Ok(Json(MessageResponse {
+            message: "Document deleted successfully.".to_string(),
+        }))
+    }
+
+    #[derive(OpenApi)]
+    #[openapi(
+        info(title = "LocalMind API", version = "1"),
+        paths(
+            handle_capabilities,
+            handle_post_documents,
+            handle_post_documents_batch,
+            handle_get_job,
+            handle_search,
+            handle_suggest,
+            handle_related,
+            handle_get_stats,
+            handle_delete_document
+        ),
+        components(schemas(
+            Capabilities,
+            Endpoint,
+            AuthRequirements,
+            Limits,
+            DocumentRequest,
+            SuccessResponse,
+            SavedDocument,
+            SaveStatus,
+            AcceptedResponse,
+            BatchItemResult,
+            BatchItemStatus,
+            MessageResponse,
+            IngestJob,
+            JobStatus,
+            crate::rag::DocumentSource,
+            crate::db::DocumentSuggestion,
+            crate::db::IndexStats
+        ))
+    )]
+    struct ApiDoc;
+
+    /// Prometheus metrics, with the queue depths read at scrape time
+    async fn handle_metrics(State(state): State<AppState>) -> Response {
+        let Some(handle) = crate::monitoring::handle() else {
+            return (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "Metrics recorder unavailable",
+            )
+                .into_response();
+        };
+
+        crate::monitoring::set_queue_depth("api", state.jobs.pending_count());
+        if let Some(ref rag) = *state.rag_state.read().await {
+            let mut queued = 0;
+            for status in [
+                crate::ingest_queue::QueueStatus::Pending,
+                crate::ingest_queue::QueueStatus::InProgress,
+            ] {
+                queued += rag
+                    .db
+                    .get_queue_items_by_status(&status)
+                    .await
+                    .map_or(0, |items| items.len());
+            }
+            crate::monitoring::set_queue_depth("bookmarks", queued);
+        }
+
+        (
+            [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+            handle.render(),
+        )
+            .into_response()
+    }
+
+    async fn handle_openapi() -> Json<utoipa::openapi::OpenApi> {
+        Json(ApiDoc::openapi())
+    }
+
+    /// Swagger UI for trying the API from a browser. The page loads the
+    /// Swagger assets from a CDN; the API itself stays on this machine.
+    async fn handle_api_docs() -> Html<&'static str> {
+        Html(
+            r##"<!DOCTYPE html>
+<html>
+<head>
+  <meta charset="utf-8">
+  <title>LocalMind API</title>
+  <link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@5/swagger-ui.css">
+</head>
+<body>
+  <div id="swagger-ui"></div>
+  <script src="https://unpkg.com/swagger-ui-dist@5/swagger-ui-bundle.js"></script>
+  <script>
+    SwaggerUIBundle({ url: "/openapi.json", dom_id: "#swagger-ui" });
+  </script>
+</body>
+</html>"##,
+        )
+    }
+
+    /// Resolve once the port or bind address settings differ from `current`
+    async fn settings_changed(
+        rag_state: RagState,
+        current: crate::http_settings::HttpServerSettings,
+    ) {
+        loop {
+            tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+            if load_http_server_settings(&rag_state).await != current {
+                return;
+            }
+        }
+    }
+
+    /// Listen on the first of the configured ports that is free
+    async fn bind(
+        settings: &crate::http_settings::HttpServerSettings,
+    ) -> Result<TcpListener, String> {
+        let ip = settings.ip();
+        let mut last_error = None;
+        for port in settings.ports() {
+            match TcpListener::bind((ip, port)).await {
+                Ok(listener) => return Ok(listener),
+                Err(e) => last_error = Some(e),
+            }
+        }
+        let ports = settings.ports();
+        let error = last_error.map(|e| e.to_string()).unwrap_or_default();
+        Err(if ports.start() == ports.end() {
+            format!("Cannot listen on {}:{}: {}", ip, ports.start(), error)
+        } else {
+            format!(
+                "No free port on {} in range {}-{}: {}",
+                ip,
+                ports.start(),
+                ports.end(),
+                error
+            )
+        })
+    }
+
+    // Drain histogram samples between scrapes
+    if let Some(handle) = crate::monitoring::handle() {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(crate::monitoring::UPKEEP_INTERVAL);
+            loop {
+                interval.tick().await;
+                handle.run_upkeep();
+            }
+        });
+    }
+
+    let cors = CorsLayer::new()
+        .allow_origin(AllowOrigin::predicate(|origin, _| {
+            origin
+                .to_str()
+                .is_ok_and(crate::http_settings::is_allowed_origin)
+        }))
+        .allow_methods([Method::GET, Method::POST, Method::OPTIONS])
+        .allow_headers([header::CONTENT_TYPE])
+        .expose_headers([header::LOCATION]);
+
+    let app_state = AppState {
+        rag_state: rag_state.clone(),
+        jobs: JobRegistry::new(),
+        cleaners: Arc::new(CleanerPipeline::default()),
+    };
+
+    // Routes that read or delete the index only answer the extension
+    let private = Router::new()
+        .route("/documents/:id", delete(handle_delete_document))
+        .route("/search", get(handle_search))
+        .route("/suggest", get(handle_suggest))
+        .route("/related", get(handle_related))
+        .route("/stats", get(handle_get_stats))
+        .route("/jobs/:id", get(handle_get_job))
+        .route_layer(middleware::from_fn(require_allowed_origin));
+
+    let api = Router::new()
+        .route("/capabilities", get(handle_capabilities))
+        .route("/documents", post(handle_post_documents))
+        .route("/documents/batch", post(handle_post_documents_batch))
+        .route("/ws", get(handle_ws))
+        .merge(private);
+
+    // The unversioned paths stay as aliases of v1 for clients written before
+    // the API was versioned
+    let app = Router::new()
+        .nest("/api/v1", api.clone())
+        .merge(api)
+        .route("/openapi.json", get(handle_openapi))
+        .route("/docs", get(handle_api_docs))
+        .route(
+            "/metrics",
+            get(handle_metrics).route_layer(middleware::from_fn(require_allowed_origin)),
+        )
+        .layer(middleware::from_fn_with_state(request_log, log_request))
+        .layer(
+            ServiceBuilder::new()
+                .layer(axum::extract::DefaultBodyLimit::max(MAX_BODY_BYTES))
+                .layer(cors),
+        )
+        .with_state(app_state);
+
+    let stop = tokio_util::sync::CancellationToken::new();
+    tokio::spawn({
+        let stop = stop.clone();
+        async move {
+            shutdown.await;
+            stop.cancel();
+        }
+    });
+
+    // Serve until the port or bind address setting changes, then rebind
+    while !stop.is_cancelled() {
+        let settings = load_http_server_settings(&rag_state).await;
+        let changed = settings_changed(rag_state.clone(), settings.clone());
+        match bind(&settings).await {
+            Ok(listener) => {
+                let address = listener.local_addr()?;
+                let stopping = stop.clone();
+                let shutdown = async move {
+                    tokio::select! {
+                        _ = changed => {}
+                        _ = stopping.cancelled() => {}
+                    }
+                };
+                if !settings.uses_tls() {
+                    println!("HTTP server listening on http://{}", address);
+                    status.set_listening(address);
+                    axum::serve(listener, app.clone())
+                        .with_graceful_shutdown(shutdown)
+                        .await?;
+                    continue;
+                }
+
+                // Reachable from other machines: never served in cleartext
+                let tls = match crate::tls::self_signed_files(&crate::db::data_dir()) {
+                    Ok((cert, key)) => crate::tls::rustls_config(&cert, &key).await,
+                    Err(e) => Err(e),
+                };
+                match tls {
+                    Ok(config) => {
+                        println!("HTTP server listening on https://{}", address);
+                        status.set_listening(address);
+                        serve_tls(listener, app.clone(), config, shutdown).await?;
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to set up TLS for the HTTP server: {}", e);
+                        drop(listener);
+                        status.set_failed(format!("Cannot serve over HTTPS: {}", e));
+                        shutdown.await;
+                    }
+                }
+            }
+            Err(e) => {
+                eprintln!("Failed to start HTTP server: {}", e);
+                status.set_failed(e);
+                tokio::select! {
+                    _ = changed => {}
+                    _ = stop.cancelled() => {}
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Current HTTP API settings; the defaults until the database is up.
+async fn load_http_server_settings(
+    rag_state: &RagState,
+) -> crate::http_settings::HttpServerSettings {
+    let rag_lock = rag_state.read().await;
+    match *rag_lock {
+        Some(ref rag) => rag.db.get_http_server_settings().await.unwrap_or_default(),
+        None => Default::default(),
+    }
+}
+
+/// Current remote access settings; the defaults (off) until the database is up.
+async fn load_remote_access_settings(
+    rag_state: &RagState,
+) -> crate::remote_access::RemoteAccessSettings {
+    let rag_lock = rag_state.read().await;
+    match *rag_lock {
+        Some(ref rag) => rag
+            .db
+            .get_remote_access_settings()
+            .await
+            .unwrap_or_default(),
+        None => Default::default(),
+    }
+}
+
+/// Run the read-only LAN server whenever remote access is enabled.
+///
+/// Settings are checked every few seconds; when they change the server is
+/// stopped (letting in-flight searches finish) and started again with the
+/// new port and token, or left stopped if remote access was turned off.
+pub async fn start_remote_access_server(rag_state: RagState) {
+    use tokio::net::TcpListener;
+
+    async fn settings_changed(
+        rag_state: RagState,
+        current: crate::remote_access::RemoteAccessSettings,
+    ) {
+        loop {
+            tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+            if load_remote_access_settings(&rag_state).await != current {
+                return;
+            }
+        }
+    }
+
+    loop {
+        let settings = load_remote_access_settings(&rag_state).await;
+        if !settings.is_active() {
+            settings_changed(rag_state.clone(), settings).await;
+            continue;
+        }
+
+        match TcpListener::bind(("0.0.0.0", settings.port)).await {
+            Ok(listener) => {
+                let app = crate::remote_access::router(rag_state.clone(), settings.token.clone());
+                let tls = match settings.tls.certificate_files(&crate::db::data_dir()) {
+                    Ok(Some((cert, key))) => crate::tls::rustls_config(&cert, &key).await.map(Some),
+                    Ok(None) => Ok(None),
+                    Err(e) => Err(e),
+                };
+                let result = match tls {
+                    Ok(Some(config)) => {
+                        println!(
+                            "Remote access listening on port {} over HTTPS (read-only, token required)",
+                            settings.port
+                        );
+                        serve_tls(
+                            listener,
+                            app,
+                            config,
+                            settings_changed(rag_state.clone(), settings),
+                        )
+                        .await
+                    }
+                    Ok(None) => {
+                        println!(
+                            "Remote access listening on port {} (read-only, token required)",
+                            settings.port
+                        );
+                        let shutdown = settings_changed(rag_state.clone(), settings);
+                        axum::serve(listener, app)
+                            .with_graceful_shutdown(shutdown)
+                            .await
+                            .map_err(Into::into)
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to set up TLS for remote access: {}", e);
+                        drop(listener);
+                        settings_changed(rag_state.clone(), settings).await;
+                        continue;
+                    }
+                };
+                if let Err(e) = result {
+                    eprintln!("Remote access server failed: {}", e);
+                }
+                println!("Remote access server stopped");
+            }
+            Err(e) => {
+                eprintln!(
+                    "Failed to start remote access on port {}: {}",
+                    settings.port, e
+                );
+                settings_changed(rag_state.clone(), settings).await;
+            }
+        }
+    }
+}
+
+/// Serve `app` over TLS on `listener` until `shutdown` resolves, then let
+/// in-flight requests finish.
+async fn serve_tls<F>(
+    listener: tokio::net::TcpListener,
+    app: axum::Router,
+    config: axum_server::tls_rustls::RustlsConfig,
+    shutdown: F,
+) -> crate::Result<()>
+where
+    F: std::future::Future<Output = ()> + Send + 'static,
+{
+    let handle = axum_server::Handle::new();
+    let stopper = tokio::spawn({
+        let handle = handle.clone();
+        async move {
+            shutdown.await;
+            handle.graceful_shutdown(Some(std::time::Duration::from_secs(10)));
+        }
+    });
+    let result = axum_server::from_tcp_rustls(listener.into_std()?, config)
+        .handle(handle)
+        .serve(app.into_make_service())
+        .await;
+    stopper.abort();
+    Ok(result?)
+}
+
+/// Run LocalMind as a server without the GUI.
+///
+/// Starts the HTTP server, initializes the RAG system, then runs bookmark
+/// ingestion and dead-link checking in the background until Ctrl+C. On
+/// shutdown the HTTP server drains in-flight requests and any embedding
+/// server we spawned is stopped.
+pub fn run_headless() -> crate::Result<()> {
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()?;
+
+    runtime.block_on(async {
+        let rag_state: RagState = Arc::new(RwLock::new(None));
+        let shutdown = tokio_util::sync::CancellationToken::new();
+
+        // Start the HTTP server first so clients get 503 instead of connection errors
+        let http_shutdown = shutdown.clone();
+        let rag_state_for_http = rag_state.clone();
+        let http_handle = tokio::spawn(async move {
+            if let Err(e) = start_http_server(
+                rag_state_for_http,
+                crate::request_log::RequestLog::new(),
+                crate::http_settings::HttpServerStatus::new(),
+                async move { http_shutdown.cancelled().await },
+            )
+            .await
+            {
+                eprintln!("Failed to start HTTP server: {}", e);
+            }
+        });
+
+        // Server status only goes to the log without a GUI
+        let (server_status_tx, server_status_rx) =
+            std::sync::mpsc::channel::<EmbeddingServerStatus>();
+        std::thread::spawn(move || {
+            for status in server_status_rx {
+                println!("{}", status.label());
+            }
+        });
+
+        let embedding_server = tokio::select! {
+            result = init_rag_system(server_status_tx) => {
+                let (rag, supervisor) = result?;
+                *rag_state.write().await = Some(rag);
+                println!("RAG system initialized successfully");
+                supervisor
+            }
+            _ = tokio::signal::ctrl_c() => {
+                println!("Interrupted during startup, shutting down");
+                shutdown.cancel();
+                let _ = http_handle.await;
+                return Ok(());
+            }
+        };
+
+        // Progress is only consumed by the GUI; both loops log to stdout
+        let (bookmark_progress_tx, _bookmark_progress_rx) = std::sync::mpsc::channel();
+        let rag_for_bookmarks = rag_state.clone();
+        let import_progress_tx = bookmark_progress_tx.clone();
+        let watch_shutdown = shutdown.clone();
+        tokio::spawn(async move {
+            let watch = match start_bookmark_monitoring(
+                rag_for_bookmarks.clone(),
+                bookmark_progress_tx.clone(),
+                None,
+            )
+            .await
+            {
+                Ok(watch) => watch,
+                Err(e) => {
+                    eprintln!("Failed to start bookmark monitoring: {}", e);
+                    false
+                }
+            };
+            // Resume a bookmark file import interrupted by the last shutdown
+            ingest_imported_bookmarks(rag_for_bookmarks.clone(), import_progress_tx).await;
+            if watch {
+                // Watcher errors are already logged
+                let (watch_error_tx, _watch_error_rx) = std::sync::mpsc::channel();
+                watch_bookmark_profiles(
+                    rag_for_bookmarks,
+                    bookmark_progress_tx,
+                    watch_error_tx,
+                    watch_shutdown,
+                )
+                .await;
+            }
+        });
+
+        // Problems only go to the log without a GUI
+        let (integrity_tx, _integrity_rx) = std::sync::mpsc::channel();
+        let rag_for_audit = rag_state.clone();
+        tokio::spawn(async move {
+            start_consistency_check(rag_for_audit.clone()).await;
+            start_integrity_audit(rag_for_audit, integrity_tx).await;
+        });
+
+        // Upgrades from keyword-only mode when the embedding backend returns;
+        // events only go to the log without a GUI
+        let (event_tx, _event_rx) = std::sync::mpsc::channel();
+        let (semantic_tx, _semantic_rx) = std::sync::mpsc::channel();
+        tokio::spawn(start_health_monitor(
+            rag_state.clone(),
+            event_tx,
+            semantic_tx,
+        ));
+
+        let (link_check_tx, _link_check_rx) = std::sync::mpsc::channel();
+        tokio::spawn(start_link_check_scheduler(rag_state.clone(), link_check_tx));
+
+        let (retention_tx, _retention_rx) = std::sync::mpsc::channel();
+        tokio::spawn(start_retention_scheduler(rag_state.clone(), retention_tx));
+
+        let (maintenance_tx, _maintenance_rx) = std::sync::mpsc::channel();
+        tokio::spawn(start_maintenance_scheduler(
+            rag_state.clone(),
+            maintenance_tx,
+        ));
+
+        let (sync_tx, _sync_rx) = std::sync::mpsc::channel();
+        tokio::spawn(start_sync_scheduler(rag_state.clone(), sync_tx));
+
+        let (snapshot_tx, _snapshot_rx) = std::sync::mpsc::channel();
+        tokio::spawn(start_snapshot_scheduler(rag_state.clone(), snapshot_tx));
+
+        // Fired reminders only go to the log without a GUI
+        let (reminder_tx, reminder_rx) = std::sync::mpsc::channel();
+        tokio::spawn(start_reminder_scheduler(rag_state.clone(), reminder_tx));
+        std::thread::spawn(move || {
+            for due in reminder_rx {
+                for reminder in due {
+                    println!("Reminder: {}", reminder.title);
+                }
+            }
+        });
+
+        tokio::spawn(start_language_detector(rag_state.clone()));
+
+        let (migration_tx, _migration_rx) = std::sync::mpsc::channel();
+        tokio::spawn(start_embedding_migration(rag_state.clone(), migration_tx));
+
+        tokio::spawn(start_remote_access_server(rag_state.clone()));
+
+        println!("LocalMind running headless. Press Ctrl+C to stop.");
+        tokio::signal::ctrl_c().await?;
+
+        println!("Shutting down...");
+        shutdown.cancel();
+        let _ = http_handle.await;
+
+        if let Some(supervisor) = embedding_server {
+            supervisor.shutdown();
+        }
+
+        Ok(())
+    })
+}
@@ -0,0 +1,361 @@
+//! Scheduled snapshots
+//!
+//! On a daily or weekly schedule, the open library is written to a folder
+//! the user picks: a JSONL file with one document per line (the same fields
+//! as `localmind-cli export`) and/or a copy of the SQLite database made with
+//! `VACUUM INTO`. File names carry the library's database name and a UTC
+//! timestamp, e.g. `localmind-20261016-093000.jsonl`, and only the newest
+//! snapshots of each kind are kept. Snapshots can also be taken from
+//! Settings at any time.
+
+use crate::db::Database;
+use crate::Result;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Snapshots of each kind kept by default.
+pub const DEFAULT_KEEP: usize = 7;
+
+/// Guards against the scheduler and a manual run overlapping
+static SNAPSHOT_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// How often scheduled snapshots are taken.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SnapshotFrequency {
+    #[default]
+    Daily,
+    Weekly,
+}
+
+impl SnapshotFrequency {
+    pub const ALL: [SnapshotFrequency; 2] = [SnapshotFrequency::Daily, SnapshotFrequency::Weekly];
+
+    pub fn interval_secs(self) -> u64 {
+        match self {
+            SnapshotFrequency::Daily => 24 * 60 * 60,
+            SnapshotFrequency::Weekly => 7 * 24 * 60 * 60,
+        }
+    }
+}
+
+/// Snapshot settings edited in Settings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SnapshotSettings {
+    /// Take snapshots on the schedule
+    pub enabled: bool,
+    /// Folder the snapshots are written to
+    pub folder: String,
+    pub frequency: SnapshotFrequency,
+    /// Write the documents as JSONL
+    pub jsonl: bool,
+    /// Write a copy of the database
+    pub database: bool,
+    /// Snapshots of each kind to keep; older ones are deleted
+    pub keep: usize,
+}
+
+impl Default for SnapshotSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            folder: String::new(),
+            frequency: SnapshotFrequency::Daily,
+            jsonl: true,
+            database: false,
+            keep: DEFAULT_KEEP,
+        }
+    }
+}
+
+impl SnapshotSettings {
+    /// Whether there is a folder and something to write to it.
+    pub fn is_configured(&self) -> bool {
+        !self.folder.trim().is_empty() && (self.jsonl || self.database)
+    }
+}
+
+/// Outcome of a snapshot run.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SnapshotReport {
+    /// Files written
+    pub files: Vec<PathBuf>,
+    /// Documents in the JSONL snapshot
+    pub documents: usize,
+    /// Older snapshots deleted to keep the configured number
+    pub removed: usize,
+}
+
+/// UTC timestamp used in snapshot file names: `YYYYMMDD-HHMMSS`.
+fn file_stamp(unix_secs: u64) -> String {
+    let days = (unix_secs / 86_400) as i64;
+    let secs = unix_secs % 86_400;
+
+    // Civil date from days since 1970-01-01 (proleptic Gregorian)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}{:02}{:02}-{:02}{:02}{:02}",
+        year,
+        month,
+        day,
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60
+    )
+}
+
+/// Whether `file_name` is a snapshot of the library named `prefix` with
+/// the given extension, as written by `run_snapshot`.
+fn is_snapshot_file(file_name: &str, prefix: &str, extension: &str) -> bool {
+    let Some(stamp) = file_name
+        .strip_prefix(prefix)
+        .and_then(|rest| rest.strip_prefix('-'))
+        .and_then(|rest| rest.strip_suffix(extension))
+        .and_then(|rest| rest.strip_suffix('.'))
+    else {
+        return false;
+    };
+    stamp.len() == 15
+        && stamp.char_indices().all(|(i, c)| match i {
+            8 => c == '-',
+            _ => c.is_ascii_digit(),
+        })
+}
+
+/// Delete all but the newest `keep` snapshots of one kind in `folder`,
+/// returning how many were deleted.
+fn prune_snapshots(folder: &Path, prefix: &str, extension: &str, keep: usize) -> Result<usize> {
+    let mut snapshots: Vec<String> = std::fs::read_dir(folder)?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| is_snapshot_file(name, prefix, extension))
+        .collect();
+    // Timestamps sort chronologically as text
+    snapshots.sort();
+
+    let excess = snapshots.len().saturating_sub(keep.max(1));
+    for name in &snapshots[..excess] {
+        std::fs::remove_file(folder.join(name))?;
+    }
+    Ok(excess)
+}
+
+/// Write every document to `path` as JSONL, returning the document count.
+/// Dead and trashed documents are included; `deleted_at` is when a trashed
+/// one was moved to the trash (Unix seconds), or null.
+async fn write_jsonl(db: &Database, path: &Path) -> Result<usize> {
+    let documents = db.get_documents_for_export().await?;
+    let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+    for (doc, deleted_at) in &documents {
+        let line = serde_json::json!({
+            "id": doc.id,
+            "title": doc.title,
+            "url": doc.url,
+            "source": doc.source,
+            "created_at": doc.created_at,
+            "profile": doc.profile,
+            "is_dead": doc.is_dead.unwrap_or(false),
+            "needs_auth": doc.needs_auth.unwrap_or(false),
+            "deleted_at": deleted_at,
+            "content": doc.content,
+        });
+        serde_json::to_writer(&mut file, &line)?;
+        file.write_all(b"\n")?;
+    }
+    file.flush()?;
+    Ok(documents.len())
+}
+
+/// Take a snapshot of the open library now, then delete old snapshots.
+/// Fails if another run is already in progress.
+///
+/// Files are written under a temporary name and renamed when complete, so
+/// an interrupted run never leaves a partial snapshot behind.
+pub async fn run_snapshot(db: &Database, settings: &SnapshotSettings) -> Result<SnapshotReport> {
+    if !settings.is_configured() {
+        return Err("Choose a folder and what to include in snapshots".into());
+    }
+    if SNAPSHOT_RUNNING.swap(true, Ordering::SeqCst) {
+        return Err("A snapshot is already being taken".into());
+    }
+
+    let result = run_snapshot_inner(db, settings).await;
+    SNAPSHOT_RUNNING.store(false, Ordering::SeqCst);
+    result
+}
+
+async fn run_snapshot_inner(db: &Database, settings: &SnapshotSettings) -> Result<SnapshotReport> {
+    let folder = PathBuf::from(settings.folder.trim());
+    std::fs::create_dir_all(&folder)?;
+
+    let library = crate::library::LibraryRegistry::load().active;
+    let prefix = crate::library::database_path(&library)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "localmind".to_string());
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let stamp = file_stamp(now);
+
+    let mut report = SnapshotReport::default();
+    let kinds = [("jsonl", settings.jsonl), ("db", settings.database)];
+    for (extension, wanted) in kinds {
+        if !wanted {
+            continue;
+        }
+        let path = folder.join(format!("{}-{}.{}", prefix, stamp, extension));
+        let partial = path.with_extension(format!("{}.partial", extension));
+        if extension == "jsonl" {
+            report.documents = write_jsonl(db, &partial).await?;
+        } else {
+            db.backup_to(&partial).await?;
+        }
+        std::fs::rename(&partial, &path)?;
+        report.removed += prune_snapshots(&folder, &prefix, extension, settings.keep)?;
+        report.files.push(path);
+    }
+
+    db.set_snapshot_last_run().await?;
+    println!(
+        "Snapshot written to {} ({} files, {} old snapshots removed)",
+        folder.display(),
+        report.files.len(),
+        report.removed
+    );
+    Ok(report)
+}
+
+/// Return `true` if snapshots are on and the last one is older than the
+/// configured frequency.
+pub async fn is_snapshot_due(db: &Database) -> bool {
+    let Ok(settings) = db.get_snapshot_settings().await else {
+        return false;
+    };
+    if !settings.enabled || !settings.is_configured() {
+        return false;
+    }
+    let last_run = db.get_snapshot_last_run().await.ok().flatten().unwrap_or(0);
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    now.saturating_sub(last_run) >= settings.frequency.interval_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_names_carry_a_sortable_utc_stamp() {
+        assert_eq!(file_stamp(0), "19700101-000000");
+        // 2024-02-29 13:45:07 UTC
+        assert_eq!(file_stamp(1_709_214_307), "20240229-134507");
+
+        assert!(is_snapshot_file(
+            "localmind-20240229-134507.jsonl",
+            "localmind",
+            "jsonl"
+        ));
+        assert!(!is_snapshot_file(
+            "localmind-work-20240229-134507.jsonl",
+            "localmind",
+            "jsonl"
+        ));
+        assert!(!is_snapshot_file(
+            "localmind-20240229-134507.jsonl.partial",
+            "localmind",
+            "jsonl"
+        ));
+        assert!(!is_snapshot_file(
+            "localmind-notes.jsonl",
+            "localmind",
+            "jsonl"
+        ));
+    }
+
+    #[test]
+    fn only_the_newest_snapshots_are_kept() {
+        let dir = tempfile::TempDir::new().unwrap();
+        for name in [
+            "localmind-20240101-000000.jsonl",
+            "localmind-20240102-000000.jsonl",
+            "localmind-20240103-000000.jsonl",
+            "localmind-20240101-000000.db",
+            "localmind-work-20240101-000000.jsonl",
+            "notes.jsonl",
+        ] {
+            std::fs::write(dir.path().join(name), "").unwrap();
+        }
+
+        assert_eq!(
+            prune_snapshots(dir.path(), "localmind", "jsonl", 2).unwrap(),
+            1
+        );
+
+        let mut left: Vec<String> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        left.sort();
+        assert_eq!(
+            left,
+            vec![
+                "localmind-20240101-000000.db",
+                "localmind-20240102-000000.jsonl",
+                "localmind-20240103-000000.jsonl",
+                "localmind-work-20240101-000000.jsonl",
+                "notes.jsonl",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn jsonl_snapshots_keep_dead_and_trashed_documents() {
+        use crate::db::OperationPriority;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let db = Database::open(&dir.path().join("test.db")).await.unwrap();
+        for (title, is_dead) in [("Live", false), ("Dead", true), ("Trashed", false)] {
+            db.insert_document(
+                title,
+                "content",
+                None,
+                "test",
+                None,
+                Some(is_dead),
+                OperationPriority::BackgroundIngest,
+                None,
+            )
+            .await
+            .unwrap();
+        }
+        db.trash_documents(&[3]).await.unwrap();
+
+        let path = dir.path().join("snapshot.jsonl");
+        assert_eq!(write_jsonl(&db, &path).await.unwrap(), 3);
+        let lines: Vec<serde_json::Value> = std::fs::read_to_string(&path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines[0]["is_dead"], false);
+        assert!(lines[0]["deleted_at"].is_null());
+        assert_eq!(lines[1]["is_dead"], true);
+        assert!(lines[2]["deleted_at"].is_i64());
+    }
+}
@@ -0,0 +1,162 @@
+//! Result snippets centered on the best-matching passage
+//!
+//! A snippet is a window of at most `max_chars` characters placed where the
+//! query words are densest, so the reason a result matched is visible even
+//! when the match is deep inside a chunk. Windows are measured in characters
+//! (never bytes, so multi-byte text cannot be split), widened to whole words,
+//! and marked with "..." on each side that was cut.
+
+/// Query words shorter than this are ignored when locating the best passage.
+const MIN_TERM_CHARS: usize = 2;
+
+/// Matches considered when choosing the window; beyond this, later
+/// occurrences are ignored.
+const MAX_MATCHES: usize = 1000;
+
+/// How far a window edge may move to land on a word boundary.
+const WORD_BOUNDARY_SLACK: usize = 20;
+
+/// Lowercase query words worth looking for in the content.
+fn query_terms(query: &str) -> Vec<Vec<char>> {
+    let mut terms: Vec<Vec<char>> = Vec::new();
+    for word in query.split(|c: char| !c.is_alphanumeric()) {
+        let term: Vec<char> = word.chars().map(lower).collect();
+        if term.len() >= MIN_TERM_CHARS && !terms.contains(&term) {
+            terms.push(term);
+        }
+    }
+    terms
+}
+
+/// Lowercase a character without changing the character count.
+fn lower(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+/// Start of the window that covers the most distinct query terms, as a
+/// character index, together with the end of the last match inside it.
+fn best_passage(text: &[char], terms: &[Vec<char>], max_chars: usize) -> Option<(usize, usize)> {
+    let lowered: Vec<char> = text.iter().copied().map(lower).collect();
+    let mut matches: Vec<(usize, usize, usize)> = Vec::new();
+    'scan: for start in 0..lowered.len() {
+        for (term_index, term) in terms.iter().enumerate() {
+            if lowered[start..].starts_with(term) {
+                matches.push((start, start + term.len(), term_index));
+                if matches.len() >= MAX_MATCHES {
+                    break 'scan;
+                }
+            }
+        }
+    }
+
+    let mut best: Option<(usize, usize, usize)> = None;
+    for (i, &(start, _, _)) in matches.iter().enumerate() {
+        let mut seen = vec![false; terms.len()];
+        let mut end = start;
+        for &(_, other_end, term_index) in &matches[i..] {
+            if other_end > start + max_chars {
+                break;
+            }
+            seen[term_index] = true;
+            end = end.max(other_end);
+        }
+        let distinct = seen.iter().filter(|s| **s).count();
+        match best {
+            Some((_, _, most)) if most >= distinct => {}
+            _ => best = Some((start, end, distinct)),
+        }
+    }
+    best.map(|(start, end, _)| (start, end))
+}
+
+/// Snippet of at most `max_chars` characters (plus ellipses) from `content`,
+/// centered on the passage matching the most words of `query`. Whitespace
+/// runs are collapsed. Without a match the snippet starts at the beginning.
+pub fn centered_snippet(content: &str, query: &str, max_chars: usize) -> String {
+    let text: Vec<char> = content
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .collect();
+    if text.len() <= max_chars {
+        return text.into_iter().collect();
+    }
+
+    let mut start = match best_passage(&text, &query_terms(query), max_chars) {
+        Some((match_start, match_end)) => {
+            let center = (match_start + match_end) / 2;
+            center
+                .saturating_sub(max_chars / 2)
+                .min(text.len() - max_chars)
+        }
+        None => 0,
+    };
+    let mut end = start + max_chars;
+
+    // Avoid cutting words in half
+    if start > 0 && !text[start - 1].is_whitespace() {
+        if let Some(offset) = text[start..]
+            .iter()
+            .take(WORD_BOUNDARY_SLACK)
+            .position(|c| c.is_whitespace())
+        {
+            start += offset + 1;
+        }
+    }
+    if end < text.len() && !text[end].is_whitespace() {
+        if let Some(offset) = text[start..end]
+            .iter()
+            .rev()
+            .take(WORD_BOUNDARY_SLACK)
+            .position(|c| c.is_whitespace())
+        {
+            end -= offset + 1;
+        }
+    }
+
+    let passage: String = text[start..end].iter().collect();
+    format!(
+        "{}{}{}",
+        if start > 0 { "..." } else { "" },
+        passage.trim(),
+        if end < text.len() { "..." } else { "" }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn centers_on_densest_match() {
+        let content = format!(
+            "{} the tokio runtime schedules async tasks {}",
+            "intro words ".repeat(20),
+            "closing words ".repeat(20)
+        );
+        let snippet = centered_snippet(&content, "Tokio async", 60);
+        assert!(snippet.starts_with("..."));
+        assert!(snippet.ends_with("..."));
+        assert!(snippet.contains("tokio runtime schedules async"));
+        assert!(snippet.chars().count() <= 66);
+
+        // No match: the beginning, cut at a word boundary
+        let snippet = centered_snippet(&content, "python", 30);
+        assert!(snippet.starts_with("intro words"));
+        assert!(snippet.ends_with("intro..."));
+
+        // Short content is returned whole, whitespace collapsed
+        assert_eq!(centered_snippet("  a\n\n b ", "a", 10), "a b");
+    }
+
+    #[test]
+    fn never_splits_multibyte_text() {
+        let content = "日本語のテキスト".repeat(40) + " 検索 " + &"émoji 🎉 ".repeat(40);
+        for max in [1, 7, 50, 120] {
+            let snippet = centered_snippet(&content, "検索", max);
+            assert!(snippet.chars().count() <= max + 6);
+        }
+        assert!(centered_snippet(&content, "検索", 50).contains("検索"));
+    }
+}
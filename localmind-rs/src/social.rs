@@ -0,0 +1,563 @@
+//! Threads on X (Twitter) and Mastodon
+//!
+//! A post's page is an empty shell filled in by JavaScript, so fetching it
+//! gives next to no text. Post URLs are read through the sites' public
+//! endpoints instead and indexed as the whole thread: the author's own
+//! posts leading up to the saved one and, on Mastodon, the ones following
+//! it. Replies from other accounts are left out.
+//!
+//! X is read through the embed (syndication) endpoint, which needs no
+//! account but only knows the post a tweet replies to, so a tweet's thread
+//! is followed back to its start. Mastodon (and servers speaking its API)
+//! is read through `/api/v1/statuses`, which gives both directions.
+
+use crate::fetcher::FetchResult;
+use crate::html_text::{self, PageLink, PageStructure};
+use serde::Deserialize;
+use std::time::Duration;
+use url::Url;
+
+/// Most posts kept from a thread.
+pub const MAX_THREAD_POSTS: usize = 50;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A post URL on X or Mastodon
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PostUrl {
+    Tweet { id: String },
+    Toot { instance: String, id: String },
+}
+
+impl PostUrl {
+    /// The post a URL links to. Mastodon servers can have any domain, so
+    /// they are recognized by their `/@user/123` and
+    /// `/users/user/statuses/123` paths.
+    pub fn parse(url: &str) -> Option<Self> {
+        let parsed = Url::parse(url).ok()?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return None;
+        }
+        let host = parsed.host_str()?.to_ascii_lowercase();
+        let segments: Vec<&str> = parsed
+            .path_segments()?
+            .filter(|segment| !segment.is_empty())
+            .collect();
+        let is_id = |id: &str| !id.is_empty() && id.chars().all(|c| c.is_ascii_digit());
+
+        let host_name = host.strip_prefix("www.").unwrap_or(&host);
+        let host_name = host_name.strip_prefix("mobile.").unwrap_or(host_name);
+        if matches!(host_name, "twitter.com" | "x.com") {
+            return match segments.as_slice() {
+                [_, "status" | "statuses", id, ..] | ["i", "web", "status", id, ..]
+                    if is_id(id) =>
+                {
+                    Some(PostUrl::Tweet { id: id.to_string() })
+                }
+                _ => None,
+            };
+        }
+
+        match segments.as_slice() {
+            [user, id] if user.len() > 1 && user.starts_with('@') && is_id(id) => {
+                Some(PostUrl::Toot {
+                    instance: host,
+                    id: id.to_string(),
+                })
+            }
+            ["users", _, "statuses", id] if is_id(id) => Some(PostUrl::Toot {
+                instance: host,
+                id: id.to_string(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Fetch the thread a post belongs to.
+pub async fn fetch(post: &PostUrl) -> crate::Result<FetchResult> {
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .user_agent("LocalMind/1.0")
+        .build()?;
+    match post {
+        PostUrl::Tweet { id } => fetch_tweet_thread(&client, id).await,
+        PostUrl::Toot { instance, id } => fetch_toot_thread(&client, instance, id).await,
+    }
+}
+
+/// A post of a thread as text, with its links and images
+struct ThreadPost {
+    text: String,
+    links: Vec<PageLink>,
+    image_url: Option<String>,
+}
+
+/// The thread as a document: who wrote it, then each post a blank line
+/// apart. The title is the author and the start of the first post.
+fn thread_result(author: &str, posts: Vec<ThreadPost>) -> FetchResult {
+    let mut content = format!("Thread by {}", author);
+    let mut structure = PageStructure::default();
+    let mut image_url = None;
+    for post in &posts {
+        content.push_str("\n\n");
+        content.push_str(post.text.trim());
+        structure.links.extend(post.links.iter().cloned());
+        if image_url.is_none() {
+            image_url.clone_from(&post.image_url);
+        }
+    }
+    let first_line = posts
+        .first()
+        .and_then(|post| post.text.lines().find(|line| !line.trim().is_empty()))
+        .unwrap_or_default()
+        .trim();
+    let mut title = format!(
+        "{}: {}",
+        author,
+        first_line.chars().take(80).collect::<String>()
+    );
+    if first_line.chars().count() > 80 {
+        title.push('…');
+    }
+    FetchResult {
+        structure: structure.located(&content),
+        content,
+        needs_auth: false,
+        image_url,
+        title: Some(title),
+    }
+}
+
+// --- X (Twitter) ---
+
+const SYNDICATION_URL: &str = "https://cdn.syndication.twimg.com/tweet-result";
+
+#[derive(Debug, Deserialize)]
+struct Tweet {
+    #[serde(default)]
+    text: String,
+    user: TweetUser,
+    in_reply_to_status_id_str: Option<String>,
+    #[serde(default)]
+    entities: TweetEntities,
+    #[serde(default, rename = "mediaDetails")]
+    media: Vec<TweetMedia>,
+    quoted_tweet: Option<Box<Tweet>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TweetUser {
+    id_str: String,
+    name: String,
+    screen_name: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct TweetEntities {
+    #[serde(default)]
+    urls: Vec<TweetUrl>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TweetUrl {
+    url: String,
+    expanded_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TweetMedia {
+    media_url_https: Option<String>,
+    ext_alt_text: Option<String>,
+}
+
+/// The token the embed endpoint expects for a tweet, as embedded tweets
+/// compute it: `(id / 1e15 * π)` in base 36 without zeros or the point.
+fn syndication_token(id: &str) -> String {
+    let value = id.parse::<f64>().unwrap_or_default() / 1e15 * std::f64::consts::PI;
+    to_base36(value).replace(['0', '.'], "")
+}
+
+/// A non-negative number in base 36 the way JavaScript's
+/// `Number.prototype.toString(36)` writes it: the shortest fraction that
+/// reads back as the same number.
+fn to_base36(value: f64) -> String {
+    const DIGITS: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+    let digit_of = |c: u8| usize::from(if c > b'9' { c - b'a' + 10 } else { c - b'0' });
+
+    let mut integer = value.floor();
+    let mut fraction = value - integer;
+    let mut delta = (0.5 * (f64::from_bits(value.to_bits() + 1) - value)).max(f64::from_bits(1));
+    let mut fraction_digits: Vec<u8> = Vec::new();
+    if fraction >= delta {
+        loop {
+            fraction *= 36.0;
+            delta *= 36.0;
+            let digit = fraction as usize;
+            fraction_digits.push(DIGITS[digit]);
+            fraction -= digit as f64;
+            if (fraction > 0.5 || (fraction == 0.5 && digit & 1 == 1)) && fraction + delta > 1.0 {
+                // Round up, carrying into the integer part when every
+                // digit was the last one
+                loop {
+                    match fraction_digits.pop() {
+                        None => {
+                            integer += 1.0;
+                            break;
+                        }
+                        Some(last) if digit_of(last) + 1 < 36 => {
+                            fraction_digits.push(DIGITS[digit_of(last) + 1]);
+                            break;
+                        }
+                        Some(_) => {}
+                    }
+                }
+                break;
+            }
+            if fraction < delta {
+                break;
+            }
+        }
+    }
+
+    let mut integer_digits = Vec::new();
+    loop {
+        let remainder = integer % 36.0;
+        integer_digits.push(DIGITS[remainder as usize]);
+        integer = (integer - remainder) / 36.0;
+        if integer <= 0.0 {
+            break;
+        }
+    }
+    integer_digits.reverse();
+    let mut text = String::from_utf8(integer_digits).unwrap_or_default();
+    if !fraction_digits.is_empty() {
+        text.push('.');
+        text.push_str(&String::from_utf8(fraction_digits).unwrap_or_default());
+    }
+    text
+}
+
+async fn fetch_tweet(client: &reqwest::Client, id: &str) -> crate::Result<Option<Tweet>> {
+    let response = client
+        .get(SYNDICATION_URL)
+        .query(&[
+            ("id", id),
+            ("lang", "en"),
+            ("token", &syndication_token(id)),
+        ])
+        .send()
+        .await?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !response.status().is_success() {
+        return Err(format!("X embed endpoint returned {} for {}", response.status(), id).into());
+    }
+    // Deleted and protected tweets come back as an empty object
+    Ok(response.json().await.ok())
+}
+
+/// A tweet's text with its short links expanded and its images' alt text,
+/// then the tweet it quotes.
+fn tweet_post(tweet: &Tweet) -> ThreadPost {
+    let mut text = tweet.text.clone();
+    let mut links = Vec::new();
+    for url in &tweet.entities.urls {
+        text = text.replace(&url.url, &url.expanded_url);
+        links.push(PageLink {
+            text: url.expanded_url.clone(),
+            href: url.expanded_url.clone(),
+        });
+    }
+    // Media links at the end of the text point back at the tweet itself
+    let text = text
+        .split_whitespace()
+        .filter(|word| !word.starts_with("https://t.co/"))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let mut text = html_text::decode_entities(&text);
+    for media in &tweet.media {
+        if let Some(alt) = media
+            .ext_alt_text
+            .as_deref()
+            .filter(|alt| !alt.trim().is_empty())
+        {
+            text.push_str(&format!("\n[Image: {}]", alt.trim()));
+        }
+    }
+    if let Some(quoted) = &tweet.quoted_tweet {
+        let quote = tweet_post(quoted);
+        text.push_str(&format!(
+            "\nQuoting @{}: {}",
+            quoted.user.screen_name, quote.text
+        ));
+        links.extend(quote.links);
+    }
+    ThreadPost {
+        text,
+        links,
+        image_url: tweet
+            .media
+            .iter()
+            .find_map(|media| media.media_url_https.clone()),
+    }
+}
+
+/// A tweet and the tweets by the same account it replies to, oldest first.
+async fn fetch_tweet_thread(client: &reqwest::Client, id: &str) -> crate::Result<FetchResult> {
+    let tweet = fetch_tweet(client, id)
+        .await?
+        .ok_or_else(|| format!("Tweet {} is not available", id))?;
+    let author = format!("{} (@{})", tweet.user.name, tweet.user.screen_name);
+    let author_id = tweet.user.id_str.clone();
+
+    let mut posts = vec![tweet_post(&tweet)];
+    let mut parent_id = tweet.in_reply_to_status_id_str.clone();
+    while let Some(id) = parent_id.take() {
+        if posts.len() >= MAX_THREAD_POSTS {
+            break;
+        }
+        match fetch_tweet(client, &id).await {
+            Ok(Some(parent)) if parent.user.id_str == author_id => {
+                posts.push(tweet_post(&parent));
+                parent_id = parent.in_reply_to_status_id_str.clone();
+            }
+            // A reply to someone else starts the author's thread
+            Ok(_) => {}
+            Err(e) => println!("Failed to fetch tweet {} of a thread: {}", id, e),
+        }
+    }
+    posts.reverse();
+    Ok(thread_result(&author, posts))
+}
+
+// --- Mastodon ---
+
+#[derive(Debug, Deserialize)]
+struct Status {
+    id: String,
+    in_reply_to_id: Option<String>,
+    #[serde(default)]
+    content: String,
+    #[serde(default)]
+    spoiler_text: String,
+    account: Account,
+    #[serde(default)]
+    media_attachments: Vec<Attachment>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Account {
+    id: String,
+    acct: String,
+    #[serde(default)]
+    display_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Attachment {
+    preview_url: Option<String>,
+    description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Context {
+    ancestors: Vec<Status>,
+    descendants: Vec<Status>,
+}
+
+async fn get_json<T: serde::de::DeserializeOwned>(
+    client: &reqwest::Client,
+    url: &str,
+) -> crate::Result<T> {
+    let response = client.get(url).send().await?;
+    if !response.status().is_success() {
+        return Err(format!("Mastodon API returned {} for {}", response.status(), url).into());
+    }
+    Ok(response.json().await?)
+}
+
+/// A status as text: its content warning, its content and its images' alt
+/// text.
+fn toot_post(status: &Status, base: &Url) -> ThreadPost {
+    let body = html_text::extract(&status.content, Some(base));
+    let mut text = String::new();
+    if !status.spoiler_text.trim().is_empty() {
+        text.push_str(&format!("CW: {}\n", status.spoiler_text.trim()));
+    }
+    text.push_str(body.text.trim());
+    for attachment in &status.media_attachments {
+        if let Some(description) = attachment
+            .description
+            .as_deref()
+            .filter(|description| !description.trim().is_empty())
+        {
+            text.push_str(&format!("\n[Image: {}]", description.trim()));
+        }
+    }
+    ThreadPost {
+        text,
+        links: body.structure.links,
+        image_url: status
+            .media_attachments
+            .iter()
+            .find_map(|attachment| attachment.preview_url.clone()),
+    }
+}
+
+/// The posts of `status`'s author in its thread, oldest first: the chain of
+/// their own posts it replies to, itself, and their replies to any of them.
+fn self_thread(status: Status, context: Context) -> Vec<Status> {
+    let author = status.account.id.clone();
+    // Ancestors come oldest first; keep the author's unbroken run before
+    // the status
+    let mut thread: Vec<Status> = context
+        .ancestors
+        .into_iter()
+        .rev()
+        .take_while(|ancestor| ancestor.account.id == author)
+        .collect();
+    thread.reverse();
+    thread.push(status);
+    for descendant in context.descendants {
+        let continues = descendant.account.id == author
+            && thread
+                .iter()
+                .any(|post| descendant.in_reply_to_id.as_ref() == Some(&post.id));
+        if continues {
+            thread.push(descendant);
+        }
+    }
+    thread.truncate(MAX_THREAD_POSTS);
+    thread
+}
+
+async fn fetch_toot_thread(
+    client: &reqwest::Client,
+    instance: &str,
+    id: &str,
+) -> crate::Result<FetchResult> {
+    let api = format!("https://{}/api/v1/statuses/{}", instance, id);
+    let status: Status = get_json(client, &api).await?;
+    let context: Context = get_json(client, &format!("{}/context", api))
+        .await
+        .unwrap_or(Context {
+            ancestors: Vec::new(),
+            descendants: Vec::new(),
+        });
+
+    let author = if status.account.display_name.trim().is_empty() {
+        format!("@{}", status.account.acct)
+    } else {
+        format!(
+            "{} (@{})",
+            status.account.display_name.trim(),
+            status.account.acct
+        )
+    };
+    let base = Url::parse(&format!("https://{}/", instance))?;
+    let posts = self_thread(status, context)
+        .iter()
+        .map(|status| toot_post(status, &base))
+        .collect();
+    Ok(thread_result(&author, posts))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_post_urls() {
+        assert_eq!(
+            PostUrl::parse("https://x.com/rustlang/status/1683920951807971329?s=20"),
+            Some(PostUrl::Tweet {
+                id: "1683920951807971329".to_string()
+            })
+        );
+        assert_eq!(
+            PostUrl::parse("https://mobile.twitter.com/i/web/status/20"),
+            Some(PostUrl::Tweet {
+                id: "20".to_string()
+            })
+        );
+        assert_eq!(
+            PostUrl::parse("https://hachyderm.io/@rust/111222333444"),
+            Some(PostUrl::Toot {
+                instance: "hachyderm.io".to_string(),
+                id: "111222333444".to_string()
+            })
+        );
+        assert_eq!(
+            PostUrl::parse("https://mastodon.social/users/rust/statuses/42"),
+            Some(PostUrl::Toot {
+                instance: "mastodon.social".to_string(),
+                id: "42".to_string()
+            })
+        );
+        assert_eq!(PostUrl::parse("https://x.com/rustlang"), None);
+        assert_eq!(
+            PostUrl::parse("https://medium.com/@writer/my-post-1a2b3c"),
+            None
+        );
+    }
+
+    #[test]
+    fn syndication_tokens_match_the_embed_script() {
+        // Values from JavaScript's `(id / 1e15 * Math.PI).toString(36)`
+        assert_eq!(
+            to_base36(1683920951807971329.0 / 1e15 * std::f64::consts::PI),
+            "42y.6z0v7ufp"
+        );
+        assert_eq!(syndication_token("1683920951807971329"), "42y6zv7ufp");
+        assert_eq!(syndication_token("1234567890123456789"), "2zqic77uqyk");
+        assert_eq!(syndication_token("20"), "6dq1a2xwd93");
+    }
+
+    fn status(id: &str, account: &str, in_reply_to_id: Option<&str>) -> Status {
+        Status {
+            id: id.to_string(),
+            in_reply_to_id: in_reply_to_id.map(str::to_string),
+            content: format!("<p>post {}</p>", id),
+            spoiler_text: String::new(),
+            account: Account {
+                id: account.to_string(),
+                acct: account.to_string(),
+                display_name: String::new(),
+            },
+            media_attachments: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn keeps_the_authors_own_thread() {
+        let context = Context {
+            ancestors: vec![
+                status("1", "other", None),
+                status("2", "me", Some("1")),
+                status("3", "me", Some("2")),
+            ],
+            descendants: vec![
+                status("5", "other", Some("4")),
+                status("6", "me", Some("4")),
+                status("7", "me", Some("5")),
+                status("8", "me", Some("6")),
+            ],
+        };
+        let thread = self_thread(status("4", "me", Some("3")), context);
+        let ids: Vec<&str> = thread.iter().map(|status| status.id.as_str()).collect();
+        assert_eq!(ids, vec!["2", "3", "4", "6", "8"]);
+
+        let base = Url::parse("https://example.social/").unwrap();
+        let posts = thread
+            .iter()
+            .map(|status| toot_post(status, &base))
+            .collect();
+        let result = thread_result("@me", posts);
+        assert!(result
+            .content
+            .starts_with("Thread by @me\n\npost 2\n\npost 3"));
+        assert_eq!(result.title.as_deref(), Some("@me: post 2"));
+    }
+}
@@ -0,0 +1,137 @@
+//! Spelling corrections for searches that found nothing
+//!
+//! Candidate words come from the full-text index vocabulary (an `fts5vocab`
+//! table over `documents_fts`), so suggestions are always words that occur in
+//! saved documents. A query word is corrected to the closest indexed term by
+//! edit distance, preferring terms found in more documents.
+
+/// Query words shorter than this are left alone.
+const MIN_WORD_LEN: usize = 3;
+
+/// Spelling suggestions offered for one query.
+pub const MAX_SUGGESTIONS: usize = 3;
+
+/// Largest edit distance accepted for a word of `len` characters.
+fn max_distance(len: usize) -> usize {
+    if len >= 6 {
+        2
+    } else {
+        1
+    }
+}
+
+/// Levenshtein distance between two strings, by characters.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+/// Whether `word` should be looked up for corrections at all.
+pub fn is_correctable(word: &str) -> bool {
+    word.chars().count() >= MIN_WORD_LEN && word.chars().all(char::is_alphabetic)
+}
+
+/// Ranked corrections for `word` among indexed `(term, document count)`
+/// candidates: closest first, then most common. Empty when the word itself is
+/// indexed or nothing is close enough.
+pub fn corrections(word: &str, candidates: &[(String, i64)]) -> Vec<String> {
+    if candidates.iter().any(|(term, _)| term == word) {
+        return Vec::new();
+    }
+
+    let limit = max_distance(word.chars().count());
+    let mut ranked: Vec<(usize, i64, &str)> = candidates
+        .iter()
+        .map(|(term, docs)| (edit_distance(word, term), *docs, term.as_str()))
+        .filter(|(distance, _, _)| *distance <= limit)
+        .collect();
+    ranked.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)).then(a.2.cmp(b.2)));
+    ranked
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, _, term)| term.to_string())
+        .collect()
+}
+
+/// Whole-query suggestions from per-word corrections (empty for words left
+/// as typed). The first suggestion uses each word's best correction; later
+/// ones swap in the runner-up corrections.
+pub fn suggest_queries(words: &[String], corrected: &[Vec<String>]) -> Vec<String> {
+    let mut suggestions: Vec<String> = Vec::new();
+    for rank in 0..MAX_SUGGESTIONS {
+        let mut changed = false;
+        let query = words
+            .iter()
+            .zip(corrected)
+            .map(|(word, options)| {
+                let option = options.get(rank).or(options.last());
+                changed |= option.is_some();
+                option.unwrap_or(word).as_str()
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        if changed && !suggestions.contains(&query) {
+            suggestions.push(query);
+        }
+    }
+    suggestions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vocab(terms: &[(&str, i64)]) -> Vec<(String, i64)> {
+        terms.iter().map(|(t, n)| (t.to_string(), *n)).collect()
+    }
+
+    #[test]
+    fn corrects_to_closest_common_term() {
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+
+        let candidates = vocab(&[
+            ("rust", 40),
+            ("rush", 5),
+            ("ruby", 12),
+            ("trust", 3),
+            ("rustic", 2),
+        ]);
+        assert_eq!(corrections("rsut", &candidates), Vec::<String>::new());
+        // All one edit away ("trust" is two), so the most common comes first
+        assert_eq!(
+            corrections("rusy", &candidates),
+            vec!["rust", "ruby", "rush"]
+        );
+        // One edit beats two, however common the farther term is
+        assert_eq!(corrections("rustik", &candidates), vec!["rustic", "rust"]);
+        // Already an indexed word: nothing to correct
+        assert!(corrections("ruby", &candidates).is_empty());
+
+        assert!(!is_correctable("go"));
+        assert!(!is_correctable("v1.2"));
+        assert!(is_correctable("tokio"));
+    }
+
+    #[test]
+    fn builds_query_suggestions() {
+        let words = vec!["asynk".to_string(), "rust".to_string()];
+        let corrected = vec![vec!["async".to_string(), "asyncio".to_string()], Vec::new()];
+        assert_eq!(
+            suggest_queries(&words, &corrected),
+            vec!["async rust", "asyncio rust"]
+        );
+        assert!(suggest_queries(&words, &[Vec::new(), Vec::new()]).is_empty());
+    }
+}
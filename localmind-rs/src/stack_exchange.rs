@@ -0,0 +1,190 @@
+//! Stack Overflow and other Stack Exchange question pages
+//!
+//! Readability keeps a question page's comments, vote buttons, related
+//! questions and sidebars as text, or settles on a single answer. The
+//! extractor registered for these sites in `fetcher` keeps the question and
+//! its best answers instead: the accepted answer first, then the highest
+//! voted, each under a heading with its score.
+
+use crate::html_text::{self, attribute, has_class, Heading, HtmlText, PageStructure};
+use url::Url;
+
+/// Most answers kept, counting the accepted one.
+pub const MAX_ANSWERS: usize = 3;
+
+/// A question or answer
+struct Post {
+    /// Its element's id, which links to it on the page
+    anchor: Option<String>,
+    score: i64,
+    accepted: bool,
+    body: HtmlText,
+}
+
+/// The question and best answers of a question page, or `None` for pages
+/// without a question (tag lists, user profiles, search).
+pub fn extract(html: &str, url: &Url) -> Option<HtmlText> {
+    let containers = html_text::elements(html, "div", |attributes| {
+        let id = attribute(attributes, "id").unwrap_or_default();
+        id == "question" || (id.starts_with("answer-") && has_class(attributes, "answer"))
+    });
+
+    let mut question = None;
+    let mut answers = Vec::new();
+    for container in containers {
+        // Comments sit next to the post body, not inside it
+        let Some(body) = html_text::elements(container.inner, "div", |attributes| {
+            has_class(attributes, "js-post-body") || has_class(attributes, "post-text")
+        })
+        .into_iter()
+        .next() else {
+            continue;
+        };
+        let anchor = attribute(container.attributes, "id");
+        let post = Post {
+            score: attribute(container.attributes, "data-score")
+                .and_then(|score| score.parse().ok())
+                .unwrap_or(0),
+            accepted: has_class(container.attributes, "accepted-answer"),
+            body: html_text::extract(body.inner, Some(url)),
+            anchor,
+        };
+        if post.anchor.as_deref() == Some("question") {
+            question.get_or_insert(post);
+        } else {
+            answers.push(post);
+        }
+    }
+    let question = question?;
+    answers.sort_by(|a, b| b.accepted.cmp(&a.accepted).then(b.score.cmp(&a.score)));
+    answers.truncate(MAX_ANSWERS);
+
+    let mut thread = HtmlText {
+        text: String::new(),
+        structure: PageStructure::default(),
+    };
+    if let Some(title) = question_title(html) {
+        push_heading(&mut thread, 1, &title, None);
+    }
+    push_post(&mut thread, "Question".to_string(), question);
+    for answer in answers {
+        let label = if answer.accepted {
+            format!("Accepted answer ({} votes)", answer.score)
+        } else {
+            format!("Answer ({} votes)", answer.score)
+        };
+        push_post(&mut thread, label, answer);
+    }
+    Some(thread)
+}
+
+/// The question's title from its `<h1>`.
+fn question_title(html: &str) -> Option<String> {
+    let heading = html_text::elements(html, "h1", |attributes| {
+        attribute(attributes, "itemprop").as_deref() == Some("name")
+    })
+    .into_iter()
+    .next()?;
+    let title = html_text::extract(heading.inner, None).text;
+    let title = title.trim();
+    (!title.is_empty()).then(|| title.to_string())
+}
+
+fn push_heading(thread: &mut HtmlText, level: u8, text: &str, anchor: Option<String>) {
+    if !thread.text.is_empty() {
+        thread.text.push_str("\n\n");
+    }
+    thread.structure.headings.push(Heading {
+        level,
+        text: text.to_string(),
+        offset: thread.text.len(),
+        anchor,
+    });
+    thread.text.push_str(text);
+}
+
+/// A post under `label`, with the headings and links of its body.
+fn push_post(thread: &mut HtmlText, label: String, post: Post) {
+    push_heading(thread, 2, &label, post.anchor);
+    let body = post.body.text.trim();
+    if body.is_empty() {
+        return;
+    }
+    thread.text.push('\n');
+    // Offsets in the body count from its untrimmed start
+    let leading = post.body.text.len() - post.body.text.trim_start().len();
+    let offset = thread.text.len();
+    thread.text.push_str(body);
+    for heading in post.body.structure.headings {
+        thread.structure.headings.push(Heading {
+            offset: (offset + heading.offset).saturating_sub(leading),
+            ..heading
+        });
+    }
+    thread.structure.links.extend(post.body.structure.links);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_the_question_and_best_answers() {
+        let html = r#"<html><body>
+            <h1 itemprop="name"><a href="/q/1">How do I sort a Vec?</a></h1>
+            <div id="question" class="question js-question" data-score="12">
+              <div class="s-prose js-post-body" itemprop="text"><p>I have a <code>Vec</code>.</p></div>
+              <div class="comments"><span class="comment-copy">Duplicate?</span></div>
+            </div>
+            <div id="answer-2" class="answer js-answer" data-score="3">
+              <div class="s-prose js-post-body"><p>Use a loop.</p></div>
+            </div>
+            <div id="answer-3" class="answer js-answer accepted-answer" data-score="1">
+              <div class="s-prose js-post-body"><p>Call <a href="https://doc.rust-lang.org/std/vec/struct.Vec.html#method.sort">sort</a>.</p></div>
+            </div>
+            <div id="answer-4" class="answer js-answer" data-score="7">
+              <div class="s-prose js-post-body"><p>Use <code>sort_unstable</code>.</p></div>
+            </div>
+            <div id="answer-5" class="answer js-answer" data-score="-2">
+              <div class="s-prose js-post-body"><p>Python.</p></div>
+            </div>
+            <div id="sidebar">Hot Network Questions</div>
+            </body></html>"#;
+        let url = Url::parse("https://stackoverflow.com/questions/1/sort").unwrap();
+        let thread = extract(html, &url).unwrap();
+        assert_eq!(
+            thread.text,
+            "How do I sort a Vec?\n\nQuestion\nI have a Vec.\n\n\
+             Accepted answer (1 votes)\nCall sort.\n\n\
+             Answer (7 votes)\nUse sort_unstable.\n\n\
+             Answer (3 votes)\nUse a loop."
+        );
+        let anchors: Vec<Option<&str>> = thread
+            .structure
+            .headings
+            .iter()
+            .map(|heading| heading.anchor.as_deref())
+            .collect();
+        assert_eq!(
+            anchors,
+            vec![
+                None,
+                Some("question"),
+                Some("answer-3"),
+                Some("answer-4"),
+                Some("answer-2")
+            ]
+        );
+        assert_eq!(thread.structure.located(&thread.text), thread.structure);
+        assert_eq!(
+            thread.structure.hrefs(),
+            vec!["https://doc.rust-lang.org/std/vec/struct.Vec.html#method.sort"]
+        );
+    }
+
+    #[test]
+    fn pages_without_a_question_are_left_to_readability() {
+        let url = Url::parse("https://stackoverflow.com/questions/tagged/rust").unwrap();
+        assert!(extract("<div class=\"question-summary\">x</div>", &url).is_none());
+    }
+}
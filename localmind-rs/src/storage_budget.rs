@@ -0,0 +1,214 @@
+//! Disk space guard and index size budget
+//!
+//! Before documents are ingested, the growth of the database is estimated
+//! from how much space the documents already indexed take per byte of
+//! content. Ingestion is refused once the configured maximum index size
+//! would be exceeded, and a warning is printed when the disk holding the
+//! data directory is nearly full. To get back under the limit, Settings
+//! suggests documents to prune: dead links first, then documents never
+//! opened, then the least recently used.
+
+use crate::notifications::LOW_DISK_SPACE_BYTES;
+use crate::Result;
+
+/// Database bytes per byte of content while the index is too small to
+/// measure: the stored text, its full-text index and chunk embeddings.
+const DEFAULT_BYTES_PER_CONTENT_BYTE: f64 = 4.0;
+
+/// Database bytes per document when nothing is indexed yet, for estimates
+/// made before the content is known (such as unfetched bookmarks).
+const DEFAULT_DOCUMENT_BYTES: u64 = 100 * 1024;
+
+/// Below this much indexed content the measured ratio is not trusted.
+const MIN_MEASURED_CONTENT_BYTES: u64 = 1024 * 1024;
+
+/// Space used by the index and left on its disk.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct StorageStatus {
+    /// Bytes of the database in use, not counting free pages
+    pub index_bytes: u64,
+    /// Total bytes of document content in the index
+    pub content_bytes: u64,
+    pub document_count: u64,
+    /// Free bytes on the disk holding the data directory, if known
+    pub free_bytes: Option<u64>,
+    /// Configured maximum index size; 0 means unlimited
+    pub max_index_bytes: u64,
+}
+
+impl StorageStatus {
+    /// Estimated database growth from indexing `content_bytes` of content.
+    pub fn estimate_growth(&self, content_bytes: u64) -> u64 {
+        let ratio = if self.content_bytes >= MIN_MEASURED_CONTENT_BYTES {
+            self.index_bytes as f64 / self.content_bytes as f64
+        } else {
+            DEFAULT_BYTES_PER_CONTENT_BYTE
+        };
+        (content_bytes as f64 * ratio) as u64
+    }
+
+    /// Estimated database growth from indexing `count` documents whose
+    /// content is not known yet.
+    pub fn estimate_documents(&self, count: usize) -> u64 {
+        let per_document = if self.content_bytes >= MIN_MEASURED_CONTENT_BYTES {
+            self.index_bytes / self.document_count.max(1)
+        } else {
+            DEFAULT_DOCUMENT_BYTES
+        };
+        per_document * count as u64
+    }
+
+    /// Whether the disk would be below `LOW_DISK_SPACE_BYTES` after
+    /// `growth` more bytes.
+    pub fn low_disk(&self, growth: u64) -> bool {
+        self.free_bytes
+            .is_some_and(|free| free.saturating_sub(growth) < LOW_DISK_SPACE_BYTES)
+    }
+
+    /// Bytes to prune so that `growth` more bytes fit in the budget.
+    pub fn bytes_over_budget(&self, growth: u64) -> u64 {
+        if self.max_index_bytes == 0 {
+            return 0;
+        }
+        (self.index_bytes + growth).saturating_sub(self.max_index_bytes)
+    }
+
+    /// Refuse growth beyond the budget; warn when the disk is nearly full.
+    pub fn check(&self, growth: u64) -> Result<()> {
+        if self.bytes_over_budget(growth) > 0 {
+            return Err(format!(
+                "Index size limit of {} reached ({} in use); prune documents in Settings > Storage or raise the limit",
+                format_bytes(self.max_index_bytes),
+                format_bytes(self.index_bytes)
+            )
+            .into());
+        }
+        if self.low_disk(growth) {
+            eprintln!(
+                "Disk space is low: {} free before adding about {}",
+                format_bytes(self.free_bytes.unwrap_or(0)),
+                format_bytes(growth)
+            );
+        }
+        Ok(())
+    }
+}
+
+/// A document suggested for pruning.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PruneCandidate {
+    pub id: i64,
+    pub title: String,
+    pub url: Option<String>,
+    pub is_dead: bool,
+    pub view_count: i64,
+    /// Last opened, or saved if never opened
+    pub last_used_at: String,
+    /// Approximate bytes freed by deleting the document
+    pub bytes: u64,
+}
+
+/// The first of `candidates` (already in pruning order) that together free
+/// at least `bytes_to_free`.
+pub fn suggest_pruning(candidates: Vec<PruneCandidate>, bytes_to_free: u64) -> Vec<PruneCandidate> {
+    let mut freed = 0;
+    candidates
+        .into_iter()
+        .take_while(|candidate| {
+            let needed = freed < bytes_to_free;
+            freed += candidate.bytes;
+            needed
+        })
+        .collect()
+}
+
+/// Human-readable size such as "12.3 MB".
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MB: u64 = 1024 * 1024;
+
+    fn candidate(id: i64, bytes: u64) -> PruneCandidate {
+        PruneCandidate {
+            id,
+            title: format!("Doc {}", id),
+            url: None,
+            is_dead: false,
+            view_count: 0,
+            last_used_at: "2024-01-01 00:00:00".to_string(),
+            bytes,
+        }
+    }
+
+    #[test]
+    fn estimates_growth_from_the_measured_ratio() {
+        let empty = StorageStatus::default();
+        assert_eq!(empty.estimate_growth(1000), 4000);
+        assert_eq!(empty.estimate_documents(2), 2 * DEFAULT_DOCUMENT_BYTES);
+
+        let measured = StorageStatus {
+            index_bytes: 30 * MB,
+            content_bytes: 10 * MB,
+            document_count: 120,
+            ..Default::default()
+        };
+        // 30 MB of index for 10 MB of content: 3 bytes per content byte
+        assert_eq!(measured.estimate_growth(1000), 3000);
+        // 30 MB over 120 documents is 256 KB each, so 10 take 2.5 MB
+        assert_eq!(measured.estimate_documents(10), 10 * 256 * 1024);
+    }
+
+    #[test]
+    fn enforces_the_budget() {
+        let mut status = StorageStatus {
+            index_bytes: 90 * MB,
+            free_bytes: Some(100 * 1024 * MB),
+            ..Default::default()
+        };
+        assert!(status.check(50 * MB).is_ok());
+
+        status.max_index_bytes = 100 * MB;
+        assert_eq!(status.bytes_over_budget(5 * MB), 0);
+        assert_eq!(status.bytes_over_budget(15 * MB), 5 * MB);
+        assert!(status.check(15 * MB).is_err());
+
+        assert!(!status.low_disk(MB));
+        status.free_bytes = Some(LOW_DISK_SPACE_BYTES + MB);
+        assert!(status.low_disk(2 * MB));
+    }
+
+    #[test]
+    fn suggests_just_enough_documents() {
+        let candidates = vec![candidate(1, 40), candidate(2, 40), candidate(3, 40)];
+        let ids = |c: Vec<PruneCandidate>| c.iter().map(|c| c.id).collect::<Vec<_>>();
+        assert_eq!(
+            ids(suggest_pruning(candidates.clone(), 0)),
+            Vec::<i64>::new()
+        );
+        assert_eq!(ids(suggest_pruning(candidates.clone(), 40)), vec![1]);
+        assert_eq!(ids(suggest_pruning(candidates.clone(), 41)), vec![1, 2]);
+        assert_eq!(ids(suggest_pruning(candidates, 1000)), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn formats_sizes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KB");
+        assert_eq!(format_bytes(3 * MB), "3.0 MB");
+    }
+}
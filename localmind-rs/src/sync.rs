@@ -0,0 +1,487 @@
+//! Encrypted sync between machines through a shared folder
+//!
+//! Each machine writes its whole index (documents, tags and chunk
+//! embeddings) to `<device id>.lmsync` in a folder the user already keeps in
+//! sync between machines, e.g. with Syncthing or Dropbox. Files are encrypted
+//! with a passphrase entered on every machine, so the folder's host only sees
+//! ciphertext. A sync run merges the other machines' files into the local
+//! index, matching documents by URL or, for documents without one, by a hash
+//! of their content. The most recent change to a document wins. Moving a
+//! document to the trash is a change like any other; documents purged from
+//! the trash simply stop being shared.
+//!
+//! Embeddings are copied when both machines use the same embedding model,
+//! otherwise merged documents are re-embedded locally.
+
+use crate::document::ChunkProvenance;
+use crate::rag::RagPipeline;
+use crate::Result;
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Seconds between scheduled sync runs.
+pub const SYNC_INTERVAL_SECS: u64 = 15 * 60;
+
+/// Extension of the per-device sync files.
+pub const FILE_EXTENSION: &str = "lmsync";
+
+/// Start of every sync file, followed by the salt, nonce and ciphertext.
+const MAGIC: &[u8; 8] = b"LMSYNC01";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+
+/// Guards against the scheduler and a manual run overlapping
+static SYNC_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Sync settings edited in Settings.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SyncSettings {
+    pub enabled: bool,
+    /// Folder shared between the machines
+    pub folder: String,
+    /// Passphrase the sync files are encrypted with; the same on every machine
+    pub passphrase: String,
+}
+
+impl SyncSettings {
+    /// Whether sync is on and has everything it needs to run.
+    pub fn is_configured(&self) -> bool {
+        self.enabled && !self.folder.trim().is_empty() && !self.passphrase.is_empty()
+    }
+}
+
+/// One chunk embedding as stored in the `embeddings` table.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyncChunk {
+    pub start: usize,
+    pub end: usize,
+    /// bincode-encoded `Vec<f32>`
+    pub embedding: Vec<u8>,
+    pub content_hash: Option<String>,
+    #[serde(default)]
+    pub section_heading: Option<String>,
+    #[serde(default)]
+    pub section_anchor: Option<String>,
+    #[serde(default)]
+    pub page_number: Option<u32>,
+    #[serde(default)]
+    pub timestamp_seconds: Option<u32>,
+}
+
+impl SyncChunk {
+    /// Where the chunk comes from, as stored with its embedding.
+    pub fn provenance(&self) -> ChunkProvenance {
+        ChunkProvenance {
+            section_heading: self.section_heading.clone(),
+            section_anchor: self.section_anchor.clone(),
+            page_number: self.page_number,
+            timestamp_seconds: self.timestamp_seconds,
+            content_hash: self.content_hash.clone(),
+        }
+    }
+}
+
+/// A document as shared with other machines.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyncDocument {
+    pub title: String,
+    pub content: String,
+    pub url: Option<String>,
+    pub source: String,
+    pub tags: Vec<String>,
+    /// Last change to the document (unix seconds), for last-write-wins
+    pub modified_at: u64,
+    /// Whether the document is in the trash
+    pub deleted: bool,
+    pub chunks: Vec<SyncChunk>,
+}
+
+impl SyncDocument {
+    /// Identity of the document across machines: its URL, or a hash of its
+    /// content when it has none.
+    pub fn key(&self) -> String {
+        match &self.url {
+            Some(url) => url.clone(),
+            None => {
+                use sha2::{Digest, Sha256};
+                format!("sha256:{:x}", Sha256::digest(self.content.as_bytes()))
+            }
+        }
+    }
+}
+
+/// The contents of one machine's sync file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyncSnapshot {
+    pub device_id: String,
+    /// Embedding backend and model the chunk embeddings were made with
+    pub embedding_model: String,
+    pub documents: Vec<SyncDocument>,
+}
+
+/// What merging a remote document does to the local index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeAction {
+    /// Not known locally: add it
+    Insert,
+    /// Changed more recently on the other machine: overwrite the local copy
+    Update(i64),
+    /// Trashed more recently on the other machine: trash the local copy
+    Trash(i64),
+    /// The local copy is as new or newer
+    Skip,
+}
+
+/// Decide how to merge `remote` given the local document with the same key.
+/// The most recent change wins; ties keep the local copy.
+pub fn merge_action(local: Option<(i64, &SyncDocument)>, remote: &SyncDocument) -> MergeAction {
+    match local {
+        // Never bring in documents that are already in the trash elsewhere
+        None if remote.deleted => MergeAction::Skip,
+        None => MergeAction::Insert,
+        Some((_, local)) if remote.modified_at <= local.modified_at => MergeAction::Skip,
+        Some((_, local)) if remote.deleted && local.deleted => MergeAction::Skip,
+        Some((id, _)) if remote.deleted => MergeAction::Trash(id),
+        Some((id, _)) => MergeAction::Update(id),
+    }
+}
+
+/// Outcome of a sync run.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SyncReport {
+    /// Other machines' sync files found in the folder
+    pub devices: usize,
+    pub inserted: usize,
+    pub updated: usize,
+    pub trashed: usize,
+    /// Sync files that could not be read, e.g. encrypted with another passphrase
+    pub failed: Vec<String>,
+    /// Whether this machine's sync file was rewritten
+    pub exported: bool,
+}
+
+impl SyncReport {
+    /// Number of local documents the run changed.
+    pub fn changed(&self) -> usize {
+        self.inserted + self.updated + self.trashed
+    }
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<[u8; 32]> {
+    let mut key = [0u8; 32];
+    argon2::Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| format!("Failed to derive sync key: {}", e))?;
+    Ok(key)
+}
+
+/// Encrypt `plaintext` with a key derived from `passphrase`.
+pub fn encrypt(plaintext: &[u8], passphrase: &str) -> Result<Vec<u8>> {
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    rand::rngs::OsRng.fill_bytes(&mut salt);
+    rand::rngs::OsRng.fill_bytes(&mut nonce);
+
+    let key = derive_key(passphrase, &salt)?;
+    let cipher = XChaCha20Poly1305::new(Key::from_slice(&key));
+    let ciphertext = cipher
+        .encrypt(XNonce::from_slice(&nonce), plaintext)
+        .map_err(|_| "Failed to encrypt sync file")?;
+
+    let mut data = Vec::with_capacity(MAGIC.len() + SALT_LEN + NONCE_LEN + ciphertext.len());
+    data.extend_from_slice(MAGIC);
+    data.extend_from_slice(&salt);
+    data.extend_from_slice(&nonce);
+    data.extend_from_slice(&ciphertext);
+    Ok(data)
+}
+
+/// Decrypt data written by `encrypt`. Fails on a wrong passphrase or a
+/// damaged file.
+pub fn decrypt(data: &[u8], passphrase: &str) -> Result<Vec<u8>> {
+    let header = MAGIC.len() + SALT_LEN + NONCE_LEN;
+    if data.len() < header || &data[..MAGIC.len()] != MAGIC {
+        return Err("Not a LocalMind sync file".into());
+    }
+    let salt = &data[MAGIC.len()..MAGIC.len() + SALT_LEN];
+    let nonce = &data[MAGIC.len() + SALT_LEN..header];
+
+    let key = derive_key(passphrase, salt)?;
+    let cipher = XChaCha20Poly1305::new(Key::from_slice(&key));
+    let plaintext = cipher
+        .decrypt(XNonce::from_slice(nonce), &data[header..])
+        .map_err(|_| "Wrong passphrase or damaged sync file")?;
+    Ok(plaintext)
+}
+
+fn read_snapshot(path: &Path, passphrase: &str) -> Result<SyncSnapshot> {
+    let data = std::fs::read(path)?;
+    let plaintext = decrypt(&data, passphrase)?;
+    Ok(bincode::deserialize(&plaintext)?)
+}
+
+/// Write a snapshot next to its final name and rename it into place, so a
+/// file sync tool never picks up a half-written file.
+fn write_snapshot(path: &Path, snapshot: &SyncSnapshot, passphrase: &str) -> Result<()> {
+    let data = encrypt(&bincode::serialize(snapshot)?, passphrase)?;
+    let partial = path.with_extension(format!("{}.partial", FILE_EXTENSION));
+    std::fs::write(&partial, data)?;
+    std::fs::rename(&partial, path)?;
+    Ok(())
+}
+
+fn modified_secs(path: &Path) -> u64 {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Merge the other machines' sync files into the local index, then write
+/// this machine's file if the index changed since it was last written.
+/// Fails if sync is not set up or another run is in progress.
+pub async fn run_sync(rag: &RagPipeline) -> Result<SyncReport> {
+    if SYNC_RUNNING.swap(true, Ordering::SeqCst) {
+        return Err("Sync is already running".into());
+    }
+
+    let result = run_sync_inner(rag).await;
+    SYNC_RUNNING.store(false, Ordering::SeqCst);
+    result
+}
+
+async fn run_sync_inner(rag: &RagPipeline) -> Result<SyncReport> {
+    let settings = rag.db.get_sync_settings().await?;
+    if !settings.is_configured() {
+        return Err("Sync is not set up".into());
+    }
+    let folder = PathBuf::from(settings.folder.trim());
+    if !folder.is_dir() {
+        return Err(format!("Sync folder {} does not exist", folder.display()).into());
+    }
+
+    let device_id = rag.db.get_sync_device_id().await?;
+    let own_file = format!("{}.{}", device_id, FILE_EXTENSION);
+    let model = rag.embedding_model_key();
+    let mut report = SyncReport::default();
+
+    let mut local: HashMap<String, (i64, SyncDocument)> = rag
+        .db
+        .get_sync_documents()
+        .await?
+        .into_iter()
+        .map(|(id, doc)| (doc.key(), (id, doc)))
+        .collect();
+    let mut seen = rag.db.get_sync_seen().await?;
+    let mut reload = Vec::new();
+    let mut trashed = HashSet::new();
+
+    for entry in std::fs::read_dir(&folder)?.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if path.extension().and_then(|e| e.to_str()) != Some(FILE_EXTENSION) || name == own_file {
+            continue;
+        }
+        report.devices += 1;
+
+        // Unchanged since the last run
+        let modified = modified_secs(&path);
+        if seen.get(&name) == Some(&modified) {
+            continue;
+        }
+
+        let snapshot = match read_snapshot(&path, &settings.passphrase) {
+            Ok(snapshot) => snapshot,
+            Err(e) => {
+                eprintln!("Failed to read sync file {}: {}", name, e);
+                report.failed.push(name);
+                continue;
+            }
+        };
+        let same_model = snapshot.embedding_model == model;
+
+        for remote in snapshot.documents {
+            let key = remote.key();
+            let existing = local.get(&key).map(|(id, doc)| (*id, doc));
+            match merge_action(existing, &remote) {
+                MergeAction::Insert => {
+                    let id = rag
+                        .db
+                        .apply_sync_document(None, &remote, same_model)
+                        .await?;
+                    report.inserted += 1;
+                    reload.push(id);
+                    local.insert(key, (id, remote));
+                }
+                MergeAction::Update(id) => {
+                    rag.db
+                        .apply_sync_document(Some(id), &remote, same_model)
+                        .await?;
+                    report.updated += 1;
+                    reload.push(id);
+                    local.insert(key, (id, remote));
+                }
+                MergeAction::Trash(id) => {
+                    rag.db.trash_documents(&[id]).await?;
+                    rag.db
+                        .set_document_modified_at(id, remote.modified_at)
+                        .await?;
+                    rag.remove_document_vectors(id).await;
+                    report.trashed += 1;
+                    trashed.insert(id);
+                    local.insert(key, (id, remote));
+                }
+                MergeAction::Skip => {}
+            }
+        }
+        seen.insert(name, modified);
+    }
+    rag.db.set_sync_seen(&seen).await?;
+
+    for doc_id in reload {
+        // Trashed later in the same run: keep its vectors out
+        if trashed.contains(&doc_id) {
+            continue;
+        }
+        if let Err(e) = rag.restore_document_vectors(doc_id).await {
+            eprintln!(
+                "Failed to load vectors for synced document {}: {}",
+                doc_id, e
+            );
+        }
+    }
+    if report.inserted + report.updated > 0 {
+        // Documents from a machine with another embedding model
+        if let Err(e) = rag.repair_incomplete_documents().await {
+            eprintln!("Failed to embed synced documents: {}", e);
+        }
+    }
+
+    let fingerprint = rag.db.get_sync_fingerprint().await?;
+    let own_path = folder.join(&own_file);
+    let last_export = rag.db.get_sync_last_export().await?;
+    if !own_path.exists() || last_export.as_deref() != Some(fingerprint.as_str()) {
+        let snapshot = SyncSnapshot {
+            device_id,
+            embedding_model: model,
+            documents: rag
+                .db
+                .get_sync_documents()
+                .await?
+                .into_iter()
+                .map(|(_, doc)| doc)
+                .collect(),
+        };
+        write_snapshot(&own_path, &snapshot, &settings.passphrase)?;
+        rag.db.set_sync_last_export(&fingerprint).await?;
+        report.exported = true;
+    }
+
+    rag.db.set_sync_last_run().await?;
+    println!(
+        "Sync: {} added, {} updated, {} trashed from {} other machines{}",
+        report.inserted,
+        report.updated,
+        report.trashed,
+        report.devices,
+        if report.exported {
+            ", index shared"
+        } else {
+            ""
+        }
+    );
+    Ok(report)
+}
+
+/// Whether a scheduled run is due: sync is set up and the last run was at
+/// least `SYNC_INTERVAL_SECS` ago.
+pub async fn is_sync_due(rag: &RagPipeline) -> bool {
+    let configured = rag
+        .db
+        .get_sync_settings()
+        .await
+        .is_ok_and(|settings| settings.is_configured());
+    let last_run = rag.db.get_sync_last_run().await.ok().flatten().unwrap_or(0);
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    configured && now.saturating_sub(last_run) >= SYNC_INTERVAL_SECS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn doc(url: Option<&str>, modified_at: u64, deleted: bool) -> SyncDocument {
+        SyncDocument {
+            title: "Title".to_string(),
+            content: "content".to_string(),
+            url: url.map(str::to_string),
+            source: "test".to_string(),
+            tags: Vec::new(),
+            modified_at,
+            deleted,
+            chunks: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn most_recent_change_wins() {
+        let local = doc(Some("https://a.example"), 100, false);
+        let newer = doc(Some("https://a.example"), 200, false);
+        let older = doc(Some("https://a.example"), 50, false);
+        let trashed = doc(Some("https://a.example"), 200, true);
+
+        assert_eq!(merge_action(None, &newer), MergeAction::Insert);
+        assert_eq!(merge_action(None, &trashed), MergeAction::Skip);
+        assert_eq!(
+            merge_action(Some((7, &local)), &newer),
+            MergeAction::Update(7)
+        );
+        assert_eq!(merge_action(Some((7, &local)), &older), MergeAction::Skip);
+        assert_eq!(merge_action(Some((7, &local)), &local), MergeAction::Skip);
+        assert_eq!(
+            merge_action(Some((7, &local)), &trashed),
+            MergeAction::Trash(7)
+        );
+
+        // Restoring from the trash is newer than the trashing
+        let restored = doc(Some("https://a.example"), 300, false);
+        assert_eq!(
+            merge_action(Some((7, &trashed)), &restored),
+            MergeAction::Update(7)
+        );
+    }
+
+    #[test]
+    fn documents_without_url_match_by_content() {
+        let a = doc(None, 1, false);
+        let mut b = doc(None, 2, false);
+        assert_eq!(a.key(), b.key());
+        assert!(a.key().starts_with("sha256:"));
+        b.content.push('!');
+        assert_ne!(a.key(), b.key());
+        assert_eq!(
+            doc(Some("https://a.example"), 1, false).key(),
+            "https://a.example"
+        );
+    }
+
+    #[test]
+    fn encryption_round_trips_with_the_right_passphrase() {
+        let data = encrypt(b"shared index", "correct horse").unwrap();
+        assert!(data.starts_with(MAGIC));
+        assert!(!data.windows(12).any(|w| w == b"shared index"));
+        assert_eq!(decrypt(&data, "correct horse").unwrap(), b"shared index");
+        assert!(decrypt(&data, "wrong horse").is_err());
+        assert!(decrypt(b"LMSYNC01", "correct horse").is_err());
+    }
+}
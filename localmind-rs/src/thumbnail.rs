@@ -0,0 +1,171 @@
+//! Document thumbnails
+//!
+//! When a page is fetched (or sent as HTML by the extension) its preview
+//! image is noted: the `og:image` or `twitter:image` it declares, otherwise
+//! the first image in the page that does not look like a logo or icon. A
+//! background task later downloads each noted image and stores a small JPEG
+//! of it, shown on result cards when the thumbnail layout is on.
+
+use crate::db::Database;
+use regex::Regex;
+use std::io::Cursor;
+use std::sync::OnceLock;
+
+/// Thumbnails fit in this box (pixels), keeping their aspect ratio.
+pub const THUMBNAIL_WIDTH: u32 = 320;
+pub const THUMBNAIL_HEIGHT: u32 = 180;
+
+/// Images smaller than this on either side (tracking pixels, spacers) are
+/// not worth a thumbnail.
+const MIN_IMAGE_SIDE: u32 = 64;
+
+/// Largest image file downloaded.
+const MAX_IMAGE_BYTES: usize = 5 * 1024 * 1024;
+
+/// Words in an `<img>` tag that mark it as page furniture rather than content.
+const DECORATIVE_WORDS: [&str; 8] = [
+    "logo", "icon", "avatar", "sprite", "pixel", "spacer", "badge", "emoji",
+];
+
+/// Value of attribute `name` in an HTML tag.
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let pattern = format!(r#"(?i)\b{}\s*=\s*["']([^"']*)["']"#, regex::escape(name));
+    let value = Regex::new(&pattern).ok()?.captures(tag)?[1]
+        .trim()
+        .to_string();
+    (!value.is_empty()).then_some(value)
+}
+
+/// The first `<img>` of a page that looks like content: not inline data,
+/// not an SVG or GIF, not marked as a logo or icon, and not declared tiny.
+fn first_content_image(html: &str) -> Option<String> {
+    static IMG: OnceLock<Regex> = OnceLock::new();
+    let img = IMG.get_or_init(|| Regex::new(r"(?i)<img\b[^>]*>").unwrap());
+    img.find_iter(html).find_map(|tag| {
+        let tag = tag.as_str();
+        let src = attribute(tag, "src")?;
+        let lower = tag.to_lowercase();
+        let path = src.to_lowercase();
+        let tiny = ["width", "height"].iter().any(|side| {
+            attribute(tag, side)
+                .and_then(|value| value.trim_end_matches("px").parse::<u32>().ok())
+                .is_some_and(|pixels| pixels < MIN_IMAGE_SIDE)
+        });
+        if src.starts_with("data:")
+            || path.ends_with(".svg")
+            || path.ends_with(".gif")
+            || tiny
+            || DECORATIVE_WORDS.iter().any(|word| lower.contains(word))
+        {
+            return None;
+        }
+        Some(src)
+    })
+}
+
+/// Absolute URL of a page's preview image, if it has one.
+pub fn preview_image_url(html: &str, page_url: &str) -> Option<String> {
+    static META: OnceLock<Regex> = OnceLock::new();
+    let meta = META.get_or_init(|| {
+        Regex::new(
+            r#"(?i)<meta\b[^>]*\b(?:property|name)\s*=\s*["'](?:og:image(?::url)?|twitter:image)["'][^>]*>"#,
+        )
+        .unwrap()
+    });
+
+    let src = meta
+        .find_iter(html)
+        .find_map(|tag| attribute(tag.as_str(), "content"))
+        .or_else(|| first_content_image(html))?;
+    let resolved = url::Url::parse(page_url).ok()?.join(&src).ok()?;
+    matches!(resolved.scheme(), "http" | "https").then(|| resolved.to_string())
+}
+
+/// Decode an image and shrink it into a JPEG thumbnail. `None` when it
+/// cannot be decoded or is too small to be worth showing.
+pub fn make_thumbnail(bytes: &[u8]) -> Option<Vec<u8>> {
+    let image = image::load_from_memory(bytes).ok()?;
+    if image.width() < MIN_IMAGE_SIDE || image.height() < MIN_IMAGE_SIDE {
+        return None;
+    }
+    let thumbnail = image.thumbnail(THUMBNAIL_WIDTH, THUMBNAIL_HEIGHT).to_rgb8();
+    let mut jpeg = Vec::new();
+    image::codecs::jpeg::JpegEncoder::new_with_quality(Cursor::new(&mut jpeg), 80)
+        .encode_image(&thumbnail)
+        .ok()?;
+    Some(jpeg)
+}
+
+/// Download an image and make its thumbnail; empty when either fails.
+pub async fn fetch_thumbnail(client: &reqwest::Client, image_url: &str) -> Vec<u8> {
+    let Ok(response) = client.get(image_url).send().await else {
+        return Vec::new();
+    };
+    if !response.status().is_success() {
+        return Vec::new();
+    }
+    match response.bytes().await {
+        Ok(bytes) if bytes.len() <= MAX_IMAGE_BYTES => make_thumbnail(&bytes).unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
+/// Note a document's preview image so the background task fetches it.
+/// Failures are logged; the document stays stored either way.
+pub async fn record_preview_image(db: &Database, doc_id: i64, image_url: Option<&str>) {
+    let Some(image_url) = image_url else {
+        return;
+    };
+    if let Err(e) = db.set_document_image_url(doc_id, image_url).await {
+        eprintln!(
+            "Failed to record preview image of document {}: {}",
+            doc_id, e
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn open_graph_image_comes_first() {
+        let html = r#"<html><head>
+            <meta content="/img/cover.jpg" property="og:image">
+            </head><body><img src="/photo.jpg"></body></html>"#;
+        assert_eq!(
+            preview_image_url(html, "https://example.com/post/1"),
+            Some("https://example.com/img/cover.jpg".to_string())
+        );
+
+        let html = r#"<img src="/static/logo.png"><img src="data:image/png;base64,AAAA">
+            <img src="pixel.png" width="1" height="1"><img class="hero" src="images/hero.jpg">"#;
+        assert_eq!(
+            preview_image_url(html, "https://example.com/post/1"),
+            Some("https://example.com/post/images/hero.jpg".to_string())
+        );
+
+        assert_eq!(
+            preview_image_url("<p>No images</p>", "https://example.com"),
+            None
+        );
+    }
+
+    #[test]
+    fn thumbnails_fit_the_box() {
+        let mut source = Vec::new();
+        image::DynamicImage::new_rgb8(1280, 640)
+            .write_to(&mut Cursor::new(&mut source), image::ImageFormat::Png)
+            .unwrap();
+
+        let thumbnail = make_thumbnail(&source).unwrap();
+        let decoded = image::load_from_memory(&thumbnail).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (THUMBNAIL_WIDTH, 160));
+
+        let mut pixel = Vec::new();
+        image::DynamicImage::new_rgb8(1, 1)
+            .write_to(&mut Cursor::new(&mut pixel), image::ImageFormat::Png)
+            .unwrap();
+        assert_eq!(make_thumbnail(&pixel), None);
+    }
+}
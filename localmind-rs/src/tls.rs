@@ -0,0 +1,146 @@
+//! TLS for the LAN remote access server and the HTTP API
+//!
+//! Remote access sends its token with every search, so on a shared network
+//! it can be served over HTTPS instead: with a certificate and key the user
+//! provides (PEM files), or with a self-signed certificate generated on
+//! first use and kept in the data directory. Browsers warn about the
+//! self-signed one until it is accepted once on each device. The HTTP API
+//! uses the same self-signed certificate whenever it is bound to an address
+//! other machines can reach.
+
+use crate::Result;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Self-signed certificate and key, in the data directory.
+const SELF_SIGNED_CERT_FILE: &str = "remote_access_cert.pem";
+const SELF_SIGNED_KEY_FILE: &str = "remote_access_key.pem";
+
+/// How the remote access server encrypts connections.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum TlsMode {
+    /// Plain HTTP
+    #[default]
+    Off,
+    /// A certificate generated for this machine
+    SelfSigned,
+    /// PEM certificate (chain) and private key files chosen by the user
+    Custom { cert_path: String, key_path: String },
+}
+
+impl TlsMode {
+    pub fn is_enabled(&self) -> bool {
+        *self != TlsMode::Off
+    }
+
+    /// Certificate and key files to serve with, generating the self-signed
+    /// pair in `data_dir` when it does not exist yet; `None` when TLS is off.
+    pub fn certificate_files(&self, data_dir: &Path) -> Result<Option<(PathBuf, PathBuf)>> {
+        match self {
+            TlsMode::Off => Ok(None),
+            TlsMode::SelfSigned => self_signed_files(data_dir).map(Some),
+            TlsMode::Custom {
+                cert_path,
+                key_path,
+            } => {
+                let (cert, key) = (PathBuf::from(cert_path), PathBuf::from(key_path));
+                for path in [&cert, &key] {
+                    if !path.is_file() {
+                        return Err(format!("TLS file not found: {}", path.display()).into());
+                    }
+                }
+                Ok(Some((cert, key)))
+            }
+        }
+    }
+}
+
+/// Self-signed certificate file in `data_dir`, which local clients of the
+/// HTTP API trust.
+pub fn self_signed_cert_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(SELF_SIGNED_CERT_FILE)
+}
+
+/// Self-signed certificate and key files in `data_dir`, generated when they
+/// do not exist yet.
+pub fn self_signed_files(data_dir: &Path) -> Result<(PathBuf, PathBuf)> {
+    let cert = self_signed_cert_path(data_dir);
+    let key = data_dir.join(SELF_SIGNED_KEY_FILE);
+    if !cert.exists() || !key.exists() {
+        generate_self_signed(&cert, &key)?;
+    }
+    Ok((cert, key))
+}
+
+/// Write a self-signed certificate for "localhost" and this machine's LAN
+/// address, and its private key.
+fn generate_self_signed(cert_path: &Path, key_path: &Path) -> Result<()> {
+    let mut names = vec!["localhost".to_string()];
+    if let Some(ip) = crate::remote_access::lan_address() {
+        names.push(ip.to_string());
+    }
+    let rcgen::CertifiedKey { cert, key_pair } = rcgen::generate_simple_self_signed(names)?;
+
+    if let Some(parent) = cert_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(cert_path, cert.pem())?;
+    std::fs::write(key_path, key_pair.serialize_pem())?;
+    println!("Generated self-signed certificate {}", cert_path.display());
+    Ok(())
+}
+
+/// Server configuration for the certificate and key files.
+pub async fn rustls_config(
+    cert_path: &Path,
+    key_path: &Path,
+) -> Result<axum_server::tls_rustls::RustlsConfig> {
+    axum_server::tls_rustls::RustlsConfig::from_pem_file(cert_path, key_path)
+        .await
+        .map_err(|e| format!("Failed to load TLS certificate: {}", e).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn self_signed_certificate_is_generated_once() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(TlsMode::Off.certificate_files(dir.path()).unwrap(), None);
+
+        let (cert, key) = TlsMode::SelfSigned
+            .certificate_files(dir.path())
+            .unwrap()
+            .unwrap();
+        let pem = std::fs::read_to_string(&cert).unwrap();
+        assert!(pem.starts_with("-----BEGIN CERTIFICATE-----"));
+        assert!(std::fs::read_to_string(&key)
+            .unwrap()
+            .contains("PRIVATE KEY"));
+
+        TlsMode::SelfSigned.certificate_files(dir.path()).unwrap();
+        assert_eq!(std::fs::read_to_string(&cert).unwrap(), pem);
+
+        let missing = TlsMode::Custom {
+            cert_path: dir.path().join("none.pem").display().to_string(),
+            key_path: key.display().to_string(),
+        };
+        assert!(missing.certificate_files(dir.path()).is_err());
+    }
+
+    #[test]
+    fn stored_modes_round_trip() {
+        let custom = TlsMode::Custom {
+            cert_path: "/etc/cert.pem".to_string(),
+            key_path: "/etc/key.pem".to_string(),
+        };
+        let json = serde_json::to_string(&custom).unwrap();
+        assert_eq!(serde_json::from_str::<TlsMode>(&json).unwrap(), custom);
+        assert_eq!(
+            serde_json::from_str::<TlsMode>(r#"{"mode":"self_signed"}"#).unwrap(),
+            TlsMode::SelfSigned
+        );
+    }
+}
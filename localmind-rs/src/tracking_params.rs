@@ -0,0 +1,133 @@
+//! Tracking parameters stripped from URLs
+//!
+//! Links shared from newsletters, ads and social sites carry query
+//! parameters such as `utm_source` or `fbclid` that say where a click came
+//! from, not which page it is. They are removed when a URL is normalized
+//! (see `db::normalize_url`), so the same page saved from two campaigns is
+//! stored once, and when a URL is shown. The list is editable in Settings
+//! and belongs to the library (see `db::CachedSettings`); a name ending in
+//! `*` matches every parameter starting with it. Names are compared
+//! ignoring case.
+
+/// Parameters stripped unless the user changed the list.
+pub const DEFAULT_TRACKING_PARAMS: [&str; 16] = [
+    "utm_*", "fbclid", "gclid", "gclsrc", "dclid", "gbraid", "wbraid", "msclkid", "yclid",
+    "twclid", "igshid", "mc_cid", "mc_eid", "_hsenc", "_hsmi", "mkt_tok",
+];
+
+/// The default list as owned strings.
+pub fn defaults() -> Vec<String> {
+    DEFAULT_TRACKING_PARAMS
+        .iter()
+        .map(|param| param.to_string())
+        .collect()
+}
+
+/// Whether a query parameter name is on `params`.
+fn is_listed(name: &str, params: &[String]) -> bool {
+    let name = name.to_lowercase();
+    params.iter().any(|param| match param.strip_suffix('*') {
+        Some(prefix) => name.starts_with(prefix),
+        None => name == *param,
+    })
+}
+
+/// `url` without the query parameters on `params`, keeping the others in
+/// order. The `?` goes too when no parameter is left; a fragment is kept.
+pub fn strip(url: &str, params: &[String]) -> String {
+    let (without_fragment, fragment) = match url.split_once('#') {
+        Some((before, after)) => (before, Some(after)),
+        None => (url, None),
+    };
+    let Some((base, query)) = without_fragment.split_once('?') else {
+        return url.to_string();
+    };
+
+    let kept: Vec<&str> = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .filter(|pair| {
+            let name = pair.split_once('=').map_or(*pair, |(name, _)| name);
+            !is_listed(name, params)
+        })
+        .collect();
+
+    let mut cleaned = base.to_string();
+    if !kept.is_empty() {
+        cleaned.push('?');
+        cleaned.push_str(&kept.join("&"));
+    }
+    if let Some(fragment) = fragment {
+        cleaned.push('#');
+        cleaned.push_str(fragment);
+    }
+    cleaned
+}
+
+/// Parse the list typed in Settings: names separated by commas, spaces or
+/// new lines, lowercased and without duplicates. A `*` may only end a name
+/// and must follow at least one character, so the list cannot strip every
+/// parameter.
+pub fn parse_list(text: &str) -> std::result::Result<Vec<String>, String> {
+    let mut params: Vec<String> = Vec::new();
+    for name in text
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|name| !name.is_empty())
+    {
+        let name = name.to_lowercase();
+        let stem = name.strip_suffix('*').unwrap_or(&name);
+        let valid = !stem.is_empty()
+            && stem
+                .chars()
+                .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | '[' | ']'));
+        if !valid {
+            return Err(format!("\"{}\" is not a parameter name", name));
+        }
+        if !params.contains(&name) {
+            params.push(name);
+        }
+    }
+    Ok(params)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_listed_parameters_only() {
+        let params = defaults();
+        assert_eq!(
+            strip(
+                "https://example.com/post?id=7&utm_source=news&UTM_Medium=email&fbclid=abc#intro",
+                &params
+            ),
+            "https://example.com/post?id=7#intro"
+        );
+        assert_eq!(
+            strip("https://example.com/?utm_campaign=x&gclid=1", &params),
+            "https://example.com/"
+        );
+        assert_eq!(
+            strip("https://example.com/search?q=rust&page=2", &params),
+            "https://example.com/search?q=rust&page=2"
+        );
+        assert_eq!(
+            strip("https://example.com/a?&&ref=1", &["ref".to_string()]),
+            "https://example.com/a"
+        );
+        assert_eq!(strip("file:///notes/a.md", &params), "file:///notes/a.md");
+    }
+
+    #[test]
+    fn parses_the_settings_list() {
+        assert_eq!(
+            parse_list("utm_*, FBCLID\n ref_src  fbclid").unwrap(),
+            vec!["utm_*", "fbclid", "ref_src"]
+        );
+        assert!(parse_list("").unwrap().is_empty());
+        assert!(parse_list("*").is_err());
+        assert!(parse_list("utm_*x").is_err());
+        assert!(parse_list("a=b").is_err());
+    }
+}
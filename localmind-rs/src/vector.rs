@@ -1,4 +1,5 @@
 use crate::Result;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 #[derive(Debug, Clone)]
 pub struct SearchResult {
@@ -15,9 +16,55 @@ pub struct ChunkSearchResult {
     pub similarity: f32,
 }
 
+/// A query compared with stored vectors of other dimensions, i.e. made by
+/// another embedding model. The documents need re-embedding first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DimensionMismatch {
+    pub query: usize,
+    /// Dimensions of the stored chunk vectors
+    pub stored: Vec<usize>,
+}
+
+impl std::fmt::Display for DimensionMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Query embedding has {} dimensions but the stored chunk vectors have {:?}; \
+             re-embed the documents with the current model",
+            self.query, self.stored
+        )
+    }
+}
+
+impl std::error::Error for DimensionMismatch {}
+
+type ChunkVector = (i64, i64, usize, usize, Vec<f32>); // (embedding_id, doc_id, chunk_start, chunk_end, vector)
+
+/// Tombstones needed before removed chunk vectors are compacted away...
+const MIN_TOMBSTONES_TO_COMPACT: usize = 256;
+/// ...and the share of stored chunk vectors they must reach (one in four).
+const COMPACT_RATIO: usize = 4;
+
+/// In-memory chunk vectors searched by cosine similarity.
+///
+/// Removing a document or chunk only records a tombstone for its embedding
+/// IDs, which searches skip; the vectors are dropped in one compaction pass
+/// once tombstones make up a quarter of the store, so deleting or
+/// re-embedding documents one at a time does not rewrite it every time.
 pub struct VectorStore {
     vectors: Vec<(i64, Vec<f32>)>, // (doc_id, vector) - legacy, will be removed
-    chunk_vectors: Vec<(i64, i64, usize, usize, Vec<f32>)>, // (embedding_id, doc_id, chunk_start, chunk_end, vector)
+    /// Chunk vectors by dimension. After switching embedding models the old
+    /// vectors stay until re-embedded, and are never compared with the new.
+    chunk_partitions: BTreeMap<usize, Vec<ChunkVector>>,
+    /// Embedding IDs and dimensions of each document's live chunk vectors
+    document_chunks: HashMap<i64, Vec<(i64, usize)>>,
+    /// Embedding IDs of removed chunk vectors not compacted away yet
+    tombstones: HashSet<i64>,
+    /// Live chunk vectors per dimension, kept up to date so that searches
+    /// need not count them
+    live_counts: BTreeMap<usize, usize>,
+    /// Dimension of the vectors the current model makes, once seen
+    active_dimensions: Option<usize>,
 }
 
 #[allow(clippy::new_without_default)]
@@ -25,7 +72,11 @@ impl VectorStore {
     pub fn new() -> Self {
         Self {
             vectors: Vec::new(),
-            chunk_vectors: Vec::new(),
+            chunk_partitions: BTreeMap::new(),
+            document_chunks: HashMap::new(),
+            tombstones: HashSet::new(),
+            live_counts: BTreeMap::new(),
+            active_dimensions: None,
         }
     }
 
@@ -34,11 +85,22 @@ impl VectorStore {
         Ok(())
     }
 
-    pub fn load_chunk_vectors(
-        &mut self,
-        chunk_vectors: Vec<(i64, i64, usize, usize, Vec<f32>)>,
-    ) -> Result<()> {
-        self.chunk_vectors = chunk_vectors;
+    pub fn load_chunk_vectors(&mut self, chunk_vectors: Vec<ChunkVector>) -> Result<()> {
+        self.chunk_partitions.clear();
+        self.document_chunks.clear();
+        self.tombstones.clear();
+        self.live_counts.clear();
+        for chunk in chunk_vectors {
+            self.document_chunks
+                .entry(chunk.1)
+                .or_default()
+                .push((chunk.0, chunk.4.len()));
+            *self.live_counts.entry(chunk.4.len()).or_default() += 1;
+            self.chunk_partitions
+                .entry(chunk.4.len())
+                .or_default()
+                .push(chunk);
+        }
         Ok(())
     }
 
@@ -47,8 +109,83 @@ impl VectorStore {
         Ok(())
     }
 
+    /// Live chunk vectors, not counting removed ones awaiting compaction.
     pub fn chunk_vector_count(&self) -> usize {
-        self.chunk_vectors.len()
+        self.live_counts.values().sum()
+    }
+
+    fn stored_chunk_count(&self) -> usize {
+        self.chunk_partitions.values().map(Vec::len).sum()
+    }
+
+    /// Removed chunk vectors still held until the next compaction.
+    pub fn tombstone_count(&self) -> usize {
+        self.tombstones.len()
+    }
+
+    /// Record the dimension of the current model's vectors, as seen in a
+    /// fresh query or chunk embedding.
+    pub fn set_active_dimensions(&mut self, dimensions: usize) {
+        if dimensions > 0 {
+            self.active_dimensions = Some(dimensions);
+        }
+    }
+
+    /// Forget the current model's dimension, after switching models.
+    pub fn reset_active_dimensions(&mut self) {
+        self.active_dimensions = None;
+    }
+
+    pub fn active_dimensions(&self) -> Option<usize> {
+        self.active_dimensions
+    }
+
+    /// Live chunk vector count per dimension, smallest dimension first.
+    pub fn dimension_counts(&self) -> Vec<(usize, usize)> {
+        self.live_counts
+            .iter()
+            .map(|(dimensions, count)| (*dimensions, *count))
+            .collect()
+    }
+
+    /// Chunks whose vectors another model made, which searches skip until
+    /// they are re-embedded; `None` until the current model's dimension is
+    /// known.
+    pub fn chunks_needing_migration(&self) -> Option<usize> {
+        let active = self.active_dimensions?;
+        Some(
+            self.dimension_counts()
+                .into_iter()
+                .filter(|(dimensions, _)| *dimensions != active)
+                .map(|(_, count)| count)
+                .sum(),
+        )
+    }
+
+    /// The entries of `chunks` that have not been removed.
+    fn live<'a>(&'a self, chunks: &'a [ChunkVector]) -> impl Iterator<Item = &'a ChunkVector> {
+        chunks
+            .iter()
+            .filter(|chunk| !self.tombstones.contains(&chunk.0))
+    }
+
+    /// The live chunk vectors comparable with `query_vector`: those of its
+    /// dimension. An error when there are chunk vectors but none of them is.
+    fn comparable_chunks(
+        &self,
+        query_vector: &[f32],
+    ) -> Result<impl Iterator<Item = &ChunkVector>> {
+        let chunks = self
+            .chunk_partitions
+            .get(&query_vector.len())
+            .map_or(&[][..], Vec::as_slice);
+        if !self.live_counts.is_empty() && !self.live_counts.contains_key(&query_vector.len()) {
+            return Err(Box::new(DimensionMismatch {
+                query: query_vector.len(),
+                stored: self.live_counts.keys().copied().collect(),
+            }));
+        }
+        Ok(self.live(chunks))
     }
 
     pub fn add_chunk_vector(
@@ -59,16 +196,84 @@ impl VectorStore {
         chunk_end: usize,
         vector: Vec<f32>,
     ) -> Result<()> {
-        self.chunk_vectors
+        // Added back (e.g. restored from the trash): drop the removed copy first
+        if self.tombstones.contains(&embedding_id) {
+            self.compact();
+        }
+        self.document_chunks
+            .entry(doc_id)
+            .or_default()
+            .push((embedding_id, vector.len()));
+        *self.live_counts.entry(vector.len()).or_default() += 1;
+        self.chunk_partitions
+            .entry(vector.len())
+            .or_default()
             .push((embedding_id, doc_id, chunk_start, chunk_end, vector));
         Ok(())
     }
 
-    pub fn remove_vectors_for_document(&mut self, doc_id: i64) {
-        self.chunk_vectors.retain(|v| v.1 != doc_id);
+    /// Remove a document's vectors; searches stop returning it at once.
+    pub fn remove_document(&mut self, doc_id: i64) {
+        if let Some(chunks) = self.document_chunks.remove(&doc_id) {
+            for (embedding_id, dimensions) in chunks {
+                self.tombstone(embedding_id, dimensions);
+            }
+            self.compact_if_needed();
+        }
         self.vectors.retain(|v| v.0 != doc_id);
     }
 
+    /// Remove one chunk vector by embedding ID. Returns whether it was live.
+    pub fn remove_chunk(&mut self, embedding_id: i64) -> bool {
+        let owner = self
+            .document_chunks
+            .iter_mut()
+            .find_map(|(doc_id, chunks)| {
+                let position = chunks.iter().position(|(id, _)| *id == embedding_id)?;
+                let (_, dimensions) = chunks.swap_remove(position);
+                Some((*doc_id, dimensions, chunks.is_empty()))
+            });
+        let Some((doc_id, dimensions, emptied)) = owner else {
+            return false;
+        };
+        if emptied {
+            self.document_chunks.remove(&doc_id);
+        }
+        self.tombstone(embedding_id, dimensions);
+        self.compact_if_needed();
+        true
+    }
+
+    fn tombstone(&mut self, embedding_id: i64, dimensions: usize) {
+        self.tombstones.insert(embedding_id);
+        if let Some(count) = self.live_counts.get_mut(&dimensions) {
+            *count -= 1;
+            if *count == 0 {
+                self.live_counts.remove(&dimensions);
+            }
+        }
+    }
+
+    fn compact_if_needed(&mut self) {
+        if self.tombstones.len() >= MIN_TOMBSTONES_TO_COMPACT
+            && self.tombstones.len() * COMPACT_RATIO >= self.stored_chunk_count()
+        {
+            self.compact();
+        }
+    }
+
+    /// Drop the removed chunk vectors from memory.
+    pub fn compact(&mut self) {
+        if self.tombstones.is_empty() {
+            return;
+        }
+        for chunks in self.chunk_partitions.values_mut() {
+            chunks.retain(|chunk| !self.tombstones.contains(&chunk.0));
+        }
+        self.chunk_partitions.retain(|_, chunks| !chunks.is_empty());
+        self.tombstones.clear();
+    }
+
     pub fn search(&self, query_vector: &[f32], limit: usize) -> Result<Vec<SearchResult>> {
         self.search_with_cutoff(query_vector, limit, 0.0)
     }
@@ -123,6 +328,17 @@ impl VectorStore {
         query_vector: &[f32],
         limit: usize,
         min_similarity: f32,
+    ) -> Result<Vec<ChunkSearchResult>> {
+        self.search_chunks_scoped(query_vector, limit, min_similarity, None)
+    }
+
+    /// Chunk search restricted to the documents in `scope`, when given.
+    pub fn search_chunks_scoped(
+        &self,
+        query_vector: &[f32],
+        limit: usize,
+        min_similarity: f32,
+        scope: Option<&HashSet<i64>>,
     ) -> Result<Vec<ChunkSearchResult>> {
         // Minimum chunk size to consider (in bytes) - filters out meaningless tiny chunks
         const MIN_CHUNK_SIZE: usize = 50;
@@ -133,7 +349,13 @@ impl VectorStore {
 
         let mut similarities: Vec<ChunkSearchResult> = Vec::new();
 
-        for (embedding_id, doc_id, chunk_start, chunk_end, vector) in &self.chunk_vectors {
+        for (embedding_id, doc_id, chunk_start, chunk_end, vector) in
+            self.comparable_chunks(query_vector)?
+        {
+            if scope.is_some_and(|scope| !scope.contains(doc_id)) {
+                continue;
+            }
+
             // Skip chunks that are too small to be semantically meaningful,
             // but allow if it's the only chunk for the doc (e.g. title-only auth-blocked docs)
             let chunk_size = chunk_end - chunk_start;
@@ -168,16 +390,108 @@ impl VectorStore {
         Ok(similarities)
     }
 
+    /// Rank the chunks of a single document against the query, best first.
+    ///
+    /// Unlike `search_chunks_with_cutoff` this keeps short chunks, since a
+    /// question about one document should be able to cite any part of it.
+    pub fn search_document_chunks(
+        &self,
+        query_vector: &[f32],
+        doc_id: i64,
+        limit: usize,
+    ) -> Result<Vec<ChunkSearchResult>> {
+        if query_vector.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut similarities: Vec<ChunkSearchResult> = self
+            .comparable_chunks(query_vector)?
+            .filter(|(_, chunk_doc_id, _, _, _)| *chunk_doc_id == doc_id)
+            .filter_map(|(embedding_id, _, chunk_start, chunk_end, vector)| {
+                cosine_similarity(query_vector, vector).map(|similarity| ChunkSearchResult {
+                    embedding_id: *embedding_id,
+                    doc_id,
+                    chunk_start: *chunk_start,
+                    chunk_end: *chunk_end,
+                    similarity,
+                })
+            })
+            .collect();
+
+        similarities.sort_by(|a, b| {
+            b.similarity
+                .partial_cmp(&a.similarity)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        similarities.truncate(limit);
+
+        Ok(similarities)
+    }
+
+    /// Documents nearest to `doc_id`, best first: each other document is
+    /// scored by its chunk closest to the mean of `doc_id`'s chunk vectors.
+    /// Only vectors of one dimension are compared, the current model's when
+    /// `doc_id` has some. Empty when `doc_id` has no chunk vectors.
+    pub fn nearest_documents(&self, doc_id: i64, limit: usize) -> Vec<SearchResult> {
+        let is_own = |chunks: &Vec<ChunkVector>| self.live(chunks).any(|chunk| chunk.1 == doc_id);
+        let partition = self
+            .active_dimensions
+            .and_then(|active| self.chunk_partitions.get(&active))
+            .filter(|chunks| is_own(chunks))
+            .or_else(|| self.chunk_partitions.values().find(|chunks| is_own(chunks)));
+        let Some(partition) = partition else {
+            return vec![];
+        };
+        let own: Vec<&Vec<f32>> = self
+            .live(partition)
+            .filter(|(_, chunk_doc_id, _, _, _)| *chunk_doc_id == doc_id)
+            .map(|(_, _, _, _, vector)| vector)
+            .collect();
+        let Some(dimensions) = own.first().map(|vector| vector.len()) else {
+            return vec![];
+        };
+        let mut centroid = vec![0.0; dimensions];
+        for vector in &own {
+            for (sum, value) in centroid.iter_mut().zip(vector.iter()) {
+                *sum += value;
+            }
+        }
+
+        let mut best: std::collections::HashMap<i64, f32> = std::collections::HashMap::new();
+        for (_, chunk_doc_id, _, _, vector) in self.live(partition) {
+            if *chunk_doc_id == doc_id {
+                continue;
+            }
+            if let Some(similarity) = cosine_similarity(&centroid, vector) {
+                let entry = best.entry(*chunk_doc_id).or_insert(similarity);
+                *entry = entry.max(similarity);
+            }
+        }
+
+        let mut results: Vec<SearchResult> = best
+            .into_iter()
+            .map(|(doc_id, similarity)| SearchResult { doc_id, similarity })
+            .collect();
+        results.sort_by(|a, b| {
+            b.similarity
+                .partial_cmp(&a.similarity)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(a.doc_id.cmp(&b.doc_id))
+        });
+        results.truncate(limit);
+        results
+    }
+
     pub fn len(&self) -> usize {
         self.vectors.len()
     }
 
     pub fn chunk_len(&self) -> usize {
-        self.chunk_vectors.len()
+        self.chunk_vector_count()
     }
 
     pub fn is_empty(&self) -> bool {
-        self.vectors.is_empty() && self.chunk_vectors.is_empty()
+        self.vectors.is_empty() && self.chunk_vector_count() == 0
     }
 }
 
@@ -254,4 +568,164 @@ mod tests {
             assert!(result.similarity >= 0.6);
         }
     }
+
+    #[test]
+    fn test_search_document_chunks_filters_by_doc() {
+        let mut store = VectorStore::new();
+        store
+            .add_chunk_vector(1, 10, 0, 100, vec![1.0, 0.0])
+            .unwrap();
+        store
+            .add_chunk_vector(2, 10, 100, 120, vec![0.0, 1.0])
+            .unwrap();
+        store
+            .add_chunk_vector(3, 20, 0, 100, vec![1.0, 0.0])
+            .unwrap();
+
+        let results = store.search_document_chunks(&[0.0, 1.0], 10, 5).unwrap();
+
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|r| r.doc_id == 10));
+        // The short trailing chunk is kept and ranks first
+        assert_eq!(results[0].embedding_id, 2);
+    }
+
+    #[test]
+    fn test_search_chunks_scoped() {
+        let mut store = VectorStore::new();
+        store
+            .add_chunk_vector(1, 10, 0, 100, vec![1.0, 0.0])
+            .unwrap();
+        store
+            .add_chunk_vector(2, 20, 0, 100, vec![0.9, 0.1])
+            .unwrap();
+
+        let scope: HashSet<i64> = [20].into_iter().collect();
+        let results = store
+            .search_chunks_scoped(&[1.0, 0.0], 10, 0.0, Some(&scope))
+            .unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].doc_id, 20);
+    }
+
+    #[test]
+    fn test_nearest_documents_uses_the_best_chunk_of_each() {
+        let mut store = VectorStore::new();
+        store
+            .add_chunk_vector(1, 10, 0, 100, vec![1.0, 0.0])
+            .unwrap();
+        store
+            .add_chunk_vector(2, 10, 100, 200, vec![0.8, 0.2])
+            .unwrap();
+        store
+            .add_chunk_vector(3, 20, 0, 100, vec![0.0, 1.0])
+            .unwrap();
+        store
+            .add_chunk_vector(4, 20, 100, 200, vec![0.9, 0.1])
+            .unwrap();
+        store
+            .add_chunk_vector(5, 30, 0, 100, vec![0.5, 0.5])
+            .unwrap();
+
+        let results = store.nearest_documents(10, 5);
+        let ids: Vec<i64> = results.iter().map(|r| r.doc_id).collect();
+        assert_eq!(ids, vec![20, 30]);
+        assert!(results[0].similarity > 0.99);
+
+        assert_eq!(store.nearest_documents(10, 1).len(), 1);
+        assert!(store.nearest_documents(99, 5).is_empty());
+    }
+
+    #[test]
+    fn test_vectors_of_other_dimensions_are_not_compared() {
+        let mut store = VectorStore::new();
+        store
+            .add_chunk_vector(1, 10, 0, 100, vec![1.0, 0.0])
+            .unwrap();
+        store
+            .add_chunk_vector(2, 20, 0, 100, vec![0.9, 0.1])
+            .unwrap();
+        assert_eq!(store.chunks_needing_migration(), None);
+
+        // Switched to a three-dimensional model; one document re-embedded
+        store.set_active_dimensions(3);
+        store.remove_document(20);
+        store
+            .add_chunk_vector(3, 20, 0, 100, vec![1.0, 0.0, 0.0])
+            .unwrap();
+        assert_eq!(store.dimension_counts(), vec![(2, 1), (3, 1)]);
+        assert_eq!(store.chunks_needing_migration(), Some(1));
+
+        let results = store.search_chunks(&[1.0, 0.0, 0.0], 10).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].doc_id, 20);
+        assert!(store.nearest_documents(20, 5).is_empty());
+
+        let error = store.search_chunks(&[1.0, 0.0, 0.0, 0.0], 10).unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<DimensionMismatch>(),
+            Some(&DimensionMismatch {
+                query: 4,
+                stored: vec![2, 3]
+            })
+        );
+
+        // Counts follow removals and compaction
+        store.remove_document(10);
+        assert_eq!(store.dimension_counts(), vec![(3, 1)]);
+        assert_eq!(store.chunks_needing_migration(), Some(0));
+        assert!(store.search_chunks(&[1.0, 0.0], 10).is_err());
+        store.compact();
+        assert_eq!(store.dimension_counts(), vec![(3, 1)]);
+        assert!(VectorStore::new()
+            .search_chunks(&[1.0], 10)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_removed_vectors_are_skipped_then_compacted() {
+        let mut store = VectorStore::new();
+        store
+            .load_chunk_vectors(vec![
+                (1, 10, 0, 100, vec![1.0, 0.0]),
+                (2, 10, 100, 200, vec![0.9, 0.1]),
+                (3, 20, 0, 100, vec![0.8, 0.2]),
+                (4, 30, 0, 100, vec![0.0, 1.0]),
+            ])
+            .unwrap();
+
+        store.remove_document(10);
+        assert!(store.remove_chunk(4));
+        assert!(!store.remove_chunk(4));
+        assert_eq!(store.chunk_vector_count(), 1);
+        assert_eq!(store.tombstone_count(), 3);
+        let results = store.search_chunks(&[1.0, 0.0], 10).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].doc_id, 20);
+        assert!(store.nearest_documents(10, 5).is_empty());
+        assert!(store.nearest_documents(20, 5).is_empty());
+
+        // Restored from the trash with the same embedding IDs
+        store
+            .add_chunk_vector(1, 10, 0, 100, vec![1.0, 0.0])
+            .unwrap();
+        assert_eq!(store.tombstone_count(), 0);
+        assert_eq!(store.chunk_vector_count(), 2);
+        assert_eq!(store.search_chunks(&[1.0, 0.0], 10).unwrap()[0].doc_id, 10);
+
+        // Removing enough at once compacts without being asked
+        let many: Vec<_> = (100..100 + MIN_TOMBSTONES_TO_COMPACT as i64)
+            .map(|id| (id, 40, 0, 100, vec![0.5, 0.5]))
+            .collect();
+        for (id, doc_id, start, end, vector) in many {
+            store
+                .add_chunk_vector(id, doc_id, start, end, vector)
+                .unwrap();
+        }
+        store.remove_document(40);
+        assert_eq!(store.tombstone_count(), 0);
+        assert_eq!(store.chunk_vector_count(), 2);
+    }
 }
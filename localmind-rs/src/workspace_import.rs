@@ -0,0 +1,600 @@
+//! Notion and Confluence export import
+//!
+//! Reads the page exports of two wiki tools so their pages can be indexed
+//! like any other document:
+//!
+//! - Notion's "Markdown & CSV" export: a zip (large workspaces are split into
+//!   nested `Part-N.zip` files) with one `Title <32-hex id>.md` per page,
+//!   subpages in a folder named like their parent, and databases as CSV
+//! - Confluence's space HTML export: a zip with one HTML file per page, whose
+//!   breadcrumbs give the page hierarchy
+//!
+//! Either can also be given as an unzipped folder. Each page's text starts
+//! with its breadcrumb so searches match on parent titles, and links to other
+//! pages in the same export are kept as document links.
+
+use crate::bookmark_import::unescape;
+use crate::content_cleaner::{CleanerPipeline, ExtractionMethod};
+use crate::Result;
+use regex::Regex;
+use std::collections::HashSet;
+use std::io::Read;
+use std::path::Path;
+
+/// Document source for pages imported from Notion.
+pub const NOTION_SOURCE: &str = "notion_import";
+
+/// Document source for pages imported from Confluence.
+pub const CONFLUENCE_SOURCE: &str = "confluence_import";
+
+/// Which tool produced an export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportKind {
+    Notion,
+    Confluence,
+}
+
+impl ExportKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            ExportKind::Notion => "Notion",
+            ExportKind::Confluence => "Confluence",
+        }
+    }
+
+    /// Value stored in the documents `source` column.
+    pub fn source(self) -> &'static str {
+        match self {
+            ExportKind::Notion => NOTION_SOURCE,
+            ExportKind::Confluence => CONFLUENCE_SOURCE,
+        }
+    }
+}
+
+/// A page read from an export.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportPage {
+    pub title: String,
+    /// Notion pages get their notion.so URL; Confluence pages, which carry no
+    /// server address, a `confluence://` URL naming the exported file
+    pub url: String,
+    /// Titles of the parent pages, outermost first
+    pub hierarchy: Vec<String>,
+    pub content: String,
+    /// URLs of the pages in the same export this page links to
+    pub links: Vec<String>,
+}
+
+impl ExportPage {
+    /// Text to index: the breadcrumb, then the page content.
+    pub fn document_text(&self) -> String {
+        if self.hierarchy.is_empty() {
+            self.content.clone()
+        } else {
+            format!(
+                "{} / {}\n\n{}",
+                self.hierarchy.join(" / "),
+                self.title,
+                self.content
+            )
+        }
+    }
+}
+
+/// A text file from the export, with a `/`-separated path relative to its
+/// root.
+struct ExportFile {
+    path: String,
+    text: String,
+}
+
+/// Whether a path looks like a wiki export (a zip or a folder) rather than a
+/// bookmark file.
+pub fn is_workspace_export(path: &Path) -> bool {
+    path.is_dir()
+        || path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"))
+}
+
+/// Read a Notion or Confluence export from a zip or folder, detecting which.
+pub fn read_workspace_export(path: &Path) -> Result<(ExportKind, Vec<ExportPage>)> {
+    let mut files = Vec::new();
+    if path.is_dir() {
+        read_dir_files(path, "", &mut files)?;
+    } else {
+        let data =
+            std::fs::read(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+        read_zip_files(data, &mut files)?;
+    }
+
+    let kind = detect(&files).ok_or("Not a Notion or Confluence export")?;
+    let pages = match kind {
+        ExportKind::Notion => parse_notion(&files),
+        ExportKind::Confluence => parse_confluence(&files),
+    };
+    if pages.is_empty() {
+        return Err(format!("No pages found in {} export", kind.label()).into());
+    }
+    Ok((kind, pages))
+}
+
+fn is_page_file(path: &str) -> bool {
+    let lower = path.to_lowercase();
+    [".md", ".csv", ".html", ".htm"]
+        .iter()
+        .any(|ext| lower.ends_with(ext))
+}
+
+fn read_dir_files(dir: &Path, prefix: &str, files: &mut Vec<ExportFile>) -> Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        let path = format!("{}{}", prefix, name);
+        if entry.file_type()?.is_dir() {
+            read_dir_files(&entry.path(), &format!("{}/", path), files)?;
+        } else if is_page_file(&path) {
+            let data = std::fs::read(entry.path())?;
+            files.push(ExportFile {
+                path,
+                text: String::from_utf8_lossy(&data).into_owned(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Read the page files in a zip, descending into nested zips.
+fn read_zip_files(data: Vec<u8>, files: &mut Vec<ExportFile>) -> Result<()> {
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(data))
+        .map_err(|e| format!("Not a readable zip file: {}", e))?;
+
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        if entry.is_dir() {
+            continue;
+        }
+        let path = entry.name().replace('\\', "/");
+        let nested = path.to_lowercase().ends_with(".zip");
+        if !nested && !is_page_file(&path) {
+            continue;
+        }
+
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data)?;
+        if nested {
+            read_zip_files(data, files)?;
+        } else {
+            files.push(ExportFile {
+                path,
+                text: String::from_utf8_lossy(&data).into_owned(),
+            });
+        }
+    }
+    Ok(())
+}
+
+fn detect(files: &[ExportFile]) -> Option<ExportKind> {
+    let is_confluence = files.iter().any(|f| {
+        is_html(&f.path)
+            && (f.text.contains("Atlassian Confluence")
+                || (f.text.contains("id=\"main-content\"")
+                    && f.text.contains("id=\"breadcrumbs\"")))
+    });
+    if is_confluence {
+        return Some(ExportKind::Confluence);
+    }
+
+    let is_notion = files.iter().any(|f| {
+        let lower = f.path.to_lowercase();
+        (lower.ends_with(".md") || lower.ends_with(".csv"))
+            && split_notion_name(file_stem(&f.path)).1.is_some()
+    });
+    is_notion.then_some(ExportKind::Notion)
+}
+
+fn is_html(path: &str) -> bool {
+    let lower = path.to_lowercase();
+    lower.ends_with(".html") || lower.ends_with(".htm")
+}
+
+/// Last path segment without its extension.
+fn file_stem(path: &str) -> &str {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    name.rsplit_once('.').map_or(name, |(stem, _)| stem)
+}
+
+/// Split a Notion file or folder name into its title and page id.
+fn split_notion_name(name: &str) -> (String, Option<String>) {
+    let name = name.strip_suffix("_all").unwrap_or(name);
+    let re = Regex::new(r"^(.*?)\s*([0-9a-f]{32})$").expect("valid regex");
+    match re.captures(name) {
+        Some(caps) => (caps[1].trim().to_string(), Some(caps[2].to_string())),
+        None => (name.trim().to_string(), None),
+    }
+}
+
+fn notion_url(id: &str) -> String {
+    format!("https://www.notion.so/{}", id)
+}
+
+/// Parse the pages and databases of a Notion export.
+fn parse_notion(files: &[ExportFile]) -> Vec<ExportPage> {
+    let link_re = Regex::new(r"\[([^\]]*)\]\(([^)\s]+)\)").expect("valid regex");
+    let id_re = Regex::new(r"([0-9a-f]{32})(?:\.(?:md|csv))?$").expect("valid regex");
+
+    let mut seen = HashSet::new();
+    let mut pages = Vec::new();
+
+    for file in files {
+        let lower = file.path.to_lowercase();
+        let is_csv = lower.ends_with(".csv");
+        if !is_csv && !lower.ends_with(".md") {
+            continue;
+        }
+
+        let (name_title, id) = split_notion_name(file_stem(&file.path));
+        let url = match id {
+            Some(ref id) => notion_url(id),
+            None => format!("notion://{}", file.path),
+        };
+        // Databases export as both `Name <id>.csv` and `Name <id>_all.csv`
+        if !seen.insert(url.clone()) {
+            continue;
+        }
+
+        // Subpages live in a folder named like their parent page
+        let hierarchy = file
+            .path
+            .rsplit_once('/')
+            .map_or("", |(dir, _)| dir)
+            .split('/')
+            .filter_map(|dir| match split_notion_name(dir) {
+                (title, Some(_)) => Some(title),
+                _ => None,
+            })
+            .collect();
+
+        let text = file.text.trim_start_matches('\u{feff}');
+        let (title, content, links) = if is_csv {
+            (name_title, csv_to_text(text), Vec::new())
+        } else {
+            let (title, body) = match text.trim_start().strip_prefix("# ") {
+                Some(rest) => {
+                    let (heading, body) = rest.split_once('\n').unwrap_or((rest, ""));
+                    (heading.trim().to_string(), body)
+                }
+                None => (name_title, text),
+            };
+
+            // Links to other pages in the export become plain text; their
+            // targets are kept as document links
+            let mut links = Vec::new();
+            let body = link_re.replace_all(body, |caps: &regex::Captures| {
+                let target = percent_decode(&caps[2]);
+                let internal = !target.contains("://") || target.contains("notion.so/");
+                match id_re.captures(&target).filter(|_| internal) {
+                    Some(id) => {
+                        links.push(notion_url(&id[1]));
+                        caps[1].to_string()
+                    }
+                    None => caps[0].to_string(),
+                }
+            });
+            links.sort();
+            links.dedup();
+            (title, body.trim().to_string(), links)
+        };
+
+        if content.is_empty() {
+            continue;
+        }
+        pages.push(ExportPage {
+            title,
+            url,
+            hierarchy,
+            content,
+            links,
+        });
+    }
+
+    pages
+}
+
+/// Render a Notion database CSV as one `Column: value` block per row.
+fn csv_to_text(text: &str) -> String {
+    let mut rows = parse_csv(text).into_iter();
+    let Some(header) = rows.next() else {
+        return String::new();
+    };
+
+    rows.map(|row| {
+        header
+            .iter()
+            .zip(row.iter())
+            .filter(|(_, value)| !value.trim().is_empty())
+            .map(|(column, value)| format!("{}: {}", column.trim(), value.trim()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    })
+    .filter(|block| !block.is_empty())
+    .collect::<Vec<_>>()
+    .join("\n\n")
+}
+
+/// Minimal RFC 4180 parser: quoted fields may contain commas, newlines and
+/// doubled quotes.
+fn parse_csv(text: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, in_quotes) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            ('"', _) => in_quotes = !in_quotes,
+            (',', false) => row.push(std::mem::take(&mut field)),
+            ('\r', false) => {}
+            ('\n', false) => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    rows
+}
+
+/// Decode `%XX` escapes in a link target.
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let decoded = (bytes[i] == b'%')
+            .then(|| text.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match decoded {
+            Some(byte) => {
+                out.push(byte);
+                i += 3;
+            }
+            None => {
+                out.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Parse the pages of a Confluence space HTML export.
+fn parse_confluence(files: &[ExportFile]) -> Vec<ExportPage> {
+    let title_re = Regex::new(r"(?is)<title>(.*?)</title>").expect("valid regex");
+    let breadcrumbs_re =
+        Regex::new(r#"(?is)<ol[^>]*id="breadcrumbs"[^>]*>(.*?)</ol>"#).expect("valid regex");
+    let anchor_re = Regex::new(r"(?is)<a\b[^>]*>(.*?)</a>").expect("valid regex");
+    let href_re = Regex::new(r##"(?i)href\s*=\s*"([^"#?]+)"##).expect("valid regex");
+    let cleaners = CleanerPipeline::default();
+
+    let mut pages = Vec::new();
+    for file in files {
+        let file_name = file.path.rsplit('/').next().unwrap_or(&file.path);
+        if !is_html(&file.path) || file_name.eq_ignore_ascii_case("index.html") {
+            continue;
+        }
+        let Some(main) = confluence_main_content(&file.text) else {
+            continue;
+        };
+
+        // "<title>Space : Page</title>"
+        let title = title_re
+            .captures(&file.text)
+            .map(|caps| unescape(&caps[1]))
+            .map(|t| match t.split_once(" : ") {
+                Some((_, page)) => page.trim().to_string(),
+                None => t,
+            })
+            .filter(|t| !t.is_empty())
+            .unwrap_or_else(|| file_stem(&file.path).to_string());
+
+        let hierarchy = breadcrumbs_re
+            .captures(&file.text)
+            .map(|caps| {
+                anchor_re
+                    .captures_iter(&caps[1])
+                    .map(|a| unescape(&a[1]))
+                    .filter(|t| !t.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let dir = file.path.rsplit_once('/').map_or("", |(dir, _)| dir);
+        let mut links: Vec<String> = href_re
+            .captures_iter(main)
+            .map(|caps| caps[1].to_string())
+            .filter(|href| !href.contains(':') && is_html(href))
+            .map(|href| confluence_url(&resolve_path(dir, &percent_decode(&href))))
+            .collect();
+        links.sort();
+        links.dedup();
+
+        let content = cleaners.clean(ExtractionMethod::Html, main);
+        if content.is_empty() {
+            continue;
+        }
+        pages.push(ExportPage {
+            title,
+            url: confluence_url(&file.path),
+            hierarchy,
+            content,
+            links,
+        });
+    }
+
+    pages
+}
+
+fn confluence_url(path: &str) -> String {
+    format!("confluence://{}", path)
+}
+
+/// The page body: from the `main-content` element up to the attachments
+/// section or footer.
+fn confluence_main_content(html: &str) -> Option<&str> {
+    let marker = html.find("id=\"main-content\"")?;
+    let start = html[..marker].rfind('<').unwrap_or(marker);
+    let end = ["<div class=\"pageSection group\">", "<div id=\"footer\""]
+        .iter()
+        .filter_map(|m| html[start..].find(m).map(|i| start + i))
+        .min()
+        .unwrap_or(html.len());
+    Some(&html[start..end])
+}
+
+/// Join a relative link onto the linking page's folder, resolving `..`.
+fn resolve_path(dir: &str, href: &str) -> String {
+    let mut parts: Vec<&str> = dir.split('/').filter(|p| !p.is_empty()).collect();
+    for segment in href.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            s => parts.push(s),
+        }
+    }
+    parts.join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn file(path: &str, text: &str) -> ExportFile {
+        ExportFile {
+            path: path.to_string(),
+            text: text.to_string(),
+        }
+    }
+
+    const PARENT_ID: &str = "0123456789abcdef0123456789abcdef";
+    const CHILD_ID: &str = "fedcba9876543210fedcba9876543210";
+
+    #[test]
+    fn parses_notion_pages_hierarchy_and_links() {
+        let files = vec![
+            file(
+                &format!("Projects {}.md", PARENT_ID),
+                &format!(
+                    "# Projects\n\nSee [Roadmap](Projects%20{p}/Roadmap%20{c}.md) and [Rust](https://www.rust-lang.org/).",
+                    p = PARENT_ID,
+                    c = CHILD_ID
+                ),
+            ),
+            file(
+                &format!("Projects {}/Roadmap {}.md", PARENT_ID, CHILD_ID),
+                "# Roadmap\n\nShip the importer.",
+            ),
+            file(
+                &format!("Projects {}/Tasks {}.csv", PARENT_ID, "aa".repeat(16)),
+                "Name,Status,Notes\nImporter,Done,\"Handles, commas\"\nDocs,,\n",
+            ),
+            file(
+                &format!("Projects {}/Tasks {}_all.csv", PARENT_ID, "aa".repeat(16)),
+                "Name,Status\nImporter,Done\n",
+            ),
+        ];
+        assert_eq!(detect(&files), Some(ExportKind::Notion));
+
+        let pages = parse_notion(&files);
+        assert_eq!(pages.len(), 3);
+
+        let projects = &pages[0];
+        assert_eq!(projects.title, "Projects");
+        assert_eq!(projects.url, notion_url(PARENT_ID));
+        assert!(projects.hierarchy.is_empty());
+        assert_eq!(projects.links, vec![notion_url(CHILD_ID)]);
+        assert!(projects.content.contains("See Roadmap and [Rust]"));
+
+        let roadmap = &pages[1];
+        assert_eq!(roadmap.hierarchy, vec!["Projects"]);
+        assert_eq!(
+            roadmap.document_text(),
+            "Projects / Roadmap\n\nShip the importer."
+        );
+
+        let tasks = &pages[2];
+        assert_eq!(tasks.title, "Tasks");
+        assert_eq!(
+            tasks.content,
+            "Name: Importer\nStatus: Done\nNotes: Handles, commas\n\nName: Docs"
+        );
+    }
+
+    #[test]
+    fn parses_confluence_pages_from_zip() {
+        let page = r#"<html><head><title>Engineering : Deploy Guide</title></head><body>
+            <div id="breadcrumb-section"><ol id="breadcrumbs">
+              <li><a href="index.html">Engineering</a></li>
+              <li><a href="Runbooks_12.html">Runbooks</a></li>
+            </ol></div>
+            <div id="main-content" class="wiki-content group">
+              <h2>Steps</h2><p>Run the <a href="Build-Setup_34.html">build</a> first.</p>
+              <script>track()</script>
+            </div>
+            <div class="pageSection group"><h2>Attachments:</h2></div>
+            <div id="footer">Document generated by Confluence</div>
+            </body></html>"#;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let zip_path = temp_dir.path().join("export.zip");
+        {
+            let mut zip = zip::ZipWriter::new(std::fs::File::create(&zip_path).unwrap());
+            let options = zip::write::FileOptions::default();
+            zip.start_file("ENG/Deploy-Guide_56.html", options).unwrap();
+            zip.write_all(page.as_bytes()).unwrap();
+            zip.start_file("ENG/index.html", options).unwrap();
+            zip.write_all(b"<html>Created by Atlassian Confluence</html>")
+                .unwrap();
+            zip.start_file("ENG/images/logo.png", options).unwrap();
+            zip.write_all(&[0u8; 8]).unwrap();
+            zip.finish().unwrap();
+        }
+        assert!(is_workspace_export(&zip_path));
+
+        let (kind, pages) = read_workspace_export(&zip_path).unwrap();
+        assert_eq!(kind, ExportKind::Confluence);
+        assert_eq!(pages.len(), 1);
+
+        let page = &pages[0];
+        assert_eq!(page.title, "Deploy Guide");
+        assert_eq!(page.url, "confluence://ENG/Deploy-Guide_56.html");
+        assert_eq!(page.hierarchy, vec!["Engineering", "Runbooks"]);
+        assert_eq!(page.links, vec!["confluence://ENG/Build-Setup_34.html"]);
+        assert!(page.content.contains("Steps"));
+        assert!(page.content.contains("first."));
+        assert!(!page.content.contains("track()"));
+        assert!(!page.content.contains("Attachments"));
+    }
+
+    #[test]
+    fn rejects_unrecognized_exports() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::fs::write(temp_dir.path().join("notes.md"), "# Just notes").unwrap();
+        assert!(read_workspace_export(temp_dir.path()).is_err());
+        assert_eq!(resolve_path("a/b", "../c.html"), "a/c.html");
+        assert_eq!(percent_decode("A%20B%zz"), "A B%zz");
+    }
+}
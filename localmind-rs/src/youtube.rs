@@ -2,6 +2,11 @@ use crate::Result;
 use url::Url;
 use yt_transcript_rs::YouTubeTranscriptApi;
 
+/// Confidence whatlang needs in a title's language for it to pick the
+/// transcript track. Short English titles are often taken for another
+/// language, but with a confidence well below this.
+const TITLE_LANGUAGE_CONFIDENCE: f64 = 0.5;
+
 pub struct YouTubeProcessor;
 
 impl YouTubeProcessor {
@@ -66,8 +71,46 @@ impl YouTubeProcessor {
         cleaned.trim().to_string()
     }
 
-    /// Fetch transcript for a YouTube video
-    pub async fn fetch_transcript(url: &str) -> Result<Option<String>> {
+    /// Fetch a video's title from YouTube's oEmbed endpoint, cleaned up
+    pub async fn fetch_title(url: &str) -> Option<String> {
+        #[derive(serde::Deserialize)]
+        struct OEmbed {
+            title: String,
+        }
+
+        let endpoint = Url::parse_with_params(
+            "https://www.youtube.com/oembed",
+            &[("url", url), ("format", "json")],
+        )
+        .ok()?;
+        let oembed: OEmbed = reqwest::get(endpoint).await.ok()?.json().await.ok()?;
+        let title = Self::cleanup_title(&oembed.title);
+        (!title.is_empty()).then_some(title)
+    }
+
+    /// Transcript tracks to ask for, in order of preference: the language
+    /// spoken in the video when YouTube declares it, otherwise the language
+    /// of its title when that can be told with confidence, then English.
+    pub fn transcript_languages(spoken: Option<&str>, title: Option<&str>) -> Vec<String> {
+        let mut languages: Vec<String> = match spoken {
+            Some(code) => vec![code.to_string()],
+            None => title
+                .and_then(|title| crate::language::guess(title, TITLE_LANGUAGE_CONFIDENCE))
+                .and_then(crate::language::two_letter_code)
+                .map(str::to_string)
+                .into_iter()
+                .collect(),
+        };
+        if !languages.iter().any(|code| code == "en") {
+            languages.push("en".to_string());
+        }
+        languages
+    }
+
+    /// Fetch transcript for a YouTube video, preferring the track in the
+    /// language spoken in it. Without a `title` it is looked up first, for
+    /// videos whose spoken language is not declared.
+    pub async fn fetch_transcript(url: &str, title: Option<&str>) -> Result<Option<String>> {
         let video_id = match Self::extract_video_id(url) {
             Some(id) => id,
             None => return Ok(None),
@@ -79,8 +122,23 @@ impl YouTubeProcessor {
         let api = YouTubeTranscriptApi::new(None, None, None)
             .map_err(|e| format!("Failed to initialize YouTube transcript API: {}", e))?;
 
+        // YouTube only generates captions in the language spoken in a video,
+        // so that track declares it
+        let spoken = api
+            .list_transcripts(&video_id)
+            .await
+            .ok()
+            .and_then(|list| list.generated_transcripts.keys().next().cloned());
+        let title = match (&spoken, title) {
+            (Some(_), _) => None,
+            (None, Some(title)) => Some(title.to_string()),
+            (None, None) => Self::fetch_title(url).await,
+        };
+        let languages = Self::transcript_languages(spoken.as_deref(), title.as_deref());
+        let languages: Vec<&str> = languages.iter().map(String::as_str).collect();
+
         // Fetch transcript
-        match api.fetch_transcript(&video_id, &["en"], false).await {
+        match api.fetch_transcript(&video_id, &languages, false).await {
             Ok(transcript) => {
                 let text = transcript.text();
                 if text.trim().is_empty() {
@@ -121,7 +179,7 @@ impl YouTubeProcessor {
         let cleaned_title = Self::cleanup_title(original_title);
 
         // Try to fetch transcript
-        match Self::fetch_transcript(url).await? {
+        match Self::fetch_transcript(url, Some(cleaned_title.as_str())).await? {
             Some(transcript) => {
                 println!(
                     "Using transcript as content for YouTube video: {}",
@@ -186,6 +244,50 @@ mod tests {
         assert_eq!(YouTubeProcessor::extract_video_id("not a url"), None);
     }
 
+    #[test]
+    fn transcript_prefers_the_spoken_language() {
+        assert_eq!(
+            YouTubeProcessor::transcript_languages(None, None),
+            vec!["en"]
+        );
+
+        // A declared spoken language wins over whatever the title looks like
+        assert_eq!(
+            YouTubeProcessor::transcript_languages(
+                Some("es"),
+                Some("How to make a traditional paella at home")
+            ),
+            vec!["es", "en"]
+        );
+        assert_eq!(
+            YouTubeProcessor::transcript_languages(
+                Some("en"),
+                Some("Cómo preparar una paella valenciana tradicional en casa")
+            ),
+            vec!["en"]
+        );
+
+        // Without one, only a confident guess from the title counts
+        assert_eq!(
+            YouTubeProcessor::transcript_languages(
+                None,
+                Some("Cómo preparar una paella valenciana tradicional en casa")
+            ),
+            vec!["es", "en"]
+        );
+        assert_eq!(
+            YouTubeProcessor::transcript_languages(
+                None,
+                Some("How to make a traditional paella at home")
+            ),
+            vec!["en"]
+        );
+        assert_eq!(
+            YouTubeProcessor::transcript_languages(None, Some("Paella")),
+            vec!["en"]
+        );
+    }
+
     #[test]
     fn test_title_cleanup() {
         assert_eq!(
@@ -0,0 +1,199 @@
+//! End-to-end ingestion and search over a temporary database, with
+//! `MockEmbedding` standing in for the embedding server.
+
+use localmind_rs::db::{Database, OperationPriority};
+use localmind_rs::embedding_provider::{EmbeddingSettings, MockEmbedding};
+use localmind_rs::rag::{RagPipeline, SearchOptions};
+use tempfile::TempDir;
+
+const RUST_NOTE: &str = "Tokio is an asynchronous runtime for the Rust programming language. \
+                         It schedules tasks, drives timers and performs network input and output.";
+
+const BREAD_NOTE: &str = "Sourdough bread rises with a wild yeast starter. Mix flour, water \
+                          and salt, fold the dough and bake it in a hot oven.";
+
+async fn open_pipeline(dir: &TempDir) -> RagPipeline {
+    let db = Database::open(&dir.path().join("localmind.db"))
+        .await
+        .unwrap();
+    RagPipeline::with_embedding_backend(db, Box::new(MockEmbedding::default()))
+        .await
+        .unwrap()
+}
+
+async fn ingest_notes(rag: &RagPipeline) -> (i64, i64) {
+    let rust = rag
+        .ingest_document("Tokio runtime", RUST_NOTE, None, "note", None)
+        .await
+        .unwrap();
+    let bread = rag
+        .ingest_document("Sourdough bread", BREAD_NOTE, None, "note", None)
+        .await
+        .unwrap();
+    (rust, bread)
+}
+
+async fn top_hit(rag: &RagPipeline, query: &str) -> Option<i64> {
+    let results = rag.search_with_cutoff(query, 5, 0.0).await.unwrap();
+    results.first().map(|(doc, _)| doc.id)
+}
+
+#[tokio::test]
+async fn ingested_documents_are_found_by_semantic_search() {
+    let dir = TempDir::new().unwrap();
+    let rag = open_pipeline(&dir).await;
+    assert!(rag.is_semantic_search_available());
+
+    let (rust, bread) = ingest_notes(&rag).await;
+    assert!(rag.vector_store_stats().0 >= 2);
+
+    assert_eq!(
+        top_hit(&rag, "asynchronous runtime tasks").await,
+        Some(rust)
+    );
+    assert_eq!(top_hit(&rag, "bake sourdough dough").await, Some(bread));
+}
+
+#[tokio::test]
+async fn fused_search_ranks_the_matching_document_first() {
+    let dir = TempDir::new().unwrap();
+    let rag = open_pipeline(&dir).await;
+    let (rust, bread) = ingest_notes(&rag).await;
+
+    let search = rag
+        .search_fused("sourdough starter", SearchOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(search.hits.first().map(|hit| hit.doc_id), Some(bread));
+
+    let search = rag
+        .search_fused("rust timers", SearchOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(search.hits.first().map(|hit| hit.doc_id), Some(rust));
+}
+
+#[tokio::test]
+async fn embeddings_are_reloaded_after_a_restart() {
+    let dir = TempDir::new().unwrap();
+    let (rust, vectors) = {
+        let rag = open_pipeline(&dir).await;
+        let (rust, _) = ingest_notes(&rag).await;
+        (rust, rag.vector_store_stats().0)
+    };
+
+    let rag = open_pipeline(&dir).await;
+    assert_eq!(rag.vector_store_stats().0, vectors);
+    assert_eq!(
+        top_hit(&rag, "asynchronous runtime tasks").await,
+        Some(rust)
+    );
+}
+
+#[tokio::test]
+async fn deleted_documents_drop_out_of_search() {
+    let dir = TempDir::new().unwrap();
+    let rag = open_pipeline(&dir).await;
+    let (rust, bread) = ingest_notes(&rag).await;
+
+    rag.db.delete_document(rust).await.unwrap();
+    rag.remove_document_vectors(rust).await;
+
+    let results = rag
+        .search_with_cutoff("asynchronous runtime tasks", 5, 0.0)
+        .await
+        .unwrap();
+    assert!(results.iter().all(|(doc, _)| doc.id != rust));
+    assert_eq!(top_hit(&rag, "bake sourdough dough").await, Some(bread));
+}
+
+#[tokio::test]
+async fn switching_model_reembeds_before_searching_with_it() {
+    let dir = TempDir::new().unwrap();
+    let mut rag = open_pipeline(&dir).await;
+    let (rust, bread) = ingest_notes(&rag).await;
+
+    rag.start_embedding_migration(
+        EmbeddingSettings::default(),
+        Box::new(MockEmbedding::new(32)),
+    )
+    .await
+    .unwrap();
+    let progress = rag.migrate_embeddings(1).await.unwrap().unwrap();
+    assert_eq!((progress.migrated, progress.total), (1, 2));
+
+    // Halfway through, search stays on the old model's vectors
+    assert!(!rag.finish_embedding_migration().await.unwrap());
+    assert_eq!(
+        top_hit(&rag, "asynchronous runtime tasks").await,
+        Some(rust)
+    );
+
+    assert!(rag
+        .migrate_embeddings(10)
+        .await
+        .unwrap()
+        .unwrap()
+        .is_complete());
+    assert!(rag.finish_embedding_migration().await.unwrap());
+    assert!(rag.embedding_migration().is_none());
+    assert_eq!(rag.embedding_model_key(), "mock:32");
+    assert_eq!(top_hit(&rag, "bake sourdough dough").await, Some(bread));
+
+    let chunks = rag.db.get_all_chunk_embeddings().await.unwrap();
+    assert!(!chunks.is_empty());
+    assert!(chunks.iter().all(|(_, _, _, _, vector)| vector.len() == 32));
+}
+
+#[tokio::test]
+async fn resaving_a_trashed_url_creates_a_new_document() {
+    let dir = TempDir::new().unwrap();
+    let rag = open_pipeline(&dir).await;
+    let url = "https://example.com/tokio";
+    let trashed = rag
+        .ingest_document("Tokio runtime", RUST_NOTE, Some(url), "note", None)
+        .await
+        .unwrap();
+    rag.db.trash_documents(&[trashed]).await.unwrap();
+    rag.remove_document_vectors(trashed).await;
+
+    // The dedupe lookup save requests go through must not match the trash
+    assert!(rag.db.get_document_by_url(url).await.unwrap().is_none());
+    assert!(!rag
+        .db
+        .url_exists(url, OperationPriority::UserSearch)
+        .await
+        .unwrap());
+    let resaved = rag
+        .ingest_document("Tokio runtime", RUST_NOTE, Some(url), "note", None)
+        .await
+        .unwrap();
+    assert_ne!(resaved, trashed);
+    assert_eq!(
+        rag.db
+            .get_document_by_url(url)
+            .await
+            .unwrap()
+            .map(|doc| doc.id),
+        Some(resaved)
+    );
+
+    let results = rag
+        .search_with_cutoff("asynchronous runtime tasks", 5, 0.0)
+        .await
+        .unwrap();
+    assert_eq!(results.first().map(|(doc, _)| doc.id), Some(resaved));
+    assert!(results.iter().all(|(doc, _)| doc.id != trashed));
+    assert_eq!(
+        rag.db
+            .count_documents(OperationPriority::UserSearch)
+            .await
+            .unwrap(),
+        1
+    );
+    let trash = rag.db.get_trashed_documents().await.unwrap();
+    assert_eq!(
+        trash.iter().map(|doc| doc.id).collect::<Vec<_>>(),
+        vec![trashed]
+    );
+}